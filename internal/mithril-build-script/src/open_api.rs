@@ -26,7 +26,10 @@ fn list_all_open_api_spec_files(paths: &[&Path]) -> Vec<PathBuf> {
     open_api_spec_files
 }
 
-fn read_version_from_open_api_spec_file(spec_file_path: PathBuf) -> OpenAPIVersionRaw {
+/// Read the version of the given Open API file.
+pub fn read_version_from_open_api_spec_file<P: AsRef<Path>>(
+    spec_file_path: P,
+) -> OpenAPIVersionRaw {
     let yaml_spec = fs::read_to_string(spec_file_path).unwrap();
     let open_api: serde_yaml::Value = serde_yaml::from_str(&yaml_spec).unwrap();
     open_api["info"]["version"].as_str().unwrap().to_owned()
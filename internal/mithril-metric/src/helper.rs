@@ -4,6 +4,8 @@
 ///
 /// To build the service you need to provide the structure name and a list of metrics.
 /// Each metrics is defined by an attribute name, a type, a metric name and a help message.
+/// Metrics partitioned by labels (`MetricCounterWithLabels`, `MetricGaugeWithLabels`,
+/// `MetricHistogramWithLabels`) also need the list of their label names.
 ///
 /// The attribute name will be used to create a getter method for the metric.
 ///
@@ -15,7 +17,11 @@
 ///     use slog::Logger;
 ///     use mithril_common::{entities::Epoch, StdResult};
 ///     use mithril_metric::build_metrics_service;
-///     use mithril_metric::{MetricCollector, MetricCounter, MetricGauge, MetricsServiceExporter};
+///     use std::time::Duration;
+///     use mithril_metric::{
+///         MetricCollector, MetricCounter, MetricCounterWithLabels, MetricGauge, MetricHistogram,
+///         MetricsServiceExporter,
+///     };
 ///
 ///     build_metrics_service!(
 ///         MetricsService,
@@ -26,16 +32,27 @@
 ///         gauge_example: MetricGauge(
 ///             "custom_gauge_example_name",
 ///             "Example of a gauge metric"
+///         ),
+///         histogram_example: MetricHistogram(
+///             "custom_histogram_example_name",
+///             "Example of a histogram metric"
+///         ),
+///         counter_with_labels_example: MetricCounterWithLabels(
+///             "custom_counter_with_labels_example_name",
+///             "Example of a counter metric with labels",
+///             ["party_id"]
 ///         )
 ///     );
 ///
 ///     let service = MetricsService::new(Logger::root(slog::Discard, slog::o!())).unwrap();
 ///     service.get_counter_example().increment();
 ///     service.get_gauge_example().record(Epoch(12));
+///     service.get_histogram_example().observe_duration(Duration::from_millis(120));
+///     service.get_counter_with_labels_example().increment(&["pool1"]);
 /// ```
 #[macro_export]
 macro_rules! build_metrics_service {
-    ($service:ident, $($metric_attribute:ident:$metric_type:ident($name:literal, $help:literal)),*) => {
+    ($service:ident, $($metric_attribute:ident:$metric_type:ident($name:literal, $help:literal $(, [$($label:literal),*])?)),*) => {
         paste::item! {
             /// Metrics service which is responsible for recording and exposing metrics.
             pub struct $service {
@@ -58,6 +75,7 @@ macro_rules! build_metrics_service {
                             ),
                             $name,
                             $help,
+                            $(&[$($label),*],)?
                         )?;
                         registry.register($metric_attribute.collector())?;
                     )*
@@ -70,7 +88,7 @@ macro_rules! build_metrics_service {
                     })
                 }
                 $(
-                    /// Get the `$metric_attribute` counter.
+                    /// Get the `$metric_attribute` metric.
                     pub fn [<get_ $metric_attribute>](&self) -> &$metric_type {
                         &self.$metric_attribute
                     }
@@ -114,7 +132,10 @@ pub mod test_tools {
 mod tests {
     use std::collections::BTreeMap;
 
-    use crate::{MetricCollector, MetricCounter, MetricGauge, MetricsServiceExporter};
+    use crate::{
+        MetricCollector, MetricCounter, MetricCounterWithLabels, MetricGauge, MetricHistogram,
+        MetricsServiceExporter,
+    };
 
     use super::*;
     use mithril_common::{entities::Epoch, StdResult};
@@ -201,6 +222,19 @@ mod tests {
         )
     );
 
+    build_metrics_service!(
+        MetricsServiceExampleWithHistogramAndLabels,
+        histogram_example: MetricHistogram(
+            "custom_histogram_example_name",
+            "Example of a histogram metric"
+        ),
+        counter_with_labels_example: MetricCounterWithLabels(
+            "custom_counter_with_labels_example_name",
+            "Example of a counter metric with labels",
+            ["party_id"]
+        )
+    );
+
     #[test]
     fn test_service_creation_using_build_metrics_service_macro() {
         let service = MetricsServiceExampleBuildWithMacro::new(TestLogger::stdout()).unwrap();
@@ -243,4 +277,22 @@ mod tests {
 
         assert_eq!(parsed_metrics_expected, parsed_metrics);
     }
+
+    #[test]
+    fn test_build_metrics_service_export_histogram_and_labeled_metrics() {
+        let service =
+            MetricsServiceExampleWithHistogramAndLabels::new(TestLogger::stdout()).unwrap();
+
+        service.get_histogram_example().observe(0.3);
+        service
+            .get_counter_with_labels_example()
+            .increment(&["pool1"]);
+
+        let exported_metrics = service.export_metrics().unwrap();
+
+        assert!(exported_metrics.contains("custom_histogram_example_name_bucket{le=\"0.5\"} 1"));
+        assert!(exported_metrics.contains("custom_histogram_example_name_count 1"));
+        assert!(exported_metrics
+            .contains("custom_counter_with_labels_example_name{party_id=\"pool1\"} 1"));
+    }
 }
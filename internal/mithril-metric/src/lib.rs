@@ -1,15 +1,19 @@
 #![warn(missing_docs)]
 
 //! metrics module.
-//! This module contains the tools to create a metrics service and a metrics server.
+//! This module contains the tools to create a metrics service, a metrics server and a metrics
+//! pusher to a Pushgateway or a remote-write endpoint.
 
 pub mod helper;
 pub mod metric;
+mod pusher;
+mod remote_write;
 mod server;
 
 pub use metric::*;
-pub use server::MetricsServer;
+pub use pusher::MetricsPusher;
 pub use server::MetricsServiceExporter;
+pub use server::{MetricsServer, MetricsServerBasicAuth, MetricsServerTlsConfig};
 
 #[cfg(test)]
 pub use helper::test_tools::TestLogger;
@@ -1,7 +1,12 @@
 //! This module contains wrapper to prometheus metrics for use in a metrics service.
 
-use prometheus::{core::Collector, Counter, Gauge, Opts};
-use slog::{debug, Logger};
+use std::time::Duration;
+
+use prometheus::{
+    core::Collector, proto::Metric, Counter, CounterVec, Gauge, GaugeVec, Histogram, HistogramOpts,
+    HistogramVec, Opts,
+};
+use slog::{debug, warn, Logger};
 
 use mithril_common::StdResult;
 
@@ -11,6 +16,9 @@ pub type MetricName = str;
 /// Type alias for a counter value.
 pub type CounterValue = u32;
 
+/// Type alias for the names of the labels of a metric.
+pub type MetricLabels<'a> = &'a [&'a str];
+
 /// Metric collector
 pub trait MetricCollector {
     /// Metric name
@@ -20,6 +28,35 @@ pub trait MetricCollector {
     fn collector(&self) -> Box<dyn Collector>;
 }
 
+/// Find the collected metric matching the given label values.
+///
+/// Unlike `get_metric_with_label_values`, it never creates the series when it does not exist.
+fn find_labeled_metric(
+    collector: &dyn Collector,
+    label_names: &[String],
+    label_values: MetricLabels,
+) -> Option<Metric> {
+    if label_names.len() != label_values.len() {
+        return None;
+    }
+    let is_matching = |metric: &Metric| {
+        metric.get_label().len() == label_names.len()
+            && metric.get_label().iter().all(|label| {
+                label_names
+                    .iter()
+                    .zip(label_values)
+                    .any(|(name, value)| label.get_name() == name && label.get_value() == *value)
+            })
+    };
+    let metric_families = collector.collect();
+
+    metric_families
+        .iter()
+        .flat_map(|family| family.get_metric())
+        .find(|metric| is_matching(metric))
+        .cloned()
+}
+
 /// Metric counter
 pub struct MetricCounter {
     name: String,
@@ -122,6 +159,244 @@ impl MetricCollector for MetricGauge {
     }
 }
 
+/// Metric histogram, used to record the distribution of values like durations.
+pub struct MetricHistogram {
+    name: String,
+    logger: Logger,
+    histogram: Box<Histogram>,
+}
+
+impl MetricHistogram {
+    /// Create a new metric histogram using the default buckets.
+    pub fn new(logger: Logger, name: &str, help: &str) -> StdResult<Self> {
+        Self::new_with_buckets(logger, name, help, prometheus::DEFAULT_BUCKETS.to_vec())
+    }
+
+    /// Create a new metric histogram with custom buckets.
+    pub fn new_with_buckets(
+        logger: Logger,
+        name: &str,
+        help: &str,
+        buckets: Vec<f64>,
+    ) -> StdResult<Self> {
+        let histogram_opts = HistogramOpts::new(name, help).buckets(buckets);
+        let histogram = Histogram::with_opts(histogram_opts)?;
+        Ok(Self {
+            logger,
+            name: name.to_string(),
+            histogram: Box::new(histogram),
+        })
+    }
+
+    /// Record a value in the histogram.
+    pub fn observe<T: Into<f64>>(&self, value: T) {
+        let value = value.into();
+        debug!(
+            self.logger,
+            "Observe '{}' histogram value {}", self.name, value
+        );
+        self.histogram.observe(value);
+    }
+
+    /// Record a duration in the histogram (in seconds).
+    pub fn observe_duration(&self, duration: Duration) {
+        self.observe(duration.as_secs_f64());
+    }
+
+    /// Get the number of values recorded in the histogram.
+    pub fn get_sample_count(&self) -> u64 {
+        self.histogram.get_sample_count()
+    }
+
+    /// Get the sum of the values recorded in the histogram.
+    pub fn get_sample_sum(&self) -> f64 {
+        self.histogram.get_sample_sum()
+    }
+}
+
+impl MetricCollector for MetricHistogram {
+    fn collector(&self) -> Box<dyn Collector> {
+        self.histogram.clone()
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+}
+
+/// Metric counter partitioned by labels
+pub struct MetricCounterWithLabels {
+    name: String,
+    logger: Logger,
+    label_names: Vec<String>,
+    counter: Box<CounterVec>,
+}
+
+impl MetricCounterWithLabels {
+    /// Create a new metric counter with the given label names.
+    pub fn new(logger: Logger, name: &str, help: &str, labels: MetricLabels) -> StdResult<Self> {
+        let counter = CounterVec::new(Opts::new(name, help), labels)?;
+        Ok(Self {
+            logger,
+            name: name.to_string(),
+            label_names: labels.iter().map(|label| label.to_string()).collect(),
+            counter: Box::new(counter),
+        })
+    }
+
+    /// Increment the counter for the given label values.
+    pub fn increment(&self, label_values: MetricLabels) {
+        self.increment_by(label_values, 1);
+    }
+
+    /// Increment the counter for the given label values by a value.
+    pub fn increment_by(&self, label_values: MetricLabels, value: CounterValue) {
+        debug!(
+            self.logger,
+            "Incrementing '{}' counter by {}", self.name, value; "labels" => ?label_values
+        );
+        match self.counter.get_metric_with_label_values(label_values) {
+            Ok(counter) => counter.inc_by(value as f64),
+            Err(error) => {
+                warn!(self.logger, "Invalid labels for '{}' counter", self.name; "labels" => ?label_values, "error" => ?error)
+            }
+        }
+    }
+
+    /// Get the counter value for the given label values.
+    pub fn get(&self, label_values: MetricLabels) -> CounterValue {
+        find_labeled_metric(self.counter.as_ref(), &self.label_names, label_values)
+            .map(|metric| metric.get_counter().get_value().round() as CounterValue)
+            .unwrap_or_default()
+    }
+}
+
+impl MetricCollector for MetricCounterWithLabels {
+    fn collector(&self) -> Box<dyn Collector> {
+        self.counter.clone()
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+}
+
+/// Metric gauge partitioned by labels
+pub struct MetricGaugeWithLabels {
+    name: String,
+    logger: Logger,
+    label_names: Vec<String>,
+    gauge: Box<GaugeVec>,
+}
+
+impl MetricGaugeWithLabels {
+    /// Create a new metric gauge with the given label names.
+    pub fn new(logger: Logger, name: &str, help: &str, labels: MetricLabels) -> StdResult<Self> {
+        let gauge = GaugeVec::new(Opts::new(name, help), labels)?;
+        Ok(Self {
+            logger,
+            name: name.to_string(),
+            label_names: labels.iter().map(|label| label.to_string()).collect(),
+            gauge: Box::new(gauge),
+        })
+    }
+
+    /// Record a value in the gauge for the given label values.
+    pub fn record<T: Into<f64>>(&self, label_values: MetricLabels, value: T) {
+        let value = value.into();
+        debug!(
+            self.logger,
+            "Set '{}' gauge value to {}", self.name, value; "labels" => ?label_values
+        );
+        match self.gauge.get_metric_with_label_values(label_values) {
+            Ok(gauge) => gauge.set(value),
+            Err(error) => {
+                warn!(self.logger, "Invalid labels for '{}' gauge", self.name; "labels" => ?label_values, "error" => ?error)
+            }
+        }
+    }
+
+    /// Get the gauge value for the given label values.
+    pub fn get(&self, label_values: MetricLabels) -> f64 {
+        find_labeled_metric(self.gauge.as_ref(), &self.label_names, label_values)
+            .map(|metric| metric.get_gauge().get_value())
+            .unwrap_or_default()
+    }
+
+    /// Remove the gauge value for the given label values so it's no longer exported.
+    pub fn remove(&self, label_values: MetricLabels) {
+        let _ = self.gauge.remove_label_values(label_values);
+    }
+}
+
+impl MetricCollector for MetricGaugeWithLabels {
+    fn collector(&self) -> Box<dyn Collector> {
+        self.gauge.clone()
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+}
+
+/// Metric histogram partitioned by labels
+pub struct MetricHistogramWithLabels {
+    name: String,
+    logger: Logger,
+    label_names: Vec<String>,
+    histogram: Box<HistogramVec>,
+}
+
+impl MetricHistogramWithLabels {
+    /// Create a new metric histogram with the given label names using the default buckets.
+    pub fn new(logger: Logger, name: &str, help: &str, labels: MetricLabels) -> StdResult<Self> {
+        let histogram = HistogramVec::new(HistogramOpts::new(name, help), labels)?;
+        Ok(Self {
+            logger,
+            name: name.to_string(),
+            label_names: labels.iter().map(|label| label.to_string()).collect(),
+            histogram: Box::new(histogram),
+        })
+    }
+
+    /// Record a value in the histogram for the given label values.
+    pub fn observe<T: Into<f64>>(&self, label_values: MetricLabels, value: T) {
+        let value = value.into();
+        debug!(
+            self.logger,
+            "Observe '{}' histogram value {}", self.name, value; "labels" => ?label_values
+        );
+        match self.histogram.get_metric_with_label_values(label_values) {
+            Ok(histogram) => histogram.observe(value),
+            Err(error) => {
+                warn!(self.logger, "Invalid labels for '{}' histogram", self.name; "labels" => ?label_values, "error" => ?error)
+            }
+        }
+    }
+
+    /// Record a duration in the histogram for the given label values (in seconds).
+    pub fn observe_duration(&self, label_values: MetricLabels, duration: Duration) {
+        self.observe(label_values, duration.as_secs_f64());
+    }
+
+    /// Get the number of values recorded in the histogram for the given label values.
+    pub fn get_sample_count(&self, label_values: MetricLabels) -> u64 {
+        find_labeled_metric(self.histogram.as_ref(), &self.label_names, label_values)
+            .map(|metric| metric.get_histogram().get_sample_count())
+            .unwrap_or_default()
+    }
+}
+
+impl MetricCollector for MetricHistogramWithLabels {
+    fn collector(&self) -> Box<dyn Collector> {
+        self.histogram.clone()
+    }
+
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::helper::test_tools::TestLogger;
@@ -158,4 +433,142 @@ mod tests {
         metric.increment_by(37);
         assert_eq!(metric.get(), 37);
     }
+
+    #[test]
+    fn test_metric_histogram_can_observe_values() {
+        let metric = MetricHistogram::new(
+            TestLogger::stdout(),
+            "test_histogram",
+            "test histogram help",
+        )
+        .unwrap();
+        assert_eq!(metric.name(), "test_histogram");
+        assert_eq!(metric.get_sample_count(), 0);
+
+        metric.observe(1.5);
+        metric.observe_duration(Duration::from_millis(500));
+        assert_eq!(metric.get_sample_count(), 2);
+        assert_eq!(metric.get_sample_sum(), 2.0);
+    }
+
+    #[test]
+    fn test_metric_counter_with_labels_are_incremented_independently() {
+        let metric = MetricCounterWithLabels::new(
+            TestLogger::stdout(),
+            "test_counter_with_labels",
+            "test counter help",
+            &["party_id"],
+        )
+        .unwrap();
+
+        metric.increment(&["pool1"]);
+        metric.increment_by(&["pool2"], 3);
+        assert_eq!(metric.get(&["pool1"]), 1);
+        assert_eq!(metric.get(&["pool2"]), 3);
+        assert_eq!(metric.get(&["pool3"]), 0);
+    }
+
+    #[test]
+    fn test_metrics_with_labels_are_read_without_creating_series() {
+        let counter = MetricCounterWithLabels::new(
+            TestLogger::stdout(),
+            "test_counter_with_labels",
+            "test counter help",
+            &["party_id"],
+        )
+        .unwrap();
+        let gauge = MetricGaugeWithLabels::new(
+            TestLogger::stdout(),
+            "test_gauge_with_labels",
+            "test gauge help",
+            &["party_id"],
+        )
+        .unwrap();
+        let histogram = MetricHistogramWithLabels::new(
+            TestLogger::stdout(),
+            "test_histogram_with_labels",
+            "test histogram help",
+            &["route"],
+        )
+        .unwrap();
+
+        assert_eq!(counter.get(&["pool1"]), 0);
+        assert_eq!(gauge.get(&["pool1"]), 0.0);
+        assert_eq!(histogram.get_sample_count(&["/certificates"]), 0);
+
+        for collector in [
+            counter.collector(),
+            gauge.collector(),
+            histogram.collector(),
+        ] {
+            let series_count: usize = collector
+                .collect()
+                .iter()
+                .map(|family| family.get_metric().len())
+                .sum();
+            assert_eq!(series_count, 0);
+        }
+    }
+
+    #[test]
+    fn test_metric_with_multiple_labels_is_read_with_the_label_values_in_declaration_order() {
+        let metric = MetricCounterWithLabels::new(
+            TestLogger::stdout(),
+            "test_counter_with_labels",
+            "test counter help",
+            &["route", "method"],
+        )
+        .unwrap();
+
+        metric.increment(&["/certificates", "GET"]);
+        assert_eq!(metric.get(&["/certificates", "GET"]), 1);
+        assert_eq!(metric.get(&["GET", "/certificates"]), 0);
+    }
+
+    #[test]
+    fn test_metric_with_labels_ignore_invalid_label_values() {
+        let metric = MetricCounterWithLabels::new(
+            TestLogger::stdout(),
+            "test_counter_with_labels",
+            "test counter help",
+            &["party_id"],
+        )
+        .unwrap();
+
+        metric.increment(&["pool1", "unexpected"]);
+        assert_eq!(metric.get(&["pool1", "unexpected"]), 0);
+    }
+
+    #[test]
+    fn test_metric_gauge_with_labels_can_be_recorded_and_removed() {
+        let metric = MetricGaugeWithLabels::new(
+            TestLogger::stdout(),
+            "test_gauge_with_labels",
+            "test gauge help",
+            &["party_id"],
+        )
+        .unwrap();
+
+        metric.record(&["pool1"], 12.3);
+        assert_eq!(metric.get(&["pool1"]), 12.3);
+
+        metric.remove(&["pool1"]);
+        assert_eq!(metric.get(&["pool1"]), 0.0);
+    }
+
+    #[test]
+    fn test_metric_histogram_with_labels_can_observe_values() {
+        let metric = MetricHistogramWithLabels::new(
+            TestLogger::stdout(),
+            "test_histogram_with_labels",
+            "test histogram help",
+            &["route"],
+        )
+        .unwrap();
+
+        metric.observe(&["/certificates"], 0.2);
+        metric.observe_duration(&["/certificates"], Duration::from_millis(100));
+        assert_eq!(metric.get_sample_count(&["/certificates"]), 2);
+        assert_eq!(metric.get_sample_count(&["/artifacts"]), 0);
+    }
 }
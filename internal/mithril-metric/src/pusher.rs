@@ -0,0 +1,328 @@
+use anyhow::{anyhow, Context};
+use reqwest::{header, RequestBuilder, Url};
+use slog::{debug, info, warn, Logger};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use mithril_common::logging::LoggerExtensions;
+use mithril_common::StdResult;
+
+use crate::remote_write::encode_remote_write_request;
+use crate::MetricsServiceExporter;
+
+/// Timeout of a push of the metrics
+const PUSH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Protocol used to push the metrics
+enum PushTarget {
+    /// Prometheus Pushgateway, the metrics are grouped by job and instance in the URL
+    Pushgateway,
+    /// Prometheus remote-write endpoint, the job and instance are added as labels of each metric
+    RemoteWrite { job: String, instance: String },
+}
+
+/// The MetricsPusher is responsible for pushing the metrics to a Prometheus Pushgateway or
+/// remote-write endpoint, for nodes that can't be scraped.
+pub struct MetricsPusher<T: MetricsServiceExporter> {
+    client: reqwest::Client,
+    push_url: Url,
+    target: PushTarget,
+    metrics_service: Arc<T>,
+    logger: Logger,
+}
+
+impl<T: MetricsServiceExporter> MetricsPusher<T> {
+    /// Create a new MetricsPusher instance, pushing the metrics to the given Pushgateway
+    /// grouped by the given job and instance labels.
+    pub fn new(
+        pushgateway_url: &str,
+        job: &str,
+        instance: &str,
+        metrics_service: Arc<T>,
+        logger: Logger,
+    ) -> StdResult<Self> {
+        let mut push_url = Url::parse(pushgateway_url)
+            .with_context(|| format!("Invalid Pushgateway URL '{pushgateway_url}'"))?;
+        push_url
+            .path_segments_mut()
+            .map_err(|_| anyhow!("Invalid Pushgateway URL '{pushgateway_url}'"))?
+            .pop_if_empty()
+            .extend(["metrics", "job", job, "instance", instance]);
+
+        Self::with_target(push_url, PushTarget::Pushgateway, metrics_service, logger)
+    }
+
+    /// Create a new MetricsPusher instance, pushing the metrics to the given Prometheus
+    /// remote-write endpoint with the given job and instance labels.
+    pub fn new_remote_write(
+        remote_write_url: &str,
+        job: &str,
+        instance: &str,
+        metrics_service: Arc<T>,
+        logger: Logger,
+    ) -> StdResult<Self> {
+        let push_url = Url::parse(remote_write_url)
+            .with_context(|| format!("Invalid remote-write URL '{remote_write_url}'"))?;
+        let target = PushTarget::RemoteWrite {
+            job: job.to_string(),
+            instance: instance.to_string(),
+        };
+
+        Self::with_target(push_url, target, metrics_service, logger)
+    }
+
+    fn with_target(
+        push_url: Url,
+        target: PushTarget,
+        metrics_service: Arc<T>,
+        logger: Logger,
+    ) -> StdResult<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(PUSH_TIMEOUT)
+            .build()
+            .with_context(|| "Could not build the metrics push HTTP client")?;
+
+        Ok(Self {
+            client,
+            push_url,
+            target,
+            metrics_service,
+            logger: logger.new_with_component_name::<Self>(),
+        })
+    }
+
+    /// URL to which the metrics are pushed, for a Pushgateway the URL of the group of the metrics.
+    pub fn push_url(&self) -> &str {
+        self.push_url.as_str()
+    }
+
+    /// Push the metrics, replacing the previously pushed metrics of the group for a Pushgateway.
+    pub async fn push(&self) -> StdResult<()> {
+        let metrics = self.metrics_service.export_metrics()?;
+        let response = self
+            .build_push_request(metrics)?
+            .send()
+            .await
+            .with_context(|| format!("Could not push metrics to '{}'", self.push_url))?;
+
+        if !response.status().is_success() {
+            return Err(anyhow!(
+                "Metrics push endpoint '{}' responded with status code '{}'",
+                self.push_url,
+                response.status()
+            ));
+        }
+        debug!(self.logger, "Metrics pushed"; "push_url" => self.push_url.as_str());
+
+        Ok(())
+    }
+
+    fn build_push_request(&self, metrics: String) -> StdResult<RequestBuilder> {
+        Ok(match &self.target {
+            PushTarget::Pushgateway => self
+                .client
+                .put(self.push_url.clone())
+                .header(header::CONTENT_TYPE, "text/plain; version=0.0.4")
+                .body(metrics),
+            PushTarget::RemoteWrite { job, instance } => {
+                let timestamp_ms = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .with_context(|| "System time is before the UNIX epoch")?
+                    .as_millis() as i64;
+                let body = encode_remote_write_request(
+                    &metrics,
+                    &[("job", job), ("instance", instance)],
+                    timestamp_ms,
+                )?;
+                self.client
+                    .post(self.push_url.clone())
+                    .header(header::CONTENT_TYPE, "application/x-protobuf")
+                    .header(header::CONTENT_ENCODING, "snappy")
+                    .header("X-Prometheus-Remote-Write-Version", "0.1.0")
+                    .body(body)
+            }
+        })
+    }
+
+    /// Push the metrics at the given interval, forever.
+    pub async fn run_forever(&self, push_interval: Duration) {
+        info!(
+            self.logger, "Pushing metrics periodically";
+            "push_url" => self.push_url.as_str(), "interval" => ?push_interval
+        );
+        let mut interval = tokio::time::interval(push_interval);
+
+        loop {
+            interval.tick().await;
+            if let Err(error) = self.push().await {
+                warn!(self.logger, "Metrics push failed"; "error" => ?error);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        extract::State,
+        http::StatusCode,
+        routing::{post, put},
+        Router,
+    };
+    use tokio::net::TcpListener;
+    use tokio::sync::Mutex;
+
+    use crate::helper::test_tools::TestLogger;
+
+    use super::*;
+
+    /// Bodies pushed to the fake Pushgateway, by path
+    type PushedBodies = Arc<Mutex<Vec<(String, String)>>>;
+
+    /// Content encoding and decompressed body of the requests received by the fake remote-write
+    /// endpoint
+    type ReceivedRequests = Arc<Mutex<Vec<(String, Vec<u8>)>>>;
+
+    struct PseudoMetricsService {}
+
+    impl MetricsServiceExporter for PseudoMetricsService {
+        fn export_metrics(&self) -> StdResult<String> {
+            Ok("pseudo_metric 1\n".to_string())
+        }
+    }
+
+    /// Start a fake Pushgateway, returning its URL and the pushed bodies by path.
+    async fn start_fake_pushgateway(status: StatusCode) -> (String, PushedBodies) {
+        let pushed = Arc::new(Mutex::new(vec![]));
+        let app = Router::new()
+            .route(
+                "/metrics/job/:job/instance/:instance",
+                put(
+                    move |State(pushed): State<PushedBodies>,
+                          uri: axum::http::Uri,
+                          body: String| async move {
+                        pushed.lock().await.push((uri.path().to_string(), body));
+                        status
+                    },
+                ),
+            )
+            .with_state(pushed.clone());
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        (format!("http://{address}/"), pushed)
+    }
+
+    /// Start a fake remote-write endpoint, returning its URL and the content encoding and
+    /// decompressed body of the received requests.
+    async fn start_fake_remote_write_endpoint() -> (String, ReceivedRequests) {
+        let received = Arc::new(Mutex::new(vec![]));
+        let app = Router::new()
+            .route(
+                "/api/v1/write",
+                post(
+                    move |State(received): State<ReceivedRequests>,
+                          headers: axum::http::HeaderMap,
+                          body: axum::body::Bytes| async move {
+                        let content_encoding = headers
+                            .get(header::CONTENT_ENCODING)
+                            .and_then(|value| value.to_str().ok())
+                            .unwrap_or_default()
+                            .to_string();
+                        let body = snap::raw::Decoder::new().decompress_vec(&body).unwrap();
+                        received.lock().await.push((content_encoding, body));
+                        StatusCode::NO_CONTENT
+                    },
+                ),
+            )
+            .with_state(received.clone());
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        (format!("http://{address}/api/v1/write"), received)
+    }
+
+    #[test]
+    fn push_url_groups_metrics_by_job_and_instance() {
+        let pusher = MetricsPusher::new(
+            "http://pushgateway:9091/",
+            "mithril-signer",
+            "pool1",
+            Arc::new(PseudoMetricsService {}),
+            TestLogger::stdout(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            "http://pushgateway:9091/metrics/job/mithril-signer/instance/pool1",
+            pusher.push_url()
+        );
+    }
+
+    #[tokio::test]
+    async fn push_exported_metrics_to_pushgateway() {
+        let (pushgateway_url, pushed) = start_fake_pushgateway(StatusCode::OK).await;
+        let pusher = MetricsPusher::new(
+            &pushgateway_url,
+            "mithril-signer",
+            "pool1",
+            Arc::new(PseudoMetricsService {}),
+            TestLogger::stdout(),
+        )
+        .unwrap();
+
+        pusher.push().await.unwrap();
+
+        assert_eq!(
+            vec![(
+                "/metrics/job/mithril-signer/instance/pool1".to_string(),
+                "pseudo_metric 1\n".to_string()
+            )],
+            *pushed.lock().await
+        );
+    }
+
+    #[tokio::test]
+    async fn push_exported_metrics_to_remote_write_endpoint() {
+        let (remote_write_url, received) = start_fake_remote_write_endpoint().await;
+        let pusher = MetricsPusher::new_remote_write(
+            &remote_write_url,
+            "mithril-signer",
+            "pool1",
+            Arc::new(PseudoMetricsService {}),
+            TestLogger::stdout(),
+        )
+        .unwrap();
+
+        pusher.push().await.unwrap();
+
+        let received = received.lock().await;
+        assert_eq!(1, received.len());
+        let (content_encoding, body) = &received[0];
+        assert_eq!("snappy", content_encoding);
+        let body = String::from_utf8_lossy(body);
+        for expected in ["pseudo_metric", "mithril-signer", "pool1"] {
+            assert!(body.contains(expected), "'{expected}' not found in {body}");
+        }
+    }
+
+    #[tokio::test]
+    async fn push_fails_if_pushgateway_rejects_the_metrics() {
+        let (pushgateway_url, _) = start_fake_pushgateway(StatusCode::BAD_REQUEST).await;
+        let pusher = MetricsPusher::new(
+            &pushgateway_url,
+            "mithril-signer",
+            "pool1",
+            Arc::new(PseudoMetricsService {}),
+            TestLogger::stdout(),
+        )
+        .unwrap();
+
+        pusher
+            .push()
+            .await
+            .expect_err("Push should fail when the Pushgateway rejects the metrics");
+    }
+}
@@ -0,0 +1,238 @@
+//! Encoding of the metrics exported in the Prometheus text format to a Prometheus remote-write
+//! request, a snappy compressed protobuf `WriteRequest`.
+
+use anyhow::{anyhow, Context};
+
+use mithril_common::StdResult;
+
+/// Remote-write request, see the `prometheus.WriteRequest` protobuf message.
+#[derive(Clone, PartialEq, prost::Message)]
+struct WriteRequest {
+    #[prost(message, repeated, tag = "1")]
+    timeseries: Vec<TimeSeries>,
+}
+
+/// Samples of a metric, see the `prometheus.TimeSeries` protobuf message.
+#[derive(Clone, PartialEq, prost::Message)]
+struct TimeSeries {
+    #[prost(message, repeated, tag = "1")]
+    labels: Vec<Label>,
+    #[prost(message, repeated, tag = "2")]
+    samples: Vec<Sample>,
+}
+
+/// Label of a metric, see the `prometheus.Label` protobuf message.
+#[derive(Clone, PartialEq, prost::Message)]
+struct Label {
+    #[prost(string, tag = "1")]
+    name: String,
+    #[prost(string, tag = "2")]
+    value: String,
+}
+
+/// Sample of a metric, see the `prometheus.Sample` protobuf message.
+#[derive(Clone, PartialEq, prost::Message)]
+struct Sample {
+    #[prost(double, tag = "1")]
+    value: f64,
+    #[prost(int64, tag = "2")]
+    timestamp: i64,
+}
+
+/// Encode the metrics exported in the Prometheus text format to the body of a remote-write
+/// request, adding the given labels to each metric and timestamping all the samples with
+/// `timestamp_ms`.
+pub(crate) fn encode_remote_write_request(
+    metrics: &str,
+    additional_labels: &[(&str, &str)],
+    timestamp_ms: i64,
+) -> StdResult<Vec<u8>> {
+    let mut timeseries = vec![];
+    for line in metrics
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+    {
+        let (name, mut labels, value) = parse_sample_line(line)
+            .with_context(|| format!("Could not parse the metric line '{line}'"))?;
+        let missing_labels = additional_labels
+            .iter()
+            .filter(|(label, _)| labels.iter().all(|l| l.name != *label))
+            .map(|(name, value)| Label {
+                name: name.to_string(),
+                value: value.to_string(),
+            })
+            .collect::<Vec<_>>();
+        labels.extend(missing_labels);
+        labels.push(Label {
+            name: "__name__".to_string(),
+            value: name,
+        });
+        // Remote-write receivers expect the labels sorted by name
+        labels.sort_by(|a, b| a.name.cmp(&b.name));
+
+        timeseries.push(TimeSeries {
+            labels,
+            samples: vec![Sample {
+                value,
+                timestamp: timestamp_ms,
+            }],
+        });
+    }
+
+    let request = prost::Message::encode_to_vec(&WriteRequest { timeseries });
+    snap::raw::Encoder::new()
+        .compress_vec(&request)
+        .with_context(|| "Could not compress the remote-write request")
+}
+
+/// Parse a sample line of the Prometheus text format: `name{label="value",...} value [timestamp]`
+fn parse_sample_line(line: &str) -> StdResult<(String, Vec<Label>, f64)> {
+    let (name, labels, rest) = match line.find('{') {
+        Some(labels_start) => {
+            let (labels, rest) = parse_labels(&line[labels_start + 1..])?;
+            (&line[..labels_start], labels, rest)
+        }
+        None => {
+            let name_end = line
+                .find(char::is_whitespace)
+                .ok_or_else(|| anyhow!("Missing metric value"))?;
+            (&line[..name_end], vec![], &line[name_end..])
+        }
+    };
+    let value = rest
+        .split_whitespace()
+        .next()
+        .ok_or_else(|| anyhow!("Missing metric value"))?
+        .parse::<f64>()
+        .with_context(|| "Invalid metric value")?;
+
+    Ok((name.trim().to_string(), labels, value))
+}
+
+/// Parse the labels of a sample line after the opening brace, returning the labels and the
+/// rest of the line after the closing brace
+fn parse_labels(mut input: &str) -> StdResult<(Vec<Label>, &str)> {
+    let mut labels = vec![];
+    loop {
+        input = input.trim_start_matches(&[',', ' '][..]);
+        if let Some(rest) = input.strip_prefix('}') {
+            return Ok((labels, rest));
+        }
+        let (name, rest) = input
+            .split_once("=\"")
+            .ok_or_else(|| anyhow!("Invalid label in '{input}'"))?;
+        let mut value = String::new();
+        let mut chars = rest.char_indices();
+        let value_end = loop {
+            match chars.next() {
+                Some((_, '\\')) => match chars.next() {
+                    Some((_, 'n')) => value.push('\n'),
+                    Some((_, escaped)) => value.push(escaped),
+                    None => break None,
+                },
+                Some((index, '"')) => break Some(index),
+                Some((_, c)) => value.push(c),
+                None => break None,
+            }
+        };
+        let value_end = value_end.ok_or_else(|| anyhow!("Unterminated label value"))?;
+        labels.push(Label {
+            name: name.trim().to_string(),
+            value,
+        });
+        input = &rest[value_end + 1..];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prost::Message;
+
+    use super::*;
+
+    fn decode(body: &[u8]) -> WriteRequest {
+        let request = snap::raw::Decoder::new().decompress_vec(body).unwrap();
+        WriteRequest::decode(request.as_slice()).unwrap()
+    }
+
+    fn label(name: &str, value: &str) -> Label {
+        Label {
+            name: name.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn encode_the_samples_with_their_labels_and_the_additional_ones() {
+        let metrics = r#"# HELP signer_registration_total Number of registrations
+# TYPE signer_registration_total counter
+signer_registration_total 3
+runtime_cycle{state="idle",escaped="a \"quoted\" \\ value"} 1.5 1700000000000
+"#;
+
+        let body = encode_remote_write_request(
+            metrics,
+            &[("job", "mithril-signer"), ("instance", "pool1")],
+            42,
+        )
+        .unwrap();
+
+        assert_eq!(
+            WriteRequest {
+                timeseries: vec![
+                    TimeSeries {
+                        labels: vec![
+                            label("__name__", "signer_registration_total"),
+                            label("instance", "pool1"),
+                            label("job", "mithril-signer"),
+                        ],
+                        samples: vec![Sample {
+                            value: 3.0,
+                            timestamp: 42
+                        }],
+                    },
+                    TimeSeries {
+                        labels: vec![
+                            label("__name__", "runtime_cycle"),
+                            label("escaped", r#"a "quoted" \ value"#),
+                            label("instance", "pool1"),
+                            label("job", "mithril-signer"),
+                            label("state", "idle"),
+                        ],
+                        samples: vec![Sample {
+                            value: 1.5,
+                            timestamp: 42
+                        }],
+                    },
+                ]
+            },
+            decode(&body)
+        );
+    }
+
+    #[test]
+    fn the_labels_of_the_metric_take_precedence_over_the_additional_ones() {
+        let body = encode_remote_write_request(
+            r#"metric{job="custom"} 1"#,
+            &[("job", "mithril-signer")],
+            42,
+        )
+        .unwrap();
+
+        assert_eq!(
+            vec![label("__name__", "metric"), label("job", "custom")],
+            decode(&body).timeseries[0].labels
+        );
+    }
+
+    #[test]
+    fn encode_fails_on_invalid_metrics() {
+        encode_remote_write_request("metric_without_value", &[], 42)
+            .expect_err("A metric without value should fail");
+        encode_remote_write_request("metric not_a_number", &[], 42)
+            .expect_err("A metric with an invalid value should fail");
+        encode_remote_write_request(r#"metric{label="unterminated} 1"#, &[], 42)
+            .expect_err("A metric with an unterminated label should fail");
+    }
+}
@@ -1,14 +1,24 @@
+use anyhow::{anyhow, Context};
 use axum::{
     body::Body,
-    extract::State,
-    http::{Response, StatusCode},
+    extract::{Request, State},
+    http::{header, Response, StatusCode},
+    middleware::{self, Next},
     response::IntoResponse,
     routing::get,
     Router,
 };
-use slog::{error, info, warn, Logger};
-use std::sync::Arc;
-use tokio::sync::oneshot::Receiver;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use hyper_util::{
+    rt::{TokioExecutor, TokioIo},
+    server::conn::auto,
+    service::TowerToHyperService,
+};
+use slog::{debug, error, info, warn, Logger};
+use std::{fs::File, io::BufReader, path::PathBuf, sync::Arc};
+use subtle::ConstantTimeEq;
+use tokio::{net::TcpListener, sync::oneshot::Receiver};
+use tokio_rustls::{rustls::ServerConfig, TlsAcceptor};
 
 use mithril_common::logging::LoggerExtensions;
 use mithril_common::StdResult;
@@ -37,11 +47,75 @@ impl IntoResponse for MetricsServerError {
     }
 }
 
+/// Credentials required to access the metrics server with HTTP basic authentication.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetricsServerBasicAuth {
+    /// Expected user name.
+    pub username: String,
+    /// Expected password.
+    pub password: String,
+}
+
+impl MetricsServerBasicAuth {
+    fn authorization_header_value(&self) -> String {
+        format!(
+            "Basic {}",
+            STANDARD.encode(format!("{}:{}", self.username, self.password))
+        )
+    }
+}
+
+/// Certificate and private key used to serve the metrics over HTTPS.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetricsServerTlsConfig {
+    /// Path of the PEM encoded certificate chain.
+    pub certificate_path: PathBuf,
+    /// Path of the PEM encoded private key.
+    pub private_key_path: PathBuf,
+}
+
+impl MetricsServerTlsConfig {
+    fn build_server_config(&self) -> StdResult<ServerConfig> {
+        let open = |path: &PathBuf| {
+            File::open(path)
+                .map(BufReader::new)
+                .with_context(|| format!("Could not open '{}'", path.display()))
+        };
+        let certificates = rustls_pemfile::certs(&mut open(&self.certificate_path)?)
+            .collect::<Result<Vec<_>, _>>()
+            .with_context(|| {
+                format!(
+                    "Could not read the certificates of '{}'",
+                    self.certificate_path.display()
+                )
+            })?;
+        let private_key = rustls_pemfile::private_key(&mut open(&self.private_key_path)?)
+            .with_context(|| {
+                format!(
+                    "Could not read the private key of '{}'",
+                    self.private_key_path.display()
+                )
+            })?
+            .ok_or(anyhow!(
+                "No private key found in '{}'",
+                self.private_key_path.display()
+            ))?;
+
+        ServerConfig::builder()
+            .with_no_client_auth()
+            .with_single_cert(certificates, private_key)
+            .with_context(|| "Invalid metrics server TLS certificate or private key")
+    }
+}
+
 /// The MetricsServer is responsible for exposing the metrics of the signer.
 pub struct MetricsServer<T: MetricsServiceExporter> {
     server_port: u16,
     server_ip: String,
     metrics_service: Arc<T>,
+    basic_auth: Option<MetricsServerBasicAuth>,
+    tls_config: Option<MetricsServerTlsConfig>,
+    additional_routes: Router,
     logger: Logger,
 }
 
@@ -57,13 +131,40 @@ impl<T: MetricsServiceExporter + 'static> MetricsServer<T> {
             server_port,
             server_ip: server_ip.to_string(),
             metrics_service,
+            basic_auth: None,
+            tls_config: None,
+            additional_routes: Router::new(),
             logger: logger.new_with_component_name::<Self>(),
         }
     }
 
+    /// Require HTTP basic authentication to access the metrics.
+    pub fn with_basic_auth(mut self, basic_auth: Option<MetricsServerBasicAuth>) -> Self {
+        self.basic_auth = basic_auth;
+        self
+    }
+
+    /// Serve the metrics over HTTPS.
+    pub fn with_tls(mut self, tls_config: Option<MetricsServerTlsConfig>) -> Self {
+        self.tls_config = tls_config;
+        self
+    }
+
+    /// Serve the given routes alongside the metrics, behind the same authentication.
+    pub fn with_additional_routes(mut self, routes: Router) -> Self {
+        self.additional_routes = routes;
+        self
+    }
+
     /// Metrics server endpoint.
     pub fn endpoint(&self) -> String {
-        format!("http://{}:{}", self.server_ip, self.server_port)
+        let scheme = if self.tls_config.is_some() {
+            "https"
+        } else {
+            "http"
+        };
+
+        format!("{scheme}://{}:{}", self.server_ip, self.server_port)
     }
 
     /// Serve the metrics on an HTTP server.
@@ -77,7 +178,7 @@ impl<T: MetricsServiceExporter + 'static> MetricsServer<T> {
             metrics_service: self.metrics_service.clone(),
             logger: self.logger.clone(),
         });
-        let app = Router::new()
+        let mut app = Router::new()
             .route(
                 "/metrics",
                 get(|State(state): State<Arc<RouterState<T>>>| async move {
@@ -87,10 +188,21 @@ impl<T: MetricsServiceExporter + 'static> MetricsServer<T> {
                     })
                 }),
             )
-            .with_state(router_state);
+            .with_state(router_state)
+            .merge(self.additional_routes.clone());
+        if let Some(basic_auth) = &self.basic_auth {
+            app = app.layer(middleware::from_fn_with_state(
+                Arc::new(basic_auth.authorization_header_value()),
+                check_basic_auth,
+            ));
+        }
         let listener =
-            tokio::net::TcpListener::bind(format!("{}:{}", self.server_ip, self.server_port))
-                .await?;
+            TcpListener::bind(format!("{}:{}", self.server_ip, self.server_port)).await?;
+
+        if let Some(tls_config) = &self.tls_config {
+            let acceptor = TlsAcceptor::from(Arc::new(tls_config.build_server_config()?));
+            return self.serve_tls(listener, acceptor, app, shutdown_rx).await;
+        }
 
         let serve_logger = self.logger.clone();
         axum::serve(listener, app)
@@ -105,6 +217,78 @@ impl<T: MetricsServiceExporter + 'static> MetricsServer<T> {
 
         Ok(())
     }
+
+    async fn serve_tls(
+        &self,
+        listener: TcpListener,
+        acceptor: TlsAcceptor,
+        app: Router,
+        mut shutdown_rx: Receiver<()>,
+    ) -> StdResult<()> {
+        loop {
+            tokio::select! {
+                _ = &mut shutdown_rx => {
+                    warn!(self.logger, "shutting down HTTPS server after receiving signal");
+                    return Ok(());
+                }
+                accepted = listener.accept() => {
+                    let (stream, peer_address) = match accepted {
+                        Ok(accepted) => accepted,
+                        Err(e) => {
+                            warn!(self.logger, "Could not accept connection"; "error" => ?e);
+                            continue;
+                        }
+                    };
+                    let acceptor = acceptor.clone();
+                    let service = TowerToHyperService::new(app.clone());
+                    let logger = self.logger.clone();
+                    tokio::spawn(async move {
+                        let stream = match acceptor.accept(stream).await {
+                            Ok(stream) => stream,
+                            Err(e) => {
+                                debug!(logger, "TLS handshake failed"; "peer" => %peer_address, "error" => ?e);
+                                return;
+                            }
+                        };
+                        if let Err(e) = auto::Builder::new(TokioExecutor::new())
+                            .serve_connection(TokioIo::new(stream), service)
+                            .await
+                        {
+                            debug!(logger, "Error while serving connection"; "peer" => %peer_address, "error" => ?e);
+                        }
+                    });
+                }
+            }
+        }
+    }
+}
+
+async fn check_basic_auth(
+    State(expected_authorization): State<Arc<String>>,
+    request: Request,
+    next: Next,
+) -> Response<Body> {
+    let authorization = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok());
+
+    let is_authorized = authorization.is_some_and(|authorization| {
+        bool::from(
+            authorization
+                .as_bytes()
+                .ct_eq(expected_authorization.as_bytes()),
+        )
+    });
+    if is_authorized {
+        next.run(request).await
+    } else {
+        (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, "Basic realm=\"metrics\"")],
+        )
+            .into_response()
+    }
 }
 
 #[cfg(test)]
@@ -166,4 +350,121 @@ mod tests {
 
         shutdown_tx.send(()).unwrap();
     }
+
+    #[tokio::test]
+    async fn test_metrics_server_with_basic_auth() {
+        let logger = TestLogger::stdout();
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        let metrics_server = Arc::new(
+            MetricsServer::new(
+                "0.0.0.0",
+                9091,
+                Arc::new(PseudoMetricsService::new()),
+                logger,
+            )
+            .with_basic_auth(Some(MetricsServerBasicAuth {
+                username: "prometheus".to_string(),
+                password: "secret".to_string(),
+            })),
+        );
+        let metrics_server_endpoint = metrics_server.endpoint();
+
+        let exported_metrics_test = tokio::spawn(async move {
+            // Yield to make sure the web server starts first.
+            yield_now().await;
+            let client = reqwest::Client::new();
+            let url = format!("{metrics_server_endpoint}/metrics");
+
+            let response = client.get(&url).send().await.unwrap();
+            assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+
+            let response = client
+                .get(&url)
+                .basic_auth("prometheus", Some("wrong"))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+
+            let response = client
+                .get(&url)
+                .basic_auth("prometheus", Some("secret"))
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(StatusCode::OK, response.status());
+            assert_eq!("pseudo metrics", response.text().await.unwrap());
+        });
+
+        tokio::select!(
+            res =  metrics_server.start(shutdown_rx)  => Err(anyhow!("Metrics server exited with value '{res:?}'")),
+            _res = sleep(Duration::from_secs(1)) => Err(anyhow!("Timeout: The test should have already completed.")),
+            res = exported_metrics_test => res.map_err(|e| e.into()),
+        )
+        .unwrap();
+
+        shutdown_tx.send(()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_metrics_server_with_additional_routes() {
+        let logger = TestLogger::stdout();
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        let metrics_server = Arc::new(
+            MetricsServer::new(
+                "0.0.0.0",
+                9093,
+                Arc::new(PseudoMetricsService::new()),
+                logger,
+            )
+            .with_additional_routes(Router::new().route("/status", get(|| async { "ok" }))),
+        );
+        let metrics_server_endpoint = metrics_server.endpoint();
+
+        let additional_route_test = tokio::spawn(async move {
+            // Yield to make sure the web server starts first.
+            yield_now().await;
+
+            let response = reqwest::get(format!("{metrics_server_endpoint}/status"))
+                .await
+                .unwrap();
+            assert_eq!(StatusCode::OK, response.status());
+            assert_eq!("ok", response.text().await.unwrap());
+
+            let response = reqwest::get(format!("{metrics_server_endpoint}/metrics"))
+                .await
+                .unwrap();
+            assert_eq!(StatusCode::OK, response.status());
+        });
+
+        tokio::select!(
+            res =  metrics_server.start(shutdown_rx)  => Err(anyhow!("Metrics server exited with value '{res:?}'")),
+            _res = sleep(Duration::from_secs(1)) => Err(anyhow!("Timeout: The test should have already completed.")),
+            res = additional_route_test => res.map_err(|e| e.into()),
+        )
+        .unwrap();
+
+        shutdown_tx.send(()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_metrics_server_with_missing_tls_certificate_fails_to_start() {
+        let (_shutdown_tx, shutdown_rx) = oneshot::channel();
+        let metrics_server = MetricsServer::new(
+            "0.0.0.0",
+            9092,
+            Arc::new(PseudoMetricsService::new()),
+            TestLogger::stdout(),
+        )
+        .with_tls(Some(MetricsServerTlsConfig {
+            certificate_path: PathBuf::from("/not/existing/certificate.pem"),
+            private_key_path: PathBuf::from("/not/existing/key.pem"),
+        }));
+
+        assert_eq!("https://0.0.0.0:9092", metrics_server.endpoint());
+        metrics_server
+            .start(shutdown_rx)
+            .await
+            .expect_err("Starting with a missing TLS certificate should fail");
+    }
 }
@@ -81,6 +81,17 @@ impl GetCardanoTransactionQuery {
         }
     }
 
+    pub fn with_highest_block_number_below_or_equal_block_number(
+        block_number: BlockNumber,
+    ) -> Self {
+        Self {
+            condition: WhereCondition::new(
+                "block_number = (select max(block_number) from cardano_tx where block_number <= ?*)",
+                vec![Value::Integer(*block_number as i64)],
+            ),
+        }
+    }
+
     pub fn with_highest_block_number() -> Self {
         Self {
             condition: WhereCondition::new(
@@ -227,4 +238,55 @@ mod tests {
             records
         );
     }
+
+    #[test]
+    fn with_highest_block_number_below_or_equal_block_number() {
+        let connection = cardano_tx_db_connection().unwrap();
+
+        let cursor = connection
+            .fetch(
+                GetCardanoTransactionQuery::with_highest_block_number_below_or_equal_block_number(
+                    BlockNumber(20),
+                ),
+            )
+            .unwrap();
+        assert_eq!(0, cursor.count());
+
+        insert_transactions(
+            &connection,
+            vec![
+                transaction_record(BlockNumber(10), SlotNumber(50)),
+                transaction_record(BlockNumber(10), SlotNumber(51)),
+                transaction_record(BlockNumber(14), SlotNumber(54)),
+                transaction_record(BlockNumber(15), SlotNumber(55)),
+            ],
+        );
+
+        let records: Vec<CardanoTransactionRecord> = connection
+            .fetch_collect(
+                GetCardanoTransactionQuery::with_highest_block_number_below_or_equal_block_number(
+                    BlockNumber(13),
+                ),
+            )
+            .unwrap();
+        assert_eq!(
+            vec![
+                transaction_record(BlockNumber(10), SlotNumber(50)),
+                transaction_record(BlockNumber(10), SlotNumber(51)),
+            ],
+            records
+        );
+
+        let records: Vec<CardanoTransactionRecord> = connection
+            .fetch_collect(
+                GetCardanoTransactionQuery::with_highest_block_number_below_or_equal_block_number(
+                    BlockNumber(14),
+                ),
+            )
+            .unwrap();
+        assert_eq!(
+            vec![transaction_record(BlockNumber(14), SlotNumber(54))],
+            records
+        );
+    }
 }
@@ -125,6 +125,24 @@ impl CardanoTransactionRepository {
         }))
     }
 
+    /// Get the highest [ChainPoint] of the cardano transactions stored in the database with a
+    /// block number lower or equal to the given one.
+    pub async fn get_transaction_chain_point_below_or_equal_block_number(
+        &self,
+        block_number: BlockNumber,
+    ) -> StdResult<Option<ChainPoint>> {
+        let first_transaction_with_highest_block_number =
+            self.connection_pool.connection()?.fetch_first(
+                GetCardanoTransactionQuery::with_highest_block_number_below_or_equal_block_number(
+                    block_number,
+                ),
+            )?;
+
+        Ok(first_transaction_with_highest_block_number.map(|record| {
+            ChainPoint::new(record.slot_number, record.block_number, record.block_hash)
+        }))
+    }
+
     /// Get the highest start [BlockNumber] of the block range roots stored in the database.
     pub async fn get_highest_start_block_number_for_block_range_roots(
         &self,
@@ -954,6 +972,42 @@ mod tests {
         assert_eq!(transaction_block_number_retrieved, Some(BlockNumber(100)));
     }
 
+    #[tokio::test]
+    async fn repository_get_transaction_chain_point_below_or_equal_block_number() {
+        let connection = cardano_tx_db_connection().unwrap();
+        let repository = CardanoTransactionRepository::new(Arc::new(
+            SqliteConnectionPool::build_from_connection(connection),
+        ));
+
+        let chain_point = repository
+            .get_transaction_chain_point_below_or_equal_block_number(BlockNumber(100))
+            .await
+            .unwrap();
+        assert_eq!(None, chain_point);
+
+        repository
+            .create_transactions(vec![
+                CardanoTransactionRecord::new("tx-1", BlockNumber(95), SlotNumber(490), "block-1"),
+                CardanoTransactionRecord::new("tx-2", BlockNumber(100), SlotNumber(500), "block-2"),
+                CardanoTransactionRecord::new("tx-3", BlockNumber(101), SlotNumber(501), "block-3"),
+            ])
+            .await
+            .unwrap();
+
+        let chain_point = repository
+            .get_transaction_chain_point_below_or_equal_block_number(BlockNumber(99))
+            .await
+            .unwrap();
+        assert_eq!(
+            Some(ChainPoint {
+                slot_number: SlotNumber(490),
+                block_number: BlockNumber(95),
+                block_hash: "block-1".to_string()
+            }),
+            chain_point
+        );
+    }
+
     #[tokio::test]
     async fn repository_store_block_range() {
         let connection = cardano_tx_db_connection().unwrap();
@@ -19,6 +19,11 @@ pub enum SqliteCleaningTask {
     ///
     /// see: <https://www.sqlite.org/pragma.html#pragma_wal_checkpoint>
     WalCheckpointTruncate,
+    /// Gather statistics about the tables and indexes so the query planner can make better
+    /// choices.
+    ///
+    /// see: <https://www.sqlite.org/lang_analyze.html>
+    Analyze,
 }
 
 impl SqliteCleaningTask {
@@ -29,6 +34,7 @@ impl SqliteCleaningTask {
             SqliteCleaningTask::WalCheckpointTruncate => {
                 "Running `wal_checkpoint(TRUNCATE)` on the SQLite database"
             }
+            SqliteCleaningTask::Analyze => "Running `analyze` on the SQLite database",
         }
     }
 }
@@ -72,6 +78,11 @@ impl<'a> SqliteCleaner<'a> {
             self.connection.execute("vacuum")?;
         }
 
+        if self.tasks.contains(&SqliteCleaningTask::Analyze) {
+            debug!(self.logger, "{}", SqliteCleaningTask::Analyze.log_message());
+            self.connection.execute("analyze")?;
+        }
+
         // Important: If WAL is enabled Vacuuming the database will not shrink until a
         // checkpoint is run, so it must be done after vacuuming.
         // Note: running a checkpoint when the WAL is disabled is harmless.
@@ -169,6 +180,38 @@ mod tests {
             .with_tasks(&[SqliteCleaningTask::WalCheckpointTruncate])
             .run()
             .expect("WalCheckpointTruncate should not fail");
+        SqliteCleaner::new(&connection)
+            .with_tasks(&[SqliteCleaningTask::Analyze])
+            .run()
+            .expect("Analyze should not fail");
+    }
+
+    #[test]
+    fn test_analyze_gather_statistics() {
+        let connection = ConnectionBuilder::open_memory().build().unwrap();
+        add_test_table(&connection);
+        connection
+            .execute("CREATE INDEX test_text_index ON test(text);")
+            .unwrap();
+        fill_test_table(&connection, 0..100);
+
+        SqliteCleaner::new(&connection)
+            .with_tasks(&[SqliteCleaningTask::Analyze])
+            .run()
+            .unwrap();
+
+        let statistics_count = connection
+            .prepare("SELECT count(*) FROM sqlite_stat1")
+            .unwrap()
+            .iter()
+            .next()
+            .unwrap()
+            .unwrap()
+            .read::<i64, _>(0);
+        assert!(
+            statistics_count > 0,
+            "analyze should have filled sqlite_stat1"
+        );
     }
 
     #[test]
@@ -0,0 +1,22 @@
+// build.rs
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/// Locations of the Open API specification of the aggregator: in the crate directory when the
+/// crate is packaged, at the root of the repository otherwise.
+const OPEN_API_SPEC_PATHS: [&str; 2] = ["./openapi.yaml", "../openapi.yaml"];
+
+fn main() {
+    let out_dir = env::var_os("OUT_DIR").unwrap();
+    let open_api_spec_path = OPEN_API_SPEC_PATHS
+        .iter()
+        .map(Path::new)
+        .find(|path| path.exists())
+        .expect("The Open API specification of the aggregator should exist");
+
+    fs::copy(open_api_spec_path, Path::new(&out_dir).join("openapi.yaml")).unwrap();
+
+    println!("cargo:rerun-if-changed={}", open_api_spec_path.display());
+}
@@ -127,6 +127,7 @@ impl CardanoImmutableFilesFullArtifactBuilder {
 
 #[async_trait]
 impl ArtifactBuilder<CardanoDbBeacon, Snapshot> for CardanoImmutableFilesFullArtifactBuilder {
+    #[tracing::instrument(skip_all, fields(beacon = ?beacon, certificate_hash = %certificate.hash))]
     async fn compute_artifact(
         &self,
         beacon: CardanoDbBeacon,
@@ -155,7 +156,8 @@ impl ArtifactBuilder<CardanoDbBeacon, Snapshot> for CardanoImmutableFilesFullArt
 
         let snapshot = self
             .create_snapshot(beacon, &ongoing_snapshot, snapshot_digest, locations)
-            .await?;
+            .await?
+            .with_hash_algorithm(certificate.metadata.hash_algorithm);
 
         Ok(snapshot)
     }
@@ -26,6 +26,7 @@ impl CardanoStakeDistributionArtifactBuilder {
 
 #[async_trait]
 impl ArtifactBuilder<Epoch, CardanoStakeDistribution> for CardanoStakeDistributionArtifactBuilder {
+    #[tracing::instrument(skip_all, fields(epoch = %epoch))]
     async fn compute_artifact(
         &self,
         epoch: Epoch,
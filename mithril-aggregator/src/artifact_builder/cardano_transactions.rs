@@ -30,6 +30,7 @@ impl CardanoTransactionsArtifactBuilder {
 impl ArtifactBuilder<BlockNumber, CardanoTransactionsSnapshot>
     for CardanoTransactionsArtifactBuilder
 {
+    #[tracing::instrument(skip_all, fields(block_number = %beacon, certificate_hash = %certificate.hash))]
     async fn compute_artifact(
         &self,
         beacon: BlockNumber,
@@ -21,6 +21,7 @@ impl MithrilStakeDistributionArtifactBuilder {
 
 #[async_trait]
 impl ArtifactBuilder<Epoch, MithrilStakeDistribution> for MithrilStakeDistributionArtifactBuilder {
+    #[tracing::instrument(skip_all, fields(epoch = %epoch))]
     async fn compute_artifact(
         &self,
         epoch: Epoch,
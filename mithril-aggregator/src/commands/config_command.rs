@@ -0,0 +1,425 @@
+use anyhow::{anyhow, Context};
+use clap::{Parser, Subcommand};
+use config::{builder::DefaultState, ConfigBuilder};
+use mithril_common::crypto_helper::ProtocolGenesisVerificationKey;
+use mithril_common::StdResult;
+use serde::Serialize;
+use slog::{debug, Logger};
+use std::fmt::Display;
+use std::path::Path;
+
+use crate::{
+    dependency_injection::DependenciesBuilder, Configuration, ExecutionEnvironment,
+    SnapshotUploaderType,
+};
+
+/// Configuration tools
+#[derive(Parser, Debug, Clone)]
+pub struct ConfigCommand {
+    /// commands
+    #[clap(subcommand)]
+    pub config_subcommand: ConfigSubCommand,
+}
+
+impl ConfigCommand {
+    pub async fn execute(
+        &self,
+        root_logger: Logger,
+        config_builder: ConfigBuilder<DefaultState>,
+    ) -> StdResult<()> {
+        self.config_subcommand
+            .execute(root_logger, config_builder)
+            .await
+    }
+}
+
+/// Configuration subcommands.
+#[derive(Debug, Clone, Subcommand)]
+pub enum ConfigSubCommand {
+    /// Load the configuration and check that the aggregator can run with it.
+    ///
+    /// Check the store paths, the snapshot uploader credentials, the chain observer
+    /// connectivity and the key material, then print a report.
+    /// The command fails if at least one check is in error.
+    Validate(ValidateConfigSubCommand),
+}
+
+impl ConfigSubCommand {
+    pub async fn execute(
+        &self,
+        root_logger: Logger,
+        config_builder: ConfigBuilder<DefaultState>,
+    ) -> StdResult<()> {
+        match self {
+            Self::Validate(cmd) => cmd.execute(root_logger, config_builder).await,
+        }
+    }
+}
+
+/// Status of a configuration check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    /// The check passed.
+    Ok,
+    /// The check passed but the configuration may not be the expected one.
+    Warning,
+    /// The check failed, the aggregator can't run with this configuration.
+    Error,
+}
+
+/// Result of a configuration check.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConfigCheck {
+    /// Name of the check.
+    pub name: String,
+    /// Status of the check.
+    pub status: CheckStatus,
+    /// Details about the check outcome.
+    pub message: String,
+}
+
+impl ConfigCheck {
+    fn new<N: Into<String>, M: Into<String>>(name: N, status: CheckStatus, message: M) -> Self {
+        Self {
+            name: name.into(),
+            status,
+            message: message.into(),
+        }
+    }
+
+    fn from_result<N: Into<String>, T: Display>(name: N, result: StdResult<T>) -> Self {
+        match result {
+            Ok(message) => Self::new(name, CheckStatus::Ok, message.to_string()),
+            Err(error) => Self::new(name, CheckStatus::Error, format!("{error:?}")),
+        }
+    }
+}
+
+/// Report of the configuration validation.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ConfigValidationReport {
+    /// Executed checks.
+    pub checks: Vec<ConfigCheck>,
+}
+
+impl ConfigValidationReport {
+    fn push(&mut self, check: ConfigCheck) {
+        self.checks.push(check);
+    }
+
+    /// Count the checks with the given status.
+    pub fn count(&self, status: CheckStatus) -> usize {
+        self.checks
+            .iter()
+            .filter(|check| check.status == status)
+            .count()
+    }
+
+    /// Return true if no check failed.
+    pub fn is_valid(&self) -> bool {
+        self.count(CheckStatus::Error) == 0
+    }
+}
+
+/// Validate configuration command.
+#[derive(Parser, Debug, Clone)]
+pub struct ValidateConfigSubCommand {
+    /// Print the report as JSON
+    #[clap(long)]
+    json: bool,
+}
+
+impl ValidateConfigSubCommand {
+    pub async fn execute(
+        &self,
+        root_logger: Logger,
+        config_builder: ConfigBuilder<DefaultState>,
+    ) -> StdResult<()> {
+        let config: Configuration = config_builder
+            .build()
+            .with_context(|| "configuration build error")?
+            .try_deserialize()
+            .with_context(|| "configuration deserialize error")?;
+        debug!(root_logger, "VALIDATE CONFIG command"; "config" => format!("{config:?}"));
+
+        let mut report = Self::check_static_configuration(&config);
+        let mut dependencies_builder = DependenciesBuilder::new(root_logger, config.clone());
+        report.push(ConfigCheck::from_result(
+            "chain_observer",
+            Self::check_chain_observer(&mut dependencies_builder).await,
+        ));
+        report.push(ConfigCheck::from_result(
+            "era_reader",
+            Self::check_era_reader(&mut dependencies_builder).await,
+        ));
+
+        self.print_report(&report)?;
+
+        if report.is_valid() {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "Invalid configuration: {} check(s) failed",
+                report.count(CheckStatus::Error)
+            ))
+        }
+    }
+
+    /// Run the checks that don't need to reach external services.
+    fn check_static_configuration(config: &Configuration) -> ConfigValidationReport {
+        let mut report = ConfigValidationReport::default();
+        report.push(ConfigCheck::from_result(
+            "network",
+            config
+                .get_network()
+                .map(|network| format!("Cardano network: {network}")),
+        ));
+        report.push(ConfigCheck::from_result(
+            "db_directory",
+            Self::check_existing_directory(&config.db_directory),
+        ));
+        report.push(ConfigCheck::from_result(
+            "data_stores_directory",
+            Self::check_writable_directory(&config.data_stores_directory),
+        ));
+        report.push(ConfigCheck::from_result(
+            "snapshot_directory",
+            Self::check_writable_directory(&config.snapshot_directory),
+        ));
+        report.push(Self::check_snapshot_uploader(config));
+        report.push(Self::check_genesis_verification_key(config));
+        report.push(ConfigCheck::from_result(
+            "signed_entity_types",
+            config
+                .compute_allowed_signed_entity_types_discriminants()
+                .map(|discriminants| {
+                    format!(
+                        "Allowed signed entity types: {}",
+                        discriminants
+                            .iter()
+                            .map(|discriminant| discriminant.to_string())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    )
+                }),
+        ));
+
+        report
+    }
+
+    fn check_existing_directory(path: &Path) -> StdResult<String> {
+        if !path.is_dir() {
+            return Err(anyhow!("Directory '{}' does not exist", path.display()));
+        }
+
+        Ok(format!("Directory '{}' exists", path.display()))
+    }
+
+    fn check_writable_directory(path: &Path) -> StdResult<String> {
+        std::fs::create_dir_all(path)
+            .with_context(|| format!("Could not create directory '{}'", path.display()))?;
+        let probe_path = path.join(".mithril-config-validate");
+        std::fs::write(&probe_path, [])
+            .with_context(|| format!("Directory '{}' is not writable", path.display()))?;
+        std::fs::remove_file(&probe_path)
+            .with_context(|| format!("Could not remove file '{}'", probe_path.display()))?;
+
+        Ok(format!("Directory '{}' is writable", path.display()))
+    }
+
+    fn check_snapshot_uploader(config: &Configuration) -> ConfigCheck {
+        let name = "snapshot_uploader";
+        match (&config.environment, config.snapshot_uploader_type) {
+            (ExecutionEnvironment::Production, SnapshotUploaderType::Gcp) => {
+                if config.snapshot_bucket_name.is_none() {
+                    ConfigCheck::new(
+                        name,
+                        CheckStatus::Error,
+                        "Missing 'snapshot_bucket_name' for the GCP uploader",
+                    )
+                } else if std::env::var("GOOGLE_APPLICATION_CREDENTIALS_JSON").is_err() {
+                    ConfigCheck::new(
+                        name,
+                        CheckStatus::Error,
+                        "Missing GOOGLE_APPLICATION_CREDENTIALS_JSON environment variable",
+                    )
+                } else {
+                    ConfigCheck::new(name, CheckStatus::Ok, "GCP uploader credentials found")
+                }
+            }
+            (ExecutionEnvironment::Production, SnapshotUploaderType::Local) => ConfigCheck::new(
+                name,
+                CheckStatus::Warning,
+                "Snapshots are served by the aggregator from its local storage",
+            ),
+            _ => ConfigCheck::new(
+                name,
+                CheckStatus::Ok,
+                "A local uploader is used outside of the production environment",
+            ),
+        }
+    }
+
+    fn check_genesis_verification_key(config: &Configuration) -> ConfigCheck {
+        let name = "genesis_verification_key";
+        match (
+            ProtocolGenesisVerificationKey::from_json_hex(&config.genesis_verification_key),
+            &config.environment,
+        ) {
+            (Ok(_), _) => ConfigCheck::new(name, CheckStatus::Ok, "Genesis verification key decoded"),
+            (Err(error), ExecutionEnvironment::Production) => ConfigCheck::new(
+                name,
+                CheckStatus::Error,
+                format!("Could not decode the genesis verification key: {error:?}"),
+            ),
+            (Err(_), _) => ConfigCheck::new(
+                name,
+                CheckStatus::Warning,
+                "Invalid genesis verification key, a deterministic key is used outside of the production environment",
+            ),
+        }
+    }
+
+    async fn check_chain_observer(
+        dependencies_builder: &mut DependenciesBuilder,
+    ) -> StdResult<String> {
+        let chain_observer = dependencies_builder.get_chain_observer().await?;
+        let epoch = chain_observer
+            .get_current_epoch()
+            .await
+            .with_context(|| "Could not read the current epoch from the chain observer")?
+            .ok_or(anyhow!("The chain observer returned no current epoch"))?;
+
+        Ok(format!("Chain observer reached, current epoch: {epoch}"))
+    }
+
+    async fn check_era_reader(dependencies_builder: &mut DependenciesBuilder) -> StdResult<String> {
+        let chain_observer = dependencies_builder.get_chain_observer().await?;
+        let epoch = chain_observer
+            .get_current_epoch()
+            .await?
+            .ok_or(anyhow!("The chain observer returned no current epoch"))?;
+        let era_reader = dependencies_builder.get_era_reader().await?;
+        let token = era_reader
+            .read_era_epoch_token(epoch)
+            .await
+            .with_context(|| "Could not read the era markers")?;
+        let era = token.get_current_supported_era()?;
+
+        Ok(format!("Current era: {era}"))
+    }
+
+    fn print_report(&self, report: &ConfigValidationReport) -> StdResult<()> {
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(report)?);
+            return Ok(());
+        }
+
+        for check in &report.checks {
+            let status = match check.status {
+                CheckStatus::Ok => "OK",
+                CheckStatus::Warning => "WARNING",
+                CheckStatus::Error => "ERROR",
+            };
+            println!("[{status:<7}] {}: {}", check.name, check.message);
+        }
+        println!(
+            "{} check(s): {} ok, {} warning(s), {} error(s)",
+            report.checks.len(),
+            report.count(CheckStatus::Ok),
+            report.count(CheckStatus::Warning),
+            report.count(CheckStatus::Error)
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mithril_common::test_utils::TempDir;
+
+    use super::*;
+
+    fn check_status(report: &ConfigValidationReport, name: &str) -> CheckStatus {
+        report
+            .checks
+            .iter()
+            .find(|check| check.name == name)
+            .unwrap_or_else(|| panic!("check '{name}' not found"))
+            .status
+    }
+
+    #[test]
+    fn sample_configuration_with_existing_directories_is_valid() {
+        let temp_dir = TempDir::create(
+            "config_command",
+            "sample_configuration_with_existing_directories_is_valid",
+        );
+        let db_directory = temp_dir.join("db");
+        std::fs::create_dir_all(&db_directory).unwrap();
+        let config = Configuration {
+            db_directory,
+            data_stores_directory: temp_dir.join("stores"),
+            snapshot_directory: temp_dir.join("snapshots"),
+            ..Configuration::new_sample()
+        };
+
+        let report = ValidateConfigSubCommand::check_static_configuration(&config);
+
+        assert!(report.is_valid(), "{report:?}");
+        assert!(temp_dir.join("stores").is_dir());
+    }
+
+    #[test]
+    fn missing_db_directory_and_invalid_network_are_errors() {
+        let config = Configuration {
+            db_directory: TempDir::create("config_command", "missing_db_directory").join("db"),
+            network: "unknown".to_string(),
+            network_magic: None,
+            ..Configuration::new_sample()
+        };
+
+        let report = ValidateConfigSubCommand::check_static_configuration(&config);
+
+        assert!(!report.is_valid());
+        assert_eq!(CheckStatus::Error, check_status(&report, "db_directory"));
+        assert_eq!(CheckStatus::Error, check_status(&report, "network"));
+    }
+
+    #[test]
+    fn production_gcp_uploader_without_bucket_is_an_error() {
+        let config = Configuration {
+            environment: ExecutionEnvironment::Production,
+            snapshot_uploader_type: SnapshotUploaderType::Gcp,
+            snapshot_bucket_name: None,
+            ..Configuration::new_sample()
+        };
+
+        let check = ValidateConfigSubCommand::check_snapshot_uploader(&config);
+
+        assert_eq!(CheckStatus::Error, check.status);
+    }
+
+    #[test]
+    fn invalid_genesis_verification_key_is_an_error_only_in_production() {
+        let config = Configuration {
+            genesis_verification_key: "invalid".to_string(),
+            ..Configuration::new_sample()
+        };
+        assert_eq!(
+            CheckStatus::Warning,
+            ValidateConfigSubCommand::check_genesis_verification_key(&config).status
+        );
+
+        let config = Configuration {
+            environment: ExecutionEnvironment::Production,
+            ..config
+        };
+        assert_eq!(
+            CheckStatus::Error,
+            ValidateConfigSubCommand::check_genesis_verification_key(&config).status
+        );
+    }
+}
@@ -0,0 +1,233 @@
+use anyhow::Context;
+use clap::{Parser, Subcommand};
+use config::{builder::DefaultState, ConfigBuilder};
+use mithril_common::StdResult;
+use mithril_persistence::database::ApplicationNodeType;
+use mithril_persistence::sqlite::{ConnectionBuilder, SqliteCleaner, SqliteCleaningTask};
+use slog::{debug, Logger};
+
+use crate::{dependency_injection::DependenciesBuilder, Configuration};
+
+/// Database tools
+#[derive(Parser, Debug, Clone)]
+pub struct DatabaseCommand {
+    /// commands
+    #[clap(subcommand)]
+    pub database_subcommand: DatabaseSubCommand,
+}
+
+impl DatabaseCommand {
+    pub async fn execute(
+        &self,
+        root_logger: Logger,
+        config_builder: ConfigBuilder<DefaultState>,
+    ) -> StdResult<()> {
+        self.database_subcommand
+            .execute(root_logger, config_builder)
+            .await
+    }
+}
+
+/// Database tools commands.
+#[derive(Debug, Clone, Subcommand)]
+pub enum DatabaseSubCommand {
+    /// Apply the pending migrations to the aggregator databases, without starting the aggregator.
+    ///
+    /// The `serve` command applies them at startup, running this command first allows to check
+    /// that an upgrade succeeds before restarting the aggregator.
+    Migrate(MigrateDatabaseSubCommand),
+
+    /// Reclaim the free space of the aggregator databases and update their query planner
+    /// statistics, without applying their pending migrations.
+    ///
+    /// The databases are locked while it runs, it's recommended to stop the aggregator before.
+    Vacuum(VacuumDatabaseSubCommand),
+}
+
+impl DatabaseSubCommand {
+    pub async fn execute(
+        &self,
+        root_logger: Logger,
+        config_builder: ConfigBuilder<DefaultState>,
+    ) -> StdResult<()> {
+        match self {
+            Self::Migrate(cmd) => cmd.execute(root_logger, config_builder).await,
+            Self::Vacuum(cmd) => cmd.execute(root_logger, config_builder).await,
+        }
+    }
+}
+
+fn build_configuration(config_builder: ConfigBuilder<DefaultState>) -> StdResult<Configuration> {
+    config_builder
+        .build()
+        .with_context(|| "configuration build error")?
+        .try_deserialize()
+        .with_context(|| "configuration deserialize error")
+}
+
+/// Database migrate command
+#[derive(Parser, Debug, Clone)]
+pub struct MigrateDatabaseSubCommand {}
+
+impl MigrateDatabaseSubCommand {
+    pub async fn execute(
+        &self,
+        root_logger: Logger,
+        config_builder: ConfigBuilder<DefaultState>,
+    ) -> StdResult<()> {
+        let config = build_configuration(config_builder)?;
+        debug!(root_logger, "DATABASE MIGRATE command"; "config" => format!("{config:?}"));
+        let mut dependencies_builder = DependenciesBuilder::new(root_logger.clone(), config);
+
+        // The migrations are applied when the connections are opened
+        dependencies_builder
+            .get_sqlite_connection()
+            .await
+            .with_context(|| "database migrate: can not migrate the aggregator database")?;
+        dependencies_builder
+            .get_event_store_sqlite_connection()
+            .await
+            .with_context(|| "database migrate: can not migrate the event store database")?;
+        dependencies_builder
+            .get_sqlite_connection_cardano_transaction_pool()
+            .await
+            .with_context(|| {
+                "database migrate: can not migrate the cardano transactions database"
+            })?;
+        println!("The aggregator databases are up to date");
+
+        Ok(())
+    }
+}
+
+/// Database vacuum command
+#[derive(Parser, Debug, Clone)]
+pub struct VacuumDatabaseSubCommand {}
+
+impl VacuumDatabaseSubCommand {
+    pub async fn execute(
+        &self,
+        root_logger: Logger,
+        config_builder: ConfigBuilder<DefaultState>,
+    ) -> StdResult<()> {
+        let config = build_configuration(config_builder)?;
+        debug!(root_logger, "DATABASE VACUUM command"; "config" => format!("{config:?}"));
+        let dependencies_builder = DependenciesBuilder::new(root_logger.clone(), config);
+
+        for (name, path) in dependencies_builder.get_sqlite_database_paths() {
+            let Some(path) = path.filter(|path| path.exists()) else {
+                debug!(
+                    root_logger,
+                    "DATABASE VACUUM: skipping the {name} that does not exist"
+                );
+                continue;
+            };
+            // No migrations are given to the builder, a vacuum must not change the schema
+            let connection = ConnectionBuilder::open_file(&path)
+                .with_node_type(ApplicationNodeType::Aggregator)
+                .with_logger(root_logger.clone())
+                .build()
+                .with_context(|| format!("database vacuum: can not open the {name}"))?;
+
+            SqliteCleaner::new(&connection)
+                .with_logger(root_logger.clone())
+                .with_tasks(&[
+                    SqliteCleaningTask::Vacuum,
+                    SqliteCleaningTask::WalCheckpointTruncate,
+                    SqliteCleaningTask::Analyze,
+                ])
+                .run()
+                .with_context(|| format!("database vacuum: can not clean the {name}"))?;
+            println!("The aggregator {name} has been vacuumed");
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use config::Config;
+    use mithril_common::test_utils::TempDir;
+    use mithril_persistence::sqlite::{ConnectionExtensions, SqliteConnection};
+
+    use crate::test_tools::TestLogger;
+
+    use super::*;
+
+    fn open_connection(path: &std::path::Path) -> SqliteConnection {
+        ConnectionBuilder::open_file(path)
+            .with_node_type(ApplicationNodeType::Aggregator)
+            .build()
+            .unwrap()
+    }
+
+    fn freelist_count(connection: &SqliteConnection) -> i64 {
+        connection
+            .query_single_cell("pragma freelist_count;", &[])
+            .unwrap()
+    }
+
+    /// Create a database with free pages for the vacuum to reclaim
+    fn create_database_with_free_pages(path: &std::path::Path) {
+        let connection = open_connection(path);
+        connection
+            .execute(
+                "pragma auto_vacuum = none;
+                create table test (id integer primary key, text text);",
+            )
+            .unwrap();
+        for id in 0..1000 {
+            connection
+                .execute(format!(
+                    "insert into test (id, text) values ({id}, 'some text to fill the database')"
+                ))
+                .unwrap();
+        }
+        connection.execute("delete from test").unwrap();
+        assert!(freelist_count(&connection) > 0);
+    }
+
+    #[tokio::test]
+    async fn vacuum_all_the_existing_databases_without_migrating_them() {
+        let data_stores_directory = TempDir::create(
+            "database_command",
+            "vacuum_all_the_existing_databases_without_migrating_them",
+        );
+        let configuration = Configuration {
+            data_stores_directory: data_stores_directory.clone(),
+            ..Configuration::new_sample()
+        };
+        let main_database_path = data_stores_directory.join("aggregator.sqlite3");
+        let cardano_transactions_database_path =
+            data_stores_directory.join("cardano-transaction.sqlite3");
+        create_database_with_free_pages(&main_database_path);
+        create_database_with_free_pages(&cardano_transactions_database_path);
+
+        VacuumDatabaseSubCommand {}
+            .execute(
+                TestLogger::stdout(),
+                Config::builder().add_source(Config::try_from(&configuration).unwrap()),
+            )
+            .await
+            .unwrap();
+
+        for path in [&main_database_path, &cardano_transactions_database_path] {
+            let connection = open_connection(path);
+            assert_eq!(
+                0,
+                freelist_count(&connection),
+                "{} was not vacuumed",
+                path.display()
+            );
+            let migrated_tables: i64 = connection
+                .query_single_cell(
+                    "select count(*) from sqlite_master where name = 'db_version';",
+                    &[],
+                )
+                .unwrap();
+            assert_eq!(0, migrated_tables, "{} was migrated", path.display());
+        }
+        assert!(!data_stores_directory.join("monitoring.sqlite3").exists());
+    }
+}
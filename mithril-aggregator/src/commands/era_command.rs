@@ -1,16 +1,26 @@
-use std::{fs::File, io::Write, path::PathBuf};
+use std::{
+    fs::{self, File},
+    io::Write,
+    path::PathBuf,
+};
 
-use anyhow::Context;
+use anyhow::{anyhow, Context};
 use clap::{Parser, Subcommand};
 use config::{builder::DefaultState, ConfigBuilder};
 use mithril_common::{
-    crypto_helper::{EraMarkersSigner, EraMarkersVerifierSecretKey},
-    entities::{Epoch, HexEncodedEraMarkersSecretKey},
+    crypto_helper::{
+        EraMarkersSigner, EraMarkersVerifierSecretKey, EraMarkersVerifierVerificationKey,
+    },
+    entities::{Epoch, HexEncodedEraMarkersSecretKey, HexEncodedEraMarkersVerificationKey},
+    era::{EraActivationReport, EraMarker},
     StdResult,
 };
+use serde_json::json;
 use slog::{debug, Logger};
 
-use crate::tools::EraTools;
+use super::create_key_usage_audit_logger;
+use crate::services::{AuditAction, AuditEntry};
+use crate::tools::{EraTools, GcpKmsKeySigner, KeySigner};
 
 /// Era tools
 #[derive(Parser, Debug, Clone)]
@@ -40,6 +50,12 @@ pub enum EraSubCommand {
 
     /// Era tx datum generate command.
     GenerateTxDatum(GenerateTxDatumEraSubCommand),
+
+    /// Era markers keys rotation command.
+    RotateKeys(RotateKeysEraSubCommand),
+
+    /// Era activation report command.
+    ActivationReport(ActivationReportEraSubCommand),
 }
 
 impl EraSubCommand {
@@ -51,6 +67,8 @@ impl EraSubCommand {
         match self {
             Self::List(cmd) => cmd.execute(root_logger, config_builder).await,
             Self::GenerateTxDatum(cmd) => cmd.execute(root_logger, config_builder).await,
+            Self::RotateKeys(cmd) => cmd.execute(root_logger, config_builder).await,
+            Self::ActivationReport(cmd) => cmd.execute(root_logger, config_builder).await,
         }
     }
 }
@@ -96,32 +114,85 @@ pub struct GenerateTxDatumEraSubCommand {
     next_era_epoch: Option<u64>,
 
     /// Era Markers Secret Key
-    #[clap(long, env = "ERA_MARKERS_SECRET_KEY")]
-    era_markers_secret_key: HexEncodedEraMarkersSecretKey,
+    #[clap(
+        long,
+        env = "ERA_MARKERS_SECRET_KEY",
+        required_unless_present = "era_markers_kms_key_name"
+    )]
+    era_markers_secret_key: Option<HexEncodedEraMarkersSecretKey>,
+
+    /// Name of the Google Cloud KMS key version holding the era markers secret key, used instead
+    /// of the era markers secret key
+    #[clap(long, conflicts_with = "era_markers_secret_key")]
+    era_markers_kms_key_name: Option<String>,
+
+    /// OAuth2 access token allowed to sign with the Google Cloud KMS key
+    #[clap(long, env = "GCP_KMS_ACCESS_TOKEN", hide_env_values = true)]
+    kms_access_token: Option<String>,
 
     /// Target Path
     #[clap(long)]
     target_path: PathBuf,
+
+    /// Write the datum as hex encoded CBOR, ready to be embedded as an inline datum in a
+    /// transaction, instead of the `cardano-cli` JSON schema
+    #[clap(long)]
+    cbor: bool,
 }
 
 impl GenerateTxDatumEraSubCommand {
     pub async fn execute(
         &self,
         root_logger: Logger,
-        _config_builder: ConfigBuilder<DefaultState>,
+        config_builder: ConfigBuilder<DefaultState>,
     ) -> StdResult<()> {
         debug!(root_logger, "GENERATETXDATUM ERA command");
+        let audit_logger = create_key_usage_audit_logger(&config_builder, root_logger.clone())?;
         let era_tools = EraTools::new();
 
-        let era_markers_secret_key =
-            EraMarkersVerifierSecretKey::from_json_hex(&self.era_markers_secret_key)
-                .with_context(|| "json hex decode of era markers secret key failure")?;
-        let era_markers_signer = EraMarkersSigner::from_secret_key(era_markers_secret_key);
-        let tx_datum = era_tools.generate_tx_datum(
-            Epoch(self.current_era_epoch),
-            self.next_era_epoch.map(Epoch),
-            &era_markers_signer,
-        )?;
+        let era_markers_signer: Box<dyn KeySigner> =
+            match (&self.era_markers_kms_key_name, &self.era_markers_secret_key) {
+                (Some(era_markers_kms_key_name), _) => Box::new(GcpKmsKeySigner::new(
+                    era_markers_kms_key_name,
+                    self.kms_access_token.as_deref().ok_or(anyhow!(
+                        "A KMS access token is required to sign with a KMS key"
+                    ))?,
+                )?),
+                (None, Some(era_markers_secret_key)) => {
+                    let era_markers_secret_key =
+                        EraMarkersVerifierSecretKey::from_json_hex(era_markers_secret_key)
+                            .with_context(|| "json hex decode of era markers secret key failure")?;
+                    Box::new(EraMarkersSigner::from_secret_key(era_markers_secret_key))
+                }
+                (None, None) => {
+                    return Err(anyhow!(
+                    "Either an era markers secret key or an era markers KMS key name is required"
+                ))
+                }
+            };
+        let result = era_tools
+            .generate_tx_datum(
+                Epoch(self.current_era_epoch),
+                self.next_era_epoch.map(Epoch),
+                era_markers_signer.as_ref(),
+            )
+            .await;
+        audit_logger.record(
+            AuditEntry::new(AuditAction::EraMarkersSigned)
+                .with_details(json!({
+                    "current_era_epoch": self.current_era_epoch,
+                    "next_era_epoch": self.next_era_epoch,
+                    "era_markers_kms_key_name": self.era_markers_kms_key_name,
+                }))
+                .with_result(&result),
+        );
+        let tx_datum = result?;
+
+        let tx_datum = if self.cbor {
+            hex::encode(tx_datum.to_cbor()?)
+        } else {
+            tx_datum.0
+        };
 
         let mut target_file = File::create(&self.target_path)?;
         target_file.write_all(tx_datum.as_bytes())?;
@@ -129,3 +200,132 @@ impl GenerateTxDatumEraSubCommand {
         Ok(())
     }
 }
+
+/// Era markers keys rotation command
+#[derive(Parser, Debug, Clone)]
+pub struct RotateKeysEraSubCommand {
+    /// Era Markers Verification Key currently used
+    #[clap(long, env = "ERA_MARKERS_VERIFICATION_KEY")]
+    era_markers_verification_key: HexEncodedEraMarkersVerificationKey,
+
+    /// Current epoch
+    #[clap(long, env = "CURRENT_EPOCH")]
+    current_epoch: u64,
+
+    /// Number of epochs after the current epoch during which the current key pair is still accepted
+    #[clap(long, default_value_t = 2)]
+    overlap_epochs: u64,
+
+    /// Target directory of the new key pair and of the rotated verification keys
+    #[clap(long)]
+    target_path: PathBuf,
+}
+
+impl RotateKeysEraSubCommand {
+    pub async fn execute(
+        &self,
+        root_logger: Logger,
+        _config_builder: ConfigBuilder<DefaultState>,
+    ) -> StdResult<()> {
+        debug!(root_logger, "ROTATEKEYS ERA command");
+        let era_tools = EraTools::new();
+
+        let era_markers_verification_key =
+            EraMarkersVerifierVerificationKey::from_json_hex(&self.era_markers_verification_key)
+                .with_context(|| "json hex decode of era markers verification key failure")?;
+        let rotated_verification_key = era_tools.rotate_keys(
+            era_markers_verification_key,
+            Epoch(self.current_epoch),
+            self.overlap_epochs,
+            &self.target_path,
+        )?;
+
+        println!(
+            "New era markers key pair written to '{}', the current key pair is accepted until epoch {}.",
+            self.target_path.display(),
+            rotated_verification_key.valid_until_epoch
+        );
+        println!(
+            "Add the content of '{}' to the 'rotated_verification_keys' field of the era reader adapter params.",
+            EraTools::ROTATED_VERIFICATION_KEYS_FILENAME
+        );
+
+        Ok(())
+    }
+}
+
+/// Era activation report command
+#[derive(Parser, Debug, Clone)]
+pub struct ActivationReportEraSubCommand {
+    /// Path of a JSON file with the era markers, ie: `[{"name":"thales","epoch":1}]`
+    #[clap(long)]
+    era_markers_path: PathBuf,
+
+    /// Current epoch
+    #[clap(long, env = "CURRENT_EPOCH")]
+    current_epoch: u64,
+
+    /// Enable JSON output.
+    #[clap(long)]
+    json: bool,
+}
+
+impl ActivationReportEraSubCommand {
+    pub async fn execute(
+        &self,
+        root_logger: Logger,
+        _config_builder: ConfigBuilder<DefaultState>,
+    ) -> StdResult<()> {
+        debug!(root_logger, "ACTIVATIONREPORT ERA command");
+        let era_markers: Vec<EraMarker> = serde_json::from_str(
+            &fs::read_to_string(&self.era_markers_path).with_context(|| {
+                format!(
+                    "Could not read era markers file '{}'",
+                    self.era_markers_path.display()
+                )
+            })?,
+        )
+        .with_context(|| "Could not parse the era markers")?;
+        let report = EraActivationReport::compute(&era_markers, Epoch(self.current_epoch));
+
+        if self.json {
+            println!("{}", serde_json::to_string(&report)?);
+        } else {
+            println!("Era activations at epoch {}:", report.current_epoch);
+            for activation in &report.activations {
+                let activation_epoch = activation
+                    .activation_epoch
+                    .map(|epoch| format!("epoch {epoch}"))
+                    .unwrap_or_else(|| "unscheduled".to_string());
+                match &activation.features {
+                    Some(features) => println!(
+                        "- {} ({activation_epoch}, {:?}): supported, signature schemes: {:?}, certifying signature scheme: {:?}, hash algorithm: {:?}",
+                        activation.era,
+                        activation.status,
+                        features.signature_schemes,
+                        features.certifying_signature_scheme,
+                        features.hash_algorithm
+                    ),
+                    None => println!(
+                        "- {} ({activation_epoch}, {:?}): NOT supported by this version",
+                        activation.era, activation.status
+                    ),
+                }
+            }
+        }
+
+        let unsupported_eras = report.unsupported_eras();
+        if !unsupported_eras.is_empty() {
+            return Err(anyhow!(
+                "This version does not support the eras: {}, upgrade before their activation",
+                unsupported_eras
+                    .iter()
+                    .map(|activation| activation.era.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+
+        Ok(())
+    }
+}
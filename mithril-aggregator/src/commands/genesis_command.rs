@@ -1,4 +1,4 @@
-use anyhow::Context;
+use anyhow::{anyhow, Context};
 use clap::{Parser, Subcommand};
 use config::{builder::DefaultState, ConfigBuilder};
 use mithril_common::{
@@ -6,10 +6,17 @@ use mithril_common::{
     entities::HexEncodedGenesisSecretKey,
     StdResult,
 };
+use serde_json::json;
 use slog::{debug, Logger};
-use std::path::PathBuf;
+use std::{path::PathBuf, sync::Arc};
 
-use crate::{dependency_injection::DependenciesBuilder, tools::GenesisTools, Configuration};
+use super::create_key_usage_audit_logger;
+use crate::{
+    dependency_injection::DependenciesBuilder,
+    services::{AuditAction, AuditEntry},
+    tools::{GcpKmsKeySigner, GenesisThresholdTools, GenesisTools, KeySigner},
+    Configuration,
+};
 
 /// Genesis tools
 #[derive(Parser, Debug, Clone)]
@@ -45,6 +52,22 @@ pub enum GenesisSubCommand {
 
     /// Genesis certificate bootstrap command.
     Bootstrap(BootstrapGenesisSubCommand),
+
+    /// Genesis keys generation and genesis certificate bootstrap command, for private networks
+    /// only (UNSAFE).
+    BootstrapDevnet(BootstrapDevnetGenesisSubCommand),
+
+    /// Genesis threshold keys generation command, by a trusted dealer that must run offline.
+    ThresholdKeygen(ThresholdKeygenGenesisSubCommand),
+
+    /// Genesis threshold signature commitment command (first round, run by each keyholder).
+    ThresholdCommit(ThresholdCommitGenesisSubCommand),
+
+    /// Genesis threshold signature share command (second round, run by each keyholder).
+    ThresholdSign(ThresholdSignGenesisSubCommand),
+
+    /// Genesis threshold signature shares aggregation command.
+    ThresholdAggregate(ThresholdAggregateGenesisSubCommand),
 }
 
 impl GenesisSubCommand {
@@ -55,9 +78,14 @@ impl GenesisSubCommand {
     ) -> StdResult<()> {
         match self {
             Self::Bootstrap(cmd) => cmd.execute(root_logger, config_builder).await,
+            Self::BootstrapDevnet(cmd) => cmd.execute(root_logger, config_builder).await,
             Self::Export(cmd) => cmd.execute(root_logger, config_builder).await,
             Self::Import(cmd) => cmd.execute(root_logger, config_builder).await,
             Self::Sign(cmd) => cmd.execute(root_logger, config_builder).await,
+            Self::ThresholdKeygen(cmd) => cmd.execute(root_logger, config_builder).await,
+            Self::ThresholdCommit(cmd) => cmd.execute(root_logger, config_builder).await,
+            Self::ThresholdSign(cmd) => cmd.execute(root_logger, config_builder).await,
+            Self::ThresholdAggregate(cmd) => cmd.execute(root_logger, config_builder).await,
         }
     }
 }
@@ -137,13 +165,20 @@ impl ImportGenesisSubCommand {
                 "Dependencies Builder can not create genesis command dependencies container"
             })?;
 
+        let audit_logger = dependencies_builder.get_audit_logger().await?;
+
         let genesis_tools = GenesisTools::from_dependencies(dependencies)
             .await
             .with_context(|| "genesis-tools: initialization error")?;
-        genesis_tools
+        let result = genesis_tools
             .import_payload_signature(&self.signed_payload_path)
-            .await
-            .with_context(|| "genesis-tools: import error")?;
+            .await;
+        audit_logger.record(
+            AuditEntry::new(AuditAction::GenesisCertificateImport)
+                .with_details(json!({ "signed_payload_path": self.signed_payload_path }))
+                .with_result(&result),
+        );
+        result.with_context(|| "genesis-tools: import error")?;
         Ok(())
     }
 }
@@ -159,30 +194,67 @@ pub struct SignGenesisSubCommand {
     target_signed_payload_path: PathBuf,
 
     /// Genesis Secret Key Path
-    #[clap(long)]
-    genesis_secret_key_path: PathBuf,
+    #[clap(long, required_unless_present = "genesis_kms_key_name")]
+    genesis_secret_key_path: Option<PathBuf>,
+
+    /// Name of the Google Cloud KMS key version holding the genesis secret key, used instead of
+    /// the genesis secret key file
+    #[clap(long, conflicts_with = "genesis_secret_key_path")]
+    genesis_kms_key_name: Option<String>,
+
+    /// OAuth2 access token allowed to sign with the Google Cloud KMS key
+    #[clap(long, env = "GCP_KMS_ACCESS_TOKEN", hide_env_values = true)]
+    kms_access_token: Option<String>,
 }
 
 impl SignGenesisSubCommand {
     pub async fn execute(
         &self,
         root_logger: Logger,
-        _config_builder: ConfigBuilder<DefaultState>,
+        config_builder: ConfigBuilder<DefaultState>,
     ) -> StdResult<()> {
         debug!(root_logger, "SIGN GENESIS command");
+        let audit_logger = create_key_usage_audit_logger(&config_builder, root_logger.clone())?;
         println!(
             "Genesis sign payload from {} to {}",
             self.to_sign_payload_path.to_string_lossy(),
             self.target_signed_payload_path.to_string_lossy()
         );
 
-        GenesisTools::sign_genesis_certificate(
+        let genesis_signer: Box<dyn KeySigner> =
+            match (&self.genesis_kms_key_name, &self.genesis_secret_key_path) {
+                (Some(genesis_kms_key_name), _) => Box::new(GcpKmsKeySigner::new(
+                    genesis_kms_key_name,
+                    self.kms_access_token.as_deref().ok_or(anyhow!(
+                        "A KMS access token is required to sign with a KMS key"
+                    ))?,
+                )?),
+                (None, Some(genesis_secret_key_path)) => Box::new(
+                    GenesisTools::load_genesis_signer(genesis_secret_key_path)
+                        .with_context(|| "genesis-tools: load genesis secret key error")?,
+                ),
+                (None, None) => {
+                    return Err(anyhow!(
+                        "Either a genesis secret key path or a genesis KMS key name is required"
+                    ))
+                }
+            };
+
+        let result = GenesisTools::sign_genesis_certificate(
             &self.to_sign_payload_path,
             &self.target_signed_payload_path,
-            &self.genesis_secret_key_path,
+            genesis_signer.as_ref(),
         )
-        .await
-        .with_context(|| "genesis-tools: sign error")?;
+        .await;
+        audit_logger.record(
+            AuditEntry::new(AuditAction::GenesisPayloadSigned)
+                .with_details(json!({
+                    "to_sign_payload_path": self.to_sign_payload_path,
+                    "genesis_kms_key_name": self.genesis_kms_key_name,
+                }))
+                .with_result(&result),
+        );
+        result.with_context(|| "genesis-tools: sign error")?;
 
         Ok(())
     }
@@ -216,16 +288,356 @@ impl BootstrapGenesisSubCommand {
                 "Dependencies Builder can not create genesis command dependencies container"
             })?;
 
+        let audit_logger = dependencies_builder.get_audit_logger().await?;
+
         let genesis_tools = GenesisTools::from_dependencies(dependencies)
             .await
             .with_context(|| "genesis-tools: initialization error")?;
         let genesis_secret_key = ProtocolGenesisSecretKey::from_json_hex(&self.genesis_secret_key)
             .with_context(|| "json hex decode of genesis secret key failure")?;
         let genesis_signer = ProtocolGenesisSigner::from_secret_key(genesis_secret_key);
-        genesis_tools
+        let result = genesis_tools
             .bootstrap_test_genesis_certificate(genesis_signer)
+            .await;
+        audit_logger
+            .record(AuditEntry::new(AuditAction::GenesisCertificateBootstrap).with_result(&result));
+        result.with_context(|| "genesis-tools: bootstrap error")?;
+        Ok(())
+    }
+}
+
+/// Generate the genesis keys, then sign and store the genesis certificate in one step.
+///
+/// The generated genesis secret key is written unencrypted next to its verification key, so this
+/// command refuses to run on the mainnet and on the public test networks.
+#[derive(Parser, Debug, Clone)]
+pub struct BootstrapDevnetGenesisSubCommand {
+    /// Target directory of the generated genesis keys (`genesis.sk` and `genesis.vk`)
+    #[clap(long)]
+    target_path: PathBuf,
+
+    /// Acknowledge that the generated genesis keys are unsafe and only fit for a private network
+    #[clap(long)]
+    unsafe_generate_genesis_keys: bool,
+}
+
+impl BootstrapDevnetGenesisSubCommand {
+    pub async fn execute(
+        &self,
+        root_logger: Logger,
+        config_builder: ConfigBuilder<DefaultState>,
+    ) -> StdResult<()> {
+        if !self.unsafe_generate_genesis_keys {
+            return Err(anyhow!(
+                "The genesis keys generated by this command are unsafe, acknowledge it with '--unsafe-generate-genesis-keys'"
+            ));
+        }
+        let config: Configuration = config_builder
+            .build()
+            .with_context(|| "configuration build error")?
+            .try_deserialize()
+            .with_context(|| "configuration deserialize error")?;
+        debug!(root_logger, "BOOTSTRAP DEVNET GENESIS command"; "config" => format!("{config:?}"));
+        let network = config.get_network()?;
+        if !network.is_private() {
+            return Err(anyhow!(
+                "Genesis bootstrap with generated keys is forbidden on the public network '{network}'"
+            ));
+        }
+        println!("UNSAFE: genesis bootstrap with generated keys, for private networks only!");
+
+        let genesis_signer = GenesisTools::generate_unsafe_genesis_keys(&self.target_path)
+            .with_context(|| "genesis-tools: keys generation error")?;
+        let genesis_verifier = genesis_signer.create_genesis_verifier();
+        println!(
+            "UNSAFE: genesis keys written unencrypted to {}",
+            self.target_path.display()
+        );
+
+        let mut dependencies_builder =
+            DependenciesBuilder::new(root_logger.clone(), config.clone());
+        let mut dependencies = dependencies_builder
+            .create_genesis_container()
+            .await
+            .with_context(|| {
+                "Dependencies Builder can not create genesis command dependencies container"
+            })?;
+        dependencies.genesis_verifier = Arc::new(genesis_verifier.clone());
+
+        let audit_logger = dependencies_builder.get_audit_logger().await?;
+
+        let genesis_tools = GenesisTools::from_dependencies(dependencies)
             .await
-            .with_context(|| "genesis-tools: bootstrap error")?;
+            .with_context(|| {
+                "genesis-tools: initialization error, the signers must be registered before the genesis bootstrap"
+            })?;
+        let result = genesis_tools
+            .bootstrap_test_genesis_certificate(genesis_signer)
+            .await;
+        audit_logger.record(
+            AuditEntry::new(AuditAction::GenesisCertificateDevnetBootstrap)
+                .with_details(json!({
+                    "network": network.to_string(),
+                    "network_magic": network.code(),
+                    "genesis_keys_path": self.target_path,
+                }))
+                .with_result(&result),
+        );
+        result.with_context(|| "genesis-tools: bootstrap error")?;
+
+        println!(
+            "Genesis certificate bootstrapped, the aggregator must be run with GENESIS_VERIFICATION_KEY={}",
+            genesis_verifier.to_verification_key().to_json_hex()?
+        );
+
+        Ok(())
+    }
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct ThresholdKeygenGenesisSubCommand {
+    /// Minimum number of keyholders needed to sign the genesis certificate
+    #[clap(long)]
+    threshold: u16,
+
+    /// Number of genesis keyholders
+    #[clap(long)]
+    keyholders: u16,
+
+    /// Target directory of the key packages and of the genesis verification key
+    #[clap(long)]
+    target_path: PathBuf,
+}
+
+impl ThresholdKeygenGenesisSubCommand {
+    pub async fn execute(
+        &self,
+        root_logger: Logger,
+        _config_builder: ConfigBuilder<DefaultState>,
+    ) -> StdResult<()> {
+        debug!(root_logger, "THRESHOLD KEYGEN GENESIS command");
+        println!(
+            "Genesis threshold keys generation ({}-of-{}) to {}",
+            self.threshold,
+            self.keyholders,
+            self.target_path.display()
+        );
+        println!(
+            "The key packages must be distributed to the keyholders and removed from this trusted dealer machine"
+        );
+
+        let genesis_verification_key = GenesisThresholdTools::generate_keys(
+            self.threshold,
+            self.keyholders,
+            &self.target_path,
+        )
+        .with_context(|| "genesis-tools: threshold keygen error")?;
+        println!(
+            "Genesis verification key: {}",
+            genesis_verification_key.to_json_hex()?
+        );
+
         Ok(())
     }
 }
+
+#[derive(Parser, Debug, Clone)]
+pub struct ThresholdCommitGenesisSubCommand {
+    /// Key package of the keyholder
+    #[clap(long)]
+    key_package_path: PathBuf,
+
+    /// Target path of the nonces, they must be kept secret until the signature share is created
+    #[clap(long)]
+    target_nonces_path: PathBuf,
+
+    /// Target path of the commitments, they must be sent to the other keyholders
+    #[clap(long)]
+    target_commitments_path: PathBuf,
+}
+
+impl ThresholdCommitGenesisSubCommand {
+    pub async fn execute(
+        &self,
+        root_logger: Logger,
+        _config_builder: ConfigBuilder<DefaultState>,
+    ) -> StdResult<()> {
+        debug!(root_logger, "THRESHOLD COMMIT GENESIS command");
+        println!(
+            "Genesis threshold commitments to {}",
+            self.target_commitments_path.display()
+        );
+
+        GenesisThresholdTools::commit(
+            &self.key_package_path,
+            &self.target_nonces_path,
+            &self.target_commitments_path,
+        )
+        .with_context(|| "genesis-tools: threshold commit error")?;
+
+        Ok(())
+    }
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct ThresholdSignGenesisSubCommand {
+    /// To Sign Payload Path
+    #[clap(long)]
+    to_sign_payload_path: PathBuf,
+
+    /// Commitments of all the signing keyholders (repeat the option for each keyholder)
+    #[clap(long = "commitments-path", required = true)]
+    commitments_paths: Vec<PathBuf>,
+
+    /// Key package of the keyholder
+    #[clap(long)]
+    key_package_path: PathBuf,
+
+    /// Nonces of the keyholder, deleted once used
+    #[clap(long)]
+    nonces_path: PathBuf,
+
+    /// Target path of the signature share
+    #[clap(long)]
+    target_signature_share_path: PathBuf,
+}
+
+impl ThresholdSignGenesisSubCommand {
+    pub async fn execute(
+        &self,
+        root_logger: Logger,
+        config_builder: ConfigBuilder<DefaultState>,
+    ) -> StdResult<()> {
+        debug!(root_logger, "THRESHOLD SIGN GENESIS command");
+        let audit_logger = create_key_usage_audit_logger(&config_builder, root_logger.clone())?;
+        println!(
+            "Genesis threshold sign payload from {} to {}",
+            self.to_sign_payload_path.display(),
+            self.target_signature_share_path.display()
+        );
+
+        let result = GenesisThresholdTools::sign(
+            &self.to_sign_payload_path,
+            &self.commitments_paths,
+            &self.key_package_path,
+            &self.nonces_path,
+            &self.target_signature_share_path,
+        );
+        audit_logger.record(
+            AuditEntry::new(AuditAction::GenesisPayloadThresholdSigned)
+                .with_details(json!({
+                    "to_sign_payload_path": self.to_sign_payload_path,
+                    "key_package_path": self.key_package_path,
+                }))
+                .with_result(&result),
+        );
+        result.with_context(|| "genesis-tools: threshold sign error")?;
+
+        Ok(())
+    }
+}
+
+#[derive(Parser, Debug, Clone)]
+pub struct ThresholdAggregateGenesisSubCommand {
+    /// To Sign Payload Path
+    #[clap(long)]
+    to_sign_payload_path: PathBuf,
+
+    /// Commitments of all the signing keyholders (repeat the option for each keyholder)
+    #[clap(long = "commitments-path", required = true)]
+    commitments_paths: Vec<PathBuf>,
+
+    /// Signature shares of all the signing keyholders (repeat the option for each keyholder)
+    #[clap(long = "signature-share-path", required = true)]
+    signature_share_paths: Vec<PathBuf>,
+
+    /// Public key package of the keyholders group
+    #[clap(long)]
+    public_key_package_path: PathBuf,
+
+    /// Target Signed Payload Path, to use with the `import` command
+    #[clap(long)]
+    target_signed_payload_path: PathBuf,
+}
+
+impl ThresholdAggregateGenesisSubCommand {
+    pub async fn execute(
+        &self,
+        root_logger: Logger,
+        _config_builder: ConfigBuilder<DefaultState>,
+    ) -> StdResult<()> {
+        debug!(root_logger, "THRESHOLD AGGREGATE GENESIS command");
+        println!(
+            "Genesis threshold aggregate {} signature shares to {}",
+            self.signature_share_paths.len(),
+            self.target_signed_payload_path.display()
+        );
+
+        GenesisThresholdTools::aggregate(
+            &self.to_sign_payload_path,
+            &self.commitments_paths,
+            &self.signature_share_paths,
+            &self.public_key_package_path,
+            &self.target_signed_payload_path,
+        )
+        .with_context(|| "genesis-tools: threshold aggregate error")?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use config::Config;
+    use mithril_common::test_utils::TempDir;
+
+    use crate::test_tools::TestLogger;
+
+    use super::*;
+
+    fn config_builder_for_network(network: &str) -> ConfigBuilder<DefaultState> {
+        let configuration = Configuration {
+            network: network.to_string(),
+            network_magic: None,
+            ..Configuration::new_sample()
+        };
+
+        Config::builder().add_source(Config::try_from(&configuration).unwrap())
+    }
+
+    #[tokio::test]
+    async fn bootstrap_devnet_is_refused_without_the_unsafe_acknowledgement() {
+        let target_path = TempDir::create("genesis_command", "bootstrap_devnet_not_acknowledged");
+        let command = BootstrapDevnetGenesisSubCommand {
+            target_path: target_path.clone(),
+            unsafe_generate_genesis_keys: false,
+        };
+
+        command
+            .execute(TestLogger::stdout(), config_builder_for_network("devnet"))
+            .await
+            .expect_err("bootstrap-devnet should be refused without the acknowledgement");
+
+        assert!(!target_path.join("genesis.sk").exists());
+    }
+
+    #[tokio::test]
+    async fn bootstrap_devnet_is_refused_on_a_public_network() {
+        let target_path = TempDir::create("genesis_command", "bootstrap_devnet_public_network");
+        let command = BootstrapDevnetGenesisSubCommand {
+            target_path: target_path.clone(),
+            unsafe_generate_genesis_keys: true,
+        };
+
+        let error = command
+            .execute(TestLogger::stdout(), config_builder_for_network("preview"))
+            .await
+            .expect_err("bootstrap-devnet should be refused on a public network");
+
+        assert!(
+            error.to_string().contains("public network"),
+            "unexpected error: {error}"
+        );
+        assert!(!target_path.join("genesis.sk").exists());
+    }
+}
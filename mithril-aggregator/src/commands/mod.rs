@@ -1,16 +1,20 @@
+mod config_command;
+mod database_command;
 mod era_command;
 mod genesis_command;
 mod serve_command;
 mod tools_command;
 
-use anyhow::anyhow;
+use anyhow::{anyhow, Context};
 use clap::{CommandFactory, Parser, Subcommand};
-use config::{builder::DefaultState, ConfigBuilder, Map, Source, Value, ValueKind};
-use mithril_common::StdResult;
+use config::{builder::DefaultState, ConfigBuilder, ConfigError, Map, Source, Value, ValueKind};
+use mithril_common::{configuration_files::ConfigurationFiles, logging::LogLevelHandle, StdResult};
 use mithril_doc::{Documenter, DocumenterDefault, StructDoc};
 use slog::{debug, Level, Logger};
 use std::path::PathBuf;
+use std::sync::Arc;
 
+use crate::services::{AuditLogger, FileAuditLogger, NoopAuditLogger};
 use crate::{Configuration, DefaultConfiguration};
 use mithril_doc::GenerateDocCommands;
 
@@ -18,7 +22,9 @@ use mithril_doc::GenerateDocCommands;
 #[derive(Debug, Clone, Subcommand)]
 pub enum MainCommand {
     Genesis(genesis_command::GenesisCommand),
+    Config(config_command::ConfigCommand),
     Era(era_command::EraCommand),
+    Database(database_command::DatabaseCommand),
     Serve(serve_command::ServeCommand),
     Tools(tools_command::ToolsCommand),
     #[clap(alias("doc"), hide(true))]
@@ -38,11 +44,17 @@ impl MainCommand {
         &self,
         root_logger: Logger,
         config_builder: ConfigBuilder<DefaultState>,
+        log_level_handle: LogLevelHandle,
     ) -> StdResult<()> {
         match self {
             Self::Genesis(cmd) => cmd.execute(root_logger, config_builder).await,
+            Self::Config(cmd) => cmd.execute(root_logger, config_builder).await,
             Self::Era(cmd) => cmd.execute(root_logger, config_builder).await,
-            Self::Serve(cmd) => cmd.execute(root_logger, config_builder).await,
+            Self::Database(cmd) => cmd.execute(root_logger, config_builder).await,
+            Self::Serve(cmd) => {
+                cmd.execute(root_logger, config_builder, log_level_handle)
+                    .await
+            }
             Self::Tools(cmd) => cmd.execute(root_logger, config_builder).await,
             Self::GenerateDoc(cmd) => {
                 let config_infos = vec![Configuration::extract(), DefaultConfiguration::extract()];
@@ -56,7 +68,9 @@ impl MainCommand {
         match self {
             MainCommand::Serve(_) => CommandType::Server,
             MainCommand::Genesis(_) => CommandType::CommandLine,
+            MainCommand::Config(_) => CommandType::CommandLine,
             MainCommand::Era(_) => CommandType::CommandLine,
+            MainCommand::Database(_) => CommandType::CommandLine,
             MainCommand::Tools(_) => CommandType::CommandLine,
             MainCommand::GenerateDoc(_) => CommandType::CommandLine,
         }
@@ -87,6 +101,10 @@ pub struct MainOpts {
     /// Directory where configuration file is located
     #[clap(long, default_value = "./config")]
     pub config_directory: PathBuf,
+
+    /// Secrets file, read instead of the `secrets.json` file of the configuration directory
+    #[clap(long, env = "SECRETS_FILE")]
+    pub secrets_file: Option<PathBuf>,
 }
 
 impl Source for MainOpts {
@@ -114,20 +132,24 @@ impl Source for MainOpts {
 
 impl MainOpts {
     /// execute command
-    pub async fn execute(&self, root_logger: Logger) -> StdResult<()> {
-        let config_file_path = self
-            .config_directory
-            .join(format!("{}.json", self.run_mode));
+    pub async fn execute(
+        &self,
+        root_logger: Logger,
+        log_level_handle: LogLevelHandle,
+    ) -> StdResult<()> {
         let config_builder = config::Config::builder()
             .add_source(DefaultConfiguration::default())
             .add_source(
-                config::File::with_name(&config_file_path.to_string_lossy()).required(false),
+                ConfigurationFiles::new(&self.config_directory, &self.run_mode)
+                    .with_secrets_file(self.secrets_file.as_ref()),
             )
             .add_source(config::Environment::default().separator("__"))
             .add_source(self.clone());
         debug!(root_logger, "Started"; "run_mode" => &self.run_mode, "node_version" => env!("CARGO_PKG_VERSION"));
 
-        self.command.execute(root_logger, config_builder).await
+        self.command
+            .execute(root_logger, config_builder, log_level_handle)
+            .await
     }
 
     /// get log level from parameters
@@ -141,3 +163,85 @@ impl MainOpts {
         }
     }
 }
+
+/// Create the [AuditLogger] recording the usages of the secret keys by the signing commands.
+///
+/// Only the `audit_log_path` parameter is read since these commands can run without the rest of
+/// the aggregator configuration, on an offline machine.
+fn create_key_usage_audit_logger(
+    config_builder: &ConfigBuilder<DefaultState>,
+    logger: Logger,
+) -> StdResult<Arc<dyn AuditLogger>> {
+    let audit_log_path = match config_builder
+        .build_cloned()
+        .with_context(|| "configuration build error")?
+        .get::<PathBuf>("audit_log_path")
+    {
+        Ok(path) => Some(path),
+        Err(ConfigError::NotFound(_)) => None,
+        Err(error) => return Err(error).with_context(|| "configuration error: 'audit_log_path'"),
+    };
+    let audit_logger: Arc<dyn AuditLogger> = match audit_log_path {
+        Some(path) => Arc::new(FileAuditLogger::new(&path, logger)?),
+        None => Arc::new(NoopAuditLogger),
+    };
+
+    Ok(audit_logger)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_tools::TestLogger;
+
+    use super::*;
+
+    #[test]
+    fn main_command_is_valid() {
+        MainOpts::command().debug_assert();
+    }
+
+    #[test]
+    fn every_subcommand_is_documented() {
+        fn assert_documented(command: &clap::Command, parent: &str) {
+            for subcommand in command.get_subcommands().filter(|c| !c.is_hide_set()) {
+                let name = format!("{parent} {}", subcommand.get_name());
+                assert!(
+                    subcommand.get_about().is_some(),
+                    "'{name}' has no description for the generated documentation"
+                );
+                assert_documented(subcommand, &name);
+            }
+        }
+
+        let command = MainOpts::command();
+        let groups: Vec<&str> = command.get_subcommands().map(|c| c.get_name()).collect();
+        for group in [
+            "serve",
+            "genesis",
+            "era",
+            "tools",
+            "database",
+            "generate-doc",
+        ] {
+            assert!(
+                groups.contains(&group),
+                "'{group}' command group is missing"
+            );
+        }
+        assert_documented(&command, command.get_name());
+    }
+    #[test]
+    fn key_usage_audit_logger_fails_on_an_invalid_audit_log_path() {
+        let config_builder = ConfigBuilder::<DefaultState>::default();
+        create_key_usage_audit_logger(&config_builder, TestLogger::stdout())
+            .expect("A missing audit log path should disable the audit log");
+
+        let config_builder = ConfigBuilder::<DefaultState>::default()
+            .set_override("audit_log_path", vec!["first.log", "second.log"])
+            .unwrap();
+        assert!(
+            create_key_usage_audit_logger(&config_builder, TestLogger::stdout()).is_err(),
+            "An invalid audit log path should fail"
+        );
+    }
+}
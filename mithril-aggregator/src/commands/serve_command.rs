@@ -1,14 +1,29 @@
 use anyhow::{anyhow, Context};
 use clap::Parser;
 use config::{builder::DefaultState, ConfigBuilder, Map, Source, Value, ValueKind};
-use mithril_common::StdResult;
+use mithril_common::{
+    logging::{log_level_changed, LogLevelHandle},
+    StdResult,
+};
 use mithril_metric::MetricsServer;
 use slog::{crit, debug, info, warn, Logger};
 use std::time::Duration;
-use std::{net::IpAddr, path::PathBuf};
-use tokio::{sync::oneshot, task::JoinSet};
-
-use crate::{dependency_injection::DependenciesBuilder, Configuration};
+use std::{
+    net::{IpAddr, SocketAddr},
+    path::PathBuf,
+};
+use tokio::{
+    signal::unix::{signal, SignalKind},
+    sync::oneshot,
+    task::JoinSet,
+};
+
+use crate::{
+    dependency_injection::DependenciesBuilder,
+    http_server::{serve_tls, SignerAddressFilter},
+    services::{AuditAction, AuditEntry, MaintenanceWindow},
+    telemetry, Configuration,
+};
 
 /// Server runtime mode
 #[derive(Parser, Debug, Clone)]
@@ -53,6 +68,17 @@ pub struct ServeCommand {
     /// Metrics HTTP server listening port.
     #[clap(long)]
     metrics_server_port: Option<u16>,
+
+    /// Run against a simulated chain, with simulated signers, advancing on a fast clock.
+    ///
+    /// Implies a `Test` environment: no Cardano node is needed.
+    #[clap(long)]
+    simulate: bool,
+
+    /// Print the implementations of the configurable dependencies and the resources they use
+    /// (database files, chain observer, stake distribution source, ...) at startup.
+    #[clap(long)]
+    explain_dependencies: bool,
 }
 
 impl Source for ServeCommand {
@@ -121,6 +147,16 @@ impl Source for ServeCommand {
                 Value::new(Some(&namespace), ValueKind::from(metrics_server_port)),
             );
         }
+        if self.simulate {
+            result.insert(
+                "simulate".to_string(),
+                Value::new(Some(&namespace), ValueKind::from(true)),
+            );
+            result.insert(
+                "environment".to_string(),
+                Value::new(Some(&namespace), ValueKind::from("Test")),
+            );
+        }
 
         Ok(result)
     }
@@ -131,6 +167,7 @@ impl ServeCommand {
         &self,
         root_logger: Logger,
         mut config_builder: ConfigBuilder<DefaultState>,
+        log_level_handle: LogLevelHandle,
     ) -> StdResult<()> {
         config_builder = config_builder.add_source(self.clone());
         let config: Configuration = config_builder
@@ -139,8 +176,33 @@ impl ServeCommand {
             .try_deserialize()
             .with_context(|| "configuration deserialize error")?;
         debug!(root_logger, "SERVE command"; "config" => format!("{config:?}"));
+        let simulation_epoch_duration = config
+            .get_simulation_epoch_duration()
+            .with_context(|| "configuration error")?;
+        let artifact_availability_check_interval = config
+            .get_artifact_availability_check_interval()
+            .with_context(|| "configuration error")?;
+        let _tracing_guard =
+            telemetry::init_tracing(config.otlp_exporter_endpoint.as_deref(), &root_logger)
+                .with_context(|| "OpenTelemetry tracing initialization error")?;
         let mut dependencies_builder =
             DependenciesBuilder::new(root_logger.clone(), config.clone());
+        dependencies_builder.log_level_handle = Some(log_level_handle.clone());
+        if self.explain_dependencies {
+            print!("{}", dependencies_builder.explain_dependencies());
+        }
+        dependencies_builder
+            .get_audit_logger()
+            .await
+            .with_context(|| "Dependencies Builder can not create audit logger")?
+            .record(
+                // Only non sensitive parameters are recorded, the configuration can hold secrets.
+                AuditEntry::new(AuditAction::ConfigurationLoaded).with_details(serde_json::json!({
+                    "network": config.network,
+                    "protocol_parameters": config.protocol_parameters,
+                    "signed_entity_types": config.signed_entity_types,
+                })),
+            );
 
         // start servers
         println!("Starting server...");
@@ -153,6 +215,22 @@ impl ServeCommand {
             .with_context(|| "Dependencies Builder can not create event store")?;
         let event_store_thread = tokio::spawn(async move { event_store.run().await.unwrap() });
 
+        // Create a ChainSimulator only if the simulation is enabled, it must be initialized
+        // before the aggregator runtime so the runtime starts on the simulated chain.
+        let chain_simulator = if config.simulate {
+            let chain_simulator = dependencies_builder
+                .create_chain_simulator()
+                .await
+                .with_context(|| "Dependencies Builder can not create chain simulator")?;
+            chain_simulator
+                .initialize()
+                .await
+                .with_context(|| "Chain simulator initialization error")?;
+            Some(chain_simulator)
+        } else {
+            None
+        };
+
         // start the aggregator runtime
         let mut runtime = dependencies_builder
             .create_aggregator_runner()
@@ -161,6 +239,13 @@ impl ServeCommand {
         let mut join_set = JoinSet::new();
         join_set.spawn(async move { runtime.run().await.map_err(|e| e.to_string()) });
 
+        if let Some(chain_simulator) = chain_simulator {
+            join_set.spawn(async move {
+                chain_simulator.run_forever(simulation_epoch_duration).await;
+                Ok(())
+            });
+        }
+
         // start the cardano transactions preloader
         let cardano_transactions_preloader = dependencies_builder
             .create_cardano_transactions_preloader()
@@ -177,20 +262,45 @@ impl ServeCommand {
             .create_http_routes()
             .await
             .with_context(|| "Dependencies Builder can not create http routes")?;
-        join_set.spawn(async move {
-            let (_, server) = warp::serve(routes).bind_with_graceful_shutdown(
-                (
-                    config.server_ip.clone().parse::<IpAddr>().unwrap(),
-                    config.server_port,
-                ),
-                async {
-                    shutdown_rx.await.ok();
-                },
-            );
-            server.await;
-
-            Ok(())
-        });
+        let server_address = SocketAddr::new(
+            config
+                .server_ip
+                .parse::<IpAddr>()
+                .with_context(|| format!("Invalid server IP address: '{}'", config.server_ip))?,
+            config.server_port,
+        );
+        match config.get_server_tls_config()? {
+            Some(tls_config) => {
+                let server_logger = root_logger.clone();
+                join_set.spawn(async move {
+                    serve_tls(
+                        routes,
+                        server_address,
+                        &tls_config,
+                        shutdown_rx,
+                        server_logger,
+                    )
+                    .await
+                    .map_err(|e| e.to_string())
+                });
+            }
+            None => {
+                join_set.spawn(async move {
+                    let listener = tokio::net::TcpListener::bind(server_address)
+                        .await
+                        .map_err(|e| e.to_string())?;
+                    axum::serve(
+                        listener,
+                        routes.into_make_service_with_connect_info::<SocketAddr>(),
+                    )
+                    .with_graceful_shutdown(async {
+                        shutdown_rx.await.ok();
+                    })
+                    .await
+                    .map_err(|e| e.to_string())
+                });
+            }
+        }
 
         // Create a SignersImporter only if the `cexplorer_pools_url` is provided in the config.
         if let Some(cexplorer_pools_url) = config.cexplorer_pools_url {
@@ -233,6 +343,66 @@ impl ServeCommand {
             Ok(())
         });
 
+        // Reload the signer address rules only if the `signer_address_rules_path` is provided in the config.
+        if config.signer_address_rules_path.is_some() {
+            let signer_address_filter = dependencies_builder
+                .get_signer_address_filter()
+                .await
+                .with_context(|| "Dependencies Builder can not create signer address filter")?;
+            join_set.spawn(async move {
+                signer_address_filter
+                    .run_forever(SignerAddressFilter::RELOAD_INTERVAL)
+                    .await;
+                Ok(())
+            });
+        }
+
+        // Create an AlertingService only if the `alerting_webhook_url` is provided in the config.
+        if let Some(alerting_webhook_url) = &config.alerting_webhook_url {
+            let alerting_service = dependencies_builder
+                .create_alerting_service(alerting_webhook_url.expose_secret())
+                .await
+                .with_context(|| "Dependencies Builder can not create alerting service")?;
+            let check_interval = Duration::from_secs(config.alerting_check_interval_in_seconds);
+            join_set.spawn(async move {
+                alerting_service.run_forever(check_interval).await;
+                Ok(())
+            });
+        }
+
+        // Create a MaintenanceScheduler only if the `maintenance_window` is provided in the config.
+        if let Some(maintenance_window) = &config.maintenance_window {
+            let maintenance_window: MaintenanceWindow = maintenance_window
+                .parse()
+                .with_context(|| "Invalid 'maintenance_window' configuration")?;
+            let maintenance_scheduler = dependencies_builder
+                .create_maintenance_scheduler(maintenance_window)
+                .await
+                .with_context(|| "Dependencies Builder can not create maintenance scheduler")?;
+            join_set.spawn(async move {
+                maintenance_scheduler
+                    .run_forever(Duration::from_secs(60))
+                    .await;
+                Ok(())
+            });
+        }
+
+        // Create an ArtifactAvailabilityVerifier only if the check interval is provided in the config.
+        if let Some(check_interval) = artifact_availability_check_interval {
+            let artifact_availability_verifier = dependencies_builder
+                .create_artifact_availability_verifier()
+                .await
+                .with_context(|| {
+                    "Dependencies Builder can not create artifact availability verifier"
+                })?;
+            join_set.spawn(async move {
+                artifact_availability_verifier
+                    .run_forever(check_interval)
+                    .await;
+                Ok(())
+            });
+        }
+
         let metrics_service = dependencies_builder
             .get_metrics_service()
             .await
@@ -255,6 +425,18 @@ impl ServeCommand {
             });
         }
 
+        // SIGHUP switches the logs to a more verbose level, and back, without restarting
+        let sighup_logger = root_logger.clone();
+        join_set.spawn(async move {
+            let mut sighup = signal(SignalKind::hangup()).map_err(|e| e.to_string())?;
+            while sighup.recv().await.is_some() {
+                let log_level = log_level_handle.toggle_verbose();
+                log_level_changed(&sighup_logger, log_level, "Received SIGHUP");
+            }
+
+            Err("Failed to receive SIGHUP".to_string())
+        });
+
         join_set.spawn(async { tokio::signal::ctrl_c().await.map_err(|e| e.to_string()) });
         dependencies_builder.vanish().await;
 
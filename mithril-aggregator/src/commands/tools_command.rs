@@ -1,16 +1,35 @@
-use anyhow::Context;
+use anyhow::{anyhow, Context};
+use chrono::Utc;
 use clap::{Parser, Subcommand};
 use config::{builder::DefaultState, ConfigBuilder};
+use mithril_common::entities::{Epoch, ProtocolParameters};
+use mithril_common::protocol::{LotteryParametersWarning, QuorumEstimate, QuorumEstimator};
 use mithril_common::StdResult;
-use mithril_persistence::sqlite::{SqliteCleaner, SqliteCleaningTask};
+use mithril_persistence::sqlite::{ConnectionExtensions, SqliteCleaner, SqliteCleaningTask};
+use mithril_persistence::store::StakeStorer;
+use serde_json::json;
 use slog::{debug, Logger};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::task::JoinSet;
 
 use crate::{
-    database::repository::{CertificateRepository, SignedEntityStore},
+    database::repository::{
+        CertificateRepository, ProtocolParametersScheduleStore, SignedEntityStore,
+        SignerRegistrationStore,
+    },
     dependency_injection::DependenciesBuilder,
-    tools::CertificatesHashMigrator,
-    Configuration,
+    http_server::SERVER_BASE_PATH,
+    services::{AuditAction, AuditEntry},
+    tools::{
+        AggregatorStressTester, CertificateChainAuditReport, CertificateChainAuditor,
+        CertificatesHashMigrationReport, CertificatesHashMigrator, SignerRegistrationExportFormat,
+        SignerRegistrationExporter, StressTestPhaseReport, StressTestSigners,
+    },
+    Configuration, ProtocolParametersScheduleStorer,
 };
 
 /// List of tools to upkeep the aggregator
@@ -39,9 +58,43 @@ pub enum ToolsSubCommand {
     /// Load all certificates in the database to recompute their hash and update all related
     /// entities.
     ///
-    /// Since it will modify the aggregator sqlite database it's strongly recommended to backup it
-    /// before running this command.
+    /// The changes are applied in a single transaction. Since it will modify the aggregator
+    /// sqlite database it's strongly recommended to backup it before running this command, use
+    /// `--dry-run` to list the hashes that would be updated.
     RecomputeCertificatesHash(RecomputeCertificatesHashCommand),
+
+    /// Schedule a change of the protocol parameters from a future epoch.
+    ///
+    /// The running aggregator takes the change into account when recording the epoch settings,
+    /// no restart is needed.
+    ScheduleProtocolParameters(ScheduleProtocolParametersCommand),
+
+    /// Simulate signers registering and sending their signatures to a running aggregator,
+    /// and report the throughput and latency percentiles of its HTTP server.
+    ///
+    /// The signers are generated with random keys, the aggregator only accepts them if its stake
+    /// distribution contains them: the other responses are still measured and reported by status
+    /// code. Don't run it against a production aggregator, use `--embedded` to test an aggregator
+    /// started in the same process instead.
+    StressTest(StressTestCommand),
+
+    /// Export the signer registrations stored by the aggregator (party id, epoch, stake,
+    /// verification keys) as CSV or JSON.
+    ///
+    /// Only the registrations within the store retention limit are available.
+    ExportSignerRegistrations(ExportSignerRegistrationsCommand),
+
+    /// Walk the stored certificate chain back to the genesis certificate, re-verifying the
+    /// hash, the multi-signature and the link of each certificate, and report the first broken
+    /// link.
+    VerifyChain(VerifyChainCommand),
+
+    /// Estimate the probability that the signers of the stake distribution of an epoch reach
+    /// the quorum of the lottery, and warn when the protocol parameters make the certification
+    /// unlikely.
+    ///
+    /// The protocol parameters default to the ones of the configuration.
+    CheckProtocolParameters(CheckProtocolParametersCommand),
 }
 
 impl ToolsSubCommand {
@@ -52,13 +105,22 @@ impl ToolsSubCommand {
     ) -> StdResult<()> {
         match self {
             Self::RecomputeCertificatesHash(cmd) => cmd.execute(root_logger, config_builder).await,
+            Self::ScheduleProtocolParameters(cmd) => cmd.execute(root_logger, config_builder).await,
+            Self::StressTest(cmd) => cmd.execute(root_logger, config_builder).await,
+            Self::ExportSignerRegistrations(cmd) => cmd.execute(root_logger, config_builder).await,
+            Self::VerifyChain(cmd) => cmd.execute(root_logger, config_builder).await,
+            Self::CheckProtocolParameters(cmd) => cmd.execute(root_logger, config_builder).await,
         }
     }
 }
 
 /// Recompute certificates hash command.
 #[derive(Parser, Debug, Clone)]
-pub struct RecomputeCertificatesHashCommand {}
+pub struct RecomputeCertificatesHashCommand {
+    /// Only report the certificates which hash would change, without modifying the database
+    #[clap(long)]
+    dry_run: bool,
+}
 
 impl RecomputeCertificatesHashCommand {
     pub async fn execute(
@@ -85,10 +147,25 @@ impl RecomputeCertificatesHashCommand {
             root_logger,
         );
 
-        migrator
+        if self.dry_run {
+            let report = migrator
+                .dry_run()
+                .await
+                .with_context(|| "recompute-certificates-hash: dry run error")?;
+            Self::print_report(&report, true);
+
+            return Ok(());
+        }
+
+        let transaction = connection.begin_transaction()?;
+        let report = migrator
             .migrate()
             .await
             .with_context(|| "recompute-certificates-hash: database migration error")?;
+        transaction
+            .commit()
+            .with_context(|| "recompute-certificates-hash: database commit error")?;
+        Self::print_report(&report, false);
 
         SqliteCleaner::new(&connection)
             .with_tasks(&[SqliteCleaningTask::Vacuum])
@@ -97,4 +174,576 @@ impl RecomputeCertificatesHashCommand {
 
         Ok(())
     }
+
+    fn print_report(report: &CertificatesHashMigrationReport, dry_run: bool) {
+        for (old_hash, new_hash) in &report.updated_hashes {
+            println!("{old_hash} -> {new_hash}");
+        }
+        println!(
+            "{} certificate(s) out of {} {} updated",
+            report.updated_hashes.len(),
+            report.total_certificates,
+            if dry_run { "would be" } else { "were" }
+        );
+    }
+}
+
+/// Schedule protocol parameters command.
+#[derive(Parser, Debug, Clone)]
+pub struct ScheduleProtocolParametersCommand {
+    /// Epoch from which the protocol parameters are used to sign
+    #[clap(long)]
+    activation_epoch: u64,
+
+    /// Quorum parameter
+    #[clap(long)]
+    k: u64,
+
+    /// Security parameter (number of lotteries)
+    #[clap(long)]
+    m: u64,
+
+    /// f in phi(w) = 1 - (1 - f)^w, where w is the stake of a participant
+    #[clap(long)]
+    phi_f: f64,
+}
+
+impl ScheduleProtocolParametersCommand {
+    pub async fn execute(
+        &self,
+        root_logger: Logger,
+        config_builder: ConfigBuilder<DefaultState>,
+    ) -> StdResult<()> {
+        let config: Configuration = config_builder
+            .build()
+            .with_context(|| "configuration build error")?
+            .try_deserialize()
+            .with_context(|| "configuration deserialize error")?;
+        debug!(root_logger, "SCHEDULE PROTOCOL PARAMETERS command"; "config" => format!("{config:?}"));
+        let activation_epoch = Epoch(self.activation_epoch);
+        let protocol_parameters = ProtocolParameters::new(self.k, self.m, self.phi_f);
+        println!(
+            "Scheduling protocol parameters {protocol_parameters:?} from epoch {activation_epoch}"
+        );
+
+        let mut dependencies_builder =
+            DependenciesBuilder::new(root_logger.clone(), config.clone());
+        let connection = dependencies_builder
+            .get_sqlite_connection()
+            .await
+            .with_context(|| "Dependencies Builder can not get sqlite connection")?;
+        let store = ProtocolParametersScheduleStore::new(connection);
+        let audit_logger = dependencies_builder.get_audit_logger().await?;
+
+        let result = store
+            .schedule_protocol_parameters(activation_epoch, protocol_parameters.clone())
+            .await;
+        audit_logger.record(
+            AuditEntry::new(AuditAction::ProtocolParametersScheduled)
+                .with_details(json!({
+                    "activation_epoch": activation_epoch,
+                    "protocol_parameters": protocol_parameters,
+                }))
+                .with_result(&result),
+        );
+        result.with_context(|| "schedule-protocol-parameters: could not schedule the change")?;
+
+        for record in store.get_all()? {
+            println!(
+                "Epoch {}: {:?} (scheduled at {})",
+                record.activation_epoch, record.protocol_parameters, record.created_at
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Stress test command.
+#[derive(Parser, Debug, Clone)]
+pub struct StressTestCommand {
+    /// Endpoint of the aggregator to test, default to the one of the configuration
+    #[clap(long, conflicts_with = "embedded")]
+    aggregator_endpoint: Option<String>,
+
+    /// Start an aggregator running on a simulated chain in this process and test it, instead
+    /// of testing a running aggregator
+    ///
+    /// The simulated chain stake distribution contains the stress test signers, so that their
+    /// registrations are accepted.
+    #[clap(long)]
+    embedded: bool,
+
+    /// Directory where the keys of the signers and the stores of the embedded aggregator are
+    /// written, default to a directory in the system temporary directory
+    #[clap(long)]
+    work_directory: Option<PathBuf>,
+
+    /// Number of simulated signers
+    #[clap(long, default_value_t = 100)]
+    num_signers: usize,
+
+    /// Maximum number of requests sent concurrently
+    #[clap(long, default_value_t = 20)]
+    concurrency: usize,
+
+    /// Print the report as JSON
+    #[clap(long)]
+    json: bool,
+}
+
+impl StressTestCommand {
+    /// Maximum duration to wait for the embedded aggregator to be ready.
+    const EMBEDDED_AGGREGATOR_STARTUP_TIMEOUT: Duration = Duration::from_secs(60);
+
+    pub async fn execute(
+        &self,
+        root_logger: Logger,
+        config_builder: ConfigBuilder<DefaultState>,
+    ) -> StdResult<()> {
+        let work_directory = self.work_directory.clone().unwrap_or_else(|| {
+            std::env::temp_dir()
+                .join("mithril-stress-test")
+                .join(Utc::now().format("%Y%m%dT%H%M%S").to_string())
+        });
+        let config: Configuration = if self.embedded {
+            Self::with_embedded_aggregator_overrides(config_builder, &work_directory)?
+        } else {
+            config_builder
+        }
+        .build()
+        .with_context(|| "configuration build error")?
+        .try_deserialize()
+        .with_context(|| "configuration deserialize error")?;
+        debug!(root_logger, "STRESS TEST command"; "config" => format!("{config:?}"));
+
+        let mut embedded_aggregator = JoinSet::new();
+        let (aggregator_endpoint, tester, signers) = if self.embedded {
+            let signers = self
+                .generate_signers(&config.protocol_parameters, &work_directory)
+                .await?;
+            let aggregator_endpoint = Self::start_embedded_aggregator(
+                &mut embedded_aggregator,
+                config,
+                &signers,
+                root_logger,
+            )
+            .await
+            .with_context(|| "stress-test: could not start the embedded aggregator")?;
+            let tester = AggregatorStressTester::new(&aggregator_endpoint, self.concurrency)?;
+            tester
+                .wait_for_epoch_settings(Self::EMBEDDED_AGGREGATOR_STARTUP_TIMEOUT)
+                .await
+                .with_context(|| "stress-test: the embedded aggregator did not start")?;
+
+            (aggregator_endpoint, tester, signers)
+        } else {
+            let aggregator_endpoint = self
+                .aggregator_endpoint
+                .clone()
+                .unwrap_or_else(|| format!("{}{SERVER_BASE_PATH}", config.get_server_url()));
+            let tester = AggregatorStressTester::new(&aggregator_endpoint, self.concurrency)?;
+            let epoch_settings = tester
+                .get_epoch_settings()
+                .await
+                .with_context(|| "stress-test: could not fetch the epoch settings")?;
+            let signers = self
+                .generate_signers(
+                    &epoch_settings.signer_registration_protocol_parameters,
+                    &work_directory,
+                )
+                .await?;
+
+            (aggregator_endpoint, tester, signers)
+        };
+
+        let epoch = tester
+            .get_epoch_settings()
+            .await
+            .with_context(|| "stress-test: could not fetch the epoch settings")?
+            .epoch;
+        println!(
+            "Stress testing '{aggregator_endpoint}' at epoch {epoch} with {} signers",
+            self.num_signers
+        );
+        let reports = vec![
+            tester
+                .register_signers(&signers, epoch)
+                .await
+                .with_context(|| "stress-test: could not send the signers registrations")?,
+            tester
+                .register_signatures(signers, epoch)
+                .await
+                .with_context(|| "stress-test: could not send the signatures")?,
+        ];
+        embedded_aggregator.abort_all();
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&reports)?);
+        } else {
+            reports.iter().for_each(Self::print_report);
+        }
+
+        Ok(())
+    }
+
+    async fn generate_signers(
+        &self,
+        protocol_parameters: &ProtocolParameters,
+        work_directory: &Path,
+    ) -> StdResult<Arc<StressTestSigners>> {
+        let number_of_signers = self.num_signers;
+        let protocol_parameters = protocol_parameters.clone();
+        let signers_directory = work_directory.join("signers");
+        let signers = tokio::task::spawn_blocking(move || {
+            StressTestSigners::generate(number_of_signers, &protocol_parameters, &signers_directory)
+        })
+        .await
+        .with_context(|| "stress-test: could not generate the signers")??;
+
+        Ok(Arc::new(signers))
+    }
+
+    /// Run the embedded aggregator on a simulated chain, with its stores in the work directory.
+    fn with_embedded_aggregator_overrides(
+        config_builder: ConfigBuilder<DefaultState>,
+        work_directory: &Path,
+    ) -> StdResult<ConfigBuilder<DefaultState>> {
+        let path = |name: &str| work_directory.join(name).to_string_lossy().to_string();
+
+        config_builder
+            .set_override("environment", "Test")?
+            .set_override("simulate", true)?
+            .set_override("server_ip", "127.0.0.1")?
+            .set_override("data_stores_directory", path("stores"))?
+            .set_override("db_directory", path("db"))?
+            .set_override("snapshot_directory", path("snapshots"))
+            .with_context(|| "stress-test: could not configure the embedded aggregator")
+    }
+
+    /// Start the runtime, the chain simulator and the HTTP server of an aggregator whose
+    /// simulated chain stake distribution contains the given signers, returning its endpoint.
+    async fn start_embedded_aggregator(
+        join_set: &mut JoinSet<StdResult<()>>,
+        config: Configuration,
+        signers: &StressTestSigners,
+        root_logger: Logger,
+    ) -> StdResult<String> {
+        let simulation_epoch_duration = config.get_simulation_epoch_duration()?;
+        let mut dependencies_builder = DependenciesBuilder::new(root_logger, config);
+        let mut event_store = dependencies_builder.create_event_store().await?;
+        join_set.spawn(async move { event_store.run().await });
+
+        let chain_simulator = dependencies_builder.create_chain_simulator().await?;
+        chain_simulator.initialize().await?;
+        dependencies_builder
+            .get_fake_chain_observer()
+            .await?
+            .signers
+            .write()
+            .await
+            .extend(signers.signers_with_stake());
+
+        let mut runtime = dependencies_builder.create_aggregator_runner().await?;
+        join_set.spawn(async move { runtime.run().await.map_err(|e| anyhow!(e)) });
+        join_set.spawn(async move {
+            chain_simulator.run_forever(simulation_epoch_duration).await;
+            Ok(())
+        });
+
+        let routes = dependencies_builder.create_http_routes().await?;
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .with_context(|| "Could not bind the embedded aggregator HTTP server")?;
+        let server_address = listener.local_addr()?;
+        join_set.spawn(async move {
+            axum::serve(
+                listener,
+                routes.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .await
+            .map_err(|e| anyhow!(e))
+        });
+
+        Ok(format!("http://{server_address}/{SERVER_BASE_PATH}"))
+    }
+
+    fn print_report(report: &StressTestPhaseReport) {
+        println!(
+            "{}: {} request(s) in {:?} ({:.1} req/s), {} without response",
+            report.name,
+            report.total_requests,
+            report.duration,
+            report.throughput(),
+            report.failed_requests
+        );
+        for (status, count) in &report.responses_by_status {
+            println!("  HTTP {status}: {count}");
+        }
+        println!(
+            "  latency min={:?} p50={:?} p90={:?} p99={:?} max={:?}",
+            report.latencies.min,
+            report.latencies.p50,
+            report.latencies.p90,
+            report.latencies.p99,
+            report.latencies.max
+        );
+    }
+}
+
+/// Export signer registrations command.
+#[derive(Parser, Debug, Clone)]
+pub struct ExportSignerRegistrationsCommand {
+    /// First epoch of the exported registrations (inclusive)
+    #[clap(long)]
+    from_epoch: Option<u64>,
+
+    /// Last epoch of the exported registrations (inclusive)
+    #[clap(long)]
+    to_epoch: Option<u64>,
+
+    /// Format of the export
+    #[clap(long, value_enum, default_value_t = SignerRegistrationExportFormat::Csv)]
+    format: SignerRegistrationExportFormat,
+
+    /// Path of the file to write the export to, default to the standard output
+    #[clap(long)]
+    target_path: Option<PathBuf>,
+}
+
+impl ExportSignerRegistrationsCommand {
+    pub async fn execute(
+        &self,
+        root_logger: Logger,
+        config_builder: ConfigBuilder<DefaultState>,
+    ) -> StdResult<()> {
+        let config: Configuration = config_builder
+            .build()
+            .with_context(|| "configuration build error")?
+            .try_deserialize()
+            .with_context(|| "configuration deserialize error")?;
+        debug!(root_logger, "EXPORT SIGNER REGISTRATIONS command"; "config" => format!("{config:?}"));
+        if let (Some(from), Some(to)) = (self.from_epoch, self.to_epoch) {
+            if from > to {
+                return Err(anyhow!(
+                    "export-signer-registrations: --from-epoch ({from}) must be lower or equal to --to-epoch ({to})"
+                ));
+            }
+        }
+
+        let mut dependencies_builder =
+            DependenciesBuilder::new(root_logger.clone(), config.clone());
+        let connection = dependencies_builder
+            .get_sqlite_connection()
+            .await
+            .with_context(|| "Dependencies Builder can not get sqlite connection")?;
+        let records = SignerRegistrationStore::new(connection)
+            .get_signer_registrations_in_epoch_range(
+                self.from_epoch.map(Epoch),
+                self.to_epoch.map(Epoch),
+            )?;
+        let number_of_records = records.len();
+        let export = SignerRegistrationExporter::export(records, self.format)?;
+
+        match &self.target_path {
+            Some(target_path) => {
+                std::fs::write(target_path, export).with_context(|| {
+                    format!(
+                        "export-signer-registrations: could not write file '{}'",
+                        target_path.display()
+                    )
+                })?;
+                println!(
+                    "{number_of_records} signer registration(s) exported to '{}'",
+                    target_path.display()
+                );
+            }
+            None => print!("{export}"),
+        }
+
+        Ok(())
+    }
+}
+
+/// Verify certificate chain command.
+#[derive(Parser, Debug, Clone)]
+pub struct VerifyChainCommand {
+    /// Hash of the certificate to start the verification from, default to the latest certificate
+    #[clap(long)]
+    certificate_hash: Option<String>,
+
+    /// Print the report as JSON
+    #[clap(long)]
+    json: bool,
+}
+
+impl VerifyChainCommand {
+    pub async fn execute(
+        &self,
+        root_logger: Logger,
+        config_builder: ConfigBuilder<DefaultState>,
+    ) -> StdResult<()> {
+        let config: Configuration = config_builder
+            .build()
+            .with_context(|| "configuration build error")?
+            .try_deserialize()
+            .with_context(|| "configuration deserialize error")?;
+        debug!(root_logger, "VERIFY CHAIN command"; "config" => format!("{config:?}"));
+        let mut dependencies_builder =
+            DependenciesBuilder::new(root_logger.clone(), config.clone());
+        let auditor = CertificateChainAuditor::new(
+            dependencies_builder.get_certificate_repository().await?,
+            dependencies_builder.get_certificate_verifier().await?,
+            dependencies_builder
+                .get_genesis_verifier()
+                .await?
+                .to_verification_key(),
+            root_logger,
+        );
+
+        let report = auditor
+            .audit(self.certificate_hash.as_deref())
+            .await
+            .with_context(|| "verify-chain: could not audit the certificate chain")?;
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        } else {
+            Self::print_report(&report);
+        }
+
+        if report.is_valid() {
+            Ok(())
+        } else {
+            Err(anyhow!("verify-chain: the certificate chain is broken"))
+        }
+    }
+
+    fn print_report(report: &CertificateChainAuditReport) {
+        println!(
+            "Verified {} certificate(s) from certificate '{}'",
+            report.verified_certificates, report.head_certificate_hash
+        );
+        if let Some(genesis_hash) = &report.genesis_certificate_hash {
+            println!("The chain is valid up to the genesis certificate '{genesis_hash}'");
+        }
+        if let Some(broken_link) = &report.broken_link {
+            println!(
+                "Broken link at certificate '{}' (epoch {}, previous hash '{}'): {}",
+                broken_link.certificate_hash,
+                broken_link.epoch,
+                broken_link.previous_hash,
+                broken_link.error
+            );
+        }
+    }
+}
+
+/// Check protocol parameters command.
+#[derive(Parser, Debug, Clone)]
+pub struct CheckProtocolParametersCommand {
+    /// Epoch of the stake distribution of the signers
+    #[clap(long)]
+    epoch: u64,
+
+    /// Quorum parameter, default to the one of the configuration
+    #[clap(long)]
+    k: Option<u64>,
+
+    /// Security parameter (number of lotteries), default to the one of the configuration
+    #[clap(long)]
+    m: Option<u64>,
+
+    /// f in phi(w) = 1 - (1 - f)^w, default to the one of the configuration
+    #[clap(long)]
+    phi_f: Option<f64>,
+
+    /// Probability that each signer of the stake distribution signs, the signing rounds are
+    /// simulated if lower than 1
+    #[clap(long, default_value_t = 1.0)]
+    participation_rate: f64,
+
+    /// Number of simulated signing rounds
+    #[clap(long, default_value_t = 1000)]
+    rounds: u32,
+
+    /// Print the estimate as JSON
+    #[clap(long)]
+    json: bool,
+}
+
+impl CheckProtocolParametersCommand {
+    pub async fn execute(
+        &self,
+        root_logger: Logger,
+        config_builder: ConfigBuilder<DefaultState>,
+    ) -> StdResult<()> {
+        let config: Configuration = config_builder
+            .build()
+            .with_context(|| "configuration build error")?
+            .try_deserialize()
+            .with_context(|| "configuration deserialize error")?;
+        debug!(root_logger, "CHECK PROTOCOL PARAMETERS command"; "config" => format!("{config:?}"));
+        let protocol_parameters = ProtocolParameters::new(
+            self.k.unwrap_or(config.protocol_parameters.k),
+            self.m.unwrap_or(config.protocol_parameters.m),
+            self.phi_f.unwrap_or(config.protocol_parameters.phi_f),
+        );
+        let epoch = Epoch(self.epoch);
+
+        let mut dependencies_builder =
+            DependenciesBuilder::new(root_logger.clone(), config.clone());
+        let stake_distribution = dependencies_builder
+            .get_stake_store()
+            .await?
+            .get_stakes(epoch)
+            .await?
+            .ok_or_else(|| {
+                anyhow!("check-protocol-parameters: no stake distribution stored for epoch {epoch}")
+            })?;
+        let estimator = QuorumEstimator::new(&protocol_parameters, &stake_distribution)
+            .with_context(|| "check-protocol-parameters: invalid lottery parameters")?;
+        let estimate = if self.participation_rate < 1.0 {
+            estimator.simulate(self.rounds, self.participation_rate, &mut rand_core::OsRng)?
+        } else {
+            estimator.estimate()
+        };
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&estimate)?);
+        } else {
+            self.print_estimate(&protocol_parameters, stake_distribution.len(), &estimate);
+        }
+
+        Ok(())
+    }
+
+    fn print_estimate(
+        &self,
+        protocol_parameters: &ProtocolParameters,
+        number_of_signers: usize,
+        estimate: &QuorumEstimate,
+    ) {
+        println!(
+            "Protocol parameters {protocol_parameters:?} with {number_of_signers} signer(s) of epoch {} and a participation rate of {}",
+            self.epoch, self.participation_rate
+        );
+        println!(
+            "Expected won lotteries: {:.1} (standard deviation {:.1}), quorum probability: {:.6}",
+            estimate.expected_won_lotteries,
+            estimate.standard_deviation,
+            estimate.quorum_probability
+        );
+        for warning in &estimate.warnings {
+            match warning {
+                LotteryParametersWarning::QuorumUnreachable { max_won_lotteries } => println!(
+                    "WARNING: the quorum k={} can not be reached, at most {max_won_lotteries} lotteries can be won",
+                    protocol_parameters.k
+                ),
+                LotteryParametersWarning::UnlikelyQuorum { quorum_probability } => println!(
+                    "WARNING: the certification is unlikely, the quorum is reached with a probability of {quorum_probability:.6}"
+                ),
+            }
+        }
+    }
 }
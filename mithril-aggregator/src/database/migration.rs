@@ -812,5 +812,26 @@ pragma foreign_keys=true;
 alter table certificate drop column immutable_file_number;
         "#,
         ),
+        // Migration 30
+        // Add the `protocol_parameters_schedule` table
+        SqlMigration::new(
+            30,
+            r#"
+create table protocol_parameters_schedule (
+    activation_epoch        integer     not null,
+    protocol_parameters     json        not null,
+    created_at              text        not null,
+    primary key (activation_epoch)
+);
+        "#,
+        ),
+        // Migration 31
+        // Add the `hash_algorithm` column to the `certificate` table
+        SqlMigration::new(
+            31,
+            r#"
+alter table certificate add column hash_algorithm text not null default 'sha256';
+        "#,
+        ),
     ]
 }
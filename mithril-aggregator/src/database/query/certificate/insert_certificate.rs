@@ -32,9 +32,10 @@ impl InsertCertificateRecordQuery {
         protocol_message, \
         signers, \
         initiated_at, \
-        sealed_at)";
+        sealed_at, \
+        hash_algorithm)";
         let values_columns: Vec<&str> =
-            repeat("(?*, ?*, ?*, ?*, ?*, ?*, ?*, ?*, ?*, ?*, ?*, ?*, ?*, ?*, ?*)")
+            repeat("(?*, ?*, ?*, ?*, ?*, ?*, ?*, ?*, ?*, ?*, ?*, ?*, ?*, ?*, ?*, ?*)")
                 .take(certificates_records.len())
                 .collect();
 
@@ -69,6 +70,7 @@ impl InsertCertificateRecordQuery {
                     Value::String(serde_json::to_string(&certificate_record.signers).unwrap()),
                     Value::String(certificate_record.initiated_at.to_rfc3339()),
                     Value::String(certificate_record.sealed_at.to_rfc3339()),
+                    Value::String(certificate_record.hash_algorithm.to_string()),
                 ]
             })
             .collect();
@@ -3,6 +3,7 @@ mod buffered_single_signature;
 mod certificate;
 mod epoch_settings;
 mod open_message;
+mod protocol_parameters_schedule;
 mod signed_entity;
 mod signer;
 mod signer_registration;
@@ -13,6 +14,7 @@ pub use buffered_single_signature::*;
 pub use certificate::*;
 pub use epoch_settings::*;
 pub use open_message::*;
+pub use protocol_parameters_schedule::*;
 pub use signed_entity::*;
 pub use signer::*;
 pub use signer_registration::*;
@@ -0,0 +1,98 @@
+use sqlite::Value;
+
+use mithril_common::entities::Epoch;
+use mithril_persistence::sqlite::{Query, SourceAlias, SqLiteEntity, WhereCondition};
+
+use crate::database::record::ProtocolParametersScheduleRecord;
+
+/// Simple queries to retrieve [ProtocolParametersScheduleRecord] from the sqlite database.
+pub struct GetProtocolParametersScheduleQuery {
+    condition: WhereCondition,
+}
+
+impl GetProtocolParametersScheduleQuery {
+    pub fn all() -> Self {
+        Self {
+            condition: WhereCondition::default(),
+        }
+    }
+
+    /// Scheduled changes with an activation epoch lower or equal to the given epoch, the most
+    /// recent activation epoch first.
+    pub fn activated_at_or_before(epoch: Epoch) -> Self {
+        Self {
+            condition: WhereCondition::new(
+                "activation_epoch <= ?*",
+                vec![Value::Integer(*epoch as i64)],
+            ),
+        }
+    }
+}
+
+impl Query for GetProtocolParametersScheduleQuery {
+    type Entity = ProtocolParametersScheduleRecord;
+
+    fn filters(&self) -> WhereCondition {
+        self.condition.clone()
+    }
+
+    fn get_definition(&self, condition: &str) -> String {
+        let aliases = SourceAlias::new(&[("{:protocol_parameters_schedule:}", "pps")]);
+        let projection = Self::Entity::get_projection().expand(aliases);
+        format!("select {projection} from protocol_parameters_schedule as pps where {condition} order by activation_epoch desc")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use mithril_common::entities::ProtocolParameters;
+    use mithril_persistence::sqlite::ConnectionExtensions;
+
+    use crate::database::query::InsertOrReplaceProtocolParametersScheduleQuery;
+    use crate::database::test_helper::main_db_connection;
+
+    use super::*;
+
+    #[test]
+    fn get_scheduled_changes_activated_at_or_before_epoch() {
+        let connection = main_db_connection().unwrap();
+        for epoch in [4, 8, 12] {
+            connection
+                .fetch_first(InsertOrReplaceProtocolParametersScheduleQuery::one(
+                    ProtocolParametersScheduleRecord {
+                        activation_epoch: Epoch(epoch),
+                        protocol_parameters: ProtocolParameters::new(epoch, 100, 0.65),
+                        created_at: Utc::now(),
+                    },
+                ))
+                .unwrap();
+        }
+
+        let records: Vec<ProtocolParametersScheduleRecord> = connection
+            .fetch_collect(GetProtocolParametersScheduleQuery::activated_at_or_before(
+                Epoch(9),
+            ))
+            .unwrap();
+        assert_eq!(
+            vec![Epoch(8), Epoch(4)],
+            records
+                .into_iter()
+                .map(|r| r.activation_epoch)
+                .collect::<Vec<_>>()
+        );
+
+        let records: Vec<ProtocolParametersScheduleRecord> = connection
+            .fetch_collect(GetProtocolParametersScheduleQuery::activated_at_or_before(
+                Epoch(3),
+            ))
+            .unwrap();
+        assert!(records.is_empty());
+
+        let records: Vec<ProtocolParametersScheduleRecord> = connection
+            .fetch_collect(GetProtocolParametersScheduleQuery::all())
+            .unwrap();
+        assert_eq!(3, records.len());
+    }
+}
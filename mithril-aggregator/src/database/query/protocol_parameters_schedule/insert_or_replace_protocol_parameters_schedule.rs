@@ -0,0 +1,86 @@
+use sqlite::Value;
+
+use mithril_persistence::sqlite::{Query, SourceAlias, SqLiteEntity, WhereCondition};
+
+use crate::database::record::ProtocolParametersScheduleRecord;
+
+/// Query to insert or replace [ProtocolParametersScheduleRecord] in the sqlite database
+pub struct InsertOrReplaceProtocolParametersScheduleQuery {
+    condition: WhereCondition,
+}
+
+impl InsertOrReplaceProtocolParametersScheduleQuery {
+    pub fn one(record: ProtocolParametersScheduleRecord) -> Self {
+        let condition = WhereCondition::new(
+            "(activation_epoch, protocol_parameters, created_at) values (?*, ?*, ?*)",
+            vec![
+                Value::Integer(*record.activation_epoch as i64),
+                Value::String(serde_json::to_string(&record.protocol_parameters).unwrap()),
+                Value::String(record.created_at.to_rfc3339()),
+            ],
+        );
+
+        Self { condition }
+    }
+}
+
+impl Query for InsertOrReplaceProtocolParametersScheduleQuery {
+    type Entity = ProtocolParametersScheduleRecord;
+
+    fn filters(&self) -> WhereCondition {
+        self.condition.clone()
+    }
+
+    fn get_definition(&self, condition: &str) -> String {
+        // it is important to alias the fields with the same name as the table
+        // since the table cannot be aliased in a RETURNING statement in SQLite.
+        let projection = Self::Entity::get_projection().expand(SourceAlias::new(&[(
+            "{:protocol_parameters_schedule:}",
+            "protocol_parameters_schedule",
+        )]));
+
+        format!(
+            "insert or replace into protocol_parameters_schedule {condition} returning {projection}"
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use mithril_common::entities::{Epoch, ProtocolParameters};
+    use mithril_persistence::sqlite::ConnectionExtensions;
+
+    use crate::database::test_helper::main_db_connection;
+
+    use super::*;
+
+    #[test]
+    fn insert_then_replace_record_for_the_same_activation_epoch() {
+        let connection = main_db_connection().unwrap();
+        let record = ProtocolParametersScheduleRecord {
+            activation_epoch: Epoch(12),
+            protocol_parameters: ProtocolParameters::new(10, 100, 0.65),
+            created_at: Utc::now(),
+        };
+
+        let inserted_record = connection
+            .fetch_first(InsertOrReplaceProtocolParametersScheduleQuery::one(
+                record.clone(),
+            ))
+            .unwrap();
+        assert_eq!(Some(record.clone()), inserted_record);
+
+        let replacing_record = ProtocolParametersScheduleRecord {
+            protocol_parameters: ProtocolParameters::new(20, 200, 0.5),
+            ..record
+        };
+        let replaced_record = connection
+            .fetch_first(InsertOrReplaceProtocolParametersScheduleQuery::one(
+                replacing_record.clone(),
+            ))
+            .unwrap();
+        assert_eq!(Some(replacing_record), replaced_record);
+    }
+}
@@ -0,0 +1,5 @@
+mod get_protocol_parameters_schedule;
+mod insert_or_replace_protocol_parameters_schedule;
+
+pub use get_protocol_parameters_schedule::*;
+pub use insert_or_replace_protocol_parameters_schedule::*;
@@ -36,6 +36,28 @@ impl GetSignerRegistrationRecordQuery {
 
         Ok(Self { condition })
     }
+
+    /// Query to get SignerRegistrationRecords registered between the given epochs (inclusive),
+    /// a missing bound is not applied.
+    pub fn by_epoch_range(from: Option<Epoch>, to: Option<Epoch>) -> StdResult<Self> {
+        let mut condition = WhereCondition::default();
+        if let Some(from) = from {
+            let from: i64 = from.try_into()?;
+            condition = condition.and_where(WhereCondition::new(
+                "epoch_setting_id >= ?*",
+                vec![Value::Integer(from)],
+            ));
+        }
+        if let Some(to) = to {
+            let to: i64 = to.try_into()?;
+            condition = condition.and_where(WhereCondition::new(
+                "epoch_setting_id <= ?*",
+                vec![Value::Integer(to)],
+            ));
+        }
+
+        Ok(Self { condition })
+    }
 }
 
 impl Query for GetSignerRegistrationRecordQuery {
@@ -153,4 +175,40 @@ mod tests {
             reset_created_at(signer_registration_records)
         );
     }
+
+    #[test]
+    fn test_get_signer_registration_records_by_epoch_range() {
+        let fixture = MithrilFixtureBuilder::default().with_signers(2).build();
+        let signer_with_stakes_by_epoch: Vec<(Epoch, Vec<SignerWithStake>)> = (0..=4)
+            .map(|e| (Epoch(e), fixture.signers_with_stake()))
+            .collect();
+
+        let connection = main_db_connection().unwrap();
+        insert_signer_registrations(&connection, signer_with_stakes_by_epoch).unwrap();
+
+        let epochs_in_range = |from: Option<Epoch>, to: Option<Epoch>| -> Vec<Epoch> {
+            let mut epochs: Vec<Epoch> = connection
+                .fetch_collect::<_, Vec<SignerRegistrationRecord>>(
+                    GetSignerRegistrationRecordQuery::by_epoch_range(from, to).unwrap(),
+                )
+                .unwrap()
+                .into_iter()
+                .map(|record| record.epoch_settings_id)
+                .collect();
+            epochs.sort();
+            epochs.dedup();
+            epochs
+        };
+
+        assert_eq!(
+            vec![Epoch(1), Epoch(2), Epoch(3)],
+            epochs_in_range(Some(Epoch(1)), Some(Epoch(3)))
+        );
+        assert_eq!(
+            vec![Epoch(3), Epoch(4)],
+            epochs_in_range(Some(Epoch(3)), None)
+        );
+        assert_eq!(vec![Epoch(0)], epochs_in_range(None, Some(Epoch(0))));
+        assert_eq!(5, epochs_in_range(None, None).len());
+    }
 }
@@ -9,6 +9,7 @@ use mithril_common::messages::{
     CertificateListItemMessage, CertificateListItemMessageMetadata, CertificateMessage,
     CertificateMetadataMessagePart,
 };
+use mithril_common::protocol::HashAlgorithm;
 #[cfg(test)]
 use mithril_common::{
     entities::{CardanoDbBeacon, ImmutableFileNumber},
@@ -65,6 +66,9 @@ pub struct CertificateRecord {
 
     /// Date and time when the certificate was sealed
     pub sealed_at: DateTime<Utc>,
+
+    /// Hash function used to compute the hash of the certificate
+    pub hash_algorithm: HashAlgorithm,
 }
 
 #[cfg(test)]
@@ -133,6 +137,7 @@ impl CertificateRecord {
             sealed_at: DateTime::parse_from_rfc3339("2024-02-12T13:12:57Z")
                 .unwrap()
                 .with_timezone(&Utc),
+            hash_algorithm: HashAlgorithm::default(),
         }
     }
 }
@@ -162,6 +167,7 @@ impl From<Certificate> for CertificateRecord {
             signers: other.metadata.signers,
             initiated_at: other.metadata.initiated_at,
             sealed_at: other.metadata.sealed_at,
+            hash_algorithm: other.metadata.hash_algorithm,
         }
     }
 }
@@ -175,7 +181,8 @@ impl From<CertificateRecord> for Certificate {
             other.initiated_at,
             other.sealed_at,
             other.signers,
-        );
+        )
+        .with_hash_algorithm(other.hash_algorithm);
         let (previous_hash, signature) = match other.parent_certificate_id {
             None => (
                 String::new(),
@@ -212,6 +219,7 @@ impl From<CertificateRecord> for CertificateMessage {
             initiated_at: value.initiated_at,
             sealed_at: value.sealed_at,
             signers: value.signers,
+            hash_algorithm: value.hash_algorithm,
         };
         let (multi_signature, genesis_signature) = if value.parent_certificate_id.is_none() {
             (String::new(), value.signature)
@@ -278,6 +286,7 @@ impl SqLiteEntity for CertificateRecord {
         let signers_string = row.read::<&str, _>(12);
         let initiated_at = row.read::<&str, _>(13);
         let sealed_at = row.read::<&str, _>(14);
+        let hash_algorithm_string = row.read::<&str, _>(15);
 
         let certificate_record = Self {
             certificate_id,
@@ -335,6 +344,13 @@ impl SqLiteEntity for CertificateRecord {
                     ))
                 },
             )?.with_timezone(&Utc),
+            hash_algorithm: hash_algorithm_string.parse().map_err(
+                |e| {
+                    HydrationError::InvalidData(format!(
+                        "Could not turn string '{hash_algorithm_string}' to HashAlgorithm. Error: {e}"
+                    ))
+                },
+            )?,
         };
 
         Ok(certificate_record)
@@ -385,6 +401,7 @@ impl SqLiteEntity for CertificateRecord {
         projection.add_field("signers", "{:certificate:}.signers", "text");
         projection.add_field("initiated_at", "{:certificate:}.initiated_at", "text");
         projection.add_field("sealed_at", "{:certificate:}.sealed_at", "text");
+        projection.add_field("hash_algorithm", "{:certificate:}.hash_algorithm", "text");
 
         projection
     }
@@ -5,6 +5,7 @@ mod certificate;
 mod epoch_settings;
 mod open_message;
 mod open_message_with_single_signatures;
+mod protocol_parameters_schedule;
 mod signed_entity;
 mod signer;
 mod signer_registration;
@@ -16,6 +17,7 @@ pub use certificate::*;
 pub use epoch_settings::*;
 pub use open_message::*;
 pub use open_message_with_single_signatures::*;
+pub use protocol_parameters_schedule::*;
 pub use signed_entity::*;
 pub use signer::*;
 pub use signer_registration::*;
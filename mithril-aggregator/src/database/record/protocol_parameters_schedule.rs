@@ -0,0 +1,73 @@
+use chrono::{DateTime, Utc};
+
+use mithril_common::entities::{Epoch, ProtocolParameters};
+use mithril_persistence::sqlite::{HydrationError, Projection, SqLiteEntity};
+
+/// Protocol parameters change scheduled by an operator.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ProtocolParametersScheduleRecord {
+    /// Epoch from which the protocol parameters are used for signing.
+    pub activation_epoch: Epoch,
+
+    /// Scheduled protocol parameters.
+    pub protocol_parameters: ProtocolParameters,
+
+    /// Date and time when the change was scheduled.
+    pub created_at: DateTime<Utc>,
+}
+
+impl SqLiteEntity for ProtocolParametersScheduleRecord {
+    fn hydrate(row: sqlite::Row) -> Result<Self, HydrationError>
+    where
+        Self: Sized,
+    {
+        let activation_epoch_int = row.read::<i64, _>(0);
+        let protocol_parameters_string = &row.read::<&str, _>(1);
+        let created_at = &row.read::<&str, _>(2);
+
+        let record = Self {
+            activation_epoch: Epoch(activation_epoch_int.try_into().map_err(|e| {
+                HydrationError::InvalidData(format!(
+                    "Could not cast i64 ({activation_epoch_int}) to u64. Error: '{e}'"
+                ))
+            })?),
+            protocol_parameters: serde_json::from_str(protocol_parameters_string).map_err(
+                |e| {
+                    HydrationError::InvalidData(format!(
+                        "Could not turn string '{protocol_parameters_string}' to ProtocolParameters. Error: {e}"
+                    ))
+                },
+            )?,
+            created_at: DateTime::parse_from_rfc3339(created_at)
+                .map_err(|e| {
+                    HydrationError::InvalidData(format!(
+                        "Could not turn string '{created_at}' to rfc3339 Datetime. Error: {e}"
+                    ))
+                })?
+                .with_timezone(&Utc),
+        };
+
+        Ok(record)
+    }
+
+    fn get_projection() -> Projection {
+        let mut projection = Projection::default();
+        projection.add_field(
+            "activation_epoch",
+            "{:protocol_parameters_schedule:}.activation_epoch",
+            "integer",
+        );
+        projection.add_field(
+            "protocol_parameters",
+            "{:protocol_parameters_schedule:}.protocol_parameters",
+            "text",
+        );
+        projection.add_field(
+            "created_at",
+            "{:protocol_parameters_schedule:}.created_at",
+            "text",
+        );
+
+        projection
+    }
+}
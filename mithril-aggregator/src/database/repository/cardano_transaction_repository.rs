@@ -17,6 +17,14 @@ impl TransactionStore for CardanoTransactionRepository {
         self.get_transaction_highest_chain_point().await
     }
 
+    async fn get_highest_beacon_below_or_equal(
+        &self,
+        block_number: BlockNumber,
+    ) -> StdResult<Option<ChainPoint>> {
+        self.get_transaction_chain_point_below_or_equal_block_number(block_number)
+            .await
+    }
+
     async fn get_highest_block_range(&self) -> StdResult<Option<BlockRange>> {
         let record = self.retrieve_highest_block_range_root().await?;
         Ok(record.map(|record| record.range))
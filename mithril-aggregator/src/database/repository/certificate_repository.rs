@@ -162,7 +162,8 @@ mod tests {
                     "stake":1009497432569
                 }]',
                 '2023-06-23T08:37:49.066Z',
-                '2023-06-23T08:37:49.066Z'
+                '2023-06-23T08:37:49.066Z',
+                'sha256'
             );
             
             -- multi-signature certificate
@@ -192,7 +193,8 @@ mod tests {
                     "stake":1009497432569
                 }]',
                 '2023-03-16T01:51:00.880Z',
-                '2023-03-16T02:07:22.145Z'
+                '2023-03-16T02:07:22.145Z',
+                'sha256'
             );
             "#,
             )
@@ -4,6 +4,7 @@ mod cardano_transaction_repository;
 mod certificate_repository;
 mod epoch_settings_store;
 mod open_message_repository;
+mod protocol_parameters_schedule_store;
 mod signed_entity_store;
 mod signer_registration_store;
 mod signer_store;
@@ -14,6 +15,7 @@ pub use buffered_single_signature_repository::*;
 pub use certificate_repository::*;
 pub use epoch_settings_store::*;
 pub use open_message_repository::*;
+pub use protocol_parameters_schedule_store::*;
 pub use signed_entity_store::*;
 pub use signer_registration_store::*;
 pub use signer_store::*;
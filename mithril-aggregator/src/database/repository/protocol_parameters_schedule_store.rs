@@ -0,0 +1,147 @@
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use chrono::Utc;
+
+use mithril_common::entities::{Epoch, ProtocolParameters};
+use mithril_common::StdResult;
+use mithril_persistence::sqlite::{ConnectionExtensions, SqliteConnection};
+
+use crate::database::query::{
+    GetProtocolParametersScheduleQuery, InsertOrReplaceProtocolParametersScheduleQuery,
+};
+use crate::database::record::ProtocolParametersScheduleRecord;
+use crate::ProtocolParametersScheduleStorer;
+
+/// Service to deal with the protocol parameters changes scheduled by the operators (read & write).
+pub struct ProtocolParametersScheduleStore {
+    connection: Arc<SqliteConnection>,
+}
+
+impl ProtocolParametersScheduleStore {
+    /// Create a new ProtocolParametersSchedule store
+    pub fn new(connection: Arc<SqliteConnection>) -> Self {
+        Self { connection }
+    }
+
+    /// Get all the scheduled changes, the most recent activation epoch first.
+    pub fn get_all(&self) -> StdResult<Vec<ProtocolParametersScheduleRecord>> {
+        self.connection
+            .fetch_collect(GetProtocolParametersScheduleQuery::all())
+    }
+
+    fn get_highest_recorded_epoch_settings_epoch(&self) -> StdResult<Option<Epoch>> {
+        let highest: Option<i64> = self.connection.query_single_cell(
+            "select max(epoch_setting_id) as highest from epoch_setting;",
+            &[],
+        )?;
+
+        Ok(highest.map(|e| Epoch(e as u64)))
+    }
+}
+
+#[async_trait]
+impl ProtocolParametersScheduleStorer for ProtocolParametersScheduleStore {
+    /// Schedule a change of the protocol parameters.
+    ///
+    /// The epoch settings used to sign at the activation epoch must not be recorded yet,
+    /// otherwise the change could not be applied at the requested epoch.
+    async fn schedule_protocol_parameters(
+        &self,
+        activation_epoch: Epoch,
+        protocol_parameters: ProtocolParameters,
+    ) -> StdResult<()> {
+        let settings_epoch = activation_epoch.offset_to_signer_retrieval_epoch()?;
+        if let Some(highest_recorded_epoch) = self.get_highest_recorded_epoch_settings_epoch()? {
+            if settings_epoch <= highest_recorded_epoch {
+                return Err(anyhow!(
+                    "Can not schedule protocol parameters at epoch {activation_epoch}: epoch settings are already recorded up to epoch {highest_recorded_epoch}, the earliest possible activation epoch is {}",
+                    highest_recorded_epoch + 2
+                ));
+            }
+        }
+
+        self.connection
+            .fetch_first(InsertOrReplaceProtocolParametersScheduleQuery::one(
+                ProtocolParametersScheduleRecord {
+                    activation_epoch,
+                    protocol_parameters,
+                    created_at: Utc::now(),
+                },
+            ))?;
+
+        Ok(())
+    }
+
+    async fn get_active_protocol_parameters(
+        &self,
+        epoch: Epoch,
+    ) -> StdResult<Option<ProtocolParameters>> {
+        let record = self.connection.fetch_first(
+            GetProtocolParametersScheduleQuery::activated_at_or_before(epoch),
+        )?;
+
+        Ok(record.map(|r| r.protocol_parameters))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::test_helper::{insert_epoch_settings, main_db_connection};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn get_active_protocol_parameters_return_latest_activated_change() {
+        let store = ProtocolParametersScheduleStore::new(Arc::new(main_db_connection().unwrap()));
+        store
+            .schedule_protocol_parameters(Epoch(10), ProtocolParameters::new(10, 100, 0.65))
+            .await
+            .unwrap();
+        store
+            .schedule_protocol_parameters(Epoch(20), ProtocolParameters::new(20, 200, 0.65))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            None,
+            store
+                .get_active_protocol_parameters(Epoch(9))
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            Some(ProtocolParameters::new(10, 100, 0.65)),
+            store
+                .get_active_protocol_parameters(Epoch(19))
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            Some(ProtocolParameters::new(20, 200, 0.65)),
+            store
+                .get_active_protocol_parameters(Epoch(25))
+                .await
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn cant_schedule_protocol_parameters_if_epoch_settings_are_already_recorded() {
+        let connection = main_db_connection().unwrap();
+        insert_epoch_settings(&connection, &[1, 2, 3]).unwrap();
+        let store = ProtocolParametersScheduleStore::new(Arc::new(connection));
+
+        store
+            .schedule_protocol_parameters(Epoch(4), ProtocolParameters::new(10, 100, 0.65))
+            .await
+            .expect_err("Epoch settings used to sign at epoch 4 are already recorded");
+
+        store
+            .schedule_protocol_parameters(Epoch(5), ProtocolParameters::new(10, 100, 0.65))
+            .await
+            .expect("Epoch settings used to sign at epoch 5 are not recorded yet");
+        assert_eq!(1, store.get_all().unwrap().len());
+    }
+}
@@ -26,6 +26,24 @@ impl SignerRegistrationStore {
     pub fn new(connection: Arc<SqliteConnection>) -> Self {
         Self { connection }
     }
+
+    /// Return the signer registrations recorded between the given epochs (inclusive), ordered by
+    /// epoch and party id.
+    pub fn get_signer_registrations_in_epoch_range(
+        &self,
+        from: Option<Epoch>,
+        to: Option<Epoch>,
+    ) -> StdResult<Vec<SignerRegistrationRecord>> {
+        let mut records: Vec<SignerRegistrationRecord> = self
+            .connection
+            .fetch_collect(GetSignerRegistrationRecordQuery::by_epoch_range(from, to)?)
+            .with_context(|| "get signer registrations failure")?;
+        records.sort_by(|a, b| {
+            (a.epoch_settings_id, &a.signer_id).cmp(&(b.epoch_settings_id, &b.signer_id))
+        });
+
+        Ok(records)
+    }
 }
 
 #[async_trait]
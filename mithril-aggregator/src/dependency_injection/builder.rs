@@ -1,7 +1,9 @@
 use anyhow::Context;
+use rayon::ThreadPoolBuilder;
 use semver::Version;
+use serde::Serialize;
 use slog::{debug, Logger};
-use std::{collections::BTreeSet, sync::Arc};
+use std::{collections::BTreeSet, path::PathBuf, sync::Arc};
 use tokio::{
     sync::{
         mpsc::{UnboundedReceiver, UnboundedSender},
@@ -9,7 +11,6 @@ use tokio::{
     },
     time::Duration,
 };
-use warp::Filter;
 
 use mithril_common::{
     api_version::APIVersionProvider,
@@ -18,7 +19,10 @@ use mithril_common::{
         CardanoTransactionsPreloader, CardanoTransactionsPreloaderActivation,
     },
     certificate_chain::{CertificateVerifier, MithrilCertificateVerifier},
-    chain_observer::{CardanoCliRunner, ChainObserver, ChainObserverBuilder, FakeObserver},
+    chain_observer::{
+        CardanoCliRunner, ChainObserver, ChainObserverBuilder, ChainObserverType, FakeObserver,
+        StakeDistributionSource, StakeDistributionSourceBuilder,
+    },
     chain_reader::{ChainBlockReader, PallasChainReader},
     crypto_helper::{
         MKTreeStoreInMemory, ProtocolGenesisSigner, ProtocolGenesisVerificationKey,
@@ -27,13 +31,14 @@ use mithril_common::{
     digesters::{
         cache::{ImmutableFileDigestCacheProvider, JsonImmutableFileDigestCacheProviderBuilder},
         CardanoImmutableDigester, DumbImmutableFileObserver, ImmutableDigester,
-        ImmutableFileObserver, ImmutableFileSystemObserver,
+        ImmutableFileObserver, ImmutableFileSystemObserver, ImmutableFileWatcher,
     },
     entities::{CertificatePending, CompressionAlgorithm, Epoch, SignedEntityTypeDiscriminants},
     era::{
-        adapters::{EraReaderAdapterBuilder, EraReaderDummyAdapter},
+        adapters::{EraReaderAdapterBuilder, EraReaderAdapterType, EraReaderDummyAdapter},
         EraChecker, EraMarker, EraReader, EraReaderAdapter, SupportedEra,
     },
+    logging::LogLevelHandle,
     signable_builder::{
         CardanoImmutableFilesFullSignableBuilder, CardanoStakeDistributionSignableBuilder,
         CardanoTransactionsSignableBuilder, MithrilSignableBuilderService,
@@ -41,6 +46,7 @@ use mithril_common::{
         TransactionsImporter,
     },
     signed_entity_type_lock::SignedEntityTypeLock,
+    test_utils::MithrilFixtureBuilder,
     MithrilTickerService, TickerService,
 };
 use mithril_persistence::{
@@ -49,7 +55,10 @@ use mithril_persistence::{
     store::adapter::{MemoryAdapter, SQLiteAdapter, StoreAdapter},
 };
 
-use super::{DependenciesBuilderError, EpochServiceWrapper, Result};
+use super::{
+    DependenciesBuilderError, DependenciesExplanation, DependencyExplanation, EpochServiceWrapper,
+    Result,
+};
 use crate::{
     artifact_builder::{
         CardanoImmutableFilesFullArtifactBuilder, CardanoStakeDistributionArtifactBuilder,
@@ -58,27 +67,40 @@ use crate::{
     configuration::ExecutionEnvironment,
     database::repository::{
         BufferedSingleSignatureRepository, CertificateRepository, EpochSettingsStore,
-        OpenMessageRepository, SignedEntityStore, SignedEntityStorer, SignerRegistrationStore,
-        SignerStore, SingleSignatureRepository, StakePoolStore,
+        OpenMessageRepository, ProtocolParametersScheduleStore, SignedEntityStore,
+        SignedEntityStorer, SignerRegistrationStore, SignerStore, SingleSignatureRepository,
+        StakePoolStore,
     },
     entities::AggregatorEpochSettings,
     event_store::{EventMessage, EventStore, TransmitterService},
-    http_server::routes::{
-        router,
-        router::{RouterConfig, RouterState},
+    http_server::{
+        routes::{
+            router,
+            router::{RouterConfig, RouterState},
+        },
+        HttpAccessLogger, RequestBodyValidator, RouteFeatureFlags, SignerAddressFilter,
     },
     services::{
-        AggregatorSignableSeedBuilder, AggregatorUpkeepService, BufferedCertifierService,
-        CardanoTransactionsImporter, CertifierService, MessageService, MithrilCertifierService,
-        MithrilEpochService, MithrilMessageService, MithrilProverService,
-        MithrilSignedEntityService, MithrilStakeDistributionService, ProverService,
-        SignedEntityService, StakeDistributionService, UpkeepService, UsageReporter,
+        AggregatorSignableSeedBuilder, AggregatorUpkeepService, AlertingRules, AlertingService,
+        ArtifactAvailabilityVerifier, AuditLogger, BufferedCertifierService,
+        CardanoTransactionsImporter, CertificateTransparencyLogPublisher, CertificationWatchdog,
+        CertifierService, ChainSimulator, DumbAuditLogger, DumbCertificateTransparencyLogPublisher,
+        DumbStaticMirrorExporter, FileAuditLogger, FileStaticMirrorExporter,
+        HttpArtifactLocationChecker, HttpCertificateTransparencyLogPublisher, MaintenanceScheduler,
+        MaintenanceWindow, MessageService, MithrilCertifierService, MithrilEpochService,
+        MithrilMessageService, MithrilProverService, MithrilRegistrationChallengeService,
+        MithrilSignatureReplayDetector, MithrilSignedEntityService,
+        MithrilStakeDistributionService, NoopAuditLogger, ProverService,
+        RegistrationChallengeService, SignatureReplayDetector, SignedEntityService, SimulatedChain,
+        SimulatedSigners, StakeDistributionService, StaticMirrorExporter, UpkeepService,
+        UsageReporter, WebhookAlertNotifier,
     },
     tools::{CExplorerSignerRetriever, GcpFileUploader, GenesisToolsDependency, SignersImporter},
     AggregatorConfig, AggregatorRunner, AggregatorRuntime, CertificatePendingStore,
     CompressedArchiveSnapshotter, Configuration, DependencyContainer, DumbSnapshotUploader,
-    DumbSnapshotter, EpochSettingsStorer, LocalSnapshotUploader, MetricsService,
-    MithrilSignerRegisterer, MultiSigner, MultiSignerImpl, RemoteSnapshotUploader,
+    DumbSnapshotter, EpochSettingsStorer, FileMerkleMapCacheStore, LocalSnapshotUploader,
+    MerkleMapCacheStorer, MetricsService, MithrilSignerRegisterer, MultiSigner, MultiSignerImpl,
+    ProtocolParametersScheduleStorer, RemoteSnapshotUploader, RuntimeStatusReporter,
     SingleSignatureAuthenticator, SnapshotUploader, SnapshotUploaderType, Snapshotter,
     SnapshotterCompressionAlgorithm, VerificationKeyStorer,
 };
@@ -87,6 +109,26 @@ const SQLITE_FILE: &str = "aggregator.sqlite3";
 const SQLITE_FILE_CARDANO_TRANSACTION: &str = "cardano-transaction.sqlite3";
 const SQLITE_MONITORING_FILE: &str = "monitoring.sqlite3";
 
+/// Name and file of the SQLite databases of the aggregator
+const SQLITE_DATABASES: [(&str, &str); 3] = [
+    ("main database", SQLITE_FILE),
+    (
+        "cardano transactions database",
+        SQLITE_FILE_CARDANO_TRANSACTION,
+    ),
+    ("monitoring database", SQLITE_MONITORING_FILE),
+];
+
+/// Clone a dependency once it has been built, failing instead of panicking if it is still unset
+fn cloned_dependency<T: Clone>(dependency: &Option<T>) -> Result<T> {
+    dependency.clone().ok_or_else(|| {
+        DependenciesBuilderError::InconsistentState(format!(
+            "The dependency '{}' is not set after being built.",
+            std::any::type_name::<T>()
+        ))
+    })
+}
+
 /// ## Dependencies container builder
 ///
 /// This is meant to create SHARED DEPENDENCIES, ie: dependencies instances that
@@ -139,12 +181,18 @@ pub struct DependenciesBuilder {
     /// Epoch settings storer.
     pub epoch_settings_storer: Option<Arc<dyn EpochSettingsStorer>>,
 
+    /// Protocol parameters schedule storer.
+    pub protocol_parameters_schedule_storer: Option<Arc<dyn ProtocolParametersScheduleStorer>>,
+
     /// Cardano CLI Runner for the [ChainObserver]
     pub cardano_cli_runner: Option<Box<CardanoCliRunner>>,
 
     /// Chain observer service.
     pub chain_observer: Option<Arc<dyn ChainObserver>>,
 
+    /// Fake chain observer, used as chain observer outside of production.
+    pub fake_chain_observer: Option<Arc<FakeObserver>>,
+
     /// Chain block reader
     pub chain_block_reader: Option<Arc<Mutex<dyn ChainBlockReader>>>,
 
@@ -160,6 +208,9 @@ pub struct DependenciesBuilder {
     /// Immutable file observer service.
     pub immutable_file_observer: Option<Arc<dyn ImmutableFileObserver>>,
 
+    /// Dumb immutable file observer, used as immutable file observer outside of production.
+    pub dumb_immutable_file_observer: Option<Arc<DumbImmutableFileObserver>>,
+
     /// Immutable cache provider service.
     pub immutable_cache_provider: Option<Arc<dyn ImmutableFileDigestCacheProvider>>,
 
@@ -246,6 +297,43 @@ pub struct DependenciesBuilder {
 
     /// Metrics service
     pub metrics_service: Option<Arc<MetricsService>>,
+
+    /// Runtime status reporter
+    pub runtime_status_reporter: Option<Arc<RuntimeStatusReporter>>,
+
+    /// Certification watchdog
+    pub certification_watchdog: Option<Arc<CertificationWatchdog>>,
+
+    /// Audit logger
+    pub audit_logger: Option<Arc<dyn AuditLogger>>,
+
+    /// HTTP access logger
+    pub http_access_logger: Option<Arc<HttpAccessLogger>>,
+
+    /// Signer registration challenge service
+    pub registration_challenge_service: Option<Arc<dyn RegistrationChallengeService>>,
+
+    /// Signer address filter
+    pub signer_address_filter: Option<Arc<SignerAddressFilter>>,
+
+    /// Request body validator
+    pub request_body_validator: Option<Arc<RequestBodyValidator>>,
+
+    /// Route feature flags
+    pub route_feature_flags: Option<Arc<RouteFeatureFlags>>,
+
+    /// Handle to change the level of the root logger at runtime
+    pub log_level_handle: Option<LogLevelHandle>,
+
+    /// Signature replay detector
+    pub signature_replay_detector: Option<Arc<dyn SignatureReplayDetector>>,
+
+    /// Certificate transparency log publisher
+    pub certificate_transparency_log_publisher:
+        Option<Arc<dyn CertificateTransparencyLogPublisher>>,
+
+    /// Static mirror exporter
+    pub static_mirror_exporter: Option<Arc<dyn StaticMirrorExporter>>,
 }
 
 impl DependenciesBuilder {
@@ -265,13 +353,16 @@ impl DependenciesBuilder {
             open_message_repository: None,
             verification_key_store: None,
             epoch_settings_storer: None,
+            protocol_parameters_schedule_storer: None,
             cardano_cli_runner: None,
             chain_observer: None,
+            fake_chain_observer: None,
             chain_block_reader: None,
             block_scanner: None,
             transaction_repository: None,
             immutable_digester: None,
             immutable_file_observer: None,
+            dumb_immutable_file_observer: None,
             immutable_cache_provider: None,
             digester: None,
             snapshotter: None,
@@ -300,6 +391,18 @@ impl DependenciesBuilder {
             upkeep_service: None,
             single_signer_authenticator: None,
             metrics_service: None,
+            runtime_status_reporter: None,
+            certification_watchdog: None,
+            audit_logger: None,
+            http_access_logger: None,
+            registration_challenge_service: None,
+            signer_address_filter: None,
+            request_body_validator: None,
+            route_feature_flags: None,
+            log_level_handle: None,
+            signature_replay_detector: None,
+            certificate_transparency_log_publisher: None,
+            static_mirror_exporter: None,
         }
     }
 
@@ -314,21 +417,51 @@ impl DependenciesBuilder {
         Ok(allowed_discriminants)
     }
 
+    /// Path of the given SQLite file in the data stores directory, `None` if the databases are
+    /// kept in memory
+    fn select_sqlite_file_path(&self, sqlite_file_name: &str) -> Option<PathBuf> {
+        match self.configuration.environment {
+            ExecutionEnvironment::Test
+                if self.configuration.data_stores_directory.to_string_lossy() == ":memory:" =>
+            {
+                None
+            }
+            _ => Some(
+                self.configuration
+                    .data_stores_directory
+                    .join(sqlite_file_name),
+            ),
+        }
+    }
+
+    /// Name and path of the SQLite databases of the aggregator, the path is `None` if the
+    /// databases are kept in memory
+    pub fn get_sqlite_database_paths(&self) -> Vec<(&'static str, Option<PathBuf>)> {
+        SQLITE_DATABASES
+            .iter()
+            .map(|(name, sqlite_file_name)| (*name, self.select_sqlite_file_path(sqlite_file_name)))
+            .collect()
+    }
+
     fn build_sqlite_connection(
         &self,
         sqlite_file_name: &str,
         migrations: Vec<SqlMigration>,
     ) -> Result<SqliteConnection> {
         let logger = self.root_logger();
-        let connection_builder = match self.configuration.environment {
-            ExecutionEnvironment::Test
-                if self.configuration.data_stores_directory.to_string_lossy() == ":memory:" =>
-            {
-                ConnectionBuilder::open_memory()
+        let connection_builder = match self.select_sqlite_file_path(sqlite_file_name) {
+            None => ConnectionBuilder::open_memory(),
+            Some(sqlite_file_path) => {
+                self.configuration.get_sqlite_dir().map_err(|e| {
+                    DependenciesBuilderError::Initialization {
+                        message: format!(
+                            "SQLite initialization: can not use the data stores directory for '{sqlite_file_name}'."
+                        ),
+                        error: Some(e),
+                    }
+                })?;
+                ConnectionBuilder::open_file(&sqlite_file_path)
             }
-            _ => ConnectionBuilder::open_file(
-                &self.configuration.get_sqlite_dir().join(sqlite_file_name),
-            ),
         };
 
         let connection = connection_builder
@@ -341,7 +474,9 @@ impl DependenciesBuilder {
             .with_migrations(migrations)
             .build()
             .map_err(|e| DependenciesBuilderError::Initialization {
-                message: "SQLite initialization: failed to build connection.".to_string(),
+                message: format!(
+                    "SQLite initialization: failed to build connection to '{sqlite_file_name}'."
+                ),
                 error: Some(e),
             })?;
 
@@ -369,7 +504,7 @@ impl DependenciesBuilder {
             )?));
         }
 
-        Ok(self.sqlite_connection.as_ref().cloned().unwrap())
+        cloned_dependency(&self.sqlite_connection)
     }
     /// Get EventStore SQLite connection
     pub async fn get_event_store_sqlite_connection(&mut self) -> Result<Arc<SqliteConnection>> {
@@ -380,11 +515,7 @@ impl DependenciesBuilder {
             )?));
         }
 
-        Ok(self
-            .sqlite_connection_event_store
-            .as_ref()
-            .cloned()
-            .unwrap())
+        cloned_dependency(&self.sqlite_connection_event_store)
     }
 
     async fn build_sqlite_connection_cardano_transaction_pool(
@@ -422,11 +553,7 @@ impl DependenciesBuilder {
             );
         }
 
-        Ok(self
-            .sqlite_connection_cardano_transaction_pool
-            .as_ref()
-            .cloned()
-            .unwrap())
+        cloned_dependency(&self.sqlite_connection_cardano_transaction_pool)
     }
 
     async fn build_stake_store(&mut self) -> Result<Arc<StakePoolStore>> {
@@ -444,13 +571,21 @@ impl DependenciesBuilder {
             self.stake_store = Some(self.build_stake_store().await?);
         }
 
-        Ok(self.stake_store.as_ref().cloned().unwrap())
+        cloned_dependency(&self.stake_store)
+    }
+
+    /// Type of the snapshot uploader to build, `None` if the snapshots are not uploaded
+    fn select_snapshot_uploader_type(&self) -> Option<SnapshotUploaderType> {
+        match self.configuration.environment {
+            ExecutionEnvironment::Production => Some(self.configuration.snapshot_uploader_type),
+            _ => None,
+        }
     }
 
     async fn build_snapshot_uploader(&mut self) -> Result<Arc<dyn SnapshotUploader>> {
         let logger = self.root_logger();
-        if self.configuration.environment == ExecutionEnvironment::Production {
-            match self.configuration.snapshot_uploader_type {
+        match self.select_snapshot_uploader_type() {
+            Some(snapshot_uploader_type) => match snapshot_uploader_type {
                 SnapshotUploaderType::Gcp => {
                     let bucket = self
                         .configuration
@@ -474,9 +609,8 @@ impl DependenciesBuilder {
                     &self.configuration.snapshot_directory,
                     logger,
                 ))),
-            }
-        } else {
-            Ok(Arc::new(DumbSnapshotUploader::new()))
+            },
+            None => Ok(Arc::new(DumbSnapshotUploader::new())),
         }
     }
 
@@ -486,7 +620,7 @@ impl DependenciesBuilder {
             self.snapshot_uploader = Some(self.build_snapshot_uploader().await?);
         }
 
-        Ok(self.snapshot_uploader.as_ref().cloned().unwrap())
+        cloned_dependency(&self.snapshot_uploader)
     }
 
     async fn build_multi_signer(&mut self) -> Result<Arc<dyn MultiSigner>> {
@@ -502,7 +636,7 @@ impl DependenciesBuilder {
             self.multi_signer = Some(self.build_multi_signer().await?);
         }
 
-        Ok(self.multi_signer.as_ref().cloned().unwrap())
+        cloned_dependency(&self.multi_signer)
     }
 
     async fn build_certificate_pending_store(&mut self) -> Result<Arc<CertificatePendingStore>> {
@@ -542,7 +676,7 @@ impl DependenciesBuilder {
             self.certificate_pending_store = Some(self.build_certificate_pending_store().await?);
         }
 
-        Ok(self.certificate_pending_store.as_ref().cloned().unwrap())
+        cloned_dependency(&self.certificate_pending_store)
     }
 
     async fn build_certificate_repository(&mut self) -> Result<Arc<CertificateRepository>> {
@@ -557,7 +691,7 @@ impl DependenciesBuilder {
             self.certificate_repository = Some(self.build_certificate_repository().await?);
         }
 
-        Ok(self.certificate_repository.as_ref().cloned().unwrap())
+        cloned_dependency(&self.certificate_repository)
     }
 
     async fn build_open_message_repository(&mut self) -> Result<Arc<OpenMessageRepository>> {
@@ -572,7 +706,7 @@ impl DependenciesBuilder {
             self.open_message_repository = Some(self.build_open_message_repository().await?);
         }
 
-        Ok(self.open_message_repository.as_ref().cloned().unwrap())
+        cloned_dependency(&self.open_message_repository)
     }
 
     async fn build_verification_key_store(&mut self) -> Result<Arc<dyn VerificationKeyStorer>> {
@@ -587,7 +721,7 @@ impl DependenciesBuilder {
             self.verification_key_store = Some(self.build_verification_key_store().await?);
         }
 
-        Ok(self.verification_key_store.as_ref().cloned().unwrap())
+        cloned_dependency(&self.verification_key_store)
     }
 
     async fn build_epoch_settings_storer(&mut self) -> Result<Arc<dyn EpochSettingsStorer>> {
@@ -646,43 +780,94 @@ impl DependenciesBuilder {
             self.epoch_settings_storer = Some(self.build_epoch_settings_storer().await?);
         }
 
-        Ok(self.epoch_settings_storer.as_ref().cloned().unwrap())
+        cloned_dependency(&self.epoch_settings_storer)
     }
 
-    async fn build_chain_observer(&mut self) -> Result<Arc<dyn ChainObserver>> {
-        let chain_observer: Arc<dyn ChainObserver> = match self.configuration.environment {
+    async fn build_protocol_parameters_schedule_storer(
+        &mut self,
+    ) -> Result<Arc<dyn ProtocolParametersScheduleStorer>> {
+        Ok(Arc::new(ProtocolParametersScheduleStore::new(
+            self.get_sqlite_connection().await?,
+        )))
+    }
+
+    /// Get a configured [ProtocolParametersScheduleStorer].
+    pub async fn get_protocol_parameters_schedule_storer(
+        &mut self,
+    ) -> Result<Arc<dyn ProtocolParametersScheduleStorer>> {
+        if self.protocol_parameters_schedule_storer.is_none() {
+            self.protocol_parameters_schedule_storer =
+                Some(self.build_protocol_parameters_schedule_storer().await?);
+        }
+
+        cloned_dependency(&self.protocol_parameters_schedule_storer)
+    }
+
+    /// Type of the chain observer to build, `None` if the [FakeObserver] is used
+    fn select_chain_observer_type(&self) -> Option<ChainObserverType> {
+        match self.configuration.environment {
             ExecutionEnvironment::Production => {
+                Some(self.configuration.chain_observer_type.clone())
+            }
+            _ => None,
+        }
+    }
+
+    async fn build_chain_observer(&mut self) -> Result<Arc<dyn ChainObserver>> {
+        let chain_observer: Arc<dyn ChainObserver> = match self.select_chain_observer_type() {
+            Some(chain_observer_type) => {
                 let cardano_cli_runner = &self.get_cardano_cli_runner().await?;
-                let chain_observer_type = &self.configuration.chain_observer_type;
                 let cardano_node_socket_path = &self.configuration.cardano_node_socket_path;
                 let cardano_network = &self
                     .configuration
                     .get_network()
                     .with_context(|| "Dependencies Builder can not get Cardano network while building the chain observer")?;
                 let chain_observer_builder = ChainObserverBuilder::new(
-                    chain_observer_type,
+                    &chain_observer_type,
                     cardano_node_socket_path,
                     cardano_network,
                     Some(cardano_cli_runner),
+                )
+                .with_blockfrost_project_id(
+                    self.configuration
+                        .blockfrost_project_id
+                        .as_ref()
+                        .map(|id| id.expose_secret().to_string()),
+                )
+                .with_blockfrost_endpoint(self.configuration.blockfrost_endpoint.clone())
+                .with_db_sync_connection_string(
+                    self.configuration
+                        .db_sync_connection_string
+                        .as_ref()
+                        .map(|connection| connection.expose_secret().to_string()),
                 );
 
                 chain_observer_builder
                     .build()
                     .with_context(|| "Dependencies Builder can not build chain observer")?
             }
-            _ => Arc::new(FakeObserver::default()),
+            None => self.get_fake_chain_observer().await?,
         };
 
         Ok(chain_observer)
     }
 
+    /// Return the [FakeObserver] used as [ChainObserver] outside of production.
+    pub async fn get_fake_chain_observer(&mut self) -> Result<Arc<FakeObserver>> {
+        if self.fake_chain_observer.is_none() {
+            self.fake_chain_observer = Some(Arc::new(FakeObserver::default()));
+        }
+
+        cloned_dependency(&self.fake_chain_observer)
+    }
+
     /// Return a [ChainObserver]
     pub async fn get_chain_observer(&mut self) -> Result<Arc<dyn ChainObserver>> {
         if self.chain_observer.is_none() {
             self.chain_observer = Some(self.build_chain_observer().await?);
         }
 
-        Ok(self.chain_observer.as_ref().cloned().unwrap())
+        cloned_dependency(&self.chain_observer)
     }
 
     async fn build_cardano_cli_runner(&mut self) -> Result<Box<CardanoCliRunner>> {
@@ -703,7 +888,7 @@ impl DependenciesBuilder {
             self.cardano_cli_runner = Some(self.build_cardano_cli_runner().await?);
         }
 
-        Ok(self.cardano_cli_runner.as_ref().cloned().unwrap())
+        cloned_dependency(&self.cardano_cli_runner)
     }
 
     async fn build_immutable_file_observer(&mut self) -> Result<Arc<dyn ImmutableFileObserver>> {
@@ -712,19 +897,31 @@ impl DependenciesBuilder {
                 ExecutionEnvironment::Production => Arc::new(ImmutableFileSystemObserver::new(
                     &self.configuration.db_directory,
                 )),
-                _ => Arc::new(DumbImmutableFileObserver::default()),
+                _ => self.get_dumb_immutable_file_observer().await?,
             };
 
         Ok(immutable_file_observer)
     }
 
+    /// Return the [DumbImmutableFileObserver] used as [ImmutableFileObserver] outside of production.
+    pub async fn get_dumb_immutable_file_observer(
+        &mut self,
+    ) -> Result<Arc<DumbImmutableFileObserver>> {
+        if self.dumb_immutable_file_observer.is_none() {
+            self.dumb_immutable_file_observer =
+                Some(Arc::new(DumbImmutableFileObserver::default()));
+        }
+
+        cloned_dependency(&self.dumb_immutable_file_observer)
+    }
+
     /// Return a [ImmutableFileObserver] instance.
     pub async fn get_immutable_file_observer(&mut self) -> Result<Arc<dyn ImmutableFileObserver>> {
         if self.immutable_file_observer.is_none() {
             self.immutable_file_observer = Some(self.build_immutable_file_observer().await?);
         }
 
-        Ok(self.immutable_file_observer.as_ref().cloned().unwrap())
+        cloned_dependency(&self.immutable_file_observer)
     }
 
     async fn build_immutable_cache_provider(
@@ -750,7 +947,7 @@ impl DependenciesBuilder {
             self.immutable_cache_provider = Some(self.build_immutable_cache_provider().await?);
         }
 
-        Ok(self.immutable_cache_provider.as_ref().cloned().unwrap())
+        cloned_dependency(&self.immutable_cache_provider)
     }
 
     /// Return a copy of the root logger.
@@ -758,6 +955,106 @@ impl DependenciesBuilder {
         self.root_logger.clone()
     }
 
+    /// Explain the implementations of the configurable dependencies selected by the
+    /// configuration and the resources they use, without building them.
+    pub fn explain_dependencies(&self) -> DependenciesExplanation {
+        fn serialized_name<T: Serialize>(value: &T) -> String {
+            match serde_json::to_value(value) {
+                Ok(serde_json::Value::String(name)) => name,
+                _ => "unknown".to_string(),
+            }
+        }
+        let configuration = &self.configuration;
+        let sqlite_databases = self
+            .get_sqlite_database_paths()
+            .into_iter()
+            .map(|(name, path)| {
+                let explanation = DependencyExplanation::new(name, "sqlite");
+                match path {
+                    Some(path) => explanation.with_resource(path.display().to_string()),
+                    None => explanation.with_resource(":memory:"),
+                }
+            });
+
+        let chain_observer = match self.select_chain_observer_type() {
+            Some(chain_observer_type) => {
+                let explanation =
+                    DependencyExplanation::new("chain observer", chain_observer_type.to_string());
+                match chain_observer_type {
+                    ChainObserverType::CardanoCli | ChainObserverType::Pallas => explanation
+                        .with_resource(
+                            configuration.cardano_node_socket_path.display().to_string(),
+                        ),
+                    _ => explanation,
+                }
+            }
+            None => DependencyExplanation::new("chain observer", "fake"),
+        };
+        let stake_distribution_source = DependencyExplanation::new(
+            "stake distribution source",
+            serialized_name(&configuration.stake_distribution_source_type),
+        );
+        let snapshot_uploader = match self.select_snapshot_uploader_type() {
+            Some(snapshot_uploader_type) => {
+                let explanation = DependencyExplanation::new(
+                    "snapshot uploader",
+                    serialized_name(&snapshot_uploader_type),
+                );
+                match (snapshot_uploader_type, &configuration.snapshot_bucket_name) {
+                    (SnapshotUploaderType::Gcp, Some(bucket)) => explanation.with_resource(bucket),
+                    _ => explanation,
+                }
+            }
+            None => DependencyExplanation::new("snapshot uploader", "dumb"),
+        };
+        let era_reader_adapter = match self.select_era_reader_adapter_type() {
+            Some(era_reader_adapter_type) => DependencyExplanation::new(
+                "era reader adapter",
+                era_reader_adapter_type.to_string(),
+            ),
+            None => DependencyExplanation::new("era reader adapter", "dummy"),
+        };
+        let optional_file = |name, path: &Option<PathBuf>| match path {
+            Some(path) => {
+                DependencyExplanation::new(name, "file").with_resource(path.display().to_string())
+            }
+            None => DependencyExplanation::new(name, "none"),
+        };
+
+        DependenciesExplanation {
+            dependencies: sqlite_databases
+                .chain([
+                    chain_observer,
+                    match &configuration.stake_distribution_source_location {
+                        Some(location) => stake_distribution_source.with_resource(location),
+                        None => stake_distribution_source,
+                    },
+                    era_reader_adapter,
+                    DependencyExplanation::new("snapshot directory", "directory")
+                        .with_resource(configuration.snapshot_directory.display().to_string()),
+                    snapshot_uploader,
+                    match &configuration.certificate_transparency_log_endpoint {
+                        Some(endpoint) => {
+                            DependencyExplanation::new("certificate transparency log", "http")
+                                .with_resource(endpoint)
+                        }
+                        None => DependencyExplanation::new("certificate transparency log", "none"),
+                    },
+                    optional_file("static mirror", &configuration.static_mirror_directory),
+                    optional_file("audit log", &configuration.audit_log_path),
+                    // The webhook URL is a secret, only its format is explained
+                    match &configuration.alerting_webhook_url {
+                        Some(_) => DependencyExplanation::new(
+                            "alerting webhook",
+                            serialized_name(&configuration.alerting_webhook_format),
+                        ),
+                        None => DependencyExplanation::new("alerting webhook", "none"),
+                    },
+                ])
+                .collect(),
+        }
+    }
+
     async fn build_transaction_repository(&mut self) -> Result<Arc<CardanoTransactionRepository>> {
         let transaction_store = CardanoTransactionRepository::new(
             self.get_sqlite_connection_cardano_transaction_pool()
@@ -775,7 +1072,7 @@ impl DependenciesBuilder {
             self.transaction_repository = Some(self.build_transaction_repository().await?);
         }
 
-        Ok(self.transaction_repository.as_ref().cloned().unwrap())
+        cloned_dependency(&self.transaction_repository)
     }
 
     async fn build_chain_block_reader(&mut self) -> Result<Arc<Mutex<dyn ChainBlockReader>>> {
@@ -794,7 +1091,7 @@ impl DependenciesBuilder {
             self.chain_block_reader = Some(self.build_chain_block_reader().await?);
         }
 
-        Ok(self.chain_block_reader.as_ref().cloned().unwrap())
+        cloned_dependency(&self.chain_block_reader)
     }
 
     async fn build_block_scanner(&mut self) -> Result<Arc<dyn BlockScanner>> {
@@ -814,7 +1111,7 @@ impl DependenciesBuilder {
             self.block_scanner = Some(self.build_block_scanner().await?);
         }
 
-        Ok(self.block_scanner.as_ref().cloned().unwrap())
+        cloned_dependency(&self.block_scanner)
     }
 
     async fn build_immutable_digester(&mut self) -> Result<Arc<dyn ImmutableDigester>> {
@@ -822,7 +1119,8 @@ impl DependenciesBuilder {
             ExecutionEnvironment::Production => Some(self.get_immutable_cache_provider().await?),
             _ => None,
         };
-        let digester = CardanoImmutableDigester::new(immutable_digester_cache, self.root_logger());
+        let digester = CardanoImmutableDigester::new(immutable_digester_cache, self.root_logger())
+            .with_era_checker(self.get_era_checker().await?);
 
         Ok(Arc::new(digester))
     }
@@ -833,7 +1131,7 @@ impl DependenciesBuilder {
             self.immutable_digester = Some(self.build_immutable_digester().await?);
         }
 
-        Ok(self.immutable_digester.as_ref().cloned().unwrap())
+        cloned_dependency(&self.immutable_digester)
     }
 
     async fn build_snapshotter(&mut self) -> Result<Arc<dyn Snapshotter>> {
@@ -872,7 +1170,7 @@ impl DependenciesBuilder {
             self.snapshotter = Some(self.build_snapshotter().await?);
         }
 
-        Ok(self.snapshotter.as_ref().cloned().unwrap())
+        cloned_dependency(&self.snapshotter)
     }
 
     async fn build_certificate_verifier(&mut self) -> Result<Arc<dyn CertificateVerifier>> {
@@ -890,7 +1188,7 @@ impl DependenciesBuilder {
             self.certificate_verifier = Some(self.build_certificate_verifier().await?);
         }
 
-        Ok(self.certificate_verifier.as_ref().cloned().unwrap())
+        cloned_dependency(&self.certificate_verifier)
     }
 
     async fn build_genesis_verifier(&mut self) -> Result<Arc<ProtocolGenesisVerifier>> {
@@ -920,7 +1218,7 @@ impl DependenciesBuilder {
             self.genesis_verifier = Some(self.build_genesis_verifier().await?);
         }
 
-        Ok(self.genesis_verifier.as_ref().cloned().unwrap())
+        cloned_dependency(&self.genesis_verifier)
     }
 
     async fn build_mithril_registerer(&mut self) -> Result<Arc<MithrilSignerRegisterer>> {
@@ -929,6 +1227,12 @@ impl DependenciesBuilder {
             self.get_verification_key_store().await?,
             self.get_signer_store().await?,
             self.configuration.safe_epoch_retention_limit(),
+            Duration::from_secs(
+                self.configuration
+                    .signer_registration_grace_period_in_seconds,
+            ),
+            self.get_metrics_service().await?,
+            self.root_logger(),
         );
 
         Ok(Arc::new(registerer))
@@ -940,13 +1244,23 @@ impl DependenciesBuilder {
             self.mithril_registerer = Some(self.build_mithril_registerer().await?);
         }
 
-        Ok(self.mithril_registerer.as_ref().cloned().unwrap())
+        cloned_dependency(&self.mithril_registerer)
+    }
+
+    /// Type of the era reader adapter to build, `None` if the dummy adapter is used
+    fn select_era_reader_adapter_type(&self) -> Option<EraReaderAdapterType> {
+        match self.configuration.environment {
+            ExecutionEnvironment::Production => {
+                Some(self.configuration.era_reader_adapter_type.clone())
+            }
+            _ => None,
+        }
     }
 
     async fn build_era_reader(&mut self) -> Result<Arc<EraReader>> {
-        let era_adapter: Arc<dyn EraReaderAdapter> = match self.configuration.environment {
-            ExecutionEnvironment::Production => EraReaderAdapterBuilder::new(
-                &self.configuration.era_reader_adapter_type,
+        let era_adapter: Arc<dyn EraReaderAdapter> = match self.select_era_reader_adapter_type() {
+            Some(era_reader_adapter_type) => EraReaderAdapterBuilder::new(
+                &era_reader_adapter_type,
                 &self.configuration.era_reader_adapter_params,
             )
             .build(self.get_chain_observer().await?)
@@ -954,7 +1268,7 @@ impl DependenciesBuilder {
                 message: "Could not build EraReader as dependency.".to_string(),
                 error: Some(e.into()),
             })?,
-            _ => Arc::new(EraReaderDummyAdapter::from_markers(vec![EraMarker::new(
+            None => Arc::new(EraReaderDummyAdapter::from_markers(vec![EraMarker::new(
                 &SupportedEra::dummy().to_string(),
                 Some(Epoch(0)),
             )])),
@@ -969,7 +1283,7 @@ impl DependenciesBuilder {
             self.era_reader = Some(self.build_era_reader().await?);
         }
 
-        Ok(self.era_reader.as_ref().cloned().unwrap())
+        cloned_dependency(&self.era_reader)
     }
 
     async fn build_era_checker(&mut self) -> Result<Arc<EraChecker>> {
@@ -1010,7 +1324,7 @@ impl DependenciesBuilder {
             self.era_checker = Some(self.build_era_checker().await?);
         }
 
-        Ok(self.era_checker.as_ref().cloned().unwrap())
+        cloned_dependency(&self.era_checker)
     }
 
     async fn build_event_transmitter_channel(
@@ -1031,12 +1345,7 @@ impl DependenciesBuilder {
             self.event_transmitter_channel = (Some(rx), Some(tx));
         }
 
-        Ok(self
-            .event_transmitter_channel
-            .1
-            .as_ref()
-            .cloned()
-            .expect("Transmitter<EventMessage> should be set."))
+        cloned_dependency(&self.event_transmitter_channel.1)
     }
 
     /// Return the channel receiver setup for the [EventStore]. Since this
@@ -1071,7 +1380,7 @@ impl DependenciesBuilder {
             self.event_transmitter = Some(self.build_event_transmitter().await?);
         }
 
-        Ok(self.event_transmitter.as_ref().cloned().unwrap())
+        cloned_dependency(&self.event_transmitter)
     }
 
     async fn build_api_version_provider(&mut self) -> Result<Arc<APIVersionProvider>> {
@@ -1086,7 +1395,27 @@ impl DependenciesBuilder {
             self.api_version_provider = Some(self.build_api_version_provider().await?);
         }
 
-        Ok(self.api_version_provider.as_ref().cloned().unwrap())
+        cloned_dependency(&self.api_version_provider)
+    }
+
+    /// Create a [StakeDistributionSource] instance.
+    async fn build_stake_distribution_source(
+        &mut self,
+    ) -> Result<Arc<dyn StakeDistributionSource>> {
+        let stake_distribution_source = StakeDistributionSourceBuilder::new(
+            self.configuration.stake_distribution_source_type,
+            self.configuration
+                .stake_distribution_source_location
+                .to_owned(),
+            self.get_chain_observer().await?,
+        )
+        .build()
+        .map_err(|e| DependenciesBuilderError::Initialization {
+            message: "Could not build the stake distribution source".to_string(),
+            error: Some(e),
+        })?;
+
+        Ok(stake_distribution_source)
     }
 
     async fn build_stake_distribution_service(
@@ -1095,6 +1424,7 @@ impl DependenciesBuilder {
         let stake_distribution_service = Arc::new(MithrilStakeDistributionService::new(
             self.get_stake_store().await?,
             self.get_chain_observer().await?,
+            self.build_stake_distribution_source().await?,
         ));
 
         Ok(stake_distribution_service)
@@ -1108,7 +1438,7 @@ impl DependenciesBuilder {
             self.stake_distribution_service = Some(self.build_stake_distribution_service().await?);
         }
 
-        Ok(self.stake_distribution_service.as_ref().cloned().unwrap())
+        cloned_dependency(&self.stake_distribution_service)
     }
 
     async fn build_signer_store(&mut self) -> Result<Arc<SignerStore>> {
@@ -1171,7 +1501,7 @@ impl DependenciesBuilder {
             self.signable_builder_service = Some(self.build_signable_builder_service().await?);
         }
 
-        Ok(self.signable_builder_service.as_ref().cloned().unwrap())
+        cloned_dependency(&self.signable_builder_service)
     }
 
     async fn build_signable_seed_builder(&mut self) -> Result<Arc<dyn SignableSeedBuilder>> {
@@ -1188,7 +1518,7 @@ impl DependenciesBuilder {
             self.signable_seed_builder = Some(self.build_signable_seed_builder().await?);
         }
 
-        Ok(self.signable_seed_builder.as_ref().cloned().unwrap())
+        cloned_dependency(&self.signable_seed_builder)
     }
 
     async fn build_signed_entity_service(&mut self) -> Result<Arc<dyn SignedEntityService>> {
@@ -1217,6 +1547,7 @@ impl DependenciesBuilder {
         let stake_store = self.get_stake_store().await?;
         let cardano_stake_distribution_artifact_builder =
             Arc::new(CardanoStakeDistributionArtifactBuilder::new(stake_store));
+        let static_mirror_exporter = self.get_static_mirror_exporter().await?;
         let signed_entity_service = Arc::new(MithrilSignedEntityService::new(
             signed_entity_storer,
             mithril_stake_distribution_artifact_builder,
@@ -1224,6 +1555,7 @@ impl DependenciesBuilder {
             cardano_transactions_artifact_builder,
             self.get_signed_entity_lock().await?,
             cardano_stake_distribution_artifact_builder,
+            static_mirror_exporter,
             logger,
         ));
 
@@ -1248,12 +1580,14 @@ impl DependenciesBuilder {
             self.signed_entity_service = Some(self.build_signed_entity_service().await?);
         }
 
-        Ok(self.signed_entity_service.as_ref().cloned().unwrap())
+        cloned_dependency(&self.signed_entity_service)
     }
 
     async fn build_epoch_service(&mut self) -> Result<EpochServiceWrapper> {
         let verification_key_store = self.get_verification_key_store().await?;
         let epoch_settings_storer = self.get_epoch_settings_storer().await?;
+        let protocol_parameters_schedule_storer =
+            self.get_protocol_parameters_schedule_storer().await?;
         let epoch_settings = self.get_epoch_settings_configuration()?;
         let network = self.configuration.get_network()?;
         let allowed_discriminants = self.get_allowed_signed_entity_types_discriminants()?;
@@ -1261,6 +1595,7 @@ impl DependenciesBuilder {
         let epoch_service = Arc::new(RwLock::new(MithrilEpochService::new(
             epoch_settings,
             epoch_settings_storer,
+            protocol_parameters_schedule_storer,
             verification_key_store,
             network,
             allowed_discriminants,
@@ -1276,7 +1611,7 @@ impl DependenciesBuilder {
             self.epoch_service = Some(self.build_epoch_service().await?);
         }
 
-        Ok(self.epoch_service.as_ref().cloned().unwrap())
+        cloned_dependency(&self.epoch_service)
     }
 
     async fn build_signed_entity_storer(&mut self) -> Result<Arc<dyn SignedEntityStorer>> {
@@ -1292,7 +1627,7 @@ impl DependenciesBuilder {
             self.signed_entity_storer = Some(self.build_signed_entity_storer().await?);
         }
 
-        Ok(self.signed_entity_storer.as_ref().cloned().unwrap())
+        cloned_dependency(&self.signed_entity_storer)
     }
 
     async fn build_signed_entity_lock(&mut self) -> Result<Arc<SignedEntityTypeLock>> {
@@ -1305,13 +1640,16 @@ impl DependenciesBuilder {
             self.signed_entity_type_lock = Some(self.build_signed_entity_lock().await?);
         }
 
-        Ok(self.signed_entity_type_lock.as_ref().cloned().unwrap())
+        cloned_dependency(&self.signed_entity_type_lock)
     }
 
     async fn build_transactions_importer(&mut self) -> Result<Arc<dyn TransactionsImporter>> {
         let transactions_importer = Arc::new(CardanoTransactionsImporter::new(
             self.get_block_scanner().await?,
             self.get_transaction_repository().await?,
+            self.configuration
+                .cardano_transactions_signing_config
+                .security_parameter,
             self.root_logger(),
         ));
 
@@ -1323,7 +1661,7 @@ impl DependenciesBuilder {
             self.transactions_importer = Some(self.build_transactions_importer().await?);
         }
 
-        Ok(self.transactions_importer.as_ref().cloned().unwrap())
+        cloned_dependency(&self.transactions_importer)
     }
 
     async fn build_upkeep_service(&mut self) -> Result<Arc<dyn UpkeepService>> {
@@ -1344,14 +1682,22 @@ impl DependenciesBuilder {
             self.upkeep_service = Some(self.build_upkeep_service().await?);
         }
 
-        Ok(self.upkeep_service.as_ref().cloned().unwrap())
+        cloned_dependency(&self.upkeep_service)
     }
 
     async fn build_single_signature_authenticator(
         &mut self,
     ) -> Result<Arc<SingleSignatureAuthenticator>> {
-        let authenticator =
-            SingleSignatureAuthenticator::new(self.get_multi_signer().await?, self.root_logger());
+        let authenticator = SingleSignatureAuthenticator::new(
+            self.get_multi_signer().await?,
+            self.get_metrics_service().await?,
+            self.root_logger(),
+        )
+        .with_verification_order(self.configuration.single_signature_verification_order)
+        .with_cache(
+            self.configuration
+                .single_signature_authentication_cache_size,
+        );
 
         Ok(Arc::new(authenticator))
     }
@@ -1365,7 +1711,7 @@ impl DependenciesBuilder {
                 Some(self.build_single_signature_authenticator().await?);
         }
 
-        Ok(self.single_signer_authenticator.as_ref().cloned().unwrap())
+        cloned_dependency(&self.single_signer_authenticator)
     }
 
     fn get_epoch_settings_configuration(&mut self) -> Result<AggregatorEpochSettings> {
@@ -1392,7 +1738,246 @@ impl DependenciesBuilder {
             self.metrics_service = Some(self.build_metrics_service().await?);
         }
 
-        Ok(self.metrics_service.as_ref().cloned().unwrap())
+        cloned_dependency(&self.metrics_service)
+    }
+
+    /// Create a [RuntimeStatusReporter] instance.
+    async fn build_runtime_status_reporter(&mut self) -> Result<Arc<RuntimeStatusReporter>> {
+        let runtime_status_reporter = RuntimeStatusReporter::new(self.get_metrics_service().await?);
+
+        Ok(Arc::new(runtime_status_reporter))
+    }
+
+    /// [RuntimeStatusReporter] service
+    pub async fn get_runtime_status_reporter(&mut self) -> Result<Arc<RuntimeStatusReporter>> {
+        if self.runtime_status_reporter.is_none() {
+            self.runtime_status_reporter = Some(self.build_runtime_status_reporter().await?);
+        }
+
+        cloned_dependency(&self.runtime_status_reporter)
+    }
+
+    /// Create a [CertificationWatchdog] instance.
+    async fn build_certification_watchdog(&mut self) -> Result<Arc<CertificationWatchdog>> {
+        let certification_watchdog = CertificationWatchdog::new(
+            self.configuration.certification_watchdog_warning_ratio,
+            self.configuration
+                .cardano_transactions_signing_config
+                .clone(),
+            self.get_event_transmitter().await?,
+            self.get_metrics_service().await?,
+            self.root_logger(),
+        );
+
+        Ok(Arc::new(certification_watchdog))
+    }
+
+    /// [CertificationWatchdog] service
+    pub async fn get_certification_watchdog(&mut self) -> Result<Arc<CertificationWatchdog>> {
+        if self.certification_watchdog.is_none() {
+            self.certification_watchdog = Some(self.build_certification_watchdog().await?);
+        }
+
+        cloned_dependency(&self.certification_watchdog)
+    }
+
+    /// Create a [AuditLogger] instance.
+    async fn build_audit_logger(&mut self) -> Result<Arc<dyn AuditLogger>> {
+        let audit_logger: Arc<dyn AuditLogger> = match (
+            &self.configuration.audit_log_path,
+            &self.configuration.environment,
+        ) {
+            (Some(path), _) => Arc::new(FileAuditLogger::new(path, self.root_logger())?),
+            (None, ExecutionEnvironment::Production) => Arc::new(NoopAuditLogger),
+            (None, _) => Arc::new(DumbAuditLogger::new()),
+        };
+
+        Ok(audit_logger)
+    }
+
+    /// [AuditLogger] service
+    pub async fn get_audit_logger(&mut self) -> Result<Arc<dyn AuditLogger>> {
+        if self.audit_logger.is_none() {
+            self.audit_logger = Some(self.build_audit_logger().await?);
+        }
+
+        cloned_dependency(&self.audit_logger)
+    }
+
+    /// Create a [CertificateTransparencyLogPublisher] instance.
+    async fn build_certificate_transparency_log_publisher(
+        &mut self,
+    ) -> Result<Arc<dyn CertificateTransparencyLogPublisher>> {
+        let publisher: Arc<dyn CertificateTransparencyLogPublisher> =
+            match &self.configuration.certificate_transparency_log_endpoint {
+                Some(endpoint) => Arc::new(HttpCertificateTransparencyLogPublisher::new(endpoint)?),
+                None => Arc::new(DumbCertificateTransparencyLogPublisher::new()),
+            };
+
+        Ok(publisher)
+    }
+
+    /// [CertificateTransparencyLogPublisher] service
+    pub async fn get_certificate_transparency_log_publisher(
+        &mut self,
+    ) -> Result<Arc<dyn CertificateTransparencyLogPublisher>> {
+        if self.certificate_transparency_log_publisher.is_none() {
+            self.certificate_transparency_log_publisher =
+                Some(self.build_certificate_transparency_log_publisher().await?);
+        }
+
+        cloned_dependency(&self.certificate_transparency_log_publisher)
+    }
+
+    /// Create a [StaticMirrorExporter] instance.
+    async fn build_static_mirror_exporter(&mut self) -> Result<Arc<dyn StaticMirrorExporter>> {
+        let exporter: Arc<dyn StaticMirrorExporter> =
+            match self.configuration.static_mirror_directory.clone() {
+                Some(directory) => {
+                    let message_service = self.get_message_service().await?;
+                    Arc::new(FileStaticMirrorExporter::new(
+                        &directory,
+                        &self.configuration.snapshot_directory,
+                        self.configuration.static_mirror_url.clone(),
+                        message_service,
+                        self.root_logger(),
+                    ))
+                }
+                None => Arc::new(DumbStaticMirrorExporter::new()),
+            };
+
+        Ok(exporter)
+    }
+
+    /// [StaticMirrorExporter] service
+    pub async fn get_static_mirror_exporter(&mut self) -> Result<Arc<dyn StaticMirrorExporter>> {
+        if self.static_mirror_exporter.is_none() {
+            self.static_mirror_exporter = Some(self.build_static_mirror_exporter().await?);
+        }
+
+        cloned_dependency(&self.static_mirror_exporter)
+    }
+
+    /// Create a [RegistrationChallengeService] instance.
+    async fn build_registration_challenge_service(
+        &mut self,
+    ) -> Result<Arc<dyn RegistrationChallengeService>> {
+        let registration_challenge_service = MithrilRegistrationChallengeService::new(
+            Duration::from_secs(
+                self.configuration
+                    .signer_registration_challenge_validity_in_seconds,
+            ),
+            self.configuration.signer_registration_challenge_required,
+        );
+
+        Ok(Arc::new(registration_challenge_service))
+    }
+
+    /// [RegistrationChallengeService] service
+    pub async fn get_registration_challenge_service(
+        &mut self,
+    ) -> Result<Arc<dyn RegistrationChallengeService>> {
+        if self.registration_challenge_service.is_none() {
+            self.registration_challenge_service =
+                Some(self.build_registration_challenge_service().await?);
+        }
+
+        cloned_dependency(&self.registration_challenge_service)
+    }
+
+    /// Create a [HttpAccessLogger] instance.
+    async fn build_http_access_logger(&mut self) -> Result<Arc<HttpAccessLogger>> {
+        let http_access_logger = match &self.configuration.http_access_log_path {
+            Some(path) => HttpAccessLogger::new(
+                path,
+                self.configuration.http_access_log_format,
+                self.root_logger(),
+            )?,
+            None => HttpAccessLogger::disabled(self.root_logger()),
+        };
+
+        Ok(Arc::new(http_access_logger))
+    }
+
+    /// [HttpAccessLogger] service
+    pub async fn get_http_access_logger(&mut self) -> Result<Arc<HttpAccessLogger>> {
+        if self.http_access_logger.is_none() {
+            self.http_access_logger = Some(self.build_http_access_logger().await?);
+        }
+
+        cloned_dependency(&self.http_access_logger)
+    }
+
+    /// Create a [SignerAddressFilter] instance.
+    async fn build_signer_address_filter(&mut self) -> Result<Arc<SignerAddressFilter>> {
+        let signer_address_filter = match self.configuration.signer_address_rules_path.clone() {
+            Some(path) => SignerAddressFilter::from_file(&path, self.root_logger())?
+                .with_audit_logger(self.get_audit_logger().await?),
+            None => SignerAddressFilter::allow_all(self.root_logger()),
+        };
+
+        Ok(Arc::new(signer_address_filter))
+    }
+
+    /// [SignerAddressFilter] service
+    pub async fn get_signer_address_filter(&mut self) -> Result<Arc<SignerAddressFilter>> {
+        if self.signer_address_filter.is_none() {
+            self.signer_address_filter = Some(self.build_signer_address_filter().await?);
+        }
+
+        cloned_dependency(&self.signer_address_filter)
+    }
+
+    /// Create a [RouteFeatureFlags] instance.
+    async fn build_route_feature_flags(&mut self) -> Result<Arc<RouteFeatureFlags>> {
+        let disabled_route_groups = self.configuration.compute_disabled_route_groups()?;
+
+        Ok(Arc::new(RouteFeatureFlags::new(
+            disabled_route_groups,
+            self.root_logger(),
+        )))
+    }
+
+    /// [RouteFeatureFlags] service
+    pub async fn get_route_feature_flags(&mut self) -> Result<Arc<RouteFeatureFlags>> {
+        if self.route_feature_flags.is_none() {
+            self.route_feature_flags = Some(self.build_route_feature_flags().await?);
+        }
+
+        cloned_dependency(&self.route_feature_flags)
+    }
+
+    /// [LogLevelHandle] of the root logger, it must be injected by the caller that built the
+    /// root logger as it can not be connected to the logger afterward
+    pub async fn get_log_level_handle(&mut self) -> Result<LogLevelHandle> {
+        self.log_level_handle.clone().ok_or_else(|| {
+            DependenciesBuilderError::InconsistentState(
+                "The log level handle of the root logger has not been injected.".to_string(),
+            )
+        })
+    }
+
+    /// [RequestBodyValidator] service
+    pub async fn get_request_body_validator(&mut self) -> Result<Arc<RequestBodyValidator>> {
+        if self.request_body_validator.is_none() {
+            self.request_body_validator = Some(Arc::new(
+                RequestBodyValidator::from_aggregator_spec()
+                    .with_context(|| "Could not build the request body validator")?,
+            ));
+        }
+
+        cloned_dependency(&self.request_body_validator)
+    }
+
+    /// [SignatureReplayDetector] service
+    pub async fn get_signature_replay_detector(
+        &mut self,
+    ) -> Result<Arc<dyn SignatureReplayDetector>> {
+        if self.signature_replay_detector.is_none() {
+            self.signature_replay_detector = Some(Arc::new(MithrilSignatureReplayDetector::new()));
+        }
+
+        cloned_dependency(&self.signature_replay_detector)
     }
 
     /// Create a [UsageReporter] instance.
@@ -1406,6 +1991,116 @@ impl DependenciesBuilder {
         Ok(usage_reporter)
     }
 
+    /// Create a [MaintenanceScheduler] instance.
+    pub async fn create_maintenance_scheduler(
+        &mut self,
+        window: MaintenanceWindow,
+    ) -> Result<MaintenanceScheduler> {
+        Ok(MaintenanceScheduler::new(
+            window,
+            self.get_sqlite_connection().await?,
+            self.get_sqlite_connection_cardano_transaction_pool()
+                .await?,
+            self.get_event_store_sqlite_connection().await?,
+            self.get_signed_entity_lock().await?,
+            self.get_immutable_cache_provider().await?,
+            self.configuration.db_directory.clone(),
+            self.get_metrics_service().await?,
+            self.root_logger(),
+        ))
+    }
+
+    /// Create an [ArtifactAvailabilityVerifier] instance.
+    pub async fn create_artifact_availability_verifier(
+        &mut self,
+    ) -> Result<ArtifactAvailabilityVerifier> {
+        let location_checker = HttpArtifactLocationChecker::new(Duration::from_secs(10))?;
+
+        Ok(ArtifactAvailabilityVerifier::new(
+            self.get_signed_entity_storer().await?,
+            Arc::new(location_checker),
+            self.get_event_transmitter().await?,
+            self.get_metrics_service().await?,
+            self.configuration.artifact_location_demotion,
+            self.root_logger(),
+        ))
+    }
+
+    /// Create a [ChainSimulator] instance, only available outside of production.
+    pub async fn create_chain_simulator(&mut self) -> Result<ChainSimulator> {
+        if self.configuration.environment == ExecutionEnvironment::Production {
+            return Err(DependenciesBuilderError::Initialization {
+                message: "The chain simulator can not be used in a production environment"
+                    .to_string(),
+                error: None,
+            });
+        }
+
+        let chain = SimulatedChain::new(
+            self.get_fake_chain_observer().await?,
+            self.get_dumb_immutable_file_observer().await?,
+            &self.configuration.db_directory,
+            self.root_logger(),
+        );
+        let fixture = MithrilFixtureBuilder::default()
+            .with_signers(self.configuration.simulation_number_of_signers)
+            .with_protocol_parameters(self.configuration.protocol_parameters.clone())
+            .build();
+        let signers = SimulatedSigners::new(
+            fixture,
+            self.get_mithril_registerer().await?,
+            self.get_certifier_service().await?,
+            self.get_certificate_pending_store().await?,
+            self.root_logger(),
+        );
+        let genesis_dependencies = GenesisToolsDependency {
+            network: self.configuration.get_network()?,
+            ticker_service: self.get_ticker_service().await?,
+            certificate_repository: self.get_certificate_repository().await?,
+            certificate_verifier: self.get_certificate_verifier().await?,
+            genesis_verifier: self.get_genesis_verifier().await?,
+            epoch_settings_storer: self.get_epoch_settings_storer().await?,
+            verification_key_store: self.get_verification_key_store().await?,
+        };
+
+        Ok(ChainSimulator::new(
+            chain,
+            signers,
+            genesis_dependencies,
+            self.root_logger(),
+        ))
+    }
+
+    /// Create a [AlertingService] instance.
+    pub async fn create_alerting_service(&mut self, webhook_url: &str) -> Result<AlertingService> {
+        let notifier = WebhookAlertNotifier::new(
+            webhook_url,
+            self.configuration.alerting_webhook_format,
+            self.configuration
+                .alerting_pagerduty_routing_key
+                .as_ref()
+                .map(|key| key.expose_secret().to_string()),
+        )?;
+        let rules = AlertingRules {
+            no_certificate_threshold: Duration::from_secs(
+                self.configuration
+                    .alerting_no_certificate_threshold_in_minutes
+                    * 60,
+            ),
+            min_signer_participation_percent: self
+                .configuration
+                .alerting_min_signer_participation_percent,
+        };
+
+        Ok(AlertingService::new(
+            rules,
+            self.get_certificate_repository().await?,
+            self.get_epoch_service().await?,
+            Arc::new(notifier),
+            self.root_logger(),
+        ))
+    }
+
     /// Return an unconfigured [DependencyContainer]
     pub async fn build_dependency_container(&mut self) -> Result<DependencyContainer> {
         #[allow(deprecated)]
@@ -1453,6 +2148,19 @@ impl DependenciesBuilder {
             upkeep_service: self.get_upkeep_service().await?,
             single_signer_authenticator: self.get_single_signature_authenticator().await?,
             metrics_service: self.get_metrics_service().await?,
+            runtime_status_reporter: self.get_runtime_status_reporter().await?,
+            certification_watchdog: self.get_certification_watchdog().await?,
+            audit_logger: self.get_audit_logger().await?,
+            http_access_logger: self.get_http_access_logger().await?,
+            registration_challenge_service: self.get_registration_challenge_service().await?,
+            signer_address_filter: self.get_signer_address_filter().await?,
+            request_body_validator: self.get_request_body_validator().await?,
+            route_feature_flags: self.get_route_feature_flags().await?,
+            log_level_handle: self.get_log_level_handle().await?,
+            signature_replay_detector: self.get_signature_replay_detector().await?,
+            certificate_transparency_log_publisher: self
+                .get_certificate_transparency_log_publisher()
+                .await?,
         };
 
         Ok(dependency_manager)
@@ -1474,25 +2182,33 @@ impl DependenciesBuilder {
         let dependency_container = Arc::new(self.build_dependency_container().await?);
 
         let config = AggregatorConfig::new(Duration::from_millis(self.configuration.run_interval));
+        let immutable_file_watcher = Arc::new(ImmutableFileWatcher::new(
+            self.get_immutable_file_observer().await?,
+            Duration::from_millis(
+                self.configuration
+                    .immutable_file_watcher_poll_interval_in_ms,
+            ),
+            self.root_logger(),
+        ));
         let runtime = AggregatorRuntime::new(
             config,
             None,
             Arc::new(AggregatorRunner::new(dependency_container)),
+            self.get_runtime_status_reporter().await?,
             self.root_logger(),
         )
         .await
         .map_err(|e| DependenciesBuilderError::Initialization {
             message: "Cannot initialize Aggregator runtime.".to_string(),
             error: Some(e.into()),
-        })?;
+        })?
+        .with_immutable_file_watcher(immutable_file_watcher);
 
         Ok(runtime)
     }
 
     /// Create the HTTP route instance
-    pub async fn create_http_routes(
-        &mut self,
-    ) -> Result<impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone> {
+    pub async fn create_http_routes(&mut self) -> Result<axum::Router> {
         let dependency_container = Arc::new(self.build_dependency_container().await?);
         let router_state = RouterState::new(
             dependency_container.clone(),
@@ -1508,6 +2224,16 @@ impl DependenciesBuilder {
                     .cardano_transactions_signing_config
                     .clone(),
                 snapshot_directory: self.configuration.snapshot_directory.clone(),
+                snapshot_compression_algorithm: self.configuration.snapshot_compression_algorithm,
+                require_signer_client_certificate: self
+                    .configuration
+                    .signer_client_ca_certificate_path
+                    .is_some(),
+                admin_api_token: self
+                    .configuration
+                    .admin_api_token
+                    .as_ref()
+                    .map(|token| token.expose_secret().to_string()),
             },
         );
 
@@ -1593,7 +2319,7 @@ impl DependenciesBuilder {
             self.ticker_service = Some(self.build_ticker_service().await?);
         }
 
-        Ok(self.ticker_service.as_ref().cloned().unwrap())
+        cloned_dependency(&self.ticker_service)
     }
 
     /// Create [CertifierService] service
@@ -1610,6 +2336,7 @@ impl DependenciesBuilder {
         let genesis_verifier = self.get_genesis_verifier().await?;
         let multi_signer = self.get_multi_signer().await?;
         let epoch_service = self.get_epoch_service().await?;
+        let era_checker = self.get_era_checker().await?;
         let logger = self.root_logger();
 
         let certifier = Arc::new(MithrilCertifierService::new(
@@ -1621,6 +2348,7 @@ impl DependenciesBuilder {
             genesis_verifier,
             multi_signer,
             epoch_service,
+            era_checker,
             logger,
         ));
 
@@ -1637,7 +2365,7 @@ impl DependenciesBuilder {
             self.certifier_service = Some(self.build_certifier_service().await?);
         }
 
-        Ok(self.certifier_service.as_ref().cloned().unwrap())
+        cloned_dependency(&self.certifier_service)
     }
 
     /// build HTTP message service
@@ -1657,7 +2385,7 @@ impl DependenciesBuilder {
             self.message_service = Some(self.build_message_service().await?);
         }
 
-        Ok(self.message_service.as_ref().cloned().unwrap())
+        cloned_dependency(&self.message_service)
     }
 
     /// Build Prover service
@@ -1665,6 +2393,32 @@ impl DependenciesBuilder {
         let mk_map_pool_size = self
             .configuration
             .cardano_transactions_prover_cache_pool_size;
+        let proofs_thread_pool = ThreadPoolBuilder::new()
+            .num_threads(self.configuration.cardano_transactions_prover_max_threads)
+            .thread_name(|index| format!("mithril-prover-{index}"))
+            .build()
+            .map_err(|e| DependenciesBuilderError::Initialization {
+                message: "Cannot create the prover thread pool.".to_string(),
+                error: Some(e.into()),
+            })?;
+        let transactions_bloom_filter_enabled = self
+            .configuration
+            .enable_cardano_transactions_prover_bloom_filter;
+        let merkle_map_cache_store = match &self
+            .configuration
+            .cardano_transactions_prover_cache_directory
+        {
+            Some(directory) => {
+                let store = FileMerkleMapCacheStore::new(directory).map_err(|e| {
+                    DependenciesBuilderError::Initialization {
+                        message: "Cannot create the prover Merkle map cache store.".to_string(),
+                        error: Some(e),
+                    }
+                })?;
+                Some(Arc::new(store) as Arc<dyn MerkleMapCacheStorer>)
+            }
+            None => None,
+        };
         let transaction_retriever = self.get_transaction_repository().await?;
         let block_range_root_retriever = self.get_transaction_repository().await?;
         let logger = self.root_logger();
@@ -1672,6 +2426,9 @@ impl DependenciesBuilder {
             transaction_retriever,
             block_range_root_retriever,
             mk_map_pool_size,
+            proofs_thread_pool,
+            transactions_bloom_filter_enabled,
+            merkle_map_cache_store,
             logger,
         );
 
@@ -1684,7 +2441,7 @@ impl DependenciesBuilder {
             self.prover_service = Some(self.build_prover_service().await?);
         }
 
-        Ok(self.prover_service.as_ref().cloned().unwrap())
+        cloned_dependency(&self.prover_service)
     }
 
     /// Remove the dependencies builder from memory to release Arc instances.
@@ -1696,13 +2453,18 @@ impl DependenciesBuilder {
 #[cfg(test)]
 impl DependenciesBuilder {
     pub(crate) fn new_with_stdout_logger(configuration: Configuration) -> Self {
-        Self::new(crate::test_tools::TestLogger::stdout(), configuration)
+        let mut builder = Self::new(crate::test_tools::TestLogger::stdout(), configuration);
+        builder.log_level_handle = Some(LogLevelHandle::new(slog::Level::Debug));
+
+        builder
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use mithril_common::chain_observer::StakeDistributionSourceType;
     use mithril_common::entities::SignedEntityTypeDiscriminants;
+    use mithril_common::test_utils::TempDir;
 
     use super::*;
 
@@ -1743,4 +2505,134 @@ mod tests {
             expected_activation, is_activated
         );
     }
+
+    #[tokio::test]
+    async fn get_log_level_handle_fails_if_the_handle_of_the_root_logger_is_not_injected() {
+        let mut dep_builder = DependenciesBuilder::new(
+            crate::test_tools::TestLogger::stdout(),
+            Configuration::new_sample(),
+        );
+
+        dep_builder
+            .get_log_level_handle()
+            .await
+            .expect_err("An handle not connected to the root logger should not be created");
+    }
+
+    #[tokio::test]
+    async fn sqlite_connection_error_explains_which_store_failed() {
+        let temp_dir = TempDir::create(
+            "dependencies_builder",
+            "sqlite_connection_error_explains_which_store_failed",
+        );
+        let not_a_directory = temp_dir.join("not_a_directory");
+        std::fs::write(&not_a_directory, "").unwrap();
+        let configuration = Configuration {
+            data_stores_directory: not_a_directory.join("stores"),
+            ..Configuration::new_sample()
+        };
+        let mut dep_builder = DependenciesBuilder::new_with_stdout_logger(configuration);
+
+        let Err(error) = dep_builder.get_sqlite_connection().await else {
+            panic!("Building a SQLite connection in an invalid directory should fail");
+        };
+
+        assert!(
+            error.to_string().contains(SQLITE_FILE),
+            "error should mention the SQLite file '{SQLITE_FILE}': {error}"
+        );
+    }
+
+    #[test]
+    fn explain_dependencies_selected_by_the_configuration() {
+        let configuration = Configuration {
+            stake_distribution_source_type: StakeDistributionSourceType::File,
+            stake_distribution_source_location: Some("/stakes.json".to_string()),
+            audit_log_path: Some(PathBuf::from("/audit.log")),
+            alerting_webhook_url: Some("https://secret.webhook".into()),
+            ..Configuration::new_sample()
+        };
+        let dep_builder = DependenciesBuilder::new_with_stdout_logger(configuration);
+
+        let explanation = dep_builder.explain_dependencies();
+
+        assert_eq!(
+            Some(&DependencyExplanation::new("main database", "sqlite").with_resource(":memory:")),
+            explanation.get("main database")
+        );
+        assert_eq!(
+            Some(&DependencyExplanation::new("chain observer", "fake")),
+            explanation.get("chain observer")
+        );
+        assert_eq!(
+            Some(
+                &DependencyExplanation::new("stake distribution source", "file")
+                    .with_resource("/stakes.json")
+            ),
+            explanation.get("stake distribution source")
+        );
+        assert_eq!(
+            Some(&DependencyExplanation::new("audit log", "file").with_resource("/audit.log")),
+            explanation.get("audit log")
+        );
+        assert!(!explanation.to_string().contains("secret.webhook"));
+        assert_eq!(
+            Some(&DependencyExplanation::new("snapshot uploader", "dumb")),
+            explanation.get("snapshot uploader")
+        );
+        assert_eq!(
+            Some(&DependencyExplanation::new("era reader adapter", "dummy")),
+            explanation.get("era reader adapter")
+        );
+    }
+
+    #[test]
+    fn explain_the_dependencies_built_in_production() {
+        let configuration = Configuration {
+            environment: ExecutionEnvironment::Production,
+            data_stores_directory: PathBuf::from("/stores"),
+            chain_observer_type: ChainObserverType::Pallas,
+            cardano_node_socket_path: PathBuf::from("/node.socket"),
+            snapshot_uploader_type: SnapshotUploaderType::Gcp,
+            snapshot_bucket_name: Some("snapshots-bucket".to_string()),
+            era_reader_adapter_type: EraReaderAdapterType::Bootstrap,
+            ..Configuration::new_sample()
+        };
+        let dep_builder = DependenciesBuilder::new_with_stdout_logger(configuration);
+
+        let explanation = dep_builder.explain_dependencies();
+
+        assert_eq!(
+            Some(
+                &DependencyExplanation::new("main database", "sqlite").with_resource(
+                    PathBuf::from("/stores")
+                        .join(SQLITE_FILE)
+                        .display()
+                        .to_string()
+                )
+            ),
+            explanation.get("main database")
+        );
+        assert_eq!(
+            Some(
+                &DependencyExplanation::new("chain observer", "pallas")
+                    .with_resource("/node.socket")
+            ),
+            explanation.get("chain observer")
+        );
+        assert_eq!(
+            Some(
+                &DependencyExplanation::new("snapshot uploader", "gcp")
+                    .with_resource("snapshots-bucket")
+            ),
+            explanation.get("snapshot uploader")
+        );
+        assert_eq!(
+            Some(&DependencyExplanation::new(
+                "era reader adapter",
+                "bootstrap"
+            )),
+            explanation.get("era reader adapter")
+        );
+    }
 }
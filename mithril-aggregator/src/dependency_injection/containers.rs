@@ -14,6 +14,7 @@ use mithril_common::{
         StakeDistribution,
     },
     era::{EraChecker, EraReader},
+    logging::LogLevelHandle,
     signable_builder::SignableBuilderService,
     signed_entity_type_lock::SignedEntityTypeLock,
     test_utils::MithrilFixture,
@@ -32,15 +33,18 @@ use crate::{
     },
     entities::AggregatorEpochSettings,
     event_store::{EventMessage, TransmitterService},
+    http_server::{HttpAccessLogger, RequestBodyValidator, RouteFeatureFlags, SignerAddressFilter},
     multi_signer::MultiSigner,
     services::{
-        CertifierService, EpochService, MessageService, ProverService, SignedEntityService,
-        StakeDistributionService, TransactionStore, UpkeepService,
+        AuditLogger, CertificateTransparencyLogPublisher, CertificationWatchdog, CertifierService,
+        EpochService, MessageService, ProverService, RegistrationChallengeService,
+        SignatureReplayDetector, SignedEntityService, StakeDistributionService, TransactionStore,
+        UpkeepService,
     },
     signer_registerer::SignerRecorder,
     snapshot_uploaders::SnapshotUploader,
-    CertificatePendingStore, EpochSettingsStorer, MetricsService, SignerRegisterer,
-    SignerRegistrationRoundOpener, SingleSignatureAuthenticator, Snapshotter,
+    CertificatePendingStore, EpochSettingsStorer, MetricsService, RuntimeStatusReporter,
+    SignerRegisterer, SignerRegistrationRoundOpener, SingleSignatureAuthenticator, Snapshotter,
     VerificationKeyStorer,
 };
 
@@ -177,6 +181,39 @@ pub struct DependencyContainer {
 
     /// Metrics service
     pub metrics_service: Arc<MetricsService>,
+
+    /// Runtime status reporter
+    pub runtime_status_reporter: Arc<RuntimeStatusReporter>,
+
+    /// Certification watchdog
+    pub certification_watchdog: Arc<CertificationWatchdog>,
+
+    /// Audit logger
+    pub audit_logger: Arc<dyn AuditLogger>,
+
+    /// HTTP access logger
+    pub http_access_logger: Arc<HttpAccessLogger>,
+
+    /// Signer registration challenge service
+    pub registration_challenge_service: Arc<dyn RegistrationChallengeService>,
+
+    /// Signer address filter
+    pub signer_address_filter: Arc<SignerAddressFilter>,
+
+    /// Request body validator
+    pub request_body_validator: Arc<RequestBodyValidator>,
+
+    /// Route feature flags
+    pub route_feature_flags: Arc<RouteFeatureFlags>,
+
+    /// Handle to change the level of the root logger at runtime
+    pub log_level_handle: LogLevelHandle,
+
+    /// Signature replay detector
+    pub signature_replay_detector: Arc<dyn SignatureReplayDetector>,
+
+    /// Certificate transparency log publisher
+    pub certificate_transparency_log_publisher: Arc<dyn CertificateTransparencyLogPublisher>,
 }
 
 #[doc(hidden)]
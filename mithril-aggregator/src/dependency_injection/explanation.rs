@@ -0,0 +1,74 @@
+use std::fmt::{Display, Formatter};
+
+/// Implementation chosen for a configurable dependency and the resource it uses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyExplanation {
+    /// Name of the dependency
+    pub name: &'static str,
+
+    /// Implementation selected by the configuration
+    pub implementation: String,
+
+    /// Resource used by the implementation (path, URL, ...), if any
+    pub resource: Option<String>,
+}
+
+impl DependencyExplanation {
+    /// [DependencyExplanation] factory
+    pub fn new<T: Into<String>>(name: &'static str, implementation: T) -> Self {
+        Self {
+            name,
+            implementation: implementation.into(),
+            resource: None,
+        }
+    }
+
+    /// Set the resource used by the dependency.
+    pub fn with_resource<T: Into<String>>(mut self, resource: T) -> Self {
+        self.resource = Some(resource.into());
+        self
+    }
+}
+
+/// Report of the configurable dependencies of the aggregator, computed from the configuration
+/// only so that it is available even if a dependency can't be built.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependenciesExplanation {
+    /// Explained dependencies
+    pub dependencies: Vec<DependencyExplanation>,
+}
+
+impl DependenciesExplanation {
+    /// Return the explanation of the dependency with the given name.
+    pub fn get(&self, name: &str) -> Option<&DependencyExplanation> {
+        self.dependencies
+            .iter()
+            .find(|dependency| dependency.name == name)
+    }
+}
+
+impl Display for DependenciesExplanation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name_width = self
+            .dependencies
+            .iter()
+            .map(|dependency| dependency.name.len())
+            .max()
+            .unwrap_or_default();
+
+        writeln!(f, "Aggregator dependencies:")?;
+        for dependency in &self.dependencies {
+            write!(
+                f,
+                "  {:name_width$}  {}",
+                dependency.name, dependency.implementation
+            )?;
+            if let Some(resource) = &dependency.resource {
+                write!(f, " ({resource})")?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
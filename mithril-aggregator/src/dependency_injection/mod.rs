@@ -14,7 +14,9 @@
 mod builder;
 mod containers;
 mod error;
+mod explanation;
 
 pub use builder::*;
 pub use containers::*;
 pub use error::*;
+pub use explanation::*;
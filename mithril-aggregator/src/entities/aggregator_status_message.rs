@@ -0,0 +1,46 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use mithril_common::entities::{Epoch, ImmutableFileNumber};
+
+/// Message structure of the last error encountered by the aggregator runtime
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AggregatorRuntimeErrorMessage {
+    /// Error message
+    pub message: String,
+
+    /// True if the error aborted the runtime
+    pub is_critical: bool,
+
+    /// Date at which the error occurred
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// Message structure of the aggregator runtime status
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AggregatorStatusMessage {
+    /// Current state of the runtime state machine
+    pub runtime_state: String,
+
+    /// Epoch of the current state, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub epoch: Option<Epoch>,
+
+    /// Immutable file number of the current state, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub immutable_file_number: Option<ImmutableFileNumber>,
+
+    /// Date of the last state transition
+    pub last_transition_at: DateTime<Utc>,
+
+    /// Number of seconds spent in the current state
+    pub seconds_in_current_state: i64,
+
+    /// Date of the last completed cycle
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_cycle_at: Option<DateTime<Utc>>,
+
+    /// Last error encountered by the runtime
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<AggregatorRuntimeErrorMessage>,
+}
@@ -3,11 +3,13 @@
 //! This module provide domain entities for the services & state machine.
 //!
 mod aggregator_epoch_settings;
+mod aggregator_status_message;
 mod open_message;
 mod signer_registration_message;
 mod signer_ticker_message;
 
 pub use aggregator_epoch_settings::AggregatorEpochSettings;
+pub use aggregator_status_message::{AggregatorRuntimeErrorMessage, AggregatorStatusMessage};
 pub use open_message::OpenMessage;
 pub use signer_registration_message::{
     SignerRegistrationsListItemMessage, SignerRegistrationsMessage,
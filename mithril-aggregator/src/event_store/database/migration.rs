@@ -82,5 +82,51 @@ group by epoch, version
 order by epoch desc, version desc;
             "#,
         ),
+        SqlMigration::new(
+            4,
+            r#"
+create view artifact_download_summary as with
+  downloads as (
+    select
+      'CardanoImmutableFilesFull' as artifact_type,
+      json_extract(content, '$.content.digest') as artifact_id,
+      created_at
+    from event
+    where source='HTTP::statistics' and action='snapshot_downloaded'
+    union all
+    select
+      json_extract(content, '$.content.artifact_type') as artifact_type,
+      json_extract(content, '$.content.artifact_id') as artifact_id,
+      created_at
+    from event
+    where source='HTTP::statistics' and action='artifact_downloaded'
+  )
+select
+  artifact_type,
+  artifact_id,
+  count(*) as downloads,
+  min(created_at) as first_downloaded_at,
+  max(created_at) as last_downloaded_at
+from downloads
+group by artifact_type, artifact_id
+order by last_downloaded_at desc;
+            "#,
+        ),
+        SqlMigration::new(
+            5,
+            r#"
+create view download_telemetry_summary as
+select
+  json_extract(content, '$.content.artifact_type') as artifact_type,
+  count(*) as downloads,
+  sum(json_extract(content, '$.content.succeeded')) as succeeded,
+  printf('%.2f %%', sum(json_extract(content, '$.content.succeeded')) * 100.0 / count(*)) as success_rate,
+  cast(avg(json_extract(content, '$.content.duration_ms')) / 1000 as integer) as average_duration_seconds
+from event
+where source='HTTP::statistics' and action='download_telemetry_reported'
+group by artifact_type
+order by artifact_type;
+            "#,
+        ),
     ]
 }
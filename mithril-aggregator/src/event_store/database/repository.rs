@@ -309,4 +309,230 @@ mod tests {
             assert!(result.len() == 1);
         }
     }
+
+    mod artifact_download_summary {
+        use std::sync::Arc;
+
+        use crate::event_store::database::test_helper::event_store_db_connection;
+        use mithril_common::entities::SignedEntityTypeDiscriminants;
+        use mithril_common::messages::{ArtifactDownloadMessage, SnapshotDownloadMessage};
+        use mithril_common::StdResult;
+        use sqlite::ConnectionThreadSafe;
+
+        use super::{EventMessage, EventPersister};
+
+        fn get_all_downloads(
+            connection: Arc<ConnectionThreadSafe>,
+        ) -> StdResult<Vec<(String, String, i64)>> {
+            let query =
+                "select artifact_type, artifact_id, downloads from artifact_download_summary";
+            let mut statement = connection.prepare(query)?;
+            let mut result = Vec::new();
+            while let Ok(sqlite::State::Row) = statement.next() {
+                result.push((
+                    statement.read::<String, _>("artifact_type")?,
+                    statement.read::<String, _>("artifact_id")?,
+                    statement.read::<i64, _>("downloads")?,
+                ));
+            }
+
+            Ok(result)
+        }
+
+        fn insert_artifact_download_event(
+            persister: &EventPersister,
+            artifact_type: SignedEntityTypeDiscriminants,
+            artifact_id: &str,
+        ) {
+            let message = EventMessage::new(
+                "HTTP::statistics",
+                "artifact_downloaded",
+                &ArtifactDownloadMessage::new(artifact_type, artifact_id),
+                Vec::new(),
+            );
+
+            let _event = persister.persist(message).unwrap();
+        }
+
+        fn insert_snapshot_download_event(persister: &EventPersister, digest: &str) {
+            let message = EventMessage::new(
+                "HTTP::statistics",
+                "snapshot_downloaded",
+                &SnapshotDownloadMessage {
+                    digest: digest.to_string(),
+                    ..SnapshotDownloadMessage::dummy()
+                },
+                Vec::new(),
+            );
+
+            let _event = persister.persist(message).unwrap();
+        }
+
+        #[test]
+        fn count_downloads_per_artifact() {
+            let connection = Arc::new(event_store_db_connection().unwrap());
+            let persister = EventPersister::new(connection.clone());
+
+            insert_artifact_download_event(
+                &persister,
+                SignedEntityTypeDiscriminants::CardanoStakeDistribution,
+                "csd-1",
+            );
+            insert_artifact_download_event(
+                &persister,
+                SignedEntityTypeDiscriminants::CardanoStakeDistribution,
+                "csd-1",
+            );
+            insert_artifact_download_event(
+                &persister,
+                SignedEntityTypeDiscriminants::MithrilStakeDistribution,
+                "msd-1",
+            );
+
+            let result = get_all_downloads(connection).unwrap();
+
+            assert_eq!(2, result.len());
+            assert!(result.contains(&(
+                "CardanoStakeDistribution".to_string(),
+                "csd-1".to_string(),
+                2
+            )));
+            assert!(result.contains(&(
+                "MithrilStakeDistribution".to_string(),
+                "msd-1".to_string(),
+                1
+            )));
+        }
+
+        #[test]
+        fn include_legacy_snapshot_download_events() {
+            let connection = Arc::new(event_store_db_connection().unwrap());
+            let persister = EventPersister::new(connection.clone());
+
+            insert_snapshot_download_event(&persister, "digest-1");
+            insert_artifact_download_event(
+                &persister,
+                SignedEntityTypeDiscriminants::CardanoImmutableFilesFull,
+                "digest-1",
+            );
+
+            let result = get_all_downloads(connection).unwrap();
+
+            assert_eq!(
+                vec![(
+                    "CardanoImmutableFilesFull".to_string(),
+                    "digest-1".to_string(),
+                    2
+                )],
+                result
+            );
+        }
+    }
+
+    mod download_telemetry_summary {
+        use std::sync::Arc;
+
+        use crate::event_store::database::test_helper::event_store_db_connection;
+        use mithril_common::entities::SignedEntityTypeDiscriminants;
+        use mithril_common::messages::DownloadTelemetryMessage;
+        use mithril_common::StdResult;
+        use sqlite::ConnectionThreadSafe;
+
+        use super::{EventMessage, EventPersister};
+
+        /// Artifact type, downloads, succeeded downloads, success rate and average duration
+        type SummaryRow = (String, i64, i64, String, i64);
+
+        fn get_all_summaries(connection: Arc<ConnectionThreadSafe>) -> StdResult<Vec<SummaryRow>> {
+            let query = "select artifact_type, downloads, succeeded, success_rate, average_duration_seconds from download_telemetry_summary";
+            let mut statement = connection.prepare(query)?;
+            let mut result = Vec::new();
+            while let Ok(sqlite::State::Row) = statement.next() {
+                result.push((
+                    statement.read::<String, _>("artifact_type")?,
+                    statement.read::<i64, _>("downloads")?,
+                    statement.read::<i64, _>("succeeded")?,
+                    statement.read::<String, _>("success_rate")?,
+                    statement.read::<i64, _>("average_duration_seconds")?,
+                ));
+            }
+
+            Ok(result)
+        }
+
+        fn insert_download_telemetry_event(
+            persister: &EventPersister,
+            artifact_type: SignedEntityTypeDiscriminants,
+            succeeded: bool,
+            duration_ms: u64,
+        ) {
+            let message = EventMessage::new(
+                "HTTP::statistics",
+                "download_telemetry_reported",
+                &DownloadTelemetryMessage::new(artifact_type, succeeded, duration_ms),
+                Vec::new(),
+            );
+
+            let _event = persister.persist(message).unwrap();
+        }
+
+        #[test]
+        fn compute_success_rate_and_average_duration_per_artifact_type() {
+            let connection = Arc::new(event_store_db_connection().unwrap());
+            let persister = EventPersister::new(connection.clone());
+
+            insert_download_telemetry_event(
+                &persister,
+                SignedEntityTypeDiscriminants::CardanoImmutableFilesFull,
+                true,
+                100_000,
+            );
+            insert_download_telemetry_event(
+                &persister,
+                SignedEntityTypeDiscriminants::CardanoImmutableFilesFull,
+                true,
+                200_000,
+            );
+            insert_download_telemetry_event(
+                &persister,
+                SignedEntityTypeDiscriminants::CardanoImmutableFilesFull,
+                true,
+                300_000,
+            );
+            insert_download_telemetry_event(
+                &persister,
+                SignedEntityTypeDiscriminants::CardanoImmutableFilesFull,
+                false,
+                400_000,
+            );
+            insert_download_telemetry_event(
+                &persister,
+                SignedEntityTypeDiscriminants::MithrilStakeDistribution,
+                false,
+                1_000,
+            );
+
+            let result = get_all_summaries(connection).unwrap();
+
+            assert_eq!(
+                vec![
+                    (
+                        "CardanoImmutableFilesFull".to_string(),
+                        4,
+                        3,
+                        "75.00 %".to_string(),
+                        250
+                    ),
+                    (
+                        "MithrilStakeDistribution".to_string(),
+                        1,
+                        0,
+                        "0.00 %".to_string(),
+                        1
+                    ),
+                ],
+                result
+            );
+        }
+    }
 }
@@ -0,0 +1,225 @@
+//! ## HTTP access log
+//!
+//! Record each HTTP request served by the aggregator in a dedicated file, separated from the
+//! application logs, to allow traffic analysis.
+
+use anyhow::Context;
+use axum::extract::{ConnectInfo, Request};
+use axum::http::header;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use slog::{warn, Logger};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::Mutex;
+
+use mithril_common::logging::{CorrelationId, LoggerExtensions};
+use mithril_common::{StdResult, MITHRIL_ORIGIN_TAG_HEADER};
+
+/// Format of the entries of the HTTP access log.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HttpAccessLogFormat {
+    /// One JSON object per line.
+    Json,
+    /// Common Log Format followed by the latency in milliseconds and the origin tag.
+    Common,
+}
+
+/// Data about a served HTTP request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpAccessLogEntry {
+    /// IP address of the client.
+    pub remote_ip: Option<String>,
+    /// HTTP method.
+    pub method: String,
+    /// Requested path.
+    pub path: String,
+    /// Template of the matched route, `None` if the request did not match any route.
+    pub route: Option<String>,
+    /// HTTP version.
+    pub version: String,
+    /// Status code of the response.
+    pub status: u16,
+    /// Time spent to serve the request, in milliseconds.
+    pub latency_ms: u64,
+    /// User agent of the client.
+    pub user_agent: Option<String>,
+    /// Tag sent by the client to identify its origin.
+    pub origin_tag: Option<String>,
+    /// Correlation id shared with the application logs of the request.
+    pub correlation_id: Option<String>,
+}
+
+impl HttpAccessLogEntry {
+    /// Extract the entry from a received request, its status and latency are set once the
+    /// request is served.
+    pub fn from_request(request: &Request) -> Self {
+        let header_value = |name: &str| {
+            request
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(|value| value.to_string())
+        };
+
+        Self {
+            remote_ip: request
+                .extensions()
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|ConnectInfo(address)| address.ip().to_string()),
+            method: request.method().to_string(),
+            path: request.uri().path().to_string(),
+            route: None,
+            version: format!("{:?}", request.version()),
+            status: 0,
+            latency_ms: 0,
+            user_agent: header_value(header::USER_AGENT.as_str()),
+            origin_tag: header_value(MITHRIL_ORIGIN_TAG_HEADER),
+            correlation_id: CorrelationId::current().map(|id| id.to_string()),
+        }
+    }
+
+    fn format(&self, format: HttpAccessLogFormat) -> String {
+        let now = Utc::now();
+        match format {
+            HttpAccessLogFormat::Json => json!({
+                "timestamp": now,
+                "remote_ip": self.remote_ip,
+                "method": self.method,
+                "path": self.path,
+                "route": self.route,
+                "status": self.status,
+                "latency_ms": self.latency_ms,
+                "user_agent": self.user_agent,
+                "origin_tag": self.origin_tag,
+                "correlation_id": self.correlation_id,
+            })
+            .to_string(),
+            HttpAccessLogFormat::Common => format!(
+                r#"{} - - [{}] "{} {} {}" {} - {} "{}""#,
+                self.remote_ip.as_deref().unwrap_or("-"),
+                now.format("%d/%b/%Y:%H:%M:%S %z"),
+                self.method,
+                self.path,
+                self.version,
+                self.status,
+                self.latency_ms,
+                self.origin_tag.as_deref().unwrap_or("-"),
+            ),
+        }
+    }
+}
+
+/// Write the HTTP access log entries to a file.
+pub struct HttpAccessLogger {
+    format: HttpAccessLogFormat,
+    file: Option<Mutex<File>>,
+    logger: Logger,
+}
+
+impl HttpAccessLogger {
+    /// Open, or create, the access log file at the given path.
+    pub fn new(path: &Path, format: HttpAccessLogFormat, logger: Logger) -> StdResult<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| {
+                format!("Could not open HTTP access log file: '{}'", path.display())
+            })?;
+
+        Ok(Self {
+            format,
+            file: Some(Mutex::new(file)),
+            logger: logger.new_with_component_name::<Self>(),
+        })
+    }
+
+    /// Create a logger that discards the entries.
+    pub fn disabled(logger: Logger) -> Self {
+        Self {
+            format: HttpAccessLogFormat::Json,
+            file: None,
+            logger: logger.new_with_component_name::<Self>(),
+        }
+    }
+
+    /// Record an entry in the access log.
+    pub fn log(&self, entry: &HttpAccessLogEntry) {
+        let Some(file) = &self.file else {
+            return;
+        };
+
+        let line = format!("{}\n", entry.format(self.format));
+        let mut file = file.lock().unwrap();
+        if let Err(error) = file.write_all(line.as_bytes()) {
+            warn!(self.logger, "Could not write HTTP access log entry"; "error" => ?error);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mithril_common::test_utils::TempDir;
+
+    use crate::test_tools::TestLogger;
+
+    use super::*;
+
+    fn entry(path: &str) -> HttpAccessLogEntry {
+        HttpAccessLogEntry {
+            remote_ip: Some("127.0.0.1".to_string()),
+            method: "GET".to_string(),
+            path: path.to_string(),
+            route: Some(path.to_string()),
+            version: "HTTP/1.1".to_string(),
+            status: 200,
+            latency_ms: 12,
+            user_agent: None,
+            origin_tag: Some("EXPLORER".to_string()),
+            correlation_id: Some("0123456789abcdef".to_string()),
+        }
+    }
+
+    #[test]
+    fn json_format_contains_route_latency_and_origin_tag() {
+        let line = entry("/aggregator/epoch-settings").format(HttpAccessLogFormat::Json);
+        let json: serde_json::Value = serde_json::from_str(&line).unwrap();
+
+        assert_eq!("/aggregator/epoch-settings", json["route"]);
+        assert_eq!(200, json["status"]);
+        assert_eq!(12, json["latency_ms"]);
+        assert_eq!("EXPLORER", json["origin_tag"]);
+        assert_eq!("0123456789abcdef", json["correlation_id"]);
+    }
+
+    #[test]
+    fn common_format_follows_common_log_format() {
+        let line = entry("/aggregator/epoch-settings").format(HttpAccessLogFormat::Common);
+
+        assert!(line.starts_with("127.0.0.1 - - ["), "{line}");
+        assert!(
+            line.ends_with(r#""GET /aggregator/epoch-settings HTTP/1.1" 200 - 12 "EXPLORER""#),
+            "{line}"
+        );
+    }
+
+    #[test]
+    fn logger_appends_entries_to_file() {
+        let path =
+            TempDir::create("http_access_log", "logger_appends_entries_to_file").join("access.log");
+        let access_logger =
+            HttpAccessLogger::new(&path, HttpAccessLogFormat::Common, TestLogger::stdout())
+                .unwrap();
+
+        access_logger.log(&entry("/aggregator"));
+        access_logger.log(&entry("/aggregator/certificates"));
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(2, content.lines().count());
+    }
+}
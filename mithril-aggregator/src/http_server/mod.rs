@@ -1,4 +1,15 @@
+mod access_log;
+mod request_body_validator;
+mod route_feature_flags;
 pub mod routes;
+mod signer_address_filter;
+mod tls;
 pub mod validators;
 
+pub use access_log::*;
+pub use request_body_validator::*;
+pub use route_feature_flags::*;
+pub use signer_address_filter::*;
+pub use tls::*;
+
 pub const SERVER_BASE_PATH: &str = "aggregator";
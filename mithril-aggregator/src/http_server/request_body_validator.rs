@@ -0,0 +1,155 @@
+//! ## Request body validator
+//!
+//! Validate the JSON bodies received by the aggregator against the schemas of its OpenAPI
+//! specification, so that the malformed payloads are rejected before reaching the services.
+
+use anyhow::{anyhow, Context};
+use jsonschema::Validator;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+use mithril_common::StdResult;
+
+/// OpenAPI specification of the aggregator, copied by the build script.
+const OPENAPI_SPEC: &str = include_str!(concat!(env!("OUT_DIR"), "/openapi.yaml"));
+
+/// Violation of the schema of a request body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestBodyViolation {
+    /// JSON pointer to the invalid value in the request body.
+    pub path: String,
+
+    /// Description of the violation.
+    pub message: String,
+}
+
+impl Display for RequestBodyViolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.path, self.message)
+        }
+    }
+}
+
+/// Validate the request bodies of the POST operations of an OpenAPI specification.
+pub struct RequestBodyValidator {
+    validators: HashMap<String, Validator>,
+}
+
+impl RequestBodyValidator {
+    /// Create a validator for the OpenAPI specification of the aggregator
+    pub fn from_aggregator_spec() -> StdResult<Self> {
+        Self::from_openapi_spec(OPENAPI_SPEC)
+    }
+
+    /// Create a validator for the given YAML encoded OpenAPI specification
+    pub fn from_openapi_spec(yaml_spec: &str) -> StdResult<Self> {
+        let openapi: Value =
+            serde_yaml::from_str(yaml_spec).with_context(|| "Could not parse the OpenAPI spec")?;
+        let paths = openapi["paths"]
+            .as_object()
+            .ok_or(anyhow!("The OpenAPI spec has no paths"))?;
+
+        let mut validators = HashMap::new();
+        for (path, operations) in paths {
+            let Some(schema) = operations["post"]["requestBody"]["content"]["application/json"]
+                ["schema"]
+                .as_object()
+            else {
+                continue;
+            };
+            let mut schema = schema.clone();
+            schema.insert("components".to_string(), openapi["components"].clone());
+            let validator = Validator::new(&Value::Object(schema)).map_err(|e| {
+                anyhow!("{e}").context(format!(
+                    "Invalid request body schema for 'POST {path}' in the OpenAPI spec"
+                ))
+            })?;
+            validators.insert(path.to_owned(), validator);
+        }
+
+        Ok(Self { validators })
+    }
+
+    /// Validate the body of a POST request on the given path of the specification, the bodies
+    /// of the paths that have no request body schema are not validated
+    pub fn validate(&self, path: &str, body: &Value) -> Result<(), Vec<RequestBodyViolation>> {
+        let Some(validator) = self.validators.get(path) else {
+            return Ok(());
+        };
+        let violations: Vec<RequestBodyViolation> = validator
+            .iter_errors(body)
+            .map(|error| RequestBodyViolation {
+                path: error.instance_path.to_string(),
+                message: error.to_string(),
+            })
+            .collect();
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use mithril_common::messages::{RegisterSignatureMessage, RegisterSignerMessage};
+
+    use super::*;
+
+    #[test]
+    fn accept_the_bodies_matching_the_schema_of_the_route() {
+        let validator = RequestBodyValidator::from_aggregator_spec().unwrap();
+
+        validator
+            .validate("/register-signer", &json!(RegisterSignerMessage::dummy()))
+            .unwrap();
+        validator
+            .validate(
+                "/register-signatures",
+                &json!(RegisterSignatureMessage::dummy()),
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn list_the_violations_of_the_bodies_not_matching_the_schema_of_the_route() {
+        let validator = RequestBodyValidator::from_aggregator_spec().unwrap();
+        let mut body = json!(RegisterSignerMessage::dummy());
+        body.as_object_mut().unwrap().remove("party_id");
+        body["epoch"] = json!("not an epoch");
+
+        let violations = validator
+            .validate("/register-signer", &body)
+            .expect_err("A body not matching the schema should be rejected");
+
+        assert!(
+            violations
+                .iter()
+                .any(|violation| violation.path == "/epoch"),
+            "violations: {violations:?}"
+        );
+        assert!(
+            violations
+                .iter()
+                .any(|violation| violation.message.contains("party_id")),
+            "violations: {violations:?}"
+        );
+    }
+
+    #[test]
+    fn do_not_validate_the_bodies_of_the_routes_without_request_body_schema() {
+        let validator = RequestBodyValidator::from_aggregator_spec().unwrap();
+
+        validator
+            .validate("/unknown-route", &json!({ "any": "thing" }))
+            .unwrap();
+    }
+}
@@ -0,0 +1,175 @@
+//! ## Route feature flags
+//!
+//! Disable groups of routes of the aggregator, at startup with the `disabled_route_groups`
+//! configuration or at runtime with the admin API, so that read-only replicas and maintenance
+//! windows don't need a dedicated build. The routes of a disabled group reply with a
+//! `503 Service Unavailable`.
+
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+use slog::{info, Logger};
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::sync::RwLock;
+
+use mithril_common::logging::LoggerExtensions;
+use mithril_common::StdResult;
+
+/// Group of routes that can be disabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RouteGroup {
+    /// Generation of the Cardano transactions proofs.
+    Proofs,
+    /// Registration of the signers, and their registration challenges.
+    SignerRegistration,
+    /// Registration of the single signatures.
+    Signatures,
+    /// Collection of the client statistics.
+    Statistics,
+}
+
+impl RouteGroup {
+    /// All the route groups.
+    pub const ALL: [RouteGroup; 4] = [
+        Self::Proofs,
+        Self::SignerRegistration,
+        Self::Signatures,
+        Self::Statistics,
+    ];
+
+    /// Name of the group, as used in the configuration and the admin API.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Proofs => "proofs",
+            Self::SignerRegistration => "signer_registration",
+            Self::Signatures => "signatures",
+            Self::Statistics => "statistics",
+        }
+    }
+
+    /// Parse a comma separated list of route groups names.
+    pub fn parse_list<T: AsRef<str>>(groups_string: T) -> StdResult<BTreeSet<Self>> {
+        groups_string
+            .as_ref()
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(Self::from_str)
+            .collect()
+    }
+}
+
+impl Display for RouteGroup {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for RouteGroup {
+    type Err = anyhow::Error;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|group| group.name() == name)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Unknown route group '{name}', expected one of: {}",
+                    Self::ALL.map(|group| group.name()).join(", ")
+                )
+            })
+    }
+}
+
+/// Enabled state of the route groups, shared by the routes and the admin API.
+pub struct RouteFeatureFlags {
+    disabled_groups: RwLock<BTreeSet<RouteGroup>>,
+    logger: Logger,
+}
+
+impl RouteFeatureFlags {
+    /// Create the flags with the given groups disabled.
+    pub fn new(disabled_groups: BTreeSet<RouteGroup>, logger: Logger) -> Self {
+        let logger = logger.new_with_component_name::<Self>();
+        if !disabled_groups.is_empty() {
+            info!(logger, "Route groups disabled at startup"; "route_groups" => ?disabled_groups);
+        }
+
+        Self {
+            disabled_groups: RwLock::new(disabled_groups),
+            logger,
+        }
+    }
+
+    /// Check if the routes of the given group are enabled.
+    pub fn is_enabled(&self, group: RouteGroup) -> bool {
+        !self.disabled_groups.read().unwrap().contains(&group)
+    }
+
+    /// Enable or disable the routes of the given group.
+    pub fn set_enabled(&self, group: RouteGroup, enabled: bool) {
+        let mut disabled_groups = self.disabled_groups.write().unwrap();
+        let changed = if enabled {
+            disabled_groups.remove(&group)
+        } else {
+            disabled_groups.insert(group)
+        };
+
+        if changed {
+            info!(self.logger, "Route group {}", if enabled { "enabled" } else { "disabled" }; "route_group" => %group);
+        }
+    }
+
+    /// The currently disabled groups.
+    pub fn disabled_groups(&self) -> BTreeSet<RouteGroup> {
+        self.disabled_groups.read().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_tools::TestLogger;
+
+    use super::*;
+
+    #[test]
+    fn parse_route_groups_list() {
+        assert_eq!(BTreeSet::new(), RouteGroup::parse_list(" ").unwrap());
+        assert_eq!(
+            BTreeSet::from([RouteGroup::Proofs, RouteGroup::SignerRegistration]),
+            RouteGroup::parse_list("proofs, signer_registration").unwrap()
+        );
+        RouteGroup::parse_list("proofs,unknown")
+            .expect_err("Parsing an unknown route group should fail");
+    }
+
+    #[test]
+    fn route_group_name_matches_its_serialization() {
+        for group in RouteGroup::ALL {
+            assert_eq!(
+                format!("\"{}\"", group.name()),
+                serde_json::to_string(&group).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn enable_and_disable_route_groups() {
+        let flags =
+            RouteFeatureFlags::new(BTreeSet::from([RouteGroup::Proofs]), TestLogger::stdout());
+        assert!(!flags.is_enabled(RouteGroup::Proofs));
+        assert!(flags.is_enabled(RouteGroup::Signatures));
+
+        flags.set_enabled(RouteGroup::Proofs, true);
+        flags.set_enabled(RouteGroup::Signatures, false);
+
+        assert!(flags.is_enabled(RouteGroup::Proofs));
+        assert!(!flags.is_enabled(RouteGroup::Signatures));
+        assert_eq!(
+            BTreeSet::from([RouteGroup::Signatures]),
+            flags.disabled_groups()
+        );
+    }
+}
@@ -0,0 +1,351 @@
+use axum::extract::State;
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::http_server::routes::middlewares::{self, RemoteAddress};
+use crate::http_server::routes::router::RouterState;
+use crate::http_server::RouteGroup;
+
+/// Enabled state of a group of routes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RouteGroupStatusMessage {
+    pub route_group: RouteGroup,
+    pub enabled: bool,
+}
+
+/// Minimum level of the aggregator logs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogLevelMessage {
+    pub level: String,
+}
+
+pub fn routes(router_state: &RouterState) -> Router<Arc<RouterState>> {
+    route_groups()
+        .merge(log_level())
+        .route_layer(middlewares::admin_authorized(router_state))
+}
+
+/// GET /admin/route-groups
+/// POST /admin/route-groups
+fn route_groups() -> Router<Arc<RouterState>> {
+    Router::new().route(
+        "/admin/route-groups",
+        get(|State(state): State<Arc<RouterState>>| async move {
+            handlers::route_groups(state.dependencies.route_feature_flags.clone()).await
+        })
+        .post(
+            |State(state): State<Arc<RouterState>>,
+             RemoteAddress(remote_address): RemoteAddress,
+             Json(message): Json<RouteGroupStatusMessage>| async move {
+                handlers::update_route_group(
+                    message,
+                    state.logger.clone(),
+                    state.dependencies.route_feature_flags.clone(),
+                    state.dependencies.audit_logger.clone(),
+                    remote_address,
+                )
+                .await
+            },
+        ),
+    )
+}
+
+/// GET /admin/log-level
+/// POST /admin/log-level
+fn log_level() -> Router<Arc<RouterState>> {
+    Router::new().route(
+        "/admin/log-level",
+        get(|State(state): State<Arc<RouterState>>| async move {
+            handlers::log_level(state.dependencies.log_level_handle.clone()).await
+        })
+        .post(
+            |State(state): State<Arc<RouterState>>,
+             RemoteAddress(remote_address): RemoteAddress,
+             Json(message): Json<LogLevelMessage>| async move {
+                handlers::update_log_level(
+                    message,
+                    state.logger.clone(),
+                    state.dependencies.log_level_handle.clone(),
+                    state.dependencies.audit_logger.clone(),
+                    remote_address,
+                )
+                .await
+            },
+        ),
+    )
+}
+
+mod handlers {
+    use axum::http::StatusCode;
+    use axum::response::IntoResponse;
+    use slog::{info, warn, Level, Logger};
+    use std::convert::Infallible;
+    use std::net::SocketAddr;
+    use std::str::FromStr;
+    use std::sync::Arc;
+
+    use mithril_common::error_codes::MithrilErrorCode;
+    use mithril_common::logging::LogLevelHandle;
+
+    use crate::http_server::routes::reply;
+    use crate::http_server::{RouteFeatureFlags, RouteGroup};
+    use crate::services::{AuditAction, AuditEntry, AuditLogger, AuditOutcome};
+
+    use super::{LogLevelMessage, RouteGroupStatusMessage};
+
+    fn route_groups_status(
+        route_feature_flags: &RouteFeatureFlags,
+    ) -> Vec<RouteGroupStatusMessage> {
+        RouteGroup::ALL
+            .into_iter()
+            .map(|route_group| RouteGroupStatusMessage {
+                route_group,
+                enabled: route_feature_flags.is_enabled(route_group),
+            })
+            .collect()
+    }
+
+    /// Route groups
+    pub async fn route_groups(
+        route_feature_flags: Arc<RouteFeatureFlags>,
+    ) -> Result<impl IntoResponse, Infallible> {
+        Ok(reply::json(
+            &route_groups_status(&route_feature_flags),
+            StatusCode::OK,
+        ))
+    }
+
+    /// Update route group
+    pub async fn update_route_group(
+        message: RouteGroupStatusMessage,
+        logger: Logger,
+        route_feature_flags: Arc<RouteFeatureFlags>,
+        audit_logger: Arc<dyn AuditLogger>,
+        remote_address: Option<SocketAddr>,
+    ) -> Result<impl IntoResponse, Infallible> {
+        info!(
+            logger, "POST /admin/route-groups";
+            "route_group" => %message.route_group, "enabled" => message.enabled
+        );
+        route_feature_flags.set_enabled(message.route_group, message.enabled);
+        audit_logger.record(
+            AuditEntry::new(AuditAction::RouteGroupUpdated)
+                .with_source_address(remote_address)
+                .with_details(serde_json::json!({
+                    "route_group": message.route_group,
+                    "enabled": message.enabled,
+                })),
+        );
+
+        Ok(reply::json(
+            &route_groups_status(&route_feature_flags),
+            StatusCode::OK,
+        ))
+    }
+
+    fn log_level_message(log_level_handle: &LogLevelHandle) -> LogLevelMessage {
+        LogLevelMessage {
+            level: log_level_handle.level().as_str().to_lowercase(),
+        }
+    }
+
+    /// Log level
+    pub async fn log_level(
+        log_level_handle: LogLevelHandle,
+    ) -> Result<impl IntoResponse, Infallible> {
+        Ok(reply::json(
+            &log_level_message(&log_level_handle),
+            StatusCode::OK,
+        ))
+    }
+
+    /// Update log level
+    pub async fn update_log_level(
+        message: LogLevelMessage,
+        logger: Logger,
+        log_level_handle: LogLevelHandle,
+        audit_logger: Arc<dyn AuditLogger>,
+        remote_address: Option<SocketAddr>,
+    ) -> Result<impl IntoResponse, Infallible> {
+        let Ok(level) = Level::from_str(&message.level) else {
+            warn!(logger, "POST /admin/log-level::bad_request"; "level" => &message.level);
+            audit_logger.record(
+                AuditEntry::new(AuditAction::LogLevelUpdated)
+                    .with_outcome(AuditOutcome::Failure("invalid log level".to_string()))
+                    .with_source_address(remote_address)
+                    .with_details(serde_json::json!({ "level": &message.level })),
+            );
+            return Ok(reply::bad_request(
+                MithrilErrorCode::InvalidRequestPayload,
+                "invalid_log_level".to_string(),
+                format!(
+                    "Unknown log level '{}', expected one of: critical, error, warn, info, debug, trace",
+                    message.level
+                ),
+            ));
+        };
+        info!(
+            logger, "POST /admin/log-level";
+            "previous_level" => log_level_handle.level().as_str(), "level" => level.as_str()
+        );
+        log_level_handle.set_level(level);
+        audit_logger.record(
+            AuditEntry::new(AuditAction::LogLevelUpdated)
+                .with_source_address(remote_address)
+                .with_details(serde_json::json!({ "level": level.as_str().to_lowercase() })),
+        );
+
+        Ok(reply::json(
+            &log_level_message(&log_level_handle),
+            StatusCode::OK,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{Method, StatusCode};
+    use std::sync::Arc;
+
+    use crate::http_server::routes::router::RouterConfig;
+    use crate::http_server::routes::test_utils::request;
+    use crate::services::{AuditAction, AuditOutcome, DumbAuditLogger};
+    use crate::{http_server::SERVER_BASE_PATH, initialize_dependencies, DependencyContainer};
+
+    use super::*;
+
+    const ADMIN_API_TOKEN: &str = "admin-token";
+
+    fn setup_router(
+        dependency_manager: Arc<DependencyContainer>,
+        admin_api_token: Option<&str>,
+    ) -> Router {
+        let state = RouterState::new(
+            dependency_manager,
+            RouterConfig {
+                admin_api_token: admin_api_token.map(str::to_string),
+                ..RouterConfig::dummy()
+            },
+        );
+
+        crate::http_server::routes::test_utils::setup_router(state, routes)
+    }
+
+    #[tokio::test]
+    async fn admin_routes_do_not_exist_without_token() {
+        let dependency_manager = Arc::new(initialize_dependencies().await);
+
+        let response = request()
+            .method(Method::GET.as_str())
+            .path(&format!("/{SERVER_BASE_PATH}/admin/route-groups"))
+            .header("authorization", format!("Bearer {ADMIN_API_TOKEN}"))
+            .reply(&setup_router(dependency_manager, None))
+            .await;
+
+        assert_eq!(StatusCode::NOT_FOUND, response.status());
+    }
+
+    #[tokio::test]
+    async fn admin_routes_require_the_token() {
+        let audit_logger = Arc::new(DumbAuditLogger::new());
+        let mut dependency_manager = initialize_dependencies().await;
+        dependency_manager.audit_logger = audit_logger.clone();
+
+        let response = request()
+            .method(Method::GET.as_str())
+            .path(&format!("/{SERVER_BASE_PATH}/admin/route-groups"))
+            .header("authorization", "Bearer invalid")
+            .reply(&setup_router(
+                Arc::new(dependency_manager),
+                Some(ADMIN_API_TOKEN),
+            ))
+            .await;
+
+        assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+        let audit_entry = audit_logger.get_last_entry().unwrap();
+        assert_eq!(AuditAction::AdminApiAccessDenied, audit_entry.action);
+        assert_eq!(
+            AuditOutcome::Failure("invalid token".to_string()),
+            audit_entry.outcome
+        );
+    }
+
+    #[tokio::test]
+    async fn disable_a_route_group() {
+        let dependency_manager = Arc::new(initialize_dependencies().await);
+
+        let response = request()
+            .method(Method::POST.as_str())
+            .path(&format!("/{SERVER_BASE_PATH}/admin/route-groups"))
+            .header("authorization", format!("Bearer {ADMIN_API_TOKEN}"))
+            .json(&RouteGroupStatusMessage {
+                route_group: RouteGroup::Proofs,
+                enabled: false,
+            })
+            .reply(&setup_router(
+                dependency_manager.clone(),
+                Some(ADMIN_API_TOKEN),
+            ))
+            .await;
+
+        assert_eq!(StatusCode::OK, response.status());
+        let status: Vec<RouteGroupStatusMessage> = serde_json::from_slice(response.body()).unwrap();
+        assert!(status.contains(&RouteGroupStatusMessage {
+            route_group: RouteGroup::Proofs,
+            enabled: false,
+        }));
+        assert!(!dependency_manager
+            .route_feature_flags
+            .is_enabled(RouteGroup::Proofs));
+    }
+
+    #[tokio::test]
+    async fn change_the_log_level() {
+        let dependency_manager = Arc::new(initialize_dependencies().await);
+
+        let response = request()
+            .method(Method::POST.as_str())
+            .path(&format!("/{SERVER_BASE_PATH}/admin/log-level"))
+            .header("authorization", format!("Bearer {ADMIN_API_TOKEN}"))
+            .json(&LogLevelMessage {
+                level: "debug".to_string(),
+            })
+            .reply(&setup_router(
+                dependency_manager.clone(),
+                Some(ADMIN_API_TOKEN),
+            ))
+            .await;
+
+        assert_eq!(StatusCode::OK, response.status());
+        let message: LogLevelMessage = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!("debug", message.level);
+        assert_eq!(
+            slog::Level::Debug,
+            dependency_manager.log_level_handle.level()
+        );
+    }
+
+    #[tokio::test]
+    async fn change_the_log_level_to_an_unknown_level_is_rejected() {
+        let dependency_manager = Arc::new(initialize_dependencies().await);
+        let initial_level = dependency_manager.log_level_handle.level();
+
+        let response = request()
+            .method(Method::POST.as_str())
+            .path(&format!("/{SERVER_BASE_PATH}/admin/log-level"))
+            .header("authorization", format!("Bearer {ADMIN_API_TOKEN}"))
+            .json(&LogLevelMessage {
+                level: "verbose".to_string(),
+            })
+            .reply(&setup_router(
+                dependency_manager.clone(),
+                Some(ADMIN_API_TOKEN),
+            ))
+            .await;
+
+        assert_eq!(StatusCode::BAD_REQUEST, response.status());
+        assert_eq!(initial_level, dependency_manager.log_level_handle.level());
+    }
+}
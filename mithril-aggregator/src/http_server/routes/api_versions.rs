@@ -0,0 +1,90 @@
+//! ## HTTP API versions
+//!
+//! The routes are mounted under a prefix per version of the HTTP API (`/aggregator/v1`,
+//! `/aggregator/v2`), and also without prefix for the signers and clients that predate the
+//! versioned prefixes, which get the `v1` format.
+//!
+//! The handlers are shared by all the versions: a breaking change of a message is done by
+//! adapting it to the format of each version with a [VersionedMessageAdapter], so that the
+//! old signers and clients keep working while they upgrade. For now only the epoch settings
+//! message differs between the versions, the other messages are served unchanged by all of them.
+
+use std::fmt::{Display, Formatter};
+
+use mithril_common::messages::EpochSettingsMessage;
+
+/// Version of the HTTP API, selected by the prefix of the route.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HttpApiVersion {
+    /// First version, also served by the routes without a version prefix.
+    V1,
+    /// Second version, without the deprecated fields of the messages.
+    V2,
+}
+
+impl HttpApiVersion {
+    /// All the versions of the HTTP API, each one is mounted under its prefix.
+    pub const ALL: [HttpApiVersion; 2] = [Self::V1, Self::V2];
+
+    /// Version used by the routes without a version prefix.
+    pub const UNPREFIXED: HttpApiVersion = Self::V1;
+
+    /// Prefix of the routes of the version.
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            Self::V1 => "v1",
+            Self::V2 => "v2",
+        }
+    }
+}
+
+impl Display for HttpApiVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.prefix())
+    }
+}
+
+/// Adapt a message computed by a shared handler to the format of a version of the HTTP API.
+pub trait VersionedMessageAdapter: Sized {
+    /// Method to trigger the conversion
+    fn adapt_to_version(self, version: HttpApiVersion) -> Self;
+}
+
+impl VersionedMessageAdapter for EpochSettingsMessage {
+    fn adapt_to_version(self, version: HttpApiVersion) -> Self {
+        match version {
+            HttpApiVersion::V1 => self,
+            // The deprecated protocol parameters are replaced by the signer registration ones
+            #[allow(deprecated)]
+            HttpApiVersion::V2 => Self {
+                protocol_parameters: None,
+                next_protocol_parameters: None,
+                ..self
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn epoch_settings_message_keeps_the_deprecated_fields_only_in_v1() {
+        let message = EpochSettingsMessage::dummy();
+
+        let v1_message = message.clone().adapt_to_version(HttpApiVersion::V1);
+        let v2_message = message.clone().adapt_to_version(HttpApiVersion::V2);
+
+        assert_eq!(message, v1_message);
+        #[allow(deprecated)]
+        {
+            assert_eq!(None, v2_message.protocol_parameters);
+            assert_eq!(None, v2_message.next_protocol_parameters);
+        }
+        assert_eq!(
+            message.signer_registration_protocol_parameters,
+            v2_message.signer_registration_protocol_parameters
+        );
+    }
+}
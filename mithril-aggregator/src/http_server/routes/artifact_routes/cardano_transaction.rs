@@ -1,34 +1,44 @@
-use crate::http_server::routes::middlewares;
+use axum::extract::{Path, State};
+use axum::routing::get;
+use axum::Router;
+use std::sync::Arc;
+
 use crate::http_server::routes::router::RouterState;
-use warp::Filter;
 
-pub fn routes(
-    router_state: &RouterState,
-) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-    artifact_cardano_transactions(router_state).or(artifact_cardano_transaction_by_id(router_state))
+pub fn routes() -> Router<Arc<RouterState>> {
+    artifact_cardano_transactions().merge(artifact_cardano_transaction_by_id())
 }
 
 /// GET /artifact/cardano-transactions
-fn artifact_cardano_transactions(
-    router_state: &RouterState,
-) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-    warp::path!("artifact" / "cardano-transactions")
-        .and(warp::get())
-        .and(middlewares::with_logger(router_state))
-        .and(middlewares::with_http_message_service(router_state))
-        .and_then(handlers::list_artifacts)
+fn artifact_cardano_transactions() -> Router<Arc<RouterState>> {
+    Router::new().route(
+        "/artifact/cardano-transactions",
+        get(|State(state): State<Arc<RouterState>>| async move {
+            handlers::list_artifacts(
+                state.logger.clone(),
+                state.dependencies.message_service.clone(),
+            )
+            .await
+        }),
+    )
 }
 
 /// GET /artifact/cardano-transaction/:id
-fn artifact_cardano_transaction_by_id(
-    router_state: &RouterState,
-) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-    warp::path!("artifact" / "cardano-transaction" / String)
-        .and(warp::get())
-        .and(middlewares::with_logger(router_state))
-        .and(middlewares::with_http_message_service(router_state))
-        .and(middlewares::with_metrics_service(router_state))
-        .and_then(handlers::get_artifact_by_signed_entity_id)
+fn artifact_cardano_transaction_by_id() -> Router<Arc<RouterState>> {
+    Router::new().route(
+        "/artifact/cardano-transaction/:id",
+        get(
+            |State(state): State<Arc<RouterState>>, Path(signed_entity_id): Path<String>| async move {
+                handlers::get_artifact_by_signed_entity_id(
+                    signed_entity_id,
+                    state.logger.clone(),
+                    state.dependencies.message_service.clone(),
+                    state.dependencies.metrics_service.clone(),
+                )
+                .await
+            },
+        ),
+    )
 }
 
 pub mod handlers {
@@ -36,10 +46,11 @@ pub mod handlers {
     use crate::services::MessageService;
     use crate::MetricsService;
 
+    use axum::http::StatusCode;
+    use axum::response::IntoResponse;
     use slog::{warn, Logger};
     use std::convert::Infallible;
     use std::sync::Arc;
-    use warp::http::StatusCode;
 
     pub const LIST_MAX_ITEMS: usize = 20;
 
@@ -47,7 +58,7 @@ pub mod handlers {
     pub async fn list_artifacts(
         logger: Logger,
         http_message_service: Arc<dyn MessageService>,
-    ) -> Result<impl warp::Reply, Infallible> {
+    ) -> Result<impl IntoResponse, Infallible> {
         match http_message_service
             .get_cardano_transaction_list_message(LIST_MAX_ITEMS)
             .await
@@ -67,7 +78,7 @@ pub mod handlers {
         logger: Logger,
         http_message_service: Arc<dyn MessageService>,
         metrics_service: Arc<MetricsService>,
-    ) -> Result<impl warp::Reply, Infallible> {
+    ) -> Result<impl IntoResponse, Infallible> {
         metrics_service
             .get_artifact_detail_cardano_transaction_total_served_since_startup()
             .increment();
@@ -91,12 +102,11 @@ pub mod handlers {
 
 #[cfg(test)]
 pub mod tests {
+    use axum::http::{Method, StatusCode};
     use serde_json::Value::Null;
     use std::sync::Arc;
-    use warp::{
-        http::{Method, StatusCode},
-        test::request,
-    };
+
+    use crate::http_server::routes::test_utils::request;
 
     use mithril_common::messages::{
         CardanoTransactionSnapshotListItemMessage, CardanoTransactionSnapshotMessage,
@@ -110,17 +120,8 @@ pub mod tests {
 
     use super::*;
 
-    fn setup_router(
-        state: RouterState,
-    ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-        let cors = warp::cors()
-            .allow_any_origin()
-            .allow_headers(vec!["content-type"])
-            .allow_methods(vec![Method::GET, Method::POST, Method::OPTIONS]);
-
-        warp::any()
-            .and(warp::path(SERVER_BASE_PATH))
-            .and(routes(&state).with(cors))
+    fn setup_router(state: RouterState) -> Router {
+        crate::http_server::routes::test_utils::setup_router(state, |_| routes())
     }
 
     #[tokio::test]
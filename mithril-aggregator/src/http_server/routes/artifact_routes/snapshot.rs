@@ -1,95 +1,131 @@
-use crate::http_server::routes::middlewares;
+use axum::extract::{Path, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::IntoResponse;
+use axum::routing::{any, get};
+use axum::Router;
+use std::sync::Arc;
+use tower::ServiceBuilder;
+use tower_http::services::ServeDir;
+
 use crate::http_server::routes::router::RouterState;
 use crate::http_server::SERVER_BASE_PATH;
-use warp::hyper::Uri;
-use warp::Filter;
-
-pub fn routes(
-    router_state: &RouterState,
-) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-    artifact_cardano_full_immutable_snapshots(router_state)
-        .or(artifact_cardano_full_immutable_snapshot_by_id(router_state))
-        .or(serve_snapshots_dir(router_state))
-        .or(snapshot_download(router_state))
-        .or(artifact_cardano_full_immutable_snapshots_legacy())
-        .or(artifact_cardano_full_immutable_snapshot_by_id_legacy())
+
+pub fn routes(router_state: &RouterState) -> Router<Arc<RouterState>> {
+    artifact_cardano_full_immutable_snapshots()
+        .merge(artifact_cardano_full_immutable_snapshot_by_id())
+        .merge(serve_snapshots_dir(router_state))
+        .merge(snapshot_download())
+        .merge(artifact_cardano_full_immutable_snapshots_legacy())
+        .merge(artifact_cardano_full_immutable_snapshot_by_id_legacy())
 }
 
 /// GET /artifact/snapshots
-fn artifact_cardano_full_immutable_snapshots(
-    router_state: &RouterState,
-) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-    warp::path!("artifact" / "snapshots")
-        .and(warp::get())
-        .and(middlewares::with_logger(router_state))
-        .and(middlewares::with_http_message_service(router_state))
-        .and_then(handlers::list_artifacts)
+fn artifact_cardano_full_immutable_snapshots() -> Router<Arc<RouterState>> {
+    Router::new().route(
+        "/artifact/snapshots",
+        get(|State(state): State<Arc<RouterState>>| async move {
+            handlers::list_artifacts(
+                state.logger.clone(),
+                state.dependencies.message_service.clone(),
+            )
+            .await
+        }),
+    )
 }
 
 /// GET /artifact/snapshot/:id
-fn artifact_cardano_full_immutable_snapshot_by_id(
-    dependency_manager: &RouterState,
-) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-    warp::path!("artifact" / "snapshot" / String)
-        .and(warp::get())
-        .and(middlewares::with_logger(dependency_manager))
-        .and(middlewares::with_http_message_service(dependency_manager))
-        .and(middlewares::with_metrics_service(dependency_manager))
-        .and_then(handlers::get_artifact_by_signed_entity_id)
+fn artifact_cardano_full_immutable_snapshot_by_id() -> Router<Arc<RouterState>> {
+    Router::new().route(
+        "/artifact/snapshot/:id",
+        get(
+            |State(state): State<Arc<RouterState>>, Path(signed_entity_id): Path<String>| async move {
+                handlers::get_artifact_by_signed_entity_id(
+                    signed_entity_id,
+                    state.logger.clone(),
+                    state.dependencies.message_service.clone(),
+                    state.dependencies.metrics_service.clone(),
+                )
+                .await
+            },
+        ),
+    )
 }
 
 /// GET /artifact/snapshots/{digest}/download
-fn snapshot_download(
-    router_state: &RouterState,
-) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-    warp::path!("artifact" / "snapshot" / String / "download")
-        .and(warp::get().or(warp::head()).unify())
-        .and(middlewares::with_logger(router_state))
-        .and(middlewares::extract_config(router_state, |config| {
-            config.server_url.clone()
-        }))
-        .and(middlewares::with_signed_entity_service(router_state))
-        .and_then(handlers::snapshot_download)
+fn snapshot_download() -> Router<Arc<RouterState>> {
+    // The `GET` method router also answers to the `HEAD` requests
+    Router::new().route(
+        "/artifact/snapshot/:digest/download",
+        get(
+            |State(state): State<Arc<RouterState>>, Path(digest): Path<String>| async move {
+                handlers::snapshot_download(
+                    digest,
+                    state.logger.clone(),
+                    state.configuration.server_url.clone(),
+                    state.dependencies.signed_entity_service.clone(),
+                )
+                .await
+            },
+        ),
+    )
 }
 
-fn serve_snapshots_dir(
-    router_state: &RouterState,
-) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-    warp::path("snapshot_download")
-        .and(warp::fs::dir(
-            router_state.configuration.snapshot_directory.clone(),
-        ))
-        .and(middlewares::with_logger(router_state))
-        .and(middlewares::with_signed_entity_service(router_state))
-        .and_then(handlers::ensure_downloaded_file_is_a_snapshot)
+fn serve_snapshots_dir(router_state: &RouterState) -> Router<Arc<RouterState>> {
+    let logger = router_state.logger.clone();
+    let signed_entity_service = router_state.dependencies.signed_entity_service.clone();
+
+    Router::new().nest_service(
+        "/snapshot_download",
+        ServiceBuilder::new()
+            .layer(middleware::from_fn(move |request: Request, next: Next| {
+                handlers::ensure_downloaded_file_is_a_snapshot(
+                    request,
+                    next,
+                    logger.clone(),
+                    signed_entity_service.clone(),
+                )
+            }))
+            .service(ServeDir::new(
+                router_state.configuration.snapshot_directory.clone(),
+            )),
+    )
 }
 
 /// GET /snapshots
 // TODO: This legacy route should be removed when this code is released with a new distribution
-fn artifact_cardano_full_immutable_snapshots_legacy(
-) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-    warp::path!("snapshots").map(|| {
-        warp::redirect(
-            format!("/{SERVER_BASE_PATH}/artifact/snapshots")
-                .as_str()
-                .parse::<Uri>()
-                .unwrap(),
-        )
-    })
+fn artifact_cardano_full_immutable_snapshots_legacy() -> Router<Arc<RouterState>> {
+    Router::new().route(
+        "/snapshots",
+        any(|| async {
+            (
+                StatusCode::MOVED_PERMANENTLY,
+                [(
+                    header::LOCATION,
+                    format!("/{SERVER_BASE_PATH}/artifact/snapshots"),
+                )],
+            )
+                .into_response()
+        }),
+    )
 }
 
 /// GET /snapshot/digest
 // TODO: This legacy route should be removed when this code is released with a new distribution
-fn artifact_cardano_full_immutable_snapshot_by_id_legacy(
-) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-    warp::path!("snapshot" / String).map(|digest| {
-        warp::redirect(
-            format!("/{SERVER_BASE_PATH}/artifact/snapshot/{digest}")
-                .as_str()
-                .parse::<Uri>()
-                .unwrap(),
-        )
-    })
+fn artifact_cardano_full_immutable_snapshot_by_id_legacy() -> Router<Arc<RouterState>> {
+    Router::new().route(
+        "/snapshot/:digest",
+        any(|Path(digest): Path<String>| async move {
+            (
+                StatusCode::MOVED_PERMANENTLY,
+                [(
+                    header::LOCATION,
+                    format!("/{SERVER_BASE_PATH}/artifact/snapshot/{digest}"),
+                )],
+            )
+                .into_response()
+        }),
+    )
 }
 
 mod handlers {
@@ -98,11 +134,14 @@ mod handlers {
     use crate::services::MessageService;
     use crate::services::SignedEntityService;
     use crate::MetricsService;
+    use axum::extract::Request;
+    use axum::http::{header, HeaderValue, StatusCode};
+    use axum::middleware::Next;
+    use axum::response::{IntoResponse, Response};
     use slog::{debug, warn, Logger};
     use std::convert::Infallible;
-    use std::str::FromStr;
+    use std::path::Path;
     use std::sync::Arc;
-    use warp::http::{StatusCode, Uri};
 
     pub const LIST_MAX_ITEMS: usize = 20;
 
@@ -110,7 +149,7 @@ mod handlers {
     pub async fn list_artifacts(
         logger: Logger,
         http_message_service: Arc<dyn MessageService>,
-    ) -> Result<impl warp::Reply, Infallible> {
+    ) -> Result<impl IntoResponse, Infallible> {
         match http_message_service
             .get_snapshot_list_message(LIST_MAX_ITEMS)
             .await
@@ -129,7 +168,7 @@ mod handlers {
         logger: Logger,
         http_message_service: Arc<dyn MessageService>,
         metrics_service: Arc<MetricsService>,
-    ) -> Result<impl warp::Reply, Infallible> {
+    ) -> Result<impl IntoResponse, Infallible> {
         metrics_service
             .get_artifact_detail_cardano_db_total_served_since_startup()
             .increment();
@@ -152,11 +191,12 @@ mod handlers {
 
     /// Download a file if and only if it's a snapshot archive
     pub async fn ensure_downloaded_file_is_a_snapshot(
-        reply: warp::fs::File,
+        request: Request,
+        next: Next,
         logger: Logger,
         signed_entity_service: Arc<dyn SignedEntityService>,
-    ) -> Result<impl warp::Reply, Infallible> {
-        let filepath = reply.path().to_path_buf();
+    ) -> Response {
+        let filepath = Path::new(request.uri().path()).to_path_buf();
         debug!(
             logger,
             ">> ensure_downloaded_file_is_a_snapshot / file: `{}`",
@@ -168,19 +208,26 @@ mod handlers {
                 .get_signed_snapshot_by_id(&digest)
                 .await
             {
-                Ok(Some(_)) => Ok(Box::new(warp::reply::with_header(
-                    reply,
-                    "Content-Disposition",
-                    format!(
-                        "attachment; filename=\"{}\"",
-                        filepath.file_name().unwrap().to_str().unwrap()
-                    ),
-                )) as Box<dyn warp::Reply>),
-                _ => Ok(reply::empty(StatusCode::NOT_FOUND)),
+                Ok(Some(_)) => {
+                    let mut response = next.run(request).await;
+                    if response.status().is_success() {
+                        let content_disposition = format!(
+                            "attachment; filename=\"{}\"",
+                            filepath.file_name().unwrap().to_str().unwrap()
+                        );
+                        if let Ok(value) = HeaderValue::from_str(&content_disposition) {
+                            response
+                                .headers_mut()
+                                .insert(header::CONTENT_DISPOSITION, value);
+                        }
+                    }
+                    response
+                }
+                _ => reply::empty(StatusCode::NOT_FOUND),
             },
             Err(err) => {
                 warn!(logger,"ensure_downloaded_file_is_a_snapshot::error"; "error" => ?err);
-                Ok(reply::empty(StatusCode::NOT_FOUND))
+                reply::empty(StatusCode::NOT_FOUND)
             }
         }
     }
@@ -191,7 +238,7 @@ mod handlers {
         logger: Logger,
         server_url: String,
         signed_entity_service: Arc<dyn SignedEntityService>,
-    ) -> Result<impl warp::Reply, Infallible> {
+    ) -> Result<impl IntoResponse, Infallible> {
         match signed_entity_service
             .get_signed_snapshot_by_id(&digest)
             .await
@@ -208,9 +255,8 @@ mod handlers {
                 );
                 let snapshot_uri =
                     format!("{server_url}{SERVER_BASE_PATH}/snapshot_download/{filename}",);
-                let snapshot_uri = Uri::from_str(&snapshot_uri).unwrap();
 
-                Ok(Box::new(warp::redirect::found(snapshot_uri)) as Box<dyn warp::Reply>)
+                Ok((StatusCode::FOUND, [(header::LOCATION, snapshot_uri)]).into_response())
             }
             Ok(None) => {
                 warn!(logger, "snapshot_download::not_found");
@@ -234,6 +280,7 @@ mod tests {
         initialize_dependencies,
         services::{MockMessageService, MockSignedEntityService},
     };
+    use axum::http::{Method, StatusCode};
     use mithril_common::messages::{SnapshotListItemMessage, SnapshotMessage};
     use mithril_common::{
         entities::{CardanoDbBeacon, SignedEntityType, Snapshot},
@@ -242,22 +289,11 @@ mod tests {
     use mithril_persistence::sqlite::HydrationError;
     use serde_json::Value::Null;
     use std::sync::Arc;
-    use warp::{
-        http::{Method, StatusCode},
-        test::request,
-    };
 
-    fn setup_router(
-        state: RouterState,
-    ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-        let cors = warp::cors()
-            .allow_any_origin()
-            .allow_headers(vec!["content-type"])
-            .allow_methods(vec![Method::GET, Method::POST, Method::OPTIONS]);
-
-        warp::any()
-            .and(warp::path(SERVER_BASE_PATH))
-            .and(routes(&state).with(cors))
+    use crate::http_server::routes::test_utils::request;
+
+    fn setup_router(state: RouterState) -> Router {
+        crate::http_server::routes::test_utils::setup_router(state, routes)
     }
 
     #[tokio::test]
@@ -1,48 +1,60 @@
-use warp::Filter;
+use axum::extract::{Path, State};
+use axum::routing::get;
+use axum::Router;
+use std::sync::Arc;
 
-use crate::http_server::routes::middlewares;
 use crate::http_server::routes::router::RouterState;
 
-pub fn routes(
-    router_state: &RouterState,
-) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-    certificate_pending(router_state)
-        .or(certificate_certificates(router_state))
-        .or(certificate_certificate_hash(router_state))
+pub fn routes() -> Router<Arc<RouterState>> {
+    certificate_pending()
+        .merge(certificate_certificates())
+        .merge(certificate_certificate_hash())
 }
 
 /// GET /certificate-pending
-fn certificate_pending(
-    router_state: &RouterState,
-) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-    warp::path!("certificate-pending")
-        .and(warp::get())
-        .and(middlewares::with_logger(router_state))
-        .and(middlewares::with_certificate_pending_store(router_state))
-        .and_then(handlers::certificate_pending)
+fn certificate_pending() -> Router<Arc<RouterState>> {
+    Router::new().route(
+        "/certificate-pending",
+        get(|State(state): State<Arc<RouterState>>| async move {
+            handlers::certificate_pending(
+                state.logger.clone(),
+                state.dependencies.certificate_pending_store.clone(),
+            )
+            .await
+        }),
+    )
 }
 
 /// GET /certificates
-fn certificate_certificates(
-    router_state: &RouterState,
-) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-    warp::path!("certificates")
-        .and(warp::get())
-        .and(middlewares::with_logger(router_state))
-        .and(middlewares::with_http_message_service(router_state))
-        .and_then(handlers::certificate_certificates)
+fn certificate_certificates() -> Router<Arc<RouterState>> {
+    Router::new().route(
+        "/certificates",
+        get(|State(state): State<Arc<RouterState>>| async move {
+            handlers::certificate_certificates(
+                state.logger.clone(),
+                state.dependencies.message_service.clone(),
+            )
+            .await
+        }),
+    )
 }
 
 /// GET /certificate/{certificate_hash}
-fn certificate_certificate_hash(
-    router_state: &RouterState,
-) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-    warp::path!("certificate" / String)
-        .and(warp::get())
-        .and(middlewares::with_logger(router_state))
-        .and(middlewares::with_http_message_service(router_state))
-        .and(middlewares::with_metrics_service(router_state))
-        .and_then(handlers::certificate_certificate_hash)
+fn certificate_certificate_hash() -> Router<Arc<RouterState>> {
+    Router::new().route(
+        "/certificate/:certificate_hash",
+        get(
+            |State(state): State<Arc<RouterState>>, Path(certificate_hash): Path<String>| async move {
+                handlers::certificate_certificate_hash(
+                    certificate_hash,
+                    state.logger.clone(),
+                    state.dependencies.message_service.clone(),
+                    state.dependencies.metrics_service.clone(),
+                )
+                .await
+            },
+        ),
+    )
 }
 
 mod handlers {
@@ -52,10 +64,11 @@ mod handlers {
         ToCertificatePendingMessageAdapter,
     };
 
+    use axum::http::StatusCode;
+    use axum::response::IntoResponse;
     use slog::{warn, Logger};
     use std::convert::Infallible;
     use std::sync::Arc;
-    use warp::http::StatusCode;
 
     pub const LIST_MAX_ITEMS: usize = 20;
 
@@ -63,7 +76,7 @@ mod handlers {
     pub async fn certificate_pending(
         logger: Logger,
         certificate_pending_store: Arc<CertificatePendingStore>,
-    ) -> Result<impl warp::Reply, Infallible> {
+    ) -> Result<impl IntoResponse, Infallible> {
         match certificate_pending_store.get().await {
             Ok(Some(certificate_pending)) => Ok(reply::json(
                 &ToCertificatePendingMessageAdapter::adapt(certificate_pending),
@@ -81,7 +94,7 @@ mod handlers {
     pub async fn certificate_certificates(
         logger: Logger,
         http_message_service: Arc<dyn MessageService>,
-    ) -> Result<impl warp::Reply, Infallible> {
+    ) -> Result<impl IntoResponse, Infallible> {
         match http_message_service
             .get_certificate_list_message(LIST_MAX_ITEMS)
             .await
@@ -100,7 +113,7 @@ mod handlers {
         logger: Logger,
         http_message_service: Arc<dyn MessageService>,
         metrics_service: Arc<MetricsService>,
-    ) -> Result<impl warp::Reply, Infallible> {
+    ) -> Result<impl IntoResponse, Infallible> {
         metrics_service
             .get_certificate_detail_total_served_since_startup()
             .increment();
@@ -122,6 +135,7 @@ mod handlers {
 #[cfg(test)]
 mod tests {
     use anyhow::anyhow;
+    use axum::http::{Method, StatusCode};
     use mithril_common::{
         entities::CertificatePending,
         test_utils::{apispec::APISpec, fake_data},
@@ -129,10 +143,8 @@ mod tests {
     use mithril_persistence::store::adapter::DumbStoreAdapter;
     use serde_json::Value::Null;
     use std::sync::Arc;
-    use warp::{
-        http::{Method, StatusCode},
-        test::request,
-    };
+
+    use crate::http_server::routes::test_utils::request;
 
     use crate::{
         http_server::SERVER_BASE_PATH, initialize_dependencies, services::MockMessageService,
@@ -141,17 +153,8 @@ mod tests {
 
     use super::*;
 
-    fn setup_router(
-        state: RouterState,
-    ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-        let cors = warp::cors()
-            .allow_any_origin()
-            .allow_headers(vec!["content-type"])
-            .allow_methods(vec![Method::GET, Method::POST, Method::OPTIONS]);
-
-        warp::any()
-            .and(warp::path(SERVER_BASE_PATH))
-            .and(routes(&state).with(cors))
+    fn setup_router(state: RouterState) -> Router {
+        crate::http_server::routes::test_utils::setup_router(state, |_| routes())
     }
 
     #[tokio::test]
@@ -1,5 +1,8 @@
+use axum::extract::State;
+use axum::routing::get;
+use axum::Router;
 use std::collections::BTreeSet;
-use warp::Filter;
+use std::sync::Arc;
 
 use mithril_common::{
     entities::SignedEntityTypeDiscriminants,
@@ -8,27 +11,27 @@ use mithril_common::{
 };
 
 use crate::dependency_injection::EpochServiceWrapper;
-use crate::http_server::routes::middlewares;
+use crate::http_server::routes::api_versions::HttpApiVersion;
 use crate::http_server::routes::router::RouterState;
 
-pub fn routes(
-    router_state: &RouterState,
-) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-    epoch_settings(router_state)
+pub fn routes(version: HttpApiVersion) -> Router<Arc<RouterState>> {
+    epoch_settings(version)
 }
 
 /// GET /epoch-settings
-fn epoch_settings(
-    router_state: &RouterState,
-) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-    warp::path!("epoch-settings")
-        .and(warp::get())
-        .and(middlewares::with_logger(router_state))
-        .and(middlewares::with_epoch_service(router_state))
-        .and(middlewares::extract_config(router_state, |config| {
-            config.allowed_discriminants.clone()
-        }))
-        .and_then(handlers::epoch_settings)
+fn epoch_settings(version: HttpApiVersion) -> Router<Arc<RouterState>> {
+    Router::new().route(
+        "/epoch-settings",
+        get(move |State(state): State<Arc<RouterState>>| async move {
+            handlers::epoch_settings(
+                version,
+                state.logger.clone(),
+                state.dependencies.epoch_service.clone(),
+                state.configuration.allowed_discriminants.clone(),
+            )
+            .await
+        }),
+    )
 }
 
 async fn get_epoch_settings_message(
@@ -74,25 +77,30 @@ async fn get_epoch_settings_message(
 }
 
 mod handlers {
+    use axum::http::StatusCode;
+    use axum::response::IntoResponse;
     use slog::{warn, Logger};
     use std::collections::BTreeSet;
     use std::convert::Infallible;
-    use warp::http::StatusCode;
 
     use mithril_common::entities::SignedEntityTypeDiscriminants;
 
     use crate::dependency_injection::EpochServiceWrapper;
+    use crate::http_server::routes::api_versions::{HttpApiVersion, VersionedMessageAdapter};
     use crate::http_server::routes::epoch_routes::get_epoch_settings_message;
     use crate::http_server::routes::reply;
 
     /// Epoch Settings
     pub async fn epoch_settings(
+        version: HttpApiVersion,
         logger: Logger,
         epoch_service: EpochServiceWrapper,
         allowed_discriminants: BTreeSet<SignedEntityTypeDiscriminants>,
-    ) -> Result<impl warp::Reply, Infallible> {
+    ) -> Result<impl IntoResponse, Infallible> {
         let epoch_settings_message =
-            get_epoch_settings_message(epoch_service, allowed_discriminants).await;
+            get_epoch_settings_message(epoch_service, allowed_discriminants)
+                .await
+                .map(|message| message.adapt_to_version(version));
 
         match epoch_settings_message {
             Ok(message) => Ok(reply::json(&message, StatusCode::OK)),
@@ -106,14 +114,13 @@ mod handlers {
 
 #[cfg(test)]
 mod tests {
+    use axum::http::{Method, StatusCode};
     use serde_json::Value::Null;
     use std::collections::BTreeSet;
     use std::sync::Arc;
     use tokio::sync::RwLock;
-    use warp::{
-        http::{Method, StatusCode},
-        test::request,
-    };
+
+    use crate::http_server::routes::test_utils::request;
 
     use mithril_common::{
         entities::{
@@ -129,17 +136,8 @@ mod tests {
 
     use super::*;
 
-    fn setup_router(
-        state: RouterState,
-    ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-        let cors = warp::cors()
-            .allow_any_origin()
-            .allow_headers(vec!["content-type"])
-            .allow_methods(vec![Method::GET, Method::POST, Method::OPTIONS]);
-
-        warp::any()
-            .and(warp::path(SERVER_BASE_PATH))
-            .and(routes(&state).with(cors))
+    fn setup_router(state: RouterState) -> Router {
+        crate::http_server::routes::test_utils::setup_router(state, |_| routes(HttpApiVersion::V1))
     }
 
     #[tokio::test]
@@ -1,176 +1,327 @@
-use slog::{debug, Logger};
+use async_trait::async_trait;
+use axum::extract::{
+    ConnectInfo, FromRequest, FromRequestParts, MatchedPath, NestedPath, Request, State,
+};
+use axum::http::{header, request::Parts, Extensions, HeaderValue, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use slog::debug;
 use std::convert::Infallible;
+use std::net::SocketAddr;
 use std::sync::Arc;
-use warp::Filter;
-
-use mithril_common::api_version::APIVersionProvider;
-
-use crate::database::repository::SignerGetter;
-use crate::dependency_injection::EpochServiceWrapper;
-use crate::event_store::{EventMessage, TransmitterService};
-use crate::http_server::routes::http_server_child_logger;
-use crate::http_server::routes::router::{RouterConfig, RouterState};
-use crate::services::{CertifierService, MessageService, ProverService, SignedEntityService};
-use crate::{
-    CertificatePendingStore, MetricsService, SignerRegisterer, SingleSignatureAuthenticator,
-    VerificationKeyStorer,
-};
+use std::time::Instant;
+use subtle::ConstantTimeEq;
+use tower_http::validate_request::ValidateRequestHeaderLayer;
 
-/// Extract a value from the configuration
-pub fn extract_config<D: Clone + Send>(
-    state: &RouterState,
-    extract: fn(&RouterConfig) -> D,
-) -> impl Filter<Extract = (D,), Error = Infallible> + Clone {
-    let config_value = extract(&state.configuration);
-    warp::any().map(move || config_value.clone())
-}
+use mithril_common::entities::PartyId;
+use mithril_common::error_codes::MithrilErrorCode;
+use mithril_common::logging::CorrelationId;
+use mithril_common::messages::{RegisterSignatureMessage, RegisterSignerMessage};
+use mithril_common::MITHRIL_CORRELATION_ID_HEADER;
 
-/// With logger middleware
-pub(crate) fn with_logger(
-    router_state: &RouterState,
-) -> impl Filter<Extract = (Logger,), Error = Infallible> + Clone {
-    let logger = http_server_child_logger(&router_state.dependencies.root_logger);
-    warp::any().map(move || logger.clone())
+use crate::http_server::routes::reply;
+use crate::http_server::routes::router::RouterState;
+use crate::http_server::{
+    AuthenticatedSigner, HttpAccessLogEntry, RequestBodyViolation, RouteGroup,
+};
+use crate::services::{AuditAction, AuditEntry, AuditOutcome};
+
+/// Error replied when a request targets a route of a disabled group
+#[derive(Debug, Serialize)]
+struct RouteGroupDisabledMessage {
+    code: MithrilErrorCode,
+    label: &'static str,
+    message: String,
+    route_group: RouteGroup,
 }
 
 /// Log to apply each time a route is called
 ///
 /// Example of log produced: `POST /aggregator/register-signatures 202 Accepted`
-pub(crate) fn log_route_call(
-    router_state: &RouterState,
-) -> warp::log::Log<impl Fn(warp::log::Info<'_>) + Clone> {
-    let logger = http_server_child_logger(&router_state.dependencies.root_logger);
-    warp::log::custom(move |info| {
-        debug!(
-            logger,
-            "{} {} {}",
-            info.method(),
-            info.path(),
-            info.status()
-        )
-    })
-}
+pub(crate) async fn log_route_call(
+    State(state): State<Arc<RouterState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let method = request.method().clone();
+    let path = request.uri().path().to_string();
+    let response = next.run(request).await;
+    debug!(state.logger, "{} {} {}", method, path, response.status());
 
-/// With certificate pending store
-pub(crate) fn with_certificate_pending_store(
-    router_state: &RouterState,
-) -> impl Filter<Extract = (Arc<CertificatePendingStore>,), Error = Infallible> + Clone {
-    let certificate_pending_store = router_state.dependencies.certificate_pending_store.clone();
-    warp::any().map(move || certificate_pending_store.clone())
+    response
 }
 
-/// With signer registerer middleware
-pub fn with_signer_registerer(
-    router_state: &RouterState,
-) -> impl Filter<Extract = (Arc<dyn SignerRegisterer>,), Error = Infallible> + Clone {
-    let signer_register = router_state.dependencies.signer_registerer.clone();
-    warp::any().map(move || signer_register.clone())
-}
+/// Serve each request with a correlation id attached to its logs, the one sent by the client in
+/// the correlation id header if it's valid or a new one, and send it back in the response headers
+pub(crate) async fn with_correlation_id(request: Request, next: Next) -> Response {
+    let correlation_id = request
+        .headers()
+        .get(MITHRIL_CORRELATION_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(CorrelationId::parse)
+        .unwrap_or_else(CorrelationId::generate);
+    let header_value = HeaderValue::from_str(correlation_id.as_str());
+    let mut response = correlation_id.scope(next.run(request)).await;
+    if let Ok(header_value) = header_value {
+        response
+            .headers_mut()
+            .insert(MITHRIL_CORRELATION_ID_HEADER, header_value);
+    }
 
-/// With signer getter middleware
-pub fn with_signer_getter(
-    router_state: &RouterState,
-) -> impl Filter<Extract = (Arc<dyn SignerGetter>,), Error = Infallible> + Clone {
-    let signer_getter = router_state.dependencies.signer_getter.clone();
-    warp::any().map(move || signer_getter.clone())
+    response
 }
 
-/// With Event transmitter middleware
-pub fn with_event_transmitter(
-    router_state: &RouterState,
-) -> impl Filter<Extract = (Arc<TransmitterService<EventMessage>>,), Error = Infallible> + Clone {
-    let event_transmitter = router_state.dependencies.event_transmitter.clone();
-    warp::any().map(move || event_transmitter.clone())
+/// Expose the template of the matched route to the outer middlewares, that run before the routing
+/// and so can't read it from the request, by copying it to the response extensions
+pub(crate) async fn with_matched_path(request: Request, next: Next) -> Response {
+    let matched_path = request.extensions().get::<MatchedPath>().cloned();
+    let mut response = next.run(request).await;
+    if let Some(matched_path) = matched_path {
+        response.extensions_mut().insert(matched_path);
+    }
+
+    response
 }
 
-/// With certifier service middleware
-pub fn with_certifier_service(
-    router_state: &RouterState,
-) -> impl Filter<Extract = (Arc<dyn CertifierService>,), Error = Infallible> + Clone {
-    let certifier_service = router_state.dependencies.certifier_service.clone();
-    warp::any().map(move || certifier_service.clone())
+/// Record each route call in the HTTP access log
+pub(crate) async fn log_access(
+    State(state): State<Arc<RouterState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let started_at = Instant::now();
+    let mut entry = HttpAccessLogEntry::from_request(&request);
+    let response = next.run(request).await;
+    entry.status = response.status().as_u16();
+    entry.latency_ms = started_at.elapsed().as_millis() as u64;
+    entry.route = response
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|matched_path| matched_path.as_str().to_string());
+    state.dependencies.http_access_logger.log(&entry);
+
+    response
 }
 
-/// With epoch service middleware
-pub fn with_epoch_service(
-    router_state: &RouterState,
-) -> impl Filter<Extract = (EpochServiceWrapper,), Error = Infallible> + Clone {
-    let epoch_service = router_state.dependencies.epoch_service.clone();
-    warp::any().map(move || epoch_service.clone())
+fn remote_address(extensions: &Extensions) -> Option<SocketAddr> {
+    extensions
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(address)| *address)
 }
 
-/// With signed entity service
-pub fn with_signed_entity_service(
-    router_state: &RouterState,
-) -> impl Filter<Extract = (Arc<dyn SignedEntityService>,), Error = Infallible> + Clone {
-    let signed_entity_service = router_state.dependencies.signed_entity_service.clone();
-    warp::any().map(move || signed_entity_service.clone())
+/// Remote address of the client, read from the connection accepted by axum or by the TLS server
+pub(crate) struct RemoteAddress(pub Option<SocketAddr>);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for RemoteAddress {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Infallible> {
+        Ok(Self(remote_address(&parts.extensions)))
+    }
 }
 
-/// With verification key store
-pub fn with_verification_key_store(
-    router_state: &RouterState,
-) -> impl Filter<Extract = (Arc<dyn VerificationKeyStorer>,), Error = Infallible> + Clone {
-    let verification_key_store = router_state.dependencies.verification_key_store.clone();
-    warp::any().map(move || verification_key_store.clone())
+/// Path of a route as declared in the OpenAPI specification, without the prefixes under which
+/// the route is mounted (`/aggregator`, `/aggregator/v1`, ...)
+fn route_path(extensions: &Extensions) -> String {
+    let matched_path = extensions
+        .get::<MatchedPath>()
+        .map(MatchedPath::as_str)
+        .unwrap_or_default();
+    let nested_path = extensions
+        .get::<NestedPath>()
+        .map(NestedPath::as_str)
+        .unwrap_or_default();
+
+    matched_path
+        .strip_prefix(nested_path.trim_end_matches('/'))
+        .unwrap_or(matched_path)
+        .to_string()
 }
 
-/// With API version provider
-pub fn with_api_version_provider(
-    router_state: &RouterState,
-) -> impl Filter<Extract = (Arc<APIVersionProvider>,), Error = Infallible> + Clone {
-    let api_version_provider = router_state.dependencies.api_version_provider.clone();
-    warp::any().map(move || api_version_provider.clone())
+fn invalid_request_body(violations: Vec<RequestBodyViolation>) -> Response {
+    let violations = violations
+        .iter()
+        .map(RequestBodyViolation::to_string)
+        .collect::<Vec<_>>();
+
+    reply::bad_request(
+        MithrilErrorCode::InvalidRequestPayload,
+        "invalid_request_body".to_string(),
+        format!(
+            "The request body does not match the schema of the route: {}",
+            violations.join("; ")
+        ),
+    )
 }
 
-/// With Message service
-pub fn with_http_message_service(
-    router_state: &RouterState,
-) -> impl Filter<Extract = (Arc<dyn MessageService>,), Error = Infallible> + Clone {
-    let message_service = router_state.dependencies.message_service.clone();
-    warp::any().map(move || message_service.clone())
+/// Message read from the JSON request body of a POST route, the request is rejected with the
+/// violations listed in its error message if the body does not match the OpenAPI schema of the
+/// route
+pub(crate) struct ValidatedJson<T>(pub T);
+
+#[async_trait]
+impl<T> FromRequest<Arc<RouterState>> for ValidatedJson<T>
+where
+    T: DeserializeOwned,
+{
+    type Rejection = Response;
+
+    async fn from_request(request: Request, state: &Arc<RouterState>) -> Result<Self, Response> {
+        let path = route_path(request.extensions());
+        let Json(body) = Json::<serde_json::Value>::from_request(request, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+
+        state
+            .dependencies
+            .request_body_validator
+            .validate(&path, &body)
+            .map_err(invalid_request_body)?;
+
+        serde_json::from_value::<T>(body)
+            .map(Self)
+            .map_err(|error| {
+                invalid_request_body(vec![RequestBodyViolation {
+                    path: String::new(),
+                    message: error.to_string(),
+                }])
+            })
+    }
 }
 
-/// With Prover service
-pub fn with_prover_service(
-    router_state: &RouterState,
-) -> impl Filter<Extract = (Arc<dyn ProverService>,), Error = Infallible> + Clone {
-    let prover_service = router_state.dependencies.prover_service.clone();
-    warp::any().map(move || prover_service.clone())
+/// Message sent by a signer, identified by its party id
+pub(crate) trait SignerMessage {
+    /// Party id of the signer that sent the message
+    fn party_id(&self) -> &PartyId;
 }
 
-/// With Single Signature Authenticator
-pub fn with_single_signature_authenticator(
-    router_state: &RouterState,
-) -> impl Filter<Extract = (Arc<SingleSignatureAuthenticator>,), Error = Infallible> + Clone {
-    let single_signer_authenticator = router_state
-        .dependencies
-        .single_signer_authenticator
-        .clone();
-    warp::any().map(move || single_signer_authenticator.clone())
+impl SignerMessage for RegisterSignerMessage {
+    fn party_id(&self) -> &PartyId {
+        &self.party_id
+    }
 }
 
-/// With Metrics service
-pub fn with_metrics_service(
-    router_state: &RouterState,
-) -> impl Filter<Extract = (Arc<MetricsService>,), Error = Infallible> + Clone {
-    let metrics_service = router_state.dependencies.metrics_service.clone();
-    warp::any().map(move || metrics_service.clone())
+impl SignerMessage for RegisterSignatureMessage {
+    fn party_id(&self) -> &PartyId {
+        &self.party_id
+    }
 }
 
-pub mod validators {
-    use crate::http_server::validators::ProverTransactionsHashValidator;
+/// Signer message read from the request body, the request is rejected if the signers must
+/// authenticate with a client certificate and the certificate of the connection does not
+/// match the party id of the message
+pub(crate) struct AuthenticatedSignerMessage<T>(pub T);
 
-    use super::*;
+#[async_trait]
+impl<T> FromRequest<Arc<RouterState>> for AuthenticatedSignerMessage<T>
+where
+    T: DeserializeOwned + SignerMessage,
+{
+    type Rejection = Response;
 
-    /// With Prover Transactions Hash Validator
-    pub fn with_prover_transactions_hash_validator(
-        router_state: &RouterState,
-    ) -> impl Filter<Extract = (ProverTransactionsHashValidator,), Error = Infallible> + Clone {
-        let max_hashes = router_state
-            .configuration
-            .cardano_transactions_prover_max_hashes_allowed_by_request;
+    async fn from_request(request: Request, state: &Arc<RouterState>) -> Result<Self, Response> {
+        let authenticated_signer = request.extensions().get::<AuthenticatedSigner>().cloned();
+        let ValidatedJson(message) = ValidatedJson::<T>::from_request(request, state).await?;
 
-        warp::any().map(move || ProverTransactionsHashValidator::new(max_hashes))
+        match authenticated_signer {
+            _ if !state.configuration.require_signer_client_certificate => Ok(Self(message)),
+            Some(signer) if &signer.party_id == message.party_id() => Ok(Self(message)),
+            _ => Err(reply::empty(StatusCode::FORBIDDEN)),
+        }
     }
 }
+
+/// Reject the requests of the signers whose address is not allowed by the signer address rules
+// The rejection of the `ValidateRequestHeaderLayer` is a whole response
+#[allow(clippy::result_large_err)]
+pub(crate) fn allowed_signer_address(
+    router_state: &RouterState,
+) -> ValidateRequestHeaderLayer<impl FnMut(&mut Request) -> Result<(), Response> + Clone> {
+    let signer_address_filter = router_state.dependencies.signer_address_filter.clone();
+    ValidateRequestHeaderLayer::custom(move |request: &mut Request| {
+        let remote_ip = remote_address(request.extensions()).map(|address| address.ip());
+        if signer_address_filter.is_allowed(remote_ip) {
+            Ok(())
+        } else {
+            Err(reply::empty(StatusCode::FORBIDDEN))
+        }
+    })
+}
+
+/// Reject the requests to the routes of a group disabled by the route feature flags
+// The rejection of the `ValidateRequestHeaderLayer` is a whole response
+#[allow(clippy::result_large_err)]
+pub(crate) fn route_group_enabled(
+    router_state: &RouterState,
+    group: RouteGroup,
+) -> ValidateRequestHeaderLayer<impl FnMut(&mut Request) -> Result<(), Response> + Clone> {
+    let route_feature_flags = router_state.dependencies.route_feature_flags.clone();
+    ValidateRequestHeaderLayer::custom(move |_request: &mut Request| {
+        if route_feature_flags.is_enabled(group) {
+            Ok(())
+        } else {
+            Err(reply::json(
+                &RouteGroupDisabledMessage {
+                    code: MithrilErrorCode::AggregatorUnavailable,
+                    label: "route_group_disabled",
+                    message: format!("The '{group}' routes are disabled on this aggregator"),
+                    route_group: group,
+                },
+                StatusCode::SERVICE_UNAVAILABLE,
+            ))
+        }
+    })
+}
+
+/// Reject the admin API requests without the configured bearer token, the rejected requests are
+/// recorded in the audit log
+// The rejection of the `ValidateRequestHeaderLayer` is a whole response
+#[allow(clippy::result_large_err)]
+pub(crate) fn admin_authorized(
+    router_state: &RouterState,
+) -> ValidateRequestHeaderLayer<impl FnMut(&mut Request) -> Result<(), Response> + Clone> {
+    let expected_authorization = router_state
+        .configuration
+        .admin_api_token
+        .as_ref()
+        .map(|token| format!("Bearer {token}"));
+    let audit_logger = router_state.dependencies.audit_logger.clone();
+    ValidateRequestHeaderLayer::custom(move |request: &mut Request| {
+        let authorization = request
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok());
+        match (&expected_authorization, authorization) {
+            (None, _) => Err(reply::empty(StatusCode::NOT_FOUND)),
+            // Constant time comparison so that the token can not be guessed from the response time
+            (Some(expected), Some(authorization))
+                if bool::from(expected.as_bytes().ct_eq(authorization.as_bytes())) =>
+            {
+                Ok(())
+            }
+            (Some(_), _) => {
+                audit_logger.record(
+                    AuditEntry::new(AuditAction::AdminApiAccessDenied)
+                        .with_outcome(AuditOutcome::Failure(
+                            if authorization.is_some() {
+                                "invalid token"
+                            } else {
+                                "missing token"
+                            }
+                            .to_string(),
+                        ))
+                        .with_source_address(remote_address(request.extensions()))
+                        .with_details(serde_json::json!({
+                            "method": request.method().as_str(),
+                            "path": request.uri().path(),
+                        })),
+                );
+                Err(reply::empty(StatusCode::UNAUTHORIZED))
+            }
+        }
+    })
+}
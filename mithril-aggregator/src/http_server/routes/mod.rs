@@ -1,3 +1,5 @@
+mod admin_routes;
+mod api_versions;
 mod artifact_routes;
 mod certificate_routes;
 mod epoch_routes;
@@ -9,6 +11,9 @@ pub mod router;
 mod signatures_routes;
 mod signer_routes;
 mod statistics_routes;
+mod status_routes;
+#[cfg(test)]
+pub(crate) mod test_utils;
 
 /// Match the given result and do an early return with an internal server error (500)
 /// if it was an Error. Else return the unwrapped value.
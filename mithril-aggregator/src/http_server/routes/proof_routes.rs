@@ -1,8 +1,13 @@
+use axum::extract::{Query, State};
+use axum::routing::get;
+use axum::Router;
 use serde::{Deserialize, Serialize};
-use warp::Filter;
+use std::sync::Arc;
 
 use crate::http_server::routes::middlewares;
 use crate::http_server::routes::router::RouterState;
+use crate::http_server::validators::ProverTransactionsHashValidator;
+use crate::http_server::RouteGroup;
 
 #[derive(Deserialize, Serialize, Debug)]
 struct CardanoTransactionProofQueryParams {
@@ -25,36 +30,51 @@ impl CardanoTransactionProofQueryParams {
     }
 }
 
-pub fn routes(
-    router_state: &RouterState,
-) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+pub fn routes(router_state: &RouterState) -> Router<Arc<RouterState>> {
     proof_cardano_transaction(router_state)
 }
 
 /// GET /proof/cardano-transaction
-fn proof_cardano_transaction(
-    router_state: &RouterState,
-) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-    warp::path!("proof" / "cardano-transaction")
-        .and(warp::get())
-        .and(warp::query::<CardanoTransactionProofQueryParams>())
-        .and(middlewares::with_logger(router_state))
-        .and(middlewares::with_signed_entity_service(router_state))
-        .and(middlewares::validators::with_prover_transactions_hash_validator(router_state))
-        .and(middlewares::with_prover_service(router_state))
-        .and(middlewares::with_metrics_service(router_state))
-        .and_then(handlers::proof_cardano_transaction)
+fn proof_cardano_transaction(router_state: &RouterState) -> Router<Arc<RouterState>> {
+    Router::new()
+        .route(
+            "/proof/cardano-transaction",
+            get(
+                |State(state): State<Arc<RouterState>>,
+                 Query(transaction_parameters): Query<CardanoTransactionProofQueryParams>| async move {
+                    handlers::proof_cardano_transaction(
+                        transaction_parameters,
+                        state.logger.clone(),
+                        state.dependencies.signed_entity_service.clone(),
+                        ProverTransactionsHashValidator::new(
+                            state
+                                .configuration
+                                .cardano_transactions_prover_max_hashes_allowed_by_request,
+                        ),
+                        state.dependencies.prover_service.clone(),
+                        state.dependencies.metrics_service.clone(),
+                    )
+                    .await
+                },
+            ),
+        )
+        .route_layer(middlewares::route_group_enabled(
+            router_state,
+            RouteGroup::Proofs,
+        ))
 }
 
 mod handlers {
+    use axum::http::StatusCode;
+    use axum::response::IntoResponse;
     use mithril_common::{
         entities::{CardanoTransactionsSnapshot, SignedEntity},
+        error_codes::MithrilErrorCode,
         messages::CardanoTransactionsProofsMessage,
         StdResult,
     };
     use slog::{debug, warn, Logger};
     use std::{convert::Infallible, sync::Arc};
-    use warp::http::StatusCode;
 
     use crate::{
         http_server::{routes::reply, validators::ProverTransactionsHashValidator},
@@ -72,7 +92,7 @@ mod handlers {
         validator: ProverTransactionsHashValidator,
         prover_service: Arc<dyn ProverService>,
         metrics_service: Arc<MetricsService>,
-    ) -> Result<impl warp::Reply, Infallible> {
+    ) -> Result<impl IntoResponse, Infallible> {
         metrics_service
             .get_proof_cardano_transaction_total_proofs_served_since_startup()
             .increment();
@@ -85,7 +105,11 @@ mod handlers {
 
         if let Err(error) = validator.validate(&transaction_hashes) {
             warn!(logger, "proof_cardano_transaction::bad_request");
-            return Ok(reply::bad_request(error.label, error.message));
+            return Ok(reply::bad_request(
+                MithrilErrorCode::InvalidRequestPayload,
+                error.label,
+                error.message,
+            ));
         }
 
         let sanitized_hashes = transaction_parameters.sanitize();
@@ -139,13 +163,12 @@ mod handlers {
 #[cfg(test)]
 mod tests {
     use anyhow::anyhow;
+    use axum::http::{Method, StatusCode};
     use serde_json::Value::Null;
     use std::sync::Arc;
     use std::vec;
-    use warp::{
-        http::{Method, StatusCode},
-        test::request,
-    };
+
+    use crate::http_server::routes::test_utils::request;
 
     use mithril_common::{
         entities::{
@@ -159,17 +182,8 @@ mod tests {
 
     use super::*;
 
-    fn setup_router(
-        state: RouterState,
-    ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-        let cors = warp::cors()
-            .allow_any_origin()
-            .allow_headers(vec!["content-type"])
-            .allow_methods(vec![Method::GET, Method::POST, Method::OPTIONS]);
-
-        warp::any()
-            .and(warp::path(SERVER_BASE_PATH))
-            .and(routes(&state).with(cors))
+    fn setup_router(state: RouterState) -> Router {
+        crate::http_server::routes::test_utils::setup_router(state, routes)
     }
 
     #[tokio::test]
@@ -318,6 +332,30 @@ mod tests {
         .unwrap();
     }
 
+    #[tokio::test]
+    async fn proof_cardano_transaction_unavailable_when_the_proofs_route_group_is_disabled() {
+        let dependency_manager = initialize_dependencies().await;
+        dependency_manager
+            .route_feature_flags
+            .set_enabled(RouteGroup::Proofs, false);
+
+        let response = request()
+            .method(Method::GET.as_str())
+            .path(&format!(
+                "/{SERVER_BASE_PATH}/proof/cardano-transaction?transaction_hashes={}",
+                fake_data::transaction_hashes()[0],
+            ))
+            .reply(&setup_router(RouterState::new_with_dummy_config(Arc::new(
+                dependency_manager,
+            ))))
+            .await;
+
+        assert_eq!(StatusCode::SERVICE_UNAVAILABLE, response.status());
+        let message: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!("route_group_disabled", message["label"]);
+        assert_eq!("proofs", message["route_group"]);
+    }
+
     #[tokio::test]
     async fn proof_cardano_transaction_ko() {
         let mut dependency_manager = initialize_dependencies().await;
@@ -1,73 +1,130 @@
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
 use serde::Serialize;
-use warp::http::StatusCode;
 
 use mithril_common::entities::{ClientError, ServerError};
+use mithril_common::error_codes::MithrilErrorCode;
 use mithril_common::StdError;
 use mithril_persistence::sqlite::error::{SqliteError, SQLITE_BUSY};
 
 use crate::tools::downcast_check;
 use crate::SignerRegistrationError;
 
-pub fn json<T>(value: &T, status_code: StatusCode) -> Box<dyn warp::Reply>
+pub fn json<T>(value: &T, status_code: StatusCode) -> Response
 where
     T: Serialize,
 {
-    Box::new(warp::reply::with_status(
-        warp::reply::json(value),
-        status_code,
-    ))
+    (status_code, Json(value)).into_response()
 }
 
-pub fn empty(status_code: StatusCode) -> Box<dyn warp::Reply> {
-    Box::new(warp::reply::with_status(warp::reply::reply(), status_code))
+pub fn empty(status_code: StatusCode) -> Response {
+    status_code.into_response()
 }
 
-pub fn bad_request(label: String, message: String) -> Box<dyn warp::Reply> {
-    json(&ClientError::new(label, message), StatusCode::BAD_REQUEST)
+pub fn bad_request(code: MithrilErrorCode, label: String, message: String) -> Response {
+    json(
+        &ClientError::new(label, message).with_code(code),
+        StatusCode::BAD_REQUEST,
+    )
 }
 
-pub fn server_error<E: Into<StdError>>(error: E) -> Box<dyn warp::Reply> {
+pub fn server_error<E: Into<StdError>>(error: E) -> Response {
     let std_error: StdError = error.into();
-    let status_code = {
-        let mut code = StatusCode::INTERNAL_SERVER_ERROR;
+    let (status_code, error_code) = {
+        let mut status_code = StatusCode::INTERNAL_SERVER_ERROR;
+        let mut error_code = MithrilErrorCode::find_in(&std_error)
+            .unwrap_or(MithrilErrorCode::AggregatorInternalError);
 
         if downcast_check::<SqliteError>(&std_error, |e| {
             e.code.is_some_and(|code| code == SQLITE_BUSY)
         }) {
-            code = StatusCode::SERVICE_UNAVAILABLE;
+            status_code = StatusCode::SERVICE_UNAVAILABLE;
+            error_code = MithrilErrorCode::DatabaseBusy;
         }
 
         if downcast_check::<SignerRegistrationError>(&std_error, |e| {
             matches!(e, SignerRegistrationError::RegistrationRoundNotYetOpened)
         }) {
-            code = StatusCode::SERVICE_UNAVAILABLE;
+            status_code = StatusCode::SERVICE_UNAVAILABLE;
+            error_code = MithrilErrorCode::SignerRegistrationRoundNotOpened;
         }
 
-        code
+        (status_code, error_code)
     };
 
-    json(&ServerError::new(format!("{std_error:?}")), status_code)
+    json(
+        &ServerError::new(format!("{std_error:?}")).with_code(error_code),
+        status_code,
+    )
 }
 
-pub fn internal_server_error<T: Into<ServerError>>(message: T) -> Box<dyn warp::Reply> {
-    json(&message.into(), StatusCode::INTERNAL_SERVER_ERROR)
+pub fn internal_server_error<T: Into<ServerError>>(message: T) -> Response {
+    json(
+        &with_default_code(message.into(), MithrilErrorCode::AggregatorInternalError),
+        StatusCode::INTERNAL_SERVER_ERROR,
+    )
 }
 
-pub fn service_unavailable<T: Into<ServerError>>(message: T) -> Box<dyn warp::Reply> {
-    json(&message.into(), StatusCode::SERVICE_UNAVAILABLE)
+pub fn service_unavailable<T: Into<ServerError>>(message: T) -> Response {
+    json(
+        &with_default_code(message.into(), MithrilErrorCode::AggregatorUnavailable),
+        StatusCode::SERVICE_UNAVAILABLE,
+    )
+}
+
+fn with_default_code(error: ServerError, code: MithrilErrorCode) -> ServerError {
+    match error.code {
+        Some(_) => error,
+        None => error.with_code(code),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use anyhow::anyhow;
-    use warp::Reply;
 
     use super::*;
 
+    async fn error_code_of_reply(reply: Response) -> Option<MithrilErrorCode> {
+        let body = axum::body::to_bytes(reply.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let error: ServerError = serde_json::from_slice(&body).unwrap();
+
+        error.code
+    }
+
+    #[tokio::test]
+    async fn test_server_error_reply_contains_the_error_code() {
+        assert_eq!(
+            Some(MithrilErrorCode::AggregatorInternalError),
+            error_code_of_reply(server_error(anyhow!("Some error"))).await
+        );
+        assert_eq!(
+            Some(MithrilErrorCode::InvalidEpoch),
+            error_code_of_reply(server_error(
+                anyhow!("Some error").context(MithrilErrorCode::InvalidEpoch)
+            ))
+            .await
+        );
+        assert_eq!(
+            Some(MithrilErrorCode::SignerRegistrationRoundNotOpened),
+            error_code_of_reply(server_error(
+                SignerRegistrationError::RegistrationRoundNotYetOpened
+            ))
+            .await
+        );
+        assert_eq!(
+            Some(MithrilErrorCode::AggregatorUnavailable),
+            error_code_of_reply(service_unavailable("Not now")).await
+        );
+    }
+
     #[test]
     fn test_server_error_convert_std_error_to_500_by_default() {
         let error = anyhow!("Some error");
-        let response = server_error(error).into_response();
+        let response = server_error(error);
 
         assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, response.status());
     }
@@ -78,7 +135,7 @@ mod tests {
             code: Some(SQLITE_BUSY),
             message: None,
         };
-        let response = server_error(res).into_response();
+        let response = server_error(res);
 
         assert_eq!(StatusCode::SERVICE_UNAVAILABLE, response.status());
 
@@ -87,7 +144,7 @@ mod tests {
             code: Some(SQLITE_BUSY),
             message: None,
         });
-        let response = server_error(res).into_response();
+        let response = server_error(res);
 
         assert_eq!(StatusCode::SERVICE_UNAVAILABLE, response.status());
     }
@@ -95,13 +152,13 @@ mod tests {
     #[test]
     fn test_server_error_convert_signer_registration_round_not_yet_opened_to_503() {
         let err = SignerRegistrationError::RegistrationRoundNotYetOpened;
-        let response = server_error(err).into_response();
+        let response = server_error(err);
 
         assert_eq!(StatusCode::SERVICE_UNAVAILABLE, response.status());
 
         // Wrapping the error in a StdError should also work
         let err = anyhow!(SignerRegistrationError::RegistrationRoundNotYetOpened);
-        let response = server_error(err).into_response();
+        let response = server_error(err);
 
         assert_eq!(StatusCode::SERVICE_UNAVAILABLE, response.status());
     }
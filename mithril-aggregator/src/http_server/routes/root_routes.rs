@@ -1,46 +1,54 @@
-use super::middlewares;
+use axum::extract::State;
+use axum::routing::get;
+use axum::Router;
+use std::sync::Arc;
+
 use crate::http_server::routes::router::RouterState;
-use warp::Filter;
 
-pub fn routes(
-    router_state: &RouterState,
-) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-    root(router_state)
+pub fn routes() -> Router<Arc<RouterState>> {
+    root()
 }
 
 /// GET /
-fn root(
-    router_state: &RouterState,
-) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-    warp::path::end()
-        .and(middlewares::with_logger(router_state))
-        .and(middlewares::with_api_version_provider(router_state))
-        .and(middlewares::extract_config(router_state, |config| {
-            config.allowed_discriminants.clone()
-        }))
-        .and(middlewares::extract_config(router_state, |config| {
-            config.cardano_transactions_prover_max_hashes_allowed_by_request
-        }))
-        .and(middlewares::extract_config(router_state, |config| {
-            config.cardano_transactions_signing_config.clone()
-        }))
-        .and_then(handlers::root)
+fn root() -> Router<Arc<RouterState>> {
+    Router::new().route(
+        "/",
+        get(|State(state): State<Arc<RouterState>>| async move {
+            handlers::root(
+                state.logger.clone(),
+                state.dependencies.api_version_provider.clone(),
+                state.configuration.allowed_discriminants.clone(),
+                state
+                    .configuration
+                    .cardano_transactions_prover_max_hashes_allowed_by_request,
+                state
+                    .configuration
+                    .cardano_transactions_signing_config
+                    .clone(),
+                state.configuration.network,
+                state.configuration.snapshot_compression_algorithm,
+            )
+            .await
+        }),
+    )
 }
 
 mod handlers {
     use std::collections::BTreeSet;
     use std::{convert::Infallible, sync::Arc};
 
+    use axum::http::StatusCode;
+    use axum::response::IntoResponse;
     use slog::Logger;
-    use warp::http::StatusCode;
 
     use mithril_common::api_version::APIVersionProvider;
     use mithril_common::entities::{
-        CardanoTransactionsSigningConfig, SignedEntityTypeDiscriminants,
+        CardanoTransactionsSigningConfig, CompressionAlgorithm, SignedEntityTypeDiscriminants,
     };
     use mithril_common::messages::{
         AggregatorCapabilities, AggregatorFeaturesMessage, CardanoTransactionsProverCapabilities,
     };
+    use mithril_common::CardanoNetwork;
 
     use crate::http_server::routes::reply::json;
     use crate::unwrap_to_internal_server_error;
@@ -52,18 +60,28 @@ mod handlers {
         allowed_signed_entity_type_discriminants: BTreeSet<SignedEntityTypeDiscriminants>,
         max_hashes_allowed_by_request: usize,
         cardano_transactions_signing_config: CardanoTransactionsSigningConfig,
-    ) -> Result<impl warp::Reply, Infallible> {
+        network: CardanoNetwork,
+        snapshot_compression_algorithm: CompressionAlgorithm,
+    ) -> Result<impl IntoResponse, Infallible> {
         let open_api_version = unwrap_to_internal_server_error!(
             api_version_provider.compute_current_version(),
             logger => "root::error"
         );
-
         let mut capabilities = AggregatorCapabilities {
             signed_entity_types: allowed_signed_entity_type_discriminants,
             cardano_transactions_prover: None,
             cardano_transactions_signing_config: None,
+            api_versions: vec![open_api_version.to_string()],
+            compression_algorithms: vec![],
         };
 
+        if capabilities
+            .signed_entity_types
+            .contains(&SignedEntityTypeDiscriminants::CardanoImmutableFilesFull)
+        {
+            capabilities.compression_algorithms = vec![snapshot_compression_algorithm];
+        }
+
         if capabilities
             .signed_entity_types
             .contains(&SignedEntityTypeDiscriminants::CardanoTransactions)
@@ -82,6 +100,8 @@ mod handlers {
                 open_api_version: open_api_version.to_string(),
                 documentation_url: env!("CARGO_PKG_HOMEPAGE").to_string(),
                 capabilities,
+                network: Some(network.to_string()),
+                network_magic: Some(network.code()),
             },
             StatusCode::OK,
         ))
@@ -93,34 +113,27 @@ mod tests {
     use crate::http_server::routes::router::RouterConfig;
     use crate::http_server::SERVER_BASE_PATH;
     use crate::initialize_dependencies;
+    use axum::http::{Method, StatusCode};
+    use mithril_common::api_version::APIVersionProvider;
     use mithril_common::entities::{
-        BlockNumber, CardanoTransactionsSigningConfig, SignedEntityTypeDiscriminants,
+        BlockNumber, CardanoTransactionsSigningConfig, CompressionAlgorithm,
+        SignedEntityTypeDiscriminants,
     };
     use mithril_common::messages::{
         AggregatorCapabilities, AggregatorFeaturesMessage, CardanoTransactionsProverCapabilities,
     };
     use mithril_common::test_utils::apispec::APISpec;
+    use semver::Version;
     use serde_json::Value::Null;
-    use std::collections::BTreeSet;
+    use std::collections::{BTreeSet, HashMap};
     use std::sync::Arc;
-    use warp::http::Method;
-    use warp::http::StatusCode;
-    use warp::test::request;
-    use warp::Filter;
+
+    use crate::http_server::routes::test_utils::request;
 
     use super::*;
 
-    fn setup_router(
-        state: RouterState,
-    ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-        let cors = warp::cors()
-            .allow_any_origin()
-            .allow_headers(vec!["content-type"])
-            .allow_methods(vec![Method::GET, Method::POST, Method::OPTIONS]);
-
-        warp::any()
-            .and(warp::path(SERVER_BASE_PATH))
-            .and(routes(&state).with(cors))
+    fn setup_router(state: RouterState) -> Router {
+        crate::http_server::routes::test_utils::setup_router(state, |_| routes())
     }
 
     #[tokio::test]
@@ -161,7 +174,7 @@ mod tests {
         assert_eq!(
             response_body,
             AggregatorFeaturesMessage {
-                open_api_version: expected_open_api_version,
+                open_api_version: expected_open_api_version.clone(),
                 documentation_url: env!("CARGO_PKG_HOMEPAGE").to_string(),
                 capabilities: AggregatorCapabilities {
                     signed_entity_types: BTreeSet::from_iter([
@@ -171,7 +184,11 @@ mod tests {
                     ]),
                     cardano_transactions_prover: None,
                     cardano_transactions_signing_config: None,
+                    api_versions: vec![expected_open_api_version],
+                    compression_algorithms: vec![CompressionAlgorithm::Zstandard],
                 },
+                network: Some("devnet".to_string()),
+                network_magic: Some(87),
             }
         );
 
@@ -231,6 +248,7 @@ mod tests {
                 .cardano_transactions_signing_config,
             Some(signing_config)
         );
+        assert!(response_body.capabilities.compression_algorithms.is_empty());
 
         APISpec::verify_conformity(
             APISpec::get_all_spec_files(),
@@ -243,4 +261,32 @@ mod tests {
         )
         .unwrap();
     }
+
+    #[tokio::test]
+    async fn test_root_route_advertises_the_api_version_served_by_the_aggregator() {
+        let mut dependency_manager = initialize_dependencies().await;
+        let mut api_version_provider =
+            APIVersionProvider::new(dependency_manager.era_checker.clone());
+        api_version_provider.update_open_api_versions(HashMap::from([(
+            "openapi.yaml".to_string(),
+            Version::new(9, 8, 7),
+        )]));
+        dependency_manager.api_version_provider = Arc::new(api_version_provider);
+
+        let response = request()
+            .method(Method::GET.as_str())
+            .path(&format!("/{SERVER_BASE_PATH}/"))
+            .reply(&setup_router(RouterState::new(
+                Arc::new(dependency_manager),
+                RouterConfig::dummy(),
+            )))
+            .await;
+
+        let response_body: AggregatorFeaturesMessage =
+            serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(
+            vec!["9.8.7".to_string()],
+            response_body.capabilities.api_versions
+        );
+    }
 }
@@ -1,34 +1,35 @@
+use crate::http_server::routes::api_versions::HttpApiVersion;
 use crate::http_server::routes::{
-    artifact_routes, certificate_routes, epoch_routes, http_server_child_logger, root_routes,
-    signatures_routes, signer_routes, statistics_routes,
+    admin_routes, artifact_routes, certificate_routes, epoch_routes, http_server_child_logger,
+    root_routes, signatures_routes, signer_routes, statistics_routes, status_routes,
 };
 use crate::http_server::SERVER_BASE_PATH;
 use crate::DependencyContainer;
 
 use mithril_common::api_version::APIVersionProvider;
-use mithril_common::entities::{CardanoTransactionsSigningConfig, SignedEntityTypeDiscriminants};
-use mithril_common::{CardanoNetwork, MITHRIL_API_VERSION_HEADER};
+use mithril_common::entities::{
+    CardanoTransactionsSigningConfig, CompressionAlgorithm, SignedEntityTypeDiscriminants,
+};
+use mithril_common::{
+    CardanoNetwork, MITHRIL_API_VERSION_HEADER, MITHRIL_CORRELATION_ID_HEADER,
+    MITHRIL_ORIGIN_TAG_HEADER,
+};
 
+use axum::extract::{Request, State};
+use axum::http::{header, HeaderName, HeaderValue, Method, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::Response;
+use axum::Router;
 use slog::{warn, Logger};
 use std::collections::BTreeSet;
 use std::path::PathBuf;
 use std::sync::Arc;
-use warp::http::Method;
-use warp::http::StatusCode;
-use warp::reject::Reject;
-use warp::{Filter, Rejection, Reply};
-
-use super::{middlewares, proof_routes};
-
-#[derive(Debug)]
-pub struct VersionMismatchError;
-
-impl Reject for VersionMismatchError {}
-
-#[derive(Debug)]
-pub struct VersionParseError;
+use tower::Layer;
+use tower_http::cors::{Any, CorsLayer};
+use tower_http::normalize_path::NormalizePathLayer;
+use tower_http::trace::TraceLayer;
 
-impl Reject for VersionParseError {}
+use super::{middlewares, proof_routes, reply};
 
 /// HTTP Server configuration
 pub struct RouterConfig {
@@ -38,6 +39,9 @@ pub struct RouterConfig {
     pub cardano_transactions_prover_max_hashes_allowed_by_request: usize,
     pub cardano_transactions_signing_config: CardanoTransactionsSigningConfig,
     pub snapshot_directory: PathBuf,
+    pub snapshot_compression_algorithm: CompressionAlgorithm,
+    pub require_signer_client_certificate: bool,
+    pub admin_api_token: Option<String>,
 }
 
 #[cfg(test)]
@@ -53,6 +57,9 @@ impl RouterConfig {
             cardano_transactions_prover_max_hashes_allowed_by_request: 1_000,
             cardano_transactions_signing_config: CardanoTransactionsSigningConfig::dummy(),
             snapshot_directory: PathBuf::from("/dummy/snapshot/directory"),
+            snapshot_compression_algorithm: CompressionAlgorithm::Zstandard,
+            require_signer_client_certificate: false,
+            admin_api_token: None,
         }
     }
 }
@@ -61,14 +68,18 @@ impl RouterConfig {
 pub struct RouterState {
     pub dependencies: Arc<DependencyContainer>,
     pub configuration: RouterConfig,
+    pub logger: Logger,
 }
 
 impl RouterState {
     /// `RouterState` factory
     pub fn new(dependencies: Arc<DependencyContainer>, configuration: RouterConfig) -> Self {
+        let logger = http_server_child_logger(&dependencies.root_logger);
+
         Self {
             dependencies,
             configuration,
+            logger,
         }
     }
 }
@@ -76,173 +87,381 @@ impl RouterState {
 #[cfg(test)]
 impl RouterState {
     pub fn new_with_dummy_config(dependencies: Arc<DependencyContainer>) -> Self {
-        Self {
-            dependencies,
-            configuration: RouterConfig::dummy(),
-        }
+        Self::new(dependencies, RouterConfig::dummy())
     }
 }
 
 /// Routes
-pub fn routes(
-    state: Arc<RouterState>,
-) -> impl Filter<Extract = (impl Reply,), Error = Rejection> + Clone {
-    let cors = warp::cors()
-        .allow_any_origin()
-        .allow_headers(vec!["content-type", MITHRIL_API_VERSION_HEADER])
-        .allow_methods(vec![Method::GET, Method::POST, Method::OPTIONS]);
-
-    warp::any()
-        .and(header_must_be(
-            state.dependencies.api_version_provider.clone(),
-            http_server_child_logger(&state.dependencies.root_logger),
-        ))
-        .and(warp::path(SERVER_BASE_PATH))
-        .and(
-            certificate_routes::routes(&state)
-                .or(artifact_routes::snapshot::routes(&state))
-                .or(artifact_routes::mithril_stake_distribution::routes(&state))
-                .or(artifact_routes::cardano_stake_distribution::routes(&state))
-                .or(artifact_routes::cardano_transaction::routes(&state))
-                .or(proof_routes::routes(&state))
-                .or(signer_routes::routes(&state))
-                .or(signatures_routes::routes(&state))
-                .or(epoch_routes::routes(&state))
-                .or(statistics_routes::routes(&state))
-                .or(root_routes::routes(&state))
-                .with(cors),
+pub fn routes(state: Arc<RouterState>) -> Router {
+    let cors = CorsLayer::new()
+        .allow_origin(Any)
+        .allow_headers([
+            header::CONTENT_TYPE,
+            HeaderName::from_static(MITHRIL_API_VERSION_HEADER),
+            HeaderName::from_static(MITHRIL_ORIGIN_TAG_HEADER),
+            HeaderName::from_static(MITHRIL_CORRELATION_ID_HEADER),
+        ])
+        .expose_headers([HeaderName::from_static(MITHRIL_CORRELATION_ID_HEADER)])
+        .allow_methods([Method::GET, Method::POST, Method::OPTIONS]);
+
+    let aggregator_routes = HttpApiVersion::ALL
+        .into_iter()
+        .fold(
+            api_routes(&state, HttpApiVersion::UNPREFIXED),
+            |routes, version| routes.merge(versioned_routes(&state, version)),
         )
-        .recover(handle_custom)
-        .and(middlewares::with_api_version_provider(&state))
-        .map(|reply, api_version_provider: Arc<APIVersionProvider>| {
-            warp::reply::with_header(
-                reply,
-                MITHRIL_API_VERSION_HEADER,
-                &api_version_provider
-                    .compute_current_version()
-                    .unwrap()
-                    .to_string(),
-            )
-        })
-        .with(middlewares::log_route_call(&state))
+        .layer(cors)
+        // The admin API is not exposed to the browsers of other origins
+        .merge(admin_routes::routes(&state))
+        .route_layer(middleware::from_fn(middlewares::with_matched_path));
+
+    let router = Router::new()
+        .nest(&format!("/{SERVER_BASE_PATH}"), aggregator_routes)
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            header_must_be,
+        ))
+        .layer(middleware::map_response_with_state(
+            state.clone(),
+            with_api_version_header,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            middlewares::log_route_call,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            middlewares::log_access,
+        ))
+        .layer(middleware::from_fn(middlewares::with_correlation_id))
+        .layer(TraceLayer::new_for_http())
+        .with_state(state);
+
+    trim_trailing_slash(router)
+}
+
+/// Remove the trailing slash of the paths before routing the requests, as the clients append the
+/// routes to an aggregator endpoint that ends with a `/` (ie: `GET /aggregator/`)
+pub(crate) fn trim_trailing_slash(router: Router) -> Router {
+    Router::new().fallback_service(NormalizePathLayer::trim_trailing_slash().layer(router))
+}
+
+/// Routes of a version of the API, mounted under its prefix
+fn versioned_routes(state: &RouterState, version: HttpApiVersion) -> Router<Arc<RouterState>> {
+    Router::new().nest(
+        &format!("/{}", version.prefix()),
+        api_routes(state, version),
+    )
+}
+
+/// Routes shared by all the versions of the API
+fn api_routes(state: &RouterState, version: HttpApiVersion) -> Router<Arc<RouterState>> {
+    certificate_routes::routes()
+        .merge(artifact_routes::snapshot::routes(state))
+        .merge(artifact_routes::mithril_stake_distribution::routes())
+        .merge(artifact_routes::cardano_stake_distribution::routes())
+        .merge(artifact_routes::cardano_transaction::routes())
+        .merge(proof_routes::routes(state))
+        .merge(signer_routes::routes(state))
+        .merge(signatures_routes::routes(state))
+        .merge(epoch_routes::routes(version))
+        .merge(statistics_routes::routes(state))
+        .merge(status_routes::routes())
+        .merge(root_routes::routes())
 }
 
 /// API Version verification
-fn header_must_be(
-    api_version_provider: Arc<APIVersionProvider>,
-    logger: Logger,
-) -> impl Filter<Extract = (), Error = Rejection> + Clone {
-    warp::header::optional(MITHRIL_API_VERSION_HEADER)
-        .and(warp::any().map(move || api_version_provider.clone()))
-        .and(warp::any().map(move || logger.clone()))
-        .and_then(
-            move |maybe_header: Option<String>,
-                  api_version_provider: Arc<APIVersionProvider>,
-                  logger: Logger| async move {
-                match maybe_header {
-                    None => Ok(()),
-                    Some(version) => match semver::Version::parse(&version) {
-                        Ok(version)
-                            if api_version_provider
-                                .compute_current_version_requirement()
-                                .unwrap()
-                                .matches(&version)
-                                .to_owned() =>
-                        {
-                            Ok(())
-                        }
-                        Ok(_version) => Err(warp::reject::custom(VersionMismatchError)),
-                        Err(err) => {
-                            warn!(logger, "api_version_check::parse_error"; "error" => ?err);
-                            Err(warp::reject::custom(VersionParseError))
-                        }
-                    },
-                }
-            },
-        )
-        .untuple_one()
+async fn header_must_be(
+    State(state): State<Arc<RouterState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let maybe_header = request
+        .headers()
+        .get(MITHRIL_API_VERSION_HEADER)
+        .map(|value| value.to_str().unwrap_or_default());
+
+    match check_api_version(
+        &state.dependencies.api_version_provider,
+        maybe_header,
+        &state.logger,
+    ) {
+        Ok(()) => next.run(request).await,
+        Err(status_code) => reply::empty(status_code),
+    }
 }
 
-pub async fn handle_custom(reject: Rejection) -> Result<impl Reply, Rejection> {
-    if reject.find::<VersionMismatchError>().is_some() {
-        Ok(StatusCode::PRECONDITION_FAILED)
-    } else {
-        Err(reject)
+/// Check that the API version sent by the client, if any, is compatible with the aggregator
+fn check_api_version(
+    api_version_provider: &APIVersionProvider,
+    maybe_header: Option<&str>,
+    logger: &Logger,
+) -> Result<(), StatusCode> {
+    match maybe_header {
+        None => Ok(()),
+        Some(version) => match semver::Version::parse(version) {
+            Ok(version)
+                if api_version_provider
+                    .compute_current_version_requirement()
+                    .unwrap()
+                    .matches(&version)
+                    .to_owned() =>
+            {
+                Ok(())
+            }
+            Ok(_version) => Err(StatusCode::PRECONDITION_FAILED),
+            Err(err) => {
+                warn!(logger, "api_version_check::parse_error"; "error" => ?err);
+                Err(StatusCode::BAD_REQUEST)
+            }
+        },
     }
 }
 
+/// Add the current API version to the headers of the responses
+async fn with_api_version_header(
+    State(state): State<Arc<RouterState>>,
+    mut response: Response,
+) -> Response {
+    let api_version = state
+        .dependencies
+        .api_version_provider
+        .compute_current_version()
+        .unwrap()
+        .to_string();
+    response.headers_mut().insert(
+        MITHRIL_API_VERSION_HEADER,
+        HeaderValue::from_str(&api_version).unwrap(),
+    );
+
+    response
+}
+
 #[cfg(test)]
 mod tests {
     use semver::Version;
     use std::collections::HashMap;
 
+    use tokio::sync::RwLock;
+
     use mithril_common::{
         entities::Epoch,
         era::{EraChecker, SupportedEra},
+        test_utils::{MithrilFixtureBuilder, TempDir},
     };
 
+    use crate::http_server::routes::test_utils::request;
+    use crate::http_server::{HttpAccessLogFormat, HttpAccessLogger};
+    use crate::initialize_dependencies;
+    use crate::services::FakeEpochService;
     use crate::test_tools::TestLogger;
 
     use super::*;
 
-    #[tokio::test]
-    async fn test_no_version() {
+    fn api_version_provider_with_open_api_version(version: Version) -> APIVersionProvider {
         let era_checker = EraChecker::new(SupportedEra::dummy(), Epoch(1));
-        let api_version_provider = Arc::new(APIVersionProvider::new(Arc::new(era_checker)));
-        let filters = header_must_be(api_version_provider, TestLogger::stdout());
-        warp::test::request()
-            .path("/aggregator/whatever")
-            .filter(&filters)
-            .await
+        let mut version_provider = APIVersionProvider::new(Arc::new(era_checker));
+        let mut open_api_versions = HashMap::new();
+        open_api_versions.insert("openapi.yaml".to_string(), version);
+        version_provider.update_open_api_versions(open_api_versions);
+
+        version_provider
+    }
+
+    #[test]
+    fn test_no_version() {
+        let era_checker = EraChecker::new(SupportedEra::dummy(), Epoch(1));
+        let api_version_provider = APIVersionProvider::new(Arc::new(era_checker));
+
+        check_api_version(&api_version_provider, None, &TestLogger::stdout())
             .expect("request without a version in headers should not be rejected");
     }
 
-    #[tokio::test]
-    async fn test_parse_version_error() {
+    #[test]
+    fn test_parse_version_error() {
         let era_checker = EraChecker::new(SupportedEra::dummy(), Epoch(1));
-        let api_version_provider = Arc::new(APIVersionProvider::new(Arc::new(era_checker)));
-        let filters = header_must_be(api_version_provider, TestLogger::stdout());
-        warp::test::request()
-            .header(MITHRIL_API_VERSION_HEADER, "not_a_version")
-            .path("/aggregator/whatever")
-            .filter(&filters)
-            .await
-            .expect_err(
-                r#"request with an unparsable version should be rejected with a version parse error"#,
-            );
+        let api_version_provider = APIVersionProvider::new(Arc::new(era_checker));
+
+        let status_code = check_api_version(
+            &api_version_provider,
+            Some("not_a_version"),
+            &TestLogger::stdout(),
+        )
+        .expect_err("request with an unparsable version should be rejected");
+
+        assert_eq!(StatusCode::BAD_REQUEST, status_code);
+    }
+
+    #[test]
+    fn test_bad_version() {
+        let api_version_provider =
+            api_version_provider_with_open_api_version(Version::new(1, 0, 0));
+
+        let status_code = check_api_version(
+            &api_version_provider,
+            Some("0.0.999"),
+            &TestLogger::stdout(),
+        )
+        .expect_err(r#"request with bad version "0.0.999" should be rejected"#);
+
+        assert_eq!(StatusCode::PRECONDITION_FAILED, status_code);
+    }
+
+    #[test]
+    fn test_good_version() {
+        let api_version_provider =
+            api_version_provider_with_open_api_version(Version::new(0, 1, 0));
+
+        check_api_version(&api_version_provider, Some("0.1.2"), &TestLogger::stdout())
+            .expect(r#"request with the good version "0.1.2" should not be rejected"#);
     }
 
     #[tokio::test]
-    async fn test_bad_version() {
-        let era_checker = EraChecker::new(SupportedEra::dummy(), Epoch(1));
-        let mut version_provider = APIVersionProvider::new(Arc::new(era_checker));
-        let mut open_api_versions = HashMap::new();
-        open_api_versions.insert("openapi.yaml".to_string(), Version::new(1, 0, 0));
-        version_provider.update_open_api_versions(open_api_versions);
-        let api_version_provider = Arc::new(version_provider);
-        let filters = header_must_be(api_version_provider, TestLogger::stdout());
-        warp::test::request()
+    async fn requests_with_a_bad_version_are_rejected_with_a_precondition_failed() {
+        let mut dependency_manager = initialize_dependencies().await;
+        dependency_manager.api_version_provider = Arc::new(
+            api_version_provider_with_open_api_version(Version::new(1, 0, 0)),
+        );
+        let router = routes(Arc::new(RouterState::new_with_dummy_config(Arc::new(
+            dependency_manager,
+        ))));
+
+        let response = request()
+            .path("/aggregator/status")
             .header(MITHRIL_API_VERSION_HEADER, "0.0.999")
-            .path("/aggregator/whatever")
-            .filter(&filters)
-            .await
-            .expect_err(r#"request with bad version "0.0.999" should be rejected with a version mismatch error"#);
+            .reply(&router)
+            .await;
+
+        assert_eq!(StatusCode::PRECONDITION_FAILED, response.status());
+        assert!(response.headers().contains_key(MITHRIL_API_VERSION_HEADER));
     }
 
     #[tokio::test]
-    async fn test_good_version() {
-        let era_checker = EraChecker::new(SupportedEra::dummy(), Epoch(1));
-        let mut version_provider = APIVersionProvider::new(Arc::new(era_checker));
-        let mut open_api_versions = HashMap::new();
-        open_api_versions.insert("openapi.yaml".to_string(), Version::new(0, 1, 0));
-        version_provider.update_open_api_versions(open_api_versions);
-        let api_version_provider = Arc::new(version_provider);
-        let filters = header_must_be(api_version_provider, TestLogger::stdout());
-        warp::test::request()
-            .header(MITHRIL_API_VERSION_HEADER, "0.1.2")
-            .path("/aggregator/whatever")
-            .filter(&filters)
-            .await
-            .expect(r#"request with the good version "0.1.2" should not be rejected"#);
+    async fn routes_are_mounted_under_versioned_prefixes() {
+        let mut dependency_manager = initialize_dependencies().await;
+        let fixture = MithrilFixtureBuilder::default().with_signers(3).build();
+        dependency_manager.epoch_service = Arc::new(RwLock::new(FakeEpochService::from_fixture(
+            Epoch(5),
+            &fixture,
+        )));
+        let router = routes(Arc::new(RouterState::new_with_dummy_config(Arc::new(
+            dependency_manager,
+        ))));
+
+        for (path, has_deprecated_fields) in [
+            ("/aggregator/epoch-settings", true),
+            ("/aggregator/v1/epoch-settings", true),
+            ("/aggregator/v2/epoch-settings", false),
+        ] {
+            let response = request().path(path).reply(&router).await;
+
+            assert_eq!(StatusCode::OK, response.status(), "path: {path}");
+            let message: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+            assert_eq!(
+                has_deprecated_fields,
+                message.get("protocol").is_some(),
+                "path: {path}"
+            );
+        }
+
+        let response = request()
+            .path("/aggregator/v3/epoch-settings")
+            .reply(&router)
+            .await;
+        assert_eq!(StatusCode::NOT_FOUND, response.status());
+    }
+
+    #[tokio::test]
+    async fn access_log_entries_contain_the_template_of_the_matched_route() {
+        let path = TempDir::create(
+            "http_server_router",
+            "access_log_entries_contain_the_template_of_the_matched_route",
+        )
+        .join("access.log");
+        let mut dependency_manager = initialize_dependencies().await;
+        dependency_manager.http_access_logger = Arc::new(
+            HttpAccessLogger::new(&path, HttpAccessLogFormat::Json, TestLogger::stdout()).unwrap(),
+        );
+        let router = routes(Arc::new(RouterState::new_with_dummy_config(Arc::new(
+            dependency_manager,
+        ))));
+
+        request()
+            .path("/aggregator/certificate/abc")
+            .reply(&router)
+            .await;
+        request().path("/aggregator/unknown").reply(&router).await;
+
+        let routes = std::fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap()["route"].clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                serde_json::json!("/aggregator/certificate/:certificate_hash"),
+                serde_json::Value::Null,
+            ],
+            routes
+        );
+    }
+
+    #[tokio::test]
+    async fn responses_carry_the_correlation_id_of_the_request() {
+        let router = routes(Arc::new(RouterState::new_with_dummy_config(Arc::new(
+            initialize_dependencies().await,
+        ))));
+
+        let response = request()
+            .path("/aggregator/status")
+            .header(MITHRIL_CORRELATION_ID_HEADER, "signer-cycle-42")
+            .reply(&router)
+            .await;
+        assert_eq!(
+            "signer-cycle-42",
+            response.headers()[MITHRIL_CORRELATION_ID_HEADER]
+        );
+
+        let response = request()
+            .path("/aggregator/status")
+            .header(MITHRIL_CORRELATION_ID_HEADER, "not a valid id")
+            .reply(&router)
+            .await;
+        let generated_id = response.headers()[MITHRIL_CORRELATION_ID_HEADER]
+            .to_str()
+            .unwrap();
+        assert_ne!("not a valid id", generated_id);
+        assert!(!generated_id.is_empty());
+    }
+
+    #[tokio::test]
+    async fn cross_origin_requests_are_not_allowed_on_the_admin_routes() {
+        let router = routes(Arc::new(RouterState::new(
+            Arc::new(initialize_dependencies().await),
+            RouterConfig {
+                admin_api_token: Some("admin-token".to_string()),
+                ..RouterConfig::dummy()
+            },
+        )));
+
+        let response = request()
+            .path("/aggregator/status")
+            .header("origin", "https://example.com")
+            .reply(&router)
+            .await;
+        assert!(response
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+
+        let response = request()
+            .path("/aggregator/admin/log-level")
+            .header("origin", "https://example.com")
+            .header("authorization", "Bearer admin-token")
+            .reply(&router)
+            .await;
+        assert_eq!(StatusCode::OK, response.status());
+        assert!(!response
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
     }
 }
@@ -1,41 +1,65 @@
-use crate::http_server::routes::middlewares;
+use axum::extract::State;
+use axum::routing::post;
+use axum::Router;
+use std::sync::Arc;
+
+use crate::http_server::routes::middlewares::{self, AuthenticatedSignerMessage};
 use crate::http_server::routes::router::RouterState;
-use warp::Filter;
+use crate::http_server::RouteGroup;
+use mithril_common::messages::RegisterSignatureMessage;
 
-pub fn routes(
-    router_state: &RouterState,
-) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+pub fn routes(router_state: &RouterState) -> Router<Arc<RouterState>> {
     register_signatures(router_state)
 }
 
 /// POST /register-signatures
-fn register_signatures(
-    router_state: &RouterState,
-) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-    warp::path!("register-signatures")
-        .and(warp::post())
-        .and(warp::body::json())
-        .and(middlewares::with_logger(router_state))
-        .and(middlewares::with_certifier_service(router_state))
-        .and(middlewares::with_single_signature_authenticator(
+fn register_signatures(router_state: &RouterState) -> Router<Arc<RouterState>> {
+    Router::new()
+        .route(
+            "/register-signatures",
+            post(
+                |State(state): State<Arc<RouterState>>,
+                 AuthenticatedSignerMessage(message): AuthenticatedSignerMessage<
+                    RegisterSignatureMessage,
+                >| async move {
+                    handlers::register_signatures(
+                        message,
+                        state.logger.clone(),
+                        state.dependencies.certifier_service.clone(),
+                        state.dependencies.single_signer_authenticator.clone(),
+                        state.dependencies.metrics_service.clone(),
+                        state.dependencies.signature_replay_detector.clone(),
+                        state.dependencies.era_checker.clone(),
+                    )
+                    .await
+                },
+            ),
+        )
+        .route_layer(middlewares::allowed_signer_address(router_state))
+        .route_layer(middlewares::route_group_enabled(
             router_state,
+            RouteGroup::Signatures,
         ))
-        .and(middlewares::with_metrics_service(router_state))
-        .and_then(handlers::register_signatures)
 }
 
 mod handlers {
+    use axum::http::StatusCode;
+    use axum::response::IntoResponse;
     use slog::{debug, warn, Logger};
     use std::convert::Infallible;
     use std::sync::Arc;
-    use warp::http::StatusCode;
 
+    use mithril_common::era::EraChecker;
+    use mithril_common::error_codes::MithrilErrorCode;
     use mithril_common::messages::{RegisterSignatureMessage, TryFromMessageAdapter};
 
     use crate::{
         http_server::routes::reply,
         message_adapters::FromRegisterSingleSignatureAdapter,
-        services::{CertifierService, CertifierServiceError, SignatureRegistrationStatus},
+        services::{
+            CertifierService, CertifierServiceError, SignatureRegistrationStatus,
+            SignatureReplayDetector,
+        },
         unwrap_to_internal_server_error, MetricsService, SingleSignatureAuthenticator,
     };
 
@@ -46,22 +70,54 @@ mod handlers {
         certifier_service: Arc<dyn CertifierService>,
         single_signer_authenticator: Arc<SingleSignatureAuthenticator>,
         metrics_service: Arc<MetricsService>,
-    ) -> Result<impl warp::Reply, Infallible> {
+        signature_replay_detector: Arc<dyn SignatureReplayDetector>,
+        era_checker: Arc<EraChecker>,
+    ) -> Result<impl IntoResponse, Infallible> {
         debug!(logger, ">> register_signatures"; "payload" => ?message);
 
         metrics_service
             .get_signature_registration_total_received_since_startup()
             .increment();
 
+        let party_id = message.party_id.clone();
         let signed_entity_type = message.signed_entity_type.clone();
         let signed_message = message.signed_message.clone();
 
+        if !era_checker.is_signature_scheme_active(message.signature_scheme) {
+            debug!(logger, "register_signatures::inactive_signature_scheme"; "signature_scheme" => %message.signature_scheme);
+            return Ok(reply::bad_request(
+                MithrilErrorCode::InactiveSignatureScheme,
+                "Inactive signature scheme".to_string(),
+                format!(
+                    "Signature scheme '{}' is not used by the current era",
+                    message.signature_scheme
+                ),
+            ));
+        }
+
+        // During a transition to a new scheme, the signatures of the scheme that does not
+        // certify yet are accepted but not aggregated.
+        if era_checker.certifying_signature_scheme() != message.signature_scheme {
+            debug!(logger, "register_signatures::non_certifying_signature_scheme"; "signature_scheme" => %message.signature_scheme);
+            return Ok(reply::empty(StatusCode::ACCEPTED));
+        }
+
+        if signature_replay_detector.is_already_registered(&party_id, &signed_entity_type) {
+            debug!(logger, "register_signatures::duplicate_signature"; "party_id" => &party_id, "signed_entity_type" => ?signed_entity_type);
+            metrics_service
+                .get_signature_registration_duplicate_since_startup()
+                .increment();
+
+            return Ok(reply::empty(StatusCode::CONFLICT));
+        }
+
         let mut signatures = match FromRegisterSingleSignatureAdapter::try_adapt(message) {
             Ok(signature) => signature,
             Err(err) => {
                 warn!(logger,"register_signatures::payload decoding error"; "error" => ?err);
 
                 return Ok(reply::bad_request(
+                    MithrilErrorCode::InvalidRequestPayload,
                     "Could not decode signature payload".to_string(),
                     err.to_string(),
                 ));
@@ -79,6 +135,7 @@ mod handlers {
             if !signatures.is_authenticated() {
                 debug!(logger, "register_signatures::unauthenticated_signature");
                 return Ok(reply::bad_request(
+                    MithrilErrorCode::SignatureAuthenticationFailed,
                     "Could not authenticate signature".to_string(),
                     "Signature could not be authenticated".to_string(),
                 ));
@@ -103,8 +160,15 @@ mod handlers {
                     Ok(reply::server_error(err))
                 }
             },
-            Ok(SignatureRegistrationStatus::Registered) => Ok(reply::empty(StatusCode::CREATED)),
-            Ok(SignatureRegistrationStatus::Buffered) => Ok(reply::empty(StatusCode::ACCEPTED)),
+            Ok(status) => {
+                signature_replay_detector.record_registration(&party_id, &signed_entity_type);
+                match status {
+                    SignatureRegistrationStatus::Registered => {
+                        Ok(reply::empty(StatusCode::CREATED))
+                    }
+                    SignatureRegistrationStatus::Buffered => Ok(reply::empty(StatusCode::ACCEPTED)),
+                }
+            }
         }
     }
 }
@@ -112,12 +176,15 @@ mod handlers {
 #[cfg(test)]
 mod tests {
     use anyhow::anyhow;
+    use axum::http::{Method, StatusCode};
     use std::sync::Arc;
-    use warp::http::{Method, StatusCode};
-    use warp::test::request;
+
+    use crate::http_server::routes::test_utils::request;
 
     use mithril_common::{
-        entities::SignedEntityType, messages::RegisterSignatureMessage,
+        entities::{ClientError, SignedEntityType},
+        error_codes::MithrilErrorCode,
+        messages::RegisterSignatureMessage,
         test_utils::apispec::APISpec,
     };
 
@@ -130,17 +197,8 @@ mod tests {
 
     use super::*;
 
-    fn setup_router(
-        state: RouterState,
-    ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-        let cors = warp::cors()
-            .allow_any_origin()
-            .allow_headers(vec!["content-type"])
-            .allow_methods(vec![Method::GET, Method::POST, Method::OPTIONS]);
-
-        warp::any()
-            .and(warp::path(SERVER_BASE_PATH))
-            .and(routes(&state).with(cors))
+    fn setup_router(state: RouterState) -> Router {
+        crate::http_server::routes::test_utils::setup_router(state, routes)
     }
 
     #[tokio::test]
@@ -349,6 +407,34 @@ mod tests {
         .unwrap();
     }
 
+    #[tokio::test]
+    async fn test_register_signatures_post_ko_400_listing_the_violations_if_the_body_does_not_match_the_schema(
+    ) {
+        let mut mock_certifier_service = MockCertifierService::new();
+        mock_certifier_service
+            .expect_register_single_signature()
+            .never();
+        let mut dependency_manager = initialize_dependencies().await;
+        dependency_manager.certifier_service = Arc::new(mock_certifier_service);
+        let mut message = serde_json::json!(RegisterSignatureMessage::dummy());
+        message.as_object_mut().unwrap().remove("party_id");
+
+        let response = request()
+            .method(Method::POST.as_str())
+            .path(&format!("/{SERVER_BASE_PATH}/register-signatures"))
+            .json(&message)
+            .reply(&setup_router(RouterState::new_with_dummy_config(Arc::new(
+                dependency_manager,
+            ))))
+            .await;
+
+        assert_eq!(StatusCode::BAD_REQUEST, response.status());
+        let error: ClientError = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!("invalid_request_body", error.label);
+        assert_eq!(Some(MithrilErrorCode::InvalidRequestPayload), error.code);
+        assert!(error.message.contains("party_id"), "error: {error:?}");
+    }
+
     #[tokio::test]
     async fn test_register_signatures_post_ko_404() {
         let signed_entity_type = SignedEntityType::dummy();
@@ -386,6 +472,61 @@ mod tests {
         .unwrap();
     }
 
+    #[tokio::test]
+    async fn test_register_signatures_post_ko_409_if_the_signature_was_already_registered() {
+        let mut mock_certifier_service = MockCertifierService::new();
+        mock_certifier_service
+            .expect_register_single_signature()
+            .once()
+            .return_once(move |_, _| Ok(SignatureRegistrationStatus::Registered));
+        let mut dependency_manager = initialize_dependencies().await;
+        dependency_manager.certifier_service = Arc::new(mock_certifier_service);
+        let dependency_manager = Arc::new(dependency_manager);
+        let initial_counter_value = dependency_manager
+            .metrics_service
+            .get_signature_registration_duplicate_since_startup()
+            .get();
+        let router = setup_router(RouterState::new_with_dummy_config(
+            dependency_manager.clone(),
+        ));
+
+        let message = RegisterSignatureMessage::dummy();
+
+        let method = Method::POST.as_str();
+        let path = "/register-signatures";
+
+        request()
+            .method(method)
+            .path(&format!("/{SERVER_BASE_PATH}{path}"))
+            .json(&message)
+            .reply(&router)
+            .await;
+        let response = request()
+            .method(method)
+            .path(&format!("/{SERVER_BASE_PATH}{path}"))
+            .json(&message)
+            .reply(&router)
+            .await;
+
+        APISpec::verify_conformity(
+            APISpec::get_all_spec_files(),
+            method,
+            path,
+            "application/json",
+            &message,
+            &response,
+            &StatusCode::CONFLICT,
+        )
+        .unwrap();
+        assert_eq!(
+            initial_counter_value + 1,
+            dependency_manager
+                .metrics_service
+                .get_signature_registration_duplicate_since_startup()
+                .get()
+        );
+    }
+
     #[tokio::test]
     async fn test_register_signatures_post_ko_410() {
         let signed_entity_type = SignedEntityType::dummy();
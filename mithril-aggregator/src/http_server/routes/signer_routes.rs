@@ -1,66 +1,129 @@
 use anyhow::Context;
+use axum::extract::{Path, Query, State};
+use axum::http::HeaderMap;
+use axum::routing::{get, post};
+use axum::Router;
+use serde::Deserialize;
 use slog::warn;
-use warp::Filter;
+use std::sync::Arc;
 
-use mithril_common::entities::Epoch;
+use mithril_common::entities::{Epoch, PartyId};
+use mithril_common::messages::RegisterSignerMessage;
 use mithril_common::StdResult;
 
 use crate::dependency_injection::EpochServiceWrapper;
-use crate::http_server::routes::middlewares;
+use crate::http_server::routes::middlewares::{self, AuthenticatedSignerMessage, RemoteAddress};
 use crate::http_server::routes::router::RouterState;
+use crate::http_server::RouteGroup;
 
 const MITHRIL_SIGNER_VERSION_HEADER: &str = "signer-node-version";
 
-pub fn routes(
-    router_state: &RouterState,
-) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+#[derive(Deserialize, Debug)]
+struct RegistrationChallengeQueryParams {
+    party_id: Option<PartyId>,
+}
+
+pub fn routes(router_state: &RouterState) -> Router<Arc<RouterState>> {
     register_signer(router_state)
-        .or(registered_signers(router_state))
-        .or(signers_tickers(router_state))
+        .merge(registration_challenge(router_state))
+        .merge(registered_signers())
+        .merge(signers_tickers())
 }
 
 /// POST /register-signer
-fn register_signer(
-    router_state: &RouterState,
-) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-    warp::path!("register-signer")
-        .and(warp::post())
-        .and(warp::header::optional::<String>(
-            MITHRIL_SIGNER_VERSION_HEADER,
+fn register_signer(router_state: &RouterState) -> Router<Arc<RouterState>> {
+    Router::new()
+        .route(
+            "/register-signer",
+            post(
+                |State(state): State<Arc<RouterState>>,
+                 headers: HeaderMap,
+                 RemoteAddress(remote_address): RemoteAddress,
+                 AuthenticatedSignerMessage(message): AuthenticatedSignerMessage<
+                    RegisterSignerMessage,
+                >| async move {
+                    let signer_node_version = headers
+                        .get(MITHRIL_SIGNER_VERSION_HEADER)
+                        .and_then(|value| value.to_str().ok())
+                        .map(|value| value.to_string());
+
+                    handlers::register_signer(
+                        signer_node_version,
+                        message,
+                        state.logger.clone(),
+                        state.dependencies.signer_registerer.clone(),
+                        state.dependencies.event_transmitter.clone(),
+                        state.dependencies.epoch_service.clone(),
+                        state.dependencies.metrics_service.clone(),
+                        state.dependencies.audit_logger.clone(),
+                        state.dependencies.registration_challenge_service.clone(),
+                        remote_address,
+                    )
+                    .await
+                },
+            ),
+        )
+        .route_layer(middlewares::allowed_signer_address(router_state))
+        .route_layer(middlewares::route_group_enabled(
+            router_state,
+            RouteGroup::SignerRegistration,
+        ))
+}
+
+/// GET /register-signer/challenge
+fn registration_challenge(router_state: &RouterState) -> Router<Arc<RouterState>> {
+    Router::new()
+        .route(
+            "/register-signer/challenge",
+            get(
+                |State(state): State<Arc<RouterState>>,
+                 Query(params): Query<RegistrationChallengeQueryParams>| async move {
+                    handlers::registration_challenge(
+                        params.party_id,
+                        state.logger.clone(),
+                        state.dependencies.registration_challenge_service.clone(),
+                    )
+                    .await
+                },
+            ),
+        )
+        .route_layer(middlewares::route_group_enabled(
+            router_state,
+            RouteGroup::SignerRegistration,
         ))
-        .and(warp::body::json())
-        .and(middlewares::with_logger(router_state))
-        .and(middlewares::with_signer_registerer(router_state))
-        .and(middlewares::with_event_transmitter(router_state))
-        .and(middlewares::with_epoch_service(router_state))
-        .and(middlewares::with_metrics_service(router_state))
-        .and_then(handlers::register_signer)
 }
 
 /// Get /signers/tickers
-fn signers_tickers(
-    router_state: &RouterState,
-) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-    warp::path!("signers" / "tickers")
-        .and(warp::get())
-        .and(middlewares::with_logger(router_state))
-        .and(middlewares::extract_config(router_state, |config| {
-            config.network.to_string()
-        }))
-        .and(middlewares::with_signer_getter(router_state))
-        .and_then(handlers::signers_tickers)
+fn signers_tickers() -> Router<Arc<RouterState>> {
+    Router::new().route(
+        "/signers/tickers",
+        get(|State(state): State<Arc<RouterState>>| async move {
+            handlers::signers_tickers(
+                state.logger.clone(),
+                state.configuration.network.to_string(),
+                state.dependencies.signer_getter.clone(),
+            )
+            .await
+        }),
+    )
 }
 
 /// Get /signers/registered/:epoch
-fn registered_signers(
-    router_state: &RouterState,
-) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-    warp::path!("signers" / "registered" / String)
-        .and(warp::get())
-        .and(middlewares::with_logger(router_state))
-        .and(middlewares::with_epoch_service(router_state))
-        .and(middlewares::with_verification_key_store(router_state))
-        .and_then(handlers::registered_signers)
+fn registered_signers() -> Router<Arc<RouterState>> {
+    Router::new().route(
+        "/signers/registered/:epoch",
+        get(
+            |State(state): State<Arc<RouterState>>, Path(registered_at): Path<String>| async move {
+                handlers::registered_signers(
+                    registered_at,
+                    state.logger.clone(),
+                    state.dependencies.epoch_service.clone(),
+                    state.dependencies.verification_key_store.clone(),
+                )
+                .await
+            },
+        ),
+    )
 }
 
 async fn fetch_epoch_header_value(
@@ -100,13 +163,21 @@ mod handlers {
     use crate::http_server::routes::signer_routes::{
         compute_registration_epoch, fetch_epoch_header_value,
     };
+    use crate::services::{
+        AuditAction, AuditEntry, AuditLogger, AuditOutcome, RegistrationChallengeService,
+    };
     use crate::{http_server::routes::reply, SignerRegisterer, SignerRegistrationError};
     use crate::{FromRegisterSignerAdapter, MetricsService, VerificationKeyStorer};
+    use axum::http::StatusCode;
+    use axum::response::IntoResponse;
+    use mithril_common::entities::PartyId;
+    use mithril_common::error_codes::MithrilErrorCode;
     use mithril_common::messages::{RegisterSignerMessage, TryFromMessageAdapter};
+    use serde_json::json;
     use slog::{debug, warn, Logger};
     use std::convert::Infallible;
+    use std::net::SocketAddr;
     use std::sync::Arc;
-    use warp::http::StatusCode;
 
     /// Register Signer
     pub async fn register_signer(
@@ -117,7 +188,10 @@ mod handlers {
         event_transmitter: Arc<TransmitterService<EventMessage>>,
         epoch_service: EpochServiceWrapper,
         metrics_service: Arc<MetricsService>,
-    ) -> Result<impl warp::Reply, Infallible> {
+        audit_logger: Arc<dyn AuditLogger>,
+        registration_challenge_service: Arc<dyn RegistrationChallengeService>,
+        remote_address: Option<SocketAddr>,
+    ) -> Result<impl IntoResponse, Infallible> {
         debug!(logger, ">> register_signer"; "payload" => ?register_signer_message);
 
         metrics_service
@@ -126,11 +200,33 @@ mod handlers {
 
         let registration_epoch = register_signer_message.epoch;
 
+        let authentication_result =
+            registration_challenge_service.authenticate_registration(&register_signer_message);
+        if let Err(err) = authentication_result.as_ref() {
+            warn!(logger,"register_signer::invalid_registration_challenge"; "error" => ?err);
+            audit_logger.record(
+                AuditEntry::new(AuditAction::SignerRegistration)
+                    .with_actor(&register_signer_message.party_id)
+                    .with_source_address(remote_address)
+                    .with_details(json!({
+                        "epoch": registration_epoch,
+                        "signer_node_version": signer_node_version,
+                    }))
+                    .with_result(&authentication_result),
+            );
+            return Ok(reply::bad_request(
+                MithrilErrorCode::InvalidRegistrationChallenge,
+                "invalid_registration_challenge".to_string(),
+                err.to_string(),
+            ));
+        }
+
         let signer = match FromRegisterSignerAdapter::try_adapt(register_signer_message) {
             Ok(signer) => signer,
             Err(err) => {
                 warn!(logger,"register_signer::payload decoding error"; "error" => ?err);
                 return Ok(reply::bad_request(
+                    MithrilErrorCode::InvalidRequestPayload,
                     "Could not decode signer payload".to_string(),
                     err.to_string(),
                 ));
@@ -139,10 +235,24 @@ mod handlers {
 
         let epoch_str = fetch_epoch_header_value(epoch_service, &logger).await;
 
-        match signer_registerer
+        let registration_result = signer_registerer
             .register_signer(registration_epoch, &signer)
-            .await
-        {
+            .await;
+        let audit_entry = AuditEntry::new(AuditAction::SignerRegistration)
+            .with_actor(&signer.party_id)
+            .with_source_address(remote_address)
+            .with_details(json!({
+                "epoch": registration_epoch,
+                "signer_node_version": signer_node_version,
+            }));
+        audit_logger.record(match &registration_result {
+            Err(SignerRegistrationError::ExistingSigner(_)) => {
+                audit_entry.with_outcome(AuditOutcome::Unchanged)
+            }
+            result => audit_entry.with_result(result),
+        });
+
+        match registration_result {
             Ok(signer_with_stake) => {
                 event_transmitter.send(EventMessage::signer_registration(
                     "HTTP::signer_register",
@@ -168,6 +278,7 @@ mod handlers {
             Err(SignerRegistrationError::FailedSignerRegistration(err)) => {
                 warn!(logger,"register_signer::failed_signer_registration"; "error" => ?err);
                 Ok(reply::bad_request(
+                    MithrilErrorCode::SignerRegistrationFailed,
                     "failed_signer_registration".to_string(),
                     err.to_string(),
                 ))
@@ -185,19 +296,35 @@ mod handlers {
         }
     }
 
+    /// Issue a signer registration challenge
+    pub async fn registration_challenge(
+        party_id: Option<PartyId>,
+        logger: Logger,
+        registration_challenge_service: Arc<dyn RegistrationChallengeService>,
+    ) -> Result<impl IntoResponse, Infallible> {
+        match registration_challenge_service.issue_challenge(party_id.clone()) {
+            Ok(message) => Ok(reply::json(&message, StatusCode::OK)),
+            Err(err) => {
+                warn!(logger,"registration_challenge::error"; "party_id" => ?party_id, "error" => ?err);
+                Ok(reply::service_unavailable(err.to_string()))
+            }
+        }
+    }
+
     /// Get Registered Signers for a given epoch
     pub async fn registered_signers(
         registered_at: String,
         logger: Logger,
         epoch_service: EpochServiceWrapper,
         verification_key_store: Arc<dyn VerificationKeyStorer>,
-    ) -> Result<impl warp::Reply, Infallible> {
+    ) -> Result<impl IntoResponse, Infallible> {
         let registered_at_epoch =
             match compute_registration_epoch(&registered_at, epoch_service).await {
                 Ok(epoch) => epoch,
                 Err(err) => {
                     warn!(logger,"registered_signers::invalid_epoch"; "error" => ?err);
                     return Ok(reply::bad_request(
+                        MithrilErrorCode::InvalidEpoch,
                         "invalid_epoch".to_string(),
                         err.to_string(),
                     ));
@@ -229,7 +356,7 @@ mod handlers {
         logger: Logger,
         network: String,
         signer_getter: Arc<dyn SignerGetter>,
-    ) -> Result<impl warp::Reply, Infallible> {
+    ) -> Result<impl IntoResponse, Infallible> {
         match signer_getter.get_all().await {
             Ok(signers) => {
                 let signers: Vec<_> = signers
@@ -256,19 +383,18 @@ mod handlers {
 #[cfg(test)]
 mod tests {
     use anyhow::anyhow;
+    use axum::http::{Method, StatusCode};
     use mockall::predicate::eq;
     use serde_json::Value::Null;
     use std::sync::Arc;
     use tokio::sync::RwLock;
-    use warp::{
-        http::{Method, StatusCode},
-        test::request,
-    };
+
+    use crate::http_server::routes::test_utils::request;
 
     use mithril_common::{
         crypto_helper::ProtocolRegistrationError,
         entities::Epoch,
-        messages::RegisterSignerMessage,
+        messages::{RegisterSignerMessage, RegistrationChallengeMessage},
         test_utils::MithrilFixtureBuilder,
         test_utils::{apispec::APISpec, fake_data},
     };
@@ -276,9 +402,15 @@ mod tests {
 
     use crate::{
         database::{record::SignerRecord, repository::MockSignerGetter},
-        http_server::SERVER_BASE_PATH,
+        http_server::{
+            routes::router::RouterConfig, AuthenticatedSigner, SignerAddressFilter,
+            SERVER_BASE_PATH,
+        },
         initialize_dependencies,
-        services::FakeEpochService,
+        services::{
+            AuditAction, AuditOutcome, DumbAuditLogger, FakeEpochService,
+            MockRegistrationChallengeService,
+        },
         signer_registerer::MockSignerRegisterer,
         store::MockVerificationKeyStorer,
         test_tools::TestLogger,
@@ -287,17 +419,8 @@ mod tests {
 
     use super::*;
 
-    fn setup_router(
-        state: RouterState,
-    ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-        let cors = warp::cors()
-            .allow_any_origin()
-            .allow_headers(vec!["content-type"])
-            .allow_methods(vec![Method::GET, Method::POST, Method::OPTIONS]);
-
-        warp::any()
-            .and(warp::path(SERVER_BASE_PATH))
-            .and(routes(&state).with(cors))
+    fn setup_router(state: RouterState) -> Router {
+        crate::http_server::routes::test_utils::setup_router(state, routes)
     }
 
     #[tokio::test]
@@ -365,6 +488,94 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_register_signer_post_requires_a_client_certificate_matching_the_party_id_if_configured(
+    ) {
+        let signer_with_stake = fake_data::signers_with_stakes(1).pop().unwrap();
+        let mut mock_signer_registerer = MockSignerRegisterer::new();
+        mock_signer_registerer
+            .expect_register_signer()
+            .return_once(|_, _| Ok(signer_with_stake));
+        let mut dependency_manager = initialize_dependencies().await;
+        dependency_manager.signer_registerer = Arc::new(mock_signer_registerer);
+        let router = setup_router(RouterState::new(
+            Arc::new(dependency_manager),
+            RouterConfig {
+                require_signer_client_certificate: true,
+                ..RouterConfig::dummy()
+            },
+        ));
+        let signer = RegisterSignerMessage::dummy();
+        let path = format!("/{SERVER_BASE_PATH}/register-signer");
+
+        let response = request()
+            .method(Method::POST.as_str())
+            .path(&path)
+            .json(&signer)
+            .reply(&router)
+            .await;
+        assert_eq!(StatusCode::FORBIDDEN, response.status());
+
+        let response = request()
+            .method(Method::POST.as_str())
+            .path(&path)
+            .json(&signer)
+            .extension(AuthenticatedSigner {
+                party_id: "another-party-id".to_string(),
+            })
+            .reply(&router)
+            .await;
+        assert_eq!(StatusCode::FORBIDDEN, response.status());
+
+        let response = request()
+            .method(Method::POST.as_str())
+            .path(&path)
+            .json(&signer)
+            .extension(AuthenticatedSigner {
+                party_id: signer.party_id.clone(),
+            })
+            .reply(&router)
+            .await;
+        assert_eq!(StatusCode::CREATED, response.status());
+    }
+
+    #[tokio::test]
+    async fn test_register_signer_post_rejects_the_signer_addresses_not_allowed_by_the_rules() {
+        let signer_with_stake = fake_data::signers_with_stakes(1).pop().unwrap();
+        let mut mock_signer_registerer = MockSignerRegisterer::new();
+        mock_signer_registerer
+            .expect_register_signer()
+            .return_once(|_, _| Ok(signer_with_stake));
+        let rules_path = tempfile::NamedTempFile::new().unwrap().into_temp_path();
+        std::fs::write(&rules_path, r#"{"allow": ["10.0.0.0/8"]}"#).unwrap();
+        let mut dependency_manager = initialize_dependencies().await;
+        dependency_manager.signer_registerer = Arc::new(mock_signer_registerer);
+        dependency_manager.signer_address_filter =
+            Arc::new(SignerAddressFilter::from_file(&rules_path, TestLogger::stdout()).unwrap());
+        let router = setup_router(RouterState::new_with_dummy_config(Arc::new(
+            dependency_manager,
+        )));
+        let path = format!("/{SERVER_BASE_PATH}/register-signer");
+
+        let response = request()
+            .method(Method::POST.as_str())
+            .path(&path)
+            .remote_addr("192.168.1.1:8000".parse().unwrap())
+            .json(&RegisterSignerMessage::dummy())
+            .reply(&router)
+            .await;
+        assert_eq!(StatusCode::FORBIDDEN, response.status());
+
+        let response = request()
+            .method(Method::POST.as_str())
+            .path(&path)
+            .remote_addr("10.0.0.1:8000".parse().unwrap())
+            .json(&RegisterSignerMessage::dummy())
+            .reply(&router)
+            .await;
+        assert_eq!(StatusCode::CREATED, response.status());
+    }
+
     #[tokio::test]
     async fn test_register_signer_post_ok_existing() {
         let signer_with_stake = fake_data::signers_with_stakes(1).pop().unwrap();
@@ -376,8 +587,10 @@ mod tests {
                     signer_with_stake,
                 )))
             });
+        let audit_logger = Arc::new(DumbAuditLogger::new());
         let mut dependency_manager = initialize_dependencies().await;
         dependency_manager.signer_registerer = Arc::new(mock_signer_registerer);
+        dependency_manager.audit_logger = audit_logger.clone();
 
         let signer: RegisterSignerMessage = RegisterSignerMessage::dummy();
 
@@ -403,6 +616,9 @@ mod tests {
             &StatusCode::CREATED,
         )
         .unwrap();
+        let audit_entry = audit_logger.get_last_entry().unwrap();
+        assert_eq!(AuditAction::SignerRegistration, audit_entry.action);
+        assert_eq!(AuditOutcome::Unchanged, audit_entry.outcome);
     }
 
     #[tokio::test]
@@ -516,6 +732,148 @@ mod tests {
         .unwrap();
     }
 
+    #[tokio::test]
+    async fn test_register_signer_post_ko_400_invalid_registration_challenge() {
+        let mut mock_registration_challenge_service = MockRegistrationChallengeService::new();
+        mock_registration_challenge_service
+            .expect_authenticate_registration()
+            .return_once(|_| Err(anyhow!("Unknown or expired registration challenge")));
+        let mut mock_signer_registerer = MockSignerRegisterer::new();
+        mock_signer_registerer.expect_register_signer().never();
+        let mut dependency_manager = initialize_dependencies().await;
+        dependency_manager.signer_registerer = Arc::new(mock_signer_registerer);
+        dependency_manager.registration_challenge_service =
+            Arc::new(mock_registration_challenge_service);
+
+        let signer: RegisterSignerMessage = RegisterSignerMessage::dummy();
+        let method = Method::POST.as_str();
+        let path = "/register-signer";
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{SERVER_BASE_PATH}{path}"))
+            .json(&signer)
+            .reply(&setup_router(RouterState::new_with_dummy_config(Arc::new(
+                dependency_manager,
+            ))))
+            .await;
+
+        APISpec::verify_conformity(
+            APISpec::get_all_spec_files(),
+            method,
+            path,
+            "application/json",
+            &signer,
+            &response,
+            &StatusCode::BAD_REQUEST,
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_registration_challenge_get_ok() {
+        let mut mock_registration_challenge_service = MockRegistrationChallengeService::new();
+        mock_registration_challenge_service
+            .expect_issue_challenge()
+            .with(eq(Some("pool1".to_string())))
+            .return_once(|_| Ok(RegistrationChallengeMessage::dummy()))
+            .once();
+        let mut dependency_manager = initialize_dependencies().await;
+        dependency_manager.registration_challenge_service =
+            Arc::new(mock_registration_challenge_service);
+
+        let method = Method::GET.as_str();
+        let path = "/register-signer/challenge";
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{SERVER_BASE_PATH}{path}?party_id=pool1"))
+            .reply(&setup_router(RouterState::new_with_dummy_config(Arc::new(
+                dependency_manager,
+            ))))
+            .await;
+
+        APISpec::verify_conformity(
+            APISpec::get_all_spec_files(),
+            method,
+            path,
+            "application/json",
+            &Null,
+            &response,
+            &StatusCode::OK,
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_registration_challenge_get_ok_without_party_id() {
+        let mut mock_registration_challenge_service = MockRegistrationChallengeService::new();
+        mock_registration_challenge_service
+            .expect_issue_challenge()
+            .with(eq(None))
+            .return_once(|_| Ok(RegistrationChallengeMessage::dummy()))
+            .once();
+        let mut dependency_manager = initialize_dependencies().await;
+        dependency_manager.registration_challenge_service =
+            Arc::new(mock_registration_challenge_service);
+
+        let method = Method::GET.as_str();
+        let path = "/register-signer/challenge";
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{SERVER_BASE_PATH}{path}"))
+            .reply(&setup_router(RouterState::new_with_dummy_config(Arc::new(
+                dependency_manager,
+            ))))
+            .await;
+
+        APISpec::verify_conformity(
+            APISpec::get_all_spec_files(),
+            method,
+            path,
+            "application/json",
+            &Null,
+            &response,
+            &StatusCode::OK,
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_registration_challenge_get_ko_503_when_the_challenge_can_not_be_issued() {
+        let mut mock_registration_challenge_service = MockRegistrationChallengeService::new();
+        mock_registration_challenge_service
+            .expect_issue_challenge()
+            .return_once(|_| Err(anyhow!("Invalid registration challenge expiry")))
+            .once();
+        let mut dependency_manager = initialize_dependencies().await;
+        dependency_manager.registration_challenge_service =
+            Arc::new(mock_registration_challenge_service);
+
+        let method = Method::GET.as_str();
+        let path = "/register-signer/challenge";
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{SERVER_BASE_PATH}{path}?party_id=pool1"))
+            .reply(&setup_router(RouterState::new_with_dummy_config(Arc::new(
+                dependency_manager,
+            ))))
+            .await;
+
+        APISpec::verify_conformity(
+            APISpec::get_all_spec_files(),
+            method,
+            path,
+            "application/json",
+            &Null,
+            &response,
+            &StatusCode::SERVICE_UNAVAILABLE,
+        )
+        .unwrap();
+    }
+
     #[tokio::test]
     async fn test_registered_signers_get_offset_given_epoch_to_registration_epoch() {
         let asked_epoch = Epoch(1);
@@ -1,33 +1,92 @@
-use warp::Filter;
+use axum::extract::State;
+use axum::routing::post;
+use axum::Router;
+use std::sync::Arc;
 
-use crate::http_server::routes::middlewares;
+use mithril_common::messages::{
+    ArtifactDownloadMessage, DownloadTelemetryMessage, SnapshotDownloadMessage,
+};
+
+use crate::http_server::routes::middlewares::{self, ValidatedJson};
 use crate::http_server::routes::router::RouterState;
+use crate::http_server::RouteGroup;
 
-pub fn routes(
-    router_state: &RouterState,
-) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-    post_statistics(router_state)
+pub fn routes(router_state: &RouterState) -> Router<Arc<RouterState>> {
+    post_statistics()
+        .merge(post_artifact_download_statistics())
+        .merge(post_download_telemetry())
+        .route_layer(middlewares::route_group_enabled(
+            router_state,
+            RouteGroup::Statistics,
+        ))
 }
 
 /// POST /statistics/snapshot
-fn post_statistics(
-    router_state: &RouterState,
-) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-    warp::path!("statistics" / "snapshot")
-        .and(warp::post())
-        .and(warp::body::json())
-        .and(middlewares::with_logger(router_state))
-        .and(middlewares::with_event_transmitter(router_state))
-        .and(middlewares::with_metrics_service(router_state))
-        .and_then(handlers::post_snapshot_statistics)
+fn post_statistics() -> Router<Arc<RouterState>> {
+    Router::new().route(
+        "/statistics/snapshot",
+        post(
+            |State(state): State<Arc<RouterState>>,
+             ValidatedJson(message): ValidatedJson<SnapshotDownloadMessage>| async move {
+                handlers::post_snapshot_statistics(
+                    message,
+                    state.logger.clone(),
+                    state.dependencies.event_transmitter.clone(),
+                    state.dependencies.metrics_service.clone(),
+                )
+                .await
+            },
+        ),
+    )
+}
+
+/// POST /statistics/artifact-download
+fn post_artifact_download_statistics() -> Router<Arc<RouterState>> {
+    Router::new().route(
+        "/statistics/artifact-download",
+        post(
+            |State(state): State<Arc<RouterState>>,
+             ValidatedJson(message): ValidatedJson<ArtifactDownloadMessage>| async move {
+                handlers::post_artifact_download_statistics(
+                    message,
+                    state.logger.clone(),
+                    state.dependencies.event_transmitter.clone(),
+                    state.dependencies.metrics_service.clone(),
+                )
+                .await
+            },
+        ),
+    )
+}
+
+/// POST /statistics/download-telemetry
+fn post_download_telemetry() -> Router<Arc<RouterState>> {
+    Router::new().route(
+        "/statistics/download-telemetry",
+        post(
+            |State(state): State<Arc<RouterState>>,
+             ValidatedJson(message): ValidatedJson<DownloadTelemetryMessage>| async move {
+                handlers::post_download_telemetry(
+                    message,
+                    state.logger.clone(),
+                    state.dependencies.event_transmitter.clone(),
+                    state.dependencies.metrics_service.clone(),
+                )
+                .await
+            },
+        ),
+    )
 }
 
 mod handlers {
+    use axum::http::StatusCode;
+    use axum::response::IntoResponse;
     use slog::warn;
     use std::{convert::Infallible, sync::Arc};
-    use warp::http::StatusCode;
 
-    use mithril_common::messages::SnapshotDownloadMessage;
+    use mithril_common::messages::{
+        ArtifactDownloadMessage, DownloadTelemetryMessage, SnapshotDownloadMessage,
+    };
 
     use crate::event_store::{EventMessage, TransmitterService};
     use crate::http_server::routes::reply;
@@ -38,7 +97,7 @@ mod handlers {
         logger: slog::Logger,
         event_transmitter: Arc<TransmitterService<EventMessage>>,
         metrics_service: Arc<MetricsService>,
-    ) -> Result<impl warp::Reply, Infallible> {
+    ) -> Result<impl IntoResponse, Infallible> {
         metrics_service
             .get_cardano_db_total_restoration_since_startup()
             .increment();
@@ -60,37 +119,90 @@ mod handlers {
             Ok(_) => Ok(reply::empty(StatusCode::CREATED)),
         }
     }
+
+    pub async fn post_artifact_download_statistics(
+        artifact_download_message: ArtifactDownloadMessage,
+        logger: slog::Logger,
+        event_transmitter: Arc<TransmitterService<EventMessage>>,
+        metrics_service: Arc<MetricsService>,
+    ) -> Result<impl IntoResponse, Infallible> {
+        metrics_service
+            .get_artifact_download_total_since_startup()
+            .increment(&[artifact_download_message.artifact_type.as_ref()]);
+
+        let headers: Vec<(&str, &str)> = Vec::new();
+
+        let message = EventMessage::new(
+            "HTTP::statistics",
+            "artifact_downloaded",
+            &artifact_download_message,
+            headers,
+        );
+
+        match event_transmitter.try_send(message.clone()) {
+            Err(e) => {
+                warn!(logger, "Event message error"; "error" => ?e);
+                Ok(reply::internal_server_error(e))
+            }
+            Ok(_) => Ok(reply::empty(StatusCode::CREATED)),
+        }
+    }
+
+    pub async fn post_download_telemetry(
+        download_telemetry_message: DownloadTelemetryMessage,
+        logger: slog::Logger,
+        event_transmitter: Arc<TransmitterService<EventMessage>>,
+        metrics_service: Arc<MetricsService>,
+    ) -> Result<impl IntoResponse, Infallible> {
+        let outcome = if download_telemetry_message.succeeded {
+            "success"
+        } else {
+            "failure"
+        };
+        metrics_service
+            .get_download_telemetry_total_since_startup()
+            .increment(&[download_telemetry_message.artifact_type.as_ref(), outcome]);
+
+        let headers: Vec<(&str, &str)> = Vec::new();
+
+        let message = EventMessage::new(
+            "HTTP::statistics",
+            "download_telemetry_reported",
+            &download_telemetry_message,
+            headers,
+        );
+
+        match event_transmitter.try_send(message.clone()) {
+            Err(e) => {
+                warn!(logger, "Event message error"; "error" => ?e);
+                Ok(reply::internal_server_error(e))
+            }
+            Ok(_) => Ok(reply::empty(StatusCode::CREATED)),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    use mithril_common::messages::SnapshotDownloadMessage;
+    use mithril_common::messages::{
+        ArtifactDownloadMessage, DownloadTelemetryMessage, SnapshotDownloadMessage,
+    };
     use mithril_common::test_utils::apispec::APISpec;
 
+    use axum::http::{Method, StatusCode};
     use std::sync::Arc;
-    use warp::{
-        http::{Method, StatusCode},
-        test::request,
-    };
+
+    use crate::http_server::routes::test_utils::request;
 
     use crate::{
         dependency_injection::DependenciesBuilder, http_server::SERVER_BASE_PATH,
         initialize_dependencies, Configuration,
     };
 
-    fn setup_router(
-        state: RouterState,
-    ) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
-        let cors = warp::cors()
-            .allow_any_origin()
-            .allow_headers(vec!["content-type"])
-            .allow_methods(vec![Method::GET, Method::POST, Method::OPTIONS]);
-
-        warp::any()
-            .and(warp::path(SERVER_BASE_PATH))
-            .and(routes(&state).with(cors))
+    fn setup_router(state: RouterState) -> Router {
+        crate::http_server::routes::test_utils::setup_router(state, routes)
     }
 
     #[tokio::test]
@@ -154,4 +266,145 @@ mod tests {
                 .get()
         );
     }
+
+    #[tokio::test]
+    async fn post_artifact_download_statistics_ok() {
+        let config = Configuration::new_sample();
+        let mut builder = DependenciesBuilder::new_with_stdout_logger(config);
+        let mut rx = builder.get_event_transmitter_receiver().await.unwrap();
+        let dependency_manager = builder.build_dependency_container().await.unwrap();
+        let artifact_download_message = ArtifactDownloadMessage::dummy();
+
+        let method = Method::POST.as_str();
+        let path = "/statistics/artifact-download";
+
+        let response = request()
+            .method(method)
+            .json(&artifact_download_message)
+            .path(&format!("/{SERVER_BASE_PATH}{path}"))
+            .reply(&setup_router(RouterState::new_with_dummy_config(Arc::new(
+                dependency_manager,
+            ))))
+            .await;
+
+        let result = APISpec::verify_conformity(
+            APISpec::get_all_spec_files(),
+            method,
+            path,
+            "application/json",
+            &artifact_download_message,
+            &response,
+            &StatusCode::CREATED,
+        );
+
+        let event = rx.try_recv().unwrap();
+        assert_eq!("artifact_downloaded", event.action);
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_post_artifact_download_statistics_increments_metric_for_the_artifact_type() {
+        let method = Method::POST.as_str();
+        let path = "/statistics/artifact-download";
+        let dependency_manager = Arc::new(initialize_dependencies().await);
+        let artifact_download_message = ArtifactDownloadMessage::dummy();
+        let artifact_type = artifact_download_message.artifact_type.as_ref();
+        let initial_counter_value = dependency_manager
+            .metrics_service
+            .get_artifact_download_total_since_startup()
+            .get(&[artifact_type]);
+
+        request()
+            .method(method)
+            .json(&artifact_download_message)
+            .path(&format!("/{SERVER_BASE_PATH}{path}"))
+            .reply(&setup_router(RouterState::new_with_dummy_config(
+                dependency_manager.clone(),
+            )))
+            .await;
+
+        assert_eq!(
+            initial_counter_value + 1,
+            dependency_manager
+                .metrics_service
+                .get_artifact_download_total_since_startup()
+                .get(&[artifact_type])
+        );
+    }
+
+    #[tokio::test]
+    async fn post_download_telemetry_ok() {
+        let config = Configuration::new_sample();
+        let mut builder = DependenciesBuilder::new_with_stdout_logger(config);
+        let mut rx = builder.get_event_transmitter_receiver().await.unwrap();
+        let dependency_manager = builder.build_dependency_container().await.unwrap();
+        let download_telemetry_message = DownloadTelemetryMessage::dummy();
+
+        let method = Method::POST.as_str();
+        let path = "/statistics/download-telemetry";
+
+        let response = request()
+            .method(method)
+            .json(&download_telemetry_message)
+            .path(&format!("/{SERVER_BASE_PATH}{path}"))
+            .reply(&setup_router(RouterState::new_with_dummy_config(Arc::new(
+                dependency_manager,
+            ))))
+            .await;
+
+        let result = APISpec::verify_conformity(
+            APISpec::get_all_spec_files(),
+            method,
+            path,
+            "application/json",
+            &download_telemetry_message,
+            &response,
+            &StatusCode::CREATED,
+        );
+
+        let event = rx.try_recv().unwrap();
+        assert_eq!("download_telemetry_reported", event.action);
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_post_download_telemetry_increments_metric_for_the_artifact_type_and_outcome() {
+        let method = Method::POST.as_str();
+        let path = "/statistics/download-telemetry";
+        let dependency_manager = Arc::new(initialize_dependencies().await);
+        let download_telemetry_message = DownloadTelemetryMessage {
+            succeeded: false,
+            ..DownloadTelemetryMessage::dummy()
+        };
+        let artifact_type = download_telemetry_message.artifact_type.as_ref();
+        let initial_failure_value = dependency_manager
+            .metrics_service
+            .get_download_telemetry_total_since_startup()
+            .get(&[artifact_type, "failure"]);
+        let initial_success_value = dependency_manager
+            .metrics_service
+            .get_download_telemetry_total_since_startup()
+            .get(&[artifact_type, "success"]);
+
+        request()
+            .method(method)
+            .json(&download_telemetry_message)
+            .path(&format!("/{SERVER_BASE_PATH}{path}"))
+            .reply(&setup_router(RouterState::new_with_dummy_config(
+                dependency_manager.clone(),
+            )))
+            .await;
+
+        let metric = dependency_manager
+            .metrics_service
+            .get_download_telemetry_total_since_startup();
+        assert_eq!(
+            initial_failure_value + 1,
+            metric.get(&[artifact_type, "failure"])
+        );
+        assert_eq!(
+            initial_success_value,
+            metric.get(&[artifact_type, "success"])
+        );
+    }
 }
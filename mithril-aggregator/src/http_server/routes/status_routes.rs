@@ -0,0 +1,133 @@
+use axum::extract::State;
+use axum::routing::get;
+use axum::Router;
+use std::sync::Arc;
+
+use crate::http_server::routes::router::RouterState;
+
+pub fn routes() -> Router<Arc<RouterState>> {
+    status()
+}
+
+/// GET /status
+fn status() -> Router<Arc<RouterState>> {
+    Router::new().route(
+        "/status",
+        get(|State(state): State<Arc<RouterState>>| async move {
+            handlers::status(state.dependencies.runtime_status_reporter.clone()).await
+        }),
+    )
+}
+
+mod handlers {
+    use axum::http::StatusCode;
+    use axum::response::IntoResponse;
+    use chrono::Utc;
+    use std::{convert::Infallible, sync::Arc};
+
+    use crate::entities::{AggregatorRuntimeErrorMessage, AggregatorStatusMessage};
+    use crate::http_server::routes::reply;
+    use crate::RuntimeStatusReporter;
+
+    /// Status
+    pub async fn status(
+        runtime_status_reporter: Arc<RuntimeStatusReporter>,
+    ) -> Result<impl IntoResponse, Infallible> {
+        let status = runtime_status_reporter.get_status().await;
+        let message = AggregatorStatusMessage {
+            seconds_in_current_state: status.seconds_in_current_state(Utc::now()),
+            runtime_state: status.state,
+            epoch: status.time_point.as_ref().map(|t| t.epoch),
+            immutable_file_number: status.time_point.as_ref().map(|t| t.immutable_file_number),
+            last_transition_at: status.last_transition_at,
+            last_cycle_at: status.last_cycle_at,
+            last_error: status.last_error.map(|e| AggregatorRuntimeErrorMessage {
+                message: e.message,
+                is_critical: e.is_critical,
+                occurred_at: e.occurred_at,
+            }),
+        };
+
+        Ok(reply::json(&message, StatusCode::OK))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{Method, StatusCode};
+    use serde_json::Value::Null;
+    use std::sync::Arc;
+
+    use crate::http_server::routes::test_utils::request;
+
+    use mithril_common::test_utils::apispec::APISpec;
+
+    use crate::entities::AggregatorStatusMessage;
+    use crate::runtime::{AggregatorState, IdleState};
+    use crate::{http_server::SERVER_BASE_PATH, initialize_dependencies, RuntimeError};
+
+    use super::*;
+
+    fn setup_router(state: RouterState) -> Router {
+        crate::http_server::routes::test_utils::setup_router(state, |_| routes())
+    }
+
+    #[tokio::test]
+    async fn status_route_ok() {
+        let method = Method::GET.as_str();
+        let path = "/status";
+        let dependency_manager = initialize_dependencies().await;
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{SERVER_BASE_PATH}{path}"))
+            .reply(&setup_router(RouterState::new_with_dummy_config(Arc::new(
+                dependency_manager,
+            ))))
+            .await;
+
+        APISpec::verify_conformity(
+            APISpec::get_all_spec_files(),
+            method,
+            path,
+            "application/json",
+            &Null,
+            &response,
+            &StatusCode::OK,
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn status_route_expose_runtime_last_error() {
+        let method = Method::GET.as_str();
+        let path = "/status";
+        let dependency_manager = initialize_dependencies().await;
+        let state = AggregatorState::Idle(IdleState {
+            current_time_point: None,
+        });
+        dependency_manager
+            .runtime_status_reporter
+            .record_cycle(
+                &state,
+                Some(&RuntimeError::keep_state("runtime error", None)),
+            )
+            .await;
+
+        let response = request()
+            .method(method)
+            .path(&format!("/{SERVER_BASE_PATH}{path}"))
+            .reply(&setup_router(RouterState::new_with_dummy_config(Arc::new(
+                dependency_manager,
+            ))))
+            .await;
+
+        let message: AggregatorStatusMessage = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!("idle", message.runtime_state);
+        assert!(message
+            .last_error
+            .expect("last error should be exposed")
+            .message
+            .contains("runtime error"));
+    }
+}
@@ -0,0 +1,89 @@
+use axum::body::{Body, Bytes};
+use axum::extract::ConnectInfo;
+use axum::http::{header, request, Request, Response};
+use axum::Router;
+use serde::Serialize;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+use crate::http_server::routes::router::{trim_trailing_slash, RouterState};
+use crate::http_server::SERVER_BASE_PATH;
+
+/// Mount the given routes under the aggregator base path
+pub fn setup_router<F>(state: RouterState, routes: F) -> Router
+where
+    F: FnOnce(&RouterState) -> Router<Arc<RouterState>>,
+{
+    let router = Router::new()
+        .nest(&format!("/{SERVER_BASE_PATH}"), routes(&state))
+        .with_state(Arc::new(state));
+
+    trim_trailing_slash(router)
+}
+
+/// Create a request to send to a router
+pub fn request() -> RequestBuilder {
+    RequestBuilder {
+        builder: Request::builder(),
+        body: Body::empty(),
+    }
+}
+
+/// Builder of a request to send to a router
+pub struct RequestBuilder {
+    builder: request::Builder,
+    body: Body,
+}
+
+impl RequestBuilder {
+    /// Set the method of the request
+    pub fn method(mut self, method: &str) -> Self {
+        self.builder = self.builder.method(method);
+        self
+    }
+
+    /// Set the path, and the query, of the request
+    pub fn path(mut self, path: &str) -> Self {
+        self.builder = self.builder.uri(path);
+        self
+    }
+
+    /// Add a header to the request
+    pub fn header<V: AsRef<str>>(mut self, name: &str, value: V) -> Self {
+        self.builder = self.builder.header(name, value.as_ref());
+        self
+    }
+
+    /// Set the JSON serialization of the given value as the body of the request
+    pub fn json<T: Serialize>(mut self, value: &T) -> Self {
+        self.builder = self
+            .builder
+            .header(header::CONTENT_TYPE, "application/json");
+        self.body = Body::from(serde_json::to_vec(value).unwrap());
+        self
+    }
+
+    /// Set the address of the client, as if the request was received on a connection from it
+    pub fn remote_addr(mut self, address: SocketAddr) -> Self {
+        self.builder = self.builder.extension(ConnectInfo(address));
+        self
+    }
+
+    /// Add an extension to the request
+    pub fn extension<T: Clone + Send + Sync + 'static>(mut self, extension: T) -> Self {
+        self.builder = self.builder.extension(extension);
+        self
+    }
+
+    /// Send the request to the router, the body of the response is collected so
+    /// [APISpec][mithril_common::test_utils::apispec::APISpec] can check its conformity
+    pub async fn reply(self, router: &Router) -> Response<Bytes> {
+        let request = self.builder.body(self.body).unwrap();
+        let response = router.clone().oneshot(request).await.unwrap();
+        let (parts, body) = response.into_parts();
+        let body = axum::body::to_bytes(body, usize::MAX).await.unwrap();
+
+        Response::from_parts(parts, body)
+    }
+}
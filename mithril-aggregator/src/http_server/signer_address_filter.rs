@@ -0,0 +1,322 @@
+//! ## Signer address filter
+//!
+//! Restrict the signer registration and signature routes to the IP addresses of a known signer
+//! fleet, with CIDR allow and deny lists read from a JSON file that is reloaded when it changes.
+//!
+//! The filtered address is the peer address of the connection, the `X-Forwarded-For` headers are
+//! not trusted: behind a reverse proxy all the signers share the proxy address, so the rules must
+//! then be enforced by the proxy.
+
+use anyhow::Context;
+use ipnet::IpNet;
+use serde::{Deserialize, Serialize};
+use slog::{info, warn, Logger};
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+
+use mithril_common::logging::LoggerExtensions;
+use mithril_common::StdResult;
+
+use crate::services::{AuditAction, AuditEntry, AuditLogger, NoopAuditLogger};
+
+/// CIDR allow and deny lists of the signer addresses.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignerAddressRules {
+    /// Networks of the allowed signers, all the addresses are allowed if empty.
+    #[serde(default)]
+    pub allow: Vec<IpNet>,
+
+    /// Networks of the denied signers, they are denied even if they are in an allowed network.
+    #[serde(default)]
+    pub deny: Vec<IpNet>,
+}
+
+impl SignerAddressRules {
+    /// Check if a signer address is allowed, an unknown address is only allowed when there is
+    /// no rules.
+    ///
+    /// An IPv4-mapped IPv6 address, as accepted by a dual-stack listener, is checked as the IPv4
+    /// address it maps.
+    pub fn is_allowed(&self, address: Option<IpAddr>) -> bool {
+        if self.allow.is_empty() && self.deny.is_empty() {
+            return true;
+        }
+        let Some(address) = address.map(|address| address.to_canonical()) else {
+            return false;
+        };
+
+        !self.deny.iter().any(|network| network.contains(&address))
+            && (self.allow.is_empty()
+                || self.allow.iter().any(|network| network.contains(&address)))
+    }
+}
+
+/// Filter the signer addresses with the rules of a file, reloaded when its modification time
+/// changes so that the rules can be updated without restarting the aggregator.
+///
+/// The file is checked by [run_forever][Self::run_forever] and not on each request, so that the
+/// requests never wait for the filesystem.
+pub struct SignerAddressFilter {
+    rules_path: Option<PathBuf>,
+    state: RwLock<SignerAddressFilterState>,
+    audit_logger: Arc<dyn AuditLogger>,
+    logger: Logger,
+}
+
+struct SignerAddressFilterState {
+    modified_at: Option<SystemTime>,
+    rules: SignerAddressRules,
+}
+
+impl SignerAddressFilter {
+    /// Time between two checks of the modification of the rules file
+    pub const RELOAD_INTERVAL: Duration = Duration::from_secs(10);
+
+    /// Create a filter allowing all the signer addresses
+    pub fn allow_all(logger: Logger) -> Self {
+        Self {
+            rules_path: None,
+            state: RwLock::new(SignerAddressFilterState {
+                modified_at: None,
+                rules: SignerAddressRules::default(),
+            }),
+            audit_logger: Arc::new(NoopAuditLogger),
+            logger: logger.new_with_component_name::<Self>(),
+        }
+    }
+
+    /// Create a filter with the rules of the given file, fails if they can not be read
+    pub fn from_file(rules_path: &Path, logger: Logger) -> StdResult<Self> {
+        let modified_at = modification_time(rules_path);
+        let rules = read_rules(rules_path)?;
+
+        Ok(Self {
+            rules_path: Some(rules_path.to_path_buf()),
+            state: RwLock::new(SignerAddressFilterState { modified_at, rules }),
+            audit_logger: Arc::new(NoopAuditLogger),
+            logger: logger.new_with_component_name::<Self>(),
+        })
+    }
+
+    /// Set the [AuditLogger] recording the reloads of the rules
+    pub fn with_audit_logger(mut self, audit_logger: Arc<dyn AuditLogger>) -> Self {
+        self.audit_logger = audit_logger;
+        self
+    }
+
+    /// Check if a signer address is allowed by the last loaded rules
+    pub fn is_allowed(&self, address: Option<IpAddr>) -> bool {
+        self.state.read().unwrap().rules.is_allowed(address)
+    }
+
+    /// Start a loop that reloads the rules when their file is modified, at the given interval.
+    pub async fn run_forever(self: Arc<Self>, run_interval: Duration) {
+        if self.rules_path.is_none() {
+            return;
+        }
+        let mut interval = tokio::time::interval(run_interval);
+
+        loop {
+            interval.tick().await;
+            let filter = self.clone();
+            if let Err(error) =
+                tokio::task::spawn_blocking(move || filter.reload_if_modified()).await
+            {
+                warn!(self.logger, "Signer address rules reload task failed"; "error" => ?error);
+            }
+        }
+    }
+
+    /// Reload the rules if their file was modified since they were last read
+    pub fn reload_if_modified(&self) {
+        let Some(rules_path) = &self.rules_path else {
+            return;
+        };
+        let modified_at = modification_time(rules_path);
+        if self.state.read().unwrap().modified_at == modified_at {
+            return;
+        }
+
+        let result = read_rules(rules_path);
+        let audit_entry = AuditEntry::new(AuditAction::SignerAddressRulesReloaded)
+            .with_result(&result)
+            .with_details(serde_json::json!({
+                "rules_path": rules_path,
+                "rules": result.as_ref().ok(),
+            }));
+        {
+            let mut state = self.state.write().unwrap();
+            state.modified_at = modified_at;
+            if let Ok(rules) = &result {
+                state.rules = rules.clone();
+            }
+        }
+        // Recorded once the lock is released so that the requests are never blocked by the audit log
+        self.audit_logger.record(audit_entry);
+
+        match result {
+            Ok(rules) => {
+                info!(self.logger, "Reloaded the signer address rules"; "allow" => ?rules.allow, "deny" => ?rules.deny);
+            }
+            Err(error) => {
+                // Keep the previous rules so that a file being edited does not open the routes
+                warn!(self.logger, "Could not reload the signer address rules, keeping the previous ones"; "error" => ?error);
+            }
+        }
+    }
+}
+
+fn modification_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+fn read_rules(path: &Path) -> StdResult<SignerAddressRules> {
+    let content = std::fs::read_to_string(path).with_context(|| {
+        format!(
+            "Could not read the signer address rules of '{}'",
+            path.display()
+        )
+    })?;
+
+    serde_json::from_str(&content)
+        .with_context(|| format!("Invalid signer address rules in '{}'", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::services::{AuditOutcome, DumbAuditLogger};
+    use crate::test_tools::TestLogger;
+
+    use super::*;
+
+    fn address(address: &str) -> Option<IpAddr> {
+        Some(address.parse().unwrap())
+    }
+
+    #[test]
+    fn allow_all_addresses_without_rules() {
+        let rules = SignerAddressRules::default();
+
+        assert!(rules.is_allowed(address("192.168.1.1")));
+        assert!(rules.is_allowed(None));
+    }
+
+    #[test]
+    fn only_allow_the_addresses_of_the_allowed_networks_that_are_not_denied() {
+        let rules = SignerAddressRules {
+            allow: vec![
+                "10.0.0.0/8".parse().unwrap(),
+                "2001:db8::/32".parse().unwrap(),
+            ],
+            deny: vec!["10.0.1.0/24".parse().unwrap()],
+        };
+
+        assert!(rules.is_allowed(address("10.0.0.1")));
+        assert!(rules.is_allowed(address("2001:db8::1")));
+        assert!(!rules.is_allowed(address("10.0.1.1")));
+        assert!(!rules.is_allowed(address("192.168.1.1")));
+        assert!(!rules.is_allowed(None));
+    }
+
+    #[test]
+    fn check_the_ipv4_mapped_ipv6_addresses_as_ipv4_addresses() {
+        let rules = SignerAddressRules {
+            allow: vec!["10.0.0.0/8".parse().unwrap()],
+            deny: vec!["10.0.1.0/24".parse().unwrap()],
+        };
+
+        assert!(rules.is_allowed(address("::ffff:10.0.0.1")));
+        assert!(!rules.is_allowed(address("::ffff:10.0.1.1")));
+        assert!(!rules.is_allowed(address("::ffff:192.168.1.1")));
+    }
+
+    #[test]
+    fn deny_the_addresses_of_the_denied_networks_without_allowed_networks() {
+        let rules = SignerAddressRules {
+            allow: vec![],
+            deny: vec!["192.168.0.0/16".parse().unwrap()],
+        };
+
+        assert!(rules.is_allowed(address("10.0.0.1")));
+        assert!(!rules.is_allowed(address("192.168.1.1")));
+    }
+
+    #[test]
+    fn reload_the_rules_when_the_file_is_modified() {
+        let rules_path = tempfile::NamedTempFile::new().unwrap().into_temp_path();
+        std::fs::write(&rules_path, r#"{"allow": ["10.0.0.0/8"]}"#).unwrap();
+        let audit_logger = Arc::new(DumbAuditLogger::new());
+        let filter = SignerAddressFilter::from_file(&rules_path, TestLogger::stdout())
+            .unwrap()
+            .with_audit_logger(audit_logger.clone());
+        assert!(filter.is_allowed(address("10.0.0.1")));
+        assert!(!filter.is_allowed(address("192.168.1.1")));
+
+        std::fs::write(&rules_path, r#"{"allow": ["192.168.0.0/16"]}"#).unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(&rules_path)
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(60))
+            .unwrap();
+        assert!(filter.is_allowed(address("10.0.0.1")));
+
+        filter.reload_if_modified();
+
+        assert!(!filter.is_allowed(address("10.0.0.1")));
+        assert!(filter.is_allowed(address("192.168.1.1")));
+        let audit_entry = audit_logger.get_last_entry().unwrap();
+        assert_eq!(AuditAction::SignerAddressRulesReloaded, audit_entry.action);
+        assert_eq!(AuditOutcome::Success, audit_entry.outcome);
+    }
+
+    #[tokio::test]
+    async fn run_forever_reloads_the_rules_when_the_file_is_modified() {
+        let rules_path = tempfile::NamedTempFile::new().unwrap().into_temp_path();
+        std::fs::write(&rules_path, r#"{"allow": ["10.0.0.0/8"]}"#).unwrap();
+        let filter =
+            Arc::new(SignerAddressFilter::from_file(&rules_path, TestLogger::stdout()).unwrap());
+        let reload_task = tokio::spawn(filter.clone().run_forever(Duration::from_millis(10)));
+
+        std::fs::write(&rules_path, r#"{"allow": ["192.168.0.0/16"]}"#).unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(&rules_path)
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(60))
+            .unwrap();
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !filter.is_allowed(address("192.168.1.1")) {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("The rules should have been reloaded");
+        reload_task.abort();
+
+        assert!(!filter.is_allowed(address("10.0.0.1")));
+    }
+
+    #[test]
+    fn keep_the_previous_rules_if_the_modified_file_is_invalid() {
+        let rules_path = tempfile::NamedTempFile::new().unwrap().into_temp_path();
+        std::fs::write(&rules_path, r#"{"allow": ["10.0.0.0/8"]}"#).unwrap();
+        let filter = SignerAddressFilter::from_file(&rules_path, TestLogger::stdout()).unwrap();
+
+        std::fs::write(&rules_path, "invalid").unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(&rules_path)
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(60))
+            .unwrap();
+        filter.reload_if_modified();
+
+        assert!(filter.is_allowed(address("10.0.0.1")));
+        assert!(!filter.is_allowed(address("192.168.1.1")));
+    }
+}
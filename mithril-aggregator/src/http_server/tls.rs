@@ -0,0 +1,409 @@
+use anyhow::{anyhow, Context};
+use axum::{
+    extract::{ConnectInfo, Request},
+    Router,
+};
+use hyper::body::Incoming;
+use hyper_util::{
+    rt::{TokioExecutor, TokioIo},
+    server::conn::auto,
+};
+use slog::{debug, warn, Logger};
+use std::{
+    fs::File,
+    io::BufReader,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tokio::{net::TcpListener, sync::oneshot};
+use tokio_rustls::{
+    rustls::{server::WebPkiClientVerifier, RootCertStore, ServerConfig},
+    TlsAcceptor,
+};
+use tower::ServiceExt;
+
+use mithril_common::entities::PartyId;
+use mithril_common::StdResult;
+
+/// TLS configuration of the aggregator HTTP server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpServerTlsConfig {
+    /// Path of the PEM encoded certificate chain of the server.
+    pub certificate_path: PathBuf,
+
+    /// Path of the PEM encoded private key of the server.
+    pub private_key_path: PathBuf,
+
+    /// Path of the PEM encoded certificate of the authority issuing the signer client
+    /// certificates, the clients are not authenticated if not set.
+    pub client_ca_certificate_path: Option<PathBuf>,
+}
+
+impl HttpServerTlsConfig {
+    fn build_server_config(&self) -> StdResult<ServerConfig> {
+        let certificates = read_certificates(&self.certificate_path)?;
+        let private_key = rustls_pemfile::private_key(&mut open(&self.private_key_path)?)
+            .with_context(|| {
+                format!(
+                    "Could not read the private key of '{}'",
+                    self.private_key_path.display()
+                )
+            })?
+            .ok_or(anyhow!(
+                "No private key found in '{}'",
+                self.private_key_path.display()
+            ))?;
+
+        let builder = match &self.client_ca_certificate_path {
+            Some(client_ca_certificate_path) => {
+                let mut roots = RootCertStore::empty();
+                for certificate in read_certificates(client_ca_certificate_path)? {
+                    roots.add(certificate).with_context(|| {
+                        format!(
+                            "Invalid client CA certificate in '{}'",
+                            client_ca_certificate_path.display()
+                        )
+                    })?;
+                }
+                // The client certificate is optional at the TLS level so that the clients that
+                // are not signers can still reach the aggregator, the signer routes check it.
+                let client_verifier = WebPkiClientVerifier::builder(Arc::new(roots))
+                    .allow_unauthenticated()
+                    .build()
+                    .with_context(|| "Could not build the client certificates verifier")?;
+                ServerConfig::builder().with_client_cert_verifier(client_verifier)
+            }
+            None => ServerConfig::builder().with_no_client_auth(),
+        };
+
+        builder
+            .with_single_cert(certificates, private_key)
+            .with_context(|| "Invalid HTTP server TLS certificate or private key")
+    }
+}
+
+fn open(path: &Path) -> StdResult<BufReader<File>> {
+    File::open(path)
+        .map(BufReader::new)
+        .with_context(|| format!("Could not open '{}'", path.display()))
+}
+
+fn read_certificates(
+    path: &Path,
+) -> StdResult<Vec<tokio_rustls::rustls::pki_types::CertificateDer<'static>>> {
+    rustls_pemfile::certs(&mut open(path)?)
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("Could not read the certificates of '{}'", path.display()))
+}
+
+/// Signer authenticated by the client certificate of a mutual TLS connection.
+///
+/// It is added to the extensions of the requests received on the connection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthenticatedSigner {
+    /// Party id of the signer, read from the common name of its client certificate
+    pub party_id: PartyId,
+}
+
+impl AuthenticatedSigner {
+    /// Read the signer from its DER encoded client certificate
+    pub fn from_certificate(certificate: &[u8]) -> StdResult<Self> {
+        let (_, certificate) = x509_parser::parse_x509_certificate(certificate)
+            .map_err(|e| anyhow!(e).context("Could not parse the client certificate"))?;
+        let party_id = certificate
+            .subject()
+            .iter_common_name()
+            .next()
+            .and_then(|common_name| common_name.as_str().ok())
+            .ok_or(anyhow!("The client certificate has no common name"))?;
+
+        Ok(Self {
+            party_id: party_id.to_string(),
+        })
+    }
+}
+
+/// Serve the given router over TLS until the shutdown signal is received.
+///
+/// The address of the peer is added to the extensions of the requests as a [ConnectInfo], like
+/// for the connections accepted by axum.
+pub async fn serve_tls(
+    router: Router,
+    address: SocketAddr,
+    tls_config: &HttpServerTlsConfig,
+    mut shutdown_rx: oneshot::Receiver<()>,
+    logger: Logger,
+) -> StdResult<()> {
+    let acceptor = TlsAcceptor::from(Arc::new(tls_config.build_server_config()?));
+    let listener = TcpListener::bind(address)
+        .await
+        .with_context(|| format!("Could not bind the HTTP server to '{address}'"))?;
+
+    loop {
+        tokio::select! {
+            _ = &mut shutdown_rx => {
+                warn!(logger, "shutting down HTTPS server after receiving signal");
+                return Ok(());
+            }
+            accepted = listener.accept() => {
+                let (stream, peer_address) = match accepted {
+                    Ok(accepted) => accepted,
+                    Err(e) => {
+                        warn!(logger, "Could not accept connection"; "error" => ?e);
+                        continue;
+                    }
+                };
+                let acceptor = acceptor.clone();
+                let router = router.clone();
+                let logger = logger.clone();
+                tokio::spawn(async move {
+                    let stream = match acceptor.accept(stream).await {
+                        Ok(stream) => stream,
+                        Err(e) => {
+                            debug!(logger, "TLS handshake failed"; "peer" => %peer_address, "error" => ?e);
+                            return;
+                        }
+                    };
+                    let authenticated_signer = stream
+                        .get_ref()
+                        .1
+                        .peer_certificates()
+                        .and_then(|certificates| certificates.first())
+                        .and_then(|certificate| {
+                            AuthenticatedSigner::from_certificate(certificate.as_ref())
+                                .inspect_err(|e| debug!(logger, "Invalid client certificate"; "peer" => %peer_address, "error" => ?e))
+                                .ok()
+                        });
+                    let connection_service = hyper::service::service_fn(move |request: Request<Incoming>| {
+                        let mut request = request.map(axum::body::Body::new);
+                        request.extensions_mut().insert(ConnectInfo(peer_address));
+                        if let Some(signer) = &authenticated_signer {
+                            request.extensions_mut().insert(signer.clone());
+                        }
+                        router.clone().oneshot(request)
+                    });
+                    if let Err(e) = auto::Builder::new(TokioExecutor::new())
+                        .serve_connection(TokioIo::new(stream), connection_service)
+                        .await
+                    {
+                        debug!(logger, "Error while serving connection"; "peer" => %peer_address, "error" => ?e);
+                    }
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{routing::get, Extension};
+    use rcgen::{BasicConstraints, Certificate, CertificateParams, DnType, IsCa};
+
+    use mithril_common::test_utils::TempDir;
+
+    use crate::test_tools::TestLogger;
+
+    use super::*;
+
+    const PARTY_ID: &str = "pool1m8crhnqj5k2kyszf5j2scshupystyxc887zdfrpzh6ty6eun4fx";
+
+    /// PEM encoded certificates and keys of a mutual TLS setup
+    struct TlsFixture {
+        directory: PathBuf,
+        ca_certificate: Certificate,
+    }
+
+    impl TlsFixture {
+        fn new(test_name: &str) -> Self {
+            let mut params = CertificateParams::new(Vec::<String>::new());
+            params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+            params
+                .distinguished_name
+                .push(DnType::CommonName, "Mithril test CA");
+
+            Self {
+                directory: TempDir::create("http_server_tls", test_name),
+                ca_certificate: Certificate::from_params(params).unwrap(),
+            }
+        }
+
+        /// Issue a certificate signed by the CA, write it with its private key and return
+        /// their paths
+        fn issue_certificate(&self, name: &str, params: CertificateParams) -> (PathBuf, PathBuf) {
+            let certificate = Certificate::from_params(params).unwrap();
+            let certificate_path = self.directory.join(format!("{name}.crt"));
+            let private_key_path = self.directory.join(format!("{name}.key"));
+            std::fs::write(
+                &certificate_path,
+                certificate
+                    .serialize_pem_with_signer(&self.ca_certificate)
+                    .unwrap(),
+            )
+            .unwrap();
+            std::fs::write(&private_key_path, certificate.serialize_private_key_pem()).unwrap();
+
+            (certificate_path, private_key_path)
+        }
+
+        fn ca_certificate_pem(&self) -> String {
+            self.ca_certificate.serialize_pem().unwrap()
+        }
+
+        fn server_tls_config(&self) -> HttpServerTlsConfig {
+            let (certificate_path, private_key_path) = self.issue_certificate(
+                "server",
+                CertificateParams::new(vec!["localhost".to_string()]),
+            );
+            let client_ca_certificate_path = self.directory.join("ca.crt");
+            std::fs::write(&client_ca_certificate_path, self.ca_certificate_pem()).unwrap();
+
+            HttpServerTlsConfig {
+                certificate_path,
+                private_key_path,
+                client_ca_certificate_path: Some(client_ca_certificate_path),
+            }
+        }
+
+        fn client_identity(&self, party_id: &str) -> reqwest::Identity {
+            let mut params = CertificateParams::new(Vec::<String>::new());
+            params.distinguished_name.push(DnType::CommonName, party_id);
+            let (certificate_path, private_key_path) = self.issue_certificate(party_id, params);
+
+            reqwest::Identity::from_pkcs8_pem(
+                &std::fs::read(certificate_path).unwrap(),
+                &std::fs::read(private_key_path).unwrap(),
+            )
+            .unwrap()
+        }
+    }
+
+    /// Serve over TLS a route answering the party id of the authenticated signer, returns the
+    /// url of the route and the sender of the shutdown signal
+    async fn start_tls_server(tls_config: HttpServerTlsConfig) -> (String, oneshot::Sender<()>) {
+        let router = Router::new().route(
+            "/signer",
+            get(
+                |signer: Option<Extension<AuthenticatedSigner>>| async move {
+                    signer
+                        .map(|Extension(signer)| signer.party_id)
+                        .unwrap_or("anonymous".to_string())
+                },
+            ),
+        );
+        let address = TcpListener::bind("127.0.0.1:0")
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        tokio::spawn(async move {
+            serve_tls(
+                router,
+                address,
+                &tls_config,
+                shutdown_rx,
+                TestLogger::stdout(),
+            )
+            .await
+            .unwrap()
+        });
+        // Let the server bind its address
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+        (
+            format!("https://localhost:{}/signer", address.port()),
+            shutdown_tx,
+        )
+    }
+
+    fn http_client(
+        ca_certificate_pem: &str,
+        identity: Option<reqwest::Identity>,
+    ) -> reqwest::Client {
+        let mut builder = reqwest::Client::builder().add_root_certificate(
+            reqwest::Certificate::from_pem(ca_certificate_pem.as_bytes()).unwrap(),
+        );
+        if let Some(identity) = identity {
+            builder = builder.identity(identity);
+        }
+
+        builder.build().unwrap()
+    }
+
+    #[tokio::test]
+    async fn authenticate_the_signer_with_its_client_certificate() {
+        let fixture = TlsFixture::new("authenticate_the_signer_with_its_client_certificate");
+        let (url, _shutdown_tx) = start_tls_server(fixture.server_tls_config()).await;
+        let client = http_client(
+            &fixture.ca_certificate_pem(),
+            Some(fixture.client_identity(PARTY_ID)),
+        );
+
+        let response = client.get(&url).send().await.unwrap();
+
+        assert_eq!(PARTY_ID, response.text().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn accept_the_clients_without_client_certificate_as_anonymous() {
+        let fixture = TlsFixture::new("accept_the_clients_without_client_certificate_as_anonymous");
+        let (url, _shutdown_tx) = start_tls_server(fixture.server_tls_config()).await;
+        let client = http_client(&fixture.ca_certificate_pem(), None);
+
+        let response = client.get(&url).send().await.unwrap();
+
+        assert_eq!("anonymous", response.text().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn reject_a_client_certificate_issued_by_another_authority() {
+        let fixture = TlsFixture::new("reject_a_client_certificate_issued_by_another_authority");
+        let other_fixture =
+            TlsFixture::new("reject_a_client_certificate_issued_by_another_authority_other_ca");
+        let (url, _shutdown_tx) = start_tls_server(fixture.server_tls_config()).await;
+        let client = http_client(
+            &fixture.ca_certificate_pem(),
+            Some(other_fixture.client_identity(PARTY_ID)),
+        );
+
+        client
+            .get(&url)
+            .send()
+            .await
+            .expect_err("The TLS handshake should fail with an unknown client certificate");
+    }
+
+    #[test]
+    fn read_the_party_id_from_the_common_name_of_the_client_certificate() {
+        let mut params = rcgen::CertificateParams::new(Vec::<String>::new());
+        params.distinguished_name.push(
+            rcgen::DnType::CommonName,
+            "pool1m8crhnqj5k2kyszf5j2scshupystyxc887zdfrpzh6ty6eun4fx",
+        );
+        let certificate = rcgen::Certificate::from_params(params)
+            .unwrap()
+            .serialize_der()
+            .unwrap();
+
+        let signer = AuthenticatedSigner::from_certificate(&certificate).unwrap();
+
+        assert_eq!(
+            "pool1m8crhnqj5k2kyszf5j2scshupystyxc887zdfrpzh6ty6eun4fx",
+            signer.party_id
+        );
+    }
+
+    #[test]
+    fn fail_to_read_a_client_certificate_without_common_name() {
+        let mut params = rcgen::CertificateParams::new(Vec::<String>::new());
+        params.distinguished_name = rcgen::DistinguishedName::new();
+        let certificate = rcgen::Certificate::from_params(params)
+            .unwrap()
+            .serialize_der()
+            .unwrap();
+
+        AuthenticatedSigner::from_certificate(&certificate)
+            .expect_err("A certificate without common name should be rejected");
+    }
+}
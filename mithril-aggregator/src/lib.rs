@@ -28,6 +28,7 @@ mod signer_registerer;
 mod snapshot_uploaders;
 mod snapshotter;
 mod store;
+mod telemetry;
 mod tools;
 
 pub use crate::artifact_builder::ArtifactBuilder;
@@ -42,6 +43,7 @@ pub use message_adapters::{FromRegisterSignerAdapter, ToCertificatePendingMessag
 pub use metrics::*;
 pub use runtime::{
     AggregatorConfig, AggregatorRunner, AggregatorRunnerTrait, AggregatorRuntime, RuntimeError,
+    RuntimeStatus, RuntimeStatusError, RuntimeStatusReporter,
 };
 pub use signer_registerer::{
     MithrilSignerRegisterer, SignerRecorder, SignerRegisterer, SignerRegistrationError,
@@ -55,7 +57,8 @@ pub use snapshotter::{
     SnapshotterCompressionAlgorithm,
 };
 pub use store::{
-    CertificatePendingStore, EpochSettingsStorer, VerificationKeyStore, VerificationKeyStorer,
+    CertificatePendingStore, EpochSettingsStorer, FileMerkleMapCacheStore, MerkleMapCacheStorer,
+    ProtocolParametersScheduleStorer, VerificationKeyStore, VerificationKeyStorer,
 };
 pub use tools::{
     CExplorerSignerRetriever, SignersImporter, SignersImporterPersister, SignersImporterRetriever,
@@ -1,41 +1,46 @@
 #![doc = include_str!("../README.md")]
 
 use clap::Parser;
-use slog::{Drain, Fuse, Level, Logger};
+use slog::{Drain, Fuse, Logger};
 use slog_async::Async;
 use std::sync::Arc;
 
 use mithril_aggregator::{CommandType, MainOpts};
+use mithril_common::logging::{CorrelationIdDrain, LogLevelHandle, ReloadableLevelFilter};
 use mithril_common::StdResult;
 
-fn build_io_logger<W: std::io::Write + Send + 'static>(log_level: Level, io: W) -> Fuse<Async> {
+fn build_io_logger<W: std::io::Write + Send + 'static>(
+    log_level_handle: &LogLevelHandle,
+    io: W,
+) -> Fuse<ReloadableLevelFilter<Fuse<Async>>> {
     let drain = slog_bunyan::with_name("mithril-aggregator", io)
         .set_pretty(false)
         .build()
         .fuse();
-    let drain = slog::LevelFilter::new(drain, log_level).fuse();
+    let drain = Async::new(drain).build().fuse();
 
-    Async::new(drain).build().fuse()
+    ReloadableLevelFilter::new(drain, log_level_handle.clone()).fuse()
 }
 
-/// Build a logger from args.
-pub fn build_logger(args: &MainOpts) -> Logger {
+/// Build a logger from args, its level can be changed with the given handle.
+pub fn build_logger(args: &MainOpts, log_level_handle: &LogLevelHandle) -> Logger {
     let drain = match args.command.command_type() {
-        CommandType::Server => build_io_logger(args.log_level(), std::io::stdout()),
-        CommandType::CommandLine => build_io_logger(args.log_level(), std::io::stderr()),
+        CommandType::Server => build_io_logger(log_level_handle, std::io::stdout()),
+        CommandType::CommandLine => build_io_logger(log_level_handle, std::io::stderr()),
     };
 
-    Logger::root(Arc::new(drain), slog::o!())
+    Logger::root(Arc::new(CorrelationIdDrain::new(drain)), slog::o!())
 }
 
 #[tokio::main]
 async fn main() -> StdResult<()> {
     // Load args
     let args = MainOpts::parse();
-    let root_logger = build_logger(&args);
+    let log_level_handle = LogLevelHandle::new(args.log_level());
+    let root_logger = build_logger(&args, &log_level_handle);
 
     #[cfg(feature = "bundle_openssl")]
     openssl_probe::init_ssl_cert_env_vars();
 
-    args.execute(root_logger).await
+    args.execute(root_logger, log_level_handle).await
 }
@@ -43,4 +43,50 @@ mod tests {
 
         assert_eq!("party_id".to_string(), signatures.party_id);
     }
+
+    mod fuzzing {
+        use mithril_common::test_utils::fake_data;
+        use proptest::prelude::*;
+
+        use super::*;
+
+        proptest! {
+            #[test]
+            fn single_signatures_round_trip_through_their_json_message(
+                party_id in "[a-z0-9]{1,56}",
+                won_indexes in prop::collection::vec(any::<u64>(), 0..16),
+            ) {
+                let single_signatures = SingleSignatures {
+                    party_id,
+                    ..fake_data::single_signatures(won_indexes)
+                };
+                let message = RegisterSignatureMessage {
+                    party_id: single_signatures.party_id.clone(),
+                    signature: single_signatures.signature.to_json_hex().unwrap(),
+                    won_indexes: single_signatures.won_indexes.clone(),
+                    ..RegisterSignatureMessage::dummy()
+                };
+                let json = serde_json::to_string(&message).unwrap();
+
+                let adapted_single_signatures = FromRegisterSingleSignatureAdapter::try_adapt(
+                    serde_json::from_str(&json).unwrap(),
+                )
+                .unwrap();
+
+                prop_assert_eq!(single_signatures, adapted_single_signatures);
+            }
+
+            #[test]
+            fn arbitrary_encoded_signatures_are_rejected_without_panicking(
+                signature in prop_oneof!["[0-9a-f]{0,512}", ".{0,64}"],
+            ) {
+                let message = RegisterSignatureMessage {
+                    signature,
+                    ..RegisterSignatureMessage::dummy()
+                };
+
+                prop_assert!(FromRegisterSingleSignatureAdapter::try_adapt(message).is_err());
+            }
+        }
+    }
 }
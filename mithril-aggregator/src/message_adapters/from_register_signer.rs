@@ -56,4 +56,78 @@ mod tests {
 
         assert_eq!("one".to_string(), signer.party_id);
     }
+
+    mod fuzzing {
+        use mithril_common::test_utils::MithrilFixtureBuilder;
+        use proptest::prelude::*;
+
+        use super::*;
+
+        fn register_signer_message(signer: &Signer) -> RegisterSignerMessage {
+            RegisterSignerMessage {
+                party_id: signer.party_id.clone(),
+                verification_key: String::try_from(&signer.verification_key).unwrap(),
+                verification_key_signature: signer
+                    .verification_key_signature
+                    .as_ref()
+                    .map(|signature| String::try_from(signature).unwrap()),
+                operational_certificate: signer
+                    .operational_certificate
+                    .as_ref()
+                    .map(|certificate| String::try_from(certificate).unwrap()),
+                kes_period: signer.kes_period,
+                ..RegisterSignerMessage::dummy()
+            }
+        }
+
+        #[test]
+        fn signers_round_trip_through_their_json_message() {
+            let fixture = MithrilFixtureBuilder::default().with_signers(3).build();
+
+            for signer in fixture.signers() {
+                let json = serde_json::to_string(&register_signer_message(&signer)).unwrap();
+
+                let adapted_signer =
+                    FromRegisterSignerAdapter::try_adapt(serde_json::from_str(&json).unwrap())
+                        .unwrap();
+
+                assert_eq!(
+                    serde_json::to_value(&signer).unwrap(),
+                    serde_json::to_value(&adapted_signer).unwrap()
+                );
+            }
+        }
+
+        proptest! {
+            #[test]
+            fn arbitrary_encoded_verification_keys_are_rejected_without_panicking(
+                verification_key in prop_oneof!["[0-9a-f]{0,512}", ".{0,64}"],
+            ) {
+                let message = RegisterSignerMessage {
+                    verification_key,
+                    ..RegisterSignerMessage::dummy()
+                };
+
+                prop_assert!(FromRegisterSignerAdapter::try_adapt(message).is_err());
+            }
+
+            #[test]
+            fn arbitrary_encoded_certification_materials_are_rejected_without_panicking(
+                verification_key_signature in prop_oneof!["[0-9a-f]{0,512}", ".{0,64}"],
+                operational_certificate in prop_oneof!["[0-9a-f]{0,512}", ".{0,64}"],
+            ) {
+                let message = RegisterSignerMessage {
+                    verification_key_signature: Some(verification_key_signature.clone()),
+                    ..RegisterSignerMessage::dummy()
+                };
+                prop_assert!(FromRegisterSignerAdapter::try_adapt(message).is_err());
+
+                let message = RegisterSignerMessage {
+                    operational_certificate: Some(operational_certificate),
+                    ..RegisterSignerMessage::dummy()
+                };
+                prop_assert!(FromRegisterSignerAdapter::try_adapt(message).is_err());
+            }
+        }
+    }
 }
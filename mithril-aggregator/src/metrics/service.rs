@@ -1,8 +1,11 @@
+use prometheus::proto::MetricType;
 use std::collections::HashMap;
 
 use mithril_metric::{build_metrics_service, MetricsServiceExporter};
 
-use mithril_metric::metric::{MetricCollector, MetricCounter};
+use mithril_metric::metric::{
+    MetricCollector, MetricCounter, MetricCounterWithLabels, MetricGauge,
+};
 
 build_metrics_service!(
     MetricsService,
@@ -42,10 +45,46 @@ build_metrics_service!(
         "mithril_aggregator_signer_registration_total_received_since_startup",
         "Number of signer registrations received since startup on a Mithril aggregator node"
     ),
+    signer_registration_late_accepted_since_startup:MetricCounter(
+        "mithril_aggregator_signer_registration_late_accepted_since_startup",
+        "Number of late signer registrations accepted during the grace period since startup on a Mithril aggregator node"
+    ),
+    signer_registration_late_rejected_since_startup:MetricCounter(
+        "mithril_aggregator_signer_registration_late_rejected_since_startup",
+        "Number of late signer registrations rejected after the grace period since startup on a Mithril aggregator node"
+    ),
     signature_registration_total_received_since_startup:MetricCounter(
         "mithril_aggregator_signature_registration_total_received_since_startup",
         "Number of signature registrations received since startup on a Mithril aggregator node"
     ),
+    single_signature_authenticated_with_current_stake_distribution_since_startup:MetricCounter(
+        "mithril_aggregator_single_signature_authenticated_with_current_stake_distribution_since_startup",
+        "Number of single signatures authenticated with the stake distribution of the current epoch since startup on a Mithril aggregator node"
+    ),
+    single_signature_authenticated_with_next_stake_distribution_since_startup:MetricCounter(
+        "mithril_aggregator_single_signature_authenticated_with_next_stake_distribution_since_startup",
+        "Number of single signatures authenticated with the stake distribution of the next epoch since startup on a Mithril aggregator node"
+    ),
+    single_signature_not_authenticated_since_startup:MetricCounter(
+        "mithril_aggregator_single_signature_not_authenticated_since_startup",
+        "Number of single signatures that could not be authenticated since startup on a Mithril aggregator node"
+    ),
+    single_signature_authentication_cache_hit_since_startup:MetricCounter(
+        "mithril_aggregator_single_signature_authentication_cache_hit_since_startup",
+        "Number of single signature authentications answered from the cache since startup on a Mithril aggregator node"
+    ),
+    single_signature_authentication_cache_miss_since_startup:MetricCounter(
+        "mithril_aggregator_single_signature_authentication_cache_miss_since_startup",
+        "Number of single signature authentications not found in the cache since startup on a Mithril aggregator node"
+    ),
+    single_signature_authentication_cache_size:MetricGauge(
+        "mithril_aggregator_single_signature_authentication_cache_size",
+        "Number of single signature authentication outcomes in the cache of a Mithril aggregator node"
+    ),
+    signature_registration_duplicate_since_startup:MetricCounter(
+        "mithril_aggregator_signature_registration_duplicate_since_startup",
+        "Number of signature registrations rejected because the signer already registered a signature for the signed entity since startup on a Mithril aggregator node"
+    ),
     certificate_total_produced_since_startup:MetricCounter(
         "mithril_aggregator_certificate_total_produced_since_startup",
         "Number of certificates produced since startup on a Mithril aggregator node"
@@ -73,18 +112,103 @@ build_metrics_service!(
     runtime_cycle_total_since_startup:MetricCounter(
         "mithril_aggregator_runtime_cycle_total_since_startup",
         "Number of runtime cycles since startup on a Mithril aggregator"
+    ),
+    runtime_current_state:MetricGauge(
+        "mithril_aggregator_runtime_current_state",
+        "Current state of the runtime state machine of a Mithril aggregator (0: idle, 1: ready, 2: signing)"
+    ),
+    runtime_last_transition_timestamp:MetricGauge(
+        "mithril_aggregator_runtime_last_transition_timestamp",
+        "Unix timestamp of the last state transition of the runtime of a Mithril aggregator"
+    ),
+    runtime_time_in_current_state_seconds:MetricGauge(
+        "mithril_aggregator_runtime_time_in_current_state_seconds",
+        "Number of seconds spent in the current state by the runtime of a Mithril aggregator"
+    ),
+    runtime_cycle_error_since_startup:MetricCounter(
+        "mithril_aggregator_runtime_cycle_error_since_startup",
+        "Number of runtime cycles that ended with an error since startup on a Mithril aggregator"
+    ),
+    certification_duration_cardano_db_seconds:MetricGauge(
+        "mithril_aggregator_certification_duration_cardano_db_seconds",
+        "Duration in seconds between the beacon detection and the certificate issuance of the last Cardano db on a Mithril aggregator"
+    ),
+    certification_duration_mithril_stake_distribution_seconds:MetricGauge(
+        "mithril_aggregator_certification_duration_mithril_stake_distribution_seconds",
+        "Duration in seconds between the beacon detection and the certificate issuance of the last Mithril stake distribution on a Mithril aggregator"
+    ),
+    certification_duration_cardano_stake_distribution_seconds:MetricGauge(
+        "mithril_aggregator_certification_duration_cardano_stake_distribution_seconds",
+        "Duration in seconds between the beacon detection and the certificate issuance of the last Cardano stake distribution on a Mithril aggregator"
+    ),
+    certification_duration_cardano_transaction_seconds:MetricGauge(
+        "mithril_aggregator_certification_duration_cardano_transaction_seconds",
+        "Duration in seconds between the beacon detection and the certificate issuance of the last Cardano transactions on a Mithril aggregator"
+    ),
+    certification_deadline_warning_since_startup:MetricCounter(
+        "mithril_aggregator_certification_deadline_warning_since_startup",
+        "Number of certifications that approached their deadline since startup on a Mithril aggregator"
+    ),
+    certification_deadline_missed_since_startup:MetricCounter(
+        "mithril_aggregator_certification_deadline_missed_since_startup",
+        "Number of certifications that missed their deadline since startup on a Mithril aggregator"
+    ),
+    signer_registered_current_epoch:MetricGauge(
+        "mithril_aggregator_signer_registered_current_epoch",
+        "Number of signers registered for the current epoch on a Mithril aggregator"
+    ),
+    signer_contributing_last_certificate:MetricGauge(
+        "mithril_aggregator_signer_contributing_last_certificate",
+        "Number of signers that contributed to the last certificate produced by a Mithril aggregator"
+    ),
+    signer_contribution_per_party_since_startup:MetricCounterWithLabels(
+        "mithril_aggregator_signer_contribution_per_party_since_startup",
+        "Number of certificates a signer contributed to since startup on a Mithril aggregator",
+        ["party_id"]
+    ),
+    signer_missed_per_party_since_startup:MetricCounterWithLabels(
+        "mithril_aggregator_signer_missed_per_party_since_startup",
+        "Number of certificates a registered signer did not contribute to since startup on a Mithril aggregator",
+        ["party_id"]
+    ),
+    artifact_download_total_since_startup:MetricCounterWithLabels(
+        "mithril_aggregator_artifact_download_total_since_startup",
+        "Number of artifact downloads reported by clients since startup on a Mithril aggregator node",
+        ["artifact_type"]
+    ),
+    download_telemetry_total_since_startup:MetricCounterWithLabels(
+        "mithril_aggregator_download_telemetry_total_since_startup",
+        "Number of downloads reported by the clients that opted in to the telemetry, per artifact type and outcome, since startup on a Mithril aggregator node",
+        ["artifact_type", "outcome"]
+    ),
+    maintenance_duration_seconds:MetricGauge(
+        "mithril_aggregator_maintenance_duration_seconds",
+        "Duration in seconds of the last maintenance (database vacuum, analyze and digests cache cleanup) of a Mithril aggregator"
+    ),
+    maintenance_total_since_startup:MetricCounter(
+        "mithril_aggregator_maintenance_total_since_startup",
+        "Number of maintenances run since startup on a Mithril aggregator"
+    ),
+    artifact_location_unreachable:MetricGauge(
+        "mithril_aggregator_artifact_location_unreachable",
+        "Number of unreachable artifact download locations found by the last availability check of a Mithril aggregator"
+    ),
+    artifact_location_unreachable_since_startup:MetricCounter(
+        "mithril_aggregator_artifact_location_unreachable_since_startup",
+        "Number of unreachable artifact download locations found since startup on a Mithril aggregator"
     )
-
 );
 
 impl MetricsService {
-    /// Export metrics in map.
+    /// Export counter metrics in map.
     // `get metric` returns a list of Metrics for CounterVec purposes for example.
     // We therefore add up the values ​​even though we will always only have one value with our Counter type metrics.
+    // Gauges are not exported since their values are not cumulative.
     pub fn export_metrics_map(&self) -> HashMap<String, u32> {
         self.registry
             .gather()
             .iter()
+            .filter(|metric_family| metric_family.get_field_type() == MetricType::COUNTER)
             .map(|metric_family| {
                 (
                     metric_family.get_name().to_string(),
@@ -142,14 +266,12 @@ mod tests {
     }
 
     #[test]
-    fn metric_service_should_only_contain_counters_as_export_metrics_map_does_not_yet_support_other_types(
-    ) {
+    fn should_not_export_gauge_metrics_in_the_map() {
         let metrics_service = MetricsService::new(TestLogger::stdout()).unwrap();
+        let gauge = metrics_service.get_runtime_current_state();
+        gauge.record(2);
 
-        for metric_family in metrics_service.registry.gather() {
-            for metric in metric_family.get_metric() {
-                assert!(metric.has_counter());
-            }
-        }
+        let export = metrics_service.export_metrics_map();
+        assert!(!export.contains_key(&gauge.name()));
     }
 }
@@ -1,7 +1,9 @@
 mod error;
 mod runner;
 mod state_machine;
+mod status;
 
 pub use error::RuntimeError;
 pub use runner::{AggregatorConfig, AggregatorRunner, AggregatorRunnerTrait};
 pub use state_machine::*;
+pub use status::{RuntimeStatus, RuntimeStatusError, RuntimeStatusReporter};
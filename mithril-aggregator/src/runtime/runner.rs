@@ -1,6 +1,7 @@
 use anyhow::{anyhow, Context};
 use async_trait::async_trait;
 use slog::{debug, warn, Logger};
+use std::collections::BTreeSet;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -173,6 +174,55 @@ impl AggregatorRunner {
         Ok(unlocked_signed_entities)
     }
 
+    /// Record the number of registered and contributing signers, and when enabled in the
+    /// configuration the contributions of each signer, for the given certificate.
+    async fn record_signer_participation_metrics(&self, certificate: &Certificate) {
+        let metrics = &self.dependencies.metrics_service;
+        let registered_signers = match self
+            .dependencies
+            .epoch_service
+            .read()
+            .await
+            .current_signers()
+        {
+            Ok(signers) => signers.clone(),
+            Err(error) => {
+                warn!(self.logger, "Could not get the current signers to record participation metrics"; "error" => ?error);
+                return;
+            }
+        };
+
+        metrics
+            .get_signer_registered_current_epoch()
+            .record(registered_signers.len() as u32);
+        metrics
+            .get_signer_contributing_last_certificate()
+            .record(certificate.metadata.signers.len() as u32);
+
+        #[allow(deprecated)]
+        let enable_per_signer_metrics = self.dependencies.config.enable_per_signer_metrics;
+        if enable_per_signer_metrics {
+            let contributors: BTreeSet<&str> = certificate
+                .metadata
+                .signers
+                .iter()
+                .map(|party| party.party_id.as_str())
+                .collect();
+
+            for signer in &registered_signers {
+                if contributors.contains(signer.party_id.as_str()) {
+                    metrics
+                        .get_signer_contribution_per_party_since_startup()
+                        .increment(&[&signer.party_id]);
+                } else {
+                    metrics
+                        .get_signer_missed_per_party_since_startup()
+                        .increment(&[&signer.party_id]);
+                }
+            }
+        }
+    }
+
     fn increment_artifact_total_produced_metric_since_startup(
         &self,
         signed_entity_type: &SignedEntityType,
@@ -208,6 +258,9 @@ impl AggregatorRunnerTrait for AggregatorRunner {
             .ticker_service
             .get_current_time_point()
             .await?;
+        self.dependencies
+            .certification_watchdog
+            .observe_time_point(&time_point);
 
         Ok(time_point)
     }
@@ -422,11 +475,23 @@ impl AggregatorRunnerTrait for AggregatorRunner {
                 )
             })?;
 
-        if certificate.is_some() {
+        if let Some(certificate) = &certificate {
             self.dependencies
                 .metrics_service
                 .get_certificate_total_produced_since_startup()
                 .increment();
+            self.dependencies
+                .certification_watchdog
+                .record_certificate(certificate);
+            self.record_signer_participation_metrics(certificate).await;
+            if let Err(error) = self
+                .dependencies
+                .certificate_transparency_log_publisher
+                .publish(certificate)
+                .await
+            {
+                warn!(self.logger, "Could not publish the certificate to the transparency log"; "certificate_hash" => &certificate.hash, "error" => ?error);
+            }
         }
 
         Ok(certificate)
@@ -565,8 +630,21 @@ impl AggregatorRunnerTrait for AggregatorRunner {
                 .time_point_to_signed_entity(&open_message_signed_entity_type, last_time_point)?;
             new_signed_entity_type != open_message_signed_entity_type
         };
+        let is_outdated = exists_newer_open_message || is_expired_open_message;
+
+        if let Some(open_message) = &current_open_message {
+            if is_outdated {
+                self.dependencies
+                    .certification_watchdog
+                    .report_missed_open_message(open_message);
+            } else {
+                self.dependencies
+                    .certification_watchdog
+                    .check_pending_open_message(open_message);
+            }
+        }
 
-        Ok(exists_newer_open_message || is_expired_open_message)
+        Ok(is_outdated)
     }
 
     fn increment_runtime_cycle_success_since_startup_counter(&self) {
@@ -593,9 +671,14 @@ pub mod tests {
         entities::OpenMessage,
         initialize_dependencies,
         runtime::{AggregatorRunner, AggregatorRunnerTrait},
-        services::{MithrilStakeDistributionService, MockCertifierService},
+        services::{
+            MithrilStakeDistributionService, MockCertificateTransparencyLogPublisher,
+            MockCertifierService,
+        },
+        test_tools::TestLogger,
         Configuration, DependencyContainer, MithrilSignerRegisterer, SignerRegistrationRound,
     };
+    use anyhow::anyhow;
     use async_trait::async_trait;
     use chrono::{DateTime, Utc};
     use mithril_common::entities::{
@@ -604,11 +687,11 @@ pub mod tests {
     };
     use mithril_common::signed_entity_type_lock::SignedEntityTypeLock;
     use mithril_common::{
-        chain_observer::FakeObserver,
+        chain_observer::{ChainObserverStakeDistributionSource, FakeObserver},
         digesters::DumbImmutableFileObserver,
         entities::{
             CertificatePending, ProtocolMessage, SignedEntityType, Signer, StakeDistribution,
-            TimePoint,
+            StakeDistributionParty, TimePoint,
         },
         signable_builder::SignableBuilderService,
         test_utils::{fake_data, MithrilFixtureBuilder},
@@ -618,6 +701,7 @@ pub mod tests {
     use mockall::predicate::eq;
     use mockall::{mock, Sequence};
     use std::sync::Arc;
+    use std::time::Duration;
     use tokio::sync::RwLock;
 
     mock! {
@@ -678,6 +762,113 @@ pub mod tests {
         runner
     }
 
+    #[tokio::test]
+    async fn test_create_certificate_record_signer_participation_metrics() {
+        let mut mock_certifier_service = MockCertifierService::new();
+        mock_certifier_service
+            .expect_inform_epoch()
+            .return_once(|_| Ok(()));
+        mock_certifier_service
+            .expect_create_certificate()
+            .return_once(|_| {
+                let mut certificate = fake_data::certificate("certificate_hash");
+                certificate.metadata.signers = vec![StakeDistributionParty {
+                    party_id: "unregistered_party".to_string(),
+                    stake: 10,
+                }];
+                Ok(Some(certificate))
+            });
+        let mut deps = initialize_dependencies().await;
+        #[allow(deprecated)]
+        let config = &mut deps.config;
+        config.enable_per_signer_metrics = true;
+        deps.certifier_service = Arc::new(mock_certifier_service);
+        let runner = build_runner_with_fixture_data(deps).await;
+        let current_epoch = runner
+            .dependencies
+            .ticker_service
+            .get_current_epoch()
+            .await
+            .unwrap();
+        runner.inform_new_epoch(current_epoch).await.unwrap();
+        runner.precompute_epoch_data().await.unwrap();
+
+        runner
+            .create_certificate(&SignedEntityType::MithrilStakeDistribution(current_epoch))
+            .await
+            .unwrap();
+
+        let metrics_service = &runner.dependencies.metrics_service;
+        assert_eq!(
+            5.0,
+            metrics_service.get_signer_registered_current_epoch().get()
+        );
+        assert_eq!(
+            1.0,
+            metrics_service
+                .get_signer_contributing_last_certificate()
+                .get()
+        );
+        let registered_signers = runner
+            .dependencies
+            .epoch_service
+            .read()
+            .await
+            .current_signers()
+            .unwrap()
+            .clone();
+        for signer in registered_signers {
+            assert_eq!(
+                1,
+                metrics_service
+                    .get_signer_missed_per_party_since_startup()
+                    .get(&[&signer.party_id])
+            );
+            assert_eq!(
+                0,
+                metrics_service
+                    .get_signer_contribution_per_party_since_startup()
+                    .get(&[&signer.party_id])
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_certificate_publish_the_certificate_to_the_transparency_log() {
+        let mut mock_certifier_service = MockCertifierService::new();
+        mock_certifier_service
+            .expect_inform_epoch()
+            .return_once(|_| Ok(()));
+        mock_certifier_service
+            .expect_create_certificate()
+            .return_once(|_| Ok(Some(fake_data::certificate("certificate_hash"))));
+        let mut mock_transparency_log_publisher = MockCertificateTransparencyLogPublisher::new();
+        mock_transparency_log_publisher
+            .expect_publish()
+            .withf(|certificate| certificate.hash == "certificate_hash")
+            .once()
+            .returning(|_| Err(anyhow!("transparency log unavailable")));
+        let mut deps = initialize_dependencies().await;
+        deps.certifier_service = Arc::new(mock_certifier_service);
+        deps.certificate_transparency_log_publisher = Arc::new(mock_transparency_log_publisher);
+        let runner = build_runner_with_fixture_data(deps).await;
+        let current_epoch = runner
+            .dependencies
+            .ticker_service
+            .get_current_epoch()
+            .await
+            .unwrap();
+        runner.inform_new_epoch(current_epoch).await.unwrap();
+        runner.precompute_epoch_data().await.unwrap();
+
+        let certificate = runner
+            .create_certificate(&SignedEntityType::MithrilStakeDistribution(current_epoch))
+            .await
+            .expect("A transparency log failure should not fail the certificate creation");
+
+        assert!(certificate.is_some());
+    }
+
     fn init_certifier_service_mock(
         mock_certifier_service: &mut MockCertifierService,
         messages: Vec<OpenMessage>,
@@ -750,6 +941,9 @@ pub mod tests {
         deps.stake_distribution_service = Arc::new(MithrilStakeDistributionService::new(
             deps.stake_store.clone(),
             chain_observer.clone(),
+            Arc::new(ChainObserverStakeDistributionSource::new(
+                chain_observer.clone(),
+            )),
         ));
         let deps = Arc::new(deps);
         let runner = AggregatorRunner::new(deps.clone());
@@ -788,6 +982,9 @@ pub mod tests {
             deps.verification_key_store.clone(),
             deps.signer_recorder.clone(),
             None,
+            Duration::ZERO,
+            deps.metrics_service.clone(),
+            TestLogger::stdout(),
         ));
         deps.signer_registration_round_opener = signer_registration_round_opener.clone();
         let stake_store = deps.stake_store.clone();
@@ -828,6 +1025,9 @@ pub mod tests {
             deps.verification_key_store.clone(),
             deps.signer_recorder.clone(),
             None,
+            Duration::ZERO,
+            deps.metrics_service.clone(),
+            TestLogger::stdout(),
         ));
         deps.signer_registration_round_opener = signer_registration_round_opener.clone();
         let deps = Arc::new(deps);
@@ -1,12 +1,13 @@
 use crate::{
     entities::OpenMessage,
-    runtime::{AggregatorRunnerTrait, RuntimeError},
+    runtime::{AggregatorRunnerTrait, RuntimeError, RuntimeStatusReporter},
     AggregatorConfig,
 };
 
 use anyhow::Context;
+use mithril_common::digesters::ImmutableFileWatcher;
 use mithril_common::entities::TimePoint;
-use mithril_common::logging::LoggerExtensions;
+use mithril_common::logging::{CorrelationId, LoggerExtensions};
 use slog::{info, trace, Logger};
 use std::fmt::Display;
 use std::sync::Arc;
@@ -14,18 +15,18 @@ use tokio::time::sleep;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct IdleState {
-    current_time_point: Option<TimePoint>,
+    pub(crate) current_time_point: Option<TimePoint>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct ReadyState {
-    current_time_point: TimePoint,
+    pub(crate) current_time_point: TimePoint,
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct SigningState {
-    current_time_point: TimePoint,
-    open_message: OpenMessage,
+    pub(crate) current_time_point: TimePoint,
+    pub(crate) open_message: OpenMessage,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -35,6 +36,35 @@ pub enum AggregatorState {
     Signing(SigningState),
 }
 
+impl AggregatorState {
+    /// Name of the state
+    pub fn name(&self) -> &'static str {
+        match self {
+            AggregatorState::Idle(_) => "idle",
+            AggregatorState::Ready(_) => "ready",
+            AggregatorState::Signing(_) => "signing",
+        }
+    }
+
+    /// Numeric code of the state, used to expose it as a metric
+    pub fn metric_code(&self) -> u32 {
+        match self {
+            AggregatorState::Idle(_) => 0,
+            AggregatorState::Ready(_) => 1,
+            AggregatorState::Signing(_) => 2,
+        }
+    }
+
+    /// Time point of the state, if any
+    pub fn time_point(&self) -> Option<&TimePoint> {
+        match self {
+            AggregatorState::Idle(state) => state.current_time_point.as_ref(),
+            AggregatorState::Ready(state) => Some(&state.current_time_point),
+            AggregatorState::Signing(state) => Some(&state.current_time_point),
+        }
+    }
+}
+
 impl Display for AggregatorState {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -61,6 +91,8 @@ pub struct AggregatorRuntime {
     config: AggregatorConfig,
     state: AggregatorState,
     runner: Arc<dyn AggregatorRunnerTrait>,
+    status_reporter: Arc<RuntimeStatusReporter>,
+    immutable_file_watcher: Option<Arc<ImmutableFileWatcher>>,
     logger: Logger,
 }
 
@@ -70,6 +102,7 @@ impl AggregatorRuntime {
         aggregator_config: AggregatorConfig,
         init_state: Option<AggregatorState>,
         runner: Arc<dyn AggregatorRunnerTrait>,
+        status_reporter: Arc<RuntimeStatusReporter>,
         logger: Logger,
     ) -> Result<Self, RuntimeError> {
         let logger = logger.new_with_component_name::<Self>();
@@ -89,17 +122,25 @@ impl AggregatorRuntime {
             config: aggregator_config,
             state,
             runner,
+            status_reporter,
+            immutable_file_watcher: None,
             logger,
         })
     }
 
+    /// Start a new cycle as soon as the given watcher detects a new immutable file, instead of
+    /// waiting for the end of the run interval.
+    pub fn with_immutable_file_watcher(
+        mut self,
+        immutable_file_watcher: Arc<ImmutableFileWatcher>,
+    ) -> Self {
+        self.immutable_file_watcher = Some(immutable_file_watcher);
+        self
+    }
+
     /// Return the actual state of the state machine.
     pub fn get_state(&self) -> String {
-        match self.state {
-            AggregatorState::Idle(_) => "idle".to_string(),
-            AggregatorState::Ready(_) => "ready".to_string(),
-            AggregatorState::Signing(_) => "signing".to_string(),
-        }
+        self.state.name().to_string()
     }
 
     /// Launches an infinite loop ticking the state machine.
@@ -107,7 +148,13 @@ impl AggregatorRuntime {
         info!(self.logger, "Launching State Machine");
 
         loop {
-            if let Err(e) = self.cycle().await {
+            // All the logs of a cycle share a correlation id
+            let cycle_result = CorrelationId::generate().scope(self.cycle()).await;
+            self.status_reporter
+                .record_cycle(&self.state, cycle_result.as_ref().err())
+                .await;
+
+            if let Err(e) = cycle_result {
                 e.write_to_log(&self.logger);
                 if e.is_critical() {
                     return Err(e);
@@ -119,7 +166,24 @@ impl AggregatorRuntime {
                 "… Cycle finished, Sleeping for {} ms",
                 self.config.interval.as_millis()
             );
-            sleep(self.config.interval).await;
+            tokio::select! {
+                _ = sleep(self.config.interval) => {}
+                _ = self.wait_for_new_immutable_file() => {}
+            }
+        }
+    }
+
+    /// Wait for a new immutable file, never returns if there is no immutable file watcher.
+    async fn wait_for_new_immutable_file(&self) {
+        match &self.immutable_file_watcher {
+            Some(watcher) => {
+                let immutable_file_number = watcher.wait_for_new_immutable_file().await;
+                info!(
+                    self.logger, "New immutable file detected, starting a new cycle";
+                    "immutable_file_number" => immutable_file_number
+                );
+            }
+            None => std::future::pending().await,
         }
     }
 
@@ -390,6 +454,7 @@ mod tests {
     use mithril_common::test_utils::fake_data;
 
     use crate::test_tools::TestLogger;
+    use crate::MetricsService;
 
     use super::super::runner::MockAggregatorRunner;
     use super::*;
@@ -402,6 +467,9 @@ mod tests {
             AggregatorConfig::new(Duration::from_millis(20)),
             init_state,
             Arc::new(runner),
+            Arc::new(RuntimeStatusReporter::new(Arc::new(
+                MetricsService::new(TestLogger::stdout()).unwrap(),
+            ))),
             TestLogger::stdout(),
         )
         .await
@@ -0,0 +1,209 @@
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use mithril_common::entities::TimePoint;
+
+use crate::runtime::{AggregatorState, RuntimeError};
+use crate::MetricsService;
+
+/// Last error encountered by the runtime
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RuntimeStatusError {
+    /// Error message
+    pub message: String,
+
+    /// True if the error aborted the runtime
+    pub is_critical: bool,
+
+    /// Date at which the error occurred
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// Snapshot of the runtime state machine
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RuntimeStatus {
+    /// Name of the current state
+    pub state: String,
+
+    /// Time point of the current state, if any
+    pub time_point: Option<TimePoint>,
+
+    /// Date of the last state transition (or of the runtime startup)
+    pub last_transition_at: DateTime<Utc>,
+
+    /// Date of the last completed cycle
+    pub last_cycle_at: Option<DateTime<Utc>>,
+
+    /// Last error encountered by the runtime
+    pub last_error: Option<RuntimeStatusError>,
+}
+
+impl RuntimeStatus {
+    /// Number of seconds elapsed since the last state transition
+    pub fn seconds_in_current_state(&self, now: DateTime<Utc>) -> i64 {
+        (now - self.last_transition_at).num_seconds().max(0)
+    }
+}
+
+/// Keep track of the runtime state machine status so it can be exposed to external monitoring.
+pub struct RuntimeStatusReporter {
+    status: RwLock<RuntimeStatus>,
+    metrics_service: Arc<MetricsService>,
+}
+
+impl RuntimeStatusReporter {
+    /// Create a new instance, the runtime is considered `idle` since its creation.
+    pub fn new(metrics_service: Arc<MetricsService>) -> Self {
+        let now = Utc::now();
+        metrics_service
+            .get_runtime_last_transition_timestamp()
+            .record(now.timestamp() as f64);
+
+        Self {
+            status: RwLock::new(RuntimeStatus {
+                state: "idle".to_string(),
+                time_point: None,
+                last_transition_at: now,
+                last_cycle_at: None,
+                last_error: None,
+            }),
+            metrics_service,
+        }
+    }
+
+    /// Record the outcome of a runtime cycle.
+    pub async fn record_cycle(&self, state: &AggregatorState, error: Option<&RuntimeError>) {
+        let now = Utc::now();
+        let mut status = self.status.write().await;
+
+        let state_name = state.name();
+        if status.state != state_name {
+            status.state = state_name.to_string();
+            status.last_transition_at = now;
+            self.metrics_service
+                .get_runtime_last_transition_timestamp()
+                .record(now.timestamp() as f64);
+        }
+        status.time_point = state.time_point().cloned();
+        status.last_cycle_at = Some(now);
+
+        if let Some(error) = error {
+            status.last_error = Some(RuntimeStatusError {
+                message: error.to_string(),
+                is_critical: error.is_critical(),
+                occurred_at: now,
+            });
+            self.metrics_service
+                .get_runtime_cycle_error_since_startup()
+                .increment();
+        }
+
+        self.metrics_service
+            .get_runtime_current_state()
+            .record(state.metric_code());
+        self.metrics_service
+            .get_runtime_time_in_current_state_seconds()
+            .record(status.seconds_in_current_state(now) as f64);
+    }
+
+    /// Get a snapshot of the current runtime status.
+    pub async fn get_status(&self) -> RuntimeStatus {
+        self.status.read().await.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mithril_common::entities::Epoch;
+
+    use crate::entities::OpenMessage;
+    use crate::runtime::{IdleState, ReadyState, SigningState};
+    use crate::test_tools::TestLogger;
+
+    use super::*;
+
+    fn build_reporter() -> (RuntimeStatusReporter, Arc<MetricsService>) {
+        let metrics_service = Arc::new(MetricsService::new(TestLogger::stdout()).unwrap());
+        (
+            RuntimeStatusReporter::new(metrics_service.clone()),
+            metrics_service,
+        )
+    }
+
+    fn ready_state(epoch: u64) -> AggregatorState {
+        AggregatorState::Ready(ReadyState {
+            current_time_point: TimePoint {
+                epoch: Epoch(epoch),
+                ..TimePoint::dummy()
+            },
+        })
+    }
+
+    #[tokio::test]
+    async fn initial_status_is_idle_without_error() {
+        let (reporter, _) = build_reporter();
+
+        let status = reporter.get_status().await;
+
+        assert_eq!("idle", status.state);
+        assert_eq!(None, status.time_point);
+        assert_eq!(None, status.last_cycle_at);
+        assert_eq!(None, status.last_error);
+    }
+
+    #[tokio::test]
+    async fn record_cycle_update_last_transition_only_when_state_changes() {
+        let (reporter, metrics_service) = build_reporter();
+        let initial_status = reporter.get_status().await;
+
+        reporter
+            .record_cycle(
+                &AggregatorState::Idle(IdleState {
+                    current_time_point: None,
+                }),
+                None,
+            )
+            .await;
+        let status = reporter.get_status().await;
+        assert_eq!(initial_status.last_transition_at, status.last_transition_at);
+        assert!(status.last_cycle_at.is_some());
+
+        reporter.record_cycle(&ready_state(3), None).await;
+        let status = reporter.get_status().await;
+        assert_eq!("ready", status.state);
+        assert_eq!(Some(Epoch(3)), status.time_point.map(|t| t.epoch));
+        assert!(status.last_transition_at > initial_status.last_transition_at);
+        assert_eq!(1.0, metrics_service.get_runtime_current_state().get());
+
+        reporter
+            .record_cycle(
+                &AggregatorState::Signing(SigningState {
+                    current_time_point: TimePoint::dummy(),
+                    open_message: OpenMessage::dummy(),
+                }),
+                None,
+            )
+            .await;
+        assert_eq!(2.0, metrics_service.get_runtime_current_state().get());
+    }
+
+    #[tokio::test]
+    async fn record_cycle_with_error_keep_it_as_last_error() {
+        let (reporter, metrics_service) = build_reporter();
+        let error = RuntimeError::keep_state("an error", None);
+
+        reporter.record_cycle(&ready_state(3), Some(&error)).await;
+        reporter.record_cycle(&ready_state(4), None).await;
+
+        let last_error = reporter.get_status().await.last_error.unwrap();
+        assert_eq!(error.to_string(), last_error.message);
+        assert!(!last_error.is_critical);
+        assert_eq!(
+            1,
+            metrics_service
+                .get_runtime_cycle_error_since_startup()
+                .get()
+        );
+    }
+}
@@ -0,0 +1,551 @@
+//! ## Alerting
+//!
+//! This service periodically evaluates simple rules on the state of the aggregator (age of the
+//! latest certificate, participation of the signers) and notifies a webhook when an alert starts
+//! firing or is resolved, so operators without a metrics stack are warned of issues.
+
+use anyhow::{anyhow, Context};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use slog::{info, warn, Logger};
+use std::collections::BTreeSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use mithril_common::entities::Certificate;
+use mithril_common::logging::LoggerExtensions;
+use mithril_common::StdResult;
+
+use crate::database::repository::CertificateRepository;
+use crate::dependency_injection::EpochServiceWrapper;
+
+/// Format of the JSON payload sent to the alerting webhook.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertWebhookFormat {
+    /// Slack incoming webhook (`{"text": "..."}`).
+    Slack,
+    /// PagerDuty Events API v2.
+    PagerDuty,
+}
+
+/// Rules that can raise an alert.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AlertRule {
+    /// No certificate has been issued for longer than the configured threshold.
+    NoRecentCertificate,
+    /// The ratio of signers that participated to the latest certificate is too low.
+    LowSignerParticipation,
+}
+
+impl AlertRule {
+    fn name(&self) -> &'static str {
+        match self {
+            AlertRule::NoRecentCertificate => "no_recent_certificate",
+            AlertRule::LowSignerParticipation => "low_signer_participation",
+        }
+    }
+}
+
+/// Status of an alert.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertStatus {
+    /// The rule has started to be violated.
+    Firing,
+    /// The rule is no longer violated.
+    Resolved,
+}
+
+/// Alert sent to the notifier.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alert {
+    /// Rule that raised the alert.
+    pub rule: AlertRule,
+    /// Status of the alert.
+    pub status: AlertStatus,
+    /// Human readable description of the alert.
+    pub summary: String,
+}
+
+/// Thresholds of the alerting rules.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlertingRules {
+    /// Maximum age of the latest certificate before an alert is raised.
+    pub no_certificate_threshold: Duration,
+    /// Minimum percentage of the signers participating to a certificate, `0` disables the rule.
+    pub min_signer_participation_percent: u8,
+}
+
+impl AlertingRules {
+    /// Return the rules that are violated with their summary.
+    ///
+    /// Without any certificate, the time without a new certificate is counted from `started_at`.
+    pub fn evaluate(
+        &self,
+        latest_certificate: Option<&Certificate>,
+        current_signers_count: Option<usize>,
+        started_at: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Vec<(AlertRule, String)> {
+        let mut violations = vec![];
+        let Some(certificate) = latest_certificate else {
+            let time_without_certificate = (now - started_at).to_std().unwrap_or_default();
+            if time_without_certificate > self.no_certificate_threshold {
+                violations.push((
+                    AlertRule::NoRecentCertificate,
+                    format!(
+                        "No certificate issued for {} minutes, no certificate was ever issued",
+                        time_without_certificate.as_secs() / 60,
+                    ),
+                ));
+            }
+            return violations;
+        };
+
+        let certificate_age = (now - certificate.metadata.sealed_at)
+            .to_std()
+            .unwrap_or_default();
+        if certificate_age > self.no_certificate_threshold {
+            violations.push((
+                AlertRule::NoRecentCertificate,
+                format!(
+                    "No certificate issued for {} minutes, latest certificate '{}' was issued at {}",
+                    certificate_age.as_secs() / 60,
+                    certificate.hash,
+                    certificate.metadata.sealed_at
+                ),
+            ));
+        }
+
+        if let Some(signers_count) = current_signers_count.filter(|count| *count > 0) {
+            let participation_percent =
+                certificate.metadata.signers.len() as f64 * 100.0 / signers_count as f64;
+            if participation_percent < self.min_signer_participation_percent as f64 {
+                violations.push((
+                    AlertRule::LowSignerParticipation,
+                    format!(
+                        "Only {participation_percent:.1}% of the signers ({}/{signers_count}) participated to the latest certificate '{}' (minimum: {}%)",
+                        certificate.metadata.signers.len(),
+                        certificate.hash,
+                        self.min_signer_participation_percent
+                    ),
+                ));
+            }
+        }
+
+        violations
+    }
+}
+
+/// Notify alerts to the operator.
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait AlertNotifier: Sync + Send {
+    /// Send the given alert.
+    async fn notify(&self, alert: &Alert) -> StdResult<()>;
+}
+
+/// [AlertNotifier] that posts the alerts as JSON to a webhook.
+pub struct WebhookAlertNotifier {
+    url: String,
+    format: AlertWebhookFormat,
+    routing_key: Option<String>,
+    client: reqwest::Client,
+}
+
+impl WebhookAlertNotifier {
+    /// Create a new [WebhookAlertNotifier].
+    ///
+    /// The `routing_key` is needed by the PagerDuty format, the creation fails without it.
+    pub fn new(
+        url: &str,
+        format: AlertWebhookFormat,
+        routing_key: Option<String>,
+    ) -> StdResult<Self> {
+        if format == AlertWebhookFormat::PagerDuty && routing_key.is_none() {
+            return Err(anyhow!(
+                "A PagerDuty routing key is required by the 'pagerduty' alerting webhook format"
+            ));
+        }
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .with_context(|| "Http Client build failed")?;
+
+        Ok(Self {
+            url: url.to_string(),
+            format,
+            routing_key,
+            client,
+        })
+    }
+
+    fn payload(&self, alert: &Alert) -> serde_json::Value {
+        match self.format {
+            AlertWebhookFormat::Slack => {
+                let status = match alert.status {
+                    AlertStatus::Firing => "FIRING",
+                    AlertStatus::Resolved => "RESOLVED",
+                };
+                json!({ "text": format!("[{status}] mithril-aggregator: {}", alert.summary) })
+            }
+            AlertWebhookFormat::PagerDuty => {
+                let event_action = match alert.status {
+                    AlertStatus::Firing => "trigger",
+                    AlertStatus::Resolved => "resolve",
+                };
+                json!({
+                    "routing_key": self.routing_key.clone().unwrap_or_default(),
+                    "event_action": event_action,
+                    "dedup_key": format!("mithril-aggregator-{}", alert.rule.name()),
+                    "payload": {
+                        "summary": alert.summary,
+                        "source": "mithril-aggregator",
+                        "severity": "warning",
+                    }
+                })
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl AlertNotifier for WebhookAlertNotifier {
+    async fn notify(&self, alert: &Alert) -> StdResult<()> {
+        self.client
+            .post(&self.url)
+            .json(&self.payload(alert))
+            .send()
+            .await
+            .with_context(|| format!("Alert webhook call failed: '{}'", self.url))?
+            .error_for_status()
+            .with_context(|| format!("Alert webhook returned an error: '{}'", self.url))?;
+
+        Ok(())
+    }
+}
+
+/// Alerting service
+pub struct AlertingService {
+    rules: AlertingRules,
+    certificate_repository: Arc<CertificateRepository>,
+    epoch_service: EpochServiceWrapper,
+    notifier: Arc<dyn AlertNotifier>,
+    firing_rules: Mutex<BTreeSet<AlertRule>>,
+    started_at: DateTime<Utc>,
+    logger: Logger,
+}
+
+impl AlertingService {
+    /// Create a new instance of the alerting service.
+    pub fn new(
+        rules: AlertingRules,
+        certificate_repository: Arc<CertificateRepository>,
+        epoch_service: EpochServiceWrapper,
+        notifier: Arc<dyn AlertNotifier>,
+        logger: Logger,
+    ) -> Self {
+        Self {
+            rules,
+            certificate_repository,
+            epoch_service,
+            notifier,
+            firing_rules: Mutex::new(BTreeSet::new()),
+            started_at: Utc::now(),
+            logger: logger.new_with_component_name::<Self>(),
+        }
+    }
+
+    /// Compute the alerts to send given the rules that are currently violated.
+    ///
+    /// Only changes are reported: an alert is sent when a rule starts being violated and when
+    /// it is no longer violated.
+    fn compute_alerts(&self, violations: Vec<(AlertRule, String)>) -> Vec<Alert> {
+        let mut firing_rules = self.firing_rules.lock().unwrap();
+        let violated_rules: BTreeSet<AlertRule> =
+            violations.iter().map(|(rule, _)| *rule).collect();
+
+        let resolved_alerts = firing_rules
+            .difference(&violated_rules)
+            .map(|rule| Alert {
+                rule: *rule,
+                status: AlertStatus::Resolved,
+                summary: format!("Alert '{}' is resolved", rule.name()),
+            })
+            .collect::<Vec<_>>();
+        let firing_alerts = violations
+            .into_iter()
+            .filter(|(rule, _)| !firing_rules.contains(rule))
+            .map(|(rule, summary)| Alert {
+                rule,
+                status: AlertStatus::Firing,
+                summary,
+            })
+            .collect::<Vec<_>>();
+        *firing_rules = violated_rules;
+
+        [resolved_alerts, firing_alerts].concat()
+    }
+
+    /// Evaluate the rules and notify the alerts that changed.
+    pub async fn check(&self) -> StdResult<()> {
+        let latest_certificate: Option<Certificate> = self
+            .certificate_repository
+            .get_latest_certificates(1)
+            .await?
+            .pop();
+        let current_signers_count = {
+            let epoch_service = self.epoch_service.read().await;
+            match (&latest_certificate, epoch_service.epoch_of_current_data()) {
+                (Some(certificate), Ok(epoch)) if certificate.epoch == epoch => epoch_service
+                    .current_signers()
+                    .map(|signers| signers.len())
+                    .ok(),
+                _ => None,
+            }
+        };
+
+        let violations = self.rules.evaluate(
+            latest_certificate.as_ref(),
+            current_signers_count,
+            self.started_at,
+            Utc::now(),
+        );
+
+        for alert in self.compute_alerts(violations) {
+            warn!(self.logger, "Alert {:?}", alert.status; "rule" => alert.rule.name(), "summary" => &alert.summary);
+            self.notifier
+                .notify(&alert)
+                .await
+                .with_context(|| format!("Could not notify alert '{}'", alert.rule.name()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Start a loop that evaluates the alerting rules at the given time interval.
+    pub async fn run_forever(&self, run_interval: Duration) {
+        let mut interval = tokio::time::interval(run_interval);
+
+        loop {
+            interval.tick().await;
+            if let Err(error) = self.check().await {
+                warn!(self.logger, "Alerting check failed"; "error" => ?error);
+            }
+
+            info!(
+                self.logger,
+                "Alerting rules evaluated, Sleeping for {} seconds",
+                run_interval.as_secs()
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mithril_common::entities::StakeDistributionParty;
+    use mithril_common::test_utils::fake_data;
+
+    use crate::database::test_helper::main_db_connection;
+    use crate::services::FakeEpochService;
+    use crate::test_tools::TestLogger;
+
+    use super::*;
+
+    fn rules() -> AlertingRules {
+        AlertingRules {
+            no_certificate_threshold: Duration::from_secs(60 * 60),
+            min_signer_participation_percent: 50,
+        }
+    }
+
+    fn certificate_sealed_at(sealed_at: DateTime<Utc>, signers_count: usize) -> Certificate {
+        let mut certificate = fake_data::certificate("certificate_hash".to_string());
+        certificate.metadata.sealed_at = sealed_at;
+        certificate.metadata.signers = (0..signers_count)
+            .map(|i| StakeDistributionParty {
+                party_id: format!("pool{i}"),
+                stake: 10,
+            })
+            .collect();
+        certificate
+    }
+
+    fn build_service(notifier: MockAlertNotifier) -> AlertingService {
+        let connection = Arc::new(main_db_connection().unwrap());
+        let epoch_service = FakeEpochService::without_data();
+
+        AlertingService::new(
+            rules(),
+            Arc::new(CertificateRepository::new(connection)),
+            Arc::new(tokio::sync::RwLock::new(epoch_service)),
+            Arc::new(notifier),
+            TestLogger::stdout(),
+        )
+    }
+
+    #[test]
+    fn no_violation_without_certificate_since_less_than_the_threshold() {
+        let now = Utc::now();
+
+        let violations = rules().evaluate(None, Some(10), now - chrono::Duration::minutes(59), now);
+
+        assert!(violations.is_empty());
+    }
+
+    #[test]
+    fn violation_without_certificate_since_more_than_the_threshold() {
+        let now = Utc::now();
+
+        let violations = rules().evaluate(None, Some(10), now - chrono::Duration::minutes(61), now);
+
+        assert_eq!(
+            vec![AlertRule::NoRecentCertificate],
+            violations
+                .into_iter()
+                .map(|(rule, _)| rule)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn no_violation_with_recent_certificate_signed_by_enough_signers() {
+        let now = Utc::now();
+        let certificate = certificate_sealed_at(now - chrono::Duration::minutes(5), 5);
+
+        let violations = rules().evaluate(Some(&certificate), Some(10), now, now);
+
+        assert!(violations.is_empty());
+    }
+
+    #[test]
+    fn violation_when_latest_certificate_is_too_old() {
+        let now = Utc::now();
+        let certificate = certificate_sealed_at(now - chrono::Duration::minutes(61), 5);
+
+        let violations = rules().evaluate(Some(&certificate), Some(10), now, now);
+
+        assert_eq!(
+            vec![AlertRule::NoRecentCertificate],
+            violations
+                .into_iter()
+                .map(|(rule, _)| rule)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn violation_when_signer_participation_is_too_low() {
+        let now = Utc::now();
+        let certificate = certificate_sealed_at(now, 4);
+
+        let violations = rules().evaluate(Some(&certificate), Some(10), now, now);
+
+        assert_eq!(
+            vec![AlertRule::LowSignerParticipation],
+            violations
+                .into_iter()
+                .map(|(rule, _)| rule)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn signer_participation_rule_is_disabled_with_zero_percent() {
+        let now = Utc::now();
+        let certificate = certificate_sealed_at(now, 0);
+        let rules = AlertingRules {
+            min_signer_participation_percent: 0,
+            ..rules()
+        };
+
+        let violations = rules.evaluate(Some(&certificate), Some(10), now, now);
+
+        assert!(violations.is_empty());
+    }
+
+    #[test]
+    fn alerts_are_only_sent_when_a_rule_starts_or_stops_being_violated() {
+        let service = build_service(MockAlertNotifier::new());
+        let violation = || vec![(AlertRule::NoRecentCertificate, "no certificate".to_string())];
+
+        let alerts = service.compute_alerts(violation());
+        assert_eq!(
+            vec![Alert {
+                rule: AlertRule::NoRecentCertificate,
+                status: AlertStatus::Firing,
+                summary: "no certificate".to_string()
+            }],
+            alerts
+        );
+
+        let alerts = service.compute_alerts(violation());
+        assert!(alerts.is_empty());
+
+        let alerts = service.compute_alerts(vec![]);
+        assert_eq!(1, alerts.len());
+        assert_eq!(AlertStatus::Resolved, alerts[0].status);
+        assert_eq!(AlertRule::NoRecentCertificate, alerts[0].rule);
+    }
+
+    #[tokio::test]
+    async fn check_does_not_notify_when_no_certificate_exists_yet() {
+        let mut notifier = MockAlertNotifier::new();
+        notifier.expect_notify().never();
+        let service = build_service(notifier);
+
+        service.check().await.unwrap();
+    }
+
+    #[test]
+    fn pagerduty_payload_use_routing_key_and_a_dedup_key_per_rule() {
+        let notifier = WebhookAlertNotifier::new(
+            "http://localhost",
+            AlertWebhookFormat::PagerDuty,
+            Some("routing-key".to_string()),
+        )
+        .unwrap();
+        let alert = Alert {
+            rule: AlertRule::LowSignerParticipation,
+            status: AlertStatus::Resolved,
+            summary: "summary".to_string(),
+        };
+
+        let payload = notifier.payload(&alert);
+
+        assert_eq!("routing-key", payload["routing_key"]);
+        assert_eq!("resolve", payload["event_action"]);
+        assert_eq!(
+            "mithril-aggregator-low_signer_participation",
+            payload["dedup_key"]
+        );
+        assert_eq!("summary", payload["payload"]["summary"]);
+    }
+
+    #[test]
+    fn pagerduty_format_requires_a_routing_key() {
+        assert!(
+            WebhookAlertNotifier::new("http://localhost", AlertWebhookFormat::PagerDuty, None)
+                .is_err(),
+            "The PagerDuty format should require a routing key"
+        );
+    }
+
+    #[test]
+    fn slack_payload_contains_status_and_summary() {
+        let notifier =
+            WebhookAlertNotifier::new("http://localhost", AlertWebhookFormat::Slack, None).unwrap();
+        let alert = Alert {
+            rule: AlertRule::NoRecentCertificate,
+            status: AlertStatus::Firing,
+            summary: "summary".to_string(),
+        };
+
+        let payload = notifier.payload(&alert);
+
+        assert_eq!("[FIRING] mithril-aggregator: summary", payload["text"]);
+    }
+}
@@ -0,0 +1,439 @@
+//! ## Artifact Availability
+//!
+//! This service periodically checks, with `HEAD` requests, that the download locations of the
+//! published artifacts are still reachable, so dead locations are noticed by the operators
+//! instead of being discovered by the clients at download time.
+//!
+//! Each unreachable location is reported with a metric and an event, and can optionally be
+//! demoted at the end of the locations of its artifact so clients try the reachable ones first.
+
+use anyhow::Context;
+use async_trait::async_trait;
+use serde::Serialize;
+use slog::{debug, info, warn, Logger};
+use std::sync::Arc;
+use std::time::Duration;
+
+use mithril_common::entities::{SignedEntityType, SignedEntityTypeDiscriminants, Snapshot};
+use mithril_common::logging::LoggerExtensions;
+use mithril_common::StdResult;
+
+use crate::database::record::SignedEntityRecord;
+use crate::database::repository::SignedEntityStorer;
+use crate::event_store::{EventMessage, TransmitterService};
+use crate::MetricsService;
+
+/// Checker of the reachability of an artifact download location
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait ArtifactLocationChecker: Sync + Send {
+    /// Check that the given location is reachable, returning the reason why it's not otherwise.
+    async fn check(&self, location: &str) -> StdResult<()>;
+}
+
+/// [ArtifactLocationChecker] that sends a `HEAD` request to the location
+pub struct HttpArtifactLocationChecker {
+    client: reqwest::Client,
+}
+
+impl HttpArtifactLocationChecker {
+    /// Create a new [HttpArtifactLocationChecker], a location is unreachable if it doesn't
+    /// answer within the given `timeout`.
+    pub fn new(timeout: Duration) -> StdResult<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .with_context(|| "Http Client build failed")?;
+
+        Ok(Self { client })
+    }
+}
+
+#[async_trait]
+impl ArtifactLocationChecker for HttpArtifactLocationChecker {
+    async fn check(&self, location: &str) -> StdResult<()> {
+        self.client
+            .head(location)
+            .send()
+            .await
+            .with_context(|| format!("Artifact location can not be reached: '{location}'"))?
+            .error_for_status()
+            .with_context(|| format!("Artifact location returned an error: '{location}'"))?;
+
+        Ok(())
+    }
+}
+
+/// Event sent when an artifact download location is unreachable.
+#[derive(Debug, Clone, Serialize)]
+struct UnreachableArtifactLocationEvent {
+    signed_entity_type: SignedEntityType,
+    artifact_id: String,
+    location: String,
+    error: String,
+}
+
+/// Artifact Availability Verifier
+pub struct ArtifactAvailabilityVerifier {
+    signed_entity_storer: Arc<dyn SignedEntityStorer>,
+    location_checker: Arc<dyn ArtifactLocationChecker>,
+    event_transmitter: Arc<TransmitterService<EventMessage>>,
+    metrics_service: Arc<MetricsService>,
+    demote_unreachable_locations: bool,
+    logger: Logger,
+}
+
+impl ArtifactAvailabilityVerifier {
+    /// Create a new instance of the artifact availability verifier.
+    ///
+    /// If `demote_unreachable_locations` is set, the unreachable locations of an artifact are
+    /// moved after its reachable ones.
+    pub fn new(
+        signed_entity_storer: Arc<dyn SignedEntityStorer>,
+        location_checker: Arc<dyn ArtifactLocationChecker>,
+        event_transmitter: Arc<TransmitterService<EventMessage>>,
+        metrics_service: Arc<MetricsService>,
+        demote_unreachable_locations: bool,
+        logger: Logger,
+    ) -> Self {
+        Self {
+            signed_entity_storer,
+            location_checker,
+            event_transmitter,
+            metrics_service,
+            demote_unreachable_locations,
+            logger: logger.new_with_component_name::<Self>(),
+        }
+    }
+
+    /// Check the download locations of all the published artifacts.
+    ///
+    /// Return the number of unreachable locations.
+    pub async fn verify(&self) -> StdResult<usize> {
+        debug!(self.logger, "Start artifacts availability verification");
+        let records = self
+            .signed_entity_storer
+            .get_last_signed_entities_by_type(
+                &SignedEntityTypeDiscriminants::CardanoImmutableFilesFull,
+                usize::MAX,
+            )
+            .await
+            .with_context(|| "Artifact availability verifier can not get the snapshots")?;
+
+        let mut unreachable_locations = 0;
+        let mut demoted_records = vec![];
+        for record in records {
+            let mut snapshot: Snapshot =
+                serde_json::from_str(&record.artifact).with_context(|| {
+                    format!(
+                        "Artifact availability verifier can not parse the snapshot '{}'",
+                        record.signed_entity_id
+                    )
+                })?;
+            let mut reachable = vec![];
+            let mut unreachable = vec![];
+            for location in snapshot.locations {
+                match self.check_location(&record, &location).await {
+                    Ok(()) => reachable.push(location),
+                    Err(_) => unreachable.push(location),
+                }
+            }
+            unreachable_locations += unreachable.len();
+
+            // Demoting is useless if no location or all of them are unreachable.
+            if self.demote_unreachable_locations && !reachable.is_empty() && !unreachable.is_empty()
+            {
+                snapshot.locations = [reachable, unreachable].concat();
+                demoted_records.push(SignedEntityRecord {
+                    artifact: serde_json::to_string(&snapshot)?,
+                    ..record
+                });
+            }
+        }
+
+        self.metrics_service
+            .get_artifact_location_unreachable()
+            .record(unreachable_locations as u32);
+        if !demoted_records.is_empty() {
+            info!(
+                self.logger, "Demoting unreachable artifact locations";
+                "artifacts" => demoted_records.len()
+            );
+            self.signed_entity_storer
+                .update_signed_entities(demoted_records)
+                .await
+                .with_context(|| {
+                    "Artifact availability verifier can not demote the unreachable locations"
+                })?;
+        }
+
+        Ok(unreachable_locations)
+    }
+
+    async fn check_location(&self, record: &SignedEntityRecord, location: &str) -> StdResult<()> {
+        if !location.starts_with("http://") && !location.starts_with("https://") {
+            debug!(self.logger, "Skipping non HTTP artifact location"; "location" => location);
+            return Ok(());
+        }
+
+        let result = self.location_checker.check(location).await;
+        if let Err(error) = &result {
+            warn!(
+                self.logger, "Artifact location is unreachable";
+                "signed_entity_type" => ?record.signed_entity_type,
+                "location" => location,
+                "error" => ?error
+            );
+            self.metrics_service
+                .get_artifact_location_unreachable_since_startup()
+                .increment();
+            let content = UnreachableArtifactLocationEvent {
+                signed_entity_type: record.signed_entity_type.clone(),
+                artifact_id: record.signed_entity_id.clone(),
+                location: location.to_string(),
+                error: format!("{error:?}"),
+            };
+            self.event_transmitter.send(EventMessage::new(
+                "ArtifactAvailabilityVerifier",
+                "artifact_location_unreachable",
+                &content,
+                vec![],
+            ));
+        }
+
+        result
+    }
+
+    /// Check the artifacts availability at each interval.
+    pub async fn run_forever(&self, run_interval: Duration) {
+        let mut interval = tokio::time::interval(run_interval);
+
+        loop {
+            interval.tick().await;
+            if let Err(error) = self.verify().await {
+                warn!(self.logger, "Artifact availability verification failed"; "error" => ?error);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use httpmock::prelude::*;
+    use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+
+    use mithril_common::test_utils::fake_data;
+
+    use crate::database::repository::MockSignedEntityStorer;
+    use crate::test_tools::TestLogger;
+
+    use anyhow::anyhow;
+
+    use super::*;
+
+    fn snapshot_record(digest: &str, locations: &[&str]) -> SignedEntityRecord {
+        let snapshot = Snapshot {
+            digest: digest.to_string(),
+            locations: locations.iter().map(|l| l.to_string()).collect(),
+            ..fake_data::snapshots(1).pop().unwrap()
+        };
+
+        SignedEntityRecord::from_snapshot(
+            snapshot,
+            format!("certificate-{digest}"),
+            chrono::Utc::now(),
+        )
+    }
+
+    fn location_checker_with_unreachable(
+        unreachable: &'static [&'static str],
+    ) -> MockArtifactLocationChecker {
+        let mut checker = MockArtifactLocationChecker::new();
+        checker.expect_check().returning(move |location| {
+            if unreachable.contains(&location) {
+                Err(anyhow!("unreachable"))
+            } else {
+                Ok(())
+            }
+        });
+
+        checker
+    }
+
+    fn build_verifier(
+        signed_entity_storer: MockSignedEntityStorer,
+        location_checker: MockArtifactLocationChecker,
+        demote_unreachable_locations: bool,
+    ) -> (
+        ArtifactAvailabilityVerifier,
+        UnboundedReceiver<EventMessage>,
+        Arc<MetricsService>,
+    ) {
+        let (tx, rx) = unbounded_channel();
+        let metrics_service = Arc::new(MetricsService::new(TestLogger::stdout()).unwrap());
+        let verifier = ArtifactAvailabilityVerifier::new(
+            Arc::new(signed_entity_storer),
+            Arc::new(location_checker),
+            Arc::new(TransmitterService::new(tx, TestLogger::stdout())),
+            metrics_service.clone(),
+            demote_unreachable_locations,
+            TestLogger::stdout(),
+        );
+
+        (verifier, rx, metrics_service)
+    }
+
+    #[tokio::test]
+    async fn report_unreachable_locations_with_metrics_and_events() {
+        let mut signed_entity_storer = MockSignedEntityStorer::new();
+        signed_entity_storer
+            .expect_get_last_signed_entities_by_type()
+            .return_once(|_, _| {
+                Ok(vec![snapshot_record(
+                    "digest-1",
+                    &[
+                        "https://dead.mirror/1.tar.zst",
+                        "https://live.mirror/1.tar.zst",
+                    ],
+                )])
+            });
+        signed_entity_storer.expect_update_signed_entities().never();
+        let (verifier, mut rx, metrics_service) = build_verifier(
+            signed_entity_storer,
+            location_checker_with_unreachable(&["https://dead.mirror/1.tar.zst"]),
+            false,
+        );
+
+        let unreachable_locations = verifier.verify().await.unwrap();
+
+        assert_eq!(1, unreachable_locations);
+        assert_eq!(
+            1,
+            metrics_service
+                .get_artifact_location_unreachable_since_startup()
+                .get()
+        );
+        assert_eq!(
+            1.0,
+            metrics_service.get_artifact_location_unreachable().get()
+        );
+        let event = rx.try_recv().unwrap();
+        assert_eq!("artifact_location_unreachable", event.action);
+        assert_eq!("https://dead.mirror/1.tar.zst", event.content["location"]);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn demote_unreachable_locations_after_the_reachable_ones_if_enabled() {
+        let mut signed_entity_storer = MockSignedEntityStorer::new();
+        signed_entity_storer
+            .expect_get_last_signed_entities_by_type()
+            .return_once(|_, _| {
+                Ok(vec![
+                    snapshot_record(
+                        "digest-1",
+                        &[
+                            "https://dead.mirror/1.tar.zst",
+                            "https://live.mirror/1.tar.zst",
+                        ],
+                    ),
+                    snapshot_record("digest-2", &["https://dead.mirror/2.tar.zst"]),
+                ])
+            });
+        signed_entity_storer
+            .expect_update_signed_entities()
+            .withf(|records| {
+                let snapshots: Vec<Snapshot> = records
+                    .iter()
+                    .map(|r| serde_json::from_str(&r.artifact).unwrap())
+                    .collect();
+                snapshots.len() == 1
+                    && snapshots[0].digest == "digest-1"
+                    && snapshots[0].locations
+                        == vec![
+                            "https://live.mirror/1.tar.zst".to_string(),
+                            "https://dead.mirror/1.tar.zst".to_string(),
+                        ]
+            })
+            .return_once(Ok)
+            .once();
+        let (verifier, _rx, _metrics_service) = build_verifier(
+            signed_entity_storer,
+            location_checker_with_unreachable(&[
+                "https://dead.mirror/1.tar.zst",
+                "https://dead.mirror/2.tar.zst",
+            ]),
+            true,
+        );
+
+        let unreachable_locations = verifier.verify().await.unwrap();
+
+        assert_eq!(2, unreachable_locations);
+    }
+
+    #[tokio::test]
+    async fn check_the_locations_of_all_the_published_snapshots() {
+        let mut signed_entity_storer = MockSignedEntityStorer::new();
+        signed_entity_storer
+            .expect_get_last_signed_entities_by_type()
+            .withf(|signed_entity_type, total| {
+                *signed_entity_type == SignedEntityTypeDiscriminants::CardanoImmutableFilesFull
+                    && *total == usize::MAX
+            })
+            .return_once(|_, _| {
+                Ok((1..=30)
+                    .map(|i| {
+                        snapshot_record(
+                            &format!("digest-{i}"),
+                            &["https://dead.mirror/snapshot.tar.zst"],
+                        )
+                    })
+                    .collect())
+            });
+        let (verifier, _rx, _metrics_service) = build_verifier(
+            signed_entity_storer,
+            location_checker_with_unreachable(&["https://dead.mirror/snapshot.tar.zst"]),
+            false,
+        );
+
+        let unreachable_locations = verifier.verify().await.unwrap();
+
+        assert_eq!(30, unreachable_locations);
+    }
+
+    #[tokio::test]
+    async fn non_http_locations_are_not_checked() {
+        let mut signed_entity_storer = MockSignedEntityStorer::new();
+        signed_entity_storer
+            .expect_get_last_signed_entities_by_type()
+            .return_once(|_, _| Ok(vec![snapshot_record("digest-1", &["file:///snapshots/1"])]));
+        let mut location_checker = MockArtifactLocationChecker::new();
+        location_checker.expect_check().never();
+        let (verifier, _rx, _metrics_service) =
+            build_verifier(signed_entity_storer, location_checker, true);
+
+        let unreachable_locations = verifier.verify().await.unwrap();
+
+        assert_eq!(0, unreachable_locations);
+    }
+
+    #[tokio::test]
+    async fn http_checker_sends_a_head_request_to_the_location() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(httpmock::Method::HEAD).path("/live.tar.zst");
+            then.status(200);
+        });
+        server.mock(|when, then| {
+            when.method(httpmock::Method::HEAD).path("/dead.tar.zst");
+            then.status(404);
+        });
+        let checker = HttpArtifactLocationChecker::new(Duration::from_secs(5)).unwrap();
+
+        checker.check(&server.url("/live.tar.zst")).await.unwrap();
+        checker
+            .check(&server.url("/dead.tar.zst"))
+            .await
+            .expect_err("A location answering with an error status should be unreachable");
+    }
+}
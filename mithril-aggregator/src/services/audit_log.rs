@@ -0,0 +1,319 @@
+//! ## Audit log
+//!
+//! Record security-sensitive operations (signer registrations, administration commands,
+//! configuration loading, key usages) in a dedicated append-only stream, separated from the
+//! application logs so it can be retained and reviewed independently.
+
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use slog::{warn, Logger};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Mutex;
+use std::thread::{self, JoinHandle};
+
+use mithril_common::logging::LoggerExtensions;
+use mithril_common::StdResult;
+
+/// Security-sensitive operation recorded in the audit log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    /// A signer asked to be registered.
+    SignerRegistration,
+    /// The configuration of the aggregator was loaded.
+    ConfigurationLoaded,
+    /// A genesis certificate was created using the genesis secret key.
+    GenesisCertificateBootstrap,
+    /// A genesis certificate was created with newly generated, unsafe, genesis keys on a private
+    /// network.
+    GenesisCertificateDevnetBootstrap,
+    /// A signed genesis payload was imported.
+    GenesisCertificateImport,
+    /// A change of the protocol parameters was scheduled.
+    ProtocolParametersScheduled,
+    /// A group of routes was enabled or disabled with the admin API.
+    RouteGroupUpdated,
+    /// The level of the logs was changed with the admin API.
+    LogLevelUpdated,
+    /// A request to the admin API was rejected because of a missing or invalid token.
+    AdminApiAccessDenied,
+    /// The signer address rules were reloaded from their file.
+    SignerAddressRulesReloaded,
+    /// A genesis payload was signed with the genesis secret key.
+    GenesisPayloadSigned,
+    /// A signature share of a genesis payload was created with the key package of a keyholder.
+    GenesisPayloadThresholdSigned,
+    /// An era markers transaction datum was signed with the era markers secret key.
+    EraMarkersSigned,
+}
+
+/// Outcome of an audited operation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "status", content = "reason")]
+pub enum AuditOutcome {
+    /// The operation succeeded.
+    Success,
+    /// The operation failed.
+    Failure(String),
+    /// The operation had no effect, its result already existed.
+    Unchanged,
+}
+
+/// Entry of the audit log.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AuditEntry {
+    /// Date of the operation.
+    pub timestamp: DateTime<Utc>,
+    /// Audited operation.
+    pub action: AuditAction,
+    /// Outcome of the operation.
+    pub outcome: AuditOutcome,
+    /// Identifier of the party that triggered the operation, if known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actor: Option<String>,
+    /// IP address of the client that triggered the operation, if known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_ip: Option<String>,
+    /// Additional data about the operation.
+    #[serde(skip_serializing_if = "serde_json::Value::is_null")]
+    pub details: serde_json::Value,
+}
+
+impl AuditEntry {
+    /// Create a successful entry for the given action.
+    pub fn new(action: AuditAction) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            action,
+            outcome: AuditOutcome::Success,
+            actor: None,
+            source_ip: None,
+            details: serde_json::Value::Null,
+        }
+    }
+
+    /// Set the party that triggered the operation.
+    pub fn with_actor<T: Into<String>>(mut self, actor: T) -> Self {
+        self.actor = Some(actor.into());
+        self
+    }
+
+    /// Set the address of the client that triggered the operation.
+    pub fn with_source_address(mut self, address: Option<SocketAddr>) -> Self {
+        self.source_ip = address.map(|address| address.ip().to_string());
+        self
+    }
+
+    /// Set additional data about the operation.
+    pub fn with_details(mut self, details: serde_json::Value) -> Self {
+        self.details = details;
+        self
+    }
+
+    /// Set the outcome of the operation.
+    pub fn with_outcome(mut self, outcome: AuditOutcome) -> Self {
+        self.outcome = outcome;
+        self
+    }
+
+    /// Set the outcome of the operation from its result.
+    pub fn with_result<T, E: std::fmt::Display>(mut self, result: &Result<T, E>) -> Self {
+        self.outcome = match result {
+            Ok(_) => AuditOutcome::Success,
+            Err(error) => AuditOutcome::Failure(error.to_string()),
+        };
+        self
+    }
+}
+
+/// Record entries in the audit log.
+pub trait AuditLogger: Sync + Send {
+    /// Record an entry, failures are logged but never interrupt the audited operation.
+    fn record(&self, entry: AuditEntry);
+}
+
+/// [AuditLogger] appending the entries as JSON lines to a file.
+///
+/// The entries are sent through a channel to a dedicated thread that writes them, so that
+/// recording an entry never blocks the audited operation, nor the async runtime, on the
+/// filesystem. The pending entries are written when the logger is dropped.
+pub struct FileAuditLogger {
+    sender: Option<Sender<AuditEntry>>,
+    writer: Option<JoinHandle<()>>,
+    logger: Logger,
+}
+
+impl FileAuditLogger {
+    /// Open, or create, the audit log file at the given path.
+    pub fn new(path: &Path, logger: Logger) -> StdResult<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Could not open audit log file: '{}'", path.display()))?;
+        let logger = logger.new_with_component_name::<Self>();
+        let (sender, receiver) = mpsc::channel();
+        let writer_logger = logger.clone();
+        let writer = thread::Builder::new()
+            .name("audit-log-writer".to_string())
+            .spawn(move || Self::write_entries(file, receiver, writer_logger))
+            .with_context(|| "Could not start the audit log writer thread")?;
+
+        Ok(Self {
+            sender: Some(sender),
+            writer: Some(writer),
+            logger,
+        })
+    }
+
+    fn write_entries(mut file: File, receiver: Receiver<AuditEntry>, logger: Logger) {
+        for entry in receiver {
+            if let Err(error) = Self::write_entry(&mut file, &entry) {
+                warn!(logger, "Could not write audit log entry"; "entry" => ?entry, "error" => ?error);
+            }
+        }
+    }
+
+    fn write_entry(file: &mut File, entry: &AuditEntry) -> StdResult<()> {
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+        file.write_all(line.as_bytes())?;
+        file.flush()?;
+
+        Ok(())
+    }
+}
+
+impl AuditLogger for FileAuditLogger {
+    fn record(&self, entry: AuditEntry) {
+        let Some(sender) = &self.sender else {
+            return;
+        };
+        if let Err(error) = sender.send(entry) {
+            warn!(self.logger, "Could not send audit log entry to its writer"; "entry" => ?error.0);
+        }
+    }
+}
+
+impl Drop for FileAuditLogger {
+    fn drop(&mut self) {
+        // Closing the channel stops the writer once the pending entries are written
+        drop(self.sender.take());
+        if let Some(writer) = self.writer.take() {
+            if writer.join().is_err() {
+                warn!(self.logger, "The audit log writer thread panicked");
+            }
+        }
+    }
+}
+
+/// [AuditLogger] discarding the entries, used when no audit log file is configured.
+pub struct NoopAuditLogger;
+
+impl AuditLogger for NoopAuditLogger {
+    fn record(&self, _entry: AuditEntry) {}
+}
+
+/// [AuditLogger] that keeps the entries in memory, used by the tests and the non production
+/// environments when no audit log file is configured.
+#[derive(Default)]
+pub struct DumbAuditLogger {
+    entries: Mutex<Vec<AuditEntry>>,
+}
+
+impl DumbAuditLogger {
+    /// Create a new instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the last recorded entry.
+    pub fn get_last_entry(&self) -> Option<AuditEntry> {
+        self.entries.lock().unwrap().last().cloned()
+    }
+}
+
+impl AuditLogger for DumbAuditLogger {
+    fn record(&self, entry: AuditEntry) {
+        let mut entries = self.entries.lock().unwrap();
+        // Only the recent entries are useful, avoid an unbounded growth.
+        if entries.len() >= 100 {
+            entries.remove(0);
+        }
+        entries.push(entry);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mithril_common::test_utils::TempDir;
+
+    use crate::test_tools::TestLogger;
+
+    use super::*;
+
+    #[test]
+    fn file_audit_logger_appends_json_lines() {
+        let path =
+            TempDir::create("audit_log", "file_audit_logger_appends_json_lines").join("audit.log");
+        std::fs::write(&path, "previous line\n").unwrap();
+        let audit_logger = FileAuditLogger::new(&path, TestLogger::stdout()).unwrap();
+
+        audit_logger.record(
+            AuditEntry::new(AuditAction::SignerRegistration)
+                .with_actor("pool1")
+                .with_source_address(Some("127.0.0.1:1234".parse().unwrap())),
+        );
+        audit_logger.record(
+            AuditEntry::new(AuditAction::ProtocolParametersScheduled)
+                .with_result::<(), _>(&Err("invalid epoch")),
+        );
+        // Wait for the pending entries to be written
+        drop(audit_logger);
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(3, lines.len());
+        assert_eq!("previous line", lines[0]);
+
+        let entry: serde_json::Value = serde_json::from_str(lines[1]).unwrap();
+        assert_eq!("signer_registration", entry["action"]);
+        assert_eq!("success", entry["outcome"]["status"]);
+        assert_eq!("pool1", entry["actor"]);
+        assert_eq!("127.0.0.1", entry["source_ip"]);
+
+        let entry: serde_json::Value = serde_json::from_str(lines[2]).unwrap();
+        assert_eq!("failure", entry["outcome"]["status"]);
+        assert_eq!("invalid epoch", entry["outcome"]["reason"]);
+        assert!(entry.get("actor").is_none());
+    }
+
+    #[test]
+    fn serialize_the_unchanged_outcome() {
+        let entry =
+            AuditEntry::new(AuditAction::SignerRegistration).with_outcome(AuditOutcome::Unchanged);
+
+        let entry = serde_json::to_value(&entry).unwrap();
+        assert_eq!("unchanged", entry["outcome"]["status"]);
+        assert!(entry["outcome"].get("reason").is_none());
+    }
+
+    #[test]
+    fn dumb_audit_logger_keeps_last_entry() {
+        let audit_logger = DumbAuditLogger::new();
+        assert_eq!(None, audit_logger.get_last_entry());
+
+        audit_logger.record(AuditEntry::new(AuditAction::ConfigurationLoaded));
+        audit_logger.record(AuditEntry::new(AuditAction::SignerRegistration));
+
+        assert_eq!(
+            Some(AuditAction::SignerRegistration),
+            audit_logger.get_last_entry().map(|entry| entry.action)
+        );
+    }
+}
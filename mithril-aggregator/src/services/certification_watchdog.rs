@@ -0,0 +1,487 @@
+//! ## Certification Watchdog
+//!
+//! This service tracks how long it takes to certify each signed entity type, from the
+//! detection of its beacon (creation of the open message) to the issuance of its certificate.
+//!
+//! It warns when a pending certification approaches the boundary that would make it outdated
+//! (a new epoch, a new immutable file, or a new block number to be signed for the Cardano
+//! transactions), so slow aggregators are noticed before they miss certifications. The expected
+//! duration until the boundary is learned from the intervals observed between the previous
+//! changes of these values.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use slog::{info, warn, Logger};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, Mutex};
+
+use mithril_common::entities::{
+    CardanoTransactionsSigningConfig, Certificate, SignedEntityType, SignedEntityTypeDiscriminants,
+    TimePoint, TimePointBeacon,
+};
+use mithril_common::logging::LoggerExtensions;
+
+use crate::entities::OpenMessage;
+use crate::event_store::{EventMessage, TransmitterService};
+use crate::MetricsService;
+
+/// Track the wall-clock interval between two changes of a chain value.
+#[derive(Debug, Default, Clone)]
+struct BoundaryTracker {
+    last_value: Option<u64>,
+    last_change_at: Option<DateTime<Utc>>,
+    last_interval_in_seconds: Option<i64>,
+}
+
+impl BoundaryTracker {
+    fn observe(&mut self, value: u64, now: DateTime<Utc>) {
+        match self.last_value {
+            Some(last_value) if last_value == value => {}
+            Some(_) => {
+                // The first observed change has an unknown start, its interval is not reliable.
+                if let Some(last_change_at) = self.last_change_at {
+                    self.last_interval_in_seconds = Some((now - last_change_at).num_seconds());
+                }
+                self.last_value = Some(value);
+                self.last_change_at = Some(now);
+            }
+            None => {
+                self.last_value = Some(value);
+            }
+        }
+    }
+}
+
+/// Event sent when a certification is about to miss, or has missed, its boundary.
+#[derive(Debug, Clone, Serialize)]
+struct CertificationDeadlineEvent {
+    signed_entity_type: SignedEntityType,
+    elapsed_seconds: i64,
+    expected_boundary_seconds: Option<i64>,
+}
+
+/// Certification Watchdog
+pub struct CertificationWatchdog {
+    warning_ratio: f64,
+    cardano_transactions_signing_config: CardanoTransactionsSigningConfig,
+    epoch_boundary: Mutex<BoundaryTracker>,
+    immutable_boundary: Mutex<BoundaryTracker>,
+    transactions_boundary: Mutex<BoundaryTracker>,
+    warned_signed_entity_types: Mutex<BTreeSet<String>>,
+    event_transmitter: Arc<TransmitterService<EventMessage>>,
+    metrics_service: Arc<MetricsService>,
+    logger: Logger,
+}
+
+impl CertificationWatchdog {
+    /// Create a new instance of the certification watchdog.
+    ///
+    /// A warning is emitted when the time elapsed since the creation of an open message
+    /// exceeds `warning_ratio` of the expected duration until its boundary.
+    ///
+    /// The boundary of the Cardano transactions is the next block number to be signed according
+    /// to the `cardano_transactions_signing_config`.
+    pub fn new(
+        warning_ratio: f64,
+        cardano_transactions_signing_config: CardanoTransactionsSigningConfig,
+        event_transmitter: Arc<TransmitterService<EventMessage>>,
+        metrics_service: Arc<MetricsService>,
+        logger: Logger,
+    ) -> Self {
+        Self {
+            warning_ratio,
+            cardano_transactions_signing_config,
+            epoch_boundary: Mutex::new(BoundaryTracker::default()),
+            immutable_boundary: Mutex::new(BoundaryTracker::default()),
+            transactions_boundary: Mutex::new(BoundaryTracker::default()),
+            warned_signed_entity_types: Mutex::new(BTreeSet::new()),
+            event_transmitter,
+            metrics_service,
+            logger: logger.new_with_component_name::<Self>(),
+        }
+    }
+
+    /// Record the given time point to learn the interval between epoch, immutable and block
+    /// number to be signed changes.
+    pub fn observe_time_point(&self, time_point: &TimePoint) {
+        let now = Utc::now();
+        self.epoch_boundary
+            .lock()
+            .unwrap()
+            .observe(*time_point.epoch, now);
+        self.immutable_boundary
+            .lock()
+            .unwrap()
+            .observe(time_point.immutable_file_number, now);
+        let block_number_to_be_signed = self
+            .cardano_transactions_signing_config
+            .compute_block_number_to_be_signed(time_point.chain_point.block_number);
+        self.transactions_boundary
+            .lock()
+            .unwrap()
+            .observe(*block_number_to_be_signed, now);
+    }
+
+    fn expected_boundary_in_seconds(&self, signed_entity_type: &SignedEntityType) -> Option<i64> {
+        let tracker = match signed_entity_type.time_point_beacon() {
+            TimePointBeacon::Epoch(_) => &self.epoch_boundary,
+            TimePointBeacon::ImmutableFileNumber(..) => &self.immutable_boundary,
+            TimePointBeacon::BlockNumber(..) => &self.transactions_boundary,
+        };
+
+        tracker.lock().unwrap().last_interval_in_seconds
+    }
+
+    /// Check a pending open message, emitting a warning if its certification is getting close
+    /// to the boundary after which it will be outdated.
+    ///
+    /// Return `true` if a warning was emitted.
+    pub fn check_pending_open_message(&self, open_message: &OpenMessage) -> bool {
+        if open_message.is_certified {
+            return false;
+        }
+
+        let elapsed_seconds = (Utc::now() - open_message.created_at).num_seconds();
+        let Some(expected_boundary_seconds) =
+            self.expected_boundary_in_seconds(&open_message.signed_entity_type)
+        else {
+            return false;
+        };
+
+        if (elapsed_seconds as f64) < (expected_boundary_seconds as f64) * self.warning_ratio {
+            return false;
+        }
+
+        let is_first_warning = self
+            .warned_signed_entity_types
+            .lock()
+            .unwrap()
+            .insert(open_message.signed_entity_type.to_string());
+        if !is_first_warning {
+            return false;
+        }
+
+        warn!(
+            self.logger, "Certification is approaching its deadline";
+            "signed_entity_type" => ?open_message.signed_entity_type,
+            "elapsed_seconds" => elapsed_seconds,
+            "expected_boundary_seconds" => expected_boundary_seconds
+        );
+        self.metrics_service
+            .get_certification_deadline_warning_since_startup()
+            .increment();
+        self.send_event(
+            "certification_deadline_approaching",
+            &open_message.signed_entity_type,
+            elapsed_seconds,
+            Some(expected_boundary_seconds),
+        );
+
+        true
+    }
+
+    /// Report an open message that became outdated before being certified.
+    pub fn report_missed_open_message(&self, open_message: &OpenMessage) {
+        if open_message.is_certified {
+            return;
+        }
+
+        let elapsed_seconds = (Utc::now() - open_message.created_at).num_seconds();
+        warn!(
+            self.logger, "Certification missed its deadline";
+            "signed_entity_type" => ?open_message.signed_entity_type,
+            "elapsed_seconds" => elapsed_seconds
+        );
+        self.metrics_service
+            .get_certification_deadline_missed_since_startup()
+            .increment();
+        self.send_event(
+            "certification_deadline_missed",
+            &open_message.signed_entity_type,
+            elapsed_seconds,
+            self.expected_boundary_in_seconds(&open_message.signed_entity_type),
+        );
+        self.forget(&open_message.signed_entity_type);
+    }
+
+    /// Record the duration of the certification of the given certificate.
+    pub fn record_certificate(&self, certificate: &Certificate) {
+        let signed_entity_type = certificate.signed_entity_type();
+        let duration_seconds =
+            (certificate.metadata.sealed_at - certificate.metadata.initiated_at).num_seconds();
+        info!(
+            self.logger, "Certificate issued";
+            "signed_entity_type" => ?signed_entity_type,
+            "certification_duration_seconds" => duration_seconds
+        );
+
+        let metrics = &self.metrics_service;
+        let gauge = match SignedEntityTypeDiscriminants::from(&signed_entity_type) {
+            SignedEntityTypeDiscriminants::MithrilStakeDistribution => {
+                metrics.get_certification_duration_mithril_stake_distribution_seconds()
+            }
+            SignedEntityTypeDiscriminants::CardanoStakeDistribution => {
+                metrics.get_certification_duration_cardano_stake_distribution_seconds()
+            }
+            SignedEntityTypeDiscriminants::CardanoImmutableFilesFull => {
+                metrics.get_certification_duration_cardano_db_seconds()
+            }
+            SignedEntityTypeDiscriminants::CardanoTransactions => {
+                metrics.get_certification_duration_cardano_transaction_seconds()
+            }
+        };
+        gauge.record(duration_seconds.max(0) as f64);
+
+        self.forget(&signed_entity_type);
+    }
+
+    fn forget(&self, signed_entity_type: &SignedEntityType) {
+        self.warned_signed_entity_types
+            .lock()
+            .unwrap()
+            .remove(&signed_entity_type.to_string());
+    }
+
+    fn send_event(
+        &self,
+        action: &str,
+        signed_entity_type: &SignedEntityType,
+        elapsed_seconds: i64,
+        expected_boundary_seconds: Option<i64>,
+    ) {
+        let content = CertificationDeadlineEvent {
+            signed_entity_type: signed_entity_type.clone(),
+            elapsed_seconds,
+            expected_boundary_seconds,
+        };
+        self.event_transmitter.send(EventMessage::new(
+            "CertificationWatchdog",
+            action,
+            &content,
+            vec![],
+        ));
+    }
+
+    /// Current expected boundaries, in seconds, by signed entity type discriminant.
+    pub fn expected_boundaries(&self) -> BTreeMap<SignedEntityTypeDiscriminants, Option<i64>> {
+        let epoch_interval = self.epoch_boundary.lock().unwrap().last_interval_in_seconds;
+        let immutable_interval = self
+            .immutable_boundary
+            .lock()
+            .unwrap()
+            .last_interval_in_seconds;
+        let transactions_interval = self
+            .transactions_boundary
+            .lock()
+            .unwrap()
+            .last_interval_in_seconds;
+
+        BTreeMap::from([
+            (
+                SignedEntityTypeDiscriminants::MithrilStakeDistribution,
+                epoch_interval,
+            ),
+            (
+                SignedEntityTypeDiscriminants::CardanoStakeDistribution,
+                epoch_interval,
+            ),
+            (
+                SignedEntityTypeDiscriminants::CardanoImmutableFilesFull,
+                immutable_interval,
+            ),
+            (
+                SignedEntityTypeDiscriminants::CardanoTransactions,
+                transactions_interval,
+            ),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+
+    use mithril_common::entities::{BlockNumber, ChainPoint, Epoch, ImmutableFileNumber};
+    use mithril_common::test_utils::fake_data;
+
+    use crate::test_tools::TestLogger;
+
+    use super::*;
+
+    fn build_watchdog(
+        warning_ratio: f64,
+    ) -> (
+        CertificationWatchdog,
+        UnboundedReceiver<EventMessage>,
+        Arc<MetricsService>,
+    ) {
+        let (tx, rx) = unbounded_channel();
+        let metrics_service = Arc::new(MetricsService::new(TestLogger::stdout()).unwrap());
+        let watchdog = CertificationWatchdog::new(
+            warning_ratio,
+            CardanoTransactionsSigningConfig::new(BlockNumber(0), BlockNumber(15)),
+            Arc::new(TransmitterService::new(tx, TestLogger::stdout())),
+            metrics_service.clone(),
+            TestLogger::stdout(),
+        );
+
+        (watchdog, rx, metrics_service)
+    }
+
+    fn time_point(epoch: u64, immutable_file_number: ImmutableFileNumber) -> TimePoint {
+        TimePoint {
+            epoch: Epoch(epoch),
+            immutable_file_number,
+            ..TimePoint::dummy()
+        }
+    }
+
+    fn time_point_at_block(block_number: u64) -> TimePoint {
+        TimePoint {
+            chain_point: ChainPoint {
+                block_number: BlockNumber(block_number),
+                ..ChainPoint::dummy()
+            },
+            ..TimePoint::dummy()
+        }
+    }
+
+    fn set_immutable_interval(watchdog: &CertificationWatchdog, interval_in_seconds: i64) {
+        watchdog
+            .immutable_boundary
+            .lock()
+            .unwrap()
+            .last_interval_in_seconds = Some(interval_in_seconds);
+    }
+
+    fn open_message_created_seconds_ago(seconds: i64) -> OpenMessage {
+        OpenMessage {
+            created_at: Utc::now() - Duration::seconds(seconds),
+            ..OpenMessage::dummy()
+        }
+    }
+
+    #[test]
+    fn boundary_interval_is_unknown_until_two_changes_are_observed() {
+        let (watchdog, _rx, _metrics) = build_watchdog(0.5);
+
+        watchdog.observe_time_point(&time_point(1, 10));
+        watchdog.observe_time_point(&time_point(1, 11));
+        assert_eq!(
+            None,
+            watchdog.expected_boundaries()
+                [&SignedEntityTypeDiscriminants::CardanoImmutableFilesFull]
+        );
+
+        watchdog.observe_time_point(&time_point(1, 12));
+        assert_eq!(
+            Some(0),
+            watchdog.expected_boundaries()
+                [&SignedEntityTypeDiscriminants::CardanoImmutableFilesFull]
+        );
+        assert_eq!(
+            None,
+            watchdog.expected_boundaries()
+                [&SignedEntityTypeDiscriminants::MithrilStakeDistribution]
+        );
+    }
+
+    #[test]
+    fn transactions_boundary_follows_the_block_number_to_be_signed() {
+        let (watchdog, _rx, _metrics) = build_watchdog(0.5);
+
+        // The block number to be signed changes every 15 blocks
+        for block_number in [20, 30, 31, 44] {
+            watchdog.observe_time_point(&time_point_at_block(block_number));
+        }
+        assert_eq!(
+            None,
+            watchdog.expected_boundaries()[&SignedEntityTypeDiscriminants::CardanoTransactions]
+        );
+
+        watchdog.observe_time_point(&time_point_at_block(45));
+        assert_eq!(
+            Some(0),
+            watchdog.expected_boundaries()[&SignedEntityTypeDiscriminants::CardanoTransactions]
+        );
+        assert_eq!(
+            None,
+            watchdog.expected_boundaries()
+                [&SignedEntityTypeDiscriminants::CardanoStakeDistribution]
+        );
+    }
+
+    #[test]
+    fn no_warning_without_known_boundary() {
+        let (watchdog, mut rx, _metrics) = build_watchdog(0.5);
+
+        assert!(!watchdog.check_pending_open_message(&open_message_created_seconds_ago(1000)));
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn no_warning_when_far_from_the_boundary() {
+        let (watchdog, mut rx, _metrics) = build_watchdog(0.5);
+        set_immutable_interval(&watchdog, 1000);
+
+        assert!(!watchdog.check_pending_open_message(&open_message_created_seconds_ago(100)));
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn warn_once_when_approaching_the_boundary() {
+        let (watchdog, mut rx, metrics) = build_watchdog(0.5);
+        set_immutable_interval(&watchdog, 1000);
+        let open_message = open_message_created_seconds_ago(600);
+
+        assert!(watchdog.check_pending_open_message(&open_message));
+        assert!(!watchdog.check_pending_open_message(&open_message));
+
+        let event = rx.try_recv().unwrap();
+        assert_eq!("certification_deadline_approaching", event.action);
+        assert!(rx.try_recv().is_err());
+        assert_eq!(
+            1,
+            metrics
+                .get_certification_deadline_warning_since_startup()
+                .get()
+        );
+    }
+
+    #[test]
+    fn report_missed_open_message_send_an_event() {
+        let (watchdog, mut rx, metrics) = build_watchdog(0.5);
+
+        watchdog.report_missed_open_message(&open_message_created_seconds_ago(10));
+
+        let event = rx.try_recv().unwrap();
+        assert_eq!("certification_deadline_missed", event.action);
+        assert_eq!(
+            1,
+            metrics
+                .get_certification_deadline_missed_since_startup()
+                .get()
+        );
+    }
+
+    #[test]
+    fn record_certificate_set_duration_gauge_and_allow_new_warnings() {
+        let (watchdog, _rx, metrics) = build_watchdog(0.5);
+        set_immutable_interval(&watchdog, 1000);
+        let open_message = open_message_created_seconds_ago(600);
+        watchdog.check_pending_open_message(&open_message);
+
+        let mut certificate = fake_data::certificate("hash".to_string());
+        certificate.metadata.sealed_at = certificate.metadata.initiated_at + Duration::seconds(42);
+        watchdog.record_certificate(&certificate);
+
+        assert_eq!(
+            42.0,
+            metrics
+                .get_certification_duration_cardano_db_seconds()
+                .get()
+        );
+        assert!(watchdog.check_pending_open_message(&open_message));
+    }
+}
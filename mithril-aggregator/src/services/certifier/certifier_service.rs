@@ -5,11 +5,12 @@ use slog::{debug, info, trace, warn, Logger};
 use std::sync::Arc;
 
 use mithril_common::certificate_chain::CertificateVerifier;
-use mithril_common::crypto_helper::{ProtocolGenesisVerifier, PROTOCOL_VERSION};
+use mithril_common::crypto_helper::ProtocolGenesisVerifier;
 use mithril_common::entities::{
     Certificate, CertificateMetadata, CertificateSignature, Epoch, ProtocolMessage,
     SignedEntityType, SingleSignatures, StakeDistributionParty,
 };
+use mithril_common::era::EraChecker;
 use mithril_common::logging::LoggerExtensions;
 use mithril_common::protocol::ToMessage;
 use mithril_common::{CardanoNetwork, StdResult};
@@ -33,6 +34,7 @@ pub struct MithrilCertifierService {
     genesis_verifier: Arc<ProtocolGenesisVerifier>,
     multi_signer: Arc<dyn MultiSigner>,
     epoch_service: EpochServiceWrapper,
+    era_checker: Arc<EraChecker>,
     logger: Logger,
 }
 
@@ -48,6 +50,7 @@ impl MithrilCertifierService {
         genesis_verifier: Arc<ProtocolGenesisVerifier>,
         multi_signer: Arc<dyn MultiSigner>,
         epoch_service: EpochServiceWrapper,
+        era_checker: Arc<EraChecker>,
         logger: Logger,
     ) -> Self {
         Self {
@@ -59,6 +62,7 @@ impl MithrilCertifierService {
             certificate_verifier,
             genesis_verifier,
             epoch_service,
+            era_checker,
             logger: logger.new_with_component_name::<Self>(),
         }
     }
@@ -98,6 +102,7 @@ impl CertifierService for MithrilCertifierService {
         Ok(())
     }
 
+    #[tracing::instrument(skip_all, fields(signed_entity_type = %signed_entity_type, party_id = %signature.party_id))]
     async fn register_single_signature(
         &self,
         signed_entity_type: &SignedEntityType,
@@ -147,6 +152,7 @@ impl CertifierService for MithrilCertifierService {
         Ok(SignatureRegistrationStatus::Registered)
     }
 
+    #[tracing::instrument(skip_all, fields(signed_entity_type = %signed_entity_type))]
     async fn create_open_message(
         &self,
         signed_entity_type: &SignedEntityType,
@@ -224,6 +230,7 @@ impl CertifierService for MithrilCertifierService {
         Ok(open_message_record.map(|record| record.into()))
     }
 
+    #[tracing::instrument(skip_all, fields(signed_entity_type = %signed_entity_type))]
     async fn create_certificate(
         &self,
         signed_entity_type: &SignedEntityType,
@@ -280,7 +287,10 @@ impl CertifierService for MithrilCertifierService {
             .filter(|signer| signer_ids.contains(&signer.party_id))
             .collect::<Vec<_>>();
 
-        let protocol_version = PROTOCOL_VERSION.to_string();
+        let protocol_version = self
+            .era_checker
+            .certifying_signature_scheme()
+            .protocol_version();
         let initiated_at = open_message.created_at;
         let sealed_at = Utc::now();
         let metadata = CertificateMetadata::new(
@@ -290,7 +300,8 @@ impl CertifierService for MithrilCertifierService {
             initiated_at,
             sealed_at,
             StakeDistributionParty::from_signers(signers),
-        );
+        )
+        .with_hash_algorithm(self.era_checker.hash_algorithm());
         let parent_certificate_hash = self
             .certificate_repository
             .get_master_certificate_for_epoch::<Certificate>(open_message.epoch)
@@ -412,6 +423,7 @@ mod tests {
             let genesis_verifier = dependency_builder.get_genesis_verifier().await.unwrap();
             let multi_signer = dependency_builder.get_multi_signer().await.unwrap();
             let epoch_service = dependency_builder.get_epoch_service().await.unwrap();
+            let era_checker = dependency_builder.get_era_checker().await.unwrap();
 
             Self::new(
                 network,
@@ -422,6 +434,7 @@ mod tests {
                 genesis_verifier,
                 multi_signer,
                 epoch_service,
+                era_checker,
                 TestLogger::stdout(),
             )
         }
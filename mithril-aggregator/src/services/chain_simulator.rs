@@ -0,0 +1,507 @@
+//! ## Chain simulator
+//!
+//! This service simulates a Cardano chain and a fleet of signers so developers can exercise the
+//! full certification pipeline of the aggregator locally, in minutes, without a devnet.
+//!
+//! The simulated chain advances on a fast clock: at each tick a new immutable files trio is
+//! written in the `immutable` folder of the database directory, the block and slot numbers of
+//! the fake chain observer are increased, and the epoch changes every
+//! [IMMUTABLES_PER_EPOCH] immutable files.
+//! The simulated signers register at each new registration round, sign the pending open message
+//! and the genesis certificate is bootstrapped as soon as signers are available.
+
+use anyhow::{anyhow, Context};
+use slog::{debug, info, warn, Logger};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+use mithril_common::chain_observer::FakeObserver;
+use mithril_common::crypto_helper::ProtocolGenesisSigner;
+use mithril_common::digesters::{DumbImmutableFileObserver, ImmutableFile, ImmutableFileObserver};
+use mithril_common::entities::{Certificate, Epoch, ImmutableFileNumber, SignedEntityType};
+use mithril_common::logging::LoggerExtensions;
+use mithril_common::test_utils::MithrilFixture;
+use mithril_common::StdResult;
+
+use crate::entities::OpenMessage;
+use crate::services::CertifierService;
+use crate::tools::{GenesisTools, GenesisToolsDependency};
+use crate::{CertificatePendingStore, SignerRegisterer, SignerRegistrationError};
+
+/// Number of immutable files produced during an epoch of the simulated chain.
+pub const IMMUTABLES_PER_EPOCH: u64 = 10;
+
+/// Number of blocks produced for each immutable file of the simulated chain.
+const BLOCKS_PER_IMMUTABLE: u64 = 20;
+
+/// Number of slots elapsed for each immutable file of the simulated chain.
+const SLOTS_PER_IMMUTABLE: u64 = 400;
+
+/// Simulated Cardano chain, advanced by writing immutable files and updating the fake observers.
+pub struct SimulatedChain {
+    chain_observer: Arc<FakeObserver>,
+    immutable_file_observer: Arc<DumbImmutableFileObserver>,
+    db_directory: PathBuf,
+    logger: Logger,
+}
+
+impl SimulatedChain {
+    /// Create a new instance
+    pub fn new(
+        chain_observer: Arc<FakeObserver>,
+        immutable_file_observer: Arc<DumbImmutableFileObserver>,
+        db_directory: &Path,
+        logger: Logger,
+    ) -> Self {
+        Self {
+            chain_observer,
+            immutable_file_observer,
+            db_directory: db_directory.to_path_buf(),
+            logger: logger.new_with_component_name::<Self>(),
+        }
+    }
+
+    fn immutable_directory(&self) -> PathBuf {
+        self.db_directory.join("immutable")
+    }
+
+    /// Resume the simulated chain from the immutable files already in the database directory,
+    /// or start a new one if there are none.
+    pub async fn initialize(&self) -> StdResult<ImmutableFileNumber> {
+        fs::create_dir_all(self.immutable_directory()).with_context(|| {
+            format!(
+                "Could not create the simulated immutable directory '{}'",
+                self.immutable_directory().display()
+            )
+        })?;
+        let last_completed_number = match ImmutableFile::list_completed_in_dir(&self.db_directory)
+            .with_context(|| "Could not list the simulated immutable files")?
+            .last()
+        {
+            Some(immutable_file) => immutable_file.number,
+            None => {
+                self.write_immutable_trio(0)?;
+                self.write_immutable_trio(1)?;
+                0
+            }
+        };
+        self.immutable_file_observer
+            .shall_return(Some(last_completed_number))
+            .await;
+
+        Ok(last_completed_number)
+    }
+
+    /// Complete a new immutable file, increasing the block and slot numbers accordingly, and
+    /// move to the next epoch at each epoch boundary.
+    pub async fn advance(&self) -> StdResult<()> {
+        let next_number = self
+            .immutable_file_observer
+            .get_last_immutable_number()
+            .await?
+            + 1;
+        // The trio after the completed one is the one being written by the node
+        self.write_immutable_trio(next_number + 1)?;
+        self.immutable_file_observer.increase().await?;
+        self.chain_observer
+            .increase_block_number(BLOCKS_PER_IMMUTABLE)
+            .await;
+        self.chain_observer
+            .increase_slot_number(SLOTS_PER_IMMUTABLE)
+            .await;
+        debug!(self.logger, "Simulated chain advanced"; "immutable_file_number" => next_number);
+
+        if next_number % IMMUTABLES_PER_EPOCH == 0 {
+            let epoch = self.chain_observer.next_epoch().await;
+            info!(self.logger, "Simulated chain moved to a new epoch"; "epoch" => ?epoch);
+        }
+
+        Ok(())
+    }
+
+    fn write_immutable_trio(&self, number: ImmutableFileNumber) -> StdResult<()> {
+        for extension in ["chunk", "primary", "secondary"] {
+            let path = self
+                .immutable_directory()
+                .join(format!("{number:05}.{extension}"));
+            fs::write(&path, format!("simulated {extension} {number}")).with_context(|| {
+                format!("Could not write simulated immutable '{}'", path.display())
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Simulated signers, registering and signing with the keys of a [MithrilFixture].
+pub struct SimulatedSigners {
+    fixture: MithrilFixture,
+    signer_registerer: Arc<dyn SignerRegisterer>,
+    certifier_service: Arc<dyn CertifierService>,
+    certificate_pending_store: Arc<CertificatePendingStore>,
+    last_registration_epoch: Mutex<Option<Epoch>>,
+    last_signed_entity_type: Mutex<Option<SignedEntityType>>,
+    logger: Logger,
+}
+
+impl SimulatedSigners {
+    /// Create a new instance
+    pub fn new(
+        fixture: MithrilFixture,
+        signer_registerer: Arc<dyn SignerRegisterer>,
+        certifier_service: Arc<dyn CertifierService>,
+        certificate_pending_store: Arc<CertificatePendingStore>,
+        logger: Logger,
+    ) -> Self {
+        Self {
+            fixture,
+            signer_registerer,
+            certifier_service,
+            certificate_pending_store,
+            last_registration_epoch: Mutex::new(None),
+            last_signed_entity_type: Mutex::new(None),
+            logger: logger.new_with_component_name::<Self>(),
+        }
+    }
+
+    /// Register all the signers to the current registration round, once per round.
+    pub async fn register_signers(&self) -> StdResult<()> {
+        let Some(round) = self.signer_registerer.get_current_round().await else {
+            return Ok(());
+        };
+        let mut last_registration_epoch = self.last_registration_epoch.lock().await;
+        if *last_registration_epoch == Some(round.epoch) {
+            return Ok(());
+        }
+
+        for signer in self.fixture.signers() {
+            match self
+                .signer_registerer
+                .register_signer(round.epoch, &signer)
+                .await
+            {
+                Ok(_) | Err(SignerRegistrationError::ExistingSigner(_)) => {}
+                Err(error) => {
+                    return Err(anyhow!(error)).with_context(|| {
+                        format!(
+                            "Could not register simulated signer '{}' for epoch {}",
+                            signer.party_id, round.epoch
+                        )
+                    })
+                }
+            }
+        }
+        info!(self.logger, "Simulated signers registered"; "epoch" => ?round.epoch, "signers" => self.fixture.signers().len());
+        *last_registration_epoch = Some(round.epoch);
+
+        Ok(())
+    }
+
+    /// Sign the open message of the pending certificate, once per signed entity type.
+    pub async fn sign_pending_open_message(&self) -> StdResult<()> {
+        let Some(open_message) = self.get_pending_open_message().await? else {
+            return Ok(());
+        };
+        let mut last_signed_entity_type = self.last_signed_entity_type.lock().await;
+        if last_signed_entity_type.as_ref() == Some(&open_message.signed_entity_type) {
+            return Ok(());
+        }
+
+        for signature in self.fixture.sign_all(&open_message.protocol_message) {
+            self.certifier_service
+                .register_single_signature(&open_message.signed_entity_type, &signature)
+                .await
+                .with_context(|| {
+                    format!(
+                        "Could not register the single signature of simulated signer '{}'",
+                        signature.party_id
+                    )
+                })?;
+        }
+        info!(self.logger, "Simulated signers signed"; "signed_entity_type" => ?open_message.signed_entity_type);
+        *last_signed_entity_type = Some(open_message.signed_entity_type);
+
+        Ok(())
+    }
+
+    async fn get_pending_open_message(&self) -> StdResult<Option<OpenMessage>> {
+        let Some(certificate_pending) = self.certificate_pending_store.get().await? else {
+            return Ok(None);
+        };
+        let open_message = self
+            .certifier_service
+            .get_open_message(&certificate_pending.signed_entity_type)
+            .await?;
+
+        Ok(open_message.filter(|message| !message.is_certified && !message.is_expired))
+    }
+}
+
+/// Drive a [SimulatedChain] and its [SimulatedSigners] on a fast clock.
+pub struct ChainSimulator {
+    chain: SimulatedChain,
+    signers: SimulatedSigners,
+    genesis_dependencies: GenesisToolsDependency,
+    logger: Logger,
+}
+
+impl ChainSimulator {
+    /// Create a new instance
+    pub fn new(
+        chain: SimulatedChain,
+        signers: SimulatedSigners,
+        genesis_dependencies: GenesisToolsDependency,
+        logger: Logger,
+    ) -> Self {
+        Self {
+            chain,
+            signers,
+            genesis_dependencies,
+            logger: logger.new_with_component_name::<Self>(),
+        }
+    }
+
+    /// Set the simulated signers as the stake distribution of the chain and resume the
+    /// simulated chain.
+    pub async fn initialize(&self) -> StdResult<()> {
+        self.chain
+            .chain_observer
+            .set_signers(self.signers.fixture.signers_with_stake())
+            .await;
+        let immutable_file_number = self.chain.initialize().await?;
+        info!(
+            self.logger, "Simulated chain initialized";
+            "immutable_file_number" => immutable_file_number,
+            "signers" => self.signers.fixture.signers().len()
+        );
+
+        Ok(())
+    }
+
+    async fn bootstrap_genesis_if_needed(&self) -> StdResult<()> {
+        let has_certificate = !self
+            .genesis_dependencies
+            .certificate_repository
+            .get_latest_certificates::<Certificate>(1)
+            .await?
+            .is_empty();
+        if has_certificate {
+            return Ok(());
+        }
+
+        let genesis_tools = GenesisTools::from_dependencies(self.genesis_dependencies.clone())
+            .await
+            .with_context(|| "Simulated signers are not yet registered for the genesis epoch")?;
+        genesis_tools
+            .bootstrap_test_genesis_certificate(
+                ProtocolGenesisSigner::create_deterministic_genesis_signer(),
+            )
+            .await?;
+        info!(self.logger, "Simulated genesis certificate bootstrapped");
+
+        Ok(())
+    }
+
+    /// Advance the simulated chain and let the simulated signers act.
+    pub async fn tick(&self) {
+        if let Err(error) = self.chain.advance().await {
+            warn!(self.logger, "Simulated chain could not advance"; "error" => ?error);
+        }
+        if let Err(error) = self.signers.register_signers().await {
+            debug!(self.logger, "Simulated signers registration failed"; "error" => ?error);
+        }
+        if let Err(error) = self.bootstrap_genesis_if_needed().await {
+            debug!(self.logger, "Simulated genesis bootstrap skipped"; "error" => ?error);
+        }
+        if let Err(error) = self.signers.sign_pending_open_message().await {
+            debug!(self.logger, "Simulated signers signature failed"; "error" => ?error);
+        }
+    }
+
+    /// Tick forever, completing [IMMUTABLES_PER_EPOCH] immutable files per epoch duration.
+    pub async fn run_forever(&self, epoch_duration: Duration) {
+        let mut interval = tokio::time::interval(epoch_duration / IMMUTABLES_PER_EPOCH as u32);
+
+        loop {
+            interval.tick().await;
+            self.tick().await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mithril_common::entities::{
+        CertificatePending, ProtocolMessage, SignerWithStake, TimePoint,
+    };
+    use mithril_common::test_utils::{fake_data, MithrilFixtureBuilder, TempDir};
+    use mithril_persistence::store::adapter::MemoryAdapter;
+    use std::collections::BTreeMap;
+
+    use crate::services::{MockCertifierService, SignatureRegistrationStatus};
+    use crate::signer_registerer::MockSignerRegisterer;
+    use crate::test_tools::TestLogger;
+    use crate::SignerRegistrationRound;
+
+    use super::*;
+
+    fn build_chain(test_name: &str) -> (SimulatedChain, Arc<FakeObserver>) {
+        let chain_observer = Arc::new(FakeObserver::new(Some(TimePoint::dummy())));
+        let chain = SimulatedChain::new(
+            chain_observer.clone(),
+            Arc::new(DumbImmutableFileObserver::new()),
+            &TempDir::create("chain_simulator", test_name),
+            TestLogger::stdout(),
+        );
+
+        (chain, chain_observer)
+    }
+
+    fn build_signers(
+        fixture: MithrilFixture,
+        signer_registerer: MockSignerRegisterer,
+        certifier_service: MockCertifierService,
+        certificate_pending: Option<CertificatePending>,
+    ) -> SimulatedSigners {
+        let certificate_pending_store = CertificatePendingStore::new(Box::new(
+            MemoryAdapter::new(
+                certificate_pending.map(|c| vec![("certificate_pending".to_string(), c)]),
+            )
+            .unwrap(),
+        ));
+
+        SimulatedSigners::new(
+            fixture,
+            Arc::new(signer_registerer),
+            Arc::new(certifier_service),
+            Arc::new(certificate_pending_store),
+            TestLogger::stdout(),
+        )
+    }
+
+    #[tokio::test]
+    async fn initialize_start_a_new_chain_with_an_uncompleted_trio() {
+        let (chain, _) = build_chain("initialize_start_a_new_chain_with_an_uncompleted_trio");
+
+        let immutable_file_number = chain.initialize().await.unwrap();
+
+        assert_eq!(0, immutable_file_number);
+        assert_eq!(
+            0,
+            chain
+                .immutable_file_observer
+                .get_last_immutable_number()
+                .await
+                .unwrap()
+        );
+        assert!(chain.immutable_directory().join("00001.chunk").exists());
+    }
+
+    #[tokio::test]
+    async fn initialize_resume_an_existing_chain() {
+        let (chain, _) = build_chain("initialize_resume_an_existing_chain");
+        chain.initialize().await.unwrap();
+        for _ in 0..4 {
+            chain.advance().await.unwrap();
+        }
+
+        let immutable_file_number = chain.initialize().await.unwrap();
+
+        assert_eq!(4, immutable_file_number);
+    }
+
+    #[tokio::test]
+    async fn advance_complete_immutables_and_change_epoch_at_boundary() {
+        let (chain, chain_observer) =
+            build_chain("advance_complete_immutables_and_change_epoch_at_boundary");
+        let initial_time_point = TimePoint::dummy();
+        chain.initialize().await.unwrap();
+
+        for _ in 0..IMMUTABLES_PER_EPOCH {
+            chain.advance().await.unwrap();
+        }
+
+        let time_point = chain_observer
+            .current_time_point
+            .read()
+            .await
+            .clone()
+            .unwrap();
+        assert_eq!(initial_time_point.epoch + 1, time_point.epoch);
+        assert_eq!(
+            initial_time_point.chain_point.block_number
+                + BLOCKS_PER_IMMUTABLE * IMMUTABLES_PER_EPOCH,
+            time_point.chain_point.block_number
+        );
+        assert_eq!(
+            IMMUTABLES_PER_EPOCH,
+            chain
+                .immutable_file_observer
+                .get_last_immutable_number()
+                .await
+                .unwrap()
+        );
+        let completed = ImmutableFile::list_completed_in_dir(&chain.db_directory).unwrap();
+        assert_eq!(
+            Some(IMMUTABLES_PER_EPOCH),
+            completed.last().map(|f| f.number)
+        );
+    }
+
+    #[tokio::test]
+    async fn register_signers_once_per_round() {
+        let mut signer_registerer = MockSignerRegisterer::new();
+        signer_registerer
+            .expect_get_current_round()
+            .returning(|| Some(SignerRegistrationRound::dummy(Epoch(5), BTreeMap::new())));
+        signer_registerer
+            .expect_register_signer()
+            .withf(|epoch, _| *epoch == Epoch(5))
+            .returning(|_, signer| Ok(SignerWithStake::from_signer(signer.clone(), 10)))
+            .times(3);
+        let signers = build_signers(
+            MithrilFixtureBuilder::default().with_signers(3).build(),
+            signer_registerer,
+            MockCertifierService::new(),
+            None,
+        );
+
+        signers.register_signers().await.unwrap();
+        signers.register_signers().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn sign_pending_open_message_once_per_signed_entity_type() {
+        let fixture = MithrilFixtureBuilder::default().with_signers(3).build();
+        let expected_signatures = fixture.sign_all(&ProtocolMessage::new()).len();
+        let certificate_pending = fake_data::certificate_pending();
+        let signed_entity_type = certificate_pending.signed_entity_type.clone();
+        let mut certifier_service = MockCertifierService::new();
+        certifier_service
+            .expect_get_open_message()
+            .returning(|signed_entity_type| {
+                Ok(Some(OpenMessage {
+                    signed_entity_type: signed_entity_type.clone(),
+                    protocol_message: ProtocolMessage::new(),
+                    ..OpenMessage::dummy()
+                }))
+            });
+        certifier_service
+            .expect_register_single_signature()
+            .withf(move |entity_type, _| *entity_type == signed_entity_type)
+            .returning(|_, _| Ok(SignatureRegistrationStatus::Registered))
+            .times(expected_signatures);
+        let signers = build_signers(
+            fixture,
+            MockSignerRegisterer::new(),
+            certifier_service,
+            Some(certificate_pending),
+        );
+
+        signers.sign_pending_open_message().await.unwrap();
+        signers.sign_pending_open_message().await.unwrap();
+    }
+}
@@ -15,7 +15,7 @@ use mithril_common::protocol::{MultiSigner as ProtocolMultiSigner, SignerBuilder
 use mithril_common::{CardanoNetwork, StdResult};
 
 use crate::entities::AggregatorEpochSettings;
-use crate::{EpochSettingsStorer, VerificationKeyStorer};
+use crate::{EpochSettingsStorer, ProtocolParametersScheduleStorer, VerificationKeyStorer};
 
 /// Errors dedicated to the CertifierService.
 #[derive(Debug, Error)]
@@ -128,6 +128,7 @@ pub struct MithrilEpochService {
     epoch_data: Option<EpochData>,
     computed_epoch_data: Option<ComputedEpochData>,
     epoch_settings_storer: Arc<dyn EpochSettingsStorer>,
+    protocol_parameters_schedule_storer: Arc<dyn ProtocolParametersScheduleStorer>,
     verification_key_store: Arc<dyn VerificationKeyStorer>,
     network: CardanoNetwork,
     allowed_signed_entity_discriminants: BTreeSet<SignedEntityTypeDiscriminants>,
@@ -139,6 +140,7 @@ impl MithrilEpochService {
     pub fn new(
         future_epoch_settings: AggregatorEpochSettings,
         epoch_settings_storer: Arc<dyn EpochSettingsStorer>,
+        protocol_parameters_schedule_storer: Arc<dyn ProtocolParametersScheduleStorer>,
         verification_key_store: Arc<dyn VerificationKeyStorer>,
         network: CardanoNetwork,
         allowed_discriminants: BTreeSet<SignedEntityTypeDiscriminants>,
@@ -149,6 +151,7 @@ impl MithrilEpochService {
             epoch_data: None,
             computed_epoch_data: None,
             epoch_settings_storer,
+            protocol_parameters_schedule_storer,
             verification_key_store,
             network,
             allowed_signed_entity_discriminants: allowed_discriminants,
@@ -186,17 +189,29 @@ impl MithrilEpochService {
 
     async fn insert_future_epoch_settings(&self, actual_epoch: Epoch) -> StdResult<()> {
         let recording_epoch = actual_epoch.offset_to_epoch_settings_recording_epoch();
+        let mut epoch_settings = self.future_epoch_settings.clone();
+
+        // Epoch settings recorded for an epoch are used to sign during the following epoch
+        if let Some(protocol_parameters) = self
+            .protocol_parameters_schedule_storer
+            .get_active_protocol_parameters(recording_epoch.next())
+            .await
+            .with_context(|| "Epoch service failed to obtain scheduled protocol parameters")?
+        {
+            debug!(
+                self.logger, "Using scheduled protocol parameters for epoch settings of epoch {recording_epoch}";
+                "protocol_parameters" => ?protocol_parameters
+            );
+            epoch_settings.protocol_parameters = protocol_parameters;
+        }
 
         debug!(
             self.logger, "Inserting epoch settings in epoch {recording_epoch}";
-            "epoch_settings" => ?self.future_epoch_settings
+            "epoch_settings" => ?epoch_settings
         );
 
         self.epoch_settings_storer
-            .save_epoch_settings(
-                recording_epoch,
-                self.future_epoch_settings.clone(),
-            )
+            .save_epoch_settings(recording_epoch, epoch_settings)
             .await
             .with_context(|| format!("Epoch service failed to insert future_epoch_settings to epoch {recording_epoch}"))
             .map(|_| ())
@@ -656,7 +671,7 @@ mod tests {
     use mithril_persistence::store::adapter::MemoryAdapter;
     use std::collections::{BTreeSet, HashMap};
 
-    use crate::store::FakeEpochSettingsStorer;
+    use crate::store::{FakeEpochSettingsStorer, FakeProtocolParametersScheduleStorer};
     use crate::test_tools::TestLogger;
     use crate::VerificationKeyStore;
 
@@ -745,6 +760,7 @@ mod tests {
         stored_current_epoch_settings: AggregatorEpochSettings,
         stored_next_epoch_settings: AggregatorEpochSettings,
         stored_signer_registration_epoch_settings: AggregatorEpochSettings,
+        scheduled_protocol_parameters: Vec<(Epoch, ProtocolParameters)>,
     }
 
     impl EpochServiceBuilder {
@@ -769,6 +785,7 @@ mod tests {
                     protocol_parameters: epoch_fixture.protocol_parameters(),
                     cardano_transactions_signing_config: CardanoTransactionsSigningConfig::dummy(),
                 },
+                scheduled_protocol_parameters: vec![],
             }
         }
 
@@ -810,6 +827,9 @@ mod tests {
                     cardano_transactions_signing_config: self.cardano_transactions_signing_config,
                 },
                 Arc::new(epoch_settings_storer),
+                Arc::new(FakeProtocolParametersScheduleStorer::new(
+                    self.scheduled_protocol_parameters,
+                )),
                 Arc::new(vkey_store),
                 self.network,
                 self.allowed_discriminants,
@@ -1024,6 +1044,44 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn update_epoch_settings_use_scheduled_protocol_parameters_once_activated() {
+        let future_protocol_parameters = ProtocolParameters::new(6, 89, 0.124);
+        let scheduled_protocol_parameters = ProtocolParameters::new(12, 150, 0.65);
+        let epoch = Epoch(4);
+        // Epoch settings recorded at epoch 4 are for epoch 6, used to sign during epoch 7
+        let mut service = EpochServiceBuilder {
+            future_protocol_parameters: future_protocol_parameters.clone(),
+            scheduled_protocol_parameters: vec![
+                (Epoch(7), scheduled_protocol_parameters.clone()),
+                (Epoch(8), ProtocolParameters::new(30, 300, 0.8)),
+            ],
+            ..EpochServiceBuilder::new(epoch, MithrilFixtureBuilder::default().build())
+        }
+        .build();
+
+        service
+            .inform_epoch(epoch)
+            .await
+            .expect("inform_epoch should not fail");
+        service
+            .update_epoch_settings()
+            .await
+            .expect("update_epoch_settings should not fail");
+
+        let inserted_epoch_settings = service
+            .epoch_settings_storer
+            .get_epoch_settings(epoch.offset_to_epoch_settings_recording_epoch())
+            .await
+            .unwrap()
+            .expect("epoch settings should have been inserted");
+
+        assert_eq!(
+            inserted_epoch_settings.protocol_parameters,
+            scheduled_protocol_parameters
+        );
+    }
+
     #[tokio::test]
     async fn cant_get_data_if_inform_epoch_has_not_been_called() {
         let fixture = MithrilFixtureBuilder::default().with_signers(3).build();
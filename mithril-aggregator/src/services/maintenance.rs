@@ -0,0 +1,411 @@
+//! ## Maintenance
+//!
+//! This service runs the expensive database maintenance tasks (vacuum, `ANALYZE`) and the
+//! immutable digests cache cleanup during a configured daily maintenance window, when no
+//! artifact is being produced, so operators don't need to stop the aggregator to run them.
+
+use anyhow::{anyhow, Context};
+use chrono::{DateTime, Duration as ChronoDuration, NaiveTime, Utc};
+use slog::{debug, info, warn, Logger};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use mithril_common::digesters::cache::ImmutableFileDigestCacheProvider;
+use mithril_common::digesters::ImmutableFile;
+use mithril_common::logging::LoggerExtensions;
+use mithril_common::signed_entity_type_lock::SignedEntityTypeLock;
+use mithril_common::{StdError, StdResult};
+use mithril_persistence::sqlite::{
+    SqliteCleaner, SqliteCleaningTask, SqliteConnection, SqliteConnectionPool,
+};
+
+use crate::MetricsService;
+
+/// Daily time window, in UTC, during which the maintenance can run.
+///
+/// The window can span over midnight (i.e. `23:00-01:00`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MaintenanceWindow {
+    start: NaiveTime,
+    end: NaiveTime,
+}
+
+impl MaintenanceWindow {
+    /// Create a new window from its bounds.
+    pub fn new(start: NaiveTime, end: NaiveTime) -> Self {
+        Self { start, end }
+    }
+
+    /// Check if the given time is within the window.
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start <= self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+
+    /// Duration of the window.
+    pub fn duration(&self) -> ChronoDuration {
+        let duration = self.end - self.start;
+        if duration < ChronoDuration::zero() {
+            duration + ChronoDuration::days(1)
+        } else {
+            duration
+        }
+    }
+}
+
+impl FromStr for MaintenanceWindow {
+    type Err = StdError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (start, end) = value.split_once('-').ok_or(anyhow!(
+            "Invalid maintenance window '{value}', expected format: 'HH:MM-HH:MM'"
+        ))?;
+        let parse_time = |time: &str| {
+            NaiveTime::parse_from_str(time.trim(), "%H:%M")
+                .with_context(|| format!("Invalid time '{time}' in maintenance window '{value}'"))
+        };
+        let window = Self::new(parse_time(start)?, parse_time(end)?);
+        if window.start == window.end {
+            return Err(anyhow!(
+                "Invalid maintenance window '{value}': start and end must differ"
+            ));
+        }
+
+        Ok(window)
+    }
+}
+
+/// Run the maintenance tasks once per maintenance window.
+pub struct MaintenanceScheduler {
+    window: MaintenanceWindow,
+    main_db_connection: Arc<SqliteConnection>,
+    cardano_tx_connection_pool: Arc<SqliteConnectionPool>,
+    event_store_connection: Arc<SqliteConnection>,
+    signed_entity_type_lock: Arc<SignedEntityTypeLock>,
+    immutable_cache_provider: Arc<dyn ImmutableFileDigestCacheProvider>,
+    db_directory: PathBuf,
+    metrics_service: Arc<MetricsService>,
+    last_run: Mutex<Option<DateTime<Utc>>>,
+    logger: Logger,
+}
+
+impl MaintenanceScheduler {
+    /// Create a new instance
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        window: MaintenanceWindow,
+        main_db_connection: Arc<SqliteConnection>,
+        cardano_tx_connection_pool: Arc<SqliteConnectionPool>,
+        event_store_connection: Arc<SqliteConnection>,
+        signed_entity_type_lock: Arc<SignedEntityTypeLock>,
+        immutable_cache_provider: Arc<dyn ImmutableFileDigestCacheProvider>,
+        db_directory: PathBuf,
+        metrics_service: Arc<MetricsService>,
+        logger: Logger,
+    ) -> Self {
+        Self {
+            window,
+            main_db_connection,
+            cardano_tx_connection_pool,
+            event_store_connection,
+            signed_entity_type_lock,
+            immutable_cache_provider,
+            db_directory,
+            metrics_service,
+            last_run: Mutex::new(None),
+            logger: logger.new_with_component_name::<Self>(),
+        }
+    }
+
+    /// Run the maintenance if the given time is within the window, the maintenance didn't
+    /// already run during this window and no artifact is being produced.
+    ///
+    /// Return true if the maintenance was run.
+    pub async fn run_if_due(&self, now: DateTime<Utc>) -> StdResult<bool> {
+        if !self.window.contains(now.time()) {
+            return Ok(false);
+        }
+        if let Some(last_run) = *self.last_run.lock().unwrap() {
+            if now - last_run < self.window.duration() {
+                return Ok(false);
+            }
+        }
+        if self.signed_entity_type_lock.has_locked_entities().await {
+            debug!(
+                self.logger,
+                "Some entities are locked - Postponing maintenance"
+            );
+            return Ok(false);
+        }
+
+        info!(self.logger, "Start maintenance");
+        let start = Instant::now();
+        self.run_database_maintenance()
+            .await
+            .with_context(|| "Database maintenance failed")?;
+        self.cleanup_immutable_digests_cache()
+            .await
+            .with_context(|| "Immutable digests cache cleanup failed")?;
+        let duration = start.elapsed();
+
+        *self.last_run.lock().unwrap() = Some(now);
+        self.metrics_service
+            .get_maintenance_duration_seconds()
+            .record(duration.as_secs_f64());
+        self.metrics_service
+            .get_maintenance_total_since_startup()
+            .increment();
+        info!(self.logger, "Maintenance finished"; "duration" => ?duration);
+
+        Ok(true)
+    }
+
+    async fn run_database_maintenance(&self) -> StdResult<()> {
+        let main_db_connection = self.main_db_connection.clone();
+        let cardano_tx_db_connection_pool = self.cardano_tx_connection_pool.clone();
+        let event_store_connection = self.event_store_connection.clone();
+        let logger = self.logger.clone();
+
+        // Run the database maintenance tasks in another thread to avoid blocking the tokio runtime
+        tokio::task::spawn_blocking(move || -> StdResult<()> {
+            info!(logger, "Maintenance of the main database");
+            SqliteCleaner::new(&main_db_connection)
+                .with_logger(logger.clone())
+                .with_tasks(&[
+                    SqliteCleaningTask::Vacuum,
+                    SqliteCleaningTask::Analyze,
+                    SqliteCleaningTask::WalCheckpointTruncate,
+                ])
+                .run()?;
+
+            // The cardano transactions database is too large to be vacuumed in a timely manner
+            info!(logger, "Maintenance of the cardano transactions database");
+            let cardano_tx_db_connection = cardano_tx_db_connection_pool.connection()?;
+            SqliteCleaner::new(&cardano_tx_db_connection)
+                .with_logger(logger.clone())
+                .with_tasks(&[
+                    SqliteCleaningTask::Analyze,
+                    SqliteCleaningTask::WalCheckpointTruncate,
+                ])
+                .run()?;
+
+            info!(logger, "Maintenance of the event database");
+            SqliteCleaner::new(&event_store_connection)
+                .with_logger(logger.clone())
+                .with_tasks(&[
+                    SqliteCleaningTask::Vacuum,
+                    SqliteCleaningTask::Analyze,
+                    SqliteCleaningTask::WalCheckpointTruncate,
+                ])
+                .run()?;
+
+            Ok(())
+        })
+        .await
+        .with_context(|| "Database maintenance thread crashed")?
+    }
+
+    /// Remove the cached digests of immutable files that are no longer in the database directory.
+    async fn cleanup_immutable_digests_cache(&self) -> StdResult<()> {
+        let immutables = ImmutableFile::list_completed_in_dir(&self.db_directory)?;
+        let cached_digests: Vec<_> = self
+            .immutable_cache_provider
+            .get(immutables)
+            .await?
+            .into_iter()
+            .filter_map(|(immutable, digest)| digest.map(|digest| (immutable.filename, digest)))
+            .collect();
+
+        self.immutable_cache_provider.reset().await?;
+        self.immutable_cache_provider.store(cached_digests).await?;
+
+        Ok(())
+    }
+
+    /// Start a loop that checks at the given time interval if the maintenance should run.
+    pub async fn run_forever(&self, run_interval: Duration) {
+        let mut interval = tokio::time::interval(run_interval);
+
+        loop {
+            interval.tick().await;
+            if let Err(error) = self.run_if_due(Utc::now()).await {
+                warn!(self.logger, "Maintenance failed"; "error" => ?error);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use mithril_common::digesters::cache::MemoryImmutableFileDigestCacheProvider;
+    use mithril_common::digesters::DummyImmutablesDbBuilder;
+    use mithril_common::entities::SignedEntityTypeDiscriminants;
+    use mithril_common::test_utils::TempDir;
+
+    use crate::database::test_helper::{cardano_tx_db_connection, main_db_connection};
+    use crate::event_store::database::test_helper::event_store_db_connection;
+    use crate::test_tools::TestLogger;
+
+    use super::*;
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn build_scheduler(
+        window: &str,
+        signed_entity_type_lock: Arc<SignedEntityTypeLock>,
+        immutable_cache_provider: Arc<dyn ImmutableFileDigestCacheProvider>,
+        db_directory: PathBuf,
+    ) -> (MaintenanceScheduler, Arc<MetricsService>) {
+        let metrics_service = Arc::new(MetricsService::new(TestLogger::stdout()).unwrap());
+        let scheduler = MaintenanceScheduler::new(
+            window.parse().unwrap(),
+            Arc::new(main_db_connection().unwrap()),
+            Arc::new(SqliteConnectionPool::build(1, cardano_tx_db_connection).unwrap()),
+            Arc::new(event_store_db_connection().unwrap()),
+            signed_entity_type_lock,
+            immutable_cache_provider,
+            db_directory,
+            metrics_service.clone(),
+            TestLogger::stdout(),
+        );
+
+        (scheduler, metrics_service)
+    }
+
+    #[test]
+    fn parse_maintenance_window() {
+        assert_eq!(
+            MaintenanceWindow::new(time(2, 0), time(4, 30)),
+            "02:00-04:30".parse().unwrap()
+        );
+        assert_eq!(
+            MaintenanceWindow::new(time(23, 0), time(1, 0)),
+            "23:00 - 01:00".parse().unwrap()
+        );
+        "02:00".parse::<MaintenanceWindow>().unwrap_err();
+        "02:00-25:00".parse::<MaintenanceWindow>().unwrap_err();
+        "02:00-02:00".parse::<MaintenanceWindow>().unwrap_err();
+    }
+
+    #[test]
+    fn window_contains_time() {
+        let window = MaintenanceWindow::new(time(2, 0), time(4, 0));
+        assert!(window.contains(time(2, 0)));
+        assert!(window.contains(time(3, 59)));
+        assert!(!window.contains(time(4, 0)));
+        assert!(!window.contains(time(1, 59)));
+        assert_eq!(ChronoDuration::hours(2), window.duration());
+
+        let window_over_midnight = MaintenanceWindow::new(time(23, 0), time(1, 0));
+        assert!(window_over_midnight.contains(time(23, 30)));
+        assert!(window_over_midnight.contains(time(0, 30)));
+        assert!(!window_over_midnight.contains(time(12, 0)));
+        assert_eq!(ChronoDuration::hours(2), window_over_midnight.duration());
+    }
+
+    #[tokio::test]
+    async fn run_once_per_window_and_record_metrics() {
+        let db_directory = TempDir::create("maintenance", "run_once_per_window_and_record_metrics")
+            .join("immutable");
+        std::fs::create_dir_all(&db_directory).unwrap();
+        let (scheduler, metrics_service) = build_scheduler(
+            "02:00-04:00",
+            Arc::new(SignedEntityTypeLock::default()),
+            Arc::new(MemoryImmutableFileDigestCacheProvider::default()),
+            db_directory,
+        );
+        let day_one = Utc.with_ymd_and_hms(2024, 5, 1, 2, 30, 0).unwrap();
+
+        assert!(!scheduler
+            .run_if_due(Utc.with_ymd_and_hms(2024, 5, 1, 1, 0, 0).unwrap())
+            .await
+            .unwrap());
+        assert!(scheduler.run_if_due(day_one).await.unwrap());
+        assert!(!scheduler
+            .run_if_due(day_one + ChronoDuration::minutes(30))
+            .await
+            .unwrap());
+        assert!(scheduler
+            .run_if_due(day_one + ChronoDuration::days(1))
+            .await
+            .unwrap());
+
+        assert_eq!(
+            2,
+            metrics_service.get_maintenance_total_since_startup().get()
+        );
+    }
+
+    #[tokio::test]
+    async fn postpone_maintenance_while_entities_are_locked() {
+        let db_directory = TempDir::create(
+            "maintenance",
+            "postpone_maintenance_while_entities_are_locked",
+        )
+        .join("immutable");
+        std::fs::create_dir_all(&db_directory).unwrap();
+        let signed_entity_type_lock = Arc::new(SignedEntityTypeLock::default());
+        signed_entity_type_lock
+            .lock(SignedEntityTypeDiscriminants::CardanoImmutableFilesFull)
+            .await;
+        let (scheduler, _) = build_scheduler(
+            "02:00-04:00",
+            signed_entity_type_lock.clone(),
+            Arc::new(MemoryImmutableFileDigestCacheProvider::default()),
+            db_directory,
+        );
+        let now = Utc.with_ymd_and_hms(2024, 5, 1, 2, 30, 0).unwrap();
+
+        assert!(!scheduler.run_if_due(now).await.unwrap());
+
+        signed_entity_type_lock
+            .release(SignedEntityTypeDiscriminants::CardanoImmutableFilesFull)
+            .await;
+        assert!(scheduler.run_if_due(now).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn cleanup_digests_of_immutables_no_longer_in_db_directory() {
+        let db = DummyImmutablesDbBuilder::new(
+            "cleanup_digests_of_immutables_no_longer_in_db_directory",
+        )
+        .with_immutables(&[1, 2])
+        .append_immutable_trio()
+        .build();
+        let cache_provider = Arc::new(MemoryImmutableFileDigestCacheProvider::default());
+        let removed_immutable = "00099.chunk".to_string();
+        let mut cached_digests: Vec<_> = db
+            .immutables_files
+            .iter()
+            .map(|immutable| (immutable.filename.clone(), "digest".to_string()))
+            .collect();
+        cached_digests.push((removed_immutable.clone(), "digest".to_string()));
+        cache_provider.store(cached_digests).await.unwrap();
+        let (scheduler, _) = build_scheduler(
+            "02:00-04:00",
+            Arc::new(SignedEntityTypeLock::default()),
+            cache_provider.clone(),
+            db.dir.clone(),
+        );
+
+        scheduler.cleanup_immutable_digests_cache().await.unwrap();
+
+        let remaining = cache_provider
+            .get(db.immutables_files.clone())
+            .await
+            .unwrap();
+        assert!(remaining.values().all(|digest| digest.is_some()));
+        let removed = ImmutableFile::new(db.dir.join(&removed_immutable)).unwrap();
+        let result = cache_provider.get(vec![removed]).await.unwrap();
+        assert_eq!(vec![None], result.into_values().collect::<Vec<_>>());
+    }
+}
@@ -6,27 +6,57 @@
 //! * StakeEntity: fetches Cardano stake distribution information
 //! * Certifier: registers signers and create certificates once ready
 //! * SignedEntity: provides information about signed entities.
+//! * CertificationWatchdog: warns when certifications are getting close to their deadline.
+//! * Alerting: notifies a webhook when the aggregator state violates alerting rules.
+//! * AuditLog: records security-sensitive operations in a dedicated stream.
+//! * ChainSimulator: simulates a Cardano chain and its signers for local development.
+//! * RegistrationChallenge: issues the challenges authenticating the signer registrations.
+//! * SignatureReplay: detects the single signatures already registered.
+//! * TransparencyLog: publishes the issued certificates to a transparency log.
+//! * TransactionsBloomFilter: pre-checks if the transactions to prove are certified.
 //!
 //! Each service is defined by a public API (a trait) that is used in the controllers (runtimes).
 
+mod alerting;
+mod artifact_availability;
+mod audit_log;
 mod cardano_transactions_importer;
+mod certification_watchdog;
 mod certifier;
+mod chain_simulator;
 mod epoch_service;
+mod maintenance;
 mod message;
 mod prover;
+mod registration_challenge;
 mod signable_builder;
+mod signature_replay;
 mod signed_entity;
 mod stake_distribution;
+mod static_mirror;
+mod transactions_bloom_filter;
+mod transparency_log;
 mod upkeep;
 mod usage_reporter;
 
+pub use alerting::*;
+pub use artifact_availability::*;
+pub use audit_log::*;
 pub use cardano_transactions_importer::*;
+pub use certification_watchdog::*;
 pub use certifier::*;
+pub use chain_simulator::*;
 pub use epoch_service::*;
+pub use maintenance::*;
 pub use message::*;
 pub use prover::*;
+pub use registration_challenge::*;
 pub use signable_builder::*;
+pub use signature_replay::*;
 pub use signed_entity::*;
 pub use stake_distribution::*;
+pub use static_mirror::*;
+pub use transactions_bloom_filter::*;
+pub use transparency_log::*;
 pub use upkeep::*;
 pub use usage_reporter::*;
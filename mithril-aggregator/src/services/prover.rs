@@ -1,12 +1,14 @@
 use async_trait::async_trait;
-use rayon::prelude::*;
-use slog::{debug, info, Logger};
+use rayon::{prelude::*, ThreadPool};
+use slog::{debug, info, warn, Logger};
 use std::{
     collections::{BTreeMap, BTreeSet, HashMap},
-    sync::Arc,
+    sync::{Arc, RwLock},
     time::Duration,
 };
 
+use anyhow::anyhow;
+
 use mithril_common::{
     crypto_helper::{MKMap, MKMapNode, MKTree, MKTreeStorer},
     entities::{
@@ -18,6 +20,23 @@ use mithril_common::{
     StdResult,
 };
 
+use crate::services::{TransactionsBloomFilter, TransactionsBloomFilterBuilder};
+use crate::store::MerkleMapCacheStorer;
+
+/// Number of block ranges whose transactions are fetched at once when computing the transactions
+/// bloom filter of the prover
+const BLOOM_FILTER_BLOCK_RANGES_BATCH_SIZE: usize = 100;
+
+/// Capacity of a rebuilt transactions bloom filter, relative to its number of transactions, so
+/// that the next transactions are added to it without rebuilding it
+const BLOOM_FILTER_CAPACITY_FACTOR: usize = 2;
+
+/// Transactions bloom filter with the block number up to which its transactions are indexed
+struct IndexedTransactionsBloomFilter {
+    bloom_filter: TransactionsBloomFilter,
+    indexed_up_to: BlockNumber,
+}
+
 /// Prover service is the cryptographic engine in charge of producing cryptographic proofs for transactions
 #[cfg_attr(test, mockall::automock)]
 #[async_trait]
@@ -56,25 +75,207 @@ pub struct MithrilProverService<S: MKTreeStorer> {
     transaction_retriever: Arc<dyn TransactionsRetriever>,
     block_range_root_retriever: Arc<dyn BlockRangeRootRetriever<S>>,
     mk_map_pool: ResourcePool<MKMap<BlockRange, MKMapNode<BlockRange, S>, S>>,
+    proofs_thread_pool: ThreadPool,
+    transactions_bloom_filter_enabled: bool,
+    transactions_bloom_filter: RwLock<Option<IndexedTransactionsBloomFilter>>,
+    merkle_map_cache_store: Option<Arc<dyn MerkleMapCacheStorer>>,
     logger: Logger,
 }
 
 impl<S: MKTreeStorer> MithrilProverService<S> {
     /// Create a new Mithril prover
+    ///
+    /// The block ranges Merkle trees and sub proofs are computed in parallel in the
+    /// `proofs_thread_pool`, whose size bounds the concurrency of a proofs request.
+    ///
+    /// If `transactions_bloom_filter_enabled` is set, a bloom filter of the certified transactions
+    /// is computed with the cache so that the transactions not certified yet are discarded
+    /// without querying the transactions retriever. Only the transactions of the block ranges
+    /// not indexed yet are added to the filter when the cache is computed again.
+    ///
+    /// If a `merkle_map_cache_store` is given, the block ranges Merkle map of the cache is
+    /// persisted so that it is not recomputed from the block range roots after a restart.
     pub fn new(
         transaction_retriever: Arc<dyn TransactionsRetriever>,
         block_range_root_retriever: Arc<dyn BlockRangeRootRetriever<S>>,
         mk_map_pool_size: usize,
+        proofs_thread_pool: ThreadPool,
+        transactions_bloom_filter_enabled: bool,
+        merkle_map_cache_store: Option<Arc<dyn MerkleMapCacheStorer>>,
         logger: Logger,
     ) -> Self {
         Self {
             transaction_retriever,
             block_range_root_retriever,
             mk_map_pool: ResourcePool::new(mk_map_pool_size, vec![]),
+            proofs_thread_pool,
+            transactions_bloom_filter_enabled,
+            transactions_bloom_filter: RwLock::new(None),
+            merkle_map_cache_store,
             logger: logger.new_with_component_name::<Self>(),
         }
     }
 
+    /// Discard the transactions that are not certified according to the bloom filter.
+    ///
+    /// All the transactions are kept if the bloom filter is not computed.
+    fn filter_possibly_certified_transactions(
+        &self,
+        transaction_hashes: &[TransactionHash],
+    ) -> StdResult<Vec<TransactionHash>> {
+        let transactions_bloom_filter = self
+            .transactions_bloom_filter
+            .read()
+            .map_err(|e| anyhow!("Transactions bloom filter lock is poisoned: {e}"))?;
+
+        Ok(match transactions_bloom_filter.as_ref() {
+            Some(indexed) => indexed
+                .bloom_filter
+                .filter_transaction_hashes(transaction_hashes),
+            None => transaction_hashes.to_vec(),
+        })
+    }
+
+    /// Load the block ranges Merkle map computed up to the given block number from the store.
+    ///
+    /// A Merkle map that can not be loaded is recomputed, so the errors are only logged.
+    async fn load_merkle_map_cache(
+        &self,
+        up_to: BlockNumber,
+    ) -> Option<MKMap<BlockRange, MKMapNode<BlockRange, S>, S>> {
+        let merkle_map_cache_store = self.merkle_map_cache_store.as_ref()?;
+        match merkle_map_cache_store
+            .get(up_to)
+            .await
+            .and_then(|snapshot| snapshot.map(MKMap::from_snapshot).transpose())
+        {
+            Ok(mk_map) => mk_map,
+            Err(error) => {
+                warn!(
+                    self.logger, "Could not load the persisted Merkle map cache";
+                    "up_to_block_number" => *up_to, "error" => ?error
+                );
+                None
+            }
+        }
+    }
+
+    /// Persist the block ranges Merkle map computed up to the given block number in the store
+    async fn save_merkle_map_cache(
+        &self,
+        up_to: BlockNumber,
+        mk_map: &MKMap<BlockRange, MKMapNode<BlockRange, S>, S>,
+    ) {
+        if let Some(merkle_map_cache_store) = &self.merkle_map_cache_store {
+            let result = match mk_map.to_snapshot() {
+                Ok(snapshot) => merkle_map_cache_store.save(up_to, snapshot).await,
+                Err(error) => Err(error),
+            };
+            if let Err(error) = result {
+                warn!(
+                    self.logger, "Could not persist the Merkle map cache";
+                    "up_to_block_number" => *up_to, "error" => ?error
+                );
+            }
+        }
+    }
+
+    /// Collect the transaction hashes of the given block ranges in a bloom filter builder
+    async fn collect_transactions_of_block_ranges(
+        &self,
+        block_ranges: &[BlockRange],
+    ) -> StdResult<TransactionsBloomFilterBuilder> {
+        let mut bloom_filter_builder = TransactionsBloomFilterBuilder::new();
+        for block_ranges_batch in block_ranges.chunks(BLOOM_FILTER_BLOCK_RANGES_BATCH_SIZE) {
+            let transactions = self
+                .transaction_retriever
+                .get_by_block_ranges(block_ranges_batch.to_vec())
+                .await?;
+            bloom_filter_builder
+                .add_transaction_hashes(transactions.iter().map(|t| &t.transaction_hash));
+        }
+
+        Ok(bloom_filter_builder)
+    }
+
+    /// Update the bloom filter with the transactions of the block ranges of the Merkle map.
+    ///
+    /// Only the block ranges after the last indexed block are read when the filter has enough
+    /// capacity left. Otherwise, or if the Merkle map ends before the last indexed block (after a
+    /// rollback), the filter is rebuilt from all the block ranges.
+    async fn update_transactions_bloom_filter(
+        &self,
+        mk_map: &MKMap<BlockRange, MKMapNode<BlockRange, S>, S>,
+    ) -> StdResult<()> {
+        let block_ranges = mk_map
+            .iter()
+            .map(|(block_range, _)| block_range.to_owned())
+            .collect::<Vec<_>>();
+        let indexed_up_to = block_ranges
+            .last()
+            .map(|block_range| block_range.end)
+            .unwrap_or_default();
+        let current_filter = self
+            .transactions_bloom_filter
+            .read()
+            .map_err(|e| anyhow!("Transactions bloom filter lock is poisoned: {e}"))?
+            .as_ref()
+            .map(|indexed| {
+                (
+                    indexed.indexed_up_to,
+                    indexed.bloom_filter.remaining_capacity(),
+                )
+            });
+
+        if let Some((last_indexed_up_to, remaining_capacity)) = current_filter {
+            if last_indexed_up_to <= indexed_up_to {
+                let new_block_ranges = block_ranges
+                    .iter()
+                    .filter(|block_range| block_range.start >= last_indexed_up_to)
+                    .cloned()
+                    .collect::<Vec<_>>();
+                let bloom_filter_builder = self
+                    .collect_transactions_of_block_ranges(&new_block_ranges)
+                    .await?;
+                if bloom_filter_builder.len() <= remaining_capacity {
+                    debug!(
+                        self.logger, "Adding the transactions of {} block ranges to the transactions bloom filter", new_block_ranges.len();
+                        "last_indexed_up_to" => *last_indexed_up_to, "indexed_up_to" => *indexed_up_to
+                    );
+                    let mut transactions_bloom_filter = self
+                        .transactions_bloom_filter
+                        .write()
+                        .map_err(|e| anyhow!("Transactions bloom filter lock is poisoned: {e}"))?;
+                    if let Some(indexed) = transactions_bloom_filter.as_mut() {
+                        indexed.bloom_filter.extend(bloom_filter_builder)?;
+                        indexed.indexed_up_to = indexed_up_to;
+                    }
+
+                    return Ok(());
+                }
+            }
+        }
+
+        debug!(
+            self.logger, "Rebuilding the transactions bloom filter";
+            "indexed_up_to" => *indexed_up_to
+        );
+        let bloom_filter_builder = self
+            .collect_transactions_of_block_ranges(&block_ranges)
+            .await?;
+        let capacity = bloom_filter_builder.len() * BLOOM_FILTER_CAPACITY_FACTOR;
+        *self
+            .transactions_bloom_filter
+            .write()
+            .map_err(|e| anyhow!("Transactions bloom filter lock is poisoned: {e}"))? =
+            Some(IndexedTransactionsBloomFilter {
+                bloom_filter: bloom_filter_builder.build_with_capacity(capacity),
+                indexed_up_to,
+            });
+
+        Ok(())
+    }
+
     async fn get_block_ranges(
         &self,
         transaction_hashes: &[TransactionHash],
@@ -120,6 +321,13 @@ impl<S: MKTreeStorer> ProverService for MithrilProverService<S> {
         up_to: BlockNumber,
         transaction_hashes: &[TransactionHash],
     ) -> StdResult<Vec<CardanoTransactionsSetProof>> {
+        // 0 - Discard the transactions that are not certified yet
+        let transaction_hashes =
+            &self.filter_possibly_certified_transactions(transaction_hashes)?;
+        if transaction_hashes.is_empty() {
+            return Ok(vec![]);
+        }
+
         // 1 - Compute the set of block ranges with transactions to prove
         let block_ranges_transactions = self.get_block_ranges(transaction_hashes, up_to).await?;
         let block_range_transactions = self
@@ -127,13 +335,16 @@ impl<S: MKTreeStorer> ProverService for MithrilProverService<S> {
             .await?;
 
         // 2 - Compute block ranges sub Merkle trees
-        let mk_trees: StdResult<Vec<(BlockRange, MKTree<S>)>> = block_range_transactions
-            .into_iter()
-            .map(|(block_range, transactions)| {
-                let mk_tree = MKTree::new(&transactions)?;
-                Ok((block_range, mk_tree))
-            })
-            .collect();
+        let mk_trees: StdResult<Vec<(BlockRange, MKTree<S>)>> =
+            self.proofs_thread_pool.install(|| {
+                block_range_transactions
+                    .into_par_iter()
+                    .map(|(block_range, transactions)| {
+                        let mk_tree = MKTree::new(&transactions)?;
+                        Ok((block_range, mk_tree))
+                    })
+                    .collect()
+            });
         let mk_trees = BTreeMap::from_iter(mk_trees?);
 
         // 3 - Compute block range roots Merkle map
@@ -146,7 +357,10 @@ impl<S: MKTreeStorer> ProverService for MithrilProverService<S> {
         }
 
         // 5 - Compute the proof for all transactions
-        if let Ok(mk_proof) = mk_map.compute_proof(transaction_hashes) {
+        if let Ok(mk_proof) = self
+            .proofs_thread_pool
+            .install(|| mk_map.compute_proof(transaction_hashes))
+        {
             self.mk_map_pool.give_back_resource_pool_item(mk_map)?;
             let mk_proof_leaves = mk_proof.leaves();
             let transaction_hashes_certified: Vec<TransactionHash> = transaction_hashes
@@ -170,10 +384,26 @@ impl<S: MKTreeStorer> ProverService for MithrilProverService<S> {
             self.logger, "Starts computing the Merkle map pool resource of size {pool_size}";
             "up_to_block_number" => *up_to,
         );
-        let mk_map_cache = self
-            .block_range_root_retriever
-            .compute_merkle_map_from_block_range_roots(up_to)
-            .await?;
+        let mk_map_cache = match self.load_merkle_map_cache(up_to).await {
+            Some(mk_map_cache) => {
+                debug!(self.logger, "Loaded the persisted Merkle map cache");
+                mk_map_cache
+            }
+            None => {
+                let mk_map_cache = self
+                    .block_range_root_retriever
+                    .compute_merkle_map_from_block_range_roots(up_to)
+                    .await?;
+                self.save_merkle_map_cache(up_to, &mk_map_cache).await;
+                mk_map_cache
+            }
+        };
+        if self.transactions_bloom_filter_enabled {
+            // The filter is replaced before the Merkle maps: the pool never contains a
+            // transaction that would be discarded by the filter, except after a rollback
+            debug!(self.logger, "Updating the transactions bloom filter");
+            self.update_transactions_bloom_filter(&mk_map_cache).await?;
+        }
         let mk_maps_new = (1..=pool_size)
             .into_par_iter()
             .map(|i| {
@@ -218,6 +448,7 @@ mod tests {
     use mithril_common::test_utils::CardanoTransactionsBuilder;
     use mockall::mock;
     use mockall::predicate::eq;
+    use rayon::ThreadPoolBuilder;
 
     use crate::test_tools::TestLogger;
 
@@ -355,10 +586,30 @@ mod tests {
         }
     }
 
+    fn build_proofs_thread_pool() -> ThreadPool {
+        ThreadPoolBuilder::new().num_threads(2).build().unwrap()
+    }
+
     fn build_prover<F, G, S: MKTreeStorer + 'static>(
         transaction_retriever_mock_config: F,
         block_range_root_retriever_mock_config: G,
     ) -> MithrilProverService<S>
+    where
+        F: FnOnce(&mut MockTransactionsRetriever),
+        G: FnOnce(&mut MockBlockRangeRootRetrieverImpl<S>),
+    {
+        build_prover_with_transactions_bloom_filter(
+            false,
+            transaction_retriever_mock_config,
+            block_range_root_retriever_mock_config,
+        )
+    }
+
+    fn build_prover_with_transactions_bloom_filter<F, G, S: MKTreeStorer + 'static>(
+        transactions_bloom_filter_enabled: bool,
+        transaction_retriever_mock_config: F,
+        block_range_root_retriever_mock_config: G,
+    ) -> MithrilProverService<S>
     where
         F: FnOnce(&mut MockTransactionsRetriever),
         G: FnOnce(&mut MockBlockRangeRootRetrieverImpl<S>),
@@ -373,6 +624,9 @@ mod tests {
             Arc::new(transaction_retriever),
             Arc::new(block_range_root_retriever),
             mk_map_pool_size,
+            build_proofs_thread_pool(),
+            transactions_bloom_filter_enabled,
+            None,
             TestLogger::stdout(),
         )
     }
@@ -641,4 +895,297 @@ mod tests {
             .await
             .expect_err("Should have failed because of block range root retriever failure");
     }
+
+    #[tokio::test]
+    async fn compute_proof_for_certified_transactions_with_transactions_bloom_filter() {
+        let transactions = CardanoTransactionsBuilder::new()
+            .max_transactions_per_block(1)
+            .blocks_per_block_range(3)
+            .build_block_ranges(5);
+        let transactions_to_prove =
+            test_data::filter_transactions_for_indices(&[1, 2, 4], &transactions);
+        let test_data = test_data::build_test_data(&transactions_to_prove, &transactions);
+        let prover = build_prover_with_transactions_bloom_filter(
+            true,
+            |transaction_retriever_mock| {
+                let transaction_hashes_to_prove = test_data.transaction_hashes_to_prove.clone();
+                let transactions_to_prove = transactions_to_prove.clone();
+                transaction_retriever_mock
+                    .expect_get_by_hashes()
+                    .with(eq(transaction_hashes_to_prove), eq(test_data.beacon))
+                    .return_once(move |_, _| Ok(transactions_to_prove));
+
+                let transactions = transactions.clone();
+                transaction_retriever_mock
+                    .expect_get_by_block_ranges()
+                    .returning(move |block_ranges| {
+                        Ok(test_data::filter_transactions_for_block_ranges(
+                            &block_ranges,
+                            &transactions,
+                        ))
+                    });
+            },
+            |block_range_root_retriever_mock| {
+                let block_ranges_map = test_data.block_ranges_map.clone();
+                block_range_root_retriever_mock
+                    .expect_compute_merkle_map_from_block_range_roots()
+                    .return_once(|_| {
+                        Ok(test_data::compute_mk_map_from_block_ranges_map(
+                            block_ranges_map,
+                        ))
+                    });
+            },
+        );
+        prover.compute_cache(test_data.beacon).await.unwrap();
+
+        let transactions_set_proof = prover
+            .compute_transactions_proofs(test_data.beacon, &test_data.transaction_hashes_to_prove)
+            .await
+            .unwrap();
+
+        assert_eq!(transactions_set_proof.len(), 1);
+        assert_eq!(
+            transactions_set_proof[0].transactions_hashes(),
+            test_data.transaction_hashes_to_prove
+        );
+        transactions_set_proof[0].verify().unwrap();
+    }
+
+    #[tokio::test]
+    async fn transactions_bloom_filter_is_updated_with_the_new_block_ranges_only() {
+        let transactions = CardanoTransactionsBuilder::new()
+            .max_transactions_per_block(1)
+            .blocks_per_block_range(3)
+            .build_block_ranges(5);
+        let test_data = test_data::build_test_data(&[], &transactions);
+        let all_block_ranges = test_data
+            .block_ranges_map
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        let (first_block_ranges, new_block_ranges) = all_block_ranges.split_at(3);
+        let first_block_ranges_map = test_data
+            .block_ranges_map
+            .clone()
+            .into_iter()
+            .filter(|(block_range, _)| first_block_ranges.contains(block_range))
+            .collect::<BTreeMap<_, _>>();
+        let new_transaction =
+            test_data::filter_transactions_for_block_ranges(new_block_ranges, &transactions)[0]
+                .clone();
+        let prover = build_prover_with_transactions_bloom_filter(
+            true,
+            |transaction_retriever_mock| {
+                let mut sequence = mockall::Sequence::new();
+                for expected_block_ranges in [first_block_ranges, new_block_ranges] {
+                    let expected_block_ranges = expected_block_ranges.to_vec();
+                    let transactions = transactions.clone();
+                    transaction_retriever_mock
+                        .expect_get_by_block_ranges()
+                        .withf(move |block_ranges| *block_ranges == expected_block_ranges)
+                        .once()
+                        .in_sequence(&mut sequence)
+                        .returning(move |block_ranges| {
+                            Ok(test_data::filter_transactions_for_block_ranges(
+                                &block_ranges,
+                                &transactions,
+                            ))
+                        });
+                }
+                transaction_retriever_mock
+                    .expect_get_by_hashes()
+                    .returning(|_, _| Ok(vec![]));
+            },
+            |block_range_root_retriever_mock| {
+                let mut sequence = mockall::Sequence::new();
+                for block_ranges_map in [first_block_ranges_map, test_data.block_ranges_map.clone()]
+                {
+                    block_range_root_retriever_mock
+                        .expect_compute_merkle_map_from_block_range_roots()
+                        .once()
+                        .in_sequence(&mut sequence)
+                        .return_once(|_| {
+                            Ok(test_data::compute_mk_map_from_block_ranges_map(
+                                block_ranges_map,
+                            ))
+                        });
+                }
+            },
+        );
+        prover.compute_cache(test_data.beacon).await.unwrap();
+        assert_eq!(
+            Vec::<TransactionHash>::new(),
+            prover
+                .filter_possibly_certified_transactions(&[new_transaction.transaction_hash.clone()])
+                .unwrap()
+        );
+
+        prover.compute_cache(test_data.beacon).await.unwrap();
+        assert_eq!(
+            vec![new_transaction.transaction_hash.clone()],
+            prover
+                .filter_possibly_certified_transactions(&[new_transaction.transaction_hash.clone()])
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn unknown_transactions_are_discarded_by_the_transactions_bloom_filter_without_retrieving_them(
+    ) {
+        let transactions = CardanoTransactionsBuilder::new()
+            .max_transactions_per_block(1)
+            .blocks_per_block_range(3)
+            .build_block_ranges(5);
+        let test_data = test_data::build_test_data(&[], &transactions);
+        let prover = build_prover_with_transactions_bloom_filter(
+            true,
+            |transaction_retriever_mock| {
+                transaction_retriever_mock.expect_get_by_hashes().never();
+
+                let transactions = transactions.clone();
+                transaction_retriever_mock
+                    .expect_get_by_block_ranges()
+                    .once()
+                    .returning(move |block_ranges| {
+                        Ok(test_data::filter_transactions_for_block_ranges(
+                            &block_ranges,
+                            &transactions,
+                        ))
+                    });
+            },
+            |block_range_root_retriever_mock| {
+                let block_ranges_map = test_data.block_ranges_map.clone();
+                block_range_root_retriever_mock
+                    .expect_compute_merkle_map_from_block_range_roots()
+                    .return_once(|_| {
+                        Ok(test_data::compute_mk_map_from_block_ranges_map(
+                            block_ranges_map,
+                        ))
+                    });
+            },
+        );
+        prover.compute_cache(test_data.beacon).await.unwrap();
+
+        let transactions_set_proof = prover
+            .compute_transactions_proofs(
+                test_data.beacon,
+                &["tx-unknown-123".to_string(), "tx-unknown-456".to_string()],
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(transactions_set_proof.len(), 0);
+    }
+
+    mod merkle_map_cache {
+        use crate::store::MockMerkleMapCacheStorer;
+
+        use super::*;
+
+        fn build_prover_with_merkle_map_cache_store(
+            transaction_retriever: MockTransactionsRetriever,
+            block_range_root_retriever: MockBlockRangeRootRetrieverImpl<MKTreeStoreInMemory>,
+            merkle_map_cache_store: MockMerkleMapCacheStorer,
+        ) -> MithrilProverService<MKTreeStoreInMemory> {
+            MithrilProverService::new(
+                Arc::new(transaction_retriever),
+                Arc::new(block_range_root_retriever),
+                1,
+                build_proofs_thread_pool(),
+                false,
+                Some(Arc::new(merkle_map_cache_store)),
+                TestLogger::stdout(),
+            )
+        }
+
+        #[tokio::test]
+        async fn compute_cache_persists_the_computed_merkle_map() {
+            let transactions = CardanoTransactionsBuilder::new()
+                .max_transactions_per_block(1)
+                .blocks_per_block_range(3)
+                .build_block_ranges(5);
+            let test_data = test_data::build_test_data(&[], &transactions);
+            let expected_snapshot =
+                test_data::compute_mk_map_from_block_ranges_map(test_data.block_ranges_map.clone())
+                    .to_snapshot()
+                    .unwrap();
+            let mut block_range_root_retriever = MockBlockRangeRootRetrieverImpl::new();
+            let block_ranges_map = test_data.block_ranges_map.clone();
+            block_range_root_retriever
+                .expect_compute_merkle_map_from_block_range_roots()
+                .return_once(|_| {
+                    Ok(test_data::compute_mk_map_from_block_ranges_map(
+                        block_ranges_map,
+                    ))
+                });
+            let mut merkle_map_cache_store = MockMerkleMapCacheStorer::new();
+            merkle_map_cache_store
+                .expect_get()
+                .with(eq(test_data.beacon))
+                .return_once(|_| Ok(None));
+            merkle_map_cache_store
+                .expect_save()
+                .with(eq(test_data.beacon), eq(expected_snapshot))
+                .return_once(|_, _| Ok(()));
+            let prover = build_prover_with_merkle_map_cache_store(
+                MockTransactionsRetriever::new(),
+                block_range_root_retriever,
+                merkle_map_cache_store,
+            );
+
+            prover.compute_cache(test_data.beacon).await.unwrap();
+        }
+
+        #[tokio::test]
+        async fn compute_cache_loads_the_persisted_merkle_map_without_recomputing_it() {
+            let transactions = CardanoTransactionsBuilder::new()
+                .max_transactions_per_block(1)
+                .blocks_per_block_range(3)
+                .build_block_ranges(5);
+            let transactions_to_prove =
+                test_data::filter_transactions_for_indices(&[1, 2, 4], &transactions);
+            let test_data = test_data::build_test_data(&transactions_to_prove, &transactions);
+            let persisted_snapshot =
+                test_data::compute_mk_map_from_block_ranges_map(test_data.block_ranges_map.clone())
+                    .to_snapshot()
+                    .unwrap();
+            let mut transaction_retriever = MockTransactionsRetriever::new();
+            let transactions_to_prove_clone = transactions_to_prove.clone();
+            transaction_retriever
+                .expect_get_by_hashes()
+                .return_once(move |_, _| Ok(transactions_to_prove_clone));
+            let all_transactions_in_block_ranges_to_prove =
+                test_data.all_transactions_in_block_ranges_to_prove.clone();
+            transaction_retriever
+                .expect_get_by_block_ranges()
+                .return_once(move |_| Ok(all_transactions_in_block_ranges_to_prove));
+            let mut block_range_root_retriever = MockBlockRangeRootRetrieverImpl::new();
+            block_range_root_retriever
+                .expect_compute_merkle_map_from_block_range_roots()
+                .never();
+            let mut merkle_map_cache_store = MockMerkleMapCacheStorer::new();
+            merkle_map_cache_store
+                .expect_get()
+                .with(eq(test_data.beacon))
+                .return_once(|_| Ok(Some(persisted_snapshot)));
+            merkle_map_cache_store.expect_save().never();
+            let prover = build_prover_with_merkle_map_cache_store(
+                transaction_retriever,
+                block_range_root_retriever,
+                merkle_map_cache_store,
+            );
+            prover.compute_cache(test_data.beacon).await.unwrap();
+
+            let transactions_set_proof = prover
+                .compute_transactions_proofs(
+                    test_data.beacon,
+                    &test_data.transaction_hashes_to_prove,
+                )
+                .await
+                .unwrap();
+
+            assert_eq!(transactions_set_proof.len(), 1);
+            transactions_set_proof[0].verify().unwrap();
+        }
+    }
 }
@@ -0,0 +1,423 @@
+//! ## Registration challenge
+//!
+//! Issue the challenges that the certified signers sign with the KES secret key of their Cardano
+//! node when they register, so that a captured registration payload can not be replayed.
+//!
+//! The challenges are stateless: they carry their expiry and are authenticated with a HMAC, bound
+//! to the party id of the signer when it is given, so that issuing them does not consume any
+//! memory of the aggregator. Only the challenges of the authenticated registrations are kept, until
+//! they expire, to reject their reuse.
+
+use anyhow::{anyhow, Context};
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use rand_core::{OsRng, RngCore};
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use mithril_common::crypto_helper::{
+    ProtocolOpCert, ProtocolRegistrationChallengeSignature, ProtocolSignerVerificationKey,
+    RegistrationChallengeVerifier,
+};
+use mithril_common::entities::PartyId;
+use mithril_common::messages::{RegisterSignerMessage, RegistrationChallengeMessage};
+use mithril_common::StdResult;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Issue and check the signer registration challenges.
+#[cfg_attr(test, mockall::automock)]
+pub trait RegistrationChallengeService: Sync + Send {
+    /// Issue a challenge for the registration of a signer, bound to its party id if given.
+    fn issue_challenge(&self, party_id: Option<PartyId>)
+        -> StdResult<RegistrationChallengeMessage>;
+
+    /// Check that the registration of a certified signer answers a challenge issued by this
+    /// service, for its party id or for any signer, that has neither expired nor been used yet.
+    fn authenticate_registration(&self, message: &RegisterSignerMessage) -> StdResult<()>;
+}
+
+/// Registration challenge service issuing challenges authenticated with a HMAC.
+///
+/// A challenge is formatted as `{nonce}.{expiry timestamp}.{hmac}`, the HMAC key is generated at
+/// startup so the challenges issued before a restart are rejected.
+pub struct MithrilRegistrationChallengeService {
+    validity: Duration,
+    is_challenge_required: bool,
+    key: [u8; 32],
+    used_challenges: Mutex<HashMap<String, DateTime<Utc>>>,
+}
+
+impl MithrilRegistrationChallengeService {
+    /// MithrilRegistrationChallengeService factory
+    pub fn new(validity: std::time::Duration, is_challenge_required: bool) -> Self {
+        let mut key = [0u8; 32];
+        OsRng.fill_bytes(&mut key);
+
+        Self {
+            validity: Duration::from_std(validity).unwrap_or(Duration::max_value()),
+            is_challenge_required,
+            key,
+            used_challenges: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn hmac(&self, nonce: &str, expires_at: i64, party_id: Option<&PartyId>) -> HmacSha256 {
+        let mut mac =
+            HmacSha256::new_from_slice(&self.key).expect("HMAC can take a key of any size");
+        mac.update(nonce.as_bytes());
+        mac.update(b".");
+        mac.update(expires_at.to_string().as_bytes());
+        mac.update(b".");
+        if let Some(party_id) = party_id {
+            mac.update(party_id.as_bytes());
+        }
+
+        mac
+    }
+
+    fn issue_challenge_at(
+        &self,
+        party_id: Option<&PartyId>,
+        now: DateTime<Utc>,
+    ) -> StdResult<RegistrationChallengeMessage> {
+        let mut nonce = [0u8; 16];
+        OsRng.fill_bytes(&mut nonce);
+        let nonce = hex::encode(nonce);
+        let expires_at = (now + self.validity).timestamp();
+        let tag = hex::encode(
+            self.hmac(&nonce, expires_at, party_id)
+                .finalize()
+                .into_bytes(),
+        );
+
+        Ok(RegistrationChallengeMessage {
+            challenge: format!("{nonce}.{expires_at}.{tag}"),
+            expires_at: DateTime::from_timestamp(expires_at, 0)
+                .ok_or_else(|| anyhow!("Invalid registration challenge expiry: {expires_at}"))?,
+        })
+    }
+
+    /// Check that the challenge was issued by this service for the party id, or for any signer,
+    /// and return its expiry.
+    fn check_challenge(
+        &self,
+        party_id: &PartyId,
+        challenge: &str,
+        now: DateTime<Utc>,
+    ) -> StdResult<DateTime<Utc>> {
+        let invalid_challenge =
+            || anyhow!("Invalid registration challenge '{challenge}' for party id '{party_id}'");
+        let [nonce, expires_at, tag] = challenge.split('.').collect::<Vec<_>>()[..] else {
+            return Err(invalid_challenge());
+        };
+        let expires_at: i64 = expires_at.parse().map_err(|_| invalid_challenge())?;
+        let tag = hex::decode(tag).map_err(|_| invalid_challenge())?;
+        let is_authentic = [Some(party_id), None].into_iter().any(|bound_party_id| {
+            self.hmac(nonce, expires_at, bound_party_id)
+                .verify_slice(&tag)
+                .is_ok()
+        });
+        if !is_authentic {
+            return Err(invalid_challenge());
+        }
+        let expires_at = DateTime::from_timestamp(expires_at, 0).ok_or_else(invalid_challenge)?;
+        if expires_at <= now {
+            return Err(anyhow!(
+                "Expired registration challenge '{challenge}' for party id '{party_id}'"
+            ));
+        }
+        if self.used_challenges.lock().unwrap().contains_key(challenge) {
+            return Err(anyhow!(
+                "Already used registration challenge '{challenge}' for party id '{party_id}'"
+            ));
+        }
+
+        Ok(expires_at)
+    }
+
+    fn authenticate_registration_at(
+        &self,
+        message: &RegisterSignerMessage,
+        now: DateTime<Utc>,
+    ) -> StdResult<()> {
+        // Uncertified signers can only register on networks that do not require certification
+        let Some(operational_certificate) = &message.operational_certificate else {
+            return Ok(());
+        };
+
+        let (challenge, signature) = match (
+            &message.registration_challenge,
+            &message.registration_challenge_signature,
+        ) {
+            (Some(challenge), Some(signature)) => (challenge, signature),
+            (None, None) if !self.is_challenge_required => return Ok(()),
+            (None, None) => return Err(anyhow!("Missing registration challenge")),
+            _ => {
+                return Err(anyhow!(
+                    "Both the registration challenge and its signature must be provided"
+                ))
+            }
+        };
+
+        let expires_at = self.check_challenge(&message.party_id, challenge, now)?;
+        RegistrationChallengeVerifier::verify(
+            challenge,
+            &ProtocolSignerVerificationKey::from_json_hex(&message.verification_key)
+                .with_context(|| "Could not decode the signer verification key")?,
+            &ProtocolRegistrationChallengeSignature::from_json_hex(signature)
+                .with_context(|| "Could not decode the registration challenge signature")?,
+            &ProtocolOpCert::from_json_hex(operational_certificate)
+                .with_context(|| "Could not decode the signer operational certificate")?,
+            message.kes_period.unwrap_or_default(),
+        )?;
+
+        // The challenge is used only once the registration is authenticated, so that a forged
+        // registration can not invalidate the challenge of a signer, and the used challenges
+        // are only kept until they expire.
+        let mut used_challenges = self.used_challenges.lock().unwrap();
+        used_challenges.retain(|_, used_challenge_expires_at| *used_challenge_expires_at > now);
+        if used_challenges
+            .insert(challenge.clone(), expires_at)
+            .is_some()
+        {
+            // A concurrent registration used the challenge meanwhile
+            return Err(anyhow!(
+                "Already used registration challenge '{challenge}' for party id '{}'",
+                message.party_id
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl RegistrationChallengeService for MithrilRegistrationChallengeService {
+    fn issue_challenge(
+        &self,
+        party_id: Option<PartyId>,
+    ) -> StdResult<RegistrationChallengeMessage> {
+        self.issue_challenge_at(party_id.as_ref(), Utc::now())
+    }
+
+    fn authenticate_registration(&self, message: &RegisterSignerMessage) -> StdResult<()> {
+        self.authenticate_registration_at(message, Utc::now())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mithril_common::crypto_helper::RegistrationChallengeSigner;
+    use mithril_common::entities::Epoch;
+    use mithril_common::test_utils::MithrilFixtureBuilder;
+
+    use super::*;
+
+    fn registration_message(challenge: Option<&str>) -> RegisterSignerMessage {
+        let fixture = MithrilFixtureBuilder::default().with_signers(1).build();
+        let signer = &fixture.signers_fixture()[0];
+        let signer_with_stake = &signer.signer_with_stake;
+        let kes_period = signer_with_stake.kes_period;
+
+        RegisterSignerMessage {
+            registration_challenge: challenge.map(|challenge| challenge.to_string()),
+            registration_challenge_signature: challenge.map(|challenge| {
+                RegistrationChallengeSigner::new(signer.kes_secret_key_path().unwrap())
+                    .sign(
+                        challenge,
+                        &signer_with_stake.verification_key,
+                        kes_period.unwrap_or_default(),
+                    )
+                    .unwrap()
+                    .to_json_hex()
+                    .unwrap()
+            }),
+            ..signer.register_signer_message(Epoch(1))
+        }
+    }
+
+    fn party_id() -> PartyId {
+        registration_message(None).party_id
+    }
+
+    fn build_service() -> MithrilRegistrationChallengeService {
+        MithrilRegistrationChallengeService::new(std::time::Duration::from_secs(600), true)
+    }
+
+    #[test]
+    fn authenticate_a_registration_answering_an_issued_challenge() {
+        let service = build_service();
+        let challenge = service.issue_challenge(Some(party_id())).unwrap();
+
+        service
+            .authenticate_registration(&registration_message(Some(&challenge.challenge)))
+            .unwrap();
+    }
+
+    #[test]
+    fn authenticate_a_registration_answering_a_challenge_issued_without_party_id() {
+        let service = build_service();
+        let challenge = service.issue_challenge(None).unwrap();
+
+        service
+            .authenticate_registration(&registration_message(Some(&challenge.challenge)))
+            .unwrap();
+    }
+
+    #[test]
+    fn reject_a_registration_answering_a_challenge_not_issued_by_the_service() {
+        let service = build_service();
+        let challenge = build_service().issue_challenge(Some(party_id())).unwrap();
+
+        for forged_challenge in ["forged-challenge", "a.b.c", &challenge.challenge] {
+            service
+                .authenticate_registration(&registration_message(Some(forged_challenge)))
+                .expect_err("A challenge not issued by the service should be rejected");
+        }
+    }
+
+    #[test]
+    fn reject_a_registration_answering_a_challenge_with_a_tampered_expiry() {
+        let service = build_service();
+        let now = Utc::now();
+        let challenge = service
+            .issue_challenge_at(Some(&party_id()), now)
+            .unwrap()
+            .challenge;
+        let [nonce, expires_at, tag] = challenge.split('.').collect::<Vec<_>>()[..] else {
+            panic!("Unexpected challenge format: {challenge}");
+        };
+        let tampered_challenge = format!(
+            "{nonce}.{}.{tag}",
+            expires_at.parse::<i64>().unwrap() + 3600
+        );
+
+        service
+            .authenticate_registration_at(&registration_message(Some(&tampered_challenge)), now)
+            .expect_err("A challenge with a tampered expiry should be rejected");
+    }
+
+    #[test]
+    fn reject_a_registration_answering_a_challenge_issued_for_another_party_id() {
+        let service = build_service();
+        let challenge = service
+            .issue_challenge(Some("another-party-id".to_string()))
+            .unwrap();
+
+        service
+            .authenticate_registration(&registration_message(Some(&challenge.challenge)))
+            .expect_err("A challenge issued for another party id should be rejected");
+    }
+
+    #[test]
+    fn reject_a_registration_answering_an_already_used_challenge() {
+        let service = build_service();
+        let challenge = service.issue_challenge(Some(party_id())).unwrap();
+        let message = registration_message(Some(&challenge.challenge));
+
+        service.authenticate_registration(&message).unwrap();
+        service
+            .authenticate_registration(&message)
+            .expect_err("An already used challenge should be rejected");
+    }
+
+    #[test]
+    fn reject_a_registration_answering_an_expired_challenge() {
+        let service = build_service();
+        let now = Utc::now();
+        let challenge = service.issue_challenge_at(Some(&party_id()), now).unwrap();
+        let message = registration_message(Some(&challenge.challenge));
+
+        service
+            .authenticate_registration_at(&message, now + Duration::seconds(600))
+            .expect_err("An expired challenge should be rejected");
+        service
+            .authenticate_registration_at(&message, now + Duration::seconds(599))
+            .unwrap();
+    }
+
+    #[test]
+    fn reject_a_registration_with_a_challenge_signature_not_matching_the_verification_key() {
+        let service = build_service();
+        let challenge = service.issue_challenge(Some(party_id())).unwrap();
+        let mut message = registration_message(Some(&challenge.challenge));
+        message.registration_challenge_signature =
+            registration_message(Some("another-challenge")).registration_challenge_signature;
+
+        service
+            .authenticate_registration(&message)
+            .expect_err("An invalid challenge signature should be rejected");
+    }
+
+    #[test]
+    fn a_rejected_registration_does_not_use_the_challenge() {
+        let service = build_service();
+        let challenge = service.issue_challenge(Some(party_id())).unwrap();
+        let message = registration_message(Some(&challenge.challenge));
+        let mut forged_message = message.clone();
+        forged_message.registration_challenge_signature =
+            registration_message(Some("another-challenge")).registration_challenge_signature;
+
+        service
+            .authenticate_registration(&forged_message)
+            .expect_err("An invalid challenge signature should be rejected");
+        service.authenticate_registration(&message).unwrap();
+    }
+
+    #[test]
+    fn issuing_challenges_does_not_keep_any_state() {
+        let service = build_service();
+
+        for i in 0..1_000 {
+            service
+                .issue_challenge(Some(format!("party-id-{i}")))
+                .unwrap();
+        }
+
+        assert!(service.used_challenges.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn forget_the_used_challenges_once_they_expire() {
+        let service = build_service();
+        let now = Utc::now();
+        let challenge = service.issue_challenge_at(Some(&party_id()), now).unwrap();
+        service
+            .authenticate_registration_at(&registration_message(Some(&challenge.challenge)), now)
+            .unwrap();
+        let other_challenge = service
+            .issue_challenge_at(Some(&party_id()), now + Duration::seconds(600))
+            .unwrap();
+
+        service
+            .authenticate_registration_at(
+                &registration_message(Some(&other_challenge.challenge)),
+                now + Duration::seconds(600),
+            )
+            .unwrap();
+
+        assert_eq!(
+            vec![other_challenge.challenge],
+            service
+                .used_challenges
+                .lock()
+                .unwrap()
+                .keys()
+                .cloned()
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn accept_a_registration_without_challenge_only_if_the_challenge_is_not_required() {
+        let message = registration_message(None);
+
+        MithrilRegistrationChallengeService::new(std::time::Duration::from_secs(600), false)
+            .authenticate_registration(&message)
+            .unwrap();
+        MithrilRegistrationChallengeService::new(std::time::Duration::from_secs(600), true)
+            .authenticate_registration(&message)
+            .expect_err("A registration without challenge should be rejected");
+    }
+}
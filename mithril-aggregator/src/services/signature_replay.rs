@@ -0,0 +1,127 @@
+//! ## Signature replay
+//!
+//! Remember the single signatures already registered so that the identical submissions of the
+//! retrying signers are rejected without being verified again.
+
+use std::collections::{HashSet, VecDeque};
+use std::sync::Mutex;
+
+use mithril_common::entities::{PartyId, SignedEntityType};
+
+/// Maximum number of registered signatures remembered, the oldest are forgotten first.
+const MAX_REGISTERED_SIGNATURES: usize = 100_000;
+
+/// Detect the single signatures already registered for a signed entity type.
+#[cfg_attr(test, mockall::automock)]
+pub trait SignatureReplayDetector: Sync + Send {
+    /// Check if a signature of the party was already registered for the signed entity type.
+    fn is_already_registered(
+        &self,
+        party_id: &PartyId,
+        signed_entity_type: &SignedEntityType,
+    ) -> bool;
+
+    /// Remember that a signature of the party was registered for the signed entity type.
+    fn record_registration(&self, party_id: &PartyId, signed_entity_type: &SignedEntityType);
+}
+
+type SignatureKey = (PartyId, SignedEntityType);
+
+/// Signature replay detector keeping the registered signatures in memory.
+pub struct MithrilSignatureReplayDetector {
+    capacity: usize,
+    registered_signatures: Mutex<RegisteredSignatures>,
+}
+
+#[derive(Default)]
+struct RegisteredSignatures {
+    keys: HashSet<SignatureKey>,
+    registration_order: VecDeque<SignatureKey>,
+}
+
+impl MithrilSignatureReplayDetector {
+    /// MithrilSignatureReplayDetector factory
+    pub fn new() -> Self {
+        Self::with_capacity(MAX_REGISTERED_SIGNATURES)
+    }
+
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            capacity,
+            registered_signatures: Mutex::new(RegisteredSignatures::default()),
+        }
+    }
+}
+
+impl Default for MithrilSignatureReplayDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SignatureReplayDetector for MithrilSignatureReplayDetector {
+    fn is_already_registered(
+        &self,
+        party_id: &PartyId,
+        signed_entity_type: &SignedEntityType,
+    ) -> bool {
+        self.registered_signatures
+            .lock()
+            .unwrap()
+            .keys
+            .contains(&(party_id.to_owned(), signed_entity_type.to_owned()))
+    }
+
+    fn record_registration(&self, party_id: &PartyId, signed_entity_type: &SignedEntityType) {
+        let key = (party_id.to_owned(), signed_entity_type.to_owned());
+        let mut registered_signatures = self.registered_signatures.lock().unwrap();
+        if !registered_signatures.keys.insert(key.clone()) {
+            return;
+        }
+        registered_signatures.registration_order.push_back(key);
+        if registered_signatures.registration_order.len() > self.capacity {
+            if let Some(oldest_key) = registered_signatures.registration_order.pop_front() {
+                registered_signatures.keys.remove(&oldest_key);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mithril_common::entities::Epoch;
+
+    use super::*;
+
+    #[test]
+    fn detect_the_signatures_already_registered_by_a_party_for_a_signed_entity_type() {
+        let detector = MithrilSignatureReplayDetector::new();
+        let party_id = "party-1".to_string();
+        let signed_entity_type = SignedEntityType::MithrilStakeDistribution(Epoch(5));
+
+        assert!(!detector.is_already_registered(&party_id, &signed_entity_type));
+
+        detector.record_registration(&party_id, &signed_entity_type);
+
+        assert!(detector.is_already_registered(&party_id, &signed_entity_type));
+        assert!(!detector.is_already_registered(&"party-2".to_string(), &signed_entity_type));
+        assert!(!detector.is_already_registered(
+            &party_id,
+            &SignedEntityType::MithrilStakeDistribution(Epoch(6))
+        ));
+    }
+
+    #[test]
+    fn forget_the_oldest_signatures_when_the_capacity_is_reached() {
+        let detector = MithrilSignatureReplayDetector::with_capacity(2);
+        let signed_entity_type = SignedEntityType::MithrilStakeDistribution(Epoch(5));
+
+        for party_id in ["party-1", "party-2", "party-3"] {
+            detector.record_registration(&party_id.to_string(), &signed_entity_type);
+        }
+
+        assert!(!detector.is_already_registered(&"party-1".to_string(), &signed_entity_type));
+        assert!(detector.is_already_registered(&"party-2".to_string(), &signed_entity_type));
+        assert!(detector.is_already_registered(&"party-3".to_string(), &signed_entity_type));
+    }
+}
@@ -5,9 +5,10 @@
 use anyhow::{anyhow, Context};
 use async_trait::async_trait;
 use chrono::Utc;
-use slog::{info, Logger};
+use slog::{info, warn, Logger};
 use std::sync::Arc;
 use tokio::task::JoinHandle;
+use tracing::Instrument;
 
 use mithril_common::{
     entities::{
@@ -24,6 +25,7 @@ use mithril_common::{
 use crate::{
     artifact_builder::ArtifactBuilder,
     database::{record::SignedEntityRecord, repository::SignedEntityStorer},
+    services::StaticMirrorExporter,
 };
 
 /// ArtifactBuilder Service trait
@@ -88,6 +90,7 @@ pub struct MithrilSignedEntityService {
     signed_entity_type_lock: Arc<SignedEntityTypeLock>,
     cardano_stake_distribution_artifact_builder:
         Arc<dyn ArtifactBuilder<Epoch, CardanoStakeDistribution>>,
+    static_mirror_exporter: Arc<dyn StaticMirrorExporter>,
     logger: Logger,
 }
 
@@ -108,6 +111,7 @@ impl MithrilSignedEntityService {
         cardano_stake_distribution_artifact_builder: Arc<
             dyn ArtifactBuilder<Epoch, CardanoStakeDistribution>,
         >,
+        static_mirror_exporter: Arc<dyn StaticMirrorExporter>,
         logger: Logger,
     ) -> Self {
         Self {
@@ -117,10 +121,12 @@ impl MithrilSignedEntityService {
             cardano_transactions_artifact_builder,
             signed_entity_type_lock,
             cardano_stake_distribution_artifact_builder,
+            static_mirror_exporter,
             logger: logger.new_with_component_name::<Self>(),
         }
     }
 
+    #[tracing::instrument(skip_all, fields(signed_entity_type = %signed_entity_type, certificate_hash = %certificate.hash))]
     async fn create_artifact_task(
         &self,
         signed_entity_type: SignedEntityType,
@@ -161,10 +167,25 @@ impl MithrilSignedEntityService {
                     "Signed Entity Service can not store signed entity with type: '{signed_entity_type}'"
                 )
             })?;
+
+        // The export runs in the background so it doesn't delay the release of the signed entity
+        // type lock, the artifact is already available from the aggregator if it fails.
+        let static_mirror_exporter = self.static_mirror_exporter.clone();
+        let logger = self.logger.clone();
+        tokio::spawn(
+            async move {
+                if let Err(error) = static_mirror_exporter.export(&signed_entity_type).await {
+                    warn!(logger, "Could not export the static mirror"; "error" => ?error);
+                }
+            }
+            .in_current_span(),
+        );
+
         Ok(())
     }
 
     /// Compute artifact from signed entity type
+    #[tracing::instrument(skip_all, fields(signed_entity_type = %signed_entity_type))]
     async fn compute_artifact(
         &self,
         signed_entity_type: SignedEntityType,
@@ -232,6 +253,7 @@ impl MithrilSignedEntityService {
 
 #[async_trait]
 impl SignedEntityService for MithrilSignedEntityService {
+    #[tracing::instrument(skip_all, fields(signed_entity_type = %signed_entity_type, certificate_hash = %certificate.hash))]
     async fn create_artifact(
         &self,
         signed_entity_type: SignedEntityType,
@@ -258,11 +280,14 @@ impl SignedEntityService for MithrilSignedEntityService {
         Ok(tokio::task::spawn(async move {
             let signed_entity_type_clone = signed_entity_type.clone();
             let service_clone = service.clone();
-            let result = tokio::task::spawn(async move {
-                service_clone
-                    .create_artifact_task(signed_entity_type_clone, &certificate_cloned)
-                    .await
-            })
+            let result = tokio::task::spawn(
+                async move {
+                    service_clone
+                        .create_artifact_task(signed_entity_type_clone, &certificate_cloned)
+                        .await
+                }
+                .in_current_span(),
+            )
             .await;
             service
                 .signed_entity_type_lock
@@ -272,7 +297,7 @@ impl SignedEntityService for MithrilSignedEntityService {
             result.with_context(|| format!(
                 "Signed Entity Service can not store signed entity with type: '{signed_entity_type}'"
             ))?
-        }))
+        }.in_current_span()))
     }
 
     async fn get_last_signed_snapshots(
@@ -400,6 +425,7 @@ mod tests {
 
     use crate::artifact_builder::MockArtifactBuilder;
     use crate::database::repository::MockSignedEntityStorer;
+    use crate::services::{DumbStaticMirrorExporter, MockStaticMirrorExporter};
     use crate::test_tools::TestLogger;
 
     use super::*;
@@ -441,6 +467,7 @@ mod tests {
             MockArtifactBuilder<BlockNumber, CardanoTransactionsSnapshot>,
         mock_cardano_stake_distribution_artifact_builder:
             MockArtifactBuilder<Epoch, CardanoStakeDistribution>,
+        static_mirror_exporter: Arc<dyn StaticMirrorExporter>,
     }
 
     impl MockDependencyInjector {
@@ -463,6 +490,7 @@ mod tests {
                     Epoch,
                     CardanoStakeDistribution,
                 >::new(),
+                static_mirror_exporter: Arc::new(DumbStaticMirrorExporter::new()),
             }
         }
 
@@ -474,6 +502,7 @@ mod tests {
                 Arc::new(self.mock_cardano_transactions_artifact_builder),
                 Arc::new(SignedEntityTypeLock::default()),
                 Arc::new(self.mock_cardano_stake_distribution_artifact_builder),
+                self.static_mirror_exporter,
                 TestLogger::stdout(),
             )
         }
@@ -526,6 +555,7 @@ mod tests {
                 Arc::new(self.mock_cardano_transactions_artifact_builder),
                 Arc::new(SignedEntityTypeLock::default()),
                 Arc::new(self.mock_cardano_stake_distribution_artifact_builder),
+                self.static_mirror_exporter,
                 TestLogger::stdout(),
             )
         }
@@ -763,6 +793,50 @@ mod tests {
             .expect(error_message_str);
     }
 
+    #[tokio::test]
+    async fn export_the_static_mirror_once_the_artifact_is_stored_without_failing_on_error() {
+        let mut mock_container = MockDependencyInjector::new();
+        mock_container
+            .mock_signed_entity_storer
+            .expect_store_signed_entity()
+            .return_once(|_| Ok(()));
+        mock_container
+            .mock_mithril_stake_distribution_artifact_builder
+            .expect_compute_artifact()
+            .return_once(|_, _| Ok(create_stake_distribution(Epoch(1), 5)));
+        let (export_sender, mut export_receiver) = tokio::sync::mpsc::unbounded_channel();
+        let mut mock_static_mirror_exporter = MockStaticMirrorExporter::new();
+        mock_static_mirror_exporter
+            .expect_export()
+            .withf(|signed_entity_type| {
+                signed_entity_type == &SignedEntityType::MithrilStakeDistribution(Epoch(1))
+            })
+            .once()
+            .returning(move |signed_entity_type| {
+                export_sender.send(signed_entity_type.clone()).unwrap();
+                Err(anyhow!("mirror unavailable"))
+            });
+        mock_container.static_mirror_exporter = Arc::new(mock_static_mirror_exporter);
+        let artifact_builder_service = mock_container.build_artifact_builder_service();
+
+        artifact_builder_service
+            .create_artifact_task(
+                SignedEntityType::MithrilStakeDistribution(Epoch(1)),
+                &fake_data::certificate("hash".to_string()),
+            )
+            .await
+            .expect("A static mirror failure should not fail the artifact creation");
+
+        let exported_signed_entity_type =
+            tokio::time::timeout(std::time::Duration::from_secs(1), export_receiver.recv())
+                .await
+                .expect("The static mirror should have been exported");
+        assert_eq!(
+            Some(SignedEntityType::MithrilStakeDistribution(Epoch(1))),
+            exported_signed_entity_type
+        );
+    }
+
     #[tokio::test]
     async fn create_artifact_for_two_signed_entity_types_in_sequence_not_blocking() {
         let atomic_stop = Arc::new(AtomicBool::new(false));
@@ -9,7 +9,7 @@ use std::{
 use tokio::sync::{Mutex, MutexGuard};
 
 use mithril_common::{
-    chain_observer::ChainObserver,
+    chain_observer::{ChainObserver, StakeDistributionSource},
     entities::{Epoch, StakeDistribution},
     StdError, StdResult,
 };
@@ -144,16 +144,23 @@ pub struct MithrilStakeDistributionService {
     stake_store: Arc<StakePoolStore>,
     /// Chain interaction subsystem
     chain_observer: Arc<dyn ChainObserver>,
+    /// Source of the stake distribution
+    stake_distribution_source: Arc<dyn StakeDistributionSource>,
     /// Lock management for updates
     update_token: UpdateToken,
 }
 
 impl MithrilStakeDistributionService {
     /// Create a new service instance
-    pub fn new(stake_store: Arc<StakePoolStore>, chain_observer: Arc<dyn ChainObserver>) -> Self {
+    pub fn new(
+        stake_store: Arc<StakePoolStore>,
+        chain_observer: Arc<dyn ChainObserver>,
+        stake_distribution_source: Arc<dyn StakeDistributionSource>,
+    ) -> Self {
         Self {
             stake_store,
             chain_observer,
+            stake_distribution_source,
             update_token: UpdateToken::default(),
         }
     }
@@ -206,11 +213,10 @@ impl StakeDistributionService for MithrilStakeDistributionService {
             .update(current_epoch)
             .map_err(StakePoolDistributionServiceError::technical_subsystem)?;
         let stake_distribution = self
-            .chain_observer
-            .get_current_stake_distribution()
+            .stake_distribution_source
+            .retrieve()
             .await
-            .map_err(|e| StakePoolDistributionServiceError::technical_subsystem(e.into()))?
-            .expect("ChainObserver get_current_stake_distribution should never return None.");
+            .map_err(StakePoolDistributionServiceError::technical_subsystem)?;
 
         let _ = self
             .stake_store
@@ -224,17 +230,32 @@ impl StakeDistributionService for MithrilStakeDistributionService {
 
 #[cfg(test)]
 mod tests {
+    use mithril_common::chain_observer::ChainObserverStakeDistributionSource;
+
     use crate::dependency_injection::DependenciesBuilder;
-    use crate::tools::mocks::MockChainObserver;
+    use crate::tools::mocks::{MockChainObserver, MockStakeDistributionSource};
 
     use super::*;
 
     async fn get_service(chain_observer: MockChainObserver) -> MithrilStakeDistributionService {
+        let chain_observer = Arc::new(chain_observer);
+        get_service_with_source(
+            chain_observer.clone(),
+            Arc::new(ChainObserverStakeDistributionSource::new(chain_observer)),
+        )
+        .await
+    }
+
+    async fn get_service_with_source(
+        chain_observer: Arc<MockChainObserver>,
+        stake_distribution_source: Arc<dyn StakeDistributionSource>,
+    ) -> MithrilStakeDistributionService {
         let mut builder =
             DependenciesBuilder::new_with_stdout_logger(crate::Configuration::new_sample());
         let stake_service = MithrilStakeDistributionService::new(
             builder.get_stake_store().await.unwrap(),
-            Arc::new(chain_observer),
+            chain_observer,
+            stake_distribution_source,
         );
 
         let store = builder.get_stake_store().await.unwrap();
@@ -323,6 +344,36 @@ mod tests {
         assert_eq!(expected_stake_distribution, sd);
     }
 
+    #[tokio::test]
+    async fn update_stake_distribution_from_a_custom_source() {
+        let expected_stake_distribution = StakeDistribution::from_iter(
+            [("party1", 10), ("party2", 30)]
+                .into_iter()
+                .map(|(p, s)| (p.to_string(), s as u64)),
+        );
+        let returned_stake_distribution = expected_stake_distribution.clone();
+        let mut chain_observer = MockChainObserver::new();
+        chain_observer
+            .expect_get_current_epoch()
+            .returning(|| Ok(Some(Epoch(3))));
+        chain_observer
+            .expect_get_current_stake_distribution()
+            .never();
+        let mut stake_distribution_source = MockStakeDistributionSource::new();
+        stake_distribution_source
+            .expect_retrieve()
+            .return_once(|| Ok(returned_stake_distribution));
+        let service = get_service_with_source(
+            Arc::new(chain_observer),
+            Arc::new(stake_distribution_source),
+        )
+        .await;
+        service.update_stake_distribution().await.unwrap();
+        let sd = service.get_stake_distribution(Epoch(4)).await.unwrap();
+
+        assert_eq!(expected_stake_distribution, sd);
+    }
+
     #[tokio::test]
     async fn update_stake_distribution_already() {
         let mut chain_observer = MockChainObserver::new();
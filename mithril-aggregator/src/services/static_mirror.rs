@@ -0,0 +1,680 @@
+//! ## Static Mirror
+//!
+//! This service exports the artifacts of the aggregator to a static-file layout that can be
+//! served as-is by a dumb CDN or an S3 website, enabling read-only mirrors of the aggregator
+//! without running the full service.
+//!
+//! Each `GET` route of the API is exported to a `<route>.json` file (i.e. `artifact/snapshots`
+//! is exported to `artifact/snapshots.json`), and the Cardano database archives stored by the
+//! local snapshot uploader are copied to `snapshot_download/<archive>`. The whole certificate
+//! chain is exported so the clients can verify the artifacts served by the mirror.
+//!
+//! Certificates and artifacts are immutable: only the ones that are not in the mirror yet are
+//! written, along with the list of the type of the artifact being exported.
+
+use anyhow::{anyhow, Context};
+use async_trait::async_trait;
+use serde::Serialize;
+use slog::{debug, Logger};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use mithril_common::entities::{CardanoDbBeacon, CompressionAlgorithm, SignedEntityType};
+use mithril_common::logging::LoggerExtensions;
+use mithril_common::StdResult;
+
+use crate::services::MessageService;
+
+/// Number of items of the exported lists, the same as the lists served by the aggregator.
+const LIST_MAX_ITEMS: usize = 20;
+
+/// Exporter of the artifacts to a static mirror
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait StaticMirrorExporter: Sync + Send {
+    /// Export the latest artifacts of the given signed entity type and their certificate chain
+    /// to the mirror.
+    async fn export(&self, signed_entity_type: &SignedEntityType) -> StdResult<()>;
+}
+
+/// [StaticMirrorExporter] that writes the mirror layout to a local directory
+pub struct FileStaticMirrorExporter {
+    target_directory: PathBuf,
+    snapshot_directory: PathBuf,
+    mirror_url: Option<String>,
+    message_service: Arc<dyn MessageService>,
+    export_lock: Mutex<()>,
+    logger: Logger,
+}
+
+impl FileStaticMirrorExporter {
+    /// Create a new [FileStaticMirrorExporter].
+    ///
+    /// If the public `mirror_url` is set, the location of the archives copied to the mirror is
+    /// added first to the locations of the exported snapshots.
+    pub fn new(
+        target_directory: &Path,
+        snapshot_directory: &Path,
+        mirror_url: Option<String>,
+        message_service: Arc<dyn MessageService>,
+        logger: Logger,
+    ) -> Self {
+        Self {
+            target_directory: target_directory.to_path_buf(),
+            snapshot_directory: snapshot_directory.to_path_buf(),
+            mirror_url: mirror_url.map(|url| url.trim_end_matches('/').to_string()),
+            message_service,
+            export_lock: Mutex::new(()),
+            logger: logger.new_with_component_name::<Self>(),
+        }
+    }
+
+    fn route_path(&self, route: &str) -> PathBuf {
+        self.target_directory.join(format!("{route}.json"))
+    }
+
+    /// Write the file atomically so a mirror being served never exposes a partial file.
+    async fn write_file(&self, path: &Path, content: &[u8]) -> StdResult<()> {
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .with_context(|| format!("Can not create directory: '{}'", parent.display()))?;
+        }
+        let temporary_path = PathBuf::from(format!("{}.tmp", path.display()));
+        tokio::fs::write(&temporary_path, content)
+            .await
+            .with_context(|| format!("Can not write file: '{}'", temporary_path.display()))?;
+        tokio::fs::rename(&temporary_path, path)
+            .await
+            .with_context(|| format!("Can not move file to: '{}'", path.display()))?;
+
+        Ok(())
+    }
+
+    async fn write_route<T: Serialize + Sync>(&self, route: &str, message: &T) -> StdResult<()> {
+        let content = serde_json::to_vec(message)
+            .with_context(|| format!("Can not serialize the message of route: '{route}'"))?;
+        self.write_file(&self.route_path(route), &content).await
+    }
+
+    fn is_route_exported(&self, route: &str) -> bool {
+        self.route_path(route).exists()
+    }
+
+    /// Link the archive to the mirror, or copy it if the mirror is on another file system.
+    ///
+    /// The copy is made to a temporary file first so a mirror being served never exposes a
+    /// partial archive.
+    async fn copy_archive(&self, source: &Path, target: &Path) -> StdResult<()> {
+        if let Some(parent) = target.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .with_context(|| format!("Can not create directory: '{}'", parent.display()))?;
+        }
+        if tokio::fs::hard_link(source, target).await.is_ok() {
+            return Ok(());
+        }
+
+        let temporary_path = PathBuf::from(format!("{}.tmp", target.display()));
+        tokio::fs::copy(source, &temporary_path)
+            .await
+            .with_context(|| {
+                format!(
+                    "Can not copy archive: '{}' to: '{}'",
+                    source.display(),
+                    temporary_path.display()
+                )
+            })?;
+        tokio::fs::rename(&temporary_path, target)
+            .await
+            .with_context(|| format!("Can not move archive to: '{}'", target.display()))?;
+
+        Ok(())
+    }
+
+    /// Copy the archive of a snapshot to the mirror if it's stored locally.
+    ///
+    /// Return the location of the archive in the mirror if it's available and the mirror url is set.
+    async fn mirror_archive(
+        &self,
+        digest: &str,
+        beacon: &CardanoDbBeacon,
+        compression_algorithm: Option<CompressionAlgorithm>,
+    ) -> StdResult<Option<String>> {
+        let filename = format!(
+            "{}-e{}-i{}.{}.{}",
+            beacon.network,
+            beacon.epoch,
+            beacon.immutable_file_number,
+            digest,
+            compression_algorithm
+                .unwrap_or_default()
+                .tar_file_extension()
+        );
+        let source = self.snapshot_directory.join(&filename);
+        let target = self
+            .target_directory
+            .join("snapshot_download")
+            .join(&filename);
+
+        if !target.exists() {
+            if !source.exists() {
+                return Ok(None);
+            }
+            debug!(self.logger, "Copying snapshot archive to the mirror"; "archive" => &filename);
+            self.copy_archive(&source, &target).await?;
+        }
+
+        Ok(self
+            .mirror_url
+            .as_ref()
+            .map(|url| format!("{url}/snapshot_download/{filename}")))
+    }
+
+    async fn export_certificates(&self) -> StdResult<()> {
+        let certificates = self
+            .message_service
+            .get_certificate_list_message(LIST_MAX_ITEMS)
+            .await?;
+
+        for item in &certificates {
+            // Certificates are immutable: the chain is walked back until an already exported
+            // certificate, or the genesis certificate, is reached.
+            let mut hash = item.hash.clone();
+            while !hash.is_empty() {
+                let route = format!("certificate/{hash}");
+                if self.is_route_exported(&route) {
+                    break;
+                }
+                let certificate = self
+                    .message_service
+                    .get_certificate_message(&hash)
+                    .await?
+                    .ok_or_else(|| anyhow!("Certificate not found: '{hash}'"))?;
+                self.write_route(&route, &certificate).await?;
+                hash = certificate.previous_hash;
+            }
+        }
+        self.write_route("certificates", &certificates).await
+    }
+
+    async fn export_snapshots(&self) -> StdResult<()> {
+        let mut snapshots = self
+            .message_service
+            .get_snapshot_list_message(LIST_MAX_ITEMS)
+            .await?;
+
+        for item in snapshots.iter_mut() {
+            let mirror_location = self
+                .mirror_archive(&item.digest, &item.beacon, item.compression_algorithm)
+                .await?;
+            if let Some(location) = &mirror_location {
+                item.locations.insert(0, location.clone());
+            }
+
+            let route = format!("artifact/snapshot/{}", item.digest);
+            if self.is_route_exported(&route) {
+                continue;
+            }
+            let mut snapshot = self
+                .message_service
+                .get_snapshot_message(&item.digest)
+                .await?
+                .ok_or_else(|| anyhow!("Snapshot not found: '{}'", item.digest))?;
+            if let Some(location) = mirror_location {
+                snapshot.locations.insert(0, location);
+            }
+            self.write_route(&route, &snapshot).await?;
+        }
+        self.write_route("artifact/snapshots", &snapshots).await
+    }
+
+    async fn export_mithril_stake_distributions(&self) -> StdResult<()> {
+        let mithril_stake_distributions = self
+            .message_service
+            .get_mithril_stake_distribution_list_message(LIST_MAX_ITEMS)
+            .await?;
+
+        for item in &mithril_stake_distributions {
+            let route = format!("artifact/mithril-stake-distribution/{}", item.hash);
+            if self.is_route_exported(&route) {
+                continue;
+            }
+            let mithril_stake_distribution = self
+                .message_service
+                .get_mithril_stake_distribution_message(&item.hash)
+                .await?
+                .ok_or_else(|| anyhow!("Mithril stake distribution not found: '{}'", item.hash))?;
+            self.write_route(&route, &mithril_stake_distribution)
+                .await?;
+        }
+        self.write_route(
+            "artifact/mithril-stake-distributions",
+            &mithril_stake_distributions,
+        )
+        .await
+    }
+
+    async fn export_cardano_transactions(&self) -> StdResult<()> {
+        let cardano_transactions = self
+            .message_service
+            .get_cardano_transaction_list_message(LIST_MAX_ITEMS)
+            .await?;
+
+        for item in &cardano_transactions {
+            let route = format!("artifact/cardano-transaction/{}", item.hash);
+            if self.is_route_exported(&route) {
+                continue;
+            }
+            let cardano_transaction = self
+                .message_service
+                .get_cardano_transaction_message(&item.hash)
+                .await?
+                .ok_or_else(|| anyhow!("Cardano transactions not found: '{}'", item.hash))?;
+            self.write_route(&route, &cardano_transaction).await?;
+        }
+        self.write_route("artifact/cardano-transactions", &cardano_transactions)
+            .await
+    }
+
+    async fn export_cardano_stake_distributions(&self) -> StdResult<()> {
+        let cardano_stake_distributions = self
+            .message_service
+            .get_cardano_stake_distribution_list_message(LIST_MAX_ITEMS)
+            .await?;
+
+        for item in &cardano_stake_distributions {
+            let route = format!("artifact/cardano-stake-distribution/{}", item.hash);
+            if self.is_route_exported(&route) {
+                continue;
+            }
+            let cardano_stake_distribution = self
+                .message_service
+                .get_cardano_stake_distribution_message(&item.hash)
+                .await?
+                .ok_or_else(|| anyhow!("Cardano stake distribution not found: '{}'", item.hash))?;
+            self.write_route(&route, &cardano_stake_distribution)
+                .await?;
+            self.write_route(
+                &format!("artifact/cardano-stake-distribution/epoch/{}", item.epoch),
+                &cardano_stake_distribution,
+            )
+            .await?;
+        }
+        self.write_route(
+            "artifact/cardano-stake-distributions",
+            &cardano_stake_distributions,
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl StaticMirrorExporter for FileStaticMirrorExporter {
+    async fn export(&self, signed_entity_type: &SignedEntityType) -> StdResult<()> {
+        // Artifacts of different types can be produced concurrently.
+        let _export_guard = self.export_lock.lock().await;
+        debug!(
+            self.logger, "Exporting the static mirror";
+            "signed_entity_type" => ?signed_entity_type, "target_directory" => %self.target_directory.display()
+        );
+
+        // The certificates are exported first so the artifacts of the mirror can always be verified.
+        self.export_certificates()
+            .await
+            .with_context(|| "Static mirror can not export the certificates")?;
+        match signed_entity_type {
+            SignedEntityType::CardanoImmutableFilesFull(_) => self
+                .export_snapshots()
+                .await
+                .with_context(|| "Static mirror can not export the snapshots"),
+            SignedEntityType::MithrilStakeDistribution(_) => self
+                .export_mithril_stake_distributions()
+                .await
+                .with_context(|| "Static mirror can not export the Mithril stake distributions"),
+            SignedEntityType::CardanoTransactions(_, _) => self
+                .export_cardano_transactions()
+                .await
+                .with_context(|| "Static mirror can not export the Cardano transactions"),
+            SignedEntityType::CardanoStakeDistribution(_) => self
+                .export_cardano_stake_distributions()
+                .await
+                .with_context(|| "Static mirror can not export the Cardano stake distributions"),
+        }
+    }
+}
+
+/// [StaticMirrorExporter] that does nothing, used when no static mirror is configured.
+#[derive(Default)]
+pub struct DumbStaticMirrorExporter;
+
+impl DumbStaticMirrorExporter {
+    /// Create a new instance.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl StaticMirrorExporter for DumbStaticMirrorExporter {
+    async fn export(&self, _signed_entity_type: &SignedEntityType) -> StdResult<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mithril_common::entities::Epoch;
+    use mithril_common::messages::{
+        CardanoStakeDistributionListItemMessage, CardanoStakeDistributionMessage,
+        CertificateListItemMessage, CertificateMessage, MithrilStakeDistributionListItemMessage,
+        MithrilStakeDistributionMessage, SnapshotListItemMessage, SnapshotMessage,
+    };
+    use mithril_common::test_utils::TempDir;
+
+    use crate::services::MockMessageService;
+    use crate::test_tools::TestLogger;
+
+    use super::*;
+
+    /// Message service mock that returns empty lists for the routes not expected by the test.
+    struct MessageServiceBuilder {
+        message_service: MockMessageService,
+        certificates: bool,
+        snapshots: bool,
+        cardano_stake_distributions: bool,
+    }
+
+    impl MessageServiceBuilder {
+        fn new() -> Self {
+            Self {
+                message_service: MockMessageService::new(),
+                certificates: false,
+                snapshots: false,
+                cardano_stake_distributions: false,
+            }
+        }
+
+        fn with_certificates(mut self, configure: impl FnOnce(&mut MockMessageService)) -> Self {
+            configure(&mut self.message_service);
+            self.certificates = true;
+            self
+        }
+
+        fn with_snapshots(mut self, configure: impl FnOnce(&mut MockMessageService)) -> Self {
+            configure(&mut self.message_service);
+            self.snapshots = true;
+            self
+        }
+
+        fn with_cardano_stake_distributions(
+            mut self,
+            configure: impl FnOnce(&mut MockMessageService),
+        ) -> Self {
+            configure(&mut self.message_service);
+            self.cardano_stake_distributions = true;
+            self
+        }
+
+        fn build(mut self) -> Arc<dyn MessageService> {
+            if !self.certificates {
+                self.message_service
+                    .expect_get_certificate_list_message()
+                    .returning(|_| Ok(vec![]));
+            }
+            if !self.snapshots {
+                self.message_service
+                    .expect_get_snapshot_list_message()
+                    .returning(|_| Ok(vec![]));
+            }
+            if !self.cardano_stake_distributions {
+                self.message_service
+                    .expect_get_cardano_stake_distribution_list_message()
+                    .returning(|_| Ok(vec![]));
+            }
+            self.message_service
+                .expect_get_mithril_stake_distribution_list_message()
+                .returning(|_| Ok(vec![]));
+            self.message_service
+                .expect_get_cardano_transaction_list_message()
+                .returning(|_| Ok(vec![]));
+
+            Arc::new(self.message_service)
+        }
+    }
+
+    fn certificate(hash: &str, previous_hash: &str) -> CertificateMessage {
+        CertificateMessage {
+            hash: hash.to_string(),
+            previous_hash: previous_hash.to_string(),
+            ..CertificateMessage::dummy()
+        }
+    }
+
+    fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> T {
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn export_the_whole_certificate_chain_only_once() {
+        let target_directory = TempDir::create("static_mirror", "export_certificate_chain");
+        let message_service = MessageServiceBuilder::new()
+            .with_certificates(|message_service| {
+                message_service
+                    .expect_get_certificate_list_message()
+                    .returning(|_| {
+                        Ok(vec![CertificateListItemMessage {
+                            hash: "certificate-2".to_string(),
+                            previous_hash: "genesis".to_string(),
+                            ..CertificateListItemMessage::dummy()
+                        }])
+                    });
+                message_service
+                    .expect_get_certificate_message()
+                    .withf(|hash| hash == "certificate-2")
+                    .times(1)
+                    .returning(|_| Ok(Some(certificate("certificate-2", "genesis"))));
+                message_service
+                    .expect_get_certificate_message()
+                    .withf(|hash| hash == "genesis")
+                    .times(1)
+                    .returning(|_| Ok(Some(certificate("genesis", ""))));
+            })
+            .build();
+        let exporter = FileStaticMirrorExporter::new(
+            &target_directory,
+            &target_directory,
+            None,
+            message_service,
+            TestLogger::stdout(),
+        );
+
+        exporter.export(&SignedEntityType::dummy()).await.unwrap();
+        // Already exported certificates are not fetched again
+        exporter.export(&SignedEntityType::dummy()).await.unwrap();
+
+        let certificates: Vec<CertificateListItemMessage> =
+            read_json(&target_directory.join("certificates.json"));
+        assert_eq!(1, certificates.len());
+        let genesis: CertificateMessage =
+            read_json(&target_directory.join("certificate/genesis.json"));
+        assert_eq!(certificate("genesis", ""), genesis);
+        assert!(target_directory
+            .join("certificate/certificate-2.json")
+            .exists());
+    }
+
+    #[tokio::test]
+    async fn export_snapshots_with_their_locally_stored_archive() {
+        let target_directory = TempDir::create("static_mirror", "export_snapshots_mirror");
+        let snapshot_directory = TempDir::create("static_mirror", "export_snapshots_archives");
+        let snapshot = SnapshotMessage {
+            locations: vec!["https://aggregator/snapshot.tar.gz".to_string()],
+            compression_algorithm: Some(CompressionAlgorithm::Gzip),
+            ..SnapshotMessage::dummy()
+        };
+        let archive_name = format!(
+            "{}-e{}-i{}.{}.tar.gz",
+            snapshot.beacon.network,
+            snapshot.beacon.epoch,
+            snapshot.beacon.immutable_file_number,
+            snapshot.digest
+        );
+        std::fs::write(snapshot_directory.join(&archive_name), "archive").unwrap();
+        let signed_entity_type =
+            SignedEntityType::CardanoImmutableFilesFull(snapshot.beacon.clone());
+        let snapshot_list_item = SnapshotListItemMessage {
+            digest: snapshot.digest.clone(),
+            beacon: snapshot.beacon.clone(),
+            locations: snapshot.locations.clone(),
+            compression_algorithm: snapshot.compression_algorithm,
+            ..SnapshotListItemMessage::dummy()
+        };
+        let message_service = MessageServiceBuilder::new()
+            .with_snapshots(|message_service| {
+                message_service
+                    .expect_get_snapshot_list_message()
+                    .return_once(move |_| Ok(vec![snapshot_list_item]));
+                message_service
+                    .expect_get_snapshot_message()
+                    .return_once(move |_| Ok(Some(snapshot)));
+            })
+            .build();
+        let exporter = FileStaticMirrorExporter::new(
+            &target_directory,
+            &snapshot_directory,
+            Some("https://mirror.example/".to_string()),
+            message_service,
+            TestLogger::stdout(),
+        );
+
+        exporter.export(&signed_entity_type).await.unwrap();
+
+        let expected_locations = vec![
+            format!("https://mirror.example/snapshot_download/{archive_name}"),
+            "https://aggregator/snapshot.tar.gz".to_string(),
+        ];
+        let snapshots: Vec<SnapshotListItemMessage> =
+            read_json(&target_directory.join("artifact/snapshots.json"));
+        assert_eq!(expected_locations, snapshots[0].locations);
+        let exported_snapshot: SnapshotMessage = read_json(
+            &target_directory.join(format!("artifact/snapshot/{}.json", snapshots[0].digest)),
+        );
+        assert_eq!(expected_locations, exported_snapshot.locations);
+        assert_eq!(
+            "archive",
+            std::fs::read_to_string(
+                target_directory
+                    .join("snapshot_download")
+                    .join(&archive_name)
+            )
+            .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn export_cardano_stake_distributions_by_hash_and_by_epoch() {
+        let target_directory =
+            TempDir::create("static_mirror", "export_cardano_stake_distributions");
+        let message_service = MessageServiceBuilder::new()
+            .with_cardano_stake_distributions(|message_service| {
+                message_service
+                    .expect_get_cardano_stake_distribution_list_message()
+                    .returning(|_| {
+                        Ok(vec![CardanoStakeDistributionListItemMessage {
+                            hash: "csd-hash".to_string(),
+                            epoch: Epoch(12),
+                            ..CardanoStakeDistributionListItemMessage::dummy()
+                        }])
+                    });
+                message_service
+                    .expect_get_cardano_stake_distribution_message()
+                    .returning(|_| {
+                        Ok(Some(CardanoStakeDistributionMessage {
+                            hash: "csd-hash".to_string(),
+                            epoch: Epoch(12),
+                            ..CardanoStakeDistributionMessage::dummy()
+                        }))
+                    });
+            })
+            .build();
+        let exporter = FileStaticMirrorExporter::new(
+            &target_directory,
+            &target_directory,
+            None,
+            message_service,
+            TestLogger::stdout(),
+        );
+
+        exporter
+            .export(&SignedEntityType::CardanoStakeDistribution(Epoch(12)))
+            .await
+            .unwrap();
+
+        for route in [
+            "artifact/cardano-stake-distributions",
+            "artifact/cardano-stake-distribution/csd-hash",
+            "artifact/cardano-stake-distribution/epoch/12",
+        ] {
+            assert!(
+                target_directory.join(format!("{route}.json")).exists(),
+                "route '{route}' should have been exported"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn export_only_the_artifacts_of_the_exported_type_that_are_not_in_the_mirror_yet() {
+        let target_directory = TempDir::create("static_mirror", "export_only_new_artifacts");
+        let mut message_service = MockMessageService::new();
+        message_service
+            .expect_get_certificate_list_message()
+            .returning(|_| Ok(vec![]));
+        message_service
+            .expect_get_mithril_stake_distribution_list_message()
+            .returning(|_| {
+                Ok(vec![MithrilStakeDistributionListItemMessage {
+                    hash: "msd-hash".to_string(),
+                    ..MithrilStakeDistributionListItemMessage::dummy()
+                }])
+            });
+        message_service
+            .expect_get_mithril_stake_distribution_message()
+            .times(1)
+            .returning(|_| {
+                Ok(Some(MithrilStakeDistributionMessage {
+                    hash: "msd-hash".to_string(),
+                    ..MithrilStakeDistributionMessage::dummy()
+                }))
+            });
+        let exporter = FileStaticMirrorExporter::new(
+            &target_directory,
+            &target_directory,
+            None,
+            Arc::new(message_service),
+            TestLogger::stdout(),
+        );
+
+        let signed_entity_type = SignedEntityType::MithrilStakeDistribution(Epoch(3));
+        exporter.export(&signed_entity_type).await.unwrap();
+        exporter.export(&signed_entity_type).await.unwrap();
+
+        assert!(target_directory
+            .join("artifact/mithril-stake-distribution/msd-hash.json")
+            .exists());
+        assert!(target_directory
+            .join("artifact/mithril-stake-distributions.json")
+            .exists());
+        for route in [
+            "artifact/snapshots",
+            "artifact/cardano-transactions",
+            "artifact/cardano-stake-distributions",
+        ] {
+            assert!(
+                !target_directory.join(format!("{route}.json")).exists(),
+                "route '{route}' should not have been exported"
+            );
+        }
+    }
+}
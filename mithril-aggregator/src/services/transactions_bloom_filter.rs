@@ -0,0 +1,266 @@
+//! ## Transactions bloom filter
+//!
+//! Probabilistic set of the certified transaction hashes used by the prover to answer without
+//! querying the database when a transaction is not certified yet: a transaction that is not in
+//! the filter is never certified, while a transaction in the filter may not be certified with a
+//! probability of [TransactionsBloomFilter::FALSE_POSITIVE_RATE].
+//!
+//! The filter is sized for a capacity of transaction hashes, and new transaction hashes can be
+//! added to it until its capacity is reached.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use anyhow::anyhow;
+use mithril_common::entities::TransactionHash;
+use mithril_common::StdResult;
+
+/// Bloom filter over a set of transaction hashes.
+#[derive(Debug, Clone)]
+pub struct TransactionsBloomFilter {
+    bits: Vec<u64>,
+    number_of_hashes: u32,
+    capacity: usize,
+    number_of_items: usize,
+}
+
+impl TransactionsBloomFilter {
+    /// Probability that a transaction hash that was not added is reported as contained
+    pub const FALSE_POSITIVE_RATE: f64 = 0.01;
+
+    /// Create a bloom filter containing the given transaction hashes
+    pub fn new<'a, T: IntoIterator<Item = &'a TransactionHash>>(transaction_hashes: T) -> Self {
+        let mut builder = TransactionsBloomFilterBuilder::new();
+        builder.add_transaction_hashes(transaction_hashes);
+
+        builder.build()
+    }
+
+    /// Check if a transaction hash may have been added to the filter.
+    ///
+    /// A `false` answer is certain, a `true` answer is wrong with a probability of
+    /// [Self::FALSE_POSITIVE_RATE].
+    pub fn might_contain(&self, transaction_hash: &str) -> bool {
+        let fingerprint = Self::fingerprint(transaction_hash);
+
+        self.bit_indices(fingerprint)
+            .all(|index| self.bits[index / 64] & (1 << (index % 64)) != 0)
+    }
+
+    /// Keep only the transaction hashes that may have been added to the filter
+    pub fn filter_transaction_hashes(
+        &self,
+        transaction_hashes: &[TransactionHash],
+    ) -> Vec<TransactionHash> {
+        transaction_hashes
+            .iter()
+            .filter(|hash| self.might_contain(hash))
+            .cloned()
+            .collect()
+    }
+
+    /// Number of transaction hashes that can still be added without exceeding the
+    /// [false positive rate][Self::FALSE_POSITIVE_RATE]
+    pub fn remaining_capacity(&self) -> usize {
+        self.capacity.saturating_sub(self.number_of_items)
+    }
+
+    /// Add the transaction hashes of the builder to the filter.
+    ///
+    /// It fails if they exceed the [remaining capacity][Self::remaining_capacity] of the filter.
+    pub fn extend(&mut self, builder: TransactionsBloomFilterBuilder) -> StdResult<()> {
+        if builder.len() > self.remaining_capacity() {
+            return Err(anyhow!(
+                "Can not add {} transaction hashes to a bloom filter with a remaining capacity of {}",
+                builder.len(),
+                self.remaining_capacity()
+            ));
+        }
+        for fingerprint in builder.fingerprints {
+            self.insert(fingerprint);
+        }
+
+        Ok(())
+    }
+
+    fn with_capacity(capacity: usize) -> Self {
+        let ln_2 = std::f64::consts::LN_2;
+        let capacity = capacity.max(1);
+        let number_of_bits =
+            (-(capacity as f64) * Self::FALSE_POSITIVE_RATE.ln() / (ln_2 * ln_2)).ceil() as usize;
+        let number_of_hashes = ((number_of_bits as f64 / capacity as f64) * ln_2).round() as u32;
+
+        Self {
+            bits: vec![0; number_of_bits.div_ceil(64)],
+            number_of_hashes: number_of_hashes.max(1),
+            capacity,
+            number_of_items: 0,
+        }
+    }
+
+    fn insert(&mut self, fingerprint: u64) {
+        let indices = self.bit_indices(fingerprint).collect::<Vec<_>>();
+        for index in indices {
+            self.bits[index / 64] |= 1 << (index % 64);
+        }
+        self.number_of_items += 1;
+    }
+
+    fn fingerprint(transaction_hash: &str) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        transaction_hash.hash(&mut hasher);
+
+        hasher.finish()
+    }
+
+    /// Derive the bit indices of a fingerprint with the double hashing technique
+    fn bit_indices(&self, fingerprint: u64) -> impl Iterator<Item = usize> {
+        let number_of_bits = (self.bits.len() * 64) as u64;
+        let first_hash = fingerprint & 0xffff_ffff;
+        let second_hash = (fingerprint >> 32) | 1;
+
+        (0..self.number_of_hashes as u64).map(move |i| {
+            (first_hash.wrapping_add(i.wrapping_mul(second_hash)) % number_of_bits) as usize
+        })
+    }
+}
+
+/// Builder of a [TransactionsBloomFilter] sized from the number of transaction hashes added.
+///
+/// Only a fingerprint of each hash is kept until the filter is built, so that the transaction
+/// hashes can be added by batches.
+#[derive(Debug, Default)]
+pub struct TransactionsBloomFilterBuilder {
+    fingerprints: Vec<u64>,
+}
+
+impl TransactionsBloomFilterBuilder {
+    /// Create a new empty builder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add transaction hashes to the future filter
+    pub fn add_transaction_hashes<'a, T: IntoIterator<Item = &'a TransactionHash>>(
+        &mut self,
+        transaction_hashes: T,
+    ) {
+        self.fingerprints.extend(
+            transaction_hashes
+                .into_iter()
+                .map(|hash| TransactionsBloomFilter::fingerprint(hash)),
+        );
+    }
+
+    /// Number of transaction hashes added
+    pub fn len(&self) -> usize {
+        self.fingerprints.len()
+    }
+
+    /// Check if no transaction hash was added
+    pub fn is_empty(&self) -> bool {
+        self.fingerprints.is_empty()
+    }
+
+    /// Build the filter containing all the transaction hashes added
+    pub fn build(self) -> TransactionsBloomFilter {
+        let capacity = self.len();
+        self.build_with_capacity(capacity)
+    }
+
+    /// Build the filter containing all the transaction hashes added, with room for new ones up
+    /// to the given capacity
+    pub fn build_with_capacity(self, capacity: usize) -> TransactionsBloomFilter {
+        let mut filter = TransactionsBloomFilter::with_capacity(capacity.max(self.len()));
+        for fingerprint in self.fingerprints {
+            filter.insert(fingerprint);
+        }
+
+        filter
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transaction_hashes(prefix: &str, count: usize) -> Vec<TransactionHash> {
+        (0..count).map(|i| format!("{prefix}-{i}")).collect()
+    }
+
+    #[test]
+    fn an_empty_filter_contains_no_transaction() {
+        let filter = TransactionsBloomFilterBuilder::new().build();
+
+        assert!(!filter.might_contain("tx-hash-123"));
+    }
+
+    #[test]
+    fn the_filter_contains_all_the_transactions_added() {
+        let certified_hashes = transaction_hashes("tx-certified", 10_000);
+        let filter = TransactionsBloomFilter::new(&certified_hashes);
+
+        for hash in &certified_hashes {
+            assert!(filter.might_contain(hash), "{hash} should be contained");
+        }
+    }
+
+    #[test]
+    fn the_filter_rejects_most_of_the_transactions_not_added() {
+        let certified_hashes = transaction_hashes("tx-certified", 10_000);
+        let filter = TransactionsBloomFilter::new(&certified_hashes);
+
+        let unknown_hashes = transaction_hashes("tx-unknown", 10_000);
+        let false_positives = filter.filter_transaction_hashes(&unknown_hashes).len();
+
+        assert!(
+            (false_positives as f64)
+                < 3.0 * TransactionsBloomFilter::FALSE_POSITIVE_RATE * 10_000.0,
+            "too many false positives: {false_positives}"
+        );
+    }
+
+    #[test]
+    fn transactions_added_to_a_filter_with_room_are_contained() {
+        let certified_hashes = transaction_hashes("tx-certified", 1_000);
+        let new_hashes = transaction_hashes("tx-new", 1_000);
+        let mut builder = TransactionsBloomFilterBuilder::new();
+        builder.add_transaction_hashes(&certified_hashes);
+        let mut filter = builder.build_with_capacity(2_000);
+        assert_eq!(1_000, filter.remaining_capacity());
+
+        let mut new_builder = TransactionsBloomFilterBuilder::new();
+        new_builder.add_transaction_hashes(&new_hashes);
+        filter.extend(new_builder).unwrap();
+
+        assert_eq!(0, filter.remaining_capacity());
+        for hash in certified_hashes.iter().chain(&new_hashes) {
+            assert!(filter.might_contain(hash), "{hash} should be contained");
+        }
+    }
+
+    #[test]
+    fn transactions_exceeding_the_capacity_of_the_filter_are_not_added() {
+        let mut filter = TransactionsBloomFilter::new(&transaction_hashes("tx-certified", 10));
+        let mut new_builder = TransactionsBloomFilterBuilder::new();
+        new_builder.add_transaction_hashes(&transaction_hashes("tx-new", 1));
+
+        filter
+            .extend(new_builder)
+            .expect_err("Exceeding the capacity of the filter should fail");
+    }
+
+    #[test]
+    fn the_filter_is_the_same_when_built_by_batches() {
+        let certified_hashes = transaction_hashes("tx-certified", 1_000);
+        let mut builder = TransactionsBloomFilterBuilder::new();
+        for batch in certified_hashes.chunks(100) {
+            builder.add_transaction_hashes(batch);
+        }
+
+        assert_eq!(
+            TransactionsBloomFilter::new(&certified_hashes).bits,
+            builder.build().bits
+        );
+    }
+}
@@ -0,0 +1,137 @@
+//! ## Certificate transparency log
+//!
+//! This service appends the hash of each issued certificate to an external append-only
+//! transparency log, so that the clients requiring an inclusion proof can detect an aggregator
+//! showing them a certificate chain that was not publicly logged.
+
+use anyhow::Context;
+use async_trait::async_trait;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use mithril_common::certificate_chain::TransparencyLogEntry;
+use mithril_common::entities::Certificate;
+use mithril_common::StdResult;
+
+/// Publish the issued certificates to a transparency log.
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait CertificateTransparencyLogPublisher: Sync + Send {
+    /// Append the hash of the certificate to the transparency log
+    async fn publish(&self, certificate: &Certificate) -> StdResult<()>;
+}
+
+/// [CertificateTransparencyLogPublisher] appending the entries with the HTTP API of a
+/// transparency log, `POST {endpoint}/entries`.
+pub struct HttpCertificateTransparencyLogPublisher {
+    entries_url: String,
+    client: reqwest::Client,
+}
+
+impl HttpCertificateTransparencyLogPublisher {
+    /// Create a new [HttpCertificateTransparencyLogPublisher].
+    pub fn new(endpoint: &str) -> StdResult<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .with_context(|| "Http Client build failed")?;
+
+        Ok(Self {
+            entries_url: format!("{}/entries", endpoint.trim_end_matches('/')),
+            client,
+        })
+    }
+}
+
+#[async_trait]
+impl CertificateTransparencyLogPublisher for HttpCertificateTransparencyLogPublisher {
+    async fn publish(&self, certificate: &Certificate) -> StdResult<()> {
+        let entry = TransparencyLogEntry {
+            certificate_hash: certificate.hash.clone(),
+        };
+        self.client
+            .post(&self.entries_url)
+            .json(&entry)
+            .send()
+            .await
+            .with_context(|| format!("Transparency log call failed: '{}'", self.entries_url))?
+            .error_for_status()
+            .with_context(|| {
+                format!("Transparency log returned an error: '{}'", self.entries_url)
+            })?;
+
+        Ok(())
+    }
+}
+
+/// [CertificateTransparencyLogPublisher] that only keeps the last published certificate hash,
+/// used when no transparency log is configured.
+#[derive(Default)]
+pub struct DumbCertificateTransparencyLogPublisher {
+    last_certificate_hash: Mutex<Option<String>>,
+}
+
+impl DumbCertificateTransparencyLogPublisher {
+    /// Create a new instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the hash of the last published certificate.
+    pub fn get_last_certificate_hash(&self) -> Option<String> {
+        self.last_certificate_hash.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl CertificateTransparencyLogPublisher for DumbCertificateTransparencyLogPublisher {
+    async fn publish(&self, certificate: &Certificate) -> StdResult<()> {
+        *self.last_certificate_hash.lock().unwrap() = Some(certificate.hash.clone());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use httpmock::prelude::*;
+    use serde_json::json;
+
+    use mithril_common::test_utils::fake_data;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn http_publisher_appends_the_certificate_hash_to_the_log() {
+        let server = MockServer::start();
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/entries")
+                .json_body(json!({ "certificate_hash": "certificate-hash" }));
+            then.status(201);
+        });
+        let publisher = HttpCertificateTransparencyLogPublisher::new(&server.base_url()).unwrap();
+
+        publisher
+            .publish(&fake_data::certificate("certificate-hash"))
+            .await
+            .unwrap();
+
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn http_publisher_fails_if_the_log_returns_an_error() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(POST).path("/entries");
+            then.status(500);
+        });
+        let publisher = HttpCertificateTransparencyLogPublisher::new(&server.base_url()).unwrap();
+
+        publisher
+            .publish(&fake_data::certificate("certificate-hash"))
+            .await
+            .expect_err("An error of the transparency log should fail the publication");
+    }
+}
@@ -1,17 +1,20 @@
 use anyhow::{anyhow, Context};
 use async_trait::async_trait;
+use slog::{warn, Logger};
 use std::sync::Arc;
+use std::time::Duration;
 use thiserror::Error;
-use tokio::sync::RwLock;
+use tokio::{sync::RwLock, time::Instant};
 
 use mithril_common::{
     chain_observer::ChainObserver,
     crypto_helper::{KESPeriod, ProtocolKeyRegistration},
     entities::{Epoch, Signer, SignerWithStake, StakeDistribution},
+    logging::LoggerExtensions,
     StdError, StdResult,
 };
 
-use crate::VerificationKeyStorer;
+use crate::{MetricsService, VerificationKeyStorer};
 
 use mithril_common::chain_observer::ChainObserverError;
 
@@ -114,6 +117,9 @@ pub struct MithrilSignerRegisterer {
     /// Current signer registration round
     current_round: RwLock<Option<SignerRegistrationRound>>,
 
+    /// Last closed signer registration round and its closing instant
+    last_closed_round: RwLock<Option<(SignerRegistrationRound, Instant)>>,
+
     /// Chain observer service.
     chain_observer: Arc<dyn ChainObserver>,
 
@@ -126,6 +132,15 @@ pub struct MithrilSignerRegisterer {
     /// Number of epochs before previous records will be deleted at the next registration round
     /// opening
     verification_key_epoch_retention_limit: Option<u64>,
+
+    /// Duration after the closing of a registration round during which the registrations
+    /// received for its epoch are accepted in the current round
+    late_registration_grace_period: Duration,
+
+    /// Metrics service
+    metrics_service: Arc<MetricsService>,
+
+    logger: Logger,
 }
 
 impl MithrilSignerRegisterer {
@@ -135,13 +150,38 @@ impl MithrilSignerRegisterer {
         verification_key_store: Arc<dyn VerificationKeyStorer>,
         signer_recorder: Arc<dyn SignerRecorder>,
         verification_key_epoch_retention_limit: Option<u64>,
+        late_registration_grace_period: Duration,
+        metrics_service: Arc<MetricsService>,
+        logger: Logger,
     ) -> Self {
         Self {
             current_round: RwLock::new(None),
+            last_closed_round: RwLock::new(None),
             chain_observer,
             verification_key_store,
             signer_recorder,
             verification_key_epoch_retention_limit,
+            late_registration_grace_period,
+            metrics_service,
+            logger: logger.new_with_component_name::<Self>(),
+        }
+    }
+
+    /// Get the closed round of a late registration for the given epoch, if its grace period is
+    /// not over.
+    ///
+    /// Return `None` if the registration is not for the last closed round, or `Some(None)` if its
+    /// grace period is over.
+    async fn get_late_registration_round_in_grace_period(
+        &self,
+        epoch: Epoch,
+    ) -> Option<Option<SignerRegistrationRound>> {
+        match &*self.last_closed_round.read().await {
+            Some((closed_round, closed_at)) if closed_round.epoch == epoch => Some(
+                (closed_at.elapsed() <= self.late_registration_grace_period)
+                    .then(|| closed_round.clone()),
+            ),
+            _ => None,
         }
     }
 
@@ -182,7 +222,9 @@ impl SignerRegistrationRoundOpener for MithrilSignerRegisterer {
 
     async fn close_registration_round(&self) -> StdResult<()> {
         let mut current_round = self.current_round.write().await;
-        *current_round = None;
+        if let Some(round) = current_round.take() {
+            *self.last_closed_round.write().await = Some((round, Instant::now()));
+        }
 
         Ok(())
     }
@@ -199,16 +241,46 @@ impl SignerRegisterer for MithrilSignerRegisterer {
         let registration_round = registration_round
             .as_ref()
             .ok_or(SignerRegistrationError::RegistrationRoundNotYetOpened)?;
+        // A late registration is saved in the current round, but with the stake distribution of
+        // the round of its epoch.
+        let mut late_registration_round = None;
         if registration_round.epoch != epoch {
-            return Err(SignerRegistrationError::RegistrationRoundUnexpectedEpoch {
-                current_round_epoch: registration_round.epoch,
-                received_epoch: epoch,
-            });
+            match self
+                .get_late_registration_round_in_grace_period(epoch)
+                .await
+            {
+                Some(Some(closed_round)) => {
+                    warn!(
+                        self.logger, "Late signer registration accepted in the current round";
+                        "party_id" => &signer.party_id,
+                        "received_epoch" => ?epoch,
+                        "current_round_epoch" => ?registration_round.epoch
+                    );
+                    self.metrics_service
+                        .get_signer_registration_late_accepted_since_startup()
+                        .increment();
+                    late_registration_round = Some(closed_round);
+                }
+                is_late => {
+                    if is_late.is_some() {
+                        self.metrics_service
+                            .get_signer_registration_late_rejected_since_startup()
+                            .increment();
+                    }
+                    return Err(SignerRegistrationError::RegistrationRoundUnexpectedEpoch {
+                        current_round_epoch: registration_round.epoch,
+                        received_epoch: epoch,
+                    });
+                }
+            }
         }
 
+        let stake_distribution = &late_registration_round
+            .as_ref()
+            .unwrap_or(registration_round)
+            .stake_distribution;
         let mut key_registration = ProtocolKeyRegistration::init(
-            &registration_round
-                .stake_distribution
+            &stake_distribution
                 .iter()
                 .map(|(k, v)| (k.to_owned(), *v))
                 .collect::<Vec<_>>(),
@@ -244,10 +316,7 @@ impl SignerRegisterer for MithrilSignerRegisterer {
             .map_err(|e| SignerRegistrationError::FailedSignerRegistration(anyhow!(e)))?;
         let mut signer_save = SignerWithStake::from_signer(
             signer.to_owned(),
-            *registration_round
-                .stake_distribution
-                .get(&party_id_save)
-                .unwrap(),
+            *stake_distribution.get(&party_id_save).unwrap(),
         );
         signer_save.party_id.clone_from(&party_id_save);
 
@@ -283,7 +352,7 @@ impl SignerRegisterer for MithrilSignerRegisterer {
 
 #[cfg(test)]
 mod tests {
-    use std::{collections::HashMap, sync::Arc};
+    use std::{collections::HashMap, sync::Arc, time::Duration};
 
     use mithril_common::{
         chain_observer::FakeObserver,
@@ -292,12 +361,13 @@ mod tests {
     };
     use mithril_persistence::store::adapter::MemoryAdapter;
 
+    use crate::test_tools::TestLogger;
     use crate::{
-        MithrilSignerRegisterer, SignerRegisterer, SignerRegistrationRoundOpener,
+        MetricsService, MithrilSignerRegisterer, SignerRegisterer, SignerRegistrationRoundOpener,
         VerificationKeyStore, VerificationKeyStorer,
     };
 
-    use super::MockSignerRecorder;
+    use super::{MockSignerRecorder, SignerRegistrationError};
 
     #[tokio::test]
     async fn can_register_signer_if_registration_round_is_opened_with_operational_certificate() {
@@ -314,6 +384,9 @@ mod tests {
             verification_key_store.clone(),
             Arc::new(signer_recorder),
             None,
+            Duration::ZERO,
+            Arc::new(MetricsService::new(TestLogger::stdout()).unwrap()),
+            TestLogger::stdout(),
         );
         let registration_epoch = Epoch(1);
         let fixture = MithrilFixtureBuilder::default().with_signers(5).build();
@@ -359,6 +432,9 @@ mod tests {
             verification_key_store.clone(),
             Arc::new(signer_recorder),
             None,
+            Duration::ZERO,
+            Arc::new(MetricsService::new(TestLogger::stdout()).unwrap()),
+            TestLogger::stdout(),
         );
         let registration_epoch = Epoch(1);
         let fixture = MithrilFixtureBuilder::default()
@@ -403,6 +479,9 @@ mod tests {
             verification_key_store.clone(),
             Arc::new(signer_recorder),
             None,
+            Duration::ZERO,
+            Arc::new(MetricsService::new(TestLogger::stdout()).unwrap()),
+            TestLogger::stdout(),
         );
         let registration_epoch = Epoch(1);
         let fixture = MithrilFixtureBuilder::default().with_signers(5).build();
@@ -414,6 +493,129 @@ mod tests {
             .expect_err("signer registration should fail if no round opened");
     }
 
+    async fn register_after_round_switch(
+        late_registration_grace_period: Duration,
+        elapsed_since_round_closing: Duration,
+    ) -> (
+        Signer,
+        Result<SignerWithStake, SignerRegistrationError>,
+        Arc<MetricsService>,
+        Arc<VerificationKeyStore>,
+    ) {
+        let verification_key_store = Arc::new(VerificationKeyStore::new(Box::new(
+            MemoryAdapter::<Epoch, HashMap<PartyId, SignerWithStake>>::new(None).unwrap(),
+        )));
+        let mut signer_recorder = MockSignerRecorder::new();
+        signer_recorder
+            .expect_record_signer_registration()
+            .returning(|_| Ok(()));
+        let metrics_service = Arc::new(MetricsService::new(TestLogger::stdout()).unwrap());
+        let signer_registerer = MithrilSignerRegisterer::new(
+            Arc::new(FakeObserver::default()),
+            verification_key_store.clone(),
+            Arc::new(signer_recorder),
+            None,
+            late_registration_grace_period,
+            metrics_service.clone(),
+            TestLogger::stdout(),
+        );
+        let fixture = MithrilFixtureBuilder::default().with_signers(5).build();
+        let signer_to_register: Signer = fixture.signers()[0].to_owned();
+
+        signer_registerer
+            .open_registration_round(Epoch(1), fixture.stake_distribution())
+            .await
+            .unwrap();
+        signer_registerer.close_registration_round().await.unwrap();
+        tokio::time::advance(elapsed_since_round_closing).await;
+        let next_stake_distribution = fixture
+            .stake_distribution()
+            .into_iter()
+            .map(|(party_id, stake)| (party_id, stake + 1_000))
+            .collect();
+        signer_registerer
+            .open_registration_round(Epoch(2), next_stake_distribution)
+            .await
+            .unwrap();
+
+        let result = signer_registerer
+            .register_signer(Epoch(1), &signer_to_register)
+            .await;
+
+        (
+            signer_to_register,
+            result,
+            metrics_service,
+            verification_key_store,
+        )
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn late_registration_is_accepted_in_current_round_during_grace_period() {
+        let (signer, result, metrics_service, verification_key_store) =
+            register_after_round_switch(Duration::from_secs(60), Duration::from_secs(60)).await;
+
+        let signer_with_stake = result.expect("late signer registration should be accepted");
+        assert_eq!(signer.party_id, signer_with_stake.party_id);
+        let registered_signers = verification_key_store
+            .get_signers(Epoch(2))
+            .await
+            .unwrap()
+            .expect("the late registration should be saved in the current round");
+        assert_eq!(
+            Some(&signer_with_stake),
+            registered_signers
+                .iter()
+                .find(|s| s.party_id == signer.party_id)
+        );
+        assert_eq!(
+            1,
+            metrics_service
+                .get_signer_registration_late_accepted_since_startup()
+                .get()
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn late_registration_is_rejected_after_grace_period() {
+        let (_signer, result, metrics_service, _verification_key_store) =
+            register_after_round_switch(Duration::from_secs(60), Duration::from_secs(61)).await;
+
+        assert!(matches!(
+            result,
+            Err(SignerRegistrationError::RegistrationRoundUnexpectedEpoch { .. })
+        ));
+        assert_eq!(
+            1,
+            metrics_service
+                .get_signer_registration_late_rejected_since_startup()
+                .get()
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn late_registration_uses_the_stake_distribution_of_its_epoch() {
+        let (signer, result, _metrics_service, verification_key_store) =
+            register_after_round_switch(Duration::from_secs(60), Duration::ZERO).await;
+
+        let signer_with_stake = result.expect("late signer registration should be accepted");
+        let registration_epoch_stake = MithrilFixtureBuilder::default()
+            .with_signers(5)
+            .build()
+            .stake_distribution()[&signer.party_id];
+        assert_eq!(registration_epoch_stake, signer_with_stake.stake);
+        let registered_signers = verification_key_store
+            .get_signers(Epoch(2))
+            .await
+            .unwrap()
+            .unwrap();
+        let registered_signer = registered_signers
+            .iter()
+            .find(|s| s.party_id == signer.party_id)
+            .expect("the late registration should be saved in the current round");
+        assert_eq!(registration_epoch_stake, registered_signer.stake);
+    }
+
     #[tokio::test]
     async fn should_prune_verification_keys_older_than_two_epochs_at_round_opening() {
         let initial_keys = (1..=5)
@@ -436,6 +638,9 @@ mod tests {
             verification_key_store.clone(),
             Arc::new(signer_recorder),
             Some(2),
+            Duration::ZERO,
+            Arc::new(MetricsService::new(TestLogger::stdout()).unwrap()),
+            TestLogger::stdout(),
         );
         let fixture = MithrilFixtureBuilder::default().with_signers(5).build();
 
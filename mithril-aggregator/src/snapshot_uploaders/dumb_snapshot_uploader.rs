@@ -41,6 +41,7 @@ impl Default for DumbSnapshotUploader {
 #[async_trait]
 impl SnapshotUploader for DumbSnapshotUploader {
     /// Upload a snapshot
+    #[tracing::instrument(skip_all, fields(snapshot_filepath = %snapshot_filepath.display()))]
     async fn upload_snapshot(&self, snapshot_filepath: &Path) -> StdResult<SnapshotLocation> {
         let mut value = self
             .last_uploaded
@@ -36,6 +36,7 @@ impl LocalSnapshotUploader {
 
 #[async_trait]
 impl SnapshotUploader for LocalSnapshotUploader {
+    #[tracing::instrument(skip_all, fields(snapshot_filepath = %snapshot_filepath.display()))]
     async fn upload_snapshot(&self, snapshot_filepath: &Path) -> StdResult<SnapshotLocation> {
         let archive_name = snapshot_filepath.file_name().unwrap().to_str().unwrap();
         let target_path = &self.target_location.join(archive_name);
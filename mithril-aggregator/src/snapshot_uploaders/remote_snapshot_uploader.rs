@@ -37,6 +37,7 @@ impl RemoteSnapshotUploader {
 
 #[async_trait]
 impl SnapshotUploader for RemoteSnapshotUploader {
+    #[tracing::instrument(skip_all, fields(snapshot_filepath = %snapshot_filepath.display()))]
     async fn upload_snapshot(&self, snapshot_filepath: &Path) -> StdResult<SnapshotLocation> {
         let archive_name = snapshot_filepath.file_name().unwrap().to_str().unwrap();
         let location = if self.use_cdn_domain {
@@ -0,0 +1,291 @@
+use anyhow::Context;
+use async_trait::async_trait;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use mithril_common::crypto_helper::MKMapSnapshot;
+use mithril_common::entities::{BlockNumber, BlockRange};
+use mithril_common::StdResult;
+
+const MERKLE_MAP_CACHE_FILE_PREFIX: &str = "merkle_map_cache-";
+const MERKLE_MAP_CACHE_FILE_EXTENSION: &str = "cbor";
+const TEMPORARY_FILE_EXTENSION: &str = "tmp";
+
+/// Age after which a temporary file is considered left over by an interrupted save.
+const STALE_TEMPORARY_FILE_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// Store of the block ranges Merkle map computed by the prover cache.
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait MerkleMapCacheStorer: Sync + Send {
+    /// Get the snapshot of the Merkle map computed up to the given block number, if stored.
+    async fn get(&self, up_to: BlockNumber) -> StdResult<Option<MKMapSnapshot<BlockRange>>>;
+
+    /// Save the snapshot of the Merkle map computed up to the given block number, replacing the
+    /// snapshots computed up to other block numbers.
+    async fn save(&self, up_to: BlockNumber, snapshot: MKMapSnapshot<BlockRange>) -> StdResult<()>;
+}
+
+/// [MerkleMapCacheStorer] keeping the snapshot in a CBOR file of a directory, that can be shared
+/// by several aggregator processes.
+///
+/// The files are read and written on the blocking threads of the runtime.
+pub struct FileMerkleMapCacheStore {
+    directory: PathBuf,
+    temporary_files_counter: AtomicU64,
+}
+
+impl FileMerkleMapCacheStore {
+    /// Create a new [FileMerkleMapCacheStore], creating its directory if needed.
+    pub fn new(directory: &Path) -> StdResult<Self> {
+        fs::create_dir_all(directory).with_context(|| {
+            format!(
+                "Could not create the Merkle map cache directory: '{}'",
+                directory.display()
+            )
+        })?;
+
+        Ok(Self {
+            directory: directory.to_path_buf(),
+            temporary_files_counter: AtomicU64::new(0),
+        })
+    }
+
+    fn cache_file_path(&self, up_to: BlockNumber) -> PathBuf {
+        self.directory.join(format!(
+            "{MERKLE_MAP_CACHE_FILE_PREFIX}{up_to}.{MERKLE_MAP_CACHE_FILE_EXTENSION}"
+        ))
+    }
+
+    /// Path of a temporary file unique to this process and to this save, so that concurrent
+    /// saves never write to the same file.
+    fn temporary_file_path(&self, up_to: BlockNumber) -> PathBuf {
+        let counter = self.temporary_files_counter.fetch_add(1, Ordering::Relaxed);
+        self.directory.join(format!(
+            "{MERKLE_MAP_CACHE_FILE_PREFIX}{up_to}.{}-{counter}.{TEMPORARY_FILE_EXTENSION}",
+            std::process::id()
+        ))
+    }
+
+    fn read_file(file_path: &Path) -> StdResult<Option<MKMapSnapshot<BlockRange>>> {
+        if !file_path.exists() {
+            return Ok(None);
+        }
+
+        let file = File::open(file_path).with_context(|| {
+            format!(
+                "Could not open the Merkle map cache file: '{}'",
+                file_path.display()
+            )
+        })?;
+        let snapshot = ciborium::from_reader(BufReader::new(file)).with_context(|| {
+            format!(
+                "Could not deserialize the Merkle map cache file: '{}'",
+                file_path.display()
+            )
+        })?;
+
+        Ok(Some(snapshot))
+    }
+
+    fn write_file(
+        file_path: &Path,
+        temporary_file_path: &Path,
+        snapshot: &MKMapSnapshot<BlockRange>,
+    ) -> StdResult<()> {
+        // Written to a temporary file first so that another process never reads a partial file
+        let file = File::create(temporary_file_path).with_context(|| {
+            format!(
+                "Could not create the Merkle map cache file: '{}'",
+                temporary_file_path.display()
+            )
+        })?;
+        let result = ciborium::into_writer(snapshot, BufWriter::new(file))
+            .with_context(|| "Could not serialize the Merkle map cache")
+            .and_then(|_| {
+                fs::rename(temporary_file_path, file_path).with_context(|| {
+                    format!(
+                        "Could not move the Merkle map cache file to: '{}'",
+                        file_path.display()
+                    )
+                })
+            });
+        if result.is_err() {
+            let _ = fs::remove_file(temporary_file_path);
+        }
+
+        result
+    }
+
+    /// Remove the cache files other than the kept one, and the temporary files left over by
+    /// interrupted saves.
+    fn remove_outdated_files(directory: &Path, kept_file_path: &Path) -> StdResult<()> {
+        for entry in fs::read_dir(directory)? {
+            let entry = entry?;
+            let path = entry.path();
+            let is_cache_file = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(MERKLE_MAP_CACHE_FILE_PREFIX));
+            if !is_cache_file || path == kept_file_path {
+                continue;
+            }
+            let is_outdated = match path.extension().and_then(|extension| extension.to_str()) {
+                Some(MERKLE_MAP_CACHE_FILE_EXTENSION) => true,
+                Some(TEMPORARY_FILE_EXTENSION) => {
+                    entry.metadata()?.modified()?.elapsed().unwrap_or_default()
+                        >= STALE_TEMPORARY_FILE_AGE
+                }
+                _ => false,
+            };
+            if is_outdated {
+                fs::remove_file(&path)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl MerkleMapCacheStorer for FileMerkleMapCacheStore {
+    async fn get(&self, up_to: BlockNumber) -> StdResult<Option<MKMapSnapshot<BlockRange>>> {
+        let file_path = self.cache_file_path(up_to);
+
+        tokio::task::spawn_blocking(move || Self::read_file(&file_path))
+            .await
+            .with_context(|| "Could not join the Merkle map cache reading task")?
+    }
+
+    async fn save(&self, up_to: BlockNumber, snapshot: MKMapSnapshot<BlockRange>) -> StdResult<()> {
+        let directory = self.directory.clone();
+        let file_path = self.cache_file_path(up_to);
+        let temporary_file_path = self.temporary_file_path(up_to);
+
+        tokio::task::spawn_blocking(move || {
+            Self::write_file(&file_path, &temporary_file_path, &snapshot)?;
+            Self::remove_outdated_files(&directory, &file_path)
+                .with_context(|| "Could not remove the outdated Merkle map cache files")
+        })
+        .await
+        .with_context(|| "Could not join the Merkle map cache writing task")?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::SystemTime;
+
+    use mithril_common::crypto_helper::{MKMap, MKMapNode, MKTreeNode, MKTreeStoreInMemory};
+    use mithril_common::test_utils::TempDir;
+
+    use super::*;
+
+    fn merkle_map_snapshot(number_of_block_ranges: u64) -> MKMapSnapshot<BlockRange> {
+        let entries = (0..number_of_block_ranges)
+            .map(|i| {
+                (
+                    BlockRange::from_block_number(BlockRange::LENGTH * i),
+                    MKMapNode::<_, MKTreeStoreInMemory>::TreeNode(MKTreeNode::from(format!(
+                        "block-range-root-{i}"
+                    ))),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        MKMap::<_, _, MKTreeStoreInMemory>::new(&entries)
+            .unwrap()
+            .to_snapshot()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn get_a_saved_snapshot() {
+        let directory = TempDir::create("merkle_map_cache_store", "get_a_saved_snapshot");
+        let store = FileMerkleMapCacheStore::new(&directory).unwrap();
+        let snapshot = merkle_map_snapshot(5);
+
+        store.save(BlockNumber(75), snapshot.clone()).await.unwrap();
+
+        assert_eq!(Some(snapshot), store.get(BlockNumber(75)).await.unwrap());
+        assert_eq!(None, store.get(BlockNumber(90)).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn saving_a_snapshot_removes_the_snapshots_up_to_other_block_numbers() {
+        let directory = TempDir::create(
+            "merkle_map_cache_store",
+            "saving_a_snapshot_removes_the_snapshots_up_to_other_block_numbers",
+        );
+        let store = FileMerkleMapCacheStore::new(&directory).unwrap();
+
+        store
+            .save(BlockNumber(75), merkle_map_snapshot(5))
+            .await
+            .unwrap();
+        store
+            .save(BlockNumber(90), merkle_map_snapshot(6))
+            .await
+            .unwrap();
+
+        assert_eq!(None, store.get(BlockNumber(75)).await.unwrap());
+        assert_eq!(1, fs::read_dir(&directory).unwrap().count());
+    }
+
+    #[tokio::test]
+    async fn saving_a_snapshot_removes_the_stale_temporary_files_only() {
+        let directory = TempDir::create(
+            "merkle_map_cache_store",
+            "saving_a_snapshot_removes_the_stale_temporary_files_only",
+        );
+        let store = FileMerkleMapCacheStore::new(&directory).unwrap();
+        let stale_temporary_file = directory.join("merkle_map_cache-60.1-0.tmp");
+        let recent_temporary_file = directory.join("merkle_map_cache-60.2-0.tmp");
+        fs::write(&stale_temporary_file, "stale").unwrap();
+        fs::write(&recent_temporary_file, "recent").unwrap();
+        File::options()
+            .write(true)
+            .open(&stale_temporary_file)
+            .unwrap()
+            .set_modified(SystemTime::now() - STALE_TEMPORARY_FILE_AGE * 2)
+            .unwrap();
+
+        store
+            .save(BlockNumber(75), merkle_map_snapshot(5))
+            .await
+            .unwrap();
+
+        assert!(!stale_temporary_file.exists());
+        assert!(recent_temporary_file.exists());
+    }
+
+    #[test]
+    fn the_temporary_files_of_two_saves_are_different() {
+        let directory = TempDir::create(
+            "merkle_map_cache_store",
+            "the_temporary_files_of_two_saves_are_different",
+        );
+        let store = FileMerkleMapCacheStore::new(&directory).unwrap();
+
+        assert_ne!(
+            store.temporary_file_path(BlockNumber(75)),
+            store.temporary_file_path(BlockNumber(75))
+        );
+    }
+
+    #[tokio::test]
+    async fn fail_to_get_a_corrupted_snapshot() {
+        let directory =
+            TempDir::create("merkle_map_cache_store", "fail_to_get_a_corrupted_snapshot");
+        let store = FileMerkleMapCacheStore::new(&directory).unwrap();
+        fs::write(store.cache_file_path(BlockNumber(75)), "corrupted").unwrap();
+
+        store
+            .get(BlockNumber(75))
+            .await
+            .expect_err("A corrupted snapshot should not be deserialized");
+    }
+}
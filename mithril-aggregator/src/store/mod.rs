@@ -1,14 +1,22 @@
 mod epoch_settings_storer;
+mod merkle_map_cache_store;
 mod pending_certificate_store;
+mod protocol_parameters_schedule_storer;
 mod verification_key_store;
 
 pub use epoch_settings_storer::EpochSettingsStorer;
+pub use merkle_map_cache_store::{FileMerkleMapCacheStore, MerkleMapCacheStorer};
 pub use pending_certificate_store::CertificatePendingStore;
+pub use protocol_parameters_schedule_storer::ProtocolParametersScheduleStorer;
 pub use verification_key_store::{VerificationKeyStore, VerificationKeyStorer};
 
 #[cfg(test)]
 pub use epoch_settings_storer::FakeEpochSettingsStorer;
 #[cfg(test)]
+pub use merkle_map_cache_store::MockMerkleMapCacheStorer;
+#[cfg(test)]
+pub use protocol_parameters_schedule_storer::FakeProtocolParametersScheduleStorer;
+#[cfg(test)]
 pub use verification_key_store::test_suite as verification_key_store_test_suite;
 #[cfg(test)]
 pub(crate) use verification_key_store::test_verification_key_storer;
@@ -0,0 +1,65 @@
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+use tokio::sync::RwLock;
+
+use mithril_common::entities::{Epoch, ProtocolParameters};
+use mithril_common::StdResult;
+
+/// Store and get the protocol parameters changes scheduled by the operators.
+///
+/// A scheduled change applies to the certificates signed from its activation epoch until a
+/// later scheduled change is activated.
+#[async_trait]
+pub trait ProtocolParametersScheduleStorer: Sync + Send {
+    /// Schedule the given [ProtocolParameters] to be used for signing from the given activation epoch.
+    async fn schedule_protocol_parameters(
+        &self,
+        activation_epoch: Epoch,
+        protocol_parameters: ProtocolParameters,
+    ) -> StdResult<()>;
+
+    /// Get the [ProtocolParameters] of the latest change activated at or before the given epoch, if any.
+    async fn get_active_protocol_parameters(
+        &self,
+        epoch: Epoch,
+    ) -> StdResult<Option<ProtocolParameters>>;
+}
+
+pub struct FakeProtocolParametersScheduleStorer {
+    pub schedule: RwLock<BTreeMap<Epoch, ProtocolParameters>>,
+}
+
+impl FakeProtocolParametersScheduleStorer {
+    #[cfg(test)]
+    pub fn new(data: Vec<(Epoch, ProtocolParameters)>) -> Self {
+        let schedule = RwLock::new(data.into_iter().collect());
+        Self { schedule }
+    }
+}
+
+#[async_trait]
+impl ProtocolParametersScheduleStorer for FakeProtocolParametersScheduleStorer {
+    async fn schedule_protocol_parameters(
+        &self,
+        activation_epoch: Epoch,
+        protocol_parameters: ProtocolParameters,
+    ) -> StdResult<()> {
+        let mut schedule = self.schedule.write().await;
+        schedule.insert(activation_epoch, protocol_parameters);
+
+        Ok(())
+    }
+
+    async fn get_active_protocol_parameters(
+        &self,
+        epoch: Epoch,
+    ) -> StdResult<Option<ProtocolParameters>> {
+        let schedule = self.schedule.read().await;
+
+        Ok(schedule
+            .range(..=epoch)
+            .next_back()
+            .map(|(_, protocol_parameters)| protocol_parameters.clone()))
+    }
+}
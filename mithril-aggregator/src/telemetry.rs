@@ -0,0 +1,144 @@
+//! ## Telemetry
+//!
+//! Export the [tracing] spans of the aggregator to an OpenTelemetry collector (Jaeger, Tempo, ...)
+//! using the OTLP protocol, so the lifecycle of a certificate can be followed across the
+//! HTTP server, the certifier, the artifact builders and the uploaders.
+
+use anyhow::Context;
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::{runtime, trace::TracerProvider, Resource};
+use slog::{info, warn, Logger};
+use tracing::Subscriber;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::Layer;
+
+use mithril_common::StdResult;
+
+const SERVICE_NAME: &str = "mithril-aggregator";
+
+/// Keep the OpenTelemetry pipeline alive, pending spans are flushed when dropped.
+pub struct TracingGuard {
+    tracer_provider: TracerProvider,
+    logger: Logger,
+}
+
+impl Drop for TracingGuard {
+    fn drop(&mut self) {
+        if let Err(error) = self.tracer_provider.shutdown() {
+            warn!(self.logger, "Failed to flush the OpenTelemetry spans"; "error" => ?error);
+        }
+    }
+}
+
+/// Install a global [tracing] subscriber that exports the spans to the given OTLP endpoint.
+///
+/// Nothing is installed if no endpoint is given, spans are then discarded.
+pub fn init_tracing(
+    otlp_exporter_endpoint: Option<&str>,
+    logger: &Logger,
+) -> StdResult<Option<TracingGuard>> {
+    let Some(endpoint) = otlp_exporter_endpoint else {
+        return Ok(None);
+    };
+
+    let tracer_provider = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(endpoint),
+        )
+        .with_trace_config(opentelemetry_sdk::trace::Config::default().with_resource(resource()))
+        .install_batch(runtime::Tokio)
+        .with_context(|| format!("Could not create the OTLP exporter for endpoint '{endpoint}'"))?;
+
+    tracing_subscriber::registry()
+        .with(tracing_layer(&tracer_provider))
+        .try_init()
+        .with_context(|| "Could not install the tracing subscriber")?;
+
+    info!(logger, "Exporting traces to OpenTelemetry collector"; "endpoint" => endpoint);
+
+    Ok(Some(TracingGuard {
+        tracer_provider,
+        logger: logger.clone(),
+    }))
+}
+
+/// Resource identifying the aggregator in the exported spans.
+fn resource() -> Resource {
+    Resource::new(vec![
+        KeyValue::new("service.name", SERVICE_NAME),
+        KeyValue::new("service.version", env!("CARGO_PKG_VERSION")),
+    ])
+}
+
+/// [tracing] layer sending the spans to the exporter of the given provider.
+fn tracing_layer<S>(tracer_provider: &TracerProvider) -> impl Layer<S>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    tracing_opentelemetry::layer().with_tracer(tracer_provider.tracer(SERVICE_NAME))
+}
+
+#[cfg(test)]
+mod tests {
+    use opentelemetry::trace::TraceError;
+    use opentelemetry_sdk::export::trace::{SpanData, SpanExporter};
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+    /// Exporter keeping the spans, and the resource they are exported with, in memory
+    #[derive(Debug, Clone, Default)]
+    struct InMemorySpanExporter {
+        spans: Arc<Mutex<Vec<SpanData>>>,
+        resource: Arc<Mutex<Option<Resource>>>,
+    }
+
+    impl SpanExporter for InMemorySpanExporter {
+        fn export(
+            &mut self,
+            batch: Vec<SpanData>,
+        ) -> Pin<Box<dyn Future<Output = Result<(), TraceError>> + Send + 'static>> {
+            self.spans.lock().unwrap().extend(batch);
+            Box::pin(async { Ok(()) })
+        }
+
+        fn set_resource(&mut self, resource: &Resource) {
+            *self.resource.lock().unwrap() = Some(resource.clone());
+        }
+    }
+
+    #[test]
+    fn export_the_spans_with_the_aggregator_resource() {
+        let exporter = InMemorySpanExporter::default();
+        let tracer_provider = TracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .with_config(opentelemetry_sdk::trace::Config::default().with_resource(resource()))
+            .build();
+        let subscriber = tracing_subscriber::registry().with(tracing_layer(&tracer_provider));
+
+        tracing::subscriber::with_default(subscriber, || {
+            let _certify = tracing::info_span!("certify").entered();
+            tracing::info_span!("upload_artifact").in_scope(|| {});
+        });
+
+        let spans = exporter.spans.lock().unwrap();
+        let span_names: Vec<&str> = spans.iter().map(|span| span.name.as_ref()).collect();
+        assert_eq!(vec!["upload_artifact", "certify"], span_names);
+        assert_eq!(spans[1].span_context.span_id(), spans[0].parent_span_id);
+
+        let resource = exporter.resource.lock().unwrap().clone().unwrap();
+        assert_eq!(
+            Some(SERVICE_NAME.into()),
+            resource.get(opentelemetry::Key::new("service.name"))
+        );
+    }
+}
@@ -0,0 +1,242 @@
+use anyhow::{anyhow, Context};
+use serde::Serialize;
+use slog::{debug, Logger};
+use std::sync::Arc;
+
+use mithril_common::{
+    certificate_chain::CertificateVerifier,
+    crypto_helper::ProtocolGenesisVerificationKey,
+    entities::{Certificate, Epoch},
+    logging::LoggerExtensions,
+    StdResult,
+};
+
+use crate::database::repository::CertificateRepository;
+
+/// Certificate of the chain that failed the verification.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CertificateChainBrokenLink {
+    /// Hash of the certificate.
+    pub certificate_hash: String,
+    /// Hash of the previous certificate, as stored in the certificate.
+    pub previous_hash: String,
+    /// Epoch of the certificate.
+    pub epoch: Epoch,
+    /// Reason of the verification failure.
+    pub error: String,
+}
+
+/// Report of a certificate chain audit.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CertificateChainAuditReport {
+    /// Hash of the certificate the audit started from.
+    pub head_certificate_hash: String,
+    /// Number of certificates successfully verified.
+    pub verified_certificates: usize,
+    /// Hash of the genesis certificate reached by the audit.
+    pub genesis_certificate_hash: Option<String>,
+    /// First certificate, starting from the head of the chain, that failed the verification.
+    pub broken_link: Option<CertificateChainBrokenLink>,
+}
+
+impl CertificateChainAuditReport {
+    /// Return true if the chain was verified up to its genesis certificate.
+    pub fn is_valid(&self) -> bool {
+        self.broken_link.is_none() && self.genesis_certificate_hash.is_some()
+    }
+}
+
+/// Walk the stored certificate chain back to its genesis certificate, re-verifying the hash,
+/// the multi-signature and the link to the previous certificate of each certificate.
+pub struct CertificateChainAuditor {
+    certificate_repository: Arc<CertificateRepository>,
+    certificate_verifier: Arc<dyn CertificateVerifier>,
+    genesis_verification_key: ProtocolGenesisVerificationKey,
+    logger: Logger,
+}
+
+impl CertificateChainAuditor {
+    /// Create a new instance
+    pub fn new(
+        certificate_repository: Arc<CertificateRepository>,
+        certificate_verifier: Arc<dyn CertificateVerifier>,
+        genesis_verification_key: ProtocolGenesisVerificationKey,
+        logger: Logger,
+    ) -> Self {
+        Self {
+            certificate_repository,
+            certificate_verifier,
+            genesis_verification_key,
+            logger: logger.new_with_component_name::<Self>(),
+        }
+    }
+
+    /// Audit the chain starting from the given certificate, or from the latest stored
+    /// certificate if none is given.
+    pub async fn audit(
+        &self,
+        head_certificate_hash: Option<&str>,
+    ) -> StdResult<CertificateChainAuditReport> {
+        let head_certificate = self.get_head_certificate(head_certificate_hash).await?;
+        let mut report = CertificateChainAuditReport {
+            head_certificate_hash: head_certificate.hash.clone(),
+            verified_certificates: 0,
+            genesis_certificate_hash: None,
+            broken_link: None,
+        };
+
+        let mut certificate = head_certificate;
+        loop {
+            debug!(self.logger, "Auditing certificate"; "hash" => &certificate.hash, "epoch" => ?certificate.epoch);
+            match self
+                .certificate_verifier
+                .verify_certificate(&certificate, &self.genesis_verification_key)
+                .await
+            {
+                Ok(Some(previous_certificate)) => {
+                    report.verified_certificates += 1;
+                    certificate = previous_certificate;
+                }
+                Ok(None) => {
+                    report.verified_certificates += 1;
+                    report.genesis_certificate_hash = Some(certificate.hash);
+                    break;
+                }
+                Err(error) => {
+                    report.broken_link = Some(CertificateChainBrokenLink {
+                        certificate_hash: certificate.hash,
+                        previous_hash: certificate.previous_hash,
+                        epoch: certificate.epoch,
+                        error: format!("{error:?}"),
+                    });
+                    break;
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    async fn get_head_certificate(&self, hash: Option<&str>) -> StdResult<Certificate> {
+        match hash {
+            Some(hash) => self
+                .certificate_repository
+                .get_certificate::<Certificate>(hash)
+                .await?
+                .ok_or(anyhow!("Certificate '{hash}' not found")),
+            None => self
+                .certificate_repository
+                .get_latest_certificates::<Certificate>(1)
+                .await
+                .with_context(|| "Could not get the latest certificate")?
+                .into_iter()
+                .next()
+                .ok_or(anyhow!("No certificate stored, the chain is empty")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mithril_common::certificate_chain::MithrilCertificateVerifier;
+    use mithril_common::crypto_helper::{
+        tests_setup::setup_certificate_chain, ProtocolGenesisSigner,
+    };
+
+    use crate::database::test_helper::{insert_certificate_records, main_db_connection};
+    use crate::test_tools::TestLogger;
+
+    use super::*;
+
+    fn build_auditor(certificates: Vec<Certificate>) -> CertificateChainAuditor {
+        let connection = Arc::new(main_db_connection().unwrap());
+        // Insert from the genesis so the head of the chain is the latest stored certificate
+        insert_certificate_records(&connection, certificates.into_iter().rev().collect());
+        let certificate_repository = Arc::new(CertificateRepository::new(connection));
+        let certificate_verifier = Arc::new(MithrilCertificateVerifier::new(
+            TestLogger::stdout(),
+            certificate_repository.clone(),
+        ));
+        let genesis_verification_key = ProtocolGenesisSigner::create_deterministic_genesis_signer()
+            .create_genesis_verifier()
+            .to_verification_key();
+
+        CertificateChainAuditor::new(
+            certificate_repository,
+            certificate_verifier,
+            genesis_verification_key,
+            TestLogger::stdout(),
+        )
+    }
+
+    /// Hashes of the certificates linked from the head of the chain to its genesis, as the chain
+    /// skips the certificates that are not the first of their epoch.
+    fn linked_certificate_hashes(certificates: &[Certificate]) -> Vec<String> {
+        let mut chain = vec![&certificates[0]];
+        while let Some(previous_certificate) = certificates
+            .iter()
+            .find(|c| c.hash == chain[chain.len() - 1].previous_hash)
+        {
+            chain.push(previous_certificate);
+        }
+
+        chain.into_iter().map(|c| c.hash.clone()).collect()
+    }
+
+    #[tokio::test]
+    async fn audit_valid_chain_up_to_genesis() {
+        let (certificates, _) = setup_certificate_chain(5, 2);
+        let linked_hashes = linked_certificate_hashes(&certificates);
+        let head_hash = certificates[0].hash.clone();
+        let genesis_hash = certificates.last().unwrap().hash.clone();
+        let auditor = build_auditor(certificates);
+
+        let report = auditor.audit(None).await.unwrap();
+
+        assert_eq!(
+            CertificateChainAuditReport {
+                head_certificate_hash: head_hash,
+                verified_certificates: linked_hashes.len(),
+                genesis_certificate_hash: Some(genesis_hash),
+                broken_link: None,
+            },
+            report
+        );
+        assert!(report.is_valid());
+    }
+
+    #[tokio::test]
+    async fn audit_report_first_broken_link() {
+        let (mut certificates, _) = setup_certificate_chain(5, 2);
+        let linked_hashes = linked_certificate_hashes(&certificates);
+        // Tamper a certificate in the middle of the chain without recomputing its hash
+        let tampered_hash = linked_hashes[2].clone();
+        let tampered_certificate = certificates
+            .iter_mut()
+            .find(|c| c.hash == tampered_hash)
+            .unwrap();
+        tampered_certificate.metadata.initiated_at = chrono::Utc::now();
+        let auditor = build_auditor(certificates);
+
+        let report = auditor.audit(None).await.unwrap();
+
+        assert!(!report.is_valid());
+        assert_eq!(2, report.verified_certificates);
+        assert_eq!(None, report.genesis_certificate_hash);
+        assert_eq!(
+            Some(tampered_hash),
+            report.broken_link.map(|link| link.certificate_hash)
+        );
+    }
+
+    #[tokio::test]
+    async fn audit_from_unknown_certificate_fails() {
+        let (certificates, _) = setup_certificate_chain(2, 1);
+        let auditor = build_auditor(certificates);
+
+        auditor
+            .audit(Some("unknown"))
+            .await
+            .expect_err("Audit from an unknown certificate should fail");
+    }
+}
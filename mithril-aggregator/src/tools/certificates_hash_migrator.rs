@@ -8,6 +8,23 @@ use mithril_common::{entities::Certificate, StdResult};
 
 use crate::database::repository::{CertificateRepository, SignedEntityStorer};
 
+/// Summary of the changes made, or that would be made, by a [CertificatesHashMigrator].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CertificatesHashMigrationReport {
+    /// Number of certificates in the database.
+    pub total_certificates: usize,
+    /// Old and new hash of the certificates which hash changes, from the oldest to the latest.
+    pub updated_hashes: Vec<(String, String)>,
+}
+
+/// Certificates with their recomputed hash, not yet written in the database.
+struct CertificatesHashMigrationPlan {
+    old_certificates: Vec<Certificate>,
+    migrated_certificates: Vec<Certificate>,
+    old_and_new_hashes: HashMap<String, String>,
+    report: CertificatesHashMigrationReport,
+}
+
 /// Tools to recompute all the certificates hashes in a aggregator database.
 pub struct CertificatesHashMigrator {
     certificate_repository: CertificateRepository,
@@ -30,35 +47,48 @@ impl CertificatesHashMigrator {
     }
 
     /// Recompute all the certificates hashes the database.
-    pub async fn migrate(&self) -> StdResult<()> {
+    pub async fn migrate(&self) -> StdResult<CertificatesHashMigrationReport> {
         info!(self.logger, "Starting migration");
-        let (old_certificates, old_and_new_hashes) =
-            self.create_certificates_with_updated_hash().await?;
+        let plan = self.compute_certificates_with_updated_hash().await?;
+
+        self.create_migrated_certificates(plan.migrated_certificates)
+            .await?;
 
-        self.update_signed_entities_certificate_hash(old_and_new_hashes)
+        self.update_signed_entities_certificate_hash(plan.old_and_new_hashes)
             .await?;
 
-        self.cleanup(old_certificates).await?;
+        self.cleanup(plan.old_certificates).await?;
 
         info!(
             self.logger,
             "All certificates have been migrated successfully"
         );
-        Ok(())
+        Ok(plan.report)
+    }
+
+    /// Recompute all the certificates hashes without modifying the database, returns a report
+    /// of the hashes that would be updated by [migrate][Self::migrate].
+    pub async fn dry_run(&self) -> StdResult<CertificatesHashMigrationReport> {
+        info!(self.logger, "Starting migration dry run");
+        let plan = self.compute_certificates_with_updated_hash().await?;
+
+        Ok(plan.report)
     }
 
-    /// Load all certificates from the database, compute their new hash, returns a list with
-    /// all old certificates joined with their new hash string.
-    async fn create_certificates_with_updated_hash(
+    /// Load all certificates from the database and compute their new hash, returns all old
+    /// certificates that must be replaced by their migrated version.
+    async fn compute_certificates_with_updated_hash(
         &self,
-    ) -> StdResult<(Vec<Certificate>, HashMap<String, String>)> {
+    ) -> StdResult<CertificatesHashMigrationPlan> {
         info!(self.logger, "Recomputing all certificates hash");
         let old_certificates = self
             .certificate_repository
             // arbitrary high value to get all existing certificates
             .get_latest_certificates::<Certificate>(usize::MAX)
             .await?;
+        let total_certificates = old_certificates.len();
         let mut certificates_to_remove = vec![];
+        let mut updated_hashes = vec![];
 
         let mut migrated_certificates = vec![];
         let mut old_and_new_hashes: HashMap<String, String> = HashMap::new();
@@ -89,6 +119,7 @@ impl CertificatesHashMigrator {
                 (computed_hash != certificate.hash).then_some(computed_hash)
             } {
                 old_and_new_hashes.insert(certificate.hash.clone(), new_hash.clone());
+                updated_hashes.push((certificate.hash.clone(), new_hash.clone()));
 
                 if certificate.is_genesis() {
                     trace!(
@@ -116,8 +147,23 @@ impl CertificatesHashMigrator {
             }
         }
 
-        // 2 - Certificates migrated, we can insert them in the db
-        // (we do this by chunks in order to avoid reaching the limit of 32766 variables in a single query)
+        Ok(CertificatesHashMigrationPlan {
+            old_certificates: certificates_to_remove,
+            migrated_certificates,
+            old_and_new_hashes,
+            report: CertificatesHashMigrationReport {
+                total_certificates,
+                updated_hashes,
+            },
+        })
+    }
+
+    /// Insert the migrated certificates in the database.
+    async fn create_migrated_certificates(
+        &self,
+        migrated_certificates: Vec<Certificate>,
+    ) -> StdResult<()> {
+        // Insert by chunks in order to avoid reaching the limit of 32766 variables in a single query
         debug!(
             self.logger,
             "Inserting migrated certificates in the database"
@@ -134,7 +180,7 @@ impl CertificatesHashMigrator {
             })?;
         }
 
-        Ok((certificates_to_remove, old_and_new_hashes))
+        Ok(())
     }
 
     async fn update_signed_entities_certificate_hash(
@@ -633,4 +679,56 @@ mod test {
             .await
             .expect("Migration should not fail if a hash doesn't change");
     }
+
+    #[tokio::test]
+    async fn dry_run_report_updated_hashes_without_modifying_the_database() {
+        let connection = Arc::new(connection_without_foreign_key_support());
+        let certificates = vec![
+            dummy_genesis("genesis", time_at(1, 1)),
+            dummy_certificate(
+                "cert1",
+                "genesis",
+                time_at(1, 2),
+                Type::MithrilStakeDistribution,
+            ),
+        ];
+        let old_certificates =
+            fill_certificates_and_signed_entities_in_db(connection.clone(), &certificates)
+                .await
+                .unwrap();
+        let expected_hashes: Vec<(String, String)> = certificates
+            .iter()
+            .map(|c| c.hash.clone())
+            .zip(
+                recompute_hashes(old_certificates)
+                    .into_iter()
+                    .map(|(c, _)| c.hash),
+            )
+            .collect();
+
+        let migrator = CertificatesHashMigrator::new(
+            CertificateRepository::new(connection.clone()),
+            Arc::new(SignedEntityStore::new(connection.clone())),
+            TestLogger::stdout(),
+        );
+        let report = migrator.dry_run().await.unwrap();
+
+        assert_eq!(
+            CertificatesHashMigrationReport {
+                total_certificates: 2,
+                updated_hashes: expected_hashes,
+            },
+            report
+        );
+        let certificates_in_db: Vec<String> = get_certificates_and_signed_entities(connection)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|(c, _)| c.hash)
+            .collect();
+        assert_eq!(
+            vec!["cert1".to_string(), "genesis".to_string()],
+            certificates_in_db
+        );
+    }
 }
@@ -1,11 +1,17 @@
-use anyhow::anyhow;
+use anyhow::{anyhow, Context};
 use mithril_common::{
-    chain_observer::{TxDatumBuilder, TxDatumFieldValue},
-    crypto_helper::EraMarkersSigner,
+    chain_observer::{TxDatum, TxDatumBuilder, TxDatumFieldValue},
+    crypto_helper::{EraMarkersSigner, EraMarkersVerifierVerificationKey},
     entities::Epoch,
-    era::{adapters::EraMarkersPayloadCardanoChain, EraMarker, SupportedEra},
+    era::{
+        adapters::{EraMarkersPayloadCardanoChain, EraMarkersRotatedVerificationKey},
+        EraMarker, SupportedEra,
+    },
     StdResult,
 };
+use std::{fs::OpenOptions, io::Write, os::unix::fs::OpenOptionsExt, path::Path};
+
+use crate::tools::KeySigner;
 
 type EraToolsResult<R> = StdResult<R>;
 
@@ -22,12 +28,12 @@ impl EraTools {
     }
 
     /// Generate TxDatum for eras with sanity check of epochs
-    pub fn generate_tx_datum(
+    pub async fn generate_tx_datum(
         &self,
         current_era_epoch: Epoch,
         maybe_next_era_epoch: Option<Epoch>,
-        era_markers_signer: &EraMarkersSigner,
-    ) -> EraToolsResult<String> {
+        era_markers_signer: &dyn KeySigner,
+    ) -> EraToolsResult<TxDatum> {
         if maybe_next_era_epoch.is_some()
             && maybe_next_era_epoch.unwrap_or_default() <= current_era_epoch
         {
@@ -48,14 +54,79 @@ impl EraTools {
         let era_markers_payload = EraMarkersPayloadCardanoChain {
             markers: era_markers,
             signature: None,
-        }
-        .sign(era_markers_signer)?;
+        };
+        let signature = era_markers_signer
+            .sign(&era_markers_payload.message_to_sign()?)
+            .await
+            .with_context(|| "Could not sign the era markers")?;
+        let era_markers_payload = era_markers_payload.with_signature(signature.into());
 
         let tx_datum = TxDatumBuilder::new()
             .add_field(TxDatumFieldValue::Bytes(era_markers_payload.to_json_hex()?))
             .build()?;
-        Ok(tx_datum.0)
+        Ok(tx_datum)
     }
+
+    /// Generate a new era markers key pair replacing the current one, the current verification
+    /// key is still accepted by the `cardano-chain` era reader adapter for `overlap_epochs`
+    /// epochs after `current_epoch`.
+    ///
+    /// The new secret and verification keys are written in the target directory, along with the
+    /// rotated verification keys to add to the `rotated_verification_keys` field of the era
+    /// reader adapter params. The files are only readable by their owner and the rotation fails
+    /// if they already exist, so a previously generated secret key is never lost.
+    pub fn rotate_keys(
+        &self,
+        current_verification_key: EraMarkersVerifierVerificationKey,
+        current_epoch: Epoch,
+        overlap_epochs: u64,
+        target_dir: &Path,
+    ) -> EraToolsResult<EraMarkersRotatedVerificationKey> {
+        let rotated_verification_key = EraMarkersRotatedVerificationKey {
+            verification_key: current_verification_key,
+            valid_until_epoch: current_epoch + overlap_epochs,
+        };
+        let era_markers_signer = EraMarkersSigner::create_non_deterministic_signer();
+
+        std::fs::create_dir_all(target_dir)?;
+        write_file(
+            &target_dir.join(Self::SECRET_KEY_FILENAME),
+            &era_markers_signer.to_secret_key().to_json_hex()?,
+        )?;
+        write_file(
+            &target_dir.join(Self::VERIFICATION_KEY_FILENAME),
+            &era_markers_signer
+                .create_verifier()
+                .to_verification_key()
+                .to_json_hex()?,
+        )?;
+        write_file(
+            &target_dir.join(Self::ROTATED_VERIFICATION_KEYS_FILENAME),
+            &serde_json::to_string(&vec![&rotated_verification_key])?,
+        )?;
+
+        Ok(rotated_verification_key)
+    }
+
+    /// Name of the file of the new era markers secret key.
+    pub const SECRET_KEY_FILENAME: &'static str = "era.sk";
+
+    /// Name of the file of the new era markers verification key.
+    pub const VERIFICATION_KEY_FILENAME: &'static str = "era.vk";
+
+    /// Name of the file of the rotated verification keys.
+    pub const ROTATED_VERIFICATION_KEYS_FILENAME: &'static str =
+        "era-rotated-verification-keys.json";
+}
+
+fn write_file(path: &Path, content: &str) -> StdResult<()> {
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)
+        .and_then(|mut file| file.write_all(content.as_bytes()))
+        .with_context(|| format!("Could not write '{}'", path.display()))
 }
 
 #[cfg(test)]
@@ -75,21 +146,117 @@ mod tests {
         assert_eq!(supported_eras_list, SupportedEra::eras());
     }
 
-    #[test]
-    fn generate_tx_datum_ok() {
+    #[tokio::test]
+    async fn generate_tx_datum_ok() {
         let era_markers_signer = EraMarkersSigner::create_deterministic_signer();
         let era_tools = build_tools();
         let _ = era_tools
             .generate_tx_datum(Epoch(1), None, &era_markers_signer)
+            .await
             .expect("generate_tx_datum should not fail");
     }
 
-    #[test]
-    fn generate_tx_datum_wrong_epochs() {
+    #[tokio::test]
+    async fn generate_tx_datum_wrong_epochs() {
         let era_markers_signer = EraMarkersSigner::create_deterministic_signer();
         let era_tools = build_tools();
         let _ = era_tools
             .generate_tx_datum(Epoch(3), Some(Epoch(2)), &era_markers_signer)
+            .await
             .expect_err("generate_tx_datum should have failed");
     }
+
+    #[tokio::test]
+    async fn generate_tx_datum_can_be_encoded_to_cbor() {
+        let era_markers_signer = EraMarkersSigner::create_deterministic_signer();
+        let era_tools = build_tools();
+        let tx_datum = era_tools
+            .generate_tx_datum(Epoch(1), Some(Epoch(2)), &era_markers_signer)
+            .await
+            .unwrap();
+
+        let cbor = tx_datum
+            .to_cbor()
+            .expect("tx datum CBOR encoding should not fail");
+
+        // CBOR tag 121: first constructor of a Plutus data
+        assert_eq!([0xd8, 0x79], cbor[0..2]);
+    }
+
+    #[test]
+    fn rotate_keys_keeps_the_current_verification_key_during_the_overlap_period() {
+        let target_dir = std::env::temp_dir()
+            .join("mithril_test")
+            .join("era_tools")
+            .join("rotate_keys");
+        if target_dir.exists() {
+            std::fs::remove_dir_all(&target_dir).unwrap();
+        }
+        let current_verification_key = EraMarkersSigner::create_deterministic_signer()
+            .create_verifier()
+            .to_verification_key();
+        let era_tools = build_tools();
+
+        let rotated_verification_key = era_tools
+            .rotate_keys(current_verification_key, Epoch(10), 3, &target_dir)
+            .expect("rotate_keys should not fail");
+
+        assert_eq!(
+            EraMarkersRotatedVerificationKey {
+                verification_key: current_verification_key,
+                valid_until_epoch: Epoch(13),
+            },
+            rotated_verification_key
+        );
+        let new_verification_key = EraMarkersVerifierVerificationKey::from_json_hex(
+            &std::fs::read_to_string(target_dir.join(EraTools::VERIFICATION_KEY_FILENAME)).unwrap(),
+        )
+        .unwrap();
+        assert_ne!(current_verification_key, new_verification_key);
+        let rotated_verification_keys: Vec<EraMarkersRotatedVerificationKey> =
+            serde_json::from_str(
+                &std::fs::read_to_string(
+                    target_dir.join(EraTools::ROTATED_VERIFICATION_KEYS_FILENAME),
+                )
+                .unwrap(),
+            )
+            .unwrap();
+        assert_eq!(vec![rotated_verification_key], rotated_verification_keys);
+    }
+
+    #[test]
+    fn rotate_keys_does_not_overwrite_an_existing_secret_key() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let target_dir = std::env::temp_dir()
+            .join("mithril_test")
+            .join("era_tools")
+            .join("rotate_keys_does_not_overwrite");
+        if target_dir.exists() {
+            std::fs::remove_dir_all(&target_dir).unwrap();
+        }
+        let current_verification_key = EraMarkersSigner::create_deterministic_signer()
+            .create_verifier()
+            .to_verification_key();
+        let era_tools = build_tools();
+        era_tools
+            .rotate_keys(current_verification_key, Epoch(10), 3, &target_dir)
+            .expect("rotate_keys should not fail");
+        let secret_key_path = target_dir.join(EraTools::SECRET_KEY_FILENAME);
+        let secret_key = std::fs::read_to_string(&secret_key_path).unwrap();
+
+        era_tools
+            .rotate_keys(current_verification_key, Epoch(10), 3, &target_dir)
+            .expect_err("rotate_keys should fail if the secret key already exists");
+
+        assert_eq!(
+            secret_key,
+            std::fs::read_to_string(&secret_key_path).unwrap()
+        );
+        let secret_key_mode = std::fs::metadata(&secret_key_path)
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(0o600, secret_key_mode & 0o777);
+    }
 }
@@ -1,5 +1,13 @@
 use anyhow::{anyhow, Context};
-use std::{fs::File, io::prelude::*, io::Write, path::Path, sync::Arc};
+use std::{
+    fs,
+    fs::{File, OpenOptions},
+    io::prelude::*,
+    io::Write,
+    os::unix::fs::OpenOptionsExt,
+    path::Path,
+    sync::Arc,
+};
 
 use mithril_common::{
     certificate_chain::{CertificateGenesisProducer, CertificateVerifier},
@@ -13,8 +21,10 @@ use mithril_common::{
 };
 
 use crate::database::repository::CertificateRepository;
+use crate::tools::KeySigner;
 use crate::{EpochSettingsStorer, VerificationKeyStorer};
 
+#[derive(Clone)]
 pub struct GenesisToolsDependency {
     /// Cardano network
     pub network: CardanoNetwork,
@@ -144,13 +154,55 @@ impl GenesisTools {
             .await
     }
 
-    /// Sign the genesis certificate
-    pub async fn sign_genesis_certificate(
-        to_sign_payload_path: &Path,
-        target_signed_payload_path: &Path,
-        genesis_secret_key_path: &Path,
-    ) -> StdResult<()> {
-        let mut genesis_secret_key_file = File::open(genesis_secret_key_path).unwrap();
+    /// Generate a new pair of genesis keys and write them, unencrypted, in the `genesis.sk` and
+    /// `genesis.vk` files of the target directory (private networks only)
+    ///
+    /// The files are only readable by their owner, and existing keys are never overwritten.
+    pub fn generate_unsafe_genesis_keys(target_path: &Path) -> StdResult<ProtocolGenesisSigner> {
+        let secret_key_path = target_path.join("genesis.sk");
+        let verification_key_path = target_path.join("genesis.vk");
+        if let Some(existing_path) = [&secret_key_path, &verification_key_path]
+            .into_iter()
+            .find(|path| path.exists())
+        {
+            return Err(anyhow!(
+                "The genesis key file '{}' already exists, it is never overwritten",
+                existing_path.display()
+            ));
+        }
+        fs::create_dir_all(target_path).with_context(|| {
+            format!(
+                "Could not create the genesis keys directory '{}'",
+                target_path.display()
+            )
+        })?;
+        let genesis_signer = ProtocolGenesisSigner::create_non_deterministic_genesis_signer();
+        write_key_file(
+            &secret_key_path,
+            &genesis_signer.to_secret_key().to_json_hex()?,
+        )
+        .with_context(|| "Could not write the genesis secret key")?;
+        write_key_file(
+            &verification_key_path,
+            &genesis_signer
+                .create_genesis_verifier()
+                .to_verification_key()
+                .to_json_hex()?,
+        )
+        .with_context(|| "Could not write the genesis verification key")?;
+
+        Ok(genesis_signer)
+    }
+
+    /// Load the genesis signer from its secret key file
+    pub fn load_genesis_signer(genesis_secret_key_path: &Path) -> StdResult<ProtocolGenesisSigner> {
+        let mut genesis_secret_key_file =
+            File::open(genesis_secret_key_path).with_context(|| {
+                format!(
+                    "Could not open the genesis secret key file '{}'",
+                    genesis_secret_key_path.display()
+                )
+            })?;
         let mut genesis_secret_key_serialized = String::new();
         genesis_secret_key_file.read_to_string(&mut genesis_secret_key_serialized)?;
 
@@ -158,13 +210,21 @@ impl GenesisTools {
             .trim()
             .try_into()
             .with_context(|| "Genesis secret key decode error")?;
-        let genesis_signer = ProtocolGenesisSigner::from_secret_key(genesis_secret_key);
 
+        Ok(ProtocolGenesisSigner::from_secret_key(genesis_secret_key))
+    }
+
+    /// Sign the genesis certificate
+    pub async fn sign_genesis_certificate(
+        to_sign_payload_path: &Path,
+        target_signed_payload_path: &Path,
+        genesis_signer: &dyn KeySigner,
+    ) -> StdResult<()> {
         let mut to_sign_payload_file = File::open(to_sign_payload_path).unwrap();
         let mut to_sign_payload_buffer = Vec::new();
         to_sign_payload_file.read_to_end(&mut to_sign_payload_buffer)?;
 
-        let genesis_signature = genesis_signer.sign(&to_sign_payload_buffer);
+        let genesis_signature = genesis_signer.sign(&to_sign_payload_buffer).await?;
         let signed_payload = genesis_signature.to_bytes();
 
         let mut target_signed_payload_file = File::create(target_signed_payload_path)?;
@@ -203,6 +263,16 @@ impl GenesisTools {
     }
 }
 
+fn write_key_file(path: &Path, content: &str) -> StdResult<()> {
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)
+        .and_then(|mut file| file.write_all(content.as_bytes()))
+        .with_context(|| format!("Could not write '{}'", path.display()))
+}
+
 #[cfg(test)]
 mod tests {
     use mithril_common::{
@@ -280,7 +350,8 @@ mod tests {
         GenesisTools::sign_genesis_certificate(
             &payload_path,
             &signed_payload_path,
-            &genesis_secret_key_path,
+            &GenesisTools::load_genesis_signer(&genesis_secret_key_path)
+                .expect("loading the genesis signer should not fail"),
         )
         .await
         .expect("sign_genesis_certificate should not fail");
@@ -303,6 +374,65 @@ mod tests {
             );
     }
 
+    #[test]
+    fn generate_unsafe_genesis_keys_writes_a_matching_key_pair() {
+        let test_dir = get_temp_dir("generate_unsafe_genesis_keys");
+
+        let genesis_signer = GenesisTools::generate_unsafe_genesis_keys(&test_dir).unwrap();
+
+        let loaded_signer =
+            GenesisTools::load_genesis_signer(&test_dir.join("genesis.sk")).unwrap();
+        let verification_key = genesis_signer
+            .create_genesis_verifier()
+            .to_verification_key();
+        assert_eq!(
+            verification_key,
+            loaded_signer
+                .create_genesis_verifier()
+                .to_verification_key()
+        );
+        assert_eq!(
+            verification_key.to_json_hex().unwrap(),
+            fs::read_to_string(test_dir.join("genesis.vk")).unwrap()
+        );
+    }
+
+    #[test]
+    fn generate_unsafe_genesis_keys_writes_files_only_readable_by_their_owner() {
+        use std::os::unix::fs::PermissionsExt;
+        let test_dir = get_temp_dir("generate_unsafe_genesis_keys_permissions");
+
+        GenesisTools::generate_unsafe_genesis_keys(&test_dir).unwrap();
+
+        for file in ["genesis.sk", "genesis.vk"] {
+            let mode = fs::metadata(test_dir.join(file))
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(
+                0o600,
+                mode & 0o777,
+                "{file} should only be readable by its owner"
+            );
+        }
+    }
+
+    #[test]
+    fn generate_unsafe_genesis_keys_does_not_overwrite_existing_keys() {
+        let test_dir = get_temp_dir("generate_unsafe_genesis_keys_no_overwrite");
+        fs::create_dir_all(&test_dir).unwrap();
+        fs::write(test_dir.join("genesis.sk"), "existing secret key").unwrap();
+
+        GenesisTools::generate_unsafe_genesis_keys(&test_dir)
+            .expect_err("generating the keys over an existing secret key should fail");
+
+        assert_eq!(
+            "existing secret key",
+            fs::read_to_string(test_dir.join("genesis.sk")).unwrap()
+        );
+        assert!(!test_dir.join("genesis.vk").exists());
+    }
+
     #[tokio::test]
     async fn bootstrap_test_genesis_certificate_works() {
         let genesis_signer = ProtocolGenesisSigner::create_deterministic_genesis_signer();
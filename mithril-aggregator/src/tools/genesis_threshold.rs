@@ -0,0 +1,414 @@
+use anyhow::{anyhow, Context};
+use frost_ed25519 as frost;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs::{File, OpenOptions},
+    io::{Read, Write},
+    os::unix::fs::OpenOptionsExt,
+    path::{Path, PathBuf},
+};
+
+use mithril_common::{
+    crypto_helper::{ProtocolGenesisSignature, ProtocolGenesisVerificationKey},
+    StdResult,
+};
+
+/// Commitments published by a genesis keyholder during the first round of a threshold signature.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenesisThresholdCommitments {
+    /// Identifier of the keyholder
+    pub identifier: frost::Identifier,
+    /// Commitments to the nonces of the keyholder
+    pub commitments: frost::round1::SigningCommitments,
+}
+
+/// Signature share produced by a genesis keyholder during the second round of a threshold signature.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenesisThresholdSignatureShare {
+    /// Identifier of the keyholder
+    pub identifier: frost::Identifier,
+    /// Share of the genesis signature
+    pub signature_share: frost::round2::SignatureShare,
+}
+
+/// Tools to sign the genesis certificate with a M-of-N threshold of genesis keyholders.
+///
+/// The keyholders sign the exported genesis payload offline using the two rounds FROST protocol,
+/// the signature shares are then aggregated in a standard Ed25519 signature that is verified by
+/// the genesis verification key of the group and can be imported with the `genesis import`
+/// command. No single keyholder can sign the genesis certificate alone.
+///
+/// The keys are generated by a trusted dealer: the machine running [Self::generate_keys] holds all
+/// the key packages until they are distributed, so it must be an offline machine trusted by all
+/// the keyholders, and the key packages must be removed from it once distributed. A distributed
+/// key generation, where no party ever holds the whole genesis secret key, is not supported.
+///
+/// The files written by these tools are only readable by their owner and are never overwritten, so
+/// a key package or a nonces file can not be lost or reused by running a command twice.
+pub struct GenesisThresholdTools;
+
+impl GenesisThresholdTools {
+    /// Name of the file of the genesis verification key of the group.
+    pub const GENESIS_VERIFICATION_KEY_FILENAME: &'static str = "genesis.vkey";
+
+    /// Name of the file of the public key package of the group.
+    pub const PUBLIC_KEY_PACKAGE_FILENAME: &'static str = "genesis-public-key-package.json";
+
+    /// Generate the key packages of `max_signers` genesis keyholders with a signing threshold of
+    /// `min_signers`, returns the genesis verification key of the group.
+    ///
+    /// The key packages must be distributed to the keyholders and removed from the target
+    /// directory.
+    pub fn generate_keys(
+        min_signers: u16,
+        max_signers: u16,
+        target_dir: &Path,
+    ) -> StdResult<ProtocolGenesisVerificationKey> {
+        let (secret_shares, public_key_package) = frost::keys::generate_with_dealer(
+            max_signers,
+            min_signers,
+            frost::keys::IdentifierList::Default,
+            rand_core::OsRng,
+        )
+        .map_err(|e| anyhow!(e))
+        .with_context(|| "Could not generate the genesis keyholders keys")?;
+
+        std::fs::create_dir_all(target_dir)?;
+        for (index, secret_share) in secret_shares.into_values().enumerate() {
+            let key_package = frost::keys::KeyPackage::try_from(secret_share)
+                .map_err(|e| anyhow!(e))
+                .with_context(|| "Could not build a genesis keyholder key package")?;
+            write_json(
+                &target_dir.join(Self::key_package_filename(index + 1)),
+                &key_package,
+            )?;
+        }
+        write_json(
+            &target_dir.join(Self::PUBLIC_KEY_PACKAGE_FILENAME),
+            &public_key_package,
+        )?;
+
+        let genesis_verification_key = Self::genesis_verification_key(&public_key_package)?;
+        let mut genesis_verification_key_file =
+            File::create(target_dir.join(Self::GENESIS_VERIFICATION_KEY_FILENAME))?;
+        genesis_verification_key_file
+            .write_all(genesis_verification_key.to_json_hex()?.as_bytes())?;
+
+        Ok(genesis_verification_key)
+    }
+
+    /// Name of the file of the key package of the keyholder with the given number.
+    pub fn key_package_filename(keyholder_number: usize) -> String {
+        format!("genesis-keyholder-{keyholder_number}.key-package.json")
+    }
+
+    /// First round: generate the nonces of a keyholder (kept secret) and their commitments
+    /// (shared with the other keyholders).
+    pub fn commit(
+        key_package_path: &Path,
+        target_nonces_path: &Path,
+        target_commitments_path: &Path,
+    ) -> StdResult<()> {
+        let key_package: frost::keys::KeyPackage = read_json(key_package_path)?;
+        let mut rng = rand_core::OsRng;
+        let (nonces, commitments) = frost::round1::commit(key_package.signing_share(), &mut rng);
+
+        write_json(target_nonces_path, &nonces)?;
+        write_json(
+            target_commitments_path,
+            &GenesisThresholdCommitments {
+                identifier: *key_package.identifier(),
+                commitments,
+            },
+        )?;
+
+        Ok(())
+    }
+
+    /// Second round: sign the genesis payload with the key package and nonces of a keyholder.
+    ///
+    /// The nonces file is deleted once used since reusing nonces would leak the keyholder secret.
+    pub fn sign(
+        to_sign_payload_path: &Path,
+        commitments_paths: &[PathBuf],
+        key_package_path: &Path,
+        nonces_path: &Path,
+        target_signature_share_path: &Path,
+    ) -> StdResult<()> {
+        let signing_package = Self::signing_package(to_sign_payload_path, commitments_paths)?;
+        let key_package: frost::keys::KeyPackage = read_json(key_package_path)?;
+        let nonces: frost::round1::SigningNonces = read_json(nonces_path)?;
+
+        let signature_share = frost::round2::sign(&signing_package, &nonces, &key_package)
+            .map_err(|e| anyhow!(e))
+            .with_context(|| "Could not sign the genesis payload")?;
+        std::fs::remove_file(nonces_path).with_context(|| {
+            format!(
+                "Could not delete the used nonces file: '{}'",
+                nonces_path.display()
+            )
+        })?;
+
+        write_json(
+            target_signature_share_path,
+            &GenesisThresholdSignatureShare {
+                identifier: *key_package.identifier(),
+                signature_share,
+            },
+        )
+    }
+
+    /// Aggregate the signature shares of the keyholders in a genesis signature, written in the
+    /// same format as the `genesis sign` command.
+    pub fn aggregate(
+        to_sign_payload_path: &Path,
+        commitments_paths: &[PathBuf],
+        signature_share_paths: &[PathBuf],
+        public_key_package_path: &Path,
+        target_signed_payload_path: &Path,
+    ) -> StdResult<ProtocolGenesisSignature> {
+        let signing_package = Self::signing_package(to_sign_payload_path, commitments_paths)?;
+        let public_key_package: frost::keys::PublicKeyPackage = read_json(public_key_package_path)?;
+        let signature_shares = signature_share_paths
+            .iter()
+            .map(|path| {
+                let share: GenesisThresholdSignatureShare = read_json(path)?;
+                Ok((share.identifier, share.signature_share))
+            })
+            .collect::<StdResult<BTreeMap<_, _>>>()?;
+
+        let signature = frost::aggregate(&signing_package, &signature_shares, &public_key_package)
+            .map_err(|e| anyhow!(e))
+            .with_context(|| "Could not aggregate the genesis signature shares")?;
+        let genesis_signature =
+            ProtocolGenesisSignature::from_bytes(&signature.serialize().map_err(|e| anyhow!(e))?)?;
+        Self::genesis_verification_key(&public_key_package)?
+            .verify(signing_package.message(), &genesis_signature)
+            .with_context(|| "The aggregated genesis signature is invalid")?;
+
+        let mut target_signed_payload_file = File::create(target_signed_payload_path)?;
+        target_signed_payload_file.write_all(&genesis_signature.to_bytes())?;
+
+        Ok(genesis_signature)
+    }
+
+    fn signing_package(
+        to_sign_payload_path: &Path,
+        commitments_paths: &[PathBuf],
+    ) -> StdResult<frost::SigningPackage> {
+        let mut to_sign_payload_file = File::open(to_sign_payload_path).with_context(|| {
+            format!(
+                "Could not open the genesis payload to sign: '{}'",
+                to_sign_payload_path.display()
+            )
+        })?;
+        let mut to_sign_payload_buffer = Vec::new();
+        to_sign_payload_file.read_to_end(&mut to_sign_payload_buffer)?;
+
+        let commitments = commitments_paths
+            .iter()
+            .map(|path| {
+                let commitments: GenesisThresholdCommitments = read_json(path)?;
+                Ok((commitments.identifier, commitments.commitments))
+            })
+            .collect::<StdResult<BTreeMap<_, _>>>()?;
+
+        Ok(frost::SigningPackage::new(
+            commitments,
+            &to_sign_payload_buffer,
+        ))
+    }
+
+    fn genesis_verification_key(
+        public_key_package: &frost::keys::PublicKeyPackage,
+    ) -> StdResult<ProtocolGenesisVerificationKey> {
+        let verifying_key = public_key_package
+            .verifying_key()
+            .serialize()
+            .map_err(|e| anyhow!(e))?;
+
+        ProtocolGenesisVerificationKey::from_bytes(&verifying_key)
+    }
+}
+
+fn read_json<T: DeserializeOwned>(path: &Path) -> StdResult<T> {
+    let file =
+        File::open(path).with_context(|| format!("Could not open file: '{}'", path.display()))?;
+
+    serde_json::from_reader(file)
+        .with_context(|| format!("Could not deserialize file: '{}'", path.display()))
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> StdResult<()> {
+    let file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)
+        .with_context(|| format!("Could not create file: '{}'", path.display()))?;
+
+    serde_json::to_writer(file, value)
+        .with_context(|| format!("Could not serialize file: '{}'", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use mithril_common::crypto_helper::ProtocolGenesisVerifier;
+    use mithril_common::test_utils::TempDir;
+
+    use super::*;
+
+    /// Run the two rounds for the given keyholders, returns the commitments and signature
+    /// shares paths.
+    fn sign_with_keyholders(
+        dir: &Path,
+        payload_path: &Path,
+        keyholders: &[usize],
+    ) -> (Vec<PathBuf>, Vec<PathBuf>) {
+        let key_package_path =
+            |keyholder| dir.join(GenesisThresholdTools::key_package_filename(keyholder));
+        let nonces_path = |keyholder| dir.join(format!("nonces-{keyholder}.json"));
+        let commitments_paths: Vec<PathBuf> = keyholders
+            .iter()
+            .map(|keyholder| dir.join(format!("commitments-{keyholder}.json")))
+            .collect();
+        let signature_share_paths: Vec<PathBuf> = keyholders
+            .iter()
+            .map(|keyholder| dir.join(format!("signature-share-{keyholder}.json")))
+            .collect();
+
+        for (keyholder, commitments_path) in keyholders.iter().zip(&commitments_paths) {
+            GenesisThresholdTools::commit(
+                &key_package_path(*keyholder),
+                &nonces_path(*keyholder),
+                commitments_path,
+            )
+            .unwrap();
+        }
+        for (keyholder, signature_share_path) in keyholders.iter().zip(&signature_share_paths) {
+            GenesisThresholdTools::sign(
+                payload_path,
+                &commitments_paths,
+                &key_package_path(*keyholder),
+                &nonces_path(*keyholder),
+                signature_share_path,
+            )
+            .unwrap();
+        }
+
+        (commitments_paths, signature_share_paths)
+    }
+
+    #[test]
+    fn threshold_of_keyholders_produce_a_valid_genesis_signature() {
+        let dir = TempDir::create(
+            "genesis_threshold",
+            "threshold_of_keyholders_produce_a_valid_genesis_signature",
+        );
+        let payload_path = dir.join("payload-to-sign");
+        std::fs::write(&payload_path, "genesis-payload-hash").unwrap();
+        let genesis_verification_key = GenesisThresholdTools::generate_keys(2, 3, &dir).unwrap();
+
+        let (commitments_paths, signature_share_paths) =
+            sign_with_keyholders(&dir, &payload_path, &[1, 3]);
+        let signed_payload_path = dir.join("signed-payload");
+        let genesis_signature = GenesisThresholdTools::aggregate(
+            &payload_path,
+            &commitments_paths,
+            &signature_share_paths,
+            &dir.join(GenesisThresholdTools::PUBLIC_KEY_PACKAGE_FILENAME),
+            &signed_payload_path,
+        )
+        .unwrap();
+
+        ProtocolGenesisVerifier::from_verification_key(genesis_verification_key)
+            .verify(b"genesis-payload-hash", &genesis_signature)
+            .expect("The aggregated genesis signature should be valid");
+        let signed_payload = std::fs::read(&signed_payload_path).unwrap();
+        assert_eq!(
+            genesis_signature,
+            ProtocolGenesisSignature::from_bytes(&signed_payload).unwrap()
+        );
+    }
+
+    #[test]
+    fn aggregation_fails_below_the_threshold() {
+        let dir = TempDir::create("genesis_threshold", "aggregation_fails_below_the_threshold");
+        let payload_path = dir.join("payload-to-sign");
+        std::fs::write(&payload_path, "genesis-payload-hash").unwrap();
+        GenesisThresholdTools::generate_keys(2, 3, &dir).unwrap();
+
+        let (commitments_paths, signature_share_paths) =
+            sign_with_keyholders(&dir, &payload_path, &[1, 2]);
+
+        GenesisThresholdTools::aggregate(
+            &payload_path,
+            &commitments_paths,
+            &signature_share_paths[0..1],
+            &dir.join(GenesisThresholdTools::PUBLIC_KEY_PACKAGE_FILENAME),
+            &dir.join("signed-payload"),
+        )
+        .expect_err("Aggregation should fail with less signature shares than the threshold");
+    }
+
+    #[test]
+    fn nonces_are_deleted_once_used() {
+        let dir = TempDir::create("genesis_threshold", "nonces_are_deleted_once_used");
+        let payload_path = dir.join("payload-to-sign");
+        std::fs::write(&payload_path, "genesis-payload-hash").unwrap();
+        GenesisThresholdTools::generate_keys(2, 2, &dir).unwrap();
+
+        sign_with_keyholders(&dir, &payload_path, &[1, 2]);
+
+        assert!(!dir.join("nonces-1.json").exists());
+        assert!(!dir.join("nonces-2.json").exists());
+    }
+
+    #[test]
+    fn key_packages_are_only_readable_by_their_owner() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::create(
+            "genesis_threshold",
+            "key_packages_are_only_readable_by_their_owner",
+        );
+        GenesisThresholdTools::generate_keys(2, 2, &dir).unwrap();
+
+        let key_package_mode =
+            std::fs::metadata(dir.join(GenesisThresholdTools::key_package_filename(1)))
+                .unwrap()
+                .permissions()
+                .mode();
+        assert_eq!(0o600, key_package_mode & 0o777);
+    }
+
+    #[test]
+    fn existing_key_packages_and_nonces_are_never_overwritten() {
+        let dir = TempDir::create(
+            "genesis_threshold",
+            "existing_key_packages_and_nonces_are_never_overwritten",
+        );
+        GenesisThresholdTools::generate_keys(2, 2, &dir).unwrap();
+        let key_package_path = dir.join(GenesisThresholdTools::key_package_filename(1));
+        let key_package = std::fs::read(&key_package_path).unwrap();
+
+        GenesisThresholdTools::generate_keys(2, 2, &dir)
+            .expect_err("Generating the keys should fail if the key packages already exist");
+        assert_eq!(key_package, std::fs::read(&key_package_path).unwrap());
+
+        let nonces_path = dir.join("nonces-1.json");
+        GenesisThresholdTools::commit(
+            &key_package_path,
+            &nonces_path,
+            &dir.join("commitments-1.json"),
+        )
+        .unwrap();
+        GenesisThresholdTools::commit(
+            &key_package_path,
+            &nonces_path,
+            &dir.join("commitments-1-bis.json"),
+        )
+        .expect_err("Committing should fail if the nonces of a previous commitment exist");
+    }
+}
@@ -0,0 +1,180 @@
+use anyhow::{anyhow, Context};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use mithril_common::crypto_helper::{EraMarkersSigner, ProtocolGenesisSigner};
+use mithril_common::StdResult;
+
+/// KeySigner signs the genesis certificates and the era markers with an Ed25519 secret key,
+/// that is either held in memory or by a key management service.
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait KeySigner: Sync + Send {
+    /// Sign a message
+    async fn sign(&self, message: &[u8]) -> StdResult<ed25519_dalek::Signature>;
+}
+
+#[async_trait]
+impl KeySigner for ProtocolGenesisSigner {
+    async fn sign(&self, message: &[u8]) -> StdResult<ed25519_dalek::Signature> {
+        Ok(ProtocolGenesisSigner::sign(self, message).into())
+    }
+}
+
+#[async_trait]
+impl KeySigner for EraMarkersSigner {
+    async fn sign(&self, message: &[u8]) -> StdResult<ed25519_dalek::Signature> {
+        Ok(EraMarkersSigner::sign(self, message).into())
+    }
+}
+
+/// Key signer delegating the signatures to an Ed25519 key of Google Cloud KMS, so that the
+/// secret key never leaves the key management service.
+pub struct GcpKmsKeySigner {
+    key_version_name: String,
+    access_token: String,
+    endpoint: String,
+    http_client: reqwest::Client,
+}
+
+#[derive(Serialize)]
+struct AsymmetricSignRequest {
+    data: String,
+}
+
+#[derive(Deserialize)]
+struct AsymmetricSignResponse {
+    signature: String,
+}
+
+impl GcpKmsKeySigner {
+    const ENDPOINT: &'static str = "https://cloudkms.googleapis.com";
+    const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+    /// GcpKmsKeySigner factory
+    ///
+    /// The key version name has the form
+    /// `projects/*/locations/*/keyRings/*/cryptoKeys/*/cryptoKeyVersions/*` and the access token
+    /// is an OAuth2 token allowed to sign with this key version.
+    pub fn new(key_version_name: &str, access_token: &str) -> StdResult<Self> {
+        let http_client = reqwest::Client::builder()
+            .timeout(Self::REQUEST_TIMEOUT)
+            .build()
+            .with_context(|| "Could not build the key management service HTTP client")?;
+
+        Ok(Self {
+            key_version_name: key_version_name.to_string(),
+            access_token: access_token.to_string(),
+            endpoint: Self::ENDPOINT.to_string(),
+            http_client,
+        })
+    }
+
+    #[cfg(test)]
+    fn with_endpoint(mut self, endpoint: &str) -> Self {
+        self.endpoint = endpoint.to_string();
+        self
+    }
+}
+
+#[async_trait]
+impl KeySigner for GcpKmsKeySigner {
+    async fn sign(&self, message: &[u8]) -> StdResult<ed25519_dalek::Signature> {
+        let url = format!(
+            "{}/v1/{}:asymmetricSign",
+            self.endpoint, self.key_version_name
+        );
+        let response = self
+            .http_client
+            .post(&url)
+            .bearer_auth(&self.access_token)
+            .json(&AsymmetricSignRequest {
+                data: STANDARD.encode(message),
+            })
+            .send()
+            .await
+            .with_context(|| format!("Could not reach the key management service at '{url}'"))?;
+
+        match response.status() {
+            StatusCode::OK => {
+                let response: AsymmetricSignResponse = response
+                    .json()
+                    .await
+                    .with_context(|| "Could not parse the key management service response")?;
+                let signature = STANDARD
+                    .decode(response.signature)
+                    .with_context(|| "Could not decode the key management service signature")?;
+
+                ed25519_dalek::Signature::from_slice(&signature)
+                    .with_context(|| "The key management service did not return an Ed25519 signature")
+            }
+            status => Err(anyhow!(
+                "The key management service refused to sign with key '{}', status: {status}, body: {}",
+                self.key_version_name,
+                response.text().await.unwrap_or_default()
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use httpmock::prelude::*;
+    use serde_json::json;
+
+    use mithril_common::crypto_helper::ProtocolGenesisSignature;
+
+    use super::*;
+
+    const KEY_VERSION_NAME: &str =
+        "projects/mithril/locations/global/keyRings/genesis/cryptoKeys/genesis/cryptoKeyVersions/1";
+
+    #[tokio::test]
+    async fn gcp_kms_key_signer_returns_the_signature_of_the_key_management_service() {
+        let genesis_signer = ProtocolGenesisSigner::create_deterministic_genesis_signer();
+        let expected_signature: ed25519_dalek::Signature = genesis_signer.sign(b"message").into();
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(POST)
+                .path(format!("/v1/{KEY_VERSION_NAME}:asymmetricSign"))
+                .header("authorization", "Bearer token")
+                .json_body(json!({ "data": STANDARD.encode(b"message") }));
+            then.status(200).json_body(json!({
+                "name": KEY_VERSION_NAME,
+                "signature": STANDARD.encode(expected_signature.to_bytes())
+            }));
+        });
+
+        let signature = GcpKmsKeySigner::new(KEY_VERSION_NAME, "token")
+            .unwrap()
+            .with_endpoint(&server.base_url())
+            .sign(b"message")
+            .await
+            .unwrap();
+
+        assert_eq!(expected_signature, signature);
+        genesis_signer
+            .create_genesis_verifier()
+            .verify(b"message", &ProtocolGenesisSignature::from(signature))
+            .expect("The signature of the key management service should be valid");
+    }
+
+    #[tokio::test]
+    async fn gcp_kms_key_signer_fails_if_the_key_management_service_refuses_to_sign() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(POST);
+            then.status(403).body("permission denied");
+        });
+
+        GcpKmsKeySigner::new(KEY_VERSION_NAME, "token")
+            .unwrap()
+            .with_endpoint(&server.base_url())
+            .sign(b"message")
+            .await
+            .expect_err("A refused signature should fail");
+    }
+}
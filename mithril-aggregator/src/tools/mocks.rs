@@ -1,7 +1,10 @@
 use async_trait::async_trait;
-use mithril_common::chain_observer::{ChainAddress, ChainObserver, ChainObserverError, TxDatum};
+use mithril_common::chain_observer::{
+    ChainAddress, ChainObserver, ChainObserverError, StakeDistributionSource, TxDatum,
+};
 use mithril_common::crypto_helper::{KESPeriod, OpCert};
 use mithril_common::entities::{ChainPoint, Epoch, StakeDistribution};
+use mithril_common::StdResult;
 use mockall::mock;
 
 mock! {
@@ -30,3 +33,12 @@ mock! {
         ) -> Result<Option<KESPeriod>, ChainObserverError>;
     }
 }
+
+mock! {
+    pub StakeDistributionSource {}
+
+    #[async_trait]
+    impl StakeDistributionSource for StakeDistributionSource {
+        async fn retrieve(&self) -> StdResult<StakeDistribution>;
+    }
+}
@@ -1,22 +1,34 @@
+mod certificate_chain_auditor;
 mod certificates_hash_migrator;
 mod digest_helpers;
 mod era;
 mod genesis;
+mod genesis_threshold;
+mod key_signer;
 #[cfg(test)]
 pub mod mocks;
 mod remote_file_uploader;
 mod signer_importer;
+mod signer_registration_exporter;
 mod single_signature_authenticator;
+mod stress_test;
 
-pub use certificates_hash_migrator::CertificatesHashMigrator;
+pub use certificate_chain_auditor::{CertificateChainAuditReport, CertificateChainAuditor};
+pub use certificates_hash_migrator::{CertificatesHashMigrationReport, CertificatesHashMigrator};
 pub use digest_helpers::extract_digest_from_path;
 pub use era::EraTools;
 pub use genesis::{GenesisTools, GenesisToolsDependency};
+pub use genesis_threshold::GenesisThresholdTools;
+pub use key_signer::{GcpKmsKeySigner, KeySigner};
 pub use remote_file_uploader::{GcpFileUploader, RemoteFileUploader};
 pub use signer_importer::{
     CExplorerSignerRetriever, SignersImporter, SignersImporterPersister, SignersImporterRetriever,
 };
+pub use signer_registration_exporter::{
+    SignerRegistrationExportFormat, SignerRegistrationExporter,
+};
 pub use single_signature_authenticator::*;
+pub use stress_test::{AggregatorStressTester, StressTestPhaseReport, StressTestSigners};
 
 #[cfg(test)]
 pub use remote_file_uploader::MockRemoteFileUploader;
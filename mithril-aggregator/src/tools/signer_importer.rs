@@ -193,18 +193,15 @@ impl SPOItem {
 
 #[cfg(test)]
 mod tests {
+    use httpmock::prelude::*;
     use std::collections::{BTreeMap, BTreeSet};
-    use std::convert::Infallible;
     use std::sync::Arc;
-    use warp::Filter;
 
-    use mithril_common::test_utils::test_http_server::test_http_server;
     use mithril_common::StdResult;
     use mithril_persistence::sqlite::SqliteConnection;
 
     use crate::database::repository::{SignerGetter, SignerStore};
     use crate::database::test_helper::main_db_connection;
-    use crate::http_server::routes::reply;
     use crate::test_tools::TestLogger;
 
     use super::*;
@@ -275,23 +272,23 @@ mod tests {
 
     #[tokio::test]
     async fn retriever_should_return_deduplicated_data_and_handle_empty_name() {
-        let server = test_http_server(warp::path("list").map(|| {
-            r#"{
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.path("/list");
+            then.status(200).body(
+                r#"{
             "data": [
                 {"pool_id": "pool1", "name": ""},
                 {"pool_id": "pool2", "name": "[] "},
                 {"pool_id": "pool3", "name": "whatever"},
                 {"pool_id": "pool3", "name": "whatever2"}
             ]
-        }"#
-        }));
+        }"#,
+            );
+        });
 
-        let retriever = CExplorerSignerRetriever::new(
-            format!("{}/list", server.url()),
-            None,
-            TestLogger::stdout(),
-        )
-        .unwrap();
+        let retriever =
+            CExplorerSignerRetriever::new(server.url("/list"), None, TestLogger::stdout()).unwrap();
         let result = retriever
             .retrieve()
             .await
@@ -309,15 +306,14 @@ mod tests {
 
     #[tokio::test]
     async fn retriever_handle_http_data_fetching_error() {
-        let server =
-            test_http_server(warp::path("list").map(|| reply::internal_server_error("whatever")));
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.path("/list");
+            then.status(500).body("whatever");
+        });
 
-        let retriever = CExplorerSignerRetriever::new(
-            format!("{}/list", server.url()),
-            None,
-            TestLogger::stdout(),
-        )
-        .unwrap();
+        let retriever =
+            CExplorerSignerRetriever::new(server.url("/list"), None, TestLogger::stdout()).unwrap();
         retriever
             .retrieve()
             .await
@@ -326,14 +322,14 @@ mod tests {
 
     #[tokio::test]
     async fn retriever_yield_error_when_json_is_malformed() {
-        let server = test_http_server(warp::path("list").map(|| r#"{ "data": [ {"pool_" ] }"#));
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.path("/list");
+            then.status(200).body(r#"{ "data": [ {"pool_" ] }"#);
+        });
 
-        let retriever = CExplorerSignerRetriever::new(
-            format!("{}/list", server.url()),
-            None,
-            TestLogger::stdout(),
-        )
-        .unwrap();
+        let retriever =
+            CExplorerSignerRetriever::new(server.url("/list"), None, TestLogger::stdout()).unwrap();
         retriever
             .retrieve()
             .await
@@ -342,13 +338,16 @@ mod tests {
 
     #[tokio::test]
     async fn retriever_can_timeout() {
-        let server = test_http_server(warp::path("list").and_then(|| async {
-            tokio::time::sleep(Duration::from_millis(70)).await;
-            Ok::<&str, Infallible>(r#"{"data":[]}"#)
-        }));
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.path("/list");
+            then.status(200)
+                .delay(Duration::from_millis(70))
+                .body(r#"{"data":[]}"#);
+        });
 
         let retriever = CExplorerSignerRetriever::new(
-            format!("{}/list", server.url()),
+            server.url("/list"),
             Some(Duration::from_millis(10)),
             TestLogger::stdout(),
         )
@@ -452,8 +451,11 @@ mod tests {
         )
         .await
         .unwrap();
-        let server = test_http_server(warp::path("list").map(|| {
-            r#"{
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.path("/list");
+            then.status(200).body(
+                r#"{
             "data": [
                 {"pool_id": "pool1", "name": ""},
                 {"pool_id": "pool2", "name": "[] "},
@@ -462,17 +464,14 @@ mod tests {
                 {"pool_id": "pool5", "name": "[Pool5 add ticker]"},
                 {"pool_id": "pool7", "name": "[] "}
             ]
-        }"#
-        }));
+        }"#,
+            );
+        });
 
         let importer = SignersImporter::new(
             Arc::new(
-                CExplorerSignerRetriever::new(
-                    format!("{}/list", server.url()),
-                    None,
-                    TestLogger::stdout(),
-                )
-                .unwrap(),
+                CExplorerSignerRetriever::new(server.url("/list"), None, TestLogger::stdout())
+                    .unwrap(),
             ),
             Arc::new(SignerStore::new(connection.clone())),
             TestLogger::stdout(),
@@ -0,0 +1,155 @@
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use serde::Serialize;
+
+use mithril_common::crypto_helper::KESPeriod;
+use mithril_common::entities::{Epoch, PartyId, Stake};
+use mithril_common::StdResult;
+
+use crate::database::record::SignerRegistrationRecord;
+
+/// Format of the exported signer registrations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SignerRegistrationExportFormat {
+    /// One line per registration, with a header line.
+    Csv,
+    /// A JSON array of registrations.
+    Json,
+}
+
+/// Exported data of a signer registration.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SignerRegistrationExportEntry {
+    /// Party id of the signer.
+    pub party_id: PartyId,
+    /// Epoch of the registration.
+    pub epoch: Epoch,
+    /// Stake of the signer, if known.
+    pub stake: Option<Stake>,
+    /// Hex encoded verification key.
+    pub verification_key: String,
+    /// Hex encoded signature of the verification key.
+    pub verification_key_signature: Option<String>,
+    /// Hex encoded operational certificate.
+    pub operational_certificate: Option<String>,
+    /// KES period used to sign the verification key.
+    pub kes_period: Option<KESPeriod>,
+    /// Date of the registration.
+    pub registered_at: DateTime<Utc>,
+}
+
+impl From<SignerRegistrationRecord> for SignerRegistrationExportEntry {
+    fn from(record: SignerRegistrationRecord) -> Self {
+        Self {
+            party_id: record.signer_id,
+            epoch: record.epoch_settings_id,
+            stake: record.stake,
+            verification_key: record.verification_key,
+            verification_key_signature: record.verification_key_signature,
+            operational_certificate: record.operational_certificate,
+            kes_period: record.kes_period,
+            registered_at: record.created_at,
+        }
+    }
+}
+
+/// Export the signer registrations for research and auditing.
+pub struct SignerRegistrationExporter;
+
+impl SignerRegistrationExporter {
+    const CSV_HEADER: &'static str = "party_id,epoch,stake,verification_key,verification_key_signature,operational_certificate,kes_period,registered_at";
+
+    /// Serialize the given signer registrations in the given format.
+    pub fn export(
+        records: Vec<SignerRegistrationRecord>,
+        format: SignerRegistrationExportFormat,
+    ) -> StdResult<String> {
+        let entries: Vec<SignerRegistrationExportEntry> =
+            records.into_iter().map(Into::into).collect();
+
+        match format {
+            SignerRegistrationExportFormat::Json => Ok(serde_json::to_string_pretty(&entries)?),
+            SignerRegistrationExportFormat::Csv => Ok(Self::to_csv(&entries)),
+        }
+    }
+
+    fn to_csv(entries: &[SignerRegistrationExportEntry]) -> String {
+        fn optional<T: ToString>(value: &Option<T>) -> String {
+            value.as_ref().map(|v| v.to_string()).unwrap_or_default()
+        }
+
+        let mut csv = format!("{}\n", Self::CSV_HEADER);
+        for entry in entries {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{},{}\n",
+                entry.party_id,
+                entry.epoch,
+                optional(&entry.stake),
+                entry.verification_key,
+                optional(&entry.verification_key_signature),
+                optional(&entry.operational_certificate),
+                optional(&entry.kes_period),
+                entry.registered_at.to_rfc3339(),
+            ));
+        }
+
+        csv
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(signer_id: &str, epoch: u64, stake: Option<Stake>) -> SignerRegistrationRecord {
+        SignerRegistrationRecord {
+            signer_id: signer_id.to_string(),
+            epoch_settings_id: Epoch(epoch),
+            verification_key: "vkey".to_string(),
+            verification_key_signature: None,
+            operational_certificate: Some("opcert".to_string()),
+            kes_period: Some(4),
+            stake,
+            created_at: DateTime::parse_from_rfc3339("2024-05-01T10:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc),
+        }
+    }
+
+    #[test]
+    fn export_as_csv_with_one_line_per_registration() {
+        let csv = SignerRegistrationExporter::export(
+            vec![record("pool1", 10, Some(150)), record("pool2", 11, None)],
+            SignerRegistrationExportFormat::Csv,
+        )
+        .unwrap();
+
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            vec![
+                SignerRegistrationExporter::CSV_HEADER,
+                "pool1,10,150,vkey,,opcert,4,2024-05-01T10:00:00+00:00",
+                "pool2,11,,vkey,,opcert,4,2024-05-01T10:00:00+00:00",
+            ],
+            lines
+        );
+    }
+
+    #[test]
+    fn export_as_json_array() {
+        let json = SignerRegistrationExporter::export(
+            vec![record("pool1", 10, Some(150))],
+            SignerRegistrationExportFormat::Json,
+        )
+        .unwrap();
+
+        let entries: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!("pool1", entries[0]["party_id"]);
+        assert_eq!(10, entries[0]["epoch"]);
+        assert_eq!(150, entries[0]["stake"]);
+        assert_eq!(
+            serde_json::Value::Null,
+            entries[0]["verification_key_signature"]
+        );
+    }
+}
@@ -1,70 +1,196 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use slog::{debug, Logger};
-use std::sync::Arc;
+use std::collections::{HashSet, VecDeque};
+use std::fmt::{Display, Formatter};
+use std::sync::{Arc, Mutex};
 
-use mithril_common::entities::{SingleSignatureAuthenticationStatus, SingleSignatures};
+use mithril_common::entities::{PartyId, SingleSignatureAuthenticationStatus, SingleSignatures};
 use mithril_common::logging::LoggerExtensions;
 use mithril_common::StdResult;
 
-use crate::MultiSigner;
+use crate::{MetricsService, MultiSigner};
+
+/// Order in which the stake distributions are tried to authenticate a single signature.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SingleSignatureVerificationOrder {
+    /// The stake distribution of the current epoch, then the one of the next epoch for the
+    /// signers that detected the epoch change before the aggregator.
+    #[default]
+    CurrentThenNext,
+    /// The stake distribution of the next epoch, then the one of the current epoch.
+    NextThenCurrent,
+    /// Only the stake distribution of the current epoch.
+    CurrentOnly,
+}
+
+/// Stake distribution against which a single signature is verified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VerificationStakeDistribution {
+    Current,
+    Next,
+}
+
+impl Display for VerificationStakeDistribution {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Current => write!(f, "current"),
+            Self::Next => write!(f, "next"),
+        }
+    }
+}
+
+impl SingleSignatureVerificationOrder {
+    fn stake_distributions(&self) -> &'static [VerificationStakeDistribution] {
+        use VerificationStakeDistribution::*;
+
+        match self {
+            Self::CurrentThenNext => &[Current, Next],
+            Self::NextThenCurrent => &[Next, Current],
+            Self::CurrentOnly => &[Current],
+        }
+    }
+}
 
 /// Authenticates single signatures against a signed message.
 pub struct SingleSignatureAuthenticator {
     multi_signer: Arc<dyn MultiSigner>,
+    verification_order: SingleSignatureVerificationOrder,
+    cache: Option<AuthenticationCache>,
+    metrics_service: Arc<MetricsService>,
     logger: Logger,
 }
 
+/// Identifies a single signature submission: the party, the signed message and the hash of the
+/// signature.
+type AuthenticationCacheKey = (PartyId, String, String);
+
+/// Recently authenticated single signatures, the oldest are forgotten first.
+///
+/// Only the successful authentications are kept: a signature rejected by the current stake
+/// distributions may be authenticated after an epoch change, and the rejected submissions must not
+/// evict the authenticated ones.
+struct AuthenticationCache {
+    capacity: usize,
+    authenticated: Mutex<AuthenticatedSignatures>,
+}
+
+#[derive(Default)]
+struct AuthenticatedSignatures {
+    keys: HashSet<AuthenticationCacheKey>,
+    insertion_order: VecDeque<AuthenticationCacheKey>,
+}
+
+impl AuthenticationCache {
+    fn key(
+        single_signature: &SingleSignatures,
+        signed_message: &str,
+    ) -> StdResult<AuthenticationCacheKey> {
+        let signature_hash = hex::encode(Sha256::digest(single_signature.signature.to_json_hex()?));
+
+        Ok((
+            single_signature.party_id.clone(),
+            signed_message.to_string(),
+            signature_hash,
+        ))
+    }
+
+    fn contains(&self, key: &AuthenticationCacheKey) -> bool {
+        self.authenticated.lock().unwrap().keys.contains(key)
+    }
+
+    /// Insert an authenticated signature and return the number of cached signatures
+    fn insert(&self, key: AuthenticationCacheKey) -> usize {
+        let mut authenticated = self.authenticated.lock().unwrap();
+        if authenticated.keys.insert(key.clone()) {
+            authenticated.insertion_order.push_back(key);
+        }
+        while authenticated.insertion_order.len() > self.capacity {
+            if let Some(oldest_key) = authenticated.insertion_order.pop_front() {
+                authenticated.keys.remove(&oldest_key);
+            }
+        }
+
+        authenticated.keys.len()
+    }
+}
+
 impl SingleSignatureAuthenticator {
     /// Creates a new `SingleSignatureAuthenticator`.
-    pub fn new(multi_signer: Arc<dyn MultiSigner>, logger: Logger) -> Self {
+    pub fn new(
+        multi_signer: Arc<dyn MultiSigner>,
+        metrics_service: Arc<MetricsService>,
+        logger: Logger,
+    ) -> Self {
         Self {
             multi_signer,
+            verification_order: SingleSignatureVerificationOrder::default(),
+            cache: None,
+            metrics_service,
             logger: logger.new_with_component_name::<Self>(),
         }
     }
 
+    /// Set the order in which the stake distributions are tried to authenticate a signature.
+    pub fn with_verification_order(
+        mut self,
+        verification_order: SingleSignatureVerificationOrder,
+    ) -> Self {
+        self.verification_order = verification_order;
+        self
+    }
+
+    /// Keep up to `capacity` authenticated signatures in memory so the signatures submitted
+    /// again are not verified again, no cache is used if `capacity` is `0`.
+    pub fn with_cache(mut self, capacity: usize) -> Self {
+        self.cache = (capacity > 0).then(|| AuthenticationCache {
+            capacity,
+            authenticated: Mutex::new(AuthenticatedSignatures::default()),
+        });
+        self
+    }
+
     /// Authenticates a single signature against a signed message.
     pub async fn authenticate(
         &self,
         single_signature: &mut SingleSignatures,
         signed_message: &str,
     ) -> StdResult<()> {
-        let is_authenticated = match self
-            .multi_signer
-            .verify_single_signature(signed_message, single_signature)
-            .await
-        {
-            Ok(()) => {
-                debug!(
-                    self.logger, "Single signature party authenticated for current stake distribution";
-                    "party_id" => &single_signature.party_id,
-                );
-                true
-            }
-            Err(_error) => {
-                // Signers may detect epoch changes before the aggregator and send
-                // new signatures using the next epoch stake distribution
-                if self
-                    .multi_signer
-                    .verify_single_signature_for_next_stake_distribution(
-                        signed_message,
-                        single_signature,
-                    )
-                    .await
-                    .is_ok()
-                {
-                    debug!(
-                        self.logger, "Single signature party authenticated for next stake distribution";
-                        "party_id" => &single_signature.party_id,
-                    );
-                    true
-                } else {
-                    debug!(
-                        self.logger, "Single signature party not authenticated";
-                        "party_id" => &single_signature.party_id,
-                    );
-                    false
+        let cache_entry = match &self.cache {
+            Some(cache) => Some((
+                cache,
+                AuthenticationCache::key(single_signature, signed_message)?,
+            )),
+            None => None,
+        };
+        let is_cached = cache_entry
+            .as_ref()
+            .is_some_and(|(cache, key)| cache.contains(key));
+
+        let is_authenticated = if is_cached {
+            self.metrics_service
+                .get_single_signature_authentication_cache_hit_since_startup()
+                .increment();
+            debug!(
+                self.logger, "Single signature authentication found in cache";
+                "party_id" => &single_signature.party_id,
+            );
+            true
+        } else {
+            let is_authenticated = self.verify(single_signature, signed_message).await;
+            if let Some((cache, key)) = cache_entry {
+                self.metrics_service
+                    .get_single_signature_authentication_cache_miss_since_startup()
+                    .increment();
+                if is_authenticated {
+                    let cache_size = cache.insert(key);
+                    self.metrics_service
+                        .get_single_signature_authentication_cache_size()
+                        .record(cache_size as f64);
                 }
             }
+            is_authenticated
         };
 
         single_signature.authentication_status = if is_authenticated {
@@ -75,6 +201,55 @@ impl SingleSignatureAuthenticator {
 
         Ok(())
     }
+
+    async fn verify(&self, single_signature: &SingleSignatures, signed_message: &str) -> bool {
+        for stake_distribution in self.verification_order.stake_distributions() {
+            let verification = match stake_distribution {
+                VerificationStakeDistribution::Current => {
+                    self.multi_signer
+                        .verify_single_signature(signed_message, single_signature)
+                        .await
+                }
+                // Signers may detect epoch changes before the aggregator and send
+                // new signatures using the next epoch stake distribution
+                VerificationStakeDistribution::Next => {
+                    self.multi_signer
+                        .verify_single_signature_for_next_stake_distribution(
+                            signed_message,
+                            single_signature,
+                        )
+                        .await
+                }
+            };
+
+            if verification.is_ok() {
+                debug!(
+                    self.logger, "Single signature party authenticated for {stake_distribution} stake distribution";
+                    "party_id" => &single_signature.party_id,
+                );
+                match stake_distribution {
+                    VerificationStakeDistribution::Current => self
+                        .metrics_service
+                        .get_single_signature_authenticated_with_current_stake_distribution_since_startup()
+                        .increment(),
+                    VerificationStakeDistribution::Next => self
+                        .metrics_service
+                        .get_single_signature_authenticated_with_next_stake_distribution_since_startup()
+                        .increment(),
+                }
+                return true;
+            }
+        }
+
+        debug!(
+            self.logger, "Single signature party not authenticated";
+            "party_id" => &single_signature.party_id,
+        );
+        self.metrics_service
+            .get_single_signature_not_authenticated_since_startup()
+            .increment();
+        false
+    }
 }
 
 #[cfg(test)]
@@ -88,10 +263,11 @@ impl SingleSignatureAuthenticator {
             .expect_verify_single_signature_for_next_stake_distribution()
             .returning(|_, _| Ok(()));
 
-        Self {
-            multi_signer: Arc::new(multi_signer),
-            logger: crate::test_tools::TestLogger::stdout(),
-        }
+        Self::new(
+            Arc::new(multi_signer),
+            Arc::new(MetricsService::new(crate::test_tools::TestLogger::stdout()).unwrap()),
+            crate::test_tools::TestLogger::stdout(),
+        )
     }
 
     pub(crate) fn new_that_reject_everything() -> Self {
@@ -103,10 +279,11 @@ impl SingleSignatureAuthenticator {
             .expect_verify_single_signature_for_next_stake_distribution()
             .returning(|_, _| Err(anyhow::anyhow!("error")));
 
-        Self {
-            multi_signer: Arc::new(multi_signer),
-            logger: crate::test_tools::TestLogger::stdout(),
-        }
+        Self::new(
+            Arc::new(multi_signer),
+            Arc::new(MetricsService::new(crate::test_tools::TestLogger::stdout()).unwrap()),
+            crate::test_tools::TestLogger::stdout(),
+        )
     }
 }
 
@@ -142,6 +319,7 @@ mod tests {
                     .expect_verify_single_signature()
                     .returning(|_, _| Ok(()));
             }),
+            Arc::new(MetricsService::new(TestLogger::stdout()).unwrap()),
             TestLogger::stdout(),
         );
 
@@ -174,6 +352,7 @@ mod tests {
                     .expect_verify_single_signature_for_next_stake_distribution()
                     .returning(|_, _| Ok(()));
             }),
+            Arc::new(MetricsService::new(TestLogger::stdout()).unwrap()),
             TestLogger::stdout(),
         );
 
@@ -210,6 +389,7 @@ mod tests {
                         ))
                     });
             }),
+            Arc::new(MetricsService::new(TestLogger::stdout()).unwrap()),
             TestLogger::stdout(),
         );
 
@@ -246,6 +426,7 @@ mod tests {
                         ))
                     });
             }),
+            Arc::new(MetricsService::new(TestLogger::stdout()).unwrap()),
             TestLogger::stdout(),
         );
 
@@ -259,4 +440,206 @@ mod tests {
             SingleSignatureAuthenticationStatus::Unauthenticated
         );
     }
+
+    #[tokio::test]
+    async fn resubmitted_single_signature_is_authenticated_from_the_cache() {
+        let signed_message = "signed_message".to_string();
+        let single_signature = SingleSignatures::fake("party_id", &signed_message);
+        let metrics_service = Arc::new(MetricsService::new(TestLogger::stdout()).unwrap());
+
+        let authenticator = SingleSignatureAuthenticator::new(
+            mock_multi_signer(|mock_config| {
+                mock_config
+                    .expect_verify_single_signature()
+                    .returning(|_, _| Ok(()))
+                    .once();
+            }),
+            metrics_service.clone(),
+            TestLogger::stdout(),
+        )
+        .with_cache(10);
+
+        for _ in 0..3 {
+            let mut resubmitted_signature = SingleSignatures {
+                authentication_status: SingleSignatureAuthenticationStatus::Unauthenticated,
+                ..single_signature.clone()
+            };
+            authenticator
+                .authenticate(&mut resubmitted_signature, &signed_message)
+                .await
+                .unwrap();
+
+            assert_eq!(
+                resubmitted_signature.authentication_status,
+                SingleSignatureAuthenticationStatus::Authenticated
+            );
+        }
+
+        assert_eq!(
+            2,
+            metrics_service
+                .get_single_signature_authentication_cache_hit_since_startup()
+                .get()
+        );
+        assert_eq!(
+            1,
+            metrics_service
+                .get_single_signature_authentication_cache_miss_since_startup()
+                .get()
+        );
+    }
+
+    #[tokio::test]
+    async fn authentication_cache_forgets_the_oldest_authenticated_signatures_when_full() {
+        let metrics_service = Arc::new(MetricsService::new(TestLogger::stdout()).unwrap());
+        let authenticator = SingleSignatureAuthenticator::new(
+            mock_multi_signer(|mock_config| {
+                mock_config
+                    .expect_verify_single_signature()
+                    .returning(|_, _| Ok(()))
+                    .times(4);
+            }),
+            metrics_service.clone(),
+            TestLogger::stdout(),
+        )
+        .with_cache(2);
+
+        for signed_message in ["message-1", "message-2", "message-3", "message-1"] {
+            let mut single_signature = SingleSignatures::fake("party_id", signed_message);
+            authenticator
+                .authenticate(&mut single_signature, signed_message)
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(
+            2.0,
+            metrics_service
+                .get_single_signature_authentication_cache_size()
+                .get()
+        );
+    }
+
+    #[tokio::test]
+    async fn rejected_single_signatures_are_not_cached_and_do_not_evict_the_authenticated_ones() {
+        let metrics_service = Arc::new(MetricsService::new(TestLogger::stdout()).unwrap());
+        let authenticator = SingleSignatureAuthenticator::new(
+            mock_multi_signer(|mock_config| {
+                mock_config
+                    .expect_verify_single_signature()
+                    .withf(|signed_message, _| signed_message == "authenticated")
+                    .returning(|_, _| Ok(()))
+                    .once();
+                mock_config
+                    .expect_verify_single_signature()
+                    .withf(|signed_message, _| signed_message == "rejected")
+                    .returning(|_, _| Err(anyhow!("error")))
+                    .times(3);
+            }),
+            metrics_service.clone(),
+            TestLogger::stdout(),
+        )
+        .with_cache(1)
+        .with_verification_order(SingleSignatureVerificationOrder::CurrentOnly);
+
+        for signed_message in [
+            "authenticated",
+            "rejected",
+            "rejected",
+            "rejected",
+            "authenticated",
+        ] {
+            let mut single_signature = SingleSignatures::fake("party_id", signed_message);
+            authenticator
+                .authenticate(&mut single_signature, signed_message)
+                .await
+                .unwrap();
+
+            assert_eq!(
+                signed_message == "authenticated",
+                single_signature.is_authenticated()
+            );
+        }
+
+        assert_eq!(
+            1,
+            metrics_service
+                .get_single_signature_authentication_cache_hit_since_startup()
+                .get()
+        );
+        assert_eq!(
+            1.0,
+            metrics_service
+                .get_single_signature_authentication_cache_size()
+                .get()
+        );
+    }
+
+    #[tokio::test]
+    async fn next_then_current_order_tries_the_next_stake_distribution_first() {
+        let signed_message = "signed_message".to_string();
+        let mut single_signature = SingleSignatures::fake("party_id", &signed_message);
+        let metrics_service = Arc::new(MetricsService::new(TestLogger::stdout()).unwrap());
+
+        let authenticator = SingleSignatureAuthenticator::new(
+            mock_multi_signer(|mock_config| {
+                mock_config.expect_verify_single_signature().never();
+                mock_config
+                    .expect_verify_single_signature_for_next_stake_distribution()
+                    .returning(|_, _| Ok(()))
+                    .once();
+            }),
+            metrics_service.clone(),
+            TestLogger::stdout(),
+        )
+        .with_verification_order(SingleSignatureVerificationOrder::NextThenCurrent);
+
+        authenticator
+            .authenticate(&mut single_signature, &signed_message)
+            .await
+            .unwrap();
+
+        assert!(single_signature.is_authenticated());
+        assert_eq!(
+            1,
+            metrics_service
+                .get_single_signature_authenticated_with_next_stake_distribution_since_startup()
+                .get()
+        );
+    }
+
+    #[tokio::test]
+    async fn current_only_order_does_not_fallback_to_the_next_stake_distribution() {
+        let signed_message = "signed_message".to_string();
+        let mut single_signature = SingleSignatures::fake("party_id", &signed_message);
+        let metrics_service = Arc::new(MetricsService::new(TestLogger::stdout()).unwrap());
+
+        let authenticator = SingleSignatureAuthenticator::new(
+            mock_multi_signer(|mock_config| {
+                mock_config
+                    .expect_verify_single_signature()
+                    .returning(|_, _| Err(anyhow!("error")))
+                    .once();
+                mock_config
+                    .expect_verify_single_signature_for_next_stake_distribution()
+                    .never();
+            }),
+            metrics_service.clone(),
+            TestLogger::stdout(),
+        )
+        .with_verification_order(SingleSignatureVerificationOrder::CurrentOnly);
+
+        authenticator
+            .authenticate(&mut single_signature, &signed_message)
+            .await
+            .unwrap();
+
+        assert!(!single_signature.is_authenticated());
+        assert_eq!(
+            1,
+            metrics_service
+                .get_single_signature_not_authenticated_since_startup()
+                .get()
+        );
+    }
 }
@@ -0,0 +1,509 @@
+//! ## Stress test
+//!
+//! Simulate a fleet of signers sending their registrations and single signatures to a running
+//! aggregator, measuring the throughput and latencies of its HTTP server.
+//!
+//! The signers are generated with random keys: they are only accepted if the aggregator stake
+//! distribution contains them (i.e. an embedded aggregator running on a simulated chain fed with
+//! the generated signers), otherwise the rejected requests are still measured and reported with
+//! their status code.
+
+use anyhow::{anyhow, Context};
+use kes_summed_ed25519::{kes::Sum6Kes, traits::KesSk};
+use rand_core::{OsRng, RngCore};
+use reqwest::StatusCode;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use mithril_common::{
+    crypto_helper::{
+        OpCert, ProtocolAggregateVerificationKey, ProtocolInitializer, SerDeShelleyFileFormat,
+        Sum6KesBytes,
+    },
+    entities::{
+        Epoch, ProtocolMessage, ProtocolMessagePartKey, ProtocolParameters, SignedEntityType,
+        SignerWithStake,
+    },
+    messages::{EpochSettingsMessage, RegisterSignatureMessage, RegisterSignerMessage},
+    protocol::{SignatureScheme, SignerBuilder, SingleSigner},
+    StdResult,
+};
+
+/// Timeout of each request sent to the aggregator.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Delay between two attempts to fetch the epoch settings of an aggregator that is starting.
+const EPOCH_SETTINGS_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Latency percentiles of the requests of a stress test phase.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct LatencyPercentiles {
+    /// Fastest request.
+    pub min: Duration,
+    /// Median latency.
+    pub p50: Duration,
+    /// 90th percentile latency.
+    pub p90: Duration,
+    /// 99th percentile latency.
+    pub p99: Duration,
+    /// Slowest request.
+    pub max: Duration,
+}
+
+impl LatencyPercentiles {
+    /// Compute the percentiles of the given latencies.
+    pub fn compute(latencies: &[Duration]) -> Self {
+        if latencies.is_empty() {
+            return Self::default();
+        }
+
+        let mut sorted = latencies.to_vec();
+        sorted.sort();
+        let percentile = |p: usize| sorted[((sorted.len() * p).div_ceil(100)).max(1) - 1];
+
+        Self {
+            min: sorted[0],
+            p50: percentile(50),
+            p90: percentile(90),
+            p99: percentile(99),
+            max: sorted[sorted.len() - 1],
+        }
+    }
+}
+
+/// Report of a stress test phase.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StressTestPhaseReport {
+    /// Name of the phase.
+    pub name: String,
+    /// Number of requests sent.
+    pub total_requests: usize,
+    /// Number of responses received by HTTP status code.
+    pub responses_by_status: BTreeMap<u16, usize>,
+    /// Number of requests that got no response.
+    pub failed_requests: usize,
+    /// Duration of the whole phase.
+    pub duration: Duration,
+    /// Latencies of the requests that got a response.
+    pub latencies: LatencyPercentiles,
+}
+
+impl StressTestPhaseReport {
+    fn new(
+        name: &str,
+        results: Vec<StdResult<(StatusCode, Duration)>>,
+        duration: Duration,
+    ) -> Self {
+        let mut responses_by_status = BTreeMap::new();
+        let mut latencies = vec![];
+        let mut failed_requests = 0;
+        for result in &results {
+            match result {
+                Ok((status, latency)) => {
+                    *responses_by_status.entry(status.as_u16()).or_insert(0) += 1;
+                    latencies.push(*latency);
+                }
+                Err(_) => failed_requests += 1,
+            }
+        }
+
+        Self {
+            name: name.to_string(),
+            total_requests: results.len(),
+            responses_by_status,
+            failed_requests,
+            duration,
+            latencies: LatencyPercentiles::compute(&latencies),
+        }
+    }
+
+    /// Number of requests per second sent during the phase.
+    pub fn throughput(&self) -> f64 {
+        if self.duration.is_zero() {
+            return 0.0;
+        }
+
+        self.total_requests as f64 / self.duration.as_secs_f64()
+    }
+}
+
+/// Signers generated for a stress test, each with its own cold key, KES key and operational
+/// certificate.
+pub struct StressTestSigners {
+    signers_with_stake: Vec<SignerWithStake>,
+    single_signers: Vec<SingleSigner>,
+    aggregate_verification_key: ProtocolAggregateVerificationKey,
+}
+
+impl StressTestSigners {
+    /// Generate the given number of signers with random keys and stakes, their KES secret keys
+    /// are written in the work directory.
+    pub fn generate(
+        number_of_signers: usize,
+        protocol_parameters: &ProtocolParameters,
+        work_directory: &Path,
+    ) -> StdResult<Self> {
+        fs::create_dir_all(work_directory).with_context(|| {
+            format!(
+                "Could not create the stress test work directory '{}'",
+                work_directory.display()
+            )
+        })?;
+
+        let mut initializers = vec![];
+        for index in 0..number_of_signers {
+            let mut cold_key_seed = [0u8; 32];
+            OsRng.fill_bytes(&mut cold_key_seed);
+            let cold_secret_key = ed25519_dalek::SigningKey::from_bytes(&cold_key_seed);
+            let mut kes_key_seed = [0u8; 32];
+            OsRng.fill_bytes(&mut kes_key_seed);
+            let mut kes_key_buffer = [0u8; Sum6Kes::SIZE + 4];
+            let (kes_secret_key, kes_verification_key) =
+                Sum6Kes::keygen(&mut kes_key_buffer, &mut kes_key_seed);
+            let mut kes_secret_key_bytes = Sum6KesBytes([0u8; Sum6Kes::SIZE + 4]);
+            kes_secret_key_bytes
+                .0
+                .copy_from_slice(&kes_secret_key.clone_sk());
+            let kes_secret_key_path = work_directory.join(format!("signer-{index}.kes.sk"));
+            kes_secret_key_bytes
+                .to_file(&kes_secret_key_path)
+                .with_context(|| "Could not write the KES secret key of a stress test signer")?;
+
+            let operational_certificate = OpCert::new(kes_verification_key, 0, 0, cold_secret_key);
+            let party_id = operational_certificate
+                .compute_protocol_party_id()
+                .with_context(|| "Could not compute the party id of a stress test signer")?;
+            let stake = 1 + OsRng.next_u64() % 999;
+            let protocol_initializer = ProtocolInitializer::setup(
+                protocol_parameters.clone().into(),
+                Some(&kes_secret_key_path),
+                Some(0),
+                stake,
+                &mut OsRng,
+            )
+            .with_context(|| "Could not set up the protocol initializer of a stress test signer")?;
+            let signer_with_stake = SignerWithStake::new(
+                party_id,
+                protocol_initializer.verification_key().into(),
+                protocol_initializer.verification_key_signature(),
+                Some(operational_certificate.into()),
+                Some(0),
+                stake,
+            );
+
+            initializers.push((signer_with_stake, protocol_initializer));
+        }
+
+        let signers_with_stake: Vec<SignerWithStake> = initializers
+            .iter()
+            .map(|(signer_with_stake, _)| signer_with_stake.clone())
+            .collect();
+        let signer_builder = SignerBuilder::new(&signers_with_stake, protocol_parameters)
+            .with_context(|| "Could not register the stress test signers")?;
+        let single_signers = initializers
+            .into_iter()
+            .map(|(signer_with_stake, protocol_initializer)| {
+                signer_builder.restore_signer_from_initializer(
+                    signer_with_stake.party_id,
+                    protocol_initializer,
+                )
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+
+        Ok(Self {
+            signers_with_stake,
+            single_signers,
+            aggregate_verification_key: signer_builder.compute_aggregate_verification_key(),
+        })
+    }
+
+    /// Signers with their stake, to be fed to the stake distribution of a simulated chain.
+    pub fn signers_with_stake(&self) -> Vec<SignerWithStake> {
+        self.signers_with_stake.clone()
+    }
+
+    fn register_signer_messages(&self, epoch: Epoch) -> StdResult<Vec<RegisterSignerMessage>> {
+        self.signers_with_stake
+            .iter()
+            .map(|signer| {
+                Ok(RegisterSignerMessage {
+                    epoch,
+                    party_id: signer.party_id.clone(),
+                    verification_key: signer.verification_key.to_json_hex()?,
+                    verification_key_signature: signer
+                        .verification_key_signature
+                        .as_ref()
+                        .map(|k| k.to_json_hex())
+                        .transpose()?,
+                    operational_certificate: signer
+                        .operational_certificate
+                        .as_ref()
+                        .map(|o| o.to_json_hex())
+                        .transpose()?,
+                    kes_period: signer.kes_period,
+                    registration_challenge: None,
+                    registration_challenge_signature: None,
+                })
+            })
+            .collect()
+    }
+
+    fn register_signature_messages(
+        &self,
+        signed_entity_type: SignedEntityType,
+    ) -> StdResult<Vec<RegisterSignatureMessage>> {
+        let mut protocol_message = ProtocolMessage::new();
+        protocol_message.set_message_part(
+            ProtocolMessagePartKey::NextAggregateVerificationKey,
+            self.aggregate_verification_key.to_json_hex()?,
+        );
+
+        let mut messages = vec![];
+        for single_signer in &self.single_signers {
+            // A signer that did not win any lottery has nothing to send
+            if let Some(signature) = single_signer.sign(&protocol_message)? {
+                messages.push(RegisterSignatureMessage {
+                    signed_entity_type: signed_entity_type.clone(),
+                    party_id: signature.party_id.clone(),
+                    signature: signature.signature.to_json_hex()?,
+                    won_indexes: signature.won_indexes,
+                    signed_message: None,
+                    signature_scheme: SignatureScheme::default(),
+                });
+            }
+        }
+
+        Ok(messages)
+    }
+}
+
+/// Send signers registrations and signatures generated with random keys to an aggregator.
+pub struct AggregatorStressTester {
+    client: reqwest::Client,
+    aggregator_endpoint: String,
+    concurrency: usize,
+}
+
+impl AggregatorStressTester {
+    /// Create a new instance targeting the given aggregator endpoint.
+    pub fn new(aggregator_endpoint: &str, concurrency: usize) -> StdResult<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .with_context(|| "Could not build the stress test HTTP client")?;
+
+        Ok(Self {
+            client,
+            aggregator_endpoint: aggregator_endpoint.trim_end_matches('/').to_string(),
+            concurrency: concurrency.max(1),
+        })
+    }
+
+    /// Fetch the epoch settings of the aggregator.
+    pub async fn get_epoch_settings(&self) -> StdResult<EpochSettingsMessage> {
+        let url = format!("{}/epoch-settings", self.aggregator_endpoint);
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .with_context(|| format!("Could not reach the aggregator at '{url}'"))?;
+        if !response.status().is_success() {
+            return Err(anyhow!(
+                "Unexpected status code '{}' when fetching '{url}'",
+                response.status()
+            ));
+        }
+
+        response
+            .json()
+            .await
+            .with_context(|| "Could not decode the epoch settings message")
+    }
+
+    /// Fetch the epoch settings of an aggregator that is starting, retrying until they are
+    /// available or the timeout is reached.
+    pub async fn wait_for_epoch_settings(
+        &self,
+        timeout: Duration,
+    ) -> StdResult<EpochSettingsMessage> {
+        let start = Instant::now();
+        loop {
+            match self.get_epoch_settings().await {
+                Ok(epoch_settings) => return Ok(epoch_settings),
+                Err(error) if start.elapsed() >= timeout => {
+                    return Err(error).with_context(|| {
+                        format!("The aggregator is still not ready after {timeout:?}")
+                    })
+                }
+                Err(_) => tokio::time::sleep(EPOCH_SETTINGS_RETRY_DELAY).await,
+            }
+        }
+    }
+
+    /// Send the registration of all the signers.
+    pub async fn register_signers(
+        &self,
+        signers: &StressTestSigners,
+        epoch: Epoch,
+    ) -> StdResult<StressTestPhaseReport> {
+        let messages = signers
+            .register_signer_messages(epoch)
+            .with_context(|| "Could not build the signers registration messages")?;
+
+        Ok(self
+            .post_all("signers registration", "register-signer", messages)
+            .await)
+    }
+
+    /// Send the single signatures of all the signers for the Mithril stake distribution of the
+    /// given epoch.
+    pub async fn register_signatures(
+        &self,
+        signers: Arc<StressTestSigners>,
+        epoch: Epoch,
+    ) -> StdResult<StressTestPhaseReport> {
+        let signed_entity_type = SignedEntityType::MithrilStakeDistribution(epoch);
+        let messages = tokio::task::spawn_blocking(move || {
+            signers.register_signature_messages(signed_entity_type)
+        })
+        .await
+        .with_context(|| "Could not compute the single signatures")??;
+
+        Ok(self
+            .post_all("signatures registration", "register-signatures", messages)
+            .await)
+    }
+
+    async fn post_all<T: Serialize + Send + 'static>(
+        &self,
+        phase_name: &str,
+        route: &str,
+        messages: Vec<T>,
+    ) -> StressTestPhaseReport {
+        let url = format!("{}/{route}", self.aggregator_endpoint);
+        let semaphore = Arc::new(Semaphore::new(self.concurrency));
+        let mut join_set = JoinSet::new();
+        let start = Instant::now();
+
+        for message in messages {
+            let client = self.client.clone();
+            let url = url.clone();
+            let semaphore = semaphore.clone();
+            join_set.spawn(async move {
+                let _permit = semaphore.acquire_owned().await?;
+                let request_start = Instant::now();
+                let response = client.post(&url).json(&message).send().await?;
+
+                Ok((response.status(), request_start.elapsed()))
+            });
+        }
+
+        let mut results = vec![];
+        while let Some(result) = join_set.join_next().await {
+            results.push(result.map_err(|e| anyhow!(e)).and_then(|r| r));
+        }
+
+        StressTestPhaseReport::new(phase_name, results, start.elapsed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use httpmock::prelude::*;
+
+    use mithril_common::test_utils::{fake_data, TempDir};
+
+    use super::*;
+
+    #[test]
+    fn compute_latency_percentiles() {
+        let latencies: Vec<Duration> = (1..=100).rev().map(Duration::from_millis).collect();
+
+        let percentiles = LatencyPercentiles::compute(&latencies);
+
+        assert_eq!(
+            LatencyPercentiles {
+                min: Duration::from_millis(1),
+                p50: Duration::from_millis(50),
+                p90: Duration::from_millis(90),
+                p99: Duration::from_millis(99),
+                max: Duration::from_millis(100),
+            },
+            percentiles
+        );
+        assert_eq!(
+            LatencyPercentiles::default(),
+            LatencyPercentiles::compute(&[])
+        );
+    }
+
+    fn generate_signers(number_of_signers: usize, test_name: &str) -> StressTestSigners {
+        StressTestSigners::generate(
+            number_of_signers,
+            &fake_data::protocol_parameters(),
+            &TempDir::create("stress_test", test_name),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn generate_certified_signers_with_valid_registrations() {
+        let signers = generate_signers(3, "generate_certified_signers_with_valid_registrations");
+        let signers_with_stake = signers.signers_with_stake();
+
+        assert_eq!(3, signers_with_stake.len());
+        for signer in &signers_with_stake {
+            assert!(signer.party_id.starts_with("pool"));
+            assert!(signer.operational_certificate.is_some());
+            assert!(signer.verification_key_signature.is_some());
+            assert!(signer.stake > 0);
+        }
+        assert_eq!(3, signers.register_signer_messages(Epoch(5)).unwrap().len());
+    }
+
+    #[tokio::test]
+    async fn register_signers_report_responses_by_status() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(POST).path("/register-signer");
+            then.status(201);
+        });
+        let tester = AggregatorStressTester::new(&server.base_url(), 2).unwrap();
+        let signers = generate_signers(3, "register_signers_report_responses_by_status");
+
+        let report = tester.register_signers(&signers, Epoch(5)).await.unwrap();
+
+        assert_eq!(3, report.total_requests);
+        assert_eq!(BTreeMap::from([(201, 3)]), report.responses_by_status);
+        assert_eq!(0, report.failed_requests);
+    }
+
+    #[tokio::test]
+    async fn register_signatures_of_the_signers_that_won_a_lottery() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(POST).path("/register-signatures");
+            then.status(201);
+        });
+        let tester = AggregatorStressTester::new(&server.base_url(), 2).unwrap();
+        let signers = Arc::new(generate_signers(
+            3,
+            "register_signatures_of_the_signers_that_won_a_lottery",
+        ));
+
+        let report = tester.register_signatures(signers, Epoch(5)).await.unwrap();
+
+        assert!(report.total_requests > 0 && report.total_requests <= 3);
+        assert_eq!(
+            BTreeMap::from([(201, report.total_requests)]),
+            report.responses_by_status
+        );
+    }
+}
@@ -21,6 +21,7 @@ use mithril_common::{
         SlotNumber, Snapshot, StakeDistribution, TimePoint,
     },
     era::{adapters::EraReaderDummyAdapter, EraMarker, EraReader, SupportedEra},
+    logging::{LogLevelHandle, ReloadableLevelFilter},
     test_utils::{
         MithrilFixture, MithrilFixtureBuilder, SignerFixture, StakeDistributionGenerationMethod,
     },
@@ -118,16 +119,18 @@ pub struct RuntimeTester {
     _global_logger_guard: slog_scope::GlobalLoggerGuard,
 }
 
-fn build_logger() -> slog::Logger {
+fn build_logger(log_level_handle: &LogLevelHandle) -> slog::Logger {
     let decorator = slog_term::PlainDecorator::new(slog_term::TestStdoutWriter);
     let drain = slog_term::CompactFormat::new(decorator).build().fuse();
     let drain = slog_async::Async::new(drain).build().fuse();
+    let drain = ReloadableLevelFilter::new(drain, log_level_handle.clone()).fuse();
     slog::Logger::root(Arc::new(drain), slog::o!())
 }
 
 impl RuntimeTester {
     pub async fn build(start_time_point: TimePoint, configuration: Configuration) -> Self {
-        let logger = build_logger();
+        let log_level_handle = LogLevelHandle::new(slog::Level::Debug);
+        let logger = build_logger(&log_level_handle);
         let global_logger = slog_scope::set_global_logger(logger.clone());
         let network = configuration.network.clone();
         let snapshot_uploader = Arc::new(DumbSnapshotUploader::new());
@@ -153,6 +156,7 @@ impl RuntimeTester {
         deps_builder.snapshotter = Some(snapshotter.clone());
         deps_builder.era_reader = Some(Arc::new(EraReader::new(era_reader_adapter.clone())));
         deps_builder.block_scanner = Some(block_scanner.clone());
+        deps_builder.log_level_handle = Some(log_level_handle);
 
         let dependencies = deps_builder.build_dependency_container().await.unwrap();
         let runtime = deps_builder.create_aggregator_runner().await.unwrap();
@@ -13,13 +13,15 @@ use crate::{
     commands::{client_builder, SharedArgs},
     configuration::{ConfigError, ConfigSource},
     utils::{
-        CardanoDbDownloadChecker, CardanoDbUtils, ExpanderUtils, IndicatifFeedbackReceiver,
-        ProgressOutputType, ProgressPrinter,
+        CardanoDbDownloadChecker, CardanoDbUtils, DownloadTelemetry, ExpanderUtils,
+        IndicatifFeedbackReceiver, ProgressOutputType, ProgressPrinter,
     },
     CommandContext,
 };
 use mithril_client::{
-    common::ProtocolMessage, Client, MessageBuilder, MithrilCertificate, MithrilResult, Snapshot,
+    common::{ProtocolMessage, SignedEntityTypeDiscriminants},
+    error_codes::MithrilErrorCode,
+    Client, MessageBuilder, MithrilCertificate, MithrilResult, Snapshot,
 };
 
 /// Clap command to download a Cardano db and verify its associated certificate.
@@ -39,7 +41,7 @@ pub struct CardanoDbDownloadCommand {
     #[clap(long)]
     download_dir: Option<PathBuf>,
 
-    /// Genesis Verification Key to check the certificate chain.
+    /// Genesis Verification Key to check the certificate chain, overrides the key bundled for the network.
     #[clap(long, env = "GENESIS_VERIFICATION_KEY")]
     genesis_verification_key: Option<String>,
 }
@@ -71,6 +73,25 @@ impl CardanoDbDownloadCommand {
             .with_logger(logger.clone())
             .build()?;
 
+        let telemetry = DownloadTelemetry::start(
+            &params,
+            SignedEntityTypeDiscriminants::CardanoImmutableFilesFull,
+        );
+        let result = self
+            .download_and_verify(logger, &progress_printer, &client, &db_dir)
+            .await;
+        telemetry.report(&client, &result, logger).await;
+
+        result
+    }
+
+    async fn download_and_verify(
+        &self,
+        logger: &Logger,
+        progress_printer: &ProgressPrinter,
+        client: &Client,
+        db_dir: &Path,
+    ) -> MithrilResult<()> {
         let get_list_of_artifact_ids = || async {
             let cardano_dbs = client.snapshot().list().await.with_context(|| {
                 "Can not get the list of artifacts while retrieving the latest cardano db digest"
@@ -91,12 +112,12 @@ impl CardanoDbDownloadCommand {
             .await?
             .with_context(|| format!("Can not get the cardano db for digest: '{}'", self.digest))?;
 
-        Self::check_local_disk_info(1, &progress_printer, &db_dir, &cardano_db_message)?;
+        Self::check_local_disk_info(1, progress_printer, db_dir, &cardano_db_message)?;
 
         let certificate = Self::fetch_certificate_and_verifying_chain(
             2,
-            &progress_printer,
-            &client,
+            progress_printer,
+            client,
             &cardano_db_message.certificate_hash,
         )
         .await?;
@@ -104,10 +125,10 @@ impl CardanoDbDownloadCommand {
         Self::download_and_unpack_cardano_db(
             logger,
             3,
-            &progress_printer,
-            &client,
+            progress_printer,
+            client,
             &cardano_db_message,
-            &db_dir,
+            db_dir,
         )
         .await
         .with_context(|| {
@@ -118,24 +139,20 @@ impl CardanoDbDownloadCommand {
         })?;
 
         let message =
-            Self::compute_cardano_db_message(4, &progress_printer, &certificate, &db_dir).await?;
+            Self::compute_cardano_db_message(4, progress_printer, &certificate, db_dir).await?;
 
         Self::verify_cardano_db_signature(
             logger,
             5,
-            &progress_printer,
+            progress_printer,
             &certificate,
             &message,
             &cardano_db_message,
-            &db_dir,
+            db_dir,
         )
         .await?;
 
-        Self::log_download_information(
-            &db_dir,
-            &cardano_db_message,
-            self.is_json_output_enabled(),
-        )?;
+        Self::log_download_information(db_dir, &cardano_db_message, self.is_json_output_enabled())?;
 
         Ok(())
     }
@@ -267,7 +284,8 @@ impl CardanoDbDownloadCommand {
             return Err(anyhow!(
                 "Certificate verification failed (cardano db digest = '{}').",
                 cardano_db.digest.clone()
-            ));
+            )
+            .context(MithrilErrorCode::ArtifactMessageMismatch));
         }
 
         Ok(())
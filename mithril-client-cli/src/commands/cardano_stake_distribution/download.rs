@@ -1,5 +1,6 @@
 use anyhow::{anyhow, Context};
 use clap::Parser;
+use slog::warn;
 use std::sync::Arc;
 use std::{
     collections::HashMap,
@@ -13,6 +14,7 @@ use crate::{
     CommandContext,
 };
 use mithril_client::common::Epoch;
+use mithril_client::error_codes::MithrilErrorCode;
 use mithril_client::Client;
 use mithril_client::{CardanoStakeDistribution, MessageBuilder, MithrilResult};
 
@@ -33,7 +35,7 @@ pub struct CardanoStakeDistributionDownloadCommand {
     #[clap(long)]
     download_dir: Option<PathBuf>,
 
-    /// Genesis Verification Key to check the certificate chain.
+    /// Genesis Verification Key to check the certificate chain, overrides the key bundled for the network.
     #[clap(long, env = "GENESIS_VERIFICATION_KEY")]
     genesis_verification_key: Option<String>,
 }
@@ -115,7 +117,8 @@ impl CardanoStakeDistributionDownloadCommand {
                     "Certificate and message did not match:\ncertificate_message: '{}'\n computed_message: '{}'",
                     certificate.signed_message,
                     message.compute_hash()
-                ));
+                )
+                .context(MithrilErrorCode::ArtifactMessageMismatch));
         }
 
         progress_printer.report_step(4, "Writing fetched Cardano stake distribution to a file")?;
@@ -136,6 +139,18 @@ impl CardanoStakeDistributionDownloadCommand {
             })?,
         )?;
 
+        // The download does not fail if the statistic call fails.
+        if let Err(e) = client
+            .cardano_stake_distribution()
+            .add_statistics(&cardano_stake_distribution)
+            .await
+        {
+            warn!(
+                logger, "Could not increment Cardano stake distribution download statistics";
+                "error" => ?e
+            );
+        }
+
         if self.is_json_output_enabled() {
             println!(
                 r#"{{"cardano_stake_distribution_epoch": "{}", "filepath": "{}"}}"#,
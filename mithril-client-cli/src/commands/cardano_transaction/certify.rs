@@ -5,8 +5,9 @@ use slog::debug;
 use std::{collections::HashMap, sync::Arc};
 
 use mithril_client::{
-    common::TransactionHash, CardanoTransactionsProofs, MessageBuilder, MithrilCertificate,
-    MithrilResult, VerifiedCardanoTransactions, VerifyCardanoTransactionsProofsError,
+    common::TransactionHash, error_codes::MithrilErrorCode, CardanoTransactionsProofs,
+    MessageBuilder, MithrilCertificate, MithrilResult, VerifiedCardanoTransactions,
+    VerifyCardanoTransactionsProofsError,
 };
 
 use crate::utils::{IndicatifFeedbackReceiver, ProgressOutputType, ProgressPrinter};
@@ -22,7 +23,7 @@ pub struct CardanoTransactionsCertifyCommand {
     #[clap(flatten)]
     shared_args: SharedArgs,
 
-    /// Genesis Verification Key to check the certificate chain.
+    /// Genesis Verification Key to check the certificate chain, overrides the key bundled for the network.
     #[clap(long, env = "GENESIS_VERIFICATION_KEY")]
     genesis_verification_key: Option<String>,
 
@@ -134,7 +135,8 @@ Mithril may not have signed those transactions yet, please try again later."
             return Err(anyhow!(
                 "Proof and certificate don't match (certificate hash = '{}').",
                 certificate.hash
-            ));
+            )
+            .context(MithrilErrorCode::ArtifactMessageMismatch));
         }
 
         Ok(())
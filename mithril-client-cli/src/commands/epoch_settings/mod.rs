@@ -0,0 +1,26 @@
+//! Commands for the epoch settings of the aggregator
+mod show;
+
+pub use show::*;
+
+use crate::CommandContext;
+use clap::Subcommand;
+use mithril_client::MithrilResult;
+
+/// Epoch settings commands
+#[derive(Subcommand, Debug, Clone)]
+#[command(about = "Epoch settings of the aggregator")]
+pub enum EpochSettingsCommands {
+    /// Show the protocol parameters and the signer registration epochs of the current and next epochs
+    #[clap(arg_required_else_help = false)]
+    Show(EpochSettingsShowCommand),
+}
+
+impl EpochSettingsCommands {
+    /// Execute epoch settings command
+    pub async fn execute(&self, context: CommandContext) -> MithrilResult<()> {
+        match self {
+            Self::Show(cmd) => cmd.execute(context).await,
+        }
+    }
+}
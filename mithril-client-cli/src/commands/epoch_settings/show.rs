@@ -0,0 +1,210 @@
+use clap::Parser;
+use cli_table::{print_stdout, Cell, Table};
+use serde::Serialize;
+
+use crate::{
+    commands::{client_builder_with_fallback_genesis_key, SharedArgs},
+    CommandContext,
+};
+use mithril_client::{
+    common::{Epoch, ProtocolParameters},
+    EpochSettings, MithrilResult,
+};
+
+/// Clap command to show the settings of the current epoch of the aggregator
+#[derive(Parser, Debug, Clone)]
+pub struct EpochSettingsShowCommand {
+    #[clap(flatten)]
+    shared_args: SharedArgs,
+}
+
+/// Epoch settings as shown to the users, with the epochs of the signer registration round
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct EpochSettingsView {
+    epoch: Epoch,
+    current_protocol_parameters: Option<ProtocolParameters>,
+    next_protocol_parameters: Option<ProtocolParameters>,
+    signer_registration_protocol_parameters: ProtocolParameters,
+    current_signers: usize,
+    next_signers: usize,
+    registration: SignerRegistrationView,
+}
+
+/// Epochs of the signer registration round that is opened during the current epoch
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct SignerRegistrationView {
+    /// The registrations are accepted until the end of this epoch
+    closes_at_end_of_epoch: Epoch,
+
+    /// The signers registered during the round sign from this epoch
+    signing_from_epoch: Epoch,
+}
+
+impl From<EpochSettings> for EpochSettingsView {
+    fn from(epoch_settings: EpochSettings) -> Self {
+        #[allow(deprecated)]
+        let (current_protocol_parameters, next_protocol_parameters) = (
+            epoch_settings.protocol_parameters,
+            epoch_settings.next_protocol_parameters,
+        );
+
+        Self {
+            epoch: epoch_settings.epoch,
+            current_protocol_parameters,
+            next_protocol_parameters,
+            signer_registration_protocol_parameters: epoch_settings
+                .signer_registration_protocol_parameters,
+            current_signers: epoch_settings.current_signers.len(),
+            next_signers: epoch_settings.next_signers.len(),
+            registration: SignerRegistrationView {
+                closes_at_end_of_epoch: epoch_settings.epoch,
+                signing_from_epoch: epoch_settings.epoch.offset_to_signer_signing_offset(),
+            },
+        }
+    }
+}
+
+fn format_protocol_parameters(protocol_parameters: &ProtocolParameters) -> String {
+    format!(
+        "k={}, m={}, phi_f={}",
+        protocol_parameters.k, protocol_parameters.m, protocol_parameters.phi_f
+    )
+}
+
+impl EpochSettingsShowCommand {
+    /// Is JSON output enabled
+    pub fn is_json_output_enabled(&self) -> bool {
+        self.shared_args.json
+    }
+
+    /// Epoch settings Show command
+    pub async fn execute(&self, context: CommandContext) -> MithrilResult<()> {
+        let params = context.config_parameters()?;
+        let client = client_builder_with_fallback_genesis_key(&params)?
+            .with_logger(context.logger().clone())
+            .build()?;
+        let epoch_settings = EpochSettingsView::from(client.epoch_settings().get().await?);
+
+        if self.is_json_output_enabled() {
+            println!("{}", serde_json::to_string(&epoch_settings)?);
+        } else {
+            let epoch_settings_table = vec![
+                vec!["Epoch".cell(), format!("{}", epoch_settings.epoch).cell()],
+                vec![
+                    "Current Protocol Parameters".cell(),
+                    epoch_settings
+                        .current_protocol_parameters
+                        .as_ref()
+                        .map(format_protocol_parameters)
+                        .unwrap_or("-".to_string())
+                        .cell(),
+                ],
+                vec![
+                    "Next Protocol Parameters".cell(),
+                    epoch_settings
+                        .next_protocol_parameters
+                        .as_ref()
+                        .map(format_protocol_parameters)
+                        .unwrap_or("-".to_string())
+                        .cell(),
+                ],
+                vec![
+                    "Signer Registration Protocol Parameters".cell(),
+                    format_protocol_parameters(
+                        &epoch_settings.signer_registration_protocol_parameters,
+                    )
+                    .cell(),
+                ],
+                vec![
+                    "Current Signers".cell(),
+                    epoch_settings.current_signers.cell(),
+                ],
+                vec!["Next Signers".cell(), epoch_settings.next_signers.cell()],
+                vec![
+                    "Signer Registration Deadline".cell(),
+                    format!(
+                        "End of epoch {}",
+                        epoch_settings.registration.closes_at_end_of_epoch
+                    )
+                    .cell(),
+                ],
+                vec![
+                    "Registered Signers Sign From".cell(),
+                    format!("Epoch {}", epoch_settings.registration.signing_from_epoch).cell(),
+                ],
+            ]
+            .table();
+
+            print_stdout(epoch_settings_table)?
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_signers_registered_during_the_current_epoch_sign_two_epochs_later() {
+        let epoch_settings = EpochSettings {
+            epoch: Epoch(10),
+            ..EpochSettings::dummy()
+        };
+
+        let view = EpochSettingsView::from(epoch_settings);
+
+        assert_eq!(
+            SignerRegistrationView {
+                closes_at_end_of_epoch: Epoch(10),
+                signing_from_epoch: Epoch(12),
+            },
+            view.registration
+        );
+    }
+
+    #[test]
+    fn the_current_next_and_signer_registration_protocol_parameters_are_kept_apart() {
+        let current_protocol_parameters = ProtocolParameters::new(5, 100, 0.65);
+        let next_protocol_parameters = ProtocolParameters::new(10, 200, 0.5);
+        let registration_protocol_parameters = ProtocolParameters::new(20, 300, 0.2);
+        #[allow(deprecated)]
+        let epoch_settings = EpochSettings {
+            protocol_parameters: Some(current_protocol_parameters.clone()),
+            next_protocol_parameters: Some(next_protocol_parameters.clone()),
+            signer_registration_protocol_parameters: registration_protocol_parameters.clone(),
+            ..EpochSettings::dummy()
+        };
+
+        let view = EpochSettingsView::from(epoch_settings);
+
+        assert_eq!(
+            Some(current_protocol_parameters),
+            view.current_protocol_parameters
+        );
+        assert_eq!(
+            Some(next_protocol_parameters),
+            view.next_protocol_parameters
+        );
+        assert_eq!(
+            registration_protocol_parameters,
+            view.signer_registration_protocol_parameters
+        );
+    }
+
+    #[test]
+    fn the_deprecated_protocol_parameters_are_optional() {
+        #[allow(deprecated)]
+        let epoch_settings = EpochSettings {
+            protocol_parameters: None,
+            next_protocol_parameters: None,
+            ..EpochSettings::dummy()
+        };
+
+        let view = EpochSettingsView::from(epoch_settings);
+
+        assert_eq!(None, view.current_protocol_parameters);
+        assert_eq!(None, view.next_protocol_parameters);
+    }
+}
@@ -1,5 +1,6 @@
 use anyhow::Context;
 use clap::Parser;
+use slog::warn;
 use std::sync::Arc;
 use std::{
     collections::HashMap,
@@ -13,6 +14,7 @@ use crate::{
     utils::ExpanderUtils,
     CommandContext,
 };
+use mithril_client::error_codes::MithrilErrorCode;
 use mithril_client::MessageBuilder;
 use mithril_client::MithrilResult;
 
@@ -34,7 +36,7 @@ pub struct MithrilStakeDistributionDownloadCommand {
     #[clap(long)]
     download_dir: Option<PathBuf>,
 
-    /// Genesis Verification Key to check the certificate chain.
+    /// Genesis Verification Key to check the certificate chain, overrides the key bundled for the network.
     #[clap(long, env = "GENESIS_VERIFICATION_KEY")]
     genesis_verification_key: Option<String>,
 }
@@ -130,7 +132,8 @@ impl MithrilStakeDistributionDownloadCommand {
                     "Certificate and message did not match:\ncertificate_message: '{}'\n computed_message: '{}'",
                     certificate.signed_message,
                     message.compute_hash()
-                ));
+                )
+                .context(MithrilErrorCode::ArtifactMessageMismatch));
         }
 
         progress_printer.report_step(4, "Writing fetched Mithril stake distribution to a file")?;
@@ -151,6 +154,18 @@ impl MithrilStakeDistributionDownloadCommand {
             })?,
         )?;
 
+        // The download does not fail if the statistic call fails.
+        if let Err(e) = client
+            .mithril_stake_distribution()
+            .add_statistics(&mithril_stake_distribution)
+            .await
+        {
+            warn!(
+                logger, "Could not increment Mithril stake distribution download statistics";
+                "error" => ?e
+            );
+        }
+
         if self.is_json_output_enabled() {
             println!(
                 r#"{{"mithril_stake_distribution_hash": "{}", "filepath": "{}"}}"#,
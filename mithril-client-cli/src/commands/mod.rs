@@ -7,14 +7,24 @@ pub mod cardano_db;
 pub mod cardano_stake_distribution;
 pub mod cardano_transaction;
 mod deprecation;
+pub mod epoch_settings;
 pub mod mithril_stake_distribution;
+pub mod tools;
 
 pub use deprecation::{DeprecatedCommand, Deprecation};
 
+use anyhow::anyhow;
 use clap::Args;
-use mithril_client::{ClientBuilder, MithrilResult};
+use mithril_client::{
+    common::{CardanoNetwork, SignedEntityTypeDiscriminants},
+    genesis_verification_keys::MithrilNetwork,
+    AggregatorCapabilities, ClientBuilder, MithrilResult,
+};
+use slog::warn;
+use std::path::Path;
 
-use crate::configuration::ConfigParameters;
+use crate::configuration::{ConfigError, ConfigParameters};
+use crate::CommandContext;
 
 /// Shared arguments for all commands
 #[derive(Debug, Clone, Args)]
@@ -27,10 +37,15 @@ pub struct SharedArgs {
 pub(crate) fn client_builder(params: &ConfigParameters) -> MithrilResult<ClientBuilder> {
     let builder = ClientBuilder::aggregator(
         &params.require("aggregator_endpoint")?,
-        &params.require("genesis_verification_key")?,
+        &genesis_verification_key(params)?.ok_or(ConfigError::Required(
+            "genesis_verification_key".to_string(),
+        ))?,
     );
 
-    Ok(builder)
+    Ok(with_static_mirror(
+        with_transparency_log(builder, params)?,
+        params,
+    ))
 }
 
 pub(crate) fn client_builder_with_fallback_genesis_key(
@@ -44,11 +59,225 @@ pub(crate) fn client_builder_with_fallback_genesis_key(
 
     let builder = ClientBuilder::aggregator(
         &params.require("aggregator_endpoint")?,
-        &params.get_or(
-            "genesis_verification_key",
-            fallback_genesis_verification_key,
-        ),
+        &genesis_verification_key(params)?.unwrap_or(fallback_genesis_verification_key.to_string()),
     );
 
-    Ok(builder)
+    Ok(with_static_mirror(
+        with_transparency_log(builder, params)?,
+        params,
+    ))
+}
+
+/// Check, from the capabilities advertised by the aggregator, that it certifies the given signed
+/// entity type.
+///
+/// The commands of the signed entity types not advertised by the aggregator are only run when the
+/// unstable commands are enabled. The check is skipped if the aggregator does not advertise its
+/// capabilities, the command then fails on its own if the signed entity type is not available.
+pub async fn check_aggregator_capabilities(
+    context: &CommandContext,
+    signed_entity_type: SignedEntityTypeDiscriminants,
+) -> MithrilResult<()> {
+    let params = context.config_parameters()?;
+    let client = client_builder_with_fallback_genesis_key(&params)?
+        .with_logger(context.logger().clone())
+        .build()?;
+
+    match client.aggregator_features().get().await {
+        Ok(features) => {
+            match ensure_signed_entity_type_enabled(&features.capabilities, signed_entity_type) {
+                Err(error) if context.is_unstable_enabled() => {
+                    warn!(context.logger(), "Running an unstable command"; "reason" => %error);
+                    Ok(())
+                }
+                result => result,
+            }
+        }
+        Err(error) => {
+            warn!(context.logger(), "Could not check the aggregator capabilities"; "error" => ?error);
+            Ok(())
+        }
+    }
+}
+
+fn ensure_signed_entity_type_enabled(
+    capabilities: &AggregatorCapabilities,
+    signed_entity_type: SignedEntityTypeDiscriminants,
+) -> MithrilResult<()> {
+    if capabilities.is_signed_entity_type_enabled(&signed_entity_type) {
+        return Ok(());
+    }
+
+    let available_commands = capabilities
+        .signed_entity_types
+        .iter()
+        .map(|discriminant| command_name(*discriminant))
+        .collect::<Vec<_>>();
+    Err(anyhow!(
+        "The \"{}\" command is not available: the aggregator does not certify {signed_entity_type}, available commands: {}.\n\n\
+        Use the --unstable flag to run it anyway.",
+        command_name(signed_entity_type),
+        available_commands.join(", ")
+    ))
+}
+
+/// Name of the command that handles the artifacts of the given signed entity type
+fn command_name(signed_entity_type: SignedEntityTypeDiscriminants) -> &'static str {
+    match signed_entity_type {
+        SignedEntityTypeDiscriminants::MithrilStakeDistribution => "mithril-stake-distribution",
+        SignedEntityTypeDiscriminants::CardanoStakeDistribution => "cardano-stake-distribution",
+        SignedEntityTypeDiscriminants::CardanoImmutableFilesFull => "cardano-db",
+        SignedEntityTypeDiscriminants::CardanoTransactions => "cardano-transaction",
+    }
+}
+
+/// Require the certificates to be included in the transparency log if one is given in the
+/// parameters, its verification key is then required.
+///
+/// The last tree head seen is kept in the transparency log state file if one is given.
+fn with_transparency_log(
+    builder: ClientBuilder,
+    params: &ConfigParameters,
+) -> MithrilResult<ClientBuilder> {
+    match params.get("transparency_log_endpoint") {
+        Some(transparency_log_endpoint) => {
+            let builder = builder.with_transparency_log(
+                &transparency_log_endpoint,
+                &params.require("transparency_log_verification_key")?,
+            );
+
+            Ok(match params.get("transparency_log_state") {
+                Some(state_file) => {
+                    builder.with_transparency_log_state_file(Path::new(&state_file))
+                }
+                None => builder,
+            })
+        }
+        None => Ok(builder),
+    }
+}
+
+fn with_static_mirror(builder: ClientBuilder, params: &ConfigParameters) -> ClientBuilder {
+    let static_mirror = params
+        .get("static_mirror")
+        .and_then(|value| value.parse::<bool>().ok())
+        .unwrap_or(false);
+
+    if static_mirror {
+        builder.with_static_mirror(true)
+    } else {
+        builder
+    }
+}
+
+/// Get the genesis verification key given in the parameters, or else the one bundled for the
+/// given network.
+fn genesis_verification_key(params: &ConfigParameters) -> MithrilResult<Option<String>> {
+    if let Some(genesis_verification_key) = params.get("genesis_verification_key") {
+        return Ok(Some(genesis_verification_key));
+    }
+
+    match params.get("network") {
+        Some(network) => match network.parse::<MithrilNetwork>() {
+            Ok(network) => Ok(Some(network.genesis_verification_key().to_string())),
+            Err(_) => {
+                let cardano_network: CardanoNetwork = network.parse().map_err(|_| {
+                    anyhow!(
+                        "Unknown network '{network}', expected one of: mainnet, preprod, preview, or a custom network given as '<network>:<magic>' or as its magic"
+                    )
+                })?;
+                Err(anyhow!(
+                    "No genesis verification key is bundled for the custom network '{network}' (magic {}), it must be given with the genesis verification key parameter",
+                    cardano_network.code()
+                ))
+            }
+        },
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mithril_client::AggregatorFeatures;
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    #[test]
+    fn a_signed_entity_type_not_certified_by_the_aggregator_is_not_available() {
+        let capabilities = AggregatorCapabilities {
+            signed_entity_types: BTreeSet::from([
+                SignedEntityTypeDiscriminants::MithrilStakeDistribution,
+                SignedEntityTypeDiscriminants::CardanoImmutableFilesFull,
+            ]),
+            ..AggregatorFeatures::dummy().capabilities
+        };
+
+        ensure_signed_entity_type_enabled(
+            &capabilities,
+            SignedEntityTypeDiscriminants::CardanoImmutableFilesFull,
+        )
+        .unwrap();
+        let error = ensure_signed_entity_type_enabled(
+            &capabilities,
+            SignedEntityTypeDiscriminants::CardanoTransactions,
+        )
+        .expect_err("A signed entity type not certified by the aggregator should fail");
+        assert!(
+            error
+                .to_string()
+                .contains("available commands: mithril-stake-distribution, cardano-db"),
+            "unexpected error: {error}"
+        );
+    }
+
+    #[test]
+    fn use_the_bundled_genesis_verification_key_of_the_network() {
+        let params = ConfigParameters::build(&[("network", "mainnet")]);
+
+        assert_eq!(
+            Some(
+                MithrilNetwork::Mainnet
+                    .genesis_verification_key()
+                    .to_string()
+            ),
+            genesis_verification_key(&params).unwrap()
+        );
+    }
+
+    #[test]
+    fn the_given_genesis_verification_key_overrides_the_bundled_one() {
+        let params = ConfigParameters::build(&[
+            ("network", "mainnet"),
+            ("genesis_verification_key", "custom-key"),
+        ]);
+
+        assert_eq!(
+            Some("custom-key".to_string()),
+            genesis_verification_key(&params).unwrap()
+        );
+    }
+
+    #[test]
+    fn fail_if_the_network_is_unknown() {
+        let params = ConfigParameters::build(&[("network", "devnet")]);
+
+        genesis_verification_key(&params).expect_err("An unknown network should fail");
+    }
+
+    #[test]
+    fn custom_network_requires_the_genesis_verification_key() {
+        let params = ConfigParameters::build(&[("network", "devnet:42")]);
+        genesis_verification_key(&params)
+            .expect_err("A custom network without genesis verification key should fail");
+
+        let params = ConfigParameters::build(&[
+            ("network", "devnet:42"),
+            ("genesis_verification_key", "custom-key"),
+        ]);
+        assert_eq!(
+            Some("custom-key".to_string()),
+            genesis_verification_key(&params).unwrap()
+        );
+    }
 }
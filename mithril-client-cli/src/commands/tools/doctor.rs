@@ -0,0 +1,454 @@
+use clap::Parser;
+use cli_table::{print_stdout, Cell, Table};
+use human_bytes::human_bytes;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::commands::{client_builder, client_builder_with_fallback_genesis_key, SharedArgs};
+use crate::configuration::{ConfigError, ConfigParameters, ConfigSource};
+use crate::CommandContext;
+use mithril_client::{Client, MithrilResult};
+
+/// Status of a check of the [ToolsDoctorCommand]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticStatus {
+    /// The check passed
+    Ok,
+
+    /// The check passed but something may prevent a command to succeed
+    Warning,
+
+    /// The check failed
+    Failed,
+}
+
+/// Result of a check of the [ToolsDoctorCommand]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    /// Name of the check
+    pub check: &'static str,
+
+    /// Status of the check
+    pub status: DiagnosticStatus,
+
+    /// Details of the result
+    pub message: String,
+}
+
+impl Diagnostic {
+    fn new(check: &'static str, status: DiagnosticStatus, message: String) -> Self {
+        Self {
+            check,
+            status,
+            message,
+        }
+    }
+}
+
+/// Check the configuration and the environment of the client and print a diagnostic report:
+/// connectivity to the aggregator, validity of the genesis verification key, and write
+/// permissions and free space of the download directory.
+#[derive(Parser, Debug, Clone)]
+pub struct ToolsDoctorCommand {
+    #[clap(flatten)]
+    shared_args: SharedArgs,
+
+    /// Directory where the artifacts are downloaded.
+    #[clap(long)]
+    download_dir: Option<PathBuf>,
+
+    /// Genesis Verification Key to check the certificate chain, overrides the key bundled for the network.
+    #[clap(long, env = "GENESIS_VERIFICATION_KEY")]
+    genesis_verification_key: Option<String>,
+}
+
+impl ToolsDoctorCommand {
+    /// Is JSON output enabled
+    pub fn is_json_output_enabled(&self) -> bool {
+        self.shared_args.json
+    }
+
+    /// Main command execution
+    pub async fn execute(&self, context: CommandContext) -> MithrilResult<()> {
+        let params = context.config_parameters()?.add_source(self)?;
+        let download_dir = PathBuf::from(params.get_or("download_dir", "."));
+        let mut diagnostics = vec![];
+
+        // The connectivity is checked with a client that does not depend on the configured
+        // genesis verification key, so an invalid key does not hide a connectivity issue.
+        let aggregator_client = match params.require("aggregator_endpoint") {
+            Ok(endpoint) => {
+                let aggregator_params = ConfigParameters::new(HashMap::from([(
+                    "aggregator_endpoint".to_string(),
+                    endpoint.clone(),
+                )]));
+                let client = client_builder_with_fallback_genesis_key(&aggregator_params)?
+                    .with_logger(context.logger().clone())
+                    .build()?;
+                let diagnostic = check_aggregator_connectivity(&client, &endpoint).await;
+                let is_reachable = diagnostic.status == DiagnosticStatus::Ok;
+                diagnostics.push(diagnostic);
+
+                is_reachable.then_some(client)
+            }
+            Err(error) => {
+                diagnostics.push(Diagnostic::new(
+                    AGGREGATOR_CONNECTIVITY,
+                    DiagnosticStatus::Failed,
+                    error.to_string(),
+                ));
+                None
+            }
+        };
+
+        diagnostics.push(match client_builder(&params) {
+            Err(error) => Diagnostic::new(
+                GENESIS_VERIFICATION_KEY,
+                DiagnosticStatus::Failed,
+                format!("{error:#}"),
+            ),
+            Ok(builder) => match builder.with_logger(context.logger().clone()).build() {
+                Err(error) => Diagnostic::new(
+                    GENESIS_VERIFICATION_KEY,
+                    DiagnosticStatus::Failed,
+                    format!("Invalid genesis verification key: {error:#}"),
+                ),
+                Ok(_) if aggregator_client.is_none() => Diagnostic::new(
+                    GENESIS_VERIFICATION_KEY,
+                    DiagnosticStatus::Warning,
+                    "The certificate chain could not be verified: the aggregator is not reachable"
+                        .to_string(),
+                ),
+                Ok(client) => check_genesis_verification_key(&client).await,
+            },
+        });
+
+        let required_space = match &aggregator_client {
+            Some(client) => latest_snapshot_required_space(client).await,
+            None => None,
+        };
+        diagnostics.extend(check_download_dir(&download_dir, required_space));
+
+        self.print_report(&diagnostics)?;
+
+        let failed_checks = diagnostics
+            .iter()
+            .filter(|d| d.status == DiagnosticStatus::Failed)
+            .count();
+        if failed_checks > 0 {
+            return Err(anyhow::anyhow!("{failed_checks} check(s) failed"));
+        }
+
+        Ok(())
+    }
+
+    fn print_report(&self, diagnostics: &[Diagnostic]) -> MithrilResult<()> {
+        if self.is_json_output_enabled() {
+            println!("{}", serde_json::to_string(diagnostics)?);
+        } else {
+            let rows = diagnostics
+                .iter()
+                .map(|diagnostic| {
+                    vec![
+                        diagnostic.check.cell(),
+                        format!("{:?}", diagnostic.status).cell(),
+                        diagnostic.message.as_str().cell(),
+                    ]
+                })
+                .collect::<Vec<_>>()
+                .table()
+                .title(vec!["Check".cell(), "Status".cell(), "Details".cell()]);
+            print_stdout(rows)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl ConfigSource for ToolsDoctorCommand {
+    fn collect(&self) -> Result<HashMap<String, String>, ConfigError> {
+        let mut map = HashMap::new();
+
+        if let Some(download_dir) = self.download_dir.clone() {
+            map.insert(
+                "download_dir".to_string(),
+                download_dir
+                    .to_str()
+                    .ok_or_else(|| {
+                        ConfigError::Conversion(format!(
+                            "Could not read download directory: '{}'.",
+                            download_dir.display()
+                        ))
+                    })?
+                    .to_string(),
+            );
+        }
+
+        if let Some(genesis_verification_key) = self.genesis_verification_key.clone() {
+            map.insert(
+                "genesis_verification_key".to_string(),
+                genesis_verification_key,
+            );
+        }
+
+        Ok(map)
+    }
+}
+
+const AGGREGATOR_CONNECTIVITY: &str = "aggregator_connectivity";
+const GENESIS_VERIFICATION_KEY: &str = "genesis_verification_key";
+const DOWNLOAD_DIR_PERMISSIONS: &str = "download_dir_permissions";
+const DOWNLOAD_DIR_FREE_SPACE: &str = "download_dir_free_space";
+
+async fn check_aggregator_connectivity(client: &Client, endpoint: &str) -> Diagnostic {
+    match client.aggregator_features().get().await {
+        Ok(features) => Diagnostic::new(
+            AGGREGATOR_CONNECTIVITY,
+            DiagnosticStatus::Ok,
+            format!(
+                "Aggregator '{endpoint}' is reachable, API version: {}",
+                features.open_api_version
+            ),
+        ),
+        Err(error) => Diagnostic::new(
+            AGGREGATOR_CONNECTIVITY,
+            DiagnosticStatus::Failed,
+            format!("Aggregator '{endpoint}' is not reachable: {error:#}"),
+        ),
+    }
+}
+
+/// Check the genesis verification key by verifying the certificate chain of the latest
+/// certificate of the aggregator
+async fn check_genesis_verification_key(client: &Client) -> Diagnostic {
+    let latest_certificate = match client.certificate().list().await {
+        Ok(certificates) => certificates.into_iter().next(),
+        Err(error) => {
+            return Diagnostic::new(
+                GENESIS_VERIFICATION_KEY,
+                DiagnosticStatus::Warning,
+                format!("The certificates could not be listed: {error:#}"),
+            )
+        }
+    };
+
+    match latest_certificate {
+        None => Diagnostic::new(
+            GENESIS_VERIFICATION_KEY,
+            DiagnosticStatus::Warning,
+            "The aggregator has no certificate to verify".to_string(),
+        ),
+        Some(certificate) => match client.certificate().verify_chain(&certificate.hash).await {
+            Ok(_) => Diagnostic::new(
+                GENESIS_VERIFICATION_KEY,
+                DiagnosticStatus::Ok,
+                format!(
+                    "The certificate chain of the latest certificate '{}' is valid",
+                    certificate.hash
+                ),
+            ),
+            Err(error) => Diagnostic::new(
+                GENESIS_VERIFICATION_KEY,
+                DiagnosticStatus::Failed,
+                format!(
+                    "The certificate chain of the latest certificate '{}' is invalid, the genesis verification key may not match the aggregator network: {error:#}",
+                    certificate.hash
+                ),
+            ),
+        },
+    }
+}
+
+/// Space needed to download and unpack the latest Cardano database of the aggregator
+async fn latest_snapshot_required_space(client: &Client) -> Option<u64> {
+    let snapshots = client.snapshot().list().await.ok()?;
+
+    snapshots.first().map(|snapshot| {
+        (snapshot.size as f64
+            * snapshot
+                .compression_algorithm
+                .unwrap_or_default()
+                .free_space_snapshot_ratio()) as u64
+    })
+}
+
+fn check_download_dir(download_dir: &Path, required_space: Option<u64>) -> Vec<Diagnostic> {
+    if !download_dir.is_dir() {
+        return vec![Diagnostic::new(
+            DOWNLOAD_DIR_PERMISSIONS,
+            DiagnosticStatus::Warning,
+            format!(
+                "Download directory '{}' does not exist, it will be created by the download",
+                download_dir.display()
+            ),
+        )];
+    }
+
+    let permissions = {
+        let probe_file = download_dir.join(".mithril-client-doctor");
+        match fs::write(&probe_file, b"").and_then(|_| fs::remove_file(&probe_file)) {
+            Ok(()) => Diagnostic::new(
+                DOWNLOAD_DIR_PERMISSIONS,
+                DiagnosticStatus::Ok,
+                format!(
+                    "Download directory '{}' is writable",
+                    download_dir.display()
+                ),
+            ),
+            Err(error) => Diagnostic::new(
+                DOWNLOAD_DIR_PERMISSIONS,
+                DiagnosticStatus::Failed,
+                format!(
+                    "Download directory '{}' is not writable: {error}",
+                    download_dir.display()
+                ),
+            ),
+        }
+    };
+
+    let free_space = match (fs2::available_space(download_dir), required_space) {
+        (Err(error), _) => Diagnostic::new(
+            DOWNLOAD_DIR_FREE_SPACE,
+            DiagnosticStatus::Warning,
+            format!("The free space could not be read: {error}"),
+        ),
+        (Ok(free_space), Some(required_space)) if free_space < required_space => Diagnostic::new(
+            DOWNLOAD_DIR_FREE_SPACE,
+            DiagnosticStatus::Failed,
+            format!(
+                "{} free, the latest Cardano database needs {}",
+                human_bytes(free_space as f64),
+                human_bytes(required_space as f64)
+            ),
+        ),
+        (Ok(free_space), Some(required_space)) => Diagnostic::new(
+            DOWNLOAD_DIR_FREE_SPACE,
+            DiagnosticStatus::Ok,
+            format!(
+                "{} free, the latest Cardano database needs {}",
+                human_bytes(free_space as f64),
+                human_bytes(required_space as f64)
+            ),
+        ),
+        (Ok(free_space), None) => Diagnostic::new(
+            DOWNLOAD_DIR_FREE_SPACE,
+            DiagnosticStatus::Ok,
+            format!("{} free", human_bytes(free_space as f64)),
+        ),
+    };
+
+    vec![permissions, free_space]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use mithril_client::aggregator_client::AggregatorRequest;
+    use mithril_client::test_utils::{CertificateChainFixtureBuilder, FakeAggregator};
+    use mithril_client::{AggregatorFeatures, ClientBuilder};
+    use mithril_common::crypto_helper::ProtocolGenesisSigner;
+    use mithril_common::test_utils::TempDir;
+
+    use super::*;
+
+    fn statuses(diagnostics: &[Diagnostic]) -> Vec<(&str, DiagnosticStatus)> {
+        diagnostics.iter().map(|d| (d.check, d.status)).collect()
+    }
+
+    #[tokio::test]
+    async fn check_the_connectivity_and_the_genesis_verification_key() {
+        let certificate_chain = CertificateChainFixtureBuilder::new().build();
+        let client = ClientBuilder::new(certificate_chain.genesis_verification_key())
+            .with_aggregator_client(Arc::new(
+                FakeAggregator::new()
+                    .with_certificate_chain(&certificate_chain)
+                    .with_response(
+                        AggregatorRequest::GetAggregatorFeatures,
+                        &AggregatorFeatures::dummy(),
+                    ),
+            ))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            DiagnosticStatus::Ok,
+            check_aggregator_connectivity(&client, "http://aggregator")
+                .await
+                .status
+        );
+        assert_eq!(
+            DiagnosticStatus::Ok,
+            check_genesis_verification_key(&client).await.status
+        );
+    }
+
+    #[tokio::test]
+    async fn a_genesis_verification_key_of_another_network_fails() {
+        let certificate_chain = CertificateChainFixtureBuilder::new().build();
+        let other_network_genesis_verification_key =
+            ProtocolGenesisSigner::create_non_deterministic_genesis_signer()
+                .create_genesis_verifier()
+                .to_verification_key()
+                .to_json_hex()
+                .unwrap();
+        let client = ClientBuilder::new(&other_network_genesis_verification_key)
+            .with_aggregator_client(Arc::new(
+                FakeAggregator::new().with_certificate_chain(&certificate_chain),
+            ))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            DiagnosticStatus::Failed,
+            check_genesis_verification_key(&client).await.status
+        );
+    }
+
+    #[tokio::test]
+    async fn an_unreachable_aggregator_fails() {
+        let client = ClientBuilder::new(
+            CertificateChainFixtureBuilder::new()
+                .with_total_certificates(2)
+                .build()
+                .genesis_verification_key(),
+        )
+        .with_aggregator_client(Arc::new(FakeAggregator::new()))
+        .build()
+        .unwrap();
+
+        assert_eq!(
+            DiagnosticStatus::Failed,
+            check_aggregator_connectivity(&client, "http://aggregator")
+                .await
+                .status
+        );
+    }
+
+    #[test]
+    fn check_the_permissions_and_the_free_space_of_the_download_dir() {
+        let download_dir = TempDir::create("tools_doctor", "check_the_download_dir");
+
+        assert_eq!(
+            vec![
+                (DOWNLOAD_DIR_PERMISSIONS, DiagnosticStatus::Ok),
+                (DOWNLOAD_DIR_FREE_SPACE, DiagnosticStatus::Ok),
+            ],
+            statuses(&check_download_dir(&download_dir, Some(1)))
+        );
+        assert_eq!(
+            vec![
+                (DOWNLOAD_DIR_PERMISSIONS, DiagnosticStatus::Ok),
+                (DOWNLOAD_DIR_FREE_SPACE, DiagnosticStatus::Failed),
+            ],
+            statuses(&check_download_dir(&download_dir, Some(u64::MAX)))
+        );
+        assert_eq!(
+            vec![(DOWNLOAD_DIR_PERMISSIONS, DiagnosticStatus::Warning)],
+            statuses(&check_download_dir(&download_dir.join("missing"), None))
+        );
+    }
+}
@@ -0,0 +1,51 @@
+//! Commands for the tools that work on the restored Cardano databases
+mod doctor;
+mod utxo_hd_convert;
+
+pub use doctor::*;
+pub use utxo_hd_convert::*;
+
+use crate::CommandContext;
+use clap::Subcommand;
+use mithril_client::MithrilResult;
+
+/// Tools commands
+#[derive(Subcommand, Debug, Clone)]
+#[command(about = "Tools to work on the restored Cardano databases [unstable]")]
+pub enum ToolsCommands {
+    /// Check the configuration and the environment of the client
+    #[clap(arg_required_else_help = false)]
+    Doctor(ToolsDoctorCommand),
+
+    /// UTxO-HD commands
+    #[clap(subcommand)]
+    UtxoHd(UTxOHDCommands),
+}
+
+impl ToolsCommands {
+    /// Execute tools command
+    pub async fn execute(&self, context: CommandContext) -> MithrilResult<()> {
+        match self {
+            Self::Doctor(cmd) => cmd.execute(context).await,
+            Self::UtxoHd(cmd) => cmd.execute(context).await,
+        }
+    }
+}
+
+/// UTxO-HD commands
+#[derive(Subcommand, Debug, Clone)]
+#[command(about = "UTxO-HD ledger snapshots management")]
+pub enum UTxOHDCommands {
+    /// Convert the latest ledger snapshot of a restored Cardano database to another format
+    #[clap(arg_required_else_help = true)]
+    Convert(UTxOHDConvertCommand),
+}
+
+impl UTxOHDCommands {
+    /// Execute UTxO-HD command
+    pub async fn execute(&self, context: CommandContext) -> MithrilResult<()> {
+        match self {
+            Self::Convert(cmd) => cmd.execute(context).await,
+        }
+    }
+}
@@ -0,0 +1,509 @@
+use anyhow::{anyhow, Context};
+use chrono::Utc;
+use clap::{Parser, ValueEnum};
+use slog::{debug, Logger};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+
+use crate::{commands::SharedArgs, CommandContext};
+use mithril_client::MithrilResult;
+
+/// Name of the directory of a Cardano database that holds the ledger snapshots
+const LEDGER_DIR: &str = "ledger";
+
+/// Name of the directory of a Cardano database where the converted ledger snapshots are kept
+const LEDGER_BACKUP_DIR: &str = "ledger-backup";
+
+/// On-disk formats of the ledger snapshots of a Cardano node
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LedgerSnapshotFormat {
+    /// Format of the nodes before UTxO-HD: the ledger state, UTxO set included, in a single file
+    Legacy,
+
+    /// UTxO-HD format of the in-memory backend: the UTxO set is kept apart from the ledger state
+    InMemory,
+
+    /// UTxO-HD format of the on-disk LMDB backend
+    Lmdb,
+}
+
+impl LedgerSnapshotFormat {
+    /// Detect the format of the ledger snapshot at the given path from its layout
+    pub fn detect(snapshot_path: &Path) -> MithrilResult<Self> {
+        let tables_dir = snapshot_path.join("tables");
+
+        if snapshot_path.is_file() {
+            Ok(Self::Legacy)
+        } else if tables_dir.join("tvar").is_file() {
+            Ok(Self::InMemory)
+        } else if tables_dir.join("data.mdb").is_file() {
+            Ok(Self::Lmdb)
+        } else {
+            Err(anyhow!(
+                "Unknown format for the ledger snapshot '{}'",
+                snapshot_path.display()
+            ))
+        }
+    }
+
+    /// Name of the format expected by the `snapshot-converter` of the Cardano node
+    fn converter_arg(&self) -> &'static str {
+        match self {
+            Self::Legacy => "Legacy",
+            Self::InMemory => "Mem",
+            Self::Lmdb => "LMDB",
+        }
+    }
+}
+
+/// Ledger snapshot converted by the [UTxOHDConvertCommand]
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ConvertedLedgerSnapshot {
+    snapshot_path: PathBuf,
+    from: LedgerSnapshotFormat,
+    backup_path: PathBuf,
+}
+
+/// Convert the latest ledger snapshot of a restored Cardano database from the legacy format to
+/// an UTxO-HD format, or vice versa, so the database is usable by the Cardano node version it
+/// is given to.
+///
+/// The conversion is done by the `snapshot-converter` binary shipped with the Cardano node, the
+/// converted ledger snapshot replaces the original one which is kept, suffixed with the time of
+/// the conversion, in the `ledger-backup` directory of the database.
+#[derive(Parser, Debug, Clone)]
+pub struct UTxOHDConvertCommand {
+    #[clap(flatten)]
+    shared_args: SharedArgs,
+
+    /// Directory of the restored Cardano database.
+    #[clap(long)]
+    db_directory: PathBuf,
+
+    /// Format to convert the latest ledger snapshot to.
+    #[clap(long, value_enum)]
+    to: LedgerSnapshotFormat,
+
+    /// Path to the `snapshot-converter` binary shipped with the Cardano node.
+    #[clap(
+        long,
+        env = "SNAPSHOT_CONVERTER_BIN",
+        default_value = "snapshot-converter"
+    )]
+    converter_bin: PathBuf,
+
+    /// Path to the configuration file of the Cardano node.
+    #[clap(long)]
+    cardano_node_config: PathBuf,
+}
+
+impl UTxOHDConvertCommand {
+    /// Is JSON output enabled
+    pub fn is_json_output_enabled(&self) -> bool {
+        self.shared_args.json
+    }
+
+    /// Main command execution
+    pub async fn execute(&self, context: CommandContext) -> MithrilResult<()> {
+        let converted = self.convert(context.logger()).await?;
+
+        if self.is_json_output_enabled() {
+            println!(
+                "{}",
+                serde_json::json!({
+                    "ledger_snapshot": converted.snapshot_path,
+                    "from": format!("{:?}", converted.from),
+                    "to": format!("{:?}", self.to),
+                    "backup": converted.backup_path,
+                })
+            );
+        } else {
+            println!(
+                "Ledger snapshot '{}' has been converted from the {:?} format to the {:?} format, the original snapshot is kept as '{}'.",
+                converted.snapshot_path.display(),
+                converted.from,
+                self.to,
+                converted.backup_path.display()
+            );
+        }
+
+        Ok(())
+    }
+
+    async fn convert(&self, logger: &Logger) -> MithrilResult<ConvertedLedgerSnapshot> {
+        let ledger_dir = self.db_directory.join(LEDGER_DIR);
+        let snapshot_path = find_latest_ledger_snapshot(&ledger_dir)?;
+        let from = LedgerSnapshotFormat::detect(&snapshot_path)?;
+        if from == self.to {
+            return Err(anyhow!(
+                "The ledger snapshot '{}' is already in the {:?} format",
+                snapshot_path.display(),
+                self.to
+            ));
+        }
+        let snapshot_name = snapshot_path
+            .file_name()
+            .ok_or(anyhow!("Invalid ledger snapshot path"))?
+            .to_owned();
+        let converted_path =
+            ledger_dir.join(format!("{}.converting", snapshot_name.to_string_lossy()));
+        remove_path_if_exists(&converted_path)?;
+
+        debug!(logger, "Converting ledger snapshot"; "snapshot" => %snapshot_path.display(), "from" => ?from, "to" => ?self.to);
+        self.run_converter(&snapshot_path, from, &converted_path)
+            .await
+            .with_context(|| {
+                format!(
+                    "Could not convert the ledger snapshot '{}'",
+                    snapshot_path.display()
+                )
+            })?;
+
+        let backup_dir = self.db_directory.join(LEDGER_BACKUP_DIR);
+        fs::create_dir_all(&backup_dir).with_context(|| {
+            format!(
+                "Could not create the ledger backup directory '{}'",
+                backup_dir.display()
+            )
+        })?;
+        let backup_path = backup_dir.join(format!(
+            "{}-{}",
+            snapshot_name.to_string_lossy(),
+            Utc::now().format("%Y%m%dT%H%M%S%.3fZ")
+        ));
+        if backup_path.exists() {
+            return Err(anyhow!(
+                "The ledger backup '{}' already exists",
+                backup_path.display()
+            ));
+        }
+        fs::rename(&snapshot_path, &backup_path).with_context(|| {
+            format!(
+                "Could not move the original ledger snapshot to '{}'",
+                backup_path.display()
+            )
+        })?;
+        fs::rename(&converted_path, &snapshot_path).with_context(|| {
+            format!(
+                "Could not move the converted ledger snapshot to '{}'",
+                snapshot_path.display()
+            )
+        })?;
+
+        Ok(ConvertedLedgerSnapshot {
+            snapshot_path,
+            from,
+            backup_path,
+        })
+    }
+
+    async fn run_converter(
+        &self,
+        input_path: &Path,
+        input_format: LedgerSnapshotFormat,
+        output_path: &Path,
+    ) -> MithrilResult<()> {
+        let output = Command::new(&self.converter_bin)
+            .arg(input_format.converter_arg())
+            .arg(input_path)
+            .arg(self.to.converter_arg())
+            .arg(output_path)
+            .arg("cardano")
+            .arg("--config")
+            .arg(&self.cardano_node_config)
+            .output()
+            .await
+            .with_context(|| {
+                format!(
+                    "Could not run the snapshot converter '{}'",
+                    self.converter_bin.display()
+                )
+            })?;
+
+        if !output.status.success() {
+            return Err(anyhow!(
+                "The snapshot converter exited with {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Find the latest ledger snapshot of the given ledger directory.
+///
+/// The ledger snapshots are named after their slot, optionally followed by a `_<suffix>`.
+fn find_latest_ledger_snapshot(ledger_dir: &Path) -> MithrilResult<PathBuf> {
+    let entries = fs::read_dir(ledger_dir).with_context(|| {
+        format!(
+            "Could not read the ledger directory '{}'",
+            ledger_dir.display()
+        )
+    })?;
+
+    let mut latest_snapshot: Option<(u64, PathBuf)> = None;
+    for entry in entries {
+        let path = entry?.path();
+        let slot = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.split('_').next())
+            .and_then(|slot| slot.parse::<u64>().ok());
+
+        if let Some(slot) = slot {
+            if latest_snapshot
+                .as_ref()
+                .map_or(true, |(latest_slot, _)| slot > *latest_slot)
+            {
+                latest_snapshot = Some((slot, path));
+            }
+        }
+    }
+
+    latest_snapshot.map(|(_, path)| path).ok_or(anyhow!(
+        "No ledger snapshot found in '{}'",
+        ledger_dir.display()
+    ))
+}
+
+fn remove_path_if_exists(path: &Path) -> MithrilResult<()> {
+    let result = if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else if path.exists() {
+        fs::remove_file(path)
+    } else {
+        Ok(())
+    };
+
+    result.with_context(|| format!("Could not remove '{}'", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use mithril_common::test_utils::TempDir;
+
+    use super::*;
+
+    fn create_in_memory_snapshot(path: &Path) {
+        fs::create_dir_all(path.join("tables")).unwrap();
+        fs::write(path.join("state"), "state").unwrap();
+        fs::write(path.join("tables").join("tvar"), "tvar").unwrap();
+    }
+
+    /// Write a fake `snapshot-converter` running the given shell script, its arguments being
+    /// `<from> <input> <to> <output> cardano --config <config>`
+    #[cfg(not(target_os = "windows"))]
+    fn write_fake_converter(dir: &Path, script: &str) -> PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+
+        let converter_bin = dir.join("snapshot-converter");
+        fs::write(&converter_bin, format!("#!/bin/sh\n{script}\n")).unwrap();
+        fs::set_permissions(&converter_bin, fs::Permissions::from_mode(0o755)).unwrap();
+
+        converter_bin
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    fn convert_command(
+        db_directory: &Path,
+        converter_bin: &Path,
+        to: LedgerSnapshotFormat,
+    ) -> UTxOHDConvertCommand {
+        UTxOHDConvertCommand {
+            shared_args: SharedArgs { json: false },
+            db_directory: db_directory.to_path_buf(),
+            to,
+            converter_bin: converter_bin.to_path_buf(),
+            cardano_node_config: db_directory.join("config.json"),
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    fn discard_logger() -> Logger {
+        Logger::root(slog::Discard, slog::o!())
+    }
+
+    #[test]
+    fn detect_the_format_of_a_ledger_snapshot() {
+        let dir = TempDir::create("utxo_hd_convert", "detect_the_format_of_a_ledger_snapshot");
+
+        fs::write(dir.join("100"), "legacy").unwrap();
+        assert_eq!(
+            LedgerSnapshotFormat::Legacy,
+            LedgerSnapshotFormat::detect(&dir.join("100")).unwrap()
+        );
+
+        create_in_memory_snapshot(&dir.join("200"));
+        assert_eq!(
+            LedgerSnapshotFormat::InMemory,
+            LedgerSnapshotFormat::detect(&dir.join("200")).unwrap()
+        );
+
+        fs::create_dir_all(dir.join("300").join("tables")).unwrap();
+        fs::write(dir.join("300").join("tables").join("data.mdb"), "").unwrap();
+        assert_eq!(
+            LedgerSnapshotFormat::Lmdb,
+            LedgerSnapshotFormat::detect(&dir.join("300")).unwrap()
+        );
+
+        fs::create_dir_all(dir.join("400")).unwrap();
+        LedgerSnapshotFormat::detect(&dir.join("400"))
+            .expect_err("An empty directory is not a ledger snapshot");
+    }
+
+    #[test]
+    fn find_the_latest_ledger_snapshot_by_slot() {
+        let ledger_dir = TempDir::create("utxo_hd_convert", "find_the_latest_ledger_snapshot");
+        fs::write(ledger_dir.join("900"), "").unwrap();
+        fs::write(ledger_dir.join("1000_db-analyser"), "").unwrap();
+        fs::write(ledger_dir.join("not-a-snapshot"), "").unwrap();
+
+        assert_eq!(
+            ledger_dir.join("1000_db-analyser"),
+            find_latest_ledger_snapshot(&ledger_dir).unwrap()
+        );
+    }
+
+    #[test]
+    fn fail_if_there_is_no_ledger_snapshot() {
+        let ledger_dir = TempDir::create("utxo_hd_convert", "fail_if_there_is_no_ledger_snapshot");
+        fs::write(ledger_dir.join("not-a-snapshot"), "").unwrap();
+
+        find_latest_ledger_snapshot(&ledger_dir)
+            .expect_err("A ledger directory without snapshot should fail");
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[tokio::test]
+    async fn convert_replaces_the_latest_snapshot_and_keeps_the_original_as_backup() {
+        let dir = TempDir::create("utxo_hd_convert", "convert_replaces_the_latest_snapshot");
+        fs::create_dir_all(dir.join(LEDGER_DIR)).unwrap();
+        fs::write(dir.join(LEDGER_DIR).join("900"), "older").unwrap();
+        fs::write(dir.join(LEDGER_DIR).join("1000"), "legacy").unwrap();
+        let converter_bin = write_fake_converter(
+            &dir,
+            r#"mkdir -p "$4/tables" && cp "$2" "$4/state" && echo "$1 to $3" > "$4/tables/tvar""#,
+        );
+        let command = convert_command(&dir, &converter_bin, LedgerSnapshotFormat::InMemory);
+
+        let converted = command.convert(&discard_logger()).await.unwrap();
+
+        let snapshot_path = dir.join(LEDGER_DIR).join("1000");
+        assert_eq!(snapshot_path, converted.snapshot_path);
+        assert_eq!(LedgerSnapshotFormat::Legacy, converted.from);
+        assert_eq!(
+            LedgerSnapshotFormat::InMemory,
+            LedgerSnapshotFormat::detect(&snapshot_path).unwrap()
+        );
+        assert_eq!(
+            "legacy",
+            fs::read_to_string(snapshot_path.join("state")).unwrap()
+        );
+        assert_eq!(
+            "Legacy to Mem\n",
+            fs::read_to_string(snapshot_path.join("tables").join("tvar")).unwrap()
+        );
+        assert_eq!(
+            dir.join(LEDGER_BACKUP_DIR),
+            converted.backup_path.parent().unwrap()
+        );
+        assert_eq!(
+            "legacy",
+            fs::read_to_string(&converted.backup_path).unwrap()
+        );
+        assert_eq!(
+            "older",
+            fs::read_to_string(dir.join(LEDGER_DIR).join("900")).unwrap()
+        );
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[tokio::test]
+    async fn convert_does_not_overwrite_the_previous_backups() {
+        let dir = TempDir::create(
+            "utxo_hd_convert",
+            "convert_does_not_overwrite_the_previous_backups",
+        );
+        fs::create_dir_all(dir.join(LEDGER_DIR)).unwrap();
+        fs::write(dir.join(LEDGER_DIR).join("1000"), "legacy").unwrap();
+        let converter_bin = write_fake_converter(
+            &dir,
+            r#"if [ "$3" = "Legacy" ]; then cp "$2/state" "$4"; else mkdir -p "$4/tables" && cp "$2" "$4/state" && touch "$4/tables/tvar"; fi"#,
+        );
+
+        let first_conversion =
+            convert_command(&dir, &converter_bin, LedgerSnapshotFormat::InMemory)
+                .convert(&discard_logger())
+                .await
+                .unwrap();
+        let second_conversion = convert_command(&dir, &converter_bin, LedgerSnapshotFormat::Legacy)
+            .convert(&discard_logger())
+            .await
+            .unwrap();
+
+        assert_ne!(first_conversion.backup_path, second_conversion.backup_path);
+        assert_eq!(
+            LedgerSnapshotFormat::Legacy,
+            LedgerSnapshotFormat::detect(&first_conversion.backup_path).unwrap()
+        );
+        assert_eq!(
+            LedgerSnapshotFormat::InMemory,
+            LedgerSnapshotFormat::detect(&second_conversion.backup_path).unwrap()
+        );
+        assert_eq!(
+            LedgerSnapshotFormat::Legacy,
+            LedgerSnapshotFormat::detect(&dir.join(LEDGER_DIR).join("1000")).unwrap()
+        );
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[tokio::test]
+    async fn convert_keeps_the_original_snapshot_when_the_converter_fails() {
+        let dir = TempDir::create(
+            "utxo_hd_convert",
+            "convert_keeps_the_original_snapshot_when_the_converter_fails",
+        );
+        fs::create_dir_all(dir.join(LEDGER_DIR)).unwrap();
+        fs::write(dir.join(LEDGER_DIR).join("1000"), "legacy").unwrap();
+        let converter_bin = write_fake_converter(
+            &dir,
+            r#"mkdir -p "$4"; echo "invalid snapshot" >&2; exit 1"#,
+        );
+        let command = convert_command(&dir, &converter_bin, LedgerSnapshotFormat::InMemory);
+
+        let error = command
+            .convert(&discard_logger())
+            .await
+            .expect_err("A failing converter should fail the conversion");
+
+        assert!(
+            format!("{error:?}").contains("invalid snapshot"),
+            "The error should contain the output of the converter: {error:?}"
+        );
+        assert_eq!(
+            "legacy",
+            fs::read_to_string(dir.join(LEDGER_DIR).join("1000")).unwrap()
+        );
+        assert!(!dir.join(LEDGER_BACKUP_DIR).exists());
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[tokio::test]
+    async fn convert_fails_if_the_snapshot_is_already_in_the_target_format() {
+        let dir = TempDir::create(
+            "utxo_hd_convert",
+            "convert_fails_if_the_snapshot_is_already_in_the_target_format",
+        );
+        create_in_memory_snapshot(&dir.join(LEDGER_DIR).join("1000"));
+        let converter_bin = write_fake_converter(&dir, "exit 1");
+        let command = convert_command(&dir, &converter_bin, LedgerSnapshotFormat::InMemory);
+
+        command
+            .convert(&discard_logger())
+            .await
+            .expect_err("A snapshot already in the target format should not be converted");
+    }
+}
@@ -0,0 +1,73 @@
+use mithril_client::error_codes::{error_code_of as client_error_code_of, MithrilErrorCode};
+use mithril_client::MithrilError;
+
+use crate::configuration::ConfigError;
+use crate::utils::CardanoDbDownloadCheckerError;
+
+/// Get the code, from the [error catalog][mithril_client::error_codes], of an error raised by
+/// a command.
+///
+/// The errors of the command line prevail, otherwise the code of the client library error is
+/// returned.
+pub fn error_code_of(error: &MithrilError) -> MithrilErrorCode {
+    error
+        .chain()
+        .find_map(|cause| {
+            if cause.is::<ConfigError>() || cause.is::<config::ConfigError>() {
+                return Some(MithrilErrorCode::InvalidConfiguration);
+            }
+
+            cause
+                .downcast_ref::<CardanoDbDownloadCheckerError>()
+                .map(|error| match error {
+                    CardanoDbDownloadCheckerError::NotEnoughSpace { .. } => {
+                        MithrilErrorCode::NotEnoughDiskSpace
+                    }
+                    CardanoDbDownloadCheckerError::UnpackDirectoryNotEmpty(_) => {
+                        MithrilErrorCode::DownloadDirectoryNotEmpty
+                    }
+                    CardanoDbDownloadCheckerError::UnpackDirectoryIsNotWritable(..) => {
+                        MithrilErrorCode::DownloadDirectoryNotWritable
+                    }
+                })
+        })
+        .unwrap_or_else(|| client_error_code_of(error))
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::{anyhow, Context};
+    use std::path::PathBuf;
+
+    use super::*;
+
+    #[test]
+    fn get_the_code_of_the_command_line_errors() {
+        let error = anyhow!(ConfigError::Required("aggregator_endpoint".to_string()))
+            .context("Could not build the client");
+        assert_eq!(
+            MithrilErrorCode::InvalidConfiguration,
+            error_code_of(&error)
+        );
+
+        let error = anyhow!(CardanoDbDownloadCheckerError::UnpackDirectoryNotEmpty(
+            PathBuf::from("db")
+        ));
+        assert_eq!(
+            MithrilErrorCode::DownloadDirectoryNotEmpty,
+            error_code_of(&error)
+        );
+    }
+
+    #[test]
+    fn fallback_to_the_code_of_the_client_library() {
+        let error: MithrilError = Err::<(), _>(anyhow!("invalid signature"))
+            .context(MithrilErrorCode::CertificateChainInvalid)
+            .unwrap_err();
+
+        assert_eq!(
+            MithrilErrorCode::CertificateChainInvalid,
+            error_code_of(&error)
+        );
+    }
+}
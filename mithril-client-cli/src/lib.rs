@@ -10,8 +10,10 @@
 mod command_context;
 pub mod commands;
 mod configuration;
+mod error_codes;
 mod utils;
 
 pub use command_context::*;
+pub use error_codes::*;
 /// Error Clap
 pub type ClapError = clap::error::Error;
@@ -9,15 +9,21 @@ use std::io::Write;
 use std::sync::Arc;
 use std::{fs::File, path::PathBuf};
 
-use mithril_client::MithrilResult;
+use mithril_client::{
+    common::SignedEntityTypeDiscriminants,
+    error_codes::{ErrorReport, MithrilErrorCode},
+    MithrilError, MithrilResult,
+};
 use mithril_doc::{Documenter, GenerateDocCommands, StructDoc};
 
 use mithril_client_cli::commands::{
     cardano_db::CardanoDbCommands, cardano_stake_distribution::CardanoStakeDistributionCommands,
-    cardano_transaction::CardanoTransactionCommands,
-    mithril_stake_distribution::MithrilStakeDistributionCommands, DeprecatedCommand, Deprecation,
+    cardano_transaction::CardanoTransactionCommands, check_aggregator_capabilities,
+    epoch_settings::EpochSettingsCommands,
+    mithril_stake_distribution::MithrilStakeDistributionCommands, tools::ToolsCommands,
+    DeprecatedCommand, Deprecation,
 };
-use mithril_client_cli::{ClapError, CommandContext};
+use mithril_client_cli::{error_code_of, ClapError, CommandContext};
 
 macro_rules! allow_unstable_dead_code {
     ($($item:item)*) => {
@@ -77,6 +83,29 @@ pub struct Args {
     #[example = "`https://aggregator.pre-release-preview.api.mithril.network/aggregator`"]
     aggregator_endpoint: Option<String>,
 
+    /// Mithril network whose bundled genesis verification key is used (mainnet, preprod or preview).
+    ///
+    /// A custom network can be given as `<network>:<magic>` or as its magic, in which case the
+    /// genesis verification key must be given too.
+    #[clap(long, env = "MITHRIL_NETWORK")]
+    #[example = "`mainnet`"]
+    network: Option<String>,
+
+    /// Require the verified certificates to be included in the transparency log at this endpoint.
+    #[clap(long, env = "TRANSPARENCY_LOG_ENDPOINT")]
+    #[example = "`https://transparency-log.mithril.network`"]
+    transparency_log_endpoint: Option<String>,
+
+    /// Verification key of the transparency log, required with the transparency log endpoint.
+    #[clap(long, env = "TRANSPARENCY_LOG_VERIFICATION_KEY")]
+    transparency_log_verification_key: Option<String>,
+
+    /// File keeping the last tree head of the transparency log seen, so that the next runs check
+    /// that the log extends it.
+    #[clap(long, env = "TRANSPARENCY_LOG_STATE")]
+    #[example = "`./transparency-log-state.json`"]
+    transparency_log_state: Option<String>,
+
     /// Enable JSON output for logs displayed according to verbosity level
     #[clap(long)]
     log_format_json: bool,
@@ -86,9 +115,22 @@ pub struct Args {
     #[example = "`./mithril-client.log`"]
     log_output: Option<String>,
 
-    /// Enable unstable commands
+    /// Enable unstable commands, including the commands of the artifacts not advertised by the
+    /// aggregator
     #[clap(long)]
     unstable: bool,
+
+    /// Send anonymous download success/failure and duration reports to the aggregator.
+    #[clap(long, conflicts_with = "no_telemetry")]
+    telemetry: bool,
+
+    /// Never send download reports to the aggregator, overrides the configuration.
+    #[clap(long)]
+    no_telemetry: bool,
+
+    /// The aggregator endpoint is a static mirror of an aggregator served by a CDN.
+    #[clap(long)]
+    static_mirror: bool,
 }
 
 impl Args {
@@ -99,7 +141,8 @@ impl Args {
         let config: ConfigBuilder<DefaultState> = config::Config::builder()
             .add_source(config::File::with_name(&filename).required(false))
             .add_source(self.clone())
-            .set_default("download_dir", "")?;
+            .set_default("download_dir", "")?
+            .set_default("telemetry", "false")?;
         let context = CommandContext::new(config, self.unstable, root_logger);
 
         self.command.execute(context).await
@@ -152,6 +195,24 @@ impl Args {
         Ok(Logger::root(Arc::new(drain), slog::o!()))
     }
 
+    /// Print the error with its code and remediation, as JSON if the logs are JSON
+    fn print_error(&self, error: &MithrilError) {
+        let report = ErrorReport::new(error_code_of(error), error);
+
+        if self.log_format_json {
+            match serde_json::to_string(&report) {
+                Ok(json) => eprintln!("{json}"),
+                Err(_) => eprintln!("Error: {error:?}"),
+            }
+        } else {
+            eprintln!(
+                "Error: {error:?}\n\nError code: {}\nRemediation: {}",
+                report.code.code(),
+                report.remediation
+            );
+        }
+    }
+
     fn parse_with_decorator(
         decorator: &dyn Fn(Result<Self, ClapError>) -> Result<Self, ClapError>,
     ) -> Self {
@@ -187,6 +248,53 @@ impl Source for Args {
             );
         }
 
+        if let Some(network) = self.network.clone() {
+            map.insert(
+                "network".to_string(),
+                Value::new(Some(&namespace), ValueKind::from(network)),
+            );
+        }
+
+        if let Some(transparency_log_endpoint) = self.transparency_log_endpoint.clone() {
+            map.insert(
+                "transparency_log_endpoint".to_string(),
+                Value::new(Some(&namespace), ValueKind::from(transparency_log_endpoint)),
+            );
+        }
+
+        if let Some(transparency_log_verification_key) =
+            self.transparency_log_verification_key.clone()
+        {
+            map.insert(
+                "transparency_log_verification_key".to_string(),
+                Value::new(
+                    Some(&namespace),
+                    ValueKind::from(transparency_log_verification_key),
+                ),
+            );
+        }
+
+        if let Some(transparency_log_state) = self.transparency_log_state.clone() {
+            map.insert(
+                "transparency_log_state".to_string(),
+                Value::new(Some(&namespace), ValueKind::from(transparency_log_state)),
+            );
+        }
+
+        if self.telemetry || self.no_telemetry {
+            map.insert(
+                "telemetry".to_string(),
+                Value::new(Some(&namespace), ValueKind::from(self.telemetry)),
+            );
+        }
+
+        if self.static_mirror {
+            map.insert(
+                "static_mirror".to_string(),
+                Value::new(Some(&namespace), ValueKind::from(true)),
+            );
+        }
+
         Ok(map)
     }
 }
@@ -205,23 +313,59 @@ enum ArtifactCommands {
     #[clap(subcommand, alias("csd"))]
     CardanoStakeDistribution(CardanoStakeDistributionCommands),
 
+    #[clap(subcommand)]
+    EpochSettings(EpochSettingsCommands),
+
+    #[clap(subcommand)]
+    Tools(ToolsCommands),
+
     #[clap(alias("doc"), hide(true))]
     GenerateDoc(GenerateDocCommands),
 }
 
 impl ArtifactCommands {
     pub async fn execute(&self, context: CommandContext) -> MithrilResult<()> {
+        if let Some(signed_entity_type) = self.signed_entity_type() {
+            check_aggregator_capabilities(&context, signed_entity_type).await?;
+        }
+
         match self {
             Self::CardanoDb(cmd) => cmd.execute(context).await,
             Self::MithrilStakeDistribution(cmd) => cmd.execute(context).await,
             Self::CardanoTransaction(cmd) => cmd.execute(context).await,
             Self::CardanoStakeDistribution(cmd) => cmd.execute(context).await,
+            Self::EpochSettings(cmd) => cmd.execute(context).await,
+            Self::Tools(cmd) => {
+                if !context.is_unstable_enabled() {
+                    return Err(anyhow!(Self::unstable_flag_missing_message(
+                        "tools",
+                        "utxo-hd convert --help"
+                    ))
+                    .context(MithrilErrorCode::UnstableCommandNotEnabled));
+                }
+                cmd.execute(context).await
+            }
             Self::GenerateDoc(cmd) => cmd
                 .execute(&mut Args::command())
                 .map_err(|message| anyhow!(message)),
         }
     }
 
+    /// Signed entity type of the artifacts handled by the command
+    fn signed_entity_type(&self) -> Option<SignedEntityTypeDiscriminants> {
+        match self {
+            Self::CardanoDb(_) => Some(SignedEntityTypeDiscriminants::CardanoImmutableFilesFull),
+            Self::MithrilStakeDistribution(_) => {
+                Some(SignedEntityTypeDiscriminants::MithrilStakeDistribution)
+            }
+            Self::CardanoTransaction(_) => Some(SignedEntityTypeDiscriminants::CardanoTransactions),
+            Self::CardanoStakeDistribution(_) => {
+                Some(SignedEntityTypeDiscriminants::CardanoStakeDistribution)
+            }
+            Self::EpochSettings(_) | Self::Tools(_) | Self::GenerateDoc(_) => None,
+        }
+    }
+
     allow_unstable_dead_code! {
         fn unstable_flag_missing_message(sub_command: &str, command_example: &str) -> String {
             format!(
@@ -247,5 +391,10 @@ async fn main() -> MithrilResult<()> {
     #[cfg(feature = "bundle_openssl")]
     openssl_probe::init_ssl_cert_env_vars();
 
-    args.execute(logger).await
+    if let Err(error) = args.execute(logger).await {
+        args.print_error(&error);
+        std::process::exit(1);
+    }
+
+    Ok(())
 }
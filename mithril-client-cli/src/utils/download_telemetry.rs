@@ -0,0 +1,132 @@
+use slog::{debug, warn, Logger};
+use std::time::Instant;
+
+use mithril_client::{common::SignedEntityTypeDiscriminants, Client, MithrilResult};
+
+use crate::configuration::ConfigParameters;
+
+/// Name of the configuration parameter that opts in to the download telemetry.
+pub const TELEMETRY_PARAMETER: &str = "telemetry";
+
+/// Opt-in telemetry that reports the outcome and the duration of a download to the aggregator.
+///
+/// The report is anonymous: it only holds the type of the downloaded artifact, whether the
+/// download succeeded and its duration. It is only sent if the `telemetry` parameter is `true`,
+/// which is not the default, and never makes the download fail.
+pub struct DownloadTelemetry {
+    enabled: bool,
+    artifact_type: SignedEntityTypeDiscriminants,
+    started_at: Instant,
+}
+
+impl DownloadTelemetry {
+    /// Start measuring the download of an artifact of the given type
+    pub fn start(params: &ConfigParameters, artifact_type: SignedEntityTypeDiscriminants) -> Self {
+        Self {
+            enabled: Self::is_enabled(params),
+            artifact_type,
+            started_at: Instant::now(),
+        }
+    }
+
+    /// Check if the user opted in to the telemetry
+    pub fn is_enabled(params: &ConfigParameters) -> bool {
+        params
+            .get(TELEMETRY_PARAMETER)
+            .and_then(|value| value.parse::<bool>().ok())
+            .unwrap_or(false)
+    }
+
+    /// Report the outcome of the download to the aggregator if the user opted in
+    pub async fn report<T>(self, client: &Client, result: &MithrilResult<T>, logger: &Logger) {
+        if !self.enabled {
+            return;
+        }
+
+        let duration = self.started_at.elapsed();
+        debug!(
+            logger, "Sending download telemetry";
+            "artifact_type" => ?self.artifact_type, "succeeded" => result.is_ok(), "duration" => ?duration
+        );
+        // The download does not fail if the telemetry call fails.
+        if let Err(e) = client
+            .telemetry()
+            .report_download(self.artifact_type, result.is_ok(), duration)
+            .await
+        {
+            warn!(logger, "Could not send the download telemetry"; "error" => ?e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+    use std::sync::Arc;
+
+    use mithril_client::aggregator_client::AggregatorRequest;
+    use mithril_client::test_utils::FakeAggregator;
+    use mithril_client::ClientBuilder;
+    use mithril_common::crypto_helper::ProtocolGenesisSigner;
+
+    use super::*;
+
+    fn build_client(fake_aggregator: Arc<FakeAggregator>) -> Client {
+        let genesis_verification_key = ProtocolGenesisSigner::create_deterministic_genesis_signer()
+            .create_genesis_verifier()
+            .to_verification_key()
+            .to_json_hex()
+            .unwrap();
+
+        ClientBuilder::new(&genesis_verification_key)
+            .with_aggregator_client(fake_aggregator)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn telemetry_is_disabled_by_default() {
+        assert!(!DownloadTelemetry::is_enabled(&ConfigParameters::default()));
+        assert!(!DownloadTelemetry::is_enabled(&ConfigParameters::build(&[
+            (TELEMETRY_PARAMETER, "false")
+        ])));
+        assert!(!DownloadTelemetry::is_enabled(&ConfigParameters::build(&[
+            (TELEMETRY_PARAMETER, "invalid")
+        ])));
+        assert!(DownloadTelemetry::is_enabled(&ConfigParameters::build(&[
+            (TELEMETRY_PARAMETER, "true")
+        ])));
+    }
+
+    #[tokio::test]
+    async fn report_the_outcome_of_the_download_only_if_enabled() {
+        let fake_aggregator = Arc::new(FakeAggregator::new());
+        let client = build_client(fake_aggregator.clone());
+        let logger = Logger::root(slog::Discard, slog::o!());
+        let failed_download: MithrilResult<()> = Err(anyhow!("download error"));
+
+        DownloadTelemetry::start(
+            &ConfigParameters::default(),
+            SignedEntityTypeDiscriminants::CardanoImmutableFilesFull,
+        )
+        .report(&client, &failed_download, &logger)
+        .await;
+        assert!(fake_aggregator.calls().is_empty());
+
+        DownloadTelemetry::start(
+            &ConfigParameters::build(&[(TELEMETRY_PARAMETER, "true")]),
+            SignedEntityTypeDiscriminants::CardanoImmutableFilesFull,
+        )
+        .report(&client, &failed_download, &logger)
+        .await;
+
+        let calls = fake_aggregator.calls();
+        assert_eq!(1, calls.len());
+        let AggregatorRequest::PostDownloadTelemetry { message } = &calls[0] else {
+            panic!("Expected a download telemetry request, got: {:?}", calls[0]);
+        };
+        let message: serde_json::Value = serde_json::from_str(message).unwrap();
+        assert_eq!("CardanoImmutableFilesFull", message["artifact_type"]);
+        assert_eq!(false, message["succeeded"]);
+    }
+}
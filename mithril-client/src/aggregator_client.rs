@@ -6,6 +6,10 @@
 //! [AggregatorRequest] enum.
 //!
 //! An implementation using HTTP is available: [AggregatorHTTPClient].
+//!
+//! The [AggregatorHTTPClient] can also read from a static mirror of an Aggregator, exported as
+//! JSON files served by a CDN, by enabling its [static mirror][AggregatorHTTPClient::with_static_mirror]
+//! mode: the certificates and the artifacts are verified the same way.
 
 use anyhow::{anyhow, Context};
 use async_recursion::async_recursion;
@@ -20,6 +24,7 @@ use thiserror::Error;
 use tokio::sync::RwLock;
 
 use mithril_common::entities::{ClientError, ServerError};
+use mithril_common::error_codes::MithrilErrorCode;
 use mithril_common::logging::LoggerExtensions;
 use mithril_common::MITHRIL_API_VERSION_HEADER;
 
@@ -46,9 +51,28 @@ pub enum AggregatorClientError {
     SubsystemError(#[source] MithrilError),
 }
 
+impl AggregatorClientError {
+    /// Code of the error in the [error catalog][mithril_common::error_codes], the code sent by
+    /// the aggregator if any
+    pub fn error_code(&self) -> MithrilErrorCode {
+        match self {
+            Self::RemoteServerTechnical(error) => MithrilErrorCode::find_in(error)
+                .unwrap_or(MithrilErrorCode::AggregatorInternalError),
+            Self::RemoteServerLogical(error) => MithrilErrorCode::find_in(error)
+                .unwrap_or(MithrilErrorCode::AggregatorRejectedRequest),
+            Self::ApiVersionMismatch(_) => MithrilErrorCode::ApiVersionMismatch,
+            Self::SubsystemError(_) => MithrilErrorCode::AggregatorUnreachable,
+        }
+    }
+}
+
 /// What can be read from an [AggregatorClient].
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum AggregatorRequest {
+    /// Get the [features][crate::AggregatorFeatures] advertised by the aggregator
+    GetAggregatorFeatures,
+    /// Get the [settings of the current epoch][crate::EpochSettings] of the aggregator
+    GetEpochSettings,
     /// Get a specific [certificate][crate::MithrilCertificate] from the aggregator
     GetCertificate {
         /// Hash of the certificate to retrieve
@@ -77,6 +101,18 @@ pub enum AggregatorRequest {
         snapshot: String,
     },
 
+    /// Increments the aggregator artifact download statistics
+    IncrementArtifactDownloadStatistic {
+        /// Artifact download message as HTTP request body
+        message: String,
+    },
+
+    /// Sends an anonymous download telemetry report to the aggregator statistics
+    PostDownloadTelemetry {
+        /// Download telemetry message as HTTP request body
+        message: String,
+    },
+
     /// Get proofs that the given set of Cardano transactions is included in the global Cardano transactions set
     GetTransactionsProofs {
         /// Hashes of the transactions to get proofs for.
@@ -112,6 +148,8 @@ impl AggregatorRequest {
     /// Get the request route relative to the aggregator root endpoint.
     pub fn route(&self) -> String {
         match self {
+            AggregatorRequest::GetAggregatorFeatures => String::new(),
+            AggregatorRequest::GetEpochSettings => "epoch-settings".to_string(),
             AggregatorRequest::GetCertificate { hash } => {
                 format!("certificate/{hash}")
             }
@@ -129,6 +167,12 @@ impl AggregatorRequest {
             AggregatorRequest::IncrementSnapshotStatistic { snapshot: _ } => {
                 "statistics/snapshot".to_string()
             }
+            AggregatorRequest::IncrementArtifactDownloadStatistic { message: _ } => {
+                "statistics/artifact-download".to_string()
+            }
+            AggregatorRequest::PostDownloadTelemetry { message: _ } => {
+                "statistics/download-telemetry".to_string()
+            }
             AggregatorRequest::GetTransactionsProofs {
                 transactions_hashes,
             } => format!(
@@ -153,12 +197,32 @@ impl AggregatorRequest {
         }
     }
 
+    /// Get the path of the file that holds the response of the request in a static mirror,
+    /// relative to the mirror root endpoint.
+    ///
+    /// Return `None` if the request is not available from a static mirror.
+    pub fn static_mirror_route(&self) -> Option<String> {
+        match self {
+            AggregatorRequest::GetAggregatorFeatures
+            | AggregatorRequest::GetEpochSettings
+            | AggregatorRequest::GetTransactionsProofs { .. }
+            | AggregatorRequest::IncrementSnapshotStatistic { .. }
+            | AggregatorRequest::IncrementArtifactDownloadStatistic { .. }
+            | AggregatorRequest::PostDownloadTelemetry { .. } => None,
+            _ => Some(format!("{}.json", self.route())),
+        }
+    }
+
     /// Get the request body to send to the aggregator
     pub fn get_body(&self) -> Option<String> {
         match self {
             AggregatorRequest::IncrementSnapshotStatistic { snapshot } => {
                 Some(snapshot.to_string())
             }
+            AggregatorRequest::IncrementArtifactDownloadStatistic { message } => {
+                Some(message.to_string())
+            }
+            AggregatorRequest::PostDownloadTelemetry { message } => Some(message.to_string()),
             _ => None,
         }
     }
@@ -188,6 +252,7 @@ pub struct AggregatorHTTPClient {
     api_versions: Arc<RwLock<Vec<Version>>>,
     logger: Logger,
     http_headers: HeaderMap,
+    static_mirror: bool,
 }
 
 impl AggregatorHTTPClient {
@@ -229,6 +294,29 @@ impl AggregatorHTTPClient {
             api_versions: Arc::new(RwLock::new(api_versions)),
             logger: logger.new_with_component_name::<Self>(),
             http_headers,
+            static_mirror: false,
+        })
+    }
+
+    /// Read from a static mirror of the aggregator instead of its API.
+    ///
+    /// Only the certificates and the artifacts are available from a static mirror, the
+    /// statistics are not sent.
+    pub fn with_static_mirror(mut self, static_mirror: bool) -> Self {
+        self.static_mirror = static_mirror;
+        self
+    }
+
+    fn get_route(&self, request: &AggregatorRequest) -> Result<String, AggregatorClientError> {
+        if !self.static_mirror {
+            return Ok(request.route());
+        }
+
+        request.static_mirror_route().ok_or_else(|| {
+            AggregatorClientError::SubsystemError(anyhow!(
+                "The route '{}' is not available from a static mirror",
+                request.route()
+            ))
         })
     }
 
@@ -385,7 +473,10 @@ impl AggregatorHTTPClient {
                 "",
             ));
 
-        AggregatorClientError::RemoteServerLogical(anyhow!("{client_error}"))
+        AggregatorClientError::RemoteServerLogical(with_error_code(
+            anyhow!("{client_error}"),
+            client_error.code,
+        ))
     }
 
     async fn remote_technical_error(response: Response) -> AggregatorClientError {
@@ -395,7 +486,18 @@ impl AggregatorHTTPClient {
             .await
             .unwrap_or(ServerError::new(format!("Unhandled error {status_code}")));
 
-        AggregatorClientError::RemoteServerTechnical(anyhow!("{server_error}"))
+        AggregatorClientError::RemoteServerTechnical(with_error_code(
+            anyhow!("{server_error}"),
+            server_error.code,
+        ))
+    }
+}
+
+/// Attach the error code sent by the aggregator, if any, to the error
+fn with_error_code(error: MithrilError, code: Option<MithrilErrorCode>) -> MithrilError {
+    match code {
+        Some(code) => error.context(code),
+        None => error,
     }
 }
 
@@ -407,7 +509,9 @@ impl AggregatorClient for AggregatorHTTPClient {
         &self,
         request: AggregatorRequest,
     ) -> Result<String, AggregatorClientError> {
-        let response = self.get(self.get_url_for_route(&request.route())?).await?;
+        let response = self
+            .get(self.get_url_for_route(&self.get_route(&request)?)?)
+            .await?;
         let content = format!("{response:?}");
 
         response.text().await.map_err(|e| {
@@ -421,6 +525,11 @@ impl AggregatorClient for AggregatorHTTPClient {
         &self,
         request: AggregatorRequest,
     ) -> Result<String, AggregatorClientError> {
+        if self.static_mirror {
+            debug!(self.logger, "Skipping POST to a static mirror"; "route" => request.route());
+            return Ok(String::new());
+        }
+
         let response = self
             .post(
                 self.get_url_for_route(&request.route())?,
@@ -521,6 +630,16 @@ mod tests {
 
     #[test]
     fn deduce_routes_from_request() {
+        assert_eq!(
+            "".to_string(),
+            AggregatorRequest::GetAggregatorFeatures.route()
+        );
+
+        assert_eq!(
+            "epoch-settings".to_string(),
+            AggregatorRequest::GetEpochSettings.route()
+        );
+
         assert_eq!(
             "certificate/abc".to_string(),
             AggregatorRequest::GetCertificate {
@@ -571,6 +690,22 @@ mod tests {
             .route()
         );
 
+        assert_eq!(
+            "statistics/artifact-download".to_string(),
+            AggregatorRequest::IncrementArtifactDownloadStatistic {
+                message: "abc".to_string()
+            }
+            .route()
+        );
+
+        assert_eq!(
+            "statistics/download-telemetry".to_string(),
+            AggregatorRequest::PostDownloadTelemetry {
+                message: "abc".to_string()
+            }
+            .route()
+        );
+
         assert_eq!(
             "proof/cardano-transaction?transaction_hashes=abc,def,ghi,jkl".to_string(),
             AggregatorRequest::GetTransactionsProofs {
@@ -641,6 +776,30 @@ mod tests {
         assert_error_eq!(post_content_error, expected_error);
     }
 
+    #[tokio::test]
+    async fn test_client_forward_the_error_code_sent_by_the_aggregator() {
+        let client_error =
+            ClientError::new("invalid_epoch", "message").with_code(MithrilErrorCode::InvalidEpoch);
+
+        let (aggregator, client) = setup_server_and_client();
+        aggregator.mock(|_when, then| {
+            then.status(StatusCode::BAD_REQUEST.as_u16())
+                .json_body_obj(&client_error);
+        });
+
+        let error = client
+            .get_content(AggregatorRequest::ListCertificates)
+            .await
+            .unwrap_err();
+        assert_eq!(MithrilErrorCode::InvalidEpoch, error.error_code());
+
+        let error = AggregatorClientError::RemoteServerLogical(anyhow!("no code"));
+        assert_eq!(
+            MithrilErrorCode::AggregatorRejectedRequest,
+            error.error_code()
+        );
+    }
+
     #[tokio::test]
     async fn test_client_handle_404_not_found_error() {
         let client_error = ClientError::new("label", "message");
@@ -811,4 +970,74 @@ mod tests {
             .await
             .expect("GET request should succeed");
     }
+
+    #[test]
+    fn deduce_static_mirror_routes_from_request() {
+        assert_eq!(
+            Some("certificate/abc.json".to_string()),
+            AggregatorRequest::GetCertificate {
+                hash: "abc".to_string()
+            }
+            .static_mirror_route()
+        );
+        assert_eq!(
+            Some("artifact/snapshots.json".to_string()),
+            AggregatorRequest::ListSnapshots.static_mirror_route()
+        );
+        assert_eq!(
+            Some("artifact/cardano-stake-distribution/epoch/5.json".to_string()),
+            AggregatorRequest::GetCardanoStakeDistributionByEpoch { epoch: Epoch(5) }
+                .static_mirror_route()
+        );
+        assert_eq!(
+            None,
+            AggregatorRequest::GetAggregatorFeatures.static_mirror_route()
+        );
+        assert_eq!(
+            None,
+            AggregatorRequest::GetTransactionsProofs {
+                transactions_hashes: vec!["abc".to_string()],
+            }
+            .static_mirror_route()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_static_mirror_client_reads_the_json_files_of_the_mirror() {
+        let (mirror, client) = setup_server_and_client();
+        let client = client.with_static_mirror(true);
+        mirror.mock(|when, then| {
+            when.path("/artifact/snapshots.json");
+            then.status(StatusCode::OK.as_u16()).body("[]");
+        });
+
+        let content = client
+            .get_content(AggregatorRequest::ListSnapshots)
+            .await
+            .expect("GET request to the mirror should succeed");
+
+        assert_eq!("[]", content);
+    }
+
+    #[tokio::test]
+    async fn test_static_mirror_client_does_not_send_requests_unavailable_from_a_mirror() {
+        let (mirror, client) = setup_server_and_client();
+        let client = client.with_static_mirror(true);
+        let mirror_mock = mirror.mock(|_when, then| {
+            then.status(StatusCode::OK.as_u16());
+        });
+
+        client
+            .get_content(AggregatorRequest::GetAggregatorFeatures)
+            .await
+            .expect_err("The aggregator features are not available from a static mirror");
+        client
+            .post_content(AggregatorRequest::IncrementSnapshotStatistic {
+                snapshot: "{}".to_string(),
+            })
+            .await
+            .expect("The statistics should be silently skipped with a static mirror");
+
+        mirror_mock.assert_hits(0);
+    }
 }
@@ -0,0 +1,75 @@
+//! A client to retrieve the features advertised by an Aggregator.
+//!
+//! In order to do so it defines a [AggregatorFeaturesClient] which exposes the following features:
+//!  - [get][AggregatorFeaturesClient::get]: get the features, ie: the signed entity types, API
+//!    versions and compression algorithms, of the aggregator
+//!
+//! # Get the features of an aggregator
+//!
+//! To get the features of an aggregator using the [ClientBuilder][crate::client::ClientBuilder].
+//!
+//! ```no_run
+//! # async fn run() -> mithril_client::MithrilResult<()> {
+//! use mithril_client::{common::SignedEntityTypeDiscriminants, ClientBuilder};
+//!
+//! let client = ClientBuilder::aggregator("YOUR_AGGREGATOR_ENDPOINT", "YOUR_GENESIS_VERIFICATION_KEY").build()?;
+//! let features = client.aggregator_features().get().await?;
+//!
+//! if features.capabilities.is_signed_entity_type_enabled(&SignedEntityTypeDiscriminants::CardanoTransactions) {
+//!     println!("The aggregator certifies the Cardano transactions");
+//! }
+//! #    Ok(())
+//! # }
+//! ```
+
+use anyhow::Context;
+use std::sync::Arc;
+
+use crate::aggregator_client::{AggregatorClient, AggregatorRequest};
+use crate::{AggregatorFeatures, MithrilResult};
+
+/// HTTP client for the features API of the Aggregator
+pub struct AggregatorFeaturesClient {
+    aggregator_client: Arc<dyn AggregatorClient>,
+}
+
+impl AggregatorFeaturesClient {
+    /// Constructs a new `AggregatorFeaturesClient`.
+    pub fn new(aggregator_client: Arc<dyn AggregatorClient>) -> Self {
+        Self { aggregator_client }
+    }
+
+    /// Fetch the features advertised by the aggregator
+    pub async fn get(&self) -> MithrilResult<AggregatorFeatures> {
+        let response = self
+            .aggregator_client
+            .get_content(AggregatorRequest::GetAggregatorFeatures)
+            .await
+            .with_context(|| "AggregatorFeatures Client can not get the aggregator features")?;
+        let features = serde_json::from_str::<AggregatorFeatures>(&response)
+            .with_context(|| "AggregatorFeatures Client can not deserialize the features")?;
+
+        Ok(features)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::aggregator_client::MockAggregatorHTTPClient;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn get_aggregator_features() {
+        let message = AggregatorFeatures::dummy();
+        let expected = message.clone();
+        let mut http_client = MockAggregatorHTTPClient::new();
+        http_client
+            .expect_get_content()
+            .withf(|request| *request == AggregatorRequest::GetAggregatorFeatures)
+            .return_once(move |_| Ok(serde_json::to_string(&message).unwrap()));
+        let client = AggregatorFeaturesClient::new(Arc::new(http_client));
+
+        assert_eq!(expected, client.get().await.unwrap());
+    }
+}
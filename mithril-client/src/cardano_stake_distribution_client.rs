@@ -4,6 +4,7 @@
 //!  - [get][CardanoStakeDistributionClient::get]: get a Cardano stake distribution data from its hash
 //!  - [get_by_epoch][CardanoStakeDistributionClient::get_by_epoch]: get a Cardano stake distribution data from its epoch
 //!  - [list][CardanoStakeDistributionClient::list]: get the list of available Cardano stake distribution
+//!  - [add_statistics][CardanoStakeDistributionClient::add_statistics]: report the download of a Cardano stake distribution to the aggregator
 //!
 //! # Get a Cardano stake distribution
 //!
@@ -70,6 +71,9 @@
 use anyhow::Context;
 use std::sync::Arc;
 
+use mithril_common::entities::SignedEntityTypeDiscriminants;
+use mithril_common::messages::ArtifactDownloadMessage;
+
 use crate::aggregator_client::{AggregatorClient, AggregatorClientError, AggregatorRequest};
 use crate::common::Epoch;
 use crate::{CardanoStakeDistribution, CardanoStakeDistributionListItem, MithrilResult};
@@ -136,6 +140,25 @@ impl CardanoStakeDistributionClient {
             Err(e) => Err(e.into()),
         }
     }
+
+    /// Increments the aggregator Cardano stake distribution download statistics
+    pub async fn add_statistics(
+        &self,
+        cardano_stake_distribution: &CardanoStakeDistribution,
+    ) -> MithrilResult<()> {
+        let message = ArtifactDownloadMessage::new(
+            SignedEntityTypeDiscriminants::CardanoStakeDistribution,
+            &cardano_stake_distribution.hash,
+        );
+        let _response = self
+            .aggregator_client
+            .post_content(AggregatorRequest::IncrementArtifactDownloadStatistic {
+                message: serde_json::to_string(&message)?,
+            })
+            .await?;
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -60,6 +60,7 @@ use std::sync::Arc;
 use anyhow::{anyhow, Context};
 use async_trait::async_trait;
 use slog::{crit, Logger};
+use tokio::sync::{mpsc, Mutex};
 
 use mithril_common::{
     certificate_chain::{
@@ -68,7 +69,8 @@ use mithril_common::{
         MithrilCertificateVerifier as CommonMithrilCertificateVerifier,
     },
     crypto_helper::ProtocolGenesisVerificationKey,
-    entities::Certificate,
+    entities::{Certificate, CertificateSignature},
+    error_codes::MithrilErrorCode,
     logging::LoggerExtensions,
     messages::CertificateMessage,
 };
@@ -143,6 +145,7 @@ impl CertificateClient {
         self.verifier
             .verify_chain(&certificate)
             .await
+            .context(MithrilErrorCode::CertificateChainInvalid)
             .with_context(|| {
                 format!("Certificate chain of certificate '{certificate_hash}' is invalid")
             })?;
@@ -187,12 +190,19 @@ impl InternalCertificateRetriever {
     }
 }
 
+/// Number of certificates of a chain fetched ahead of their verification.
+const CERTIFICATE_PREFETCH_DEPTH: usize = 32;
+
 /// Implementation of a [CertificateVerifier] that can send feedbacks using
 /// the [feedback][crate::feedback] mechanism.
+///
+/// The previous certificates of the chain are fetched concurrently with the verification of the
+/// ones already fetched, so that the network round trips overlap with the cryptographic checks.
 pub struct MithrilCertificateVerifier {
-    internal_verifier: Arc<dyn CommonCertificateVerifier>,
+    retriever: Arc<InternalCertificateRetriever>,
     genesis_verification_key: ProtocolGenesisVerificationKey,
     feedback_sender: FeedbackSender,
+    logger: Logger,
 }
 
 impl MithrilCertificateVerifier {
@@ -208,29 +218,93 @@ impl MithrilCertificateVerifier {
             aggregator_client: aggregator_client.clone(),
             logger: logger.clone(),
         });
-        let internal_verifier = Arc::new(CommonMithrilCertificateVerifier::new(
-            logger,
-            retriever.clone(),
-        ));
         let genesis_verification_key =
             ProtocolGenesisVerificationKey::try_from(genesis_verification_key)
+                .context(MithrilErrorCode::InvalidGenesisVerificationKey)
                 .with_context(|| "Invalid genesis verification key")?;
 
         Ok(Self {
-            internal_verifier,
+            retriever,
             genesis_verification_key,
             feedback_sender,
+            logger,
         })
     }
+
+    /// Fetch the previous certificates of the chain of the given certificate, down to the genesis
+    /// certificate, and send them to the verification in the order of the chain.
+    ///
+    /// The fetching stops early if the verification is over.
+    async fn prefetch_certificate_chain(
+        &self,
+        certificate: &Certificate,
+        prefetched_certificates: mpsc::Sender<Certificate>,
+    ) -> MithrilResult<()> {
+        let mut current_certificate = certificate.clone();
+        while matches!(
+            current_certificate.signature,
+            CertificateSignature::MultiSignature(..)
+        ) && !current_certificate.is_chaining_to_itself()
+        {
+            current_certificate = self
+                .retriever
+                .get_certificate_details(&current_certificate.previous_hash)
+                .await
+                .with_context(|| "Can not retrieve previous certificate during verification")?;
+
+            if prefetched_certificates
+                .send(current_certificate.clone())
+                .await
+                .is_err()
+            {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Verify the chain by batches made of the certificates already prefetched.
+    async fn verify_prefetched_certificate_chain(
+        &self,
+        certificate: Certificate,
+        prefetched_certificates: mpsc::Receiver<Certificate>,
+        certificate_chain_validation_id: &str,
+    ) -> MithrilResult<()> {
+        let retriever = Arc::new(PrefetchedCertificateRetriever {
+            prefetched_certificates: Mutex::new(prefetched_certificates),
+        });
+        let verifier =
+            CommonMithrilCertificateVerifier::new(self.logger.clone(), retriever.clone());
+
+        let mut next_certificate = Some(certificate);
+        while let Some(certificate) = next_certificate {
+            let mut certificates = vec![certificate];
+            certificates.extend(retriever.take_prefetched().await);
+
+            next_certificate = verifier
+                .verify_certificates(&certificates, &self.genesis_verification_key)
+                .await?;
+
+            for certificate in certificates {
+                self.feedback_sender
+                    .send_event(MithrilEvent::CertificateValidated {
+                        certificate_hash: certificate.hash,
+                        certificate_chain_validation_id: certificate_chain_validation_id
+                            .to_string(),
+                    })
+                    .await;
+            }
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg_attr(target_family = "wasm", async_trait(?Send))]
 #[cfg_attr(not(target_family = "wasm"), async_trait)]
 impl CertificateVerifier for MithrilCertificateVerifier {
     async fn verify_chain(&self, certificate: &MithrilCertificate) -> MithrilResult<()> {
-        // Todo: move most of this code in the `mithril_common` verifier by defining
-        // a new `verify_chain` method that take a callback called when a certificate is
-        // validated.
         let certificate_chain_validation_id = MithrilEvent::new_certificate_chain_validation_id();
         self.feedback_sender
             .send_event(MithrilEvent::CertificateChainValidationStarted {
@@ -238,25 +312,16 @@ impl CertificateVerifier for MithrilCertificateVerifier {
             })
             .await;
 
-        let mut current_certificate = certificate.clone().try_into()?;
-        loop {
-            let previous_or_none = self
-                .internal_verifier
-                .verify_certificate(&current_certificate, &self.genesis_verification_key)
-                .await?;
-
-            self.feedback_sender
-                .send_event(MithrilEvent::CertificateValidated {
-                    certificate_hash: current_certificate.hash.clone(),
-                    certificate_chain_validation_id: certificate_chain_validation_id.clone(),
-                })
-                .await;
-
-            match previous_or_none {
-                Some(previous_certificate) => current_certificate = previous_certificate,
-                None => break,
-            }
-        }
+        let certificate: Certificate = certificate.clone().try_into()?;
+        let (sender, receiver) = mpsc::channel(CERTIFICATE_PREFETCH_DEPTH);
+        futures::try_join!(
+            self.prefetch_certificate_chain(&certificate, sender),
+            self.verify_prefetched_certificate_chain(
+                certificate.clone(),
+                receiver,
+                &certificate_chain_validation_id
+            ),
+        )?;
 
         self.feedback_sender
             .send_event(MithrilEvent::CertificateChainValidated {
@@ -268,6 +333,45 @@ impl CertificateVerifier for MithrilCertificateVerifier {
     }
 }
 
+/// [CertificateRetriever] serving the previous certificates of a chain from the certificates
+/// prefetched by a [MithrilCertificateVerifier], in the order of the chain.
+struct PrefetchedCertificateRetriever {
+    prefetched_certificates: Mutex<mpsc::Receiver<Certificate>>,
+}
+
+impl PrefetchedCertificateRetriever {
+    /// Take the certificates already prefetched without waiting for the next ones.
+    async fn take_prefetched(&self) -> Vec<Certificate> {
+        let mut prefetched_certificates = self.prefetched_certificates.lock().await;
+        let mut certificates = vec![];
+        while let Ok(certificate) = prefetched_certificates.try_recv() {
+            certificates.push(certificate);
+        }
+
+        certificates
+    }
+}
+
+#[cfg_attr(target_family = "wasm", async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait)]
+impl CertificateRetriever for PrefetchedCertificateRetriever {
+    async fn get_certificate_details(
+        &self,
+        certificate_hash: &str,
+    ) -> Result<Certificate, CertificateRetrieverError> {
+        match self.prefetched_certificates.lock().await.recv().await {
+            Some(certificate) if certificate.hash == certificate_hash => Ok(certificate),
+            Some(certificate) => Err(CertificateRetrieverError(anyhow!(
+                "Prefetched certificate '{}' is not the expected certificate '{certificate_hash}'",
+                certificate.hash
+            ))),
+            None => Err(CertificateRetrieverError(anyhow!(
+                "Certificate '{certificate_hash}' was not prefetched"
+            ))),
+        }
+    }
+}
+
 #[cfg_attr(target_family = "wasm", async_trait(?Send))]
 #[cfg_attr(not(target_family = "wasm"), async_trait)]
 impl CertificateRetriever for InternalCertificateRetriever {
@@ -290,12 +394,15 @@ impl CertificateRetriever for InternalCertificateRetriever {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+    use std::time::Duration;
+
     use mithril_common::crypto_helper::tests_setup::setup_certificate_chain;
     use mithril_common::test_utils::fake_data;
     use mockall::predicate::eq;
 
     use crate::aggregator_client::MockAggregatorHTTPClient;
-    use crate::feedback::StackFeedbackReceiver;
+    use crate::feedback::{FeedbackReceiver, StackFeedbackReceiver};
     use crate::test_utils;
 
     use super::*;
@@ -516,4 +623,228 @@ mod tests {
 
         assert_eq!(certificate.hash, last_certificate_hash);
     }
+
+    #[tokio::test]
+    async fn verify_chain_fails_if_a_previous_certificate_can_not_be_fetched() {
+        let (chain, verifier) = setup_certificate_chain(3, 1);
+        let verification_key: String = verifier.to_verification_key().try_into().unwrap();
+        let mut aggregator_client = MockAggregatorHTTPClient::new();
+        let last_certificate_hash = chain.first().unwrap().hash.clone();
+        let missing_certificate_hash = chain[1].hash.clone();
+
+        for certificate in chain.clone() {
+            let hash = certificate.hash.clone();
+            let message = serde_json::to_string(
+                &TryInto::<CertificateMessage>::try_into(certificate).unwrap(),
+            )
+            .unwrap();
+            if hash == missing_certificate_hash {
+                aggregator_client
+                    .expect_get_content()
+                    .with(eq(AggregatorRequest::GetCertificate { hash }))
+                    .returning(|_| {
+                        Err(AggregatorClientError::RemoteServerLogical(anyhow!(
+                            "not found"
+                        )))
+                    });
+            } else {
+                aggregator_client
+                    .expect_get_content()
+                    .with(eq(AggregatorRequest::GetCertificate { hash }))
+                    .returning(move |_| Ok(message.to_owned()));
+            }
+        }
+
+        let aggregator_client = Arc::new(aggregator_client);
+        let certificate_client = build_client(
+            aggregator_client.clone(),
+            Some(Arc::new(
+                MithrilCertificateVerifier::new(
+                    aggregator_client,
+                    &verification_key,
+                    FeedbackSender::new(&[]),
+                    test_utils::test_logger(),
+                )
+                .unwrap(),
+            )),
+        );
+
+        certificate_client
+            .verify_chain(&last_certificate_hash)
+            .await
+            .expect_err("Chain validation should fail");
+    }
+
+    /// Record of the certificates fetched from the aggregator and of the certificates validated,
+    /// in the order in which they happened.
+    type ChainLog = Arc<std::sync::Mutex<Vec<String>>>;
+
+    /// [AggregatorClient] serving the certificates of a chain, each after a delay.
+    struct DelayedAggregatorClient {
+        certificates: HashMap<String, String>,
+        delay: Duration,
+        log: ChainLog,
+    }
+
+    impl DelayedAggregatorClient {
+        fn new(chain: &[Certificate], delay: Duration, log: ChainLog) -> Self {
+            let certificates = chain
+                .iter()
+                .map(|certificate| {
+                    let message: CertificateMessage = certificate.clone().try_into().unwrap();
+                    (
+                        certificate.hash.clone(),
+                        serde_json::to_string(&message).unwrap(),
+                    )
+                })
+                .collect();
+
+            Self {
+                certificates,
+                delay,
+                log,
+            }
+        }
+    }
+
+    #[async_trait]
+    impl AggregatorClient for DelayedAggregatorClient {
+        async fn get_content(
+            &self,
+            request: AggregatorRequest,
+        ) -> Result<String, AggregatorClientError> {
+            let AggregatorRequest::GetCertificate { hash } = request else {
+                panic!("Unexpected request: {request:?}");
+            };
+            tokio::time::sleep(self.delay).await;
+            self.log.lock().unwrap().push(format!("fetched {hash}"));
+
+            self.certificates
+                .get(&hash)
+                .cloned()
+                .ok_or(AggregatorClientError::RemoteServerLogical(anyhow!(
+                    "not found"
+                )))
+        }
+
+        async fn post_content(
+            &self,
+            _request: AggregatorRequest,
+        ) -> Result<String, AggregatorClientError> {
+            unimplemented!()
+        }
+    }
+
+    /// [FeedbackReceiver] recording the certificates validated in a [ChainLog].
+    struct ChainLogFeedbackReceiver {
+        log: ChainLog,
+    }
+
+    #[async_trait]
+    impl FeedbackReceiver for ChainLogFeedbackReceiver {
+        async fn handle_event(&self, event: MithrilEvent) {
+            if let MithrilEvent::CertificateValidated {
+                certificate_hash, ..
+            } = event
+            {
+                self.log
+                    .lock()
+                    .unwrap()
+                    .push(format!("validated {certificate_hash}"));
+            }
+        }
+    }
+
+    fn build_client_with_delayed_aggregator(
+        chain: &[Certificate],
+        verification_key: &str,
+        delay: Duration,
+        log: ChainLog,
+    ) -> CertificateClient {
+        let aggregator_client = Arc::new(DelayedAggregatorClient::new(chain, delay, log.clone()));
+        let feedback_receiver = Arc::new(ChainLogFeedbackReceiver { log });
+
+        build_client(
+            aggregator_client.clone(),
+            Some(Arc::new(
+                MithrilCertificateVerifier::new(
+                    aggregator_client,
+                    verification_key,
+                    FeedbackSender::new(&[feedback_receiver]),
+                    test_utils::test_logger(),
+                )
+                .unwrap(),
+            )),
+        )
+    }
+
+    #[tokio::test]
+    async fn verify_chain_verifies_the_certificates_while_the_previous_ones_are_fetched() {
+        let (chain, verifier) = setup_certificate_chain(5, 1);
+        let verification_key: String = verifier.to_verification_key().try_into().unwrap();
+        let log = ChainLog::default();
+        let certificate_client = build_client_with_delayed_aggregator(
+            &chain,
+            &verification_key,
+            Duration::from_millis(500),
+            log.clone(),
+        );
+
+        certificate_client
+            .verify_chain(&chain[0].hash)
+            .await
+            .expect("Chain validation should succeed");
+
+        let log = log.lock().unwrap().clone();
+        for certificate in &chain {
+            assert_eq!(
+                1,
+                log.iter()
+                    .filter(|entry| **entry == format!("fetched {}", certificate.hash))
+                    .count(),
+                "Certificate '{}' should be fetched exactly once, log: {log:#?}",
+                certificate.hash
+            );
+        }
+        let position = |entry: String| log.iter().position(|e| *e == entry).unwrap();
+        assert!(
+            position(format!("validated {}", chain[0].hash))
+                < position(format!("fetched {}", chain.last().unwrap().hash)),
+            "The latest certificate should be validated before the genesis certificate is fetched, log: {log:#?}"
+        );
+        assert_eq!(
+            chain
+                .iter()
+                .map(|certificate| format!("validated {}", certificate.hash))
+                .collect::<Vec<_>>(),
+            log.iter()
+                .filter(|entry| entry.starts_with("validated"))
+                .cloned()
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    #[tokio::test]
+    async fn verify_chain_stops_fetching_the_previous_certificates_when_the_verification_fails() {
+        let (mut chain, verifier) = setup_certificate_chain(5, 1);
+        let verification_key: String = verifier.to_verification_key().try_into().unwrap();
+        chain[0].signed_message = "tampered-signed-message".to_string();
+        let log = ChainLog::default();
+        let certificate_client = build_client_with_delayed_aggregator(
+            &chain,
+            &verification_key,
+            Duration::from_millis(500),
+            log.clone(),
+        );
+
+        certificate_client
+            .verify_chain(&chain[0].hash)
+            .await
+            .expect_err("Chain validation should fail");
+
+        assert_eq!(
+            vec![format!("fetched {}", chain[0].hash)],
+            log.lock().unwrap().clone()
+        );
+    }
 }
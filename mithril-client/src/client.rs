@@ -3,21 +3,27 @@ use reqwest::Url;
 use serde::{Deserialize, Serialize};
 use slog::{o, Logger};
 use std::collections::HashMap;
+#[cfg(feature = "fs")]
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use mithril_common::api_version::APIVersionProvider;
 
 use crate::aggregator_client::{AggregatorClient, AggregatorHTTPClient};
+use crate::aggregator_features_client::AggregatorFeaturesClient;
 use crate::cardano_stake_distribution_client::CardanoStakeDistributionClient;
 use crate::cardano_transaction_client::CardanoTransactionClient;
 use crate::certificate_client::{
     CertificateClient, CertificateVerifier, MithrilCertificateVerifier,
 };
+use crate::epoch_settings_client::EpochSettingsClient;
 use crate::feedback::{FeedbackReceiver, FeedbackSender};
 use crate::mithril_stake_distribution_client::MithrilStakeDistributionClient;
 use crate::snapshot_client::SnapshotClient;
 #[cfg(feature = "fs")]
 use crate::snapshot_downloader::{HttpSnapshotDownloader, SnapshotDownloader};
+use crate::telemetry_client::TelemetryClient;
+use crate::transparency_log::TransparencyLogCertificateVerifier;
 use crate::MithrilResult;
 
 /// Options that can be used to configure the client.
@@ -30,6 +36,10 @@ pub struct ClientOptions {
     #[cfg(target_family = "wasm")]
     #[cfg_attr(target_family = "wasm", serde(default))]
     pub unstable: bool,
+
+    /// Whether the aggregator endpoint is a static mirror of an aggregator.
+    #[serde(default)]
+    pub static_mirror: bool,
 }
 
 impl ClientOptions {
@@ -39,6 +49,15 @@ impl ClientOptions {
             http_headers,
             #[cfg(target_family = "wasm")]
             unstable: false,
+            static_mirror: false,
+        }
+    }
+
+    /// Read from a static mirror of an aggregator, served by a CDN, instead of its API.
+    pub fn with_static_mirror(self, static_mirror: bool) -> Self {
+        Self {
+            static_mirror,
+            ..self
         }
     }
 
@@ -54,14 +73,22 @@ impl ClientOptions {
 /// Use the [ClientBuilder] to instantiate it easily.
 #[derive(Clone)]
 pub struct Client {
+    aggregator_features_client: Arc<AggregatorFeaturesClient>,
     cardano_transaction_client: Arc<CardanoTransactionClient>,
     cardano_stake_distribution_client: Arc<CardanoStakeDistributionClient>,
     certificate_client: Arc<CertificateClient>,
+    epoch_settings_client: Arc<EpochSettingsClient>,
     mithril_stake_distribution_client: Arc<MithrilStakeDistributionClient>,
     snapshot_client: Arc<SnapshotClient>,
+    telemetry_client: Arc<TelemetryClient>,
 }
 
 impl Client {
+    /// Get the client that fetches the features advertised by the aggregator.
+    pub fn aggregator_features(&self) -> Arc<AggregatorFeaturesClient> {
+        self.aggregator_features_client.clone()
+    }
+
     /// Get the client that fetches and verifies Mithril Cardano transaction proof.
     pub fn cardano_transaction(&self) -> Arc<CardanoTransactionClient> {
         self.cardano_transaction_client.clone()
@@ -72,6 +99,11 @@ impl Client {
         self.certificate_client.clone()
     }
 
+    /// Get the client that fetches the settings of the current epoch of the aggregator.
+    pub fn epoch_settings(&self) -> Arc<EpochSettingsClient> {
+        self.epoch_settings_client.clone()
+    }
+
     /// Get the client that fetches Mithril stake distributions.
     pub fn mithril_stake_distribution(&self) -> Arc<MithrilStakeDistributionClient> {
         self.mithril_stake_distribution_client.clone()
@@ -86,6 +118,11 @@ impl Client {
     pub fn cardano_stake_distribution(&self) -> Arc<CardanoStakeDistributionClient> {
         self.cardano_stake_distribution_client.clone()
     }
+
+    /// Get the client that sends anonymous telemetry reports to the aggregator.
+    pub fn telemetry(&self) -> Arc<TelemetryClient> {
+        self.telemetry_client.clone()
+    }
 }
 
 /// Builder than can be used to create a [Client] easily or with custom dependencies.
@@ -94,6 +131,10 @@ pub struct ClientBuilder {
     genesis_verification_key: String,
     aggregator_client: Option<Arc<dyn AggregatorClient>>,
     certificate_verifier: Option<Arc<dyn CertificateVerifier>>,
+    transparency_log_endpoint: Option<String>,
+    transparency_log_verification_key: String,
+    #[cfg(feature = "fs")]
+    transparency_log_state_file: Option<PathBuf>,
     #[cfg(feature = "fs")]
     snapshot_downloader: Option<Arc<dyn SnapshotDownloader>>,
     logger: Option<Logger>,
@@ -110,6 +151,10 @@ impl ClientBuilder {
             genesis_verification_key: genesis_verification_key.to_string(),
             aggregator_client: None,
             certificate_verifier: None,
+            transparency_log_endpoint: None,
+            transparency_log_verification_key: String::new(),
+            #[cfg(feature = "fs")]
+            transparency_log_state_file: None,
             #[cfg(feature = "fs")]
             snapshot_downloader: None,
             logger: None,
@@ -128,6 +173,10 @@ impl ClientBuilder {
             genesis_verification_key: genesis_verification_key.to_string(),
             aggregator_client: None,
             certificate_verifier: None,
+            transparency_log_endpoint: None,
+            transparency_log_verification_key: String::new(),
+            #[cfg(feature = "fs")]
+            transparency_log_state_file: None,
             #[cfg(feature = "fs")]
             snapshot_downloader: None,
             logger: None,
@@ -164,7 +213,8 @@ impl ClientBuilder {
                         logger.clone(),
                         self.options.http_headers,
                     )
-                    .with_context(|| "Building aggregator client failed")?,
+                    .with_context(|| "Building aggregator client failed")?
+                    .with_static_mirror(self.options.static_mirror),
                 )
             }
             Some(client) => client,
@@ -179,9 +229,14 @@ impl ClientBuilder {
             Some(snapshot_downloader) => snapshot_downloader,
         };
 
+        let aggregator_features_client =
+            Arc::new(AggregatorFeaturesClient::new(aggregator_client.clone()));
+
         let cardano_transaction_client =
             Arc::new(CardanoTransactionClient::new(aggregator_client.clone()));
 
+        let epoch_settings_client = Arc::new(EpochSettingsClient::new(aggregator_client.clone()));
+
         let certificate_verifier = match self.certificate_verifier {
             None => Arc::new(
                 MithrilCertificateVerifier::new(
@@ -194,6 +249,28 @@ impl ClientBuilder {
             ),
             Some(verifier) => verifier,
         };
+        let certificate_verifier: Arc<dyn CertificateVerifier> = match self
+            .transparency_log_endpoint
+        {
+            None => certificate_verifier,
+            Some(endpoint) => {
+                let verifier = TransparencyLogCertificateVerifier::new(
+                    &endpoint,
+                    &self.transparency_log_verification_key,
+                    certificate_verifier,
+                )
+                .with_context(|| "Building transparency log certificate verifier failed")?;
+                #[cfg(feature = "fs")]
+                let verifier = match &self.transparency_log_state_file {
+                    Some(state_file) => verifier
+                        .with_state_file(state_file)
+                        .with_context(|| "Building transparency log certificate verifier failed")?,
+                    None => verifier,
+                };
+
+                Arc::new(verifier)
+            }
+        };
         let certificate_client = Arc::new(CertificateClient::new(
             aggregator_client.clone(),
             certificate_verifier,
@@ -213,15 +290,21 @@ impl ClientBuilder {
             logger,
         ));
 
-        let cardano_stake_distribution_client =
-            Arc::new(CardanoStakeDistributionClient::new(aggregator_client));
+        let cardano_stake_distribution_client = Arc::new(CardanoStakeDistributionClient::new(
+            aggregator_client.clone(),
+        ));
+
+        let telemetry_client = Arc::new(TelemetryClient::new(aggregator_client));
 
         Ok(Client {
+            aggregator_features_client,
             cardano_transaction_client,
             cardano_stake_distribution_client,
             certificate_client,
+            epoch_settings_client,
             mithril_stake_distribution_client,
             snapshot_client,
+            telemetry_client,
         })
     }
 
@@ -243,7 +326,26 @@ impl ClientBuilder {
         self
     }
 
+    /// Require the verified certificates to be included in the transparency log at the given
+    /// endpoint, whose tree heads are signed with the given JSON hex encoded verification key.
+    pub fn with_transparency_log(
+        mut self,
+        transparency_log_endpoint: &str,
+        transparency_log_verification_key: &str,
+    ) -> ClientBuilder {
+        self.transparency_log_endpoint = Some(transparency_log_endpoint.to_string());
+        self.transparency_log_verification_key = transparency_log_verification_key.to_string();
+        self
+    }
+
     cfg_fs! {
+    /// Keep the last tree head of the transparency log seen by the client in the given state
+    /// file, so that the tree heads of the next runs are proven to extend it.
+    pub fn with_transparency_log_state_file(mut self, state_file: &Path) -> ClientBuilder {
+        self.transparency_log_state_file = Some(state_file.to_path_buf());
+        self
+    }
+
     /// Set the [SnapshotDownloader] that will be used to download snapshots.
     pub fn with_snapshot_downloader(
         mut self,
@@ -273,4 +375,26 @@ impl ClientBuilder {
         self.options = options;
         self
     }
+
+    /// Read from a static mirror of an aggregator, keeping the other options already set.
+    pub fn with_static_mirror(mut self, static_mirror: bool) -> Self {
+        self.options.static_mirror = static_mirror;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn setting_the_static_mirror_keeps_the_other_options() {
+        let http_headers = HashMap::from([("key".to_string(), "value".to_string())]);
+        let builder = ClientBuilder::aggregator("http://localhost", "genesis_key")
+            .with_options(ClientOptions::new(Some(http_headers.clone())))
+            .with_static_mirror(true);
+
+        assert!(builder.options.static_mirror);
+        assert_eq!(Some(http_headers), builder.options.http_headers);
+    }
 }
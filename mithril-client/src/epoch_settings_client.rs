@@ -0,0 +1,76 @@
+//! A client to retrieve the epoch settings of an Aggregator.
+//!
+//! In order to do so it defines a [EpochSettingsClient] which exposes the following features:
+//!  - [get][EpochSettingsClient::get]: get the settings of the current epoch, ie: the protocol
+//!    parameters of the current and next epochs and the signers of the current and next epochs
+//!
+//! # Get the epoch settings of an aggregator
+//!
+//! To get the epoch settings of an aggregator using the [ClientBuilder][crate::client::ClientBuilder].
+//!
+//! ```no_run
+//! # async fn run() -> mithril_client::MithrilResult<()> {
+//! use mithril_client::ClientBuilder;
+//!
+//! let client = ClientBuilder::aggregator("YOUR_AGGREGATOR_ENDPOINT", "YOUR_GENESIS_VERIFICATION_KEY").build()?;
+//! let epoch_settings = client.epoch_settings().get().await?;
+//!
+//! println!(
+//!     "Epoch {}: the signers registering now use the protocol parameters {:?}",
+//!     epoch_settings.epoch, epoch_settings.signer_registration_protocol_parameters
+//! );
+//! #    Ok(())
+//! # }
+//! ```
+
+use anyhow::Context;
+use std::sync::Arc;
+
+use crate::aggregator_client::{AggregatorClient, AggregatorRequest};
+use crate::{EpochSettings, MithrilResult};
+
+/// HTTP client for the epoch settings API of the Aggregator
+pub struct EpochSettingsClient {
+    aggregator_client: Arc<dyn AggregatorClient>,
+}
+
+impl EpochSettingsClient {
+    /// Constructs a new `EpochSettingsClient`.
+    pub fn new(aggregator_client: Arc<dyn AggregatorClient>) -> Self {
+        Self { aggregator_client }
+    }
+
+    /// Fetch the settings of the current epoch of the aggregator
+    pub async fn get(&self) -> MithrilResult<EpochSettings> {
+        let response = self
+            .aggregator_client
+            .get_content(AggregatorRequest::GetEpochSettings)
+            .await
+            .with_context(|| "EpochSettings Client can not get the epoch settings")?;
+        let epoch_settings = serde_json::from_str::<EpochSettings>(&response)
+            .with_context(|| "EpochSettings Client can not deserialize the epoch settings")?;
+
+        Ok(epoch_settings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::aggregator_client::MockAggregatorHTTPClient;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn get_epoch_settings() {
+        let message = EpochSettings::dummy();
+        let expected = message.clone();
+        let mut http_client = MockAggregatorHTTPClient::new();
+        http_client
+            .expect_get_content()
+            .withf(|request| *request == AggregatorRequest::GetEpochSettings)
+            .return_once(move |_| Ok(serde_json::to_string(&message).unwrap()));
+        let client = EpochSettingsClient::new(Arc::new(http_client));
+
+        assert_eq!(expected, client.get().await.unwrap());
+    }
+}
@@ -0,0 +1,66 @@
+//! Codes of the errors of the client from the [error catalog][mithril_common::error_codes] of
+//! Mithril, so the applications can map a failure to its remediation.
+//!
+//! ```
+//! use mithril_client::error_codes::{error_code_of, MithrilErrorCode};
+//!
+//! let error = anyhow::anyhow!("unexpected failure");
+//! assert_eq!(MithrilErrorCode::ClientInternalError, error_code_of(&error));
+//! ```
+
+pub use mithril_common::error_codes::{ErrorReport, MithrilErrorCode};
+
+use crate::aggregator_client::AggregatorClientError;
+use crate::MithrilError;
+
+/// Get the code of the given error.
+///
+/// The code of the root cause prevails if it is a failure to communicate with the aggregator,
+/// otherwise the outermost code attached to the error is returned, or
+/// [MithrilErrorCode::ClientInternalError] if the error has no code.
+pub fn error_code_of(error: &MithrilError) -> MithrilErrorCode {
+    error
+        .chain()
+        .find_map(|cause| {
+            cause
+                .downcast_ref::<AggregatorClientError>()
+                .map(AggregatorClientError::error_code)
+        })
+        .or_else(|| MithrilErrorCode::find_in(error))
+        .unwrap_or(MithrilErrorCode::ClientInternalError)
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::{anyhow, Context};
+
+    use super::*;
+
+    #[test]
+    fn the_aggregator_client_error_prevails() {
+        let error = anyhow!(AggregatorClientError::SubsystemError(anyhow!("timeout")))
+            .context(MithrilErrorCode::CertificateChainInvalid)
+            .context("Could not verify the certificate chain");
+
+        assert_eq!(
+            MithrilErrorCode::AggregatorUnreachable,
+            error_code_of(&error)
+        );
+    }
+
+    #[test]
+    fn get_the_code_attached_to_the_error() {
+        let error: MithrilError = Err::<(), _>(anyhow!("invalid signature"))
+            .context(MithrilErrorCode::CertificateChainInvalid)
+            .unwrap_err();
+
+        assert_eq!(
+            MithrilErrorCode::CertificateChainInvalid,
+            error_code_of(&error)
+        );
+        assert_eq!(
+            MithrilErrorCode::ClientInternalError,
+            error_code_of(&anyhow!("unexpected failure"))
+        );
+    }
+}
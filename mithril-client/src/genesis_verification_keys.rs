@@ -0,0 +1,80 @@
+//! Bundle of the genesis verification keys of the well-known Mithril networks.
+//!
+//! Using the keys embedded in the library avoids copying them from a web page, which would allow
+//! a malicious page to substitute its own key and trick the client into trusting a forged
+//! certificate chain.
+//!
+//! ```
+//! use mithril_client::genesis_verification_keys::MithrilNetwork;
+//! use mithril_client::ClientBuilder;
+//!
+//! let network: MithrilNetwork = "mainnet".parse().unwrap();
+//! let client_builder = ClientBuilder::aggregator(
+//!     "https://aggregator.release-mainnet.api.mithril.network/aggregator",
+//!     network.genesis_verification_key(),
+//! );
+//! ```
+
+use strum::{Display, EnumIter, EnumString};
+
+/// Version of the bundle of genesis verification keys, incremented each time a key is added or
+/// replaced.
+pub const GENESIS_VERIFICATION_KEYS_BUNDLE_VERSION: u32 = 1;
+
+const MAINNET_GENESIS_VERIFICATION_KEY: &str = "5b3139312c36362c3134302c3138352c3133382c31312c3233372c3230372c3235302c3134342c32372c322c3138382c33302c31322c38312c3135352c3230342c31302c3137392c37352c32332c3133382c3139362c3231372c352c31342c32302c35372c37392c33392c3137365d";
+
+const PREPROD_GENESIS_VERIFICATION_KEY: &str = "5b3132372c37332c3132342c3136312c362c3133372c3133312c3231332c3230372c3131372c3139382c38352c3137362c3139392c3136322c3234312c36382c3132332c3131392c3134352c31332c3233322c3234332c34392c3232392c322c3234392c3230352c3230352c33392c3233352c34345d";
+
+const PREVIEW_GENESIS_VERIFICATION_KEY: &str = "5b3132372c37332c3132342c3136312c362c3133372c3133312c3231332c3230372c3131372c3139382c38352c3137362c3139392c3136322c3234312c36382c3132332c3131392c3134352c31332c3233322c3234332c34392c3232392c322c3234392c3230352c3230352c33392c3233352c34345d";
+
+/// Well-known Mithril network whose genesis verification key is bundled in the library.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display, EnumString, EnumIter)]
+#[strum(serialize_all = "lowercase")]
+pub enum MithrilNetwork {
+    /// `release-mainnet` network
+    Mainnet,
+    /// `release-preprod` network
+    Preprod,
+    /// `pre-release-preview` network
+    Preview,
+}
+
+impl MithrilNetwork {
+    /// Get the JSON hex encoded genesis verification key of the network
+    pub fn genesis_verification_key(&self) -> &'static str {
+        match self {
+            MithrilNetwork::Mainnet => MAINNET_GENESIS_VERIFICATION_KEY,
+            MithrilNetwork::Preprod => PREPROD_GENESIS_VERIFICATION_KEY,
+            MithrilNetwork::Preview => PREVIEW_GENESIS_VERIFICATION_KEY,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use strum::IntoEnumIterator;
+
+    use mithril_common::crypto_helper::ProtocolGenesisVerificationKey;
+
+    use super::*;
+
+    #[test]
+    fn bundled_genesis_verification_keys_are_valid() {
+        for network in MithrilNetwork::iter() {
+            ProtocolGenesisVerificationKey::try_from(network.genesis_verification_key())
+                .unwrap_or_else(|e| {
+                    panic!("Invalid genesis verification key for network '{network}': {e:?}")
+                });
+        }
+    }
+
+    #[test]
+    fn parse_the_network_from_its_name() {
+        for network in MithrilNetwork::iter() {
+            assert_eq!(network, network.to_string().parse().unwrap());
+        }
+        "devnet"
+            .parse::<MithrilNetwork>()
+            .expect_err("An unknown network should not be parsed");
+    }
+}
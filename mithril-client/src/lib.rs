@@ -11,6 +11,17 @@
 //! - [Cardano transactions][cardano_transaction_client] list & get snapshot, get proofs.
 //! - [Cardano stake distribution][cardano_stake_distribution_client] list, get and get by epoch.
 //! - [Certificates][certificate_client] list, get, and chain validation.
+//! - [Aggregator features][aggregator_features_client] get, to check which types the aggregator
+//!   certifies.
+//! - [Epoch settings][epoch_settings_client] get, to check the protocol parameters and the signers
+//!   of the current and next epochs.
+//! - [Telemetry][telemetry_client] report the outcome of the downloads, for the users who opted in.
+//!
+//! The genesis verification keys of the well-known networks are bundled in the
+//! [genesis_verification_keys] submodule.
+//!
+//! The verified certificates can be required to be included in a
+//! [certificate transparency log][transparency_log].
 //!
 //! The [Client] aggregates the queries of all of those types.
 //!
@@ -83,15 +94,31 @@ macro_rules! cfg_unstable {
     }
 }
 
+macro_rules! cfg_test_utils {
+    ($($item:item)*) => {
+        $(
+            #[cfg(any(test, feature = "test_utils"))]
+            #[cfg_attr(docsrs, doc(cfg(feature = "test_utils")))]
+            $item
+        )*
+    }
+}
+
 pub mod aggregator_client;
+pub mod aggregator_features_client;
 pub mod cardano_stake_distribution_client;
 pub mod cardano_transaction_client;
 pub mod certificate_client;
 mod client;
+pub mod epoch_settings_client;
+pub mod error_codes;
 pub mod feedback;
+pub mod genesis_verification_keys;
 mod message;
 pub mod mithril_stake_distribution_client;
 pub mod snapshot_client;
+pub mod telemetry_client;
+pub mod transparency_log;
 cfg_fs! {
     pub mod snapshot_downloader;
 }
@@ -103,15 +130,6 @@ pub use client::*;
 pub use message::*;
 pub use type_alias::*;
 
-#[cfg(test)]
-pub(crate) mod test_utils {
-    use slog::Drain;
-    use std::sync::Arc;
-
-    pub fn test_logger() -> slog::Logger {
-        let decorator = slog_term::PlainDecorator::new(slog_term::TestStdoutWriter);
-        let drain = slog_term::CompactFormat::new(decorator).build().fuse();
-        let drain = slog_async::Async::new(drain).build().fuse();
-        slog::Logger::root(Arc::new(drain), slog::o!())
-    }
+cfg_test_utils! {
+    pub mod test_utils;
 }
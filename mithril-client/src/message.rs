@@ -6,6 +6,8 @@ use std::path::Path;
 use std::sync::Arc;
 
 use mithril_common::logging::LoggerExtensions;
+#[cfg(feature = "fs")]
+use mithril_common::protocol::HashAlgorithm;
 use mithril_common::protocol::SignerBuilder;
 use mithril_common::signable_builder::CardanoStakeDistributionSignableBuilder;
 #[cfg(feature = "fs")]
@@ -45,16 +47,23 @@ impl MessageBuilder {
     }
 
     cfg_fs! {
-        fn get_immutable_digester(&self) -> Arc<dyn ImmutableDigester> {
+        fn get_immutable_digester(
+            &self,
+            hash_algorithm: HashAlgorithm,
+        ) -> Arc<dyn ImmutableDigester> {
             match self.immutable_digester.as_ref() {
-                None => Arc::new(CardanoImmutableDigester::new(None, self.logger.clone())),
+                None => Arc::new(
+                    CardanoImmutableDigester::new(None, self.logger.clone())
+                        .with_hash_algorithm(hash_algorithm),
+                ),
                 Some(digester) => digester.clone(),
             }
         }
 
         /// Set the [ImmutableDigester] to be used for the message computation for snapshot.
         ///
-        /// If not set a default implementation will be used.
+        /// If not set a default implementation, using the hash algorithm of the snapshot
+        /// certificate, will be used.
         pub fn with_immutable_digester(
             mut self,
             immutable_digester: Arc<dyn ImmutableDigester>,
@@ -71,7 +80,8 @@ impl MessageBuilder {
             snapshot_certificate: &MithrilCertificate,
             unpacked_snapshot_directory: &Path,
         ) -> MithrilResult<ProtocolMessage> {
-            let digester = self.get_immutable_digester();
+            let digester =
+                self.get_immutable_digester(snapshot_certificate.metadata.hash_algorithm);
             let beacon =
                 match &snapshot_certificate.signed_entity_type {
                 SignedEntityType::CardanoImmutableFilesFull(beacon) => {Ok(beacon)},
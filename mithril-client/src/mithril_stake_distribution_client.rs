@@ -3,6 +3,7 @@
 //! In order to do so it defines a [MithrilStakeDistributionClient] which exposes the following features:
 //!  - [get][MithrilStakeDistributionClient::get]: get a Mithril stake distribution data from its hash
 //!  - [list][MithrilStakeDistributionClient::list]: get the list of available Mithril stake distribution
+//!  - [add_statistics][MithrilStakeDistributionClient::add_statistics]: report the download of a Mithril stake distribution to the aggregator
 //!
 //! # Get a Mithril stake distribution
 //!
@@ -42,6 +43,8 @@ use std::sync::Arc;
 
 use crate::aggregator_client::{AggregatorClient, AggregatorClientError, AggregatorRequest};
 use anyhow::Context;
+use mithril_common::entities::SignedEntityTypeDiscriminants;
+use mithril_common::messages::ArtifactDownloadMessage;
 
 use crate::{MithrilResult, MithrilStakeDistribution, MithrilStakeDistributionListItem};
 
@@ -90,6 +93,25 @@ impl MithrilStakeDistributionClient {
             Err(e) => Err(e.into()),
         }
     }
+
+    /// Increments the aggregator Mithril stake distribution download statistics
+    pub async fn add_statistics(
+        &self,
+        mithril_stake_distribution: &MithrilStakeDistribution,
+    ) -> MithrilResult<()> {
+        let message = ArtifactDownloadMessage::new(
+            SignedEntityTypeDiscriminants::MithrilStakeDistribution,
+            &mithril_stake_distribution.hash,
+        );
+        let _response = self
+            .aggregator_client
+            .post_content(AggregatorRequest::IncrementArtifactDownloadStatistic {
+                message: serde_json::to_string(&message)?,
+            })
+            .await?;
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -163,4 +185,29 @@ mod tests {
         assert_eq!("hash".to_string(), stake_distribution_entity.hash);
         assert_eq!(2, stake_distribution_entity.signers_with_stake.len(),);
     }
+
+    #[tokio::test]
+    async fn add_statistics_post_an_artifact_download_message() {
+        let mut http_client = MockAggregatorHTTPClient::new();
+        http_client
+            .expect_post_content()
+            .withf(|request| {
+                let expected_message = ArtifactDownloadMessage::new(
+                    SignedEntityTypeDiscriminants::MithrilStakeDistribution,
+                    "hash",
+                );
+                *request
+                    == AggregatorRequest::IncrementArtifactDownloadStatistic {
+                        message: serde_json::to_string(&expected_message).unwrap(),
+                    }
+            })
+            .return_once(|_| Ok(String::new()));
+        let client = MithrilStakeDistributionClient::new(Arc::new(http_client));
+        let message = MithrilStakeDistribution {
+            hash: "hash".to_string(),
+            ..MithrilStakeDistribution::dummy()
+        };
+
+        client.add_statistics(&message).await.unwrap();
+    }
 }
@@ -0,0 +1,120 @@
+//! A client to send anonymous telemetry reports to an Aggregator.
+//!
+//! In order to do so it defines a [TelemetryClient] which exposes the following features:
+//!  - [report_download][TelemetryClient::report_download]: report the outcome and the duration
+//!    of a download to the aggregator statistics
+//!
+//! The reports only hold the type of the downloaded artifact, whether the download succeeded and
+//! its duration: they do not hold any data that identifies the client or the downloaded artifact.
+//! Sending them is up to the application, which should only do so if its user opted in.
+//!
+//! # Report a download
+//!
+//! To report the download of a Cardano database using the [ClientBuilder][crate::client::ClientBuilder].
+//!
+//! ```no_run
+//! # async fn run() -> mithril_client::MithrilResult<()> {
+//! use mithril_client::{common::SignedEntityTypeDiscriminants, ClientBuilder};
+//! use std::time::Instant;
+//!
+//! let client = ClientBuilder::aggregator("YOUR_AGGREGATOR_ENDPOINT", "YOUR_GENESIS_VERIFICATION_KEY").build()?;
+//! let started_at = Instant::now();
+//!
+//! // Download and verify a Cardano database
+//! let succeeded = true;
+//!
+//! if let Err(e) = client
+//!     .telemetry()
+//!     .report_download(
+//!         SignedEntityTypeDiscriminants::CardanoImmutableFilesFull,
+//!         succeeded,
+//!         started_at.elapsed(),
+//!     )
+//!     .await
+//! {
+//!     println!("Could not send the download telemetry: {:?}", e);
+//! }
+//! #    Ok(())
+//! # }
+//! ```
+
+use anyhow::Context;
+use std::sync::Arc;
+use std::time::Duration;
+
+use mithril_common::entities::SignedEntityTypeDiscriminants;
+use mithril_common::messages::DownloadTelemetryMessage;
+
+use crate::aggregator_client::{AggregatorClient, AggregatorRequest};
+use crate::MithrilResult;
+
+/// HTTP client for the telemetry API of the Aggregator
+pub struct TelemetryClient {
+    aggregator_client: Arc<dyn AggregatorClient>,
+}
+
+impl TelemetryClient {
+    /// Constructs a new `TelemetryClient`.
+    pub fn new(aggregator_client: Arc<dyn AggregatorClient>) -> Self {
+        Self { aggregator_client }
+    }
+
+    /// Report the outcome and the duration of the download of an artifact of the given type
+    pub async fn report_download(
+        &self,
+        artifact_type: SignedEntityTypeDiscriminants,
+        succeeded: bool,
+        duration: Duration,
+    ) -> MithrilResult<()> {
+        let message = DownloadTelemetryMessage::new(
+            artifact_type,
+            succeeded,
+            duration.as_millis().try_into().unwrap_or(u64::MAX),
+        );
+        let _response = self
+            .aggregator_client
+            .post_content(AggregatorRequest::PostDownloadTelemetry {
+                message: serde_json::to_string(&message)?,
+            })
+            .await
+            .with_context(|| "Telemetry Client can not send the download telemetry")?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::aggregator_client::MockAggregatorHTTPClient;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn report_download_post_an_anonymous_download_telemetry_message() {
+        let mut http_client = MockAggregatorHTTPClient::new();
+        http_client
+            .expect_post_content()
+            .withf(|request| {
+                let expected_message = DownloadTelemetryMessage::new(
+                    SignedEntityTypeDiscriminants::CardanoImmutableFilesFull,
+                    false,
+                    12_345,
+                );
+                *request
+                    == AggregatorRequest::PostDownloadTelemetry {
+                        message: serde_json::to_string(&expected_message).unwrap(),
+                    }
+            })
+            .return_once(|_| Ok(String::new()));
+        let client = TelemetryClient::new(Arc::new(http_client));
+
+        client
+            .report_download(
+                SignedEntityTypeDiscriminants::CardanoImmutableFilesFull,
+                false,
+                Duration::from_millis(12_345),
+            )
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,143 @@
+use mithril_common::crypto_helper::ProtocolClerk;
+use mithril_common::entities::CertificateSignature;
+use mithril_common::test_utils::CertificateChainBuilder;
+
+use crate::common::ProtocolMessagePartKey;
+use crate::MithrilCertificate;
+
+/// A certificate chain signed by fake signers, built by a [CertificateChainFixtureBuilder].
+#[derive(Debug, Clone)]
+pub struct CertificateChainFixture {
+    certificates: Vec<MithrilCertificate>,
+    genesis_verification_key: String,
+}
+
+impl CertificateChainFixture {
+    /// Certificates of the chain, the latest first and the genesis certificate last
+    pub fn certificates(&self) -> &[MithrilCertificate] {
+        &self.certificates
+    }
+
+    /// The latest certificate of the chain, which certifies the message parts given to the
+    /// builder
+    pub fn latest_certificate(&self) -> &MithrilCertificate {
+        &self.certificates[0]
+    }
+
+    /// The genesis verification key that validates the chain, to give to the
+    /// [ClientBuilder][crate::ClientBuilder]
+    pub fn genesis_verification_key(&self) -> &str {
+        &self.genesis_verification_key
+    }
+}
+
+/// A builder of [CertificateChainFixture].
+///
+/// The certificates are signed by fake signers with real keys, so the chain passes the
+/// verification of the [CertificateClient][crate::certificate_client::CertificateClient].
+pub struct CertificateChainFixtureBuilder {
+    total_certificates: u64,
+    certificates_per_epoch: u64,
+    certified_message_parts: Vec<(ProtocolMessagePartKey, String)>,
+}
+
+impl Default for CertificateChainFixtureBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CertificateChainFixtureBuilder {
+    /// [CertificateChainFixtureBuilder] factory, by default the chain has five certificates with
+    /// two certificates per epoch.
+    pub fn new() -> Self {
+        Self {
+            total_certificates: 5,
+            certificates_per_epoch: 2,
+            certified_message_parts: vec![],
+        }
+    }
+
+    /// Set the total number of certificates of the chain, genesis certificate included
+    pub fn with_total_certificates(mut self, total_certificates: u64) -> Self {
+        self.total_certificates = total_certificates;
+        self
+    }
+
+    /// Set the number of certificates per epoch
+    pub fn with_certificates_per_epoch(mut self, certificates_per_epoch: u64) -> Self {
+        self.certificates_per_epoch = certificates_per_epoch;
+        self
+    }
+
+    /// Add a part to the protocol message signed by the latest certificate of the chain, ie: the
+    /// digest of the snapshot that the certificate certifies.
+    ///
+    /// The chain must have at least two certificates, the genesis certificate does not certify
+    /// artifacts.
+    pub fn with_certified_message_part<T: Into<String>>(
+        mut self,
+        key: ProtocolMessagePartKey,
+        value: T,
+    ) -> Self {
+        self.certified_message_parts.push((key, value.into()));
+        self
+    }
+
+    /// Build the [CertificateChainFixture]
+    pub fn build(self) -> CertificateChainFixture {
+        let certified_message_parts = self.certified_message_parts;
+        let (certificates, genesis_verifier) = CertificateChainBuilder::new()
+            .with_total_certificates(self.total_certificates)
+            .with_certificates_per_epoch(self.certificates_per_epoch)
+            .with_standard_certificate_processor(&move |certificate, context| {
+                if !context.is_last_certificate() || certified_message_parts.is_empty() {
+                    return certificate;
+                }
+
+                let mut protocol_message = context.compute_protocol_message_seed();
+                for (key, value) in &certified_message_parts {
+                    protocol_message.set_message_part(*key, value.clone());
+                }
+                let mut certificate = certificate;
+                certificate.signed_message = protocol_message.compute_hash();
+                certificate.protocol_message = protocol_message;
+
+                // Sign again the certificate with the signers of its epoch since its message
+                // changed
+                let signers = context.fixture.signers_fixture();
+                let single_signatures = signers
+                    .iter()
+                    .filter_map(|signer| {
+                        signer
+                            .protocol_signer
+                            .sign(certificate.signed_message.as_bytes())
+                    })
+                    .collect::<Vec<_>>();
+                let multi_signature = ProtocolClerk::from_signer(&signers[0].protocol_signer)
+                    .aggregate(&single_signatures, certificate.signed_message.as_bytes())
+                    .expect("The signers of the epoch should reach the quorum");
+                certificate.signature = CertificateSignature::MultiSignature(
+                    certificate.signed_entity_type(),
+                    multi_signature.into(),
+                );
+
+                certificate
+            })
+            .build();
+
+        CertificateChainFixture {
+            certificates: certificates
+                .into_iter()
+                .map(|certificate| {
+                    MithrilCertificate::try_from(certificate)
+                        .expect("A certificate of the chain should convert to a message")
+                })
+                .collect(),
+            genesis_verification_key: genesis_verifier
+                .to_verification_key()
+                .to_json_hex()
+                .expect("The genesis verification key should be encodable"),
+        }
+    }
+}
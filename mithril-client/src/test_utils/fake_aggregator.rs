@@ -0,0 +1,222 @@
+use anyhow::anyhow;
+use async_trait::async_trait;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Mutex, RwLock};
+
+use crate::aggregator_client::{AggregatorClient, AggregatorClientError, AggregatorRequest};
+use crate::test_utils::snapshot_message::to_list_item;
+use crate::test_utils::CertificateChainFixture;
+use crate::{MithrilCertificate, MithrilCertificateListItem, MithrilCertificateListItemMetadata};
+use crate::{Snapshot, SnapshotListItem};
+
+/// An in-process fake aggregator, it serves the certificates and the artifacts given to it
+/// through the [AggregatorClient] interface, so a [Client][crate::Client] can be built on it
+/// with [ClientBuilder::with_aggregator_client][crate::ClientBuilder::with_aggregator_client].
+///
+/// The requests for data that were not given fail as a request to a missing route of an
+/// aggregator, and the statistics posted are accepted and discarded.
+#[derive(Default)]
+pub struct FakeAggregator {
+    responses: RwLock<HashMap<String, String>>,
+    calls: Mutex<Vec<AggregatorRequest>>,
+}
+
+impl FakeAggregator {
+    /// [FakeAggregator] factory, without any data
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Serve the given response, serialized as JSON, to the given request
+    pub fn with_response<T: Serialize>(self, request: AggregatorRequest, response: &T) -> Self {
+        let response = serde_json::to_string(response)
+            .expect("The response of the fake aggregator should be serializable");
+        self.responses
+            .write()
+            .unwrap()
+            .insert(request.route(), response);
+        self
+    }
+
+    /// Serve the certificates of the given chain and their list
+    pub fn with_certificate_chain(self, certificate_chain: &CertificateChainFixture) -> Self {
+        let certificates_list: Vec<MithrilCertificateListItem> = certificate_chain
+            .certificates()
+            .iter()
+            .map(to_certificate_list_item)
+            .collect();
+
+        certificate_chain
+            .certificates()
+            .iter()
+            .fold(self, |aggregator, certificate| {
+                aggregator.with_response(
+                    AggregatorRequest::GetCertificate {
+                        hash: certificate.hash.clone(),
+                    },
+                    certificate,
+                )
+            })
+            .with_response(AggregatorRequest::ListCertificates, &certificates_list)
+    }
+
+    /// Serve the given snapshots and their list
+    pub fn with_snapshots(self, snapshots: &[Snapshot]) -> Self {
+        let snapshots_list: Vec<SnapshotListItem> =
+            snapshots.iter().cloned().map(to_list_item).collect();
+
+        snapshots
+            .iter()
+            .fold(self, |aggregator, snapshot| {
+                aggregator.with_response(
+                    AggregatorRequest::GetSnapshot {
+                        digest: snapshot.digest.clone(),
+                    },
+                    snapshot,
+                )
+            })
+            .with_response(AggregatorRequest::ListSnapshots, &snapshots_list)
+    }
+
+    /// The requests received by the fake aggregator, in the order they were received
+    pub fn calls(&self) -> Vec<AggregatorRequest> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    fn record_call(&self, request: &AggregatorRequest) {
+        self.calls.lock().unwrap().push(request.clone());
+    }
+}
+
+fn to_certificate_list_item(certificate: &MithrilCertificate) -> MithrilCertificateListItem {
+    MithrilCertificateListItem {
+        hash: certificate.hash.clone(),
+        previous_hash: certificate.previous_hash.clone(),
+        epoch: certificate.epoch,
+        signed_entity_type: certificate.signed_entity_type.clone(),
+        metadata: MithrilCertificateListItemMetadata {
+            network: certificate.metadata.network.clone(),
+            protocol_version: certificate.metadata.protocol_version.clone(),
+            protocol_parameters: certificate.metadata.protocol_parameters.clone(),
+            initiated_at: certificate.metadata.initiated_at,
+            sealed_at: certificate.metadata.sealed_at,
+            total_signers: certificate.metadata.signers.len(),
+        },
+        protocol_message: certificate.protocol_message.clone(),
+        signed_message: certificate.signed_message.clone(),
+        aggregate_verification_key: certificate.aggregate_verification_key.clone(),
+    }
+}
+
+#[cfg_attr(target_family = "wasm", async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait)]
+impl AggregatorClient for FakeAggregator {
+    async fn get_content(
+        &self,
+        request: AggregatorRequest,
+    ) -> Result<String, AggregatorClientError> {
+        self.record_call(&request);
+        let route = request.route();
+
+        self.responses
+            .read()
+            .unwrap()
+            .get(&route)
+            .cloned()
+            .ok_or_else(|| {
+                AggregatorClientError::RemoteServerLogical(anyhow!(
+                    "Fake aggregator: route='{route}' not found"
+                ))
+            })
+    }
+
+    async fn post_content(
+        &self,
+        request: AggregatorRequest,
+    ) -> Result<String, AggregatorClientError> {
+        self.record_call(&request);
+
+        Ok(String::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::ProtocolMessagePartKey;
+    use crate::test_utils::{CertificateChainFixtureBuilder, SnapshotMessageBuilder};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn serve_the_given_snapshots_and_record_the_calls() {
+        let snapshot = SnapshotMessageBuilder::new("digest-123").build();
+        let fake_aggregator = FakeAggregator::new().with_snapshots(&[snapshot.clone()]);
+        let request = AggregatorRequest::GetSnapshot {
+            digest: "digest-123".to_string(),
+        };
+
+        let content = fake_aggregator.get_content(request.clone()).await.unwrap();
+        assert_eq!(snapshot, serde_json::from_str(&content).unwrap());
+
+        let list: Vec<SnapshotListItem> = serde_json::from_str(
+            &fake_aggregator
+                .get_content(AggregatorRequest::ListSnapshots)
+                .await
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            vec!["digest-123".to_string()],
+            list.into_iter().map(|s| s.digest).collect::<Vec<_>>()
+        );
+
+        assert_eq!(
+            vec![request, AggregatorRequest::ListSnapshots],
+            fake_aggregator.calls()
+        );
+    }
+
+    #[tokio::test]
+    async fn fail_as_a_missing_route_for_data_that_was_not_given() {
+        let fake_aggregator = FakeAggregator::new();
+
+        let error = fake_aggregator
+            .get_content(AggregatorRequest::GetSnapshot {
+                digest: "whatever".to_string(),
+            })
+            .await
+            .unwrap_err();
+
+        assert!(
+            matches!(error, AggregatorClientError::RemoteServerLogical(_)),
+            "Expected a RemoteServerLogical error, got: {error:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn the_certificate_chain_is_valid_and_certifies_the_given_message_parts() {
+        let certificate_chain = CertificateChainFixtureBuilder::new()
+            .with_certified_message_part(ProtocolMessagePartKey::SnapshotDigest, "digest-123")
+            .build();
+        let client = crate::ClientBuilder::new(certificate_chain.genesis_verification_key())
+            .with_aggregator_client(std::sync::Arc::new(
+                FakeAggregator::new().with_certificate_chain(&certificate_chain),
+            ))
+            .build()
+            .unwrap();
+
+        let certificate = client
+            .certificate()
+            .verify_chain(&certificate_chain.latest_certificate().hash)
+            .await
+            .expect("The certificate chain should be valid");
+
+        assert_eq!(
+            Some(&"digest-123".to_string()),
+            certificate
+                .protocol_message
+                .get_message_part(&ProtocolMessagePartKey::SnapshotDigest)
+        );
+    }
+}
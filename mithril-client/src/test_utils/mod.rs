@@ -0,0 +1,65 @@
+//! Tools to test the applications that embed the Mithril client, without network access.
+//!
+//! They contain:
+//! * A builder of [certificate chains][CertificateChainFixtureBuilder] signed by fake signers,
+//!   which are validated by the same verification as the certificate chains of a real aggregator
+//! * A builder of [snapshot messages][SnapshotMessageBuilder]
+//! * An in-process [fake aggregator][FakeAggregator] serving the certificates and artifacts
+//!   given to it
+//!
+//! Requires the `test_utils` feature.
+//!
+//! # Verify the certificate chain of a snapshot
+//!
+//! ```
+//! # async fn run() -> mithril_client::MithrilResult<()> {
+//! use mithril_client::common::ProtocolMessagePartKey;
+//! use mithril_client::test_utils::{
+//!     CertificateChainFixtureBuilder, FakeAggregator, SnapshotMessageBuilder,
+//! };
+//! use mithril_client::ClientBuilder;
+//! use std::sync::Arc;
+//!
+//! let certificate_chain = CertificateChainFixtureBuilder::new()
+//!     .with_certified_message_part(ProtocolMessagePartKey::SnapshotDigest, "digest-123")
+//!     .build();
+//! let snapshot = SnapshotMessageBuilder::new("digest-123")
+//!     .with_certificate_hash(&certificate_chain.latest_certificate().hash)
+//!     .build();
+//! let fake_aggregator = FakeAggregator::new()
+//!     .with_certificate_chain(&certificate_chain)
+//!     .with_snapshots(&[snapshot.clone()]);
+//! let client = ClientBuilder::new(certificate_chain.genesis_verification_key())
+//!     .with_aggregator_client(Arc::new(fake_aggregator))
+//!     .build()?;
+//!
+//! let certificate = client
+//!     .certificate()
+//!     .verify_chain(&snapshot.certificate_hash)
+//!     .await?;
+//! let mut message = certificate.protocol_message.clone();
+//! message.set_message_part(ProtocolMessagePartKey::SnapshotDigest, snapshot.digest);
+//!
+//! assert!(certificate.match_message(&message));
+//! #    Ok(())
+//! # }
+//! ```
+
+mod certificate_chain;
+mod fake_aggregator;
+mod snapshot_message;
+
+pub use certificate_chain::{CertificateChainFixture, CertificateChainFixtureBuilder};
+pub use fake_aggregator::FakeAggregator;
+pub use snapshot_message::SnapshotMessageBuilder;
+
+#[cfg(test)]
+pub(crate) fn test_logger() -> slog::Logger {
+    use slog::Drain;
+    use std::sync::Arc;
+
+    let decorator = slog_term::PlainDecorator::new(slog_term::TestStdoutWriter);
+    let drain = slog_term::CompactFormat::new(decorator).build().fuse();
+    let drain = slog_async::Async::new(drain).build().fuse();
+    slog::Logger::root(Arc::new(drain), slog::o!())
+}
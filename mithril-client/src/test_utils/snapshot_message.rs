@@ -0,0 +1,75 @@
+use crate::common::{CardanoDbBeacon, CompressionAlgorithm};
+use crate::{Snapshot, SnapshotListItem};
+
+/// A builder of [Snapshot] messages, the fields that are not set are filled with dummy values.
+pub struct SnapshotMessageBuilder {
+    snapshot: Snapshot,
+}
+
+impl SnapshotMessageBuilder {
+    /// [SnapshotMessageBuilder] factory, for a snapshot with the given digest
+    pub fn new<T: Into<String>>(digest: T) -> Self {
+        Self {
+            snapshot: Snapshot {
+                digest: digest.into(),
+                ..Snapshot::dummy()
+            },
+        }
+    }
+
+    /// Set the hash of the certificate that certifies the snapshot
+    pub fn with_certificate_hash(mut self, certificate_hash: &str) -> Self {
+        self.snapshot.certificate_hash = certificate_hash.to_string();
+        self
+    }
+
+    /// Set the beacon of the snapshot
+    pub fn with_beacon(mut self, beacon: CardanoDbBeacon) -> Self {
+        self.snapshot.beacon = beacon;
+        self
+    }
+
+    /// Set the size of the snapshot archive
+    pub fn with_size(mut self, size: u64) -> Self {
+        self.snapshot.size = size;
+        self
+    }
+
+    /// Set the locations where the snapshot archive can be downloaded
+    pub fn with_locations(mut self, locations: Vec<String>) -> Self {
+        self.snapshot.locations = locations;
+        self
+    }
+
+    /// Set the compression algorithm of the snapshot archive
+    pub fn with_compression_algorithm(
+        mut self,
+        compression_algorithm: CompressionAlgorithm,
+    ) -> Self {
+        self.snapshot.compression_algorithm = Some(compression_algorithm);
+        self
+    }
+
+    /// Build the [Snapshot]
+    pub fn build(self) -> Snapshot {
+        self.snapshot
+    }
+
+    /// Build the [SnapshotListItem] of the snapshot
+    pub fn build_list_item(self) -> SnapshotListItem {
+        to_list_item(self.snapshot)
+    }
+}
+
+pub(super) fn to_list_item(snapshot: Snapshot) -> SnapshotListItem {
+    SnapshotListItem {
+        digest: snapshot.digest,
+        beacon: snapshot.beacon,
+        certificate_hash: snapshot.certificate_hash,
+        size: snapshot.size,
+        created_at: snapshot.created_at,
+        locations: snapshot.locations,
+        compression_algorithm: snapshot.compression_algorithm,
+        cardano_node_version: snapshot.cardano_node_version,
+    }
+}
@@ -0,0 +1,563 @@
+//! Require the certificates to be included in a certificate transparency log.
+//!
+//! When the aggregator publishes its certificates to a transparency log, the client can require
+//! a proof that the certificate it verifies was logged: an aggregator showing a forged
+//! certificate chain to some clients (split-view attack) would then have to log it publicly.
+//!
+//! The proofs are verified against the tree head signed with the key of the log, and each new
+//! tree head must be proven to extend the last one seen by the client, so that a log showing
+//! different trees to some clients is detected as well. With the `fs` feature, the last tree head
+//! seen can be kept in a state file so that this check also spans the runs of the client.
+//!
+//! ```no_run
+//! # async fn run() -> mithril_client::MithrilResult<()> {
+//! use mithril_client::ClientBuilder;
+//!
+//! let client = ClientBuilder::aggregator("YOUR_AGGREGATOR_ENDPOINT", "YOUR_GENESIS_VERIFICATION_KEY")
+//!     .with_transparency_log("YOUR_TRANSPARENCY_LOG_ENDPOINT", "YOUR_TRANSPARENCY_LOG_VERIFICATION_KEY")
+//!     .build()?;
+//! let certificate = client.certificate().verify_chain("CERTIFICATE_HASH").await?;
+//!
+//! println!("Chain of Certificate (hash: {}) is valid and logged", certificate.hash);
+//! #    Ok(())
+//! # }
+//! ```
+
+#[cfg(feature = "fs")]
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+#[cfg(not(target_family = "wasm"))]
+use std::time::Duration;
+
+use anyhow::{anyhow, Context};
+use async_trait::async_trait;
+use reqwest::{StatusCode, Url};
+use serde::de::DeserializeOwned;
+use tokio::sync::Mutex;
+
+use mithril_common::certificate_chain::{
+    TransparencyLogConsistencyProof, TransparencyLogInclusionProof, TransparencyLogSignedTreeHead,
+    TransparencyLogVerificationKey,
+};
+
+use crate::certificate_client::CertificateVerifier;
+use crate::{MithrilCertificate, MithrilResult};
+
+/// Timeout of the requests to the transparency log.
+#[cfg(not(target_family = "wasm"))]
+const HTTP_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// [CertificateVerifier] checking that the certificate is included in a transparency log before
+/// validating its chain with another verifier.
+pub struct TransparencyLogCertificateVerifier {
+    transparency_log_endpoint: Url,
+    verification_key: TransparencyLogVerificationKey,
+    http_client: reqwest::Client,
+    last_tree_head: Mutex<Option<TransparencyLogSignedTreeHead>>,
+    #[cfg(feature = "fs")]
+    state_file: Option<PathBuf>,
+    verifier: Arc<dyn CertificateVerifier>,
+}
+
+impl TransparencyLogCertificateVerifier {
+    /// Constructs a new `TransparencyLogCertificateVerifier`.
+    ///
+    /// The transparency log must expose:
+    /// - its signed tree head at `GET {transparency_log_endpoint}/tree-head`,
+    /// - the inclusion proofs at `GET {transparency_log_endpoint}/proofs/{certificate_hash}?tree_size={tree_size}`,
+    /// - the consistency proofs at `GET {transparency_log_endpoint}/consistency?first_tree_size={first}&second_tree_size={second}`.
+    ///
+    /// The `verification_key` is the JSON hex encoded Ed25519 key of the log.
+    pub fn new(
+        transparency_log_endpoint: &str,
+        verification_key: &str,
+        verifier: Arc<dyn CertificateVerifier>,
+    ) -> MithrilResult<Self> {
+        let transparency_log_endpoint = Url::parse(&format!(
+            "{}/",
+            transparency_log_endpoint.trim_end_matches('/')
+        ))
+        .with_context(|| {
+            format!("Invalid transparency log endpoint, it must be a correctly formed url: '{transparency_log_endpoint}'")
+        })?;
+        let verification_key = TransparencyLogVerificationKey::from_json_hex(verification_key)
+            .with_context(|| "Invalid transparency log verification key")?;
+
+        Ok(Self {
+            transparency_log_endpoint,
+            verification_key,
+            http_client: reqwest::Client::new(),
+            last_tree_head: Mutex::new(None),
+            #[cfg(feature = "fs")]
+            state_file: None,
+            verifier,
+        })
+    }
+
+    cfg_fs! {
+    /// Keep the last tree head seen in the given state file, so the next tree heads are proven to
+    /// extend it across the runs of the client.
+    ///
+    /// The tree head stored in the file, if it exists, must be signed by the log.
+    pub fn with_state_file(mut self, state_file: &Path) -> MithrilResult<Self> {
+        if state_file.exists() {
+            let tree_head: TransparencyLogSignedTreeHead = serde_json::from_slice(
+                &std::fs::read(state_file).with_context(|| {
+                    format!(
+                        "Could not read the transparency log state file: '{}'",
+                        state_file.display()
+                    )
+                })?,
+            )
+            .with_context(|| {
+                format!(
+                    "Could not parse the transparency log state file: '{}'",
+                    state_file.display()
+                )
+            })?;
+            tree_head.verify(&self.verification_key).with_context(|| {
+                "The tree head of the transparency log state file is not signed by the log"
+            })?;
+            self.last_tree_head = Mutex::new(Some(tree_head));
+        }
+        self.state_file = Some(state_file.to_path_buf());
+
+        Ok(self)
+    }
+    }
+
+    /// Store the last tree head in the state file, if any, the file is replaced atomically so a
+    /// failed write never loses the previous tree head.
+    #[cfg(feature = "fs")]
+    async fn save_state(&self, tree_head: &TransparencyLogSignedTreeHead) -> MithrilResult<()> {
+        if let Some(state_file) = &self.state_file {
+            let temporary_file = state_file.with_extension("tmp");
+            tokio::fs::write(&temporary_file, serde_json::to_vec(tree_head)?)
+                .await
+                .with_context(|| {
+                    format!(
+                        "Could not write the transparency log state file: '{}'",
+                        temporary_file.display()
+                    )
+                })?;
+            tokio::fs::rename(&temporary_file, state_file)
+                .await
+                .with_context(|| {
+                    format!(
+                        "Could not write the transparency log state file: '{}'",
+                        state_file.display()
+                    )
+                })?;
+        }
+
+        Ok(())
+    }
+
+    /// Get the last tree head of the log verified by this verifier
+    pub async fn last_tree_head(&self) -> Option<TransparencyLogSignedTreeHead> {
+        self.last_tree_head.lock().await.clone()
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> MithrilResult<Option<T>> {
+        let url = self
+            .transparency_log_endpoint
+            .join(path)
+            .with_context(|| format!("Could not build the transparency log url for '{path}'"))?;
+        let request = self.http_client.get(url.clone());
+        #[cfg(not(target_family = "wasm"))]
+        let request = request.timeout(HTTP_REQUEST_TIMEOUT);
+        let response = request
+            .send()
+            .await
+            .with_context(|| format!("Could not reach the transparency log at '{url}'"))?;
+
+        match response.status() {
+            StatusCode::OK => response.json().await.map(Some).with_context(|| {
+                format!("Could not parse the response of the transparency log at '{url}'")
+            }),
+            StatusCode::NOT_FOUND => Ok(None),
+            status => Err(anyhow!(
+                "The transparency log returned an unexpected status: {status}"
+            )),
+        }
+    }
+
+    async fn get_signed_tree_head(&self) -> MithrilResult<TransparencyLogSignedTreeHead> {
+        let tree_head: TransparencyLogSignedTreeHead = self
+            .get("tree-head")
+            .await?
+            .ok_or_else(|| anyhow!("The transparency log has no tree head"))?;
+        tree_head.verify(&self.verification_key)?;
+
+        Ok(tree_head)
+    }
+
+    async fn verify_consistency(
+        &self,
+        last_tree_head: &TransparencyLogSignedTreeHead,
+        tree_head: &TransparencyLogSignedTreeHead,
+    ) -> MithrilResult<()> {
+        if last_tree_head.tree_size > tree_head.tree_size {
+            return Err(anyhow!(
+                "The transparency log tree shrank from {} to {} entries",
+                last_tree_head.tree_size,
+                tree_head.tree_size
+            ));
+        }
+        let proof = if last_tree_head.tree_size == tree_head.tree_size {
+            TransparencyLogConsistencyProof {
+                first_tree_size: tree_head.tree_size,
+                second_tree_size: tree_head.tree_size,
+                consistency_path: vec![],
+            }
+        } else {
+            self.get::<TransparencyLogConsistencyProof>(&format!(
+                "consistency?first_tree_size={}&second_tree_size={}",
+                last_tree_head.tree_size, tree_head.tree_size
+            ))
+            .await?
+            .ok_or_else(|| anyhow!("The transparency log has no consistency proof"))?
+        };
+
+        proof.verify(last_tree_head, tree_head).with_context(|| {
+            format!(
+                "The transparency log tree of root '{}' is not consistent with the last seen tree of root '{}'",
+                tree_head.root_hash, last_tree_head.root_hash
+            )
+        })
+    }
+
+    async fn get_inclusion_proof(
+        &self,
+        certificate_hash: &str,
+        tree_size: u64,
+    ) -> MithrilResult<TransparencyLogInclusionProof> {
+        self.get(&format!("proofs/{certificate_hash}?tree_size={tree_size}"))
+            .await?
+            .ok_or_else(|| {
+                anyhow!("Certificate '{certificate_hash}' is not included in the transparency log")
+            })
+    }
+}
+
+#[cfg_attr(target_family = "wasm", async_trait(?Send))]
+#[cfg_attr(not(target_family = "wasm"), async_trait)]
+impl CertificateVerifier for TransparencyLogCertificateVerifier {
+    async fn verify_chain(&self, certificate: &MithrilCertificate) -> MithrilResult<()> {
+        let mut last_tree_head = self.last_tree_head.lock().await;
+        let tree_head = self.get_signed_tree_head().await?;
+        if let Some(last_tree_head) = last_tree_head.as_ref() {
+            self.verify_consistency(last_tree_head, &tree_head).await?;
+        }
+        #[cfg(feature = "fs")]
+        self.save_state(&tree_head).await?;
+        *last_tree_head = Some(tree_head.clone());
+        drop(last_tree_head);
+
+        self.get_inclusion_proof(&certificate.hash, tree_head.tree_size)
+            .await?
+            .verify(&certificate.hash, &tree_head)
+            .with_context(|| {
+                format!(
+                    "Certificate '{}' is not proven to be included in the transparency log",
+                    certificate.hash
+                )
+            })?;
+
+        self.verifier.verify_chain(certificate).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use httpmock::prelude::*;
+
+    use mithril_common::certificate_chain::TransparencyLogSigner;
+    use mithril_common::test_utils::{fake_data, fake_keys};
+
+    use crate::certificate_client::MockCertificateVerifier;
+
+    use super::*;
+
+    fn leaf_hash(certificate_hash: &str) -> String {
+        hex::encode(TransparencyLogInclusionProof::compute_leaf_hash(
+            certificate_hash,
+        ))
+    }
+
+    fn two_leaves_root_hash(first_hash: &str, second_hash: &str) -> String {
+        hex::encode(TransparencyLogInclusionProof::compute_node_hash(
+            &TransparencyLogInclusionProof::compute_leaf_hash(first_hash),
+            &TransparencyLogInclusionProof::compute_leaf_hash(second_hash),
+        ))
+    }
+
+    fn log_signer() -> TransparencyLogSigner {
+        TransparencyLogSigner::create_deterministic_signer()
+    }
+
+    fn log_verification_key() -> String {
+        log_signer().verification_key().to_json_hex().unwrap()
+    }
+
+    fn mithril_certificate(hash: &str) -> MithrilCertificate {
+        fake_data::certificate(hash).try_into().unwrap()
+    }
+
+    fn build_verifier(
+        server: &MockServer,
+        inner_verifier: MockCertificateVerifier,
+    ) -> TransparencyLogCertificateVerifier {
+        TransparencyLogCertificateVerifier::new(
+            &server.base_url(),
+            &log_verification_key(),
+            Arc::new(inner_verifier),
+        )
+        .unwrap()
+    }
+
+    /// Serve a log made of a single certificate hash
+    fn serve_single_leaf_log<'a>(
+        server: &'a MockServer,
+        certificate_hash: &str,
+    ) -> [httpmock::Mock<'a>; 2] {
+        let tree_head = log_signer().sign_tree_head(1, &leaf_hash(certificate_hash));
+        let tree_head_mock = server.mock(|when, then| {
+            when.method(GET).path("/tree-head");
+            then.status(200).json_body_obj(&tree_head);
+        });
+        let proof_mock = server.mock(|when, then| {
+            when.method(GET)
+                .path(format!("/proofs/{certificate_hash}"))
+                .query_param("tree_size", "1");
+            then.status(200)
+                .json_body_obj(&TransparencyLogInclusionProof {
+                    leaf_index: 0,
+                    tree_size: 1,
+                    audit_path: vec![],
+                });
+        });
+
+        [tree_head_mock, proof_mock]
+    }
+
+    #[tokio::test]
+    async fn verify_the_chain_of_a_certificate_included_in_the_log() {
+        let server = MockServer::start();
+        serve_single_leaf_log(&server, "certificate-hash");
+        let mut inner_verifier = MockCertificateVerifier::new();
+        inner_verifier
+            .expect_verify_chain()
+            .once()
+            .returning(|_| Ok(()));
+        let verifier = build_verifier(&server, inner_verifier);
+
+        verifier
+            .verify_chain(&mithril_certificate("certificate-hash"))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn reject_a_certificate_not_included_in_the_log() {
+        let server = MockServer::start();
+        serve_single_leaf_log(&server, "another-certificate-hash");
+        let mut inner_verifier = MockCertificateVerifier::new();
+        inner_verifier.expect_verify_chain().never();
+        let verifier = build_verifier(&server, inner_verifier);
+
+        verifier
+            .verify_chain(&mithril_certificate("certificate-hash"))
+            .await
+            .expect_err("A certificate not included in the log should be rejected");
+    }
+
+    #[tokio::test]
+    async fn reject_a_certificate_with_an_invalid_inclusion_proof() {
+        let server = MockServer::start();
+        serve_single_leaf_log(&server, "another-certificate-hash");
+        server.mock(|when, then| {
+            when.method(GET).path("/proofs/certificate-hash");
+            then.status(200)
+                .json_body_obj(&TransparencyLogInclusionProof {
+                    leaf_index: 0,
+                    tree_size: 1,
+                    audit_path: vec![],
+                });
+        });
+        let mut inner_verifier = MockCertificateVerifier::new();
+        inner_verifier.expect_verify_chain().never();
+        let verifier = build_verifier(&server, inner_verifier);
+
+        verifier
+            .verify_chain(&mithril_certificate("certificate-hash"))
+            .await
+            .expect_err("A certificate with an invalid inclusion proof should be rejected");
+    }
+
+    #[tokio::test]
+    async fn reject_a_tree_head_not_signed_by_the_log() {
+        let server = MockServer::start();
+        serve_single_leaf_log(&server, "certificate-hash");
+        let mut inner_verifier = MockCertificateVerifier::new();
+        inner_verifier.expect_verify_chain().never();
+        let another_log_verification_key = fake_keys::genesis_verification_key()[1];
+        let verifier = TransparencyLogCertificateVerifier::new(
+            &server.base_url(),
+            another_log_verification_key,
+            Arc::new(inner_verifier),
+        )
+        .unwrap();
+
+        verifier
+            .verify_chain(&mithril_certificate("certificate-hash"))
+            .await
+            .expect_err("A tree head not signed by the log should be rejected");
+    }
+
+    #[tokio::test]
+    async fn verify_that_the_log_tree_extends_the_last_seen_tree() {
+        let server = MockServer::start();
+        let single_leaf_log_mocks = serve_single_leaf_log(&server, "certificate-hash-0");
+        let mut inner_verifier = MockCertificateVerifier::new();
+        inner_verifier.expect_verify_chain().returning(|_| Ok(()));
+        let verifier = build_verifier(&server, inner_verifier);
+        verifier
+            .verify_chain(&mithril_certificate("certificate-hash-0"))
+            .await
+            .unwrap();
+
+        for mut mock in single_leaf_log_mocks {
+            mock.delete();
+        }
+        let tree_head = log_signer().sign_tree_head(
+            2,
+            &two_leaves_root_hash("certificate-hash-0", "certificate-hash-1"),
+        );
+        server.mock(|when, then| {
+            when.method(GET).path("/tree-head");
+            then.status(200).json_body_obj(&tree_head);
+        });
+        let consistency_mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/consistency")
+                .query_param("first_tree_size", "1")
+                .query_param("second_tree_size", "2");
+            then.status(200)
+                .json_body_obj(&TransparencyLogConsistencyProof {
+                    first_tree_size: 1,
+                    second_tree_size: 2,
+                    consistency_path: vec![leaf_hash("certificate-hash-1")],
+                });
+        });
+        server.mock(|when, then| {
+            when.method(GET)
+                .path("/proofs/certificate-hash-1")
+                .query_param("tree_size", "2");
+            then.status(200)
+                .json_body_obj(&TransparencyLogInclusionProof {
+                    leaf_index: 1,
+                    tree_size: 2,
+                    audit_path: vec![leaf_hash("certificate-hash-0")],
+                });
+        });
+
+        verifier
+            .verify_chain(&mithril_certificate("certificate-hash-1"))
+            .await
+            .unwrap();
+
+        consistency_mock.assert();
+        assert_eq!(Some(tree_head), verifier.last_tree_head().await);
+    }
+
+    #[tokio::test]
+    async fn reject_a_log_tree_that_does_not_extend_the_last_seen_tree() {
+        let server = MockServer::start();
+        let single_leaf_log_mocks = serve_single_leaf_log(&server, "certificate-hash-0");
+        let mut inner_verifier = MockCertificateVerifier::new();
+        inner_verifier
+            .expect_verify_chain()
+            .once()
+            .returning(|_| Ok(()));
+        let verifier = build_verifier(&server, inner_verifier);
+        verifier
+            .verify_chain(&mithril_certificate("certificate-hash-0"))
+            .await
+            .unwrap();
+        let last_tree_head = verifier.last_tree_head().await;
+
+        for mut mock in single_leaf_log_mocks {
+            mock.delete();
+        }
+        let forked_tree_head = log_signer().sign_tree_head(
+            2,
+            &two_leaves_root_hash("forged-certificate-hash", "certificate-hash-1"),
+        );
+        server.mock(|when, then| {
+            when.method(GET).path("/tree-head");
+            then.status(200).json_body_obj(&forked_tree_head);
+        });
+        server.mock(|when, then| {
+            when.method(GET).path("/consistency");
+            then.status(200)
+                .json_body_obj(&TransparencyLogConsistencyProof {
+                    first_tree_size: 1,
+                    second_tree_size: 2,
+                    consistency_path: vec![leaf_hash("certificate-hash-1")],
+                });
+        });
+
+        verifier
+            .verify_chain(&mithril_certificate("certificate-hash-1"))
+            .await
+            .expect_err("A log tree that does not extend the last seen tree should be rejected");
+
+        assert_eq!(last_tree_head, verifier.last_tree_head().await);
+    }
+
+    #[cfg(feature = "fs")]
+    #[tokio::test]
+    async fn the_last_seen_tree_head_is_kept_in_the_state_file_across_runs() {
+        let state_file = mithril_common::test_utils::TempDir::create(
+            "transparency_log",
+            "the_last_seen_tree_head_is_kept_in_the_state_file_across_runs",
+        )
+        .join("transparency-log-state.json");
+        let server = MockServer::start();
+        let single_leaf_log_mocks = serve_single_leaf_log(&server, "certificate-hash-0");
+        let mut inner_verifier = MockCertificateVerifier::new();
+        inner_verifier.expect_verify_chain().returning(|_| Ok(()));
+        let verifier = build_verifier(&server, inner_verifier)
+            .with_state_file(&state_file)
+            .unwrap();
+        verifier
+            .verify_chain(&mithril_certificate("certificate-hash-0"))
+            .await
+            .unwrap();
+        let last_tree_head = verifier.last_tree_head().await;
+
+        for mut mock in single_leaf_log_mocks {
+            mock.delete();
+        }
+        let forked_tree_head =
+            log_signer().sign_tree_head(1, &leaf_hash("forged-certificate-hash"));
+        server.mock(|when, then| {
+            when.method(GET).path("/tree-head");
+            then.status(200).json_body_obj(&forked_tree_head);
+        });
+        let mut inner_verifier = MockCertificateVerifier::new();
+        inner_verifier.expect_verify_chain().never();
+        let verifier = build_verifier(&server, inner_verifier)
+            .with_state_file(&state_file)
+            .unwrap();
+        assert_eq!(last_tree_head, verifier.last_tree_head().await);
+
+        verifier
+            .verify_chain(&mithril_certificate("forged-certificate-hash"))
+            .await
+            .expect_err(
+                "A log tree inconsistent with the one seen by a previous run should be rejected",
+            );
+    }
+}
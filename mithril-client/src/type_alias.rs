@@ -56,11 +56,21 @@ pub use mithril_common::messages::CardanoStakeDistributionMessage as CardanoStak
 /// List item of Cardano stake distributions.
 pub use mithril_common::messages::CardanoStakeDistributionListItemMessage as CardanoStakeDistributionListItem;
 
+/// Features advertised by an aggregator, ie: the signed entity types it certifies.
+pub use mithril_common::messages::AggregatorFeaturesMessage as AggregatorFeatures;
+
+/// Capabilities of an aggregator.
+pub use mithril_common::messages::AggregatorCapabilities;
+
+/// Settings of the current epoch of an aggregator, ie: the protocol parameters and the signers of
+/// the current and next epochs.
+pub use mithril_common::messages::EpochSettingsMessage as EpochSettings;
+
 /// `mithril-common` re-exports
 pub mod common {
     pub use mithril_common::entities::{
-        BlockHash, BlockNumber, CardanoDbBeacon, ChainPoint, CompressionAlgorithm, Epoch,
-        ImmutableFileNumber, ProtocolMessage, ProtocolMessagePartKey, ProtocolParameters,
-        SlotNumber, StakeDistribution, TransactionHash,
+        BlockHash, BlockNumber, CardanoDbBeacon, CardanoNetwork, ChainPoint, CompressionAlgorithm,
+        Epoch, ImmutableFileNumber, ProtocolMessage, ProtocolMessagePartKey, ProtocolParameters,
+        SignedEntityTypeDiscriminants, SlotNumber, StakeDistribution, TransactionHash,
     };
 }
@@ -0,0 +1,55 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use mithril_common::{
+    certificate_chain::{
+        CertificateVerifier, FakeCertificaterRetriever, MithrilCertificateVerifier,
+    },
+    crypto_helper::tests_setup::setup_certificate_chain,
+};
+use slog::Drain;
+use std::sync::Arc;
+
+const TOTAL_CERTIFICATES_BENCHES: &[u64] = &[10, 50, 100];
+const CERTIFICATES_PER_EPOCH: u64 = 5;
+
+#[inline]
+fn create_logger() -> slog::Logger {
+    let drain = slog_async::Async::new(slog::Discard).build().fuse();
+    slog::Logger::root(Arc::new(drain), slog::o!())
+}
+
+fn verify_certificate_chain_benches(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let mut group = c.benchmark_group("verify_certificate_chain");
+    for total_certificates in TOTAL_CERTIFICATES_BENCHES.iter() {
+        let (certificates, genesis_verifier) =
+            setup_certificate_chain(*total_certificates, CERTIFICATES_PER_EPOCH);
+        let genesis_verification_key = genesis_verifier.to_verification_key();
+        let verifier = MithrilCertificateVerifier::new(
+            create_logger(),
+            Arc::new(FakeCertificaterRetriever::from_certificates(&certificates)),
+        );
+        group.bench_with_input(
+            BenchmarkId::from_parameter(total_certificates),
+            total_certificates,
+            |b, &_total_certificates| {
+                b.to_async(&runtime).iter(|| async {
+                    verifier
+                        .verify_certificate_chain(
+                            certificates[0].clone(),
+                            &genesis_verification_key,
+                        )
+                        .await
+                        .unwrap()
+                });
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(
+    name=benches;
+    config = Criterion::default().sample_size(10);
+    targets=verify_certificate_chain_benches
+);
+criterion_main!(benches);
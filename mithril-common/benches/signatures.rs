@@ -0,0 +1,122 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use mithril_common::{
+    crypto_helper::{tests_setup::setup_protocol_parameters, ProtocolParameters},
+    entities::{ProtocolMessage, ProtocolMessagePartKey},
+    protocol::{MultiSigner, SignerBuilder, ToMessage},
+    test_utils::{MithrilFixture, MithrilFixtureBuilder},
+};
+
+const TOTAL_SIGNERS_BENCHES: &[usize] = &[10, 100, 500];
+
+fn setup_fixture(total_signers: usize) -> MithrilFixture {
+    MithrilFixtureBuilder::default()
+        .with_signers(total_signers)
+        .with_protocol_parameters(setup_protocol_parameters().into())
+        .build()
+}
+
+fn build_multi_signer(fixture: &MithrilFixture) -> MultiSigner {
+    SignerBuilder::new(
+        &fixture.signers_with_stake(),
+        &fixture.protocol_parameters(),
+    )
+    .unwrap()
+    .build_multi_signer()
+}
+
+fn setup_message() -> ProtocolMessage {
+    let mut message = ProtocolMessage::new();
+    message.set_message_part(
+        ProtocolMessagePartKey::SnapshotDigest,
+        "bench-digest".to_string(),
+    );
+    message.set_message_part(
+        ProtocolMessagePartKey::NextAggregateVerificationKey,
+        "bench-next-avk".to_string(),
+    );
+
+    message
+}
+
+fn verify_single_signature_benches(c: &mut Criterion) {
+    let mut group = c.benchmark_group("verify_single_signature");
+    for total_signers in TOTAL_SIGNERS_BENCHES.iter() {
+        let fixture = setup_fixture(*total_signers);
+        let multi_signer = build_multi_signer(&fixture);
+        let message = setup_message();
+        let single_signature = fixture
+            .sign_all(&message)
+            .pop()
+            .expect("at least one signer should win the lottery");
+        group.bench_with_input(
+            BenchmarkId::from_parameter(total_signers),
+            total_signers,
+            |b, &_total_signers| {
+                b.iter(|| {
+                    multi_signer
+                        .verify_single_signature(&message, &single_signature)
+                        .unwrap()
+                });
+            },
+        );
+    }
+    group.finish();
+}
+
+fn aggregate_single_signatures_benches(c: &mut Criterion) {
+    let mut group = c.benchmark_group("aggregate_single_signatures");
+    for total_signers in TOTAL_SIGNERS_BENCHES.iter() {
+        let fixture = setup_fixture(*total_signers);
+        let multi_signer = build_multi_signer(&fixture);
+        let message = setup_message();
+        let single_signatures = fixture.sign_all(&message);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(total_signers),
+            total_signers,
+            |b, &_total_signers| {
+                b.iter(|| {
+                    multi_signer
+                        .aggregate_single_signatures(&single_signatures, &message)
+                        .unwrap()
+                });
+            },
+        );
+    }
+    group.finish();
+}
+
+fn verify_multi_signature_benches(c: &mut Criterion) {
+    let mut group = c.benchmark_group("verify_multi_signature");
+    for total_signers in TOTAL_SIGNERS_BENCHES.iter() {
+        let fixture = setup_fixture(*total_signers);
+        let multi_signer = build_multi_signer(&fixture);
+        let message = setup_message();
+        let multi_signature = multi_signer
+            .aggregate_single_signatures(&fixture.sign_all(&message), &message)
+            .unwrap();
+        let avk = multi_signer.compute_aggregate_verification_key();
+        let protocol_parameters: ProtocolParameters = fixture.protocol_parameters().into();
+        group.bench_with_input(
+            BenchmarkId::from_parameter(total_signers),
+            total_signers,
+            |b, &_total_signers| {
+                b.iter(|| {
+                    multi_signature
+                        .verify(message.to_message().as_bytes(), &avk, &protocol_parameters)
+                        .unwrap()
+                });
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(
+    name=benches;
+    config = Criterion::default().sample_size(10);
+    targets=
+      verify_single_signature_benches,
+      aggregate_single_signatures_benches,
+      verify_multi_signature_benches
+);
+criterion_main!(benches);
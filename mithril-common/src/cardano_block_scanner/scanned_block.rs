@@ -1,7 +1,21 @@
+use anyhow::{anyhow, Context};
 use pallas_traverse::MultiEraBlock;
 use std::fmt::{Debug, Formatter};
 
 use crate::entities::{BlockNumber, CardanoTransaction, ChainPoint, SlotNumber, TransactionHash};
+use crate::StdResult;
+
+/// Names of the eras of the hard fork combinator, indexed by the tag wrapping their blocks
+const ERA_NAMES: [&str; 8] = [
+    "Byron (epoch boundary)",
+    "Byron",
+    "Shelley",
+    "Allegra",
+    "Mary",
+    "Alonzo",
+    "Babbage",
+    "Conway",
+];
 
 /// A block scanned from a Cardano database
 #[derive(Clone, PartialEq)]
@@ -32,7 +46,34 @@ impl ScannedBlock {
         }
     }
 
-    pub(crate) fn convert(multi_era_block: MultiEraBlock) -> Self {
+    /// Decode a raw block, as sent by the ChainSync mini-protocol, of any era from Byron to
+    /// Conway.
+    pub fn decode(raw_block: &[u8]) -> StdResult<Self> {
+        let multi_era_block = MultiEraBlock::decode(raw_block)
+            .map_err(|e| anyhow!(e))
+            .with_context(|| match Self::era_tag(raw_block) {
+                Some(tag) if (tag as usize) < ERA_NAMES.len() => {
+                    format!("Failed to decode a block of the {} era", ERA_NAMES[tag as usize])
+                }
+                Some(tag) => format!(
+                    "Failed to decode a block of an unsupported era (tag: {tag}), a hard fork may require an upgrade"
+                ),
+                None => "Failed to decode a block not wrapped with its era tag".to_string(),
+            })?;
+
+        Ok(Self::convert(multi_era_block))
+    }
+
+    /// Read the era tag of a raw block, the first item of the `[era, block]` array wrapping it
+    fn era_tag(raw_block: &[u8]) -> Option<u8> {
+        match raw_block {
+            [0x82, tag, ..] if *tag < 0x18 => Some(*tag),
+            [0x82, 0x18, tag, ..] => Some(*tag),
+            _ => None,
+        }
+    }
+
+    fn convert(multi_era_block: MultiEraBlock) -> Self {
         let mut transactions = Vec::new();
         for tx in &multi_era_block.txs() {
             transactions.push(tx.hash().to_string());
@@ -91,3 +132,158 @@ impl From<&ScannedBlock> for ChainPoint {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Expected content of the blocks of `mithril-test-lab/test_data/blocks`
+    struct GoldenBlock {
+        raw_block: &'static str,
+        block_hash: &'static str,
+        block_number: u64,
+        slot_number: u64,
+        transactions_hashes: Vec<&'static str>,
+    }
+
+    fn decode_golden_block(golden_block: GoldenBlock) {
+        let raw_block = hex::decode(golden_block.raw_block.trim()).unwrap();
+
+        let scanned_block = ScannedBlock::decode(&raw_block).unwrap();
+
+        assert_eq!(
+            ScannedBlock::new(
+                hex::decode(golden_block.block_hash).unwrap(),
+                BlockNumber(golden_block.block_number),
+                SlotNumber(golden_block.slot_number),
+                golden_block.transactions_hashes,
+            ),
+            scanned_block
+        );
+    }
+
+    #[test]
+    fn decode_byron_block() {
+        decode_golden_block(GoldenBlock {
+            raw_block: include_str!("../../../mithril-test-lab/test_data/blocks/byron2.block"),
+            block_hash: "5a4f135aac8083df35161afca982d2cbd6c752421ae292ad09735b982d86ce40",
+            block_number: 3239842,
+            slot_number: 3241381,
+            transactions_hashes: vec![
+                "a06e5a0150e09f8983be2deafab9e04afc60d92e7110999eb672c903343f1e26",
+                "f736d55633782151cd528f9918c3587155cbd59c46b6ca839e5b4860a780f379",
+            ],
+        });
+    }
+
+    #[test]
+    fn decode_shelley_block() {
+        decode_golden_block(GoldenBlock {
+            raw_block: include_str!("../../../mithril-test-lab/test_data/blocks/shelley1.block"),
+            block_hash: "7dce9cfd6d44c5eb58eb5200532b3fa04086ee26cbdd712a4dd04f1b1ef90ca5",
+            block_number: 4662237,
+            slot_number: 7948610,
+            transactions_hashes: vec![
+                "48347a50990c63680b9c4af9808bbca2e2e9782fe7f8b2f811ac6c51952863bc",
+                "9d1ad32177c90c866be4e29650b7bbaddec7f8707cf7c2a4d0fc80faa32a04e3",
+                "fdb308fe3c32d0b27eea6af70e0086b8c3aa8efe7c79f0322351b8083e853859",
+                "8ac3db74ed1f93b232c37e3e1a1509d1977cf65fd54a38c438273c1925dbfe6f",
+            ],
+        });
+    }
+
+    #[test]
+    fn decode_allegra_block() {
+        decode_golden_block(GoldenBlock {
+            raw_block: include_str!("../../../mithril-test-lab/test_data/blocks/allegra1.block"),
+            block_hash: "f23a7dc9c587fc056a25ff88c8a4d0f8a3f86a799b931672ccbc02edbcc63c98",
+            block_number: 5192804,
+            slot_number: 18748707,
+            transactions_hashes: vec![
+                "f811d6905239d0834d6f5b9322ad6e8abe38c420518e40ebc3549cbd4929472f",
+                "791e578a590251de4d4fb3bd263d66330aa3caaf683adba528ef1f41d835094d",
+                "1a6d30b4e0cc5df2d6bf8b37008e2c2f33c6713794c7d81f37aaf803094d405b",
+            ],
+        });
+    }
+
+    #[test]
+    fn decode_mary_block() {
+        decode_golden_block(GoldenBlock {
+            raw_block: include_str!("../../../mithril-test-lab/test_data/blocks/mary1.block"),
+            block_hash: "5ccb2a9061bea6b20353489dfd21ea47787e368c88d00ed381b34759ec8d0eb4",
+            block_number: 5616812,
+            slot_number: 27388606,
+            transactions_hashes: vec![
+                "39949ce990b150f7f1e5903114080ab6f8cca777c07ac76fcb32c2d9353fbf56",
+                "083cb789b89ebf89fbbfa66dcae3aa9c16908361eebce28245a877159852588a",
+                "748866af27fc7f3c74c505c78dd45ee220656d732a882ada6917c8e3d3ba1d83",
+                "b3e832bfdc48cde6ce4260897f079e5a8d3220f39491135fa240c2685a6312b6",
+                "790437753c3bdadde375f3a3b492f62cdf5c51b5f8c81bf0e51ec3639ab44e61",
+                "c3bcd11114c5fe14251d5bae16997e49f3017306d934eb55188207f7e2e79154",
+                "c5c1cb2a443831e0c93fab7ddccba98b09ecf4e7b409e9829ec851bc59001c85",
+                "835f034d2c177c762f96eb42260dcf7156a5401169ce9a1b4cf4ba7fb39589bb",
+                "2e6907e2f70b14b6aba0a2705ad4faefe753a44b75670b476a9bc0ce142fb4f8",
+                "f343582d8368471961063a2011a582cbfb6aa7bd2c3906ba5e90c2f31d562b56",
+                "a5f9011bb2e72fe87b12c751e6fcc70fd12ff0fdf3080ffc66709509a8a3b76d",
+                "11663bec0781ff09550ff3c32694e3d144a9cf91fc231692e4b756d7a50a6418",
+                "d0df00a38bfbda1e33e8c951ce2984eaf6374eebb19e9ac07879977cafc83171",
+                "abb24970824a7e34e560006c0965feb0a620436fcbf94ddc7c8d8e71038d26eb",
+            ],
+        });
+    }
+
+    #[test]
+    fn decode_alonzo_block() {
+        decode_golden_block(GoldenBlock {
+            raw_block: include_str!("../../../mithril-test-lab/test_data/blocks/alonzo1.block"),
+            block_hash: "18362a803c351d5950fa929d87d17c4c34c624d3558f3f96d927221ed6436d23",
+            block_number: 3098772,
+            slot_number: 43381130,
+            transactions_hashes: vec![
+                "8ae0cd531635579a9b52b954a840782d12235251fb1451e5c699e864c677514a",
+                "bb5bb4e1c09c02aa199c60e9f330102912e3ef977bb73ecfd8f790945c6091d4",
+                "8cdd88042ddb6c800714fb1469fb1a1a93152aae3c87a81f2a3016f2ee5c664a",
+                "10add6bdaa7ade06466bdd768456e756709090846b58bf473f240c484db517fa",
+                "8838f5ab27894a6543255aeaec086f7b3405a6db6e7457a541409cdbbf0cd474",
+            ],
+        });
+    }
+
+    #[test]
+    fn decode_conway_block() {
+        decode_golden_block(GoldenBlock {
+            raw_block: include_str!("../../../mithril-test-lab/test_data/blocks/conway1.block"),
+            block_hash: "9b51ccd4f161c08382a445684ff3eb788923608acbea283081fa5ccf663fef8d",
+            block_number: 1093546,
+            slot_number: 22075282,
+            transactions_hashes: vec![
+                "ed8431dbe32cff36814ee838a7a002152d43a7465faaf05529907717c793527a",
+            ],
+        });
+    }
+
+    #[test]
+    fn decoding_a_block_of_an_unknown_era_fails_with_its_era_tag() {
+        let mut raw_block = hex::decode(
+            include_str!("../../../mithril-test-lab/test_data/blocks/conway1.block").trim(),
+        )
+        .unwrap();
+        raw_block[1] = 0x08;
+
+        let error = ScannedBlock::decode(&raw_block).expect_err("An unknown era should fail");
+
+        assert!(
+            format!("{error:?}").contains("unsupported era (tag: 8)"),
+            "unexpected error: {error:?}"
+        );
+    }
+
+    #[test]
+    fn read_the_era_tag_of_a_raw_block() {
+        assert_eq!(Some(7), ScannedBlock::era_tag(&[0x82, 0x07, 0x85]));
+        assert_eq!(Some(42), ScannedBlock::era_tag(&[0x82, 0x18, 0x2a, 0x85]));
+        assert_eq!(None, ScannedBlock::era_tag(&[0x85, 0x07]));
+        assert_eq!(None, ScannedBlock::era_tag(&[]));
+    }
+}
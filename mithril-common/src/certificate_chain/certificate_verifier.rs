@@ -3,7 +3,12 @@
 use anyhow::{anyhow, Context};
 use async_trait::async_trait;
 use hex::ToHex;
+#[cfg(not(target_arch = "wasm32"))]
+use mithril_stm::stm::{StmAggrSig, StmParameters};
+use rayon::prelude::*;
 use slog::{debug, Logger};
+#[cfg(not(target_arch = "wasm32"))]
+use std::ops::Deref;
 use std::sync::Arc;
 use thiserror::Error;
 
@@ -16,6 +21,7 @@ use crate::entities::{
     Certificate, CertificateSignature, ProtocolMessage, ProtocolMessagePartKey, ProtocolParameters,
 };
 use crate::logging::LoggerExtensions;
+use crate::protocol::SignatureScheme;
 use crate::StdResult;
 
 #[cfg(test)]
@@ -56,6 +62,10 @@ pub enum CertificateVerifierError {
     /// certificate that's not a genesis certificate.
     #[error("can't validate genesis certificate: given certificate isn't a genesis certificate")]
     InvalidGenesisCertificateProvided,
+
+    /// Error raised when the protocol version of a [Certificate] matches no known signature scheme.
+    #[error("unsupported certificate protocol version: '{0}'")]
+    UnsupportedProtocolVersion(String),
 }
 
 /// CertificateVerifier is the cryptographic engine in charge of verifying multi signatures and
@@ -99,6 +109,36 @@ pub trait CertificateVerifier: Send + Sync {
         Ok(())
     }
 
+    /// Verify a batch of consecutive certificates of a chain, ordered from the latest to the
+    /// oldest, and returns the previous certificate of the last one of the batch if the chain
+    /// continues (as with [verify_certificate][CertificateVerifier::verify_certificate], the
+    /// returned certificate is not verified yet)
+    ///
+    /// Each certificate of the batch must be the previous certificate of the one before it.
+    async fn verify_certificates(
+        &self,
+        certificates: &[Certificate],
+        genesis_verification_key: &ProtocolGenesisVerificationKey,
+    ) -> StdResult<Option<Certificate>> {
+        let mut previous_or_none: Option<Certificate> = None;
+        for (index, certificate) in certificates.iter().enumerate() {
+            if let Some(previous_certificate) = &previous_or_none {
+                if previous_certificate.hash != certificate.hash {
+                    return Err(anyhow!(
+                        CertificateVerifierError::CertificateChainPreviousHashUnmatch
+                    ));
+                }
+            } else if index > 0 {
+                return Ok(None);
+            }
+            previous_or_none = self
+                .verify_certificate(certificate, genesis_verification_key)
+                .await?;
+        }
+
+        Ok(previous_or_none)
+    }
+
     /// still a dirty hack to mock the protocol message
     /// verify that the protocol message is equal to the signed message of the certificate.
     /// TODO: Remove this method.
@@ -151,18 +191,59 @@ impl MithrilCertificateVerifier {
             .map_err(|e| CertificateVerifierError::VerifyMultiSignature(e.to_string()))
     }
 
+    /// Retrieve the signature scheme of a certificate from the protocol version of its metadata
+    fn certificate_signature_scheme(certificate: &Certificate) -> StdResult<SignatureScheme> {
+        SignatureScheme::from_protocol_version(&certificate.metadata.protocol_version).ok_or_else(
+            || {
+                anyhow!(CertificateVerifierError::UnsupportedProtocolVersion(
+                    certificate.metadata.protocol_version.clone()
+                ))
+            },
+        )
+    }
+
+    /// Verify that the hash of a certificate matches its content and that it does not chain to
+    /// itself
+    fn verify_certificate_integrity(certificate: &Certificate) -> StdResult<()> {
+        certificate
+            .hash
+            .eq(&certificate.compute_hash())
+            .then(|| certificate.hash.clone())
+            .ok_or(CertificateVerifierError::CertificateHashUnmatch)?;
+
+        if certificate.is_chaining_to_itself() {
+            return Err(anyhow!(
+                CertificateVerifierError::CertificateChainInfiniteLoop
+            ));
+        }
+
+        Ok(())
+    }
+
     /// Verify Standard certificate
     async fn verify_standard_certificate(
         &self,
         certificate: &Certificate,
         signature: &ProtocolMultiSignature,
     ) -> StdResult<Option<Certificate>> {
-        self.verify_multi_signature(
-            certificate.signed_message.as_bytes(),
-            signature,
-            &certificate.aggregate_verification_key,
-            &certificate.metadata.protocol_parameters,
-        )?;
+        match Self::certificate_signature_scheme(certificate)? {
+            SignatureScheme::StmV1 => self.verify_multi_signature(
+                certificate.signed_message.as_bytes(),
+                signature,
+                &certificate.aggregate_verification_key,
+                &certificate.metadata.protocol_parameters,
+            )?,
+        }
+
+        self.verify_previous_certificate(certificate).await
+    }
+
+    /// Retrieve the previous certificate of a standard certificate and verify that they are
+    /// chained, returns the previous certificate if the chain continues
+    async fn verify_previous_certificate(
+        &self,
+        certificate: &Certificate,
+    ) -> StdResult<Option<Certificate>> {
         let previous_certificate = self
             .certificate_retriever
             .get_certificate_details(&certificate.previous_hash)
@@ -170,6 +251,20 @@ impl MithrilCertificateVerifier {
             .map_err(|e| anyhow!(e))
             .with_context(|| "Can not retrieve previous certificate during verification")?;
 
+        if self.verify_previous_certificate_link(certificate, &previous_certificate)? {
+            Ok(Some(previous_certificate))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Verify that a standard certificate is chained to its previous certificate, returns `false`
+    /// if the previous certificate does not register a next aggregate verification key
+    fn verify_previous_certificate_link(
+        &self,
+        certificate: &Certificate,
+        previous_certificate: &Certificate,
+    ) -> StdResult<bool> {
         if previous_certificate.hash != certificate.previous_hash {
             return Err(anyhow!(
                 CertificateVerifierError::CertificateChainPreviousHashUnmatch
@@ -215,12 +310,10 @@ impl MithrilCertificateVerifier {
                     next_aggregate_verification_key,
                 ) =>
             {
-                Ok(Some(previous_certificate.to_owned()))
+                Ok(true)
             }
-            Some(_) if valid_certificate_has_same_epoch_as_previous() => {
-                Ok(Some(previous_certificate.to_owned()))
-            }
-            None => Ok(None),
+            Some(_) if valid_certificate_has_same_epoch_as_previous() => Ok(true),
+            None => Ok(false),
             _ => {
                 debug!(
                     self.logger, "Certificate chain AVK unmatch";
@@ -232,6 +325,83 @@ impl MithrilCertificateVerifier {
             }
         }
     }
+
+    /// Verify the multi signatures of standard certificates signed with [SignatureScheme::StmV1]
+    ///
+    /// The signatures are first verified in a single batch, if it fails they are verified one by
+    /// one to find the invalid one.
+    fn verify_stm_v1_multi_signatures(
+        &self,
+        certificates: &[(&Certificate, &ProtocolMultiSignature)],
+    ) -> StdResult<()> {
+        // A batch made only of a genesis certificate has no multi signature to verify
+        if certificates.is_empty() {
+            return Ok(());
+        }
+
+        match Self::batch_verify_stm_v1_multi_signatures(certificates) {
+            Ok(()) => return Ok(()),
+            Err(error) => {
+                debug!(self.logger, "Batch verification of the multi signatures failed, verifying them one by one"; "error" => ?error);
+            }
+        }
+
+        certificates
+            .par_iter()
+            .map(|(certificate, signature)| {
+                self.verify_multi_signature(
+                    certificate.signed_message.as_bytes(),
+                    signature,
+                    &certificate.aggregate_verification_key,
+                    &certificate.metadata.protocol_parameters,
+                )
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(())
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn batch_verify_stm_v1_multi_signatures(
+        certificates: &[(&Certificate, &ProtocolMultiSignature)],
+    ) -> Result<(), CertificateVerifierError> {
+        let signatures = certificates
+            .iter()
+            .map(|(_, signature)| (*signature).deref().clone())
+            .collect::<Vec<_>>();
+        let messages = certificates
+            .iter()
+            .map(|(certificate, _)| certificate.signed_message.as_bytes().to_vec())
+            .collect::<Vec<_>>();
+        let aggregate_verification_keys = certificates
+            .iter()
+            .map(|(certificate, _)| certificate.aggregate_verification_key.deref().clone())
+            .collect::<Vec<_>>();
+        let protocol_parameters = certificates
+            .iter()
+            .map(|(certificate, _)| certificate.metadata.protocol_parameters.to_owned().into())
+            .collect::<Vec<StmParameters>>();
+
+        StmAggrSig::batch_verify(
+            &signatures,
+            &messages,
+            &aggregate_verification_keys,
+            &protocol_parameters,
+        )
+        .map_err(|e| CertificateVerifierError::VerifyMultiSignature(e.to_string()))
+    }
+
+    /// The batch verification of the aggregates is not available on wasm targets
+    #[cfg(target_arch = "wasm32")]
+    fn batch_verify_stm_v1_multi_signatures(
+        _certificates: &[(&Certificate, &ProtocolMultiSignature)],
+    ) -> Result<(), CertificateVerifierError> {
+        Err(CertificateVerifierError::VerifyMultiSignature(
+            "batch verification is not available on this target".to_string(),
+        ))
+    }
 }
 
 #[cfg_attr(target_family = "wasm", async_trait(?Send))]
@@ -272,29 +442,85 @@ impl CertificateVerifier for MithrilCertificateVerifier {
             "certificate_signed_entity_type" => ?certificate.signed_entity_type(),
         );
 
-        certificate
-            .hash
-            .eq(&certificate.compute_hash())
-            .then(|| certificate.hash.clone())
-            .ok_or(CertificateVerifierError::CertificateHashUnmatch)?;
+        Self::verify_certificate_integrity(certificate)?;
 
-        if certificate.is_chaining_to_itself() {
-            Err(anyhow!(
-                CertificateVerifierError::CertificateChainInfiniteLoop
-            ))
-        } else {
+        match &certificate.signature {
+            CertificateSignature::GenesisSignature(_signature) => {
+                self.verify_genesis_certificate(certificate, genesis_verification_key)
+                    .await?;
+                Ok(None)
+            }
+            CertificateSignature::MultiSignature(_, signature) => {
+                self.verify_standard_certificate(certificate, signature)
+                    .await
+            }
+        }
+    }
+
+    /// Verify a batch of certificates
+    async fn verify_certificates(
+        &self,
+        certificates: &[Certificate],
+        genesis_verification_key: &ProtocolGenesisVerificationKey,
+    ) -> StdResult<Option<Certificate>> {
+        debug!(
+            self.logger, "Verifying a batch of certificates";
+            "batch_size" => certificates.len(),
+            "first_certificate_hash" => certificates.first().map(|c| c.hash.as_str()),
+        );
+
+        // The hashes and the links between the certificates of the batch do not depend on each
+        // other: they are checked in parallel (the first error of the batch is reported).
+        let links = certificates
+            .par_iter()
+            .enumerate()
+            .map(|(index, certificate)| {
+                Self::verify_certificate_integrity(certificate)?;
+                match (&certificate.signature, certificates.get(index + 1)) {
+                    (CertificateSignature::MultiSignature(..), Some(previous_certificate)) => {
+                        self.verify_previous_certificate_link(certificate, previous_certificate)
+                    }
+                    _ => Ok(false),
+                }
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .collect::<StdResult<Vec<_>>>()?;
+
+        // As with the certificate by certificate verification, the chain ends at the first
+        // certificate that is not linked to its previous one
+        let chain_length = match links.iter().position(|is_linked| !is_linked) {
+            Some(last_index) => last_index + 1,
+            None => return Ok(None),
+        };
+        let certificates = &certificates[..chain_length];
+
+        let mut stm_v1_certificates = vec![];
+        for certificate in certificates {
             match &certificate.signature {
                 CertificateSignature::GenesisSignature(_signature) => {
                     self.verify_genesis_certificate(certificate, genesis_verification_key)
                         .await?;
-                    Ok(None)
                 }
                 CertificateSignature::MultiSignature(_, signature) => {
-                    self.verify_standard_certificate(certificate, signature)
-                        .await
+                    match Self::certificate_signature_scheme(certificate)? {
+                        SignatureScheme::StmV1 => {
+                            stm_v1_certificates.push((certificate, signature))
+                        }
+                    }
                 }
             }
         }
+        self.verify_stm_v1_multi_signatures(&stm_v1_certificates)?;
+
+        // The chain continues after the last certificate of the batch if it is a standard one
+        let last_certificate = &certificates[chain_length - 1];
+        match last_certificate.signature {
+            CertificateSignature::MultiSignature(..) if chain_length == links.len() => {
+                self.verify_previous_certificate(last_certificate).await
+            }
+            _ => Ok(None),
+        }
     }
 }
 
@@ -521,6 +747,37 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_verify_certificate_ko_unsupported_protocol_version() {
+        let total_certificates = 5;
+        let certificates_per_epoch = 1;
+        let (fake_certificates, genesis_verifier) =
+            setup_certificate_chain(total_certificates, certificates_per_epoch);
+        let mut fake_certificate1 = fake_certificates[0].clone();
+        fake_certificate1.metadata.protocol_version = "unknown-version".to_string();
+        fake_certificate1.hash = fake_certificate1.compute_hash();
+        let mock_certificate_retriever = MockCertificateRetrieverImpl::new();
+        let verifier = MithrilCertificateVerifier::new(
+            TestLogger::stdout(),
+            Arc::new(mock_certificate_retriever),
+        );
+        let error = verifier
+            .verify_certificate(&fake_certificate1, &genesis_verifier.to_verification_key())
+            .await
+            .expect_err("verify_certificate should fail");
+        let error = error
+            .downcast_ref::<CertificateVerifierError>()
+            .expect("Can not downcast to `CertificateVerifierError`.");
+
+        assert!(
+            matches!(
+                error,
+                CertificateVerifierError::UnsupportedProtocolVersion(version) if version == "unknown-version"
+            ),
+            "unexpected error type: {error:?}"
+        );
+    }
+
     #[tokio::test]
     async fn test_verify_certificate_chain_ok() {
         let total_certificates = 15;
@@ -573,4 +830,140 @@ mod tests {
             "unexpected error type: {error:?}"
         );
     }
+
+    /// The certificates from the latest one to the genesis one, following their previous hashes
+    fn follow_chain_to_genesis(certificates: &[Certificate]) -> Vec<Certificate> {
+        let mut chain = vec![certificates[0].clone()];
+        while let Some(previous_certificate) = certificates
+            .iter()
+            .find(|c| c.hash == chain[chain.len() - 1].previous_hash)
+        {
+            chain.push(previous_certificate.clone());
+        }
+
+        chain
+    }
+
+    #[tokio::test]
+    async fn test_verify_certificates_ok() {
+        let total_certificates = 15;
+        let certificates_per_epoch = 2;
+        let (fake_certificates, genesis_verifier) =
+            setup_certificate_chain(total_certificates, certificates_per_epoch);
+        let certificates = follow_chain_to_genesis(&fake_certificates);
+        let mut mock_certificate_retriever = MockCertificateRetrieverImpl::new();
+        mock_certificate_retriever
+            .expect_get_certificate_details()
+            .never();
+        let verifier = MithrilCertificateVerifier::new(
+            TestLogger::stdout(),
+            Arc::new(mock_certificate_retriever),
+        );
+
+        let previous_certificate = verifier
+            .verify_certificates(&certificates, &genesis_verifier.to_verification_key())
+            .await
+            .expect("unexpected error");
+
+        assert_eq!(None, previous_certificate);
+    }
+
+    #[tokio::test]
+    async fn test_verify_certificates_ok_with_only_the_genesis_certificate() {
+        let (fake_certificates, genesis_verifier) = setup_certificate_chain(3, 1);
+        let genesis_certificate = fake_certificates.last().unwrap().clone();
+        let verifier = MithrilCertificateVerifier::new(
+            TestLogger::stdout(),
+            Arc::new(MockCertificateRetrieverImpl::new()),
+        );
+
+        let previous_certificate = verifier
+            .verify_certificates(
+                &[genesis_certificate],
+                &genesis_verifier.to_verification_key(),
+            )
+            .await
+            .expect("unexpected error");
+
+        assert_eq!(None, previous_certificate);
+    }
+
+    #[tokio::test]
+    async fn test_verify_certificates_ok_return_the_previous_certificate_of_the_batch() {
+        let total_certificates = 15;
+        let certificates_per_epoch = 2;
+        let (fake_certificates, genesis_verifier) =
+            setup_certificate_chain(total_certificates, certificates_per_epoch);
+        let certificates = follow_chain_to_genesis(&fake_certificates);
+        let certificate_retriever =
+            FakeCertificaterRetriever::from_certificates(&fake_certificates);
+        let verifier =
+            MithrilCertificateVerifier::new(TestLogger::stdout(), Arc::new(certificate_retriever));
+
+        let previous_certificate = verifier
+            .verify_certificates(&certificates[..3], &genesis_verifier.to_verification_key())
+            .await
+            .expect("unexpected error");
+
+        assert_eq!(Some(certificates[3].clone()), previous_certificate);
+    }
+
+    #[tokio::test]
+    async fn test_verify_certificates_ko_certificate_chain_previous_hash_unmatch() {
+        let total_certificates = 15;
+        let certificates_per_epoch = 2;
+        let (fake_certificates, genesis_verifier) =
+            setup_certificate_chain(total_certificates, certificates_per_epoch);
+        let certificate_retriever =
+            FakeCertificaterRetriever::from_certificates(&fake_certificates);
+        let verifier =
+            MithrilCertificateVerifier::new(TestLogger::stdout(), Arc::new(certificate_retriever));
+        let chain = follow_chain_to_genesis(&fake_certificates);
+        let certificates = vec![chain[0].clone(), chain[2].clone()];
+
+        let error = verifier
+            .verify_certificates(&certificates, &genesis_verifier.to_verification_key())
+            .await
+            .expect_err("verify_certificates should fail");
+        let error = error
+            .downcast_ref::<CertificateVerifierError>()
+            .expect("Can not downcast to `CertificateVerifierError`.");
+
+        assert!(
+            matches!(
+                error,
+                CertificateVerifierError::CertificateChainPreviousHashUnmatch
+            ),
+            "unexpected error type: {error:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_verify_certificates_ko_invalid_multi_signature() {
+        let total_certificates = 15;
+        let certificates_per_epoch = 2;
+        let (mut fake_certificates, genesis_verifier) =
+            setup_certificate_chain(total_certificates, certificates_per_epoch);
+        fake_certificates[0].signed_message = "tampered-message".to_string();
+        fake_certificates[0].hash = fake_certificates[0].compute_hash();
+        let certificate_retriever =
+            FakeCertificaterRetriever::from_certificates(&fake_certificates);
+        let verifier =
+            MithrilCertificateVerifier::new(TestLogger::stdout(), Arc::new(certificate_retriever));
+
+        let certificates = follow_chain_to_genesis(&fake_certificates);
+
+        let error = verifier
+            .verify_certificates(&certificates, &genesis_verifier.to_verification_key())
+            .await
+            .expect_err("verify_certificates should fail");
+        let error = error
+            .downcast_ref::<CertificateVerifierError>()
+            .expect("Can not downcast to `CertificateVerifierError`.");
+
+        assert!(
+            matches!(error, CertificateVerifierError::VerifyMultiSignature(_)),
+            "unexpected error type: {error:?}"
+        );
+    }
 }
@@ -3,6 +3,7 @@
 mod certificate_genesis;
 mod certificate_retriever;
 mod certificate_verifier;
+mod transparency_log;
 cfg_test_tools! {
     mod fake_certificate_retriever;
 }
@@ -12,6 +13,11 @@ pub use certificate_retriever::{CertificateRetriever, CertificateRetrieverError}
 pub use certificate_verifier::{
     CertificateVerifier, CertificateVerifierError, MithrilCertificateVerifier,
 };
+pub use transparency_log::{
+    TransparencyLogConsistencyProof, TransparencyLogEntry, TransparencyLogInclusionProof,
+    TransparencyLogSecretKey, TransparencyLogSignature, TransparencyLogSignedTreeHead,
+    TransparencyLogSigner, TransparencyLogVerificationKey,
+};
 
 cfg_test_tools! {
     pub use fake_certificate_retriever::FakeCertificaterRetriever;
@@ -0,0 +1,549 @@
+//! Entries and inclusion proofs of a certificate transparency log.
+//!
+//! The aggregator appends the hash of each issued certificate to an append-only Merkle tree log
+//! (RFC 9162 style), so that a client can require a proof that a certificate was publicly logged.
+//! An aggregator showing a different certificate chain to some clients (split-view) would then
+//! have to log the forged certificates where they can be detected by the log monitors.
+//!
+//! The proofs are verified against a tree head signed by the log, and the successive tree heads
+//! seen by a client must be consistent: a log showing different trees to some clients is then
+//! detected as well.
+
+use anyhow::{anyhow, Context};
+use ed25519_dalek::{Signer, SigningKey};
+use rand_chacha::rand_core::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::crypto_helper::ProtocolKey;
+use crate::StdResult;
+
+/// Wrapper of [Ed25519:PublicKey](https://docs.rs/ed25519-dalek/latest/ed25519_dalek/struct.VerifyingKey.html).
+pub type TransparencyLogVerificationKey = ProtocolKey<ed25519_dalek::VerifyingKey>;
+
+/// Wrapper of [Ed25519:SigningKey](https://docs.rs/ed25519-dalek/latest/ed25519_dalek/struct.SigningKey.html).
+pub type TransparencyLogSecretKey = ProtocolKey<ed25519_dalek::SigningKey>;
+
+/// Wrapper of [Ed25519:Signature](https://docs.rs/ed25519-dalek/latest/ed25519_dalek/struct.Signature.html).
+pub type TransparencyLogSignature = ProtocolKey<ed25519_dalek::Signature>;
+
+const LEAF_HASH_PREFIX: u8 = 0x00;
+const NODE_HASH_PREFIX: u8 = 0x01;
+
+/// Entry appended to the transparency log for an issued certificate.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransparencyLogEntry {
+    /// Hash of the certificate
+    pub certificate_hash: String,
+}
+
+/// Tree head of the transparency log signed with the key of the log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransparencyLogSignedTreeHead {
+    /// Number of leaves of the tree
+    pub tree_size: u64,
+
+    /// Hex encoded root hash of the tree
+    pub root_hash: String,
+
+    /// Signature of the tree size and root hash by the log
+    pub signature: TransparencyLogSignature,
+}
+
+impl TransparencyLogSignedTreeHead {
+    /// Compute the message signed by the log for a tree head
+    pub fn compute_signed_message(tree_size: u64, root_hash: &str) -> Vec<u8> {
+        format!("{tree_size}:{root_hash}").into_bytes()
+    }
+
+    /// Verify that the tree head is signed with the key of the log
+    pub fn verify(&self, verification_key: &TransparencyLogVerificationKey) -> StdResult<()> {
+        verification_key
+            .verify_strict(
+                &Self::compute_signed_message(self.tree_size, &self.root_hash),
+                &self.signature,
+            )
+            .map_err(|e| anyhow!(e))
+            .with_context(|| {
+                format!(
+                    "Invalid signature of the transparency log tree head of size {} and root '{}'",
+                    self.tree_size, self.root_hash
+                )
+            })
+    }
+}
+
+/// A cryptographic signer of the tree heads of a transparency log
+pub struct TransparencyLogSigner {
+    secret_key: TransparencyLogSecretKey,
+}
+
+impl TransparencyLogSigner {
+    /// [TransparencyLogSigner] deterministic
+    pub fn create_deterministic_signer() -> Self {
+        let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+        Self::from_secret_key(SigningKey::generate(&mut rng).into())
+    }
+
+    /// [TransparencyLogSigner] from [TransparencyLogSecretKey]
+    pub fn from_secret_key(secret_key: TransparencyLogSecretKey) -> Self {
+        Self { secret_key }
+    }
+
+    /// Get the [TransparencyLogVerificationKey] of the signer
+    pub fn verification_key(&self) -> TransparencyLogVerificationKey {
+        self.secret_key.verifying_key().into()
+    }
+
+    /// Sign the tree head of the given size and root hash
+    pub fn sign_tree_head(&self, tree_size: u64, root_hash: &str) -> TransparencyLogSignedTreeHead {
+        let message = TransparencyLogSignedTreeHead::compute_signed_message(tree_size, root_hash);
+
+        TransparencyLogSignedTreeHead {
+            tree_size,
+            root_hash: root_hash.to_string(),
+            signature: self.secret_key.sign(&message).into(),
+        }
+    }
+}
+
+/// Proof that a certificate hash is included in a tree of the transparency log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransparencyLogInclusionProof {
+    /// Index of the leaf of the certificate hash in the tree
+    pub leaf_index: u64,
+
+    /// Number of leaves of the tree
+    pub tree_size: u64,
+
+    /// Hex encoded hashes of the sibling nodes from the leaf to the root
+    pub audit_path: Vec<String>,
+}
+
+impl TransparencyLogInclusionProof {
+    /// Compute the hash of the leaf of a certificate hash
+    pub fn compute_leaf_hash(certificate_hash: &str) -> [u8; 32] {
+        Sha256::new()
+            .chain_update([LEAF_HASH_PREFIX])
+            .chain_update(certificate_hash.as_bytes())
+            .finalize()
+            .into()
+    }
+
+    /// Compute the hash of a node from the hashes of its children
+    pub fn compute_node_hash(left: &[u8], right: &[u8]) -> [u8; 32] {
+        Sha256::new()
+            .chain_update([NODE_HASH_PREFIX])
+            .chain_update(left)
+            .chain_update(right)
+            .finalize()
+            .into()
+    }
+
+    /// Verify that the certificate hash is included at the leaf index in the tree of the given
+    /// tree head.
+    ///
+    /// The signature of the tree head must be verified beforehand.
+    pub fn verify(
+        &self,
+        certificate_hash: &str,
+        tree_head: &TransparencyLogSignedTreeHead,
+    ) -> StdResult<()> {
+        if self.tree_size != tree_head.tree_size {
+            return Err(anyhow!(
+                "Invalid inclusion proof: it is computed for a tree of size {} instead of {}",
+                self.tree_size,
+                tree_head.tree_size
+            ));
+        }
+        if self.leaf_index >= self.tree_size {
+            return Err(anyhow!(
+                "Invalid inclusion proof: leaf index {} is out of a tree of size {}",
+                self.leaf_index,
+                self.tree_size
+            ));
+        }
+
+        let mut leaf_index = self.leaf_index;
+        let mut last_index = self.tree_size - 1;
+        let mut hash = Self::compute_leaf_hash(certificate_hash);
+        for sibling in &self.audit_path {
+            let sibling =
+                decode_hash(sibling).with_context(|| "Invalid inclusion proof audit path hash")?;
+            if last_index == 0 {
+                return Err(anyhow!(
+                    "Invalid inclusion proof: the audit path is too long"
+                ));
+            }
+            if leaf_index % 2 == 1 || leaf_index == last_index {
+                hash = Self::compute_node_hash(&sibling, &hash);
+                while leaf_index % 2 == 0 && leaf_index != 0 {
+                    leaf_index >>= 1;
+                    last_index >>= 1;
+                }
+            } else {
+                hash = Self::compute_node_hash(&hash, &sibling);
+            }
+            leaf_index >>= 1;
+            last_index >>= 1;
+        }
+
+        if last_index != 0 || hex::encode(hash) != tree_head.root_hash {
+            return Err(anyhow!(
+                "Invalid inclusion proof: certificate '{certificate_hash}' is not included in the tree of root '{}'",
+                tree_head.root_hash
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Proof that a tree of the transparency log is an extension of a previous tree of the log, ie:
+/// that no entry was removed nor modified between them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransparencyLogConsistencyProof {
+    /// Number of leaves of the previous tree
+    pub first_tree_size: u64,
+
+    /// Number of leaves of the extended tree
+    pub second_tree_size: u64,
+
+    /// Hex encoded hashes of the nodes needed to compute both roots
+    pub consistency_path: Vec<String>,
+}
+
+impl TransparencyLogConsistencyProof {
+    /// Verify that the tree of the second tree head is an extension of the tree of the first
+    /// tree head.
+    ///
+    /// The signatures of the tree heads must be verified beforehand.
+    pub fn verify(
+        &self,
+        first_tree_head: &TransparencyLogSignedTreeHead,
+        second_tree_head: &TransparencyLogSignedTreeHead,
+    ) -> StdResult<()> {
+        if self.first_tree_size != first_tree_head.tree_size
+            || self.second_tree_size != second_tree_head.tree_size
+        {
+            return Err(anyhow!(
+                "Invalid consistency proof: it is computed between trees of sizes {} and {} instead of {} and {}",
+                self.first_tree_size,
+                self.second_tree_size,
+                first_tree_head.tree_size,
+                second_tree_head.tree_size
+            ));
+        }
+        if self.first_tree_size == 0 || self.first_tree_size > self.second_tree_size {
+            return Err(anyhow!(
+                "Invalid consistency proof: a tree of size {} can not be extended to a tree of size {}",
+                self.first_tree_size,
+                self.second_tree_size
+            ));
+        }
+        if self.first_tree_size == self.second_tree_size {
+            if !self.consistency_path.is_empty()
+                || first_tree_head.root_hash != second_tree_head.root_hash
+            {
+                return Err(anyhow!(
+                    "Invalid consistency proof: trees of the same size {} have different roots",
+                    self.first_tree_size
+                ));
+            }
+            return Ok(());
+        }
+
+        let first_root_hash = decode_hash(&first_tree_head.root_hash)
+            .with_context(|| "Invalid first tree head root hash")?;
+        let mut path = self
+            .consistency_path
+            .iter()
+            .map(|hash| decode_hash(hash))
+            .collect::<StdResult<Vec<_>>>()
+            .with_context(|| "Invalid consistency proof path hash")?;
+        if self.first_tree_size.is_power_of_two() {
+            path.insert(0, first_root_hash.clone());
+        }
+        let Some((first_node, path)) = path.split_first() else {
+            return Err(anyhow!("Invalid consistency proof: the path is empty"));
+        };
+
+        let mut first_index = self.first_tree_size - 1;
+        let mut second_index = self.second_tree_size - 1;
+        while first_index % 2 == 1 {
+            first_index >>= 1;
+            second_index >>= 1;
+        }
+        let mut first_hash = first_node.clone();
+        let mut second_hash = first_node.clone();
+        for node in path {
+            if second_index == 0 {
+                return Err(anyhow!("Invalid consistency proof: the path is too long"));
+            }
+            if first_index % 2 == 1 || first_index == second_index {
+                first_hash =
+                    TransparencyLogInclusionProof::compute_node_hash(node, &first_hash).to_vec();
+                second_hash =
+                    TransparencyLogInclusionProof::compute_node_hash(node, &second_hash).to_vec();
+                while first_index % 2 == 0 && first_index != 0 {
+                    first_index >>= 1;
+                    second_index >>= 1;
+                }
+            } else {
+                second_hash =
+                    TransparencyLogInclusionProof::compute_node_hash(&second_hash, node).to_vec();
+            }
+            first_index >>= 1;
+            second_index >>= 1;
+        }
+
+        if second_index != 0
+            || first_hash != first_root_hash
+            || hex::encode(second_hash) != second_tree_head.root_hash
+        {
+            return Err(anyhow!(
+                "Invalid consistency proof: the tree of root '{}' is not an extension of the tree of root '{}'",
+                second_tree_head.root_hash,
+                first_tree_head.root_hash
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+fn decode_hash(hash: &str) -> StdResult<Vec<u8>> {
+    hex::decode(hash).map_err(|e| anyhow!(e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree_hash(leaves: &[&str]) -> [u8; 32] {
+        match leaves.len() {
+            1 => TransparencyLogInclusionProof::compute_leaf_hash(leaves[0]),
+            n => {
+                let split = n.next_power_of_two() / 2;
+                TransparencyLogInclusionProof::compute_node_hash(
+                    &tree_hash(&leaves[..split]),
+                    &tree_hash(&leaves[split..]),
+                )
+            }
+        }
+    }
+
+    fn audit_path(leaf_index: usize, leaves: &[&str]) -> Vec<String> {
+        if leaves.len() == 1 {
+            return vec![];
+        }
+        let split = leaves.len().next_power_of_two() / 2;
+        if leaf_index < split {
+            let mut path = audit_path(leaf_index, &leaves[..split]);
+            path.push(hex::encode(tree_hash(&leaves[split..])));
+            path
+        } else {
+            let mut path = audit_path(leaf_index - split, &leaves[split..]);
+            path.push(hex::encode(tree_hash(&leaves[..split])));
+            path
+        }
+    }
+
+    /// Consistency path between the tree of the `first_tree_size` first leaves and the tree of
+    /// all the leaves, as defined by the RFC 9162
+    fn consistency_path(first_tree_size: usize, leaves: &[&str], is_complete: bool) -> Vec<String> {
+        if first_tree_size == leaves.len() {
+            return match is_complete {
+                true => vec![],
+                false => vec![hex::encode(tree_hash(leaves))],
+            };
+        }
+        let split = leaves.len().next_power_of_two() / 2;
+        if first_tree_size <= split {
+            let mut path = consistency_path(first_tree_size, &leaves[..split], is_complete);
+            path.push(hex::encode(tree_hash(&leaves[split..])));
+            path
+        } else {
+            let mut path = consistency_path(first_tree_size - split, &leaves[split..], false);
+            path.push(hex::encode(tree_hash(&leaves[..split])));
+            path
+        }
+    }
+
+    fn signed_tree_head(leaves: &[&str]) -> TransparencyLogSignedTreeHead {
+        TransparencyLogSigner::create_deterministic_signer()
+            .sign_tree_head(leaves.len() as u64, &hex::encode(tree_hash(leaves)))
+    }
+
+    fn build_inclusion_proof(leaf_index: usize, leaves: &[&str]) -> TransparencyLogInclusionProof {
+        TransparencyLogInclusionProof {
+            leaf_index: leaf_index as u64,
+            tree_size: leaves.len() as u64,
+            audit_path: audit_path(leaf_index, leaves),
+        }
+    }
+
+    fn build_consistency_proof(
+        first_tree_size: usize,
+        leaves: &[&str],
+    ) -> TransparencyLogConsistencyProof {
+        TransparencyLogConsistencyProof {
+            first_tree_size: first_tree_size as u64,
+            second_tree_size: leaves.len() as u64,
+            consistency_path: consistency_path(first_tree_size, leaves, true),
+        }
+    }
+
+    const HASHES: [&str; 7] = [
+        "hash-0", "hash-1", "hash-2", "hash-3", "hash-4", "hash-5", "hash-6",
+    ];
+
+    #[test]
+    fn verify_the_signature_of_a_tree_head() {
+        let verification_key =
+            TransparencyLogSigner::create_deterministic_signer().verification_key();
+        let tree_head = signed_tree_head(&HASHES);
+
+        tree_head.verify(&verification_key).unwrap();
+    }
+
+    #[test]
+    fn reject_a_tree_head_signed_with_another_key() {
+        let another_verification_key = TransparencyLogSigner::from_secret_key(
+            SigningKey::generate(&mut ChaCha20Rng::from_seed([1u8; 32])).into(),
+        )
+        .verification_key();
+        let tree_head = signed_tree_head(&HASHES);
+
+        tree_head
+            .verify(&another_verification_key)
+            .expect_err("A tree head signed with another key should be rejected");
+    }
+
+    #[test]
+    fn reject_a_tree_head_with_a_modified_root_hash() {
+        let verification_key =
+            TransparencyLogSigner::create_deterministic_signer().verification_key();
+        let tree_head = TransparencyLogSignedTreeHead {
+            root_hash: hex::encode(tree_hash(&HASHES[..3])),
+            ..signed_tree_head(&HASHES)
+        };
+
+        tree_head
+            .verify(&verification_key)
+            .expect_err("A tree head with a modified root hash should be rejected");
+    }
+
+    #[test]
+    fn verify_the_inclusion_of_every_leaf_of_trees_of_various_sizes() {
+        for tree_size in 1..=HASHES.len() {
+            let leaves = &HASHES[..tree_size];
+            let tree_head = signed_tree_head(leaves);
+            for (leaf_index, leaf) in leaves.iter().enumerate() {
+                build_inclusion_proof(leaf_index, leaves)
+                    .verify(leaf, &tree_head)
+                    .unwrap_or_else(|e| {
+                        panic!("leaf {leaf_index} of a tree of size {tree_size}: {e:?}")
+                    });
+            }
+        }
+    }
+
+    #[test]
+    fn reject_the_inclusion_proof_of_another_certificate_hash() {
+        let leaves = ["hash-0", "hash-1", "hash-2"];
+
+        build_inclusion_proof(1, &leaves)
+            .verify("hash-2", &signed_tree_head(&leaves))
+            .expect_err("The proof of another certificate hash should be rejected");
+    }
+
+    #[test]
+    fn reject_an_inclusion_proof_in_another_tree() {
+        let leaves = ["hash-0", "hash-1", "hash-2"];
+        let another_tree_head = TransparencyLogSignedTreeHead {
+            root_hash: hex::encode(tree_hash(&["hash-0", "hash-1", "hash-x"])),
+            ..signed_tree_head(&leaves)
+        };
+
+        build_inclusion_proof(1, &leaves)
+            .verify("hash-1", &another_tree_head)
+            .expect_err("A proof in another tree should be rejected");
+    }
+
+    #[test]
+    fn reject_an_inclusion_proof_computed_for_another_tree_size() {
+        let leaves = ["hash-0", "hash-1", "hash-2"];
+
+        build_inclusion_proof(1, &leaves[..2])
+            .verify("hash-1", &signed_tree_head(&leaves))
+            .expect_err("A proof computed for another tree size should be rejected");
+    }
+
+    #[test]
+    fn reject_an_inclusion_proof_with_a_leaf_index_out_of_the_tree() {
+        let leaves = ["hash-0", "hash-1"];
+        let proof = TransparencyLogInclusionProof {
+            leaf_index: 2,
+            ..build_inclusion_proof(1, &leaves)
+        };
+
+        proof
+            .verify("hash-1", &signed_tree_head(&leaves))
+            .expect_err("A proof with a leaf index out of the tree should be rejected");
+    }
+
+    #[test]
+    fn verify_the_consistency_of_trees_of_various_sizes() {
+        for second_tree_size in 1..=HASHES.len() {
+            let leaves = &HASHES[..second_tree_size];
+            for first_tree_size in 1..=second_tree_size {
+                build_consistency_proof(first_tree_size, leaves)
+                    .verify(
+                        &signed_tree_head(&leaves[..first_tree_size]),
+                        &signed_tree_head(leaves),
+                    )
+                    .unwrap_or_else(|e| {
+                        panic!(
+                            "tree of size {first_tree_size} extended to {second_tree_size}: {e:?}"
+                        )
+                    });
+            }
+        }
+    }
+
+    #[test]
+    fn reject_the_consistency_of_a_tree_whose_previous_entries_were_modified() {
+        let forked_leaves = ["hash-0", "hash-x", "hash-2", "hash-3", "hash-4"];
+
+        for first_tree_size in 2..forked_leaves.len() {
+            build_consistency_proof(first_tree_size, &forked_leaves)
+                .verify(
+                    &signed_tree_head(&HASHES[..first_tree_size]),
+                    &signed_tree_head(&forked_leaves),
+                )
+                .expect_err("A tree modifying the previous entries should be rejected");
+        }
+    }
+
+    #[test]
+    fn reject_the_consistency_of_trees_of_the_same_size_with_different_roots() {
+        build_consistency_proof(3, &HASHES[..3])
+            .verify(
+                &signed_tree_head(&HASHES[..3]),
+                &signed_tree_head(&["hash-0", "hash-1", "hash-x"]),
+            )
+            .expect_err("Trees of the same size with different roots should be rejected");
+    }
+
+    #[test]
+    fn reject_a_consistency_proof_computed_for_other_tree_sizes() {
+        build_consistency_proof(2, &HASHES[..5])
+            .verify(
+                &signed_tree_head(&HASHES[..3]),
+                &signed_tree_head(&HASHES[..5]),
+            )
+            .expect_err("A proof computed for other tree sizes should be rejected");
+    }
+}
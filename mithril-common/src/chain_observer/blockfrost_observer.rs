@@ -0,0 +1,472 @@
+use anyhow::{anyhow, Context};
+use async_trait::async_trait;
+use pallas_primitives::ToCanonicalJson;
+use reqwest::{Client, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+use crate::{
+    chain_observer::{interface::*, ChainAddress, TxDatum},
+    crypto_helper::{KESPeriod, OpCert},
+    entities::{BlockNumber, ChainPoint, Epoch, SlotNumber, StakeDistribution},
+    CardanoNetwork, StdResult,
+};
+
+use super::model::{era_name_from_protocol_major_version, try_inspect, Datum, Datums};
+
+/// Maximum number of items per page of the Blockfrost API
+const BLOCKFROST_PAGE_SIZE: usize = 100;
+
+/// Latest epoch, as returned by Blockfrost
+#[derive(Debug, Deserialize)]
+struct BlockfrostEpoch {
+    epoch: u64,
+}
+
+/// Latest block, as returned by Blockfrost
+#[derive(Debug, Deserialize)]
+struct BlockfrostBlock {
+    hash: String,
+    height: Option<u64>,
+    slot: Option<u64>,
+}
+
+/// Protocol parameters of an epoch, as returned by Blockfrost
+#[derive(Debug, Deserialize)]
+struct BlockfrostProtocolParameters {
+    protocol_major_ver: u64,
+}
+
+/// Stake pool with its stakes, as returned by Blockfrost
+#[derive(Debug, Deserialize)]
+struct BlockfrostExtendedPool {
+    pool_id: String,
+    active_stake: String,
+}
+
+/// Output of a transaction, as returned by Blockfrost
+#[derive(Debug, Deserialize)]
+struct BlockfrostUtxo {
+    inline_datum: Option<String>,
+}
+
+/// Shelley genesis configuration, as returned by Blockfrost
+#[derive(Debug, Deserialize)]
+struct BlockfrostGenesis {
+    slots_per_kes_period: u64,
+}
+
+/// A chain observer that queries the REST API of [Blockfrost](https://blockfrost.io), to run a
+/// node without a local Cardano node.
+pub struct BlockfrostChainObserver {
+    endpoint: String,
+    project_id: String,
+    client: Client,
+}
+
+impl BlockfrostChainObserver {
+    /// Creates a new BlockfrostChainObserver querying the given API endpoint, ie:
+    /// `https://cardano-preprod.blockfrost.io/api/v0`, authenticated with the given project id.
+    pub fn new(endpoint: &str, project_id: &str) -> Self {
+        Self {
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            project_id: project_id.to_string(),
+            client: Client::new(),
+        }
+    }
+
+    /// Endpoint of the public Blockfrost API of a network, if Blockfrost hosts it
+    pub fn default_endpoint(network: &CardanoNetwork) -> Option<String> {
+        let network_name = match network {
+            CardanoNetwork::MainNet => "mainnet",
+            CardanoNetwork::TestNet(1) => "preprod",
+            CardanoNetwork::TestNet(2) => "preview",
+            CardanoNetwork::TestNet(4) => "sanchonet",
+            _ => return None,
+        };
+
+        Some(format!(
+            "https://cardano-{network_name}.blockfrost.io/api/v0"
+        ))
+    }
+
+    /// Sends a GET request to Blockfrost and returns its result, `None` if the resource does not
+    /// exist.
+    async fn query<T: DeserializeOwned>(&self, path: &str) -> StdResult<Option<T>> {
+        let url = format!("{}/{path}", self.endpoint);
+        let response = self
+            .client
+            .get(&url)
+            .header("project_id", &self.project_id)
+            .send()
+            .await
+            .with_context(|| format!("BlockfrostChainObserver failed to query '{url}'"))?;
+
+        match response.status() {
+            StatusCode::OK => {
+                let body = response.bytes().await.with_context(|| {
+                    format!("BlockfrostChainObserver failed to read '{url}' response")
+                })?;
+                let result = serde_json::from_slice(&body).with_context(|| {
+                    format!(
+                        "BlockfrostChainObserver failed to parse '{url}' response: '{}'",
+                        String::from_utf8_lossy(&body)
+                    )
+                })?;
+
+                Ok(Some(result))
+            }
+            StatusCode::NOT_FOUND => Ok(None),
+            status => Err(anyhow!(
+                "BlockfrostChainObserver query '{url}' failed with status {status}"
+            )),
+        }
+    }
+
+    /// Sends GET requests to Blockfrost for all the pages of a list
+    async fn query_all_pages<T: DeserializeOwned>(&self, path: &str) -> StdResult<Vec<T>> {
+        let mut items = vec![];
+        for page in 1.. {
+            let page_items: Vec<T> = self
+                .query(&format!("{path}?count={BLOCKFROST_PAGE_SIZE}&page={page}"))
+                .await?
+                .unwrap_or_default();
+            let is_last_page = page_items.len() < BLOCKFROST_PAGE_SIZE;
+            items.extend(page_items);
+
+            if is_last_page {
+                break;
+            }
+        }
+
+        Ok(items)
+    }
+
+    /// Serializes an inline datum, hex encoded CBOR, to a `TxDatum` instance.
+    fn serialize_datum(&self, datum: &str) -> StdResult<TxDatum> {
+        let datum = hex::decode(datum)
+            .with_context(|| "BlockfrostChainObserver failed to decode datum hex")?;
+        let datum = try_inspect::<Datum>(datum)?;
+        let serialized = serde_json::to_string(&datum.to_json())
+            .map_err(|err| anyhow!(err))
+            .with_context(|| "BlockfrostChainObserver failed to serialize datum")?;
+
+        Ok(TxDatum(serialized))
+    }
+}
+
+#[async_trait]
+impl ChainObserver for BlockfrostChainObserver {
+    async fn get_current_datums(
+        &self,
+        address: &ChainAddress,
+    ) -> Result<Datums, ChainObserverError> {
+        let utxos: Vec<BlockfrostUtxo> = self
+            .query_all_pages(&format!("addresses/{address}/utxos"))
+            .await?;
+
+        Ok(utxos
+            .iter()
+            .filter_map(|utxo| utxo.inline_datum.as_deref())
+            .map(|datum| self.serialize_datum(datum))
+            .collect::<StdResult<Datums>>()?)
+    }
+
+    async fn get_current_era(&self) -> Result<Option<String>, ChainObserverError> {
+        let protocol_parameters: Option<BlockfrostProtocolParameters> =
+            self.query("epochs/latest/parameters").await?;
+
+        Ok(protocol_parameters
+            .and_then(|parameters| {
+                era_name_from_protocol_major_version(parameters.protocol_major_ver)
+            })
+            .map(|era| era.to_string()))
+    }
+
+    async fn get_current_epoch(&self) -> Result<Option<Epoch>, ChainObserverError> {
+        let epoch: Option<BlockfrostEpoch> = self.query("epochs/latest").await?;
+
+        Ok(epoch.map(|epoch| Epoch(epoch.epoch)))
+    }
+
+    async fn get_current_chain_point(&self) -> Result<Option<ChainPoint>, ChainObserverError> {
+        let block: Option<BlockfrostBlock> = self.query("blocks/latest").await?;
+
+        Ok(block.and_then(|block| match (block.slot, block.height) {
+            (Some(slot), Some(height)) => Some(ChainPoint {
+                slot_number: SlotNumber(slot),
+                block_number: BlockNumber(height),
+                block_hash: block.hash,
+            }),
+            _ => None,
+        }))
+    }
+
+    async fn get_current_stake_distribution(
+        &self,
+    ) -> Result<Option<StakeDistribution>, ChainObserverError> {
+        let pools: Vec<BlockfrostExtendedPool> = self.query_all_pages("pools/extended").await?;
+        let mut stake_distribution = StakeDistribution::new();
+        for pool in pools {
+            let stake = pool
+                .active_stake
+                .parse::<u64>()
+                .map_err(|err| anyhow!(err))
+                .with_context(|| {
+                    format!(
+                        "BlockfrostChainObserver failed to parse the stake of pool '{}'",
+                        pool.pool_id
+                    )
+                })
+                .map_err(ChainObserverError::InvalidContent)?;
+            if stake > 0 {
+                stake_distribution.insert(pool.pool_id, stake);
+            }
+        }
+
+        Ok(Some(stake_distribution))
+    }
+
+    async fn get_current_kes_period(
+        &self,
+        _opcert: &OpCert,
+    ) -> Result<Option<KESPeriod>, ChainObserverError> {
+        let Some(genesis) = self.query::<BlockfrostGenesis>("genesis").await? else {
+            return Ok(None);
+        };
+        if genesis.slots_per_kes_period == 0 {
+            return Err(anyhow!("slots_per_kes_period must be greater than 0"))
+                .with_context(|| "BlockfrostChainObserver failed to calculate kes period")?;
+        }
+
+        let Some(chain_point) = self.get_current_chain_point().await? else {
+            return Ok(None);
+        };
+        let current_kes_period = *chain_point.slot_number / genesis.slots_per_kes_period;
+
+        Ok(Some(
+            u32::try_from(current_kes_period)
+                .map_err(|err| anyhow!(err))
+                .with_context(|| "BlockfrostChainObserver failed to convert kes period")?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use kes_summed_ed25519::{kes::Sum6Kes, traits::KesSk};
+    use serde_json::{json, Value};
+    use std::collections::BTreeMap;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    use crate::crypto_helper::ColdKeyGenerator;
+
+    use super::*;
+
+    const PROJECT_ID: &str = "preprod-project-id";
+
+    /// Start a fake Blockfrost server answering each request with the body associated to its
+    /// path (query included), or with a 404 if the path is unknown or the project id is wrong.
+    async fn start_fake_blockfrost(results: Vec<(&'static str, Value)>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let results: BTreeMap<String, Value> = results
+            .into_iter()
+            .map(|(path, body)| (format!("/api/v0/{path}"), body))
+            .collect();
+
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let results = results.clone();
+                tokio::spawn(async move {
+                    let mut buffer = vec![0u8; 8192];
+                    let read = stream.read(&mut buffer).await.unwrap();
+                    let request = String::from_utf8_lossy(&buffer[..read]).to_string();
+                    let path = request.split_whitespace().nth(1).unwrap_or_default();
+                    let is_authenticated = request
+                        .lines()
+                        .any(|line| line == format!("project_id: {PROJECT_ID}"));
+                    let (status, body) = match results.get(path) {
+                        Some(body) if is_authenticated => ("200 OK", body.to_string()),
+                        Some(_) => ("403 Forbidden", json!({"status_code": 403}).to_string()),
+                        None => ("404 Not Found", json!({"status_code": 404}).to_string()),
+                    };
+                    let response = format!(
+                        "HTTP/1.1 {status}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                        body.len()
+                    );
+                    stream.write_all(response.as_bytes()).await.unwrap();
+                });
+            }
+        });
+
+        format!("http://{address}/api/v0")
+    }
+
+    #[test]
+    fn default_endpoint_of_the_networks_hosted_by_blockfrost() {
+        assert_eq!(
+            Some("https://cardano-mainnet.blockfrost.io/api/v0".to_string()),
+            BlockfrostChainObserver::default_endpoint(&CardanoNetwork::MainNet)
+        );
+        assert_eq!(
+            Some("https://cardano-preprod.blockfrost.io/api/v0".to_string()),
+            BlockfrostChainObserver::default_endpoint(&CardanoNetwork::TestNet(1))
+        );
+        assert_eq!(
+            None,
+            BlockfrostChainObserver::default_endpoint(&CardanoNetwork::DevNet(42))
+        );
+    }
+
+    #[tokio::test]
+    async fn get_current_era() {
+        let endpoint = start_fake_blockfrost(vec![(
+            "epochs/latest/parameters",
+            json!({ "epoch": 8, "protocol_major_ver": 9 }),
+        )])
+        .await;
+        let observer = BlockfrostChainObserver::new(&endpoint, PROJECT_ID);
+
+        let era = observer.get_current_era().await.unwrap();
+
+        assert_eq!(Some("Conway".to_string()), era);
+    }
+
+    #[tokio::test]
+    async fn get_current_epoch() {
+        let endpoint = start_fake_blockfrost(vec![("epochs/latest", json!({ "epoch": 8 }))]).await;
+        let observer = BlockfrostChainObserver::new(&endpoint, PROJECT_ID);
+
+        let epoch = observer.get_current_epoch().await.unwrap();
+
+        assert_eq!(Some(Epoch(8)), epoch);
+    }
+
+    #[tokio::test]
+    async fn get_current_chain_point() {
+        let endpoint = start_fake_blockfrost(vec![(
+            "blocks/latest",
+            json!({ "hash": "6e8a3f3f", "height": 23, "slot": 52, "epoch": 8 }),
+        )])
+        .await;
+        let observer = BlockfrostChainObserver::new(&endpoint, PROJECT_ID);
+
+        let chain_point = observer.get_current_chain_point().await.unwrap();
+
+        assert_eq!(
+            Some(ChainPoint {
+                slot_number: SlotNumber(52),
+                block_number: BlockNumber(23),
+                block_hash: "6e8a3f3f".to_string(),
+            }),
+            chain_point
+        );
+    }
+
+    #[tokio::test]
+    async fn get_current_datums() {
+        let address = "addr_test1vr80076l3x5uw6n94nwhgmv7ssgy6muzf47ugn6z0l92rhg2mgtu0";
+        let endpoint = start_fake_blockfrost(vec![(
+            "addresses/addr_test1vr80076l3x5uw6n94nwhgmv7ssgy6muzf47ugn6z0l92rhg2mgtu0/utxos?count=100&page=1",
+            json!([
+                { "tx_hash": "aa", "output_index": 0, "inline_datum": "d879814101" },
+                { "tx_hash": "bb", "output_index": 1, "inline_datum": null }
+            ]),
+        )])
+        .await;
+        let observer = BlockfrostChainObserver::new(&endpoint, PROJECT_ID);
+
+        let datums = observer
+            .get_current_datums(&address.to_string())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            vec![TxDatum(
+                r#"{"constructor":0,"fields":[{"bytes":"01"}]}"#.to_string()
+            )],
+            datums
+        );
+    }
+
+    #[tokio::test]
+    async fn get_current_datums_of_an_address_without_utxo() {
+        let endpoint = start_fake_blockfrost(vec![]).await;
+        let observer = BlockfrostChainObserver::new(&endpoint, PROJECT_ID);
+
+        let datums = observer
+            .get_current_datums(&"addr_test_unknown".to_string())
+            .await
+            .unwrap();
+
+        assert_eq!(Vec::<TxDatum>::new(), datums);
+    }
+
+    #[tokio::test]
+    async fn get_current_stake_distribution_from_all_the_pages() {
+        let first_page = (0..BLOCKFROST_PAGE_SIZE)
+            .map(|i| json!({ "pool_id": format!("pool1a{i}"), "active_stake": "1000" }))
+            .collect::<Vec<_>>();
+        let endpoint = start_fake_blockfrost(vec![
+            ("pools/extended?count=100&page=1", json!(first_page)),
+            (
+                "pools/extended?count=100&page=2",
+                json!([
+                    { "pool_id": "pool1b", "active_stake": "0" },
+                    { "pool_id": "pool1c", "active_stake": "2000" }
+                ]),
+            ),
+        ])
+        .await;
+        let observer = BlockfrostChainObserver::new(&endpoint, PROJECT_ID);
+
+        let stake_distribution = observer
+            .get_current_stake_distribution()
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(BLOCKFROST_PAGE_SIZE + 1, stake_distribution.len());
+        assert_eq!(Some(&1000), stake_distribution.get("pool1a0"));
+        assert_eq!(Some(&2000), stake_distribution.get("pool1c"));
+        assert_eq!(None, stake_distribution.get("pool1b"));
+    }
+
+    #[tokio::test]
+    async fn get_current_kes_period() {
+        let endpoint = start_fake_blockfrost(vec![
+            ("genesis", json!({ "slots_per_kes_period": 10 })),
+            (
+                "blocks/latest",
+                json!({ "hash": "6e8a3f3f", "height": 23, "slot": 52 }),
+            ),
+        ])
+        .await;
+        let observer = BlockfrostChainObserver::new(&endpoint, PROJECT_ID);
+        let keypair = ColdKeyGenerator::create_deterministic_keypair([0u8; 32]);
+        let mut dummy_key_buffer = [0u8; Sum6Kes::SIZE + 4];
+        let mut dummy_seed = [0u8; 32];
+        let (_, kes_verification_key) = Sum6Kes::keygen(&mut dummy_key_buffer, &mut dummy_seed);
+        let operational_certificate = OpCert::new(kes_verification_key, 0, 0, keypair);
+
+        let kes_period = observer
+            .get_current_kes_period(&operational_certificate)
+            .await
+            .unwrap();
+
+        assert_eq!(Some(5), kes_period);
+    }
+
+    #[tokio::test]
+    async fn query_fails_with_a_wrong_project_id() {
+        let endpoint = start_fake_blockfrost(vec![("epochs/latest", json!({ "epoch": 8 }))]).await;
+        let observer = BlockfrostChainObserver::new(&endpoint, "wrong-project-id");
+
+        observer
+            .get_current_epoch()
+            .await
+            .expect_err("Query should fail when Blockfrost rejects the project id");
+    }
+}
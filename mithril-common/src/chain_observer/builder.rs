@@ -6,7 +6,10 @@ use crate::{chain_observer::ChainObserver, CardanoNetwork, StdResult};
 
 #[cfg(any(test, feature = "test_tools"))]
 use super::FakeObserver;
-use super::{CardanoCliChainObserver, CardanoCliRunner, PallasChainObserver};
+use super::{
+    BlockfrostChainObserver, CardanoCliChainObserver, CardanoCliRunner, DbSyncChainObserver,
+    OgmiosChainObserver, PallasChainObserver,
+};
 
 /// Type of chain observers available
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -17,6 +20,13 @@ pub enum ChainObserverType {
     CardanoCli,
     /// Pallas chain observer.
     Pallas,
+    /// Ogmios chain observer.
+    Ogmios,
+    /// Blockfrost chain observer.
+    Blockfrost,
+    /// Cardano db-sync chain observer.
+    #[serde(rename = "db-sync")]
+    DbSync,
     /// Fake chain observer.
     #[cfg(any(test, feature = "test_tools"))]
     Fake,
@@ -27,6 +37,9 @@ impl Display for ChainObserverType {
         match self {
             Self::CardanoCli => write!(f, "cardano-cli"),
             Self::Pallas => write!(f, "pallas"),
+            Self::Ogmios => write!(f, "ogmios"),
+            Self::Blockfrost => write!(f, "blockfrost"),
+            Self::DbSync => write!(f, "db-sync"),
             #[cfg(any(test, feature = "test_tools"))]
             Self::Fake => write!(f, "fake"),
         }
@@ -39,6 +52,24 @@ pub enum ChainObserverBuilderError {
     /// Missing cardano cli runner error.
     #[error("cardano cli runner is missing")]
     MissingCardanoCliRunner,
+
+    /// Missing Ogmios endpoint error.
+    #[error("ogmios endpoint is missing")]
+    MissingOgmiosEndpoint,
+
+    /// Missing Blockfrost project id error.
+    #[error("blockfrost project id is missing")]
+    MissingBlockfrostProjectId,
+
+    /// Missing Blockfrost endpoint error.
+    #[error(
+        "blockfrost endpoint is missing, it is required for networks not hosted by blockfrost"
+    )]
+    MissingBlockfrostEndpoint,
+
+    /// Missing db-sync connection string error.
+    #[error("db-sync connection string is missing")]
+    MissingDbSyncConnectionString,
 }
 
 /// Chain observer builder
@@ -47,6 +78,10 @@ pub struct ChainObserverBuilder {
     cardano_node_socket_path: PathBuf,
     cardano_network: CardanoNetwork,
     cardano_cli_runner: Option<Box<CardanoCliRunner>>,
+    ogmios_endpoint: Option<String>,
+    blockfrost_endpoint: Option<String>,
+    blockfrost_project_id: Option<String>,
+    db_sync_connection_string: Option<String>,
 }
 
 impl ChainObserverBuilder {
@@ -62,9 +97,42 @@ impl ChainObserverBuilder {
             cardano_node_socket_path: cardano_node_socket_path.to_owned(),
             cardano_network: cardano_node_network.to_owned(),
             cardano_cli_runner: cardano_cli_runner.map(|c| c.to_owned().into()),
+            ogmios_endpoint: None,
+            blockfrost_endpoint: None,
+            blockfrost_project_id: None,
+            db_sync_connection_string: None,
         }
     }
 
+    /// Set the WebSocket endpoint of Ogmios, required by the Ogmios chain observer
+    pub fn with_ogmios_endpoint(mut self, ogmios_endpoint: Option<String>) -> Self {
+        self.ogmios_endpoint = ogmios_endpoint;
+        self
+    }
+
+    /// Set the project id authenticating the requests to Blockfrost, required by the Blockfrost
+    /// chain observer
+    pub fn with_blockfrost_project_id(mut self, blockfrost_project_id: Option<String>) -> Self {
+        self.blockfrost_project_id = blockfrost_project_id;
+        self
+    }
+
+    /// Set the endpoint of the Blockfrost API, defaults to the public endpoint of the network
+    pub fn with_blockfrost_endpoint(mut self, blockfrost_endpoint: Option<String>) -> Self {
+        self.blockfrost_endpoint = blockfrost_endpoint;
+        self
+    }
+
+    /// Set the connection string of the cardano-db-sync PostgreSQL database, required by the
+    /// db-sync chain observer
+    pub fn with_db_sync_connection_string(
+        mut self,
+        db_sync_connection_string: Option<String>,
+    ) -> Self {
+        self.db_sync_connection_string = db_sync_connection_string;
+        self
+    }
+
     /// Create chain observer
     pub fn build(&self) -> StdResult<Arc<dyn ChainObserver>> {
         match self.chain_observer_type {
@@ -79,6 +147,31 @@ impl ChainObserverBuilder {
                     PallasChainObserver::new(&self.cardano_node_socket_path, self.cardano_network);
                 Ok(Arc::new(observer))
             }
+            ChainObserverType::Ogmios => Ok(Arc::new(OgmiosChainObserver::new(
+                self.ogmios_endpoint
+                    .as_ref()
+                    .ok_or(ChainObserverBuilderError::MissingOgmiosEndpoint)?,
+            ))),
+            ChainObserverType::Blockfrost => {
+                let endpoint = self
+                    .blockfrost_endpoint
+                    .clone()
+                    .or_else(|| BlockfrostChainObserver::default_endpoint(&self.cardano_network))
+                    .ok_or(ChainObserverBuilderError::MissingBlockfrostEndpoint)?;
+                let project_id = self
+                    .blockfrost_project_id
+                    .as_ref()
+                    .ok_or(ChainObserverBuilderError::MissingBlockfrostProjectId)?;
+
+                Ok(Arc::new(BlockfrostChainObserver::new(
+                    &endpoint, project_id,
+                )))
+            }
+            ChainObserverType::DbSync => Ok(Arc::new(DbSyncChainObserver::new(
+                self.db_sync_connection_string
+                    .as_ref()
+                    .ok_or(ChainObserverBuilderError::MissingDbSyncConnectionString)?,
+            )?)),
             #[cfg(any(test, feature = "test_tools"))]
             ChainObserverType::Fake => Ok(Arc::new(FakeObserver::default())),
         }
@@ -11,17 +11,25 @@ pub use model::{
 };
 
 cfg_fs_random! {
+    mod blockfrost_observer;
     mod builder;
     mod cli_observer;
+    mod dbsync_observer;
+    mod ogmios_observer;
     mod pallas_observer;
+    mod stake_distribution_source;
 
     #[cfg(test)]
     mod test_cli_runner;
 
+    pub use blockfrost_observer::BlockfrostChainObserver;
     pub use builder::{ChainObserverBuilder, ChainObserverType};
     pub use cli_observer::CliRunner;
     pub use cli_observer::{CardanoCliChainObserver, CardanoCliRunner};
+    pub use dbsync_observer::DbSyncChainObserver;
+    pub use ogmios_observer::OgmiosChainObserver;
     pub use pallas_observer::PallasChainObserver;
+    pub use stake_distribution_source::*;
 }
 
 cfg_test_tools! {
@@ -11,7 +11,11 @@ cfg_fs_random! {
     use serde::Deserialize;
     use anyhow::Context;
     use pallas_codec::minicbor::{Decode, Decoder, decode};
-    use pallas_primitives::{alonzo::PlutusData, ToCanonicalJson};
+    use pallas_codec::utils::Int;
+    use pallas_primitives::{
+        alonzo::{BigInt, BoundedBytes, Constr, PlutusData},
+        ToCanonicalJson,
+    };
 
     /// [Datum] represents an inline datum from UTxO.
     #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
@@ -45,6 +49,72 @@ cfg_fs_random! {
 
     /// [Datums] represents a list of [TxDatum].
     pub type Datums = Vec<TxDatum>;
+
+    /// Name of the era of a protocol major version, for the chain observers that can only
+    /// retrieve the protocol version of the ledger.
+    pub(super) fn era_name_from_protocol_major_version(
+        protocol_major_version: u64,
+    ) -> Option<&'static str> {
+        match protocol_major_version {
+            0 | 1 => Some("Byron"),
+            2 => Some("Shelley"),
+            3 => Some("Allegra"),
+            4 => Some("Mary"),
+            5 | 6 => Some("Alonzo"),
+            7 | 8 => Some("Babbage"),
+            9 | 10 => Some("Conway"),
+            _ => None,
+        }
+    }
+
+    impl TxDatum {
+        /// Encode the datum to CBOR, the format of the inline datums of the transaction outputs.
+        pub fn to_cbor(&self) -> StdResult<Vec<u8>> {
+            let tx_datum_raw = &self.0;
+            let v: HashMap<String, Value> = serde_json::from_str(tx_datum_raw).map_err(|e| {
+                TxDatumError::InvalidContent(anyhow!(e).context("tx datum was = '{tx_datum_raw}'"))
+            })?;
+            let constructor = v
+                .get("constructor")
+                .and_then(|constructor| constructor.as_u64())
+                .ok_or_else(|| anyhow!("Error: missing 'constructor' entry, tx datum was = '{tx_datum_raw}'"))?;
+            let fields = v
+                .get("fields")
+                .and_then(|fields| fields.as_array())
+                .ok_or_else(|| anyhow!("Error: missing 'fields' entry, tx datum was = '{tx_datum_raw}'"))?
+                .iter()
+                .map(|field| {
+                    if let Some(bytes) = field.get("bytes").and_then(|bytes| bytes.as_str()) {
+                        let bytes = hex::decode(bytes)
+                            .with_context(|| format!("Error: 'bytes' field is not hex encoded: '{bytes}'"))?;
+                        Ok(PlutusData::BoundedBytes(BoundedBytes::from(bytes)))
+                    } else if let Some(int) = field.get("int").and_then(|int| int.as_i64()) {
+                        Ok(PlutusData::BigInt(BigInt::Int(Int::from(int))))
+                    } else {
+                        Err(anyhow!("Error: unsupported field '{field}', tx datum was = '{tx_datum_raw}'"))
+                    }
+                })
+                .collect::<StdResult<Vec<_>>>()?;
+
+            // Constructors 0 to 6 are encoded with the CBOR tags 121 to 127, constructors 7 to
+            // 127 with the CBOR tags 1280 to 1400, and the others with the CBOR tag 102 followed
+            // by the constructor
+            let (tag, any_constructor) = match constructor {
+                0..=6 => (121 + constructor, None),
+                7..=127 => (1280 + constructor - 7, None),
+                _ => (102, Some(constructor)),
+            };
+            let datum = PlutusData::Constr(Constr {
+                tag,
+                any_constructor,
+                fields,
+            });
+
+            pallas_codec::minicbor::to_vec(&datum)
+                .map_err(|e| anyhow!(e))
+                .with_context(|| "failed to encode datum to CBOR")
+        }
+    }
 }
 
 /// [ChainAddress] represents an on chain address.
@@ -272,4 +342,55 @@ mod test {
             .get_nth_field_by_type(&TxDatumFieldTypeName::Int, 100)
             .expect_err("should have returned an error");
     }
+
+    cfg_fs_random! {
+        #[test]
+        fn test_tx_datum_to_cbor_match_the_inline_datum_of_the_transaction_output() {
+            let tx_datum = TxDatum(r#"{"constructor":0,"fields":[{"bytes":"7b226d61726b657273223a5b7b226e616d65223a227468616c6573222c2265706f6368223a307d5d2c227369676e6174757265223a2238356632326562626164"},{"bytes":"33333537633865613264663036323039376639613138306464333564396633626131643236383263373263386431323238386661643863623864306365656562"},{"bytes":"366134643665383465653865353631376164323037313836366363313930373466326137366538373864663166393733346438343061227d"}]}"#.to_string());
+            let expected_datum = "D8799F58407B226D61726B657273223A5B7B226E616D65223A227468616C6573222C2265706F6368223A307D5D2C227369676E6174757265223A22383566323265626261645840333335376338656132646630363230393766396131383064643335643966336261316432363832633732633864313232383866616438636238643063656565625838366134643665383465653865353631376164323037313836366363313930373466326137366538373864663166393733346438343061227DFF";
+
+            let cbor = tx_datum.to_cbor().unwrap();
+
+            assert_eq!(
+                try_inspect::<Datum>(hex::decode(expected_datum).unwrap()).unwrap(),
+                try_inspect::<Datum>(cbor).unwrap()
+            );
+        }
+
+        #[test]
+        fn test_tx_datum_to_cbor_encode_the_constructor_with_its_cbor_tag() {
+            for (constructor, expected_tag, expected_any_constructor) in [
+                (0, 121, None),
+                (6, 127, None),
+                (7, 1280, None),
+                (127, 1400, None),
+                (128, 102, Some(128)),
+                (1000, 102, Some(1000)),
+            ] {
+                let tx_datum = TxDatum(format!(
+                    r#"{{"constructor":{constructor},"fields":[{{"int":1}}]}}"#
+                ));
+
+                let datum = try_inspect::<Datum>(tx_datum.to_cbor().unwrap()).unwrap();
+
+                match datum.0 {
+                    PlutusData::Constr(constr) => {
+                        assert_eq!(
+                            (expected_tag, expected_any_constructor),
+                            (constr.tag, constr.any_constructor),
+                            "unexpected tag for constructor {constructor}"
+                        );
+                    }
+                    other => panic!("expected a constructor, got {other:?}"),
+                }
+            }
+        }
+
+        #[test]
+        fn test_tx_datum_to_cbor_fails_with_non_hex_bytes() {
+            let tx_datum = TxDatum(r#"{"constructor":0,"fields":[{"bytes":"not-hex"}]}"#.to_string());
+
+            tx_datum.to_cbor().expect_err("to_cbor should have failed");
+        }
+    }
 }
@@ -0,0 +1,494 @@
+use anyhow::{anyhow, Context};
+use async_trait::async_trait;
+use futures_util::{SinkExt, StreamExt};
+use pallas_primitives::ToCanonicalJson;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+use crate::{
+    chain_observer::{interface::*, ChainAddress, TxDatum},
+    crypto_helper::{KESPeriod, OpCert},
+    entities::{BlockNumber, ChainPoint, Epoch, SlotNumber, StakeDistribution},
+    StdResult,
+};
+
+use super::model::{try_inspect, Datum, Datums};
+
+/// Response of the Ogmios JSON-RPC interface
+#[derive(Debug, Deserialize)]
+struct OgmiosResponse<T> {
+    result: Option<T>,
+    error: Option<OgmiosResponseError>,
+}
+
+/// Error returned by the Ogmios JSON-RPC interface
+#[derive(Debug, Deserialize)]
+struct OgmiosResponseError {
+    code: i64,
+    message: String,
+}
+
+/// Point of the chain, as returned by Ogmios when the tip is not the origin
+#[derive(Debug, Deserialize)]
+struct OgmiosPoint {
+    slot: u64,
+    id: String,
+}
+
+/// Stake pool, as returned by Ogmios when the stake is included
+#[derive(Debug, Deserialize)]
+struct OgmiosStakePool {
+    stake: OgmiosValue,
+}
+
+/// Value in ada, as returned by Ogmios
+#[derive(Debug, Deserialize)]
+struct OgmiosValue {
+    ada: OgmiosLovelace,
+}
+
+/// Amount of lovelace, as returned by Ogmios
+#[derive(Debug, Deserialize)]
+struct OgmiosLovelace {
+    lovelace: u64,
+}
+
+/// Output of a transaction, as returned by Ogmios
+#[derive(Debug, Deserialize)]
+struct OgmiosUtxo {
+    datum: Option<String>,
+}
+
+/// Shelley genesis configuration, as returned by Ogmios
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OgmiosShelleyGenesisConfiguration {
+    slots_per_kes_period: u64,
+}
+
+/// Maximum duration of a request to Ogmios, from the connection to the reception of its response.
+const QUERY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A chain observer that queries the ledger state of a Cardano node through the WebSocket
+/// JSON-RPC interface of [Ogmios](https://ogmios.dev).
+pub struct OgmiosChainObserver {
+    endpoint: String,
+    timeout: Duration,
+}
+
+impl OgmiosChainObserver {
+    /// Creates a new OgmiosChainObserver, connecting to the given WebSocket endpoint, ie: `ws://localhost:1337`
+    pub fn new(endpoint: &str) -> Self {
+        Self {
+            endpoint: endpoint.to_string(),
+            timeout: QUERY_TIMEOUT,
+        }
+    }
+
+    /// Sends a JSON-RPC request to Ogmios and returns its result.
+    async fn query<T: DeserializeOwned>(&self, method: &str, params: Value) -> StdResult<T> {
+        tokio::time::timeout(self.timeout, self.query_without_timeout(method, params))
+            .await
+            .map_err(|_| {
+                anyhow!(
+                    "OgmiosChainObserver '{method}' request timed out after {:?}",
+                    self.timeout
+                )
+            })?
+    }
+
+    async fn query_without_timeout<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: Value,
+    ) -> StdResult<T> {
+        let (mut websocket, _) = connect_async(&self.endpoint).await.with_context(|| {
+            format!(
+                "OgmiosChainObserver failed to connect to '{}'",
+                self.endpoint
+            )
+        })?;
+        let request = json!({
+            "jsonrpc": "2.0",
+            "method": method,
+            "params": params,
+            "id": method,
+        });
+        websocket
+            .send(Message::Text(request.to_string()))
+            .await
+            .with_context(|| format!("OgmiosChainObserver failed to send '{method}' request"))?;
+
+        while let Some(message) = websocket.next().await {
+            let message = message.with_context(|| {
+                format!("OgmiosChainObserver failed to receive '{method}' response")
+            })?;
+            let text = match message {
+                Message::Text(text) => text,
+                Message::Close(_) => break,
+                _ => continue,
+            };
+            let _ = websocket.close(None).await;
+
+            let response: OgmiosResponse<T> = serde_json::from_str(&text).with_context(|| {
+                format!("OgmiosChainObserver failed to parse '{method}' response: '{text}'")
+            })?;
+
+            return match (response.result, response.error) {
+                (_, Some(error)) => Err(anyhow!(
+                    "OgmiosChainObserver '{method}' request failed with code {}: {}",
+                    error.code,
+                    error.message
+                )),
+                (Some(result), None) => Ok(result),
+                (None, None) => Err(anyhow!(
+                    "OgmiosChainObserver '{method}' response has no result"
+                )),
+            };
+        }
+
+        Err(anyhow!(
+            "OgmiosChainObserver connection closed before receiving the '{method}' response"
+        ))
+    }
+
+    /// Serializes an inline datum, hex encoded CBOR, to a `TxDatum` instance.
+    fn serialize_datum(&self, datum: &str) -> StdResult<TxDatum> {
+        let datum =
+            hex::decode(datum).with_context(|| "OgmiosChainObserver failed to decode datum hex")?;
+        let datum = try_inspect::<Datum>(datum)?;
+        let serialized = serde_json::to_string(&datum.to_json())
+            .map_err(|err| anyhow!(err))
+            .with_context(|| "OgmiosChainObserver failed to serialize datum")?;
+
+        Ok(TxDatum(serialized))
+    }
+}
+
+#[async_trait]
+impl ChainObserver for OgmiosChainObserver {
+    async fn get_current_datums(
+        &self,
+        address: &ChainAddress,
+    ) -> Result<Datums, ChainObserverError> {
+        let utxos: Vec<OgmiosUtxo> = self
+            .query("queryLedgerState/utxo", json!({ "addresses": [address] }))
+            .await?;
+
+        Ok(utxos
+            .iter()
+            .filter_map(|utxo| utxo.datum.as_deref())
+            .map(|datum| self.serialize_datum(datum))
+            .collect::<StdResult<Datums>>()?)
+    }
+
+    async fn get_current_era(&self) -> Result<Option<String>, ChainObserverError> {
+        let era: String = self.query("queryLedgerState/era", json!({})).await?;
+        let mut chars = era.chars();
+
+        Ok(chars
+            .next()
+            .map(|first| first.to_uppercase().chain(chars).collect()))
+    }
+
+    async fn get_current_epoch(&self) -> Result<Option<Epoch>, ChainObserverError> {
+        let epoch: u64 = self.query("queryLedgerState/epoch", json!({})).await?;
+
+        Ok(Some(Epoch(epoch)))
+    }
+
+    async fn get_current_chain_point(&self) -> Result<Option<ChainPoint>, ChainObserverError> {
+        let tip: Value = self.query("queryLedgerState/tip", json!({})).await?;
+        let block_height: Value = self.query("queryNetwork/blockHeight", json!({})).await?;
+
+        // Both queries return "origin" when the chain is empty
+        match (
+            serde_json::from_value::<OgmiosPoint>(tip),
+            block_height.as_u64(),
+        ) {
+            (Ok(point), Some(block_height)) => Ok(Some(ChainPoint {
+                slot_number: SlotNumber(point.slot),
+                block_number: BlockNumber(block_height),
+                block_hash: point.id,
+            })),
+            _ => Ok(None),
+        }
+    }
+
+    async fn get_current_stake_distribution(
+        &self,
+    ) -> Result<Option<StakeDistribution>, ChainObserverError> {
+        let stake_pools: BTreeMap<String, OgmiosStakePool> = self
+            .query(
+                "queryLedgerState/stakePools",
+                json!({ "includeStake": true }),
+            )
+            .await?;
+
+        Ok(Some(
+            stake_pools
+                .into_iter()
+                .filter(|(_, pool)| pool.stake.ada.lovelace > 0)
+                .map(|(pool_id, pool)| (pool_id, pool.stake.ada.lovelace))
+                .collect(),
+        ))
+    }
+
+    async fn get_current_kes_period(
+        &self,
+        _opcert: &OpCert,
+    ) -> Result<Option<KESPeriod>, ChainObserverError> {
+        let genesis_configuration: OgmiosShelleyGenesisConfiguration = self
+            .query(
+                "queryNetwork/genesisConfiguration",
+                json!({ "era": "shelley" }),
+            )
+            .await?;
+        if genesis_configuration.slots_per_kes_period == 0 {
+            return Err(anyhow!("slots_per_kes_period must be greater than 0"))
+                .with_context(|| "OgmiosChainObserver failed to calculate kes period")?;
+        }
+
+        let Some(chain_point) = self.get_current_chain_point().await? else {
+            return Ok(None);
+        };
+        let current_kes_period =
+            *chain_point.slot_number / genesis_configuration.slots_per_kes_period;
+
+        Ok(Some(
+            u32::try_from(current_kes_period)
+                .map_err(|err| anyhow!(err))
+                .with_context(|| "OgmiosChainObserver failed to convert kes period")?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use kes_summed_ed25519::{kes::Sum6Kes, traits::KesSk};
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::accept_async;
+
+    use crate::crypto_helper::ColdKeyGenerator;
+
+    use super::*;
+
+    /// Start a fake Ogmios server answering each request with the result associated to its method.
+    async fn start_fake_ogmios(results: Vec<(&'static str, Value)>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let results: BTreeMap<&'static str, Value> = results.into_iter().collect();
+
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let results = results.clone();
+                tokio::spawn(async move {
+                    let mut websocket = accept_async(stream).await.unwrap();
+                    while let Some(Ok(Message::Text(text))) = websocket.next().await {
+                        let request: Value = serde_json::from_str(&text).unwrap();
+                        let method = request["method"].as_str().unwrap();
+                        let response = match results.get(method) {
+                            Some(result) => {
+                                json!({ "jsonrpc": "2.0", "method": method, "result": result })
+                            }
+                            None => json!({
+                                "jsonrpc": "2.0",
+                                "method": method,
+                                "error": { "code": -32601, "message": "Unknown method" }
+                            }),
+                        };
+                        websocket
+                            .send(Message::Text(response.to_string()))
+                            .await
+                            .unwrap();
+                    }
+                });
+            }
+        });
+
+        format!("ws://{address}")
+    }
+
+    #[tokio::test]
+    async fn get_current_era() {
+        let endpoint = start_fake_ogmios(vec![("queryLedgerState/era", json!("conway"))]).await;
+        let observer = OgmiosChainObserver::new(&endpoint);
+
+        let era = observer.get_current_era().await.unwrap();
+
+        assert_eq!(Some("Conway".to_string()), era);
+    }
+
+    #[tokio::test]
+    async fn get_current_epoch() {
+        let endpoint = start_fake_ogmios(vec![("queryLedgerState/epoch", json!(8))]).await;
+        let observer = OgmiosChainObserver::new(&endpoint);
+
+        let epoch = observer.get_current_epoch().await.unwrap();
+
+        assert_eq!(Some(Epoch(8)), epoch);
+    }
+
+    #[tokio::test]
+    async fn get_current_chain_point() {
+        let endpoint = start_fake_ogmios(vec![
+            (
+                "queryLedgerState/tip",
+                json!({ "slot": 52, "id": "6e8a3f3f" }),
+            ),
+            ("queryNetwork/blockHeight", json!(23)),
+        ])
+        .await;
+        let observer = OgmiosChainObserver::new(&endpoint);
+
+        let chain_point = observer.get_current_chain_point().await.unwrap();
+
+        assert_eq!(
+            Some(ChainPoint {
+                slot_number: SlotNumber(52),
+                block_number: BlockNumber(23),
+                block_hash: "6e8a3f3f".to_string(),
+            }),
+            chain_point
+        );
+    }
+
+    #[tokio::test]
+    async fn get_current_chain_point_at_origin() {
+        let endpoint = start_fake_ogmios(vec![
+            ("queryLedgerState/tip", json!("origin")),
+            ("queryNetwork/blockHeight", json!("origin")),
+        ])
+        .await;
+        let observer = OgmiosChainObserver::new(&endpoint);
+
+        let chain_point = observer.get_current_chain_point().await.unwrap();
+
+        assert_eq!(None, chain_point);
+    }
+
+    #[tokio::test]
+    async fn get_current_datums() {
+        let endpoint = start_fake_ogmios(vec![(
+            "queryLedgerState/utxo",
+            json!([
+                { "transaction": { "id": "aa" }, "index": 0, "datum": "d879814101" },
+                { "transaction": { "id": "bb" }, "index": 1 }
+            ]),
+        )])
+        .await;
+        let observer = OgmiosChainObserver::new(&endpoint);
+
+        let datums = observer
+            .get_current_datums(
+                &"addr_test1vr80076l3x5uw6n94nwhgmv7ssgy6muzf47ugn6z0l92rhg2mgtu0".to_string(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            vec![TxDatum(
+                r#"{"constructor":0,"fields":[{"bytes":"01"}]}"#.to_string()
+            )],
+            datums
+        );
+    }
+
+    #[tokio::test]
+    async fn get_current_stake_distribution() {
+        let endpoint = start_fake_ogmios(vec![(
+            "queryLedgerState/stakePools",
+            json!({
+                "pool1a": { "id": "pool1a", "stake": { "ada": { "lovelace": 1000 } } },
+                "pool1b": { "id": "pool1b", "stake": { "ada": { "lovelace": 0 } } },
+                "pool1c": { "id": "pool1c", "stake": { "ada": { "lovelace": 2000 } } }
+            }),
+        )])
+        .await;
+        let observer = OgmiosChainObserver::new(&endpoint);
+
+        let stake_distribution = observer
+            .get_current_stake_distribution()
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            StakeDistribution::from([("pool1a".to_string(), 1000), ("pool1c".to_string(), 2000)]),
+            stake_distribution
+        );
+    }
+
+    #[tokio::test]
+    async fn get_current_kes_period() {
+        let endpoint = start_fake_ogmios(vec![
+            (
+                "queryNetwork/genesisConfiguration",
+                json!({ "era": "shelley", "slotsPerKesPeriod": 10 }),
+            ),
+            (
+                "queryLedgerState/tip",
+                json!({ "slot": 52, "id": "6e8a3f3f" }),
+            ),
+            ("queryNetwork/blockHeight", json!(23)),
+        ])
+        .await;
+        let observer = OgmiosChainObserver::new(&endpoint);
+        let keypair = ColdKeyGenerator::create_deterministic_keypair([0u8; 32]);
+        let mut dummy_key_buffer = [0u8; Sum6Kes::SIZE + 4];
+        let mut dummy_seed = [0u8; 32];
+        let (_, kes_verification_key) = Sum6Kes::keygen(&mut dummy_key_buffer, &mut dummy_seed);
+        let operational_certificate = OpCert::new(kes_verification_key, 0, 0, keypair);
+
+        let kes_period = observer
+            .get_current_kes_period(&operational_certificate)
+            .await
+            .unwrap();
+
+        assert_eq!(Some(5), kes_period);
+    }
+
+    #[tokio::test]
+    async fn query_fails_when_ogmios_returns_an_error() {
+        let endpoint = start_fake_ogmios(vec![]).await;
+        let observer = OgmiosChainObserver::new(&endpoint);
+
+        observer
+            .get_current_epoch()
+            .await
+            .expect_err("Query should fail when Ogmios returns an error");
+    }
+
+    #[tokio::test]
+    async fn query_fails_when_ogmios_does_not_answer_in_time() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut websocket = accept_async(stream).await.unwrap();
+                    // Read the requests without ever answering them
+                    while let Some(Ok(_)) = websocket.next().await {}
+                });
+            }
+        });
+        let observer = OgmiosChainObserver {
+            endpoint: format!("ws://{address}"),
+            timeout: Duration::from_millis(50),
+        };
+
+        let error = observer
+            .get_current_epoch()
+            .await
+            .expect_err("Query should fail when Ogmios does not answer in time");
+
+        assert!(
+            format!("{error:?}").contains("timed out"),
+            "unexpected error: {error:?}"
+        );
+    }
+}
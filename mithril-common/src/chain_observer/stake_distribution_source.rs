@@ -0,0 +1,391 @@
+//! Provide the weighted stake distribution used by the aggregator and the signers. It is read from
+//! the Cardano chain by default, but sidechains and permissioned networks can define their own
+//! weights in a JSON file or serve them with an HTTP API, as an object mapping each party id to its
+//! stake: `{"pool1...": 1000, "pool1...": 2500}`.
+//!
+//! The aggregator and its signers must be configured with the same source, otherwise the
+//! signatures computed by the signers can't be aggregated.
+
+use anyhow::{anyhow, Context};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+
+use crate::chain_observer::ChainObserver;
+use crate::entities::StakeDistribution;
+use crate::StdResult;
+
+/// Type of the source of the stake distribution used by the multi-signer.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StakeDistributionSourceType {
+    /// Stake distribution of the Cardano chain, read with the chain observer.
+    ChainObserver,
+    /// Stake distribution read from a JSON file.
+    File,
+    /// Stake distribution fetched from an HTTP API.
+    Http,
+}
+
+impl Display for StakeDistributionSourceType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ChainObserver => write!(f, "chain_observer"),
+            Self::File => write!(f, "file"),
+            Self::Http => write!(f, "http"),
+        }
+    }
+}
+
+/// Provide the current stake distribution.
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait StakeDistributionSource: Sync + Send {
+    /// Retrieve the current stake distribution.
+    async fn retrieve(&self) -> StdResult<StakeDistribution>;
+}
+
+/// Reject the stake distributions that can't be used by the multi-signer.
+fn check_stake_distribution(stake_distribution: &StakeDistribution) -> StdResult<()> {
+    if stake_distribution.is_empty() {
+        return Err(anyhow!("The stake distribution is empty"));
+    }
+    if let Some(party_id) = stake_distribution
+        .iter()
+        .find_map(|(party_id, stake)| (*stake == 0).then_some(party_id))
+    {
+        return Err(anyhow!(
+            "The stake of the party '{party_id}' must be greater than zero"
+        ));
+    }
+
+    Ok(())
+}
+
+/// [StakeDistributionSource] reading the stake distribution of the Cardano chain.
+pub struct ChainObserverStakeDistributionSource {
+    chain_observer: Arc<dyn ChainObserver>,
+}
+
+impl ChainObserverStakeDistributionSource {
+    /// Create a new [ChainObserverStakeDistributionSource].
+    pub fn new(chain_observer: Arc<dyn ChainObserver>) -> Self {
+        Self { chain_observer }
+    }
+}
+
+#[async_trait]
+impl StakeDistributionSource for ChainObserverStakeDistributionSource {
+    async fn retrieve(&self) -> StdResult<StakeDistribution> {
+        self.chain_observer
+            .get_current_stake_distribution()
+            .await
+            .with_context(|| "Chain observer could not get the current stake distribution")?
+            .ok_or_else(|| anyhow!("Chain observer returned no stake distribution"))
+    }
+}
+
+/// [StakeDistributionSource] reading the stake distribution from a JSON file, read again at
+/// each epoch so the weights can be updated without restarting the node.
+pub struct FileStakeDistributionSource {
+    path: PathBuf,
+}
+
+impl FileStakeDistributionSource {
+    /// Create a new [FileStakeDistributionSource].
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+}
+
+#[async_trait]
+impl StakeDistributionSource for FileStakeDistributionSource {
+    async fn retrieve(&self) -> StdResult<StakeDistribution> {
+        let content = tokio::fs::read_to_string(&self.path)
+            .await
+            .with_context(|| {
+                format!(
+                    "Could not read the stake distribution file: '{}'",
+                    self.path.display()
+                )
+            })?;
+        let stake_distribution: StakeDistribution =
+            serde_json::from_str(&content).with_context(|| {
+                format!("Invalid stake distribution file: '{}'", self.path.display())
+            })?;
+        check_stake_distribution(&stake_distribution).with_context(|| {
+            format!("Invalid stake distribution file: '{}'", self.path.display())
+        })?;
+
+        Ok(stake_distribution)
+    }
+}
+
+/// [StakeDistributionSource] fetching the stake distribution with a `GET` on an HTTP API.
+pub struct HttpStakeDistributionSource {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl HttpStakeDistributionSource {
+    /// Create a new [HttpStakeDistributionSource].
+    pub fn new(url: &str) -> StdResult<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .with_context(|| "Http Client build failed")?;
+
+        Ok(Self {
+            url: url.to_string(),
+            client,
+        })
+    }
+}
+
+#[async_trait]
+impl StakeDistributionSource for HttpStakeDistributionSource {
+    async fn retrieve(&self) -> StdResult<StakeDistribution> {
+        let content = self
+            .client
+            .get(&self.url)
+            .send()
+            .await
+            .with_context(|| format!("Stake distribution API call failed: '{}'", self.url))?
+            .error_for_status()
+            .with_context(|| format!("Stake distribution API returned an error: '{}'", self.url))?
+            .text()
+            .await
+            .with_context(|| format!("Stake distribution API call failed: '{}'", self.url))?;
+        let stake_distribution: StakeDistribution = serde_json::from_str(&content)
+            .with_context(|| format!("Invalid stake distribution from API: '{}'", self.url))?;
+        check_stake_distribution(&stake_distribution)
+            .with_context(|| format!("Invalid stake distribution from API: '{}'", self.url))?;
+
+        Ok(stake_distribution)
+    }
+}
+
+/// Error type for the stake distribution source builder.
+#[derive(Error, Debug)]
+pub enum StakeDistributionSourceBuilderError {
+    /// Missing location of a `file` or `http` source.
+    #[error("the location of the '{0}' stake distribution source is missing")]
+    MissingLocation(StakeDistributionSourceType),
+
+    /// Location given to the `chain_observer` source, which does not use it.
+    #[error("the 'chain_observer' stake distribution source does not have a location, got: '{0}'")]
+    UnexpectedLocation(String),
+}
+
+/// Stake distribution source builder
+pub struct StakeDistributionSourceBuilder {
+    source_type: StakeDistributionSourceType,
+    location: Option<String>,
+    chain_observer: Arc<dyn ChainObserver>,
+}
+
+impl StakeDistributionSourceBuilder {
+    /// Stake distribution source builder factory
+    pub fn new(
+        source_type: StakeDistributionSourceType,
+        location: Option<String>,
+        chain_observer: Arc<dyn ChainObserver>,
+    ) -> Self {
+        Self {
+            source_type,
+            location,
+            chain_observer,
+        }
+    }
+
+    /// Create the stake distribution source, the location is required by the `file` and `http`
+    /// sources and rejected by the `chain_observer` one so that a misconfiguration is not silently
+    /// ignored.
+    pub fn build(&self) -> StdResult<Arc<dyn StakeDistributionSource>> {
+        let location = || {
+            self.location
+                .clone()
+                .ok_or(StakeDistributionSourceBuilderError::MissingLocation(
+                    self.source_type,
+                ))
+        };
+
+        match self.source_type {
+            StakeDistributionSourceType::ChainObserver => match &self.location {
+                Some(location) => Err(StakeDistributionSourceBuilderError::UnexpectedLocation(
+                    location.clone(),
+                )
+                .into()),
+                None => Ok(Arc::new(ChainObserverStakeDistributionSource::new(
+                    self.chain_observer.clone(),
+                ))),
+            },
+            StakeDistributionSourceType::File => Ok(Arc::new(FileStakeDistributionSource::new(
+                location()?.into(),
+            ))),
+            StakeDistributionSourceType::Http => {
+                Ok(Arc::new(HttpStakeDistributionSource::new(&location()?)?))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    use crate::chain_observer::FakeObserver;
+    use crate::test_utils::TempDir;
+
+    use super::*;
+
+    fn stake_distribution(stakes: &[(&str, u64)]) -> StakeDistribution {
+        stakes
+            .iter()
+            .map(|(party_id, stake)| (party_id.to_string(), *stake))
+            .collect()
+    }
+
+    /// Start a fake stake distribution API answering each request with the given status and body
+    async fn start_fake_api(status: &'static str, body: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buffer = vec![0u8; 8192];
+                    let _ = stream.read(&mut buffer).await.unwrap();
+                    let response = format!(
+                        "HTTP/1.1 {status}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                        body.len()
+                    );
+                    stream.write_all(response.as_bytes()).await.unwrap();
+                });
+            }
+        });
+
+        format!("http://{address}/stakes")
+    }
+
+    fn build_source(
+        source_type: StakeDistributionSourceType,
+        location: Option<&str>,
+    ) -> StdResult<Arc<dyn StakeDistributionSource>> {
+        StakeDistributionSourceBuilder::new(
+            source_type,
+            location.map(|l| l.to_string()),
+            Arc::new(FakeObserver::default()),
+        )
+        .build()
+    }
+
+    #[test]
+    fn check_stake_distribution_rejects_empty_or_zero_stakes() {
+        check_stake_distribution(&stake_distribution(&[("pool1", 10), ("pool2", 20)])).unwrap();
+        check_stake_distribution(&StakeDistribution::new())
+            .expect_err("An empty stake distribution should be rejected");
+        check_stake_distribution(&stake_distribution(&[("pool1", 10), ("pool2", 0)]))
+            .expect_err("A zero stake should be rejected");
+    }
+
+    #[test]
+    fn builder_requires_a_location_for_the_file_and_http_sources() {
+        assert!(
+            build_source(StakeDistributionSourceType::File, None).is_err(),
+            "A file source without location should be rejected"
+        );
+        assert!(
+            build_source(StakeDistributionSourceType::Http, None).is_err(),
+            "An http source without location should be rejected"
+        );
+
+        build_source(StakeDistributionSourceType::File, Some("/stakes.json")).unwrap();
+        build_source(
+            StakeDistributionSourceType::Http,
+            Some("http://127.0.0.1/stakes"),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn builder_rejects_a_location_for_the_chain_observer_source() {
+        build_source(StakeDistributionSourceType::ChainObserver, None).unwrap();
+        let Err(error) = build_source(
+            StakeDistributionSourceType::ChainObserver,
+            Some("/stakes.json"),
+        ) else {
+            panic!("A chain observer source with a location should be rejected");
+        };
+
+        assert!(
+            matches!(
+                error.downcast_ref::<StakeDistributionSourceBuilderError>(),
+                Some(StakeDistributionSourceBuilderError::UnexpectedLocation(_))
+            ),
+            "unexpected error: {error:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn file_source_reads_the_stake_distribution() {
+        let path = TempDir::create(
+            "stake_distribution_source",
+            "file_source_reads_the_stake_distribution",
+        )
+        .join("stake_distribution.json");
+        std::fs::write(&path, r#"{"pool1": 1000, "pool2": 2500}"#).unwrap();
+        let source = FileStakeDistributionSource::new(path);
+
+        let stake_distribution_retrieved = source.retrieve().await.unwrap();
+
+        assert_eq!(
+            stake_distribution(&[("pool1", 1000), ("pool2", 2500)]),
+            stake_distribution_retrieved
+        );
+    }
+
+    #[tokio::test]
+    async fn file_source_fails_if_the_file_is_missing() {
+        let path = TempDir::create(
+            "stake_distribution_source",
+            "file_source_fails_if_the_file_is_missing",
+        )
+        .join("missing.json");
+        let source = FileStakeDistributionSource::new(path);
+
+        source
+            .retrieve()
+            .await
+            .expect_err("Reading a missing file should fail");
+    }
+
+    #[tokio::test]
+    async fn http_source_fetches_the_stake_distribution() {
+        let url = start_fake_api("200 OK", r#"{"pool1": 1000, "pool2": 2500}"#).await;
+        let source = HttpStakeDistributionSource::new(&url).unwrap();
+
+        let stake_distribution_retrieved = source.retrieve().await.unwrap();
+
+        assert_eq!(
+            stake_distribution(&[("pool1", 1000), ("pool2", 2500)]),
+            stake_distribution_retrieved
+        );
+    }
+
+    #[tokio::test]
+    async fn http_source_fails_on_an_error_status() {
+        let url = start_fake_api("500 Internal Server Error", "{}").await;
+        let source = HttpStakeDistributionSource::new(&url).unwrap();
+
+        source
+            .retrieve()
+            .await
+            .expect_err("An error status should fail");
+    }
+}
@@ -6,7 +6,6 @@ use pallas_network::{
     facades::NodeClient,
     miniprotocols::chainsync::{BlockContent, NextResponse},
 };
-use pallas_traverse::MultiEraBlock;
 use slog::{debug, Logger};
 
 use crate::logging::LoggerExtensions;
@@ -65,9 +64,17 @@ impl PallasChainReader {
 
         if chainsync.has_agency() {
             debug!(logger, "Has agency, finding intersect point..."; "point" => ?point);
-            chainsync
+            let (intersection, _tip) = chainsync
                 .find_intersect(vec![point.to_owned().into()])
                 .await?;
+
+            // Without a known intersection the node would stream from the origin of the chain,
+            // this happens when the given point belongs to a fork that was rolled back.
+            if intersection.is_none() {
+                return Err(anyhow!(
+                    "Intersection point not found on the chain, it may have been rolled back: {point:?}"
+                ));
+            }
         } else {
             debug!(logger, "Doesn't have agency, no need to find intersect point";);
         }
@@ -82,9 +89,8 @@ impl PallasChainReader {
     ) -> StdResult<Option<ChainBlockNextAction>> {
         match next {
             NextResponse::RollForward(raw_block, _forward_tip) => {
-                let multi_era_block = MultiEraBlock::decode(&raw_block)
+                let parsed_block = ScannedBlock::decode(&raw_block)
                     .with_context(|| "PallasChainReader failed to decode raw block")?;
-                let parsed_block = ScannedBlock::convert(multi_era_block);
                 Ok(Some(ChainBlockNextAction::RollForward { parsed_block }))
             }
             NextResponse::RollBackward(rollback_point, _) => {
@@ -189,9 +195,8 @@ mod tests {
 
     fn get_fake_scanned_block() -> ScannedBlock {
         let raw_block = get_fake_raw_block();
-        let multi_era_block = MultiEraBlock::decode(&raw_block).unwrap();
 
-        ScannedBlock::convert(multi_era_block)
+        ScannedBlock::decode(&raw_block).unwrap()
     }
 
     /// Sets up a mock server for related tests.
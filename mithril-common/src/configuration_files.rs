@@ -0,0 +1,402 @@
+//! Layered configuration files of the Mithril nodes.
+//!
+//! The configuration of a node is read from an ordered chain of JSON files of its configuration
+//! directory, each file overriding the values of the previous ones:
+//! 1. `base.json`: values shared by all the run modes,
+//! 2. `{run_mode}.json`: values specific to the run mode (`dev`, `testnet`, ...),
+//! 3. `secrets.json`: secret values, that can be kept outside of the main configuration with
+//!    tighter permissions, another secrets file can be given in its place.
+//!
+//! All the files are optional. The string values can reference environment variables with the
+//! `${VARIABLE}` syntax, they are expanded when the files are read. A literal `${` is written
+//! `$${`.
+//!
+//! The secret values of a configuration should be read as [SecretString] so that they are not
+//! written in the logs of the configuration.
+
+use config::{ConfigError, FileFormat, Map, Source, Value, ValueKind};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Formatter};
+use std::path::{Path, PathBuf};
+
+/// Name of the configuration file shared by all the run modes.
+pub const BASE_CONFIGURATION_FILE: &str = "base.json";
+
+/// Name of the configuration file of the secret values.
+pub const SECRETS_CONFIGURATION_FILE: &str = "secrets.json";
+
+/// Secret string value of a configuration, redacted when formatted with [Debug].
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SecretString(String);
+
+impl SecretString {
+    /// Text written instead of the secret value when it is formatted with [Debug]
+    pub const REDACTED: &'static str = "[REDACTED]";
+
+    /// Get the secret value
+    pub fn expose_secret(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Debug for SecretString {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(Self::REDACTED)
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&str> for SecretString {
+    fn from(value: &str) -> Self {
+        Self(value.to_string())
+    }
+}
+
+/// Lookup of the value of an environment variable referenced in the configuration files.
+type VariableLookup = fn(&str) -> Option<String>;
+
+/// [Source] of the configuration read from the layered configuration files of a run mode.
+#[derive(Debug, Clone)]
+pub struct ConfigurationFiles {
+    directory: PathBuf,
+    run_mode: String,
+    secrets_file: Option<PathBuf>,
+    lookup_variable: VariableLookup,
+}
+
+impl ConfigurationFiles {
+    /// [ConfigurationFiles] factory
+    pub fn new<P: AsRef<Path>>(directory: P, run_mode: &str) -> Self {
+        Self {
+            directory: directory.as_ref().to_path_buf(),
+            run_mode: run_mode.to_string(),
+            secrets_file: None,
+            lookup_variable: |name| std::env::var(name).ok(),
+        }
+    }
+
+    /// Read the secret values from the given file instead of the `secrets.json` file of the
+    /// configuration directory, if any.
+    pub fn with_secrets_file<P: AsRef<Path>>(mut self, secrets_file: Option<P>) -> Self {
+        self.secrets_file = secrets_file.map(|path| path.as_ref().to_path_buf());
+        self
+    }
+
+    #[cfg(test)]
+    fn with_variable_lookup(mut self, lookup_variable: VariableLookup) -> Self {
+        self.lookup_variable = lookup_variable;
+        self
+    }
+
+    /// Paths of the configuration files, ordered from the lowest to the highest priority.
+    pub fn paths(&self) -> Vec<PathBuf> {
+        vec![
+            self.directory.join(BASE_CONFIGURATION_FILE),
+            self.directory.join(format!("{}.json", self.run_mode)),
+            self.secrets_file
+                .clone()
+                .unwrap_or_else(|| self.directory.join(SECRETS_CONFIGURATION_FILE)),
+        ]
+    }
+
+    fn read_file(&self, path: &Path) -> Result<Map<String, Value>, ConfigError> {
+        let mut values = config::File::from(path)
+            .format(FileFormat::Json)
+            .required(false)
+            .collect()?;
+        for value in values.values_mut() {
+            expand_environment_variables(value, self.lookup_variable).map_err(|error| {
+                ConfigError::Message(format!(
+                    "Invalid configuration file '{}': {error}",
+                    path.display()
+                ))
+            })?;
+        }
+
+        Ok(values)
+    }
+}
+
+impl Source for ConfigurationFiles {
+    fn clone_into_box(&self) -> Box<dyn Source + Send + Sync> {
+        Box::new(self.clone())
+    }
+
+    fn collect(&self) -> Result<Map<String, Value>, ConfigError> {
+        let mut result = Map::new();
+        for path in self.paths() {
+            merge(&mut result, self.read_file(&path)?);
+        }
+
+        Ok(result)
+    }
+}
+
+/// Merge the given values into the target, the nested tables are merged key by key.
+fn merge(target: &mut Map<String, Value>, values: Map<String, Value>) {
+    for (key, value) in values {
+        let kind = match (target.get_mut(&key), value.kind) {
+            (
+                Some(Value {
+                    kind: ValueKind::Table(target_table),
+                    ..
+                }),
+                ValueKind::Table(table),
+            ) => {
+                merge(target_table, table);
+                continue;
+            }
+            (_, kind) => kind,
+        };
+        target.insert(key, Value::new(None, kind));
+    }
+}
+
+fn expand_environment_variables(
+    value: &mut Value,
+    lookup_variable: VariableLookup,
+) -> Result<(), String> {
+    match &mut value.kind {
+        ValueKind::String(string) => *string = expand_string(string, lookup_variable)?,
+        ValueKind::Table(table) => {
+            for value in table.values_mut() {
+                expand_environment_variables(value, lookup_variable)?;
+            }
+        }
+        ValueKind::Array(array) => {
+            for value in array.iter_mut() {
+                expand_environment_variables(value, lookup_variable)?;
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// Replace the `${VARIABLE}` references of the given string by the value of the environment
+/// variable, it fails if the variable is not set.
+///
+/// The `$${` escape sequence is replaced by a literal `${`.
+fn expand_string(string: &str, lookup_variable: VariableLookup) -> Result<String, String> {
+    let mut expanded = String::with_capacity(string.len());
+    let mut remaining = string;
+    while let Some(start) = remaining.find("${") {
+        if remaining[..start].ends_with('$') {
+            expanded.push_str(&remaining[..start - 1]);
+            expanded.push_str("${");
+            remaining = &remaining[start + 2..];
+            continue;
+        }
+        let Some(length) = remaining[start + 2..].find('}') else {
+            return Err(format!(
+                "unclosed environment variable reference in '{string}'"
+            ));
+        };
+        let name = &remaining[start + 2..start + 2 + length];
+        let value = lookup_variable(name)
+            .ok_or_else(|| format!("environment variable '{name}' is not set"))?;
+        expanded.push_str(&remaining[..start]);
+        expanded.push_str(&value);
+        remaining = &remaining[start + 3 + length..];
+    }
+    expanded.push_str(remaining);
+
+    Ok(expanded)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use crate::test_utils::TempDir;
+
+    use super::*;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct NestedConfiguration {
+        first: String,
+        second: String,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct TestConfiguration {
+        network: String,
+        server_port: u16,
+        password: Option<String>,
+        nested: NestedConfiguration,
+    }
+
+    fn lookup_test_variable(name: &str) -> Option<String> {
+        match name {
+            "CONFIGURATION_FILES_TEST_PASSWORD" => Some("from-env".to_string()),
+            _ => None,
+        }
+    }
+
+    fn build_configuration_from(
+        files: ConfigurationFiles,
+    ) -> Result<TestConfiguration, ConfigError> {
+        config::Config::builder()
+            .add_source(files.with_variable_lookup(lookup_test_variable))
+            .build()?
+            .try_deserialize()
+    }
+
+    fn build_configuration(
+        directory: &Path,
+        run_mode: &str,
+    ) -> Result<TestConfiguration, ConfigError> {
+        build_configuration_from(ConfigurationFiles::new(directory, run_mode))
+    }
+
+    #[test]
+    fn files_override_the_previous_ones_in_order() {
+        let directory = TempDir::create(
+            "configuration_files",
+            "files_override_the_previous_ones_in_order",
+        );
+        std::fs::write(
+            directory.join("base.json"),
+            r#"{"network": "mainnet", "server_port": 8080, "password": "base", "nested": {"first": "base", "second": "base"}}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            directory.join("testnet.json"),
+            r#"{"network": "testnet", "password": "testnet", "nested": {"first": "testnet"}}"#,
+        )
+        .unwrap();
+        std::fs::write(directory.join("secrets.json"), r#"{"password": "secret"}"#).unwrap();
+
+        let configuration = build_configuration(&directory, "testnet").unwrap();
+
+        assert_eq!(
+            TestConfiguration {
+                network: "testnet".to_string(),
+                server_port: 8080,
+                password: Some("secret".to_string()),
+                nested: NestedConfiguration {
+                    first: "testnet".to_string(),
+                    second: "base".to_string(),
+                },
+            },
+            configuration
+        );
+    }
+
+    #[test]
+    fn files_are_optional() {
+        let directory = TempDir::create("configuration_files", "files_are_optional");
+        std::fs::write(
+            directory.join("dev.json"),
+            r#"{"network": "devnet", "server_port": 8080, "nested": {"first": "a", "second": "b"}}"#,
+        )
+        .unwrap();
+
+        let configuration = build_configuration(&directory, "dev").unwrap();
+
+        assert_eq!("devnet", configuration.network);
+        assert_eq!(None, configuration.password);
+    }
+
+    #[test]
+    fn environment_variables_are_expanded_in_the_string_values() {
+        let directory = TempDir::create(
+            "configuration_files",
+            "environment_variables_are_expanded_in_the_string_values",
+        );
+        std::fs::write(
+            directory.join("base.json"),
+            r#"{"network": "devnet", "server_port": 8080, "nested": {"first": "a", "second": "b"}}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            directory.join("secrets.json"),
+            r#"{"password": "prefix-${CONFIGURATION_FILES_TEST_PASSWORD}"}"#,
+        )
+        .unwrap();
+
+        let configuration = build_configuration(&directory, "dev").unwrap();
+
+        assert_eq!(Some("prefix-from-env".to_string()), configuration.password);
+    }
+
+    #[test]
+    fn the_secrets_file_replaces_the_one_of_the_configuration_directory() {
+        let directory = TempDir::create(
+            "configuration_files",
+            "the_secrets_file_replaces_the_one_of_the_configuration_directory",
+        );
+        std::fs::write(
+            directory.join("base.json"),
+            r#"{"network": "devnet", "server_port": 8080, "nested": {"first": "a", "second": "b"}}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            directory.join("secrets.json"),
+            r#"{"password": "directory"}"#,
+        )
+        .unwrap();
+        let secrets_file = directory.join("other-secrets.json");
+        std::fs::write(&secrets_file, r#"{"password": "secrets-file"}"#).unwrap();
+
+        let configuration = build_configuration_from(
+            ConfigurationFiles::new(&directory, "dev").with_secrets_file(Some(&secrets_file)),
+        )
+        .unwrap();
+
+        assert_eq!(Some("secrets-file".to_string()), configuration.password);
+    }
+
+    #[test]
+    fn secret_strings_are_redacted_when_debug_formatted_but_not_when_deserialized() {
+        #[derive(Debug, Deserialize)]
+        struct ConfigurationWithSecret {
+            password: SecretString,
+        }
+
+        let configuration: ConfigurationWithSecret =
+            serde_json::from_str(r#"{"password": "very-secret"}"#).unwrap();
+
+        assert_eq!("very-secret", configuration.password.expose_secret());
+        assert!(!format!("{configuration:?}").contains("very-secret"));
+        assert!(format!("{configuration:?}").contains(SecretString::REDACTED));
+    }
+
+    #[test]
+    fn expanding_an_unset_environment_variable_fails() {
+        expand_string(
+            "${CONFIGURATION_FILES_TEST_UNSET_VARIABLE}",
+            lookup_test_variable,
+        )
+        .expect_err("Expanding an unset variable should fail");
+        expand_string("${UNCLOSED", lookup_test_variable)
+            .expect_err("Expanding an unclosed reference should fail");
+        assert_eq!(
+            "without reference",
+            expand_string("without reference", lookup_test_variable).unwrap()
+        );
+    }
+
+    #[test]
+    fn escaped_references_are_kept_literally() {
+        assert_eq!(
+            "literal ${CONFIGURATION_FILES_TEST_PASSWORD} and from-env",
+            expand_string(
+                "literal $${CONFIGURATION_FILES_TEST_PASSWORD} and ${CONFIGURATION_FILES_TEST_PASSWORD}",
+                lookup_test_variable
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            "unclosed ${",
+            expand_string("unclosed $${", lookup_test_variable).unwrap()
+        );
+    }
+}
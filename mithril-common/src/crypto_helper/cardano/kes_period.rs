@@ -0,0 +1,196 @@
+//! Computation of the KES period of an operational certificate from the Shelley genesis
+//! parameters, without querying the Cardano node or shelling out to the `cardano-cli`.
+
+use anyhow::{anyhow, Context};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+use crate::crypto_helper::cardano::{KESPeriod, OpCert};
+use crate::entities::SlotNumber;
+use crate::StdResult;
+
+/// Parameters of the Shelley genesis that drive the evolution of the KES keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KesGenesisParameters {
+    /// Number of slots in a KES period
+    #[serde(rename = "slotsPerKESPeriod")]
+    pub slots_per_kes_period: u64,
+
+    /// Number of times a KES key can evolve before a new operational certificate is needed
+    #[serde(rename = "maxKESEvolutions")]
+    pub max_kes_evolutions: u64,
+}
+
+impl Default for KesGenesisParameters {
+    /// The parameters shared by the mainnet and the public test networks
+    fn default() -> Self {
+        Self {
+            slots_per_kes_period: 129600,
+            max_kes_evolutions: 62,
+        }
+    }
+}
+
+impl KesGenesisParameters {
+    /// Read the parameters from a Shelley genesis file
+    pub fn from_shelley_genesis_file(path: &Path) -> StdResult<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Could not read Shelley genesis file '{}'", path.display()))?;
+
+        serde_json::from_str(&content).with_context(|| {
+            format!(
+                "Could not read the KES parameters of Shelley genesis file '{}'",
+                path.display()
+            )
+        })
+    }
+
+    /// Compute the KES period of the chain at the given slot
+    pub fn compute_kes_period(&self, slot_number: SlotNumber) -> StdResult<KESPeriod> {
+        if self.slots_per_kes_period == 0 {
+            return Err(anyhow!("slots_per_kes_period must be greater than 0"));
+        }
+
+        KESPeriod::try_from(*slot_number / self.slots_per_kes_period)
+            .with_context(|| format!("KES period overflow at slot {slot_number}"))
+    }
+
+    /// Compute the [KesPeriodInfo] of the given operational certificate at the given slot
+    pub fn compute_kes_period_info(
+        &self,
+        operational_certificate: &OpCert,
+        slot_number: SlotNumber,
+    ) -> StdResult<KesPeriodInfo> {
+        let current_kes_period = self.compute_kes_period(slot_number)?;
+        let start_kes_period = KESPeriod::try_from(operational_certificate.start_kes_period)
+            .with_context(|| "Start KES period of the operational certificate overflow")?;
+        let evolutions = current_kes_period.checked_sub(start_kes_period).ok_or(anyhow!(
+            "Current KES period {current_kes_period} is before the start KES period {start_kes_period} of the operational certificate"
+        ))?;
+
+        Ok(KesPeriodInfo {
+            current_kes_period,
+            start_kes_period,
+            remaining_kes_evolutions: self.max_kes_evolutions.saturating_sub(evolutions as u64),
+        })
+    }
+}
+
+/// KES period of an operational certificate at a point of the chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KesPeriodInfo {
+    /// KES period of the chain
+    pub current_kes_period: KESPeriod,
+
+    /// KES period at which the operational certificate starts
+    pub start_kes_period: KESPeriod,
+
+    /// Number of evolutions left to the KES key before the operational certificate expires
+    pub remaining_kes_evolutions: u64,
+}
+
+impl KesPeriodInfo {
+    /// KES period relative to the start of the operational certificate, which is the number of
+    /// times its KES key has evolved
+    pub fn kes_evolutions(&self) -> KESPeriod {
+        self.current_kes_period - self.start_kes_period
+    }
+
+    /// Check if the operational certificate has expired
+    pub fn is_expired(&self) -> bool {
+        self.remaining_kes_evolutions == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use kes_summed_ed25519::{kes::Sum6Kes, traits::KesSk};
+
+    use crate::crypto_helper::ColdKeyGenerator;
+    use crate::test_utils::TempDir;
+
+    use super::*;
+
+    fn dummy_opcert(start_kes_period: u64) -> OpCert {
+        let keypair = ColdKeyGenerator::create_deterministic_keypair([0u8; 32]);
+        let mut dummy_key_buffer = [0u8; Sum6Kes::SIZE + 4];
+        let mut dummy_seed = [0u8; 32];
+        let (_, kes_verification_key) = Sum6Kes::keygen(&mut dummy_key_buffer, &mut dummy_seed);
+
+        OpCert::new(kes_verification_key, 0, start_kes_period, keypair)
+    }
+
+    #[test]
+    fn compute_kes_period_at_slot() {
+        let parameters = KesGenesisParameters::default();
+
+        assert_eq!(
+            413,
+            parameters.compute_kes_period(SlotNumber(53536042)).unwrap()
+        );
+        assert_eq!(
+            0,
+            parameters.compute_kes_period(SlotNumber(129599)).unwrap()
+        );
+        KesGenesisParameters {
+            slots_per_kes_period: 0,
+            max_kes_evolutions: 62,
+        }
+        .compute_kes_period(SlotNumber(10))
+        .expect_err("compute_kes_period should fail with 0 slots per KES period");
+    }
+
+    #[test]
+    fn compute_kes_period_info_of_an_operational_certificate() {
+        let parameters = KesGenesisParameters {
+            slots_per_kes_period: 100,
+            max_kes_evolutions: 10,
+        };
+
+        let kes_period_info = parameters
+            .compute_kes_period_info(&dummy_opcert(3), SlotNumber(750))
+            .unwrap();
+        assert_eq!(
+            KesPeriodInfo {
+                current_kes_period: 7,
+                start_kes_period: 3,
+                remaining_kes_evolutions: 6,
+            },
+            kes_period_info
+        );
+        assert_eq!(4, kes_period_info.kes_evolutions());
+        assert!(!kes_period_info.is_expired());
+
+        let kes_period_info = parameters
+            .compute_kes_period_info(&dummy_opcert(3), SlotNumber(1500))
+            .unwrap();
+        assert_eq!(0, kes_period_info.remaining_kes_evolutions);
+        assert!(kes_period_info.is_expired());
+
+        parameters
+            .compute_kes_period_info(&dummy_opcert(8), SlotNumber(750))
+            .expect_err(
+                "an operational certificate starting after the current KES period is invalid",
+            );
+    }
+
+    #[test]
+    fn read_parameters_from_shelley_genesis_file() {
+        let genesis_file =
+            TempDir::create("kes_period", "read_parameters_from_shelley_genesis_file")
+                .join("shelley-genesis.json");
+        fs::write(
+            &genesis_file,
+            r#"{"epochLength": 432000, "maxKESEvolutions": 60, "slotsPerKESPeriod": 1000}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            KesGenesisParameters {
+                slots_per_kes_period: 1000,
+                max_kes_evolutions: 60,
+            },
+            KesGenesisParameters::from_shelley_genesis_file(&genesis_file).unwrap()
+        );
+    }
+}
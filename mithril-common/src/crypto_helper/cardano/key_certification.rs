@@ -117,6 +117,37 @@ pub struct KeyRegWrapper {
     stake_distribution: HashMap<ProtocolPartyId, Stake>,
 }
 
+/// Sign a message with the KES secret key read from the given file, evolved to the given
+/// KES period.
+pub(crate) fn sign_with_kes_secret_key<P: AsRef<Path>>(
+    kes_sk_path: P,
+    kes_period: KESPeriod,
+    message: &[u8],
+) -> StdResult<Sum6KesSig> {
+    let mut kes_sk_bytes = Sum6KesBytes::from_file(kes_sk_path)
+        .map_err(|e| anyhow!(e))
+        .with_context(|| "Can not read KES secret key from file")?;
+    let mut kes_sk = Sum6Kes::try_from(&mut kes_sk_bytes)
+        .map_err(|e| ProtocolInitializerErrorWrapper::ProtocolInitializer(anyhow!(e)))
+        .with_context(|| "Can not use KES secret key")?;
+    let kes_sk_period = kes_sk.get_period();
+    if kes_sk_period > kes_period {
+        return Err(anyhow!(ProtocolInitializerErrorWrapper::KesMismatch(
+            kes_sk_period,
+            kes_period,
+        )));
+    }
+
+    // We need to perform the evolutions
+    for period in kes_sk_period..kes_period {
+        kes_sk
+            .update()
+            .map_err(|_| ProtocolInitializerErrorWrapper::KesUpdate(period))?;
+    }
+
+    Ok(kes_sk.sign(message))
+}
+
 impl StmInitializerWrapper {
     /// Builds an `StmInitializer` that is ready to register with the key registration service.
     /// This function generates the signing and verification key with a PoP, signs the verification
@@ -130,29 +161,14 @@ impl StmInitializerWrapper {
     ) -> StdResult<Self> {
         let stm_initializer = StmInitializer::setup(params, stake, rng);
         let kes_signature = if let Some(kes_sk_path) = kes_sk_path {
-            let mut kes_sk_bytes = Sum6KesBytes::from_file(kes_sk_path)
-                .map_err(|e| anyhow!(e))
-                .with_context(|| "StmInitializerWrapper can not read KES secret key from file")?;
-            let mut kes_sk = Sum6Kes::try_from(&mut kes_sk_bytes)
-                .map_err(|e| ProtocolInitializerErrorWrapper::ProtocolInitializer(anyhow!(e)))
-                .with_context(|| "StmInitializerWrapper can not use KES secret key")?;
-            let kes_sk_period = kes_sk.get_period();
-            let provided_period = kes_period.unwrap_or_default();
-            if kes_sk_period > provided_period {
-                return Err(anyhow!(ProtocolInitializerErrorWrapper::KesMismatch(
-                    kes_sk_period,
-                    provided_period,
-                )));
-            }
-
-            // We need to perform the evolutions
-            for period in kes_sk_period..provided_period {
-                kes_sk
-                    .update()
-                    .map_err(|_| ProtocolInitializerErrorWrapper::KesUpdate(period))?;
-            }
-
-            Some(kes_sk.sign(&stm_initializer.verification_key().to_bytes()))
+            Some(
+                sign_with_kes_secret_key(
+                    kes_sk_path,
+                    kes_period.unwrap_or_default(),
+                    &stm_initializer.verification_key().to_bytes(),
+                )
+                .with_context(|| "StmInitializerWrapper can not sign the verification key")?,
+            )
         } else {
             println!("WARNING: Non certified signer registration by providing only a Pool Id is decommissioned and must be used for tests only!");
             None
@@ -1,10 +1,14 @@
 mod codec;
+mod kes_period;
 mod key_certification;
 mod opcert;
+mod registration_challenge;
 
 pub use codec::*;
+pub use kes_period::*;
 pub use key_certification::*;
 pub use opcert::*;
+pub use registration_challenge::*;
 
 cfg_random! {
     mod cold_key;
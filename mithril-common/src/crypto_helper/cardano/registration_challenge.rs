@@ -0,0 +1,205 @@
+//! Challenge-response authenticating a signer registration.
+//!
+//! The aggregator issues a short-lived challenge that the signer signs, together with its Mithril
+//! verification key, with the KES secret key of its Cardano node. The signature is checked
+//! against the KES verification key certified by the operational certificate of the signer, so
+//! that a captured registration payload can not be replayed once its challenge has expired.
+
+use anyhow::{anyhow, Context};
+use kes_summed_ed25519::traits::KesSig;
+use std::path::{Path, PathBuf};
+
+use crate::crypto_helper::cardano::{sign_with_kes_secret_key, KESPeriod};
+use crate::crypto_helper::{
+    ProtocolOpCert, ProtocolRegistrationChallengeSignature, ProtocolSignerVerificationKey,
+};
+use crate::StdResult;
+
+fn compute_signed_data(
+    challenge: &str,
+    verification_key: &ProtocolSignerVerificationKey,
+) -> Vec<u8> {
+    [challenge.as_bytes(), &verification_key.to_bytes()].concat()
+}
+
+/// Sign the registration challenges with the KES secret key of a Cardano node.
+#[derive(Debug, Clone)]
+pub struct RegistrationChallengeSigner {
+    kes_secret_key_path: PathBuf,
+}
+
+impl RegistrationChallengeSigner {
+    /// RegistrationChallengeSigner factory
+    pub fn new(kes_secret_key_path: &Path) -> Self {
+        Self {
+            kes_secret_key_path: kes_secret_key_path.to_path_buf(),
+        }
+    }
+
+    /// Sign the challenge and the verification key to register at the given KES period
+    pub fn sign(
+        &self,
+        challenge: &str,
+        verification_key: &ProtocolSignerVerificationKey,
+        kes_period: KESPeriod,
+    ) -> StdResult<ProtocolRegistrationChallengeSignature> {
+        let signature = sign_with_kes_secret_key(
+            &self.kes_secret_key_path,
+            kes_period,
+            &compute_signed_data(challenge, verification_key),
+        )
+        .with_context(|| "Could not sign the registration challenge")?;
+
+        Ok(signature.into())
+    }
+}
+
+/// Verify the registration challenges signatures.
+pub struct RegistrationChallengeVerifier;
+
+impl RegistrationChallengeVerifier {
+    /// Check that the challenge and the verification key were signed by the KES secret key
+    /// certified by the operational certificate, at the given KES period or at one of its
+    /// adjacent periods to tolerate a KES period change between the signature and its check
+    pub fn verify(
+        challenge: &str,
+        verification_key: &ProtocolSignerVerificationKey,
+        signature: &ProtocolRegistrationChallengeSignature,
+        operational_certificate: &ProtocolOpCert,
+        kes_period: KESPeriod,
+    ) -> StdResult<()> {
+        operational_certificate
+            .validate()
+            .map_err(|e| anyhow!(e).context("Invalid operational certificate"))?;
+        let signed_data = compute_signed_data(challenge, verification_key);
+        let kes_period_try_min = kes_period.saturating_sub(1);
+        let kes_period_try_max = std::cmp::min(63, kes_period.saturating_add(1));
+        let is_signature_valid = (kes_period_try_min..=kes_period_try_max).any(|kes_period_try| {
+            signature
+                .verify(
+                    kes_period_try,
+                    &operational_certificate.kes_vk,
+                    &signed_data,
+                )
+                .is_ok()
+        });
+
+        if !is_signature_valid {
+            return Err(anyhow!(
+                "Invalid registration challenge signature at KES period {kes_period}"
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use kes_summed_ed25519::{kes::Sum6Kes, traits::KesSk};
+
+    use crate::crypto_helper::cardano::{ColdKeyGenerator, OpCert};
+    use crate::test_utils::MithrilFixtureBuilder;
+
+    use super::*;
+
+    #[test]
+    fn verify_a_challenge_signed_by_the_signer() {
+        let fixture = MithrilFixtureBuilder::default().with_signers(2).build();
+        let signer = &fixture.signers_fixture()[0];
+        let signer_with_stake = &signer.signer_with_stake;
+        let operational_certificate = signer_with_stake.operational_certificate.clone().unwrap();
+        let kes_period = signer_with_stake.kes_period.unwrap_or_default();
+        let challenge_signer =
+            RegistrationChallengeSigner::new(signer.kes_secret_key_path().unwrap());
+
+        let signature = challenge_signer
+            .sign("challenge", &signer_with_stake.verification_key, kes_period)
+            .unwrap();
+
+        RegistrationChallengeVerifier::verify(
+            "challenge",
+            &signer_with_stake.verification_key,
+            &signature,
+            &operational_certificate,
+            kes_period,
+        )
+        .expect("The challenge signed by the signer should be valid");
+        RegistrationChallengeVerifier::verify(
+            "another challenge",
+            &signer_with_stake.verification_key,
+            &signature,
+            &operational_certificate,
+            kes_period,
+        )
+        .expect_err("The signature of another challenge should be invalid");
+        RegistrationChallengeVerifier::verify(
+            "challenge",
+            &fixture.signers_with_stake()[1].verification_key,
+            &signature,
+            &operational_certificate,
+            kes_period,
+        )
+        .expect_err("The signature of another verification key should be invalid");
+    }
+
+    #[test]
+    fn verify_a_challenge_signed_at_an_adjacent_kes_period() {
+        let fixture = MithrilFixtureBuilder::default().with_signers(1).build();
+        let signer = &fixture.signers_fixture()[0];
+        let signer_with_stake = &signer.signer_with_stake;
+        let operational_certificate = signer_with_stake.operational_certificate.clone().unwrap();
+        let kes_period = signer_with_stake.kes_period.unwrap_or_default();
+        let signature = RegistrationChallengeSigner::new(signer.kes_secret_key_path().unwrap())
+            .sign("challenge", &signer_with_stake.verification_key, kes_period)
+            .unwrap();
+
+        RegistrationChallengeVerifier::verify(
+            "challenge",
+            &signer_with_stake.verification_key,
+            &signature,
+            &operational_certificate,
+            kes_period + 1,
+        )
+        .expect("A challenge signed at the previous KES period should be valid");
+        RegistrationChallengeVerifier::verify(
+            "challenge",
+            &signer_with_stake.verification_key,
+            &signature,
+            &operational_certificate,
+            kes_period + 2,
+        )
+        .expect_err("A challenge signed two KES periods before should be invalid");
+    }
+
+    #[test]
+    fn reject_a_challenge_signed_by_another_signer() {
+        let fixture = MithrilFixtureBuilder::default().with_signers(1).build();
+        let signer = &fixture.signers_fixture()[0];
+        let kes_period = signer.signer_with_stake.kes_period.unwrap_or_default();
+        let other_operational_certificate: ProtocolOpCert = {
+            let keypair = ColdKeyGenerator::create_deterministic_keypair([1u8; 32]);
+            let mut dummy_key_buffer = [0u8; Sum6Kes::SIZE + 4];
+            let mut dummy_seed = [1u8; 32];
+            let (_, kes_verification_key) = Sum6Kes::keygen(&mut dummy_key_buffer, &mut dummy_seed);
+            OpCert::new(kes_verification_key, 0, 0, keypair).into()
+        };
+
+        let signature = RegistrationChallengeSigner::new(signer.kes_secret_key_path().unwrap())
+            .sign(
+                "challenge",
+                &signer.signer_with_stake.verification_key,
+                kes_period,
+            )
+            .unwrap();
+
+        RegistrationChallengeVerifier::verify(
+            "challenge",
+            &signer.signer_with_stake.verification_key,
+            &signature,
+            &other_operational_certificate,
+            kes_period,
+        )
+        .expect_err("The signature should not be valid for another operational certificate");
+    }
+}
@@ -67,12 +67,158 @@ pub fn encode_bech32(human_readable_part: &str, data: &[u8]) -> StdResult<String
     bech32::encode::<Bech32>(human_readable_part, data).map_err(|e| anyhow!(e))
 }
 
+/// Decode from bech32 to the Human Readable Part (hrp) and data
+pub fn decode_bech32(encoded: &str) -> StdResult<(String, Vec<u8>)> {
+    let (human_readable_part, data) = bech32::decode(encoded).map_err(|e| anyhow!(e))?;
+
+    Ok((human_readable_part.as_str().to_string(), data))
+}
+
+/// Encoding of a key exchanged as a string
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyEncoding {
+    /// Hex encoded JSON, the default encoding of the Mithril keys
+    JsonHex,
+
+    /// Hex encoded raw CBOR, as in the `cborHex` field of the Cardano text envelopes
+    CborHex,
+
+    /// Bech32 encoded raw CBOR, with the Human Readable Part (hrp) of the key type
+    Bech32,
+}
+
+impl KeyEncoding {
+    /// Detect the encoding of a key from its prefix:
+    /// - bech32 if it starts with the Human Readable Part of the key type followed by `1`,
+    /// - JSON hex if it starts with the hex encoding of `{`, `[` or `"`,
+    /// - CBOR hex otherwise.
+    pub fn detect(encoded: &str, human_readable_part: &str) -> Self {
+        let encoded = encoded.to_lowercase();
+
+        if encoded.starts_with(&format!("{human_readable_part}1")) {
+            Self::Bech32
+        } else if ["7b", "5b", "22"]
+            .iter()
+            .any(|prefix| encoded.starts_with(prefix))
+        {
+            Self::JsonHex
+        } else {
+            Self::CborHex
+        }
+    }
+}
+
+/// Encode key to hex encoded CBOR helper
+pub fn key_encode_cbor_hex<T>(from: T) -> Result<String, CodecError>
+where
+    T: Serialize,
+{
+    Ok(key_encode_cbor(from)?.encode_hex::<String>())
+}
+
+/// Decode key from hex encoded CBOR helper
+pub fn key_decode_cbor_hex<T>(from: &str) -> Result<T, CodecError>
+where
+    T: DeserializeOwned,
+{
+    let from_vec = Vec::from_hex(from).map_err(|e| {
+        CodecError::new(
+            "Key decode CBOR hex: can not turn hexadecimal value into bytes",
+            e.into(),
+        )
+    })?;
+
+    key_decode_cbor(&from_vec)
+}
+
+/// Encode key to bech32 encoded CBOR helper
+pub fn key_encode_bech32<T>(human_readable_part: &str, from: T) -> Result<String, CodecError>
+where
+    T: Serialize,
+{
+    encode_bech32(human_readable_part, &key_encode_cbor(from)?)
+        .map_err(|e| CodecError::new("Key encode bech32: can not encode to bech32", e))
+}
+
+/// Decode key from bech32 encoded CBOR helper, the Human Readable Part (hrp) must match the
+/// expected one
+pub fn key_decode_bech32<T>(human_readable_part: &str, from: &str) -> Result<T, CodecError>
+where
+    T: DeserializeOwned,
+{
+    let (decoded_human_readable_part, data) = decode_bech32(from)
+        .map_err(|e| CodecError::new("Key decode bech32: can not decode from bech32", e))?;
+    if decoded_human_readable_part != human_readable_part {
+        return Err(CodecError::new(
+            "Key decode bech32: unexpected human readable part",
+            anyhow!("expected '{human_readable_part}', got '{decoded_human_readable_part}'"),
+        ));
+    }
+
+    key_decode_cbor(&data)
+}
+
+/// Encode key with the given encoding helper
+pub fn key_encode<T>(
+    from: T,
+    encoding: KeyEncoding,
+    human_readable_part: &str,
+) -> Result<String, CodecError>
+where
+    T: Serialize,
+{
+    match encoding {
+        KeyEncoding::JsonHex => key_encode_hex(from),
+        KeyEncoding::CborHex => key_encode_cbor_hex(from),
+        KeyEncoding::Bech32 => key_encode_bech32(human_readable_part, from),
+    }
+}
+
+/// Decode key helper, the encoding is detected from the prefix of the value (see
+/// [KeyEncoding::detect])
+pub fn key_decode<T>(from: &str, human_readable_part: &str) -> Result<T, CodecError>
+where
+    T: DeserializeOwned,
+{
+    match KeyEncoding::detect(from, human_readable_part) {
+        KeyEncoding::JsonHex => key_decode_hex(from),
+        KeyEncoding::CborHex => key_decode_cbor_hex(from),
+        KeyEncoding::Bech32 => key_decode_bech32(human_readable_part, from),
+    }
+}
+
+fn key_encode_cbor<T>(from: T) -> Result<Vec<u8>, CodecError>
+where
+    T: Serialize,
+{
+    let mut bytes = vec![];
+    ciborium::ser::into_writer(&from, &mut bytes)
+        .map_err(|e| CodecError::new("Key encode CBOR: can not serialize to CBOR", anyhow!(e)))?;
+
+    Ok(bytes)
+}
+
+fn key_decode_cbor<T>(from: &[u8]) -> Result<T, CodecError>
+where
+    T: DeserializeOwned,
+{
+    ciborium::de::from_reader(from).map_err(|e| {
+        CodecError::new(
+            &format!(
+                "Key decode CBOR: can not deserialize to type '{}' from CBOR",
+                std::any::type_name::<T>()
+            ),
+            anyhow!(e),
+        )
+    })
+}
+
 #[cfg(test)]
 pub mod tests {
     use hex::FromHex;
     use serde::{Deserialize, Serialize};
 
-    use super::{encode_bech32, key_decode_hex, key_encode_hex};
+    use super::*;
 
     #[derive(Debug, PartialEq, Serialize, Deserialize)]
     struct TestSerialize {
@@ -102,4 +248,49 @@ pub mod tests {
 
         assert_eq!(expected_encoded_data, encoded_data);
     }
+
+    #[test]
+    fn test_bech32_encode_decode() {
+        let data =
+            Vec::from_hex("edfa208d441511f9595ba80e8f3a7b07b6a80cbc9dda9d8e9d1dc039").unwrap();
+        let encoded_data = encode_bech32("pool", &data).unwrap();
+
+        assert_eq!(
+            ("pool".to_string(), data),
+            decode_bech32(&encoded_data).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_key_encode_decode_with_every_encoding() {
+        let test_to_serialize = TestSerialize {
+            inner_string: "my inner string".to_string(),
+        };
+
+        for encoding in [
+            KeyEncoding::JsonHex,
+            KeyEncoding::CborHex,
+            KeyEncoding::Bech32,
+        ] {
+            let encoded = key_encode(&test_to_serialize, encoding, "test_key").unwrap();
+            assert_eq!(encoding, KeyEncoding::detect(&encoded, "test_key"));
+
+            let restored: TestSerialize = key_decode(&encoded, "test_key").unwrap();
+            assert_eq!(test_to_serialize, restored, "encoding: {encoding:?}");
+        }
+    }
+
+    #[test]
+    fn test_key_decode_bech32_with_another_human_readable_part_fails() {
+        let encoded = key_encode_bech32(
+            "another_key",
+            TestSerialize {
+                inner_string: "my inner string".to_string(),
+            },
+        )
+        .unwrap();
+
+        key_decode_bech32::<TestSerialize>("test_key", &encoded)
+            .expect_err("Decoding a key with another human readable part should fail");
+    }
 }
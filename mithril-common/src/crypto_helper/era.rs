@@ -62,6 +62,11 @@ impl EraMarkersSigner {
         Self { secret_key }
     }
 
+    /// Get the [EraMarkersVerifierSecretKey] of the signer
+    pub fn to_secret_key(&self) -> EraMarkersVerifierSecretKey {
+        self.secret_key.clone()
+    }
+
     /// Create a [EraMarkersVerifier]
     pub fn create_verifier(&self) -> EraMarkersVerifier {
         EraMarkersVerifier::from_verification_key(self.secret_key.verifying_key().into())
@@ -1,5 +1,5 @@
 use crate::{StdError, StdResult};
-use anyhow::anyhow;
+use anyhow::{anyhow, Context};
 use ed25519_dalek::{Signer, SigningKey};
 #[cfg(feature = "random")]
 use rand_chacha::rand_core;
@@ -53,6 +53,11 @@ impl ProtocolGenesisSigner {
         Self { secret_key }
     }
 
+    /// Get the [ProtocolGenesisSecretKey] of the signer
+    pub fn to_secret_key(&self) -> ProtocolGenesisSecretKey {
+        self.secret_key.clone()
+    }
+
     /// Create a [ProtocolGenesisVerifier]
     pub fn create_genesis_verifier(&self) -> ProtocolGenesisVerifier {
         ProtocolGenesisVerifier::from_verification_key(self.secret_key.verifying_key().into())
@@ -98,6 +103,15 @@ impl ProtocolGenesisVerifier {
 }
 
 impl ProtocolGenesisVerificationKey {
+    /// Create an instance from the 32 bytes of an Ed25519 public key
+    pub fn from_bytes(bytes: &[u8]) -> StdResult<Self> {
+        let key = ed25519_dalek::VerifyingKey::try_from(bytes)
+            .map_err(|e| anyhow!(e))
+            .with_context(|| "Could not deserialize a ProtocolGenesisVerificationKey from bytes")?;
+
+        Ok(key.into())
+    }
+
     /// Verifies the genesis signature of a message
     pub fn verify(&self, message: &[u8], signature: &ProtocolGenesisSignature) -> StdResult<()> {
         Ok(self
@@ -173,4 +187,19 @@ mod tests {
             "genesis signature verification should not fail"
         );
     }
+
+    #[test]
+    fn test_genesis_verification_key_from_bytes() {
+        let genesis_signer = ProtocolGenesisSigner::create_deterministic_genesis_signer();
+        let verification_key = genesis_signer
+            .create_genesis_verifier()
+            .to_verification_key();
+
+        let decoded_key =
+            ProtocolGenesisVerificationKey::from_bytes(&verification_key.to_bytes()).unwrap();
+
+        assert_eq!(verification_key, decoded_key);
+        ProtocolGenesisVerificationKey::from_bytes(&[0u8; 12])
+            .expect_err("from_bytes should fail with an invalid length");
+    }
 }
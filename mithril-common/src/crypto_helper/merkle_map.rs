@@ -1,6 +1,7 @@
 //! Merkelized map and associated proof
 
 use anyhow::{anyhow, Context};
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::{
     collections::{BTreeMap, BTreeSet, HashMap},
@@ -10,13 +11,32 @@ use std::{
 
 use crate::{resource_pool::Reset, StdError, StdResult};
 
-use super::{MKProof, MKTree, MKTreeNode, MKTreeStorer};
+use super::{MKProof, MKTree, MKTreeNode, MKTreeSnapshot, MKTreeStorer, MKPROOF_MAX_LEAVES};
+
+/// Maximum depth of the sub proofs of a [MKMapProof]
+pub const MKMAP_PROOF_MAX_DEPTH: usize = 8;
+
+/// Snapshot of a [MKMap] with its values compressed to their roots, used to persist a map and
+/// restore it without recomputing the roots of its values.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MKMapSnapshot<K> {
+    /// Keys of the map with the roots of their values
+    pub entries: Vec<(K, MKTreeNode)>,
+
+    /// Snapshot of the Merkle tree of the map
+    pub merkle_tree: MKTreeSnapshot,
+}
 
 /// The trait implemented by the keys of a MKMap
-pub trait MKMapKey: PartialEq + Eq + PartialOrd + Ord + Clone + Hash + Into<MKTreeNode> {}
+pub trait MKMapKey:
+    PartialEq + Eq + PartialOrd + Ord + Clone + Hash + Into<MKTreeNode> + Send + Sync
+{
+}
 
 /// The trait implemented by the values of a MKMap
-pub trait MKMapValue<K: MKMapKey>: Clone + TryInto<MKTreeNode> + TryFrom<MKTreeNode> {
+pub trait MKMapValue<K: MKMapKey>:
+    Clone + TryInto<MKTreeNode> + TryFrom<MKTreeNode> + Send + Sync
+{
     /// Get the root of the merkelized map value
     fn compute_root(&self) -> StdResult<MKTreeNode>;
 
@@ -177,12 +197,63 @@ impl<K: MKMapKey, V: MKMapValue<K>, S: MKTreeStorer> MKMap<K, V, S> {
         Ok(())
     }
 
+    /// Take a snapshot of the merkelized map, with its values compressed to their roots
+    pub fn to_snapshot(&self) -> StdResult<MKMapSnapshot<K>> {
+        let entries = self
+            .inner_map_values
+            .iter()
+            .map(|(key, value)| Ok((key.to_owned(), value.compute_root()?)))
+            .collect::<StdResult<Vec<_>>>()?;
+
+        Ok(MKMapSnapshot {
+            entries,
+            merkle_tree: self.inner_merkle_tree.to_snapshot()?,
+        })
+    }
+
+    /// Restore a merkelized map from a snapshot, without recomputing the roots of its values.
+    ///
+    /// The snapshot is rejected if its Merkle tree does not match its entries, or if the nodes of
+    /// the tree, and so its root, are not the ones computed from its leaves.
+    pub fn from_snapshot(snapshot: MKMapSnapshot<K>) -> StdResult<Self> {
+        let mut mk_map = Self {
+            inner_map_values: BTreeMap::default(),
+            inner_merkle_tree: MKTree::from_snapshot(snapshot.merkle_tree)?,
+            provable_keys: BTreeSet::default(),
+        };
+        for (key, root) in snapshot.entries {
+            let mktree_node_key: MKTreeNode = key.clone().into();
+            if !mk_map
+                .inner_merkle_tree
+                .contains(&(mktree_node_key + root.clone()))
+            {
+                return Err(anyhow!(
+                    "Invalid MKMap snapshot: an entry is not in the Merkle tree"
+                ));
+            }
+            let value = root
+                .try_into()
+                .map_err(|_| anyhow!("Merkle root could not be converted to V"))?;
+            mk_map.update_provable_keys(&key, &value)?;
+            mk_map.inner_map_values.insert(key, value);
+        }
+        if mk_map.inner_merkle_tree.total_leaves() != mk_map.inner_map_values.len() {
+            return Err(anyhow!(
+                "Invalid MKMap snapshot: the Merkle tree has leaves that are not entries"
+            ));
+        }
+
+        Ok(mk_map)
+    }
+
     /// Get the root of the merkle tree of the merkelized map
     pub fn compute_root(&self) -> StdResult<MKTreeNode> {
         self.inner_merkle_tree.compute_root()
     }
 
     /// Get the proof for a set of values of the merkelized map (recursively if needed)
+    ///
+    /// The sub proofs of the values are computed in parallel.
     pub fn compute_proof<T: Into<MKTreeNode> + Clone>(
         &self,
         leaves: &[T],
@@ -192,14 +263,16 @@ impl<K: MKMapKey, V: MKMapValue<K>, S: MKTreeStorer> MKMap<K, V, S> {
         }
 
         let leaves_by_keys = self.group_leaves_by_keys(leaves);
-        let mut sub_proofs = BTreeMap::<K, MKMapProof<K>>::default();
-        for (key, sub_leaves) in leaves_by_keys {
-            if let Some(value) = self.get(&key) {
-                if let Some(proof) = value.compute_proof(&sub_leaves)? {
-                    sub_proofs.insert(key.to_owned(), proof);
-                }
-            }
-        }
+        let sub_proofs = leaves_by_keys
+            .into_par_iter()
+            .filter_map(|(key, sub_leaves)| {
+                self.get(&key)
+                    .map(|value| Ok(value.compute_proof(&sub_leaves)?.map(|proof| (key, proof))))
+            })
+            .collect::<StdResult<Vec<_>>>()?
+            .into_iter()
+            .flatten()
+            .collect::<BTreeMap<K, MKMapProof<K>>>();
 
         let master_proof = self
             .inner_merkle_tree
@@ -301,11 +374,43 @@ impl<K: MKMapKey> MKMapProof<K> {
         self.master_proof.root().to_owned()
     }
 
+    /// Check that the nesting of the sub proofs is well-formed and within the size limits, so
+    /// that the recursive verification can not exhaust the stack, the memory or the CPU
+    fn check_structure(&self, depth: usize) -> StdResult<()> {
+        if depth > MKMAP_PROOF_MAX_DEPTH {
+            return Err(anyhow!(
+                "Invalid MKMapProof: its sub proofs exceed the maximum depth of {MKMAP_PROOF_MAX_DEPTH}"
+            ));
+        }
+        if self.sub_proofs.len() > MKPROOF_MAX_LEAVES {
+            return Err(anyhow!(
+                "Invalid MKMapProof: it has {} sub proofs, more than the maximum of {MKPROOF_MAX_LEAVES}",
+                self.sub_proofs.len()
+            ));
+        }
+
+        let mut keys = BTreeSet::new();
+        for (key, proof) in &self.sub_proofs {
+            if !keys.insert(key) {
+                return Err(anyhow!("Invalid MKMapProof: a sub proof key is duplicated"));
+            }
+            proof.check_structure(depth + 1)?;
+        }
+
+        Ok(())
+    }
+
     /// Verify the merkelized map proof
     pub fn verify(&self) -> StdResult<()> {
+        self.check_structure(1)?;
+
+        self.verify_proofs()
+    }
+
+    fn verify_proofs(&self) -> StdResult<()> {
         for (_key, proof) in &self.sub_proofs {
             proof
-                .verify()
+                .verify_proofs()
                 .with_context(|| "MKMapProof could not verify sub proof")?;
         }
 
@@ -538,6 +643,64 @@ mod tests {
         assert_eq!(mk_map_full_root, mk_map_nodes_root);
     }
 
+    #[test]
+    fn test_mk_map_should_be_restored_from_its_snapshot() {
+        let entries = generate_merkle_trees(10, 3);
+        let mk_map =
+            MKMap::<_, _, MKTreeStoreInMemory>::new(&into_mkmap_tree_entries(entries.clone()))
+                .unwrap();
+
+        let restored_mk_map =
+            MKMap::<_, MKMapNode<_, MKTreeStoreInMemory>, MKTreeStoreInMemory>::from_snapshot(
+                mk_map.to_snapshot().unwrap(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            mk_map.compute_root().unwrap(),
+            restored_mk_map.compute_root().unwrap()
+        );
+        assert_eq!(mk_map.len(), restored_mk_map.len());
+        assert!(restored_mk_map.get_provable_keys().is_empty());
+    }
+
+    #[test]
+    fn test_mk_map_should_reject_snapshot_with_an_entry_not_in_the_merkle_tree() {
+        let entries = generate_merkle_trees(10, 3);
+        let mk_map =
+            MKMap::<_, _, MKTreeStoreInMemory>::new(&into_mkmap_tree_node_entries(entries))
+                .unwrap();
+        let mut snapshot = mk_map.to_snapshot().unwrap();
+        snapshot.entries[0].1 = "test-123".to_string().into();
+
+        assert!(
+            MKMap::<_, MKMapNode<_, MKTreeStoreInMemory>, MKTreeStoreInMemory>::from_snapshot(
+                snapshot
+            )
+            .is_err(),
+            "A snapshot with an entry not in the Merkle tree should be rejected"
+        );
+    }
+
+    #[test]
+    fn test_mk_map_should_reject_snapshot_with_a_tampered_root() {
+        let entries = generate_merkle_trees(10, 3);
+        let mk_map =
+            MKMap::<_, _, MKTreeStoreInMemory>::new(&into_mkmap_tree_node_entries(entries))
+                .unwrap();
+        let mut snapshot = mk_map.to_snapshot().unwrap();
+        let last_position = snapshot.merkle_tree.nodes.len() - 1;
+        snapshot.merkle_tree.nodes[last_position] = "tampered-root".to_string().into();
+
+        assert!(
+            MKMap::<_, MKMapNode<_, MKTreeStoreInMemory>, MKTreeStoreInMemory>::from_snapshot(
+                snapshot
+            )
+            .is_err(),
+            "A snapshot with a tampered root should be rejected"
+        );
+    }
+
     #[test]
     fn test_mk_map_should_accept_replacement_with_same_root_value() {
         let entries = generate_merkle_trees_for_ranges(&[
@@ -806,7 +969,11 @@ mod tests {
 
     #[test]
     fn test_mk_map_should_compute_and_verify_valid_proof() {
-        let entries = generate_merkle_trees(10, 3);
+        let entries = generate_merkle_trees_for_ranges(&[
+            BlockRange::new(0, 3),
+            BlockRange::new(4, 6),
+            BlockRange::new(7, 9),
+        ]);
         let mktree_nodes_to_certify = [
             entries[0].1.leaves()[0].clone(),
             entries[1].1.leaves()[0].clone(),
@@ -827,6 +994,41 @@ mod tests {
         assert_eq!(mktree_nodes_to_certify.to_vec(), mk_proof_leaves);
     }
 
+    #[test]
+    fn test_mk_map_proof_should_reject_sub_proofs_exceeding_the_maximum_depth() {
+        let entries = generate_merkle_trees(10, 3);
+        let mktree_nodes_to_certify = [entries[0].1.leaves()[0].clone()];
+        let mk_map_full =
+            MKMap::<_, _, MKTreeStoreInMemory>::new(&into_mkmap_tree_entries(entries)).unwrap();
+        let mut mk_map_proof = mk_map_full.compute_proof(&mktree_nodes_to_certify).unwrap();
+        let (key, _) = mk_map_proof.sub_proofs[0].clone();
+        for _ in 0..MKMAP_PROOF_MAX_DEPTH {
+            let master_proof = mk_map_proof.master_proof.clone();
+            mk_map_proof =
+                MKMapProof::new(master_proof, BTreeMap::from([(key.clone(), mk_map_proof)]));
+        }
+
+        mk_map_proof
+            .verify()
+            .expect_err("A proof exceeding the maximum depth should be rejected");
+    }
+
+    #[test]
+    fn test_mk_map_proof_should_reject_duplicated_sub_proof_keys() {
+        let entries = generate_merkle_trees(10, 3);
+        let mktree_nodes_to_certify = [entries[0].1.leaves()[0].clone()];
+        let mk_map_full =
+            MKMap::<_, _, MKTreeStoreInMemory>::new(&into_mkmap_tree_entries(entries)).unwrap();
+        let mut mk_map_proof = mk_map_full.compute_proof(&mktree_nodes_to_certify).unwrap();
+        mk_map_proof
+            .sub_proofs
+            .push(mk_map_proof.sub_proofs[0].clone());
+
+        mk_map_proof
+            .verify()
+            .expect_err("A proof with duplicated sub proof keys should be rejected");
+    }
+
     #[test]
     fn test_mk_map_should_compute_and_verify_valid_proof_recursively() {
         let entries = generate_merkle_trees(100, 3);
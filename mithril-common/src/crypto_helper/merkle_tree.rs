@@ -6,7 +6,7 @@ use ckb_merkle_mountain_range::{
 };
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, BTreeSet, HashMap},
     fmt::Display,
     ops::{Add, Deref},
     sync::{Arc, RwLock},
@@ -20,6 +20,15 @@ pub type Bytes = Vec<u8>;
 /// Alias for a Merkle tree leaf position
 pub type MKTreeLeafPosition = u64;
 
+/// Maximum depth of the Merkle tree of a [MKProof]
+pub const MKPROOF_MAX_DEPTH: usize = 48;
+
+/// Maximum number of leaves proven by a [MKProof]
+pub const MKPROOF_MAX_LEAVES: usize = 100_000;
+
+/// Maximum size in bytes of a node of a [MKProof]
+pub const MKPROOF_MAX_NODE_SIZE: usize = 1024;
+
 /// A node of a Merkle tree
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Hash, Serialize, Deserialize)]
 pub struct MKTreeNode {
@@ -133,8 +142,66 @@ impl MKProof {
         &self.inner_root
     }
 
+    /// Check that the structure of the proof is well-formed and within the size limits, so that
+    /// its verification can not exhaust the memory or the CPU
+    fn check_structure(&self) -> StdResult<()> {
+        if self.inner_leaves.is_empty() {
+            return Err(anyhow!("Invalid MKProof: it does not prove any leaf"));
+        }
+        if self.inner_leaves.len() > MKPROOF_MAX_LEAVES {
+            return Err(anyhow!(
+                "Invalid MKProof: it proves {} leaves, more than the maximum of {MKPROOF_MAX_LEAVES}",
+                self.inner_leaves.len()
+            ));
+        }
+        if self.inner_proof_size >= 1 << (MKPROOF_MAX_DEPTH + 1) {
+            return Err(anyhow!(
+                "Invalid MKProof: its tree size {} exceeds the maximum depth of {MKPROOF_MAX_DEPTH}",
+                self.inner_proof_size
+            ));
+        }
+        let max_proof_items = (self.inner_leaves.len() + 1) * MKPROOF_MAX_DEPTH;
+        if self.inner_proof_items.len() > max_proof_items {
+            return Err(anyhow!(
+                "Invalid MKProof: it has {} items, more than the maximum of {max_proof_items} for its leaves",
+                self.inner_proof_items.len()
+            ));
+        }
+
+        let mut positions = BTreeSet::new();
+        for (position, _) in &self.inner_leaves {
+            if *position >= self.inner_proof_size {
+                return Err(anyhow!(
+                    "Invalid MKProof: leaf position {position} is out of a tree of size {}",
+                    self.inner_proof_size
+                ));
+            }
+            if !positions.insert(position) {
+                return Err(anyhow!(
+                    "Invalid MKProof: leaf position {position} is duplicated"
+                ));
+            }
+        }
+
+        let nodes = std::iter::once(&self.inner_root)
+            .chain(self.inner_leaves.iter().map(|(_, leaf)| leaf))
+            .chain(self.inner_proof_items.iter());
+        for node in nodes {
+            if node.len() > MKPROOF_MAX_NODE_SIZE {
+                return Err(anyhow!(
+                    "Invalid MKProof: a node of {} bytes exceeds the maximum size of {MKPROOF_MAX_NODE_SIZE} bytes",
+                    node.len()
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
     /// Verification of a Merkle proof
     pub fn verify(&self) -> StdResult<()> {
+        self.check_structure()?;
+
         MerkleProof::<Arc<MKTreeNode>, MergeMKTreeNode>::new(
             self.inner_proof_size,
             self.inner_proof_items.clone(),
@@ -433,6 +500,76 @@ impl<S: MKTreeStorer> MKTree<S> {
             inner_proof_items: proof.proof_items().to_vec(),
         });
     }
+
+    /// Take a snapshot of the nodes of the Merkle tree
+    pub fn to_snapshot(&self) -> StdResult<MKTreeSnapshot> {
+        let store = self.inner_tree.store();
+        let nodes = (0..self.inner_tree.mmr_size())
+            .map(|position| {
+                store
+                    .storer
+                    .get_elem(position)?
+                    .map(|node| (*node).clone())
+                    .ok_or_else(|| {
+                        anyhow!("Missing node at position {position} in the Merkle tree")
+                    })
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+        let leaves_positions = store
+            .leaves()
+            .into_iter()
+            .map(|leaf| {
+                store
+                    .get_leaf_position(&leaf)
+                    .map(|position| (position, leaf))
+                    .ok_or_else(|| anyhow!("Missing position of a leaf in the Merkle tree"))
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+
+        Ok(MKTreeSnapshot {
+            nodes,
+            leaves_positions,
+        })
+    }
+
+    /// Restore a Merkle tree from a snapshot of its nodes.
+    ///
+    /// The tree is rebuilt from the leaves of the snapshot, and the snapshot is rejected if any
+    /// of its nodes, and so its root, differs from the ones of the rebuilt tree.
+    pub fn from_snapshot(snapshot: MKTreeSnapshot) -> StdResult<Self> {
+        let mut leaves_positions = snapshot.leaves_positions;
+        leaves_positions.sort_by_key(|(position, _)| *position);
+        for (position, leaf) in &leaves_positions {
+            if snapshot.nodes.get(*position as usize) != Some(leaf) {
+                return Err(anyhow!(
+                    "Invalid Merkle tree snapshot: leaf at position {position} does not match its node"
+                ));
+            }
+        }
+
+        let leaves = leaves_positions
+            .into_iter()
+            .map(|(_, leaf)| leaf)
+            .collect::<Vec<_>>();
+        let mktree = Self::new(&leaves)?;
+        if mktree.to_snapshot()?.nodes != snapshot.nodes {
+            return Err(anyhow!(
+                "Invalid Merkle tree snapshot: its nodes do not match the ones computed from its leaves"
+            ));
+        }
+
+        Ok(mktree)
+    }
+}
+
+/// Snapshot of the nodes of a [MKTree], used to persist a tree and verify it when it is restored.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MKTreeSnapshot {
+    /// Nodes of the tree ordered by their positions
+    pub nodes: Vec<MKTreeNode>,
+
+    /// Positions of the leaves of the tree
+    pub leaves_positions: Vec<(MKTreeLeafPosition, MKTreeNode)>,
 }
 
 impl<S: MKTreeStorer> Clone for MKTree<S> {
@@ -467,6 +604,51 @@ mod tests {
         );
     }
 
+    #[test]
+    fn restore_a_merkle_tree_from_its_snapshot() {
+        let leaves = generate_leaves(10);
+        let mktree = MKTree::<MKTreeStoreInMemory>::new(&leaves).unwrap();
+
+        let restored_mktree =
+            MKTree::<MKTreeStoreInMemory>::from_snapshot(mktree.to_snapshot().unwrap()).unwrap();
+
+        assert_eq!(
+            mktree.compute_root().unwrap(),
+            restored_mktree.compute_root().unwrap()
+        );
+        assert_eq!(mktree.leaves(), restored_mktree.leaves());
+        restored_mktree
+            .compute_proof(&[leaves[2].to_owned(), leaves[7].to_owned()])
+            .unwrap()
+            .verify()
+            .unwrap();
+    }
+
+    #[test]
+    fn reject_a_merkle_tree_snapshot_with_a_leaf_not_matching_its_node() {
+        let mktree = MKTree::<MKTreeStoreInMemory>::new(&generate_leaves(10)).unwrap();
+        let mut snapshot = mktree.to_snapshot().unwrap();
+        snapshot.leaves_positions[0].1 = "another-leaf".into();
+
+        assert!(
+            MKTree::<MKTreeStoreInMemory>::from_snapshot(snapshot).is_err(),
+            "A snapshot with a leaf not matching its node should be rejected"
+        );
+    }
+
+    #[test]
+    fn reject_a_merkle_tree_snapshot_with_a_node_not_matching_its_leaves() {
+        let mktree = MKTree::<MKTreeStoreInMemory>::new(&generate_leaves(10)).unwrap();
+        let mut snapshot = mktree.to_snapshot().unwrap();
+        let last_position = snapshot.nodes.len() - 1;
+        snapshot.nodes[last_position] = "tampered-node".into();
+
+        assert!(
+            MKTree::<MKTreeStoreInMemory>::from_snapshot(snapshot).is_err(),
+            "A snapshot with a node not matching its leaves should be rejected"
+        );
+    }
+
     #[test]
     fn test_should_accept_valid_proof_generated_by_merkle_tree() {
         let leaves = generate_leaves(10);
@@ -486,6 +668,123 @@ mod tests {
         proof.verify().expect_err("The MKProof should be invalid");
     }
 
+    fn valid_proof() -> MKProof {
+        let leaves = generate_leaves(10);
+        let leaves_to_verify = &[leaves[0].to_owned(), leaves[3].to_owned()];
+
+        MKProof::from_subset_of_leaves(&leaves, leaves_to_verify).unwrap()
+    }
+
+    #[test]
+    fn test_should_reject_proof_without_leaves() {
+        let proof = MKProof {
+            inner_leaves: vec![],
+            ..valid_proof()
+        };
+
+        proof
+            .verify()
+            .expect_err("A proof without leaves should be rejected");
+    }
+
+    #[test]
+    fn test_should_reject_proof_with_a_leaf_out_of_the_tree() {
+        let mut proof = valid_proof();
+        proof.inner_leaves[0].0 = proof.inner_proof_size;
+
+        proof
+            .verify()
+            .expect_err("A proof with a leaf out of the tree should be rejected");
+    }
+
+    #[test]
+    fn test_should_reject_proof_with_duplicated_leaves() {
+        let mut proof = valid_proof();
+        proof.inner_leaves.push(proof.inner_leaves[0].clone());
+
+        proof
+            .verify()
+            .expect_err("A proof with duplicated leaves should be rejected");
+    }
+
+    #[test]
+    fn test_should_reject_proof_of_a_tree_exceeding_the_maximum_depth() {
+        let proof = MKProof {
+            inner_proof_size: u64::MAX,
+            ..valid_proof()
+        };
+
+        proof
+            .verify()
+            .expect_err("A proof of a tree exceeding the maximum depth should be rejected");
+    }
+
+    #[test]
+    fn test_should_reject_proof_with_too_many_items() {
+        let mut proof = valid_proof();
+        let item = proof.inner_proof_items[0].clone();
+        proof.inner_proof_items =
+            vec![item; (proof.inner_leaves.len() + 1) * MKPROOF_MAX_DEPTH + 1];
+
+        proof
+            .verify()
+            .expect_err("A proof with too many items should be rejected");
+    }
+
+    #[test]
+    fn test_should_reject_proof_with_an_oversized_node() {
+        let mut proof = valid_proof();
+        proof.inner_proof_items[0] = Arc::new(MKTreeNode::new(vec![0; MKPROOF_MAX_NODE_SIZE + 1]));
+
+        proof
+            .verify()
+            .expect_err("A proof with an oversized node should be rejected");
+    }
+
+    mod fuzzing {
+        use proptest::prelude::*;
+
+        use super::*;
+
+        fn arbitrary_node() -> impl Strategy<Value = Arc<MKTreeNode>> {
+            prop::collection::vec(any::<u8>(), 32).prop_map(|hash| Arc::new(MKTreeNode::new(hash)))
+        }
+
+        proptest! {
+            #[test]
+            fn verify_rejects_arbitrary_proofs_without_panicking(
+                inner_root in arbitrary_node(),
+                inner_leaves in prop::collection::vec((0u64..1024, arbitrary_node()), 0..16),
+                inner_proof_size in prop_oneof![0u64..1024, any::<u64>()],
+                inner_proof_items in prop::collection::vec(arbitrary_node(), 0..64),
+            ) {
+                let proof = MKProof {
+                    inner_root,
+                    inner_leaves,
+                    inner_proof_size,
+                    inner_proof_items,
+                };
+
+                prop_assert!(proof.verify().is_err());
+            }
+
+            #[test]
+            fn verify_rejects_tampered_valid_proofs_without_panicking(
+                leaf_index in 0usize..2,
+                position in any::<u64>(),
+                item_index in 0usize..8,
+                item in arbitrary_node(),
+            ) {
+                let mut proof = valid_proof();
+                proof.inner_leaves[leaf_index].0 = position;
+                let item_index = item_index % proof.inner_proof_items.len();
+                proof.inner_proof_items[item_index] = item;
+
+                prop_assert!(proof.verify().is_err());
+            }
+        }
+    }
+
     #[test]
     fn test_should_list_leaves() {
         let leaves: Vec<MKTreeNode> = vec!["test-0".into(), "test-1".into(), "test-2".into()];
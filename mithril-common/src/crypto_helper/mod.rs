@@ -18,7 +18,8 @@ cfg_random! {
 }
 
 pub use cardano::{
-    KESPeriod, OpCert, ProtocolInitializerErrorWrapper, ProtocolRegistrationErrorWrapper,
+    KESPeriod, KesGenesisParameters, KesPeriodInfo, OpCert, ProtocolInitializerErrorWrapper,
+    ProtocolRegistrationErrorWrapper, RegistrationChallengeSigner, RegistrationChallengeVerifier,
     SerDeShelleyFileFormat, Sum6KesBytes,
 };
 pub use codec::*;
@@ -27,10 +28,13 @@ pub use era::{
     EraMarkersVerifierSignature, EraMarkersVerifierVerificationKey,
 };
 pub use genesis::{ProtocolGenesisError, ProtocolGenesisSigner, ProtocolGenesisVerifier};
-pub use merkle_map::{MKMap, MKMapKey, MKMapNode, MKMapProof, MKMapValue};
+pub use merkle_map::{
+    MKMap, MKMapKey, MKMapNode, MKMapProof, MKMapSnapshot, MKMapValue, MKMAP_PROOF_MAX_DEPTH,
+};
 pub use merkle_tree::{
-    Bytes, MKProof, MKTree, MKTreeLeafIndexer, MKTreeLeafPosition, MKTreeNode, MKTreeStoreInMemory,
-    MKTreeStorer,
+    Bytes, MKProof, MKTree, MKTreeLeafIndexer, MKTreeLeafPosition, MKTreeNode, MKTreeSnapshot,
+    MKTreeStoreInMemory, MKTreeStorer, MKPROOF_MAX_DEPTH, MKPROOF_MAX_LEAVES,
+    MKPROOF_MAX_NODE_SIZE,
 };
 pub use types::*;
 
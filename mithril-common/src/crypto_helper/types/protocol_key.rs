@@ -3,13 +3,17 @@ use serde::{de::DeserializeOwned, Deserialize, Serialize, Serializer};
 use std::any::type_name;
 use std::ops::Deref;
 
-use crate::crypto_helper::{key_decode_hex, key_encode_hex};
+use crate::crypto_helper::{
+    key_decode_bech32, key_decode_cbor_hex, key_decode_hex, key_encode_bech32, key_encode_cbor_hex,
+    key_encode_hex, KeyEncoding,
+};
 use crate::StdResult;
 
 /// A ProtocolKey is a wrapped that add Serialization capabilities.
 ///
 /// When using serde to (de)serialize it will done using json hex (see [key_decode_hex] and
-/// [key_encode_hex]).
+/// [key_encode_hex]), the keys encoded in CBOR hex or bech32 are accepted too when deserializing
+/// (see [KeyEncoding::detect]).
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ProtocolKey<T>
 where
@@ -20,11 +24,20 @@ where
 
 /// The codec used to serialize/deserialize a [ProtocolKey].
 ///
-/// Default to json hex.
+/// Default to json hex, the decoding negotiates the encoding from the prefix of the key.
 pub trait ProtocolKeyCodec<T: Serialize + DeserializeOwned>: Sized {
+    /// Human Readable Part of the key when it is bech32 encoded
+    const BECH32_HUMAN_READABLE_PART: &'static str = "mithril_key";
+
     /// Do the decoding of the given key
     fn decode_key(encoded: &str) -> StdResult<ProtocolKey<T>> {
-        ProtocolKey::from_json_hex(encoded)
+        match KeyEncoding::detect(encoded, Self::BECH32_HUMAN_READABLE_PART) {
+            KeyEncoding::JsonHex => ProtocolKey::from_json_hex(encoded),
+            KeyEncoding::CborHex => ProtocolKey::from_cbor_hex(encoded),
+            KeyEncoding::Bech32 => {
+                ProtocolKey::from_bech32(Self::BECH32_HUMAN_READABLE_PART, encoded)
+            }
+        }
     }
 
     /// Do the encoding of the given key
@@ -68,6 +81,66 @@ where
             )
         })
     }
+
+    /// Create an instance from a CBOR hex representation
+    pub fn from_cbor_hex(hex_string: &str) -> StdResult<Self> {
+        let key = key_decode_cbor_hex::<T>(hex_string).with_context(|| {
+            format!(
+                "Could not deserialize a ProtocolKey from CBOR hex string. Inner key type: {}",
+                type_name::<T>()
+            )
+        })?;
+
+        Ok(Self { key })
+    }
+
+    /// Create a CBOR hex representation of the key
+    pub fn to_cbor_hex(&self) -> StdResult<String> {
+        key_encode_cbor_hex(&self.key).with_context(|| {
+            format!(
+                "Could not serialize a ProtocolKey to CBOR hex key string. Inner key type: {}",
+                type_name::<T>()
+            )
+        })
+    }
+
+    /// Create an instance from a bech32 representation with the given Human Readable Part
+    pub fn from_bech32(human_readable_part: &str, bech32_string: &str) -> StdResult<Self> {
+        let key =
+            key_decode_bech32::<T>(human_readable_part, bech32_string).with_context(|| {
+                format!(
+                    "Could not deserialize a ProtocolKey from bech32 string. Inner key type: {}",
+                    type_name::<T>()
+                )
+            })?;
+
+        Ok(Self { key })
+    }
+
+    /// Create a bech32 representation of the key with the given Human Readable Part
+    pub fn to_bech32(&self, human_readable_part: &str) -> StdResult<String> {
+        key_encode_bech32(human_readable_part, &self.key).with_context(|| {
+            format!(
+                "Could not serialize a ProtocolKey to bech32 string. Inner key type: {}",
+                type_name::<T>()
+            )
+        })
+    }
+}
+
+impl<T> ProtocolKey<T>
+where
+    T: ProtocolKeyCodec<T> + Serialize + DeserializeOwned,
+{
+    /// Create a representation of the key with the given encoding, the bech32 representation
+    /// uses the Human Readable Part of the key type
+    pub fn to_encoded(&self, encoding: KeyEncoding) -> StdResult<String> {
+        match encoding {
+            KeyEncoding::JsonHex => self.to_json_hex(),
+            KeyEncoding::CborHex => self.to_cbor_hex(),
+            KeyEncoding::Bech32 => self.to_bech32(T::BECH32_HUMAN_READABLE_PART),
+        }
+    }
 }
 
 impl<T> Deref for ProtocolKey<T>
@@ -160,9 +233,11 @@ where
 /// Macro to batch define a [ProtocolKeyCodec] implementation and conversions From<ProtocolKey> / To
 /// the given type and vis versa.
 macro_rules! impl_codec_and_type_conversions_for_protocol_key {
-    (json_hex_codec => $($key_type:ty),+) => {
+    (json_hex_codec => $($key_type:ty: $human_readable_part:literal),+) => {
         $(
-            impl crate::crypto_helper::ProtocolKeyCodec<$key_type> for $key_type {}
+            impl crate::crypto_helper::ProtocolKeyCodec<$key_type> for $key_type {
+                const BECH32_HUMAN_READABLE_PART: &'static str = $human_readable_part;
+            }
 
             impl From<ProtocolKey<$key_type >> for $key_type {
                 fn from(value: ProtocolKey<$key_type>) -> Self {
@@ -196,7 +271,10 @@ macro_rules! impl_codec_and_type_conversions_for_protocol_key {
 
 #[cfg(test)]
 mod test {
-    use crate::{crypto_helper::ProtocolKey, test_utils::fake_keys};
+    use crate::{
+        crypto_helper::{KeyEncoding, ProtocolKey},
+        test_utils::fake_keys,
+    };
     use mithril_stm::stm::StmVerificationKeyPoP;
     use serde::{Deserialize, Serialize};
 
@@ -249,4 +327,32 @@ mod test {
             serde_json::from_str(&serialized).expect("Deserialization should not fail");
         assert_eq!(expected, deserialized);
     }
+
+    #[test]
+    fn can_decode_a_key_from_every_encoding() {
+        let key: ProtocolKey<StmVerificationKeyPoP> = VERIFICATION_KEY.try_into().unwrap();
+
+        for encoding in [
+            KeyEncoding::JsonHex,
+            KeyEncoding::CborHex,
+            KeyEncoding::Bech32,
+        ] {
+            let encoded = key.to_encoded(encoding).unwrap();
+            let decoded: ProtocolKey<StmVerificationKeyPoP> = encoded
+                .as_str()
+                .try_into()
+                .unwrap_or_else(|e| panic!("Could not decode the {encoding:?} key: {e:?}"));
+
+            assert_eq!(key, decoded, "encoding: {encoding:?}");
+        }
+    }
+
+    #[test]
+    fn bech32_encoded_key_starts_with_the_human_readable_part_of_its_type() {
+        let key: ProtocolKey<StmVerificationKeyPoP> = VERIFICATION_KEY.try_into().unwrap();
+
+        let encoded = key.to_encoded(KeyEncoding::Bech32).unwrap();
+
+        assert!(encoded.starts_with("mithril_vk1"), "encoded: {encoded}");
+    }
 }
@@ -15,6 +15,10 @@ pub type ProtocolSignerVerificationKey = ProtocolKey<StmVerificationKeyPoP>;
 /// serialization utilities.
 pub type ProtocolSignerVerificationKeySignature = ProtocolKey<Sum6KesSig>;
 
+/// Wrapper of [KES:Sum6KesSig](https://github.com/input-output-hk/kes/blob/master/src/kes.rs) signing
+/// a registration challenge, to add serialization utilities.
+pub type ProtocolRegistrationChallengeSignature = ProtocolKey<Sum6KesSig>;
+
 /// Wrapper of [MithrilStm:StmSig](type@StmSig) to add serialization utilities.
 pub type ProtocolSingleSignature = ProtocolKey<StmSig>;
 
@@ -83,8 +87,9 @@ impl ProtocolKeyCodec<ed25519_dalek::Signature> for ed25519_dalek::Signature {
 }
 
 impl_codec_and_type_conversions_for_protocol_key!(
-    json_hex_codec => StmVerificationKeyPoP, Sum6KesSig, StmSig, StmAggrSig<D>, OpCert,
-        ed25519_dalek::VerifyingKey, ed25519_dalek::SigningKey, StmAggrVerificationKey<D>,
-        MKProof
+    json_hex_codec => StmVerificationKeyPoP: "mithril_vk", Sum6KesSig: "kes_sig",
+        StmSig: "mithril_sig", StmAggrSig<D>: "mithril_msig", OpCert: "opcert",
+        ed25519_dalek::VerifyingKey: "ed25519_pk", ed25519_dalek::SigningKey: "ed25519_sk",
+        StmAggrVerificationKey<D>: "mithril_avk", MKProof: "mithril_mkproof"
 );
 impl_codec_and_type_conversions_for_protocol_key!(no_default_codec => ed25519_dalek::Signature);
@@ -1,26 +1,60 @@
 use crate::{
     digesters::{
-        cache::ImmutableFileDigestCacheProvider, ImmutableDigester, ImmutableDigesterError,
-        ImmutableFile,
+        cache::{ImmutableFileDigestCacheProvider, MemoryImmutableFileDigestCacheProvider},
+        ImmutableDigester, ImmutableDigesterError, ImmutableFile,
     },
-    entities::{CardanoDbBeacon, HexEncodedDigest, ImmutableFileName},
+    entities::{CardanoDbBeacon, HexEncodedDigest, ImmutableFileName, ImmutableFileNumber},
+    era::EraChecker,
     logging::LoggerExtensions,
+    protocol::{Blake2b256, HashAlgorithm},
 };
 use async_trait::async_trait;
 use sha2::{Digest, Sha256};
 use slog::{debug, info, warn, Logger};
-use std::{collections::BTreeMap, io, path::Path, sync::Arc};
+use std::{
+    collections::BTreeMap,
+    io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
 
 /// Result of a cache computation, contains the digest and the list of new entries to add
 /// to the [ImmutableFileDigestCacheProvider].
 type CacheComputationResult =
-    Result<([u8; 32], Vec<(ImmutableFileName, HexEncodedDigest)>), io::Error>;
+    Result<(Vec<u8>, Vec<(ImmutableFileName, HexEncodedDigest)>), io::Error>;
+
+/// Cardano DB and hash function of the digests known from the previous computations
+struct KnownDigestsOrigin {
+    dirpath: PathBuf,
+    hash_algorithm: HashAlgorithm,
+    last_immutable_file_number: ImmutableFileNumber,
+}
 
 /// A digester working directly on a Cardano DB immutables files
+///
+/// The digests of the immutable files are kept in a [MemoryImmutableFileDigestCacheProvider]
+/// between two computations on the same Cardano DB, so that computing the digest of a new beacon
+/// only reads the immutable files added since the previous beacon.
+///
+/// The known digests are invalidated when the digest is computed on another directory or with
+/// another hash function, or when the Cardano DB ends before the last immutable file known (the
+/// database was replaced or truncated).
 pub struct CardanoImmutableDigester {
     /// A [ImmutableFileDigestCacheProvider] instance
     cache_provider: Option<Arc<dyn ImmutableFileDigestCacheProvider>>,
 
+    /// Hash function used to compute the digest
+    hash_algorithm: HashAlgorithm,
+
+    /// If set, the hash function is the one of the current era instead of [Self::hash_algorithm]
+    era_checker: Option<Arc<EraChecker>>,
+
+    /// Digests of the immutable files computed or read from the cache by the previous computations
+    known_digests: MemoryImmutableFileDigestCacheProvider,
+
+    /// Cardano DB and hash function of the [Self::known_digests]
+    known_digests_origin: Mutex<Option<KnownDigestsOrigin>>,
+
     /// The logger where the logs should be written
     logger: Logger,
 }
@@ -33,9 +67,129 @@ impl CardanoImmutableDigester {
     ) -> Self {
         Self {
             cache_provider,
+            hash_algorithm: HashAlgorithm::default(),
+            era_checker: None,
+            known_digests: MemoryImmutableFileDigestCacheProvider::default(),
+            known_digests_origin: Mutex::new(None),
             logger: logger.new_with_component_name::<Self>(),
         }
     }
+
+    /// Set the hash function used to compute the digest.
+    ///
+    /// The cached immutable files digests are computed with the default algorithm, so the cache
+    /// is not used with any other algorithm.
+    pub fn with_hash_algorithm(mut self, hash_algorithm: HashAlgorithm) -> Self {
+        self.hash_algorithm = hash_algorithm;
+        self
+    }
+
+    /// Use the hash function of the current era of the given [EraChecker], read at each
+    /// computation so that the digests follow the era changes.
+    pub fn with_era_checker(mut self, era_checker: Arc<EraChecker>) -> Self {
+        self.era_checker = Some(era_checker);
+        self
+    }
+
+    fn hash_algorithm(&self) -> HashAlgorithm {
+        self.era_checker
+            .as_ref()
+            .map(|era_checker| era_checker.hash_algorithm())
+            .unwrap_or(self.hash_algorithm)
+    }
+
+    fn cache_provider(
+        &self,
+        hash_algorithm: HashAlgorithm,
+    ) -> Option<&Arc<dyn ImmutableFileDigestCacheProvider>> {
+        self.cache_provider
+            .as_ref()
+            .filter(|_| hash_algorithm.is_default())
+    }
+
+    /// Reset the known digests if they were not computed on the same Cardano DB with the same
+    /// hash function, and record the origin of the digests of this computation
+    async fn invalidate_known_digests(
+        &self,
+        dirpath: &Path,
+        hash_algorithm: HashAlgorithm,
+        last_immutable_file_number: ImmutableFileNumber,
+    ) {
+        let is_same_origin = {
+            let mut known_digests_origin = self.known_digests_origin.lock().unwrap();
+            let is_same_origin = known_digests_origin.as_ref().is_some_and(|origin| {
+                origin.dirpath == dirpath
+                    && origin.hash_algorithm == hash_algorithm
+                    && origin.last_immutable_file_number <= last_immutable_file_number
+            });
+            *known_digests_origin = Some(KnownDigestsOrigin {
+                dirpath: dirpath.to_path_buf(),
+                hash_algorithm,
+                last_immutable_file_number,
+            });
+            is_same_origin
+        };
+
+        if !is_same_origin {
+            debug!(
+                self.logger,
+                "Invalidating the known immutable files digests"
+            );
+            if let Err(error) = self.known_digests.reset().await {
+                warn!(
+                    self.logger, "Error while resetting the known immutable files digests";
+                    "error" => ?error
+                );
+            }
+        }
+    }
+
+    async fn get_known_values(
+        &self,
+        immutables: Vec<ImmutableFile>,
+    ) -> BTreeMap<ImmutableFile, Option<HexEncodedDigest>> {
+        match self.known_digests.get(immutables.clone()).await {
+            Ok(values) => values,
+            Err(error) => {
+                warn!(
+                    self.logger, "Error while getting known immutable files digests";
+                    "error" => ?error
+                );
+                BTreeMap::from_iter(immutables.into_iter().map(|i| (i, None)))
+            }
+        }
+    }
+
+    async fn store_known_values(&self, digests: Vec<(ImmutableFileName, HexEncodedDigest)>) {
+        if let Err(error) = self.known_digests.store(digests).await {
+            warn!(
+                self.logger, "Error while storing known immutable files digests";
+                "error" => ?error
+            );
+        }
+    }
+
+    async fn get_cached_values(
+        &self,
+        immutables: Vec<ImmutableFile>,
+        hash_algorithm: HashAlgorithm,
+    ) -> BTreeMap<ImmutableFile, Option<HexEncodedDigest>> {
+        match self.cache_provider(hash_algorithm) {
+            Some(cache_provider) if !immutables.is_empty() => {
+                match cache_provider.get(immutables.clone()).await {
+                    Ok(values) => values,
+                    Err(error) => {
+                        warn!(
+                            self.logger, "Error while getting cached immutable files digests";
+                            "error" => ?error
+                        );
+                        BTreeMap::from_iter(immutables.into_iter().map(|i| (i, None)))
+                    }
+                }
+            }
+            _ => BTreeMap::from_iter(immutables.into_iter().map(|i| (i, None))),
+        }
+    }
 }
 
 #[async_trait]
@@ -46,11 +200,17 @@ impl ImmutableDigester for CardanoImmutableDigester {
         beacon: &CardanoDbBeacon,
     ) -> Result<String, ImmutableDigesterError> {
         let up_to_file_number = beacon.immutable_file_number;
-        let immutables = ImmutableFile::list_completed_in_dir(dirpath)?
+        let completed_immutables = ImmutableFile::list_completed_in_dir(dirpath)?;
+        let last_completed_immutable_file_number = completed_immutables
+            .last()
+            .map(|f| f.number)
+            .unwrap_or_default();
+        let immutables = completed_immutables
             .into_iter()
             .filter(|f| f.number <= up_to_file_number)
             .collect::<Vec<_>>();
-        info!(self.logger, ">> compute_digest"; "beacon" => #?beacon, "nb_of_immutables" => immutables.len());
+        let hash_algorithm = self.hash_algorithm();
+        info!(self.logger, ">> compute_digest"; "beacon" => #?beacon, "nb_of_immutables" => immutables.len(), "hash_algorithm" => %hash_algorithm);
 
         match immutables.last() {
             None => Err(ImmutableDigesterError::NotEnoughImmutable {
@@ -66,26 +226,51 @@ impl ImmutableDigester for CardanoImmutableDigester {
                 })
             }
             Some(_) => {
-                let cached_values = match self.cache_provider.as_ref() {
-                    None => BTreeMap::from_iter(immutables.into_iter().map(|i| (i, None))),
-                    Some(cache_provider) => match cache_provider.get(immutables.clone()).await {
-                        Ok(values) => values,
-                        Err(error) => {
-                            warn!(
-                                self.logger, "Error while getting cached immutable files digests";
-                                "error" => ?error
-                            );
-                            BTreeMap::from_iter(immutables.into_iter().map(|i| (i, None)))
-                        }
-                    },
-                };
+                self.invalidate_known_digests(
+                    dirpath,
+                    hash_algorithm,
+                    last_completed_immutable_file_number,
+                )
+                .await;
+                let (mut cached_values, unknown_immutables): (BTreeMap<_, _>, BTreeMap<_, _>) =
+                    self.get_known_values(immutables)
+                        .await
+                        .into_iter()
+                        .partition(|(_, digest)| digest.is_some());
+                debug!(
+                    self.logger, "Immutable files digests known from the previous computations";
+                    "nb_of_known_immutables" => cached_values.len()
+                );
+
+                let persisted_values = self
+                    .get_cached_values(unknown_immutables.into_keys().collect(), hash_algorithm)
+                    .await;
+                self.store_known_values(
+                    persisted_values
+                        .iter()
+                        .filter_map(|(immutable, digest)| {
+                            digest
+                                .as_ref()
+                                .map(|digest| (immutable.filename.clone(), digest.clone()))
+                        })
+                        .collect(),
+                )
+                .await;
+                cached_values.extend(persisted_values);
 
                 // digest is done in a separate thread because it is blocking the whole task
                 let logger = self.logger.clone();
                 let thread_beacon = beacon.clone();
                 let (hash, new_cache_entries) =
                     tokio::task::spawn_blocking(move || -> CacheComputationResult {
-                        compute_hash(logger, &thread_beacon, cached_values)
+                        match hash_algorithm {
+                            HashAlgorithm::Sha256 => {
+                                compute_hash::<Sha256>(logger, &thread_beacon, cached_values)
+                            }
+                            HashAlgorithm::Blake2b256 => {
+                                compute_hash::<Blake2b256>(logger, &thread_beacon, cached_values)
+                            }
+                        }
                     })
                     .await
                     .map_err(|e| ImmutableDigesterError::DigestComputationError(e.into()))??;
@@ -93,7 +278,9 @@ impl ImmutableDigester for CardanoImmutableDigester {
 
                 debug!(self.logger, "Computed digest: {digest:?}");
 
-                if let Some(cache_provider) = self.cache_provider.as_ref() {
+                self.store_known_values(new_cache_entries.clone()).await;
+
+                if let Some(cache_provider) = self.cache_provider(hash_algorithm) {
                     if let Err(error) = cache_provider.store(new_cache_entries).await {
                         warn!(
                             self.logger, "Error while storing new immutable files digests to cache";
@@ -108,12 +295,12 @@ impl ImmutableDigester for CardanoImmutableDigester {
     }
 }
 
-fn compute_hash(
+fn compute_hash<D: Digest + io::Write>(
     logger: Logger,
     beacon: &CardanoDbBeacon,
     entries: BTreeMap<ImmutableFile, Option<HexEncodedDigest>>,
 ) -> CacheComputationResult {
-    let mut hasher = Sha256::new();
+    let mut hasher = D::new();
     let mut new_cached_entries = Vec::new();
     let mut progress = Progress {
         index: 0,
@@ -125,7 +312,7 @@ fn compute_hash(
     for (ix, (entry, cache)) in entries.iter().enumerate() {
         match cache {
             None => {
-                let data = hex::encode(entry.compute_raw_hash::<Sha256>()?);
+                let data = hex::encode(entry.compute_raw_hash::<D>()?);
                 hasher.update(&data);
                 new_cached_entries.push((entry.filename.clone(), data));
             }
@@ -139,7 +326,7 @@ fn compute_hash(
         }
     }
 
-    Ok((hasher.finalize().into(), new_cached_entries))
+    Ok((hasher.finalize().to_vec(), new_cached_entries))
 }
 
 struct Progress {
@@ -177,7 +364,9 @@ mod tests {
             CardanoImmutableDigester, DummyImmutablesDbBuilder, ImmutableDigester,
             ImmutableDigesterError,
         },
-        entities::{CardanoDbBeacon, ImmutableFileNumber},
+        entities::{CardanoDbBeacon, Epoch, ImmutableFileNumber},
+        era::{EraChecker, SupportedEra},
+        protocol::HashAlgorithm,
         test_utils::TestLogger,
     };
     use sha2::Sha256;
@@ -361,6 +550,66 @@ mod tests {
         assert_eq!(expected, cached_entries);
     }
 
+    #[tokio::test]
+    async fn digest_with_another_hash_algorithm_differs_and_bypasses_the_cache() {
+        let immutable_db =
+            db_builder("digest_with_another_hash_algorithm_differs_and_bypasses_the_cache")
+                .with_immutables(&[1, 2])
+                .append_immutable_trio()
+                .build();
+        let immutables = immutable_db.immutables_files;
+        let cache = Arc::new(MemoryImmutableFileDigestCacheProvider::default());
+        let logger = TestLogger::stdout();
+        let sha256_digester = CardanoImmutableDigester::new(None, logger.clone());
+        let blake2b256_digester = CardanoImmutableDigester::new(Some(cache.clone()), logger)
+            .with_hash_algorithm(HashAlgorithm::Blake2b256);
+        let beacon = CardanoDbBeacon::new("devnet".to_string(), 1, 2);
+
+        let sha256_digest = sha256_digester
+            .compute_digest(&immutable_db.dir, &beacon)
+            .await
+            .expect("compute_digest must not fail");
+        let blake2b256_digest = blake2b256_digester
+            .compute_digest(&immutable_db.dir, &beacon)
+            .await
+            .expect("compute_digest must not fail");
+
+        assert_ne!(sha256_digest, blake2b256_digest);
+        let cached_entries = cache
+            .get(immutables.clone())
+            .await
+            .expect("Cache read should not fail");
+        assert!(cached_entries.values().all(Option::is_none));
+    }
+
+    #[tokio::test]
+    async fn digest_with_an_era_checker_uses_the_hash_algorithm_of_the_current_era() {
+        let immutable_db =
+            db_builder("digest_with_an_era_checker_uses_the_hash_algorithm_of_the_current_era")
+                .with_immutables(&[1, 2])
+                .append_immutable_trio()
+                .build();
+        let logger = TestLogger::stdout();
+        let era_checker = Arc::new(EraChecker::new(SupportedEra::dummy(), Epoch(1)));
+        let era_digester = CardanoImmutableDigester::new(None, logger.clone())
+            .with_hash_algorithm(HashAlgorithm::Blake2b256)
+            .with_era_checker(era_checker.clone());
+        let era_algorithm_digester = CardanoImmutableDigester::new(None, logger)
+            .with_hash_algorithm(era_checker.hash_algorithm());
+        let beacon = CardanoDbBeacon::new("devnet".to_string(), 1, 2);
+
+        let era_digest = era_digester
+            .compute_digest(&immutable_db.dir, &beacon)
+            .await
+            .expect("compute_digest must not fail");
+        let expected_digest = era_algorithm_digester
+            .compute_digest(&immutable_db.dir, &beacon)
+            .await
+            .expect("compute_digest must not fail");
+
+        assert_eq!(expected_digest, era_digest);
+    }
+
     #[tokio::test]
     async fn computed_digest_with_cold_or_hot_or_without_any_cache_are_equals() {
         let immutable_db = DummyImmutablesDbBuilder::new(
@@ -403,6 +652,114 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn only_the_new_immutable_files_are_digested_for_the_next_beacon() {
+        let immutable_db =
+            db_builder("only_the_new_immutable_files_are_digested_for_the_next_beacon")
+                .with_immutables(&[1, 2, 3])
+                .append_immutable_trio()
+                .build();
+        let mut cache = MockImmutableFileDigestCacheProvider::new();
+        cache
+            .expect_get()
+            .withf(|immutables| immutables.iter().all(|i| i.number <= 2))
+            .returning(|immutables| Ok(immutables.into_iter().map(|i| (i, None)).collect()))
+            .once();
+        cache
+            .expect_get()
+            .withf(|immutables| immutables.iter().all(|i| i.number == 3))
+            .returning(|immutables| Ok(immutables.into_iter().map(|i| (i, None)).collect()))
+            .once();
+        cache.expect_store().returning(|_| Ok(()));
+        let logger = TestLogger::stdout();
+        let digester = CardanoImmutableDigester::new(Some(Arc::new(cache)), logger.clone());
+
+        digester
+            .compute_digest(
+                &immutable_db.dir,
+                &CardanoDbBeacon::new("devnet".to_string(), 1, 2),
+            )
+            .await
+            .expect("compute_digest must not fail");
+        let beacon = CardanoDbBeacon::new("devnet".to_string(), 1, 3);
+        let incremental_digest = digester
+            .compute_digest(&immutable_db.dir, &beacon)
+            .await
+            .expect("compute_digest must not fail");
+
+        let full_digest = CardanoImmutableDigester::new(None, logger)
+            .compute_digest(&immutable_db.dir, &beacon)
+            .await
+            .expect("compute_digest must not fail");
+        assert_eq!(full_digest, incremental_digest);
+    }
+
+    #[tokio::test]
+    async fn known_digests_are_not_reused_after_a_hash_algorithm_change() {
+        let immutable_db = db_builder("known_digests_are_not_reused_after_a_hash_algorithm_change")
+            .with_immutables(&[1, 2, 3])
+            .append_immutable_trio()
+            .build();
+        let logger = TestLogger::stdout();
+        let mut digester = CardanoImmutableDigester::new(None, logger.clone());
+        digester
+            .compute_digest(
+                &immutable_db.dir,
+                &CardanoDbBeacon::new("devnet".to_string(), 1, 2),
+            )
+            .await
+            .expect("compute_digest must not fail");
+
+        digester.hash_algorithm = HashAlgorithm::Blake2b256;
+        let beacon = CardanoDbBeacon::new("devnet".to_string(), 1, 3);
+        let incremental_digest = digester
+            .compute_digest(&immutable_db.dir, &beacon)
+            .await
+            .expect("compute_digest must not fail");
+
+        let full_digest = CardanoImmutableDigester::new(None, logger)
+            .with_hash_algorithm(HashAlgorithm::Blake2b256)
+            .compute_digest(&immutable_db.dir, &beacon)
+            .await
+            .expect("compute_digest must not fail");
+        assert_eq!(full_digest, incremental_digest);
+    }
+
+    #[tokio::test]
+    async fn known_digests_are_invalidated_when_the_cardano_db_is_replaced() {
+        let test_name = "known_digests_are_invalidated_when_the_cardano_db_is_replaced";
+        let immutable_db = db_builder(test_name)
+            .with_immutables(&[1, 2, 3, 4])
+            .append_immutable_trio()
+            .build();
+        let logger = TestLogger::stdout();
+        let digester = CardanoImmutableDigester::new(None, logger.clone());
+        digester
+            .compute_digest(
+                &immutable_db.dir,
+                &CardanoDbBeacon::new("devnet".to_string(), 1, 4),
+            )
+            .await
+            .expect("compute_digest must not fail");
+
+        let replaced_immutable_db = db_builder(test_name)
+            .with_immutables(&[1, 2])
+            .append_immutable_trio()
+            .set_file_size(1024)
+            .build();
+        let beacon = CardanoDbBeacon::new("devnet".to_string(), 1, 2);
+        let digest_after_replacement = digester
+            .compute_digest(&replaced_immutable_db.dir, &beacon)
+            .await
+            .expect("compute_digest must not fail");
+
+        let full_digest = CardanoImmutableDigester::new(None, logger)
+            .compute_digest(&replaced_immutable_db.dir, &beacon)
+            .await
+            .expect("compute_digest must not fail");
+        assert_eq!(full_digest, digest_after_replacement);
+    }
+
     #[tokio::test]
     async fn hash_computation_is_quicker_with_a_full_cache() {
         let immutable_db = db_builder("hash_computation_is_quicker_with_a_full_cache")
@@ -0,0 +1,145 @@
+use slog::{debug, Logger};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::sleep;
+
+use crate::digesters::ImmutableFileObserver;
+use crate::entities::ImmutableFileNumber;
+use crate::logging::LoggerExtensions;
+
+/// Watch a cardano database for new immutable files, so that the nodes can react as soon as an
+/// immutable file is completed instead of waiting for their next tick.
+///
+/// The watcher polls an [ImmutableFileObserver] at a short interval, which is cheap since only
+/// the file names of the immutable directory are listed.
+pub struct ImmutableFileWatcher {
+    observer: Arc<dyn ImmutableFileObserver>,
+    poll_interval: Duration,
+    last_immutable_file_number: Mutex<Option<ImmutableFileNumber>>,
+    logger: Logger,
+}
+
+impl ImmutableFileWatcher {
+    /// [ImmutableFileWatcher] factory
+    pub fn new(
+        observer: Arc<dyn ImmutableFileObserver>,
+        poll_interval: Duration,
+        logger: Logger,
+    ) -> Self {
+        Self {
+            observer,
+            poll_interval,
+            last_immutable_file_number: Mutex::new(None),
+            logger: logger.new_with_component_name::<Self>(),
+        }
+    }
+
+    /// Check once for a new immutable file, returns its number if one was completed since the
+    /// previous check.
+    ///
+    /// The first check only records the last immutable file number and never reports it as new.
+    pub async fn check_new_immutable_file(&self) -> Option<ImmutableFileNumber> {
+        let immutable_file_number = match self.observer.get_last_immutable_number().await {
+            Ok(number) => number,
+            Err(error) => {
+                debug!(self.logger, "Could not get the last immutable file number"; "error" => ?error);
+                return None;
+            }
+        };
+
+        let mut last_immutable_file_number = self.last_immutable_file_number.lock().await;
+        match *last_immutable_file_number {
+            Some(last_number) if last_number >= immutable_file_number => None,
+            previous_number => {
+                *last_immutable_file_number = Some(immutable_file_number);
+                previous_number.map(|_| immutable_file_number)
+            }
+        }
+    }
+
+    /// Wait until a new immutable file is completed and return its number.
+    ///
+    /// This future is cancel safe: it can be used as a branch of a `tokio::select!`.
+    pub async fn wait_for_new_immutable_file(&self) -> ImmutableFileNumber {
+        loop {
+            if let Some(immutable_file_number) = self.check_new_immutable_file().await {
+                debug!(
+                    self.logger, "New immutable file detected";
+                    "immutable_file_number" => immutable_file_number
+                );
+                return immutable_file_number;
+            }
+
+            sleep(self.poll_interval).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::digesters::DumbImmutableFileObserver;
+    use crate::test_utils::TestLogger;
+
+    use super::*;
+
+    fn build_watcher(observer: Arc<DumbImmutableFileObserver>) -> ImmutableFileWatcher {
+        ImmutableFileWatcher::new(observer, Duration::from_millis(1), TestLogger::stdout())
+    }
+
+    #[tokio::test]
+    async fn the_first_check_does_not_report_a_new_immutable_file() {
+        let observer = Arc::new(DumbImmutableFileObserver::default());
+        let watcher = build_watcher(observer);
+
+        assert_eq!(None, watcher.check_new_immutable_file().await);
+    }
+
+    #[tokio::test]
+    async fn report_a_new_immutable_file_only_once() {
+        let observer = Arc::new(DumbImmutableFileObserver::default());
+        let watcher = build_watcher(observer.clone());
+        watcher.check_new_immutable_file().await;
+
+        let new_number = observer.increase().await.unwrap();
+
+        assert_eq!(Some(new_number), watcher.check_new_immutable_file().await);
+        assert_eq!(None, watcher.check_new_immutable_file().await);
+    }
+
+    #[tokio::test]
+    async fn do_not_report_an_immutable_file_number_going_backward() {
+        let observer = Arc::new(DumbImmutableFileObserver::default());
+        let watcher = build_watcher(observer.clone());
+        watcher.check_new_immutable_file().await;
+
+        observer.shall_return(Some(10)).await;
+
+        assert_eq!(None, watcher.check_new_immutable_file().await);
+    }
+
+    #[tokio::test]
+    async fn do_not_report_anything_while_the_immutable_files_are_missing() {
+        let observer = Arc::new(DumbImmutableFileObserver::new());
+        let watcher = build_watcher(observer.clone());
+
+        assert_eq!(None, watcher.check_new_immutable_file().await);
+
+        observer.shall_return(Some(3)).await;
+        assert_eq!(None, watcher.check_new_immutable_file().await);
+    }
+
+    #[tokio::test]
+    async fn wait_for_new_immutable_file_returns_when_a_new_immutable_file_is_completed() {
+        let observer = Arc::new(DumbImmutableFileObserver::default());
+        let watcher = build_watcher(observer.clone());
+        watcher.check_new_immutable_file().await;
+
+        let (new_number, _) = tokio::join!(watcher.wait_for_new_immutable_file(), async {
+            sleep(Duration::from_millis(10)).await;
+            observer.increase().await.unwrap()
+        });
+
+        assert_eq!(501, new_number);
+    }
+}
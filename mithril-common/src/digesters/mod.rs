@@ -6,6 +6,7 @@ mod dumb_immutable_observer;
 mod immutable_digester;
 mod immutable_file;
 mod immutable_file_observer;
+mod immutable_file_watcher;
 
 pub use cardano_immutable_digester::CardanoImmutableDigester;
 pub use immutable_digester::{ImmutableDigester, ImmutableDigesterError};
@@ -14,6 +15,7 @@ pub use immutable_file_observer::{
     DumbImmutableFileObserver, ImmutableFileObserver, ImmutableFileObserverError,
     ImmutableFileSystemObserver,
 };
+pub use immutable_file_watcher::ImmutableFileWatcher;
 
 pub use dumb_immutable_observer::DumbImmutableDigester;
 
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
+use std::str::FromStr;
 use thiserror::Error;
 
 use crate::{MagicId, StdResult};
@@ -60,9 +61,21 @@ impl CardanoNetwork {
                     ))
                 }
             }
-            what => Err(CardanoNetworkError::ParseFromCode(format!(
-                "could not parse network '{what}', the only recognized networks are: mainnet, devnet, testnet, preview, preprod and private"
-            ))),
+            what => match what.parse::<MagicId>() {
+                Ok(magic) => Ok(CardanoNetwork::from_magic_id(magic)),
+                Err(_) => Err(CardanoNetworkError::ParseFromCode(format!(
+                    "could not parse network '{what}', the only recognized networks are: mainnet, devnet, testnet, preview, preprod, private, or a network magic number"
+                ))),
+            },
+        }
+    }
+
+    /// Instantiates the CardanoNetwork of the given magic id, any magic id that is not the one of
+    /// the mainnet is a test network (either public or private)
+    pub fn from_magic_id(magic_id: MagicId) -> CardanoNetwork {
+        match magic_id {
+            MAINNET_MAGIC_ID => CardanoNetwork::MainNet,
+            magic_id => CardanoNetwork::TestNet(magic_id),
         }
     }
 
@@ -75,6 +88,19 @@ impl CardanoNetwork {
         }
     }
 
+    /// Check if the network is a private network: a devnet or a test network whose magic is not
+    /// the one of the mainnet or of a public test network
+    pub fn is_private(&self) -> bool {
+        !matches!(
+            self.code(),
+            MAINNET_MAGIC_ID
+                | TESTNET_MAGIC_ID
+                | PREVIEW_MAGIC_ID
+                | PREPROD_MAGIC_ID
+                | SANCHONET_MAGIC_ID
+        )
+    }
+
     /// Determines whether unparsable blocks should be allowed based on the specific Cardano network.
     pub fn compute_allow_unparsable_block(&self, allow_unparsable_block: bool) -> StdResult<bool> {
         let allow_unparsable_block = match self {
@@ -103,6 +129,26 @@ impl Display for CardanoNetwork {
     }
 }
 
+impl FromStr for CardanoNetwork {
+    type Err = CardanoNetworkError;
+
+    /// Parse a network given as its code, optionally followed by its magic id separated by a
+    /// colon (ie: `preview`, `devnet:42`, `private:1234`), or as its magic id alone (ie: `1234`)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            Some((network_code, network_magic)) => {
+                let network_magic = network_magic.parse::<MagicId>().map_err(|_| {
+                    CardanoNetworkError::ParseFromCode(format!(
+                        "invalid network magic '{network_magic}' for network '{network_code}'"
+                    ))
+                })?;
+                Self::from_code(network_code.to_string(), Some(network_magic))
+            }
+            None => Self::from_code(s.to_string(), None),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -154,6 +200,58 @@ mod tests {
             CardanoNetwork::TestNet(123)
         );
         assert!(CardanoNetwork::from_code("private".to_string(), None).is_err());
+        assert_eq!(
+            CardanoNetwork::from_code("1234".to_string(), None).unwrap(),
+            CardanoNetwork::TestNet(1234)
+        );
+        assert_eq!(
+            CardanoNetwork::from_code(MAINNET_MAGIC_ID.to_string(), None).unwrap(),
+            CardanoNetwork::MainNet
+        );
+        assert!(CardanoNetwork::from_code("unknown".to_string(), None).is_err());
+    }
+
+    #[test]
+    fn parse_cardano_network_from_str() {
+        assert_eq!(
+            CardanoNetwork::MainNet,
+            "mainnet".parse::<CardanoNetwork>().unwrap()
+        );
+        assert_eq!(
+            CardanoNetwork::TestNet(PREVIEW_MAGIC_ID),
+            "preview".parse::<CardanoNetwork>().unwrap()
+        );
+        assert_eq!(
+            CardanoNetwork::DevNet(42),
+            "devnet:42".parse::<CardanoNetwork>().unwrap()
+        );
+        assert_eq!(
+            CardanoNetwork::TestNet(1234),
+            "private:1234".parse::<CardanoNetwork>().unwrap()
+        );
+        assert_eq!(
+            CardanoNetwork::TestNet(1234),
+            "1234".parse::<CardanoNetwork>().unwrap()
+        );
+        assert!("devnet".parse::<CardanoNetwork>().is_err());
+        assert!("devnet:not_a_magic".parse::<CardanoNetwork>().is_err());
+    }
+
+    #[test]
+    fn only_the_networks_with_an_unknown_magic_are_private() {
+        assert!(!CardanoNetwork::MainNet.is_private());
+        assert!(!CardanoNetwork::TestNet(PREPROD_MAGIC_ID).is_private());
+        assert!(!CardanoNetwork::TestNet(PREVIEW_MAGIC_ID).is_private());
+        assert!(CardanoNetwork::TestNet(1234).is_private());
+        assert!(CardanoNetwork::DevNet(1234).is_private());
+    }
+
+    #[test]
+    fn devnets_with_the_magic_of_a_public_network_are_not_private() {
+        assert!(!CardanoNetwork::DevNet(MAINNET_MAGIC_ID).is_private());
+        assert!(!CardanoNetwork::DevNet(PREPROD_MAGIC_ID).is_private());
+        assert!(!CardanoNetwork::DevNet(PREVIEW_MAGIC_ID).is_private());
+        assert!(!CardanoNetwork::DevNet(SANCHONET_MAGIC_ID).is_private());
     }
 
     #[test]
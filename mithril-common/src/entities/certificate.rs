@@ -2,6 +2,7 @@ use crate::crypto_helper::{
     ProtocolAggregateVerificationKey, ProtocolGenesisSignature, ProtocolMultiSignature,
 };
 use crate::entities::{CertificateMetadata, Epoch, ProtocolMessage, SignedEntityType};
+use crate::protocol::{Blake2b256, HashAlgorithm};
 use std::fmt::{Debug, Formatter};
 
 use sha2::{Digest, Sha256};
@@ -81,9 +82,17 @@ impl Certificate {
         certificate
     }
 
-    /// Computes the hash of a Certificate
+    /// Computes the hash of a Certificate, using the hash function of its
+    /// [metadata][CertificateMetadata::hash_algorithm]
     pub fn compute_hash(&self) -> String {
-        let mut hasher = Sha256::new();
+        match self.metadata.hash_algorithm {
+            HashAlgorithm::Sha256 => self.compute_hash_with::<Sha256>(),
+            HashAlgorithm::Blake2b256 => self.compute_hash_with::<Blake2b256>(),
+        }
+    }
+
+    fn compute_hash_with<D: Digest>(&self) -> String {
+        let mut hasher = D::new();
         hasher.update(self.previous_hash.as_bytes());
         hasher.update(self.epoch.to_be_bytes());
         hasher.update(self.metadata.compute_hash().as_bytes());
@@ -270,6 +279,18 @@ mod tests {
             .compute_hash(),
         );
 
+        assert_ne!(
+            HASH_EXPECTED,
+            Certificate {
+                metadata: certificate
+                    .metadata
+                    .clone()
+                    .with_hash_algorithm(HashAlgorithm::Blake2b256),
+                ..certificate.clone()
+            }
+            .compute_hash(),
+        );
+
         assert_ne!(
             HASH_EXPECTED,
             Certificate {
@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 
 use crate::entities::{ProtocolParameters, ProtocolVersion, SignerWithStake, StakeDistribution};
+use crate::protocol::HashAlgorithm;
 
 use super::{PartyId, Stake};
 
@@ -69,6 +70,10 @@ pub struct CertificateMetadata {
     /// The list of the active signers with their stakes and verification keys
     /// part of METADATA(p,n)
     pub signers: Vec<StakeDistributionParty>,
+
+    /// Hash function used to compute the hash of the certificate and the digest of its artifact
+    /// part of METADATA(p,n)
+    pub hash_algorithm: HashAlgorithm,
 }
 
 impl CertificateMetadata {
@@ -88,9 +93,16 @@ impl CertificateMetadata {
             initiated_at,
             sealed_at,
             signers,
+            hash_algorithm: HashAlgorithm::default(),
         }
     }
 
+    /// Set the hash function used to compute the hash of the certificate
+    pub fn with_hash_algorithm(mut self, hash_algorithm: HashAlgorithm) -> Self {
+        self.hash_algorithm = hash_algorithm;
+        self
+    }
+
     /// Deduce the stake distribution from the metadata [signers][CertificateMetadata::signers]
     pub fn get_stake_distribution(&self) -> StakeDistribution {
         self.signers
@@ -122,6 +134,11 @@ impl CertificateMetadata {
         for party in &self.signers {
             hasher.update(party.compute_hash().as_bytes());
         }
+        // The default algorithm is not hashed so that the hashes of the existing certificates
+        // are unchanged
+        if !self.hash_algorithm.is_default() {
+            hasher.update(self.hash_algorithm.to_string().as_bytes());
+        }
 
         hex::encode(hasher.finalize())
     }
@@ -226,5 +243,13 @@ mod tests {
             }
             .compute_hash(),
         );
+
+        assert_ne!(
+            hash_expected,
+            metadata
+                .clone()
+                .with_hash_algorithm(HashAlgorithm::Blake2b256)
+                .compute_hash(),
+        );
     }
 }
@@ -2,6 +2,7 @@ use std::fmt::Display;
 
 use serde::{Deserialize, Serialize};
 
+use crate::error_codes::MithrilErrorCode;
 use crate::StdError;
 
 /// Representation of a Server Error raised by a http server
@@ -9,6 +10,10 @@ use crate::StdError;
 pub struct ServerError {
     /// error message
     pub message: String,
+
+    /// error code from the [error catalog][crate::error_codes]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<MithrilErrorCode>,
 }
 
 impl ServerError {
@@ -16,8 +21,15 @@ impl ServerError {
     pub fn new<M: Into<String>>(message: M) -> ServerError {
         ServerError {
             message: message.into(),
+            code: None,
         }
     }
+
+    /// Set the error code
+    pub fn with_code(mut self, code: MithrilErrorCode) -> Self {
+        self.code = Some(code);
+        self
+    }
 }
 
 impl Display for ServerError {
@@ -52,6 +64,10 @@ pub struct ClientError {
 
     /// error message
     pub message: String,
+
+    /// error code from the [error catalog][crate::error_codes]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<MithrilErrorCode>,
 }
 
 impl ClientError {
@@ -60,8 +76,15 @@ impl ClientError {
         ClientError {
             label: label.into(),
             message: message.into(),
+            code: None,
         }
     }
+
+    /// Set the error code
+    pub fn with_code(mut self, code: MithrilErrorCode) -> Self {
+        self.code = Some(code);
+        self
+    }
 }
 
 impl Display for ClientError {
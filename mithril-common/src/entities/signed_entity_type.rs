@@ -3,14 +3,13 @@ use std::str::FromStr;
 use std::time::Duration;
 
 use anyhow::anyhow;
-use digest::Update;
+use digest::Digest;
 use serde::{Deserialize, Serialize};
-use sha2::Sha256;
 use strum::{AsRefStr, Display, EnumDiscriminants, EnumIter, EnumString, IntoEnumIterator};
 
 use crate::StdResult;
 
-use super::{BlockNumber, CardanoDbBeacon, Epoch};
+use super::{BlockNumber, CardanoDbBeacon, Epoch, TimePointBeacon};
 
 /// Database representation of the SignedEntityType::MithrilStakeDistribution value
 const ENTITY_TYPE_MITHRIL_STAKE_DISTRIBUTION: usize = 0;
@@ -31,7 +30,7 @@ const ENTITY_TYPE_CARDANO_TRANSACTIONS: usize = 3;
 /// modification of this type should only ever consist of appending new
 /// variants.
 // Important note: The order of the variants is important as it is used for the derived Ord trait.
-#[derive(Display, Debug, Clone, PartialEq, Eq, Serialize, Deserialize, EnumDiscriminants)]
+#[derive(Display, Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, EnumDiscriminants)]
 #[strum(serialize_all = "PascalCase")]
 #[strum_discriminants(derive(
     Display,
@@ -68,16 +67,26 @@ impl SignedEntityType {
         Self::MithrilStakeDistribution(epoch)
     }
 
-    /// Return the epoch from the signed entity.
-    pub fn get_epoch(&self) -> Epoch {
+    /// Return the point of the chain at which the signed entity is signed.
+    pub fn time_point_beacon(&self) -> TimePointBeacon {
         match self {
-            Self::CardanoImmutableFilesFull(b) => b.epoch,
-            Self::CardanoStakeDistribution(e)
-            | Self::MithrilStakeDistribution(e)
-            | Self::CardanoTransactions(e, _) => *e,
+            Self::MithrilStakeDistribution(epoch) | Self::CardanoStakeDistribution(epoch) => {
+                TimePointBeacon::Epoch(*epoch)
+            }
+            Self::CardanoImmutableFilesFull(beacon) => {
+                TimePointBeacon::ImmutableFileNumber(beacon.epoch, beacon.immutable_file_number)
+            }
+            Self::CardanoTransactions(epoch, block_number) => {
+                TimePointBeacon::BlockNumber(*epoch, *block_number)
+            }
         }
     }
 
+    /// Return the epoch from the signed entity.
+    pub fn get_epoch(&self) -> Epoch {
+        self.time_point_beacon().epoch()
+    }
+
     /// Return the epoch at which the signed entity type is signed.
     pub fn get_epoch_when_signed_entity_type_is_signed(&self) -> Epoch {
         match self {
@@ -125,7 +134,7 @@ impl SignedEntityType {
         }
     }
 
-    pub(crate) fn feed_hash(&self, hasher: &mut Sha256) {
+    pub(crate) fn feed_hash<D: Digest>(&self, hasher: &mut D) {
         match self {
             SignedEntityType::MithrilStakeDistribution(epoch)
             | SignedEntityType::CardanoStakeDistribution(epoch) => {
@@ -224,7 +233,7 @@ Accepted values are (case-sensitive): {}."#,
 
 #[cfg(test)]
 mod tests {
-    use digest::Digest;
+    use sha2::Sha256;
 
     use crate::test_utils::assert_same_json;
 
@@ -272,6 +281,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn time_point_beacon_of_the_signed_entity_types() {
+        assert_eq!(
+            TimePointBeacon::Epoch(Epoch(3)),
+            SignedEntityType::MithrilStakeDistribution(Epoch(3)).time_point_beacon()
+        );
+        assert_eq!(
+            TimePointBeacon::Epoch(Epoch(3)),
+            SignedEntityType::CardanoStakeDistribution(Epoch(3)).time_point_beacon()
+        );
+        assert_eq!(
+            TimePointBeacon::ImmutableFileNumber(Epoch(3), 100),
+            SignedEntityType::CardanoImmutableFilesFull(CardanoDbBeacon::new("network", 3, 100))
+                .time_point_beacon()
+        );
+        assert_eq!(
+            TimePointBeacon::BlockNumber(Epoch(3), BlockNumber(77)),
+            SignedEntityType::CardanoTransactions(Epoch(3), BlockNumber(77)).time_point_beacon()
+        );
+    }
+
     #[test]
     fn verify_signed_entity_type_properties_are_included_in_computed_hash() {
         fn hash(signed_entity_type: SignedEntityType) -> String {
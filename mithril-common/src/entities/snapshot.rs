@@ -1,4 +1,4 @@
-use crate::{entities::CardanoDbBeacon, signable_builder::Artifact};
+use crate::{entities::CardanoDbBeacon, protocol::HashAlgorithm, signable_builder::Artifact};
 use semver::Version;
 use serde::{Deserialize, Serialize};
 use strum::{Display, EnumIter, IntoEnumIterator};
@@ -23,6 +23,10 @@ pub struct Snapshot {
 
     /// Version of the Cardano node used to create snapshot archive.
     pub cardano_node_version: String,
+
+    /// Hash function used to compute the digest
+    #[serde(default, skip_serializing_if = "HashAlgorithm::is_default")]
+    pub hash_algorithm: HashAlgorithm,
 }
 
 /// Compression algorithm for the snapshot archive artifacts.
@@ -81,8 +85,15 @@ impl Snapshot {
             locations,
             compression_algorithm,
             cardano_node_version,
+            hash_algorithm: HashAlgorithm::default(),
         }
     }
+
+    /// Set the hash function used to compute the digest
+    pub fn with_hash_algorithm(mut self, hash_algorithm: HashAlgorithm) -> Self {
+        self.hash_algorithm = hash_algorithm;
+        self
+    }
 }
 
 #[typetag::serde]
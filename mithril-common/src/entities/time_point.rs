@@ -1,7 +1,7 @@
 use std::cmp::Ordering;
 use std::fmt::{Display, Formatter};
 
-use crate::entities::{ChainPoint, Epoch, ImmutableFileNumber};
+use crate::entities::{BlockNumber, ChainPoint, Epoch, ImmutableFileNumber};
 
 /// TimePoint aggregates all types of point in the Cardano chain and is used by the state machines
 /// for their computations.
@@ -39,6 +39,81 @@ impl TimePoint {
     }
 }
 
+/// Point of the Cardano chain at which a signed entity type is signed, at the epoch granularity
+/// or at the immutable file or block granularity within an epoch.
+///
+/// Beacons of different kinds can be compared as long as they are in different epochs or one of
+/// them is an epoch beacon, which is ordered at the start of its epoch.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TimePointBeacon {
+    /// Epoch of the chain
+    Epoch(Epoch),
+
+    /// Number of the last immutable file of the Cardano node database in an epoch
+    ImmutableFileNumber(Epoch, ImmutableFileNumber),
+
+    /// Block number in an epoch
+    BlockNumber(Epoch, BlockNumber),
+}
+
+impl TimePointBeacon {
+    /// Epoch of the beacon
+    pub fn epoch(&self) -> Epoch {
+        match self {
+            Self::Epoch(epoch)
+            | Self::ImmutableFileNumber(epoch, _)
+            | Self::BlockNumber(epoch, _) => *epoch,
+        }
+    }
+
+    /// Position of the beacon within its epoch, `None` for an epoch beacon
+    pub fn sub_epoch_position(&self) -> Option<u64> {
+        match self {
+            Self::Epoch(_) => None,
+            Self::ImmutableFileNumber(_, immutable_file_number) => Some(*immutable_file_number),
+            Self::BlockNumber(_, block_number) => Some(**block_number),
+        }
+    }
+
+    fn is_same_kind(&self, other: &Self) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+}
+
+impl PartialOrd for TimePointBeacon {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match self.epoch().cmp(&other.epoch()) {
+            Ordering::Equal => match (self.sub_epoch_position(), other.sub_epoch_position()) {
+                (None, None) => Some(Ordering::Equal),
+                (None, Some(_)) => Some(Ordering::Less),
+                (Some(_), None) => Some(Ordering::Greater),
+                (Some(position), Some(other_position)) if self.is_same_kind(other) => {
+                    Some(position.cmp(&other_position))
+                }
+                (Some(_), Some(_)) => None,
+            },
+            ordering => Some(ordering),
+        }
+    }
+}
+
+impl Display for TimePointBeacon {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Epoch(epoch) => write!(f, "epoch {epoch}"),
+            Self::ImmutableFileNumber(epoch, immutable_file_number) => {
+                write!(
+                    f,
+                    "epoch {epoch}, immutable file number {immutable_file_number}"
+                )
+            }
+            Self::BlockNumber(epoch, block_number) => {
+                write!(f, "epoch {epoch}, block number {block_number}")
+            }
+        }
+    }
+}
+
 impl PartialOrd for TimePoint {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
@@ -68,7 +143,7 @@ impl Display for TimePoint {
 mod tests {
     use std::cmp::Ordering;
 
-    use crate::entities::{BlockNumber, SlotNumber};
+    use crate::entities::SlotNumber;
 
     use super::*;
 
@@ -143,4 +218,51 @@ mod tests {
 
         assert_eq!(Ordering::Less, time_point1.cmp(&time_point2));
     }
+
+    #[test]
+    fn time_point_beacons_are_ordered_by_epoch_first() {
+        assert!(
+            TimePointBeacon::BlockNumber(Epoch(4), BlockNumber(500))
+                < TimePointBeacon::BlockNumber(Epoch(5), BlockNumber(100))
+        );
+        assert!(
+            TimePointBeacon::ImmutableFileNumber(Epoch(6), 1)
+                > TimePointBeacon::BlockNumber(Epoch(5), BlockNumber(900))
+        );
+        assert!(
+            TimePointBeacon::Epoch(Epoch(6)) > TimePointBeacon::ImmutableFileNumber(Epoch(5), 1)
+        );
+    }
+
+    #[test]
+    fn time_point_beacons_of_the_same_kind_in_the_same_epoch_are_ordered_by_position() {
+        assert!(
+            TimePointBeacon::BlockNumber(Epoch(5), BlockNumber(100))
+                < TimePointBeacon::BlockNumber(Epoch(5), BlockNumber(200))
+        );
+        assert!(
+            TimePointBeacon::ImmutableFileNumber(Epoch(5), 12)
+                > TimePointBeacon::ImmutableFileNumber(Epoch(5), 11)
+        );
+    }
+
+    #[test]
+    fn an_epoch_beacon_is_ordered_at_the_start_of_its_epoch() {
+        assert!(
+            TimePointBeacon::Epoch(Epoch(5)) < TimePointBeacon::ImmutableFileNumber(Epoch(5), 0)
+        );
+        assert!(
+            TimePointBeacon::BlockNumber(Epoch(5), BlockNumber(0))
+                > TimePointBeacon::Epoch(Epoch(5))
+        );
+    }
+
+    #[test]
+    fn time_point_beacons_of_different_kinds_in_the_same_epoch_are_not_comparable() {
+        assert_eq!(
+            None,
+            TimePointBeacon::BlockNumber(Epoch(5), BlockNumber(100))
+                .partial_cmp(&TimePointBeacon::ImmutableFileNumber(Epoch(5), 100))
+        );
+    }
 }
@@ -42,6 +42,9 @@ pub type HexEncodedVerificationKey = HexEncodedKey;
 /// Hex encoded Verification Key Signature
 pub type HexEncodedVerificationKeySignature = HexEncodedKey;
 
+/// Hex encoded Registration Challenge Signature
+pub type HexEncodedRegistrationChallengeSignature = HexEncodedKey;
+
 /// Hex encoded Operational Certificate
 pub type HexEncodedOpCert = HexEncodedKey;
 
@@ -56,3 +59,6 @@ pub type HexEncodedDigest = HexEncodedKey;
 
 /// Hex encoded Era Markers Secret Key
 pub type HexEncodedEraMarkersSecretKey = HexEncodedKey;
+
+/// Hex encoded Era Markers Verification Key
+pub type HexEncodedEraMarkersVerificationKey = HexEncodedKey;
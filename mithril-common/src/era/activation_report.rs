@@ -0,0 +1,186 @@
+use serde::Serialize;
+use std::str::FromStr;
+
+use crate::entities::Epoch;
+use crate::protocol::{HashAlgorithm, SignatureScheme};
+
+use super::{EraMarker, SupportedEra};
+
+/// Status of an era at the epoch of an [EraActivationReport]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EraActivationStatus {
+    /// The era was replaced by a later era
+    Past,
+
+    /// The era is the current one
+    Current,
+
+    /// The era will activate at a future epoch
+    Upcoming,
+
+    /// The era is announced but its activation epoch is not known yet
+    Unscheduled,
+}
+
+/// Features of the Mithril nodes and clients that change with an era
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EraFeatures {
+    /// Signature schemes signed by the signers and aggregated by the aggregator
+    pub signature_schemes: Vec<SignatureScheme>,
+
+    /// Signature scheme of the multi-signatures of the certificates, verified by the clients
+    pub certifying_signature_scheme: SignatureScheme,
+
+    /// Hash algorithm of the artifacts digests and of the certificates hashes
+    pub hash_algorithm: HashAlgorithm,
+}
+
+impl From<SupportedEra> for EraFeatures {
+    fn from(era: SupportedEra) -> Self {
+        Self {
+            signature_schemes: era.signature_schemes(),
+            certifying_signature_scheme: era.certifying_signature_scheme(),
+            hash_algorithm: era.hash_algorithm(),
+        }
+    }
+}
+
+/// Activation of an era given by an [EraMarker]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EraActivation {
+    /// Era name
+    pub era: String,
+
+    /// Epoch at which the era activates, if known
+    pub activation_epoch: Option<Epoch>,
+
+    /// Status of the era at the epoch of the report
+    pub status: EraActivationStatus,
+
+    /// Features of the era, `None` if this version of the software does not support the era
+    pub features: Option<EraFeatures>,
+}
+
+impl EraActivation {
+    /// Check if this version of the software supports the era
+    pub fn is_supported(&self) -> bool {
+        self.features.is_some()
+    }
+}
+
+/// Dry-run of the era markers: which era, and which features, activate at which epoch and if
+/// this version of the software supports them, so that the upgrades can be planned ahead of an
+/// era switch.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EraActivationReport {
+    /// Epoch at which the report is computed
+    pub current_epoch: Epoch,
+
+    /// Activations of the eras, by activation epoch, the unscheduled eras last
+    pub activations: Vec<EraActivation>,
+}
+
+impl EraActivationReport {
+    /// Compute the activation report of the given era markers at the given epoch
+    pub fn compute(era_markers: &[EraMarker], current_epoch: Epoch) -> Self {
+        let mut era_markers = era_markers.to_vec();
+        // Unscheduled markers last, `None` is lower than any `Some` otherwise
+        era_markers.sort_by_key(|marker| (marker.epoch.is_none(), marker.epoch));
+        let current_era_index = era_markers
+            .iter()
+            .rposition(|marker| marker.epoch.is_some_and(|epoch| epoch <= current_epoch));
+
+        let activations = era_markers
+            .into_iter()
+            .enumerate()
+            .map(|(index, marker)| {
+                let status = match (marker.epoch, current_era_index) {
+                    (None, _) => EraActivationStatus::Unscheduled,
+                    (Some(_), Some(current_index)) if index == current_index => {
+                        EraActivationStatus::Current
+                    }
+                    (Some(epoch), _) if epoch > current_epoch => EraActivationStatus::Upcoming,
+                    (Some(_), _) => EraActivationStatus::Past,
+                };
+
+                EraActivation {
+                    features: SupportedEra::from_str(&marker.name)
+                        .ok()
+                        .map(EraFeatures::from),
+                    era: marker.name,
+                    activation_epoch: marker.epoch,
+                    status,
+                }
+            })
+            .collect();
+
+        Self {
+            current_epoch,
+            activations,
+        }
+    }
+
+    /// Get the eras that are current or upcoming but not supported by this version of the
+    /// software, which must be upgraded before their activation
+    pub fn unsupported_eras(&self) -> Vec<&EraActivation> {
+        self.activations
+            .iter()
+            .filter(|activation| {
+                !activation.is_supported() && activation.status != EraActivationStatus::Past
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compute_the_status_of_each_era_at_the_current_epoch() {
+        let report = EraActivationReport::compute(
+            &[
+                EraMarker::new("unscheduled", None),
+                EraMarker::new("pythagoras", Some(Epoch(12))),
+                EraMarker::new("thales", Some(Epoch(1))),
+                EraMarker::new("future", Some(Epoch(20))),
+            ],
+            Epoch(15),
+        );
+
+        assert_eq!(
+            vec![
+                ("thales", EraActivationStatus::Past, true),
+                ("pythagoras", EraActivationStatus::Current, true),
+                ("future", EraActivationStatus::Upcoming, false),
+                ("unscheduled", EraActivationStatus::Unscheduled, false),
+            ],
+            report
+                .activations
+                .iter()
+                .map(|a| (a.era.as_str(), a.status, a.is_supported()))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec!["future", "unscheduled"],
+            report
+                .unsupported_eras()
+                .iter()
+                .map(|a| a.era.as_str())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn the_features_of_a_supported_era_are_reported() {
+        let report =
+            EraActivationReport::compute(&[EraMarker::new("thales", Some(Epoch(1)))], Epoch(1));
+
+        assert_eq!(
+            Some(EraFeatures::from(SupportedEra::Thales)),
+            report.activations[0].features
+        );
+        assert_eq!(EraActivationStatus::Current, report.activations[0].status);
+    }
+}
@@ -7,8 +7,8 @@ use crate::{
     crypto_helper::EraMarkersVerifierVerificationKey,
     era::{
         adapters::{
-            EraReaderBootstrapAdapter, EraReaderCardanoChainAdapter, EraReaderDummyAdapter,
-            EraReaderFileAdapter,
+            EraMarkersRotatedVerificationKey, EraReaderBootstrapAdapter,
+            EraReaderCardanoChainAdapter, EraReaderDummyAdapter, EraReaderFileAdapter,
         },
         EraMarker, EraReaderAdapter,
     },
@@ -28,6 +28,12 @@ pub enum AdapterType {
     Dummy,
     /// Bootstrap adapter.
     Bootstrap,
+    /// URL adapter.
+    #[cfg(feature = "fs")]
+    Url,
+    /// Fallback adapter, combining other adapters by priority order.
+    #[cfg(feature = "fs")]
+    Fallback,
 }
 
 impl Display for AdapterType {
@@ -37,6 +43,10 @@ impl Display for AdapterType {
             Self::CardanoChain => write!(f, "cardano chain"),
             Self::Dummy => write!(f, "dummy"),
             Self::File => write!(f, "file"),
+            #[cfg(feature = "fs")]
+            Self::Url => write!(f, "url"),
+            #[cfg(feature = "fs")]
+            Self::Fallback => write!(f, "fallback"),
         }
     }
 }
@@ -83,6 +93,8 @@ impl AdapterBuilder {
                 struct CardanoChainAdapterConfig {
                     address: ChainAddress,
                     verification_key: EraMarkersVerifierVerificationKey,
+                    #[serde(default)]
+                    rotated_verification_keys: Vec<EraMarkersRotatedVerificationKey>,
                 }
 
                 let adapter_config: CardanoChainAdapterConfig = serde_json::from_str(
@@ -92,11 +104,14 @@ impl AdapterBuilder {
                 )
                 .map_err(AdapterBuilderError::ParseParameters)?;
 
-                Ok(Arc::new(EraReaderCardanoChainAdapter::new(
-                    adapter_config.address,
-                    chain_observer,
-                    adapter_config.verification_key,
-                )))
+                Ok(Arc::new(
+                    EraReaderCardanoChainAdapter::new(
+                        adapter_config.address,
+                        chain_observer,
+                        adapter_config.verification_key,
+                    )
+                    .with_rotated_verification_keys(adapter_config.rotated_verification_keys),
+                ))
             }
             AdapterType::File => {
                 #[derive(Deserialize)]
@@ -132,6 +147,70 @@ impl AdapterBuilder {
                 Ok(Arc::new(dummy_adapter))
             }
             AdapterType::Bootstrap => Ok(Arc::new(EraReaderBootstrapAdapter)),
+            #[cfg(feature = "fs")]
+            AdapterType::Url => {
+                #[derive(Deserialize)]
+                struct EraReaderUrlAdapterConfig {
+                    url: String,
+                    verification_key: EraMarkersVerifierVerificationKey,
+                }
+
+                let adapter_config: EraReaderUrlAdapterConfig = serde_json::from_str(
+                    self.adapter_params
+                        .as_ref()
+                        .ok_or_else(AdapterBuilderError::MissingParameters)?,
+                )
+                .map_err(AdapterBuilderError::ParseParameters)?;
+                let url_adapter = crate::era::adapters::EraReaderUrlAdapter::new(
+                    &adapter_config.url,
+                    adapter_config.verification_key,
+                )
+                .map_err(AdapterBuilderError::Decode)?;
+
+                Ok(Arc::new(url_adapter))
+            }
+            #[cfg(feature = "fs")]
+            AdapterType::Fallback => {
+                #[derive(Deserialize)]
+                struct FallbackAdapterItemConfig {
+                    #[serde(rename = "type")]
+                    adapter_type: AdapterType,
+                    #[serde(default)]
+                    params: Option<serde_json::Value>,
+                }
+
+                #[derive(Deserialize)]
+                struct EraReaderFallbackAdapterConfig {
+                    adapters: Vec<FallbackAdapterItemConfig>,
+                    #[serde(default)]
+                    cache_file: Option<PathBuf>,
+                }
+
+                let adapter_config: EraReaderFallbackAdapterConfig = serde_json::from_str(
+                    self.adapter_params
+                        .as_ref()
+                        .ok_or_else(AdapterBuilderError::MissingParameters)?,
+                )
+                .map_err(AdapterBuilderError::ParseParameters)?;
+                let adapters = adapter_config
+                    .adapters
+                    .iter()
+                    .map(|item| {
+                        AdapterBuilder::new(
+                            &item.adapter_type,
+                            &item.params.as_ref().map(|params| params.to_string()),
+                        )
+                        .build(chain_observer.clone())
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let mut fallback_adapter =
+                    crate::era::adapters::EraReaderFallbackAdapter::new(adapters);
+                if let Some(cache_file) = adapter_config.cache_file {
+                    fallback_adapter = fallback_adapter.with_cache_file(cache_file);
+                }
+
+                Ok(Arc::new(fallback_adapter))
+            }
         }
     }
 }
@@ -147,6 +226,38 @@ mod test {
         "verification_key":"5b35352c3232382c3134342c38372c3133382c3133362c34382c382c31342c3138372c38352c3134382c39372c3233322c3235352c3232392c33382c3234342c3234372c3230342c3139382c31332c33312c3232322c32352c3136342c35322c3130322c39312c3132302c3230382c3134375d"
     }"#;
 
+    #[cfg(feature = "fs")]
+    #[test]
+    fn build_a_fallback_adapter_from_nested_adapters() {
+        let params = format!(
+            r#"{{
+                "adapters": [
+                    {{ "type": "cardano-chain", "params": {GOLDEN_ADAPTER_PARAMS} }},
+                    {{ "type": "bootstrap" }}
+                ],
+                "cache_file": "/tmp/era_markers_cache.json"
+            }}"#
+        );
+
+        AdapterBuilder::new(&AdapterType::Fallback, &Some(params))
+            .build(Arc::new(MockChainObserver::new()))
+            .expect("building a fallback era reader with nested adapters should not fail");
+    }
+
+    #[cfg(feature = "fs")]
+    #[test]
+    fn building_a_fallback_adapter_fails_if_a_nested_adapter_is_invalid() {
+        let params = r#"{ "adapters": [{ "type": "cardano-chain" }] }"#.to_string();
+
+        let result = AdapterBuilder::new(&AdapterType::Fallback, &Some(params))
+            .build(Arc::new(MockChainObserver::new()));
+
+        assert!(
+            result.is_err(),
+            "building a fallback era reader with an invalid nested adapter should fail"
+        );
+    }
+
     #[test]
     fn golden_test_for_cardano_chain() {
         AdapterBuilder::new(
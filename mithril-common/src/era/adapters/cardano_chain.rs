@@ -4,6 +4,7 @@ use crate::{
         key_decode_hex, key_encode_hex, EraMarkersSigner, EraMarkersVerifier,
         EraMarkersVerifierSignature, EraMarkersVerifierVerificationKey,
     },
+    entities::Epoch,
     era::{EraMarker, EraReaderAdapter},
     StdError, StdResult,
 };
@@ -86,24 +87,45 @@ impl EraMarkersPayload {
 
     /// Sign an era markers payload
     pub fn sign(self, signer: &EraMarkersSigner) -> Result<Self, EraMarkersPayloadError> {
-        let signature = signer.sign(
-            &self
-                .message_to_bytes()
-                .map_err(|e| EraMarkersPayloadError::CreateSignature(e.into()))?,
-        );
+        let signature = signer.sign(&self.message_to_sign()?);
+
+        Ok(self.with_signature(signature))
+    }
+
+    /// Message of an era markers payload to sign, for signers that do not hold the secret key
+    /// in an [EraMarkersSigner]
+    pub fn message_to_sign(&self) -> Result<Vec<u8>, EraMarkersPayloadError> {
+        self.message_to_bytes()
+            .map_err(|e| EraMarkersPayloadError::CreateSignature(e.into()))
+    }
 
-        Ok(Self {
+    /// Set the signature of an era markers payload
+    pub fn with_signature(self, signature: EraMarkersVerifierSignature) -> Self {
+        Self {
             markers: self.markers,
             signature: Some(signature),
-        })
+        }
     }
 }
 
+/// Verification key of a rotated era markers key pair, still accepted during the overlap period
+/// of a key rotation so that the era markers signed with it remain valid until the new key
+/// pair is used.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EraMarkersRotatedVerificationKey {
+    /// Verification key of the rotated key pair
+    pub verification_key: EraMarkersVerifierVerificationKey,
+
+    /// Last epoch at which the rotated key pair is accepted
+    pub valid_until_epoch: Epoch,
+}
+
 /// Cardano Chain adapter retrieves era markers on chain
 pub struct CardanoChainAdapter {
     address: ChainAddress,
     chain_observer: Arc<dyn ChainObserver>,
     verification_key: EraMarkersVerifierVerificationKey,
+    rotated_verification_keys: Vec<EraMarkersRotatedVerificationKey>,
 }
 
 impl CardanoChainAdapter {
@@ -117,13 +139,48 @@ impl CardanoChainAdapter {
             address,
             chain_observer,
             verification_key,
+            rotated_verification_keys: Vec::new(),
+        }
+    }
+
+    /// Also accept the era markers signed by rotated key pairs until the end of their overlap
+    /// period
+    pub fn with_rotated_verification_keys(
+        mut self,
+        rotated_verification_keys: Vec<EraMarkersRotatedVerificationKey>,
+    ) -> Self {
+        self.rotated_verification_keys = rotated_verification_keys;
+        self
+    }
+
+    async fn get_accepted_verification_keys(
+        &self,
+    ) -> StdResult<Vec<EraMarkersVerifierVerificationKey>> {
+        let mut verification_keys = vec![self.verification_key];
+        if self.rotated_verification_keys.is_empty() {
+            return Ok(verification_keys);
         }
+
+        let current_epoch = self
+            .chain_observer
+            .get_current_epoch()
+            .await?
+            .ok_or(anyhow!("could not read the current epoch"))?;
+        verification_keys.extend(
+            self.rotated_verification_keys
+                .iter()
+                .filter(|rotated_key| current_epoch <= rotated_key.valid_until_epoch)
+                .map(|rotated_key| rotated_key.verification_key),
+        );
+
+        Ok(verification_keys)
     }
 }
 
 #[async_trait]
 impl EraReaderAdapter for CardanoChainAdapter {
     async fn read(&self) -> StdResult<Vec<EraMarker>> {
+        let verification_keys = self.get_accepted_verification_keys().await?;
         let tx_datums = self
             .chain_observer
             .get_current_datums(&self.address)
@@ -139,12 +196,14 @@ impl EraReaderAdapter for CardanoChainAdapter {
                     .join("")
             })
             .filter_map(|field_value_str| EraMarkersPayload::from_json_hex(&field_value_str).ok())
-            .filter_map(|era_markers_payload| {
-                era_markers_payload
-                    .verify_signature(self.verification_key)
-                    .ok()
-                    .map(|_| era_markers_payload.markers)
+            .filter(|era_markers_payload| {
+                verification_keys.iter().any(|verification_key| {
+                    era_markers_payload
+                        .verify_signature(*verification_key)
+                        .is_ok()
+                })
             })
+            .map(|era_markers_payload| era_markers_payload.markers)
             .collect::<Vec<Vec<EraMarker>>>();
 
         Ok(markers_list.first().unwrap_or(&Vec::new()).to_owned())
@@ -155,7 +214,7 @@ impl EraReaderAdapter for CardanoChainAdapter {
 mod test {
     use crate::chain_observer::{FakeObserver, TxDatum, TxDatumBuilder, TxDatumFieldValue};
     use crate::crypto_helper::EraMarkersSigner;
-    use crate::entities::Epoch;
+    use crate::entities::TimePoint;
 
     use super::*;
 
@@ -224,4 +283,57 @@ mod test {
         let expected_markers = era_marker_payload_2.markers.to_owned();
         assert_eq!(expected_markers, markers);
     }
+
+    async fn read_markers_signed_by(
+        era_markers_signer: &EraMarkersSigner,
+        current_epoch: Epoch,
+        adapter_builder: impl FnOnce(Arc<FakeObserver>) -> CardanoChainAdapter,
+    ) -> Vec<EraMarker> {
+        let era_marker_payload = EraMarkersPayload {
+            markers: vec![EraMarker::new("thales", Some(Epoch(1)))],
+            signature: None,
+        }
+        .sign(era_markers_signer)
+        .unwrap();
+        let chain_observer = Arc::new(FakeObserver::new(Some(TimePoint {
+            epoch: current_epoch,
+            ..TimePoint::dummy()
+        })));
+        chain_observer
+            .set_datums(dummy_tx_datums_from_markers_payload(vec![
+                era_marker_payload,
+            ]))
+            .await;
+
+        adapter_builder(chain_observer)
+            .read()
+            .await
+            .expect("CardanoChainAdapter read should not fail")
+    }
+
+    #[tokio::test]
+    async fn test_cardano_chain_adapter_accepts_rotated_key_until_the_end_of_the_overlap_period() {
+        let rotated_signer = EraMarkersSigner::create_deterministic_signer();
+        let new_signer = EraMarkersSigner::create_non_deterministic_signer();
+        let build_adapter = |chain_observer: Arc<FakeObserver>| {
+            CardanoChainAdapter::new(
+                "addr_test_123456".to_string(),
+                chain_observer,
+                new_signer.create_verifier().to_verification_key(),
+            )
+            .with_rotated_verification_keys(vec![EraMarkersRotatedVerificationKey {
+                verification_key: rotated_signer.create_verifier().to_verification_key(),
+                valid_until_epoch: Epoch(10),
+            }])
+        };
+
+        let markers = read_markers_signed_by(&new_signer, Epoch(11), build_adapter).await;
+        assert_eq!(1, markers.len());
+
+        let markers = read_markers_signed_by(&rotated_signer, Epoch(10), build_adapter).await;
+        assert_eq!(1, markers.len());
+
+        let markers = read_markers_signed_by(&rotated_signer, Epoch(11), build_adapter).await;
+        assert!(markers.is_empty());
+    }
 }
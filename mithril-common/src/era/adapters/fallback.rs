@@ -0,0 +1,183 @@
+use anyhow::anyhow;
+use async_trait::async_trait;
+use std::{path::PathBuf, sync::Arc};
+
+use crate::entities::Epoch;
+
+use crate::era::{EraMarker, EraReaderAdapter};
+use crate::StdResult;
+
+/// Fallback adapter reads the era markers from the first of its adapters, by priority order, that
+/// succeeds to return some markers.
+///
+/// The last markers read can be cached in a file, which is read when all the adapters fail so that
+/// a transient failure of a backend, ie: the Cardano node not yet ready at startup, does not block
+/// the era detection. The cached markers are never replaced by markers announcing an older era,
+/// so a lagging backend can not roll back the cache.
+pub struct FallbackAdapter {
+    adapters: Vec<Arc<dyn EraReaderAdapter>>,
+    cache_file: Option<PathBuf>,
+}
+
+impl FallbackAdapter {
+    /// Fallback adapter factory, the adapters are tried in the given order
+    pub fn new(adapters: Vec<Arc<dyn EraReaderAdapter>>) -> Self {
+        Self {
+            adapters,
+            cache_file: None,
+        }
+    }
+
+    /// Cache the last era markers read in the given file
+    pub fn with_cache_file(mut self, cache_file: PathBuf) -> Self {
+        self.cache_file = Some(cache_file);
+        self
+    }
+
+    /// Epoch of the most recent era announced by the markers
+    fn latest_era_epoch(markers: &[EraMarker]) -> Option<Epoch> {
+        markers.iter().filter_map(|marker| marker.epoch).max()
+    }
+
+    async fn store_in_cache(&self, markers: &[EraMarker]) {
+        if let Some(cache_file) = &self.cache_file {
+            if let Ok(cached_markers) = self.read_from_cache().await {
+                if Self::latest_era_epoch(markers) < Self::latest_era_epoch(&cached_markers) {
+                    return;
+                }
+            }
+            // The cache is best effort: failing to write it must not fail the read
+            if let Ok(content) = serde_json::to_string(markers) {
+                let _ = tokio::fs::write(cache_file, content).await;
+            }
+        }
+    }
+
+    async fn read_from_cache(&self) -> StdResult<Vec<EraMarker>> {
+        let cache_file = self
+            .cache_file
+            .as_ref()
+            .ok_or_else(|| anyhow!("no era markers cache file configured"))?;
+
+        Ok(serde_json::from_str(
+            &tokio::fs::read_to_string(cache_file).await?,
+        )?)
+    }
+}
+
+#[async_trait]
+impl EraReaderAdapter for FallbackAdapter {
+    async fn read(&self) -> StdResult<Vec<EraMarker>> {
+        let mut errors = vec![];
+
+        for (index, adapter) in self.adapters.iter().enumerate() {
+            match adapter.read().await {
+                Ok(markers) if !markers.is_empty() => {
+                    self.store_in_cache(&markers).await;
+                    return Ok(markers);
+                }
+                Ok(_) => errors.push(format!("adapter #{index}: no era markers")),
+                Err(error) => errors.push(format!("adapter #{index}: {error:?}")),
+            }
+        }
+
+        match self.read_from_cache().await {
+            Ok(markers) if !markers.is_empty() => return Ok(markers),
+            Ok(_) => errors.push("cache: no era markers".to_string()),
+            Err(error) => errors.push(format!("cache: {error:?}")),
+        }
+
+        Err(anyhow!(
+            "All era reader adapters failed: {}",
+            errors.join(", ")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::era::adapters::EraReaderDummyAdapter;
+    use crate::test_utils::TempDir;
+
+    use super::*;
+
+    struct FailingAdapter;
+
+    #[async_trait]
+    impl EraReaderAdapter for FailingAdapter {
+        async fn read(&self) -> StdResult<Vec<EraMarker>> {
+            Err(anyhow!("read failure"))
+        }
+    }
+
+    fn dummy_adapter(markers: &[EraMarker]) -> Arc<dyn EraReaderAdapter> {
+        Arc::new(EraReaderDummyAdapter::from_markers(markers.to_vec()))
+    }
+
+    fn get_cache_file(dir_name: &str) -> PathBuf {
+        TempDir::create("era-adapter", dir_name).join("era_markers_cache.json")
+    }
+
+    #[tokio::test]
+    async fn read_from_the_first_adapter_returning_markers() {
+        let markers = vec![EraMarker::new("one", Some(Epoch(1)))];
+        let adapter = FallbackAdapter::new(vec![
+            Arc::new(FailingAdapter),
+            dummy_adapter(&[]),
+            dummy_adapter(&markers),
+            dummy_adapter(&[EraMarker::new("two", Some(Epoch(2)))]),
+        ]);
+
+        assert_eq!(markers, adapter.read().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn fail_when_all_adapters_fail_without_cache() {
+        let adapter = FallbackAdapter::new(vec![Arc::new(FailingAdapter), dummy_adapter(&[])]);
+
+        adapter
+            .read()
+            .await
+            .expect_err("read should fail when all adapters fail");
+    }
+
+    #[tokio::test]
+    async fn read_the_cached_markers_when_all_adapters_fail() {
+        let cache_file = get_cache_file("read_the_cached_markers_when_all_adapters_fail");
+        let markers = vec![EraMarker::new("one", Some(Epoch(1)))];
+        FallbackAdapter::new(vec![dummy_adapter(&markers)])
+            .with_cache_file(cache_file.clone())
+            .read()
+            .await
+            .unwrap();
+
+        let adapter =
+            FallbackAdapter::new(vec![Arc::new(FailingAdapter)]).with_cache_file(cache_file);
+
+        assert_eq!(markers, adapter.read().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn cached_markers_are_not_replaced_by_markers_of_an_older_era() {
+        let cache_file =
+            get_cache_file("cached_markers_are_not_replaced_by_markers_of_an_older_era");
+        let markers = vec![
+            EraMarker::new("one", Some(Epoch(1))),
+            EraMarker::new("two", Some(Epoch(10))),
+        ];
+        FallbackAdapter::new(vec![dummy_adapter(&markers)])
+            .with_cache_file(cache_file.clone())
+            .read()
+            .await
+            .unwrap();
+
+        let older_markers = vec![EraMarker::new("one", Some(Epoch(1)))];
+        let adapter = FallbackAdapter::new(vec![dummy_adapter(&older_markers)])
+            .with_cache_file(cache_file.clone());
+        assert_eq!(older_markers, adapter.read().await.unwrap());
+
+        let adapter =
+            FallbackAdapter::new(vec![Arc::new(FailingAdapter)]).with_cache_file(cache_file);
+        assert_eq!(markers, adapter.read().await.unwrap());
+    }
+}
@@ -9,7 +9,15 @@ pub use bootstrap::BootstrapAdapter as EraReaderBootstrapAdapter;
 pub use builder::{AdapterBuilder as EraReaderAdapterBuilder, AdapterType as EraReaderAdapterType};
 pub use cardano_chain::{
     CardanoChainAdapter as EraReaderCardanoChainAdapter,
-    EraMarkersPayload as EraMarkersPayloadCardanoChain,
+    EraMarkersPayload as EraMarkersPayloadCardanoChain, EraMarkersRotatedVerificationKey,
 };
 pub use dummy::DummyAdapter as EraReaderDummyAdapter;
 pub use file::FileAdapter as EraReaderFileAdapter;
+
+cfg_fs! {
+    mod fallback;
+    mod url;
+
+    pub use fallback::FallbackAdapter as EraReaderFallbackAdapter;
+    pub use url::UrlAdapter as EraReaderUrlAdapter;
+}
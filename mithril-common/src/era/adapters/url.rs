@@ -0,0 +1,176 @@
+use anyhow::Context;
+use async_trait::async_trait;
+use std::time::Duration;
+
+use crate::crypto_helper::EraMarkersVerifierVerificationKey;
+use crate::era::adapters::EraMarkersPayloadCardanoChain as EraMarkersPayload;
+use crate::era::{EraMarker, EraReaderAdapter};
+use crate::StdResult;
+
+/// Timeout of the requests to the era markers URL.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// URL adapter reads the era markers published at a remote URL.
+///
+/// The markers are published as a signed [EraMarkersPayload] serialized in JSON, the same payload
+/// as the one stored on chain, and are rejected if their signature can not be verified with the
+/// era markers verification key.
+pub struct UrlAdapter {
+    url: String,
+    verification_key: EraMarkersVerifierVerificationKey,
+    client: reqwest::Client,
+}
+
+impl UrlAdapter {
+    /// URL adapter factory
+    pub fn new(url: &str, verification_key: EraMarkersVerifierVerificationKey) -> StdResult<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .with_context(|| "Could not build the era markers HTTP client")?;
+
+        Ok(Self {
+            url: url.to_string(),
+            verification_key,
+            client,
+        })
+    }
+}
+
+#[async_trait]
+impl EraReaderAdapter for UrlAdapter {
+    async fn read(&self) -> StdResult<Vec<EraMarker>> {
+        let response = self
+            .client
+            .get(&self.url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .with_context(|| format!("Could not fetch era markers from '{}'", self.url))?;
+        let body = response
+            .text()
+            .await
+            .with_context(|| format!("Could not read era markers from '{}'", self.url))?;
+
+        let payload: EraMarkersPayload = serde_json::from_str(&body)
+            .with_context(|| format!("Could not parse era markers from '{}'", self.url))?;
+        payload
+            .verify_signature(self.verification_key)
+            .with_context(|| format!("Could not verify era markers from '{}'", self.url))?;
+
+        Ok(payload.markers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    use crate::crypto_helper::EraMarkersSigner;
+    use crate::entities::Epoch;
+
+    use super::*;
+
+    /// Start a fake server answering each request with the given JSON body
+    async fn start_fake_server(body: String) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let body = body.clone();
+                tokio::spawn(async move {
+                    let mut buffer = vec![0u8; 8192];
+                    let _ = stream.read(&mut buffer).await.unwrap();
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                        body.len()
+                    );
+                    stream.write_all(response.as_bytes()).await.unwrap();
+                });
+            }
+        });
+
+        format!("http://{address}/era-markers.json")
+    }
+
+    fn markers() -> Vec<EraMarker> {
+        vec![
+            EraMarker::new("thales", Some(Epoch(1))),
+            EraMarker::new("pythagoras", Some(Epoch(12))),
+        ]
+    }
+
+    #[tokio::test]
+    async fn read_the_markers_signed_with_the_era_markers_key() {
+        let signer = EraMarkersSigner::create_deterministic_signer();
+        let payload = EraMarkersPayload {
+            markers: markers(),
+            signature: None,
+        }
+        .sign(&signer)
+        .unwrap();
+        let url = start_fake_server(serde_json::to_string(&payload).unwrap()).await;
+        let adapter =
+            UrlAdapter::new(&url, signer.create_verifier().to_verification_key()).unwrap();
+
+        assert_eq!(markers(), adapter.read().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn read_fails_when_the_markers_are_not_signed() {
+        let signer = EraMarkersSigner::create_deterministic_signer();
+        let payload = EraMarkersPayload {
+            markers: markers(),
+            signature: None,
+        };
+        let url = start_fake_server(serde_json::to_string(&payload).unwrap()).await;
+        let adapter =
+            UrlAdapter::new(&url, signer.create_verifier().to_verification_key()).unwrap();
+
+        adapter
+            .read()
+            .await
+            .expect_err("read should fail when the markers are not signed");
+    }
+
+    #[tokio::test]
+    async fn read_fails_when_the_markers_are_signed_with_another_key() {
+        let payload = EraMarkersPayload {
+            markers: markers(),
+            signature: None,
+        }
+        .sign(&EraMarkersSigner::create_non_deterministic_signer())
+        .unwrap();
+        let url = start_fake_server(serde_json::to_string(&payload).unwrap()).await;
+        let adapter = UrlAdapter::new(
+            &url,
+            EraMarkersSigner::create_deterministic_signer()
+                .create_verifier()
+                .to_verification_key(),
+        )
+        .unwrap();
+
+        adapter
+            .read()
+            .await
+            .expect_err("read should fail when the markers are signed with another key");
+    }
+
+    #[tokio::test]
+    async fn read_fails_when_the_url_is_unreachable() {
+        let adapter = UrlAdapter::new(
+            "http://127.0.0.1:1/era-markers.json",
+            EraMarkersSigner::create_deterministic_signer()
+                .create_verifier()
+                .to_verification_key(),
+        )
+        .unwrap();
+
+        adapter
+            .read()
+            .await
+            .expect_err("read should fail when the url is unreachable");
+    }
+}
@@ -1,6 +1,7 @@
 use std::sync::RwLock;
 
 use crate::entities::Epoch;
+use crate::protocol::{HashAlgorithm, SignatureScheme};
 
 use super::SupportedEra;
 
@@ -46,6 +47,25 @@ impl EraChecker {
     pub fn is_era_active(&self, era: SupportedEra) -> bool {
         self.current_era() == era
     }
+
+    /// Retrieve the signature scheme of the multi-signatures of the current era
+    pub fn certifying_signature_scheme(&self) -> SignatureScheme {
+        self.current_era().certifying_signature_scheme()
+    }
+
+    /// Check if a signature scheme is signed during the current era, either as the certifying
+    /// scheme or during a transition to a new scheme
+    pub fn is_signature_scheme_active(&self, signature_scheme: SignatureScheme) -> bool {
+        self.current_era()
+            .signature_schemes()
+            .contains(&signature_scheme)
+    }
+
+    /// Retrieve the hash algorithm of the artifacts digests and of the certificates hashes of
+    /// the current era
+    pub fn hash_algorithm(&self) -> HashAlgorithm {
+        self.current_era().hash_algorithm()
+    }
 }
 
 #[cfg(test)]
@@ -62,4 +82,15 @@ mod tests {
         assert_eq!(Epoch(2), era_checker.current_epoch());
         assert!(era_checker.is_era_active(expected_era));
     }
+
+    #[test]
+    fn the_certifying_signature_scheme_of_the_current_era_is_active() {
+        let era_checker = EraChecker::new(SupportedEra::dummy(), Epoch(1));
+
+        assert_eq!(
+            SupportedEra::dummy().certifying_signature_scheme(),
+            era_checker.certifying_signature_scheme()
+        );
+        assert!(era_checker.is_signature_scheme_active(era_checker.certifying_signature_scheme()));
+    }
 }
@@ -1,10 +1,12 @@
 //! The module used for handling eras
 
+mod activation_report;
 pub mod adapters;
 mod era_checker;
 mod era_reader;
 mod supported_era;
 
+pub use activation_report::*;
 pub use era_checker::EraChecker;
 pub use era_reader::*;
 pub use supported_era::*;
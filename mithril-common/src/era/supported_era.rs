@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use strum::{Display, EnumIter, EnumString, IntoEnumIterator};
 
+use crate::protocol::{HashAlgorithm, SignatureScheme};
+
 /// The era that the software is running or will run
 #[derive(
     Display, EnumString, EnumIter, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize,
@@ -21,6 +23,31 @@ impl SupportedEra {
         Self::iter().collect()
     }
 
+    /// Retrieve the signature schemes used during the era
+    ///
+    /// The first scheme is the certifying one, the next ones are the schemes of a transition to a
+    /// new scheme whose signatures are accepted by the aggregator. The signers only sign with the
+    /// certifying scheme: signing with every scheme of the era (dual-signing) will come with the
+    /// second scheme.
+    pub fn signature_schemes(&self) -> Vec<SignatureScheme> {
+        match self {
+            Self::Thales | Self::Pythagoras => vec![SignatureScheme::StmV1],
+        }
+    }
+
+    /// Retrieve the signature scheme of the multi-signatures of the certificates of the era
+    pub fn certifying_signature_scheme(&self) -> SignatureScheme {
+        self.signature_schemes()[0]
+    }
+
+    /// Retrieve the hash algorithm of the artifacts digests and of the certificates hashes of
+    /// the era
+    pub fn hash_algorithm(&self) -> HashAlgorithm {
+        match self {
+            Self::Thales | Self::Pythagoras => HashAlgorithm::Sha256,
+        }
+    }
+
     /// Retrieve a dummy era (for test only)
     pub fn dummy() -> Self {
         Self::eras().first().unwrap().to_owned()
@@ -40,6 +67,16 @@ mod tests {
         assert!(total_eras <= 2);
     }
 
+    #[test]
+    fn each_era_has_a_certifying_signature_scheme() {
+        for era in SupportedEra::eras() {
+            assert_eq!(
+                era.signature_schemes().first(),
+                Some(&era.certifying_signature_scheme())
+            );
+        }
+    }
+
     #[test]
     fn from_str() {
         let supported_era = SupportedEra::from_str(&SupportedEra::dummy().to_string())
@@ -0,0 +1,351 @@
+//! Catalog of the stable error codes of Mithril.
+//!
+//! The codes are returned in the error responses of the aggregator and printed by the clients,
+//! so integrators can map a failure to its remediation without parsing error messages.
+//!
+//! A code can be attached to any [StdError] as a context, and found back with [MithrilErrorCode::find_in]:
+//!
+//! ```
+//! use anyhow::anyhow;
+//! use mithril_common::error_codes::MithrilErrorCode;
+//!
+//! let error = anyhow!("signature does not verify")
+//!     .context(MithrilErrorCode::CertificateChainInvalid)
+//!     .context("Could not verify the certificate chain");
+//!
+//! assert_eq!(
+//!     Some(MithrilErrorCode::CertificateChainInvalid),
+//!     MithrilErrorCode::find_in(&error)
+//! );
+//! ```
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use strum::{EnumIter, IntoEnumIterator};
+use thiserror::Error;
+
+use crate::StdError;
+
+/// Stable error codes of Mithril, formatted as `MITHRIL-Exxxx`.
+///
+/// The codes of the aggregator start at `E1000`, the codes of the client library at `E2000` and
+/// the codes of the client CLI at `E3000`. A code is never reused for another failure.
+///
+/// The codes that are not in this catalog, ie: a code added by a newer aggregator, are parsed as
+/// [MithrilErrorCode::Unknown] so the errors that carry them can still be read.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, EnumIter)]
+pub enum MithrilErrorCode {
+    /// The aggregator failed to process the request
+    AggregatorInternalError,
+
+    /// The aggregator can not process the request for now
+    AggregatorUnavailable,
+
+    /// The body of the request sent to the aggregator could not be decoded
+    InvalidRequestPayload,
+
+    /// The epoch of the request does not match the epoch of the aggregator
+    InvalidEpoch,
+
+    /// The registration challenge of the signer is not valid
+    InvalidRegistrationChallenge,
+
+    /// The aggregator rejected the registration of the signer
+    SignerRegistrationFailed,
+
+    /// The signer registration round of the current epoch is not opened yet
+    SignerRegistrationRoundNotOpened,
+
+    /// The single signature could not be authenticated
+    SignatureAuthenticationFailed,
+
+    /// The signature scheme of the single signature is not used by the current era
+    InactiveSignatureScheme,
+
+    /// The database of the aggregator is busy
+    DatabaseBusy,
+
+    /// The client failed for an unclassified reason
+    ClientInternalError,
+
+    /// The aggregator could not be reached
+    AggregatorUnreachable,
+
+    /// The API versions of the client and of the aggregator are not compatible
+    ApiVersionMismatch,
+
+    /// The aggregator rejected the request of the client
+    AggregatorRejectedRequest,
+
+    /// The certificate chain could not be verified
+    CertificateChainInvalid,
+
+    /// The genesis verification key could not be decoded
+    InvalidGenesisVerificationKey,
+
+    /// The artifact is not signed by its certificate
+    ArtifactMessageMismatch,
+
+    /// A configuration parameter of the client is missing or invalid
+    InvalidConfiguration,
+
+    /// The download directory has not enough free space to store and unpack the artifact
+    NotEnoughDiskSpace,
+
+    /// The download directory is not empty
+    DownloadDirectoryNotEmpty,
+
+    /// The download directory can not be written
+    DownloadDirectoryNotWritable,
+
+    /// The command is unstable and requires the `--unstable` flag
+    UnstableCommandNotEnabled,
+
+    /// A code that is not in this catalog
+    #[strum(disabled)]
+    Unknown(String),
+}
+
+impl MithrilErrorCode {
+    /// The code, ie: `MITHRIL-E2004`
+    pub fn code(&self) -> &str {
+        match self {
+            Self::AggregatorInternalError => "MITHRIL-E1000",
+            Self::AggregatorUnavailable => "MITHRIL-E1001",
+            Self::InvalidRequestPayload => "MITHRIL-E1002",
+            Self::InvalidEpoch => "MITHRIL-E1003",
+            Self::InvalidRegistrationChallenge => "MITHRIL-E1004",
+            Self::SignerRegistrationFailed => "MITHRIL-E1005",
+            Self::SignerRegistrationRoundNotOpened => "MITHRIL-E1006",
+            Self::SignatureAuthenticationFailed => "MITHRIL-E1007",
+            Self::InactiveSignatureScheme => "MITHRIL-E1008",
+            Self::DatabaseBusy => "MITHRIL-E1009",
+            Self::ClientInternalError => "MITHRIL-E2000",
+            Self::AggregatorUnreachable => "MITHRIL-E2001",
+            Self::ApiVersionMismatch => "MITHRIL-E2002",
+            Self::AggregatorRejectedRequest => "MITHRIL-E2003",
+            Self::CertificateChainInvalid => "MITHRIL-E2004",
+            Self::InvalidGenesisVerificationKey => "MITHRIL-E2005",
+            Self::ArtifactMessageMismatch => "MITHRIL-E2006",
+            Self::InvalidConfiguration => "MITHRIL-E3000",
+            Self::NotEnoughDiskSpace => "MITHRIL-E3001",
+            Self::DownloadDirectoryNotEmpty => "MITHRIL-E3002",
+            Self::DownloadDirectoryNotWritable => "MITHRIL-E3003",
+            Self::UnstableCommandNotEnabled => "MITHRIL-E3004",
+            Self::Unknown(code) => code,
+        }
+    }
+
+    /// Short description of the failure
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::AggregatorInternalError => "The aggregator failed to process the request",
+            Self::AggregatorUnavailable => "The aggregator can not process the request for now",
+            Self::InvalidRequestPayload => "The body of the request could not be decoded",
+            Self::InvalidEpoch => "The epoch of the request does not match the aggregator epoch",
+            Self::InvalidRegistrationChallenge => "The signer registration challenge is invalid",
+            Self::SignerRegistrationFailed => "The signer registration was rejected",
+            Self::SignerRegistrationRoundNotOpened => "The signer registration round is not opened",
+            Self::SignatureAuthenticationFailed => {
+                "The single signature could not be authenticated"
+            }
+            Self::InactiveSignatureScheme => "The signature scheme is not used by the current era",
+            Self::DatabaseBusy => "The aggregator database is busy",
+            Self::ClientInternalError => "The client failed",
+            Self::AggregatorUnreachable => "The aggregator could not be reached",
+            Self::ApiVersionMismatch => "The client and the aggregator API versions mismatch",
+            Self::AggregatorRejectedRequest => "The aggregator rejected the request",
+            Self::CertificateChainInvalid => "The certificate chain is invalid",
+            Self::InvalidGenesisVerificationKey => "The genesis verification key is invalid",
+            Self::ArtifactMessageMismatch => "The artifact is not signed by its certificate",
+            Self::InvalidConfiguration => "The configuration is invalid",
+            Self::NotEnoughDiskSpace => "Not enough free space in the download directory",
+            Self::DownloadDirectoryNotEmpty => "The download directory is not empty",
+            Self::DownloadDirectoryNotWritable => "The download directory is not writable",
+            Self::UnstableCommandNotEnabled => "The command requires the --unstable flag",
+            Self::Unknown(_) => "The error code is unknown to this version",
+        }
+    }
+
+    /// What to do to fix the failure
+    pub fn remediation(&self) -> &'static str {
+        match self {
+            Self::AggregatorInternalError => {
+                "Retry later, and report the issue to the aggregator operator if it persists"
+            }
+            Self::AggregatorUnavailable | Self::DatabaseBusy => "Retry later",
+            Self::InvalidRequestPayload | Self::ApiVersionMismatch => {
+                "Upgrade the node or the client to a version compatible with the aggregator"
+            }
+            Self::InvalidEpoch => "Check that the Cardano node of the signer is synchronized",
+            Self::InvalidRegistrationChallenge => {
+                "Check that the signer runs a version that answers the registration challenge"
+            }
+            Self::SignerRegistrationFailed => {
+                "Check the operational certificate and the KES keys of the signer"
+            }
+            Self::SignerRegistrationRoundNotOpened => {
+                "Retry later, the round opens when the aggregator has read the stake distribution of the epoch"
+            }
+            Self::SignatureAuthenticationFailed => {
+                "Check that the signer is registered for the epoch of the signature"
+            }
+            Self::InactiveSignatureScheme => {
+                "Upgrade the signer to a version that supports the current era"
+            }
+            Self::ClientInternalError => "Run the command with -vvv to get more details",
+            Self::AggregatorUnreachable => {
+                "Check the aggregator endpoint and the network connectivity of the client"
+            }
+            Self::AggregatorRejectedRequest => "Check the parameters of the command",
+            Self::CertificateChainInvalid | Self::InvalidGenesisVerificationKey => {
+                "Check that the genesis verification key matches the network of the aggregator"
+            }
+            Self::ArtifactMessageMismatch => {
+                "Do not use the artifact, and report the issue to the aggregator operator"
+            }
+            Self::InvalidConfiguration => "Check the configuration parameters of the client",
+            Self::NotEnoughDiskSpace => "Free some space or use another download directory",
+            Self::DownloadDirectoryNotEmpty => "Clean up or use another download directory",
+            Self::DownloadDirectoryNotWritable => {
+                "Check the permissions and the ownership of the download directory"
+            }
+            Self::UnstableCommandNotEnabled => "Run the command with the --unstable flag",
+            Self::Unknown(_) => {
+                "Upgrade to the latest version to get the remediation of this error code"
+            }
+        }
+    }
+
+    /// All the codes of the catalog, [MithrilErrorCode::Unknown] excluded
+    pub fn all() -> Vec<Self> {
+        Self::iter().collect()
+    }
+
+    /// Find the code attached as a context to the given error, the outermost code if there
+    /// are several
+    pub fn find_in(error: &StdError) -> Option<Self> {
+        error.downcast_ref::<Self>().cloned()
+    }
+}
+
+impl Display for MithrilErrorCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.code(), self.description())
+    }
+}
+
+/// Error raised when parsing an unknown [MithrilErrorCode]
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("Unknown Mithril error code: '{0}'")]
+pub struct UnknownErrorCode(String);
+
+impl FromStr for MithrilErrorCode {
+    type Err = UnknownErrorCode;
+
+    /// Parse a code of the catalog, fails on the codes that are not in the catalog
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::iter()
+            .find(|code| code.code() == s)
+            .ok_or_else(|| UnknownErrorCode(s.to_string()))
+    }
+}
+
+impl Serialize for MithrilErrorCode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.code())
+    }
+}
+
+impl<'de> Deserialize<'de> for MithrilErrorCode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let code = String::deserialize(deserializer)?;
+        Ok(Self::from_str(&code).unwrap_or(Self::Unknown(code)))
+    }
+}
+
+/// Report of an error for the machine-readable outputs, with its code and remediation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorReport {
+    /// Code of the error, ie: `MITHRIL-E2004`
+    pub code: MithrilErrorCode,
+
+    /// Message of the error, its causes included
+    pub message: String,
+
+    /// What to do to fix the error
+    pub remediation: String,
+}
+
+impl ErrorReport {
+    /// Create the report of the given error with the given code
+    pub fn new(code: MithrilErrorCode, error: &StdError) -> Self {
+        Self {
+            remediation: code.remediation().to_string(),
+            code,
+            message: format!("{error:#}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn the_codes_are_unique_and_well_formed() {
+        let codes = MithrilErrorCode::all()
+            .into_iter()
+            .map(|code| code.code().to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(codes.len(), codes.iter().collect::<HashSet<_>>().len());
+        for code in codes {
+            assert!(
+                code.starts_with("MITHRIL-E")
+                    && code.len() == 13
+                    && code[9..].chars().all(|c| c.is_ascii_digit()),
+                "Malformed code: {code}"
+            );
+        }
+    }
+
+    #[test]
+    fn serialize_and_parse_the_codes() {
+        for code in MithrilErrorCode::all() {
+            let json = serde_json::to_string(&code).unwrap();
+            assert_eq!(format!("\"{}\"", code.code()), json);
+            assert_eq!(code, serde_json::from_str(&json).unwrap());
+        }
+
+        MithrilErrorCode::from_str("MITHRIL-E9999").expect_err("An unknown code should fail");
+    }
+
+    #[test]
+    fn deserialize_the_codes_not_in_the_catalog_as_unknown() {
+        let code: MithrilErrorCode = serde_json::from_str("\"MITHRIL-E9999\"").unwrap();
+
+        assert_eq!(MithrilErrorCode::Unknown("MITHRIL-E9999".to_string()), code);
+        assert_eq!("\"MITHRIL-E9999\"", serde_json::to_string(&code).unwrap());
+        assert!(!MithrilErrorCode::all().contains(&code));
+    }
+
+    #[test]
+    fn find_the_outermost_code_attached_to_an_error() {
+        let error = anyhow!("root cause")
+            .context(MithrilErrorCode::CertificateChainInvalid)
+            .context("middle")
+            .context(MithrilErrorCode::AggregatorRejectedRequest)
+            .context("top");
+
+        assert_eq!(
+            Some(MithrilErrorCode::AggregatorRejectedRequest),
+            MithrilErrorCode::find_in(&error)
+        );
+        assert_eq!(None, MithrilErrorCode::find_in(&anyhow!("no code")));
+    }
+}
@@ -55,6 +55,7 @@ pub mod certificate_chain;
 pub mod chain_observer;
 pub mod crypto_helper;
 pub mod entities;
+pub mod error_codes;
 #[macro_use]
 pub mod era;
 pub mod logging;
@@ -66,10 +67,16 @@ pub mod signed_entity_type_lock;
 
 cfg_test_tools! {
     pub mod test_utils;
+    pub mod test_vectors;
 }
 
+#[cfg(feature = "testing")]
+#[cfg_attr(docsrs, doc(cfg(feature = "testing")))]
+pub mod testing;
+
 cfg_fs! {
     mod ticker_service;
+    pub mod configuration_files;
     pub mod digesters;
     pub mod cardano_block_scanner;
     pub mod chain_reader;
@@ -92,6 +99,13 @@ pub const MITHRIL_API_VERSION_HEADER: &str = "mithril-api-version";
 /// Mithril Signer node version header name
 pub const MITHRIL_SIGNER_VERSION_HEADER: &str = "signer-node-version";
 
+/// Mithril origin tag header name, used by clients to identify the origin of their requests
+pub const MITHRIL_ORIGIN_TAG_HEADER: &str = "mithril-origin-tag";
+
+/// Mithril correlation id header name, used to share the correlation id of the logs of a request
+/// between the nodes
+pub const MITHRIL_CORRELATION_ID_HEADER: &str = "mithril-correlation-id";
+
 #[cfg(test)]
 mod tests {
     #[cfg(feature = "apispec")]
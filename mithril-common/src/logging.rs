@@ -1,6 +1,10 @@
 //! Logging utilities for the Mithril project.
 
-use slog::Logger;
+use slog::{BorrowedKV, Drain, Level, Logger, OwnedKVList, Record, RecordStatic, SingleKV};
+use std::fmt::{Display, Formatter};
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 
 /// Extension trait for `slog::Logger`
 pub trait LoggerExtensions {
@@ -21,6 +25,212 @@ impl LoggerExtensions for Logger {
     }
 }
 
+/// Handle to change at runtime the minimum level of the logs let through by a
+/// [ReloadableLevelFilter], without restarting the node.
+#[derive(Debug, Clone)]
+pub struct LogLevelHandle {
+    level: Arc<AtomicUsize>,
+    initial_level: Level,
+}
+
+impl LogLevelHandle {
+    /// Create a handle starting at the given level
+    pub fn new(initial_level: Level) -> Self {
+        Self {
+            level: Arc::new(AtomicUsize::new(initial_level.as_usize())),
+            initial_level,
+        }
+    }
+
+    /// Current minimum level of the logs
+    pub fn level(&self) -> Level {
+        Level::from_usize(self.level.load(Ordering::Relaxed)).unwrap_or(self.initial_level)
+    }
+
+    /// Level of the logs when the node started
+    pub fn initial_level(&self) -> Level {
+        self.initial_level
+    }
+
+    /// Change the minimum level of the logs
+    pub fn set_level(&self, level: Level) {
+        self.level.store(level.as_usize(), Ordering::Relaxed);
+    }
+
+    /// Switch between the initial level and a more verbose one (`Debug`, or `Trace` if the
+    /// initial level is already `Debug`), returns the new level.
+    pub fn toggle_verbose(&self) -> Level {
+        let level = if self.level() != self.initial_level {
+            self.initial_level
+        } else if self.initial_level.is_at_least(Level::Info) {
+            Level::Debug
+        } else {
+            Level::Trace
+        };
+        self.set_level(level);
+
+        level
+    }
+}
+
+/// Log that the level of the logs changed to `level`, the record is logged at this level so it
+/// is let through whatever the previous level was.
+pub fn log_level_changed(logger: &Logger, level: Level, reason: &str) {
+    match level {
+        Level::Critical => {
+            slog::crit!(logger, "{reason}, log level changed"; "log_level" => level.as_str())
+        }
+        Level::Error => {
+            slog::error!(logger, "{reason}, log level changed"; "log_level" => level.as_str())
+        }
+        Level::Warning => {
+            slog::warn!(logger, "{reason}, log level changed"; "log_level" => level.as_str())
+        }
+        Level::Info => {
+            slog::info!(logger, "{reason}, log level changed"; "log_level" => level.as_str())
+        }
+        Level::Debug => {
+            slog::debug!(logger, "{reason}, log level changed"; "log_level" => level.as_str())
+        }
+        Level::Trace => {
+            slog::trace!(logger, "{reason}, log level changed"; "log_level" => level.as_str())
+        }
+    }
+}
+
+/// Drain that filters the records below the level of its [LogLevelHandle], which can be changed
+/// while the node runs.
+///
+/// It must wrap any asynchronous drain, so the records are filtered with the level at the time
+/// they are logged.
+pub struct ReloadableLevelFilter<D: Drain> {
+    drain: D,
+    handle: LogLevelHandle,
+}
+
+impl<D: Drain> ReloadableLevelFilter<D> {
+    /// Wrap the given drain, filtered with the level of the handle
+    pub fn new(drain: D, handle: LogLevelHandle) -> Self {
+        Self { drain, handle }
+    }
+}
+
+impl<D: Drain> Drain for ReloadableLevelFilter<D> {
+    type Ok = Option<D::Ok>;
+    type Err = D::Err;
+
+    fn log(&self, record: &Record, values: &OwnedKVList) -> Result<Self::Ok, Self::Err> {
+        if record.level().is_at_least(self.handle.level()) {
+            self.drain.log(record, values).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// Key of the correlation id in the logs.
+pub const CORRELATION_ID_LOG_KEY: &str = "correlation_id";
+
+tokio::task_local! {
+    static CURRENT_CORRELATION_ID: CorrelationId;
+}
+
+/// Identifier shared by the logs of a unit of work (a cycle of a runtime, an HTTP request) so
+/// that they can be stitched together by the log pipelines.
+///
+/// The id is attached to a task with [CorrelationId::scope], the records logged in this task are
+/// enriched with it by a [CorrelationIdDrain], whatever the logger used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorrelationId(String);
+
+impl CorrelationId {
+    /// Maximum length of a correlation id received from another node.
+    pub const MAX_LENGTH: usize = 64;
+
+    cfg_random! {
+        /// Generate a new random correlation id
+        pub fn generate() -> Self {
+            use rand_core::RngCore;
+
+            Self(format!("{:016x}", rand_core::OsRng.next_u64()))
+        }
+    }
+
+    /// Read a correlation id received from another node, it is refused if it is empty, longer
+    /// than [Self::MAX_LENGTH] or contains other characters than ASCII alphanumerics, `-` and `_`.
+    pub fn parse(value: &str) -> Option<Self> {
+        let is_valid = !value.is_empty()
+            && value.len() <= Self::MAX_LENGTH
+            && value
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+        is_valid.then(|| Self(value.to_string()))
+    }
+
+    /// Correlation id of the current task, if it runs in a [scope][Self::scope]
+    pub fn current() -> Option<Self> {
+        CURRENT_CORRELATION_ID.try_with(Clone::clone).ok()
+    }
+
+    /// Run the given future with this correlation id attached to its logs
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        CURRENT_CORRELATION_ID.scope(self, future).await
+    }
+
+    /// Value of the correlation id
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for CorrelationId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Drain that adds the [CorrelationId] of the current task to the records, under the
+/// [CORRELATION_ID_LOG_KEY] key.
+///
+/// It must be placed before any asynchronous drain, as the correlation id is only known by the
+/// task that logs the record.
+pub struct CorrelationIdDrain<D: Drain> {
+    drain: D,
+}
+
+impl<D: Drain> CorrelationIdDrain<D> {
+    /// Wrap the given drain
+    pub fn new(drain: D) -> Self {
+        Self { drain }
+    }
+}
+
+impl<D: Drain> Drain for CorrelationIdDrain<D> {
+    type Ok = D::Ok;
+    type Err = D::Err;
+
+    fn log(&self, record: &Record, values: &OwnedKVList) -> Result<Self::Ok, Self::Err> {
+        let Some(correlation_id) = CorrelationId::current() else {
+            return self.drain.log(record, values);
+        };
+        let record_static = RecordStatic {
+            location: record.location(),
+            tag: record.tag(),
+            level: record.level(),
+        };
+        let kv = (
+            record.kv(),
+            SingleKV::from((CORRELATION_ID_LOG_KEY, correlation_id.0)),
+        );
+
+        self.drain.log(
+            &Record::new(&record_static, record.msg(), BorrowedKV(&kv)),
+            values,
+        )
+    }
+}
+
 fn component_name<T>() -> &'static str {
     let complete_name = std::any::type_name::<T>();
     let without_generic = {
@@ -105,6 +315,138 @@ mod tests {
         );
     }
 
+    #[test]
+    fn reloadable_level_filter_follows_the_level_of_its_handle() {
+        let log_path = TempDir::create(
+            "common_logging",
+            "reloadable_level_filter_follows_the_level_of_its_handle",
+        )
+        .join("test.log");
+        {
+            let handle = LogLevelHandle::new(Level::Info);
+            let decorator =
+                slog_term::PlainDecorator::new(std::fs::File::create(&log_path).unwrap());
+            let drain = slog_term::CompactFormat::new(decorator).build().fuse();
+            let drain = slog_async::Async::new(drain).build().fuse();
+            let drain = ReloadableLevelFilter::new(drain, handle.clone()).fuse();
+            let logger = Logger::root(Arc::new(drain), slog::o!());
+            slog::debug!(logger, "filtered debug log");
+            handle.set_level(Level::Debug);
+            slog::debug!(logger, "visible debug log");
+        }
+
+        let logs = std::fs::read_to_string(&log_path).unwrap();
+        assert!(!logs.contains("filtered debug log"), "logs:\n{logs}");
+        assert!(logs.contains("visible debug log"), "logs:\n{logs}");
+    }
+
+    #[test]
+    fn log_level_handle_toggle_verbose_switches_back_to_the_initial_level() {
+        let handle = LogLevelHandle::new(Level::Warning);
+
+        assert_eq!(Level::Debug, handle.toggle_verbose());
+        assert_eq!(Level::Debug, handle.level());
+        assert_eq!(Level::Warning, handle.toggle_verbose());
+        assert_eq!(Level::Warning, handle.level());
+
+        handle.set_level(Level::Error);
+        assert_eq!(Level::Warning, handle.toggle_verbose());
+
+        let handle = LogLevelHandle::new(Level::Debug);
+        assert_eq!(Level::Trace, handle.toggle_verbose());
+    }
+
+    #[test]
+    fn log_level_changed_is_let_through_whatever_the_level_of_the_handle() {
+        let log_path = TempDir::create(
+            "common_logging",
+            "log_level_changed_is_let_through_whatever_the_level_of_the_handle",
+        )
+        .join("test.log");
+        {
+            let handle = LogLevelHandle::new(Level::Error);
+            let decorator =
+                slog_term::PlainDecorator::new(std::fs::File::create(&log_path).unwrap());
+            let drain = slog_term::CompactFormat::new(decorator).build().fuse();
+            let drain = slog_async::Async::new(drain).build().fuse();
+            let drain = ReloadableLevelFilter::new(drain, handle.clone()).fuse();
+            let logger = Logger::root(Arc::new(drain), slog::o!());
+            log_level_changed(&logger, handle.toggle_verbose(), "Switched to verbose");
+            log_level_changed(&logger, handle.toggle_verbose(), "Switched back");
+        }
+
+        let logs = std::fs::read_to_string(&log_path).unwrap();
+        assert!(
+            logs.contains("Switched to verbose, log level changed"),
+            "logs:\n{logs}"
+        );
+        assert!(
+            logs.contains("Switched back, log level changed"),
+            "logs:\n{logs}"
+        );
+    }
+
+    #[test]
+    fn correlation_id_parse_refuses_unsafe_values() {
+        assert_eq!(
+            Some(CorrelationId("0a1b-2c_3d".to_string())),
+            CorrelationId::parse("0a1b-2c_3d")
+        );
+        assert_eq!(None, CorrelationId::parse(""));
+        assert_eq!(None, CorrelationId::parse("with space"));
+        assert_eq!(None, CorrelationId::parse("with\nnew line"));
+        assert_eq!(
+            None,
+            CorrelationId::parse(&"a".repeat(CorrelationId::MAX_LENGTH + 1))
+        );
+    }
+
+    #[tokio::test]
+    async fn correlation_id_is_only_available_in_its_scope() {
+        let correlation_id = CorrelationId::generate();
+
+        let current = correlation_id
+            .clone()
+            .scope(async { CorrelationId::current() })
+            .await;
+
+        assert_eq!(Some(correlation_id), current);
+        assert_eq!(None, CorrelationId::current());
+    }
+
+    #[tokio::test]
+    async fn correlation_id_drain_adds_the_correlation_id_of_the_scope() {
+        let log_path = TempDir::create(
+            "common_logging",
+            "correlation_id_drain_adds_the_correlation_id_of_the_scope",
+        )
+        .join("test.log");
+        let correlation_id = CorrelationId::parse("cycle-0123456789").unwrap();
+        {
+            let decorator =
+                slog_term::PlainDecorator::new(std::fs::File::create(&log_path).unwrap());
+            let drain = slog_term::CompactFormat::new(decorator).build().fuse();
+            let drain = slog_async::Async::new(drain).build().fuse();
+            let logger = Logger::root(Arc::new(CorrelationIdDrain::new(drain)), slog::o!());
+            info!(logger, "log without correlation id");
+            correlation_id
+                .clone()
+                .scope(async { info!(logger, "log with correlation id") })
+                .await;
+        }
+
+        let logs = std::fs::read_to_string(&log_path).unwrap();
+        let line_with = |message: &str| {
+            logs.lines()
+                .find(|line| line.contains(message))
+                .unwrap_or_else(|| panic!("log '{message}' not found, logs:\n{logs}"))
+                .to_string()
+        };
+        assert!(!line_with("log without correlation id").contains(CORRELATION_ID_LOG_KEY));
+        assert!(line_with("log with correlation id")
+            .contains(&format!("{CORRELATION_ID_LOG_KEY}: {correlation_id}")));
+    }
+
     #[test]
     fn logger_extension_new_with_name() {
         let expected_name = "my name";
@@ -2,7 +2,10 @@ use std::collections::BTreeSet;
 
 use serde::{Deserialize, Serialize};
 
-use crate::entities::{CardanoTransactionsSigningConfig, SignedEntityTypeDiscriminants};
+use crate::entities::{
+    CardanoTransactionsSigningConfig, CompressionAlgorithm, SignedEntityTypeDiscriminants,
+};
+use crate::MagicId;
 
 /// Message advertised by an Aggregator to inform about its features
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -15,6 +18,14 @@ pub struct AggregatorFeaturesMessage {
 
     /// Capabilities of the Aggregator
     pub capabilities: AggregatorCapabilities,
+
+    /// Cardano network of the Aggregator (ie: `mainnet`, `preprod`, `devnet`, `private`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network: Option<String>,
+
+    /// Magic id of the Cardano network of the Aggregator, which identifies the private networks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network_magic: Option<MagicId>,
 }
 
 impl AggregatorFeaturesMessage {
@@ -29,7 +40,11 @@ impl AggregatorFeaturesMessage {
                 ]),
                 cardano_transactions_prover: None,
                 cardano_transactions_signing_config: None,
+                api_versions: vec![],
+                compression_algorithms: vec![],
             },
+            network: None,
+            network_magic: None,
         }
     }
 }
@@ -47,6 +62,24 @@ pub struct AggregatorCapabilities {
     /// Cardano transactions signing configuration
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cardano_transactions_signing_config: Option<CardanoTransactionsSigningConfig>,
+
+    /// Versions of the API served by the aggregator, sorted from the oldest to the newest
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub api_versions: Vec<String>,
+
+    /// Compression algorithms of the artifacts archives produced by the aggregator
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub compression_algorithms: Vec<CompressionAlgorithm>,
+}
+
+impl AggregatorCapabilities {
+    /// Check if the aggregator signs the given signed entity type
+    pub fn is_signed_entity_type_enabled(
+        &self,
+        signed_entity_type: &SignedEntityTypeDiscriminants,
+    ) -> bool {
+        self.signed_entity_types.contains(signed_entity_type)
+    }
 }
 
 /// Cardano transactions prover capabilities
@@ -106,7 +139,11 @@ mod tests {
                     security_parameter: BlockNumber(70),
                     step: BlockNumber(20),
                 }),
+                api_versions: vec!["0.1.0".to_string(), "0.1.1".to_string()],
+                compression_algorithms: vec![CompressionAlgorithm::Zstandard],
             },
+            network: Some("private".to_string()),
+            network_magic: Some(1234),
         }
     }
 
@@ -121,8 +158,12 @@ mod tests {
             "cardano_transactions_signing_config": {
                 "security_parameter": 70,
                 "step": 20
-            }
-        }
+            },
+            "api_versions": ["0.1.0", "0.1.1"],
+            "compression_algorithms": ["zstandard"]
+        },
+        "network": "private",
+        "network_magic": 1234
     }"#;
 
     // Test the retro compatibility with possible future upgrades.
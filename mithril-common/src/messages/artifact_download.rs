@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+
+use crate::entities::SignedEntityTypeDiscriminants;
+
+/// Message structure of an artifact download event
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArtifactDownloadMessage {
+    /// Type of the downloaded artifact
+    pub artifact_type: SignedEntityTypeDiscriminants,
+
+    /// Identifier of the downloaded artifact (hash or digest)
+    pub artifact_id: String,
+}
+
+impl ArtifactDownloadMessage {
+    /// Create a new message for the given artifact.
+    pub fn new<T: Into<String>>(
+        artifact_type: SignedEntityTypeDiscriminants,
+        artifact_id: T,
+    ) -> Self {
+        Self {
+            artifact_type,
+            artifact_id: artifact_id.into(),
+        }
+    }
+
+    /// Return a dummy test entity (test-only).
+    pub fn dummy() -> Self {
+        Self {
+            artifact_type: SignedEntityTypeDiscriminants::CardanoStakeDistribution,
+            artifact_id: "6da2b104ed68481ef829d72d72c2f6a20142916d17985e01774b14ed49f0fea1"
+                .to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn golden_message_v1() -> ArtifactDownloadMessage {
+        ArtifactDownloadMessage {
+            artifact_type: SignedEntityTypeDiscriminants::CardanoStakeDistribution,
+            artifact_id: "6da2b104ed68481ef829d72d72c2f6a20142916d17985e01774b14ed49f0fea1"
+                .to_string(),
+        }
+    }
+
+    // Test the retro compatibility with possible future upgrades.
+    #[test]
+    fn test_v1() {
+        let json = r#"{
+"artifact_type": "CardanoStakeDistribution",
+"artifact_id": "6da2b104ed68481ef829d72d72c2f6a20142916d17985e01774b14ed49f0fea1"
+}
+"#;
+        let message: ArtifactDownloadMessage = serde_json::from_str(json).expect(
+            "This JSON is expected to be successfully parsed into a ArtifactDownloadMessage instance.",
+        );
+
+        assert_eq!(golden_message_v1(), message);
+    }
+}
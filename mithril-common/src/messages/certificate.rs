@@ -139,6 +139,7 @@ impl TryFrom<CertificateMessage> for Certificate {
             initiated_at: certificate_message.metadata.initiated_at,
             sealed_at: certificate_message.metadata.sealed_at,
             signers: certificate_message.metadata.signers,
+            hash_algorithm: certificate_message.metadata.hash_algorithm,
         };
 
         let certificate = Certificate {
@@ -192,6 +193,7 @@ impl TryFrom<Certificate> for CertificateMessage {
             initiated_at: certificate.metadata.initiated_at,
             sealed_at: certificate.metadata.sealed_at,
             signers: certificate.metadata.signers,
+            hash_algorithm: certificate.metadata.hash_algorithm,
         };
 
         let (multi_signature, genesis_signature) = match certificate.signature {
@@ -233,6 +235,7 @@ mod tests {
     use chrono::{DateTime, Utc};
 
     use crate::entities::{CardanoDbBeacon, ProtocolParameters, StakeDistributionParty};
+    use crate::protocol::HashAlgorithm;
 
     use super::*;
 
@@ -331,6 +334,7 @@ mod tests {
                         stake: 20,
                     },
                 ],
+                hash_algorithm: HashAlgorithm::default(),
             },
             protocol_message: protocol_message.clone(),
             signed_message: "signed_message".to_string(),
@@ -377,6 +381,7 @@ mod tests {
                         stake: 20,
                     },
                 ],
+                hash_algorithm: HashAlgorithm::default(),
             },
             protocol_message: protocol_message.clone(),
             signed_message: "signed_message".to_string(),
@@ -401,4 +406,75 @@ mod tests {
 
         assert_eq!(golden_actual_message(), message);
     }
+
+    mod fuzzing {
+        use proptest::prelude::*;
+
+        use crate::crypto_helper::tests_setup::setup_certificate_chain;
+
+        use super::*;
+
+        fn round_trip(message: CertificateMessage) -> CertificateMessage {
+            let json = serde_json::to_string(&message).unwrap();
+            let deserialized_message: CertificateMessage = serde_json::from_str(&json).unwrap();
+            let certificate = Certificate::try_from(deserialized_message).unwrap();
+
+            CertificateMessage::try_from(certificate).unwrap()
+        }
+
+        #[test]
+        fn genesis_and_standard_certificates_round_trip_through_their_json_message() {
+            let (certificates, _) = setup_certificate_chain(5, 2);
+
+            for certificate in certificates {
+                let message = CertificateMessage::try_from(certificate).unwrap();
+
+                assert_eq!(message, round_trip(message.clone()));
+            }
+        }
+
+        proptest! {
+            #[test]
+            fn truncated_json_payloads_are_rejected_without_panicking(
+                position in any::<prop::sample::Index>(),
+            ) {
+                let json = serde_json::to_string(&CertificateMessage::dummy()).unwrap();
+                let position = position.index(json.len());
+                prop_assume!(json.is_char_boundary(position));
+
+                prop_assert!(serde_json::from_str::<CertificateMessage>(&json[..position]).is_err());
+            }
+
+            #[test]
+            fn json_payloads_with_inserted_garbage_are_handled_without_panicking(
+                position in any::<prop::sample::Index>(),
+                garbage in ".{1,16}",
+            ) {
+                let json = serde_json::to_string(&CertificateMessage::dummy()).unwrap();
+                let position = position.index(json.len());
+                prop_assume!(json.is_char_boundary(position));
+                let payload = format!("{}{garbage}{}", &json[..position], &json[position..]);
+
+                if let Ok(message) = serde_json::from_str::<CertificateMessage>(&payload) {
+                    let _ = Certificate::try_from(message);
+                }
+            }
+
+            #[test]
+            fn arbitrary_encoded_keys_and_signatures_are_rejected_without_panicking(
+                aggregate_verification_key in "[0-9a-f]{0,256}",
+                multi_signature in "[0-9a-f]{0,256}",
+                genesis_signature in "[0-9a-f]{0,256}",
+            ) {
+                let message = CertificateMessage {
+                    aggregate_verification_key,
+                    multi_signature,
+                    genesis_signature,
+                    ..CertificateMessage::dummy()
+                };
+
+                prop_assert!(Certificate::try_from(message).is_err());
+            }
+        }
+    }
 }
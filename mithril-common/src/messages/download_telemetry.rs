@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+
+use crate::entities::SignedEntityTypeDiscriminants;
+
+/// Message structure of an anonymous download telemetry report sent by a client.
+///
+/// The report does not hold any data that identifies the client or the downloaded artifact.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DownloadTelemetryMessage {
+    /// Type of the downloaded artifact
+    pub artifact_type: SignedEntityTypeDiscriminants,
+
+    /// Whether the download, and the verification of the downloaded artifact, succeeded
+    pub succeeded: bool,
+
+    /// Duration of the download, in milliseconds
+    pub duration_ms: u64,
+}
+
+impl DownloadTelemetryMessage {
+    /// Create a new message for a download of the given artifact type.
+    pub fn new(
+        artifact_type: SignedEntityTypeDiscriminants,
+        succeeded: bool,
+        duration_ms: u64,
+    ) -> Self {
+        Self {
+            artifact_type,
+            succeeded,
+            duration_ms,
+        }
+    }
+
+    /// Return a dummy test entity (test-only).
+    pub fn dummy() -> Self {
+        Self {
+            artifact_type: SignedEntityTypeDiscriminants::CardanoImmutableFilesFull,
+            succeeded: true,
+            duration_ms: 183_000,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn golden_message_v1() -> DownloadTelemetryMessage {
+        DownloadTelemetryMessage {
+            artifact_type: SignedEntityTypeDiscriminants::CardanoImmutableFilesFull,
+            succeeded: false,
+            duration_ms: 183_000,
+        }
+    }
+
+    // Test the retro compatibility with possible future upgrades.
+    #[test]
+    fn test_v1() {
+        let json = r#"{
+"artifact_type": "CardanoImmutableFilesFull",
+"succeeded": false,
+"duration_ms": 183000
+}
+"#;
+        let message: DownloadTelemetryMessage = serde_json::from_str(json).expect(
+            "This JSON is expected to be successfully parsed into a DownloadTelemetryMessage instance.",
+        );
+
+        assert_eq!(golden_message_v1(), message);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::entities::{ProtocolParameters, ProtocolVersion, StakeDistributionParty};
+use crate::protocol::HashAlgorithm;
 
 use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
@@ -34,6 +35,11 @@ pub struct CertificateMetadataMessagePart {
     /// The list of the active signers with their stakes and verification keys
     /// part of METADATA(p,n)
     pub signers: Vec<StakeDistributionParty>,
+
+    /// Hash function used to compute the hash of the certificate and the digest of its artifact
+    /// part of METADATA(p,n)
+    #[serde(default, skip_serializing_if = "HashAlgorithm::is_default")]
+    pub hash_algorithm: HashAlgorithm,
 }
 
 impl CertificateMetadataMessagePart {
@@ -59,6 +65,7 @@ impl CertificateMetadataMessagePart {
                     stake: 20,
                 },
             ],
+            hash_algorithm: HashAlgorithm::default(),
         }
     }
 }
@@ -88,6 +95,7 @@ mod tests {
                     stake: 20,
                 },
             ],
+            hash_algorithm: HashAlgorithm::default(),
         }
     }
 
@@ -154,4 +162,20 @@ mod tests {
 
         assert_eq!(golden_message(), message);
     }
+
+    #[test]
+    fn hash_algorithm_is_serialized_only_when_not_default() {
+        let json = serde_json::to_value(golden_message()).unwrap();
+        assert!(json.get("hash_algorithm").is_none());
+
+        let message = CertificateMetadataMessagePart {
+            hash_algorithm: HashAlgorithm::Blake2b256,
+            ..golden_message()
+        };
+        let json = serde_json::to_value(&message).unwrap();
+        assert_eq!("blake2b256", json["hash_algorithm"]);
+
+        let deserialized: CertificateMetadataMessagePart = serde_json::from_value(json).unwrap();
+        assert_eq!(message, deserialized);
+    }
 }
@@ -1,6 +1,7 @@
 //! Messages module
 //! This module aims at providing shared structures for API communications.
 mod aggregator_features;
+mod artifact_download;
 mod cardano_stake_distribution;
 mod cardano_stake_distribution_list;
 mod cardano_transaction_snapshot;
@@ -9,6 +10,7 @@ mod cardano_transactions_proof;
 mod certificate;
 mod certificate_list;
 mod certificate_pending;
+mod download_telemetry;
 mod epoch_settings;
 mod interface;
 mod message_parts;
@@ -16,6 +18,7 @@ mod mithril_stake_distribution;
 mod mithril_stake_distribution_list;
 mod register_signature;
 mod register_signer;
+mod registration_challenge;
 mod snapshot;
 mod snapshot_download;
 mod snapshot_list;
@@ -23,6 +26,7 @@ mod snapshot_list;
 pub use aggregator_features::{
     AggregatorCapabilities, AggregatorFeaturesMessage, CardanoTransactionsProverCapabilities,
 };
+pub use artifact_download::ArtifactDownloadMessage;
 pub use cardano_stake_distribution::CardanoStakeDistributionMessage;
 pub use cardano_stake_distribution_list::{
     CardanoStakeDistributionListItemMessage, CardanoStakeDistributionListMessage,
@@ -41,6 +45,7 @@ pub use certificate_list::{
 };
 #[allow(deprecated)]
 pub use certificate_pending::CertificatePendingMessage;
+pub use download_telemetry::DownloadTelemetryMessage;
 pub use epoch_settings::EpochSettingsMessage;
 pub use interface::*;
 pub use message_parts::*;
@@ -50,6 +55,7 @@ pub use mithril_stake_distribution_list::{
 };
 pub use register_signature::RegisterSignatureMessage;
 pub use register_signer::RegisterSignerMessage;
+pub use registration_challenge::RegistrationChallengeMessage;
 pub use snapshot::SnapshotMessage;
 pub use snapshot_download::SnapshotDownloadMessage;
 pub use snapshot_list::{SnapshotListItemMessage, SnapshotListMessage};
@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use std::fmt::{Debug, Formatter};
 
 use crate::entities::{HexEncodedSingleSignature, LotteryIndex, PartyId, SignedEntityType};
+use crate::protocol::SignatureScheme;
 #[cfg(any(test, feature = "test_tools"))]
 use crate::test_utils::fake_keys;
 
@@ -28,6 +29,13 @@ pub struct RegisterSignatureMessage {
     /// for the signed entity type.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub signed_message: Option<String>,
+
+    /// Signature scheme of the single signature
+    ///
+    /// Omitted for the default scheme, other schemes are sent during the transition to a new
+    /// signature scheme.
+    #[serde(default, skip_serializing_if = "SignatureScheme::is_default")]
+    pub signature_scheme: SignatureScheme,
 }
 
 impl RegisterSignatureMessage {
@@ -40,6 +48,7 @@ impl RegisterSignatureMessage {
                 signature: fake_keys::single_signature()[0].to_string(),
                 won_indexes: vec![1, 3],
                 signed_message: None,
+                signature_scheme: SignatureScheme::default(),
             }
         }
     }
@@ -55,7 +64,8 @@ impl Debug for RegisterSignatureMessage {
                 &format_args!("{:?}", self.signed_entity_type),
             )
             .field("party_id", &self.party_id)
-            .field("won_indexes", &format_args!("{:?}", self.won_indexes));
+            .field("won_indexes", &format_args!("{:?}", self.won_indexes))
+            .field("signature_scheme", &self.signature_scheme);
 
         match is_pretty_printing {
             true => debug.field("signature", &self.signature).finish(),
@@ -96,6 +106,7 @@ mod tests {
             signature: "7b227369676d61223a5b3133302c3137372c31352c3232392c32342c3235312c3234372c3137312c3139362c3231302c3134332c3131332c38362c3138392c39322c35362c3131322c33332c3139332c3231322c35342c3231342c32382c3231362c3232372c3137332c3130302c3132372c3137382c34302c39382c38372c32392c3138312c3235352c3131312c3135372c3232342c3233352c34362c3130302c3136392c3233322c3138392c3235322c38322c3133392c33365d2c22696e6465786573223a5b302c312c332c342c362c382c392c31302c31312c31322c31342c31382c32312c32322c32332c32352c32362c32372c33302c33332c33342c33382c34312c34332c35302c35382c35392c36302c36312c36322c36372c36392c37312c37332c37352c37362c37372c38312c38322c38332c38342c39302c39312c39322c39332c39372c39385d2c227369676e65725f696e646578223a327d".to_string(),
             won_indexes: vec![1, 3],
             signed_message: Some("6a7e737c312972d2346b65ac3075696e04286d046dddaf8004121e3d5e27cc0d".to_string()),
+            signature_scheme: SignatureScheme::StmV1,
         }
     }
 
@@ -104,7 +115,8 @@ mod tests {
         "party_id": "party_id",
         "signature": "7b227369676d61223a5b3133302c3137372c31352c3232392c32342c3235312c3234372c3137312c3139362c3231302c3134332c3131332c38362c3138392c39322c35362c3131322c33332c3139332c3231322c35342c3231342c32382c3231362c3232372c3137332c3130302c3132372c3137382c34302c39382c38372c32392c3138312c3235352c3131312c3135372c3232342c3233352c34362c3130302c3136392c3233322c3138392c3235322c38322c3133392c33365d2c22696e6465786573223a5b302c312c332c342c362c382c392c31302c31312c31322c31342c31382c32312c32322c32332c32352c32362c32372c33302c33332c33342c33382c34312c34332c35302c35382c35392c36302c36312c36322c36372c36392c37312c37332c37352c37362c37372c38312c38322c38332c38342c39302c39312c39322c39332c39372c39385d2c227369676e65725f696e646578223a327d",
         "indexes": [1, 3],
-        "signed_message": "6a7e737c312972d2346b65ac3075696e04286d046dddaf8004121e3d5e27cc0d"
+        "signed_message": "6a7e737c312972d2346b65ac3075696e04286d046dddaf8004121e3d5e27cc0d",
+        "signature_scheme": "stm-v1"
     }"#;
 
     #[test]
@@ -124,4 +136,11 @@ mod tests {
 
         assert_eq!(golden_message_actual(), message);
     }
+
+    #[test]
+    fn test_default_signature_scheme_is_not_serialized() {
+        let json = serde_json::to_value(golden_message_actual()).unwrap();
+
+        assert!(json.get("signature_scheme").is_none());
+    }
 }
@@ -0,0 +1,55 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Message structure of a challenge issued by an aggregator to authenticate a signer registration
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegistrationChallengeMessage {
+    /// Challenge to sign with the KES secret key of the signer
+    pub challenge: String,
+
+    /// Date after which the challenge is not accepted anymore
+    pub expires_at: DateTime<Utc>,
+}
+
+impl RegistrationChallengeMessage {
+    /// Return a dummy test entity (test-only).
+    pub fn dummy() -> Self {
+        Self {
+            challenge: "5e1fa6d2c0e8b1b1b2b9f7c8d59a5f0a3f6c3a8b7b3c4a1d2e9f0a1b2c3d4e5f"
+                .to_string(),
+            expires_at: DateTime::parse_from_rfc3339("2024-02-12T13:11:47Z")
+                .unwrap()
+                .with_timezone(&Utc),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn golden_message_v1() -> RegistrationChallengeMessage {
+        RegistrationChallengeMessage {
+            challenge: "5e1fa6d2c0e8b1b1b2b9f7c8d59a5f0a3f6c3a8b7b3c4a1d2e9f0a1b2c3d4e5f"
+                .to_string(),
+            expires_at: DateTime::parse_from_rfc3339("2024-02-12T13:11:47Z")
+                .unwrap()
+                .with_timezone(&Utc),
+        }
+    }
+
+    // Test the retro compatibility with possible future upgrades.
+    #[test]
+    fn test_v1() {
+        let json = r#"{
+"challenge": "5e1fa6d2c0e8b1b1b2b9f7c8d59a5f0a3f6c3a8b7b3c4a1d2e9f0a1b2c3d4e5f",
+"expires_at": "2024-02-12T13:11:47Z"
+}
+"#;
+        let message: RegistrationChallengeMessage = serde_json::from_str(json).expect(
+            "This JSON is expected to be successfully parsed into a RegistrationChallengeMessage instance.",
+        );
+
+        assert_eq!(golden_message_v1(), message);
+    }
+}
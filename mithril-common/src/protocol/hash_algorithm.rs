@@ -0,0 +1,83 @@
+use blake2::{digest::consts::U32, Blake2b};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use strum::{Display, EnumIter, EnumString};
+
+/// BLAKE2b hash function with a 256 bits output
+pub(crate) type Blake2b256 = Blake2b<U32>;
+
+/// Hash function used to compute the digests of the signed artifacts and the hashes of the
+/// certificates.
+///
+/// The algorithm of each era is given by [SupportedEra::hash_algorithm][crate::era::SupportedEra::hash_algorithm].
+#[derive(
+    Display,
+    EnumString,
+    EnumIter,
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum HashAlgorithm {
+    /// SHA-256, used since the first era
+    #[default]
+    Sha256,
+    /// BLAKE2b with a 256 bits output, the hash function of the Cardano ledger
+    Blake2b256,
+}
+
+impl HashAlgorithm {
+    /// Check if this is the default algorithm, which is omitted from the messages and from the
+    /// hashes so that they stay readable, and unchanged, for the nodes that predate the hash
+    /// algorithms
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Compute the hash of the given data, hex encoded
+    pub fn hash_hex<T: AsRef<[u8]>>(&self, data: T) -> String {
+        match self {
+            Self::Sha256 => hex::encode(Sha256::digest(data)),
+            Self::Blake2b256 => hex::encode(Blake2b256::digest(data)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use strum::IntoEnumIterator;
+
+    use super::*;
+
+    #[test]
+    fn hash_hex_with_each_algorithm() {
+        assert_eq!(
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            HashAlgorithm::Sha256.hash_hex("")
+        );
+        assert_eq!(
+            "0e5751c026e543b2e8ab2eb06099daa1d1e5df47778f7787faab45cdf12fe3a8",
+            HashAlgorithm::Blake2b256.hash_hex("")
+        );
+    }
+
+    #[test]
+    fn algorithm_names_round_trip() {
+        for algorithm in HashAlgorithm::iter() {
+            assert_eq!(
+                algorithm,
+                HashAlgorithm::from_str(&algorithm.to_string()).unwrap()
+            );
+        }
+        assert_eq!("blake2b256", HashAlgorithm::Blake2b256.to_string());
+    }
+}
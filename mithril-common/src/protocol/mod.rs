@@ -4,11 +4,21 @@
 //! such as issuing single signatures, aggregating them as multi-signatures or computing
 //! aggregate verification keys.
 
+mod hash_algorithm;
 mod multi_signer;
+mod quorum_estimator;
+mod signature_scheme;
 mod signer_builder;
 mod single_signer;
 
+pub(crate) use hash_algorithm::Blake2b256;
+pub use hash_algorithm::HashAlgorithm;
 pub use multi_signer::MultiSigner;
+pub use quorum_estimator::{
+    check_lottery_parameters, LotteryParametersError, LotteryParametersWarning, QuorumEstimate,
+    QuorumEstimator, UNLIKELY_QUORUM_PROBABILITY_THRESHOLD,
+};
+pub use signature_scheme::SignatureScheme;
 pub use signer_builder::{SignerBuilder, SignerBuilderError};
 pub use single_signer::SingleSigner;
 
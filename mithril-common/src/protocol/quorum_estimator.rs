@@ -0,0 +1,492 @@
+use anyhow::{anyhow, Context};
+use rand_core::RngCore;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::entities::{ProtocolParameters, Stake, StakeDistribution};
+use crate::StdResult;
+
+/// Probability of reaching the quorum below which the certification is considered unlikely.
+pub const UNLIKELY_QUORUM_PROBABILITY_THRESHOLD: f64 = 0.99;
+
+/// Error raised when the protocol parameters or the stake distribution can not be used by the
+/// lottery.
+#[derive(Error, Debug, PartialEq)]
+pub enum LotteryParametersError {
+    /// The quorum parameter `k` is zero.
+    #[error("the quorum parameter k must be strictly positive")]
+    ZeroQuorum,
+
+    /// The number of lotteries `m` is zero.
+    #[error("the number of lotteries m must be strictly positive")]
+    ZeroLotteries,
+
+    /// The `phi_f` parameter is not in `]0, 1]`.
+    #[error("the phi_f parameter must be in ]0, 1], got {0}")]
+    InvalidPhiF(f64),
+
+    /// The stake distribution has no stake.
+    #[error("the stake distribution has no stake")]
+    EmptyStakeDistribution,
+
+    /// The total stake of the stake distribution overflows.
+    #[error("the total stake of the stake distribution overflows")]
+    StakeOverflow,
+}
+
+/// Warning about protocol parameters that make the certification unlikely.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum LotteryParametersWarning {
+    /// The quorum can not be reached even if every lottery is won, as it is greater than the
+    /// number of lotteries.
+    QuorumUnreachable {
+        /// Maximum number of distinct lotteries that can be won
+        max_won_lotteries: u64,
+    },
+
+    /// The probability to reach the quorum is below [UNLIKELY_QUORUM_PROBABILITY_THRESHOLD].
+    UnlikelyQuorum {
+        /// Estimated probability to reach the quorum
+        quorum_probability: f64,
+    },
+}
+
+/// Estimate of the number of lotteries won by the signers of a stake distribution.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuorumEstimate {
+    /// Expected number of distinct lotteries won by at least one signer
+    pub expected_won_lotteries: f64,
+
+    /// Standard deviation of the number of distinct lotteries won by at least one signer
+    pub standard_deviation: f64,
+
+    /// Probability that the signers win at least `k` distinct lotteries
+    pub quorum_probability: f64,
+
+    /// Warnings about the protocol parameters
+    pub warnings: Vec<LotteryParametersWarning>,
+}
+
+/// Estimate the probability that the signers of a stake distribution reach the quorum of the
+/// lottery with given protocol parameters.
+///
+/// A signer with a relative stake `w` wins each of the `m` lotteries with the probability
+/// `phi(w) = 1 - (1 - phi_f)^w`. A multi-signature aggregates the signatures of `k` distinct
+/// lottery indexes, so what matters is the number of indexes won by at least one signer: each
+/// index is won with the probability `q = 1 - Π_j (1 - phi(w_j))`, independently of the other
+/// indexes, and the quorum is reached with the probability `P(Binomial(m, q) >= k)`.
+#[derive(Debug, Clone)]
+pub struct QuorumEstimator {
+    protocol_parameters: ProtocolParameters,
+    stakes: Vec<Stake>,
+    total_stake: Stake,
+}
+
+impl QuorumEstimator {
+    /// Create a new estimator after checking the protocol parameters and the stake distribution.
+    pub fn new(
+        protocol_parameters: &ProtocolParameters,
+        stake_distribution: &StakeDistribution,
+    ) -> Result<Self, LotteryParametersError> {
+        if protocol_parameters.k == 0 {
+            return Err(LotteryParametersError::ZeroQuorum);
+        }
+        if protocol_parameters.m == 0 {
+            return Err(LotteryParametersError::ZeroLotteries);
+        }
+        if !(protocol_parameters.phi_f > 0.0 && protocol_parameters.phi_f <= 1.0) {
+            return Err(LotteryParametersError::InvalidPhiF(
+                protocol_parameters.phi_f,
+            ));
+        }
+        let total_stake = stake_distribution
+            .values()
+            .try_fold(0 as Stake, |total, stake| total.checked_add(*stake))
+            .ok_or(LotteryParametersError::StakeOverflow)?;
+        if total_stake == 0 {
+            return Err(LotteryParametersError::EmptyStakeDistribution);
+        }
+
+        Ok(Self {
+            protocol_parameters: protocol_parameters.to_owned(),
+            stakes: stake_distribution.values().copied().collect(),
+            total_stake,
+        })
+    }
+
+    /// Probability that a signer with the given stake wins a lottery
+    pub fn lottery_win_probability(&self, stake: Stake) -> f64 {
+        let relative_stake = stake as f64 / self.total_stake as f64;
+
+        1.0 - (1.0 - self.protocol_parameters.phi_f).powf(relative_stake)
+    }
+
+    /// Estimate the probability to reach the quorum when all the signers sign.
+    pub fn estimate(&self) -> QuorumEstimate {
+        let index_win_probability = self.index_win_probability(&self.stakes);
+        let (expected_won_lotteries, variance) = self.won_lotteries_moments(index_win_probability);
+        let quorum_probability = self.quorum_probability(index_win_probability);
+
+        QuorumEstimate {
+            expected_won_lotteries,
+            standard_deviation: variance.sqrt(),
+            quorum_probability,
+            warnings: self.warnings(quorum_probability),
+        }
+    }
+
+    /// Simulate `rounds` signing rounds where each signer signs with the probability
+    /// `participation_rate`, and return the average probability to reach the quorum.
+    pub fn simulate<R: RngCore>(
+        &self,
+        rounds: u32,
+        participation_rate: f64,
+        rng: &mut R,
+    ) -> StdResult<QuorumEstimate> {
+        if rounds == 0 {
+            return Err(anyhow!(
+                "the number of simulated rounds must be strictly positive"
+            ));
+        }
+        if !(0.0..=1.0).contains(&participation_rate) {
+            return Err(anyhow!(
+                "the participation rate must be in [0, 1], got {participation_rate}"
+            ));
+        }
+
+        let mut quorum_probabilities_sum = 0.0;
+        let mut expected_won_lotteries_sum = 0.0;
+        let mut variances_sum = 0.0;
+        for _ in 0..rounds {
+            let participating_stakes = self
+                .stakes
+                .iter()
+                .filter(|_| uniform_sample(rng) < participation_rate)
+                .copied()
+                .collect::<Vec<_>>();
+            let index_win_probability = self.index_win_probability(&participating_stakes);
+            let (expected_won_lotteries, variance) =
+                self.won_lotteries_moments(index_win_probability);
+            quorum_probabilities_sum += self.quorum_probability(index_win_probability);
+            expected_won_lotteries_sum += expected_won_lotteries;
+            variances_sum += variance;
+        }
+        let rounds = rounds as f64;
+        let quorum_probability = quorum_probabilities_sum / rounds;
+
+        Ok(QuorumEstimate {
+            expected_won_lotteries: expected_won_lotteries_sum / rounds,
+            standard_deviation: (variances_sum / rounds).sqrt(),
+            quorum_probability,
+            warnings: self.warnings(quorum_probability),
+        })
+    }
+
+    /// Probability that a lottery index is won by at least one of the signers with the given
+    /// stakes
+    fn index_win_probability(&self, stakes: &[Stake]) -> f64 {
+        let index_loss_probability = stakes.iter().fold(1.0, |probability, stake| {
+            probability * (1.0 - self.lottery_win_probability(*stake))
+        });
+
+        1.0 - index_loss_probability
+    }
+
+    /// Expected value and variance of the number of distinct lottery indexes won
+    fn won_lotteries_moments(&self, index_win_probability: f64) -> (f64, f64) {
+        let lotteries = self.protocol_parameters.m as f64;
+
+        (
+            lotteries * index_win_probability,
+            lotteries * index_win_probability * (1.0 - index_win_probability),
+        )
+    }
+
+    /// Probability to win at least `k` distinct lottery indexes out of `m`
+    fn quorum_probability(&self, index_win_probability: f64) -> f64 {
+        binomial_upper_tail(
+            self.protocol_parameters.m,
+            index_win_probability,
+            self.protocol_parameters.k,
+        )
+    }
+
+    fn warnings(&self, quorum_probability: f64) -> Vec<LotteryParametersWarning> {
+        let mut warnings = vec![];
+        let max_won_lotteries = self.protocol_parameters.m;
+        if max_won_lotteries < self.protocol_parameters.k {
+            warnings.push(LotteryParametersWarning::QuorumUnreachable { max_won_lotteries });
+        }
+        if quorum_probability < UNLIKELY_QUORUM_PROBABILITY_THRESHOLD {
+            warnings.push(LotteryParametersWarning::UnlikelyQuorum { quorum_probability });
+        }
+
+        warnings
+    }
+}
+
+/// Check the protocol parameters against a stake distribution and estimate the probability
+/// to reach the quorum when all the signers sign.
+pub fn check_lottery_parameters(
+    protocol_parameters: &ProtocolParameters,
+    stake_distribution: &StakeDistribution,
+) -> StdResult<QuorumEstimate> {
+    let estimator = QuorumEstimator::new(protocol_parameters, stake_distribution)
+        .with_context(|| "Invalid lottery parameters")?;
+
+    Ok(estimator.estimate())
+}
+
+fn uniform_sample<R: RngCore>(rng: &mut R) -> f64 {
+    // 53 random bits, the precision of the mantissa of a f64
+    (rng.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Probability `P(X >= k)` for `X ~ Binomial(n, p)`, summed in the log domain so that it stays
+/// accurate for large numbers of lotteries
+fn binomial_upper_tail(n: u64, p: f64, k: u64) -> f64 {
+    if k == 0 {
+        return 1.0;
+    }
+    if k > n || p <= 0.0 {
+        return 0.0;
+    }
+    if p >= 1.0 {
+        return 1.0;
+    }
+
+    // ln P(X = i + 1) = ln P(X = i) + ln((n - i) / (i + 1)) + ln(p / (1 - p))
+    let log_odds = p.ln() - (-p).ln_1p();
+    let mut log_pmf = n as f64 * (-p).ln_1p();
+    let mut tail_log_pmfs = Vec::with_capacity((n - k + 1) as usize);
+    for i in 0..=n {
+        if i >= k {
+            tail_log_pmfs.push(log_pmf);
+        }
+        if i < n {
+            log_pmf += ((n - i) as f64 / (i + 1) as f64).ln() + log_odds;
+        }
+    }
+    let max_log_pmf = tail_log_pmfs
+        .iter()
+        .copied()
+        .fold(f64::NEG_INFINITY, f64::max);
+    let tail = tail_log_pmfs
+        .iter()
+        .map(|log_pmf| (log_pmf - max_log_pmf).exp())
+        .sum::<f64>()
+        * max_log_pmf.exp();
+
+    tail.min(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
+
+    use super::*;
+
+    fn stake_distribution(stakes: &[Stake]) -> StakeDistribution {
+        stakes
+            .iter()
+            .enumerate()
+            .map(|(i, stake)| (format!("pool-{i}"), *stake))
+            .collect()
+    }
+
+    fn equal_stake_distribution(number_of_signers: usize) -> StakeDistribution {
+        stake_distribution(&vec![1_000; number_of_signers])
+    }
+
+    #[test]
+    fn binomial_upper_tail_matches_known_values() {
+        for (n, p, k, expected) in [
+            (10, 0.5, 5, 638.0 / 1024.0),
+            (10, 0.5, 0, 1.0),
+            (10, 0.5, 10, 1.0 / 1024.0),
+            (10, 0.5, 11, 0.0),
+            (3, 0.2, 2, 3.0 * 0.2 * 0.2 * 0.8 + 0.2 * 0.2 * 0.2),
+            (20_000, 0.65, 13_000, 0.5 + 0.002_5),
+        ] {
+            let tail = binomial_upper_tail(n, p, k);
+            assert!(
+                (tail - expected).abs() < 5e-3,
+                "P(Binomial({n}, {p}) >= {k}) = {tail} instead of {expected}",
+            );
+        }
+        assert_eq!(0.0, binomial_upper_tail(10, 0.0, 1));
+        assert_eq!(1.0, binomial_upper_tail(10, 1.0, 10));
+    }
+
+    #[test]
+    fn reject_invalid_protocol_parameters() {
+        let stake_distribution = equal_stake_distribution(10);
+
+        for (protocol_parameters, expected_error) in [
+            (
+                ProtocolParameters::new(0, 100, 0.65),
+                LotteryParametersError::ZeroQuorum,
+            ),
+            (
+                ProtocolParameters::new(5, 0, 0.65),
+                LotteryParametersError::ZeroLotteries,
+            ),
+            (
+                ProtocolParameters::new(5, 100, 0.0),
+                LotteryParametersError::InvalidPhiF(0.0),
+            ),
+            (
+                ProtocolParameters::new(5, 100, 1.5),
+                LotteryParametersError::InvalidPhiF(1.5),
+            ),
+        ] {
+            assert_eq!(
+                expected_error,
+                QuorumEstimator::new(&protocol_parameters, &stake_distribution).unwrap_err()
+            );
+        }
+    }
+
+    #[test]
+    fn reject_empty_or_overflowing_stake_distributions() {
+        let protocol_parameters = ProtocolParameters::new(5, 100, 0.65);
+
+        assert_eq!(
+            LotteryParametersError::EmptyStakeDistribution,
+            QuorumEstimator::new(&protocol_parameters, &stake_distribution(&[0, 0])).unwrap_err()
+        );
+        assert_eq!(
+            LotteryParametersError::StakeOverflow,
+            QuorumEstimator::new(&protocol_parameters, &stake_distribution(&[Stake::MAX, 1]))
+                .unwrap_err()
+        );
+    }
+
+    #[test]
+    fn a_signer_with_all_the_stake_wins_with_probability_phi_f() {
+        let estimator = QuorumEstimator::new(
+            &ProtocolParameters::new(5, 100, 0.65),
+            &stake_distribution(&[1_000]),
+        )
+        .unwrap();
+
+        assert!((estimator.lottery_win_probability(1_000) - 0.65).abs() < 1e-12);
+        assert_eq!(0.0, estimator.lottery_win_probability(0));
+    }
+
+    #[test]
+    fn expected_won_lotteries_of_a_single_signer() {
+        let estimate = check_lottery_parameters(
+            &ProtocolParameters::new(5, 100, 0.65),
+            &stake_distribution(&[1_000]),
+        )
+        .unwrap();
+
+        assert!((estimate.expected_won_lotteries - 65.0).abs() < 1e-9);
+        assert!((estimate.standard_deviation - (100.0 * 0.65 * 0.35_f64).sqrt()).abs() < 1e-9);
+        assert!(estimate.quorum_probability > 0.999);
+        assert!(estimate.warnings.is_empty());
+    }
+
+    #[test]
+    fn warn_when_the_quorum_is_unreachable() {
+        // Only 100 distinct lottery indexes exist, whatever the number of signers
+        let estimate = check_lottery_parameters(
+            &ProtocolParameters::new(110, 100, 0.65),
+            &equal_stake_distribution(10),
+        )
+        .unwrap();
+
+        assert_eq!(0.0, estimate.quorum_probability);
+        assert!(estimate
+            .warnings
+            .contains(&LotteryParametersWarning::QuorumUnreachable {
+                max_won_lotteries: 100
+            }));
+    }
+
+    #[test]
+    fn all_the_signers_win_a_lottery_index_with_probability_phi_f() {
+        let estimate = check_lottery_parameters(
+            &ProtocolParameters::new(5, 100, 0.65),
+            &stake_distribution(&[1_000, 2_000, 3_000, 4_000]),
+        )
+        .unwrap();
+
+        assert!((estimate.expected_won_lotteries - 65.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn warn_when_the_quorum_is_unlikely() {
+        // About 65 distinct lottery indexes out of 100 are expected to be won
+        let estimate = check_lottery_parameters(
+            &ProtocolParameters::new(70, 100, 0.65),
+            &equal_stake_distribution(10),
+        )
+        .unwrap();
+
+        assert!(estimate.quorum_probability < UNLIKELY_QUORUM_PROBABILITY_THRESHOLD);
+        assert_eq!(
+            vec![LotteryParametersWarning::UnlikelyQuorum {
+                quorum_probability: estimate.quorum_probability
+            }],
+            estimate.warnings
+        );
+    }
+
+    #[test]
+    fn simulation_with_full_participation_matches_the_estimate() {
+        let estimator = QuorumEstimator::new(
+            &ProtocolParameters::new(50, 100, 0.65),
+            &equal_stake_distribution(10),
+        )
+        .unwrap();
+        let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+
+        let estimate = estimator.estimate();
+        let simulated_estimate = estimator.simulate(10, 1.0, &mut rng).unwrap();
+
+        assert!((estimate.quorum_probability - simulated_estimate.quorum_probability).abs() < 1e-9);
+        assert!(
+            (estimate.expected_won_lotteries - simulated_estimate.expected_won_lotteries).abs()
+                < 1e-9
+        );
+    }
+
+    #[test]
+    fn lower_participation_lowers_the_quorum_probability() {
+        let estimator = QuorumEstimator::new(
+            &ProtocolParameters::new(55, 100, 0.65),
+            &equal_stake_distribution(10),
+        )
+        .unwrap();
+        let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+
+        let full_participation = estimator.simulate(100, 1.0, &mut rng).unwrap();
+        let half_participation = estimator.simulate(100, 0.5, &mut rng).unwrap();
+
+        assert!(half_participation.quorum_probability < full_participation.quorum_probability);
+        assert!(
+            half_participation.expected_won_lotteries < full_participation.expected_won_lotteries
+        );
+    }
+
+    #[test]
+    fn reject_invalid_simulation_parameters() {
+        let estimator = QuorumEstimator::new(
+            &ProtocolParameters::new(50, 100, 0.65),
+            &equal_stake_distribution(10),
+        )
+        .unwrap();
+        let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+
+        estimator
+            .simulate(0, 1.0, &mut rng)
+            .expect_err("Zero simulated rounds should be rejected");
+        estimator
+            .simulate(10, 1.5, &mut rng)
+            .expect_err("A participation rate above 1 should be rejected");
+    }
+}
@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, EnumString, IntoEnumIterator};
+
+use crate::crypto_helper::PROTOCOL_VERSION;
+use crate::entities::ProtocolVersion;
+
+/// Signature scheme used by the signers to sign the protocol messages and by the aggregator to
+/// aggregate their single signatures in the multi-signature of a certificate.
+///
+/// The schemes used by each era are given by [SupportedEra::signature_schemes][crate::era::SupportedEra::signature_schemes].
+#[derive(
+    Display,
+    EnumString,
+    EnumIter,
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum SignatureScheme {
+    /// Stake-based threshold multi-signatures, first version
+    #[default]
+    StmV1,
+}
+
+impl SignatureScheme {
+    /// Version of the protocol written in the metadata of the certificates signed with the scheme
+    pub fn protocol_version(&self) -> ProtocolVersion {
+        match self {
+            SignatureScheme::StmV1 => PROTOCOL_VERSION.to_string(),
+        }
+    }
+
+    /// Retrieve the scheme that signed a certificate from the protocol version of its metadata
+    pub fn from_protocol_version(protocol_version: &str) -> Option<Self> {
+        Self::iter().find(|scheme| scheme.protocol_version() == protocol_version)
+    }
+
+    /// Check if this is the default scheme, which is omitted from the messages so that they stay
+    /// readable by the nodes that predate the signature schemes
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retrieve_the_scheme_from_its_protocol_version() {
+        for scheme in SignatureScheme::iter() {
+            assert_eq!(
+                Some(scheme),
+                SignatureScheme::from_protocol_version(&scheme.protocol_version())
+            );
+        }
+        assert_eq!(None, SignatureScheme::from_protocol_version("unknown"));
+    }
+}
@@ -6,9 +6,8 @@ use reqwest::Url;
 use serde::Serialize;
 use serde_json::{json, Value, Value::Null};
 
-use warp::http::Response;
-use warp::http::StatusCode;
-use warp::hyper::body::Bytes;
+use bytes::Bytes;
+use http::{Response, StatusCode};
 
 use crate::era::SupportedEra;
 
@@ -326,10 +325,10 @@ fn check_query_parameter_limitations(url: &Url, operation_object: &Value) {
 
 #[cfg(test)]
 mod tests {
+    use http::Method;
+    use http::StatusCode;
     use std::fs;
     use std::path::{Path, PathBuf};
-    use warp::http::Method;
-    use warp::http::StatusCode;
 
     use super::*;
     use crate::entities;
@@ -15,9 +15,10 @@ use crate::{
     },
     entities::{
         Certificate, Epoch, HexEncodedAggregateVerificationKey, PartyId, ProtocolParameters,
-        Signer, SignerWithStake, SingleSignatures, Stake, StakeDistribution,
+        SignedEntityType, Signer, SignerWithStake, SingleSignatures, Stake, StakeDistribution,
         StakeDistributionParty,
     },
+    messages::{RegisterSignatureMessage, RegisterSignerMessage},
     protocol::{SignerBuilder, ToMessage},
 };
 
@@ -243,6 +244,45 @@ impl SignerFixture {
             })
     }
 
+    /// Sign the given protocol message and wrap the signature in a [RegisterSignatureMessage].
+    pub fn register_signature_message<T: ToMessage>(
+        &self,
+        signed_entity_type: SignedEntityType,
+        message: &T,
+    ) -> Option<RegisterSignatureMessage> {
+        self.sign(message)
+            .map(|signature| RegisterSignatureMessage {
+                signed_entity_type,
+                party_id: signature.party_id,
+                signature: signature.signature.to_json_hex().unwrap(),
+                won_indexes: signature.won_indexes,
+                signed_message: Some(message.to_message()),
+                signature_scheme: Default::default(),
+            })
+    }
+
+    /// Build the [RegisterSignerMessage] sent by this signer to register at the given epoch.
+    pub fn register_signer_message(&self, epoch: Epoch) -> RegisterSignerMessage {
+        let signer = &self.signer_with_stake;
+
+        RegisterSignerMessage {
+            epoch,
+            party_id: signer.party_id.clone(),
+            verification_key: signer.verification_key.to_json_hex().unwrap(),
+            verification_key_signature: signer
+                .verification_key_signature
+                .as_ref()
+                .map(|signature| signature.to_json_hex().unwrap()),
+            operational_certificate: signer
+                .operational_certificate
+                .as_ref()
+                .map(|operational_certificate| operational_certificate.to_json_hex().unwrap()),
+            kes_period: signer.kes_period,
+            registration_challenge: None,
+            registration_challenge_signature: None,
+        }
+    }
+
     /// Shortcut to get the party id from the inner signer with stake
     pub fn party_id(&self) -> PartyId {
         self.signer_with_stake.party_id.clone()
@@ -0,0 +1,494 @@
+//! Deterministic test vectors of the Mithril protocol.
+//!
+//! A [TestVector] is a JSON fixture generated from a seed that contains a stake distribution of
+//! signers, the single signatures they issued for a protocol message, their aggregation in a
+//! multi-signature and a genesis certificate chained to a standard certificate.
+//!
+//! Generating a vector twice from the same [TestVectorSpecification] always gives the same
+//! result, so that the vectors can be exported and used by other implementations of the protocol
+//! or by auditors to check their compatibility with this crate:
+//!
+//! ```ignore
+//! use mithril_common::test_vectors::{TestVector, TestVectorSpecification};
+//!
+//! let specification = TestVectorSpecification::canonical().remove(0);
+//! let test_vector = TestVector::generate(&specification)?;
+//! let json = serde_json::to_string_pretty(&test_vector)?;
+//!
+//! let imported: TestVector = serde_json::from_str(&json)?;
+//! imported.verify()?;
+//! ```
+//!
+//! The canonical vectors are committed in the `test-vectors` directory of this crate, a test
+//! checks that they are still the ones generated by this crate. They are regenerated with
+//! `make update-test-vectors`.
+
+use anyhow::{anyhow, Context};
+use chrono::{DateTime, Utc};
+use rand_chacha::ChaCha20Rng;
+use rand_core::SeedableRng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    certificate_chain::CertificateGenesisProducer,
+    crypto_helper::{
+        ProtocolAggregateVerificationKey, ProtocolGenesisSigner, ProtocolGenesisVerificationKey,
+        ProtocolGenesisVerifier, ProtocolMultiSignature, PROTOCOL_VERSION,
+    },
+    entities::{
+        CardanoDbBeacon, Certificate, CertificateMetadata, CertificateSignature, Epoch,
+        ProtocolMessage, ProtocolMessagePartKey, ProtocolParameters, SignedEntityType,
+        SingleSignatures, StakeDistributionParty,
+    },
+    messages::{CertificateMessage, SignerWithStakeMessagePart},
+    protocol::SignerBuilder,
+    test_utils::{MithrilFixtureBuilder, StakeDistributionGenerationMethod},
+    StdResult,
+};
+
+const TEST_VECTOR_NETWORK: &str = "testnet";
+const TEST_VECTOR_DATE: &str = "2024-01-01T00:00:00Z";
+
+/// Inputs from which a [TestVector] is generated.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestVectorSpecification {
+    /// Name of the test vector
+    pub name: String,
+
+    /// Seed of the stake distribution and of the genesis keys
+    pub seed: [u8; 32],
+
+    /// Number of signers in the stake distribution
+    pub number_of_signers: usize,
+
+    /// Protocol parameters used to sign
+    pub protocol_parameters: ProtocolParameters,
+}
+
+impl TestVectorSpecification {
+    /// Specifications of the canonical set of test vectors
+    pub fn canonical() -> Vec<Self> {
+        vec![
+            Self {
+                name: "small-stake-distribution".to_string(),
+                seed: [0u8; 32],
+                number_of_signers: 3,
+                protocol_parameters: ProtocolParameters::new(5, 100, 0.65),
+            },
+            Self {
+                name: "medium-stake-distribution".to_string(),
+                seed: [1u8; 32],
+                number_of_signers: 10,
+                protocol_parameters: ProtocolParameters::new(20, 200, 0.65),
+            },
+            Self {
+                name: "low-phi-f".to_string(),
+                seed: [2u8; 32],
+                number_of_signers: 5,
+                protocol_parameters: ProtocolParameters::new(10, 400, 0.2),
+            },
+        ]
+    }
+}
+
+/// A deterministic test vector of the Mithril protocol, see the [module documentation][self].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TestVector {
+    /// Name of the test vector
+    pub name: String,
+
+    /// Hex encoded seed used to generate the test vector
+    pub seed: String,
+
+    /// Protocol parameters used to sign
+    pub protocol_parameters: ProtocolParameters,
+
+    /// Hex encoded verification key of the genesis certificate
+    pub genesis_verification_key: String,
+
+    /// Registered signers with their stakes
+    pub signers: Vec<SignerWithStakeMessagePart>,
+
+    /// Hex encoded aggregate verification key of the signers
+    pub aggregate_verification_key: String,
+
+    /// Protocol message signed by the signers
+    pub protocol_message: ProtocolMessage,
+
+    /// Message signed by the signers, the hash of the protocol message
+    pub signed_message: String,
+
+    /// Single signatures of the signers that won at least one lottery
+    pub single_signatures: Vec<SingleSignatures>,
+
+    /// Hex encoded multi-signature aggregating the single signatures
+    pub multi_signature: String,
+
+    /// Genesis certificate of the aggregate verification key
+    pub genesis_certificate: CertificateMessage,
+
+    /// Standard certificate of the protocol message, chained to the genesis certificate
+    pub certificate: CertificateMessage,
+}
+
+impl TestVector {
+    /// Generate the test vector of the given specification
+    pub fn generate(specification: &TestVectorSpecification) -> StdResult<Self> {
+        let fixture = MithrilFixtureBuilder::default()
+            .with_signers(specification.number_of_signers)
+            .with_stake_distribution(StakeDistributionGenerationMethod::RandomDistribution {
+                seed: specification.seed,
+            })
+            .with_protocol_parameters(specification.protocol_parameters.clone())
+            .build();
+        let genesis_signer = ProtocolGenesisSigner::create_test_genesis_signer(
+            ChaCha20Rng::from_seed(specification.seed),
+        );
+        let avk = fixture.compute_avk();
+        let genesis_epoch = Epoch(1);
+        let epoch = genesis_epoch.next();
+
+        let genesis_protocol_message = CertificateGenesisProducer::create_genesis_protocol_message(
+            &specification.protocol_parameters,
+            &avk,
+            &genesis_epoch,
+        )?;
+        let genesis_signature =
+            genesis_signer.sign(genesis_protocol_message.compute_hash().as_bytes());
+        let genesis_certificate = Certificate::new(
+            "",
+            genesis_epoch,
+            Self::certificate_metadata(&specification.protocol_parameters, vec![])?,
+            genesis_protocol_message,
+            avk.clone(),
+            CertificateSignature::GenesisSignature(genesis_signature),
+        );
+
+        let mut protocol_message = ProtocolMessage::new();
+        protocol_message.set_message_part(
+            ProtocolMessagePartKey::SnapshotDigest,
+            hex::encode(specification.seed),
+        );
+        protocol_message.set_message_part(
+            ProtocolMessagePartKey::NextAggregateVerificationKey,
+            avk.to_json_hex()?,
+        );
+        let signed_message = protocol_message.compute_hash();
+        let single_signatures = fixture.sign_all(&signed_message);
+        let multi_signature = SignerBuilder::new(
+            &fixture.signers_with_stake(),
+            &specification.protocol_parameters,
+        )?
+        .build_multi_signer()
+        .aggregate_single_signatures(&single_signatures, &signed_message)
+        .with_context(|| {
+            format!(
+                "Could not aggregate the single signatures of test vector '{}'",
+                specification.name
+            )
+        })?;
+        let certificate = Certificate::new(
+            genesis_certificate.hash.clone(),
+            epoch,
+            Self::certificate_metadata(
+                &specification.protocol_parameters,
+                fixture.stake_distribution_parties(),
+            )?,
+            protocol_message.clone(),
+            avk.clone(),
+            CertificateSignature::MultiSignature(
+                SignedEntityType::CardanoImmutableFilesFull(CardanoDbBeacon::new(
+                    TEST_VECTOR_NETWORK,
+                    *epoch,
+                    1,
+                )),
+                multi_signature.clone(),
+            ),
+        );
+
+        Ok(Self {
+            name: specification.name.clone(),
+            seed: hex::encode(specification.seed),
+            protocol_parameters: specification.protocol_parameters.clone(),
+            genesis_verification_key: genesis_signer
+                .create_genesis_verifier()
+                .to_verification_key()
+                .to_json_hex()?,
+            signers: SignerWithStakeMessagePart::from_signers(fixture.signers_with_stake()),
+            aggregate_verification_key: avk.to_json_hex()?,
+            protocol_message,
+            signed_message,
+            single_signatures,
+            multi_signature: multi_signature.to_json_hex()?,
+            genesis_certificate: genesis_certificate.try_into()?,
+            certificate: certificate.try_into()?,
+        })
+    }
+
+    /// Generate the canonical set of test vectors
+    pub fn generate_canonical() -> StdResult<Vec<Self>> {
+        TestVectorSpecification::canonical()
+            .iter()
+            .map(Self::generate)
+            .collect()
+    }
+
+    /// Verify the test vector with the cryptographic primitives of this crate:
+    /// - the signed message is the hash of the protocol message
+    /// - the aggregate verification key is the one of the signers
+    /// - each single signature is valid for the signed message
+    /// - the multi-signature is valid for the signed message
+    /// - the genesis certificate is signed by the genesis verification key and certifies the
+    ///   aggregate verification key
+    /// - the standard certificate is chained to the genesis certificate and carries the
+    ///   multi-signature
+    pub fn verify(&self) -> StdResult<()> {
+        if self.protocol_message.compute_hash() != self.signed_message {
+            return Err(anyhow!(
+                "The signed message is not the hash of the protocol message"
+            ));
+        }
+
+        let signers = SignerWithStakeMessagePart::try_into_signers(self.signers.clone())?;
+        let multi_signer =
+            SignerBuilder::new(&signers, &self.protocol_parameters)?.build_multi_signer();
+        let avk = multi_signer.compute_aggregate_verification_key();
+        if avk.to_json_hex()? != self.aggregate_verification_key {
+            return Err(anyhow!(
+                "The aggregate verification key does not match the signers"
+            ));
+        }
+
+        for single_signature in &self.single_signatures {
+            multi_signer
+                .verify_single_signature(&self.signed_message, single_signature)
+                .with_context(|| {
+                    format!(
+                        "Invalid single signature of party '{}'",
+                        single_signature.party_id
+                    )
+                })?;
+        }
+
+        let multi_signature = ProtocolMultiSignature::from_json_hex(&self.multi_signature)
+            .with_context(|| "Could not decode the multi-signature")?;
+        multi_signature
+            .verify(
+                self.signed_message.as_bytes(),
+                &avk,
+                &self.protocol_parameters.clone().into(),
+            )
+            .map_err(|e| anyhow!(e.to_string()))
+            .with_context(|| "Invalid multi-signature")?;
+
+        self.verify_genesis_certificate(&avk)?;
+        self.verify_certificate(&avk, &multi_signature)
+    }
+
+    fn verify_genesis_certificate(&self, avk: &ProtocolAggregateVerificationKey) -> StdResult<()> {
+        let genesis_certificate: Certificate = self.genesis_certificate.clone().try_into()?;
+        Self::verify_certificate_hash(&genesis_certificate)?;
+
+        let genesis_signature = match &genesis_certificate.signature {
+            CertificateSignature::GenesisSignature(signature) => signature,
+            CertificateSignature::MultiSignature(..) => {
+                return Err(anyhow!("The genesis certificate has no genesis signature"))
+            }
+        };
+        let genesis_verification_key =
+            ProtocolGenesisVerificationKey::from_json_hex(&self.genesis_verification_key)
+                .with_context(|| "Could not decode the genesis verification key")?;
+        ProtocolGenesisVerifier::from_verification_key(genesis_verification_key)
+            .verify(
+                genesis_certificate.signed_message.as_bytes(),
+                genesis_signature,
+            )
+            .with_context(|| "Invalid genesis signature")?;
+
+        if genesis_certificate
+            .protocol_message
+            .get_message_part(&ProtocolMessagePartKey::NextAggregateVerificationKey)
+            != Some(&avk.to_json_hex()?)
+        {
+            return Err(anyhow!(
+                "The genesis certificate does not certify the aggregate verification key"
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn verify_certificate(
+        &self,
+        avk: &ProtocolAggregateVerificationKey,
+        multi_signature: &ProtocolMultiSignature,
+    ) -> StdResult<()> {
+        let certificate: Certificate = self.certificate.clone().try_into()?;
+        Self::verify_certificate_hash(&certificate)?;
+
+        if certificate.previous_hash != self.genesis_certificate.hash {
+            return Err(anyhow!(
+                "The certificate is not chained to the genesis certificate"
+            ));
+        }
+        if certificate.signed_message != self.signed_message
+            || certificate.aggregate_verification_key.to_json_hex()? != avk.to_json_hex()?
+        {
+            return Err(anyhow!(
+                "The certificate does not certify the signed message with the aggregate verification key"
+            ));
+        }
+        match &certificate.signature {
+            CertificateSignature::MultiSignature(_, signature)
+                if signature.to_json_hex()? == multi_signature.to_json_hex()? =>
+            {
+                Ok(())
+            }
+            _ => Err(anyhow!(
+                "The certificate does not carry the multi-signature"
+            )),
+        }
+    }
+
+    fn verify_certificate_hash(certificate: &Certificate) -> StdResult<()> {
+        if certificate.compute_hash() != certificate.hash {
+            return Err(anyhow!(
+                "The hash of certificate '{}' does not match its content",
+                certificate.hash
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn certificate_metadata(
+        protocol_parameters: &ProtocolParameters,
+        signers: Vec<StakeDistributionParty>,
+    ) -> StdResult<CertificateMetadata> {
+        let date = DateTime::parse_from_rfc3339(TEST_VECTOR_DATE)?.with_timezone(&Utc);
+
+        Ok(CertificateMetadata::new(
+            TEST_VECTOR_NETWORK,
+            PROTOCOL_VERSION,
+            protocol_parameters.clone(),
+            date,
+            date,
+            signers,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    /// Environment variable that makes the tests write the canonical test vectors to the
+    /// `test-vectors` directory instead of comparing them
+    const UPDATE_TEST_VECTORS_ENV: &str = "MITHRIL_UPDATE_TEST_VECTORS";
+
+    fn test_vector_path(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("test-vectors")
+            .join(format!("{name}.json"))
+    }
+
+    fn small_test_vector() -> TestVector {
+        TestVector::generate(&TestVectorSpecification::canonical()[0]).unwrap()
+    }
+
+    #[test]
+    fn canonical_test_vectors_are_valid() {
+        let test_vectors = TestVector::generate_canonical().unwrap();
+
+        assert_eq!(
+            TestVectorSpecification::canonical().len(),
+            test_vectors.len()
+        );
+        for test_vector in test_vectors {
+            test_vector
+                .verify()
+                .unwrap_or_else(|e| panic!("test vector '{}' is invalid: {e:?}", test_vector.name));
+        }
+    }
+
+    #[test]
+    fn committed_test_vectors_are_the_generated_ones() {
+        for test_vector in TestVector::generate_canonical().unwrap() {
+            let path = test_vector_path(&test_vector.name);
+            let json = format!("{}\n", serde_json::to_string_pretty(&test_vector).unwrap());
+
+            if std::env::var(UPDATE_TEST_VECTORS_ENV).is_ok() {
+                std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+                std::fs::write(&path, json).unwrap();
+                continue;
+            }
+
+            let committed_json = std::fs::read_to_string(&path).unwrap_or_else(|e| {
+                panic!("Could not read the test vector '{}': {e}", path.display())
+            });
+            let committed_test_vector: TestVector = serde_json::from_str(&committed_json).unwrap();
+            committed_test_vector.verify().unwrap_or_else(|e| {
+                panic!(
+                    "committed test vector '{}' is invalid: {e:?}",
+                    test_vector.name
+                )
+            });
+            assert_eq!(
+                test_vector, committed_test_vector,
+                "The committed test vector '{}' differs from the generated one, run `make update-test-vectors` if the change is expected",
+                test_vector.name
+            );
+        }
+    }
+
+    #[test]
+    fn generation_is_deterministic() {
+        let first_json = serde_json::to_string(&small_test_vector()).unwrap();
+        let second_json = serde_json::to_string(&small_test_vector()).unwrap();
+
+        assert_eq!(first_json, second_json);
+    }
+
+    #[test]
+    fn test_vector_is_valid_after_a_json_round_trip() {
+        let json = serde_json::to_string_pretty(&small_test_vector()).unwrap();
+        let test_vector: TestVector = serde_json::from_str(&json).unwrap();
+
+        test_vector.verify().unwrap();
+    }
+
+    #[test]
+    fn verify_fails_if_the_protocol_message_is_altered() {
+        let mut test_vector = small_test_vector();
+        test_vector.protocol_message.set_message_part(
+            ProtocolMessagePartKey::SnapshotDigest,
+            "altered".to_string(),
+        );
+
+        test_vector
+            .verify()
+            .expect_err("An altered protocol message should fail the verification");
+    }
+
+    #[test]
+    fn verify_fails_if_a_stake_is_altered() {
+        let mut test_vector = small_test_vector();
+        test_vector.signers[0].stake += 1;
+
+        test_vector
+            .verify()
+            .expect_err("An altered stake should fail the verification");
+    }
+
+    #[test]
+    fn verify_fails_if_the_certificate_is_not_chained_to_the_genesis_certificate() {
+        let mut test_vector = small_test_vector();
+        test_vector.certificate.previous_hash = "another-hash".to_string();
+
+        test_vector
+            .verify()
+            .expect_err("An unchained certificate should fail the verification");
+    }
+}
@@ -0,0 +1,271 @@
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio::sync::RwLock;
+
+use crate::chain_observer::FakeObserver;
+use crate::digesters::{
+    DummyImmutableDb, DummyImmutablesDbBuilder, ImmutableFileObserver, ImmutableFileSystemObserver,
+};
+use crate::entities::{ChainPoint, Epoch, ImmutableFileNumber, TimePoint};
+use crate::test_utils::fake_data;
+use crate::testing::StakeSchedule;
+use crate::{MithrilTickerService, TickerService};
+
+/// A [FakeChain] builder.
+pub struct FakeChainBuilder {
+    dir_name: String,
+    epoch: Epoch,
+    immutable_files: ImmutableFileNumber,
+    chain_point: ChainPoint,
+    stake_schedule: StakeSchedule,
+}
+
+impl FakeChainBuilder {
+    /// [FakeChainBuilder] factory, the immutable database of the chain will be written in a
+    /// folder with the given `dir_name` in the system temp directory, cleaned if it exists.
+    pub fn new(dir_name: &str) -> Self {
+        let time_point = TimePoint::dummy();
+
+        Self {
+            dir_name: dir_name.to_string(),
+            epoch: time_point.epoch,
+            immutable_files: 1,
+            chain_point: time_point.chain_point,
+            stake_schedule: StakeSchedule::new(),
+        }
+    }
+
+    /// Set the epoch at which the chain starts
+    pub fn with_epoch(mut self, epoch: Epoch) -> Self {
+        self.epoch = epoch;
+        self
+    }
+
+    /// Set the number of completed immutable files at which the chain starts
+    pub fn with_immutable_files(mut self, immutable_files: ImmutableFileNumber) -> Self {
+        self.immutable_files = immutable_files;
+        self
+    }
+
+    /// Set the chain point at which the chain starts
+    pub fn with_chain_point(mut self, chain_point: ChainPoint) -> Self {
+        self.chain_point = chain_point;
+        self
+    }
+
+    /// Set the signers of the stake distribution of each epoch.
+    ///
+    /// If no signers are scheduled for the starting epoch, two fake signers are used until the
+    /// first scheduled epoch.
+    pub fn with_stake_schedule(mut self, stake_schedule: StakeSchedule) -> Self {
+        self.stake_schedule = stake_schedule;
+        self
+    }
+
+    /// Build the [FakeChain] and write its immutable database
+    pub fn build(self) -> FakeChain {
+        let mut observer = FakeObserver::new(Some(TimePoint::new(
+            *self.epoch,
+            self.immutable_files,
+            self.chain_point,
+        )));
+        observer.signers = RwLock::new(
+            self.stake_schedule
+                .signers_at(self.epoch)
+                .cloned()
+                .unwrap_or_else(|| fake_data::signers_with_stakes(2)),
+        );
+
+        // The last immutable files trio is the one still written by the node
+        let immutable_db = DummyImmutablesDbBuilder::new(&self.dir_name)
+            .with_immutables(&(1..=self.immutable_files + 1).collect::<Vec<_>>())
+            .build();
+        let immutable_file_observer = Arc::new(ImmutableFileSystemObserver::new(&immutable_db.dir));
+
+        FakeChain {
+            observer: Arc::new(observer),
+            immutable_file_observer,
+            immutable_db: Mutex::new(immutable_db),
+            stake_schedule: self.stake_schedule,
+        }
+    }
+}
+
+/// A deterministic Cardano chain whose clock is advanced explicitly, see the
+/// [module documentation][crate::testing].
+pub struct FakeChain {
+    observer: Arc<FakeObserver>,
+    immutable_file_observer: Arc<ImmutableFileSystemObserver>,
+    immutable_db: Mutex<DummyImmutableDb>,
+    stake_schedule: StakeSchedule,
+}
+
+impl FakeChain {
+    /// Number of slots elapsed for each block added by [advance_blocks][Self::advance_blocks],
+    /// the average on the Cardano mainnet
+    pub const SLOTS_PER_BLOCK: u64 = 20;
+
+    /// The chain observer of the chain
+    pub fn chain_observer(&self) -> Arc<FakeObserver> {
+        self.observer.clone()
+    }
+
+    /// The immutable file observer of the chain immutable database
+    pub fn immutable_file_observer(&self) -> Arc<dyn ImmutableFileObserver> {
+        self.immutable_file_observer.clone()
+    }
+
+    /// A ticker service reading the chain observer and the immutable database of the chain
+    pub fn ticker_service(&self) -> Arc<dyn TickerService> {
+        Arc::new(MithrilTickerService::new(
+            self.observer.clone(),
+            self.immutable_file_observer.clone(),
+        ))
+    }
+
+    /// The Cardano database directory, which contains the `immutable` directory
+    pub fn cardano_db_directory(&self) -> PathBuf {
+        let immutable_db = self.immutable_db.lock().unwrap();
+        immutable_db
+            .dir
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| immutable_db.dir.clone())
+    }
+
+    /// Number of the last completed immutable file
+    pub fn last_immutable_file_number(&self) -> ImmutableFileNumber {
+        let immutable_db = self.immutable_db.lock().unwrap();
+        immutable_db.last_immutable_number().unwrap_or(1) - 1
+    }
+
+    /// Current epoch of the chain
+    pub async fn current_epoch(&self) -> Epoch {
+        self.observer
+            .current_time_point
+            .read()
+            .await
+            .as_ref()
+            .map(|time_point| time_point.epoch)
+            .expect("A FakeChain always has a time point")
+    }
+
+    /// Move the chain to the next epoch and apply the signers scheduled for it
+    pub async fn next_epoch(&self) -> Epoch {
+        let epoch = self
+            .observer
+            .next_epoch()
+            .await
+            .expect("A FakeChain always has a time point");
+        if let Some(signers) = self.stake_schedule.signers_at(epoch) {
+            self.observer.set_signers(signers.clone()).await;
+        }
+
+        epoch
+    }
+
+    /// Complete the immutable file being written and return its number
+    pub fn next_immutable_file(&self) -> ImmutableFileNumber {
+        let mut immutable_db = self.immutable_db.lock().unwrap();
+        immutable_db.add_immutable_file() - 1
+    }
+
+    /// Add the given number of blocks to the chain and return the new chain point
+    pub async fn advance_blocks(&self, blocks: u64) -> ChainPoint {
+        self.observer.increase_block_number(blocks).await;
+        self.observer
+            .increase_slot_number(blocks * Self::SLOTS_PER_BLOCK)
+            .await;
+
+        self.observer
+            .current_time_point
+            .read()
+            .await
+            .as_ref()
+            .map(|time_point| time_point.chain_point.clone())
+            .expect("A FakeChain always has a time point")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::chain_observer::ChainObserver;
+    use crate::entities::{BlockNumber, SlotNumber, StakeDistribution};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn next_immutable_file_is_seen_by_the_ticker_service() {
+        let fake_chain = FakeChainBuilder::new("fake_chain_next_immutable_file")
+            .with_immutable_files(3)
+            .build();
+        let ticker_service = fake_chain.ticker_service();
+        assert_eq!(
+            3,
+            ticker_service
+                .get_current_time_point()
+                .await
+                .unwrap()
+                .immutable_file_number
+        );
+
+        assert_eq!(4, fake_chain.next_immutable_file());
+
+        assert_eq!(4, fake_chain.last_immutable_file_number());
+        assert_eq!(
+            4,
+            ticker_service
+                .get_current_time_point()
+                .await
+                .unwrap()
+                .immutable_file_number
+        );
+    }
+
+    #[tokio::test]
+    async fn next_epoch_applies_the_stake_schedule() {
+        let signers = fake_data::signers_with_stakes(3);
+        let fake_chain = FakeChainBuilder::new("fake_chain_next_epoch")
+            .with_epoch(Epoch(4))
+            .with_stake_schedule(StakeSchedule::new().with_signers_from(Epoch(5), signers.clone()))
+            .build();
+        let chain_observer = fake_chain.chain_observer();
+        let default_stake_distribution = chain_observer
+            .get_current_stake_distribution()
+            .await
+            .unwrap();
+
+        assert_eq!(Epoch(5), fake_chain.next_epoch().await);
+
+        let stake_distribution = chain_observer
+            .get_current_stake_distribution()
+            .await
+            .unwrap();
+        assert_ne!(default_stake_distribution, stake_distribution);
+        assert_eq!(
+            Some(
+                signers
+                    .into_iter()
+                    .map(|s| (s.party_id, s.stake))
+                    .collect::<StakeDistribution>()
+            ),
+            stake_distribution
+        );
+        assert_eq!(Epoch(5), fake_chain.current_epoch().await);
+    }
+
+    #[tokio::test]
+    async fn advance_blocks_moves_the_chain_point() {
+        let fake_chain = FakeChainBuilder::new("fake_chain_advance_blocks")
+            .with_chain_point(ChainPoint::new(SlotNumber(100), BlockNumber(5), "hash"))
+            .build();
+
+        let chain_point = fake_chain.advance_blocks(2).await;
+
+        assert_eq!(BlockNumber(7), chain_point.block_number);
+        assert_eq!(
+            SlotNumber(100 + 2 * FakeChain::SLOTS_PER_BLOCK),
+            chain_point.slot_number
+        );
+    }
+}
@@ -0,0 +1,37 @@
+//! Deterministic in-memory Cardano chain to write end-to-end tests against the Mithril crates.
+//!
+//! The [FakeChain] bundles a [FakeObserver][crate::chain_observer::FakeObserver] and a dummy
+//! Cardano immutable database whose clock is advanced explicitly by the tests: one epoch, one
+//! immutable file or some blocks at a time. The stake distribution of each epoch follows a
+//! [StakeSchedule].
+//!
+//! ```
+//! # #[tokio::main]
+//! # async fn main() {
+//! use mithril_common::entities::Epoch;
+//! use mithril_common::test_utils::fake_data;
+//! use mithril_common::testing::{FakeChainBuilder, StakeSchedule};
+//! use mithril_common::TickerService;
+//!
+//! let fake_chain = FakeChainBuilder::new("doc_fake_chain")
+//!     .with_epoch(Epoch(10))
+//!     .with_immutable_files(5)
+//!     .with_stake_schedule(
+//!         StakeSchedule::new().with_signers_from(Epoch(11), fake_data::signers_with_stakes(3)),
+//!     )
+//!     .build();
+//! let ticker_service = fake_chain.ticker_service();
+//!
+//! assert_eq!(Epoch(11), fake_chain.next_epoch().await);
+//! assert_eq!(6, fake_chain.next_immutable_file());
+//! let time_point = ticker_service.get_current_time_point().await.unwrap();
+//! assert_eq!(Epoch(11), time_point.epoch);
+//! assert_eq!(6, time_point.immutable_file_number);
+//! # }
+//! ```
+
+mod fake_chain;
+mod stake_schedule;
+
+pub use fake_chain::{FakeChain, FakeChainBuilder};
+pub use stake_schedule::StakeSchedule;
@@ -0,0 +1,56 @@
+use std::collections::BTreeMap;
+
+use crate::entities::{Epoch, SignerWithStake};
+
+/// Signers, with their stakes, of a [FakeChain][crate::testing::FakeChain] by epoch.
+///
+/// The signers set from an epoch are kept for the following epochs until another set of signers
+/// is scheduled.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StakeSchedule {
+    signers_by_epoch: BTreeMap<Epoch, Vec<SignerWithStake>>,
+}
+
+impl StakeSchedule {
+    /// [StakeSchedule] factory, with no signers scheduled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Schedule the signers of the stake distribution from the given epoch
+    pub fn with_signers_from(mut self, epoch: Epoch, signers: Vec<SignerWithStake>) -> Self {
+        self.signers_by_epoch.insert(epoch, signers);
+        self
+    }
+
+    /// Get the signers of the stake distribution at the given epoch, if some were scheduled at or
+    /// before it
+    pub fn signers_at(&self, epoch: Epoch) -> Option<&Vec<SignerWithStake>> {
+        self.signers_by_epoch
+            .range(..=epoch)
+            .next_back()
+            .map(|(_, signers)| signers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_utils::fake_data;
+
+    use super::*;
+
+    #[test]
+    fn signers_at_an_epoch_are_the_last_scheduled_ones() {
+        let first_signers = fake_data::signers_with_stakes(1);
+        let second_signers = fake_data::signers_with_stakes(2);
+        let schedule = StakeSchedule::new()
+            .with_signers_from(Epoch(2), first_signers.clone())
+            .with_signers_from(Epoch(5), second_signers.clone());
+
+        assert_eq!(None, schedule.signers_at(Epoch(1)));
+        assert_eq!(Some(&first_signers), schedule.signers_at(Epoch(2)));
+        assert_eq!(Some(&first_signers), schedule.signers_at(Epoch(4)));
+        assert_eq!(Some(&second_signers), schedule.signers_at(Epoch(5)));
+        assert_eq!(Some(&second_signers), schedule.signers_at(Epoch(50)));
+    }
+}
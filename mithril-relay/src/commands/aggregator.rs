@@ -1,10 +1,15 @@
+use std::sync::Arc;
+use std::time::Duration;
+
 use clap::Parser;
 use libp2p::Multiaddr;
 use mithril_common::StdResult;
+use mithril_metric::MetricsServer;
 use slog::error;
+use tokio::sync::oneshot;
 
-use super::CommandContext;
-use crate::AggregatorRelay;
+use super::{spawn_signers_verification_keys_updater, CommandContext, PeerArgs};
+use crate::{AggregatorForwardingSettings, AggregatorRelay, RelayMetricsService};
 
 #[derive(Parser, Debug, Clone)]
 pub struct AggregatorCommand {
@@ -16,9 +21,33 @@ pub struct AggregatorCommand {
     #[clap(long, env = "DIAL_TO")]
     dial_to: Option<Multiaddr>,
 
+    #[command(flatten)]
+    peer: PeerArgs,
+
     /// Aggregator endpoint URL.
     #[clap(long, env = "AGGREGATOR_ENDPOINT")]
     aggregator_endpoint: String,
+
+    /// Maximum number of messages waiting to be forwarded to the aggregator, the messages
+    /// received when the queue is full are dropped
+    #[clap(long, env = "MESSAGE_QUEUE_SIZE", default_value_t = 1000)]
+    message_queue_size: usize,
+
+    /// Time window, in seconds, during which an already received message is ignored
+    #[clap(long, env = "DEDUPLICATION_WINDOW_IN_SECONDS", default_value_t = 600)]
+    deduplication_window_in_seconds: u64,
+
+    /// Enable metrics HTTP server (Prometheus endpoint on /metrics).
+    #[clap(long, env = "ENABLE_METRICS_SERVER", default_value_t = false)]
+    enable_metrics_server: bool,
+
+    /// Metrics HTTP server IP.
+    #[clap(long, env = "METRICS_SERVER_IP", default_value = "0.0.0.0")]
+    metrics_server_ip: String,
+
+    /// Metrics HTTP server listening port.
+    #[clap(long, env = "METRICS_SERVER_PORT", default_value_t = 9090)]
+    metrics_server_port: u16,
 }
 
 impl AggregatorCommand {
@@ -28,8 +57,40 @@ impl AggregatorCommand {
         let addr: Multiaddr = format!("/ip4/0.0.0.0/tcp/{}", self.listen_port).parse()?;
         let aggregator_endpoint = self.aggregator_endpoint.to_owned();
         let logger = context.logger();
+        let metrics_service = Arc::new(RelayMetricsService::new(logger.clone())?);
+
+        // The metrics server is stopped when the sender is dropped, it must live as long as the relay
+        let (_metrics_server_shutdown_tx, metrics_server_shutdown_rx) = oneshot::channel();
+        if self.enable_metrics_server {
+            let metrics_server = MetricsServer::new(
+                &self.metrics_server_ip,
+                self.metrics_server_port,
+                metrics_service.clone(),
+                logger.clone(),
+            );
+            let metrics_logger = logger.clone();
+            tokio::spawn(async move {
+                if let Err(err) = metrics_server.start(metrics_server_shutdown_rx).await {
+                    error!(metrics_logger, "RelayAggregator: metrics server error"; "error" => ?err);
+                }
+            });
+        }
+
+        let peer_configuration = self.peer.to_peer_configuration()?;
+        spawn_signers_verification_keys_updater(&peer_configuration, &aggregator_endpoint, logger)?;
 
-        let mut relay = AggregatorRelay::start(&addr, &aggregator_endpoint, logger).await?;
+        let mut relay = AggregatorRelay::start(
+            &addr,
+            peer_configuration,
+            &aggregator_endpoint,
+            AggregatorForwardingSettings {
+                message_queue_size: self.message_queue_size,
+                deduplication_window: Duration::from_secs(self.deduplication_window_in_seconds),
+            },
+            metrics_service,
+            logger,
+        )
+        .await?;
         if let Some(dial_to_address) = dial_to {
             relay.dial_peer(dial_to_address.clone())?;
         }
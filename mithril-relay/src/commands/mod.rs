@@ -1,11 +1,13 @@
 mod aggregator;
 mod context;
 mod passive;
+mod peer;
 mod relay;
 mod signer;
 
 pub use aggregator::AggregatorCommand;
 pub use passive::PassiveCommand;
+pub use peer::{spawn_signers_verification_keys_updater, PeerArgs};
 pub use relay::RelayCommands;
 pub use signer::SignerCommand;
 
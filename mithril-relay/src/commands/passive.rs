@@ -3,7 +3,7 @@ use libp2p::Multiaddr;
 use mithril_common::StdResult;
 use slog::error;
 
-use super::CommandContext;
+use super::{spawn_signers_verification_keys_updater, CommandContext, PeerArgs};
 use crate::PassiveRelay;
 
 #[derive(Parser, Debug, Clone)]
@@ -15,6 +15,14 @@ pub struct PassiveCommand {
     /// Dial to peer multi-address (e.g. /ip4/0.0.0.0/tcp/1234)
     #[clap(long, env = "DIAL_TO")]
     dial_to: Option<Multiaddr>,
+
+    #[command(flatten)]
+    peer: PeerArgs,
+
+    /// Aggregator endpoint URL, used to retrieve the signers verification keys authenticating
+    /// the relayed signatures
+    #[clap(long, env = "AGGREGATOR_ENDPOINT")]
+    aggregator_endpoint: String,
 }
 
 impl PassiveCommand {
@@ -24,7 +32,14 @@ impl PassiveCommand {
         let addr: Multiaddr = format!("/ip4/0.0.0.0/tcp/{}", self.listen_port).parse()?;
         let logger = context.logger();
 
-        let mut relay = PassiveRelay::start(&addr, logger).await?;
+        let peer_configuration = self.peer.to_peer_configuration()?;
+        spawn_signers_verification_keys_updater(
+            &peer_configuration,
+            &self.aggregator_endpoint,
+            logger,
+        )?;
+
+        let mut relay = PassiveRelay::start(&addr, peer_configuration, logger).await?;
         if let Some(dial_to_address) = dial_to {
             relay.dial_peer(dial_to_address.clone())?;
         }
@@ -0,0 +1,71 @@
+use clap::Args;
+use libp2p::Multiaddr;
+use mithril_common::StdResult;
+use slog::Logger;
+use std::path::PathBuf;
+
+use crate::p2p::{
+    load_or_generate_keypair, PeerConfiguration, SignersVerificationKeysUpdater,
+    SIGNERS_VERIFICATION_KEYS_UPDATE_INTERVAL,
+};
+
+/// Configuration of the peer of a relay in the P2P network
+#[derive(Args, Debug, Clone)]
+pub struct PeerArgs {
+    /// Path of the file storing the keypair identifying the peer and signing the messages it
+    /// publishes, generated if it does not exist (a new keypair is used at each start if not set)
+    #[clap(long, env = "PEER_KEYPAIR_PATH")]
+    peer_keypair_path: Option<PathBuf>,
+
+    /// Comma separated list of peer multi-addresses dialed at start to join the P2P network
+    #[clap(long, env = "BOOTSTRAP_PEERS", value_delimiter = ',')]
+    bootstrap_peers: Vec<Multiaddr>,
+
+    /// Comma separated list of circuit relay peer multi-addresses, including their peer id,
+    /// through which the relay is reachable when it is behind a NAT
+    #[clap(long, env = "CIRCUIT_RELAY_PEERS", value_delimiter = ',')]
+    circuit_relay_peers: Vec<Multiaddr>,
+
+    /// Relay the connections of the peers behind a NAT, the relay must be publicly reachable
+    #[clap(long, env = "ENABLE_CIRCUIT_RELAY_SERVER", default_value_t = false)]
+    enable_circuit_relay_server: bool,
+
+    /// Comma separated list of the public multi-addresses of the relay, advertised to the peers
+    /// behind a NAT that reserve a circuit through it (required by the circuit relay server)
+    #[clap(long, env = "EXTERNAL_ADDRESSES", value_delimiter = ',')]
+    external_addresses: Vec<Multiaddr>,
+}
+
+impl PeerArgs {
+    /// Build the configuration of the peer
+    pub fn to_peer_configuration(&self) -> StdResult<PeerConfiguration> {
+        Ok(PeerConfiguration {
+            keypair: self
+                .peer_keypair_path
+                .as_deref()
+                .map(load_or_generate_keypair)
+                .transpose()?,
+            bootstrap_peers: self.bootstrap_peers.clone(),
+            circuit_relay_peers: self.circuit_relay_peers.clone(),
+            enable_circuit_relay_server: self.enable_circuit_relay_server,
+            external_addresses: self.external_addresses.clone(),
+            signers_verification_keys: Default::default(),
+        })
+    }
+}
+
+/// Keep the signers verification keys of the peer up to date with the ones of the aggregator
+pub fn spawn_signers_verification_keys_updater(
+    peer_configuration: &PeerConfiguration,
+    aggregator_endpoint: &str,
+    logger: &Logger,
+) -> StdResult<()> {
+    let updater = SignersVerificationKeysUpdater::new(
+        aggregator_endpoint,
+        peer_configuration.signers_verification_keys.clone(),
+        logger,
+    )?;
+    tokio::spawn(updater.run_forever(SIGNERS_VERIFICATION_KEYS_UPDATE_INTERVAL));
+
+    Ok(())
+}
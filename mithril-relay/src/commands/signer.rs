@@ -5,7 +5,7 @@ use libp2p::Multiaddr;
 use mithril_common::StdResult;
 use slog::error;
 
-use super::CommandContext;
+use super::{spawn_signers_verification_keys_updater, CommandContext, PeerArgs};
 use crate::SignerRelay;
 
 #[derive(Parser, Debug, Clone)]
@@ -22,6 +22,9 @@ pub struct SignerCommand {
     #[clap(long, env = "DIAL_TO")]
     dial_to: Option<Multiaddr>,
 
+    #[command(flatten)]
+    peer: PeerArgs,
+
     /// Aggregator endpoint URL.
     #[clap(long, env = "AGGREGATOR_ENDPOINT")]
     aggregator_endpoint: String,
@@ -40,9 +43,12 @@ impl SignerCommand {
         let addr: Multiaddr = format!("/ip4/0.0.0.0/tcp/{}", self.listen_port).parse()?;
         let aggregator_endpoint = self.aggregator_endpoint.to_owned();
         let signer_repeater_delay = Duration::from_millis(self.signer_repeater_delay);
+        let peer_configuration = self.peer.to_peer_configuration()?;
+        spawn_signers_verification_keys_updater(&peer_configuration, &aggregator_endpoint, logger)?;
 
         let mut relay = SignerRelay::start(
             &addr,
+            peer_configuration,
             &server_port,
             &aggregator_endpoint,
             &signer_repeater_delay,
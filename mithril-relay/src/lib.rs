@@ -2,6 +2,7 @@
 #![doc = include_str!("../README.md")]
 
 mod commands;
+mod metrics;
 /// Peer to peer module
 pub mod p2p;
 mod relay;
@@ -9,6 +10,8 @@ mod repeater;
 
 pub use commands::Args;
 pub use commands::RelayCommands;
+pub use metrics::RelayMetricsService;
+pub use relay::AggregatorForwardingSettings;
 pub use relay::AggregatorRelay;
 pub use relay::PassiveRelay;
 pub use relay::SignerRelay;
@@ -0,0 +1,31 @@
+use mithril_metric::{build_metrics_service, MetricsServiceExporter};
+
+use mithril_metric::metric::{MetricCollector, MetricCounter};
+
+build_metrics_service!(
+    RelayMetricsService,
+    gossip_message_received_since_startup_counter:MetricCounter(
+        "mithril_relay_gossip_message_received_since_startup",
+        "Number of messages received from the P2P network since startup on a Mithril relay"
+    ),
+    gossip_message_invalid_since_startup_counter:MetricCounter(
+        "mithril_relay_gossip_message_invalid_since_startup",
+        "Number of unauthenticated or unparsable messages received from the P2P network since startup on a Mithril relay"
+    ),
+    gossip_message_duplicate_since_startup_counter:MetricCounter(
+        "mithril_relay_gossip_message_duplicate_since_startup",
+        "Number of already received messages received from the P2P network since startup on a Mithril relay"
+    ),
+    gossip_message_dropped_since_startup_counter:MetricCounter(
+        "mithril_relay_gossip_message_dropped_since_startup",
+        "Number of messages dropped because the queue of messages to forward to the aggregator was full since startup on a Mithril relay"
+    ),
+    aggregator_forward_success_since_startup_counter:MetricCounter(
+        "mithril_relay_aggregator_forward_success_since_startup",
+        "Number of messages successfully forwarded to the aggregator since startup on a Mithril relay"
+    ),
+    aggregator_forward_failure_since_startup_counter:MetricCounter(
+        "mithril_relay_aggregator_forward_failure_since_startup",
+        "Number of messages that could not be forwarded to the aggregator since startup on a Mithril relay"
+    )
+);
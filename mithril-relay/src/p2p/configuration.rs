@@ -0,0 +1,78 @@
+use libp2p::{identity::Keypair, multiaddr::Protocol, Multiaddr};
+use std::sync::Arc;
+
+use crate::p2p::SignersVerificationKeys;
+
+/// Configuration of a [Peer][crate::p2p::Peer] in the P2P network
+#[derive(Debug, Clone, Default)]
+pub struct PeerConfiguration {
+    /// Keypair identifying the peer and signing its gossip messages, a new keypair is
+    /// generated at start if not set
+    pub keypair: Option<Keypair>,
+
+    /// Peers dialed at start to join the P2P network
+    pub bootstrap_peers: Vec<Multiaddr>,
+
+    /// Circuit relay peers through which the peer is reachable when it is behind a NAT
+    pub circuit_relay_peers: Vec<Multiaddr>,
+
+    /// If set, the peer relays the connections of the peers behind a NAT, it must be
+    /// publicly reachable
+    pub enable_circuit_relay_server: bool,
+
+    /// Public addresses on which the peer is reachable, advertised to the peers behind a NAT
+    /// that reserve a circuit through it
+    pub external_addresses: Vec<Multiaddr>,
+
+    /// Verification keys of the signers, used to authenticate the signatures received from the
+    /// P2P network before propagating them
+    pub signers_verification_keys: Arc<SignersVerificationKeys>,
+}
+
+impl PeerConfiguration {
+    /// Addresses on which the peer listens through its circuit relay peers
+    pub fn circuit_relay_listen_addresses(&self) -> Vec<Multiaddr> {
+        self.circuit_relay_peers
+            .iter()
+            .map(|relay_address| {
+                if relay_address.iter().any(|p| p == Protocol::P2pCircuit) {
+                    relay_address.clone()
+                } else {
+                    relay_address.clone().with(Protocol::P2pCircuit)
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn circuit_relay_listen_addresses_end_with_the_circuit_protocol() {
+        let configuration = PeerConfiguration {
+            circuit_relay_peers: vec![
+                "/ip4/1.2.3.4/tcp/1234/p2p/12D3KooWDpJ7As7BWAwRMfu1VU2WCqNjvq387JEYKDBj4kx6nXTN"
+                    .parse()
+                    .unwrap(),
+                "/ip4/1.2.3.4/tcp/5678/p2p/12D3KooWDpJ7As7BWAwRMfu1VU2WCqNjvq387JEYKDBj4kx6nXTN/p2p-circuit"
+                    .parse()
+                    .unwrap(),
+            ],
+            ..PeerConfiguration::default()
+        };
+
+        assert_eq!(
+            vec![
+                "/ip4/1.2.3.4/tcp/1234/p2p/12D3KooWDpJ7As7BWAwRMfu1VU2WCqNjvq387JEYKDBj4kx6nXTN/p2p-circuit"
+                    .parse::<Multiaddr>()
+                    .unwrap(),
+                "/ip4/1.2.3.4/tcp/5678/p2p/12D3KooWDpJ7As7BWAwRMfu1VU2WCqNjvq387JEYKDBj4kx6nXTN/p2p-circuit"
+                    .parse::<Multiaddr>()
+                    .unwrap(),
+            ],
+            configuration.circuit_relay_listen_addresses()
+        );
+    }
+}
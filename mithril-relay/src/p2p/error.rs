@@ -1,3 +1,4 @@
+use mithril_common::StdError;
 use thiserror::Error;
 
 /// [Peer][crate::p2p::Peer] related errors.
@@ -11,3 +12,16 @@ pub enum PeerError {
     #[error("no available swarm")]
     UnavailableSwarm(),
 }
+
+/// [BroadcastMessageValidator][crate::p2p::BroadcastMessageValidator] related errors.
+#[derive(Debug, Error)]
+pub enum BroadcastMessageValidationError {
+    /// The message is invalid, the peer that forwarded it is penalized
+    #[error("invalid broadcast message")]
+    Invalid(#[source] StdError),
+
+    /// The message can't be authenticated yet, it is not propagated but the peer that forwarded
+    /// it is not penalized
+    #[error("unverifiable broadcast message: {0}")]
+    Unverifiable(String),
+}
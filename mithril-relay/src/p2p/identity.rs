@@ -0,0 +1,69 @@
+use anyhow::{anyhow, Context};
+use libp2p::identity::Keypair;
+use mithril_common::StdResult;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::Path;
+
+/// Load the keypair of a peer from the given file, or generate a new ed25519 keypair and store
+/// it in the file if it does not exist.
+///
+/// Using a persisted keypair gives a stable peer id to the peer, so the other peers of the
+/// network can identify the author of the gossip messages it signs across restarts.
+pub fn load_or_generate_keypair(path: &Path) -> StdResult<Keypair> {
+    if path.exists() {
+        let encoded_keypair = std::fs::read(path)
+            .with_context(|| format!("Could not read peer keypair file '{}'", path.display()))?;
+
+        return Keypair::from_protobuf_encoding(&encoded_keypair).map_err(|e| {
+            anyhow!(e).context(format!(
+                "Could not decode peer keypair file '{}'",
+                path.display()
+            ))
+        });
+    }
+
+    let keypair = Keypair::generate_ed25519();
+    let encoded_keypair = keypair
+        .to_protobuf_encoding()
+        .with_context(|| "Could not encode the generated peer keypair")?;
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)
+        .and_then(|mut file| file.write_all(&encoded_keypair))
+        .with_context(|| format!("Could not write peer keypair file '{}'", path.display()))?;
+
+    Ok(keypair)
+}
+
+#[cfg(test)]
+mod tests {
+    use mithril_common::test_utils::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn generate_a_keypair_then_load_it_on_next_call() {
+        let path = TempDir::create("relay_peer_identity", "generate_then_load").join("peer.key");
+
+        let generated_keypair = load_or_generate_keypair(&path).unwrap();
+        let loaded_keypair = load_or_generate_keypair(&path).unwrap();
+
+        assert!(path.exists());
+        assert_eq!(
+            generated_keypair.public().to_peer_id(),
+            loaded_keypair.public().to_peer_id()
+        );
+    }
+
+    #[test]
+    fn fail_to_load_an_invalid_keypair_file() {
+        let path = TempDir::create("relay_peer_identity", "invalid_keypair").join("peer.key");
+        std::fs::write(&path, b"invalid").unwrap();
+
+        load_or_generate_keypair(&path).expect_err("Loading an invalid keypair should fail");
+    }
+}
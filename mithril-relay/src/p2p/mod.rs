@@ -1,5 +1,13 @@
+mod configuration;
 mod error;
+mod identity;
 mod peer;
+mod signers;
+mod validation;
 
+pub use configuration::*;
 pub use error::*;
+pub use identity::*;
 pub use peer::*;
+pub use signers::*;
+pub use validation::*;
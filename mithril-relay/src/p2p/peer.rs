@@ -1,11 +1,18 @@
 #![allow(missing_docs)]
 use anyhow::{anyhow, Context};
 use libp2p::{
+    autonat,
     core::{muxing::StreamMuxerBox, transport::dummy::DummyTransport},
+    dcutr,
     futures::StreamExt,
-    gossipsub::{self, ValidationMode},
-    noise, ping,
-    swarm::{self, DialError, NetworkBehaviour},
+    gossipsub::{
+        self, MessageAcceptance, PeerScoreParams, PeerScoreThresholds, TopicScoreParams,
+        ValidationMode,
+    },
+    identify,
+    identity::Keypair,
+    noise, ping, relay,
+    swarm::{self, behaviour::toggle::Toggle, DialError, NetworkBehaviour},
     tls, yamux, Multiaddr, PeerId, Swarm, SwarmBuilder,
 };
 use mithril_common::{
@@ -14,19 +21,73 @@ use mithril_common::{
     StdResult,
 };
 use serde::{Deserialize, Serialize};
-use slog::{debug, info, Logger};
-use std::{collections::HashMap, time::Duration};
+use slog::{debug, info, warn, Logger};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
 
-use crate::{mithril_p2p_topic, p2p::PeerError};
+use crate::{
+    mithril_p2p_topic,
+    p2p::{
+        BroadcastMessageValidationError, BroadcastMessageValidator, PeerConfiguration, PeerError,
+    },
+};
 
 /// The idle connection timeout for a P2P connection
 const P2P_IDLE_CONNECTION_TIMEOUT: Duration = Duration::from_secs(30);
 
+/// The protocol version advertised to the other peers by the identify protocol
+const P2P_IDENTIFY_PROTOCOL_VERSION: &str = "/mithril/relay/1.0.0";
+
+/// The number of invalid messages forwarded by a peer after which it is banned
+const P2P_INVALID_MESSAGES_BAN_THRESHOLD: f64 = 10.0;
+
+/// The duration after which the count of invalid messages forwarded by a peer is halved
+const P2P_INVALID_MESSAGES_HALF_LIFE: Duration = Duration::from_secs(600);
+
+/// The count below which the invalid messages forwarded by a peer are forgotten
+const P2P_INVALID_MESSAGES_FORGET_THRESHOLD: f64 = 0.1;
+
+/// Count of the invalid messages forwarded by a peer, decaying over time so that only the peers
+/// that keep forwarding invalid messages are banned
+#[derive(Debug, Clone, Copy)]
+struct InvalidMessagesCount {
+    count: f64,
+    updated_at: Instant,
+}
+
+impl InvalidMessagesCount {
+    fn new(now: Instant) -> Self {
+        Self {
+            count: 0.0,
+            updated_at: now,
+        }
+    }
+
+    fn decayed_count(&self, now: Instant) -> f64 {
+        let elapsed = now.saturating_duration_since(self.updated_at);
+        self.count
+            * 0.5_f64.powf(elapsed.as_secs_f64() / P2P_INVALID_MESSAGES_HALF_LIFE.as_secs_f64())
+    }
+
+    fn increment(&mut self, now: Instant) -> f64 {
+        self.count = self.decayed_count(now) + 1.0;
+        self.updated_at = now;
+        self.count
+    }
+}
+
 /// [Peer] custom network behaviour
 #[derive(NetworkBehaviour)]
 pub struct PeerBehaviour {
     gossipsub: gossipsub::Behaviour,
     ping: ping::Behaviour,
+    identify: identify::Behaviour,
+    autonat: autonat::Behaviour,
+    relay_client: relay::client::Behaviour,
+    relay_server: Toggle<relay::Behaviour>,
+    dcutr: dcutr::Behaviour,
 }
 
 /// [Peer] event that is polled from the swarm
@@ -49,6 +110,11 @@ pub enum PeerEvent {
         /// Error that occurred when dialing the remote peer
         error: DialError,
     },
+    /// The peer rejected an invalid message forwarded by another peer
+    MessageRejected {
+        /// Id of the peer that forwarded the message
+        propagation_source: PeerId,
+    },
     /// The peer received a behaviour related event
     Behaviour {
         /// The behaviour event the peer received
@@ -60,7 +126,7 @@ pub enum PeerEvent {
 pub type TopicName = String;
 
 /// The broadcast message received from a Gossip sub event
-#[derive(Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub enum BroadcastMessage {
     /// A signer registration message received from the Gossip sub
     RegisterSigner(RegisterSignerMessage),
@@ -73,6 +139,8 @@ pub enum BroadcastMessage {
 pub struct Peer {
     topics: HashMap<TopicName, gossipsub::IdentTopic>,
     swarm: Option<Swarm<PeerBehaviour>>,
+    configuration: PeerConfiguration,
+    invalid_messages_by_peer: HashMap<PeerId, InvalidMessagesCount>,
     addr: Multiaddr,
     /// Multi address on which the peer is listening
     pub addr_peer: Option<Multiaddr>,
@@ -85,6 +153,8 @@ impl Peer {
         Self {
             topics: Self::build_topics(),
             swarm: None,
+            configuration: PeerConfiguration::default(),
+            invalid_messages_by_peer: HashMap::new(),
             addr: addr.to_owned(),
             addr_peer: None,
             logger: Logger::root(slog::Discard, slog::o!()),
@@ -104,16 +174,54 @@ impl Peer {
         ])
     }
 
+    /// Build the peer scoring parameters: the peers forwarding invalid messages are penalized
+    /// until they are graylisted, their messages are then ignored.
+    fn build_peer_score_params(
+        topics: &HashMap<TopicName, gossipsub::IdentTopic>,
+    ) -> PeerScoreParams {
+        let topic_score_params = TopicScoreParams {
+            topic_weight: 1.0,
+            // Mithril topics have a low and irregular traffic, the peers are not penalized
+            // for not delivering enough messages
+            mesh_message_deliveries_weight: 0.0,
+            mesh_failure_penalty_weight: 0.0,
+            invalid_message_deliveries_weight: -100.0,
+            invalid_message_deliveries_decay: 0.9,
+            ..TopicScoreParams::default()
+        };
+
+        PeerScoreParams {
+            topics: topics
+                .values()
+                .map(|topic| (topic.hash(), topic_score_params.clone()))
+                .collect(),
+            ..PeerScoreParams::default()
+        }
+    }
+
     /// Set the logger for the peer
     pub fn with_logger(mut self, logger: &Logger) -> Self {
         self.logger = logger.new_with_component_name::<Self>();
         self
     }
 
+    /// Set the configuration of the peer
+    pub fn with_configuration(mut self, configuration: PeerConfiguration) -> Self {
+        self.configuration = configuration;
+        self
+    }
+
     /// Start the peer
     pub async fn start(mut self) -> StdResult<Self> {
         debug!(self.logger, "Starting...");
-        let mut swarm = SwarmBuilder::with_new_identity()
+        let keypair = self
+            .configuration
+            .keypair
+            .take()
+            .unwrap_or_else(Keypair::generate_ed25519);
+        let enable_circuit_relay_server = self.configuration.enable_circuit_relay_server;
+        let topics = self.topics.clone();
+        let mut swarm = SwarmBuilder::with_existing_identity(keypair)
             .with_tokio()
             .with_tcp(
                 Default::default(),
@@ -128,7 +236,8 @@ impl Peer {
                 yamux::Config::default,
             )
             .await?
-            .with_behaviour(|key| {
+            .with_relay_client(noise::Config::new, yamux::Config::default)?
+            .with_behaviour(|key, relay_client| {
                 let gossipsub_config = gossipsub::ConfigBuilder::default()
                     .max_transmit_size(262144)
                     .heartbeat_initial_delay(Duration::from_millis(100))
@@ -136,19 +245,43 @@ impl Peer {
                     .history_length(10)
                     .history_gossip(10)
                     .validation_mode(ValidationMode::Strict)
+                    .validate_messages()
                     .build()?;
-                Ok(PeerBehaviour {
-                    gossipsub: gossipsub::Behaviour::new(
-                        gossipsub::MessageAuthenticity::Signed(key.clone()),
-                        gossipsub_config,
+                let mut gossipsub = gossipsub::Behaviour::new(
+                    gossipsub::MessageAuthenticity::Signed(key.clone()),
+                    gossipsub_config,
+                )
+                .expect("Valid configuration");
+                gossipsub
+                    .with_peer_score(
+                        Self::build_peer_score_params(&topics),
+                        PeerScoreThresholds::default(),
                     )
-                    .expect("Valid configuration"),
+                    .expect("Valid peer score configuration");
+                Ok(PeerBehaviour {
+                    gossipsub,
                     ping: ping::Behaviour::new(ping::Config::new()),
+                    identify: identify::Behaviour::new(identify::Config::new(
+                        P2P_IDENTIFY_PROTOCOL_VERSION.to_string(),
+                        key.public(),
+                    )),
+                    autonat: autonat::Behaviour::new(
+                        key.public().to_peer_id(),
+                        autonat::Config::default(),
+                    ),
+                    relay_client,
+                    relay_server: Toggle::from(enable_circuit_relay_server.then(|| {
+                        relay::Behaviour::new(key.public().to_peer_id(), relay::Config::default())
+                    })),
+                    dcutr: dcutr::Behaviour::new(key.public().to_peer_id()),
                 })
             })?
             .with_swarm_config(|c| c.with_idle_connection_timeout(P2P_IDLE_CONNECTION_TIMEOUT))
             .build();
 
+        for external_address in &self.configuration.external_addresses {
+            swarm.add_external_address(external_address.clone());
+        }
         for topic in self.topics.values() {
             debug!(self.logger, "Subscribing to"; "topic" => ?topic, "local_peer_id" => ?self.local_peer_id());
             swarm.behaviour_mut().gossipsub.subscribe(topic)?;
@@ -165,6 +298,17 @@ impl Peer {
             }
         }
 
+        for circuit_address in self.configuration.circuit_relay_listen_addresses() {
+            info!(self.logger, "Listening through circuit relay"; "address" => ?circuit_address);
+            self.swarm
+                .as_mut()
+                .ok_or(PeerError::UnavailableSwarm())?
+                .listen_on(circuit_address)?;
+        }
+        for bootstrap_peer in self.configuration.bootstrap_peers.clone() {
+            self.dial(bootstrap_peer)?;
+        }
+
         Ok(self)
     }
 
@@ -204,6 +348,21 @@ impl Peer {
                 debug!(self.logger, "Received connection established event"; "remote_peer_id" => ?peer_id, "local_peer_id" => ?self.local_peer_id());
                 Ok(Some(PeerEvent::ConnectionEstablished { peer_id }))
             }
+            Some(swarm::SwarmEvent::Behaviour(PeerBehaviourEvent::Gossipsub(
+                gossipsub::Event::Message {
+                    propagation_source,
+                    message_id,
+                    message,
+                },
+            ))) => Ok(self.validate_gossip_message(propagation_source, message_id, message)),
+            Some(swarm::SwarmEvent::Behaviour(PeerBehaviourEvent::Autonat(
+                autonat::Event::StatusChanged { old, new },
+            ))) => {
+                info!(self.logger, "NAT status changed"; "old" => ?old, "new" => ?new, "local_peer_id" => ?self.local_peer_id());
+                Ok(Some(PeerEvent::Behaviour {
+                    event: PeerBehaviourEvent::Autonat(autonat::Event::StatusChanged { old, new }),
+                }))
+            }
             Some(swarm::SwarmEvent::Behaviour(event)) => {
                 debug!(self.logger, "Received behaviour event"; "event" => ?event, "local_peer_id" => ?self.local_peer_id());
                 Ok(Some(PeerEvent::Behaviour { event }))
@@ -216,6 +375,71 @@ impl Peer {
         }
     }
 
+    /// Validate a message received from the P2P pubsub: the valid messages are propagated to the
+    /// other peers, and the peers that forward invalid messages are penalized then banned.
+    fn validate_gossip_message(
+        &mut self,
+        propagation_source: PeerId,
+        message_id: gossipsub::MessageId,
+        message: gossipsub::Message,
+    ) -> Option<PeerEvent> {
+        let validation =
+            BroadcastMessageValidator::new(self.configuration.signers_verification_keys.clone())
+                .validate(message.topic.as_str(), &message.data);
+        let acceptance = match &validation {
+            Ok(_) => MessageAcceptance::Accept,
+            Err(BroadcastMessageValidationError::Unverifiable(_)) => MessageAcceptance::Ignore,
+            Err(BroadcastMessageValidationError::Invalid(_)) => MessageAcceptance::Reject,
+        };
+        let swarm = self.swarm.as_mut()?;
+        if let Err(error) = swarm
+            .behaviour_mut()
+            .gossipsub
+            .report_message_validation_result(&message_id, &propagation_source, acceptance)
+        {
+            warn!(self.logger, "Could not report message validation result"; "error" => ?error, "message_id" => %message_id);
+        }
+
+        match validation {
+            Ok(_) => {
+                debug!(self.logger, "Received valid gossip message"; "message_id" => %message_id, "propagation_source" => %propagation_source);
+                Some(PeerEvent::Behaviour {
+                    event: PeerBehaviourEvent::Gossipsub(gossipsub::Event::Message {
+                        propagation_source,
+                        message_id,
+                        message,
+                    }),
+                })
+            }
+            Err(BroadcastMessageValidationError::Unverifiable(reason)) => {
+                debug!(self.logger, "Ignored unverifiable gossip message"; "reason" => reason, "message_id" => %message_id, "propagation_source" => %propagation_source);
+                None
+            }
+            Err(error) => {
+                let now = Instant::now();
+                self.invalid_messages_by_peer.retain(|_, invalid_messages| {
+                    invalid_messages.decayed_count(now) >= P2P_INVALID_MESSAGES_FORGET_THRESHOLD
+                });
+                let invalid_messages = self
+                    .invalid_messages_by_peer
+                    .entry(propagation_source)
+                    .or_insert_with(|| InvalidMessagesCount::new(now))
+                    .increment(now);
+                warn!(self.logger, "Rejected invalid gossip message"; "error" => ?error, "message_id" => %message_id, "propagation_source" => %propagation_source, "invalid_messages" => invalid_messages);
+                if invalid_messages >= P2P_INVALID_MESSAGES_BAN_THRESHOLD {
+                    warn!(self.logger, "Banning peer forwarding invalid messages"; "peer_id" => %propagation_source);
+                    swarm
+                        .behaviour_mut()
+                        .gossipsub
+                        .blacklist_peer(&propagation_source);
+                    let _ = swarm.disconnect_peer_id(propagation_source);
+                }
+
+                Some(PeerEvent::MessageRejected { propagation_source })
+            }
+        }
+    }
+
     /// Publish a signature on the P2P pubsub
     pub fn publish_signature(
         &mut self,
@@ -290,3 +514,150 @@ impl Peer {
         self.swarm.as_ref().map(|s| s.local_peer_id().to_owned())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use libp2p::{futures::future::select_all, multiaddr::Protocol};
+
+    use super::*;
+
+    fn local_address() -> Multiaddr {
+        "/ip4/127.0.0.1/tcp/0".parse().unwrap()
+    }
+
+    /// Address of a started peer, including its peer id
+    fn peer_address(peer: &Peer) -> Multiaddr {
+        peer.addr_peer
+            .clone()
+            .unwrap()
+            .with(Protocol::P2p(peer.local_peer_id().unwrap()))
+    }
+
+    /// Tick the given peers together until one of them receives an event matching the predicate
+    async fn tick_until<F>(peers: &mut [&mut Peer], mut predicate: F)
+    where
+        F: FnMut(usize, &PeerEvent) -> bool,
+    {
+        tokio::time::timeout(Duration::from_secs(10), async {
+            loop {
+                let (result, index, _) =
+                    select_all(peers.iter_mut().map(|peer| Box::pin(peer.tick_swarm()))).await;
+                if let Some(event) = result.unwrap() {
+                    if predicate(index, &event) {
+                        return;
+                    }
+                }
+            }
+        })
+        .await
+        .expect("The expected event should have been received")
+    }
+
+    #[tokio::test]
+    async fn a_peer_joins_the_network_through_its_bootstrap_peers() {
+        let mut bootstrap_peer = Peer::new(&local_address()).start().await.unwrap();
+        let bootstrap_peer_id = bootstrap_peer.local_peer_id().unwrap();
+        let mut peer = Peer::new(&local_address())
+            .with_configuration(PeerConfiguration {
+                bootstrap_peers: vec![peer_address(&bootstrap_peer)],
+                ..PeerConfiguration::default()
+            })
+            .start()
+            .await
+            .unwrap();
+
+        tick_until(&mut [&mut bootstrap_peer, &mut peer], |index, event| {
+            index == 1
+                && matches!(event, PeerEvent::ConnectionEstablished { peer_id } if *peer_id == bootstrap_peer_id)
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn a_peer_behind_a_nat_is_reachable_through_a_circuit_relay() {
+        let relay_port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let relay_address: Multiaddr = format!("/ip4/127.0.0.1/tcp/{relay_port}").parse().unwrap();
+        let mut relay = Peer::new(&relay_address)
+            .with_configuration(PeerConfiguration {
+                enable_circuit_relay_server: true,
+                external_addresses: vec![relay_address.clone()],
+                ..PeerConfiguration::default()
+            })
+            .start()
+            .await
+            .unwrap();
+        let relay_peer_id = relay.local_peer_id().unwrap();
+        let mut peer_behind_nat = Peer::new(&local_address())
+            .with_configuration(PeerConfiguration {
+                circuit_relay_peers: vec![peer_address(&relay)],
+                ..PeerConfiguration::default()
+            })
+            .start()
+            .await
+            .unwrap();
+        let peer_behind_nat_id = peer_behind_nat.local_peer_id().unwrap();
+
+        tick_until(&mut [&mut relay, &mut peer_behind_nat], |index, event| {
+            index == 1
+                && matches!(
+                    event,
+                    PeerEvent::Behaviour {
+                        event: PeerBehaviourEvent::RelayClient(
+                            relay::client::Event::ReservationReqAccepted { relay_peer_id: id, .. }
+                        )
+                    } if *id == relay_peer_id
+                )
+        })
+        .await;
+
+        let mut remote_peer = Peer::new(&local_address()).start().await.unwrap();
+        remote_peer
+            .dial(
+                peer_address(&relay)
+                    .with(Protocol::P2pCircuit)
+                    .with(Protocol::P2p(peer_behind_nat_id)),
+            )
+            .unwrap();
+        tick_until(
+            &mut [&mut relay, &mut peer_behind_nat, &mut remote_peer],
+            |index, event| {
+                index == 2
+                    && matches!(event, PeerEvent::ConnectionEstablished { peer_id } if *peer_id == peer_behind_nat_id)
+            },
+        )
+        .await;
+    }
+
+    #[test]
+    fn invalid_messages_count_is_halved_after_its_half_life() {
+        let now = Instant::now();
+        let mut invalid_messages = InvalidMessagesCount::new(now);
+        for _ in 0..8 {
+            invalid_messages.increment(now);
+        }
+
+        assert_eq!(8.0, invalid_messages.decayed_count(now));
+        assert_eq!(
+            4.0,
+            invalid_messages.decayed_count(now + P2P_INVALID_MESSAGES_HALF_LIFE)
+        );
+        assert_eq!(
+            3.0,
+            invalid_messages.increment(now + P2P_INVALID_MESSAGES_HALF_LIFE * 2)
+        );
+    }
+
+    #[test]
+    fn a_peer_forwarding_invalid_messages_slowly_is_never_banned() {
+        let mut now = Instant::now();
+        let mut invalid_messages = InvalidMessagesCount::new(now);
+        for _ in 0..100 {
+            now += P2P_INVALID_MESSAGES_HALF_LIFE;
+            assert!(invalid_messages.increment(now) < P2P_INVALID_MESSAGES_BAN_THRESHOLD);
+        }
+    }
+}
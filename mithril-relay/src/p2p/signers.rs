@@ -0,0 +1,385 @@
+use anyhow::{anyhow, Context};
+use mithril_common::{
+    entities::{Epoch, PartyId, ProtocolParameters, SignerWithStake, SingleSignatures, Stake},
+    logging::LoggerExtensions,
+    messages::{EpochSettingsMessage, SignerMessagePart},
+    protocol::{MultiSigner, SignerBuilder},
+    StdResult,
+};
+use serde::Deserialize;
+use slog::{debug, warn, Logger};
+use std::{
+    collections::HashMap,
+    fmt::{Debug, Formatter},
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+use tokio::sync::Mutex;
+
+/// Interval at which the signers verification keys are updated from the aggregator
+pub const SIGNERS_VERIFICATION_KEYS_UPDATE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Timeout of the requests sent to the aggregator to update the signers verification keys
+const AGGREGATOR_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Maximum number of protocol parameters remembered to verify the signatures
+const MAX_KNOWN_PROTOCOL_PARAMETERS: usize = 4;
+
+/// Verification keys and stakes of the signers allowed to sign, used to authenticate the
+/// signatures broadcast on the P2P network.
+///
+/// A signature is authentic if it verifies against the stake distribution of the current or
+/// the next epoch, with one of the protocol parameters advertised by the aggregator: the
+/// parameters only weight the lottery, a signature can't verify without the secret key of a
+/// registered signer whatever the parameters.
+#[derive(Default)]
+pub struct SignersVerificationKeys {
+    multi_signers: RwLock<Vec<MultiSigner>>,
+}
+
+impl SignersVerificationKeys {
+    /// Replace the known signers with the given stake distributions
+    pub fn update(
+        &self,
+        stake_distributions: &[Vec<SignerWithStake>],
+        protocol_parameters: &[ProtocolParameters],
+    ) -> StdResult<()> {
+        let mut multi_signers = vec![];
+        for signers in stake_distributions.iter().filter(|s| !s.is_empty()) {
+            for parameters in protocol_parameters {
+                multi_signers.push(SignerBuilder::new(signers, parameters)?.build_multi_signer());
+            }
+        }
+        *self
+            .multi_signers
+            .write()
+            .map_err(|e| anyhow!("Signers verification keys lock is poisoned: {e}"))? =
+            multi_signers;
+
+        Ok(())
+    }
+
+    /// Check if no signer is known yet, in which case no signature can be authenticated
+    pub fn is_empty(&self) -> bool {
+        self.multi_signers
+            .read()
+            .map(|multi_signers| multi_signers.is_empty())
+            .unwrap_or(true)
+    }
+
+    /// Verify a single signature of the given message against the known signers
+    pub fn verify(
+        &self,
+        signed_message: &str,
+        single_signature: &SingleSignatures,
+    ) -> StdResult<()> {
+        let multi_signers = self
+            .multi_signers
+            .read()
+            .map_err(|e| anyhow!("Signers verification keys lock is poisoned: {e}"))?;
+        if multi_signers.iter().any(|multi_signer| {
+            multi_signer
+                .verify_single_signature(&signed_message, single_signature)
+                .is_ok()
+        }) {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "Signature of party id '{}' does not verify against the known signers",
+                single_signature.party_id
+            ))
+        }
+    }
+}
+
+impl Debug for SignersVerificationKeys {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SignersVerificationKeys")
+            .field("is_empty", &self.is_empty())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Stake of a signer registered with the aggregator
+#[derive(Deserialize)]
+struct SignerRegistration {
+    party_id: PartyId,
+    stake: Stake,
+}
+
+/// Signers registered with the aggregator at an epoch
+#[derive(Deserialize)]
+struct SignerRegistrations {
+    registrations: Vec<SignerRegistration>,
+}
+
+/// Update periodically the [SignersVerificationKeys] with the signers published by an aggregator
+pub struct SignersVerificationKeysUpdater {
+    aggregator_endpoint: String,
+    http_client: reqwest::Client,
+    signers_verification_keys: Arc<SignersVerificationKeys>,
+    known_protocol_parameters: Mutex<Vec<ProtocolParameters>>,
+    logger: Logger,
+}
+
+impl SignersVerificationKeysUpdater {
+    /// SignersVerificationKeysUpdater factory
+    pub fn new(
+        aggregator_endpoint: &str,
+        signers_verification_keys: Arc<SignersVerificationKeys>,
+        logger: &Logger,
+    ) -> StdResult<Self> {
+        let http_client = reqwest::Client::builder()
+            .timeout(AGGREGATOR_REQUEST_TIMEOUT)
+            .build()
+            .with_context(|| "Could not build the signers verification keys HTTP client")?;
+
+        Ok(Self {
+            aggregator_endpoint: aggregator_endpoint.to_owned(),
+            http_client,
+            signers_verification_keys,
+            known_protocol_parameters: Mutex::new(vec![]),
+            logger: logger.new_with_component_name::<Self>(),
+        })
+    }
+
+    /// Update the signers verification keys at the given interval, the previous keys are kept
+    /// if the aggregator can't be reached
+    pub async fn run_forever(self, run_interval: Duration) {
+        let mut interval = tokio::time::interval(run_interval);
+        loop {
+            interval.tick().await;
+            if let Err(error) = self.update().await {
+                warn!(self.logger, "Could not update the signers verification keys"; "error" => ?error);
+            }
+        }
+    }
+
+    /// Update the signers verification keys with the current and next signers of the aggregator
+    pub async fn update(&self) -> StdResult<()> {
+        let epoch_settings: EpochSettingsMessage = self.get("epoch-settings").await?;
+        // Signers registered two epochs ago sign during the current epoch, the ones registered
+        // during the previous epoch sign during the next epoch
+        let current_signers = self
+            .get_signers_with_stake(
+                epoch_settings.current_signers.clone(),
+                epoch_settings.epoch.offset_by(-2).ok(),
+            )
+            .await?;
+        let next_signers = self
+            .get_signers_with_stake(
+                epoch_settings.next_signers.clone(),
+                epoch_settings.epoch.offset_by(-1).ok(),
+            )
+            .await?;
+        let protocol_parameters = self.remember_protocol_parameters(&epoch_settings).await;
+
+        self.signers_verification_keys
+            .update(&[current_signers, next_signers], &protocol_parameters)?;
+        debug!(
+            self.logger, "Updated the signers verification keys";
+            "epoch" => ?epoch_settings.epoch
+        );
+
+        Ok(())
+    }
+
+    async fn get<T: serde::de::DeserializeOwned>(&self, route: &str) -> StdResult<T> {
+        let url = format!("{}/{route}", self.aggregator_endpoint);
+        self.http_client
+            .get(&url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .with_context(|| format!("Get '{url}' failed"))?
+            .json()
+            .await
+            .with_context(|| format!("Could not parse the response of '{url}'"))
+    }
+
+    async fn get_signers_with_stake(
+        &self,
+        signers: Vec<SignerMessagePart>,
+        registration_epoch: Option<Epoch>,
+    ) -> StdResult<Vec<SignerWithStake>> {
+        let Some(registration_epoch) = registration_epoch else {
+            return Ok(vec![]);
+        };
+        let registrations: SignerRegistrations = self
+            .get(&format!("signers/registered/{registration_epoch}"))
+            .await?;
+        let stakes: HashMap<PartyId, Stake> = registrations
+            .registrations
+            .into_iter()
+            .map(|registration| (registration.party_id, registration.stake))
+            .collect();
+
+        Ok(SignerMessagePart::try_into_signers(signers)?
+            .into_iter()
+            .filter_map(|signer| {
+                let stake = *stakes.get(&signer.party_id)?;
+                Some(SignerWithStake::from_signer(signer, stake))
+            })
+            .collect())
+    }
+
+    async fn remember_protocol_parameters(
+        &self,
+        epoch_settings: &EpochSettingsMessage,
+    ) -> Vec<ProtocolParameters> {
+        #[allow(deprecated)]
+        let advertised_protocol_parameters = [
+            epoch_settings.protocol_parameters.clone(),
+            epoch_settings.next_protocol_parameters.clone(),
+            Some(
+                epoch_settings
+                    .signer_registration_protocol_parameters
+                    .clone(),
+            ),
+        ];
+        let mut known_protocol_parameters = self.known_protocol_parameters.lock().await;
+        for parameters in advertised_protocol_parameters.into_iter().flatten() {
+            if let Some(position) = known_protocol_parameters
+                .iter()
+                .position(|known| known == &parameters)
+            {
+                known_protocol_parameters.remove(position);
+            }
+            known_protocol_parameters.push(parameters);
+        }
+        let excess = known_protocol_parameters
+            .len()
+            .saturating_sub(MAX_KNOWN_PROTOCOL_PARAMETERS);
+        known_protocol_parameters.drain(..excess);
+
+        known_protocol_parameters.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mithril_common::entities::ProtocolMessage;
+    use mithril_common::protocol::ToMessage;
+    use mithril_common::test_utils::test_http_server::test_http_server;
+    use mithril_common::test_utils::{MithrilFixture, MithrilFixtureBuilder};
+    use warp::Filter;
+
+    use crate::test_tools::TestLogger;
+
+    use super::*;
+
+    fn single_signature(fixture: &MithrilFixture) -> (String, SingleSignatures) {
+        let message = ProtocolMessage::default();
+        let signature = fixture.signers_fixture()[0].sign(&message).unwrap();
+
+        (message.to_message(), signature)
+    }
+
+    #[test]
+    fn verify_a_signature_of_a_known_signer() {
+        let fixture = MithrilFixtureBuilder::default().with_signers(3).build();
+        let keys = SignersVerificationKeys::default();
+        keys.update(
+            &[fixture.signers_with_stake()],
+            &[fixture.protocol_parameters()],
+        )
+        .unwrap();
+        let (message, signature) = single_signature(&fixture);
+
+        keys.verify(&message, &signature).unwrap();
+        keys.verify("another message", &signature)
+            .expect_err("A signature of another message should not verify");
+    }
+
+    #[test]
+    fn reject_a_signature_of_an_unknown_signer() {
+        let fixture = MithrilFixtureBuilder::default().with_signers(3).build();
+        let other_fixture = MithrilFixtureBuilder::default()
+            .with_signers(3)
+            .with_party_id_seed([9; 32])
+            .build();
+        let keys = SignersVerificationKeys::default();
+        keys.update(
+            &[fixture.signers_with_stake()],
+            &[fixture.protocol_parameters()],
+        )
+        .unwrap();
+        let (message, signature) = single_signature(&other_fixture);
+
+        keys.verify(&message, &signature)
+            .expect_err("A signature of an unknown signer should not verify");
+    }
+
+    #[test]
+    fn no_signature_verifies_without_known_signers() {
+        let fixture = MithrilFixtureBuilder::default().with_signers(3).build();
+        let keys = SignersVerificationKeys::default();
+        let (message, signature) = single_signature(&fixture);
+
+        assert!(keys.is_empty());
+        keys.verify(&message, &signature)
+            .expect_err("No signature should verify without known signers");
+    }
+
+    #[tokio::test]
+    async fn update_the_keys_with_the_signers_published_by_the_aggregator() {
+        let fixture = MithrilFixtureBuilder::default().with_signers(3).build();
+        #[allow(deprecated)]
+        let epoch_settings = EpochSettingsMessage {
+            epoch: Epoch(10),
+            protocol_parameters: Some(fixture.protocol_parameters()),
+            next_protocol_parameters: Some(fixture.protocol_parameters()),
+            signer_registration_protocol_parameters: fixture.protocol_parameters(),
+            current_signers: SignerMessagePart::from_signers(fixture.signers()),
+            next_signers: SignerMessagePart::from_signers(fixture.signers()),
+            ..EpochSettingsMessage::dummy()
+        };
+        let registrations = serde_json::json!({
+            "registrations": fixture
+                .signers_with_stake()
+                .iter()
+                .map(|signer| serde_json::json!({"party_id": signer.party_id, "stake": signer.stake}))
+                .collect::<Vec<_>>()
+        });
+        let server = test_http_server(
+            warp::path("epoch-settings")
+                .map(move || warp::reply::json(&epoch_settings))
+                .or(warp::path!("signers" / "registered" / u64)
+                    .map(move |_epoch| warp::reply::json(&registrations))),
+        );
+        let keys = Arc::new(SignersVerificationKeys::default());
+        let updater =
+            SignersVerificationKeysUpdater::new(&server.url(), keys.clone(), &TestLogger::stdout())
+                .unwrap();
+
+        updater.update().await.unwrap();
+
+        let (message, signature) = single_signature(&fixture);
+        keys.verify(&message, &signature).unwrap();
+    }
+
+    #[tokio::test]
+    async fn keep_the_keys_when_the_aggregator_is_unreachable() {
+        let fixture = MithrilFixtureBuilder::default().with_signers(3).build();
+        let keys = Arc::new(SignersVerificationKeys::default());
+        keys.update(
+            &[fixture.signers_with_stake()],
+            &[fixture.protocol_parameters()],
+        )
+        .unwrap();
+        let updater = SignersVerificationKeysUpdater::new(
+            "http://127.0.0.1:1",
+            keys.clone(),
+            &TestLogger::stdout(),
+        )
+        .unwrap();
+
+        updater
+            .update()
+            .await
+            .expect_err("Update should fail when the aggregator is unreachable");
+
+        let (message, signature) = single_signature(&fixture);
+        keys.verify(&message, &signature).unwrap();
+    }
+}
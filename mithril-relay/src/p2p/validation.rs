@@ -0,0 +1,364 @@
+use anyhow::{anyhow, Context};
+use mithril_common::{
+    crypto_helper::{
+        ProtocolKeyRegistration, ProtocolOpCert, ProtocolSignerVerificationKey,
+        ProtocolSignerVerificationKeySignature, ProtocolSingleSignature,
+    },
+    entities::SingleSignatures,
+    messages::{RegisterSignatureMessage, RegisterSignerMessage},
+    StdResult,
+};
+use std::sync::Arc;
+
+use crate::{
+    mithril_p2p_topic,
+    p2p::{BroadcastMessage, BroadcastMessageValidationError, SignersVerificationKeys},
+};
+
+/// Validate the broadcast messages received from the P2P network before they are delivered
+/// and propagated to the other peers.
+///
+/// A signer registration is signed by the KES key of the originating signer, certified by its
+/// operational certificate, and a signature is verified against the verification keys of the
+/// registered signers, so a relay can check that they were not forged or altered by the
+/// peers that forwarded them.
+pub struct BroadcastMessageValidator {
+    signers_verification_keys: Arc<SignersVerificationKeys>,
+}
+
+impl BroadcastMessageValidator {
+    /// BroadcastMessageValidator factory
+    pub fn new(signers_verification_keys: Arc<SignersVerificationKeys>) -> Self {
+        Self {
+            signers_verification_keys,
+        }
+    }
+
+    /// Parse and validate a message received on the given topic
+    pub fn validate(
+        &self,
+        topic_name: &str,
+        data: &[u8],
+    ) -> Result<BroadcastMessage, BroadcastMessageValidationError> {
+        let message: BroadcastMessage = serde_json::from_slice(data)
+            .with_context(|| "Could not parse broadcast message")
+            .map_err(BroadcastMessageValidationError::Invalid)?;
+        match (&message, topic_name) {
+            (BroadcastMessage::RegisterSigner(signer_message), mithril_p2p_topic::SIGNERS) => {
+                Self::validate_signer_registration(signer_message)
+                    .map_err(BroadcastMessageValidationError::Invalid)?
+            }
+            (
+                BroadcastMessage::RegisterSignature(signature_message),
+                mithril_p2p_topic::SIGNATURES,
+            ) => self.validate_signature(signature_message)?,
+            _ => {
+                return Err(BroadcastMessageValidationError::Invalid(anyhow!(
+                    "Broadcast message published on unexpected topic '{topic_name}'"
+                )))
+            }
+        }
+
+        Ok(message)
+    }
+
+    /// Check that a signer registration is signed by the signer it registers
+    pub fn validate_signer_registration(message: &RegisterSignerMessage) -> StdResult<()> {
+        let verification_key =
+            ProtocolSignerVerificationKey::from_json_hex(&message.verification_key)
+                .with_context(|| "Could not decode the signer verification key")?;
+        // The relays never gossip the registration of an uncertified signer, even when the
+        // signer certification is skipped by the key registration
+        let operational_certificate = message
+            .operational_certificate
+            .as_ref()
+            .ok_or(anyhow!(
+                "The signer registration of party id '{}' has no operational certificate",
+                message.party_id
+            ))
+            .and_then(|operational_certificate| {
+                ProtocolOpCert::from_json_hex(operational_certificate)
+                    .with_context(|| "Could not decode the signer operational certificate")
+            })?;
+        let verification_key_signature = message
+            .verification_key_signature
+            .as_ref()
+            .map(|signature| ProtocolSignerVerificationKeySignature::from_json_hex(signature))
+            .transpose()
+            .with_context(|| "Could not decode the signer verification key signature")?;
+
+        let mut key_registration =
+            ProtocolKeyRegistration::init(&vec![(message.party_id.clone(), 1)]);
+        key_registration
+            .register(
+                Some(message.party_id.clone()),
+                Some(operational_certificate),
+                verification_key_signature,
+                message.kes_period,
+                verification_key,
+            )
+            .with_context(|| {
+                format!(
+                    "Invalid signer registration signature for party id '{}'",
+                    message.party_id
+                )
+            })?;
+
+        Ok(())
+    }
+
+    /// Check that a signature message is signed by a registered signer
+    ///
+    /// The signature can't be authenticated if the verification keys of the signers are not
+    /// known yet, it is then reported as unverifiable rather than invalid.
+    pub fn validate_signature(
+        &self,
+        message: &RegisterSignatureMessage,
+    ) -> Result<(), BroadcastMessageValidationError> {
+        let single_signature =
+            Self::decode_signature(message).map_err(BroadcastMessageValidationError::Invalid)?;
+        let signed_message = message.signed_message.as_ref().ok_or_else(|| {
+            BroadcastMessageValidationError::Invalid(anyhow!(
+                "Signature message of party id '{}' without signed message",
+                message.party_id
+            ))
+        })?;
+        if self.signers_verification_keys.is_empty() {
+            return Err(BroadcastMessageValidationError::Unverifiable(format!(
+                "the signers verification keys are not known yet to verify the signature of party id '{}'",
+                message.party_id
+            )));
+        }
+
+        self.signers_verification_keys
+            .verify(signed_message, &single_signature)
+            .map_err(BroadcastMessageValidationError::Invalid)
+    }
+
+    fn decode_signature(message: &RegisterSignatureMessage) -> StdResult<SingleSignatures> {
+        if message.party_id.is_empty() {
+            return Err(anyhow!("Signature message without party id"));
+        }
+        if message.won_indexes.is_empty() {
+            return Err(anyhow!(
+                "Signature message of party id '{}' without won indexes",
+                message.party_id
+            ));
+        }
+        let signature =
+            ProtocolSingleSignature::from_json_hex(&message.signature).with_context(|| {
+                format!(
+                    "Could not decode the single signature of party id '{}'",
+                    message.party_id
+                )
+            })?;
+
+        Ok(SingleSignatures::new(
+            message.party_id.clone(),
+            signature,
+            message.won_indexes.clone(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mithril_common::entities::{Epoch, ProtocolMessage, SignedEntityType};
+    use mithril_common::test_utils::{MithrilFixture, MithrilFixtureBuilder};
+
+    use super::*;
+
+    fn fixture() -> MithrilFixture {
+        MithrilFixtureBuilder::default().with_signers(3).build()
+    }
+
+    fn validator(fixture: &MithrilFixture) -> BroadcastMessageValidator {
+        let signers_verification_keys = SignersVerificationKeys::default();
+        signers_verification_keys
+            .update(
+                &[fixture.signers_with_stake()],
+                &[fixture.protocol_parameters()],
+            )
+            .unwrap();
+
+        BroadcastMessageValidator::new(Arc::new(signers_verification_keys))
+    }
+
+    fn signature_message(fixture: &MithrilFixture) -> RegisterSignatureMessage {
+        fixture.signers_fixture()[0]
+            .register_signature_message(SignedEntityType::dummy(), &ProtocolMessage::default())
+            .unwrap()
+    }
+
+    fn encode(message: BroadcastMessage) -> Vec<u8> {
+        serde_json::to_vec(&message).unwrap()
+    }
+
+    #[test]
+    fn accept_a_signer_registration_signed_by_the_signer() {
+        let fixture = fixture();
+        let data = encode(BroadcastMessage::RegisterSigner(
+            fixture.signers_fixture()[0].register_signer_message(Epoch(1)),
+        ));
+
+        validator(&fixture)
+            .validate(mithril_p2p_topic::SIGNERS, &data)
+            .unwrap();
+    }
+
+    #[test]
+    fn reject_a_signer_registration_with_a_forged_party_id() {
+        let fixture = fixture();
+        let mut message = fixture.signers_fixture()[0].register_signer_message(Epoch(1));
+        message.party_id = "pool1forged".to_string();
+        let data = encode(BroadcastMessage::RegisterSigner(message));
+
+        validator(&fixture)
+            .validate(mithril_p2p_topic::SIGNERS, &data)
+            .expect_err("A forged party id should be rejected");
+    }
+
+    #[test]
+    fn reject_a_signer_registration_with_a_verification_key_not_signed_by_the_signer() {
+        let fixture = fixture();
+        let mut message = fixture.signers_fixture()[0].register_signer_message(Epoch(1));
+        let other_fixture = MithrilFixtureBuilder::default()
+            .with_signers(1)
+            .with_party_id_seed([9; 32])
+            .build();
+        message.verification_key = other_fixture.signers_with_stake()[0]
+            .verification_key
+            .to_json_hex()
+            .unwrap();
+        let data = encode(BroadcastMessage::RegisterSigner(message));
+
+        validator(&fixture)
+            .validate(mithril_p2p_topic::SIGNERS, &data)
+            .expect_err("A verification key not signed by the signer should be rejected");
+    }
+
+    #[test]
+    fn reject_a_signer_registration_without_operational_certificate() {
+        let fixture = fixture();
+        let message = RegisterSignerMessage {
+            operational_certificate: None,
+            verification_key_signature: None,
+            kes_period: None,
+            ..fixture.signers_fixture()[0].register_signer_message(Epoch(1))
+        };
+
+        BroadcastMessageValidator::validate_signer_registration(&message)
+            .expect_err("A registration without operational certificate should be rejected");
+    }
+
+    #[test]
+    fn accept_a_signature_of_a_registered_signer() {
+        let fixture = fixture();
+        let data = encode(BroadcastMessage::RegisterSignature(signature_message(
+            &fixture,
+        )));
+
+        validator(&fixture)
+            .validate(mithril_p2p_topic::SIGNATURES, &data)
+            .unwrap();
+    }
+
+    #[test]
+    fn reject_a_forged_signature() {
+        let fixture = fixture();
+        let other_fixture = MithrilFixtureBuilder::default()
+            .with_signers(3)
+            .with_party_id_seed([9; 32])
+            .build();
+        let data = encode(BroadcastMessage::RegisterSignature(
+            RegisterSignatureMessage {
+                party_id: fixture.signers_fixture()[0].party_id(),
+                ..signature_message(&other_fixture)
+            },
+        ));
+
+        let error = validator(&fixture)
+            .validate(mithril_p2p_topic::SIGNATURES, &data)
+            .expect_err("A signature not signed by a registered signer should be rejected");
+        assert!(matches!(error, BroadcastMessageValidationError::Invalid(_)));
+    }
+
+    #[test]
+    fn reject_a_signature_of_another_message() {
+        let fixture = fixture();
+        let data = encode(BroadcastMessage::RegisterSignature(
+            RegisterSignatureMessage {
+                signed_message: Some("another message".to_string()),
+                ..signature_message(&fixture)
+            },
+        ));
+
+        validator(&fixture)
+            .validate(mithril_p2p_topic::SIGNATURES, &data)
+            .expect_err("A signature of another message should be rejected");
+    }
+
+    #[test]
+    fn reject_a_signature_without_signed_message() {
+        let fixture = fixture();
+        let data = encode(BroadcastMessage::RegisterSignature(
+            RegisterSignatureMessage {
+                signed_message: None,
+                ..signature_message(&fixture)
+            },
+        ));
+
+        validator(&fixture)
+            .validate(mithril_p2p_topic::SIGNATURES, &data)
+            .expect_err("A signature without signed message should be rejected");
+    }
+
+    #[test]
+    fn reject_a_signature_with_an_invalid_format() {
+        let fixture = fixture();
+        let data = encode(BroadcastMessage::RegisterSignature(
+            RegisterSignatureMessage {
+                signature: "invalid".to_string(),
+                ..signature_message(&fixture)
+            },
+        ));
+
+        validator(&fixture)
+            .validate(mithril_p2p_topic::SIGNATURES, &data)
+            .expect_err("An invalid signature should be rejected");
+    }
+
+    #[test]
+    fn a_signature_is_unverifiable_without_known_signers() {
+        let fixture = fixture();
+        let data = encode(BroadcastMessage::RegisterSignature(signature_message(
+            &fixture,
+        )));
+        let validator =
+            BroadcastMessageValidator::new(Arc::new(SignersVerificationKeys::default()));
+
+        let error = validator
+            .validate(mithril_p2p_topic::SIGNATURES, &data)
+            .expect_err("A signature should not be accepted without known signers");
+        assert!(matches!(
+            error,
+            BroadcastMessageValidationError::Unverifiable(_)
+        ));
+    }
+
+    #[test]
+    fn reject_unparsable_messages_or_messages_on_another_topic() {
+        let fixture = fixture();
+        let validator = validator(&fixture);
+        validator
+            .validate(mithril_p2p_topic::SIGNATURES, b"invalid")
+            .expect_err("An unparsable message should be rejected");
+
+        let data = encode(BroadcastMessage::RegisterSignature(signature_message(
+            &fixture,
+        )));
+        validator
+            .validate(mithril_p2p_topic::SIGNERS, &data)
+            .expect_err("A signature published on the signers topic should be rejected");
+    }
+}
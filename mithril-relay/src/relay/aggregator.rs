@@ -1,18 +1,60 @@
-use crate::p2p::{BroadcastMessage, Peer, PeerEvent};
-use anyhow::anyhow;
-use libp2p::Multiaddr;
+use crate::metrics::RelayMetricsService;
+use crate::p2p::{BroadcastMessage, Peer, PeerBehaviourEvent, PeerConfiguration, PeerEvent};
+use crate::relay::MessageDeduplicator;
+use anyhow::{anyhow, Context};
+use libp2p::{gossipsub, Multiaddr};
 use mithril_common::{
     logging::LoggerExtensions,
     messages::{RegisterSignatureMessage, RegisterSignerMessage},
     StdResult,
 };
 use reqwest::StatusCode;
-use slog::{error, info, Logger};
+use slog::{debug, error, info, warn, Logger};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc::{self, error::TrySendError};
+
+/// Maximum number of messages remembered by the deduplicator of an aggregator relay
+const DEDUPLICATOR_CAPACITY: usize = 100_000;
+
+/// Maximum number of attempts to forward a message to the aggregator
+const FORWARD_RETRY_MAX: u32 = 3;
+
+/// Delay before the first retry to forward a message to the aggregator, doubled at each retry
+const FORWARD_RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// Timeout of a request forwarding a message to the aggregator
+const FORWARD_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Settings of the forwarding of the messages received by an aggregator relay
+#[derive(Debug, Clone)]
+pub struct AggregatorForwardingSettings {
+    /// Maximum number of messages waiting to be forwarded to the aggregator, the messages
+    /// received when the queue is full are dropped
+    pub message_queue_size: usize,
+
+    /// Time window during which an already received message is considered as a duplicate
+    pub deduplication_window: Duration,
+}
+
+impl Default for AggregatorForwardingSettings {
+    fn default() -> Self {
+        Self {
+            message_queue_size: 1_000,
+            deduplication_window: Duration::from_secs(600),
+        }
+    }
+}
 
 /// A relay for a Mithril aggregator
+///
+/// The messages received from the P2P network are deduplicated, then queued to be forwarded
+/// to the aggregator by a dedicated task, so that a slow aggregator does not block the peer.
 pub struct AggregatorRelay {
-    aggregator_endpoint: String,
     peer: Peer,
+    deduplicator: MessageDeduplicator,
+    message_sender: mpsc::Sender<BroadcastMessage>,
+    metrics_service: Arc<RelayMetricsService>,
     logger: Logger,
 }
 
@@ -20,21 +62,202 @@ impl AggregatorRelay {
     /// Start a relay for a Mithril aggregator
     pub async fn start(
         addr: &Multiaddr,
+        peer_configuration: PeerConfiguration,
+        aggregator_endpoint: &str,
+        forwarding_settings: AggregatorForwardingSettings,
+        metrics_service: Arc<RelayMetricsService>,
+        logger: &Logger,
+    ) -> StdResult<Self> {
+        let (message_sender, message_receiver) =
+            mpsc::channel(forwarding_settings.message_queue_size);
+        let forwarder = AggregatorForwarder::new(
+            aggregator_endpoint,
+            FORWARD_RETRY_BASE_DELAY,
+            metrics_service.clone(),
+            logger,
+        )?;
+        tokio::spawn(forwarder.run(message_receiver));
+
+        Ok(Self {
+            peer: Peer::new(addr)
+                .with_configuration(peer_configuration)
+                .with_logger(logger)
+                .start()
+                .await?,
+            deduplicator: MessageDeduplicator::new(
+                forwarding_settings.deduplication_window,
+                DEDUPLICATOR_CAPACITY,
+            ),
+            message_sender,
+            metrics_service,
+            logger: logger.new_with_component_name::<Self>(),
+        })
+    }
+
+    /// Tick the aggregator relay
+    pub async fn tick(&mut self) -> StdResult<()> {
+        match self.peer.tick_swarm().await? {
+            Some(PeerEvent::Behaviour {
+                event: PeerBehaviourEvent::Gossipsub(gossipsub::Event::Message { message, .. }),
+            }) => self.handle_gossip_message(message),
+            Some(PeerEvent::MessageRejected { .. }) => {
+                self.metrics_service
+                    .get_gossip_message_received_since_startup_counter()
+                    .increment();
+                self.metrics_service
+                    .get_gossip_message_invalid_since_startup_counter()
+                    .increment();
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn handle_gossip_message(&mut self, message: gossipsub::Message) {
+        self.metrics_service
+            .get_gossip_message_received_since_startup_counter()
+            .increment();
+
+        // Gossip messages are signed by their publisher and the signature is checked on reception,
+        // so a message without source has not been authenticated.
+        let Some(source) = message.source else {
+            warn!(self.logger, "Rejected unauthenticated gossip message"; "topic" => %message.topic);
+            self.metrics_service
+                .get_gossip_message_invalid_since_startup_counter()
+                .increment();
+            return;
+        };
+        if self.deduplicator.is_duplicate(&message.data) {
+            debug!(self.logger, "Ignored duplicate gossip message"; "source" => %source, "topic" => %message.topic);
+            self.metrics_service
+                .get_gossip_message_duplicate_since_startup_counter()
+                .increment();
+            return;
+        }
+        let broadcast_message: BroadcastMessage = match serde_json::from_slice(&message.data) {
+            Ok(broadcast_message) => broadcast_message,
+            Err(err) => {
+                warn!(self.logger, "Rejected unparsable gossip message"; "source" => %source, "topic" => %message.topic, "error" => ?err);
+                self.metrics_service
+                    .get_gossip_message_invalid_since_startup_counter()
+                    .increment();
+                return;
+            }
+        };
+
+        match self.message_sender.try_send(broadcast_message) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => {
+                warn!(self.logger, "Dropped gossip message: the queue of messages to forward to the aggregator is full"; "source" => %source);
+                self.metrics_service
+                    .get_gossip_message_dropped_since_startup_counter()
+                    .increment();
+            }
+            Err(TrySendError::Closed(_)) => {
+                error!(self.logger, "Dropped gossip message: the aggregator forwarder is stopped"; "source" => %source);
+                self.metrics_service
+                    .get_gossip_message_dropped_since_startup_counter()
+                    .increment();
+            }
+        }
+    }
+
+    /// Tick the peer of the aggregator relay
+    #[allow(dead_code)]
+    pub(crate) async fn tick_peer(&mut self) -> StdResult<Option<PeerEvent>> {
+        self.peer.tick_swarm().await
+    }
+
+    /// Connect to a remote peer
+    pub fn dial_peer(&mut self, addr: Multiaddr) -> StdResult<()> {
+        self.peer.dial(addr)
+    }
+
+    /// Retrieve address on which the peer is listening
+    pub fn peer_address(&self) -> Option<Multiaddr> {
+        self.peer.addr_peer.to_owned()
+    }
+}
+
+/// Forward the messages queued by an [AggregatorRelay] to the aggregator
+struct AggregatorForwarder {
+    aggregator_endpoint: String,
+    client: reqwest::Client,
+    retry_base_delay: Duration,
+    metrics_service: Arc<RelayMetricsService>,
+    logger: Logger,
+}
+
+impl AggregatorForwarder {
+    fn new(
         aggregator_endpoint: &str,
+        retry_base_delay: Duration,
+        metrics_service: Arc<RelayMetricsService>,
         logger: &Logger,
     ) -> StdResult<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(FORWARD_TIMEOUT)
+            .build()
+            .with_context(|| "Could not build the aggregator forwarder HTTP client")?;
+
         Ok(Self {
             aggregator_endpoint: aggregator_endpoint.to_owned(),
-            peer: Peer::new(addr).with_logger(logger).start().await?,
+            client,
+            retry_base_delay,
+            metrics_service,
             logger: logger.new_with_component_name::<Self>(),
         })
     }
 
+    async fn run(self, mut message_receiver: mpsc::Receiver<BroadcastMessage>) {
+        while let Some(message) = message_receiver.recv().await {
+            match self.forward_with_retry(&message).await {
+                Ok(()) => self
+                    .metrics_service
+                    .get_aggregator_forward_success_since_startup_counter()
+                    .increment(),
+                Err(err) => {
+                    error!(self.logger, "Failed to forward message to aggregator after {FORWARD_RETRY_MAX} attempts"; "error" => ?err);
+                    self.metrics_service
+                        .get_aggregator_forward_failure_since_startup_counter()
+                        .increment();
+                }
+            }
+        }
+    }
+
+    /// Forward a message to the aggregator, retrying with an exponential backoff on failure
+    async fn forward_with_retry(&self, message: &BroadcastMessage) -> StdResult<()> {
+        let mut retry_count = 0;
+        let mut retry_delay = self.retry_base_delay;
+        loop {
+            let result = match message {
+                BroadcastMessage::RegisterSigner(signer_message) => {
+                    self.notify_signer_to_aggregator(signer_message).await
+                }
+                BroadcastMessage::RegisterSignature(signature_message) => {
+                    self.notify_signature_to_aggregator(signature_message).await
+                }
+            };
+            retry_count += 1;
+            match result {
+                Err(e) if retry_count >= FORWARD_RETRY_MAX => return Err(e),
+                Err(_) => {
+                    tokio::time::sleep(retry_delay).await;
+                    retry_delay *= 2;
+                }
+                Ok(()) => return Ok(()),
+            }
+        }
+    }
+
     async fn notify_signature_to_aggregator(
         &self,
         signature_message: &RegisterSignatureMessage,
     ) -> StdResult<()> {
-        let response = reqwest::Client::new()
+        let response = self
+            .client
             .post(format!("{}/register-signatures", self.aggregator_endpoint))
             .json(signature_message)
             //.header(MITHRIL_API_VERSION_HEADER, "0.1.13") // TODO: retrieve current version
@@ -62,7 +285,8 @@ impl AggregatorRelay {
         &self,
         signer_message: &RegisterSignerMessage,
     ) -> StdResult<()> {
-        let response = reqwest::Client::new()
+        let response = self
+            .client
             .post(format!("{}/register-signer", self.aggregator_endpoint))
             .json(signer_message)
             //.header(MITHRIL_API_VERSION_HEADER, "0.1.13") // TODO: retrieve current version
@@ -85,60 +309,91 @@ impl AggregatorRelay {
             }
         }
     }
+}
 
-    /// Tick the aggregator relay
-    pub async fn tick(&mut self) -> StdResult<()> {
-        if let Some(peer_event) = self.peer.tick_swarm().await? {
-            match self.peer.convert_peer_event_to_message(peer_event) {
-                Ok(Some(BroadcastMessage::RegisterSigner(signer_message_received))) => {
-                    let retry_max = 3;
-                    let mut retry_count = 0;
-                    while let Err(e) = self
-                        .notify_signer_to_aggregator(&signer_message_received)
-                        .await
-                    {
-                        retry_count += 1;
-                        if retry_count >= retry_max {
-                            error!(self.logger, "Failed to send signer registration message to aggregator after {retry_count} attempts"; "signer_message" => #?signer_message_received, "error" => ?e);
-                            return Err(e);
-                        }
-                    }
-                }
-                Ok(Some(BroadcastMessage::RegisterSignature(signature_message_received))) => {
-                    let retry_max = 3;
-                    let mut retry_count = 0;
-                    while let Err(e) = self
-                        .notify_signature_to_aggregator(&signature_message_received)
-                        .await
-                    {
-                        retry_count += 1;
-                        if retry_count >= retry_max {
-                            error!(self.logger, "Failed to send signature message to aggregator after {retry_count} attempts"; "signature_message" => #?signature_message_received, "error" => ?e);
-                            return Err(e);
-                        }
-                    }
-                }
-                Ok(None) => {}
-                Err(e) => return Err(e),
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::Instant;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    /// Start a fake aggregator answering each request with the given status line, returning its
+    /// endpoint and the number of received requests
+    async fn start_fake_aggregator(status_line: &'static str) -> (String, Arc<AtomicU32>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let requests_count = Arc::new(AtomicU32::new(0));
+
+        let counter = requests_count.clone();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                counter.fetch_add(1, Ordering::SeqCst);
+                tokio::spawn(async move {
+                    let mut buffer = vec![0u8; 65536];
+                    let _ = stream.read(&mut buffer).await.unwrap();
+                    let response = format!(
+                        "HTTP/1.1 {status_line}\r\ncontent-length: 0\r\nconnection: close\r\n\r\n"
+                    );
+                    stream.write_all(response.as_bytes()).await.unwrap();
+                });
             }
-        }
+        });
 
-        Ok(())
+        (format!("http://{address}"), requests_count)
     }
 
-    /// Tick the peer of the aggregator relay
-    #[allow(dead_code)]
-    pub(crate) async fn tick_peer(&mut self) -> StdResult<Option<PeerEvent>> {
-        self.peer.tick_swarm().await
+    fn build_forwarder(
+        aggregator_endpoint: &str,
+        retry_base_delay: Duration,
+    ) -> AggregatorForwarder {
+        let logger = Logger::root(slog::Discard, slog::o!());
+        AggregatorForwarder::new(
+            aggregator_endpoint,
+            retry_base_delay,
+            Arc::new(RelayMetricsService::new(logger.clone()).unwrap()),
+            &logger,
+        )
+        .unwrap()
     }
 
-    /// Connect to a remote peer
-    pub fn dial_peer(&mut self, addr: Multiaddr) -> StdResult<()> {
-        self.peer.dial(addr)
+    #[tokio::test]
+    async fn forward_a_message_accepted_by_the_aggregator_once() {
+        let (endpoint, requests_count) = start_fake_aggregator("201 Created").await;
+        let forwarder = build_forwarder(&endpoint, Duration::from_millis(50));
+
+        forwarder
+            .forward_with_retry(&BroadcastMessage::RegisterSignature(
+                RegisterSignatureMessage::dummy(),
+            ))
+            .await
+            .unwrap();
+
+        assert_eq!(1, requests_count.load(Ordering::SeqCst));
     }
 
-    /// Retrieve address on which the peer is listening
-    pub fn peer_address(&self) -> Option<Multiaddr> {
-        self.peer.addr_peer.to_owned()
+    #[tokio::test]
+    async fn retry_to_forward_a_message_with_an_exponential_backoff() {
+        let (endpoint, requests_count) = start_fake_aggregator("500 Internal Server Error").await;
+        let retry_base_delay = Duration::from_millis(50);
+        let forwarder = build_forwarder(&endpoint, retry_base_delay);
+
+        let start = Instant::now();
+        forwarder
+            .forward_with_retry(&BroadcastMessage::RegisterSignature(
+                RegisterSignatureMessage::dummy(),
+            ))
+            .await
+            .expect_err("Forwarding should fail when the aggregator rejects the message");
+
+        assert_eq!(FORWARD_RETRY_MAX, requests_count.load(Ordering::SeqCst));
+        // Two retries, after the base delay then after twice the base delay
+        assert!(
+            start.elapsed() >= retry_base_delay * 3,
+            "elapsed: {:?}",
+            start.elapsed()
+        );
     }
 }
@@ -0,0 +1,97 @@
+use sha2::{Digest, Sha256};
+use std::collections::{HashSet, VecDeque};
+use std::time::{Duration, Instant};
+
+type MessageHash = [u8; 32];
+
+/// Detect the messages already received during a time window.
+///
+/// The same message can be received from several peers of the gossip mesh, or be repeated by
+/// the signer relays, so that it would be forwarded several times to the aggregator.
+pub struct MessageDeduplicator {
+    window: Duration,
+    capacity: usize,
+    seen_hashes: HashSet<MessageHash>,
+    seen_order: VecDeque<(MessageHash, Instant)>,
+}
+
+impl MessageDeduplicator {
+    /// Create a new instance remembering at most `capacity` messages during the given `window`
+    pub fn new(window: Duration, capacity: usize) -> Self {
+        Self {
+            window,
+            capacity,
+            seen_hashes: HashSet::new(),
+            seen_order: VecDeque::new(),
+        }
+    }
+
+    /// Return true if the message was already received during the window, otherwise remember it
+    pub fn is_duplicate(&mut self, message: &[u8]) -> bool {
+        self.is_duplicate_at(message, Instant::now())
+    }
+
+    fn is_duplicate_at(&mut self, message: &[u8], now: Instant) -> bool {
+        self.forget_expired(now);
+        let hash: MessageHash = Sha256::digest(message).into();
+        if self.seen_hashes.contains(&hash) {
+            return true;
+        }
+
+        if self.seen_order.len() >= self.capacity {
+            if let Some((oldest_hash, _)) = self.seen_order.pop_front() {
+                self.seen_hashes.remove(&oldest_hash);
+            }
+        }
+        self.seen_hashes.insert(hash);
+        self.seen_order.push_back((hash, now));
+
+        false
+    }
+
+    fn forget_expired(&mut self, now: Instant) {
+        while let Some((hash, seen_at)) = self.seen_order.front() {
+            if now.duration_since(*seen_at) < self.window {
+                break;
+            }
+            self.seen_hashes.remove(hash);
+            self.seen_order.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_a_message_received_twice() {
+        let mut deduplicator = MessageDeduplicator::new(Duration::from_secs(60), 10);
+
+        assert!(!deduplicator.is_duplicate(b"message-1"));
+        assert!(!deduplicator.is_duplicate(b"message-2"));
+        assert!(deduplicator.is_duplicate(b"message-1"));
+    }
+
+    #[test]
+    fn forget_the_messages_received_before_the_window() {
+        let mut deduplicator = MessageDeduplicator::new(Duration::from_secs(60), 10);
+        let now = Instant::now();
+
+        assert!(!deduplicator.is_duplicate_at(b"message-1", now));
+        assert!(deduplicator.is_duplicate_at(b"message-1", now + Duration::from_secs(59)));
+        assert!(!deduplicator.is_duplicate_at(b"message-1", now + Duration::from_secs(61)));
+    }
+
+    #[test]
+    fn forget_the_oldest_messages_when_the_capacity_is_reached() {
+        let mut deduplicator = MessageDeduplicator::new(Duration::from_secs(60), 2);
+
+        assert!(!deduplicator.is_duplicate(b"message-1"));
+        assert!(!deduplicator.is_duplicate(b"message-2"));
+        assert!(!deduplicator.is_duplicate(b"message-3"));
+
+        assert!(deduplicator.is_duplicate(b"message-3"));
+        assert!(!deduplicator.is_duplicate(b"message-1"));
+    }
+}
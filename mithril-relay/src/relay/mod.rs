@@ -1,7 +1,9 @@
 mod aggregator;
+mod deduplicator;
 mod passive;
 mod signer;
 
-pub use aggregator::AggregatorRelay;
+pub use aggregator::{AggregatorForwardingSettings, AggregatorRelay};
+pub use deduplicator::MessageDeduplicator;
 pub use passive::PassiveRelay;
 pub use signer::SignerRelay;
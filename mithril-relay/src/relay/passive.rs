@@ -1,4 +1,4 @@
-use crate::p2p::{BroadcastMessage, Peer, PeerEvent};
+use crate::p2p::{BroadcastMessage, Peer, PeerConfiguration, PeerEvent};
 use libp2p::Multiaddr;
 use mithril_common::logging::LoggerExtensions;
 use mithril_common::StdResult;
@@ -14,12 +14,20 @@ pub struct PassiveRelay {
 
 impl PassiveRelay {
     /// Start a passive relay
-    pub async fn start(addr: &Multiaddr, logger: &Logger) -> StdResult<Self> {
+    pub async fn start(
+        addr: &Multiaddr,
+        peer_configuration: PeerConfiguration,
+        logger: &Logger,
+    ) -> StdResult<Self> {
         let relay_logger = logger.new_with_component_name::<Self>();
         debug!(relay_logger, "Starting...");
 
         Ok(Self {
-            peer: Peer::new(addr).with_logger(logger).start().await?,
+            peer: Peer::new(addr)
+                .with_configuration(peer_configuration)
+                .with_logger(logger)
+                .start()
+                .await?,
             logger: relay_logger,
         })
     }
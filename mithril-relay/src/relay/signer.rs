@@ -1,5 +1,5 @@
 use crate::{
-    p2p::{Peer, PeerEvent},
+    p2p::{BroadcastMessageValidator, Peer, PeerConfiguration, PeerEvent},
     repeater::MessageRepeater,
 };
 use libp2p::Multiaddr;
@@ -10,7 +10,7 @@ use mithril_common::{
     StdResult,
 };
 use slog::{debug, info, Logger};
-use std::{net::SocketAddr, sync::Arc, time::Duration};
+use std::{collections::HashMap, net::SocketAddr, sync::Arc, time::Duration};
 use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
 use warp::Filter;
 
@@ -28,6 +28,7 @@ impl SignerRelay {
     /// Start a relay for a Mithril signer
     pub async fn start(
         address: &Multiaddr,
+        peer_configuration: PeerConfiguration,
         server_port: &u16,
         aggregator_endpoint: &str,
         signer_repeater_delay: &Duration,
@@ -42,13 +43,20 @@ impl SignerRelay {
             signer_repeater_delay.to_owned(),
             logger,
         ));
-        let peer = Peer::new(address).start().await?;
+        let validator = Arc::new(BroadcastMessageValidator::new(
+            peer_configuration.signers_verification_keys.clone(),
+        ));
+        let peer = Peer::new(address)
+            .with_configuration(peer_configuration)
+            .start()
+            .await?;
         let server = Self::start_http_server(
             server_port,
             aggregator_endpoint,
             signer_tx,
             signature_tx,
             signer_repeater.clone(),
+            validator,
             logger,
         )
         .await;
@@ -70,6 +78,7 @@ impl SignerRelay {
         signer_tx: UnboundedSender<RegisterSignerMessage>,
         signature_tx: UnboundedSender<RegisterSignatureMessage>,
         signer_repeater: Arc<MessageRepeater<RegisterSignerMessage>>,
+        validator: Arc<BroadcastMessageValidator>,
         logger: &Logger,
     ) -> TestHttpServer {
         let server_logger = logger.new_with_name("http_server");
@@ -86,7 +95,16 @@ impl SignerRelay {
                     .and(warp::body::json())
                     .and(middlewares::with_logger(&server_logger))
                     .and(middlewares::with_transmitter(signature_tx))
+                    .and(middlewares::with_validator(validator))
                     .and_then(handlers::register_signatures_handler))
+                .or(warp::path!("register-signer" / "challenge")
+                    .and(warp::get())
+                    .and(warp::query::<HashMap<String, String>>())
+                    .and(middlewares::with_logger(&server_logger))
+                    .and(middlewares::with_aggregator_endpoint(
+                        aggregator_endpoint.to_string(),
+                    ))
+                    .and_then(handlers::registration_challenge_handler))
                 .or(warp::path("register-signer")
                     .and(warp::post())
                     .and(warp::body::json())
@@ -178,7 +196,7 @@ mod middlewares {
     use tokio::sync::mpsc::UnboundedSender;
     use warp::Filter;
 
-    use crate::repeater::MessageRepeater;
+    use crate::{p2p::BroadcastMessageValidator, repeater::MessageRepeater};
 
     pub fn with_logger(
         logger: &slog::Logger,
@@ -199,6 +217,12 @@ mod middlewares {
         warp::any().map(move || repeater.clone())
     }
 
+    pub fn with_validator(
+        validator: Arc<BroadcastMessageValidator>,
+    ) -> impl Filter<Extract = (Arc<BroadcastMessageValidator>,), Error = Infallible> + Clone {
+        warp::any().map(move || validator.clone())
+    }
+
     pub fn with_aggregator_endpoint(
         aggregator_endpoint: String,
     ) -> impl Filter<Extract = (String,), Error = Infallible> + Clone {
@@ -209,12 +233,15 @@ mod middlewares {
 mod handlers {
     use mithril_common::messages::{RegisterSignatureMessage, RegisterSignerMessage};
     use reqwest::{Error, Response};
-    use slog::{debug, Logger};
-    use std::{convert::Infallible, sync::Arc};
+    use slog::{debug, warn, Logger};
+    use std::{collections::HashMap, convert::Infallible, sync::Arc};
     use tokio::sync::mpsc::UnboundedSender;
     use warp::http::StatusCode;
 
-    use crate::repeater;
+    use crate::{
+        p2p::{BroadcastMessageValidationError, BroadcastMessageValidator},
+        repeater,
+    };
 
     pub async fn aggregator_features_handler(
         logger: Logger,
@@ -235,6 +262,15 @@ mod handlers {
         repeater: Arc<repeater::MessageRepeater<RegisterSignerMessage>>,
     ) -> Result<impl warp::Reply, Infallible> {
         debug!(logger, "Serve HTTP route /register-signer"; "register_signer_message" => #?register_signer_message);
+        if let Err(err) =
+            BroadcastMessageValidator::validate_signer_registration(&register_signer_message)
+        {
+            warn!(logger, "Rejected invalid signer registration"; "error" => ?err);
+            return Ok(Box::new(warp::reply::with_status(
+                format!("{err:?}"),
+                StatusCode::BAD_REQUEST,
+            )));
+        }
 
         repeater.set_message(register_signer_message.clone()).await;
         match tx.send(register_signer_message) {
@@ -253,8 +289,24 @@ mod handlers {
         register_signature_message: RegisterSignatureMessage,
         logger: Logger,
         tx: UnboundedSender<RegisterSignatureMessage>,
+        validator: Arc<BroadcastMessageValidator>,
     ) -> Result<impl warp::Reply, Infallible> {
         debug!(logger, "Serve HTTP route /register-signatures"; "register_signature_message" => #?register_signature_message);
+        match validator.validate_signature(&register_signature_message) {
+            Ok(()) => {}
+            // The signature is published anyway, the peers will authenticate it once they
+            // know the signers verification keys
+            Err(BroadcastMessageValidationError::Unverifiable(reason)) => {
+                debug!(logger, "Publishing unverifiable signature"; "reason" => reason);
+            }
+            Err(err) => {
+                warn!(logger, "Rejected invalid signature"; "error" => ?err);
+                return Ok(Box::new(warp::reply::with_status(
+                    format!("{err:?}"),
+                    StatusCode::BAD_REQUEST,
+                )));
+            }
+        }
         match tx.send(register_signature_message) {
             Ok(_) => Ok(Box::new(warp::reply::with_status(
                 "".to_string(),
@@ -267,6 +319,20 @@ mod handlers {
         }
     }
 
+    pub async fn registration_challenge_handler(
+        query: HashMap<String, String>,
+        logger: Logger,
+        aggregator_endpoint: String,
+    ) -> Result<impl warp::Reply, Infallible> {
+        debug!(logger, "Serve HTTP route /register-signer/challenge");
+        let response = reqwest::Client::new()
+            .get(format!("{aggregator_endpoint}/register-signer/challenge"))
+            .query(&query)
+            .send()
+            .await;
+        reply_response(logger, response).await
+    }
+
     pub async fn epoch_settings_handler(
         logger: Logger,
         aggregator_endpoint: String,
@@ -1,9 +1,14 @@
 use std::{sync::Arc, time::Duration};
 
 use libp2p::{gossipsub, Multiaddr};
-use mithril_common::messages::{RegisterSignatureMessage, RegisterSignerMessage};
+use mithril_common::{
+    entities::{Epoch, ProtocolMessage, SignedEntityType},
+    test_utils::MithrilFixtureBuilder,
+};
 use mithril_relay::{
-    p2p::{BroadcastMessage, PeerBehaviourEvent, PeerEvent},
+    p2p::{
+        BroadcastMessage, PeerBehaviourEvent, PeerConfiguration, PeerEvent, SignersVerificationKeys,
+    },
     PassiveRelay, SignerRelay,
 };
 use reqwest::StatusCode;
@@ -37,8 +42,21 @@ async fn should_receive_registrations_from_signers_when_subscribed_to_pubsub() {
     let server_port = 0;
     let aggregator_endpoint = "http://0.0.0.0:1234".to_string();
     let signer_repeater_delay = Duration::from_secs(100);
+    let fixture = MithrilFixtureBuilder::default().with_signers(1).build();
+    let signers_verification_keys = Arc::new(SignersVerificationKeys::default());
+    signers_verification_keys
+        .update(
+            &[fixture.signers_with_stake()],
+            &[fixture.protocol_parameters()],
+        )
+        .unwrap();
+    let peer_configuration = PeerConfiguration {
+        signers_verification_keys,
+        ..PeerConfiguration::default()
+    };
     let mut signer_relay = SignerRelay::start(
         &addr,
+        peer_configuration.clone(),
         &server_port,
         &aggregator_endpoint,
         &signer_repeater_delay,
@@ -50,7 +68,7 @@ async fn should_receive_registrations_from_signers_when_subscribed_to_pubsub() {
     let relay_peer_address = signer_relay.peer_address().unwrap();
     info!("Test: relay_address is '{relay_address:?}'");
 
-    let mut p2p_client1 = PassiveRelay::start(&addr, &logger)
+    let mut p2p_client1 = PassiveRelay::start(&addr, peer_configuration.clone(), &logger)
         .await
         .expect("P2P client start failed");
     p2p_client1
@@ -58,7 +76,7 @@ async fn should_receive_registrations_from_signers_when_subscribed_to_pubsub() {
         .dial(relay_peer_address.clone())
         .expect("P2P client dial to the relay should not fail");
 
-    let mut p2p_client2 = PassiveRelay::start(&addr, &logger)
+    let mut p2p_client2 = PassiveRelay::start(&addr, peer_configuration.clone(), &logger)
         .await
         .expect("P2P client start failed");
     p2p_client2
@@ -114,8 +132,7 @@ async fn should_receive_registrations_from_signers_when_subscribed_to_pubsub() {
     });
 
     info!("Test: send a signer registration to the relay via HTTP gateway");
-    let mut signer_message_sent = RegisterSignerMessage::dummy();
-    signer_message_sent.party_id = format!("{}-new", signer_message_sent.party_id);
+    let signer_message_sent = fixture.signers_fixture()[0].register_signer_message(Epoch(1));
     let response = reqwest::Client::new()
         .post(format!("http://{}/register-signer", relay_address))
         .json(&signer_message_sent)
@@ -161,8 +178,9 @@ async fn should_receive_registrations_from_signers_when_subscribed_to_pubsub() {
     }
 
     info!("Test: send a signature to the relay via HTTP gateway");
-    let mut signature_message_sent = RegisterSignatureMessage::dummy();
-    signature_message_sent.party_id = format!("{}-new", signature_message_sent.party_id);
+    let signature_message_sent = fixture.signers_fixture()[0]
+        .register_signature_message(SignedEntityType::dummy(), &ProtocolMessage::default())
+        .expect("The fixture signer should win at least one lottery");
     let response = reqwest::Client::new()
         .post(format!("http://{}/register-signatures", relay_address))
         .json(&signature_message_sent)
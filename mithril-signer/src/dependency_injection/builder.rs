@@ -3,7 +3,7 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::{anyhow, Context};
-use slog::Logger;
+use slog::{info, Logger};
 use tokio::sync::{Mutex, RwLock};
 
 use mithril_common::api_version::APIVersionProvider;
@@ -13,13 +13,18 @@ use mithril_common::chain_observer::{
     CardanoCliRunner, ChainObserver, ChainObserverBuilder, ChainObserverType,
 };
 use mithril_common::chain_reader::PallasChainReader;
-use mithril_common::crypto_helper::{OpCert, ProtocolPartyId, SerDeShelleyFileFormat};
+use mithril_common::crypto_helper::{
+    OpCert, ProtocolInitializer, ProtocolPartyId, RegistrationChallengeSigner,
+    SerDeShelleyFileFormat,
+};
 use mithril_common::digesters::cache::{
     ImmutableFileDigestCacheProvider, JsonImmutableFileDigestCacheProviderBuilder,
 };
 use mithril_common::digesters::{
     CardanoImmutableDigester, ImmutableFileObserver, ImmutableFileSystemObserver,
+    ImmutableFileWatcher,
 };
+use mithril_common::entities::Epoch;
 use mithril_common::era::{EraChecker, EraReader};
 use mithril_common::signable_builder::{
     CardanoImmutableFilesFullSignableBuilder, CardanoStakeDistributionSignableBuilder,
@@ -32,19 +37,22 @@ use mithril_common::{MithrilTickerService, StdResult, TickerService};
 use mithril_persistence::database::repository::CardanoTransactionRepository;
 use mithril_persistence::database::{ApplicationNodeType, SqlMigration};
 use mithril_persistence::sqlite::{ConnectionBuilder, SqliteConnection, SqliteConnectionPool};
-use mithril_persistence::store::adapter::SQLiteAdapter;
+use mithril_persistence::store::adapter::{SQLiteAdapter, StoreAdapter};
 use mithril_persistence::store::StakeStore;
 
 use crate::database::repository::SignedBeaconRepository;
 use crate::dependency_injection::SignerDependencyContainer;
 use crate::services::{
-    AggregatorHTTPClient, CardanoTransactionsImporter,
-    CardanoTransactionsPreloaderActivationSigner, MithrilEpochService, MithrilSingleSigner,
-    SignerCertifierService, SignerSignableSeedBuilder, SignerSignedEntityConfigProvider,
-    SignerUpkeepService, TransactionsImporterByChunk, TransactionsImporterWithPruner,
-    TransactionsImporterWithVacuum,
+    AggregatorClient, AggregatorHTTPClient, CardanoTransactionsImporter,
+    CardanoTransactionsPreloaderActivationSigner, FailoverAggregatorClient, MithrilEpochService,
+    MithrilSingleSigner, RetryAggregatorClient, SignerCertifierService, SignerSignableSeedBuilder,
+    SignerSignedEntityConfigProvider, SignerUpkeepService, TransactionsImporterByChunk,
+    TransactionsImporterWithPruner, TransactionsImporterWithVacuum,
+};
+use crate::store::{
+    EncryptedStoreAdapter, MKTreeStoreSqlite, PassphraseKeyProvider, ProtocolInitializerStore,
+    ProtocolInitializerStorer,
 };
-use crate::store::{MKTreeStoreSqlite, ProtocolInitializerStore};
 use crate::{
     Configuration, MetricsService, HTTP_REQUEST_TIMEOUT_DURATION, SQLITE_FILE,
     SQLITE_FILE_CARDANO_TRANSACTION,
@@ -66,23 +74,41 @@ impl<'a> DependenciesBuilder<'a> {
     pub fn new(config: &'a Configuration, root_logger: Logger) -> Self {
         let chain_observer_builder: fn(&Configuration) -> StdResult<Arc<dyn ChainObserver>> =
             |config: &Configuration| {
-                let chain_observer_type = ChainObserverType::Pallas;
-                let cardano_cli_path = &config.cardano_cli_path;
+                let chain_observer_type = &config.chain_observer_type;
                 let cardano_node_socket_path = &config.cardano_node_socket_path;
                 let cardano_network = &config.get_network().with_context(|| {
                     "Dependencies Builder can not get Cardano network while building the chain observer"
                 })?;
-                let cardano_cli_runner = &CardanoCliRunner::new(
-                    cardano_cli_path.to_owned(),
-                    cardano_node_socket_path.to_owned(),
-                    cardano_network.to_owned(),
-                );
+                let cardano_cli_runner = match chain_observer_type {
+                    ChainObserverType::CardanoCli => Some(CardanoCliRunner::new(
+                        config.cardano_cli_path.clone().ok_or(anyhow!(
+                            "Dependencies Builder can not build the 'cardano-cli' chain observer without 'cardano_cli_path'"
+                        ))?,
+                        cardano_node_socket_path.to_owned(),
+                        cardano_network.to_owned(),
+                    )),
+                    _ => None,
+                };
 
                 let chain_observer_builder = ChainObserverBuilder::new(
-                    &chain_observer_type,
+                    chain_observer_type,
                     cardano_node_socket_path,
                     cardano_network,
-                    Some(cardano_cli_runner),
+                    cardano_cli_runner.as_ref(),
+                )
+                .with_ogmios_endpoint(config.ogmios_endpoint.clone())
+                .with_blockfrost_project_id(
+                    config
+                        .blockfrost_project_id
+                        .as_ref()
+                        .map(|id| id.expose_secret().to_string()),
+                )
+                .with_blockfrost_endpoint(config.blockfrost_endpoint.clone())
+                .with_db_sync_connection_string(
+                    config
+                        .db_sync_connection_string
+                        .as_ref()
+                        .map(|connection| connection.expose_secret().to_string()),
                 );
 
                 chain_observer_builder
@@ -132,6 +158,20 @@ impl<'a> DependenciesBuilder<'a> {
         self
     }
 
+    /// Build the chain observer used to read the Cardano chain.
+    pub fn build_chain_observer(&self) -> StdResult<Arc<dyn ChainObserver>> {
+        (self.chain_observer_builder)(self.config)
+    }
+
+    /// Build the watcher notifying the state machine of the new immutable files.
+    pub fn build_immutable_file_watcher(&self) -> StdResult<Arc<ImmutableFileWatcher>> {
+        Ok(Arc::new(ImmutableFileWatcher::new(
+            (self.immutable_file_observer_builder)(self.config)?,
+            Duration::from_millis(self.config.immutable_file_watcher_poll_interval_in_ms),
+            self.root_logger(),
+        )))
+    }
+
     /// Compute protocol party id
     fn compute_protocol_party_id(&self) -> StdResult<ProtocolPartyId> {
         match &self.config.operational_certificate_path {
@@ -169,6 +209,19 @@ impl<'a> DependenciesBuilder<'a> {
         Ok(Some(Arc::new(cache_provider)))
     }
 
+    fn create_data_stores_directory(&self) -> StdResult<()> {
+        if !self.config.data_stores_directory.exists() {
+            fs::create_dir_all(self.config.data_stores_directory.clone()).with_context(|| {
+                format!(
+                    "Could not create data stores directory: `{}`",
+                    self.config.data_stores_directory.display()
+                )
+            })?;
+        }
+
+        Ok(())
+    }
+
     /// Build a SQLite connection.
     pub async fn build_sqlite_connection(
         &self,
@@ -186,17 +239,63 @@ impl<'a> DependenciesBuilder<'a> {
         Ok(connection)
     }
 
-    /// Build dependencies for the Production environment.
-    pub async fn build(&self) -> StdResult<SignerDependencyContainer> {
-        if !self.config.data_stores_directory.exists() {
-            fs::create_dir_all(self.config.data_stores_directory.clone()).with_context(|| {
-                format!(
-                    "Could not create data stores directory: `{}`",
-                    self.config.data_stores_directory.display()
-                )
-            })?;
+    /// Build the adapter of the protocol initializer store, encrypting its records if a store
+    /// encryption passphrase is configured.
+    ///
+    /// The records stored in plaintext before the encryption was enabled are encrypted.
+    async fn build_protocol_initializer_store_adapter(
+        &self,
+        sqlite_connection: Arc<SqliteConnection>,
+    ) -> StdResult<Box<dyn StoreAdapter<Key = Epoch, Record = ProtocolInitializer>>> {
+        match &self.config.store_encryption_passphrase {
+            Some(passphrase) => {
+                let mut adapter = EncryptedStoreAdapter::new(
+                    Box::new(SQLiteAdapter::new(
+                        "protocol_initializer",
+                        sqlite_connection,
+                    )?),
+                    Box::new(PassphraseKeyProvider::new(passphrase.expose_secret())),
+                );
+                let migrated_records = adapter
+                    .migrate_plaintext_records()
+                    .await
+                    .with_context(|| "Could not encrypt the plaintext protocol initializers")?;
+                if migrated_records > 0 {
+                    info!(
+                        self.root_logger(), "Encrypted the plaintext protocol initializers";
+                        "migrated_records" => migrated_records
+                    );
+                }
+
+                Ok(Box::new(adapter))
+            }
+            None => Ok(Box::new(SQLiteAdapter::new(
+                "protocol_initializer",
+                sqlite_connection,
+            )?)),
         }
+    }
 
+    /// Build the protocol initializer store, without the other dependencies of the signer.
+    pub async fn build_protocol_initializer_store(
+        &self,
+    ) -> StdResult<Arc<dyn ProtocolInitializerStorer>> {
+        self.create_data_stores_directory()?;
+        let sqlite_connection = Arc::new(
+            self.build_sqlite_connection(SQLITE_FILE, crate::database::migration::get_migrations())
+                .await?,
+        );
+
+        Ok(Arc::new(ProtocolInitializerStore::new(
+            self.build_protocol_initializer_store_adapter(sqlite_connection)
+                .await?,
+            self.config.store_retention_limit,
+        )))
+    }
+
+    /// Build dependencies for the Production environment.
+    pub async fn build(&self) -> StdResult<SignerDependencyContainer> {
+        self.create_data_stores_directory()?;
         let network = self.config.get_network()?;
         let sqlite_connection = Arc::new(
             self.build_sqlite_connection(SQLITE_FILE, crate::database::migration::get_migrations())
@@ -214,24 +313,18 @@ impl<'a> DependenciesBuilder<'a> {
 
         let signed_entity_type_lock = Arc::new(SignedEntityTypeLock::default());
         let protocol_initializer_store = Arc::new(ProtocolInitializerStore::new(
-            Box::new(SQLiteAdapter::new(
-                "protocol_initializer",
-                sqlite_connection.clone(),
-            )?),
+            self.build_protocol_initializer_store_adapter(sqlite_connection.clone())
+                .await?,
             self.config.store_retention_limit,
         ));
-        let digester = Arc::new(CardanoImmutableDigester::new(
-            self.build_digester_cache_provider().await?,
-            self.root_logger(),
-        ));
         let stake_store = Arc::new(StakeStore::new(
             Box::new(SQLiteAdapter::new("stake", sqlite_connection.clone())?),
             self.config.store_retention_limit,
         ));
-        let chain_observer = {
-            let builder = self.chain_observer_builder;
-            builder(self.config)?
-        };
+        let chain_observer = self.build_chain_observer()?;
+        let stake_distribution_source = self
+            .config
+            .build_stake_distribution_source(chain_observer.clone())?;
         let ticker_service = {
             let builder = self.immutable_file_observer_builder;
             Arc::new(MithrilTickerService::new(
@@ -251,13 +344,60 @@ impl<'a> DependenciesBuilder<'a> {
             era_epoch_token.get_current_supported_era()?,
             era_epoch_token.get_current_epoch(),
         ));
+        let digester = Arc::new(
+            CardanoImmutableDigester::new(
+                self.build_digester_cache_provider().await?,
+                self.root_logger(),
+            )
+            .with_era_checker(era_checker.clone()),
+        );
 
         let api_version_provider = Arc::new(APIVersionProvider::new(era_checker.clone()));
-        let aggregator_client = Arc::new(AggregatorHTTPClient::new(
-            self.config.aggregator_endpoint.clone(),
-            self.config.relay_endpoint.clone(),
-            api_version_provider.clone(),
-            Some(Duration::from_millis(HTTP_REQUEST_TIMEOUT_DURATION)),
+        let failover_aggregator_client = Arc::new(FailoverAggregatorClient::new(
+            self.config
+                .list_aggregator_endpoints()
+                .into_iter()
+                .map(|aggregator_endpoint| {
+                    let mut client = AggregatorHTTPClient::new(
+                        aggregator_endpoint.clone(),
+                        self.config.relay_endpoint.clone(),
+                        api_version_provider.clone(),
+                        Some(Duration::from_millis(HTTP_REQUEST_TIMEOUT_DURATION)),
+                        self.root_logger(),
+                    );
+                    match (
+                        &self.config.aggregator_client_certificate_path,
+                        &self.config.aggregator_client_private_key_path,
+                    ) {
+                        (Some(certificate_path), Some(private_key_path)) => {
+                            client = client
+                                .with_client_certificate(certificate_path, private_key_path)?;
+                        }
+                        (None, None) => {}
+                        _ => {
+                            return Err(anyhow!(
+                                "Both 'aggregator_client_certificate_path' and 'aggregator_client_private_key_path' must be set to present a client certificate to the aggregator"
+                            ));
+                        }
+                    }
+                    if let Some(ca_certificate_path) = &self.config.aggregator_ca_certificate_path
+                    {
+                        client = client.with_ca_certificate(ca_certificate_path)?;
+                    }
+                    if let Some(kes_secret_key_path) = &self.config.kes_secret_key_path {
+                        client = client.with_registration_challenge_signer(
+                            RegistrationChallengeSigner::new(kes_secret_key_path),
+                        );
+                    }
+                    let client: Arc<dyn AggregatorClient> = Arc::new(client);
+                    Ok((aggregator_endpoint, client))
+                })
+                .collect::<StdResult<Vec<_>>>()?,
+            self.root_logger(),
+        ));
+        let aggregator_client = Arc::new(RetryAggregatorClient::new(
+            failover_aggregator_client,
+            self.config.get_aggregator_client_retry_policy(),
             self.root_logger(),
         ));
 
@@ -286,6 +426,7 @@ impl<'a> DependenciesBuilder<'a> {
         let transactions_importer = Arc::new(CardanoTransactionsImporter::new(
             block_scanner,
             transaction_store.clone(),
+            self.config.preload_security_parameter,
             self.root_logger(),
         ));
         // Wrap the transaction importer with decorator to prune the transactions after import
@@ -388,6 +529,7 @@ impl<'a> DependenciesBuilder<'a> {
             ticker_service,
             certificate_handler: aggregator_client,
             chain_observer,
+            stake_distribution_source,
             digester,
             single_signer,
             stake_store,
@@ -451,4 +593,97 @@ mod tests {
             .expect("service builder build should not fail");
         assert!(stores_dir.exists());
     }
+
+    #[test]
+    fn build_pallas_chain_observer_without_cardano_cli_path() {
+        let config = Configuration {
+            chain_observer_type: ChainObserverType::Pallas,
+            cardano_cli_path: None,
+            ..Configuration::new_sample("party-123456")
+        };
+
+        DependenciesBuilder::new(&config, TestLogger::stdout())
+            .build_chain_observer()
+            .expect("pallas chain observer build should not fail without cardano cli path");
+    }
+
+    #[test]
+    fn build_cardano_cli_chain_observer_fails_without_cardano_cli_path() {
+        let config = Configuration {
+            chain_observer_type: ChainObserverType::CardanoCli,
+            cardano_cli_path: None,
+            ..Configuration::new_sample("party-123456")
+        };
+
+        assert!(
+            DependenciesBuilder::new(&config, TestLogger::stdout())
+                .build_chain_observer()
+                .is_err(),
+            "cardano-cli chain observer build should fail without cardano cli path"
+        );
+    }
+
+    #[test]
+    fn build_ogmios_chain_observer_fails_without_ogmios_endpoint() {
+        let config = Configuration {
+            chain_observer_type: ChainObserverType::Ogmios,
+            ogmios_endpoint: None,
+            ..Configuration::new_sample("party-123456")
+        };
+
+        assert!(
+            DependenciesBuilder::new(&config, TestLogger::stdout())
+                .build_chain_observer()
+                .is_err(),
+            "ogmios chain observer build should fail without ogmios endpoint"
+        );
+    }
+
+    #[test]
+    fn build_blockfrost_chain_observer_fails_without_blockfrost_project_id() {
+        let config = Configuration {
+            chain_observer_type: ChainObserverType::Blockfrost,
+            network: "preprod".to_string(),
+            blockfrost_project_id: None,
+            ..Configuration::new_sample("party-123456")
+        };
+
+        assert!(
+            DependenciesBuilder::new(&config, TestLogger::stdout())
+                .build_chain_observer()
+                .is_err(),
+            "blockfrost chain observer build should fail without blockfrost project id"
+        );
+    }
+
+    #[test]
+    fn build_blockfrost_chain_observer_with_the_default_endpoint_of_the_network() {
+        let config = Configuration {
+            chain_observer_type: ChainObserverType::Blockfrost,
+            network: "preprod".to_string(),
+            blockfrost_project_id: Some("preprod-project-id".into()),
+            blockfrost_endpoint: None,
+            ..Configuration::new_sample("party-123456")
+        };
+
+        DependenciesBuilder::new(&config, TestLogger::stdout())
+            .build_chain_observer()
+            .expect("blockfrost chain observer build should use the public endpoint of preprod");
+    }
+
+    #[test]
+    fn build_db_sync_chain_observer_fails_without_db_sync_connection_string() {
+        let config = Configuration {
+            chain_observer_type: ChainObserverType::DbSync,
+            db_sync_connection_string: None,
+            ..Configuration::new_sample("party-123456")
+        };
+
+        assert!(
+            DependenciesBuilder::new(&config, TestLogger::stdout())
+                .build_chain_observer()
+                .is_err(),
+            "db-sync chain observer build should fail without db-sync connection string"
+        );
+    }
 }
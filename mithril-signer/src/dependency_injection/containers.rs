@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use mithril_common::api_version::APIVersionProvider;
 use mithril_common::cardano_transactions_preloader::CardanoTransactionsPreloader;
-use mithril_common::chain_observer::ChainObserver;
+use mithril_common::chain_observer::{ChainObserver, StakeDistributionSource};
 use mithril_common::digesters::ImmutableDigester;
 use mithril_common::era::{EraChecker, EraReader};
 use mithril_common::signable_builder::SignableBuilderService;
@@ -42,6 +42,9 @@ pub struct SignerDependencyContainer {
     /// Chain Observer service
     pub chain_observer: ChainObserverService,
 
+    /// Stake distribution source
+    pub stake_distribution_source: Arc<dyn StakeDistributionSource>,
+
     /// Digester service
     pub digester: DigesterService,
 
@@ -0,0 +1,299 @@
+//! Backup and restore of the signer key material, so that a signer can be migrated to a new
+//! host without losing its registrations.
+
+use anyhow::{anyhow, Context};
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::Path;
+
+use mithril_common::crypto_helper::ProtocolInitializer;
+use mithril_common::entities::{Epoch, PartyId};
+use mithril_common::StdResult;
+
+use crate::store::{EncryptedRecord, PassphraseKeyProvider, ProtocolInitializerStorer};
+
+/// Version of the format of the keys bundles.
+const KEYS_BUNDLE_VERSION: u32 = 1;
+
+/// Key material and registration history of a signer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignerKeysBundle {
+    /// Header identifying the signer of the bundle
+    pub header: SignerKeysBundleHeader,
+
+    /// Protocol initializers of the signer, associated with the epoch at which they were
+    /// registered
+    pub protocol_initializers: Vec<(Epoch, ProtocolInitializer)>,
+}
+
+/// Header of a [SignerKeysBundle], readable without decrypting the bundle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignerKeysBundleHeader {
+    /// Version of the bundle format
+    pub version: u32,
+
+    /// Party id of the signer, if known
+    pub party_id: Option<PartyId>,
+
+    /// Cardano network of the signer
+    pub network: String,
+}
+
+/// A [SignerKeysBundle] encrypted with a passphrase.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncryptedSignerKeysBundle {
+    /// Header of the bundle, authenticated with the encrypted content
+    pub header: SignerKeysBundleHeader,
+
+    /// Encrypted protocol initializers
+    pub content: EncryptedRecord,
+}
+
+impl SignerKeysBundle {
+    /// Export all the protocol initializers of the given store.
+    pub async fn export_from_store(
+        store: &dyn ProtocolInitializerStorer,
+        party_id: Option<PartyId>,
+        network: &str,
+    ) -> StdResult<Self> {
+        let protocol_initializers = store
+            .get_last_protocol_initializer(usize::MAX)
+            .await
+            .with_context(|| "Could not read the protocol initializers to export")?;
+
+        Ok(Self {
+            header: SignerKeysBundleHeader {
+                version: KEYS_BUNDLE_VERSION,
+                party_id,
+                network: network.to_string(),
+            },
+            protocol_initializers,
+        })
+    }
+
+    /// Import the protocol initializers of the bundle in the given store, from the oldest to the
+    /// latest, returns the number of imported protocol initializers.
+    ///
+    /// The protocol initializers already stored for an epoch are kept.
+    pub async fn import_into_store(
+        &self,
+        store: &dyn ProtocolInitializerStorer,
+    ) -> StdResult<usize> {
+        let mut imported = 0;
+        for (epoch, protocol_initializer) in self.protocol_initializers.iter().rev() {
+            if store.get_protocol_initializer(*epoch).await?.is_none() {
+                store
+                    .save_protocol_initializer(*epoch, protocol_initializer.clone())
+                    .await
+                    .with_context(|| {
+                        format!("Could not import the protocol initializer of epoch {epoch}")
+                    })?;
+                imported += 1;
+            }
+        }
+
+        Ok(imported)
+    }
+
+    /// Encrypt the bundle with the given passphrase.
+    pub fn encrypt(&self, passphrase: &str) -> StdResult<EncryptedSignerKeysBundle> {
+        let content = EncryptedRecord::encrypt(
+            &PassphraseKeyProvider::new(passphrase),
+            &serde_json::to_vec(&self.protocol_initializers)?,
+            &serde_json::to_vec(&self.header)?,
+        )?;
+
+        Ok(EncryptedSignerKeysBundle {
+            header: self.header.clone(),
+            content,
+        })
+    }
+}
+
+impl EncryptedSignerKeysBundle {
+    /// Decrypt the bundle with the given passphrase.
+    pub fn decrypt(&self, passphrase: &str) -> StdResult<SignerKeysBundle> {
+        if self.header.version != KEYS_BUNDLE_VERSION {
+            return Err(anyhow!(
+                "Unsupported keys bundle version: {}",
+                self.header.version
+            ));
+        }
+        let protocol_initializers = self
+            .content
+            .decrypt(
+                &PassphraseKeyProvider::new(passphrase),
+                &serde_json::to_vec(&self.header)?,
+            )
+            .with_context(|| "Could not decrypt the keys bundle")?;
+
+        Ok(SignerKeysBundle {
+            header: self.header.clone(),
+            protocol_initializers: serde_json::from_slice(&protocol_initializers)?,
+        })
+    }
+
+    /// Check that the bundle was exported by the signer with the given party id and network.
+    pub fn check_signer(&self, party_id: Option<&PartyId>, network: &str) -> StdResult<()> {
+        if self.header.network != network {
+            return Err(anyhow!(
+                "The keys bundle was exported on network '{}' but the signer runs on network '{network}'",
+                self.header.network
+            ));
+        }
+        if let (Some(bundle_party_id), Some(party_id)) = (&self.header.party_id, party_id) {
+            if bundle_party_id != party_id {
+                return Err(anyhow!(
+                    "The keys bundle was exported by party id '{bundle_party_id}' but the signer party id is '{party_id}'"
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write the bundle to the given file, only readable by its owner.
+    pub fn write_to_file(&self, path: &Path) -> StdResult<()> {
+        let content = serde_json::to_vec_pretty(self)?;
+        OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(path)
+            .and_then(|mut file| file.write_all(&content))
+            .with_context(|| format!("Could not write the keys bundle to '{}'", path.display()))
+    }
+
+    /// Read a bundle from the given file.
+    pub fn read_from_file(path: &Path) -> StdResult<Self> {
+        let content = std::fs::read(path)
+            .with_context(|| format!("Could not read the keys bundle from '{}'", path.display()))?;
+
+        serde_json::from_slice(&content)
+            .with_context(|| format!("Could not parse the keys bundle '{}'", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mithril_common::test_utils::{fake_data, TempDir};
+    use mithril_persistence::store::adapter::MemoryAdapter;
+
+    use crate::store::ProtocolInitializerStore;
+
+    use super::*;
+
+    fn protocol_initializers(epochs: &[u64]) -> Vec<(Epoch, ProtocolInitializer)> {
+        let protocol_initializer = fake_data::protocol_initializer("1", 100);
+
+        epochs
+            .iter()
+            .map(|epoch| (Epoch(*epoch), protocol_initializer.clone()))
+            .collect()
+    }
+
+    fn store(records: Vec<(Epoch, ProtocolInitializer)>) -> ProtocolInitializerStore {
+        ProtocolInitializerStore::new(Box::new(MemoryAdapter::new(Some(records)).unwrap()), None)
+    }
+
+    #[tokio::test]
+    async fn export_encrypt_then_import_in_an_empty_store() {
+        let export_store = store(protocol_initializers(&[1, 2, 3]));
+        let bundle =
+            SignerKeysBundle::export_from_store(&export_store, Some("pool1".to_string()), "devnet")
+                .await
+                .unwrap();
+        let bundle_path =
+            TempDir::create("signer_keys_bundle", "export_then_import").join("bundle.json");
+        bundle
+            .encrypt("passphrase")
+            .unwrap()
+            .write_to_file(&bundle_path)
+            .unwrap();
+
+        let import_store = store(vec![]);
+        let imported = EncryptedSignerKeysBundle::read_from_file(&bundle_path)
+            .unwrap()
+            .decrypt("passphrase")
+            .unwrap()
+            .import_into_store(&import_store)
+            .await
+            .unwrap();
+
+        assert_eq!(3, imported);
+        let imported_epochs: Vec<Epoch> = import_store
+            .get_last_protocol_initializer(10)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|(epoch, _)| epoch)
+            .collect();
+        assert_eq!(vec![Epoch(3), Epoch(2), Epoch(1)], imported_epochs);
+    }
+
+    #[tokio::test]
+    async fn import_keeps_the_protocol_initializers_already_stored() {
+        let bundle = SignerKeysBundle::export_from_store(
+            &store(protocol_initializers(&[1, 2])),
+            None,
+            "devnet",
+        )
+        .await
+        .unwrap();
+        let import_store = store(protocol_initializers(&[2]));
+
+        let imported = bundle.import_into_store(&import_store).await.unwrap();
+
+        assert_eq!(1, imported);
+    }
+
+    #[tokio::test]
+    async fn decrypt_fails_with_a_wrong_passphrase_or_a_tampered_header() {
+        let bundle = SignerKeysBundle::export_from_store(
+            &store(protocol_initializers(&[1])),
+            Some("pool1".to_string()),
+            "devnet",
+        )
+        .await
+        .unwrap()
+        .encrypt("passphrase")
+        .unwrap();
+
+        bundle
+            .decrypt("wrong passphrase")
+            .expect_err("Decrypting with a wrong passphrase should fail");
+
+        let mut tampered_bundle = bundle.clone();
+        tampered_bundle.header.party_id = Some("pool2".to_string());
+        tampered_bundle
+            .decrypt("passphrase")
+            .expect_err("Decrypting a bundle with a tampered header should fail");
+    }
+
+    #[test]
+    fn check_signer_rejects_bundles_of_another_signer() {
+        let bundle = EncryptedSignerKeysBundle {
+            header: SignerKeysBundleHeader {
+                version: KEYS_BUNDLE_VERSION,
+                party_id: Some("pool1".to_string()),
+                network: "devnet".to_string(),
+            },
+            content: EncryptedRecord::encrypt(&PassphraseKeyProvider::new("p"), b"[]", b"")
+                .unwrap(),
+        };
+
+        bundle
+            .check_signer(Some(&"pool1".to_string()), "devnet")
+            .unwrap();
+        bundle.check_signer(None, "devnet").unwrap();
+        bundle
+            .check_signer(Some(&"pool2".to_string()), "devnet")
+            .expect_err("Another party id should be rejected");
+        bundle
+            .check_signer(Some(&"pool1".to_string()), "mainnet")
+            .expect_err("Another network should be rejected");
+    }
+}
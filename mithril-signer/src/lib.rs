@@ -10,13 +10,15 @@ mod configuration;
 pub mod database;
 pub mod dependency_injection;
 pub mod entities;
+pub mod keys_bundle;
 mod message_adapters;
 pub mod metrics;
+pub mod preflight;
 mod runtime;
 pub mod services;
 pub mod store;
 
-pub use configuration::{Configuration, DefaultConfiguration};
+pub use configuration::{Configuration, DefaultConfiguration, SignerOperationMode};
 pub use entities::SignerEpochSettings;
 pub use message_adapters::{FromEpochSettingsAdapter, ToRegisterSignerMessageAdapter};
 pub use metrics::*;
@@ -2,22 +2,29 @@ use anyhow::{anyhow, Context};
 use clap::{CommandFactory, Parser, Subcommand};
 use config::{Map, Value};
 
-use slog::{crit, debug, info, o, Drain, Level, Logger};
+use slog::{crit, debug, info, o, warn, Drain, Level, Logger};
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::{
     signal::unix::{signal, SignalKind},
-    sync::oneshot,
+    sync::{oneshot, watch},
     task::JoinSet,
 };
 
-use mithril_common::StdResult;
+use mithril_common::logging::{
+    log_level_changed, CorrelationIdDrain, LogLevelHandle, ReloadableLevelFilter,
+};
+use mithril_common::{configuration_files::ConfigurationFiles, StdResult};
 use mithril_doc::{Documenter, DocumenterDefault, GenerateDocCommands, StructDoc};
-use mithril_metric::MetricsServer;
+use mithril_metric::{MetricsPusher, MetricsServer};
 use mithril_signer::dependency_injection::DependenciesBuilder;
+use mithril_signer::keys_bundle::{EncryptedSignerKeysBundle, SignerKeysBundle};
+use mithril_signer::preflight::PreflightChecker;
+use mithril_signer::services::SystemdNotifier;
 use mithril_signer::{
-    Configuration, DefaultConfiguration, SignerRunner, SignerState, StateMachine,
+    Configuration, DefaultConfiguration, SignerRunner, SignerState, SignerStatusReporter,
+    StateMachine,
 };
 
 /// CLI args
@@ -53,6 +60,10 @@ pub struct Args {
     )]
     configuration_dir: PathBuf,
 
+    /// Secrets file, read instead of the `secrets.json` file of the configuration directory
+    #[clap(long, env = "SECRETS_FILE")]
+    secrets_file: Option<PathBuf>,
+
     /// Disable immutables digests cache.
     #[clap(long)]
     disable_digests_cache: bool,
@@ -75,6 +86,10 @@ pub struct Args {
     #[clap(long, env = "METRICS_SERVER_PORT", default_value_t = 9090)]
     metrics_server_port: u16,
 
+    /// Disable metrics HTTP server, even if enabled in the configuration.
+    #[clap(long)]
+    disable_metrics_server: bool,
+
     /// If set no error is returned in case of unparsable block and an error log is written instead.
     ///
     /// Will be ignored on (pre)production networks.
@@ -102,28 +117,102 @@ impl Args {
     }
 }
 
-fn build_logger(min_level: Level) -> Logger {
+fn build_logger(log_level_handle: &LogLevelHandle) -> Logger {
     let drain = slog_bunyan::with_name("mithril-signer", std::io::stdout())
         .set_pretty(false)
         .build()
         .fuse();
-    let drain = slog::LevelFilter::new(drain, min_level).fuse();
     let drain = slog_async::Async::new(drain).build().fuse();
+    let drain = ReloadableLevelFilter::new(drain, log_level_handle.clone()).fuse();
 
-    Logger::root(Arc::new(drain), o!())
+    Logger::root(Arc::new(CorrelationIdDrain::new(drain)), o!())
 }
 
 #[derive(Subcommand, Debug, Clone)]
 enum SignerCommands {
     #[clap(alias("doc"), hide(true))]
     GenerateDoc(GenerateDocCommands),
+
+    /// Check the configuration and the dependencies of the signer, then print a report
+    Preflight,
+
+    /// Backup and restore the signer key material
+    #[command(subcommand)]
+    Keys(KeysCommands),
+}
+
+#[derive(Subcommand, Debug, Clone)]
+enum KeysCommands {
+    /// Export the signer key material and registration history to an encrypted bundle
+    Export {
+        /// Path of the bundle file to create
+        #[clap(long)]
+        output: PathBuf,
+
+        /// Passphrase used to encrypt the bundle
+        #[clap(long, env = "KEYS_BUNDLE_PASSPHRASE")]
+        passphrase: String,
+    },
+
+    /// Import the signer key material and registration history from an encrypted bundle
+    Import {
+        /// Path of the bundle file to import
+        #[clap(long)]
+        input: PathBuf,
+
+        /// Passphrase used to encrypt the bundle
+        #[clap(long, env = "KEYS_BUNDLE_PASSPHRASE")]
+        passphrase: String,
+    },
+}
+
+impl KeysCommands {
+    async fn execute(&self, config: &Configuration, root_logger: Logger) -> StdResult<()> {
+        let protocol_initializer_store = DependenciesBuilder::new(config, root_logger)
+            .build_protocol_initializer_store()
+            .await
+            .with_context(|| "protocol initializer store initialization error")?;
+
+        match self {
+            Self::Export { output, passphrase } => {
+                let bundle = SignerKeysBundle::export_from_store(
+                    protocol_initializer_store.as_ref(),
+                    config.party_id.clone(),
+                    &config.network,
+                )
+                .await?;
+                bundle.encrypt(passphrase)?.write_to_file(output)?;
+                println!(
+                    "Exported {} protocol initializers to '{}'",
+                    bundle.protocol_initializers.len(),
+                    output.display()
+                );
+            }
+            Self::Import { input, passphrase } => {
+                let encrypted_bundle = EncryptedSignerKeysBundle::read_from_file(input)?;
+                encrypted_bundle.check_signer(config.party_id.as_ref(), &config.network)?;
+                let bundle = encrypted_bundle.decrypt(passphrase)?;
+                let imported = bundle
+                    .import_into_store(protocol_initializer_store.as_ref())
+                    .await?;
+                println!(
+                    "Imported {imported} of the {} protocol initializers of '{}'",
+                    bundle.protocol_initializers.len(),
+                    input.display()
+                );
+            }
+        }
+
+        Ok(())
+    }
 }
 
 #[tokio::main]
 async fn main() -> StdResult<()> {
     // Load args
     let args = Args::parse();
-    let root_logger = build_logger(args.log_level());
+    let log_level_handle = LogLevelHandle::new(args.log_level());
+    let root_logger = build_logger(&log_level_handle);
 
     if let Some(SignerCommands::GenerateDoc(cmd)) = &args.command {
         let config_infos = vec![
@@ -160,25 +249,52 @@ async fn main() -> StdResult<()> {
         })?
         .add_source(DefaultConfiguration::default())
         .add_source(
-            config::File::with_name(&format!(
-                "{}/{}.json",
-                args.configuration_dir.display(),
-                args.run_mode
-            ))
-            .required(false),
+            ConfigurationFiles::new(&args.configuration_dir, &args.run_mode)
+                .with_secrets_file(args.secrets_file.as_ref()),
         )
         .add_source(config::Environment::default())
+        .set_override_option(
+            "enable_metrics_server",
+            args.disable_metrics_server.then_some(false),
+        )
+        .with_context(|| "configuration error: could not override `enable_metrics_server`")?
         .build()
         .with_context(|| "configuration build error")?
         .try_deserialize()
         .with_context(|| "configuration deserialize error")?;
 
-    let services = DependenciesBuilder::new(&config, root_logger.clone())
+    if let Some(SignerCommands::Preflight) = &args.command {
+        let chain_observer = DependenciesBuilder::new(&config, root_logger.clone())
+            .build_chain_observer()
+            .with_context(|| "chain observer initialization error")?;
+        let report = PreflightChecker::new(&config, chain_observer).run().await;
+        println!("{report}");
+
+        if !report.is_success() {
+            return Err(anyhow!("Preflight checks failed"));
+        }
+        return Ok(());
+    }
+
+    if let Some(SignerCommands::Keys(cmd)) = &args.command {
+        return cmd.execute(&config, root_logger.clone()).await;
+    }
+
+    let metrics_push_interval = config
+        .get_metrics_push_interval()
+        .with_context(|| "configuration error")?;
+    let dependencies_builder = DependenciesBuilder::new(&config, root_logger.clone());
+    let services = dependencies_builder
         .build()
         .await
         .with_context(|| "services initialization error")?;
+    let immutable_file_watcher = dependencies_builder
+        .build_immutable_file_watcher()
+        .with_context(|| "immutable file watcher initialization error")?;
 
     let metrics_service = services.metrics_service.clone();
+    let aggregator_client = services.certificate_handler.clone();
+    let chain_observer = services.chain_observer.clone();
     let cardano_transaction_preloader = services.cardano_transactions_preloader.clone();
 
     debug!(root_logger, "Started"; "run_mode" => &args.run_mode, "config" => format!("{config:?}"));
@@ -193,16 +309,42 @@ async fn main() -> StdResult<()> {
         Duration::from_millis(config.run_interval),
         metrics_service.clone(),
         root_logger.clone(),
-    );
+    )
+    .with_immutable_file_watcher(immutable_file_watcher)
+    .with_state_file(config.data_stores_directory.join("signer_state.json"));
+    let status_reporter = Arc::new(SignerStatusReporter::new(
+        state_machine.subscribe_to_state(),
+        metrics_service.clone(),
+        aggregator_client,
+        chain_observer,
+    ));
+
+    let systemd_notifier = SystemdNotifier::from_env(root_logger.clone()).map(Arc::new);
+    let state_receiver = state_machine.subscribe_to_state();
+
+    let shutdown_grace_period = Duration::from_secs(config.shutdown_grace_period_in_seconds);
+    let (state_machine_stop_tx, state_machine_stop_rx) = watch::channel(false);
+    let mut state_machine_handle =
+        tokio::spawn(async move { state_machine.run_until_stopped(state_machine_stop_rx).await });
 
     let mut join_set = JoinSet::new();
-    join_set.spawn(async move {
-        state_machine
-            .run()
-            .await
-            .map_err(|e| anyhow!(e))
-            .map(|_| None)
-    });
+
+    if let Some(notifier) = systemd_notifier.clone() {
+        if notifier
+            .watchdog_timeout()
+            .is_some_and(|timeout| timeout <= Duration::from_millis(config.run_interval))
+        {
+            warn!(
+                root_logger,
+                "Systemd watchdog timeout is shorter than the run interval, the signer will be restarted between cycles";
+                "watchdog_timeout" => ?notifier.watchdog_timeout(), "run_interval" => config.run_interval
+            );
+        }
+        join_set.spawn(async move {
+            notifier.run_forever(state_receiver).await;
+            Ok(None)
+        });
+    }
 
     let preload_logger = root_logger.clone();
     join_set.spawn(async move {
@@ -222,18 +364,47 @@ async fn main() -> StdResult<()> {
 
     let (metrics_server_shutdown_tx, metrics_server_shutdown_rx) = oneshot::channel();
     if config.enable_metrics_server {
-        let metrics_logger = root_logger.clone();
+        let metrics_server = MetricsServer::new(
+            &config.metrics_server_ip,
+            config.metrics_server_port,
+            metrics_service.clone(),
+            root_logger.clone(),
+        )
+        .with_basic_auth(config.get_metrics_server_basic_auth()?)
+        .with_tls(config.get_metrics_server_tls_config()?)
+        .with_additional_routes(status_reporter.router());
         join_set.spawn(async move {
-            MetricsServer::new(
-                &config.metrics_server_ip,
-                config.metrics_server_port,
-                metrics_service,
-                metrics_logger.clone(),
-            )
-            .start(metrics_server_shutdown_rx)
-            .await
-            .map_err(|e| anyhow!(e))
-            .map(|_| None)
+            metrics_server
+                .start(metrics_server_shutdown_rx)
+                .await
+                .map_err(|e| anyhow!(e))
+                .map(|_| None)
+        });
+    }
+
+    let mut metrics_pushers = vec![];
+    if let Some(pushgateway_url) = &config.metrics_pushgateway_url {
+        metrics_pushers.push(MetricsPusher::new(
+            pushgateway_url,
+            "mithril-signer",
+            &config.get_metrics_push_instance(),
+            metrics_service.clone(),
+            root_logger.clone(),
+        )?);
+    }
+    if let Some(remote_write_url) = &config.metrics_remote_write_url {
+        metrics_pushers.push(MetricsPusher::new_remote_write(
+            remote_write_url,
+            "mithril-signer",
+            &config.get_metrics_push_instance(),
+            metrics_service.clone(),
+            root_logger.clone(),
+        )?);
+    }
+    for metrics_pusher in metrics_pushers {
+        join_set.spawn(async move {
+            metrics_pusher.run_forever(metrics_push_interval).await;
+            Ok(None)
         });
     }
 
@@ -262,15 +433,60 @@ async fn main() -> StdResult<()> {
             .map(|_| Some("Received SIGQUIT".to_string()))
     });
 
-    let shutdown_reason = match join_set.join_next().await {
-        Some(Err(e)) => {
-            crit!(root_logger, "A critical error occurred"; "error" => ?e);
-            None
+    // SIGHUP switches the logs to a more verbose level, and back, without restarting
+    let sighup_logger = root_logger.clone();
+    join_set.spawn(async move {
+        let mut sighup = signal(SignalKind::hangup()).expect("Failed to create SIGHUP signal");
+        while sighup.recv().await.is_some() {
+            let log_level = log_level_handle.toggle_verbose();
+            log_level_changed(&sighup_logger, log_level, "Received SIGHUP");
         }
-        Some(Ok(res)) => res?,
-        None => None,
+
+        Err(anyhow!("Failed to receive SIGHUP"))
+    });
+
+    let shutdown_reason = tokio::select! {
+        res = &mut state_machine_handle => match res {
+            Err(e) => {
+                crit!(root_logger, "A critical error occurred"; "error" => ?e);
+                None
+            }
+            Ok(res) => res.map_err(|e| anyhow!(e)).map(|_| None)?,
+        },
+        res = join_set.join_next() => match res {
+            Some(Err(e)) => {
+                crit!(root_logger, "A critical error occurred"; "error" => ?e);
+                None
+            }
+            Some(Ok(res)) => res?,
+            None => None,
+        },
     };
 
+    if let Some(notifier) = &systemd_notifier {
+        notifier.notify_stopping()?;
+    }
+
+    if !state_machine_handle.is_finished() {
+        info!(
+            root_logger, "Waiting for the running cycle of the state machine to finish";
+            "grace_period" => ?shutdown_grace_period
+        );
+        state_machine_stop_tx.send_replace(true);
+        match tokio::time::timeout(shutdown_grace_period, &mut state_machine_handle).await {
+            Ok(Ok(Err(e))) => e.write_to_log(&root_logger),
+            Ok(Err(e)) => crit!(root_logger, "A critical error occurred"; "error" => ?e),
+            Ok(Ok(Ok(()))) => {}
+            Err(_) => {
+                warn!(
+                    root_logger,
+                    "The running cycle of the state machine did not finish in time, aborting it"
+                );
+                state_machine_handle.abort();
+            }
+        }
+    }
+
     metrics_server_shutdown_tx
         .send(())
         .map_err(|e| anyhow!("Metrics server shutdown signal could not be sent: {e:?}"))?;
@@ -40,4 +40,37 @@ mod tests {
 
         assert_eq!(epoch, epoch_settings.epoch);
     }
+
+    mod fuzzing {
+        use proptest::prelude::*;
+
+        use super::*;
+
+        proptest! {
+            #[test]
+            fn malformed_json_payloads_are_handled_without_panicking(
+                position in any::<prop::sample::Index>(),
+                garbage in ".{0,16}",
+            ) {
+                let json = serde_json::to_string(&EpochSettingsMessage::dummy()).unwrap();
+                let position = position.index(json.len());
+                prop_assume!(json.is_char_boundary(position));
+                let payload = format!("{}{garbage}", &json[..position]);
+
+                if let Ok(message) = serde_json::from_str::<EpochSettingsMessage>(&payload) {
+                    let _ = FromEpochSettingsAdapter::try_adapt(message);
+                }
+            }
+
+            #[test]
+            fn arbitrary_encoded_signers_keys_are_rejected_without_panicking(
+                verification_key in prop_oneof!["[0-9a-f]{0,512}", ".{0,64}"],
+            ) {
+                let mut message = EpochSettingsMessage::dummy();
+                message.next_signers[0].verification_key = verification_key;
+
+                prop_assert!(FromEpochSettingsAdapter::try_adapt(message).is_err());
+            }
+        }
+    }
 }
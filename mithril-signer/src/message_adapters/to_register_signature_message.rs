@@ -1,7 +1,7 @@
 use anyhow::Context;
 use mithril_common::entities::{ProtocolMessage, SignedEntityType, SingleSignatures};
 use mithril_common::messages::{RegisterSignatureMessage, TryToMessageAdapter};
-use mithril_common::protocol::ToMessage;
+use mithril_common::protocol::{SignatureScheme, ToMessage};
 use mithril_common::StdResult;
 
 pub struct ToRegisterSignatureMessageAdapter;
@@ -27,6 +27,7 @@ impl
             })?,
             won_indexes: single_signature.won_indexes,
             signed_message: Some(protocol_message.to_message()),
+            signature_scheme: SignatureScheme::StmV1,
         };
 
         Ok(message)
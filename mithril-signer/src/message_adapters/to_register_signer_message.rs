@@ -38,6 +38,8 @@ impl TryToMessageAdapter<(Epoch, Signer), RegisterSignerMessage>
                 None => None,
             },
             kes_period: signer.kes_period,
+            registration_challenge: None,
+            registration_challenge_signature: None,
         };
 
         Ok(message)
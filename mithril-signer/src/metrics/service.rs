@@ -1,6 +1,8 @@
 use mithril_metric::{build_metrics_service, MetricsServiceExporter};
 
-use mithril_metric::metric::{MetricCollector, MetricCounter, MetricGauge};
+use mithril_metric::metric::{
+    MetricCollector, MetricCounter, MetricGauge, MetricHistogramWithLabels,
+};
 
 build_metrics_service!(
     MetricsService,
@@ -35,6 +37,44 @@ build_metrics_service!(
     runtime_cycle_total_since_startup_counter:MetricCounter(
         "mithril_signer_runtime_cycle_total_since_startup",
         "Number of runtime cycles since startup on a Mithril signer node"
+    ),
+    current_epoch_gauge:MetricGauge(
+        "mithril_signer_current_epoch",
+        "Current epoch of the Cardano chain as seen by a Mithril signer node"
+    ),
+    active_stake_gauge:MetricGauge(
+        "mithril_signer_active_stake",
+        "Stake of the pool used by a Mithril signer node for its latest registration"
+    ),
+    current_kes_period_gauge:MetricGauge(
+        "mithril_signer_current_kes_period",
+        "Current KES period of the Cardano chain as seen by a Mithril signer node"
+    ),
+    operational_certificate_start_kes_period_gauge:MetricGauge(
+        "mithril_signer_operational_certificate_start_kes_period",
+        "Start KES period of the operational certificate of a Mithril signer node"
+    ),
+    remaining_kes_evolutions_gauge:MetricGauge(
+        "mithril_signer_remaining_kes_evolutions",
+        "Number of KES evolutions left before the operational certificate of a Mithril signer node expires"
+    ),
+    kes_expiring_gauge:MetricGauge(
+        "mithril_signer_kes_expiring",
+        "1 if the operational certificate of a Mithril signer node expires within the configured KES evolutions threshold, 0 otherwise"
+    ),
+    epochs_until_signing_window_gauge:MetricGauge(
+        "mithril_signer_epochs_until_signing_window",
+        "Number of epochs before a Mithril signer node is able to sign, 0 if it can sign in the current epoch"
+    ),
+    message_computation_duration_seconds:MetricHistogramWithLabels(
+        "mithril_signer_message_computation_duration_seconds",
+        "Duration in seconds of the computation of the message to sign by a Mithril signer node",
+        ["signed_entity_type"]
+    ),
+    single_signature_computation_duration_seconds:MetricHistogramWithLabels(
+        "mithril_signer_single_signature_computation_duration_seconds",
+        "Duration in seconds of the computation and publication of a single signature by a Mithril signer node",
+        ["signed_entity_type"]
     )
 
 );
@@ -0,0 +1,460 @@
+//! Checks run by the `preflight` command to detect misconfigurations of a signer before
+//! starting it.
+
+use anyhow::{anyhow, Context};
+use chrono::{DateTime, Utc};
+use reqwest::header;
+use std::fmt::{Display, Formatter};
+use std::os::unix::fs::FileTypeExt;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use mithril_common::chain_observer::ChainObserver;
+use mithril_common::crypto_helper::{OpCert, SerDeShelleyFileFormat, Sum6KesBytes};
+use mithril_common::era::EraReader;
+use mithril_common::StdResult;
+
+use crate::{Configuration, HTTP_REQUEST_TIMEOUT_DURATION};
+
+/// Maximum accepted difference between the clock of the signer and the clock of an aggregator.
+pub const MAX_CLOCK_SKEW: Duration = Duration::from_secs(30);
+
+/// Outcome of a single preflight check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreflightCheck {
+    /// Name of the check
+    pub name: String,
+    /// Whether the check passed
+    pub passed: bool,
+    /// Details on the outcome of the check
+    pub message: String,
+}
+
+impl PreflightCheck {
+    fn from_result<N: Into<String>>(name: N, result: StdResult<String>) -> Self {
+        let (passed, message) = match result {
+            Ok(message) => (true, message),
+            Err(error) => (false, format!("{error:#}")),
+        };
+
+        Self {
+            name: name.into(),
+            passed,
+            message,
+        }
+    }
+}
+
+/// Report of all the preflight checks.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PreflightReport {
+    /// Outcome of each check, in the order they were run
+    pub checks: Vec<PreflightCheck>,
+}
+
+impl PreflightReport {
+    /// Return true if all the checks passed.
+    pub fn is_success(&self) -> bool {
+        self.checks.iter().all(|check| check.passed)
+    }
+}
+
+impl Display for PreflightReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for check in &self.checks {
+            let status = if check.passed { "PASS" } else { "FAIL" };
+            writeln!(f, "[{status}] {}: {}", check.name, check.message)?;
+        }
+        let failed = self.checks.iter().filter(|check| !check.passed).count();
+        write!(
+            f,
+            "{} check(s) passed, {failed} check(s) failed",
+            self.checks.len() - failed
+        )
+    }
+}
+
+/// Check the configuration of a signer and the availability of its dependencies: Cardano
+/// node, aggregators and clock.
+pub struct PreflightChecker<'a> {
+    config: &'a Configuration,
+    chain_observer: Arc<dyn ChainObserver>,
+}
+
+impl<'a> PreflightChecker<'a> {
+    /// Create a new instance
+    pub fn new(config: &'a Configuration, chain_observer: Arc<dyn ChainObserver>) -> Self {
+        Self {
+            config,
+            chain_observer,
+        }
+    }
+
+    /// Run all the checks, even if some of them fail.
+    pub async fn run(&self) -> PreflightReport {
+        let mut checks = vec![
+            PreflightCheck::from_result("Configuration", self.check_configuration()),
+            PreflightCheck::from_result(
+                "Operational certificate and KES key",
+                self.check_operational_certificate(),
+            ),
+            PreflightCheck::from_result("Cardano node socket", self.check_cardano_node_socket()),
+            PreflightCheck::from_result(
+                "Cardano node connection",
+                self.check_cardano_node_connection().await,
+            ),
+            PreflightCheck::from_result("Era compatibility", self.check_era_compatibility().await),
+        ];
+
+        for aggregator_endpoint in self.config.list_aggregator_endpoints() {
+            let (reachability, clock_skew) = self.check_aggregator(&aggregator_endpoint).await;
+            checks.push(PreflightCheck::from_result(
+                format!("Aggregator reachability '{aggregator_endpoint}'"),
+                reachability,
+            ));
+            checks.push(PreflightCheck::from_result(
+                format!("Clock skew with '{aggregator_endpoint}'"),
+                clock_skew,
+            ));
+        }
+
+        PreflightReport { checks }
+    }
+
+    /// Check that the configuration values that are only validated at runtime are valid.
+    pub fn check_configuration(&self) -> StdResult<String> {
+        let network = self.config.get_network()?;
+        self.config.get_metrics_server_basic_auth()?;
+        self.config.get_metrics_server_tls_config()?;
+        self.config.get_metrics_push_interval()?;
+
+        Ok(format!(
+            "Network '{network}', {} aggregator endpoint(s)",
+            self.config.list_aggregator_endpoints().len()
+        ))
+    }
+
+    /// Check that the operational certificate and the KES secret key can be read, and that they
+    /// match the configured party id.
+    pub fn check_operational_certificate(&self) -> StdResult<String> {
+        match (
+            &self.config.operational_certificate_path,
+            &self.config.kes_secret_key_path,
+        ) {
+            (None, None) => Ok(
+                "Not configured, the signer can only register on networks that do not require certification"
+                    .to_string(),
+            ),
+            (Some(operational_certificate_path), Some(kes_secret_key_path)) => {
+                let operational_certificate = OpCert::from_file(operational_certificate_path)
+                    .map_err(|e| anyhow!(e))
+                    .with_context(|| {
+                        format!(
+                            "Could not read operational certificate '{}'",
+                            operational_certificate_path.display()
+                        )
+                    })?;
+                Sum6KesBytes::from_file(kes_secret_key_path)
+                    .map_err(|e| anyhow!(e))
+                    .with_context(|| {
+                        format!(
+                            "Could not read KES secret key '{}'",
+                            kes_secret_key_path.display()
+                        )
+                    })?;
+                let party_id = operational_certificate.compute_protocol_party_id()?;
+                if let Some(configured_party_id) = &self.config.party_id {
+                    if configured_party_id != &party_id {
+                        return Err(anyhow!(
+                            "Operational certificate is issued for '{party_id}' but the configured party id is '{configured_party_id}'"
+                        ));
+                    }
+                }
+
+                Ok(format!(
+                    "Party id '{party_id}', start KES period {}",
+                    operational_certificate.start_kes_period
+                ))
+            }
+            _ => Err(anyhow!(
+                "Both 'operational_certificate_path' and 'kes_secret_key_path' must be set"
+            )),
+        }
+    }
+
+    /// Check that the Cardano node socket exists.
+    pub fn check_cardano_node_socket(&self) -> StdResult<String> {
+        let socket_path: &Path = &self.config.cardano_node_socket_path;
+        let metadata = std::fs::metadata(socket_path).with_context(|| {
+            format!(
+                "Cardano node socket '{}' does not exist",
+                socket_path.display()
+            )
+        })?;
+        if !metadata.file_type().is_socket() {
+            return Err(anyhow!("'{}' is not a socket", socket_path.display()));
+        }
+
+        Ok(format!("'{}'", socket_path.display()))
+    }
+
+    /// Check that the Cardano node answers, and that the operational certificate is valid for
+    /// the current KES period.
+    pub async fn check_cardano_node_connection(&self) -> StdResult<String> {
+        let epoch = self
+            .chain_observer
+            .get_current_epoch()
+            .await?
+            .ok_or(anyhow!("The Cardano node did not return the current epoch"))?;
+
+        let Some(operational_certificate_path) = &self.config.operational_certificate_path else {
+            return Ok(format!("Current epoch {epoch}"));
+        };
+        let operational_certificate = OpCert::from_file(operational_certificate_path)
+            .map_err(|e| anyhow!(e))
+            .with_context(|| "Could not read operational certificate")?;
+        let chain_point = self
+            .chain_observer
+            .get_current_chain_point()
+            .await?
+            .ok_or(anyhow!(
+                "The Cardano node did not return the current chain point"
+            ))?;
+        let kes_period_info = self
+            .config
+            .get_kes_genesis_parameters()?
+            .compute_kes_period_info(&operational_certificate, chain_point.slot_number)?;
+        if kes_period_info.is_expired() {
+            return Err(anyhow!(
+                "The operational certificate has expired at KES period {}, a new one must be issued",
+                kes_period_info.current_kes_period
+            ));
+        }
+
+        Ok(format!(
+            "Current epoch {epoch}, current KES period {}, {} KES evolutions left",
+            kes_period_info.current_kes_period, kes_period_info.remaining_kes_evolutions
+        ))
+    }
+
+    /// Check that this version of the signer supports the current era, and the next one if it
+    /// is already announced.
+    pub async fn check_era_compatibility(&self) -> StdResult<String> {
+        let epoch = self
+            .chain_observer
+            .get_current_epoch()
+            .await?
+            .ok_or(anyhow!("The Cardano node did not return the current epoch"))?;
+        let era_reader = EraReader::new(
+            self.config
+                .build_era_reader_adapter(self.chain_observer.clone())?,
+        );
+        let era_epoch_token = era_reader.read_era_epoch_token(epoch).await?;
+        let current_era = era_epoch_token.get_current_supported_era()?;
+
+        match era_epoch_token.get_next_era_marker() {
+            Some(next_era_marker) => {
+                era_epoch_token.get_next_supported_era().with_context(|| {
+                    format!(
+                        "The next era '{}' is not supported, the signer must be upgraded",
+                        next_era_marker.name
+                    )
+                })?;
+                Ok(format!(
+                    "Current era '{current_era}', next era '{}'",
+                    next_era_marker.name
+                ))
+            }
+            None => Ok(format!("Current era '{current_era}'")),
+        }
+    }
+
+    /// Check that the aggregator answers, and that the clock skew with its clock is acceptable.
+    pub async fn check_aggregator(
+        &self,
+        aggregator_endpoint: &str,
+    ) -> (StdResult<String>, StdResult<String>) {
+        let response = match reqwest::Client::new()
+            .get(aggregator_endpoint)
+            .timeout(Duration::from_millis(HTTP_REQUEST_TIMEOUT_DURATION))
+            .send()
+            .await
+            .with_context(|| format!("Aggregator '{aggregator_endpoint}' is unreachable"))
+        {
+            Ok(response) => response,
+            Err(error) => {
+                return (
+                    Err(error),
+                    Err(anyhow!("Skipped, the aggregator is unreachable")),
+                )
+            }
+        };
+
+        let reachability = if response.status().is_success() {
+            Ok(format!("Status code '{}'", response.status()))
+        } else {
+            Err(anyhow!(
+                "Aggregator responded with status code '{}'",
+                response.status()
+            ))
+        };
+        let clock_skew = response
+            .headers()
+            .get(header::DATE)
+            .ok_or(anyhow!("The aggregator response has no 'Date' header"))
+            .and_then(|date| Ok(date.to_str()?))
+            .and_then(|date| Self::check_clock_skew(date, Utc::now()));
+
+        (reachability, clock_skew)
+    }
+
+    /// Check that the difference between the given HTTP date and the local time is lower than
+    /// [MAX_CLOCK_SKEW].
+    pub fn check_clock_skew(http_date: &str, now: DateTime<Utc>) -> StdResult<String> {
+        let remote_time = DateTime::parse_from_rfc2822(http_date)
+            .with_context(|| format!("Could not parse the 'Date' header '{http_date}'"))?;
+        let skew = (now - remote_time.with_timezone(&Utc)).abs();
+        let skew = skew.to_std()?;
+
+        if skew > MAX_CLOCK_SKEW {
+            return Err(anyhow!(
+                "Clock skew of {}s is greater than the maximum of {}s, the system clock must be synchronized",
+                skew.as_secs(),
+                MAX_CLOCK_SKEW.as_secs()
+            ));
+        }
+
+        Ok(format!("{}s", skew.as_secs()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeDelta;
+    use httpmock::prelude::*;
+    use std::os::unix::net::UnixListener;
+
+    use mithril_common::chain_observer::FakeObserver;
+    use mithril_common::entities::TimePoint;
+    use mithril_common::test_utils::{MithrilFixtureBuilder, TempDir};
+
+    use super::*;
+
+    fn checker(config: &Configuration) -> PreflightChecker<'_> {
+        PreflightChecker::new(
+            config,
+            Arc::new(FakeObserver::new(Some(TimePoint::dummy()))),
+        )
+    }
+
+    #[test]
+    fn report_is_successful_only_if_all_checks_passed() {
+        let mut report = PreflightReport {
+            checks: vec![PreflightCheck::from_result("first", Ok("ok".to_string()))],
+        };
+        assert!(report.is_success());
+
+        report
+            .checks
+            .push(PreflightCheck::from_result("second", Err(anyhow!("ko"))));
+        assert!(!report.is_success());
+        assert_eq!(
+            "[PASS] first: ok\n[FAIL] second: ko\n1 check(s) passed, 1 check(s) failed",
+            report.to_string()
+        );
+    }
+
+    #[test]
+    fn operational_certificate_check_fails_if_only_one_file_is_set() {
+        let mut config = Configuration::new_sample("party");
+        config.operational_certificate_path = None;
+        config.kes_secret_key_path = Some("kes.sk".into());
+
+        checker(&config)
+            .check_operational_certificate()
+            .expect_err("Check should fail when the operational certificate is missing");
+    }
+
+    #[test]
+    fn operational_certificate_check_verifies_the_party_id() {
+        let temp_dir = TempDir::create(
+            "signer_preflight",
+            "operational_certificate_check_verifies_the_party_id",
+        );
+        let fixture = MithrilFixtureBuilder::default().with_signers(1).build();
+        let signer = &fixture.signers_fixture()[0];
+        let operational_certificate_path = temp_dir.join("opcert.cert");
+        signer
+            .operational_certificate()
+            .unwrap()
+            .to_file(&operational_certificate_path)
+            .unwrap();
+        let mut config = Configuration::new_sample(signer.party_id());
+        config.operational_certificate_path = Some(operational_certificate_path);
+        config.kes_secret_key_path = signer.kes_secret_key_path().map(Path::to_path_buf);
+
+        checker(&config).check_operational_certificate().unwrap();
+
+        config.party_id = Some("another-party".to_string());
+        checker(&config)
+            .check_operational_certificate()
+            .expect_err("Check should fail when the party id does not match");
+    }
+
+    #[test]
+    fn cardano_node_socket_check_fails_if_the_path_is_not_a_socket() {
+        let temp_dir = TempDir::create_with_short_path(
+            "signer_preflight",
+            "cardano_node_socket_check_fails_if_the_path_is_not_a_socket",
+        );
+        let mut config = Configuration::new_sample("party");
+        config.cardano_node_socket_path = temp_dir.join("node.socket");
+
+        checker(&config)
+            .check_cardano_node_socket()
+            .expect_err("Check should fail when the socket does not exist");
+
+        let _listener = UnixListener::bind(&config.cardano_node_socket_path).unwrap();
+        checker(&config).check_cardano_node_socket().unwrap();
+    }
+
+    #[test]
+    fn clock_skew_check_fails_above_the_maximum_skew() {
+        let now = Utc::now();
+        let http_date = |time: DateTime<Utc>| time.to_rfc2822();
+
+        PreflightChecker::check_clock_skew(&http_date(now - TimeDelta::seconds(10)), now).unwrap();
+        PreflightChecker::check_clock_skew(&http_date(now + TimeDelta::seconds(10)), now).unwrap();
+        PreflightChecker::check_clock_skew(&http_date(now - TimeDelta::seconds(60)), now)
+            .expect_err("Check should fail when the clock is late");
+        PreflightChecker::check_clock_skew("not a date", now)
+            .expect_err("Check should fail when the date can not be parsed");
+    }
+
+    #[tokio::test]
+    async fn aggregator_check_uses_the_date_header_of_the_response() {
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(GET).path("/aggregator");
+            then.status(200)
+                .header("date", Utc::now().to_rfc2822())
+                .body("{}");
+        });
+        let config = Configuration::new_sample("party");
+
+        let (reachability, clock_skew) = checker(&config)
+            .check_aggregator(&server.url("/aggregator"))
+            .await;
+
+        reachability.unwrap();
+        clock_skew.unwrap();
+    }
+
+    #[tokio::test]
+    async fn era_compatibility_check_passes_with_the_bootstrap_era_reader() {
+        let config = Configuration::new_sample("party");
+
+        checker(&config).check_era_compatibility().await.unwrap();
+    }
+}
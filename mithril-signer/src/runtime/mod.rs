@@ -1,7 +1,9 @@
 mod error;
 mod runner;
 mod state_machine;
+mod status;
 
 pub use error::*;
 pub use runner::*;
 pub use state_machine::*;
+pub use status::*;
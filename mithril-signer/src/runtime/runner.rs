@@ -1,14 +1,21 @@
-use anyhow::Context;
+use anyhow::{anyhow, Context};
 use async_trait::async_trait;
-use slog::{debug, warn, Logger};
+use slog::{crit, debug, info, warn, Logger};
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Instant, SystemTime};
 use thiserror::Error;
 use tokio::sync::RwLockReadGuard;
 
-use mithril_common::crypto_helper::{KESPeriod, OpCert, ProtocolOpCert, SerDeShelleyFileFormat};
+use mithril_common::crypto_helper::{
+    KesGenesisParameters, KesPeriodInfo, OpCert, ProtocolOpCert, SerDeShelleyFileFormat,
+};
 use mithril_common::entities::{
-    Epoch, PartyId, ProtocolMessage, SignedEntityType, Signer, TimePoint,
+    Epoch, PartyId, ProtocolMessage, SignedEntityType, SignedEntityTypeDiscriminants, Signer,
+    TimePoint,
 };
 use mithril_common::logging::LoggerExtensions;
+use mithril_common::protocol::SignatureScheme;
 use mithril_common::StdResult;
 use mithril_persistence::store::StakeStorer;
 
@@ -59,6 +66,9 @@ pub trait Runner: Send + Sync {
 
     /// Perform the upkeep tasks.
     async fn upkeep(&self, current_epoch: Epoch) -> StdResult<()>;
+
+    /// Check the remaining KES evolutions of the operational certificate, if any.
+    async fn check_kes_period(&self) -> StdResult<()>;
 }
 
 /// This type represents the errors thrown from the Runner.
@@ -76,12 +86,21 @@ pub enum RunnerError {
     /// Parse file error
     #[error("File parse failed: {0}.")]
     FileParse(String),
+    /// The signature scheme certifying the current era is not implemented by this signer.
+    #[error("Signature scheme '{0}' is not supported by this version of the signer.")]
+    UnsupportedSignatureScheme(SignatureScheme),
 }
 
+/// Signature schemes implemented by the single signer, which only signs with the certifying
+/// scheme of the current era.
+const SUPPORTED_SIGNATURE_SCHEMES: [SignatureScheme; 1] = [SignatureScheme::StmV1];
+
 /// Controller methods for the Signer's state machine.
 pub struct SignerRunner {
     config: Configuration,
     services: SignerDependencyContainer,
+    kes_genesis_parameters: OnceLock<KesGenesisParameters>,
+    operational_certificate: Mutex<Option<(SystemTime, OpCert)>>,
     logger: Logger,
 }
 
@@ -91,6 +110,8 @@ impl SignerRunner {
         Self {
             services,
             config,
+            kes_genesis_parameters: OnceLock::new(),
+            operational_certificate: Mutex::new(None),
             logger: logger.new_with_component_name::<Self>(),
         }
     }
@@ -98,6 +119,102 @@ impl SignerRunner {
     async fn epoch_service_read(&self) -> RwLockReadGuard<'_, dyn EpochService> {
         self.services.epoch_service.read().await
     }
+
+    /// Return the KES parameters of the Cardano network, the Shelley genesis file is only read
+    /// the first time.
+    fn get_kes_genesis_parameters(&self) -> StdResult<KesGenesisParameters> {
+        if let Some(kes_genesis_parameters) = self.kes_genesis_parameters.get() {
+            return Ok(*kes_genesis_parameters);
+        }
+        let kes_genesis_parameters = self.config.get_kes_genesis_parameters()?;
+
+        Ok(*self
+            .kes_genesis_parameters
+            .get_or_init(|| kes_genesis_parameters))
+    }
+
+    /// Return the operational certificate of the given file, the file is only read again when it
+    /// is modified (ie: when the operational certificate is renewed).
+    fn get_operational_certificate(
+        &self,
+        operational_certificate_path: &Path,
+    ) -> StdResult<OpCert> {
+        let modified_at = std::fs::metadata(operational_certificate_path)
+            .and_then(|metadata| metadata.modified())
+            .with_context(|| {
+                format!(
+                    "Could not read the modification date of the operational certificate '{}'",
+                    operational_certificate_path.display()
+                )
+            })?;
+        let mut operational_certificate = self.operational_certificate.lock().unwrap();
+        match operational_certificate.as_ref() {
+            Some((read_at, opcert)) if *read_at == modified_at => Ok(opcert.clone()),
+            _ => {
+                let opcert = OpCert::from_file(operational_certificate_path)
+                    .map_err(|_| RunnerError::FileParse("operational_certificate_path".to_string()))
+                    .with_context(|| "Can not decode OpCert from file")?;
+                *operational_certificate = Some((modified_at, opcert.clone()));
+
+                Ok(opcert)
+            }
+        }
+    }
+
+    /// Compute the KES period info of the operational certificate at the current chain point,
+    /// record it in the metrics and warn if the certificate is about to expire.
+    async fn compute_kes_period_info(
+        &self,
+        operational_certificate: &OpCert,
+    ) -> StdResult<KesPeriodInfo> {
+        let chain_point = self
+            .services
+            .chain_observer
+            .get_current_chain_point()
+            .await?
+            .ok_or_else(|| RunnerError::NoValueError("current_chain_point".to_string()))?;
+        let kes_period_info = self
+            .get_kes_genesis_parameters()?
+            .compute_kes_period_info(operational_certificate, chain_point.slot_number)?;
+        self.record_kes_period_info(&kes_period_info);
+
+        Ok(kes_period_info)
+    }
+
+    fn record_kes_period_info(&self, kes_period_info: &KesPeriodInfo) {
+        let metrics_service = &self.services.metrics_service;
+        metrics_service
+            .get_current_kes_period_gauge()
+            .record(kes_period_info.current_kes_period);
+        metrics_service
+            .get_operational_certificate_start_kes_period_gauge()
+            .record(kes_period_info.start_kes_period);
+        metrics_service
+            .get_remaining_kes_evolutions_gauge()
+            .record(kes_period_info.remaining_kes_evolutions as f64);
+
+        let is_expiring = kes_period_info.remaining_kes_evolutions
+            < self.config.kes_expiry_warning_threshold_in_evolutions;
+        metrics_service
+            .get_kes_expiring_gauge()
+            .record(u8::from(is_expiring));
+
+        if kes_period_info.is_expired() {
+            crit!(
+                self.logger, "The operational certificate has expired, the signer can't sign anymore: a new one must be issued";
+                "current_kes_period" => kes_period_info.current_kes_period,
+                "start_kes_period" => kes_period_info.start_kes_period,
+            );
+        } else if is_expiring {
+            warn!(
+                self.logger, "The operational certificate expires soon: a new one must be issued";
+                "remaining_kes_evolutions" => kes_period_info.remaining_kes_evolutions,
+                "threshold" => self.config.kes_expiry_warning_threshold_in_evolutions,
+                "current_kes_period" => kes_period_info.current_kes_period,
+                "start_kes_period" => kes_period_info.start_kes_period,
+            );
+        }
+    }
 }
 
 #[cfg_attr(test, mockall::automock)]
@@ -135,6 +252,14 @@ impl Runner for SignerRunner {
     async fn register_signer_to_aggregator(&self) -> StdResult<()> {
         debug!(self.logger, ">> register_signer_to_aggregator");
 
+        if !self.config.operation_mode.is_registration_enabled() {
+            info!(
+                self.logger, "Registration disabled, relying on the protocol initializer stored by the registration signer";
+                "operation_mode" => ?self.config.operation_mode
+            );
+            return Ok(());
+        }
+
         let (epoch, protocol_parameters) = {
             let epoch_service = self.services.epoch_service.read().await;
             let epoch = epoch_service.epoch_of_current_data()?;
@@ -157,6 +282,10 @@ impl Runner for SignerRunner {
         let stake = stake_distribution
             .get(&self.services.single_signer.get_party_id())
             .ok_or_else(RunnerError::NoStakeForSelf)?;
+        self.services
+            .metrics_service
+            .get_active_stake_gauge()
+            .record(*stake as f64);
         let (operational_certificate, protocol_operational_certificate) = match &self
             .config
             .operational_certificate_path
@@ -173,14 +302,13 @@ impl Runner for SignerRunner {
         };
 
         let kes_period = match operational_certificate {
-            Some(operational_certificate) => Some(
-                self.services
-                    .chain_observer
-                    .get_current_kes_period(&operational_certificate)
-                    .await?
-                    .unwrap_or_default()
-                    - operational_certificate.start_kes_period as KESPeriod,
-            ),
+            Some(operational_certificate) => {
+                let kes_period_info = self
+                    .compute_kes_period_info(&operational_certificate)
+                    .await?;
+
+                Some(kes_period_info.kes_evolutions())
+            }
             None => None,
         };
         let protocol_initializer = MithrilProtocolInitializerBuilder::build(
@@ -222,12 +350,7 @@ impl Runner for SignerRunner {
             return Ok(());
         }
 
-        let stake_distribution = self
-            .services
-            .chain_observer
-            .get_current_stake_distribution()
-            .await?
-            .ok_or_else(|| RunnerError::NoValueError("current_stake_distribution".to_string()))?;
+        let stake_distribution = self.services.stake_distribution_source.retrieve().await?;
         self.services
             .stake_store
             .save_stakes(epoch.offset_to_recording_epoch(), stake_distribution)
@@ -237,6 +360,11 @@ impl Runner for SignerRunner {
     }
 
     async fn can_sign_current_epoch(&self) -> StdResult<bool> {
+        if !self.config.operation_mode.is_signing_enabled() {
+            debug!(self.logger, "Signing disabled"; "operation_mode" => ?self.config.operation_mode);
+            return Ok(false);
+        }
+
         let epoch_service = self.epoch_service_read().await;
         epoch_service.can_signer_sign_current_epoch(self.services.single_signer.get_party_id())
     }
@@ -251,6 +379,15 @@ impl Runner for SignerRunner {
             .certificate_handler
             .retrieve_aggregator_features()
             .await?;
+        if let Some(aggregator_network_magic) = aggregator_features.network_magic {
+            let network = self.config.get_network()?;
+            if network.code() != aggregator_network_magic {
+                return Err(anyhow!(
+                    "The aggregator runs on the Cardano network with magic {aggregator_network_magic}, but the signer is configured for '{network}' with magic {}",
+                    network.code()
+                ));
+            }
+        }
 
         self.services
             .epoch_service
@@ -269,12 +406,20 @@ impl Runner for SignerRunner {
     ) -> StdResult<ProtocolMessage> {
         debug!(self.logger, ">> compute_message({signed_entity_type:?})");
 
+        let start = Instant::now();
         let protocol_message = self
             .services
             .signable_builder_service
             .compute_protocol_message(signed_entity_type.to_owned())
             .await
             .with_context(|| format!("Runner can not compute protocol message for signed entity type: '{signed_entity_type}'"))?;
+        self.services
+            .metrics_service
+            .get_message_computation_duration_seconds()
+            .observe_duration(
+                &[SignedEntityTypeDiscriminants::from(signed_entity_type).as_ref()],
+                start.elapsed(),
+            );
 
         Ok(protocol_message)
     }
@@ -285,10 +430,27 @@ impl Runner for SignerRunner {
         message: &ProtocolMessage,
     ) -> StdResult<()> {
         debug!(self.logger, ">> compute_publish_single_signature"; "beacon_to_sign" => ?beacon_to_sign);
+        let signature_scheme = self.services.era_checker.certifying_signature_scheme();
+        if !SUPPORTED_SIGNATURE_SCHEMES.contains(&signature_scheme) {
+            return Err(RunnerError::UnsupportedSignatureScheme(signature_scheme).into());
+        }
+        let start = Instant::now();
         self.services
             .certifier
             .compute_publish_single_signature(beacon_to_sign, message)
-            .await
+            .await?;
+        self.services
+            .metrics_service
+            .get_single_signature_computation_duration_seconds()
+            .observe_duration(
+                &[
+                    SignedEntityTypeDiscriminants::from(&beacon_to_sign.signed_entity_type)
+                        .as_ref(),
+                ],
+                start.elapsed(),
+            );
+
+        Ok(())
     }
 
     async fn update_era_checker(&self, epoch: Epoch) -> StdResult<()> {
@@ -324,6 +486,20 @@ impl Runner for SignerRunner {
         self.services.upkeep_service.run(current_epoch).await?;
         Ok(())
     }
+
+    async fn check_kes_period(&self) -> StdResult<()> {
+        debug!(self.logger, ">> check_kes_period");
+        let Some(operational_certificate_path) = &self.config.operational_certificate_path else {
+            return Ok(());
+        };
+        let operational_certificate = self
+            .get_operational_certificate(operational_certificate_path)
+            .with_context(|| "check_kes_period can not read the operational certificate")?;
+        self.compute_kes_period_info(&operational_certificate)
+            .await?;
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -331,6 +507,7 @@ mod tests {
     use mockall::mock;
     use mockall::predicate::eq;
     use std::collections::BTreeSet;
+    use std::time::Duration;
     use std::{path::Path, sync::Arc};
     use tokio::sync::RwLock;
 
@@ -340,21 +517,26 @@ mod tests {
         cardano_transactions_preloader::{
             CardanoTransactionsPreloader, CardanoTransactionsPreloaderActivation,
         },
-        chain_observer::FakeObserver,
+        chain_observer::{
+            ChainObserverStakeDistributionSource, FakeObserver, FileStakeDistributionSource,
+        },
         crypto_helper::{
             MKMap, MKMapNode, MKTreeNode, MKTreeStoreInMemory, MKTreeStorer, ProtocolInitializer,
         },
         digesters::{DumbImmutableDigester, DumbImmutableFileObserver},
-        entities::{BlockNumber, BlockRange, Epoch, SignedEntityTypeDiscriminants},
+        entities::{
+            BlockNumber, BlockRange, Epoch, SignedEntityTypeDiscriminants, StakeDistribution,
+        },
         era::{adapters::EraReaderBootstrapAdapter, EraChecker, EraReader},
         messages::{AggregatorCapabilities, AggregatorFeaturesMessage},
         signable_builder::{
             BlockRangeRootRetriever, CardanoImmutableFilesFullSignableBuilder,
             CardanoStakeDistributionSignableBuilder, CardanoTransactionsSignableBuilder,
             MithrilSignableBuilderService, MithrilStakeDistributionSignableBuilder,
+            SignableBuilderService,
         },
         signed_entity_type_lock::SignedEntityTypeLock,
-        test_utils::{fake_data, MithrilFixtureBuilder},
+        test_utils::{fake_data, MithrilFixtureBuilder, TempDir},
         MithrilTickerService, TickerService,
     };
     use mithril_persistence::store::adapter::{DumbStoreAdapter, MemoryAdapter};
@@ -370,6 +552,7 @@ mod tests {
     };
     use crate::store::ProtocolInitializerStore;
     use crate::test_tools::TestLogger;
+    use crate::SignerOperationMode;
 
     use super::*;
 
@@ -384,6 +567,18 @@ mod tests {
         }
     }
 
+    mock! {
+        pub SignableBuilderServiceImpl { }
+
+        #[async_trait]
+        impl SignableBuilderService for SignableBuilderServiceImpl {
+            async fn compute_protocol_message(
+                &self,
+                signed_entity_type: SignedEntityType,
+            ) -> StdResult<ProtocolMessage>;
+        }
+    }
+
     mock! {
         pub BlockRangeRootRetrieverImpl<S: MKTreeStorer> { }
 
@@ -440,6 +635,7 @@ mod tests {
         let transactions_importer = Arc::new(CardanoTransactionsImporter::new(
             transaction_parser.clone(),
             transaction_store.clone(),
+            BlockNumber(0),
             logger.clone(),
         ));
         let block_range_root_retriever =
@@ -505,6 +701,9 @@ mod tests {
         SignerDependencyContainer {
             stake_store,
             certificate_handler: aggregator_client,
+            stake_distribution_source: Arc::new(ChainObserverStakeDistributionSource::new(
+                chain_observer.clone(),
+            )),
             chain_observer,
             digester,
             single_signer,
@@ -586,6 +785,41 @@ mod tests {
         assert_eq!(2, stake_distribution.len());
     }
 
+    #[tokio::test]
+    async fn test_update_stake_distribution_from_the_configured_source() {
+        let stake_distribution_path = TempDir::create(
+            "signer_runner",
+            "test_update_stake_distribution_from_the_configured_source",
+        )
+        .join("stake_distribution.json");
+        std::fs::write(
+            &stake_distribution_path,
+            r#"{"pool1": 1000, "pool2": 2500}"#,
+        )
+        .unwrap();
+        let mut services = init_services().await;
+        services.stake_distribution_source =
+            Arc::new(FileStakeDistributionSource::new(stake_distribution_path));
+        let stake_store = services.stake_store.clone();
+        let current_epoch = services.ticker_service.get_current_epoch().await.unwrap();
+        let runner = init_runner(Some(services), None).await;
+
+        runner
+            .update_stake_distribution(current_epoch)
+            .await
+            .expect("update_stake_distribution should not fail.");
+
+        let stake_distribution = stake_store
+            .get_stakes(current_epoch.offset_to_recording_epoch())
+            .await
+            .unwrap()
+            .expect("there should be stakes for this epoch");
+        assert_eq!(
+            StakeDistribution::from([("pool1".to_string(), 1000), ("pool2".to_string(), 2500)]),
+            stake_distribution
+        );
+    }
+
     #[tokio::test]
     async fn test_register_signer_to_aggregator() {
         let mut services = init_services().await;
@@ -601,11 +835,14 @@ mod tests {
             .await
             .unwrap()
             .unwrap();
+        let party_id = services.single_signer.get_party_id();
+        let expected_stake = *stakes.get(&party_id).unwrap();
         services
             .stake_store
             .save_stakes(current_epoch.offset_to_recording_epoch(), stakes)
             .await
             .unwrap();
+        let metrics_service = services.metrics_service.clone();
 
         let runner = init_runner(Some(services), None).await;
         // inform epoch settings
@@ -634,6 +871,120 @@ mod tests {
             maybe_protocol_initializer.is_some(),
             "A protocol initializer should have been registered at the 'Recording' epoch"
         );
+        assert_eq!(
+            expected_stake as f64,
+            metrics_service.get_active_stake_gauge().get()
+        );
+    }
+
+    #[tokio::test]
+    async fn signing_only_signer_does_not_register_to_aggregator() {
+        let mut services = init_services().await;
+        let certificate_handler = Arc::new(DumbAggregatorClient::default());
+        services.certificate_handler = certificate_handler.clone();
+        let protocol_initializer_store = services.protocol_initializer_store.clone();
+        let current_epoch = services.ticker_service.get_current_epoch().await.unwrap();
+        let config = Configuration {
+            operation_mode: SignerOperationMode::SigningOnly,
+            ..Configuration::new_sample("1")
+        };
+
+        let runner = init_runner(Some(services), Some(config)).await;
+        runner
+            .register_signer_to_aggregator()
+            .await
+            .expect("registration should be skipped without failing");
+
+        assert!(certificate_handler
+            .get_last_registered_signer()
+            .await
+            .is_none());
+        assert!(protocol_initializer_store
+            .get_protocol_initializer(current_epoch.offset_to_recording_epoch())
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn registration_only_signer_can_never_sign() {
+        let config = Configuration {
+            operation_mode: SignerOperationMode::RegistrationOnly,
+            ..Configuration::new_sample("1")
+        };
+        let runner = init_runner(None, Some(config)).await;
+
+        assert!(!runner.can_sign_current_epoch().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn record_kes_period_info_reports_an_operational_certificate_about_to_expire() {
+        let services = init_services().await;
+        let metrics_service = services.metrics_service.clone();
+        let config = Configuration {
+            kes_expiry_warning_threshold_in_evolutions: 5,
+            ..Configuration::new_sample("1")
+        };
+        let runner = init_runner(Some(services), Some(config)).await;
+        let kes_period_info = |remaining_kes_evolutions| KesPeriodInfo {
+            current_kes_period: 100,
+            start_kes_period: 90,
+            remaining_kes_evolutions,
+        };
+
+        runner.record_kes_period_info(&kes_period_info(6));
+        assert_eq!(
+            6.0,
+            metrics_service.get_remaining_kes_evolutions_gauge().get()
+        );
+        assert_eq!(0.0, metrics_service.get_kes_expiring_gauge().get());
+
+        runner.record_kes_period_info(&kes_period_info(5));
+        assert_eq!(0.0, metrics_service.get_kes_expiring_gauge().get());
+
+        runner.record_kes_period_info(&kes_period_info(4));
+        assert_eq!(1.0, metrics_service.get_kes_expiring_gauge().get());
+
+        runner.record_kes_period_info(&kes_period_info(0));
+        assert_eq!(1.0, metrics_service.get_kes_expiring_gauge().get());
+    }
+
+    #[tokio::test]
+    async fn get_operational_certificate_reads_the_file_again_only_when_it_is_modified() {
+        let fixture = MithrilFixtureBuilder::default().with_signers(1).build();
+        let fixture_opcert_path = fixture.signers_fixture()[0]
+            .kes_secret_key_path()
+            .unwrap()
+            .with_file_name("opcert.cert");
+        let opcert_path = TempDir::create(
+            "signer_runner",
+            "get_operational_certificate_reads_the_file_again_only_when_it_is_modified",
+        )
+        .join("opcert.cert");
+        std::fs::copy(&fixture_opcert_path, &opcert_path).unwrap();
+        let runner = init_runner(None, None).await;
+
+        let opcert = runner.get_operational_certificate(&opcert_path).unwrap();
+        assert_eq!(OpCert::from_file(&fixture_opcert_path).unwrap(), opcert);
+
+        // A change that keeps the modification date is not seen
+        let modified_at = std::fs::metadata(&opcert_path).unwrap().modified().unwrap();
+        std::fs::write(&opcert_path, "not an operational certificate").unwrap();
+        let file = std::fs::File::options()
+            .write(true)
+            .open(&opcert_path)
+            .unwrap();
+        file.set_modified(modified_at).unwrap();
+        assert_eq!(
+            opcert,
+            runner.get_operational_certificate(&opcert_path).unwrap()
+        );
+
+        file.set_modified(modified_at + Duration::from_secs(1))
+            .unwrap();
+        runner
+            .get_operational_certificate(&opcert_path)
+            .expect_err("A modified file should be read again");
     }
 
     #[tokio::test]
@@ -705,4 +1056,57 @@ mod tests {
             recorded_allowed_discriminants
         );
     }
+
+    #[tokio::test]
+    async fn test_inform_epoch_setting_fails_if_the_aggregator_runs_on_another_network() {
+        let mut services = init_services().await;
+        let certificate_handler = Arc::new(DumbAggregatorClient::default());
+        certificate_handler
+            .set_aggregator_features(AggregatorFeaturesMessage {
+                network: Some("private".to_string()),
+                network_magic: Some(1234),
+                ..AggregatorFeaturesMessage::dummy()
+            })
+            .await;
+        services.certificate_handler = certificate_handler;
+        let runner = init_runner(Some(services), None).await;
+
+        runner
+            .inform_epoch_settings(SignerEpochSettings::dummy())
+            .await
+            .expect_err("inform_epoch_settings should fail if the networks do not match");
+    }
+
+    #[tokio::test]
+    async fn test_compute_message_records_computation_duration() {
+        let mut services = init_services().await;
+        let mut signable_builder_service = MockSignableBuilderServiceImpl::new();
+        signable_builder_service
+            .expect_compute_protocol_message()
+            .returning(|_| Ok(ProtocolMessage::new()))
+            .once();
+        services.signable_builder_service = Arc::new(signable_builder_service);
+        let metrics_service = services.metrics_service.clone();
+        let runner = init_runner(Some(services), None).await;
+
+        runner
+            .compute_message(&SignedEntityType::MithrilStakeDistribution(Epoch(3)))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            1,
+            metrics_service
+                .get_message_computation_duration_seconds()
+                .get_sample_count(&[
+                    SignedEntityTypeDiscriminants::MithrilStakeDistribution.as_ref()
+                ])
+        );
+        assert_eq!(
+            0,
+            metrics_service
+                .get_message_computation_duration_seconds()
+                .get_sample_count(&[SignedEntityTypeDiscriminants::CardanoTransactions.as_ref()])
+        );
+    }
 }
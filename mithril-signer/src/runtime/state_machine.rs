@@ -1,11 +1,18 @@
-use slog::{debug, info, Logger};
-use std::{fmt::Display, ops::Deref, sync::Arc, time::Duration};
-use tokio::{sync::Mutex, time::sleep};
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use slog::{debug, info, warn, Logger};
+use std::{fmt::Display, ops::Deref, path::PathBuf, sync::Arc, time::Duration};
+use tokio::{
+    sync::{watch, Mutex},
+    time::sleep,
+};
 
 use mithril_common::{
     crypto_helper::ProtocolInitializerError,
+    digesters::ImmutableFileWatcher,
     entities::{Epoch, TimePoint},
-    logging::LoggerExtensions,
+    logging::{CorrelationId, LoggerExtensions},
+    StdResult,
 };
 
 use crate::entities::{BeaconToSign, SignerEpochSettings};
@@ -14,7 +21,7 @@ use crate::MetricsService;
 use super::{Runner, RuntimeError};
 
 /// Different possible states of the state machine.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SignerState {
     /// Starting state
     Init,
@@ -58,6 +65,16 @@ impl SignerState {
     pub fn is_registered_not_able_to_sign(&self) -> bool {
         matches!(*self, SignerState::RegisteredNotAbleToSign { .. })
     }
+
+    /// Epoch of the registration of the signer, if it is in one of the registered states
+    fn registered_epoch(&self) -> Option<Epoch> {
+        match self {
+            SignerState::ReadyToSign { epoch } | SignerState::RegisteredNotAbleToSign { epoch } => {
+                Some(*epoch)
+            }
+            SignerState::Init | SignerState::Unregistered { .. } => None,
+        }
+    }
 }
 
 impl Display for SignerState {
@@ -83,8 +100,11 @@ enum EpochStatus {
 /// The state machine is responsible of the execution of the signer automate.
 pub struct StateMachine {
     state: Mutex<SignerState>,
+    state_sender: watch::Sender<SignerState>,
     runner: Box<dyn Runner>,
     state_sleep: Duration,
+    immutable_file_watcher: Option<Arc<ImmutableFileWatcher>>,
+    state_file: Option<PathBuf>,
     metrics_service: Arc<MetricsService>,
     logger: Logger,
 }
@@ -98,38 +118,177 @@ impl StateMachine {
         metrics_service: Arc<MetricsService>,
         logger: Logger,
     ) -> Self {
+        let (state_sender, _) = watch::channel(starting_state.clone());
         Self {
             state: Mutex::new(starting_state),
+            state_sender,
             runner,
             state_sleep,
+            immutable_file_watcher: None,
+            state_file: None,
             metrics_service,
             logger: logger.new_with_component_name::<Self>(),
         }
     }
 
+    /// Start a new cycle as soon as the given watcher detects a new immutable file, instead of
+    /// waiting for the end of the state sleep.
+    pub fn with_immutable_file_watcher(
+        mut self,
+        immutable_file_watcher: Arc<ImmutableFileWatcher>,
+    ) -> Self {
+        self.immutable_file_watcher = Some(immutable_file_watcher);
+        self
+    }
+
+    /// Persist the state to the given file when the state machine is stopped, and resume from
+    /// the state persisted by a previous run when it is launched.
+    ///
+    /// The state machine always starts from its starting state since the services of the
+    /// runner must be initialized, but a signer that was registered in the current epoch is
+    /// registered back right away instead of waiting for the next cycle.
+    pub fn with_state_file(mut self, state_file: PathBuf) -> Self {
+        self.state_file = Some(state_file);
+        self
+    }
+
     /// Return the current state of the state machine.
     pub async fn get_state(&self) -> SignerState {
         self.state.lock().await.to_owned()
     }
 
+    /// Return a receiver of the state of the state machine, updated at the end of each cycle.
+    ///
+    /// Unlike [Self::get_state], reading it never waits for a running cycle to finish.
+    pub fn subscribe_to_state(&self) -> watch::Receiver<SignerState> {
+        self.state_sender.subscribe()
+    }
+
     /// Launch the state machine until an error occurs or it is interrupted.
     pub async fn run(&self) -> Result<(), RuntimeError> {
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+        self.run_until_stopped(stop_receiver).await
+    }
+
+    /// Launch the state machine until an error occurs or a stop is requested by sending `true`
+    /// to the given receiver.
+    ///
+    /// A stop request never interrupts a running cycle: the state machine returns once the
+    /// current cycle, and the signature it may be submitting, is finished.
+    pub async fn run_until_stopped(
+        &self,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> Result<(), RuntimeError> {
         info!(self.logger, "Launching State Machine");
+        let mut resumed_registration_epoch = self
+            .read_persisted_state()
+            .await
+            .and_then(|state| state.registered_epoch());
 
-        loop {
-            if let Err(e) = self.cycle().await {
+        while !*stop_receiver.borrow_and_update() {
+            // All the logs of a cycle, and the requests it sends to the aggregator, share a
+            // correlation id
+            if let Err(e) = CorrelationId::generate().scope(self.cycle()).await {
                 e.write_to_log(&self.logger);
                 if e.is_critical() {
                     return Err(e);
                 }
             }
 
+            if let Some(epoch) = resumed_registration_epoch.take() {
+                if self.get_state().await == (SignerState::Unregistered { epoch }) {
+                    info!(
+                        self.logger, "… Cycle finished, registering back right away in the epoch of the previous run";
+                        "epoch" => ?epoch
+                    );
+                    continue;
+                }
+            }
+
             info!(
                 self.logger,
                 "… Cycle finished, Sleeping for {} ms",
                 self.state_sleep.as_millis()
             );
-            sleep(self.state_sleep).await;
+            tokio::select! {
+                _ = sleep(self.state_sleep) => {}
+                Ok(()) = stop_receiver.changed() => {}
+                _ = self.wait_for_new_immutable_file() => {}
+            }
+        }
+        let state = self.get_state().await;
+        info!(self.logger, "State Machine stopped"; "state" => %state);
+        self.persist_state(&state).await;
+
+        Ok(())
+    }
+
+    /// Read, and remove, the state persisted by a previous run
+    async fn read_persisted_state(&self) -> Option<SignerState> {
+        let state_file = self.state_file.as_ref()?;
+        if !tokio::fs::try_exists(state_file).await.unwrap_or(false) {
+            return None;
+        }
+
+        let state = async {
+            let content = tokio::fs::read_to_string(state_file).await?;
+            serde_json::from_str::<SignerState>(&content).map_err(anyhow::Error::from)
+        }
+        .await
+        .with_context(|| {
+            format!(
+                "Could not read the persisted state from '{}'",
+                state_file.display()
+            )
+        });
+        // A persisted state is only valid for the run that follows the one that persisted it
+        if let Err(error) = tokio::fs::remove_file(state_file).await {
+            warn!(self.logger, "Could not remove the persisted state file"; "error" => ?error);
+        }
+
+        match state {
+            Ok(state) => {
+                info!(self.logger, "Resuming from the state persisted by the previous run"; "state" => %state);
+                Some(state)
+            }
+            Err(error) => {
+                warn!(self.logger, "Ignoring the state persisted by the previous run"; "error" => ?error);
+                None
+            }
+        }
+    }
+
+    /// Persist the state so the next run can resume from it
+    async fn persist_state(&self, state: &SignerState) {
+        let Some(state_file) = &self.state_file else {
+            return;
+        };
+
+        let result: StdResult<()> = async {
+            let content = serde_json::to_string(state)?;
+            tokio::fs::write(state_file, content).await?;
+            Ok(())
+        }
+        .await;
+        if let Err(error) = result {
+            warn!(
+                self.logger, "Could not persist the state";
+                "state_file" => %state_file.display(), "error" => ?error
+            );
+        }
+    }
+
+    /// Wait for a new immutable file, never returns if there is no immutable file watcher.
+    async fn wait_for_new_immutable_file(&self) {
+        match &self.immutable_file_watcher {
+            Some(watcher) => {
+                let immutable_file_number = watcher.wait_for_new_immutable_file().await;
+                info!(
+                    self.logger, "New immutable file detected, starting a new cycle";
+                    "immutable_file_number" => immutable_file_number
+                );
+            }
+            None => std::future::pending().await,
         }
     }
 
@@ -146,6 +305,12 @@ impl StateMachine {
             .get_runtime_cycle_total_since_startup_counter()
             .increment();
 
+        // An expired operational certificate makes the signatures fail later in the cycle,
+        // the check only reports it and never interrupts the cycle.
+        if let Err(error) = self.runner.check_kes_period().await {
+            warn!(self.logger, "Could not check the KES period"; "error" => ?error);
+        }
+
         match state.deref() {
             SignerState::Init => {
                 *state = self.transition_from_init_to_unregistered().await?;
@@ -240,6 +405,7 @@ impl StateMachine {
             },
         };
 
+        self.state_sender.send_replace(state.clone());
         self.metrics_service
             .get_runtime_cycle_success_since_startup_counter()
             .increment();
@@ -327,6 +493,10 @@ impl StateMachine {
             .get_signer_registration_success_since_startup_counter()
             .increment();
 
+        let previous_registration_epoch = self
+            .metrics_service
+            .get_signer_registration_success_last_epoch_gauge()
+            .get() as u64;
         self.metrics_service
             .get_signer_registration_success_last_epoch_gauge()
             .record(epoch);
@@ -347,8 +517,25 @@ impl StateMachine {
                 message: "Failed to check if signer can sign in the current epoch in 'unregistered → ?' phase".to_string(),
                 nested_error: Some(e),
             })? {
-            true => Ok(SignerState::ReadyToSign { epoch }),
-            false => Ok(SignerState::RegisteredNotAbleToSign { epoch }),
+            true => {
+                self.metrics_service
+                    .get_epochs_until_signing_window_gauge()
+                    .record(0);
+                Ok(SignerState::ReadyToSign { epoch })
+            }
+            false => {
+                // A registration done at epoch N is used to sign at epoch N+2, so the signer
+                // is one epoch closer to signing if it was already registered at epoch N-1.
+                let epochs_until_signing_window = if previous_registration_epoch + 1 == epoch.0 {
+                    1
+                } else {
+                    2
+                };
+                self.metrics_service
+                    .get_epochs_until_signing_window_gauge()
+                    .record(epochs_until_signing_window);
+                Ok(SignerState::RegisteredNotAbleToSign { epoch })
+            }
         }
     }
 
@@ -433,6 +620,9 @@ impl StateMachine {
                     ),
                     nested_error: Some(e),
                 })?;
+        self.metrics_service
+            .get_current_epoch_gauge()
+            .record(current_time_point.epoch);
 
         Ok(current_time_point)
     }
@@ -455,24 +645,192 @@ mod tests {
     use chrono::DateTime;
     use mockall::predicate;
 
+    use mithril_common::digesters::DumbImmutableFileObserver;
     use mithril_common::entities::{ChainPoint, Epoch, ProtocolMessage, SignedEntityType};
-    use mithril_common::test_utils::fake_data;
+    use mithril_common::test_utils::{fake_data, TempDir};
 
     use crate::runtime::runner::MockSignerRunner;
     use crate::test_tools::TestLogger;
 
     use super::*;
 
-    fn init_state_machine(init_state: SignerState, runner: MockSignerRunner) -> StateMachine {
+    fn init_state_machine(init_state: SignerState, mut runner: MockSignerRunner) -> StateMachine {
+        runner.expect_check_kes_period().returning(|| Ok(()));
         let logger = TestLogger::stdout();
         let metrics_service = Arc::new(MetricsService::new(logger.clone()).unwrap());
-        StateMachine {
-            state: init_state.into(),
-            runner: Box::new(runner),
-            state_sleep: Duration::from_millis(100),
+        StateMachine::new(
+            init_state,
+            Box::new(runner),
+            Duration::from_millis(100),
             metrics_service,
             logger,
-        }
+        )
+    }
+
+    #[tokio::test]
+    async fn run_until_stopped_finishes_the_running_cycle_before_returning() {
+        let mut runner = MockSignerRunner::new();
+        runner
+            .expect_get_current_time_point()
+            .once()
+            .returning(|| Ok(TimePoint::dummy()));
+        runner.expect_update_era_checker().returning(|_| Ok(()));
+        let state_machine = init_state_machine(SignerState::Init, runner);
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let mut state_receiver = state_machine.subscribe_to_state();
+
+        let stop_on_first_cycle = async move {
+            state_receiver.changed().await.unwrap();
+            stop_sender.send(true).unwrap();
+        };
+        let (result, _) = tokio::time::timeout(Duration::from_secs(1), async {
+            tokio::join!(
+                state_machine.run_until_stopped(stop_receiver),
+                stop_on_first_cycle
+            )
+        })
+        .await
+        .expect("State machine should have stopped");
+
+        result.unwrap();
+        assert_eq!(
+            SignerState::Unregistered {
+                epoch: TimePoint::dummy().epoch
+            },
+            state_machine.get_state().await
+        );
+    }
+
+    #[tokio::test]
+    async fn run_until_stopped_persists_the_state_when_stopped() {
+        let state_file = TempDir::create(
+            "signer_state_machine",
+            "run_until_stopped_persists_the_state_when_stopped",
+        )
+        .join("signer_state.json");
+        let mut runner = MockSignerRunner::new();
+        runner
+            .expect_get_current_time_point()
+            .returning(|| Ok(TimePoint::dummy()));
+        let registered_state = SignerState::RegisteredNotAbleToSign {
+            epoch: TimePoint::dummy().epoch,
+        };
+        let state_machine = init_state_machine(registered_state.clone(), runner)
+            .with_state_file(state_file.clone());
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let mut state_receiver = state_machine.subscribe_to_state();
+
+        let stop_on_first_cycle = async move {
+            state_receiver.changed().await.unwrap();
+            stop_sender.send(true).unwrap();
+        };
+        let (result, _) = tokio::time::timeout(Duration::from_secs(1), async {
+            tokio::join!(
+                state_machine.run_until_stopped(stop_receiver),
+                stop_on_first_cycle
+            )
+        })
+        .await
+        .expect("State machine should have stopped");
+
+        result.unwrap();
+        let persisted_state: SignerState =
+            serde_json::from_str(&std::fs::read_to_string(&state_file).unwrap()).unwrap();
+        assert_eq!(registered_state, persisted_state);
+    }
+
+    #[tokio::test]
+    async fn run_until_stopped_registers_back_right_away_when_resuming_in_the_same_epoch() {
+        let state_file = TempDir::create(
+            "signer_state_machine",
+            "run_until_stopped_registers_back_right_away_when_resuming_in_the_same_epoch",
+        )
+        .join("signer_state.json");
+        std::fs::write(
+            &state_file,
+            serde_json::to_string(&SignerState::ReadyToSign {
+                epoch: TimePoint::dummy().epoch,
+            })
+            .unwrap(),
+        )
+        .unwrap();
+        let mut runner = MockSignerRunner::new();
+        runner
+            .expect_get_current_time_point()
+            .returning(|| Ok(TimePoint::dummy()));
+        runner.expect_update_era_checker().returning(|_| Ok(()));
+        runner
+            .expect_get_epoch_settings()
+            .once()
+            .returning(|| Ok(None));
+        let mut state_machine =
+            init_state_machine(SignerState::Init, runner).with_state_file(state_file.clone());
+        // Waiting between the two cycles would make the second one miss the timeout
+        state_machine.state_sleep = Duration::from_secs(10);
+
+        tokio::time::timeout(Duration::from_millis(500), state_machine.run())
+            .await
+            .expect_err("The state machine should still be running");
+
+        assert_eq!(
+            SignerState::Unregistered {
+                epoch: TimePoint::dummy().epoch
+            },
+            state_machine.get_state().await
+        );
+        assert!(
+            !state_file.exists(),
+            "The persisted state should be removed once read"
+        );
+        // The expectations of the runner, the epoch settings read by the second cycle, are
+        // checked when it is dropped
+        drop(state_machine);
+    }
+
+    #[tokio::test]
+    async fn a_new_immutable_file_starts_a_new_cycle_without_waiting_for_the_state_sleep() {
+        let mut runner = MockSignerRunner::new();
+        runner
+            .expect_get_current_time_point()
+            .returning(|| Ok(TimePoint::dummy()));
+        runner.expect_get_epoch_settings().returning(|| Ok(None));
+        runner.expect_check_kes_period().returning(|| Ok(()));
+        let immutable_file_observer = Arc::new(DumbImmutableFileObserver::default());
+        let immutable_file_watcher = Arc::new(ImmutableFileWatcher::new(
+            immutable_file_observer.clone(),
+            Duration::from_millis(1),
+            TestLogger::stdout(),
+        ));
+        immutable_file_watcher.check_new_immutable_file().await;
+        let state_machine = StateMachine::new(
+            SignerState::Unregistered {
+                epoch: TimePoint::dummy().epoch,
+            },
+            Box::new(runner),
+            Duration::from_secs(60),
+            Arc::new(MetricsService::new(TestLogger::stdout()).unwrap()),
+            TestLogger::stdout(),
+        )
+        .with_immutable_file_watcher(immutable_file_watcher);
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let mut state_receiver = state_machine.subscribe_to_state();
+
+        let stop_on_second_cycle = async move {
+            state_receiver.changed().await.unwrap();
+            immutable_file_observer.increase().await.unwrap();
+            state_receiver.changed().await.unwrap();
+            stop_sender.send(true).unwrap();
+        };
+        let (result, _) = tokio::time::timeout(Duration::from_secs(1), async {
+            tokio::join!(
+                state_machine.run_until_stopped(stop_receiver),
+                stop_on_second_cycle
+            )
+        })
+        .await
+        .expect("A new immutable file should have started a new cycle");
+
+        result.unwrap();
     }
 
     #[tokio::test]
@@ -592,6 +950,67 @@ mod tests {
                 state_machine.get_state().await
             );
         }
+        assert_eq!(
+            2.0,
+            state_machine
+                .metrics_service
+                .get_epochs_until_signing_window_gauge()
+                .get()
+        );
+    }
+
+    #[tokio::test]
+    async fn unregistered_to_registered_not_able_to_sign_after_registration_in_previous_epoch() {
+        let mut runner = MockSignerRunner::new();
+        runner.expect_upkeep().returning(|_| Ok(())).once();
+        runner
+            .expect_get_epoch_settings()
+            .once()
+            .returning(|| Ok(Some(SignerEpochSettings::dummy())));
+        runner
+            .expect_inform_epoch_settings()
+            .once()
+            .returning(|_| Ok(()));
+        runner
+            .expect_get_current_time_point()
+            .times(2)
+            .returning(|| Ok(TimePoint::dummy()));
+        runner
+            .expect_update_stake_distribution()
+            .once()
+            .returning(|_| Ok(()));
+        runner
+            .expect_register_signer_to_aggregator()
+            .once()
+            .returning(|| Ok(()));
+        runner
+            .expect_can_sign_current_epoch()
+            .once()
+            .returning(|| Ok(false));
+
+        let state_machine = init_state_machine(
+            SignerState::Unregistered {
+                epoch: TimePoint::dummy().epoch,
+            },
+            runner,
+        );
+        state_machine
+            .metrics_service
+            .get_signer_registration_success_last_epoch_gauge()
+            .record(TimePoint::dummy().epoch - 1);
+
+        state_machine
+            .cycle()
+            .await
+            .expect("Cycling the state machine should not fail");
+
+        assert_eq!(
+            1.0,
+            state_machine
+                .metrics_service
+                .get_epochs_until_signing_window_gauge()
+                .get()
+        );
     }
 
     #[tokio::test]
@@ -634,6 +1053,7 @@ mod tests {
             runner,
         );
 
+        let state_receiver = state_machine.subscribe_to_state();
         state_machine
             .cycle()
             .await
@@ -645,6 +1065,26 @@ mod tests {
             },
             state_machine.get_state().await
         );
+        assert_eq!(
+            SignerState::ReadyToSign {
+                epoch: TimePoint::dummy().epoch,
+            },
+            *state_receiver.borrow()
+        );
+        assert_eq!(
+            0.0,
+            state_machine
+                .metrics_service
+                .get_epochs_until_signing_window_gauge()
+                .get()
+        );
+        assert_eq!(
+            TimePoint::dummy().epoch.0 as f64,
+            state_machine
+                .metrics_service
+                .get_current_epoch_gauge()
+                .get()
+        );
     }
 
     #[tokio::test]
@@ -0,0 +1,266 @@
+use axum::{extract::State, http::StatusCode, routing::get, Json, Router};
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{watch, Mutex};
+use tokio::time::Instant;
+
+use mithril_common::chain_observer::ChainObserver;
+use mithril_common::entities::Epoch;
+
+use crate::services::AggregatorClient;
+use crate::MetricsService;
+
+use super::SignerState;
+
+/// Status of the signer, as returned by the `/status` route.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SignerStatusMessage {
+    /// Name of the current state of the state machine.
+    pub state: String,
+    /// Epoch of the current state of the state machine, if any.
+    pub epoch: Option<Epoch>,
+    /// Latest epoch at which the signer successfully registered.
+    pub last_registration_epoch: Option<Epoch>,
+    /// Latest epoch at which the signer successfully registered a signature.
+    pub last_signature_epoch: Option<Epoch>,
+    /// Whether the aggregator answered to the status check.
+    pub aggregator_reachable: bool,
+    /// Whether the Cardano node answered to the status check.
+    pub cardano_node_reachable: bool,
+}
+
+impl SignerStatusMessage {
+    /// Return true if both the aggregator and the Cardano node are reachable.
+    pub fn is_healthy(&self) -> bool {
+        self.aggregator_reachable && self.cardano_node_reachable
+    }
+}
+
+/// Maximum duration of a connectivity probe, the dependency is reported as unreachable beyond.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Duration during which the result of the connectivity probes is reused, so that frequent
+/// status requests do not flood the aggregator and the Cardano node.
+const PROBE_CACHE_DURATION: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy)]
+struct Connectivity {
+    aggregator_reachable: bool,
+    cardano_node_reachable: bool,
+}
+
+/// Compute the status of the signer so it can be supervised over HTTP.
+pub struct SignerStatusReporter {
+    state_receiver: watch::Receiver<SignerState>,
+    metrics_service: Arc<MetricsService>,
+    aggregator_client: Arc<dyn AggregatorClient>,
+    chain_observer: Arc<dyn ChainObserver>,
+    last_connectivity: Mutex<Option<(Instant, Connectivity)>>,
+}
+
+impl SignerStatusReporter {
+    /// Create a new instance
+    pub fn new(
+        state_receiver: watch::Receiver<SignerState>,
+        metrics_service: Arc<MetricsService>,
+        aggregator_client: Arc<dyn AggregatorClient>,
+        chain_observer: Arc<dyn ChainObserver>,
+    ) -> Self {
+        Self {
+            state_receiver,
+            metrics_service,
+            aggregator_client,
+            chain_observer,
+            last_connectivity: Mutex::new(None),
+        }
+    }
+
+    /// Probe the connectivity to the aggregator and to the Cardano node, reusing the result of
+    /// the previous probes if they are recent enough.
+    async fn get_connectivity(&self) -> Connectivity {
+        let mut last_connectivity = self.last_connectivity.lock().await;
+        if let Some((probed_at, connectivity)) = *last_connectivity {
+            if probed_at.elapsed() < PROBE_CACHE_DURATION {
+                return connectivity;
+            }
+        }
+
+        let (aggregator_probe, cardano_node_probe) = tokio::join!(
+            tokio::time::timeout(
+                PROBE_TIMEOUT,
+                self.aggregator_client.retrieve_aggregator_features()
+            ),
+            tokio::time::timeout(PROBE_TIMEOUT, self.chain_observer.get_current_epoch())
+        );
+        let connectivity = Connectivity {
+            aggregator_reachable: matches!(aggregator_probe, Ok(Ok(_))),
+            cardano_node_reachable: matches!(cardano_node_probe, Ok(Ok(Some(_)))),
+        };
+        *last_connectivity = Some((Instant::now(), connectivity));
+
+        connectivity
+    }
+
+    /// Compute the current status of the signer, checking the connectivity to the aggregator
+    /// and to the Cardano node at most once every [PROBE_CACHE_DURATION].
+    pub async fn get_status(&self) -> SignerStatusMessage {
+        let connectivity = self.get_connectivity().await;
+        let (state, epoch) = match &*self.state_receiver.borrow() {
+            SignerState::Init => ("Init", None),
+            SignerState::Unregistered { epoch } => ("Unregistered", Some(*epoch)),
+            SignerState::ReadyToSign { epoch } => ("ReadyToSign", Some(*epoch)),
+            SignerState::RegisteredNotAbleToSign { epoch } => {
+                ("RegisteredNotAbleToSign", Some(*epoch))
+            }
+        };
+        let gauge_epoch = |value: f64| (value > 0.0).then_some(Epoch(value as u64));
+
+        SignerStatusMessage {
+            state: state.to_string(),
+            epoch,
+            last_registration_epoch: gauge_epoch(
+                self.metrics_service
+                    .get_signer_registration_success_last_epoch_gauge()
+                    .get(),
+            ),
+            last_signature_epoch: gauge_epoch(
+                self.metrics_service
+                    .get_signature_registration_success_last_epoch_gauge()
+                    .get(),
+            ),
+            aggregator_reachable: connectivity.aggregator_reachable,
+            cardano_node_reachable: connectivity.cardano_node_reachable,
+        }
+    }
+
+    /// Router serving the status of the signer on the `/status` route.
+    ///
+    /// The route responds with a `503 Service Unavailable` status code if the signer is not
+    /// healthy, the body holding the status in both cases.
+    pub fn router(self: Arc<Self>) -> Router {
+        Router::new()
+            .route(
+                "/status",
+                get(|State(reporter): State<Arc<Self>>| async move {
+                    let status = reporter.get_status().await;
+                    let status_code = if status.is_healthy() {
+                        StatusCode::OK
+                    } else {
+                        StatusCode::SERVICE_UNAVAILABLE
+                    };
+
+                    (status_code, Json(status))
+                }),
+            )
+            .with_state(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+
+    use mithril_common::chain_observer::FakeObserver;
+    use mithril_common::entities::TimePoint;
+    use mithril_common::messages::AggregatorFeaturesMessage;
+
+    use crate::services::{AggregatorClientError, MockAggregatorClient};
+    use crate::test_tools::TestLogger;
+
+    use super::*;
+
+    fn aggregator_client(reachable: bool) -> Arc<dyn AggregatorClient> {
+        let mut aggregator_client = MockAggregatorClient::new();
+        aggregator_client
+            .expect_retrieve_aggregator_features()
+            .times(1)
+            .returning(move || {
+                if reachable {
+                    Ok(AggregatorFeaturesMessage::dummy())
+                } else {
+                    Err(AggregatorClientError::RemoteServerUnreachable(anyhow!(
+                        "unreachable"
+                    )))
+                }
+            });
+
+        Arc::new(aggregator_client)
+    }
+
+    #[tokio::test]
+    async fn status_of_a_healthy_signer() {
+        let (_state_sender, state_receiver) =
+            watch::channel(SignerState::ReadyToSign { epoch: Epoch(12) });
+        let metrics_service = Arc::new(MetricsService::new(TestLogger::stdout()).unwrap());
+        metrics_service
+            .get_signer_registration_success_last_epoch_gauge()
+            .record(Epoch(12));
+        let reporter = SignerStatusReporter::new(
+            state_receiver,
+            metrics_service,
+            aggregator_client(true),
+            Arc::new(FakeObserver::new(Some(TimePoint::dummy()))),
+        );
+
+        let status = reporter.get_status().await;
+
+        assert_eq!(
+            SignerStatusMessage {
+                state: "ReadyToSign".to_string(),
+                epoch: Some(Epoch(12)),
+                last_registration_epoch: Some(Epoch(12)),
+                last_signature_epoch: None,
+                aggregator_reachable: true,
+                cardano_node_reachable: true,
+            },
+            status
+        );
+        assert!(status.is_healthy());
+    }
+
+    #[tokio::test]
+    async fn status_of_a_signer_that_can_not_reach_its_dependencies() {
+        let (_state_sender, state_receiver) = watch::channel(SignerState::Init);
+        let reporter = SignerStatusReporter::new(
+            state_receiver,
+            Arc::new(MetricsService::new(TestLogger::stdout()).unwrap()),
+            aggregator_client(false),
+            Arc::new(FakeObserver::new(None)),
+        );
+
+        let status = reporter.get_status().await;
+
+        assert_eq!("Init", status.state);
+        assert!(!status.aggregator_reachable);
+        assert!(!status.cardano_node_reachable);
+        assert!(!status.is_healthy());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn connectivity_probes_are_reused_until_they_expire() {
+        let (state_sender, state_receiver) = watch::channel(SignerState::Init);
+        let mut aggregator_client = MockAggregatorClient::new();
+        aggregator_client
+            .expect_retrieve_aggregator_features()
+            .times(2)
+            .returning(|| Ok(AggregatorFeaturesMessage::dummy()));
+        let reporter = SignerStatusReporter::new(
+            state_receiver,
+            Arc::new(MetricsService::new(TestLogger::stdout()).unwrap()),
+            Arc::new(aggregator_client),
+            Arc::new(FakeObserver::new(Some(TimePoint::dummy()))),
+        );
+
+        assert!(reporter.get_status().await.aggregator_reachable);
+        state_sender
+            .send(SignerState::Unregistered { epoch: Epoch(3) })
+            .unwrap();
+        let status = reporter.get_status().await;
+        assert_eq!("Unregistered", status.state);
+        assert!(status.aggregator_reachable);
+
+        tokio::time::advance(PROBE_CACHE_DURATION).await;
+        assert!(reporter.get_status().await.aggregator_reachable);
+    }
+}
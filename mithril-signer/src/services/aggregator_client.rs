@@ -1,22 +1,25 @@
-use anyhow::anyhow;
+use anyhow::{anyhow, Context};
 use async_trait::async_trait;
 use reqwest::header::{self, HeaderValue};
-use reqwest::{self, Client, Proxy, RequestBuilder, Response, StatusCode};
+use reqwest::{self, Certificate, Client, Identity, Proxy, RequestBuilder, Response, StatusCode};
 use slog::{debug, Logger};
-use std::{io, sync::Arc, time::Duration};
+use std::{io, path::Path, sync::Arc, time::Duration};
 use thiserror::Error;
 
 use mithril_common::{
     api_version::APIVersionProvider,
+    crypto_helper::RegistrationChallengeSigner,
     entities::{
-        ClientError, Epoch, ProtocolMessage, ServerError, SignedEntityType, Signer,
+        ClientError, Epoch, PartyId, ProtocolMessage, ServerError, SignedEntityType, Signer,
         SingleSignatures,
     },
-    logging::LoggerExtensions,
+    logging::{CorrelationId, LoggerExtensions},
     messages::{
-        AggregatorFeaturesMessage, EpochSettingsMessage, TryFromMessageAdapter, TryToMessageAdapter,
+        AggregatorFeaturesMessage, EpochSettingsMessage, RegistrationChallengeMessage,
+        TryFromMessageAdapter, TryToMessageAdapter,
     },
-    StdError, StdResult, MITHRIL_API_VERSION_HEADER, MITHRIL_SIGNER_VERSION_HEADER,
+    StdError, StdResult, MITHRIL_API_VERSION_HEADER, MITHRIL_CORRELATION_ID_HEADER,
+    MITHRIL_SIGNER_VERSION_HEADER,
 };
 
 use crate::entities::SignerEpochSettings;
@@ -69,6 +72,10 @@ pub enum AggregatorClientError {
     /// Adapter error
     #[error("adapter failed")]
     Adapter(#[source] StdError),
+
+    /// Registration challenge signing error
+    #[error("registration challenge signing failed")]
+    RegistrationChallengeSigning(#[source] StdError),
 }
 
 #[cfg(test)]
@@ -185,6 +192,9 @@ pub struct AggregatorHTTPClient {
     relay_endpoint: Option<String>,
     api_version_provider: Arc<APIVersionProvider>,
     timeout_duration: Option<Duration>,
+    client_identity: Option<Identity>,
+    ca_certificate: Option<Certificate>,
+    registration_challenge_signer: Option<RegistrationChallengeSigner>,
     logger: Logger,
 }
 
@@ -204,23 +214,117 @@ impl AggregatorHTTPClient {
             relay_endpoint,
             api_version_provider,
             timeout_duration,
+            client_identity: None,
+            ca_certificate: None,
+            registration_challenge_signer: None,
             logger,
         }
     }
 
+    /// Sign the registration challenges issued by the aggregator when registering a certified
+    /// signer
+    pub fn with_registration_challenge_signer(
+        mut self,
+        registration_challenge_signer: RegistrationChallengeSigner,
+    ) -> Self {
+        self.registration_challenge_signer = Some(registration_challenge_signer);
+        self
+    }
+
+    /// Present the given client certificate to the aggregator, its private key must be a
+    /// PKCS#8 PEM file
+    pub fn with_client_certificate(
+        mut self,
+        certificate_path: &Path,
+        private_key_path: &Path,
+    ) -> StdResult<Self> {
+        let certificate = std::fs::read(certificate_path).with_context(|| {
+            format!(
+                "Could not read the client certificate '{}'",
+                certificate_path.display()
+            )
+        })?;
+        let private_key = std::fs::read(private_key_path).with_context(|| {
+            format!(
+                "Could not read the client private key '{}'",
+                private_key_path.display()
+            )
+        })?;
+        self.client_identity = Some(
+            Identity::from_pkcs8_pem(&certificate, &private_key)
+                .with_context(|| "Invalid client certificate or private key")?,
+        );
+
+        Ok(self)
+    }
+
+    /// Trust the given certificate authority to authenticate the aggregator
+    pub fn with_ca_certificate(mut self, ca_certificate_path: &Path) -> StdResult<Self> {
+        let ca_certificate = std::fs::read(ca_certificate_path).with_context(|| {
+            format!(
+                "Could not read the CA certificate '{}'",
+                ca_certificate_path.display()
+            )
+        })?;
+        self.ca_certificate = Some(
+            Certificate::from_pem(&ca_certificate)
+                .with_context(|| "Invalid aggregator CA certificate")?,
+        );
+
+        Ok(self)
+    }
+
     fn prepare_http_client(&self) -> Result<Client, AggregatorClientError> {
-        let client = match &self.relay_endpoint {
-            Some(relay_endpoint) => Client::builder()
-                .proxy(
-                    Proxy::all(relay_endpoint)
-                        .map_err(|e| AggregatorClientError::ProxyCreation(anyhow!(e)))?,
-                )
-                .build()
-                .map_err(|e| AggregatorClientError::HTTPClientCreation(anyhow!(e)))?,
-            None => Client::new(),
-        };
+        let mut client_builder = Client::builder();
+        if let Some(relay_endpoint) = &self.relay_endpoint {
+            client_builder = client_builder.proxy(
+                Proxy::all(relay_endpoint)
+                    .map_err(|e| AggregatorClientError::ProxyCreation(anyhow!(e)))?,
+            );
+        }
+        if let Some(ca_certificate) = &self.ca_certificate {
+            client_builder = client_builder.add_root_certificate(ca_certificate.clone());
+        }
+        if let Some(client_identity) = &self.client_identity {
+            client_builder = client_builder.identity(client_identity.clone());
+        }
 
-        Ok(client)
+        client_builder
+            .build()
+            .map_err(|e| AggregatorClientError::HTTPClientCreation(anyhow!(e)))
+    }
+
+    /// Retrieve a registration challenge for the given party id, `None` if the aggregator does
+    /// not issue challenges
+    async fn retrieve_registration_challenge(
+        &self,
+        party_id: &PartyId,
+    ) -> Result<Option<RegistrationChallengeMessage>, AggregatorClientError> {
+        debug!(self.logger, "Retrieve registration challenge"; "party_id" => party_id);
+        let url = format!("{}/register-signer/challenge", self.aggregator_endpoint);
+        let response = self
+            .prepare_request_builder(
+                self.prepare_http_client()?
+                    .get(url.clone())
+                    .query(&[("party_id", party_id)]),
+            )
+            .send()
+            .await;
+
+        match response {
+            Ok(response) => match response.status() {
+                StatusCode::OK => Ok(Some(
+                    response
+                        .json::<RegistrationChallengeMessage>()
+                        .await
+                        .map_err(|e| AggregatorClientError::JsonParseFailed(anyhow!(e)))?,
+                )),
+                StatusCode::NOT_FOUND => Ok(None),
+                StatusCode::PRECONDITION_FAILED => Err(self.handle_api_error(&response)),
+                _ => Err(AggregatorClientError::from_response(response).await),
+            },
+            Err(err) => Err(AggregatorClientError::RemoteServerUnreachable(anyhow!(err))),
+        }
     }
 
     /// Forge a client request adding protocol version in the headers.
@@ -234,6 +338,12 @@ impl AggregatorHTTPClient {
                     .to_string(),
             )
             .header(MITHRIL_SIGNER_VERSION_HEADER, env!("CARGO_PKG_VERSION"));
+        let request_builder = match CorrelationId::current() {
+            Some(correlation_id) => {
+                request_builder.header(MITHRIL_CORRELATION_ID_HEADER, correlation_id.as_str())
+            }
+            None => request_builder,
+        };
 
         if let Some(duration) = self.timeout_duration {
             request_builder.timeout(duration)
@@ -295,9 +405,29 @@ impl AggregatorClient for AggregatorHTTPClient {
     ) -> Result<(), AggregatorClientError> {
         debug!(self.logger, "Register signer");
         let url = format!("{}/register-signer", self.aggregator_endpoint);
-        let register_signer_message =
+        let mut register_signer_message =
             ToRegisterSignerMessageAdapter::try_adapt((epoch, signer.to_owned()))
                 .map_err(|e| AggregatorClientError::Adapter(anyhow!(e)))?;
+        if let (Some(registration_challenge_signer), Some(_)) = (
+            &self.registration_challenge_signer,
+            &signer.operational_certificate,
+        ) {
+            if let Some(challenge) = self
+                .retrieve_registration_challenge(&signer.party_id)
+                .await?
+            {
+                let signature = registration_challenge_signer
+                    .sign(
+                        &challenge.challenge,
+                        &signer.verification_key,
+                        signer.kes_period.unwrap_or_default(),
+                    )
+                    .and_then(|signature| signature.to_json_hex())
+                    .map_err(AggregatorClientError::RegistrationChallengeSigning)?;
+                register_signer_message.registration_challenge = Some(challenge.challenge);
+                register_signer_message.registration_challenge_signature = Some(signature);
+            }
+        }
         let response = self
             .prepare_request_builder(self.prepare_http_client()?.post(url.clone()))
             .json(&register_signer_message)
@@ -342,9 +472,10 @@ impl AggregatorClient for AggregatorHTTPClient {
                     Ok(())
                 }
                 StatusCode::PRECONDITION_FAILED => Err(self.handle_api_error(&response)),
-                StatusCode::CONFLICT => Err(AggregatorClientError::RemoteServerLogical(anyhow!(
-                    "already registered single signatures"
-                ))),
+                StatusCode::CONFLICT => {
+                    debug!(self.logger, "Aggregator already registered those single signatures"; "signed_entity_type" => ?signed_entity_type);
+                    Ok(())
+                }
                 _ => Err(AggregatorClientError::from_response(response).await),
             },
             Err(err) => Err(AggregatorClientError::RemoteServerUnreachable(anyhow!(err))),
@@ -481,7 +612,7 @@ mod tests {
     use mithril_common::entities::Epoch;
     use mithril_common::era::{EraChecker, SupportedEra};
     use mithril_common::messages::TryFromMessageAdapter;
-    use mithril_common::test_utils::fake_data;
+    use mithril_common::test_utils::{fake_data, MithrilFixtureBuilder};
 
     use crate::test_tools::TestLogger;
 
@@ -578,6 +709,26 @@ mod tests {
         assert_eq!(message_expected, message);
     }
 
+    #[tokio::test]
+    async fn test_requests_send_the_correlation_id_of_the_scope() {
+        let (server, client) = setup_server_and_client();
+        let correlation_id = CorrelationId::generate();
+        let server_mock = server.mock(|when, then| {
+            when.path("/")
+                .header(MITHRIL_CORRELATION_ID_HEADER, correlation_id.as_str());
+            then.status(200)
+                .body(json!(AggregatorFeaturesMessage::dummy()).to_string());
+        });
+
+        correlation_id
+            .clone()
+            .scope(client.retrieve_aggregator_features())
+            .await
+            .unwrap();
+
+        server_mock.assert();
+    }
+
     #[tokio::test]
     async fn test_aggregator_features_ko_412() {
         let (server, client) = setup_server_and_client();
@@ -703,6 +854,65 @@ mod tests {
         register_signer.expect("unexpected error");
     }
 
+    #[tokio::test]
+    async fn test_register_signer_signs_the_registration_challenge() {
+        let epoch = Epoch(1);
+        let fixture = MithrilFixtureBuilder::default().with_signers(1).build();
+        let signer_fixture = &fixture.signers_fixture()[0];
+        let signer: Signer = signer_fixture.signer_with_stake.clone().into();
+        let challenge = RegistrationChallengeMessage::dummy();
+        let (server, client) = setup_server_and_client();
+        let client = client.with_registration_challenge_signer(RegistrationChallengeSigner::new(
+            signer_fixture.kes_secret_key_path().unwrap(),
+        ));
+        let _challenge_mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/register-signer/challenge")
+                .query_param("party_id", &signer.party_id);
+            then.status(200).body(json!(challenge).to_string());
+        });
+        let register_signer_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/register-signer")
+                .json_body_partial(
+                    json!({"registration_challenge": challenge.challenge}).to_string(),
+                );
+            then.status(201);
+        });
+
+        client
+            .register_signer(epoch, &signer)
+            .await
+            .expect("unexpected error");
+        register_signer_mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_register_signer_without_challenge_if_the_aggregator_does_not_issue_challenges() {
+        let epoch = Epoch(1);
+        let fixture = MithrilFixtureBuilder::default().with_signers(1).build();
+        let signer_fixture = &fixture.signers_fixture()[0];
+        let signer: Signer = signer_fixture.signer_with_stake.clone().into();
+        let (server, client) = setup_server_and_client();
+        let client = client.with_registration_challenge_signer(RegistrationChallengeSigner::new(
+            signer_fixture.kes_secret_key_path().unwrap(),
+        ));
+        let _challenge_mock = server.mock(|when, then| {
+            when.method(GET).path("/register-signer/challenge");
+            then.status(404);
+        });
+        let register_signer_mock = server.mock(|when, then| {
+            when.method(POST).path("/register-signer");
+            then.status(201);
+        });
+
+        client
+            .register_signer(epoch, &signer)
+            .await
+            .expect("unexpected error");
+        register_signer_mock.assert();
+    }
+
     #[tokio::test]
     async fn test_register_signer_ko_412() {
         let epoch = Epoch(1);
@@ -913,7 +1123,7 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_register_signatures_ko_409() {
+    async fn test_register_signatures_ok_409() {
         let single_signatures = fake_data::single_signatures((1..5).collect());
         let (server, client) = setup_server_and_client();
         let _server_mock = server.mock(|when, then| {
@@ -921,18 +1131,14 @@ mod tests {
             then.status(409);
         });
 
-        match client
+        client
             .register_signatures(
                 &SignedEntityType::dummy(),
                 &single_signatures,
                 &ProtocolMessage::default(),
             )
             .await
-            .unwrap_err()
-        {
-            AggregatorClientError::RemoteServerLogical(_) => (),
-            e => panic!("Expected Aggregator::RemoteServerLogical error, got '{e:?}'."),
-        }
+            .expect("Should not fail when status is 409 (CONFLICT)");
     }
 
     #[tokio::test]
@@ -4,7 +4,7 @@ use std::sync::Arc;
 
 use anyhow::Context;
 use async_trait::async_trait;
-use slog::{debug, Logger};
+use slog::{debug, warn, Logger};
 use tokio::{runtime::Handle, sync::Mutex, task};
 
 use mithril_common::cardano_block_scanner::{BlockScanner, ChainScannedBlocks, RawCardanoPoint};
@@ -23,6 +23,13 @@ pub trait TransactionStore: Send + Sync {
     /// Get the highest known transaction beacon
     async fn get_highest_beacon(&self) -> StdResult<Option<ChainPoint>>;
 
+    /// Get the highest chain point of the stored transactions with a block number lower or equal
+    /// to the given block number
+    async fn get_highest_beacon_below_or_equal(
+        &self,
+        block_number: BlockNumber,
+    ) -> StdResult<Option<ChainPoint>>;
+
     /// Get the highest stored block range root bounds
     async fn get_highest_block_range(&self) -> StdResult<Option<BlockRange>>;
 
@@ -57,20 +64,27 @@ pub struct CardanoTransactionsImporter {
     block_scanner: Arc<dyn BlockScanner>,
     transaction_store: Arc<dyn TransactionStore>,
     last_polled_point: Arc<Mutex<Option<RawCardanoPoint>>>,
+    rollback_safety_depth: BlockNumber,
     logger: Logger,
 }
 
 impl CardanoTransactionsImporter {
     /// Constructor
+    ///
+    /// The `rollback_safety_depth` is the number of blocks below the highest stored transaction
+    /// from which the import restarts when no point was polled yet (i.e. after a restart), since
+    /// the stored blocks above this depth may belong to a fork that was rolled back meanwhile.
     pub fn new(
         block_scanner: Arc<dyn BlockScanner>,
         transaction_store: Arc<dyn TransactionStore>,
+        rollback_safety_depth: BlockNumber,
         logger: Logger,
     ) -> Self {
         Self {
             block_scanner,
             transaction_store,
             last_polled_point: Arc::new(Mutex::new(None)),
+            rollback_safety_depth,
             logger: logger.new_with_component_name::<Self>(),
         }
     }
@@ -80,16 +94,59 @@ impl CardanoTransactionsImporter {
         highest_stored_chain_point: &Option<ChainPoint>,
     ) -> StdResult<Option<RawCardanoPoint>> {
         let last_polled_point = self.last_polled_point.lock().await.clone();
-        if last_polled_point.is_none() {
-            debug!(
-                self.logger,
-                "No last polled point available, falling back to the highest stored chain point"
-            );
+        if last_polled_point.is_some() {
+            return Ok(last_polled_point);
         }
 
-        Ok(last_polled_point.or(highest_stored_chain_point
-            .as_ref()
-            .map(RawCardanoPoint::from)))
+        debug!(
+            self.logger,
+            "No last polled point available, falling back to the highest stored chain point"
+        );
+        match highest_stored_chain_point {
+            Some(chain_point) if *self.rollback_safety_depth > 0 => {
+                self.rewind_to_safe_chain_point(chain_point).await
+            }
+            Some(chain_point) => Ok(Some(RawCardanoPoint::from(chain_point))),
+            None => Ok(None),
+        }
+    }
+
+    /// Remove the stored transactions that are not deep enough to be safe from a rollback and
+    /// return the chain point from which they must be imported again.
+    async fn rewind_to_safe_chain_point(
+        &self,
+        highest_stored_chain_point: &ChainPoint,
+    ) -> StdResult<Option<RawCardanoPoint>> {
+        let safe_block_number = highest_stored_chain_point
+            .block_number
+            .saturating_sub(*self.rollback_safety_depth);
+        let safe_chain_point = self
+            .transaction_store
+            .get_highest_beacon_below_or_equal(BlockNumber(safe_block_number))
+            .await?;
+
+        match &safe_chain_point {
+            Some(chain_point) => {
+                debug!(
+                    self.logger, "Rewinding transactions import to a chain point safe from rollbacks";
+                    "slot_number" => *chain_point.slot_number,
+                    "block_number" => *chain_point.block_number
+                );
+                self.transaction_store
+                    .remove_rolled_back_transactions_and_block_range(chain_point.slot_number)
+                    .await?;
+            }
+            None => {
+                debug!(
+                    self.logger,
+                    "No stored transaction deep enough to be safe from rollbacks, keeping the highest stored chain point";
+                    "block_number" => *highest_stored_chain_point.block_number
+                );
+                return Ok(Some(RawCardanoPoint::from(highest_stored_chain_point)));
+            }
+        }
+
+        Ok(safe_chain_point.as_ref().map(RawCardanoPoint::from))
     }
 
     async fn import_transactions(&self, up_to_beacon: BlockNumber) -> StdResult<()> {
@@ -138,6 +195,10 @@ impl CardanoTransactionsImporter {
                         .await?;
                 }
                 ChainScannedBlocks::RollBackward(slot_number) => {
+                    warn!(
+                        self.logger, "Chain rollback detected, removing transactions and block range roots from the rolled back fork";
+                        "slot_number" => *slot_number
+                    );
                     self.transaction_store
                         .remove_rolled_back_transactions_and_block_range(slot_number)
                         .await?;
@@ -255,7 +316,12 @@ mod tests {
             scanner: Arc<dyn BlockScanner>,
             transaction_store: Arc<dyn TransactionStore>,
         ) -> Self {
-            CardanoTransactionsImporter::new(scanner, transaction_store, TestLogger::stdout())
+            CardanoTransactionsImporter::new(
+                scanner,
+                transaction_store,
+                BlockNumber(0),
+                TestLogger::stdout(),
+            )
         }
     }
 
@@ -1123,6 +1189,72 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn when_no_point_polled_yet_should_reimport_blocks_above_rollback_safety_depth() {
+        let connection = cardano_tx_db_connection().unwrap();
+        let repository = Arc::new(CardanoTransactionRepository::new(Arc::new(
+            SqliteConnectionPool::build_from_connection(connection),
+        )));
+
+        let safe_blocks = build_blocks(BlockNumber(10), BlockNumber(6));
+        // Stored blocks that may belong to a fork rolled back while the signer was down
+        let orphaned_blocks = vec![
+            ScannedBlock::new(
+                "orphan_hash-16",
+                BlockNumber(16),
+                SlotNumber(1600),
+                vec!["orphan_tx-16"],
+            ),
+            ScannedBlock::new(
+                "orphan_hash-17",
+                BlockNumber(17),
+                SlotNumber(1700),
+                vec!["orphan_tx-17"],
+            ),
+        ];
+        repository
+            .store_transactions(into_transactions(
+                &[safe_blocks.clone(), orphaned_blocks].concat(),
+            ))
+            .await
+            .unwrap();
+
+        let new_fork_blocks = build_blocks(BlockNumber(16), BlockNumber(4));
+        let up_to_block_number = BlockNumber(20);
+        let importer = {
+            let mut scanner_mock = MockBlockScannerImpl::new();
+            let expected_start_point = RawCardanoPoint::new(SlotNumber(1500), "block_hash-15");
+            let new_fork_blocks = new_fork_blocks.clone();
+            scanner_mock
+                .expect_scan()
+                .withf(move |from, until| {
+                    from == &Some(expected_start_point.clone()) && *until == up_to_block_number
+                })
+                .return_once(move |_, _| {
+                    Ok(Box::new(
+                        DumbBlockStreamer::new().forwards(vec![new_fork_blocks]),
+                    ))
+                });
+            CardanoTransactionsImporter::new(
+                Arc::new(scanner_mock),
+                repository.clone(),
+                BlockNumber(2),
+                TestLogger::stdout(),
+            )
+        };
+
+        importer
+            .import_transactions(up_to_block_number)
+            .await
+            .expect("Transactions Importer should succeed");
+
+        let stored_transactions = repository.get_all().await.unwrap();
+        assert_eq!(
+            into_transactions(&[safe_blocks, new_fork_blocks].concat()),
+            stored_transactions
+        );
+    }
+
     #[tokio::test]
     async fn test_import_is_non_blocking() {
         static COUNTER: AtomicUsize = AtomicUsize::new(0);
@@ -1175,6 +1307,14 @@ mod tests {
                 Ok(None)
             }
 
+            async fn get_highest_beacon_below_or_equal(
+                &self,
+                _: BlockNumber,
+            ) -> StdResult<Option<ChainPoint>> {
+                self.block_thread();
+                Ok(None)
+            }
+
             async fn get_highest_block_range(&self) -> StdResult<Option<BlockRange>> {
                 self.block_thread();
                 Ok(None)
@@ -110,10 +110,17 @@ impl SignerCertifierService {
             .signed_entity_type_lock
             .filter_unlocked_entries(signed_entity_types)
             .await;
+        let unlocked_signed_entities_count = unlocked_signed_entities.len();
         let not_already_signed_entities = self
             .signed_beacon_store
             .filter_out_already_signed_entities(unlocked_signed_entities)
             .await?;
+        if not_already_signed_entities.len() < unlocked_signed_entities_count {
+            debug!(
+                self.logger, "Skipping signed entities already signed by this signer";
+                "already_signed_count" => unlocked_signed_entities_count - not_already_signed_entities.len()
+            );
+        }
 
         Ok(not_already_signed_entities)
     }
@@ -175,8 +182,12 @@ mod tests {
         CardanoTransactionsSigningConfig, ChainPoint, Epoch, ProtocolMessagePartKey,
         SignedEntityTypeDiscriminants,
     };
-    use mithril_common::test_utils::fake_data;
+    use std::collections::BTreeSet;
+
+    use mithril_common::test_utils::{fake_data, TempDir};
 
+    use crate::database::repository::SignedBeaconRepository;
+    use crate::database::test_helper::main_db_file_connection;
     use crate::services::MockSingleSigner;
 
     use super::{tests::tests_tooling::*, *};
@@ -346,6 +357,62 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn beacons_signed_before_a_restart_are_not_returned_anymore() {
+        let db_path = TempDir::create(
+            "signer_certifier",
+            "beacons_signed_before_a_restart_are_not_returned_anymore",
+        )
+        .join("signer.sqlite3");
+        let time_point = TimePoint::new(3, 14, ChainPoint::dummy());
+        let build_certifier_service = || {
+            let connection = Arc::new(main_db_file_connection(&db_path).unwrap());
+            SignerCertifierService {
+                signed_beacon_store: Arc::new(SignedBeaconRepository::new(connection, None)),
+                signed_entity_config_provider: Arc::new(DumbSignedEntityConfigProvider::new(
+                    CardanoTransactionsSigningConfig::dummy(),
+                    BTreeSet::from([SignedEntityTypeDiscriminants::MithrilStakeDistribution]),
+                )),
+                single_signer: {
+                    let mut single_signer = MockSingleSigner::new();
+                    single_signer
+                        .expect_compute_single_signatures()
+                        .returning(|_| Ok(None));
+                    Arc::new(single_signer)
+                },
+                ..SignerCertifierService::dumb_dependencies()
+            }
+        };
+
+        let certifier_service = build_certifier_service();
+        // The default signed entity types are always allowed, sign all of them before the restart
+        let mut signed_beacons = 0;
+        while let Some(beacon_to_sign) = certifier_service
+            .get_beacon_to_sign(time_point.clone())
+            .await
+            .unwrap()
+        {
+            certifier_service
+                .compute_publish_single_signature(&beacon_to_sign, &ProtocolMessage::new())
+                .await
+                .unwrap();
+            signed_beacons += 1;
+        }
+        assert!(
+            signed_beacons > 0,
+            "There should be beacons to sign before the restart"
+        );
+        drop(certifier_service);
+
+        let restarted_certifier_service = build_certifier_service();
+        let beacon_to_sign = restarted_certifier_service
+            .get_beacon_to_sign(time_point)
+            .await
+            .unwrap();
+
+        assert_eq!(None, beacon_to_sign);
+    }
+
     #[tokio::test]
     async fn compute_publish_single_signature_success_if_a_signature_was_issued() {
         let protocol_message = {
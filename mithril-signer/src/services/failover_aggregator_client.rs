@@ -0,0 +1,244 @@
+use async_trait::async_trait;
+use slog::{info, warn, Logger};
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use mithril_common::entities::{
+    Epoch, ProtocolMessage, SignedEntityType, Signer, SingleSignatures,
+};
+use mithril_common::logging::LoggerExtensions;
+use mithril_common::messages::AggregatorFeaturesMessage;
+
+use crate::entities::SignerEpochSettings;
+use crate::services::{AggregatorClient, AggregatorClientError};
+
+/// Aggregator client that forwards the calls to a prioritized list of aggregators.
+///
+/// The calls are sent to the latest aggregator that answered successfully, and to the
+/// other aggregators in priority order if it can't be reached.
+pub struct FailoverAggregatorClient {
+    aggregator_clients: Vec<(String, Arc<dyn AggregatorClient>)>,
+    healthy_client_index: AtomicUsize,
+    logger: Logger,
+}
+
+impl FailoverAggregatorClient {
+    /// Create a new instance from a list of aggregator clients, associated with their
+    /// endpoint and ordered by priority.
+    pub fn new(
+        aggregator_clients: Vec<(String, Arc<dyn AggregatorClient>)>,
+        logger: Logger,
+    ) -> Self {
+        Self {
+            aggregator_clients,
+            healthy_client_index: AtomicUsize::new(0),
+            logger: logger.new_with_component_name::<Self>(),
+        }
+    }
+
+    /// Endpoint of the aggregator that will be called first.
+    pub fn healthy_endpoint(&self) -> &str {
+        &self.aggregator_clients[self.healthy_client_index.load(Ordering::Relaxed)].0
+    }
+
+    /// Return true if the call should be retried on the next aggregator.
+    fn should_failover(error: &AggregatorClientError) -> bool {
+        !matches!(error, AggregatorClientError::RemoteServerLogical(_))
+    }
+
+    async fn call_with_failover<T, F, Fut>(
+        &self,
+        operation: &str,
+        call: F,
+    ) -> Result<T, AggregatorClientError>
+    where
+        F: Fn(Arc<dyn AggregatorClient>) -> Fut,
+        Fut: Future<Output = Result<T, AggregatorClientError>>,
+    {
+        let healthy_client_index = self.healthy_client_index.load(Ordering::Relaxed);
+        let client_indexes = std::iter::once(healthy_client_index).chain(
+            (0..self.aggregator_clients.len()).filter(|index| *index != healthy_client_index),
+        );
+        let mut last_error = None;
+
+        for index in client_indexes {
+            let (endpoint, client) = &self.aggregator_clients[index];
+            match call(client.clone()).await {
+                Ok(result) => {
+                    if index != healthy_client_index {
+                        info!(
+                            self.logger, "Failing over to aggregator";
+                            "operation" => operation, "aggregator_endpoint" => endpoint
+                        );
+                        self.healthy_client_index.store(index, Ordering::Relaxed);
+                    }
+                    return Ok(result);
+                }
+                Err(error) if Self::should_failover(&error) => {
+                    warn!(
+                        self.logger, "Aggregator call failed, trying the next aggregator";
+                        "operation" => operation, "aggregator_endpoint" => endpoint, "error" => ?error
+                    );
+                    last_error = Some(error);
+                }
+                Err(error) => return Err(error),
+            }
+        }
+
+        Err(last_error.expect("At least one aggregator client must be configured"))
+    }
+}
+
+#[async_trait]
+impl AggregatorClient for FailoverAggregatorClient {
+    async fn retrieve_epoch_settings(
+        &self,
+    ) -> Result<Option<SignerEpochSettings>, AggregatorClientError> {
+        self.call_with_failover("retrieve_epoch_settings", |client| async move {
+            client.retrieve_epoch_settings().await
+        })
+        .await
+    }
+
+    async fn register_signer(
+        &self,
+        epoch: Epoch,
+        signer: &Signer,
+    ) -> Result<(), AggregatorClientError> {
+        self.call_with_failover("register_signer", |client| async move {
+            client.register_signer(epoch, signer).await
+        })
+        .await
+    }
+
+    async fn register_signatures(
+        &self,
+        signed_entity_type: &SignedEntityType,
+        signatures: &SingleSignatures,
+        protocol_message: &ProtocolMessage,
+    ) -> Result<(), AggregatorClientError> {
+        self.call_with_failover("register_signatures", |client| async move {
+            client
+                .register_signatures(signed_entity_type, signatures, protocol_message)
+                .await
+        })
+        .await
+    }
+
+    async fn retrieve_aggregator_features(
+        &self,
+    ) -> Result<AggregatorFeaturesMessage, AggregatorClientError> {
+        self.call_with_failover("retrieve_aggregator_features", |client| async move {
+            client.retrieve_aggregator_features().await
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+
+    use crate::services::MockAggregatorClient;
+    use crate::test_tools::TestLogger;
+
+    use super::*;
+
+    fn aggregator_client(
+        endpoint: &str,
+        expected_calls: usize,
+        result: fn() -> Result<AggregatorFeaturesMessage, AggregatorClientError>,
+    ) -> (String, Arc<dyn AggregatorClient>) {
+        let mut client = MockAggregatorClient::new();
+        client
+            .expect_retrieve_aggregator_features()
+            .times(expected_calls)
+            .returning(result);
+
+        (endpoint.to_string(), Arc::new(client))
+    }
+
+    fn unreachable() -> Result<AggregatorFeaturesMessage, AggregatorClientError> {
+        Err(AggregatorClientError::RemoteServerUnreachable(anyhow!(
+            "unreachable"
+        )))
+    }
+
+    fn reachable() -> Result<AggregatorFeaturesMessage, AggregatorClientError> {
+        Ok(AggregatorFeaturesMessage::dummy())
+    }
+
+    #[tokio::test]
+    async fn call_the_primary_aggregator_first() {
+        let client = FailoverAggregatorClient::new(
+            vec![
+                aggregator_client("primary", 1, reachable),
+                aggregator_client("secondary", 0, reachable),
+            ],
+            TestLogger::stdout(),
+        );
+
+        client.retrieve_aggregator_features().await.unwrap();
+
+        assert_eq!("primary", client.healthy_endpoint());
+    }
+
+    #[tokio::test]
+    async fn failover_to_the_next_aggregator_and_remember_it() {
+        let client = FailoverAggregatorClient::new(
+            vec![
+                aggregator_client("primary", 1, unreachable),
+                aggregator_client("secondary", 2, reachable),
+            ],
+            TestLogger::stdout(),
+        );
+
+        client.retrieve_aggregator_features().await.unwrap();
+        assert_eq!("secondary", client.healthy_endpoint());
+
+        client.retrieve_aggregator_features().await.unwrap();
+        assert_eq!("secondary", client.healthy_endpoint());
+    }
+
+    #[tokio::test]
+    async fn fail_with_the_last_error_if_no_aggregator_can_be_reached() {
+        let client = FailoverAggregatorClient::new(
+            vec![
+                aggregator_client("primary", 1, unreachable),
+                aggregator_client("secondary", 1, unreachable),
+            ],
+            TestLogger::stdout(),
+        );
+
+        let error = client.retrieve_aggregator_features().await.unwrap_err();
+
+        assert!(matches!(
+            error,
+            AggregatorClientError::RemoteServerUnreachable(_)
+        ));
+        assert_eq!("primary", client.healthy_endpoint());
+    }
+
+    #[tokio::test]
+    async fn do_not_failover_on_logical_errors() {
+        let client = FailoverAggregatorClient::new(
+            vec![
+                aggregator_client("primary", 1, || {
+                    Err(AggregatorClientError::RemoteServerLogical(anyhow!(
+                        "invalid request"
+                    )))
+                }),
+                aggregator_client("secondary", 0, reachable),
+            ],
+            TestLogger::stdout(),
+        );
+
+        let error = client.retrieve_aggregator_features().await.unwrap_err();
+
+        assert!(matches!(
+            error,
+            AggregatorClientError::RemoteServerLogical(_)
+        ));
+    }
+}
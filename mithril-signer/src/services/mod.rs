@@ -3,8 +3,11 @@
 //! This module regroups services. Services are adapters in charge of the different  bounded contexts of the application:
 //!
 //! * Aggregator Client: communicate with the Aggregator
+//! * Failover Aggregator Client: communicate with the first reachable of several Aggregators
+//! * Retry Aggregator Client: retry the failed calls to the Aggregator
 //! * Cardano Transactions: handle Cardano transactions (import, preload, etc.)
 //! * Single Signer: create single signatures
+//! * Systemd Notifier: notify systemd of the liveness of the signer
 //! * Upkeep: perform maintenance tasks
 //!
 //! Each service is defined by a public API (a trait) that is used in the controllers (runtimes).
@@ -13,8 +16,11 @@ mod aggregator_client;
 mod cardano_transactions;
 mod certifier;
 mod epoch_service;
+mod failover_aggregator_client;
+mod retry_aggregator_client;
 mod signable_builder;
 mod single_signer;
+mod systemd_notifier;
 mod upkeep_service;
 
 #[cfg(test)]
@@ -23,6 +29,9 @@ pub use aggregator_client::*;
 pub use cardano_transactions::*;
 pub use certifier::*;
 pub use epoch_service::*;
+pub use failover_aggregator_client::*;
+pub use retry_aggregator_client::*;
 pub use signable_builder::*;
 pub use single_signer::*;
+pub use systemd_notifier::*;
 pub use upkeep_service::*;
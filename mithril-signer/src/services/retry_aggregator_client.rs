@@ -0,0 +1,266 @@
+use async_trait::async_trait;
+use rand_core::{OsRng, RngCore};
+use slog::{warn, Logger};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use mithril_common::entities::{
+    Epoch, ProtocolMessage, SignedEntityType, Signer, SingleSignatures,
+};
+use mithril_common::logging::LoggerExtensions;
+use mithril_common::messages::AggregatorFeaturesMessage;
+
+use crate::entities::SignerEpochSettings;
+use crate::services::{AggregatorClient, AggregatorClientError};
+
+/// Policy used to retry the failed calls to the aggregator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AggregatorClientRetryPolicy {
+    /// Maximum number of attempts of a call, including the first one.
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled at each following retry.
+    pub initial_backoff: Duration,
+    /// Maximum delay between two attempts.
+    pub max_backoff: Duration,
+    /// Maximum random delay added to each backoff, so that signers don't retry all at once.
+    pub max_jitter: Duration,
+}
+
+impl AggregatorClientRetryPolicy {
+    /// Delay to wait before the given retry, starting at 1, without the jitter.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2_u32.saturating_pow(retry.saturating_sub(1)))
+            .min(self.max_backoff)
+    }
+
+    fn jitter(&self) -> Duration {
+        let max_jitter_millis = self.max_jitter.as_millis() as u64;
+        if max_jitter_millis == 0 {
+            return Duration::ZERO;
+        }
+
+        Duration::from_millis(OsRng.next_u64() % (max_jitter_millis + 1))
+    }
+}
+
+/// Aggregator client that retries the failed epoch settings retrievals, signer registrations
+/// and signature registrations according to a [AggregatorClientRetryPolicy].
+pub struct RetryAggregatorClient {
+    aggregator_client: Arc<dyn AggregatorClient>,
+    retry_policy: AggregatorClientRetryPolicy,
+    logger: Logger,
+}
+
+impl RetryAggregatorClient {
+    /// Create a new instance
+    pub fn new(
+        aggregator_client: Arc<dyn AggregatorClient>,
+        retry_policy: AggregatorClientRetryPolicy,
+        logger: Logger,
+    ) -> Self {
+        Self {
+            aggregator_client,
+            retry_policy,
+            logger: logger.new_with_component_name::<Self>(),
+        }
+    }
+
+    /// Return true if the call may succeed if it is attempted again.
+    fn is_retryable(error: &AggregatorClientError) -> bool {
+        matches!(
+            error,
+            AggregatorClientError::RemoteServerTechnical(_)
+                | AggregatorClientError::RemoteServerUnreachable(_)
+                | AggregatorClientError::UnhandledStatusCode(_, _)
+                | AggregatorClientError::IOError(_)
+        )
+    }
+
+    async fn call_with_retry<T, F, Fut>(
+        &self,
+        operation: &str,
+        call: F,
+    ) -> Result<T, AggregatorClientError>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T, AggregatorClientError>>,
+    {
+        let mut attempt = 1;
+        loop {
+            match call().await {
+                Err(error)
+                    if Self::is_retryable(&error) && attempt < self.retry_policy.max_attempts =>
+                {
+                    let delay = self.retry_policy.backoff(attempt) + self.retry_policy.jitter();
+                    warn!(
+                        self.logger, "Aggregator call failed, retrying";
+                        "operation" => operation, "attempt" => attempt, "delay" => ?delay, "error" => ?error
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl AggregatorClient for RetryAggregatorClient {
+    async fn retrieve_epoch_settings(
+        &self,
+    ) -> Result<Option<SignerEpochSettings>, AggregatorClientError> {
+        self.call_with_retry("retrieve_epoch_settings", || {
+            self.aggregator_client.retrieve_epoch_settings()
+        })
+        .await
+    }
+
+    async fn register_signer(
+        &self,
+        epoch: Epoch,
+        signer: &Signer,
+    ) -> Result<(), AggregatorClientError> {
+        self.call_with_retry("register_signer", || {
+            self.aggregator_client.register_signer(epoch, signer)
+        })
+        .await
+    }
+
+    async fn register_signatures(
+        &self,
+        signed_entity_type: &SignedEntityType,
+        signatures: &SingleSignatures,
+        protocol_message: &ProtocolMessage,
+    ) -> Result<(), AggregatorClientError> {
+        self.call_with_retry("register_signatures", || {
+            self.aggregator_client.register_signatures(
+                signed_entity_type,
+                signatures,
+                protocol_message,
+            )
+        })
+        .await
+    }
+
+    async fn retrieve_aggregator_features(
+        &self,
+    ) -> Result<AggregatorFeaturesMessage, AggregatorClientError> {
+        self.aggregator_client.retrieve_aggregator_features().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+    use mockall::Sequence;
+
+    use mithril_common::test_utils::fake_data;
+
+    use crate::services::MockAggregatorClient;
+    use crate::test_tools::TestLogger;
+
+    use super::*;
+
+    fn retry_policy(max_attempts: u32) -> AggregatorClientRetryPolicy {
+        AggregatorClientRetryPolicy {
+            max_attempts,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(2),
+            max_jitter: Duration::from_millis(1),
+        }
+    }
+
+    #[test]
+    fn backoff_is_doubled_at_each_retry_up_to_the_maximum() {
+        let policy = AggregatorClientRetryPolicy {
+            max_attempts: 10,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(500),
+            max_jitter: Duration::ZERO,
+        };
+
+        assert_eq!(Duration::from_millis(100), policy.backoff(1));
+        assert_eq!(Duration::from_millis(200), policy.backoff(2));
+        assert_eq!(Duration::from_millis(400), policy.backoff(3));
+        assert_eq!(Duration::from_millis(500), policy.backoff(4));
+        assert_eq!(Duration::from_millis(500), policy.backoff(40));
+    }
+
+    #[tokio::test]
+    async fn retry_until_the_call_succeeds() {
+        let mut sequence = Sequence::new();
+        let mut aggregator_client = MockAggregatorClient::new();
+        aggregator_client
+            .expect_retrieve_epoch_settings()
+            .times(2)
+            .in_sequence(&mut sequence)
+            .returning(|| {
+                Err(AggregatorClientError::RemoteServerUnreachable(anyhow!(
+                    "unreachable"
+                )))
+            });
+        aggregator_client
+            .expect_retrieve_epoch_settings()
+            .once()
+            .in_sequence(&mut sequence)
+            .returning(|| Ok(Some(SignerEpochSettings::dummy())));
+        let client = RetryAggregatorClient::new(
+            Arc::new(aggregator_client),
+            retry_policy(3),
+            TestLogger::stdout(),
+        );
+
+        let epoch_settings = client.retrieve_epoch_settings().await.unwrap();
+
+        assert_eq!(Some(SignerEpochSettings::dummy()), epoch_settings);
+    }
+
+    #[tokio::test]
+    async fn stop_retrying_after_the_maximum_number_of_attempts() {
+        let mut aggregator_client = MockAggregatorClient::new();
+        aggregator_client
+            .expect_retrieve_epoch_settings()
+            .times(3)
+            .returning(|| {
+                Err(AggregatorClientError::RemoteServerTechnical(anyhow!(
+                    "technical error"
+                )))
+            });
+        let client = RetryAggregatorClient::new(
+            Arc::new(aggregator_client),
+            retry_policy(3),
+            TestLogger::stdout(),
+        );
+
+        client
+            .retrieve_epoch_settings()
+            .await
+            .expect_err("The call should fail after the maximum number of attempts");
+    }
+
+    #[tokio::test]
+    async fn do_not_retry_errors_that_will_not_be_solved_by_retrying() {
+        let mut aggregator_client = MockAggregatorClient::new();
+        aggregator_client
+            .expect_register_signer()
+            .once()
+            .returning(|_, _| {
+                Err(AggregatorClientError::RemoteServerLogical(anyhow!(
+                    "invalid signer"
+                )))
+            });
+        let client = RetryAggregatorClient::new(
+            Arc::new(aggregator_client),
+            retry_policy(3),
+            TestLogger::stdout(),
+        );
+
+        client
+            .register_signer(Epoch(1), &fake_data::signers(1)[0])
+            .await
+            .expect_err("The call should fail without being retried");
+    }
+}
@@ -0,0 +1,194 @@
+use anyhow::Context;
+use slog::{debug, info, warn, Logger};
+use std::os::unix::net::UnixDatagram;
+use std::time::Duration;
+use tokio::sync::watch;
+
+use mithril_common::logging::LoggerExtensions;
+use mithril_common::StdResult;
+
+use crate::SignerState;
+
+/// Environment variable set by systemd with the path of its notification socket.
+const NOTIFY_SOCKET_ENV_VAR: &str = "NOTIFY_SOCKET";
+
+/// Environment variable set by systemd with the watchdog timeout, in microseconds.
+const WATCHDOG_USEC_ENV_VAR: &str = "WATCHDOG_USEC";
+
+/// Environment variable set by systemd with the pid of the process that must send the watchdog pings.
+const WATCHDOG_PID_ENV_VAR: &str = "WATCHDOG_PID";
+
+/// Send `sd_notify` messages to systemd, so it can detect when the signer is ready and restart
+/// it when it hangs.
+///
+/// It is only enabled when the signer is started by a systemd service of type `notify`, which
+/// sets the `NOTIFY_SOCKET` environment variable.
+pub struct SystemdNotifier {
+    socket_path: String,
+    watchdog_timeout: Option<Duration>,
+    logger: Logger,
+}
+
+impl SystemdNotifier {
+    /// Create a new instance from the environment variables set by systemd, returns `None` if
+    /// the signer is not run by a systemd service of type `notify`.
+    pub fn from_env(logger: Logger) -> Option<Self> {
+        let socket_path = std::env::var(NOTIFY_SOCKET_ENV_VAR).ok()?;
+        let watchdog_pid = std::env::var(WATCHDOG_PID_ENV_VAR)
+            .ok()
+            .and_then(|pid| pid.parse::<u32>().ok());
+        let watchdog_pid_matches = !matches!(watchdog_pid, Some(pid) if pid != std::process::id());
+        let watchdog_timeout = std::env::var(WATCHDOG_USEC_ENV_VAR)
+            .ok()
+            .and_then(|usec| usec.parse::<u64>().ok())
+            .filter(|_| watchdog_pid_matches)
+            .map(Duration::from_micros);
+
+        Some(Self::new(&socket_path, watchdog_timeout, logger))
+    }
+
+    /// Create a new instance sending its messages to the given socket.
+    pub fn new(socket_path: &str, watchdog_timeout: Option<Duration>, logger: Logger) -> Self {
+        Self {
+            socket_path: socket_path.to_string(),
+            watchdog_timeout,
+            logger: logger.new_with_component_name::<Self>(),
+        }
+    }
+
+    /// Watchdog timeout configured in the systemd service, if any.
+    pub fn watchdog_timeout(&self) -> Option<Duration> {
+        self.watchdog_timeout
+    }
+
+    /// Send the given state, i.e. `READY=1`, to systemd.
+    pub fn notify(&self, state: &str) -> StdResult<()> {
+        let socket = UnixDatagram::unbound()?;
+        match self.socket_path.strip_prefix('@') {
+            #[cfg(target_os = "linux")]
+            Some(abstract_name) => {
+                use std::os::linux::net::SocketAddrExt;
+                let address =
+                    std::os::unix::net::SocketAddr::from_abstract_name(abstract_name.as_bytes())?;
+                socket.send_to_addr(state.as_bytes(), &address)
+            }
+            _ => socket.send_to(state.as_bytes(), &self.socket_path),
+        }
+        .with_context(|| format!("Could not notify systemd on socket '{}'", self.socket_path))?;
+        debug!(self.logger, "Notified systemd"; "state" => state);
+
+        Ok(())
+    }
+
+    /// Notify systemd that the signer has started.
+    pub fn notify_ready(&self) -> StdResult<()> {
+        self.notify("READY=1")
+    }
+
+    /// Send a keep-alive ping to the systemd watchdog.
+    pub fn notify_watchdog(&self) -> StdResult<()> {
+        self.notify("WATCHDOG=1")
+    }
+
+    /// Notify systemd that the signer is stopping.
+    pub fn notify_stopping(&self) -> StdResult<()> {
+        self.notify("STOPPING=1")
+    }
+
+    /// Notify systemd after each successful cycle of the state machine, `READY=1` after the
+    /// first one and `WATCHDOG=1` after all of them.
+    ///
+    /// The watchdog timeout of the systemd service must be larger than the run interval of the
+    /// signer, otherwise systemd restarts it between two cycles.
+    pub async fn run_forever(&self, mut state_receiver: watch::Receiver<SignerState>) {
+        info!(
+            self.logger, "Notifying systemd after each successful cycle";
+            "socket" => &self.socket_path, "watchdog_timeout" => ?self.watchdog_timeout
+        );
+        let mut is_ready = false;
+
+        while state_receiver.changed().await.is_ok() {
+            if !is_ready {
+                is_ready = self
+                    .notify_ready()
+                    .inspect_err(|error| warn!(self.logger, "Systemd notification failed"; "error" => ?error))
+                    .is_ok();
+            }
+            if self.watchdog_timeout.is_some() {
+                if let Err(error) = self.notify_watchdog() {
+                    warn!(self.logger, "Systemd watchdog notification failed"; "error" => ?error);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mithril_common::entities::Epoch;
+    use mithril_common::test_utils::TempDir;
+
+    use crate::test_tools::TestLogger;
+
+    use super::*;
+
+    fn bind_notify_socket(test_name: &str) -> (String, UnixDatagram) {
+        let socket_path = TempDir::create("signer_systemd_notifier", test_name).join("notify.sock");
+        let socket = UnixDatagram::bind(&socket_path).unwrap();
+        socket
+            .set_read_timeout(Some(Duration::from_secs(1)))
+            .unwrap();
+
+        (socket_path.to_string_lossy().to_string(), socket)
+    }
+
+    fn receive(socket: &UnixDatagram) -> String {
+        let mut buffer = [0; 64];
+        let size = socket.recv(&mut buffer).unwrap();
+
+        String::from_utf8_lossy(&buffer[..size]).to_string()
+    }
+
+    #[test]
+    fn notify_sends_the_state_to_the_socket() {
+        let (socket_path, socket) = bind_notify_socket("notify_sends_the_state_to_the_socket");
+        let notifier = SystemdNotifier::new(&socket_path, None, TestLogger::stdout());
+
+        notifier.notify_ready().unwrap();
+
+        assert_eq!("READY=1", receive(&socket));
+    }
+
+    #[test]
+    fn notify_fails_if_the_socket_does_not_exist() {
+        let notifier =
+            SystemdNotifier::new("/not/existing/notify.sock", None, TestLogger::stdout());
+
+        notifier
+            .notify_ready()
+            .expect_err("Notifying a not existing socket should fail");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn notify_ready_then_watchdog_after_each_state_change() {
+        let (socket_path, socket) =
+            bind_notify_socket("notify_ready_then_watchdog_after_each_state_change");
+        let notifier = SystemdNotifier::new(
+            &socket_path,
+            Some(Duration::from_secs(30)),
+            TestLogger::stdout(),
+        );
+        let (state_sender, state_receiver) = watch::channel(SignerState::Init);
+
+        let notifier_task = tokio::spawn(async move { notifier.run_forever(state_receiver).await });
+        state_sender.send_replace(SignerState::Unregistered { epoch: Epoch(1) });
+        assert_eq!("READY=1", receive(&socket));
+        assert_eq!("WATCHDOG=1", receive(&socket));
+
+        state_sender.send_replace(SignerState::Unregistered { epoch: Epoch(1) });
+        assert_eq!("WATCHDOG=1", receive(&socket));
+
+        drop(state_sender);
+        notifier_task.await.unwrap();
+    }
+}
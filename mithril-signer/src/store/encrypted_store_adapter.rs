@@ -0,0 +1,463 @@
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use anyhow::{anyhow, Context};
+use argon2::Argon2;
+use async_trait::async_trait;
+use rand_core::{OsRng, RngCore};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::marker::PhantomData;
+
+use mithril_common::StdResult;
+use mithril_persistence::store::adapter::{AdapterError, StoreAdapter};
+
+/// Name of the cipher used to encrypt the records.
+const CIPHER: &str = "aes-256-gcm";
+
+/// Size of the salt used to derive the encryption key of a record.
+const SALT_SIZE: usize = 16;
+
+/// Size of the nonce used to encrypt a record.
+const NONCE_SIZE: usize = 12;
+
+/// Provider of the keys used to encrypt the records of an [EncryptedStoreAdapter].
+///
+/// Implement it to fetch the keys from a key management service.
+pub trait StoreEncryptionKeyProvider: Sync + Send {
+    /// Return the 256 bits key used to encrypt the records salted with the given salt.
+    fn derive_key(&self, salt: &[u8]) -> StdResult<[u8; 32]>;
+}
+
+/// [StoreEncryptionKeyProvider] deriving the keys from a passphrase with Argon2id.
+pub struct PassphraseKeyProvider {
+    passphrase: String,
+}
+
+impl PassphraseKeyProvider {
+    /// Create a new instance
+    pub fn new(passphrase: &str) -> Self {
+        Self {
+            passphrase: passphrase.to_string(),
+        }
+    }
+}
+
+impl StoreEncryptionKeyProvider for PassphraseKeyProvider {
+    fn derive_key(&self, salt: &[u8]) -> StdResult<[u8; 32]> {
+        let mut key = [0u8; 32];
+        Argon2::default()
+            .hash_password_into(self.passphrase.as_bytes(), salt, &mut key)
+            .map_err(|e| anyhow!("Could not derive the store encryption key: {e}"))?;
+
+        Ok(key)
+    }
+}
+
+/// A record encrypted at rest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncryptedRecord {
+    cipher: String,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+impl EncryptedRecord {
+    /// Encrypt the given plaintext with a key derived from a random salt, the associated data
+    /// is authenticated but not stored.
+    pub fn encrypt(
+        key_provider: &dyn StoreEncryptionKeyProvider,
+        plaintext: &[u8],
+        associated_data: &[u8],
+    ) -> StdResult<Self> {
+        let mut salt = [0u8; SALT_SIZE];
+        let mut nonce = [0u8; NONCE_SIZE];
+        OsRng.fill_bytes(&mut salt);
+        OsRng.fill_bytes(&mut nonce);
+
+        let ciphertext = Self::cipher(key_provider, &salt)?
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: plaintext,
+                    aad: associated_data,
+                },
+            )
+            .map_err(|e| anyhow!("Could not encrypt the record: {e}"))?;
+
+        Ok(Self {
+            cipher: CIPHER.to_string(),
+            salt: hex::encode(salt),
+            nonce: hex::encode(nonce),
+            ciphertext: hex::encode(ciphertext),
+        })
+    }
+
+    /// Decrypt the record, the associated data must be the one given at its encryption.
+    pub fn decrypt(
+        &self,
+        key_provider: &dyn StoreEncryptionKeyProvider,
+        associated_data: &[u8],
+    ) -> StdResult<Vec<u8>> {
+        if self.cipher != CIPHER {
+            return Err(anyhow!("Unsupported record cipher: '{}'", self.cipher));
+        }
+        let nonce = hex::decode(&self.nonce)?;
+        if nonce.len() != NONCE_SIZE {
+            return Err(anyhow!("Invalid record nonce size: {}", nonce.len()));
+        }
+
+        Self::cipher(key_provider, &hex::decode(&self.salt)?)?
+            .decrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &hex::decode(&self.ciphertext)?,
+                    aad: associated_data,
+                },
+            )
+            .map_err(|_| anyhow!("Could not decrypt the record, the encryption key may be wrong"))
+    }
+
+    fn cipher(key_provider: &dyn StoreEncryptionKeyProvider, salt: &[u8]) -> StdResult<Aes256Gcm> {
+        let key = key_provider.derive_key(salt)?;
+
+        Ok(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key)))
+    }
+}
+
+/// A record as stored by an [EncryptedStoreAdapter], records stored before the encryption was
+/// enabled are kept in plaintext until they are migrated.
+///
+/// The plaintext records can only be migrated: they are rejected when read through the
+/// [EncryptedStoreAdapter], so that a record written in plaintext in an encrypted store is not
+/// trusted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum StoredRecord {
+    /// Encrypted record
+    Encrypted(EncryptedRecord),
+    /// Record stored before the encryption was enabled
+    Plaintext(serde_json::Value),
+}
+
+/// A [StoreAdapter] that encrypts the records with AES-256-GCM before storing them in the
+/// given adapter.
+///
+/// The key of each record is authenticated with it, so an encrypted record can't be moved to
+/// another key. The records stored in plaintext must be encrypted with
+/// [migrate_plaintext_records][Self::migrate_plaintext_records] before being read.
+pub struct EncryptedStoreAdapter<K, V> {
+    adapter: Box<dyn StoreAdapter<Key = K, Record = StoredRecord>>,
+    key_provider: Box<dyn StoreEncryptionKeyProvider>,
+    _record: PhantomData<V>,
+}
+
+impl<K, V> EncryptedStoreAdapter<K, V>
+where
+    K: Serialize + Send + Sync,
+    V: Serialize + DeserializeOwned + Send + Sync,
+{
+    /// Create a new instance
+    pub fn new(
+        adapter: Box<dyn StoreAdapter<Key = K, Record = StoredRecord>>,
+        key_provider: Box<dyn StoreEncryptionKeyProvider>,
+    ) -> Self {
+        Self {
+            adapter,
+            key_provider,
+            _record: PhantomData,
+        }
+    }
+
+    /// Encrypt all the records stored in plaintext, returns the number of migrated records.
+    pub async fn migrate_plaintext_records(&mut self) -> StdResult<usize> {
+        let plaintext_records: Vec<(K, V)> = self
+            .adapter
+            .get_last_n_records(usize::MAX)
+            .await?
+            .into_iter()
+            .filter_map(|(key, record)| match record {
+                StoredRecord::Plaintext(value) => Some((key, value)),
+                StoredRecord::Encrypted(_) => None,
+            })
+            .map(|(key, value)| Ok((key, serde_json::from_value(value)?)))
+            .collect::<StdResult<_>>()
+            .with_context(|| "Could not parse the plaintext records to migrate")?;
+
+        for (key, record) in &plaintext_records {
+            self.store_record(key, record).await?;
+        }
+
+        Ok(plaintext_records.len())
+    }
+
+    fn encrypt(&self, key: &K, record: &V) -> StdResult<EncryptedRecord> {
+        EncryptedRecord::encrypt(
+            self.key_provider.as_ref(),
+            &serde_json::to_vec(record)?,
+            &serde_json::to_vec(key)?,
+        )
+    }
+
+    fn decrypt(&self, key: &K, record: StoredRecord) -> StdResult<V> {
+        match record {
+            StoredRecord::Plaintext(_) => Err(anyhow!(
+                "Plaintext record found in the encrypted store, it must be migrated first"
+            )),
+            StoredRecord::Encrypted(encrypted_record) => {
+                let plaintext = encrypted_record
+                    .decrypt(self.key_provider.as_ref(), &serde_json::to_vec(key)?)?;
+                Ok(serde_json::from_slice(&plaintext)?)
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<K, V> StoreAdapter for EncryptedStoreAdapter<K, V>
+where
+    K: Serialize + Send + Sync,
+    V: Serialize + DeserializeOwned + Send + Sync,
+{
+    type Key = K;
+    type Record = V;
+
+    async fn store_record(
+        &mut self,
+        key: &Self::Key,
+        record: &Self::Record,
+    ) -> Result<(), AdapterError> {
+        let encrypted_record = self
+            .encrypt(key, record)
+            .map_err(AdapterError::GeneralError)?;
+
+        self.adapter
+            .store_record(key, &StoredRecord::Encrypted(encrypted_record))
+            .await
+    }
+
+    async fn get_record(&self, key: &Self::Key) -> Result<Option<Self::Record>, AdapterError> {
+        match self.adapter.get_record(key).await? {
+            Some(record) => self
+                .decrypt(key, record)
+                .map(Some)
+                .map_err(AdapterError::ParsingDataError),
+            None => Ok(None),
+        }
+    }
+
+    async fn record_exists(&self, key: &Self::Key) -> Result<bool, AdapterError> {
+        self.adapter.record_exists(key).await
+    }
+
+    async fn get_last_n_records(
+        &self,
+        how_many: usize,
+    ) -> Result<Vec<(Self::Key, Self::Record)>, AdapterError> {
+        self.adapter
+            .get_last_n_records(how_many)
+            .await?
+            .into_iter()
+            .map(|(key, record)| {
+                let record = self
+                    .decrypt(&key, record)
+                    .map_err(AdapterError::ParsingDataError)?;
+                Ok((key, record))
+            })
+            .collect()
+    }
+
+    async fn remove(&mut self, key: &Self::Key) -> Result<Option<Self::Record>, AdapterError> {
+        match self.adapter.remove(key).await? {
+            Some(record) => self
+                .decrypt(key, record)
+                .map(Some)
+                .map_err(AdapterError::ParsingDataError),
+            None => Ok(None),
+        }
+    }
+
+    async fn get_iter(&self) -> Result<Box<dyn Iterator<Item = Self::Record> + '_>, AdapterError> {
+        let records = self.get_last_n_records(usize::MAX).await?;
+
+        Ok(Box::new(records.into_iter().map(|(_key, record)| record)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mithril_common::entities::Epoch;
+    use mithril_persistence::store::adapter::MemoryAdapter;
+
+    use super::*;
+
+    struct FixedKeyProvider([u8; 32]);
+
+    impl StoreEncryptionKeyProvider for FixedKeyProvider {
+        fn derive_key(&self, _salt: &[u8]) -> StdResult<[u8; 32]> {
+            Ok(self.0)
+        }
+    }
+
+    fn memory_adapter(
+        records: Vec<(Epoch, StoredRecord)>,
+    ) -> Box<MemoryAdapter<Epoch, StoredRecord>> {
+        Box::new(MemoryAdapter::new(Some(records)).unwrap())
+    }
+
+    fn encrypted_adapter(
+        records: Vec<(Epoch, StoredRecord)>,
+        key: [u8; 32],
+    ) -> EncryptedStoreAdapter<Epoch, String> {
+        EncryptedStoreAdapter::new(memory_adapter(records), Box::new(FixedKeyProvider(key)))
+    }
+
+    #[tokio::test]
+    async fn store_records_encrypted_and_read_them_back() {
+        let mut adapter = encrypted_adapter(vec![], [1; 32]);
+
+        adapter
+            .store_record(&Epoch(1), &"secret".to_string())
+            .await
+            .unwrap();
+
+        let stored_record = adapter.adapter.get_record(&Epoch(1)).await.unwrap();
+        assert!(matches!(stored_record, Some(StoredRecord::Encrypted(_))));
+        assert!(!serde_json::to_string(&stored_record)
+            .unwrap()
+            .contains("secret"));
+        assert_eq!(
+            Some("secret".to_string()),
+            adapter.get_record(&Epoch(1)).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn fail_to_read_records_with_another_key() {
+        let mut adapter = encrypted_adapter(vec![], [1; 32]);
+        adapter
+            .store_record(&Epoch(1), &"secret".to_string())
+            .await
+            .unwrap();
+        let stored_record = adapter
+            .adapter
+            .get_record(&Epoch(1))
+            .await
+            .unwrap()
+            .unwrap();
+
+        let adapter = encrypted_adapter(vec![(Epoch(1), stored_record)], [2; 32]);
+
+        adapter
+            .get_record(&Epoch(1))
+            .await
+            .expect_err("Decrypting with another key should fail");
+    }
+
+    #[tokio::test]
+    async fn fail_to_read_records_moved_to_another_store_key() {
+        let mut adapter = encrypted_adapter(vec![], [1; 32]);
+        adapter
+            .store_record(&Epoch(1), &"secret".to_string())
+            .await
+            .unwrap();
+        let stored_record = adapter
+            .adapter
+            .get_record(&Epoch(1))
+            .await
+            .unwrap()
+            .unwrap();
+
+        let adapter = encrypted_adapter(vec![(Epoch(2), stored_record)], [1; 32]);
+
+        adapter
+            .get_record(&Epoch(2))
+            .await
+            .expect_err("Decrypting a record moved to another key should fail");
+    }
+
+    #[tokio::test]
+    async fn migrate_plaintext_records_and_keep_reading_them() {
+        let mut adapter = encrypted_adapter(
+            vec![
+                (Epoch(1), StoredRecord::Plaintext("secret-1".into())),
+                (Epoch(2), StoredRecord::Plaintext("secret-2".into())),
+            ],
+            [1; 32],
+        );
+        adapter
+            .store_record(&Epoch(3), &"secret-3".to_string())
+            .await
+            .unwrap();
+
+        let migrated_records = adapter.migrate_plaintext_records().await.unwrap();
+
+        assert_eq!(2, migrated_records);
+        for (_key, record) in adapter.adapter.get_last_n_records(10).await.unwrap() {
+            assert!(matches!(record, StoredRecord::Encrypted(_)));
+        }
+        assert_eq!(
+            vec![
+                (Epoch(3), "secret-3".to_string()),
+                (Epoch(2), "secret-2".to_string()),
+                (Epoch(1), "secret-1".to_string()),
+            ],
+            adapter.get_last_n_records(10).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn reject_the_plaintext_records_not_migrated() {
+        let adapter = encrypted_adapter(
+            vec![(Epoch(1), StoredRecord::Plaintext("injected".into()))],
+            [1; 32],
+        );
+
+        adapter
+            .get_record(&Epoch(1))
+            .await
+            .expect_err("A plaintext record should be rejected");
+        adapter
+            .get_last_n_records(10)
+            .await
+            .expect_err("A plaintext record should be rejected");
+    }
+
+    #[tokio::test]
+    async fn reject_a_plaintext_record_written_after_the_migration() {
+        let mut adapter = encrypted_adapter(
+            vec![(Epoch(1), StoredRecord::Plaintext("secret-1".into()))],
+            [1; 32],
+        );
+        adapter.migrate_plaintext_records().await.unwrap();
+
+        adapter
+            .adapter
+            .store_record(&Epoch(2), &StoredRecord::Plaintext("injected".into()))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            Some("secret-1".to_string()),
+            adapter.get_record(&Epoch(1)).await.unwrap()
+        );
+        adapter
+            .get_record(&Epoch(2))
+            .await
+            .expect_err("A plaintext record written after the migration should be rejected");
+    }
+
+    #[test]
+    fn passphrase_key_provider_derives_a_key_per_passphrase_and_salt() {
+        let provider = PassphraseKeyProvider::new("passphrase");
+        let key = provider.derive_key(b"salt-of-16-bytes").unwrap();
+
+        assert_eq!(key, provider.derive_key(b"salt-of-16-bytes").unwrap());
+        assert_ne!(key, provider.derive_key(b"another-16-bytes").unwrap());
+        assert_ne!(
+            key,
+            PassphraseKeyProvider::new("other passphrase")
+                .derive_key(b"salt-of-16-bytes")
+                .unwrap()
+        );
+    }
+}
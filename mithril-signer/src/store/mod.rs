@@ -1,7 +1,9 @@
 //! Alternative storage backends when relational database capabilities are not needed.
 
+mod encrypted_store_adapter;
 mod mktree_store_sqlite;
 mod protocol_initializer_store;
 
+pub use encrypted_store_adapter::*;
 pub use mktree_store_sqlite::*;
 pub use protocol_initializer_store::*;
@@ -20,11 +20,13 @@ use mithril_common::{
     cardano_transactions_preloader::{
         CardanoTransactionsPreloader, CardanoTransactionsPreloaderActivation,
     },
-    chain_observer::{ChainObserver, FakeObserver},
+    chain_observer::{ChainObserver, ChainObserverStakeDistributionSource, FakeObserver},
+    crypto_helper::KesGenesisParameters,
     digesters::{DumbImmutableDigester, DumbImmutableFileObserver, ImmutableFileObserver},
     entities::{
         BlockNumber, CardanoTransactionsSigningConfig, ChainPoint, Epoch, SignedEntityConfig,
-        SignedEntityType, SignedEntityTypeDiscriminants, SignerWithStake, SlotNumber, TimePoint,
+        SignedEntityType, SignedEntityTypeDiscriminants, SignerWithStake, SlotNumber, Stake,
+        TimePoint,
     },
     era::{adapters::EraReaderDummyAdapter, EraChecker, EraMarker, EraReader, SupportedEra},
     signable_builder::{
@@ -33,6 +35,7 @@ use mithril_common::{
         MithrilStakeDistributionSignableBuilder,
     },
     signed_entity_type_lock::SignedEntityTypeLock,
+    test_utils::TempDir,
     MithrilTickerService, StdError, TickerService,
 };
 use mithril_persistence::{
@@ -86,6 +89,8 @@ pub struct StateMachineTester {
     signed_beacon_repository: Arc<SignedBeaconRepository>,
     metrics_service: Arc<MetricsService>,
     expected_metrics_service: Arc<MetricsService>,
+    selected_signer_stake: Stake,
+    kes_genesis_parameters: KesGenesisParameters,
     comment_no: u32,
     _logs_guard: slog_scope::GlobalLoggerGuard,
 }
@@ -113,7 +118,21 @@ impl StateMachineTester {
             TestError::AssertFailed("there should be at least one signer with stakes".to_string())
         })?;
         let selected_signer_party_id = selected_signer_with_stake.party_id.clone();
-        let config = Configuration::new_sample(&selected_signer_party_id);
+        let selected_signer_stake = selected_signer_with_stake.stake;
+        // The sample configuration targets a private network, its KES period is computed with
+        // the parameters of a Shelley genesis file
+        let kes_genesis_parameters = KesGenesisParameters::default();
+        let shelley_genesis_path = TempDir::create("signer_state_machine_tester", "kes_genesis")
+            .join("shelley-genesis.json");
+        std::fs::write(
+            &shelley_genesis_path,
+            serde_json::to_string(&kes_genesis_parameters).unwrap(),
+        )
+        .unwrap();
+        let config = Configuration {
+            cardano_shelley_genesis_path: Some(shelley_genesis_path),
+            ..Configuration::new_sample(&selected_signer_party_id)
+        };
         let network = config.get_network()?;
 
         let logger = stdout_logger();
@@ -204,6 +223,7 @@ impl StateMachineTester {
         let transactions_importer = Arc::new(CardanoTransactionsImporter::new(
             block_scanner.clone(),
             transaction_store.clone(),
+            BlockNumber(0),
             logger.clone(),
         ));
         let block_range_root_retriever = transaction_store.clone();
@@ -276,6 +296,9 @@ impl StateMachineTester {
             certificate_handler: certificate_handler.clone(),
             ticker_service: ticker_service.clone(),
             chain_observer: chain_observer.clone(),
+            stake_distribution_source: Arc::new(ChainObserverStakeDistributionSource::new(
+                chain_observer.clone(),
+            )),
             digester: digester.clone(),
             protocol_initializer_store: protocol_initializer_store.clone(),
             single_signer: single_signer.clone(),
@@ -319,6 +342,8 @@ impl StateMachineTester {
             signed_beacon_repository,
             metrics_service,
             expected_metrics_service,
+            selected_signer_stake,
+            kes_genesis_parameters,
             comment_no: 0,
             _logs_guard: logs_guard,
         })
@@ -696,7 +721,21 @@ impl StateMachineTester {
         total_signer_registrations_expected: u64,
         total_signature_registrations_expected: u64,
     ) -> Result<&mut Self> {
-        let metrics = Self::parse_exported_metrics(&self.metrics_service)?;
+        // The computation durations depend on the run, only the other metrics are checked
+        let duration_histogram_names = [
+            self.metrics_service
+                .get_message_computation_duration_seconds()
+                .name(),
+            self.metrics_service
+                .get_single_signature_computation_duration_seconds()
+                .name(),
+        ];
+        let mut metrics = Self::parse_exported_metrics(&self.metrics_service)?;
+        metrics.retain(|name, _| {
+            !duration_histogram_names
+                .iter()
+                .any(|histogram_name| name.starts_with(histogram_name))
+        });
         let mut expected_metrics = Self::parse_exported_metrics(&self.expected_metrics_service)?;
         expected_metrics.insert(
             self.metrics_service
@@ -734,6 +773,21 @@ impl StateMachineTester {
                 .name(),
             Value::Counter(total_signer_registrations_expected as f64),
         );
+        expected_metrics.insert(
+            self.metrics_service.get_current_epoch_gauge().name(),
+            Value::Gauge(self.current_epoch().await?.0 as f64),
+        );
+        expected_metrics.insert(
+            self.metrics_service.get_active_stake_gauge().name(),
+            Value::Gauge(self.selected_signer_stake as f64),
+        );
+        // The chain of the tests does not leave the start KES period of the operational certificate
+        expected_metrics.insert(
+            self.metrics_service
+                .get_remaining_kes_evolutions_gauge()
+                .name(),
+            Value::Gauge(self.kes_genesis_parameters.max_kes_evolutions as f64),
+        );
         self.assert(
             expected_metrics == metrics,
             format!("Metrics service should export expected metrics: given {metrics:?}, expected {expected_metrics:?}"),
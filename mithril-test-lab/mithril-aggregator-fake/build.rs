@@ -1,6 +1,7 @@
 // build.rs
 
-use mithril_build_script::fake_aggregator::FakeAggregatorData;
+use mithril_build_script::fake_aggregator::{generate_list_getter, FakeAggregatorData};
+use mithril_build_script::open_api::read_version_from_open_api_spec_file;
 use std::env;
 use std::fs;
 use std::path::Path;
@@ -11,8 +12,16 @@ fn main() {
 
     let data_folder_path: &Path = Path::new("./default_data");
     let data = FakeAggregatorData::load_from_folder(data_folder_path);
-    let generated_code = data.generate_code_for_all_data();
+    let mut generated_code = data.generate_code_for_all_data();
+
+    // The fake aggregator serves the routes of the aggregator Open API specification
+    let open_api_spec_path = Path::new("../../openapi.yaml");
+    generated_code.push_str(&generate_list_getter(
+        "open_api_version",
+        read_version_from_open_api_spec_file(open_api_spec_path),
+    ));
     fs::write(dest_path, generated_code).unwrap();
 
     println!("cargo:rerun-if-changed=default_data/");
+    println!("cargo:rerun-if-changed=../../openapi.yaml");
 }
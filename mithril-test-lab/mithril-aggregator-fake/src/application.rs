@@ -89,14 +89,14 @@ impl Application {
 
 #[cfg(test)]
 mod tests {
+    use axum::body::Bytes;
+    use axum::http::{Response, StatusCode};
     use serde_json::Value::Null;
     use std::time::Duration;
     use tokio::{
         task::{yield_now, JoinHandle},
         time::sleep,
     };
-    use warp::http::{Response, StatusCode};
-    use warp::hyper::body::Bytes;
 
     use mithril_common::test_utils::apispec::APISpec;
 
@@ -628,4 +628,29 @@ mod tests {
 
         test(task, PORT).await;
     }
+
+    #[tokio::test]
+    async fn get_aggregator_features() {
+        const PORT: u16 = 3020;
+        let task = tokio::spawn(async move {
+            // Yield back to Tokio's scheduler to ensure the web server is ready before going on.
+            yield_now().await;
+
+            // The nested root route is served on the base URL, without a trailing slash
+            let response = http_request(PORT, "").await;
+
+            APISpec::verify_conformity(
+                get_spec_files(),
+                "GET",
+                "/",
+                "application/json",
+                &Null,
+                &response,
+                &StatusCode::OK,
+            )
+            .map_err(|e| anyhow!(e))
+        });
+
+        test(task, PORT).await;
+    }
 }
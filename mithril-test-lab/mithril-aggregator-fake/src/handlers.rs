@@ -23,6 +23,7 @@ use crate::AppError;
 
 pub async fn aggregator_router() -> Router<SharedState> {
     Router::new()
+        .route("/", get(aggregator_features))
         .route("/epoch-settings", get(epoch_settings))
         .route("/artifact/snapshots", get(snapshots))
         .route("/artifact/mithril-stake-distributions", get(msds))
@@ -40,6 +41,7 @@ pub async fn aggregator_router() -> Router<SharedState> {
         .route("/certificates", get(certificates))
         .route("/certificate/:hash", get(certificate))
         .route("/statistics/snapshot", post(statistics))
+        .route("/statistics/artifact-download", post(statistics))
         .layer(CorsLayer::permissive())
         .layer(from_fn(set_json_app_header))
         .layer(
@@ -59,6 +61,14 @@ pub async fn aggregator_router() -> Router<SharedState> {
         )
 }
 
+/// HTTP: Return the Aggregator features.
+pub async fn aggregator_features(State(state): State<SharedState>) -> Result<String, AppError> {
+    let app_state = state.read().await;
+    let aggregator_features = app_state.get_aggregator_features().await?;
+
+    Ok(aggregator_features)
+}
+
 /// HTTP: Return the Epoch Settings.
 pub async fn epoch_settings(State(state): State<SharedState>) -> Result<String, AppError> {
     let app_state = state.read().await;
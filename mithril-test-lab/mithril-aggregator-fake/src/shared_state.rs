@@ -85,6 +85,34 @@ impl AppState {
         Ok(instance)
     }
 
+    /// return the aggregator features, the signed entity types advertised are the ones with at
+    /// least one artifact so the client capability checks are consistent with the served data
+    pub async fn get_aggregator_features(&self) -> StdResult<String> {
+        let mut signed_entity_types = vec![];
+        for (signed_entity_type, list) in [
+            ("MithrilStakeDistribution", &self.msd_list),
+            ("CardanoStakeDistribution", &self.csd_list),
+            ("CardanoImmutableFilesFull", &self.snapshot_list),
+            ("CardanoTransactions", &self.ctx_snapshot_list),
+        ] {
+            let artifacts: Vec<serde_json::Value> = serde_json::from_str(list)
+                .with_context(|| format!("Could not parse the {signed_entity_type} list."))?;
+            if !artifacts.is_empty() {
+                signed_entity_types.push(signed_entity_type);
+            }
+        }
+
+        let features = serde_json::json!({
+            "open_api_version": default_values::open_api_version(),
+            "documentation_url": env!("CARGO_PKG_HOMEPAGE"),
+            "capabilities": {
+                "signed_entity_types": signed_entity_types,
+            },
+        });
+
+        Ok(features.to_string())
+    }
+
     /// return the compiled epoch settings
     pub async fn get_epoch_settings(&self) -> StdResult<String> {
         Ok(self.epoch_settings.clone())
@@ -237,4 +265,24 @@ mod tests {
         AppState::from_directory(Path::new("./default_data"))
             .expect("Should be able to construct an AppState from the default_data");
     }
+
+    #[tokio::test]
+    async fn aggregator_features_advertise_the_signed_entity_types_with_artifacts() {
+        let state = AppState {
+            ctx_snapshot_list: "[]".to_string(),
+            ..AppState::default()
+        };
+
+        let features: serde_json::Value =
+            serde_json::from_str(&state.get_aggregator_features().await.unwrap()).unwrap();
+
+        assert_eq!(
+            serde_json::json!([
+                "MithrilStakeDistribution",
+                "CardanoStakeDistribution",
+                "CardanoImmutableFilesFull"
+            ]),
+            features["capabilities"]["signed_entity_types"]
+        );
+    }
 }
@@ -223,6 +223,7 @@ EOF
       - CARDANO_CLI_PATH=/app/bin/cardano-cli
       - KES_SECRET_KEY_PATH=/data/shelley/kes.skey
       - OPERATIONAL_CERTIFICATE_PATH=/data/shelley/opcert.cert
+      - CARDANO_SHELLEY_GENESIS_PATH=/data/shelley/genesis.json
     command:
       [
         "-vvv"
@@ -72,7 +72,9 @@ done
 # Prepare transactions for delegating to stake pools
 for N in ${POOL_NODES_N}; do
   cat >> delegate.sh <<EOF
-    AMOUNT_STAKED=\$(( $N*1000000 +  \$DELEGATION_ROUND*1 ))
+    # The stake weight of the pool is read from the comma separated POOL_STAKE_WEIGHTS list, or else is the pool number
+    STAKE_WEIGHT=\$(echo "\${POOL_STAKE_WEIGHTS}" | tr ',' '\n' | sed -n '${N}p')
+    AMOUNT_STAKED=\$(( \${STAKE_WEIGHT:-$N}*1000000 +  \$DELEGATION_ROUND*1 ))
 
     # Get the UTxO
     TX_IN=\$(CARDANO_NODE_SOCKET_PATH=node-pool${N}/ipc/node.sock $CARDANO_CLI \${CURRENT_CARDANO_ERA} query utxo \\
@@ -312,7 +312,7 @@ pub async fn assert_signer_is_signing_cardano_stake_distribution(
 pub async fn assert_is_creating_certificate_with_enough_signers(
     aggregator_endpoint: &str,
     certificate_hash: &str,
-    total_signers_expected: usize,
+    min_signers_expected: usize,
 ) -> StdResult<()> {
     let url = format!("{aggregator_endpoint}/certificate/{certificate_hash}");
 
@@ -331,18 +331,18 @@ pub async fn assert_is_creating_certificate_with_enough_signers(
     }) {
         AttemptResult::Ok(certificate) => {
             info!("Aggregator produced a certificate"; "certificate" => ?certificate);
-            if certificate.metadata.signers.len() == total_signers_expected {
+            if certificate.metadata.signers.len() >= min_signers_expected {
                 info!(
                     "Certificate is signed by expected number of signers: {} >= {} ",
                     certificate.metadata.signers.len(),
-                    total_signers_expected
+                    min_signers_expected
                 );
                 Ok(())
             } else {
                 Err(anyhow!(
                     "Certificate is not signed by expected number of signers: {} < {} ",
                     certificate.metadata.signers.len(),
-                    total_signers_expected
+                    min_signers_expected
                 ))
             }
         }
@@ -18,6 +18,16 @@ pub async fn bootstrap_genesis_certificate(aggregator: &mut Aggregator) -> StdRe
     Ok(())
 }
 
+pub async fn restart_aggregator(aggregator: &mut Aggregator) -> StdResult<()> {
+    info!("Restart aggregator");
+
+    info!("> killing aggregator");
+    aggregator.restart().await?;
+    info!("> done, aggregator restarted");
+
+    Ok(())
+}
+
 pub async fn register_era_marker(
     aggregator: &mut Aggregator,
     devnet: &Devnet,
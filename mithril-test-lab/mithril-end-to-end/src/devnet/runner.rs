@@ -12,6 +12,7 @@ use tokio::process::Command;
 pub struct Devnet {
     artifacts_dir: PathBuf,
     number_of_pool_nodes: u8,
+    pool_stake_weights: Vec<u64>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -21,6 +22,7 @@ pub struct PoolNode {
     pub pool_env_path: PathBuf,
     pub kes_secret_key_path: PathBuf,
     pub operational_certificate_path: PathBuf,
+    pub shelley_genesis_path: PathBuf,
 }
 
 impl PoolNode {
@@ -55,6 +57,9 @@ pub struct DevnetBootstrapArgs {
     pub cardano_node_version: String,
     pub cardano_hard_fork_latest_era_at_epoch: u16,
     pub skip_cardano_bin_download: bool,
+    /// Stake weight of each pool node, in order, used when delegating the stakes (the pool
+    /// number is used for the pools without a weight)
+    pub pool_stake_weights: Vec<u64>,
 }
 
 impl Devnet {
@@ -124,6 +129,7 @@ impl Devnet {
         Ok(Devnet {
             artifacts_dir: bootstrap_args.artifacts_target_dir.to_owned(),
             number_of_pool_nodes: bootstrap_args.number_of_pool_nodes,
+            pool_stake_weights: bootstrap_args.pool_stake_weights.clone(),
         })
     }
 
@@ -133,6 +139,7 @@ impl Devnet {
         Self {
             artifacts_dir,
             number_of_pool_nodes,
+            pool_stake_weights: vec![],
         }
     }
 
@@ -187,6 +194,9 @@ impl Devnet {
                 operational_certificate_path: self
                     .artifacts_dir
                     .join(format!("node-pool{n}/shelley/opcert.cert")),
+                shelley_genesis_path: self
+                    .artifacts_dir
+                    .join(format!("node-pool{n}/shelley/genesis.json")),
             })
             .collect::<Vec<_>>();
 
@@ -247,6 +257,14 @@ impl Devnet {
             .current_dir(&self.artifacts_dir)
             .kill_on_drop(true);
         run_command.env("DELEGATION_ROUND", delegation_round.to_string());
+        run_command.env(
+            "POOL_STAKE_WEIGHTS",
+            self.pool_stake_weights
+                .iter()
+                .map(|weight| weight.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+        );
 
         info!("Delegating stakes to the pools"; "script" => &run_script_path.display());
 
@@ -352,6 +370,9 @@ mod tests {
                     operational_certificate_path: PathBuf::from(
                         r"test/path/node-pool1/shelley/opcert.cert"
                     ),
+                    shelley_genesis_path: PathBuf::from(
+                        r"test/path/node-pool1/shelley/genesis.json"
+                    ),
                 },],
             },
             devnet.topology()
@@ -11,6 +11,8 @@ pub struct Spec<'a> {
     is_signing_cardano_stake_distribution: bool,
     next_era: Option<String>,
     regenesis_on_era_switch: bool,
+    restart_aggregator_mid_round: bool,
+    tolerate_missing_signatures: bool,
 }
 
 impl<'a> Spec<'a> {
@@ -19,6 +21,8 @@ impl<'a> Spec<'a> {
         signed_entity_types: Vec<String>,
         next_era: Option<String>,
         regenesis_on_era_switch: bool,
+        restart_aggregator_mid_round: bool,
+        tolerate_missing_signatures: bool,
     ) -> Self {
         Self {
             infrastructure,
@@ -34,6 +38,8 @@ impl<'a> Spec<'a> {
             ),
             next_era,
             regenesis_on_era_switch,
+            restart_aggregator_mid_round,
+            tolerate_missing_signatures,
         }
     }
 
@@ -97,6 +103,12 @@ impl<'a> Spec<'a> {
         )
         .await?;
 
+        // Kill and restart the aggregator while the signers keep signing: the immutable files are
+        // produced continuously so some signed entities are always being certified
+        if self.restart_aggregator_mid_round {
+            assertions::restart_aggregator(self.infrastructure.aggregator_mut()).await?;
+        }
+
         // Verify that artifacts are produced and signed correctly
         let mut target_epoch = self.verify_artifacts_production(target_epoch).await?;
 
@@ -134,6 +146,16 @@ impl<'a> Spec<'a> {
         Ok(())
     }
 
+    /// Minimum number of signers of a certificate: all the signers, or only one if some single
+    /// signatures may be lost, the quorum being checked by the aggregator
+    fn min_signers_per_certificate(&self) -> usize {
+        if self.tolerate_missing_signatures {
+            1
+        } else {
+            self.infrastructure.signers().len()
+        }
+    }
+
     async fn verify_artifacts_production(&self, target_epoch: Epoch) -> StdResult<Epoch> {
         let aggregator_endpoint = self.infrastructure.aggregator().endpoint();
         let expected_epoch_min = target_epoch - 3;
@@ -151,7 +173,7 @@ impl<'a> Spec<'a> {
             assertions::assert_is_creating_certificate_with_enough_signers(
                 &aggregator_endpoint,
                 &certificate_hash,
-                self.infrastructure.signers().len(),
+                self.min_signers_per_certificate(),
             )
             .await?;
             let mut client = self.infrastructure.build_client()?;
@@ -172,7 +194,7 @@ impl<'a> Spec<'a> {
             assertions::assert_is_creating_certificate_with_enough_signers(
                 &aggregator_endpoint,
                 &certificate_hash,
-                self.infrastructure.signers().len(),
+                self.min_signers_per_certificate(),
             )
             .await?;
 
@@ -194,7 +216,7 @@ impl<'a> Spec<'a> {
             assertions::assert_is_creating_certificate_with_enough_signers(
                 &aggregator_endpoint,
                 &certificate_hash,
-                self.infrastructure.signers().len(),
+                self.min_signers_per_certificate(),
             )
             .await?;
 
@@ -224,7 +246,7 @@ impl<'a> Spec<'a> {
                 assertions::assert_is_creating_certificate_with_enough_signers(
                     &aggregator_endpoint,
                     &certificate_hash,
-                    self.infrastructure.signers().len(),
+                    self.min_signers_per_certificate(),
                 )
                 .await?;
 
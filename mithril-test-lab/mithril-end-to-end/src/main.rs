@@ -17,7 +17,8 @@ use tokio::{
 use mithril_common::StdResult;
 use mithril_doc::GenerateDocCommands;
 use mithril_end_to_end::{
-    Devnet, DevnetBootstrapArgs, MithrilInfrastructure, MithrilInfrastructureConfig, RunOnly, Spec,
+    Devnet, DevnetBootstrapArgs, MithrilInfrastructure, MithrilInfrastructureConfig, RunOnly,
+    SignaturesFaults, Spec,
 };
 
 /// Tests args
@@ -52,6 +53,11 @@ pub struct Args {
     #[clap(long, default_value_t = 3, value_parser = has_at_least_two_pool_nodes)]
     number_of_pool_nodes: u8,
 
+    /// Stake weights of the pool nodes, in order, applied when the stakes are delegated (comma
+    /// separated list, the pool number is used for the pools without a weight)
+    #[clap(long, value_delimiter = ',')]
+    pool_stake_weights: Vec<u64>,
+
     /// Length of a Cardano slot in the devnet (in s)
     #[clap(long, default_value_t = 0.10)]
     cardano_slot_length: f64,
@@ -108,6 +114,22 @@ pub struct Args {
     #[clap(long, default_value = "true")]
     use_p2p_passive_relays: bool,
 
+    /// Percentage of the single signatures dropped between the signers and the aggregator
+    #[clap(long, default_value_t = 0, value_parser = clap::value_parser!(u8).range(0..=100))]
+    signatures_drop_percentage: u8,
+
+    /// Percentage of the single signatures delayed between the signers and the aggregator
+    #[clap(long, default_value_t = 0, value_parser = clap::value_parser!(u8).range(0..=100))]
+    signatures_delay_percentage: u8,
+
+    /// Delay of the delayed single signatures (in ms)
+    #[clap(long, default_value_t = 2000)]
+    signatures_delay_ms: u64,
+
+    /// Kill and restart the aggregator while the signers are signing
+    #[clap(long)]
+    restart_aggregator_mid_round: bool,
+
     /// Skip cardano binaries download
     #[clap(long)]
     skip_cardano_bin_download: bool,
@@ -232,6 +254,11 @@ impl App {
         let run_only_mode = args.run_only;
         let use_p2p_network_mode = args.use_p2p_network;
         let use_p2p_passive_relays = args.use_p2p_passive_relays;
+        let signatures_faults = SignaturesFaults {
+            drop_percentage: args.signatures_drop_percentage,
+            delay_percentage: args.signatures_delay_percentage,
+            delay: Duration::from_millis(args.signatures_delay_ms),
+        };
 
         let devnet = Devnet::bootstrap(&DevnetBootstrapArgs {
             devnet_scripts_dir: args.devnet_scripts_directory,
@@ -242,6 +269,7 @@ impl App {
             cardano_node_version: args.cardano_node_version.to_owned(),
             cardano_hard_fork_latest_era_at_epoch: args.cardano_hard_fork_latest_era_at_epoch,
             skip_cardano_bin_download: args.skip_cardano_bin_download,
+            pool_stake_weights: args.pool_stake_weights.clone(),
         })
         .await?;
         *self.devnet.lock().await = Some(devnet.clone());
@@ -261,6 +289,7 @@ impl App {
             use_p2p_network_mode,
             use_p2p_passive_relays,
             use_era_specific_work_dir: args.mithril_next_era.is_some(),
+            signatures_faults: signatures_faults.clone(),
         })
         .await?;
 
@@ -275,6 +304,8 @@ impl App {
                     args.signed_entity_types,
                     args.mithril_next_era,
                     args.mithril_era_regenesis_on_switch,
+                    args.restart_aggregator_mid_round,
+                    signatures_faults.drop_percentage > 0,
                 );
                 spec.run().await
             }
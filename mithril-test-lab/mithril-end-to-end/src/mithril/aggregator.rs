@@ -185,6 +185,12 @@ impl Aggregator {
         Ok(())
     }
 
+    /// Kill the aggregator process and serve again, as after a crash
+    pub async fn restart(&mut self) -> StdResult<()> {
+        self.stop().await?;
+        self.serve()
+    }
+
     pub async fn era_generate_tx_datum(
         &mut self,
         target_path: &Path,
@@ -0,0 +1,235 @@
+use anyhow::{anyhow, Context};
+use mithril_common::StdResult;
+use slog_scope::{info, warn};
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use warp::http::{Response, StatusCode};
+use warp::hyper::body::Bytes;
+use warp::Filter;
+
+/// Faults injected on the single signatures sent by the signers to the aggregator
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SignaturesFaults {
+    /// Percentage of the single signatures that are dropped, the signer gets a
+    /// `503 Service Unavailable` response
+    pub drop_percentage: u8,
+
+    /// Percentage of the single signatures that are forwarded after a delay
+    pub delay_percentage: u8,
+
+    /// Delay of the delayed single signatures
+    pub delay: Duration,
+}
+
+impl SignaturesFaults {
+    /// Check if some faults are injected
+    pub fn is_enabled(&self) -> bool {
+        self.drop_percentage > 0 || self.delay_percentage > 0
+    }
+}
+
+/// Select the requests on which a fault is injected: the requests are counted and a fault is
+/// injected each time the given percentage of the count reaches a new integer, which spreads the
+/// faults evenly and deterministically.
+#[derive(Debug)]
+struct FaultSchedule {
+    percentage: u64,
+    requests_count: AtomicU64,
+}
+
+impl FaultSchedule {
+    fn new(percentage: u8) -> Self {
+        Self {
+            percentage: u64::from(percentage.min(100)),
+            requests_count: AtomicU64::new(0),
+        }
+    }
+
+    fn next_request_is_faulty(&self) -> bool {
+        let index = self.requests_count.fetch_add(1, Ordering::SeqCst);
+
+        (index + 1) * self.percentage / 100 > index * self.percentage / 100
+    }
+}
+
+#[derive(Debug)]
+struct FaultInjector {
+    aggregator_server_url: String,
+    drop_schedule: FaultSchedule,
+    delay_schedule: FaultSchedule,
+    delay: Duration,
+    http_client: reqwest::Client,
+}
+
+impl FaultInjector {
+    const SIGNATURES_ROUTE: &'static str = "/register-signatures";
+
+    async fn forward(
+        self: Arc<Self>,
+        method: warp::http::Method,
+        path: warp::path::FullPath,
+        query: String,
+        headers: warp::http::HeaderMap,
+        body: Bytes,
+    ) -> Result<Response<Bytes>, Infallible> {
+        if method == warp::http::Method::POST && path.as_str().ends_with(Self::SIGNATURES_ROUTE) {
+            if self.drop_schedule.next_request_is_faulty() {
+                info!("Fault injection proxy: drop a single signature");
+                return Ok(Self::response(
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    Bytes::new(),
+                ));
+            }
+            if self.delay_schedule.next_request_is_faulty() {
+                info!("Fault injection proxy: delay a single signature"; "delay" => ?self.delay);
+                tokio::time::sleep(self.delay).await;
+            }
+        }
+
+        match self.send(&method, &path, &query, &headers, body).await {
+            Ok(response) => Ok(response),
+            Err(error) => {
+                warn!("Fault injection proxy: could not forward the request"; "error" => ?error);
+                Ok(Self::response(StatusCode::BAD_GATEWAY, Bytes::new()))
+            }
+        }
+    }
+
+    async fn send(
+        &self,
+        method: &warp::http::Method,
+        path: &warp::path::FullPath,
+        query: &str,
+        headers: &warp::http::HeaderMap,
+        body: Bytes,
+    ) -> StdResult<Response<Bytes>> {
+        let url = match query {
+            "" => format!("{}{}", self.aggregator_server_url, path.as_str()),
+            query => format!("{}{}?{query}", self.aggregator_server_url, path.as_str()),
+        };
+        // warp and reqwest do not share the same version of the `http` crate
+        let mut request = self
+            .http_client
+            .request(
+                reqwest::Method::from_bytes(method.as_str().as_bytes())?,
+                url,
+            )
+            .body(body.to_vec());
+        for (name, value) in headers.iter().filter(|(name, _)| *name != "host") {
+            request = request.header(name.as_str(), value.as_bytes());
+        }
+
+        let response = request.send().await?;
+        let mut builder = Response::builder().status(response.status().as_u16());
+        for (name, value) in response.headers() {
+            builder = builder.header(name.as_str(), value.as_bytes());
+        }
+        let body = response.bytes().await?;
+
+        Ok(builder.body(Bytes::from(body.to_vec()))?)
+    }
+
+    fn response(status: StatusCode, body: Bytes) -> Response<Bytes> {
+        let mut response = Response::new(body);
+        *response.status_mut() = status;
+        response
+    }
+}
+
+/// HTTP proxy between the signers and the aggregator that injects faults on the single
+/// signatures, to check that the certification succeeds despite lost or late signatures.
+#[derive(Debug)]
+pub struct FaultInjectionProxy {
+    port: u64,
+    aggregator_server_port: u64,
+    faults: SignaturesFaults,
+    task: Option<JoinHandle<()>>,
+}
+
+impl FaultInjectionProxy {
+    pub fn new(port: u64, aggregator_server_port: u64, faults: SignaturesFaults) -> Self {
+        Self {
+            port,
+            aggregator_server_port,
+            faults,
+            task: None,
+        }
+    }
+
+    pub fn endpoint(&self) -> String {
+        format!("http://localhost:{}/aggregator", &self.port)
+    }
+
+    pub fn start(&mut self) -> StdResult<()> {
+        let injector = Arc::new(FaultInjector {
+            aggregator_server_url: format!("http://localhost:{}", self.aggregator_server_port),
+            drop_schedule: FaultSchedule::new(self.faults.drop_percentage),
+            delay_schedule: FaultSchedule::new(self.faults.delay_percentage),
+            delay: self.faults.delay,
+            http_client: reqwest::Client::new(),
+        });
+        let routes = warp::any()
+            .map(move || injector.clone())
+            .and(warp::method())
+            .and(warp::path::full())
+            .and(warp::query::raw().or(warp::any().map(String::new)).unify())
+            .and(warp::header::headers_cloned())
+            .and(warp::body::bytes())
+            .and_then(FaultInjector::forward);
+        let address: SocketAddr = format!("127.0.0.1:{}", self.port)
+            .parse()
+            .with_context(|| "Invalid fault injection proxy address")?;
+        let (_, server) = warp::serve(routes)
+            .try_bind_ephemeral(address)
+            .map_err(|e| anyhow!(e).context("Could not bind the fault injection proxy"))?;
+
+        info!("Starting the fault injection proxy"; "endpoint" => self.endpoint(), "faults" => ?self.faults);
+        self.task = Some(tokio::spawn(server));
+
+        Ok(())
+    }
+
+    pub fn stop(&mut self) {
+        if let Some(task) = self.task.take() {
+            info!("Stopping the fault injection proxy");
+            task.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count_faulty_requests(percentage: u8, requests: usize) -> usize {
+        let schedule = FaultSchedule::new(percentage);
+        (0..requests)
+            .filter(|_| schedule.next_request_is_faulty())
+            .count()
+    }
+
+    #[test]
+    fn the_faults_are_injected_on_the_given_percentage_of_requests() {
+        assert_eq!(0, count_faulty_requests(0, 100));
+        assert_eq!(25, count_faulty_requests(25, 100));
+        assert_eq!(5, count_faulty_requests(50, 10));
+        assert_eq!(10, count_faulty_requests(100, 10));
+        assert_eq!(10, count_faulty_requests(150, 10));
+    }
+
+    #[test]
+    fn the_faults_are_spread_evenly() {
+        let schedule = FaultSchedule::new(50);
+
+        assert_eq!(
+            vec![false, true, false, true],
+            (0..4)
+                .map(|_| schedule.next_request_is_faulty())
+                .collect::<Vec<_>>()
+        );
+    }
+}
@@ -9,8 +9,8 @@ use std::path::PathBuf;
 use std::sync::Arc;
 
 use crate::{
-    assertions, Aggregator, AggregatorConfig, Client, Devnet, PoolNode, RelayAggregator,
-    RelayPassive, RelaySigner, Signer, DEVNET_MAGIC_ID,
+    assertions, Aggregator, AggregatorConfig, Client, Devnet, FaultInjectionProxy, PoolNode,
+    RelayAggregator, RelayPassive, RelaySigner, SignaturesFaults, Signer, DEVNET_MAGIC_ID,
 };
 
 use super::signer::SignerConfig;
@@ -30,6 +30,7 @@ pub struct MithrilInfrastructureConfig {
     pub use_p2p_network_mode: bool,
     pub use_p2p_passive_relays: bool,
     pub use_era_specific_work_dir: bool,
+    pub signatures_faults: SignaturesFaults,
 }
 
 pub struct MithrilInfrastructure {
@@ -37,6 +38,7 @@ pub struct MithrilInfrastructure {
     bin_dir: PathBuf,
     devnet: Devnet,
     aggregator: Aggregator,
+    fault_injection_proxy: Option<FaultInjectionProxy>,
     signers: Vec<Signer>,
     relay_aggregators: Vec<RelayAggregator>,
     relay_signers: Vec<RelaySigner>,
@@ -64,12 +66,22 @@ impl MithrilInfrastructure {
         let aggregator =
             Self::start_aggregator(config, aggregator_cardano_node, chain_observer_type).await?;
 
-        let (relay_aggregators, relay_signers, relay_passives) =
-            Self::start_relays(config, aggregator.endpoint(), &signer_party_ids)?;
+        // The signers reach the aggregator through the fault injection proxy, if any
+        let fault_injection_proxy = Self::start_fault_injection_proxy(config)?;
+        let signers_aggregator_endpoint = fault_injection_proxy
+            .as_ref()
+            .map(|proxy| proxy.endpoint())
+            .unwrap_or_else(|| aggregator.endpoint());
+
+        let (relay_aggregators, relay_signers, relay_passives) = Self::start_relays(
+            config,
+            signers_aggregator_endpoint.clone(),
+            &signer_party_ids,
+        )?;
 
         let signers = Self::start_signers(
             config,
-            aggregator.endpoint(),
+            signers_aggregator_endpoint,
             signer_cardano_nodes,
             &relay_signers,
         )?;
@@ -84,6 +96,7 @@ impl MithrilInfrastructure {
             artifacts_dir: config.artifacts_dir.to_path_buf(),
             devnet: config.devnet.clone(),
             aggregator,
+            fault_injection_proxy,
             signers,
             relay_aggregators,
             relay_signers,
@@ -179,6 +192,23 @@ impl MithrilInfrastructure {
         Ok(aggregator)
     }
 
+    fn start_fault_injection_proxy(
+        config: &MithrilInfrastructureConfig,
+    ) -> StdResult<Option<FaultInjectionProxy>> {
+        if !config.signatures_faults.is_enabled() {
+            return Ok(None);
+        }
+
+        let mut fault_injection_proxy = FaultInjectionProxy::new(
+            config.server_port + 50,
+            config.server_port,
+            config.signatures_faults.clone(),
+        );
+        fault_injection_proxy.start()?;
+
+        Ok(Some(fault_injection_proxy))
+    }
+
     fn start_relays(
         config: &MithrilInfrastructureConfig,
         aggregator_endpoint: String,
@@ -207,6 +237,7 @@ impl MithrilInfrastructure {
             let mut relay_passive_aggregator = RelayPassive::new(
                 config.server_port + 200,
                 relay_aggregator.peer_addr().to_owned(),
+                &aggregator_endpoint,
                 format!("{relay_passive_id}"),
                 &config.work_dir,
                 &config.bin_dir,
@@ -232,6 +263,7 @@ impl MithrilInfrastructure {
                 let mut relay_passive_signer = RelayPassive::new(
                     config.server_port + index as u64 + 500,
                     relay_signer.peer_addr().to_owned(),
+                    &aggregator_endpoint,
                     format!("{relay_passive_id}"),
                     &config.work_dir,
                     &config.bin_dir,
@@ -296,6 +328,10 @@ impl MithrilInfrastructure {
             signer.stop().await?;
         }
 
+        if let Some(fault_injection_proxy) = self.fault_injection_proxy.as_mut() {
+            fault_injection_proxy.stop();
+        }
+
         self.aggregator.stop().await?;
 
         Ok(())
@@ -1,5 +1,6 @@
 mod aggregator;
 mod client;
+mod fault_injection_proxy;
 mod infrastructure;
 mod relay_aggregator;
 mod relay_passive;
@@ -11,6 +12,7 @@ pub use client::{
     CardanoDbCommand, CardanoStakeDistributionCommand, CardanoTransactionCommand, Client,
     ClientCommand, MithrilStakeDistributionCommand,
 };
+pub use fault_injection_proxy::{FaultInjectionProxy, SignaturesFaults};
 pub use infrastructure::{MithrilInfrastructure, MithrilInfrastructureConfig};
 pub use relay_aggregator::RelayAggregator;
 pub use relay_passive::RelayPassive;
@@ -16,6 +16,7 @@ impl RelayPassive {
     pub fn new(
         listen_port: u64,
         dial_to: String,
+        aggregator_endpoint: &str,
         relay_id: String,
         work_dir: &Path,
         bin_dir: &Path,
@@ -24,6 +25,7 @@ impl RelayPassive {
         let env = HashMap::from([
             ("LISTEN_PORT", listen_port_str.as_str()),
             ("DIAL_TO", &dial_to),
+            ("AGGREGATOR_ENDPOINT", aggregator_endpoint),
         ]);
         let args = vec!["-vvv", "passive"];
 
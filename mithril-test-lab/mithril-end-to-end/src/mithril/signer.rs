@@ -94,6 +94,14 @@ impl Signer {
                     .to_str()
                     .unwrap(),
             );
+            env.insert(
+                "CARDANO_SHELLEY_GENESIS_PATH",
+                signer_config
+                    .pool_node
+                    .shelley_genesis_path
+                    .to_str()
+                    .unwrap(),
+            );
         } else {
             env.insert("PARTY_ID", &party_id);
         }
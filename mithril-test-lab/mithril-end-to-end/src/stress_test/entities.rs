@@ -77,6 +77,7 @@ impl AggregatorParameters {
             pool_env_path: PathBuf::new(),
             kes_secret_key_path: PathBuf::new(),
             operational_certificate_path: PathBuf::new(),
+            shelley_genesis_path: PathBuf::new(),
         };
         let tmp_dir = opts
             .temporary_path
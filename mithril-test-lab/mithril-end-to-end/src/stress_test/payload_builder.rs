@@ -8,6 +8,7 @@ use mithril_common::{
         SignedEntityType, Signer, SingleSignatures,
     },
     messages::{RegisterSignatureMessage, RegisterSignerMessage},
+    protocol::SignatureScheme,
     test_utils::{MithrilFixture, MithrilFixtureBuilder},
     StdResult,
 };
@@ -42,6 +43,8 @@ pub fn generate_register_signer_message(
                 .operational_certificate
                 .map(|o| o.to_json_hex().unwrap()),
             kes_period: signer.kes_period,
+            registration_challenge: None,
+            registration_challenge_signature: None,
         })
         .collect::<Vec<_>>()
 }
@@ -59,6 +62,7 @@ pub fn generate_register_signature_message(
             signature: s.signature.clone().to_json_hex().unwrap(),
             won_indexes: s.won_indexes.clone(),
             signed_message: None,
+            signature_scheme: SignatureScheme::default(),
         })
         .collect::<Vec<_>>()
 }